@@ -1,7 +1,8 @@
 //! Middleware configuration for the HTTP server.
 //!
 //! This module provides CLI-configurable middleware settings including CORS,
-//! OpenAPI documentation, and request recovery (timeouts/panic handling).
+//! request body limits, OpenAPI documentation, and request recovery
+//! (timeouts/panic handling).
 //!
 //! Each field is a clap args struct that converts into the corresponding
 //! plain config type owned by `nvisy-server`.
@@ -16,11 +17,15 @@
 use std::time::Duration;
 
 use clap::Args;
-use nvisy_server::middleware::{CorsConfig, OpenApiConfig, RecoveryConfig};
+use nvisy_server::middleware::{
+    BodyLimitConfig, CorsConfig, DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_FILE_BODY_SIZE, OpenApiConfig,
+    RecoveryConfig,
+};
 
 use super::TRACING_TARGET_CONFIG;
 
-/// Middleware configuration combining CORS, OpenAPI, and recovery settings.
+/// Middleware configuration combining CORS, body limit, OpenAPI, and recovery
+/// settings.
 ///
 /// This struct groups all HTTP middleware configurations that can be
 /// customized via CLI arguments or environment variables.
@@ -30,6 +35,10 @@ pub struct MiddlewareConfig {
     #[clap(flatten)]
     pub cors: CorsArgs,
 
+    /// Request body limit configuration.
+    #[clap(flatten)]
+    pub body_limits: BodyLimitArgs,
+
     /// OpenAPI documentation configuration.
     #[clap(flatten)]
     pub openapi: OpenApiArgs,
@@ -45,6 +54,11 @@ impl MiddlewareConfig {
         self.cors.clone().into()
     }
 
+    /// Returns the request body limit configuration.
+    pub fn body_limits(&self) -> BodyLimitConfig {
+        self.body_limits.clone().into()
+    }
+
     /// Returns the OpenAPI configuration.
     pub fn openapi(&self) -> OpenApiConfig {
         self.openapi.clone().into()
@@ -64,6 +78,13 @@ impl MiddlewareConfig {
             "CORS configuration"
         );
 
+        tracing::info!(
+            target: TRACING_TARGET_CONFIG,
+            max_body_size = self.body_limits.max_body_size,
+            max_upload_size = self.body_limits.max_upload_size,
+            "Body limit configuration"
+        );
+
         tracing::info!(
             target: TRACING_TARGET_CONFIG,
             openapi_path = %self.openapi.open_api_json,
@@ -110,6 +131,27 @@ impl From<CorsArgs> for CorsConfig {
     }
 }
 
+/// Request body limit arguments, in bytes.
+#[derive(Debug, Clone, Args)]
+pub struct BodyLimitArgs {
+    /// Maximum body size of regular API requests.
+    #[arg(long, env = "MAX_BODY_SIZE", default_value_t = DEFAULT_MAX_BODY_SIZE)]
+    pub max_body_size: usize,
+
+    /// Maximum body size of uploads, across all files of a request.
+    #[arg(long, env = "MAX_UPLOAD_SIZE", default_value_t = DEFAULT_MAX_FILE_BODY_SIZE)]
+    pub max_upload_size: usize,
+}
+
+impl From<BodyLimitArgs> for BodyLimitConfig {
+    fn from(args: BodyLimitArgs) -> Self {
+        Self {
+            max_body_size: args.max_body_size,
+            max_upload_size: args.max_upload_size,
+        }
+    }
+}
+
 /// OpenAPI documentation path arguments.
 #[derive(Debug, Clone, Args)]
 pub struct OpenApiArgs {
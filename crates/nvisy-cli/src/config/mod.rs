@@ -6,8 +6,10 @@
 //! Cli
 //! ├── server: ServerConfig         # Host, port, TLS, shutdown
 //! ├── middleware: MiddlewareConfig  # CORS, OpenAPI, recovery/timeouts
+//! ├── preflight: PreflightArgs      # Startup validation
 //! ├── service: ServiceArgs          # Database, NATS, auth keys
-//! └── reqwest: ReqwestArgs          # HTTP client for webhooks
+//! ├── reqwest: ReqwestArgs          # HTTP client for webhooks
+//! └── webhook_circuit: CircuitBreakerArgs # Per-endpoint webhook circuits
 //! ```
 //!
 //! The `*Args` structs carry the clap/env wiring and convert into the plain
@@ -26,24 +28,29 @@
 //! ```
 
 mod middleware;
+mod preflight;
 mod server;
 mod service;
 mod webhook;
 
 use std::process;
+use std::sync::Arc;
 
 use clap::Parser;
+use nvisy_core::clock::SystemClock;
+use nvisy_core::id::RandomIds;
 use nvisy_server::service::ServiceState;
-use nvisy_webhook::WebhookService;
 use nvisy_webhook::reqwest::ReqwestClient;
+use nvisy_webhook::{CircuitBreakerProvider, WebhookService};
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
 pub use self::middleware::MiddlewareConfig;
+pub use self::preflight::PreflightArgs;
 pub use self::server::ServerConfig;
 pub use self::service::ServiceArgs;
-pub use self::webhook::ReqwestArgs;
+pub use self::webhook::{CircuitBreakerArgs, ReqwestArgs};
 use crate::server::TRACING_TARGET_STARTUP;
 
 /// Tracing target for configuration events.
@@ -54,8 +61,10 @@ pub const TRACING_TARGET_CONFIG: &str = "nvisy_cli::config";
 /// Combines all configuration groups for the nvisy server:
 /// - [`ServerConfig`]: Network binding and TLS
 /// - [`MiddlewareConfig`]: HTTP middleware (CORS, OpenAPI, recovery)
+/// - [`PreflightArgs`]: Startup validation of configured backends
 /// - [`ServiceArgs`]: External service connections (Postgres, NATS, auth keys)
 /// - [`ReqwestArgs`]: HTTP client configuration for webhooks
+/// - [`CircuitBreakerArgs`]: Circuit breaking of failing webhook endpoints
 #[derive(Debug, Clone, Parser)]
 #[command(name = "nvisy")]
 #[command(about = "Nvisy document processing server")]
@@ -69,6 +78,10 @@ pub struct Cli {
     #[clap(flatten)]
     pub middleware: MiddlewareConfig,
 
+    /// Startup validation configuration.
+    #[clap(flatten)]
+    pub preflight: PreflightArgs,
+
     /// External service configuration (databases, message queues).
     #[clap(flatten)]
     pub service: ServiceArgs,
@@ -76,6 +89,10 @@ pub struct Cli {
     /// HTTP client configuration for webhook delivery.
     #[clap(flatten)]
     pub reqwest: ReqwestArgs,
+
+    /// Circuit breaker configuration for webhook endpoints.
+    #[clap(flatten)]
+    pub webhook_circuit: CircuitBreakerArgs,
 }
 
 impl Cli {
@@ -137,6 +154,7 @@ impl Cli {
 
         tracing::info!(
             target: TRACING_TARGET_CONFIG,
+            postgres_replicas = self.service.postgres.postgres_replica_urls.len(),
             postgres_max_connections = self.service.postgres.postgres_max_connections,
             postgres_connection_timeout = ?self.service.postgres.postgres_connection_timeout,
             postgres_idle_timeout = ?self.service.postgres.postgres_idle_timeout,
@@ -150,6 +168,8 @@ impl Cli {
             cfg!(feature = "tls").then_some("tls"),
             cfg!(feature = "otel").then_some("otel"),
             cfg!(feature = "dotenv").then_some("dotenv"),
+            cfg!(feature = "fips").then_some("fips"),
+            cfg!(feature = "redis").then_some("redis"),
         ]
         .into_iter()
         .flatten()
@@ -157,22 +177,46 @@ impl Cli {
     }
 
     /// Creates webhook service from CLI configuration.
-    pub fn webhook_service(&self) -> WebhookService {
-        ReqwestClient::new(self.reqwest.clone().into()).into_service()
+    ///
+    /// Payloads are signed with the provider of the configured crypto policy,
+    /// and endpoints that keep failing are cut off by their circuit.
+    pub fn webhook_service(&self) -> anyhow::Result<WebhookService> {
+        let crypto = self.service.crypto.crypto_policy.provider()?;
+        let client = ReqwestClient::new(self.reqwest.clone().into()).with_crypto_provider(crypto);
+        Ok(WebhookService::new(CircuitBreakerProvider::new(
+            client,
+            self.webhook_circuit.clone().into(),
+        )))
     }
 
     /// Initializes application state from CLI configuration.
     pub async fn service_state(&self) -> anyhow::Result<ServiceState> {
-        let webhook = self.webhook_service();
+        let webhook = self.webhook_service()?;
         let service = self.service.clone();
         Ok(ServiceState::from_config(
             service.postgres.into(),
             service.nats.into(),
+            #[cfg(feature = "redis")]
+            service.redis.into(),
             service.session_keys.into(),
+            service.audit.into(),
             service.crypto.into(),
             service.engine.into(),
             service.health.into(),
+            service.oidc.into(),
+            service.operations.into(),
+            service.policy.into(),
+            service.privacy.into(),
+            service.residency.into(),
+            service.retention.into(),
+            service.key_migration.into(),
+            service.credential_rotation.into(),
+            service.garbage.into(),
+            service.storage_costs.into(),
+            service.secrets.into(),
             webhook,
+            Arc::new(SystemClock),
+            Arc::new(RandomIds),
         )
         .await?)
     }
@@ -0,0 +1,25 @@
+//! Startup validation configuration.
+//!
+//! Controls the preflight phase that checks every configured backend before
+//! the server starts.
+//!
+//! # Example
+//!
+//! ```bash
+//! # Validate the configuration and exit without starting the server
+//! nvisy-cli --check-config
+//! ```
+
+use clap::Args;
+
+/// Preflight validation configuration.
+#[derive(Debug, Clone, Default, Args)]
+pub struct PreflightArgs {
+    /// Skips the startup validation of configured backends.
+    #[arg(long, env = "SKIP_PREFLIGHT")]
+    pub skip_preflight: bool,
+
+    /// Runs the startup validation, prints the report and exits.
+    #[arg(long, conflicts_with = "skip_preflight")]
+    pub check_config: bool,
+}
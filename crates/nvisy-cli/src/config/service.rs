@@ -9,7 +9,14 @@ use std::time::Duration;
 use clap::Args;
 use nvisy_nats::NatsConfig;
 use nvisy_postgres::PgConfig;
-use nvisy_server::service::{CryptoConfig, EngineConfig, HealthConfig, SessionKeysConfig};
+#[cfg(feature = "redis")]
+use nvisy_redis::RedisConfig;
+use nvisy_server::service::{
+    AuditConfig, CredentialRotationConfig, CryptoConfig, CryptoPolicy, EngineConfig,
+    GarbageCollectionConfig, HealthConfig, KeyMigrationConfig, KmsProvider, OidcConfig,
+    OperationConfig, PolicyConfig, PrivacyConfig, ResidencyConfig, RetentionConfig, SecretsBackend,
+    SecretsConfig, SessionKeysConfig, StorageCostConfig, WatchdogConfig,
+};
 
 /// Aggregated external-service arguments (database, NATS, auth keys).
 #[derive(Debug, Clone, Args)]
@@ -22,11 +29,20 @@ pub struct ServiceArgs {
     #[clap(flatten)]
     pub nats: NatsArgs,
 
+    /// Redis configuration.
+    #[cfg(feature = "redis")]
+    #[clap(flatten)]
+    pub redis: RedisArgs,
+
     /// JWT session key paths.
     #[clap(flatten)]
     pub session_keys: SessionKeysArgs,
 
-    /// Master encryption key path.
+    /// Audit log retention configuration.
+    #[clap(flatten)]
+    pub audit: AuditArgs,
+
+    /// Master encryption key path and crypto policy.
     #[clap(flatten)]
     pub crypto: CryptoArgs,
 
@@ -37,6 +53,54 @@ pub struct ServiceArgs {
     /// Health monitoring configuration.
     #[clap(flatten)]
     pub health: HealthArgs,
+
+    /// Single sign-on configuration.
+    #[clap(flatten)]
+    pub oidc: OidcArgs,
+
+    /// Long-running operation retention configuration.
+    #[clap(flatten)]
+    pub operations: OperationArgs,
+
+    /// Role-based access control configuration.
+    #[clap(flatten)]
+    pub policy: PolicyArgs,
+
+    /// Differential privacy configuration.
+    #[clap(flatten)]
+    pub privacy: PrivacyArgs,
+
+    /// Data residency configuration.
+    #[clap(flatten)]
+    pub residency: ResidencyArgs,
+
+    /// Workspace retention purge configuration.
+    #[clap(flatten)]
+    pub retention: RetentionArgs,
+
+    /// Legacy object key migration configuration.
+    #[clap(flatten)]
+    pub key_migration: KeyMigrationArgs,
+
+    /// Credential vault rotation configuration.
+    #[clap(flatten)]
+    pub credential_rotation: CredentialRotationArgs,
+
+    /// Garbage collection configuration.
+    #[clap(flatten)]
+    pub garbage: GarbageCollectionArgs,
+
+    /// Storage cost reporting configuration.
+    #[clap(flatten)]
+    pub storage_costs: StorageCostArgs,
+
+    /// Secrets manager configuration.
+    #[clap(flatten)]
+    pub secrets: SecretsArgs,
+
+    /// Background worker watchdog configuration.
+    #[clap(flatten)]
+    pub worker: WorkerArgs,
 }
 
 /// Postgres connection arguments.
@@ -46,6 +110,14 @@ pub struct PgArgs {
     #[arg(long = "postgres-url", env = "POSTGRES_URL")]
     pub postgres_url: String,
 
+    /// Read replica connection URLs (comma-separated).
+    #[arg(
+        long = "postgres-replica-urls",
+        env = "POSTGRES_REPLICA_URLS",
+        value_delimiter = ','
+    )]
+    pub postgres_replica_urls: Vec<String>,
+
     /// Maximum number of connections in the pool.
     #[arg(
         long = "postgres-max-connections",
@@ -69,15 +141,27 @@ pub struct PgArgs {
         value_parser = humantime::parse_duration,
     )]
     pub postgres_idle_timeout: Option<Duration>,
+
+    /// Queries slower than this are logged as warnings (e.g. `500ms`, `0s`
+    /// disables).
+    #[arg(
+        long = "postgres-slow-query-threshold",
+        env = "POSTGRES_SLOW_QUERY_THRESHOLD",
+        default_value = "500ms",
+        value_parser = humantime::parse_duration,
+    )]
+    pub postgres_slow_query_threshold: Duration,
 }
 
 impl From<PgArgs> for PgConfig {
     fn from(args: PgArgs) -> Self {
         Self {
             postgres_url: args.postgres_url,
+            postgres_replica_urls: args.postgres_replica_urls,
             postgres_max_connections: args.postgres_max_connections,
             postgres_connection_timeout: args.postgres_connection_timeout,
             postgres_idle_timeout: args.postgres_idle_timeout,
+            postgres_slow_query_threshold: Some(args.postgres_slow_query_threshold),
         }
     }
 }
@@ -121,12 +205,52 @@ pub struct NatsArgs {
 impl From<NatsArgs> for NatsConfig {
     fn from(args: NatsArgs) -> Self {
         Self {
-            nats_url: args.nats_url,
-            nats_token: args.nats_token,
             nats_client_name: args.nats_client_name,
             nats_connect_timeout: args.nats_connect_timeout,
             nats_request_timeout: args.nats_request_timeout,
             nats_max_reconnects: args.nats_max_reconnects,
+            ..Self::new(args.nats_url, args.nats_token)
+        }
+    }
+}
+
+/// Redis connection arguments.
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone, Args)]
+pub struct RedisArgs {
+    /// Redis server URL (comma-separated cluster nodes with `redis-cluster`).
+    #[arg(long = "redis-url", env = "REDIS_URL")]
+    pub redis_url: String,
+
+    /// Password, overriding one in the URL; may be a secret reference.
+    #[arg(long = "redis-password", env = "REDIS_PASSWORD")]
+    pub redis_password: Option<String>,
+
+    /// Connection timeout (e.g. `10s`).
+    #[arg(
+        long = "redis-connect-timeout",
+        env = "REDIS_CONNECT_TIMEOUT",
+        value_parser = humantime::parse_duration,
+    )]
+    pub redis_connect_timeout: Option<Duration>,
+
+    /// Response timeout of a single command (e.g. `5s`).
+    #[arg(
+        long = "redis-response-timeout",
+        env = "REDIS_RESPONSE_TIMEOUT",
+        value_parser = humantime::parse_duration,
+    )]
+    pub redis_response_timeout: Option<Duration>,
+}
+
+#[cfg(feature = "redis")]
+impl From<RedisArgs> for RedisConfig {
+    fn from(args: RedisArgs) -> Self {
+        Self {
+            redis_url: args.redis_url,
+            redis_password: args.redis_password,
+            redis_connect_timeout: args.redis_connect_timeout,
+            redis_response_timeout: args.redis_response_timeout,
         }
     }
 }
@@ -156,7 +280,38 @@ impl From<SessionKeysArgs> for SessionKeysConfig {
     }
 }
 
-/// Encryption key path arguments.
+/// Audit log arguments.
+#[derive(Debug, Clone, Args)]
+pub struct AuditArgs {
+    /// How long audit records are kept (e.g. `365d`).
+    #[arg(
+        long = "audit-retention",
+        env = "AUDIT_RETENTION",
+        default_value = "365d",
+        value_parser = humantime::parse_duration,
+    )]
+    pub retention: Duration,
+
+    /// How often expired audit records are removed (e.g. `1h`).
+    #[arg(
+        long = "audit-cleanup-interval",
+        env = "AUDIT_CLEANUP_INTERVAL",
+        default_value = "1h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub cleanup_interval: Duration,
+}
+
+impl From<AuditArgs> for AuditConfig {
+    fn from(args: AuditArgs) -> Self {
+        Self {
+            retention: args.retention,
+            cleanup_interval: args.cleanup_interval,
+        }
+    }
+}
+
+/// Encryption key path and crypto policy arguments.
 #[derive(Debug, Clone, Args)]
 pub struct CryptoArgs {
     /// File path to the 32-byte master encryption key.
@@ -166,6 +321,26 @@ pub struct CryptoArgs {
         default_value = "./encryption.key"
     )]
     pub key_path: PathBuf,
+
+    /// Crypto provider for hashing, signing and encryption (`standard` or
+    /// `fips`). `fips` requires a build with the `fips` feature.
+    #[arg(long, env = "CRYPTO_POLICY", default_value = "standard")]
+    pub crypto_policy: CryptoPolicy,
+
+    /// Key management service wrapping envelope data keys (`local` or
+    /// `aws`). `aws` requires a build with the `aws-kms` feature.
+    #[arg(long, env = "KMS_PROVIDER", default_value = "local")]
+    pub kms_provider: KmsProvider,
+
+    /// Key-encryption key ID or ARN for the `aws` provider.
+    #[arg(long, env = "KMS_KEY_ID")]
+    pub kms_key_id: Option<String>,
+
+    /// Directory of additional credential vault keys, one 32-byte
+    /// `v{N}.key` file per version starting at 2. The highest version seals
+    /// new credentials.
+    #[arg(long, env = "CREDENTIAL_KEYS_DIRPATH")]
+    pub credential_keys_path: Option<PathBuf>,
 }
 
 /// Redaction engine arguments.
@@ -190,6 +365,10 @@ impl From<CryptoArgs> for CryptoConfig {
     fn from(args: CryptoArgs) -> Self {
         Self {
             key_path: args.key_path,
+            policy: args.crypto_policy,
+            kms_provider: args.kms_provider,
+            kms_key_id: args.kms_key_id,
+            credential_keys_path: args.credential_keys_path,
         }
     }
 }
@@ -214,3 +393,384 @@ impl From<HealthArgs> for HealthConfig {
         }
     }
 }
+
+/// Single sign-on arguments.
+#[derive(Debug, Clone, Args)]
+pub struct OidcArgs {
+    /// Optional path to a JSON file declaring the OpenID Connect identity
+    /// provider. Absent means single sign-on is disabled.
+    #[arg(long = "oidc-config-filepath", env = "OIDC_CONFIG_FILEPATH")]
+    pub config_path: Option<PathBuf>,
+}
+
+impl From<OidcArgs> for OidcConfig {
+    fn from(args: OidcArgs) -> Self {
+        Self {
+            config_path: args.config_path,
+        }
+    }
+}
+
+/// Long-running operation arguments.
+#[derive(Debug, Clone, Args)]
+pub struct OperationArgs {
+    /// How long a finished operation and its result artifact are kept
+    /// (e.g. `7d`).
+    #[arg(
+        long = "operation-result-ttl",
+        env = "OPERATION_RESULT_TTL",
+        default_value = "7d",
+        value_parser = humantime::parse_duration,
+    )]
+    pub result_ttl: Duration,
+
+    /// How long a signed artifact download link stays valid (e.g. `15m`).
+    #[arg(
+        long = "operation-link-ttl",
+        env = "OPERATION_LINK_TTL",
+        default_value = "15m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub link_ttl: Duration,
+
+    /// How often expired operations are removed (e.g. `10m`).
+    #[arg(
+        long = "operation-cleanup-interval",
+        env = "OPERATION_CLEANUP_INTERVAL",
+        default_value = "10m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub cleanup_interval: Duration,
+}
+
+impl From<OperationArgs> for OperationConfig {
+    fn from(args: OperationArgs) -> Self {
+        Self {
+            result_ttl: args.result_ttl,
+            link_ttl: args.link_ttl,
+            cleanup_interval: args.cleanup_interval,
+        }
+    }
+}
+
+/// Role-based access control arguments.
+#[derive(Debug, Clone, Args)]
+pub struct PolicyArgs {
+    /// How long a member's resolved roles are cached (e.g. `30s`).
+    #[arg(
+        long = "role-cache-ttl",
+        env = "ROLE_CACHE_TTL",
+        default_value = "30s",
+        value_parser = humantime::parse_duration,
+    )]
+    pub role_cache_ttl: Duration,
+}
+
+impl From<PolicyArgs> for PolicyConfig {
+    fn from(args: PolicyArgs) -> Self {
+        Self {
+            role_cache_ttl: args.role_cache_ttl,
+        }
+    }
+}
+
+/// Differential privacy arguments for aggregate analytics.
+#[derive(Debug, Clone, Args)]
+pub struct PrivacyArgs {
+    /// Epsilon spent by each noised analytics query.
+    #[arg(
+        long = "privacy-query-epsilon",
+        env = "PRIVACY_QUERY_EPSILON",
+        default_value = "0.1"
+    )]
+    pub query_epsilon: f64,
+
+    /// Total epsilon a workspace may spend within one budget window.
+    #[arg(
+        long = "privacy-window-budget",
+        env = "PRIVACY_WINDOW_BUDGET",
+        default_value = "2.0"
+    )]
+    pub window_budget: f64,
+
+    /// Length of a privacy budget window (e.g. `24h`).
+    #[arg(
+        long = "privacy-budget-window",
+        env = "PRIVACY_BUDGET_WINDOW",
+        default_value = "24h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub budget_window: Duration,
+
+    /// Most events a single user may contribute to one noised query.
+    #[arg(
+        long = "privacy-max-contributions",
+        env = "PRIVACY_MAX_CONTRIBUTIONS",
+        default_value = "20"
+    )]
+    pub max_contributions: u32,
+}
+
+impl From<PrivacyArgs> for PrivacyConfig {
+    fn from(args: PrivacyArgs) -> Self {
+        Self {
+            query_epsilon: args.query_epsilon,
+            window_budget: args.window_budget,
+            budget_window: args.budget_window,
+            max_contributions: args.max_contributions,
+        }
+    }
+}
+
+/// Data residency arguments.
+#[derive(Debug, Clone, Args)]
+pub struct ResidencyArgs {
+    /// Optional path to a JSON file declaring the backends of each pinned
+    /// data region. Absent means workspaces can only use the `global` region.
+    #[arg(long = "residency-config-filepath", env = "RESIDENCY_CONFIG_FILEPATH")]
+    pub config_path: Option<PathBuf>,
+}
+
+impl From<ResidencyArgs> for ResidencyConfig {
+    fn from(args: ResidencyArgs) -> Self {
+        Self {
+            config_path: args.config_path,
+        }
+    }
+}
+
+/// Workspace retention arguments.
+#[derive(Debug, Clone, Args)]
+pub struct RetentionArgs {
+    /// How often data past its workspace's retention policy is purged
+    /// (e.g. `1h`).
+    #[arg(
+        long = "retention-purge-interval",
+        env = "RETENTION_PURGE_INTERVAL",
+        default_value = "1h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub purge_interval: Duration,
+}
+
+impl From<RetentionArgs> for RetentionConfig {
+    fn from(args: RetentionArgs) -> Self {
+        Self {
+            purge_interval: args.purge_interval,
+        }
+    }
+}
+
+/// Legacy object key migration arguments.
+#[derive(Debug, Clone, Args)]
+pub struct KeyMigrationArgs {
+    /// How often file content under legacy object keys is moved to the
+    /// current key layout (e.g. `10m`).
+    #[arg(
+        long = "key-migration-interval",
+        env = "KEY_MIGRATION_INTERVAL",
+        default_value = "10m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub interval: Duration,
+}
+
+impl From<KeyMigrationArgs> for KeyMigrationConfig {
+    fn from(args: KeyMigrationArgs) -> Self {
+        Self {
+            interval: args.interval,
+        }
+    }
+}
+
+/// Credential vault rotation arguments.
+#[derive(Debug, Clone, Args)]
+pub struct CredentialRotationArgs {
+    /// How often connection credentials sealed under an older vault key are
+    /// resealed under the current one (e.g. `10m`).
+    #[arg(
+        long = "credential-rotation-interval",
+        env = "CREDENTIAL_ROTATION_INTERVAL",
+        default_value = "10m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub interval: Duration,
+}
+
+impl From<CredentialRotationArgs> for CredentialRotationConfig {
+    fn from(args: CredentialRotationArgs) -> Self {
+        Self {
+            interval: args.interval,
+        }
+    }
+}
+
+/// Garbage collection arguments.
+#[derive(Debug, Clone, Args)]
+pub struct GarbageCollectionArgs {
+    /// How often abandoned uploads and unreferenced objects are removed
+    /// (e.g. `30m`).
+    #[arg(
+        long = "gc-interval",
+        env = "GC_INTERVAL",
+        default_value = "30m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub interval: Duration,
+
+    /// How long an upload may take before its stored content is considered
+    /// abandoned (e.g. `6h`).
+    #[arg(
+        long = "gc-upload-ttl",
+        env = "GC_UPLOAD_TTL",
+        default_value = "6h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub upload_ttl: Duration,
+
+    /// How old an object no record refers to must be before it is removed
+    /// (e.g. `24h`).
+    #[arg(
+        long = "gc-orphan-grace-period",
+        env = "GC_ORPHAN_GRACE_PERIOD",
+        default_value = "24h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub orphan_grace_period: Duration,
+}
+
+impl From<GarbageCollectionArgs> for GarbageCollectionConfig {
+    fn from(args: GarbageCollectionArgs) -> Self {
+        Self {
+            interval: args.interval,
+            upload_ttl: args.upload_ttl,
+            orphan_grace_period: args.orphan_grace_period,
+        }
+    }
+}
+
+/// Storage cost reporting arguments.
+#[derive(Debug, Clone, Args)]
+pub struct StorageCostArgs {
+    /// How often stored objects are sampled for cost reports (e.g. `6h`).
+    #[arg(
+        long = "storage-cost-interval",
+        env = "STORAGE_COST_INTERVAL",
+        default_value = "6h",
+        value_parser = humantime::parse_duration,
+    )]
+    pub interval: Duration,
+
+    /// How long an object must go unmodified before it is priced as cold
+    /// storage (e.g. `90d`).
+    #[arg(
+        long = "storage-cold-after",
+        env = "STORAGE_COLD_AFTER",
+        default_value = "90d",
+        value_parser = humantime::parse_duration,
+    )]
+    pub cold_after: Duration,
+}
+
+impl From<StorageCostArgs> for StorageCostConfig {
+    fn from(args: StorageCostArgs) -> Self {
+        Self {
+            interval: args.interval,
+            cold_after: args.cold_after,
+        }
+    }
+}
+
+/// Secrets manager arguments.
+#[derive(Debug, Clone, Args)]
+pub struct SecretsArgs {
+    /// Secrets manager `secret://` references are resolved through (`env`,
+    /// `vault` or `aws`). `aws` requires a build with the `aws-secrets`
+    /// feature.
+    #[arg(long, env = "SECRETS_BACKEND", default_value = "env")]
+    pub secrets_backend: SecretsBackend,
+
+    /// How long a resolved secret is cached (e.g. `5m`).
+    #[arg(
+        long = "secrets-cache-ttl",
+        env = "SECRETS_CACHE_TTL",
+        default_value = "5m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub cache_ttl: Duration,
+
+    /// Prefix of the environment variables read by the `env` backend.
+    #[arg(
+        long = "secrets-env-prefix",
+        env = "SECRETS_ENV_PREFIX",
+        default_value = "NVISY_SECRET_"
+    )]
+    pub env_prefix: String,
+
+    /// Address of the Vault server for the `vault` backend.
+    #[arg(long = "vault-addr", env = "VAULT_ADDR")]
+    pub vault_address: Option<String>,
+
+    /// Token authenticating with Vault.
+    #[arg(long = "vault-token", env = "VAULT_TOKEN")]
+    pub vault_token: Option<String>,
+
+    /// Mount path of the Vault KV v2 engine.
+    #[arg(long = "vault-mount", env = "VAULT_MOUNT", default_value = "secret")]
+    pub vault_mount: String,
+}
+
+impl From<SecretsArgs> for SecretsConfig {
+    fn from(args: SecretsArgs) -> Self {
+        Self {
+            backend: args.secrets_backend,
+            cache_ttl: args.cache_ttl,
+            env_prefix: args.env_prefix,
+            vault_address: args.vault_address,
+            vault_token: args.vault_token,
+            vault_mount: args.vault_mount,
+        }
+    }
+}
+
+/// Background worker watchdog arguments.
+#[derive(Debug, Clone, Args)]
+pub struct WorkerArgs {
+    /// How long a worker may go without progress before it is restarted
+    /// (e.g. `5m`).
+    #[arg(
+        long = "worker-stall-timeout",
+        env = "WORKER_STALL_TIMEOUT",
+        default_value = "5m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub stall_timeout: Duration,
+
+    /// Restarts within the escalation window at which a worker is reported
+    /// as repeatedly failing.
+    #[arg(
+        long = "worker-escalation-restarts",
+        env = "WORKER_ESCALATION_RESTARTS",
+        default_value = "3"
+    )]
+    pub escalation_restarts: usize,
+
+    /// Window over which worker restarts are counted (e.g. `30m`).
+    #[arg(
+        long = "worker-escalation-window",
+        env = "WORKER_ESCALATION_WINDOW",
+        default_value = "30m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub escalation_window: Duration,
+}
+
+impl From<WorkerArgs> for WatchdogConfig {
+    fn from(args: WorkerArgs) -> Self {
+        Self {
+            stall_timeout: args.stall_timeout,
+            escalation_restarts: args.escalation_restarts,
+            escalation_window: args.escalation_window,
+        }
+    }
+}
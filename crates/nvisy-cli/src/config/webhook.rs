@@ -1,8 +1,9 @@
-//! Webhook HTTP client configuration arguments.
+//! Webhook HTTP client and circuit breaker configuration arguments.
 
 use std::time::Duration;
 
 use clap::Args;
+use nvisy_core::circuit_breaker::CircuitBreakerConfig;
 use nvisy_webhook::reqwest::ReqwestConfig;
 
 /// Reqwest HTTP client arguments.
@@ -58,3 +59,48 @@ impl From<ReqwestArgs> for ReqwestConfig {
         }
     }
 }
+
+/// Circuit breaker arguments for webhook delivery.
+///
+/// Each endpoint origin gets its own circuit.
+#[derive(Debug, Clone, Args)]
+pub struct CircuitBreakerArgs {
+    /// Share of recent deliveries to an endpoint that must fail before its
+    /// circuit opens (`0.0`-`1.0`).
+    #[arg(
+        long = "webhook-circuit-failure-rate",
+        env = "WEBHOOK_CIRCUIT_FAILURE_RATE",
+        default_value = "0.5"
+    )]
+    pub failure_rate_threshold: f64,
+
+    /// Duration after which a delivery counts as slow (e.g. `10s`).
+    #[arg(
+        long = "webhook-circuit-slow-call",
+        env = "WEBHOOK_CIRCUIT_SLOW_CALL",
+        default_value = "10s",
+        value_parser = humantime::parse_duration,
+    )]
+    pub slow_call_duration: Duration,
+
+    /// How long an open circuit rejects deliveries before probing the
+    /// endpoint again (e.g. `30s`).
+    #[arg(
+        long = "webhook-circuit-open-duration",
+        env = "WEBHOOK_CIRCUIT_OPEN_DURATION",
+        default_value = "30s",
+        value_parser = humantime::parse_duration,
+    )]
+    pub open_duration: Duration,
+}
+
+impl From<CircuitBreakerArgs> for CircuitBreakerConfig {
+    fn from(args: CircuitBreakerArgs) -> Self {
+        Self {
+            failure_rate_threshold: args.failure_rate_threshold,
+            slow_call_duration: args.slow_call_duration,
+            open_duration: args.open_duration,
+            ..Self::default()
+        }
+    }
+}
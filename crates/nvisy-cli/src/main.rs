@@ -3,18 +3,24 @@
 #![doc = include_str!("../README.md")]
 
 mod config;
+mod preflight;
 mod server;
 
 use std::process;
 
 use axum::Router;
+use nvisy_nats::stream::{ActiveConsumer, ConsumerSpec, WebhookStream};
 use nvisy_server::handler::{CustomRoutes, routes};
 use nvisy_server::middleware::*;
-use nvisy_server::service::{ServiceState, WebhookWorker};
-use tokio_util::sync::CancellationToken;
+use nvisy_server::service::{
+    AuditRetention, ChangeEventBridge, CredentialRotation, GarbageCollector, KeyMigration,
+    OperationCleanup, RetentionPurge, ServiceState, StorageCostReporter, WebhookWorker,
+    WorkerHandles,
+};
+use nvisy_webhook::provider::WebhookRequest;
 
 use crate::config::{Cli, MiddlewareConfig};
-use crate::server::TRACING_TARGET_SHUTDOWN;
+use crate::server::{TRACING_TARGET_SHUTDOWN, TRACING_TARGET_STARTUP};
 
 #[tokio::main]
 async fn main() {
@@ -42,49 +48,159 @@ async fn run() -> anyhow::Result<()> {
 
     cli.log();
 
+    // Validate every configured backend before connecting for real
+    if !cli.preflight.skip_preflight {
+        let report = preflight::run(&cli.service).await;
+        report.log();
+        report.ensure_passed()?;
+
+        if cli.preflight.check_config {
+            return Ok(());
+        }
+    }
+
     // Initialize application state
     let state = cli.service_state().await?;
 
     // Build router
-    let router = create_router(state.clone(), &cli.middleware);
+    let router = create_router(state.clone(), &cli.middleware)?;
 
-    // Create cancellation token for graceful shutdown of workers
-    let cancel = CancellationToken::new();
+    // Bring durable consumers in line with the current config
+    let webhook_consumer = reconcile_consumers(&state).await?;
 
-    // Spawn webhook worker (logs lifecycle events internally)
-    let webhook_worker = WebhookWorker::new(state.nats.clone(), state.webhook.clone());
-    let worker_cancel = cancel.clone();
-    let worker_handle = tokio::spawn(async move {
-        let _ = webhook_worker.run(worker_cancel).await;
-    });
+    // Spawn background workers under the liveness watchdog
+    let mut workers = WorkerHandles::new(cli.service.worker.clone().into());
+    spawn_workers(&mut workers, &state, webhook_consumer);
 
     // Run the HTTP server
     let server_result = server::serve(router, cli.server).await;
 
-    // Signal workers to stop
-    cancel.cancel();
-
-    // Wait for worker to finish
-    if let Err(err) = worker_handle.await {
-        tracing::error!(
-            target: TRACING_TARGET_SHUTDOWN,
-            error = %err,
-            "Webhook worker task panicked"
-        );
-    }
+    // Stop workers and wait for them to finish
+    workers.shutdown().await;
 
     server_result?;
     Ok(())
 }
 
+/// Reconciles the webhook delivery consumer against its current spec.
+///
+/// A changed spec is rolled out as a new consumer version starting right
+/// after the old one's ack floor, so deliveries continue without loss.
+/// Returns the handle the webhook worker follows.
+async fn reconcile_consumers(state: &ServiceState) -> anyhow::Result<ActiveConsumer> {
+    // Make sure the stream exists before looking at its consumers.
+    state.nats.webhook_subscriber::<WebhookRequest>().await?;
+
+    let active = ActiveConsumer::paused();
+    let outcome = state
+        .nats
+        .consumer_migrator()
+        .reconcile(&ConsumerSpec::for_stream::<WebhookStream>(), &active)
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET_STARTUP,
+        consumer = outcome.consumer(),
+        outcome = ?outcome,
+        "Webhook consumer reconciled"
+    );
+
+    Ok(active)
+}
+
+/// Spawns the webhook delivery worker, the change event bridge, the
+/// operation cleanup worker, the audit retention worker, the workspace
+/// retention purge and the legacy object key migration.
+///
+/// Each restart builds a fresh instance from cloned clients, so a stuck
+/// worker's subscriptions and connections are dropped with it.
+fn spawn_workers(
+    workers: &mut WorkerHandles,
+    state: &ServiceState,
+    webhook_consumer: ActiveConsumer,
+) {
+    let (nats, postgres, webhook) = (
+        state.nats.clone(),
+        state.postgres.clone(),
+        state.webhook.clone(),
+    );
+    workers.spawn("webhook", move |heartbeat, cancel| {
+        let worker = WebhookWorker::new(
+            nats.clone(),
+            postgres.clone(),
+            webhook.clone(),
+            webhook_consumer.clone(),
+        );
+        async move { worker.run(heartbeat, cancel).await }
+    });
+
+    let (postgres, webhook_emitter) = (state.postgres.clone(), state.webhook_emitter.clone());
+    workers.spawn("change_bridge", move |heartbeat, cancel| {
+        let bridge = ChangeEventBridge::new(postgres.clone(), webhook_emitter.clone());
+        async move { bridge.run(heartbeat, cancel).await }
+    });
+
+    let operations = state.operations.clone();
+    workers.spawn("operation_cleanup", move |heartbeat, cancel| {
+        let cleanup = OperationCleanup::new(operations.clone());
+        async move { cleanup.run(heartbeat, cancel).await }
+    });
+
+    let audit = state.audit.clone();
+    workers.spawn("audit_retention", move |heartbeat, cancel| {
+        let retention = AuditRetention::new(audit.clone());
+        async move { retention.run(heartbeat, cancel).await }
+    });
+
+    let retention = state.retention.clone();
+    workers.spawn("retention_purge", move |heartbeat, cancel| {
+        let purge = RetentionPurge::new(retention.clone());
+        async move { purge.run(heartbeat, cancel).await }
+    });
+
+    let key_migration = state.key_migration.clone();
+    workers.spawn("key_migration", move |heartbeat, cancel| {
+        let migration = KeyMigration::new(key_migration.clone());
+        async move { migration.run(heartbeat, cancel).await }
+    });
+
+    let credential_rotation = state.credential_rotation.clone();
+    workers.spawn("credential_rotation", move |heartbeat, cancel| {
+        let rotation = CredentialRotation::new(credential_rotation.clone());
+        async move { rotation.run(heartbeat, cancel).await }
+    });
+
+    let garbage = state.garbage.clone();
+    workers.spawn("garbage_collection", move |heartbeat, cancel| {
+        let collector = GarbageCollector::new(garbage.clone());
+        async move { collector.run(heartbeat, cancel).await }
+    });
+
+    let storage_costs = state.storage_costs.clone();
+    workers.spawn("storage_costs", move |heartbeat, cancel| {
+        let reporter = StorageCostReporter::new(storage_costs.clone());
+        async move { reporter.run(heartbeat, cancel).await }
+    });
+}
+
 /// Creates the router with all middleware layers applied.
-fn create_router(state: ServiceState, middleware: &MiddlewareConfig) -> Router {
-    let api_routes = routes(CustomRoutes::new(), state.clone()).with_state(state);
+///
+/// With the `grpc` feature the gRPC services are merged in first, so they
+/// share the middleware, the port and the TLS setup of the REST API.
+fn create_router(state: ServiceState, middleware: &MiddlewareConfig) -> anyhow::Result<Router> {
+    let api_routes = routes(CustomRoutes::new(), state.clone()).with_state(state.clone());
+
+    #[cfg(feature = "grpc")]
+    let api_routes = api_routes.merge(nvisy_server::grpc::routes(state)?);
 
-    api_routes
+    Ok(api_routes
         .with_open_api(&middleware.openapi())
         .with_metrics()
-        .with_security(&middleware.cors(), &Default::default())
+        .with_security(
+            &middleware.cors(),
+            &Default::default(),
+            &middleware.body_limits(),
+        )
         .with_observability()
-        .with_recovery(&middleware.recovery())
+        .with_recovery(&middleware.recovery()))
 }
@@ -0,0 +1,447 @@
+//! Per-backend preflight checks.
+//!
+//! Each function probes one backend, records its outcome in the report, and
+//! returns the client it connected when later checks depend on it.
+
+use std::error::Error as StdError;
+use std::time::Duration;
+
+use nvisy_core::health::{HealthCheck, HealthStatus};
+use nvisy_nats::{NatsClient, NatsConfig};
+use nvisy_postgres::{PgClient, PgClientMigrationExt, PgConfig, PgPoolRole};
+#[cfg(feature = "redis")]
+use nvisy_redis::{RedisClient, RedisConfig};
+use nvisy_server::service::{
+    Caches, CryptoConfig, CryptoService, EngineConfig, EngineService, OidcConfig, OidcService,
+    RegionBackends, ResidencyConfig, ResidencyService, SecretsConfig, SecretsService, SessionKeys,
+    SessionKeysConfig,
+};
+
+use super::{PreflightCheck, PreflightReport};
+
+/// Upper bound for any single network probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs `future` bounded by [`PROBE_TIMEOUT`], rendering any error.
+async fn probe<T, E>(future: impl Future<Output = Result<T, E>>) -> Result<T, String>
+where
+    E: StdError,
+{
+    match tokio::time::timeout(PROBE_TIMEOUT, future).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(error_chain(&error)),
+        Err(_) => Err(format!("timed out after {PROBE_TIMEOUT:?}")),
+    }
+}
+
+/// Renders an error followed by its sources, `outer: inner: ...`.
+fn error_chain(error: &dyn StdError) -> String {
+    let mut rendered = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        rendered.push_str(": ");
+        rendered.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    rendered
+}
+
+/// Checks Postgres connectivity, migration rights and read replicas.
+pub async fn postgres(report: &mut PreflightReport, config: PgConfig) {
+    const COMPONENT: &str = "postgres";
+
+    let connected = match PgClient::new(config) {
+        Ok(client) => probe(client.get_connection()).await.map(|_| client),
+        Err(error) => Err(error_chain(&error)),
+    };
+
+    let client = match connected {
+        Ok(client) => {
+            report.push(PreflightCheck::pass(COMPONENT, "connect", "connected"));
+            client
+        }
+        Err(error) => {
+            report.push(PreflightCheck::fail(COMPONENT, "connect", error).with_hint(
+                "Verify POSTGRES_URL (host, port, database, credentials) and that the server \
+                 accepts connections from this host",
+            ));
+            report.push(PreflightCheck::skip(
+                COMPONENT,
+                "migration privileges",
+                "postgres/connect",
+            ));
+            return;
+        }
+    };
+
+    let check = match probe(client.get_migration_privileges()).await {
+        Ok(privileges) if privileges.can_migrate() => PreflightCheck::pass(
+            COMPONENT,
+            "migration privileges",
+            format!("role '{}' can apply migrations", privileges.role),
+        ),
+        Ok(privileges) => PreflightCheck::fail(
+            COMPONENT,
+            "migration privileges",
+            format!(
+                "role '{}' lacks {}",
+                privileges.role,
+                privileges.missing().join(", ")
+            ),
+        )
+        .with_hint(format!(
+            "Migrations run at startup; grant the missing privileges (e.g. `GRANT CREATE ON \
+             DATABASE <db> TO {role}; GRANT CREATE ON SCHEMA public TO {role};`) or connect as \
+             the schema owner",
+            role = privileges.role
+        )),
+        Err(error) => PreflightCheck::fail(COMPONENT, "migration privileges", error)
+            .with_hint("Check that the role can query the system catalogs"),
+    };
+    report.push(check);
+
+    if client.has_replicas() {
+        report.push(postgres_replicas(&client).await);
+    }
+}
+
+/// Probes every read replica; unreachable replicas only degrade reads.
+async fn postgres_replicas(client: &PgClient) -> PreflightCheck {
+    const COMPONENT: &str = "postgres";
+
+    let health = client.check_health().await;
+    let unreachable: Vec<_> = client
+        .pool_statuses()
+        .into_iter()
+        .filter(|status| status.role == PgPoolRole::Replica && !status.healthy)
+        .map(|status| status.url)
+        .collect();
+
+    if health.status == HealthStatus::Healthy && unreachable.is_empty() {
+        return PreflightCheck::pass(COMPONENT, "read replicas", "all replicas reachable");
+    }
+
+    PreflightCheck::warn(
+        COMPONENT,
+        "read replicas",
+        format!("unreachable: {}", unreachable.join(", ")),
+    )
+    .with_hint(
+        "Reads fall back to the primary until the replicas recover; check POSTGRES_REPLICA_URLS",
+    )
+}
+
+/// Checks that the secrets provider is configured and reachable.
+///
+/// Returns the service for checks whose config may reference secrets.
+pub async fn secrets(
+    report: &mut PreflightReport,
+    config: SecretsConfig,
+) -> Option<SecretsService> {
+    const COMPONENT: &str = "secrets";
+
+    let secrets = match SecretsService::from_config(&config).await {
+        Ok(secrets) => {
+            report.push(PreflightCheck::pass(
+                COMPONENT,
+                "provider",
+                format!("provider '{}'", secrets.provider().name()),
+            ));
+            secrets
+        }
+        Err(error) => {
+            report.push(
+                PreflightCheck::fail(COMPONENT, "provider", error_chain(&error)).with_hint(
+                    "SECRETS_BACKEND=vault needs VAULT_ADDR and VAULT_TOKEN; \
+                     SECRETS_BACKEND=aws needs a build with the `aws-secrets` feature",
+                ),
+            );
+            return None;
+        }
+    };
+
+    // Listing needs broader rights than reading, so a failure only warns.
+    let check = match probe(secrets.list()).await {
+        Ok(names) => PreflightCheck::pass(
+            COMPONENT,
+            "list",
+            format!("{} secret(s) visible", names.len()),
+        ),
+        Err(error) => PreflightCheck::warn(COMPONENT, "list", error).with_hint(
+            "Check that the provider is reachable; reads may still succeed if only listing is \
+             denied",
+        ),
+    };
+    report.push(check);
+
+    Some(secrets)
+}
+
+/// Checks NATS connectivity, JetStream and object store writes.
+///
+/// Returns the connected client for checks that need NATS.
+pub async fn nats(
+    report: &mut PreflightReport,
+    mut config: NatsConfig,
+    secrets: Option<&SecretsService>,
+) -> Option<NatsClient> {
+    const COMPONENT: &str = "nats";
+
+    if let Some(secrets) = secrets {
+        match secrets.resolve_str(&config.nats_token).await {
+            Ok(token) => config.nats_token = token,
+            Err(error) => {
+                report.push(
+                    PreflightCheck::fail(COMPONENT, "connect", error_chain(&error))
+                        .with_hint("NATS_TOKEN references a secret the provider does not hold"),
+                );
+                return None;
+            }
+        }
+    }
+
+    let client = match probe(NatsClient::connect(config)).await {
+        Ok(client) => {
+            report.push(PreflightCheck::pass(COMPONENT, "connect", "connected"));
+            client
+        }
+        Err(error) => {
+            report.push(PreflightCheck::fail(COMPONENT, "connect", error).with_hint(
+                "Verify NATS_URL and NATS_TOKEN, and that the server is reachable from this host",
+            ));
+            report.push(PreflightCheck::skip(COMPONENT, "jetstream", "nats/connect"));
+            report.push(PreflightCheck::skip(
+                COMPONENT,
+                "object store write",
+                "nats/connect",
+            ));
+            return None;
+        }
+    };
+
+    if let Err(error) = probe(client.check_jetstream()).await {
+        report.push(PreflightCheck::fail(COMPONENT, "jetstream", error).with_hint(
+            "Enable JetStream on the server (`nats-server -js` or a `jetstream {}` block) and for \
+             this account",
+        ));
+        report.push(PreflightCheck::skip(
+            COMPONENT,
+            "object store write",
+            "nats/jetstream",
+        ));
+        return Some(client);
+    }
+    report.push(PreflightCheck::pass(COMPONENT, "jetstream", "enabled"));
+
+    let check = match probe(client.check_object_store_write()).await {
+        Ok(()) => PreflightCheck::pass(COMPONENT, "object store write", "probe object written"),
+        Err(error) => PreflightCheck::fail(COMPONENT, "object store write", error).with_hint(
+            "Give the account JetStream storage quota and permission to create object store \
+             buckets",
+        ),
+    };
+    report.push(check);
+
+    Some(client)
+}
+
+/// Checks Redis connectivity.
+#[cfg(feature = "redis")]
+pub async fn redis(
+    report: &mut PreflightReport,
+    mut config: RedisConfig,
+    secrets: Option<&SecretsService>,
+) {
+    const COMPONENT: &str = "redis";
+
+    if let (Some(secrets), Some(password)) = (secrets, &config.redis_password) {
+        match secrets.resolve_str(password).await {
+            Ok(password) => config.redis_password = Some(password),
+            Err(error) => {
+                report.push(
+                    PreflightCheck::fail(COMPONENT, "connect", error_chain(&error))
+                        .with_hint("REDIS_PASSWORD references a secret the provider does not hold"),
+                );
+                return;
+            }
+        }
+    }
+
+    let client = match probe(RedisClient::connect(config)).await {
+        Ok(client) => client,
+        Err(error) => {
+            report.push(PreflightCheck::fail(COMPONENT, "connect", error).with_hint(
+                "Verify REDIS_URL and REDIS_PASSWORD, and that the server is reachable from this \
+                 host",
+            ));
+            return;
+        }
+    };
+
+    let check = match probe(client.ping()).await {
+        Ok(latency) => PreflightCheck::pass(
+            COMPONENT,
+            "connect",
+            format!("connected, ping {}ms", latency.as_millis()),
+        ),
+        Err(error) => PreflightCheck::fail(COMPONENT, "connect", error)
+            .with_hint("Check that the server accepts commands from this user"),
+    };
+    report.push(check);
+}
+
+/// Checks that the JWT session key pair loads.
+pub async fn session_keys(report: &mut PreflightReport, config: SessionKeysConfig) {
+    let check = match SessionKeys::from_config(&config).await {
+        Ok(_) => PreflightCheck::pass("session keys", "load", "key pair loaded"),
+        Err(error) => PreflightCheck::fail("session keys", "load", error_chain(&error)).with_hint(
+            "Point AUTH_PUBLIC_PEM_FILEPATH and AUTH_PRIVATE_PEM_FILEPATH at a readable key pair \
+             (`make generate-keys` creates one)",
+        ),
+    };
+    report.push(check);
+}
+
+/// Checks that the master key loads and the crypto provider passes its
+/// self-test.
+pub async fn crypto(report: &mut PreflightReport, config: CryptoConfig) -> Option<CryptoService> {
+    match CryptoService::from_config(&config).await {
+        Ok(crypto) => {
+            report.push(PreflightCheck::pass(
+                "crypto",
+                "master key",
+                format!("key loaded, provider '{}'", crypto.provider().name()),
+            ));
+            Some(crypto)
+        }
+        Err(error) => {
+            report.push(
+                PreflightCheck::fail("crypto", "master key", error_chain(&error)).with_hint(
+                    "Point ENCRYPTION_KEY_FILEPATH at a 32-byte key (`make generate-keys` creates \
+                     one); CRYPTO_POLICY=fips needs a build with the `fips` feature",
+                ),
+            );
+            None
+        }
+    }
+}
+
+/// Checks that the redaction engine's recognizer lineups load.
+///
+/// Returns the engine for checks that need it.
+pub async fn engine(
+    report: &mut PreflightReport,
+    config: EngineConfig,
+    secrets: Option<&SecretsService>,
+) -> Option<EngineService> {
+    let Some(secrets) = secrets else {
+        report.push(PreflightCheck::skip("engine", "config", "secrets/provider"));
+        return None;
+    };
+
+    match EngineService::from_config(config, secrets).await {
+        Ok(engine) => {
+            report.push(PreflightCheck::pass(
+                "engine",
+                "config",
+                "recognizer lineups loaded",
+            ));
+            Some(engine)
+        }
+        Err(error) => {
+            report.push(
+                PreflightCheck::fail("engine", "config", error_chain(&error)).with_hint(
+                    "Check that ENGINE_CONFIG_FILEPATH points to a readable JSON recognizer lineup \
+                     and that every secret:// reference in it exists",
+                ),
+            );
+            None
+        }
+    }
+}
+
+/// Checks that the backends of every pinned data region are reachable.
+pub async fn residency(
+    report: &mut PreflightReport,
+    config: ResidencyConfig,
+    nats: Option<NatsClient>,
+    engine: Option<EngineService>,
+    secrets: Option<SecretsService>,
+) {
+    const COMPONENT: &str = "residency";
+
+    if config.config_path.is_none() {
+        report.push(PreflightCheck::pass(
+            COMPONENT,
+            "regional backends",
+            "no pinned regions configured",
+        ));
+        return;
+    }
+
+    let (Some(nats), Some(engine), Some(secrets)) = (nats, engine, secrets) else {
+        report.push(PreflightCheck::skip(
+            COMPONENT,
+            "regional backends",
+            "nats/connect, engine/config and secrets/provider",
+        ));
+        return;
+    };
+
+    let backends = RegionBackends::new(nats, engine);
+    let check = match probe(ResidencyService::from_config(&config, backends, &secrets)).await {
+        Ok(residency) => PreflightCheck::pass(
+            COMPONENT,
+            "regional backends",
+            format!(
+                "{} pinned region(s) connected",
+                residency.health_checks().len()
+            ),
+        ),
+        Err(error) => PreflightCheck::fail(COMPONENT, "regional backends", error).with_hint(
+            "Check RESIDENCY_CONFIG_FILEPATH and each region's natsUrl, natsToken and \
+             engineConfigPath",
+        ),
+    };
+    report.push(check);
+}
+
+/// Checks that the single sign-on provider's discovery document and signing
+/// keys load.
+pub async fn oidc(report: &mut PreflightReport, config: OidcConfig, crypto: Option<CryptoService>) {
+    const COMPONENT: &str = "oidc";
+
+    if config.config_path.is_none() {
+        report.push(PreflightCheck::pass(
+            COMPONENT,
+            "identity provider",
+            "single sign-on disabled",
+        ));
+        return;
+    }
+
+    let Some(crypto) = crypto else {
+        report.push(PreflightCheck::skip(
+            COMPONENT,
+            "identity provider",
+            "crypto/master key",
+        ));
+        return;
+    };
+
+    // Discovery never touches the login cache, so an in-process one keeps
+    // this check independent of the cache backend.
+    let logins = Caches::in_memory().oidc_logins;
+    let check = match probe(OidcService::from_config(&config, logins, crypto)).await {
+        Ok(oidc) => PreflightCheck::pass(
+            COMPONENT,
+            "identity provider",
+            format!("discovered '{}'", oidc.issuer().unwrap_or_default()),
+        ),
+        Err(error) => PreflightCheck::fail(COMPONENT, "identity provider", error).with_hint(
+            "Check OIDC_CONFIG_FILEPATH and that the provider's issuerUrl serves \
+             /.well-known/openid-configuration",
+        ),
+    };
+    report.push(check);
+}
@@ -0,0 +1,40 @@
+//! Startup configuration validation.
+//!
+//! Before the listener binds, every configured backend is probed on its own
+//! short-lived connection and the outcomes are collected into a single
+//! [`PreflightReport`] with remediation hints. An operator sees every
+//! misconfiguration at once, instead of whichever error service
+//! initialization happens to hit first.
+//!
+//! Checks cover Postgres (connectivity, migration privileges, read replicas),
+//! NATS (connectivity, JetStream, object store writes), Redis when it holds
+//! the caches, the session and encryption keys, the engine's recognizer
+//! lineups, the regional backends and the single sign-on provider.
+
+mod checks;
+mod report;
+
+pub use self::report::{PreflightCheck, PreflightReport};
+use crate::config::ServiceArgs;
+
+/// Tracing target for preflight validation events.
+pub const TRACING_TARGET_PREFLIGHT: &str = "nvisy_cli::preflight";
+
+/// Runs every preflight check against the service configuration.
+pub async fn run(service: &ServiceArgs) -> PreflightReport {
+    let service = service.clone();
+    let mut report = PreflightReport::default();
+
+    let secrets = checks::secrets(&mut report, service.secrets.into()).await;
+    checks::postgres(&mut report, service.postgres.into()).await;
+    let nats = checks::nats(&mut report, service.nats.into(), secrets.as_ref()).await;
+    #[cfg(feature = "redis")]
+    checks::redis(&mut report, service.redis.into(), secrets.as_ref()).await;
+    checks::session_keys(&mut report, service.session_keys.into()).await;
+    let crypto = checks::crypto(&mut report, service.crypto.into()).await;
+    let engine = checks::engine(&mut report, service.engine.into(), secrets.as_ref()).await;
+    checks::residency(&mut report, service.residency.into(), nats, engine, secrets).await;
+    checks::oidc(&mut report, service.oidc.into(), crypto).await;
+
+    report
+}
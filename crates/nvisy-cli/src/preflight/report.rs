@@ -0,0 +1,254 @@
+//! Preflight check results and the aggregated report.
+
+use std::fmt;
+
+use super::TRACING_TARGET_PREFLIGHT;
+
+/// Outcome of a single preflight check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The backend is configured correctly.
+    Pass,
+    /// The server can start, but a feature is degraded.
+    Warn,
+    /// The server cannot start until this is fixed.
+    Fail,
+    /// Not checked because a check it depends on failed.
+    Skip,
+}
+
+impl CheckStatus {
+    /// Returns the label used in the rendered report.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+}
+
+/// Result of a single preflight check.
+#[derive(Debug, Clone)]
+pub struct PreflightCheck {
+    /// Backend the check belongs to (e.g. `"postgres"`).
+    pub component: &'static str,
+    /// What was checked (e.g. `"migration privileges"`).
+    pub name: &'static str,
+    /// Outcome of the check.
+    pub status: CheckStatus,
+    /// What was observed.
+    pub detail: String,
+    /// How to fix a warning or failure.
+    pub hint: Option<String>,
+}
+
+impl PreflightCheck {
+    /// Creates a passing check.
+    pub fn pass(component: &'static str, name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(component, name, CheckStatus::Pass, detail)
+    }
+
+    /// Creates a warning.
+    pub fn warn(component: &'static str, name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(component, name, CheckStatus::Warn, detail)
+    }
+
+    /// Creates a failing check.
+    pub fn fail(component: &'static str, name: &'static str, detail: impl Into<String>) -> Self {
+        Self::new(component, name, CheckStatus::Fail, detail)
+    }
+
+    /// Creates a check skipped because `dependency` did not pass.
+    pub fn skip(component: &'static str, name: &'static str, dependency: &str) -> Self {
+        Self::new(
+            component,
+            name,
+            CheckStatus::Skip,
+            format!("requires {dependency}"),
+        )
+    }
+
+    fn new(
+        component: &'static str,
+        name: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            component,
+            name,
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    /// Attaches a remediation hint.
+    #[must_use]
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Results of every preflight check, in execution order.
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    /// All checks that ran (or were skipped).
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    /// Appends a check result.
+    pub fn push(&mut self, check: PreflightCheck) {
+        self.checks.push(check);
+    }
+
+    /// Returns whether no check failed.
+    pub fn passed(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    /// Returns the number of checks with the given status.
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == status)
+            .count()
+    }
+
+    /// Returns the failed checks.
+    pub fn failures(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Logs the whole report as one event, at error level if any check failed.
+    pub fn log(&self) {
+        let passed = self.count(CheckStatus::Pass);
+        let warnings = self.count(CheckStatus::Warn);
+        let failed = self.count(CheckStatus::Fail);
+
+        if failed > 0 {
+            tracing::error!(
+                target: TRACING_TARGET_PREFLIGHT,
+                passed,
+                warnings,
+                failed,
+                "Startup validation failed: {self}"
+            );
+        } else if warnings > 0 {
+            tracing::warn!(
+                target: TRACING_TARGET_PREFLIGHT,
+                passed,
+                warnings,
+                "Startup validation passed with warnings: {self}"
+            );
+        } else {
+            tracing::info!(
+                target: TRACING_TARGET_PREFLIGHT,
+                passed,
+                "Startup validation passed: {self}"
+            );
+        }
+    }
+
+    /// Returns an error naming the failed checks, if any.
+    pub fn ensure_passed(&self) -> anyhow::Result<()> {
+        if self.passed() {
+            return Ok(());
+        }
+
+        let failures: Vec<_> = self
+            .failures()
+            .map(|check| format!("{}/{}", check.component, check.name))
+            .collect();
+        anyhow::bail!(
+            "startup validation failed: {} check(s) failed ({})",
+            failures.len(),
+            failures.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} warning(s), {} failed, {} skipped",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail),
+            self.count(CheckStatus::Skip),
+        )?;
+
+        for check in &self.checks {
+            write!(
+                f,
+                "\n  [{}] {}/{}: {}",
+                check.status.as_str(),
+                check.component,
+                check.name,
+                check.detail
+            )?;
+            if let Some(hint) = &check.hint {
+                write!(f, "\n         hint: {hint}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> PreflightReport {
+        let mut report = PreflightReport::default();
+        report.push(PreflightCheck::pass("postgres", "connect", "connected"));
+        report.push(
+            PreflightCheck::fail("nats", "jetstream", "not enabled").with_hint("enable JetStream"),
+        );
+        report.push(PreflightCheck::skip(
+            "nats",
+            "object store",
+            "nats/jetstream",
+        ));
+        report
+    }
+
+    #[test]
+    fn counts_and_failures() {
+        let report = report();
+        assert!(!report.passed());
+        assert_eq!(report.count(CheckStatus::Pass), 1);
+        assert_eq!(report.count(CheckStatus::Skip), 1);
+        assert_eq!(report.failures().count(), 1);
+    }
+
+    #[test]
+    fn ensure_passed_names_failures() {
+        let error = report().ensure_passed().unwrap_err();
+        assert!(error.to_string().contains("nats/jetstream"));
+
+        let mut report = PreflightReport::default();
+        report.push(PreflightCheck::warn(
+            "postgres",
+            "read replicas",
+            "1 of 2 down",
+        ));
+        assert!(report.ensure_passed().is_ok());
+    }
+
+    #[test]
+    fn display_includes_hints() {
+        let rendered = report().to_string();
+        assert!(rendered.starts_with("1 passed, 0 warning(s), 1 failed, 1 skipped"));
+        assert!(rendered.contains("[FAIL] nats/jetstream: not enabled"));
+        assert!(rendered.contains("hint: enable JetStream"));
+    }
+}
@@ -0,0 +1,731 @@
+//! Backend-agnostic key-value caching and the [`KeyValueStore`] trait.
+//!
+//! Services keep short-lived state (API key lookups, pending logins, privacy
+//! budgets) in a [`KeyValueStore`] instead of naming a backend. The NATS and
+//! Redis clients implement it for their typed stores; [`MemoryStore`] keeps
+//! entries in the process, for tests and single-node deployments, with
+//! optional size bounds, stale-while-revalidate reads and [`CacheStats`].
+//!
+//! Every write bumps the entry's revision, which
+//! [`KeyValueStore::compare_and_swap`] checks to apply optimistic updates.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error as StdError;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use jiff::Timestamp;
+
+use crate::clock::{Clock, SystemClock};
+
+/// Boxed error of a cache backend.
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// Result type of [`KeyValueStore`] operations.
+pub type CacheResult<T, E = CacheError> = Result<T, E>;
+
+/// Error returned by [`KeyValueStore`] operations.
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    /// A compare-and-swap found the entry at another revision.
+    #[error("revision mismatch for key '{key}': expected {expected}")]
+    RevisionMismatch { key: String, expected: u64 },
+    /// The backend failed to serve the operation.
+    #[error("{backend} cache operation failed")]
+    Backend {
+        backend: &'static str,
+        #[source]
+        source: BoxError,
+    },
+}
+
+impl CacheError {
+    /// Creates a revision mismatch error.
+    pub fn revision_mismatch(key: impl Into<String>, expected: u64) -> Self {
+        Self::RevisionMismatch {
+            key: key.into(),
+            expected,
+        }
+    }
+
+    /// Wraps an error of the named backend.
+    pub fn backend(backend: &'static str, source: impl Into<BoxError>) -> Self {
+        Self::Backend {
+            backend,
+            source: source.into(),
+        }
+    }
+
+    /// Whether the error is a lost compare-and-swap race worth retrying.
+    #[must_use]
+    pub fn is_revision_mismatch(&self) -> bool {
+        matches!(self, Self::RevisionMismatch { .. })
+    }
+}
+
+/// A cached value and the revision it was written at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry<V> {
+    /// The cached value.
+    pub value: V,
+    /// Revision of the write that stored the value.
+    pub revision: u64,
+}
+
+/// A typed key-value store with per-entry revisions.
+#[async_trait::async_trait]
+pub trait KeyValueStore<K, V>: Send + Sync
+where
+    K: Sync,
+    V: Send + Sync,
+{
+    /// Returns the entry stored under `key`, if any.
+    async fn get(&self, key: &K) -> CacheResult<Option<CacheEntry<V>>>;
+
+    /// Returns the value stored under `key`, if any.
+    async fn get_value(&self, key: &K) -> CacheResult<Option<V>> {
+        Ok(self.get(key).await?.map(|entry| entry.value))
+    }
+
+    /// Stores `value` under `key` and returns the new revision.
+    async fn put(&self, key: &K, value: &V) -> CacheResult<u64>;
+
+    /// Removes `key`; removing a missing key is not an error.
+    async fn delete(&self, key: &K) -> CacheResult<()>;
+
+    /// Returns how long `key` has left before it expires.
+    ///
+    /// Returns `None` for missing keys and keys that never expire.
+    async fn ttl(&self, key: &K) -> CacheResult<Option<Duration>>;
+
+    /// Stores `value` only if the entry is still at `revision`, and returns
+    /// the new revision.
+    ///
+    /// Revision zero stands for a missing entry, so it creates the key only
+    /// if nobody else did. Fails with [`CacheError::RevisionMismatch`] when
+    /// the entry moved on.
+    async fn compare_and_swap(&self, key: &K, value: &V, revision: u64) -> CacheResult<u64>;
+}
+
+/// Upper bounds of the hit latency histogram buckets, in microseconds.
+///
+/// A final overflow bucket counts everything slower than the last bound.
+const HIT_LATENCY_BOUNDS_US: [u64; 8] = [1, 5, 10, 50, 100, 500, 1_000, 10_000];
+
+/// Measures the size of a cached value, in bytes.
+type Weigher<V> = Arc<dyn Fn(&V) -> usize + Send + Sync>;
+
+/// An entry of a [`MemoryStore`].
+#[derive(Debug, Clone)]
+struct MemoryEntry<V> {
+    value: V,
+    revision: u64,
+    expires_at: Option<Timestamp>,
+    weight: usize,
+    last_used: u64,
+}
+
+/// Whether a stored entry may still be served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    /// The entry has not expired.
+    Fresh,
+    /// The entry expired but is within the stale-while-revalidate window.
+    Stale,
+}
+
+/// Running counters of a [`MemoryStore`].
+#[derive(Debug, Clone, Default)]
+struct Counters {
+    hits: u64,
+    stale_hits: u64,
+    misses: u64,
+    evictions: u64,
+    expirations: u64,
+    refresh_failures: u64,
+    max_hit_latency: Duration,
+    hit_latency: [u64; HIT_LATENCY_BOUNDS_US.len() + 1],
+}
+
+impl Counters {
+    fn record_hit(&mut self, elapsed: Duration) {
+        self.hits += 1;
+        self.max_hit_latency = self.max_hit_latency.max(elapsed);
+
+        let elapsed_us = elapsed.as_micros();
+        let bucket = HIT_LATENCY_BOUNDS_US
+            .iter()
+            .position(|&bound| elapsed_us <= u128::from(bound))
+            .unwrap_or(HIT_LATENCY_BOUNDS_US.len());
+        self.hit_latency[bucket] += 1;
+    }
+}
+
+/// Entries of a [`MemoryStore`], their recency and the last revision handed
+/// out.
+#[derive(Debug)]
+struct MemoryState<K, V> {
+    entries: HashMap<K, MemoryEntry<V>>,
+    revision: u64,
+    /// Keys by last use, least recently used first.
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+    bytes: usize,
+    /// Keys with a background refresh in flight.
+    refreshing: HashSet<K>,
+    counters: Counters,
+}
+
+impl<K, V> MemoryState<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Removes `key` along with its recency and size.
+    fn remove(&mut self, key: &K) -> Option<MemoryEntry<V>> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        self.bytes -= entry.weight;
+        Some(entry)
+    }
+
+    /// Marks `key` as the most recently used entry.
+    fn touch(&mut self, key: &K) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+        self.tick += 1;
+        self.recency.remove(&entry.last_used);
+        entry.last_used = self.tick;
+        self.recency.insert(self.tick, key.clone());
+    }
+}
+
+/// Point-in-time copy of a [`MemoryStore`]'s statistics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups that found a fresh entry.
+    pub hits: u64,
+    /// Lookups served a stale entry while it was refreshed.
+    pub stale_hits: u64,
+    /// Lookups that found nothing.
+    pub misses: u64,
+    /// Entries dropped to stay within the size bounds.
+    pub evictions: u64,
+    /// Entries dropped because they expired.
+    pub expirations: u64,
+    /// Background refreshes that failed, leaving the stale entry in place.
+    pub refresh_failures: u64,
+    /// Entries held, including expired ones not yet dropped.
+    pub entries: usize,
+    /// Total weight of the entries held; zero without a byte bound.
+    pub bytes: usize,
+    /// Slowest hit recorded.
+    pub max_hit_latency: Duration,
+    /// Hit latency histogram as `(upper bound, count)` pairs; the final
+    /// bucket has no upper bound (`None`) and counts everything slower.
+    pub hit_latency: Vec<(Option<Duration>, u64)>,
+}
+
+impl CacheStats {
+    /// Share of lookups served from the cache, stale hits included.
+    pub fn hit_ratio(&self) -> f64 {
+        let served = self.hits + self.stale_hits;
+        let lookups = served + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            served as f64 / lookups as f64
+        }
+    }
+
+    /// Approximate hit latency at quantile `q` (0.0 to 1.0), as the upper
+    /// bound of the bucket it falls in. Returns
+    /// [`max_hit_latency`](Self::max_hit_latency) for the overflow bucket.
+    pub fn hit_latency_quantile(&self, q: f64) -> Duration {
+        let rank = ((self.hits as f64) * q.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for &(bound, count) in &self.hit_latency {
+            seen += count;
+            if seen >= rank {
+                return bound.unwrap_or(self.max_hit_latency);
+            }
+        }
+        self.max_hit_latency
+    }
+}
+
+/// A [`KeyValueStore`] keeping entries in the process.
+///
+/// Entries expire a fixed TTL after their last write, as in a NATS bucket,
+/// unless written with [`put_with_ttl`](Self::put_with_ttl); expired entries
+/// read as missing. Revisions count writes across the whole store. Clones
+/// share the same entries.
+///
+/// The store can be bounded by entry count and by total size, evicting the
+/// least recently used entries past either bound. The entry just written is
+/// never evicted, so a single oversized value is still kept.
+#[derive(Clone)]
+pub struct MemoryStore<K, V> {
+    state: Arc<Mutex<MemoryState<K, V>>>,
+    ttl: Option<Duration>,
+    stale_for: Duration,
+    max_entries: Option<usize>,
+    max_bytes: Option<usize>,
+    weigher: Option<Weigher<V>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<K, V> MemoryStore<K, V> {
+    /// Creates a store whose entries never expire.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MemoryState {
+                entries: HashMap::new(),
+                revision: 0,
+                recency: BTreeMap::new(),
+                tick: 0,
+                bytes: 0,
+                refreshing: HashSet::new(),
+                counters: Counters::default(),
+            })),
+            ttl: None,
+            stale_for: Duration::ZERO,
+            max_entries: None,
+            max_bytes: None,
+            weigher: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Creates a store whose entries expire `ttl` after their last write.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..Self::new()
+        }
+    }
+
+    /// Replaces the clock entries expire by.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Evicts the least recently used entries past `max` entries.
+    #[must_use]
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        self.max_entries = Some(max);
+        self
+    }
+
+    /// Evicts the least recently used entries past `max` bytes, as measured
+    /// by `weigh`.
+    #[must_use]
+    pub fn with_max_bytes(
+        mut self,
+        max: usize,
+        weigh: impl Fn(&V) -> usize + Send + Sync + 'static,
+    ) -> Self {
+        self.max_bytes = Some(max);
+        self.weigher = Some(Arc::new(weigh));
+        self
+    }
+
+    /// Keeps serving expired entries from
+    /// [`get_or_compute`](Self::get_or_compute) for `window` while they are
+    /// refreshed in the background.
+    #[must_use]
+    pub fn with_stale_while_revalidate(mut self, window: Duration) -> Self {
+        self.stale_for = window;
+        self
+    }
+
+    /// Returns a snapshot of the store's statistics.
+    pub fn stats(&self) -> CacheStats {
+        let state = self.lock();
+        let bounds = HIT_LATENCY_BOUNDS_US
+            .iter()
+            .map(|&us| Some(Duration::from_micros(us)))
+            .chain(std::iter::once(None));
+        let counters = &state.counters;
+
+        CacheStats {
+            hits: counters.hits,
+            stale_hits: counters.stale_hits,
+            misses: counters.misses,
+            evictions: counters.evictions,
+            expirations: counters.expirations,
+            refresh_failures: counters.refresh_failures,
+            entries: state.entries.len(),
+            bytes: state.bytes,
+            max_hit_latency: counters.max_hit_latency,
+            hit_latency: bounds.zip(counters.hit_latency.iter().copied()).collect(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MemoryState<K, V>> {
+        // Every update leaves the map consistent, even one that panicked.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<K, V> Default for MemoryStore<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> fmt::Debug for MemoryStore<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryStore")
+            .field("ttl", &self.ttl)
+            .field("stale_for", &self.stale_for)
+            .field("max_entries", &self.max_entries)
+            .field("max_bytes", &self.max_bytes)
+            .field("clock", &self.clock)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> MemoryStore<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    /// Stores `value` under `key`, expiring it after `ttl` instead of the
+    /// store's TTL, and returns the new revision.
+    ///
+    /// `None` keeps the entry until it is overwritten, deleted or evicted.
+    pub fn put_with_ttl(&self, key: &K, value: &V, ttl: Option<Duration>) -> u64 {
+        let mut state = self.lock();
+        self.write(&mut state, key, value, ttl)
+    }
+
+    /// Returns whether the entry under `key` may be served, dropping it once
+    /// it is past its stale-while-revalidate window.
+    fn lookup(&self, state: &mut MemoryState<K, V>, key: &K) -> Option<Freshness> {
+        let Some(expires_at) = state.entries.get(key)?.expires_at else {
+            return Some(Freshness::Fresh);
+        };
+
+        let now = self.clock.now();
+        if now < expires_at {
+            return Some(Freshness::Fresh);
+        }
+        if self.stale_for > Duration::ZERO
+            && expires_at
+                .checked_add(self.stale_for)
+                .is_ok_and(|stale_until| now < stale_until)
+        {
+            return Some(Freshness::Stale);
+        }
+
+        state.remove(key);
+        state.counters.expirations += 1;
+        None
+    }
+
+    /// Returns the fresh entry under `key`, dropping it if it expired.
+    fn live<'a>(&self, state: &'a mut MemoryState<K, V>, key: &K) -> Option<&'a MemoryEntry<V>> {
+        match self.lookup(state, key)? {
+            Freshness::Fresh => state.entries.get(key),
+            Freshness::Stale => None,
+        }
+    }
+
+    /// Returns the fresh entry under `key` and records the lookup.
+    fn read(
+        &self,
+        state: &mut MemoryState<K, V>,
+        key: &K,
+        started: Instant,
+    ) -> Option<CacheEntry<V>> {
+        if self.live(state, key).is_none() {
+            state.counters.misses += 1;
+            return None;
+        }
+
+        state.touch(key);
+        state.counters.record_hit(started.elapsed());
+        state.entries.get(key).map(|entry| CacheEntry {
+            value: entry.value.clone(),
+            revision: entry.revision,
+        })
+    }
+
+    /// Writes `value` under `key` at the next revision, expiring it after
+    /// `ttl`, then evicts past the size bounds.
+    fn write(
+        &self,
+        state: &mut MemoryState<K, V>,
+        key: &K,
+        value: &V,
+        ttl: Option<Duration>,
+    ) -> u64 {
+        state.remove(key);
+        state.revision += 1;
+        state.tick += 1;
+
+        let expires_at = ttl.and_then(|ttl| self.clock.now().checked_add(ttl).ok());
+        let weight = self.weigher.as_ref().map_or(0, |weigh| weigh(value));
+        state.entries.insert(
+            key.clone(),
+            MemoryEntry {
+                value: value.clone(),
+                revision: state.revision,
+                expires_at,
+                weight,
+                last_used: state.tick,
+            },
+        );
+        state.recency.insert(state.tick, key.clone());
+        state.bytes += weight;
+
+        self.evict(state);
+        state.revision
+    }
+
+    /// Drops least recently used entries until the store is within bounds.
+    fn evict(&self, state: &mut MemoryState<K, V>) {
+        while state.entries.len() > 1
+            && (self
+                .max_entries
+                .is_some_and(|max| state.entries.len() > max)
+                || self.max_bytes.is_some_and(|max| state.bytes > max))
+        {
+            let Some((_, oldest)) = state.recency.first_key_value() else {
+                break;
+            };
+            let oldest = oldest.clone();
+            state.remove(&oldest);
+            state.counters.evictions += 1;
+        }
+    }
+}
+
+impl<K, V> MemoryStore<K, V>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    /// Returns the value under `key`, computing and storing it when missing.
+    ///
+    /// Within the stale-while-revalidate window an expired value is returned
+    /// as is while `compute` refreshes it on a background task; one refresh
+    /// runs per key at a time. A failed refresh leaves the stale value until
+    /// the window closes. Must be called from within a Tokio runtime.
+    pub async fn get_or_compute<F, Fut, E>(&self, key: &K, compute: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>> + Send + 'static,
+    {
+        let started = Instant::now();
+        let stale = {
+            let mut state = self.lock();
+            if self.lookup(&mut state, key) == Some(Freshness::Stale) {
+                state.counters.stale_hits += 1;
+                let refresh = state.refreshing.insert(key.clone());
+                Some((state.entries[key].value.clone(), refresh))
+            } else if let Some(entry) = self.read(&mut state, key, started) {
+                return Ok(entry.value);
+            } else {
+                None
+            }
+        };
+
+        match stale {
+            Some((value, true)) => {
+                let store = self.clone();
+                let key = key.clone();
+                let refresh = compute();
+                tokio::spawn(async move {
+                    let result = refresh.await;
+                    let mut state = store.lock();
+                    state.refreshing.remove(&key);
+                    match result {
+                        Ok(value) => {
+                            store.write(&mut state, &key, &value, store.ttl);
+                        }
+                        Err(_) => state.counters.refresh_failures += 1,
+                    }
+                });
+                Ok(value)
+            }
+            Some((value, false)) => Ok(value),
+            None => {
+                let value = compute().await?;
+                let mut state = self.lock();
+                self.write(&mut state, key, &value, self.ttl);
+                Ok(value)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<K, V> KeyValueStore<K, V> for MemoryStore<K, V>
+where
+    K: Eq + Hash + Clone + fmt::Display + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    async fn get(&self, key: &K) -> CacheResult<Option<CacheEntry<V>>> {
+        let started = Instant::now();
+        let mut state = self.lock();
+        Ok(self.read(&mut state, key, started))
+    }
+
+    async fn put(&self, key: &K, value: &V) -> CacheResult<u64> {
+        let mut state = self.lock();
+        Ok(self.write(&mut state, key, value, self.ttl))
+    }
+
+    async fn delete(&self, key: &K) -> CacheResult<()> {
+        self.lock().remove(key);
+        Ok(())
+    }
+
+    async fn ttl(&self, key: &K) -> CacheResult<Option<Duration>> {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        let expires_at = self
+            .live(&mut state, key)
+            .and_then(|entry| entry.expires_at);
+        Ok(expires_at.and_then(|expires_at| now.duration_until(expires_at).try_into().ok()))
+    }
+
+    async fn compare_and_swap(&self, key: &K, value: &V, revision: u64) -> CacheResult<u64> {
+        let mut state = self.lock();
+        let current = self.live(&mut state, key).map_or(0, |entry| entry.revision);
+        if current != revision {
+            return Err(CacheError::revision_mismatch(key.to_string(), revision));
+        }
+        Ok(self.write(&mut state, key, value, self.ttl))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[tokio::test]
+    async fn memory_store_round_trip() {
+        let store = MemoryStore::<String, u32>::new();
+        let key = "answer".to_owned();
+        assert_eq!(store.get(&key).await.unwrap(), None);
+
+        let revision = store.put(&key, &42).await.unwrap();
+        let entry = store.get(&key).await.unwrap().unwrap();
+        assert_eq!(
+            entry,
+            CacheEntry {
+                value: 42,
+                revision
+            }
+        );
+        assert_eq!(store.ttl(&key).await.unwrap(), None);
+
+        store.delete(&key).await.unwrap();
+        assert_eq!(store.get_value(&key).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn memory_store_compare_and_swap() {
+        let store = MemoryStore::<String, u32>::new();
+        let key = "counter".to_owned();
+
+        let first = store.compare_and_swap(&key, &1, 0).await.unwrap();
+        let err = store.compare_and_swap(&key, &1, 0).await.unwrap_err();
+        assert!(err.is_revision_mismatch());
+
+        let second = store.compare_and_swap(&key, &2, first).await.unwrap();
+        assert!(second > first);
+        assert_eq!(store.get_value(&key).await.unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn memory_store_expires_entries() {
+        let clock = ManualClock::new(Timestamp::from_second(1_760_000_000).unwrap());
+        let store = MemoryStore::<String, u32>::with_ttl(Duration::from_secs(60))
+            .with_clock(Arc::new(clock.clone()));
+        let key = "session".to_owned();
+
+        store.put(&key, &7).await.unwrap();
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(
+            store.ttl(&key).await.unwrap(),
+            Some(Duration::from_secs(40))
+        );
+
+        clock.advance(Duration::from_secs(40));
+        assert_eq!(store.get(&key).await.unwrap(), None);
+        assert_eq!(store.ttl(&key).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn memory_store_evicts_least_recently_used() {
+        let store = MemoryStore::<String, String>::new()
+            .with_max_entries(2)
+            .with_max_bytes(7, String::len);
+        let [a, b, c] = ["a", "b", "c"].map(str::to_owned);
+
+        store.put(&a, &"1".to_owned()).await.unwrap();
+        store.put(&b, &"2".to_owned()).await.unwrap();
+        store.get(&a).await.unwrap();
+        store.put(&c, &"3".to_owned()).await.unwrap();
+        assert_eq!(store.get(&b).await.unwrap(), None);
+        assert!(store.get(&a).await.unwrap().is_some());
+
+        store.put(&b, &"1234567".to_owned()).await.unwrap();
+        let stats = store.stats();
+        assert_eq!((stats.entries, stats.bytes), (1, 7));
+        assert_eq!(stats.evictions, 3);
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        let recorded: u64 = stats.hit_latency.iter().map(|&(_, count)| count).sum();
+        assert_eq!(recorded, stats.hits);
+    }
+
+    #[tokio::test]
+    async fn memory_store_per_entry_ttl() {
+        let clock = ManualClock::new(Timestamp::from_second(1_760_000_000).unwrap());
+        let store = MemoryStore::<String, u32>::with_ttl(Duration::from_secs(60))
+            .with_clock(Arc::new(clock.clone()));
+        let [short, forever] = ["short", "forever"].map(str::to_owned);
+
+        store.put_with_ttl(&short, &1, Some(Duration::from_secs(5)));
+        store.put_with_ttl(&forever, &2, None);
+        clock.advance(Duration::from_secs(3600));
+
+        assert_eq!(store.get_value(&short).await.unwrap(), None);
+        assert_eq!(store.get_value(&forever).await.unwrap(), Some(2));
+        assert_eq!(store.stats().expirations, 1);
+    }
+
+    #[tokio::test]
+    async fn memory_store_stale_while_revalidate() {
+        let clock = ManualClock::new(Timestamp::from_second(1_760_000_000).unwrap());
+        let store = MemoryStore::<String, u32>::with_ttl(Duration::from_secs(60))
+            .with_stale_while_revalidate(Duration::from_secs(30))
+            .with_clock(Arc::new(clock.clone()));
+        let key = "report".to_owned();
+        let compute = |value| async move { Ok::<_, CacheError>(value) };
+
+        assert_eq!(store.get_or_compute(&key, || compute(1)).await.unwrap(), 1);
+        assert_eq!(store.get_or_compute(&key, || compute(2)).await.unwrap(), 1);
+
+        clock.advance(Duration::from_secs(70));
+        assert_eq!(store.get_value(&key).await.unwrap(), None);
+        assert_eq!(store.get_or_compute(&key, || compute(3)).await.unwrap(), 1);
+        tokio::task::yield_now().await;
+        assert_eq!(store.get_value(&key).await.unwrap(), Some(3));
+
+        clock.advance(Duration::from_secs(100));
+        assert_eq!(store.get_or_compute(&key, || compute(4)).await.unwrap(), 4);
+        assert_eq!(store.stats().stale_hits, 1);
+    }
+}
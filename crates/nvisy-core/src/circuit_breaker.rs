@@ -0,0 +1,585 @@
+//! Circuit breaker for calls to external providers.
+//!
+//! A [`CircuitBreaker`] watches the outcome of recent calls to one upstream
+//! and stops sending it traffic once too many fail or run slow, so a
+//! provider that is down is not hammered while callers back up behind it.
+//!
+//! The breaker starts [closed](CircuitState::Closed) and lets every call
+//! through. Once the failure rate or the slow-call rate over the last
+//! [`window_size`](CircuitBreakerConfig::window_size) calls reaches its
+//! threshold, it [opens](CircuitState::Open) and rejects calls outright.
+//! After [`open_duration`](CircuitBreakerConfig::open_duration) it turns
+//! [half-open](CircuitState::HalfOpen) and admits a few probe calls: if
+//! they all succeed it closes again, and the first failing probe opens it
+//! for another period.
+//!
+//! Every transition is reported to the listeners registered with
+//! [`CircuitBreaker::on_state_change`], which is the hook for alerting.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Default number of recent calls the rates are computed over.
+pub const DEFAULT_WINDOW_SIZE: u32 = 20;
+
+/// Default number of calls recorded before the circuit may open.
+pub const DEFAULT_MINIMUM_CALLS: u32 = 10;
+
+/// Default share of failed calls that opens the circuit.
+pub const DEFAULT_FAILURE_RATE_THRESHOLD: f64 = 0.5;
+
+/// Default duration after which a call counts as slow.
+pub const DEFAULT_SLOW_CALL_DURATION: Duration = Duration::from_secs(10);
+
+/// Default share of slow calls that opens the circuit.
+pub const DEFAULT_SLOW_CALL_RATE_THRESHOLD: f64 = 0.8;
+
+/// Default time the circuit stays open before probing the upstream.
+pub const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// Default number of probe calls admitted while half-open.
+pub const DEFAULT_HALF_OPEN_CALLS: u32 = 3;
+
+/// Circuit breaker thresholds.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct CircuitBreakerConfig {
+    /// Number of recent calls the failure and slow-call rates cover.
+    pub window_size: u32,
+    /// Calls that must be recorded before the rates can open the circuit.
+    pub minimum_calls: u32,
+    /// Share of failed calls (`0.0..=1.0`) that opens the circuit.
+    pub failure_rate_threshold: f64,
+    /// Duration after which a call counts as slow, whatever its outcome.
+    pub slow_call_duration: Duration,
+    /// Share of slow calls (`0.0..=1.0`) that opens the circuit.
+    pub slow_call_rate_threshold: f64,
+    /// How long the circuit stays open before probe calls are admitted.
+    pub open_duration: Duration,
+    /// Probe calls admitted while half-open; all must succeed to close.
+    pub half_open_calls: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            window_size: DEFAULT_WINDOW_SIZE,
+            minimum_calls: DEFAULT_MINIMUM_CALLS,
+            failure_rate_threshold: DEFAULT_FAILURE_RATE_THRESHOLD,
+            slow_call_duration: DEFAULT_SLOW_CALL_DURATION,
+            slow_call_rate_threshold: DEFAULT_SLOW_CALL_RATE_THRESHOLD,
+            open_duration: DEFAULT_OPEN_DURATION,
+            half_open_calls: DEFAULT_HALF_OPEN_CALLS,
+        }
+    }
+}
+
+/// State of a circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitState {
+    /// Calls go through and their outcomes are recorded.
+    Closed,
+    /// Calls are rejected without reaching the upstream.
+    Open,
+    /// A limited number of probe calls go through.
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Returns the state's name, in `snake_case`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A transition of a circuit from one state to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateChange {
+    /// Name of the circuit's upstream.
+    pub name: Cow<'static, str>,
+    /// State before the transition.
+    pub from: CircuitState,
+    /// State after the transition.
+    pub to: CircuitState,
+    /// Failure rate over the window when the transition happened.
+    pub failure_rate: f64,
+    /// Slow-call rate over the window when the transition happened.
+    pub slow_call_rate: f64,
+}
+
+/// Error returned by [`CircuitBreaker::call`].
+#[derive(Debug)]
+pub enum CircuitError<E> {
+    /// The circuit is open; the call was not made.
+    Open,
+    /// The call was made and failed.
+    Inner(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CircuitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => f.write_str("circuit open"),
+            Self::Inner(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for CircuitError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Open => None,
+            Self::Inner(err) => Some(err),
+        }
+    }
+}
+
+/// Callback invoked on every state change.
+type StateChangeFn = Arc<dyn Fn(&StateChange) + Send + Sync>;
+
+/// Outcome of one call, as kept in the window.
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+    failed: bool,
+    slow: bool,
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: CircuitState,
+    window: VecDeque<Outcome>,
+    opened_at: Option<Instant>,
+    probes_in_flight: u32,
+    probes_succeeded: u32,
+}
+
+impl Circuit {
+    fn rates(&self) -> (f64, f64) {
+        if self.window.is_empty() {
+            return (0.0, 0.0);
+        }
+        let len = self.window.len() as f64;
+        let failed = self.window.iter().filter(|o| o.failed).count() as f64;
+        let slow = self.window.iter().filter(|o| o.slow).count() as f64;
+        (failed / len, slow / len)
+    }
+}
+
+/// Stops calling an upstream that keeps failing or running slow.
+///
+/// Cheap to share behind an [`Arc`]; all state sits behind one mutex that
+/// is only held to record outcomes, never across a call.
+pub struct CircuitBreaker {
+    name: Cow<'static, str>,
+    config: CircuitBreakerConfig,
+    circuit: Mutex<Circuit>,
+    listeners: Vec<StateChangeFn>,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed circuit for the upstream `name`.
+    pub fn new(name: impl Into<Cow<'static, str>>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            name: name.into(),
+            circuit: Mutex::new(Circuit {
+                state: CircuitState::Closed,
+                window: VecDeque::with_capacity(config.window_size as usize),
+                opened_at: None,
+                probes_in_flight: 0,
+                probes_succeeded: 0,
+            }),
+            config,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Registers a callback invoked after every state change.
+    ///
+    /// Callbacks run on the task that caused the transition, outside the
+    /// breaker's lock, and should not block.
+    pub fn on_state_change(
+        mut self,
+        listener: impl Fn(&StateChange) + Send + Sync + 'static,
+    ) -> Self {
+        self.listeners.push(Arc::new(listener));
+        self
+    }
+
+    /// Returns the name of the circuit's upstream.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the breaker's thresholds.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Returns the current state, turning an open circuit whose open
+    /// duration has elapsed half-open.
+    pub fn state(&self) -> CircuitState {
+        let (state, change) = {
+            let mut circuit = self.lock();
+            let change = self.expire_open(&mut circuit);
+            (circuit.state, change)
+        };
+        self.notify(change);
+        state
+    }
+
+    /// Asks to make a call, returning `None` if the circuit rejects it.
+    ///
+    /// The call's outcome must be reported through the returned permit; a
+    /// permit dropped without an outcome (say, because the call was
+    /// cancelled) is not counted.
+    pub fn try_acquire(&self) -> Option<CallPermit<'_>> {
+        let (probe, change) = {
+            let mut circuit = self.lock();
+            let change = self.expire_open(&mut circuit);
+            let probe = match circuit.state {
+                CircuitState::Closed => Some(false),
+                CircuitState::Open => None,
+                CircuitState::HalfOpen
+                    if circuit.probes_in_flight + circuit.probes_succeeded
+                        < self.config.half_open_calls =>
+                {
+                    circuit.probes_in_flight += 1;
+                    Some(true)
+                }
+                CircuitState::HalfOpen => None,
+            };
+            (probe, change)
+        };
+        self.notify(change);
+
+        Some(CallPermit {
+            breaker: self,
+            started_at: Instant::now(),
+            probe: probe?,
+            recorded: false,
+        })
+    }
+
+    /// Makes a call through the breaker, counting any error as a failure.
+    ///
+    /// For upstreams where only some errors mean the upstream is unhealthy,
+    /// use [`try_acquire`](Self::try_acquire) and classify the outcome.
+    pub async fn call<T, E, F, Fut>(&self, f: F) -> Result<T, CircuitError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let permit = self.try_acquire().ok_or(CircuitError::Open)?;
+        match f().await {
+            Ok(value) => {
+                permit.success();
+                Ok(value)
+            }
+            Err(err) => {
+                permit.failure();
+                Err(CircuitError::Inner(err))
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Circuit> {
+        // Every update leaves the circuit consistent, so a poisoned lock is
+        // still safe to use.
+        self.circuit.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn record(&self, probe: bool, outcome: Outcome) {
+        let change = {
+            let mut circuit = self.lock();
+            self.apply(&mut circuit, probe, outcome)
+        };
+        self.notify(change);
+    }
+
+    fn release(&self, probe: bool) {
+        if probe {
+            let mut circuit = self.lock();
+            circuit.probes_in_flight = circuit.probes_in_flight.saturating_sub(1);
+        }
+    }
+
+    fn apply(&self, circuit: &mut Circuit, probe: bool, outcome: Outcome) -> Option<StateChange> {
+        match circuit.state {
+            CircuitState::Closed if !probe => {
+                if circuit.window.len() >= self.config.window_size.max(1) as usize {
+                    circuit.window.pop_front();
+                }
+                circuit.window.push_back(outcome);
+
+                if circuit.window.len() < self.config.minimum_calls as usize {
+                    return None;
+                }
+                let (failure_rate, slow_call_rate) = circuit.rates();
+                let tripped = failure_rate >= self.config.failure_rate_threshold
+                    || slow_call_rate >= self.config.slow_call_rate_threshold;
+                tripped.then(|| self.transition(circuit, CircuitState::Open))
+            }
+            CircuitState::HalfOpen if probe => {
+                circuit.probes_in_flight = circuit.probes_in_flight.saturating_sub(1);
+                if outcome.failed || outcome.slow {
+                    return Some(self.transition(circuit, CircuitState::Open));
+                }
+
+                circuit.probes_succeeded += 1;
+                (circuit.probes_succeeded >= self.config.half_open_calls)
+                    .then(|| self.transition(circuit, CircuitState::Closed))
+            }
+            // A call admitted before the last transition says nothing about
+            // the upstream's current state.
+            _ => None,
+        }
+    }
+
+    fn expire_open(&self, circuit: &mut Circuit) -> Option<StateChange> {
+        let elapsed = circuit
+            .opened_at
+            .is_some_and(|opened_at| opened_at.elapsed() >= self.config.open_duration);
+        (circuit.state == CircuitState::Open && elapsed)
+            .then(|| self.transition(circuit, CircuitState::HalfOpen))
+    }
+
+    fn transition(&self, circuit: &mut Circuit, to: CircuitState) -> StateChange {
+        let (failure_rate, slow_call_rate) = circuit.rates();
+        let from = circuit.state;
+
+        circuit.state = to;
+        circuit.probes_in_flight = 0;
+        circuit.probes_succeeded = 0;
+        match to {
+            CircuitState::Open => circuit.opened_at = Some(Instant::now()),
+            CircuitState::HalfOpen => {}
+            CircuitState::Closed => {
+                circuit.opened_at = None;
+                circuit.window.clear();
+            }
+        }
+
+        StateChange {
+            name: self.name.clone(),
+            from,
+            to,
+            failure_rate,
+            slow_call_rate,
+        }
+    }
+
+    fn notify(&self, change: Option<StateChange>) {
+        if let Some(change) = change {
+            for listener in &self.listeners {
+                listener(&change);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("name", &self.name)
+            .field("config", &self.config)
+            .field("state", &self.lock().state)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+/// Permission to make one call through a [`CircuitBreaker`].
+///
+/// The call's duration is measured from when the permit was acquired.
+#[must_use = "the call's outcome must be recorded through the permit"]
+pub struct CallPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    started_at: Instant,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CallPermit<'_> {
+    /// Records that the call succeeded.
+    ///
+    /// A success that took longer than the slow-call duration still counts
+    /// towards the slow-call rate.
+    pub fn success(self) {
+        self.finish(false);
+    }
+
+    /// Records that the call failed.
+    pub fn failure(self) {
+        self.finish(true);
+    }
+
+    fn finish(mut self, failed: bool) {
+        self.recorded = true;
+        let slow = self.started_at.elapsed() >= self.breaker.config.slow_call_duration;
+        self.breaker.record(self.probe, Outcome { failed, slow });
+    }
+}
+
+impl Drop for CallPermit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.release(self.probe);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            window_size: 4,
+            minimum_calls: 4,
+            failure_rate_threshold: 0.5,
+            slow_call_duration: Duration::from_secs(60),
+            slow_call_rate_threshold: 1.0,
+            open_duration: Duration::from_secs(60),
+            half_open_calls: 2,
+        }
+    }
+
+    fn record(breaker: &CircuitBreaker, failures: usize, successes: usize) {
+        for _ in 0..failures {
+            breaker.try_acquire().unwrap().failure();
+        }
+        for _ in 0..successes {
+            breaker.try_acquire().unwrap().success();
+        }
+    }
+
+    #[test]
+    fn opens_once_the_failure_rate_is_reached() {
+        let breaker = CircuitBreaker::new("test", config());
+
+        record(&breaker, 1, 2);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        record(&breaker, 1, 0);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.try_acquire().is_none());
+    }
+
+    #[test]
+    fn waits_for_the_minimum_number_of_calls() {
+        let breaker = CircuitBreaker::new("test", config());
+
+        record(&breaker, 3, 0);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn opens_on_slow_calls() {
+        let breaker = CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig {
+                slow_call_duration: Duration::ZERO,
+                ..config()
+            },
+        );
+
+        record(&breaker, 0, 4);
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn closes_after_successful_probes() {
+        let breaker = CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig {
+                open_duration: Duration::ZERO,
+                ..config()
+            },
+        );
+
+        record(&breaker, 4, 0);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        let first = breaker.try_acquire().unwrap();
+        let second = breaker.try_acquire().unwrap();
+        assert!(breaker.try_acquire().is_none());
+
+        first.success();
+        second.success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn reopens_on_a_failed_probe() {
+        let breaker = CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig {
+                open_duration: Duration::from_millis(20),
+                ..config()
+            },
+        );
+
+        record(&breaker, 4, 0);
+        std::thread::sleep(Duration::from_millis(30));
+
+        breaker.try_acquire().unwrap().failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[test]
+    fn dropped_probes_free_their_slot() {
+        let breaker = CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig {
+                open_duration: Duration::ZERO,
+                half_open_calls: 1,
+                ..config()
+            },
+        );
+
+        record(&breaker, 4, 0);
+        drop(breaker.try_acquire().unwrap());
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn reports_state_changes() {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let seen = changes.clone();
+        let breaker = CircuitBreaker::new(
+            "ocr",
+            CircuitBreakerConfig {
+                open_duration: Duration::ZERO,
+                half_open_calls: 1,
+                ..config()
+            },
+        )
+        .on_state_change(move |change| seen.lock().unwrap().push((change.from, change.to)));
+
+        record(&breaker, 4, 0);
+        breaker.try_acquire().unwrap().success();
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            [
+                (CircuitState::Closed, CircuitState::Open),
+                (CircuitState::Open, CircuitState::HalfOpen),
+                (CircuitState::HalfOpen, CircuitState::Closed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! FIPS provider built on the AWS-LC FIPS module.
+
+use aws_lc_rs::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use aws_lc_rs::rand::{SecureRandom, SystemRandom};
+use aws_lc_rs::{digest, hkdf, hmac};
+
+use super::{
+    AEAD_KEY_LEN, AES_GCM_NONCE_LEN, CryptoProvider, CryptoProviderError, CryptoProviderResult,
+    SHA256_LEN, Sha256Context,
+};
+
+/// The FIPS provider: AWS-LC SHA-256, HMAC, HKDF, AES-256-GCM and DRBG.
+///
+/// Only constructible while the module reports FIPS mode, so holding one is
+/// proof that every operation runs inside the validated boundary.
+#[derive(Debug)]
+pub struct AwsLcFipsProvider {
+    rng: SystemRandom,
+}
+
+impl AwsLcFipsProvider {
+    /// Creates the provider, failing unless AWS-LC is in FIPS mode.
+    pub fn new() -> CryptoProviderResult<Self> {
+        aws_lc_rs::try_fips_mode()
+            .map_err(|_| CryptoProviderError::Unavailable("AWS-LC is not in FIPS mode"))?;
+
+        Ok(Self {
+            rng: SystemRandom::new(),
+        })
+    }
+
+    fn key(key: &[u8; AEAD_KEY_LEN]) -> CryptoProviderResult<LessSafeKey> {
+        let key =
+            UnboundKey::new(&AES_256_GCM, key).map_err(|_| CryptoProviderError::SealFailed)?;
+        Ok(LessSafeKey::new(key))
+    }
+
+    fn nonce(nonce: &[u8]) -> CryptoProviderResult<Nonce> {
+        Nonce::try_assume_unique_for_key(nonce).map_err(|_| CryptoProviderError::InvalidNonceLength)
+    }
+}
+
+/// Incremental SHA-256 over an AWS-LC digest context.
+struct AwsLcSha256(digest::Context);
+
+impl Sha256Context for AwsLcSha256 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn digest(&self) -> [u8; SHA256_LEN] {
+        to_array(self.0.clone().finish().as_ref())
+    }
+}
+
+/// HKDF output length for [`hkdf::Prk::expand`].
+struct OkmLen(usize);
+
+impl hkdf::KeyType for OkmLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+impl CryptoProvider for AwsLcFipsProvider {
+    fn name(&self) -> &'static str {
+        "aws-lc-fips"
+    }
+
+    fn is_fips(&self) -> bool {
+        true
+    }
+
+    fn sha256(&self, data: &[u8]) -> [u8; SHA256_LEN] {
+        to_array(digest::digest(&digest::SHA256, data).as_ref())
+    }
+
+    fn sha256_context(&self) -> Box<dyn Sha256Context> {
+        Box::new(AwsLcSha256(digest::Context::new(&digest::SHA256)))
+    }
+
+    fn hmac_sha256(&self, key: &[u8], parts: &[&[u8]]) -> [u8; SHA256_LEN] {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+        let mut context = hmac::Context::with_key(&key);
+        for part in parts {
+            context.update(part);
+        }
+        to_array(context.sign().as_ref())
+    }
+
+    fn hkdf_sha256(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+        info: &[u8],
+        okm: &mut [u8],
+    ) -> CryptoProviderResult<()> {
+        hkdf::Salt::new(hkdf::HKDF_SHA256, salt)
+            .extract(ikm)
+            .expand(&[info], OkmLen(okm.len()))
+            .and_then(|material| material.fill(okm))
+            .map_err(|_| CryptoProviderError::OutputTooLong)
+    }
+
+    fn aead_name(&self) -> &'static str {
+        "AES-256-GCM"
+    }
+
+    fn aead_nonce_len(&self) -> usize {
+        NONCE_LEN
+    }
+
+    fn aead_seal(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8],
+        plaintext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        let mut in_out = plaintext.to_vec();
+        Self::key(key)?
+            .seal_in_place_append_tag(Self::nonce(nonce)?, Aad::empty(), &mut in_out)
+            .map_err(|_| CryptoProviderError::SealFailed)?;
+        Ok(in_out)
+    }
+
+    fn aead_open(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        let mut in_out = ciphertext.to_vec();
+        let plaintext_len = Self::key(key)?
+            .open_in_place(Self::nonce(nonce)?, Aad::empty(), &mut in_out)
+            .map_err(|_| CryptoProviderError::OpenFailed)?
+            .len();
+        in_out.truncate(plaintext_len);
+        Ok(in_out)
+    }
+
+    fn aes256_gcm_seal(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8; AES_GCM_NONCE_LEN],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        // The provider's AEAD is AES-256-GCM already.
+        let mut in_out = plaintext.to_vec();
+        Self::key(key)?
+            .seal_in_place_append_tag(Self::nonce(nonce)?, Aad::from(aad), &mut in_out)
+            .map_err(|_| CryptoProviderError::SealFailed)?;
+        Ok(in_out)
+    }
+
+    fn aes256_gcm_open(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8; AES_GCM_NONCE_LEN],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        let mut in_out = ciphertext.to_vec();
+        let plaintext_len = Self::key(key)?
+            .open_in_place(Self::nonce(nonce)?, Aad::from(aad), &mut in_out)
+            .map_err(|_| CryptoProviderError::OpenFailed)?
+            .len();
+        in_out.truncate(plaintext_len);
+        Ok(in_out)
+    }
+
+    fn fill_random(&self, buf: &mut [u8]) -> CryptoProviderResult<()> {
+        self.rng
+            .fill(buf)
+            .map_err(|_| CryptoProviderError::RandomFailed)
+    }
+}
+
+/// Copies a SHA-256-sized output into an array.
+fn to_array(bytes: &[u8]) -> [u8; SHA256_LEN] {
+    bytes.try_into().expect("SHA-256 output is 32 bytes")
+}
@@ -0,0 +1,229 @@
+//! Pluggable cryptographic primitives behind a single [`CryptoProvider`].
+//!
+//! Every hash, MAC, key derivation, AEAD seal and random draw the services
+//! perform goes through a provider, so a deployment's crypto policy is decided
+//! in one place. Two providers exist:
+//!
+//! - [`RustCryptoProvider`]: pure-Rust RustCrypto implementations
+//!   (SHA-256, HMAC-SHA256, HKDF-SHA256, XChaCha20-Poly1305). The default.
+//! - `AwsLcFipsProvider` (feature `fips`): the AWS-LC FIPS module, with
+//!   AES-256-GCM as the AEAD.
+//!
+//! Both also expose AES-256-GCM with associated data directly, for stored
+//! formats that fix the cipher regardless of policy.
+//!
+//! The two AEADs differ, so ciphertext sealed under one policy cannot be
+//! opened under the other; the policy is fixed for the lifetime of a
+//! deployment's data. Run [`self_test`] at startup to verify the active
+//! provider against known-answer vectors before serving traffic.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[cfg(feature = "fips")]
+mod aws_lc_fips;
+mod rust_crypto;
+mod self_test;
+
+#[cfg(feature = "fips")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fips")))]
+pub use aws_lc_fips::AwsLcFipsProvider;
+pub use rust_crypto::RustCryptoProvider;
+pub use self_test::{SelfTestCheck, SelfTestReport, self_test};
+
+/// Size of a SHA-256 digest (and HMAC-SHA256 tag) in bytes.
+pub const SHA256_LEN: usize = 32;
+
+/// Size of an AEAD key in bytes (256-bit keys for every provider).
+pub const AEAD_KEY_LEN: usize = 32;
+
+/// Size of the authentication tag appended by [`CryptoProvider::aead_seal`].
+pub const AEAD_TAG_LEN: usize = 16;
+
+/// Size of an AES-256-GCM nonce in bytes.
+pub const AES_GCM_NONCE_LEN: usize = 12;
+
+/// Result type for crypto provider operations.
+pub type CryptoProviderResult<T> = Result<T, CryptoProviderError>;
+
+/// Errors returned by a [`CryptoProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CryptoProviderError {
+    /// The nonce does not match the provider's [`CryptoProvider::aead_nonce_len`].
+    #[error("invalid nonce length")]
+    InvalidNonceLength,
+    /// The requested HKDF output is longer than HKDF-SHA256 can produce.
+    #[error("requested key material is too long")]
+    OutputTooLong,
+    /// Sealing failed.
+    #[error("aead seal failed")]
+    SealFailed,
+    /// Opening failed: the ciphertext is corrupted, tampered with, or was
+    /// sealed under another key.
+    #[error("aead open failed")]
+    OpenFailed,
+    /// The random number generator failed.
+    #[error("random number generator failed")]
+    RandomFailed,
+    /// The requested provider is not available in this build or environment.
+    #[error("crypto provider unavailable: {0}")]
+    Unavailable(&'static str),
+}
+
+/// An incremental SHA-256 computation.
+pub trait Sha256Context: Send {
+    /// Absorbs more input.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the digest of everything absorbed so far.
+    ///
+    /// Does not consume the context, so more input may follow.
+    fn digest(&self) -> [u8; SHA256_LEN];
+}
+
+/// A source of the cryptographic primitives used across the platform.
+pub trait CryptoProvider: fmt::Debug + Send + Sync {
+    /// Short provider name for logs and health reports.
+    fn name(&self) -> &'static str;
+
+    /// Whether the provider runs a FIPS 140 validated module in FIPS mode.
+    fn is_fips(&self) -> bool;
+
+    /// Computes the SHA-256 digest of `data`.
+    fn sha256(&self, data: &[u8]) -> [u8; SHA256_LEN];
+
+    /// Starts an incremental SHA-256 computation.
+    fn sha256_context(&self) -> Box<dyn Sha256Context>;
+
+    /// Computes HMAC-SHA256 over the concatenation of `parts`.
+    fn hmac_sha256(&self, key: &[u8], parts: &[&[u8]]) -> [u8; SHA256_LEN];
+
+    /// Fills `okm` with HKDF-SHA256 output keying material.
+    fn hkdf_sha256(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+        info: &[u8],
+        okm: &mut [u8],
+    ) -> CryptoProviderResult<()>;
+
+    /// Name of the provider's AEAD cipher.
+    fn aead_name(&self) -> &'static str;
+
+    /// Nonce length of the provider's AEAD cipher in bytes.
+    fn aead_nonce_len(&self) -> usize;
+
+    /// Seals `plaintext`, returning the ciphertext with the tag appended.
+    fn aead_seal(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8],
+        plaintext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>>;
+
+    /// Opens a ciphertext produced by [`aead_seal`](Self::aead_seal).
+    fn aead_open(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>>;
+
+    /// Seals `plaintext` with AES-256-GCM, authenticating `aad` with it,
+    /// returning the ciphertext with the tag appended.
+    ///
+    /// Unlike [`aead_seal`](Self::aead_seal), the cipher does not depend on
+    /// the provider.
+    fn aes256_gcm_seal(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8; AES_GCM_NONCE_LEN],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>>;
+
+    /// Opens a ciphertext produced by
+    /// [`aes256_gcm_seal`](Self::aes256_gcm_seal) under the same `aad`.
+    fn aes256_gcm_open(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8; AES_GCM_NONCE_LEN],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>>;
+
+    /// Fills `buf` with cryptographically secure random bytes.
+    fn fill_random(&self, buf: &mut [u8]) -> CryptoProviderResult<()>;
+}
+
+/// Which [`CryptoProvider`] a deployment runs with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CryptoPolicy {
+    /// Pure-Rust primitives ([`RustCryptoProvider`]).
+    #[default]
+    Standard,
+    /// FIPS 140 validated primitives (`AwsLcFipsProvider`).
+    Fips,
+}
+
+impl CryptoPolicy {
+    /// Instantiates the provider for this policy.
+    ///
+    /// Fails for [`CryptoPolicy::Fips`] when built without the `fips` feature
+    /// or when the FIPS module is not in FIPS mode.
+    pub fn provider(self) -> CryptoProviderResult<Arc<dyn CryptoProvider>> {
+        match self {
+            Self::Standard => Ok(Arc::new(RustCryptoProvider)),
+            #[cfg(feature = "fips")]
+            Self::Fips => Ok(Arc::new(AwsLcFipsProvider::new()?)),
+            #[cfg(not(feature = "fips"))]
+            Self::Fips => Err(CryptoProviderError::Unavailable(
+                "built without the `fips` feature",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CryptoPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => f.write_str("standard"),
+            Self::Fips => f.write_str("fips"),
+        }
+    }
+}
+
+impl FromStr for CryptoPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "standard" => Ok(Self::Standard),
+            "fips" => Ok(Self::Fips),
+            other => Err(format!(
+                "unknown crypto policy '{other}' (expected 'standard' or 'fips')"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_parse() {
+        assert_eq!("standard".parse(), Ok(CryptoPolicy::Standard));
+        assert_eq!("FIPS".parse(), Ok(CryptoPolicy::Fips));
+        assert!("fast".parse::<CryptoPolicy>().is_err());
+        assert_eq!(CryptoPolicy::Fips.to_string(), "fips");
+    }
+
+    #[test]
+    fn test_standard_policy_provider() {
+        let provider = CryptoPolicy::Standard.provider().unwrap();
+        assert_eq!(provider.name(), "rustcrypto");
+        assert!(!provider.is_fips());
+    }
+}
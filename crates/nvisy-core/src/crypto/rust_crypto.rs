@@ -0,0 +1,150 @@
+//! Pure-Rust provider built on the RustCrypto crates.
+
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use super::{
+    AEAD_KEY_LEN, AES_GCM_NONCE_LEN, CryptoProvider, CryptoProviderError, CryptoProviderResult,
+    SHA256_LEN, Sha256Context,
+};
+
+/// Size of the XChaCha20-Poly1305 nonce in bytes.
+const XCHACHA_NONCE_LEN: usize = 24;
+
+/// The default provider: RustCrypto SHA-256, HMAC, HKDF, XChaCha20-Poly1305
+/// and AES-256-GCM, with the thread-local OS-seeded RNG.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RustCryptoProvider;
+
+/// Incremental SHA-256 over [`sha2::Sha256`].
+struct RustSha256(Sha256);
+
+impl Sha256Context for RustSha256 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn digest(&self) -> [u8; SHA256_LEN] {
+        self.0.clone().finalize().into()
+    }
+}
+
+impl RustCryptoProvider {
+    fn cipher(key: &[u8; AEAD_KEY_LEN]) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(key.into())
+    }
+
+    fn nonce(nonce: &[u8]) -> CryptoProviderResult<XNonce> {
+        XNonce::try_from(nonce).map_err(|_| CryptoProviderError::InvalidNonceLength)
+    }
+}
+
+impl CryptoProvider for RustCryptoProvider {
+    fn name(&self) -> &'static str {
+        "rustcrypto"
+    }
+
+    fn is_fips(&self) -> bool {
+        false
+    }
+
+    fn sha256(&self, data: &[u8]) -> [u8; SHA256_LEN] {
+        Sha256::digest(data).into()
+    }
+
+    fn sha256_context(&self) -> Box<dyn Sha256Context> {
+        Box::new(RustSha256(Sha256::new()))
+    }
+
+    fn hmac_sha256(&self, key: &[u8], parts: &[&[u8]]) -> [u8; SHA256_LEN] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().into()
+    }
+
+    fn hkdf_sha256(
+        &self,
+        salt: &[u8],
+        ikm: &[u8],
+        info: &[u8],
+        okm: &mut [u8],
+    ) -> CryptoProviderResult<()> {
+        Hkdf::<Sha256>::new(Some(salt), ikm)
+            .expand(info, okm)
+            .map_err(|_| CryptoProviderError::OutputTooLong)
+    }
+
+    fn aead_name(&self) -> &'static str {
+        "XChaCha20-Poly1305"
+    }
+
+    fn aead_nonce_len(&self) -> usize {
+        XCHACHA_NONCE_LEN
+    }
+
+    fn aead_seal(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8],
+        plaintext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        Self::cipher(key)
+            .encrypt(&Self::nonce(nonce)?, plaintext)
+            .map_err(|_| CryptoProviderError::SealFailed)
+    }
+
+    fn aead_open(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        Self::cipher(key)
+            .decrypt(&Self::nonce(nonce)?, ciphertext)
+            .map_err(|_| CryptoProviderError::OpenFailed)
+    }
+
+    fn aes256_gcm_seal(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8; AES_GCM_NONCE_LEN],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        Aes256Gcm::new(key.into())
+            .encrypt(nonce.into(), payload)
+            .map_err(|_| CryptoProviderError::SealFailed)
+    }
+
+    fn aes256_gcm_open(
+        &self,
+        key: &[u8; AEAD_KEY_LEN],
+        nonce: &[u8; AES_GCM_NONCE_LEN],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> CryptoProviderResult<Vec<u8>> {
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        Aes256Gcm::new(key.into())
+            .decrypt(nonce.into(), payload)
+            .map_err(|_| CryptoProviderError::OpenFailed)
+    }
+
+    fn fill_random(&self, buf: &mut [u8]) -> CryptoProviderResult<()> {
+        rand::rng().fill_bytes(buf);
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Known-answer self-test for a [`CryptoProvider`].
+//!
+//! Checks each primitive against published test vectors (FIPS 180-4,
+//! RFC 4231, RFC 5869, the GCM specification) and the provider's AEAD
+//! against a seal/open round trip with tamper detection, producing a report
+//! to log at startup.
+
+use std::fmt;
+
+use super::{AEAD_KEY_LEN, AES_GCM_NONCE_LEN, CryptoProvider};
+
+/// SHA-256("abc"), FIPS 180-4 example.
+const SHA256_ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+/// RFC 4231 test case 2 (key "Jefe").
+const HMAC_SHA256_JEFE: &str = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+
+/// RFC 5869 test case 1 output keying material (42 bytes).
+const HKDF_SHA256_OKM: &str =
+    "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865";
+
+/// GCM specification test case 14 (zero key, nonce and 16-byte plaintext):
+/// ciphertext followed by tag.
+const AES256_GCM_ZERO: &str = "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919";
+
+/// Outcome of one self-test check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTestCheck {
+    /// Primitive under test (e.g. `"sha256"`).
+    pub name: &'static str,
+    /// Whether the primitive produced the expected output.
+    pub passed: bool,
+}
+
+/// Result of [`self_test`] for one provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Name of the tested provider.
+    pub provider: &'static str,
+    /// Whether the provider runs in FIPS mode.
+    pub fips: bool,
+    /// Name of the provider's AEAD cipher.
+    pub aead: &'static str,
+    /// Outcome of every check, in execution order.
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Names of the checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name)
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (fips: {}, aead: {}): ",
+            self.provider, self.fips, self.aead
+        )?;
+
+        if self.passed() {
+            write!(f, "all {} checks passed", self.checks.len())
+        } else {
+            let failures: Vec<_> = self.failures().collect();
+            write!(f, "failed {}", failures.join(", "))
+        }
+    }
+}
+
+/// Runs the known-answer self-test against `provider`.
+pub fn self_test(provider: &dyn CryptoProvider) -> SelfTestReport {
+    let checks = vec![
+        SelfTestCheck {
+            name: "sha256",
+            passed: check_sha256(provider),
+        },
+        SelfTestCheck {
+            name: "sha256_incremental",
+            passed: check_sha256_incremental(provider),
+        },
+        SelfTestCheck {
+            name: "hmac_sha256",
+            passed: check_hmac_sha256(provider),
+        },
+        SelfTestCheck {
+            name: "hkdf_sha256",
+            passed: check_hkdf_sha256(provider),
+        },
+        SelfTestCheck {
+            name: "aead",
+            passed: check_aead(provider),
+        },
+        SelfTestCheck {
+            name: "aes256_gcm",
+            passed: check_aes256_gcm(provider),
+        },
+        SelfTestCheck {
+            name: "random",
+            passed: check_random(provider),
+        },
+    ];
+
+    SelfTestReport {
+        provider: provider.name(),
+        fips: provider.is_fips(),
+        aead: provider.aead_name(),
+        checks,
+    }
+}
+
+fn check_sha256(provider: &dyn CryptoProvider) -> bool {
+    hex::encode(provider.sha256(b"abc")) == SHA256_ABC
+}
+
+fn check_sha256_incremental(provider: &dyn CryptoProvider) -> bool {
+    let mut context = provider.sha256_context();
+    context.update(b"a");
+    context.update(b"bc");
+    hex::encode(context.digest()) == SHA256_ABC
+}
+
+fn check_hmac_sha256(provider: &dyn CryptoProvider) -> bool {
+    let tag = provider.hmac_sha256(b"Jefe", &[b"what do ya want ", b"for nothing?"]);
+    hex::encode(tag) == HMAC_SHA256_JEFE
+}
+
+fn check_hkdf_sha256(provider: &dyn CryptoProvider) -> bool {
+    let ikm = [0x0b; 22];
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let mut okm = [0u8; 42];
+
+    provider
+        .hkdf_sha256(&salt, &ikm, &info, &mut okm)
+        .is_ok_and(|()| hex::encode(okm) == HKDF_SHA256_OKM)
+}
+
+fn check_aead(provider: &dyn CryptoProvider) -> bool {
+    let key = [0x42; AEAD_KEY_LEN];
+    let nonce = vec![0x24; provider.aead_nonce_len()];
+    let plaintext = b"nvisy crypto self-test";
+
+    let Ok(mut sealed) = provider.aead_seal(&key, &nonce, plaintext) else {
+        return false;
+    };
+    let round_trips = provider
+        .aead_open(&key, &nonce, &sealed)
+        .is_ok_and(|opened| opened == plaintext);
+
+    sealed[0] ^= 0x01;
+    let rejects_tamper = provider.aead_open(&key, &nonce, &sealed).is_err();
+
+    round_trips && rejects_tamper
+}
+
+fn check_aes256_gcm(provider: &dyn CryptoProvider) -> bool {
+    let key = [0u8; AEAD_KEY_LEN];
+    let nonce = [0u8; AES_GCM_NONCE_LEN];
+
+    let Ok(sealed) = provider.aes256_gcm_seal(&key, &nonce, &[], &[0u8; 16]) else {
+        return false;
+    };
+    let known_answer = hex::encode(&sealed) == AES256_GCM_ZERO;
+
+    // The associated data is authenticated: opening under other data fails.
+    let sealed = provider.aes256_gcm_seal(&key, &nonce, b"aad", b"nvisy");
+    let binds_aad = sealed.is_ok_and(|sealed| {
+        provider
+            .aes256_gcm_open(&key, &nonce, b"aad", &sealed)
+            .is_ok_and(|opened| opened == b"nvisy")
+            && provider
+                .aes256_gcm_open(&key, &nonce, b"other", &sealed)
+                .is_err()
+    });
+
+    known_answer && binds_aad
+}
+
+fn check_random(provider: &dyn CryptoProvider) -> bool {
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+
+    provider.fill_random(&mut first).is_ok()
+        && provider.fill_random(&mut second).is_ok()
+        && first != second
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::RustCryptoProvider;
+
+    #[test]
+    fn test_rust_crypto_passes() {
+        let report = self_test(&RustCryptoProvider);
+        assert!(report.passed(), "{report}");
+        assert_eq!(report.provider, "rustcrypto");
+        assert!(!report.fips);
+    }
+
+    #[test]
+    fn test_report_lists_failures() {
+        let report = SelfTestReport {
+            provider: "test",
+            fips: false,
+            aead: "none",
+            checks: vec![
+                SelfTestCheck {
+                    name: "sha256",
+                    passed: true,
+                },
+                SelfTestCheck {
+                    name: "aead",
+                    passed: false,
+                },
+            ],
+        };
+
+        assert!(!report.passed());
+        assert_eq!(report.failures().collect::<Vec<_>>(), ["aead"]);
+        assert!(report.to_string().ends_with("failed aead"));
+    }
+}
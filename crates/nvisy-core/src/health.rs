@@ -1,8 +1,10 @@
 //! Shared health-reporting vocabulary and the [`HealthCheck`] trait.
 //!
 //! Each service client implements [`HealthCheck`] to report the health of the
-//! component it manages as a [`ComponentHealth`]. Aggregation into an overall
-//! report (and any transport concerns) is left to the consumer.
+//! component it manages as a [`ComponentHealth`], optionally naming the other
+//! components it relies on as [`HealthDependency`] edges. Aggregation into an
+//! overall report, including how failures propagate along those edges, is left
+//! to the consumer.
 
 use std::borrow::Cow;
 use std::time::Duration;
@@ -10,7 +12,10 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 
 /// Operational status of a service component.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+///
+/// Ordered from best to worst, so the combined status of several components
+/// is their maximum.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
@@ -32,6 +37,52 @@ impl HealthStatus {
     }
 }
 
+/// How much a component relies on one of its dependencies.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    /// The component cannot work without the dependency: its failures
+    /// propagate unchanged.
+    #[default]
+    Required,
+    /// The component keeps working without the dependency, with reduced
+    /// functionality: any failure degrades it at most.
+    Optional,
+}
+
+/// An edge from a component to another component it relies on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct HealthDependency {
+    /// Name of the component depended on.
+    pub name: Cow<'static, str>,
+    /// How much the dependent relies on it.
+    #[serde(default)]
+    pub kind: DependencyKind,
+}
+
+impl HealthDependency {
+    /// Creates an edge to a dependency the component cannot work without.
+    pub fn required(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            kind: DependencyKind::Required,
+        }
+    }
+
+    /// Creates an edge to a dependency the component can work without.
+    pub fn optional(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            kind: DependencyKind::Optional,
+        }
+    }
+}
+
 /// Health of a single service component.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
@@ -45,6 +96,9 @@ pub struct ComponentHealth {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "schema", schemars(with = "Option<u64>"))]
     pub latency: Option<Duration>,
+    /// Components this one relies on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<HealthDependency>,
 }
 
 impl ComponentHealth {
@@ -54,6 +108,7 @@ impl ComponentHealth {
             name: name.into(),
             status: HealthStatus::Healthy,
             latency: None,
+            dependencies: Vec::new(),
         }
     }
 
@@ -63,6 +118,7 @@ impl ComponentHealth {
             name: name.into(),
             status: HealthStatus::Unhealthy,
             latency: None,
+            dependencies: Vec::new(),
         }
     }
 
@@ -72,6 +128,13 @@ impl ComponentHealth {
         self.latency = Some(latency);
         self
     }
+
+    /// Declares a component this one relies on.
+    #[must_use]
+    pub fn with_dependency(mut self, dependency: HealthDependency) -> Self {
+        self.dependencies.push(dependency);
+        self
+    }
 }
 
 /// Reports the health of the component a client manages.
@@ -2,7 +2,12 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
+pub mod cache;
+pub mod circuit_breaker;
+pub mod clock;
+pub mod crypto;
 pub mod health;
+pub mod id;
 
 /// Tracing target for core operations.
 pub const TRACING_TARGET: &str = "nvisy_core";
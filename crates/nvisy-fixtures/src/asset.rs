@@ -0,0 +1,348 @@
+//! Large assets kept outside the repository.
+//!
+//! Real-world scans and long documents are too large to commit. They are
+//! hosted together with a `manifest.json` listing each asset's path, size,
+//! SHA-256 and golden, and cached in a local directory. An [`AssetStore`]
+//! only hands out assets whose content matches the manifest; an asset that
+//! is not cached, cannot be downloaded or is over the download limit is
+//! reported as unavailable, so tests using it can skip.
+
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Fixture, FixtureFormat, FixtureKind, Golden, Result};
+
+/// Name of the manifest, both hosted and in the cache directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Default largest asset that may be downloaded, in bytes.
+pub const DEFAULT_MAX_ASSET_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Environment variable naming the cache directory.
+const DIR_VAR: &str = "NVISY_FIXTURES_DIR";
+
+/// Environment variable with the base URL of the hosted assets.
+#[cfg(feature = "download")]
+const URL_VAR: &str = "NVISY_FIXTURES_URL";
+
+/// Environment variable with the largest asset that may be downloaded.
+const MAX_SIZE_VAR: &str = "NVISY_FIXTURES_MAX_SIZE";
+
+/// The list of large assets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    /// Every hosted asset.
+    pub assets: Vec<AssetEntry>,
+}
+
+/// One hosted asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetEntry {
+    /// Fixture name, unique within the manifest.
+    pub name: String,
+    /// What the asset exercises.
+    pub kind: FixtureKind,
+    /// The asset's file format.
+    pub format: FixtureFormat,
+    /// Path relative to the base URL and the cache directory.
+    pub path: String,
+    /// Size in bytes.
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the content.
+    pub sha256: String,
+    /// What an extraction of the asset should produce.
+    pub golden: Golden,
+}
+
+impl AssetManifest {
+    /// Parses a manifest.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Returns the entry with the given name.
+    pub fn get(&self, name: &str) -> Option<&AssetEntry> {
+        self.assets.iter().find(|entry| entry.name == name)
+    }
+}
+
+impl AssetEntry {
+    /// Returns the asset's path below `dir`, rejecting paths that would
+    /// leave it.
+    fn path_in(&self, dir: &Path) -> Result<PathBuf> {
+        let path = Path::new(&self.path);
+        let contained = path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if !contained || self.path.is_empty() {
+            return Err(Error::InvalidPath {
+                name: self.name.clone(),
+            });
+        }
+        Ok(dir.join(path))
+    }
+
+    /// Turns verified content into a fixture.
+    fn into_fixture(self, bytes: Vec<u8>) -> Result<Fixture> {
+        let digest = hex::encode(Sha256::digest(&bytes));
+        if !digest.eq_ignore_ascii_case(&self.sha256) {
+            return Err(Error::ChecksumMismatch { name: self.name });
+        }
+        Ok(Fixture::new(
+            self.name,
+            self.kind,
+            self.format,
+            bytes,
+            self.golden,
+        ))
+    }
+}
+
+/// Local cache of large assets, optionally filled by downloads.
+#[derive(Debug, Clone)]
+pub struct AssetStore {
+    dir: PathBuf,
+    max_size: u64,
+    #[cfg(feature = "download")]
+    base_url: Option<url::Url>,
+}
+
+impl AssetStore {
+    /// Creates a store caching assets in `dir`, without downloads.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size: DEFAULT_MAX_ASSET_SIZE,
+            #[cfg(feature = "download")]
+            base_url: None,
+        }
+    }
+
+    /// Creates a store configured from the environment.
+    ///
+    /// `NVISY_FIXTURES_DIR` sets the cache directory (a `nvisy-fixtures`
+    /// directory under the system temporary directory by default),
+    /// `NVISY_FIXTURES_MAX_SIZE` the download limit in bytes, and
+    /// `NVISY_FIXTURES_URL` the base URL downloads are made from. Invalid
+    /// values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let dir = env::var_os(DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| env::temp_dir().join("nvisy-fixtures"));
+        let mut store = Self::new(dir);
+
+        if let Some(max_size) = env::var(MAX_SIZE_VAR)
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            store = store.with_max_size(max_size);
+        }
+
+        #[cfg(feature = "download")]
+        if let Some(base_url) = env::var(URL_VAR)
+            .ok()
+            .and_then(|value| url::Url::parse(&value).ok())
+        {
+            store = store.with_base_url(base_url);
+        }
+
+        store
+    }
+
+    /// Sets the largest asset that may be downloaded, in bytes.
+    ///
+    /// Assets already in the cache are used whatever their size.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets the base URL assets and the manifest are downloaded from.
+    #[cfg(feature = "download")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "download")))]
+    pub fn with_base_url(mut self, mut base_url: url::Url) -> Self {
+        // Without a trailing slash, joining would replace the last segment.
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Returns the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the largest asset that may be downloaded, in bytes.
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Reads the manifest from the cache, if it is there.
+    pub fn manifest(&self) -> Result<Option<AssetManifest>> {
+        match fs::read(self.dir.join(MANIFEST_FILE)) {
+            Ok(bytes) => Ok(Some(AssetManifest::from_slice(&bytes)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Loads a cached asset by name, using the cached manifest.
+    ///
+    /// Returns `None` if the manifest or the asset is not cached.
+    pub fn cached_asset(&self, name: &str) -> Result<Option<Fixture>> {
+        let Some(manifest) = self.manifest()? else {
+            return Ok(None);
+        };
+        match manifest.get(name) {
+            Some(entry) => self.cached(entry),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads an asset from the cache.
+    ///
+    /// Returns `None` if it is not cached, and an error if the cached
+    /// content does not match the manifest.
+    pub fn cached(&self, entry: &AssetEntry) -> Result<Option<Fixture>> {
+        let path = entry.path_in(&self.dir)?;
+        match fs::read(path) {
+            Ok(bytes) => entry.clone().into_fixture(bytes).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(feature = "download")]
+#[cfg_attr(docsrs, doc(cfg(feature = "download")))]
+impl AssetStore {
+    /// Downloads the manifest into the cache.
+    ///
+    /// Returns the cached manifest, if any, when no base URL is set.
+    pub async fn fetch_manifest(&self) -> Result<Option<AssetManifest>> {
+        let Some(base_url) = &self.base_url else {
+            return self.manifest();
+        };
+        let url = base_url
+            .join(MANIFEST_FILE)
+            .map_err(|_| Error::InvalidPath {
+                name: MANIFEST_FILE.to_owned(),
+            })?;
+
+        let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+        let manifest = AssetManifest::from_slice(&bytes)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(MANIFEST_FILE), &bytes)?;
+        Ok(Some(manifest))
+    }
+
+    /// Loads an asset, downloading it into the cache if it is missing.
+    ///
+    /// Returns `None` if the asset is not cached and either no base URL is
+    /// set or the asset is larger than the download limit.
+    pub async fn load(&self, entry: &AssetEntry) -> Result<Option<Fixture>> {
+        if let Some(fixture) = self.cached(entry)? {
+            return Ok(Some(fixture));
+        }
+        let Some(base_url) = &self.base_url else {
+            return Ok(None);
+        };
+        if entry.size > self.max_size {
+            return Ok(None);
+        }
+
+        let path = entry.path_in(&self.dir)?;
+        let url = base_url.join(&entry.path).map_err(|_| Error::InvalidPath {
+            name: entry.name.clone(),
+        })?;
+        let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+        let fixture = entry.clone().into_fixture(bytes.to_vec())?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, fixture.bytes())?;
+        Ok(Some(fixture))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &[u8] = b"%PDF-1.4\n%%EOF\n";
+
+    fn entry(path: &str, sha256: String) -> AssetEntry {
+        AssetEntry {
+            name: "court-filing".to_owned(),
+            kind: FixtureKind::MultiPage,
+            format: FixtureFormat::Pdf,
+            path: path.to_owned(),
+            size: CONTENT.len() as u64,
+            sha256,
+            golden: Golden::default(),
+        }
+    }
+
+    fn store_with(entry: &AssetEntry) -> (tempfile::TempDir, AssetStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = AssetManifest {
+            assets: vec![entry.clone()],
+        };
+        fs::write(
+            dir.path().join(MANIFEST_FILE),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        let store = AssetStore::new(dir.path());
+        (dir, store)
+    }
+
+    #[test]
+    fn cached_assets_are_verified() {
+        let entry = entry(
+            "legal/court-filing.pdf",
+            hex::encode(Sha256::digest(CONTENT)),
+        );
+        let (dir, store) = store_with(&entry);
+        assert!(store.cached_asset("court-filing").unwrap().is_none());
+
+        fs::create_dir_all(dir.path().join("legal")).unwrap();
+        fs::write(dir.path().join("legal/court-filing.pdf"), CONTENT).unwrap();
+        let fixture = store.cached_asset("court-filing").unwrap().unwrap();
+        assert_eq!(fixture.bytes(), CONTENT);
+
+        fs::write(dir.path().join("legal/court-filing.pdf"), b"tampered").unwrap();
+        assert!(matches!(
+            store.cached_asset("court-filing"),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn paths_must_stay_in_the_cache() {
+        for path in ["../court-filing.pdf", "/etc/court-filing.pdf", ""] {
+            let entry = entry(path, String::new());
+            let (_dir, store) = store_with(&entry);
+            assert!(matches!(
+                store.cached(&entry),
+                Err(Error::InvalidPath { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn missing_manifest_means_no_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AssetStore::new(dir.path());
+        assert!(store.manifest().unwrap().is_none());
+        assert!(store.cached_asset("court-filing").unwrap().is_none());
+    }
+}
@@ -0,0 +1,584 @@
+//! The generated documents of the standard corpus.
+//!
+//! Content is fictional but shaped like what the platform redacts: parties,
+//! contacts, account numbers and amounts, in the layouts they come in. Every
+//! domain is under the reserved `.example` top-level domain.
+
+use crate::render::pdf::{self, PAGE_HEIGHT, PdfPage, TextPage};
+use crate::render::raster::{Raster, TextLayout};
+use crate::{Fixture, FixtureFormat, FixtureKind, Golden, GoldenPage, GoldenTable};
+
+/// Left margin of text pages in points.
+const MARGIN: f32 = 72.0;
+
+/// Baseline of the first line of a text page in points.
+const TOP: f32 = PAGE_HEIGHT - 72.0;
+
+/// Body text size in points.
+const BODY_SIZE: f32 = 11.0;
+
+/// Distance between body baselines in points.
+const LEADING: f32 = 15.0;
+
+/// Characters per body line; Helvetica at 11 points fits the text width.
+const LINE_WIDTH: usize = 88;
+
+/// Returns every generated document.
+pub(super) fn all() -> Vec<Fixture> {
+    vec![
+        contract(),
+        invoice(),
+        invoice_lines(),
+        notice(),
+        notice_scripts(),
+        letter_scan(),
+        letter_scan_skewed(),
+        statement_scan(),
+    ]
+}
+
+/// A four-page services agreement between two parties.
+fn contract() -> Fixture {
+    const SECTIONS: [&[(&str, &str)]; 4] = [
+        &[
+            (
+                "MASTER SERVICES AGREEMENT",
+                "This Master Services Agreement (the \"Agreement\") is entered into on 14 March \
+                 2025 between Northwind Analytics GmbH, Friedrichstraße 112, 10117 Berlin, \
+                 Germany (the \"Provider\"), and Contoso Health Partners LLC, 4410 Meridian \
+                 Avenue, Seattle, WA 98103, United States (the \"Customer\").",
+            ),
+            (
+                "1. Definitions",
+                "\"Customer Data\" means any document, image or record the Customer submits to \
+                 the Services, including personal data of patients, employees and \
+                 correspondents. \"Services\" means the document redaction, recognition and \
+                 review services described in Schedule A.",
+            ),
+            (
+                "2. Services",
+                "The Provider shall perform the Services in the European Union region and shall \
+                 not transfer Customer Data outside of it without the Customer's prior written \
+                 consent. Redacted outputs are delivered within two business days of submission.",
+            ),
+        ],
+        &[
+            (
+                "3. Contacts",
+                "The Provider's account manager is Katharina Vogel \
+                 (katharina.vogel@northwind.example, +49 30 5550 1834). The Customer's primary \
+                 contact is Daniel O'Connor (d.oconnor@contoso-health.example, +1 206 555 0147). \
+                 Either party may replace its contact by notice in writing.",
+            ),
+            (
+                "4. Fees and Payment",
+                "The Customer shall pay the fees set out in Schedule B within thirty days of \
+                 each invoice. Payments are made by bank transfer to the Provider's account, \
+                 IBAN DE89 3704 0044 0532 0130 00, BIC COBADEFFXXX, quoting the invoice number.",
+            ),
+        ],
+        &[
+            (
+                "5. Confidentiality",
+                "Each party shall keep the other's confidential information secret and use it \
+                 only to perform this Agreement. This obligation survives termination for five \
+                 years, and indefinitely for personal data.",
+            ),
+            (
+                "6. Data Protection",
+                "The Provider processes Customer Data as a processor on the Customer's behalf. \
+                 Records such as medical record number MRN 00482913, dates of birth and \
+                 insurance identifiers are redacted before any output leaves the Services, and \
+                 originals are deleted ninety days after processing.",
+            ),
+        ],
+        &[
+            (
+                "7. Term and Termination",
+                "This Agreement runs for an initial term of twenty-four months and renews for \
+                 successive twelve-month terms unless either party gives ninety days' notice \
+                 before the end of the current term.",
+            ),
+            (
+                "8. Governing Law",
+                "This Agreement is governed by the laws of Germany. The courts of Berlin have \
+                 exclusive jurisdiction over any dispute arising from it.",
+            ),
+            (
+                "Signatures",
+                "Signed for the Provider: Katharina Vogel, Managing Director. Signed for the \
+                 Customer: Daniel O'Connor, Chief Operating Officer.",
+            ),
+        ],
+    ];
+
+    let page_count = SECTIONS.len();
+    let pages = SECTIONS
+        .iter()
+        .enumerate()
+        .map(|(index, sections)| {
+            let mut lines = Vec::new();
+            for (heading, body) in sections.iter() {
+                lines.push(Line::heading(heading));
+                lines.extend(wrap(body, LINE_WIDTH).into_iter().map(Line::body));
+                lines.push(Line::blank());
+            }
+            lines.push(Line::body(format!("Page {} of {page_count}", index + 1)));
+            ("en", lines)
+        })
+        .collect();
+
+    text_fixture("contract", FixtureKind::MultiPage, pages, Vec::new())
+}
+
+/// Column headers of the invoice table.
+const INVOICE_HEADER: [&str; 4] = ["Item", "Quantity", "Unit price", "Amount"];
+
+/// Rows of the invoice table.
+const INVOICE_ROWS: [[&str; 4]; 5] = [
+    ["Document redaction (pages)", "12,000", "0.45", "5,400.00"],
+    ["OCR processing (pages)", "8,000", "0.30", "2,400.00"],
+    ["Reviewer seats", "6", "490.00", "2,940.00"],
+    ["Dedicated region (EU)", "1", "1,500.00", "1,500.00"],
+    ["Priority support", "1", "600.00", "600.00"],
+];
+
+fn invoice_table() -> GoldenTable {
+    GoldenTable {
+        page: 1,
+        header: INVOICE_HEADER.map(String::from).to_vec(),
+        rows: INVOICE_ROWS
+            .iter()
+            .map(|row| row.map(String::from).to_vec())
+            .collect(),
+    }
+}
+
+/// A one-page invoice laid out as a ruled table.
+fn invoice() -> Fixture {
+    const COLUMNS: [f32; 4] = [MARGIN, 300.0, 380.0, 460.0];
+    const RIGHT: f32 = 523.0;
+    const ROW_HEIGHT: f32 = 20.0;
+
+    let intro = [
+        "INVOICE INV-2025-0417",
+        "Issued 31 March 2025 by Northwind Analytics GmbH, VAT ID DE 298 471 506",
+        "Billed to: Contoso Health Partners LLC, 4410 Meridian Avenue, Seattle, WA 98103",
+    ];
+
+    let mut page = TextPage::new();
+    let mut text = Vec::new();
+    let mut y = TOP;
+    for line in intro {
+        page = page.text(MARGIN, y, BODY_SIZE, line);
+        text.push(line.to_owned());
+        y -= LEADING;
+    }
+
+    y -= LEADING;
+    let rows = std::iter::once(INVOICE_HEADER).chain(INVOICE_ROWS);
+    for cells in rows {
+        page = page.rule(
+            (MARGIN, y + ROW_HEIGHT - 5.0),
+            (RIGHT, y + ROW_HEIGHT - 5.0),
+        );
+        for (x, cell) in COLUMNS.iter().zip(cells) {
+            page = page.text(*x, y, BODY_SIZE, cell);
+        }
+        text.push(cells.join(" "));
+        y -= ROW_HEIGHT;
+    }
+    page = page.rule(
+        (MARGIN, y + ROW_HEIGHT - 5.0),
+        (RIGHT, y + ROW_HEIGHT - 5.0),
+    );
+
+    let total = "Total due: EUR 12,840.00";
+    page = page.text(COLUMNS[2], y - LEADING, BODY_SIZE, total);
+    text.push(total.to_owned());
+
+    let golden = Golden {
+        pages: vec![GoldenPage::new("en", text.join("\n"))],
+        tables: vec![invoice_table()],
+    };
+    Fixture::new(
+        "invoice",
+        FixtureKind::Table,
+        FixtureFormat::Pdf,
+        pdf::write(&[PdfPage::Text(page)]),
+        golden,
+    )
+}
+
+/// The invoice table as comma-separated values.
+fn invoice_lines() -> Fixture {
+    let mut csv = String::new();
+    let rows = std::iter::once(INVOICE_HEADER).chain(INVOICE_ROWS);
+    for cells in rows {
+        let quoted: Vec<String> = cells
+            .iter()
+            .map(|cell| {
+                if cell.contains(',') {
+                    format!("\"{cell}\"")
+                } else {
+                    (*cell).to_owned()
+                }
+            })
+            .collect();
+        csv.push_str(&quoted.join(","));
+        csv.push('\n');
+    }
+
+    let golden = Golden {
+        pages: vec![GoldenPage::new("en", csv.trim_end())],
+        tables: vec![invoice_table()],
+    };
+    Fixture::new(
+        "invoice-lines",
+        FixtureKind::Table,
+        FixtureFormat::Csv,
+        csv.into_bytes(),
+        golden,
+    )
+}
+
+/// A privacy notice with one page per language, in Latin scripts.
+fn notice() -> Fixture {
+    const PAGES: [(&str, &str, &str); 5] = [
+        (
+            "en",
+            "Privacy Notice",
+            "We process your personal data, such as your name, address and date of birth, \
+             only to provide the services you requested. Questions can be sent to our data \
+             protection officer, Laura Bennett, at privacy@northwind.example or +44 20 7946 0321.",
+        ),
+        (
+            "de",
+            "Datenschutzhinweis",
+            "Wir verarbeiten Ihre personenbezogenen Daten, etwa Ihren Namen, Ihre Anschrift und \
+             Ihr Geburtsdatum, ausschließlich zur Erbringung der von Ihnen angeforderten \
+             Leistungen. Fragen richten Sie bitte an unseren Datenschutzbeauftragten, Jürgen \
+             Weiß, unter datenschutz@northwind.example oder +49 30 5550 2290.",
+        ),
+        (
+            "fr",
+            "Avis de confidentialité",
+            "Nous traitons vos données personnelles, telles que votre nom, votre adresse et \
+             votre date de naissance, uniquement pour fournir les services que vous avez \
+             demandés. Vos questions peuvent être adressées à notre déléguée à la protection \
+             des données, Hélène Durand, à confidentialite@northwind.example ou au +33 1 55 50 \
+             12 34.",
+        ),
+        (
+            "es",
+            "Aviso de privacidad",
+            "Tratamos sus datos personales, como su nombre, su dirección y su fecha de \
+             nacimiento, únicamente para prestar los servicios que ha solicitado. Puede enviar \
+             sus preguntas a nuestra delegada de protección de datos, María José Núñez, en \
+             privacidad@northwind.example o al +34 91 555 0187.",
+        ),
+        (
+            "pt",
+            "Aviso de privacidade",
+            "Tratamos os seus dados pessoais, como o seu nome, a sua morada e a sua data de \
+             nascimento, apenas para prestar os serviços que solicitou. As suas questões podem \
+             ser enviadas ao nosso encarregado da proteção de dados, João Conceição, através \
+             de privacidade@northwind.example ou +351 21 555 0142.",
+        ),
+    ];
+
+    let pages = PAGES
+        .iter()
+        .map(|(language, heading, body)| {
+            let mut lines = vec![Line::heading(heading)];
+            lines.extend(wrap(body, LINE_WIDTH).into_iter().map(Line::body));
+            (*language, lines)
+        })
+        .collect();
+
+    text_fixture("notice", FixtureKind::Multilingual, pages, Vec::new())
+}
+
+/// The privacy notice in non-Latin scripts, as UTF-8 text with one page per
+/// language.
+fn notice_scripts() -> Fixture {
+    const PAGES: [(&str, &str); 6] = [
+        (
+            "en",
+            "Privacy Notice\nWe process your personal data only to provide the services you \
+             requested. Contact: Laura Bennett, privacy@northwind.example, +44 20 7946 0321.",
+        ),
+        (
+            "el",
+            "Ειδοποίηση απορρήτου\nΕπεξεργαζόμαστε τα προσωπικά σας δεδομένα μόνο για την \
+             παροχή των υπηρεσιών που ζητήσατε. Επικοινωνία: Νίκος Παπαδόπουλος, \
+             privacy@northwind.example, +30 21 0555 0198.",
+        ),
+        (
+            "ru",
+            "Уведомление о конфиденциальности\nМы обрабатываем ваши персональные данные только \
+             для предоставления запрошенных вами услуг. Контакт: Анна Смирнова, \
+             privacy@northwind.example, +7 495 555-01-76.",
+        ),
+        (
+            "ja",
+            "プライバシーに関するお知らせ\n当社は、ご依頼いただいたサービスを提供する目的でのみ、\
+             お客様の個人情報を取り扱います。お問い合わせ：佐藤 花子、privacy@northwind.example、\
+             +81 3-5555-0143。",
+        ),
+        (
+            "ar",
+            "إشعار الخصوصية\nنعالج بياناتك الشخصية فقط لتقديم الخدمات التي طلبتها. للتواصل: ليلى \
+             حداد، privacy@northwind.example، +971 4 555 0163.",
+        ),
+        (
+            "hi",
+            "गोपनीयता सूचना\nहम आपके व्यक्तिगत डेटा का उपयोग केवल आपके द्वारा अनुरोधित सेवाएँ प्रदान \
+             करने के लिए करते हैं। संपर्क: प्रिया शर्मा, privacy@northwind.example, +91 22 5555 0129।",
+        ),
+    ];
+
+    let text = PAGES
+        .iter()
+        .map(|(_, text)| *text)
+        .collect::<Vec<_>>()
+        .join("\n\u{c}\n");
+    let golden = Golden {
+        pages: PAGES
+            .iter()
+            .map(|(language, text)| GoldenPage::new(*language, *text))
+            .collect(),
+        tables: Vec::new(),
+    };
+    Fixture::new(
+        "notice-scripts",
+        FixtureKind::Multilingual,
+        FixtureFormat::Txt,
+        text.into_bytes(),
+        golden,
+    )
+}
+
+/// Lines of a claims letter, written for the bitmap font.
+const LETTER: [&str; 27] = [
+    "NORTHWIND ANALYTICS GMBH",
+    "FRIEDRICHSTRASSE 112, 10117 BERLIN",
+    "",
+    "MS JANE MORRISON",
+    "27 ELM GROVE",
+    "LEEDS LS6 2AB",
+    "UNITED KINGDOM",
+    "",
+    "BERLIN, 2 APRIL 2025",
+    "",
+    "YOUR REFERENCE: CLM-2025-00871",
+    "",
+    "DEAR MS MORRISON,",
+    "",
+    "THANK YOU FOR YOUR CLAIM OF 18 MARCH 2025. WE HAVE",
+    "REVIEWED THE DOCUMENTS YOU SENT AND WILL REFUND",
+    "EUR 1,240.50 TO THE ACCOUNT ENDING 4471 WITHIN TEN",
+    "WORKING DAYS.",
+    "",
+    "IF YOU HAVE ANY QUESTIONS, PLEASE CALL US ON",
+    "+49 30 5550 1834 OR WRITE TO CLAIMS@NORTHWIND.EXAMPLE.",
+    "",
+    "YOURS SINCERELY,",
+    "",
+    "",
+    "KATHARINA VOGEL",
+    "CLAIMS DEPARTMENT",
+];
+
+/// The claims letter, scanned straight.
+fn letter_scan() -> Fixture {
+    let raster = Raster::text(TextLayout::A4, &LETTER).noise(0x5ca1_ab1e, 40);
+    scan_fixture(
+        "letter-scan",
+        FixtureKind::Scan,
+        FixtureFormat::Png,
+        raster.to_png(),
+        &[&LETTER],
+    )
+}
+
+/// The claims letter, placed crooked on the scanner.
+fn letter_scan_skewed() -> Fixture {
+    let raster = Raster::text(TextLayout::A4, &LETTER)
+        .skew(3.5)
+        .noise(0x0dd_ba11, 400);
+    scan_fixture(
+        "letter-scan-skewed",
+        FixtureKind::SkewedScan,
+        FixtureFormat::Png,
+        raster.to_png(),
+        &[&LETTER],
+    )
+}
+
+/// A two-page bank statement, scanned page by page into a PDF.
+fn statement_scan() -> Fixture {
+    const PAGES: [&[&str]; 2] = [
+        &[
+            "FIRST HARBOUR BANK",
+            "ACCOUNT STATEMENT - MARCH 2025",
+            "",
+            "ACCOUNT HOLDER: DANIEL O'CONNOR",
+            "ACCOUNT NUMBER: 4417-2290-0031",
+            "SORT CODE: 20-45-77",
+            "",
+            "DATE        DESCRIPTION               AMOUNT",
+            "01/03/2025  OPENING BALANCE          2,184.62",
+            "03/03/2025  SALARY NORTHWIND        +3,250.00",
+            "05/03/2025  RENT - 14 HARBOUR VIEW  -1,150.00",
+            "09/03/2025  CARD 4471 GROCER          -86.40",
+            "12/03/2025  TRANSFER TO J MORRISON   -250.00",
+            "",
+            "CONTINUED OVERLEAF",
+        ],
+        &[
+            "FIRST HARBOUR BANK",
+            "ACCOUNT NUMBER: 4417-2290-0031 - PAGE 2 OF 2",
+            "",
+            "DATE        DESCRIPTION               AMOUNT",
+            "18/03/2025  CARD 4471 PHARMACY        -23.95",
+            "21/03/2025  DIRECT DEBIT CITY POWER   -94.10",
+            "27/03/2025  REFUND CLM-2025-00871   +1,240.50",
+            "31/03/2025  CLOSING BALANCE          5,070.67",
+            "",
+            "QUERIES: +44 20 7946 0100 OR HELP@FIRSTHARBOUR.EXAMPLE",
+        ],
+    ];
+    const SKEW: [f64; 2] = [-1.5, 2.0];
+
+    let pages: Vec<PdfPage> = PAGES
+        .iter()
+        .zip(SKEW)
+        .enumerate()
+        .map(|(index, (lines, skew))| {
+            let raster = Raster::text(TextLayout::A4, lines)
+                .skew(skew)
+                .noise(0xba5e_ba11 + index as u64, 200);
+            PdfPage::Scan(raster)
+        })
+        .collect();
+
+    scan_fixture(
+        "statement-scan",
+        FixtureKind::SkewedScan,
+        FixtureFormat::Pdf,
+        pdf::write(&pages),
+        &PAGES,
+    )
+}
+
+/// A line of a text page.
+struct Line {
+    text: String,
+    size: f32,
+}
+
+impl Line {
+    fn heading(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            size: 14.0,
+        }
+    }
+
+    fn body(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            size: BODY_SIZE,
+        }
+    }
+
+    fn blank() -> Self {
+        Self::body(String::new())
+    }
+}
+
+/// Builds a PDF fixture from pages of lines, each with its language.
+fn text_fixture(
+    name: &str,
+    kind: FixtureKind,
+    pages: Vec<(&str, Vec<Line>)>,
+    tables: Vec<GoldenTable>,
+) -> Fixture {
+    let mut pdf_pages = Vec::with_capacity(pages.len());
+    let mut golden_pages = Vec::with_capacity(pages.len());
+    for (language, lines) in pages {
+        let mut page = TextPage::new();
+        let mut y = TOP;
+        for line in &lines {
+            if !line.text.is_empty() {
+                page = page.text(MARGIN, y, line.size, &line.text);
+            }
+            y -= line.size.max(BODY_SIZE) + LEADING - BODY_SIZE;
+        }
+        pdf_pages.push(PdfPage::Text(page));
+
+        let text: Vec<&str> = lines.iter().map(|line| line.text.as_str()).collect();
+        golden_pages.push(GoldenPage::new(language, text.join("\n").trim_end()));
+    }
+
+    let golden = Golden {
+        pages: golden_pages,
+        tables,
+    };
+    Fixture::new(
+        name,
+        kind,
+        FixtureFormat::Pdf,
+        pdf::write(&pdf_pages),
+        golden,
+    )
+}
+
+/// Builds a scanned fixture whose pages show the given lines.
+fn scan_fixture(
+    name: &str,
+    kind: FixtureKind,
+    format: FixtureFormat,
+    bytes: Vec<u8>,
+    pages: &[&[&str]],
+) -> Fixture {
+    let golden = Golden {
+        pages: pages
+            .iter()
+            .map(|lines| GoldenPage::new("en", lines.join("\n").trim()))
+            .collect(),
+        tables: Vec::new(),
+    };
+    Fixture::new(name, kind, format, bytes, golden)
+}
+
+/// Breaks text into lines of at most `width` characters at spaces.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_breaks_at_spaces() {
+        assert_eq!(wrap("aa bb cc", 5), ["aa bb", "cc"]);
+        assert_eq!(wrap("  ", 5), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,171 @@
+//! Collections of fixtures.
+
+mod documents;
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{fs, io};
+
+use crate::{Fixture, FixtureKind};
+
+/// A named collection of fixtures.
+#[derive(Debug, Clone, Default)]
+pub struct Corpus {
+    fixtures: Vec<Fixture>,
+}
+
+impl Corpus {
+    /// Creates a corpus from fixtures.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two fixtures share a name.
+    pub fn new(fixtures: Vec<Fixture>) -> Self {
+        let mut corpus = Self::default();
+        for fixture in fixtures {
+            corpus.push(fixture);
+        }
+        corpus
+    }
+
+    /// Returns the generated standard corpus.
+    ///
+    /// The corpus covers a multi-page contract, an invoice as a ruled PDF
+    /// table and as CSV, a privacy notice in five Latin-script languages and
+    /// in six scripts, and a letter and a bank statement as straight and
+    /// skewed scans. It is generated on first use and shared afterwards.
+    pub fn standard() -> &'static Self {
+        static STANDARD: OnceLock<Corpus> = OnceLock::new();
+        STANDARD.get_or_init(|| Self::new(documents::all()))
+    }
+
+    /// Adds a fixture, such as a large asset, to the corpus.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the corpus already has a fixture of that name.
+    pub fn push(&mut self, fixture: Fixture) {
+        assert!(
+            self.get(fixture.name()).is_none(),
+            "duplicate fixture `{}`",
+            fixture.name()
+        );
+        self.fixtures.push(fixture);
+    }
+
+    /// Returns the fixture with the given name.
+    pub fn get(&self, name: &str) -> Option<&Fixture> {
+        self.fixtures.iter().find(|fixture| fixture.name() == name)
+    }
+
+    /// Returns the fixtures exercising `kind`.
+    pub fn of_kind(&self, kind: FixtureKind) -> impl Iterator<Item = &Fixture> {
+        self.fixtures
+            .iter()
+            .filter(move |fixture| fixture.kind() == kind)
+    }
+
+    /// Returns an iterator over the fixtures.
+    pub fn iter(&self) -> std::slice::Iter<'_, Fixture> {
+        self.fixtures.iter()
+    }
+
+    /// Returns the number of fixtures.
+    pub fn len(&self) -> usize {
+        self.fixtures.len()
+    }
+
+    /// Returns whether the corpus has no fixtures.
+    pub fn is_empty(&self) -> bool {
+        self.fixtures.is_empty()
+    }
+
+    /// Returns the combined size of the documents in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.fixtures
+            .iter()
+            .map(|fixture| fixture.bytes().len() as u64)
+            .sum()
+    }
+
+    /// Writes every document into `dir`, each next to its golden as
+    /// `<name>.golden.json`, for tools that read fixtures from disk.
+    ///
+    /// Returns the paths of the documents.
+    pub fn write_to(&self, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let mut paths = Vec::with_capacity(self.fixtures.len());
+        for fixture in &self.fixtures {
+            paths.push(fixture.write_to(dir)?);
+            let golden = serde_json::to_vec_pretty(fixture.golden())?;
+            fs::write(dir.join(format!("{}.golden.json", fixture.name())), golden)?;
+        }
+        Ok(paths)
+    }
+}
+
+impl<'a> IntoIterator for &'a Corpus {
+    type IntoIter = std::slice::Iter<'a, Fixture>;
+    type Item = &'a Fixture;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FixtureFormat, character_error_rate};
+
+    #[test]
+    fn standard_corpus_covers_every_kind() {
+        let corpus = Corpus::standard();
+        for kind in [
+            FixtureKind::MultiPage,
+            FixtureKind::Table,
+            FixtureKind::Multilingual,
+            FixtureKind::Scan,
+            FixtureKind::SkewedScan,
+        ] {
+            assert!(corpus.of_kind(kind).next().is_some(), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn documents_match_their_format() {
+        for fixture in Corpus::standard() {
+            let bytes = fixture.bytes();
+            let matches = match fixture.format() {
+                FixtureFormat::Pdf => bytes.starts_with(b"%PDF-") && bytes.ends_with(b"%%EOF\n"),
+                FixtureFormat::Png => bytes.starts_with(b"\x89PNG\r\n\x1a\n"),
+                FixtureFormat::Jpeg => bytes.starts_with(b"\xff\xd8\xff"),
+                FixtureFormat::Txt | FixtureFormat::Csv => std::str::from_utf8(bytes).is_ok(),
+            };
+            assert!(matches, "{}", fixture.name());
+            assert!(fixture.golden().page_count() > 0, "{}", fixture.name());
+        }
+    }
+
+    #[test]
+    fn text_documents_contain_their_golden() {
+        let notice = Corpus::standard().get("notice-scripts").unwrap();
+        let text = std::str::from_utf8(notice.bytes()).unwrap();
+        assert_eq!(text.split('\u{c}').count(), notice.golden().page_count());
+        assert_eq!(character_error_rate(&notice.golden().text(), text), 0.0);
+    }
+
+    #[test]
+    fn corpus_is_written_with_goldens() {
+        let dir = tempfile::tempdir().unwrap();
+        let corpus = Corpus::new(vec![
+            Corpus::standard().get("invoice-lines").unwrap().clone(),
+        ]);
+
+        let paths = corpus.write_to(dir.path()).unwrap();
+        assert_eq!(paths, [dir.path().join("invoice-lines.csv")]);
+        assert!(dir.path().join("invoice-lines.golden.json").exists());
+    }
+}
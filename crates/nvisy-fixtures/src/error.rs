@@ -0,0 +1,38 @@
+//! Error handling for fixture loading.
+
+use thiserror::Error;
+
+/// Type alias for `Result`s with the fixtures [`Error`] type.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors that can occur while loading large assets.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Reading or writing the asset cache failed.
+    #[error("asset cache I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The asset manifest could not be parsed.
+    #[error("invalid asset manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    /// A manifest entry points outside the asset directory.
+    #[error("asset `{name}` has an invalid path")]
+    InvalidPath {
+        /// Name of the asset.
+        name: String,
+    },
+
+    /// An asset's content does not match the checksum in the manifest.
+    #[error("asset `{name}` does not match its checksum")]
+    ChecksumMismatch {
+        /// Name of the asset.
+        name: String,
+    },
+
+    /// Downloading an asset or the manifest failed.
+    #[cfg(feature = "download")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "download")))]
+    #[error("asset download failed: {0}")]
+    Download(#[from] reqwest::Error),
+}
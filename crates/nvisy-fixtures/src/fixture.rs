@@ -0,0 +1,137 @@
+//! Sample documents.
+
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Golden;
+
+/// What a fixture is meant to exercise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureKind {
+    /// A born-digital document spanning several pages.
+    MultiPage,
+    /// A document whose content is laid out in a table.
+    Table,
+    /// A document with pages in different languages and scripts.
+    Multilingual,
+    /// A scanned page, straight as placed on the scanner.
+    Scan,
+    /// A scanned page rotated off the horizontal, with scanner noise.
+    SkewedScan,
+}
+
+impl FixtureKind {
+    /// Returns whether the text has to be recognized from images.
+    pub fn is_scan(self) -> bool {
+        matches!(self, Self::Scan | Self::SkewedScan)
+    }
+}
+
+/// File format of a fixture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixtureFormat {
+    /// PDF document.
+    Pdf,
+    /// PNG image.
+    Png,
+    /// JPEG image.
+    Jpeg,
+    /// UTF-8 plain text; pages are separated by form feeds.
+    Txt,
+    /// Comma-separated values.
+    Csv,
+}
+
+impl FixtureFormat {
+    /// Returns the file extension, without the leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Txt => "txt",
+            Self::Csv => "csv",
+        }
+    }
+
+    /// Returns the MIME type.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Txt => "text/plain",
+            Self::Csv => "text/csv",
+        }
+    }
+}
+
+/// A sample document together with what its extraction should produce.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    name: String,
+    kind: FixtureKind,
+    format: FixtureFormat,
+    bytes: Vec<u8>,
+    golden: Golden,
+}
+
+impl Fixture {
+    /// Creates a fixture from a document's content and its golden.
+    pub fn new(
+        name: impl Into<String>,
+        kind: FixtureKind,
+        format: FixtureFormat,
+        bytes: Vec<u8>,
+        golden: Golden,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            format,
+            bytes,
+            golden,
+        }
+    }
+
+    /// Returns the fixture's name, unique within its corpus.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns what the fixture exercises.
+    pub fn kind(&self) -> FixtureKind {
+        self.kind
+    }
+
+    /// Returns the document's file format.
+    pub fn format(&self) -> FixtureFormat {
+        self.format
+    }
+
+    /// Returns the document's content.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns what an extraction of the document should produce.
+    pub fn golden(&self) -> &Golden {
+        &self.golden
+    }
+
+    /// Returns the document's file name, with its extension.
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.name, self.format.extension())
+    }
+
+    /// Writes the document into `dir`, for code that reads from a path.
+    pub fn write_to(&self, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let path = dir.as_ref().join(self.file_name());
+        fs::write(&path, &self.bytes)?;
+        Ok(path)
+    }
+}
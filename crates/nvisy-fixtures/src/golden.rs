@@ -0,0 +1,151 @@
+//! Expected extraction results and comparison helpers.
+
+use serde::{Deserialize, Serialize};
+
+/// What an extraction of a fixture is expected to produce.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Golden {
+    /// Text of each page, in page order.
+    pub pages: Vec<GoldenPage>,
+    /// Tables found in the document.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<GoldenTable>,
+}
+
+/// Expected text of one page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoldenPage {
+    /// BCP 47 language tag of the page's text.
+    pub language: String,
+    /// The page's text, lines separated by newlines.
+    pub text: String,
+}
+
+/// Expected content of one table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoldenTable {
+    /// One-based number of the page the table is on.
+    pub page: u32,
+    /// Column headers.
+    pub header: Vec<String>,
+    /// Cell values, row by row.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Golden {
+    /// Returns the number of pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns the text of every page, separated by blank lines.
+    pub fn text(&self) -> String {
+        self.pages
+            .iter()
+            .map(|page| page.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Returns the character error rate of `actual` against the whole
+    /// document's text.
+    pub fn character_error_rate(&self, actual: &str) -> f64 {
+        character_error_rate(&self.text(), actual)
+    }
+
+    /// Returns the word error rate of `actual` against the whole document's
+    /// text.
+    pub fn word_error_rate(&self, actual: &str) -> f64 {
+        word_error_rate(&self.text(), actual)
+    }
+}
+
+impl GoldenPage {
+    /// Creates the expected text of a page.
+    pub fn new(language: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            text: text.into(),
+        }
+    }
+}
+
+/// Returns the character error rate of `actual` against `expected`.
+///
+/// This is the edit distance between the two texts divided by the length of
+/// `expected`, after collapsing runs of whitespace: line breaks and spacing
+/// differ between extractors without the text being wrong. It is `0.0` for
+/// a perfect match and may exceed `1.0` when `actual` is much longer.
+pub fn character_error_rate(expected: &str, actual: &str) -> f64 {
+    let expected: Vec<char> = normalize(expected).chars().collect();
+    let actual: Vec<char> = normalize(actual).chars().collect();
+    error_rate(&expected, &actual)
+}
+
+/// Returns the word error rate of `actual` against `expected`.
+///
+/// This is the edit distance between the two texts' words divided by the
+/// number of words in `expected`.
+pub fn word_error_rate(expected: &str, actual: &str) -> f64 {
+    let expected: Vec<&str> = expected.split_whitespace().collect();
+    let actual: Vec<&str> = actual.split_whitespace().collect();
+    error_rate(&expected, &actual)
+}
+
+/// Collapses every run of whitespace into a single space.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn error_rate<T: PartialEq>(expected: &[T], actual: &[T]) -> f64 {
+    if expected.is_empty() {
+        return if actual.is_empty() { 0.0 } else { 1.0 };
+    }
+    edit_distance(expected, actual) as f64 / expected.len() as f64
+}
+
+/// Levenshtein distance, keeping a single row of the table.
+fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, left) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, right) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(left != right);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespace_does_not_count_as_errors() {
+        let expected = "Dear Ms Morrison,\nthank you.";
+        assert_eq!(
+            character_error_rate(expected, "Dear  Ms Morrison, thank you."),
+            0.0
+        );
+    }
+
+    #[test]
+    fn character_errors_are_relative_to_the_expected_text() {
+        // One substitution in ten characters.
+        assert!((character_error_rate("0123456789", "0123456780") - 0.1).abs() < f64::EPSILON);
+        assert_eq!(character_error_rate("", ""), 0.0);
+        assert_eq!(character_error_rate("", "noise"), 1.0);
+    }
+
+    #[test]
+    fn word_errors_count_whole_words() {
+        let rate = word_error_rate("the quick brown fox", "the quick brawn fox jumps");
+        assert!((rate - 0.5).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,16 @@
+#![forbid(unsafe_code)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc = include_str!("../README.md")]
+
+mod asset;
+mod corpus;
+mod error;
+mod fixture;
+mod golden;
+mod render;
+
+pub use asset::{AssetEntry, AssetManifest, AssetStore, DEFAULT_MAX_ASSET_SIZE, MANIFEST_FILE};
+pub use corpus::Corpus;
+pub use error::{Error, Result};
+pub use fixture::{Fixture, FixtureFormat, FixtureKind};
+pub use golden::{Golden, GoldenPage, GoldenTable, character_error_rate, word_error_rate};
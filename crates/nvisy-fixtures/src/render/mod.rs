@@ -0,0 +1,6 @@
+//! Document rendering for generated fixtures.
+
+mod font;
+pub(crate) mod pdf;
+mod png;
+pub(crate) mod raster;
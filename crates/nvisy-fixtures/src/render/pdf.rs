@@ -0,0 +1,212 @@
+//! Minimal PDF writing for text and scanned pages.
+//!
+//! Text pages use the standard Helvetica font with WinAnsi encoding, so
+//! their text is limited to Latin-1. Scanned pages embed an uncompressed
+//! grayscale image and carry no font, as a scanner's output would.
+
+use std::fmt::Write as _;
+
+use super::raster::Raster;
+
+/// Width of an A4 page in points.
+pub(crate) const PAGE_WIDTH: f32 = 595.0;
+
+/// Height of an A4 page in points.
+pub(crate) const PAGE_HEIGHT: f32 = 842.0;
+
+/// Content of one page.
+#[derive(Debug, Clone)]
+pub(crate) enum PdfPage {
+    /// Text and rules drawn with vector operators.
+    Text(TextPage),
+    /// A full-page image.
+    Scan(Raster),
+}
+
+/// A page of positioned text and rules.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TextPage {
+    content: String,
+}
+
+impl TextPage {
+    /// Creates an empty page.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws `text` with its baseline starting at (`x`, `y`), in points
+    /// from the bottom left corner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the text is not Latin-1.
+    pub fn text(mut self, x: f32, y: f32, size: f32, text: &str) -> Self {
+        let _ = writeln!(
+            self.content,
+            "BT /F1 {size} Tf {x} {y} Td ({}) Tj ET",
+            escape(text)
+        );
+        self
+    }
+
+    /// Draws a straight rule between two points.
+    pub fn rule(mut self, from: (f32, f32), to: (f32, f32)) -> Self {
+        let _ = writeln!(
+            self.content,
+            "0.5 w {} {} m {} {} l S",
+            from.0, from.1, to.0, to.1
+        );
+        self
+    }
+}
+
+/// Writes pages as a PDF document.
+pub(crate) fn write(pages: &[PdfPage]) -> Vec<u8> {
+    // Objects 1 and 2 are the catalog and the page tree, 3 the font; each
+    // page then takes its page object, its content stream and its image.
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(Vec::new());
+    let has_text = pages.iter().any(|page| matches!(page, PdfPage::Text(_)));
+    objects.push(if has_text {
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec()
+    } else {
+        b"<< >>".to_vec()
+    });
+
+    let mut kids = Vec::with_capacity(pages.len());
+    for page in pages {
+        let page_id = objects.len() + 1;
+        let content_id = page_id + 1;
+        kids.push(format!("{page_id} 0 R"));
+
+        match page {
+            PdfPage::Text(text) => {
+                objects.push(
+                    format!(
+                        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                         /Resources << /Font << /F1 3 0 R >> >> /Contents {content_id} 0 R >>"
+                    )
+                    .into_bytes(),
+                );
+                objects.push(stream("", text.content.as_bytes()));
+            }
+            PdfPage::Scan(raster) => {
+                let image_id = content_id + 1;
+                objects.push(
+                    format!(
+                        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                         /Resources << /XObject << /Im1 {image_id} 0 R >> >> \
+                         /Contents {content_id} 0 R >>"
+                    )
+                    .into_bytes(),
+                );
+                let draw = format!("q {PAGE_WIDTH} 0 0 {PAGE_HEIGHT} 0 0 cm /Im1 Do Q\n");
+                objects.push(stream("", draw.as_bytes()));
+                let dictionary = format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                     /ColorSpace /DeviceGray /BitsPerComponent 8 ",
+                    raster.width(),
+                    raster.height()
+                );
+                objects.push(stream(&dictionary, raster.pixels()));
+            }
+        }
+    }
+    objects[1] = format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        pages.len()
+    )
+    .into_bytes();
+
+    serialize(&objects)
+}
+
+/// Builds a stream object with extra dictionary entries.
+fn stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {dictionary}/Length {} >>\nstream\n", data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+/// Lays out numbered objects, the cross-reference table and the trailer.
+fn serialize(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(table, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        table,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.extend_from_slice(table.as_bytes());
+    pdf
+}
+
+/// Escapes text for a PDF string literal in WinAnsi encoding.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            // WinAnsi matches Latin-1 from here on.
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(escaped, "\\{:03o}", c as u32);
+            }
+            _ => panic!("{c:?} cannot be written in WinAnsi encoding"),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_escaped_for_winansi() {
+        assert_eq!(escape("a (b) \\ c"), "a \\(b\\) \\\\ c");
+        assert_eq!(escape("Größe"), "Gr\\366\\337e");
+    }
+
+    #[test]
+    fn cross_references_point_at_objects() {
+        let pdf = write(&[PdfPage::Text(TextPage::new().text(72.0, 770.0, 11.0, "Hi"))]);
+        let start = pdf
+            .windows(5)
+            .position(|window| window == b"xref\n")
+            .unwrap();
+        let table = std::str::from_utf8(&pdf[start..]).unwrap();
+        let offsets: Vec<usize> = table
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+
+        assert_eq!(offsets.len(), 5);
+        for (index, offset) in offsets.into_iter().enumerate() {
+            let header = format!("{} 0 obj", index + 1);
+            assert!(pdf[offset..].starts_with(header.as_bytes()));
+        }
+    }
+}
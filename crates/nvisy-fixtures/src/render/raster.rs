@@ -0,0 +1,209 @@
+//! Grayscale page images, as a scanner would produce them.
+
+use super::font::{GLYPH_HEIGHT, GLYPH_WIDTH, glyph};
+use super::png;
+
+/// Gray level of blank paper.
+const PAPER: u8 = 250;
+
+/// Gray level of toner.
+const INK: u8 = 24;
+
+/// An 8-bit grayscale image.
+#[derive(Debug, Clone)]
+pub(crate) struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+/// Layout of text rendered onto a page.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TextLayout {
+    /// Page width in pixels.
+    pub width: usize,
+    /// Page height in pixels.
+    pub height: usize,
+    /// Margin around the text in pixels.
+    pub margin: usize,
+    /// Size of one font pixel in image pixels.
+    pub scale: usize,
+}
+
+impl TextLayout {
+    /// An A4 page at 150 dpi, with text of about 10 points.
+    pub const A4: Self = Self {
+        width: 1240,
+        height: 1754,
+        margin: 120,
+        scale: 3,
+    };
+}
+
+impl Raster {
+    /// Renders lines of text onto a blank page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a character has no glyph or the text overflows the page;
+    /// fixture text is written for the bitmap font and the page.
+    pub fn text(layout: TextLayout, lines: &[&str]) -> Self {
+        let mut raster = Self {
+            width: layout.width,
+            height: layout.height,
+            pixels: vec![PAPER; layout.width * layout.height],
+        };
+
+        let advance = (GLYPH_WIDTH + 1) * layout.scale;
+        let line_height = (GLYPH_HEIGHT + 4) * layout.scale;
+        for (line_index, line) in lines.iter().enumerate() {
+            let top = layout.margin + line_index * line_height;
+            let right = layout.margin + line.chars().count() * advance;
+            assert!(
+                right <= layout.width - layout.margin && top + line_height <= layout.height,
+                "{line:?} does not fit the page"
+            );
+            for (column, c) in line.chars().enumerate() {
+                let rows = glyph(c).unwrap_or_else(|| panic!("no glyph for {c:?}"));
+                let left = layout.margin + column * advance;
+                raster.draw_glyph(left, top, layout.scale, &rows);
+            }
+        }
+
+        raster
+    }
+
+    fn draw_glyph(&mut self, left: usize, top: usize, scale: usize, rows: &[u8; GLYPH_HEIGHT]) {
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        self.set(left + column * scale + dx, top + row * scale + dy, INK);
+                    }
+                }
+            }
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: u8) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = value;
+        }
+    }
+
+    /// Rotates the page by `degrees` (clockwise) around its centre, filling
+    /// the uncovered corners with paper.
+    pub fn skew(&self, degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let cx = self.width as f64 / 2.0;
+        let cy = self.height as f64 / 2.0;
+
+        let mut pixels = vec![PAPER; self.pixels.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let dx = x as f64 + 0.5 - cx;
+                let dy = y as f64 + 0.5 - cy;
+                let sx = cos * dx + sin * dy + cx;
+                let sy = -sin * dx + cos * dy + cy;
+                if sx >= 0.0 && sy >= 0.0 {
+                    let (sx, sy) = (sx as usize, sy as usize);
+                    if sx < self.width && sy < self.height {
+                        pixels[y * self.width + x] = self.pixels[sy * self.width + sx];
+                    }
+                }
+            }
+        }
+
+        Self {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+
+    /// Adds scanner noise: a slight gradient across the page and speckles of
+    /// dust, about `per_million` for every million pixels.
+    pub fn noise(mut self, seed: u64, per_million: u64) -> Self {
+        let mut rng = XorShift(seed | 1);
+        for y in 0..self.height {
+            // The lamp falls off towards the bottom of the glass.
+            let shade = (y * 12 / self.height) as u8;
+            for x in 0..self.width {
+                let pixel = &mut self.pixels[y * self.width + x];
+                *pixel = pixel.saturating_sub(shade);
+                if rng.next() % 1_000_000 < per_million {
+                    *pixel = if *pixel > 128 { INK + 40 } else { PAPER };
+                }
+            }
+        }
+        self
+    }
+
+    /// Returns the image width in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the image height in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixels, row by row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Encodes the image as a PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode_gray(self.width, self.height, &self.pixels)
+    }
+}
+
+/// Deterministic pseudo-random numbers, so fixtures are reproducible.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: TextLayout = TextLayout {
+        width: 80,
+        height: 40,
+        margin: 4,
+        scale: 1,
+    };
+
+    fn ink(raster: &Raster) -> usize {
+        raster.pixels().iter().filter(|&&pixel| pixel < 128).count()
+    }
+
+    #[test]
+    fn text_is_drawn_in_ink() {
+        let blank = Raster::text(SMALL, &[" "]);
+        let text = Raster::text(SMALL, &["HELLO"]);
+        assert_eq!(ink(&blank), 0);
+        assert!(ink(&text) > 0);
+    }
+
+    #[test]
+    fn skew_keeps_the_text_on_the_page() {
+        let text = Raster::text(SMALL, &["HELLO"]);
+        let skewed = text.skew(3.0);
+        assert_eq!(text.skew(0.0).pixels(), text.pixels());
+        assert_ne!(skewed.pixels(), text.pixels());
+        assert!(ink(&skewed).abs_diff(ink(&text)) < ink(&text) / 4);
+    }
+}
@@ -37,16 +37,24 @@ use async_nats::{Client, ConnectOptions, jetstream};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tokio::time::timeout;
+use uuid::Uuid;
 
 use super::nats_config::NatsConfig;
 use crate::kv::{
-    ApiToken, ApiTokensBucket, ChatHistoryBucket, KvBucket, KvKey, KvStore, SessionKey, TokenKey,
+    ApiKey, ApiKeysBucket, ApiToken, ApiTokensBucket, ChatHistoryBucket, DigestKey, DocumentKey,
+    DocumentPassword, DocumentPasswordsBucket, InboxKey, KvBucket, KvKey, KvStore, OidcLogin,
+    OidcLoginsBucket, PrivacyBudget, PrivacyBudgetsBucket, ProcessedMessage,
+    ProcessedMessagesBucket, SessionKey, TokenKey, WorkspaceKey,
 };
 use crate::object::{
-    AccountKey, AvatarsBucket, ContextFilesBucket, ContextKey, FileKey, FilesBucket,
-    IntermediatesBucket, ObjectBucket, ObjectKey, ObjectStore, ThumbnailsBucket,
+    AccountKey, AnalysesBucket, AvatarsBucket, BucketLifecycle, ContextFilesBucket, ContextKey,
+    FileKey, FilesBucket, IntermediateKey, IntermediatesBucket, ObjectBucket, ObjectKey,
+    ObjectStore, OperationResultsBucket, ResultKey, ThumbnailsBucket,
+};
+use crate::stream::{
+    ConsumerMigrator, EventPublisher, EventStream, EventSubscriber, Inbox, KvInboxStore,
+    RunProgress, RunProgressStream, WebhookStream, WorkspaceEvent, WorkspaceEventStream,
 };
-use crate::stream::{EventPublisher, EventStream, EventSubscriber, WebhookStream};
 use crate::{Error, Result, TRACING_TARGET_CLIENT, TRACING_TARGET_CONNECTION};
 
 /// NATS client wrapper with connection management.
@@ -163,6 +171,76 @@ impl NatsClient {
     pub fn is_connected(&self) -> bool {
         matches!(self.inner.client.connection_state(), State::Connected)
     }
+
+    /// Verify that JetStream is enabled for this account.
+    ///
+    /// KV buckets, object stores and event streams all require JetStream, so a
+    /// server without it fails on first use rather than at connect time.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CONNECTION)]
+    pub async fn check_jetstream(&self) -> Result<()> {
+        let account = self
+            .inner
+            .jetstream
+            .query_account()
+            .await
+            .map_err(|e| Error::operation("jetstream_account", e.to_string()))?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_CONNECTION,
+            streams = account.streams,
+            storage_bytes = account.storage,
+            "JetStream account available"
+        );
+        Ok(())
+    }
+
+    /// Verify that objects can be written to and deleted from object storage.
+    ///
+    /// Writes a small probe object to the intermediates bucket, which only
+    /// holds temporary artifacts, and removes it again.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CONNECTION)]
+    pub async fn check_object_store_write(&self) -> Result<()> {
+        let store = self.intermediates_store().await?;
+        let key = FileKey::generate(Uuid::nil());
+
+        store.put(&key, &b"nvisy preflight probe"[..]).await?;
+        store.delete(&key).await
+    }
+
+    /// Applies every object bucket's declared TTL to the server.
+    ///
+    /// Buckets only receive their TTL when they are created, so this is run
+    /// at startup to make objects in an existing bucket, such as pipeline
+    /// intermediates, expire as [`ObjectBucket::MAX_AGE`] says they should.
+    /// Returns each bucket's lifecycle as the server now enforces it.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn sync_object_bucket_ttls(&self) -> Result<Vec<BucketLifecycle>> {
+        let jetstream = &self.inner.jetstream;
+        Ok(vec![
+            ObjectStore::<FilesBucket, FileKey>::sync_max_age(jetstream).await?,
+            ObjectStore::<IntermediatesBucket, FileKey>::sync_max_age(jetstream).await?,
+            ObjectStore::<AnalysesBucket, IntermediateKey>::sync_max_age(jetstream).await?,
+            ObjectStore::<ThumbnailsBucket, FileKey>::sync_max_age(jetstream).await?,
+            ObjectStore::<AvatarsBucket, AccountKey>::sync_max_age(jetstream).await?,
+            ObjectStore::<ContextFilesBucket, ContextKey>::sync_max_age(jetstream).await?,
+            ObjectStore::<OperationResultsBucket, ResultKey>::sync_max_age(jetstream).await?,
+        ])
+    }
+
+    /// Reads every object bucket's lifecycle as the server enforces it.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn object_bucket_lifecycles(&self) -> Result<Vec<BucketLifecycle>> {
+        let jetstream = &self.inner.jetstream;
+        Ok(vec![
+            ObjectStore::<FilesBucket, FileKey>::lifecycle(jetstream).await?,
+            ObjectStore::<IntermediatesBucket, FileKey>::lifecycle(jetstream).await?,
+            ObjectStore::<AnalysesBucket, IntermediateKey>::lifecycle(jetstream).await?,
+            ObjectStore::<ThumbnailsBucket, FileKey>::lifecycle(jetstream).await?,
+            ObjectStore::<AvatarsBucket, AccountKey>::lifecycle(jetstream).await?,
+            ObjectStore::<ContextFilesBucket, ContextKey>::lifecycle(jetstream).await?,
+            ObjectStore::<OperationResultsBucket, ResultKey>::lifecycle(jetstream).await?,
+        ])
+    }
 }
 
 // Key-value store getters
@@ -198,6 +276,51 @@ impl NatsClient {
         self.kv_store_with_ttl(ttl).await
     }
 
+    /// Get or create the account API key lookup cache.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn api_key_store(&self) -> Result<KvStore<DigestKey, ApiKey, ApiKeysBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get or create the pending OIDC login store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn oidc_login_store(
+        &self,
+    ) -> Result<KvStore<DigestKey, OidcLogin, OidcLoginsBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get or create the memory-only store of transient document passwords.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn document_password_store(
+        &self,
+    ) -> Result<KvStore<DocumentKey, DocumentPassword, DocumentPasswordsBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get or create the per-workspace privacy budget store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn privacy_budget_store(
+        &self,
+    ) -> Result<KvStore<WorkspaceKey, PrivacyBudget, PrivacyBudgetsBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get or create the store of processed message ids.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn processed_message_store(
+        &self,
+    ) -> Result<KvStore<InboxKey, ProcessedMessage, ProcessedMessagesBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get the inbox deduplicating the messages processed by `consumer`.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn message_inbox(&self, consumer: &str) -> Result<Inbox> {
+        let store = self.processed_message_store().await?;
+        Ok(Inbox::new(consumer, KvInboxStore::new(store)))
+    }
+
     /// Get or create a chat history store with default TTL.
     #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
     pub async fn chat_history_store<V>(&self) -> Result<KvStore<SessionKey, V, ChatHistoryBucket>>
@@ -229,7 +352,8 @@ impl NatsClient {
         B: ObjectBucket,
         K: ObjectKey,
     {
-        ObjectStore::new(&self.inner.jetstream).await
+        let crypto = self.inner.config.crypto_provider.clone();
+        ObjectStore::new(&self.inner.jetstream, crypto).await
     }
 
     /// Get or create a file store for primary file storage.
@@ -261,6 +385,14 @@ impl NatsClient {
     pub async fn context_file_store(&self) -> Result<ObjectStore<ContextFilesBucket, ContextKey>> {
         self.object_store().await
     }
+
+    /// Get or create a store for encrypted operation result artifacts.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn operation_result_store(
+        &self,
+    ) -> Result<ObjectStore<OperationResultsBucket, ResultKey>> {
+        self.object_store().await
+    }
 }
 
 // Stream getters
@@ -285,6 +417,11 @@ impl NatsClient {
         EventSubscriber::new(&self.inner.jetstream).await
     }
 
+    /// Create a migrator for moving durable consumers between configurations.
+    pub fn consumer_migrator(&self) -> ConsumerMigrator {
+        ConsumerMigrator::new(self.inner.jetstream.clone())
+    }
+
     /// Create a webhook publisher.
     #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
     pub async fn webhook_publisher<T>(&self) -> Result<EventPublisher<T, WebhookStream>>
@@ -302,4 +439,36 @@ impl NatsClient {
     {
         self.event_subscriber().await
     }
+
+    /// Create a run progress publisher.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn run_progress_publisher(
+        &self,
+    ) -> Result<EventPublisher<RunProgress, RunProgressStream>> {
+        self.event_publisher().await
+    }
+
+    /// Create a run progress subscriber.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn run_progress_subscriber(
+        &self,
+    ) -> Result<EventSubscriber<RunProgress, RunProgressStream>> {
+        self.event_subscriber().await
+    }
+
+    /// Create a workspace event publisher.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn workspace_event_publisher(
+        &self,
+    ) -> Result<EventPublisher<WorkspaceEvent, WorkspaceEventStream>> {
+        self.event_publisher().await
+    }
+
+    /// Create a workspace event subscriber.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn workspace_event_subscriber(
+        &self,
+    ) -> Result<EventSubscriber<WorkspaceEvent, WorkspaceEventStream>> {
+        self.event_subscriber().await
+    }
 }
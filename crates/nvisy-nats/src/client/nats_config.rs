@@ -1,7 +1,10 @@
 //! NATS connection configuration.
 
+use std::sync::Arc;
 use std::time::Duration;
 
+use nvisy_core::crypto::{CryptoProvider, RustCryptoProvider};
+
 /// Configuration for NATS connections with sensible defaults.
 #[derive(Debug, Clone)]
 pub struct NatsConfig {
@@ -22,6 +25,9 @@ pub struct NatsConfig {
 
     /// Maximum number of reconnection attempts (0 = unlimited)
     pub nats_max_reconnects: Option<usize>,
+
+    /// Crypto provider hashing downloaded objects to verify their digest.
+    pub crypto_provider: Arc<dyn CryptoProvider>,
 }
 
 // Default values
@@ -40,6 +46,7 @@ impl NatsConfig {
             nats_connect_timeout: None,
             nats_request_timeout: None,
             nats_max_reconnects: None,
+            crypto_provider: Arc::new(RustCryptoProvider),
         }
     }
 
@@ -115,6 +122,13 @@ impl NatsConfig {
         self
     }
 
+    /// Set the crypto provider used to verify object digests.
+    #[must_use]
+    pub fn with_crypto_provider(mut self, provider: Arc<dyn CryptoProvider>) -> Self {
+        self.crypto_provider = provider;
+        self
+    }
+
     /// Validate the configuration and return any issues.
     pub fn validate(&self) -> Result<(), String> {
         let servers = self.servers();
@@ -2,6 +2,8 @@
 
 use std::time::Duration;
 
+use nvisy_core::cache::CacheError;
+
 /// Result type for all NATS operations in this crate.
 ///
 /// This is a convenience type alias that defaults to using [`Error`] as the error type.
@@ -201,3 +203,14 @@ impl Error {
         }
     }
 }
+
+impl From<Error> for CacheError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::KvRevisionMismatch { key, expected, .. } => {
+                CacheError::revision_mismatch(key, expected)
+            }
+            error => CacheError::backend("NATS", error),
+        }
+    }
+}
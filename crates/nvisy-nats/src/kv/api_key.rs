@@ -0,0 +1,50 @@
+//! Cached account API key type.
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Account API key as resolved on the authentication path.
+///
+/// Entries are keyed by the key's secret digest and only ever hold what is
+/// needed to authorize a request; the database remains the source of truth
+/// and entries are deleted when a key is revoked or rotated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Identifier of the key row.
+    pub key_id: Uuid,
+    /// Reference to the account this key belongs to.
+    pub account_id: Uuid,
+    /// Scopes granted to the key, in their wire form.
+    pub scopes: Vec<String>,
+    /// Timestamp when the key stops authenticating.
+    pub expired_at: Option<Timestamp>,
+}
+
+impl ApiKey {
+    /// Returns whether the key has expired.
+    /// Returns false if the key never expires.
+    pub fn is_expired(&self) -> bool {
+        self.expired_at
+            .is_some_and(|expired_at| Timestamp::now() > expired_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_expiry() {
+        let mut key = ApiKey {
+            key_id: Uuid::nil(),
+            account_id: Uuid::nil(),
+            scopes: vec!["read".to_owned()],
+            expired_at: None,
+        };
+        assert!(!key.is_expired());
+
+        key.expired_at = Some(Timestamp::UNIX_EPOCH);
+        assert!(key.is_expired());
+    }
+}
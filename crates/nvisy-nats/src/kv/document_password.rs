@@ -0,0 +1,17 @@
+//! Transient document password type.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Password of a protected document, supplied without storing it on the file.
+///
+/// Entries are keyed by file id and held only until the bucket's TTL
+/// expires them. The password stays encrypted under the workspace key while
+/// it is held.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentPassword {
+    /// Workspace the document belongs to.
+    pub workspace_id: Uuid,
+    /// Password, encrypted under the workspace key.
+    pub encrypted_password: Vec<u8>,
+}
@@ -16,6 +16,25 @@ pub trait KvBucket: Clone + Send + Sync + 'static {
     /// Default TTL for entries in this bucket.
     /// Returns `None` for buckets where entries should not expire.
     const TTL: Option<Duration>;
+
+    /// Whether the bucket is kept in server memory only.
+    ///
+    /// Entries of a memory-only bucket are never written to disk, and are
+    /// lost when the NATS servers restart.
+    const MEMORY_ONLY: bool = false;
+}
+
+/// Bucket caching account API keys by secret digest.
+///
+/// Kept short-lived so a revocation missed by cache invalidation still takes
+/// effect within minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ApiKeysBucket;
+
+impl KvBucket for ApiKeysBucket {
+    const DESCRIPTION: &'static str = "Account API key lookups";
+    const NAME: &'static str = "api_keys";
+    const TTL: Option<Duration> = Some(Duration::from_secs(5 * 60)); // 5 minutes
 }
 
 /// Bucket for API authentication tokens.
@@ -38,10 +57,66 @@ impl KvBucket for ChatHistoryBucket {
     const TTL: Option<Duration> = Some(Duration::from_secs(30 * 60)); // 30 minutes
 }
 
+/// Bucket for document passwords supplied without storing them on the file.
+///
+/// Kept in memory only, so a password never reaches disk; the TTL bounds how
+/// long a supplied password keeps working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DocumentPasswordsBucket;
+
+impl KvBucket for DocumentPasswordsBucket {
+    const DESCRIPTION: &'static str = "Transient document passwords";
+    const MEMORY_ONLY: bool = true;
+    const NAME: &'static str = "document_passwords";
+    const TTL: Option<Duration> = Some(Duration::from_secs(60 * 60)); // 1 hour
+}
+
+/// Bucket for single sign-on logins awaiting their provider callback.
+///
+/// The TTL bounds how long a user may spend at the identity provider before
+/// the login has to be restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OidcLoginsBucket;
+
+impl KvBucket for OidcLoginsBucket {
+    const DESCRIPTION: &'static str = "Pending OIDC logins";
+    const NAME: &'static str = "oidc_logins";
+    const TTL: Option<Duration> = Some(Duration::from_secs(10 * 60)); // 10 minutes
+}
+
+/// Bucket for per-workspace differential privacy budgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PrivacyBudgetsBucket;
+
+impl KvBucket for PrivacyBudgetsBucket {
+    const DESCRIPTION: &'static str = "Differential privacy budgets";
+    const NAME: &'static str = "privacy_budgets";
+    const TTL: Option<Duration> = None;
+}
+
+/// Bucket recording the messages each consumer has processed.
+///
+/// Outlives the longest stream retention, so any redelivery of a processed
+/// message still finds its entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ProcessedMessagesBucket;
+
+impl KvBucket for ProcessedMessagesBucket {
+    const DESCRIPTION: &'static str = "Processed message ids per consumer";
+    const NAME: &'static str = "processed_messages";
+    const TTL: Option<Duration> = Some(Duration::from_secs(7 * 24 * 60 * 60)); // 7 days
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_api_keys_bucket() {
+        assert_eq!(ApiKeysBucket::NAME, "api_keys");
+        assert_eq!(ApiKeysBucket::TTL, Some(Duration::from_secs(5 * 60)));
+    }
+
     #[test]
     fn test_api_tokens_bucket() {
         assert_eq!(ApiTokensBucket::NAME, "api_tokens");
@@ -56,4 +131,36 @@ mod tests {
         assert_eq!(ChatHistoryBucket::NAME, "chat_history");
         assert_eq!(ChatHistoryBucket::TTL, Some(Duration::from_secs(30 * 60)));
     }
+
+    #[test]
+    fn test_document_passwords_bucket() {
+        assert_eq!(DocumentPasswordsBucket::NAME, "document_passwords");
+        assert_eq!(
+            DocumentPasswordsBucket::TTL,
+            Some(Duration::from_secs(60 * 60))
+        );
+        assert!(DocumentPasswordsBucket::MEMORY_ONLY);
+        assert!(!ApiKeysBucket::MEMORY_ONLY);
+    }
+
+    #[test]
+    fn test_oidc_logins_bucket() {
+        assert_eq!(OidcLoginsBucket::NAME, "oidc_logins");
+        assert_eq!(OidcLoginsBucket::TTL, Some(Duration::from_secs(10 * 60)));
+    }
+
+    #[test]
+    fn test_privacy_budgets_bucket() {
+        assert_eq!(PrivacyBudgetsBucket::NAME, "privacy_budgets");
+        assert_eq!(PrivacyBudgetsBucket::TTL, None);
+    }
+
+    #[test]
+    fn test_processed_messages_bucket() {
+        assert_eq!(ProcessedMessagesBucket::NAME, "processed_messages");
+        assert_eq!(
+            ProcessedMessagesBucket::TTL,
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+    }
 }
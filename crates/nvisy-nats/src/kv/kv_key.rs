@@ -3,6 +3,7 @@
 use std::fmt;
 use std::str::FromStr;
 
+use base64::prelude::*;
 use uuid::Uuid;
 
 use crate::Error;
@@ -68,6 +69,165 @@ impl From<Uuid> for TokenKey {
     }
 }
 
+/// Key for entries addressed by a hex-encoded secret digest.
+///
+/// Used for API keys, which are looked up by the hash of the presented
+/// secret rather than by id, and for pending OIDC logins, keyed by their
+/// random `state` parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DigestKey(String);
+
+impl DigestKey {
+    /// Hex-encodes a digest into a key.
+    pub fn from_digest(digest: &[u8]) -> Self {
+        use fmt::Write;
+
+        let mut hex = String::with_capacity(digest.len() * 2);
+        for byte in digest {
+            let _ = write!(hex, "{byte:02x}");
+        }
+        Self(hex)
+    }
+}
+
+impl KvKey for DigestKey {}
+
+impl fmt::Display for DigestKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for DigestKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::operation(
+                "parse_digest_key",
+                "digest key must be non-empty hex",
+            ));
+        }
+        Ok(Self(s.to_ascii_lowercase()))
+    }
+}
+
+/// Key for per-document entries, by file id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DocumentKey(pub Uuid);
+
+impl KvKey for DocumentKey {}
+
+impl fmt::Display for DocumentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for DocumentKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = Uuid::parse_str(s)
+            .map_err(|e| Error::operation("parse_document_key", e.to_string()))?;
+        Ok(Self(id))
+    }
+}
+
+impl From<Uuid> for DocumentKey {
+    fn from(id: Uuid) -> Self {
+        Self(id)
+    }
+}
+
+/// Key for per-workspace entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WorkspaceKey(pub Uuid);
+
+impl KvKey for WorkspaceKey {}
+
+impl fmt::Display for WorkspaceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for WorkspaceKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = Uuid::parse_str(s)
+            .map_err(|e| Error::operation("parse_workspace_key", e.to_string()))?;
+        Ok(Self(id))
+    }
+}
+
+impl From<Uuid> for WorkspaceKey {
+    fn from(id: Uuid) -> Self {
+        Self(id)
+    }
+}
+
+/// Key for a message processed by a consumer.
+///
+/// Message ids are chosen by publishers and may hold characters NATS KV keys
+/// do not allow, so they are stored base64url-encoded after the consumer
+/// name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InboxKey {
+    consumer: String,
+    message_id: String,
+}
+
+impl InboxKey {
+    /// Creates the key for `message_id` as processed by `consumer`.
+    pub fn new(consumer: impl Into<String>, message_id: impl Into<String>) -> Self {
+        Self {
+            consumer: consumer.into(),
+            message_id: message_id.into(),
+        }
+    }
+
+    /// Returns the consumer name.
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
+    /// Returns the message id.
+    pub fn message_id(&self) -> &str {
+        &self.message_id
+    }
+}
+
+impl KvKey for InboxKey {}
+
+impl fmt::Display for InboxKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}",
+            self.consumer,
+            BASE64_URL_SAFE_NO_PAD.encode(&self.message_id)
+        )
+    }
+}
+
+impl FromStr for InboxKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (consumer, encoded) = s
+            .rsplit_once('.')
+            .ok_or_else(|| Error::operation("parse_inbox_key", "missing message id"))?;
+        let message_id = BASE64_URL_SAFE_NO_PAD
+            .decode(encoded)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| Error::operation("parse_inbox_key", "invalid message id"))?;
+        Ok(Self::new(consumer, message_id))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,4 +249,33 @@ mod tests {
         let parsed: TokenKey = s.parse().unwrap();
         assert_eq!(key, parsed);
     }
+
+    #[test]
+    fn test_digest_key_roundtrip() {
+        let key = DigestKey::from_digest(&[0x00, 0xab, 0x7f]);
+        assert_eq!(key.to_string(), "00ab7f");
+        let parsed: DigestKey = key.to_string().parse().unwrap();
+        assert_eq!(key, parsed);
+        assert!("not-hex".parse::<DigestKey>().is_err());
+    }
+
+    #[test]
+    fn test_workspace_key_roundtrip() {
+        let id = Uuid::nil();
+        let key = WorkspaceKey(id);
+        let s = key.to_string();
+        let parsed: WorkspaceKey = s.parse().unwrap();
+        assert_eq!(key, parsed);
+    }
+
+    #[test]
+    fn test_inbox_key_roundtrip() {
+        let key = InboxKey::new("webhook-worker", "change-42 / retry*");
+        let s = key.to_string();
+        assert!(s.starts_with("webhook-worker."));
+        assert!(!s.contains(' ') && !s.contains('*'));
+        let parsed: InboxKey = s.parse().unwrap();
+        assert_eq!(key, parsed);
+        assert!("no-separator".parse::<InboxKey>().is_err());
+    }
 }
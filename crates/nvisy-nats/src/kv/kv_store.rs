@@ -6,6 +6,7 @@ use std::time::{Duration, SystemTime};
 
 use async_nats::jetstream::{self, kv};
 use futures::StreamExt;
+use nvisy_core::cache::{CacheEntry, CacheResult, KeyValueStore};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +27,7 @@ where
     B: KvBucket,
 {
     store: kv::Store,
+    ttl: Duration,
     _key: PhantomData<K>,
     _value: PhantomData<V>,
     _bucket: PhantomData<B>,
@@ -46,10 +48,16 @@ where
     /// Create or get a KV bucket with custom TTL.
     #[tracing::instrument(skip(jetstream), target = TRACING_TARGET_KV)]
     pub(crate) async fn with_ttl(jetstream: &jetstream::Context, ttl: Duration) -> Result<Self> {
+        let storage = if B::MEMORY_ONLY {
+            jetstream::stream::StorageType::Memory
+        } else {
+            jetstream::stream::StorageType::File
+        };
         let config = kv::Config {
             bucket: B::NAME.to_string(),
             description: B::DESCRIPTION.to_string(),
             max_age: ttl,
+            storage,
             ..Default::default()
         };
 
@@ -78,6 +86,7 @@ where
 
         Ok(Self {
             store,
+            ttl,
             _key: PhantomData,
             _value: PhantomData,
             _bucket: PhantomData,
@@ -175,6 +184,22 @@ where
         Ok(())
     }
 
+    /// Returns how long a key has left before it expires.
+    ///
+    /// Entries expire a fixed TTL after their last write. Returns `None` for
+    /// missing keys and buckets without a TTL.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn ttl(&self, key: &K) -> Result<Option<Duration>> {
+        if self.ttl.is_zero() {
+            return Ok(None);
+        }
+
+        Ok(self.get(key).await?.map(|kv_value| {
+            let age = kv_value.created.elapsed().unwrap_or_default();
+            self.ttl.saturating_sub(age)
+        }))
+    }
+
     /// Check if a key exists in the store.
     #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
     pub async fn exists(&self, key: &K) -> Result<bool> {
@@ -256,11 +281,14 @@ where
         let key_str = key.to_string();
         let json = serde_json::to_vec(value)?;
         let size = json.len();
-        let new_revision = self
-            .store
-            .update(&key_str, json.into(), revision)
-            .await
-            .map_err(|e| Error::operation("kv_update", e.to_string()))?;
+        let new_revision = match self.store.update(&key_str, json.into(), revision).await {
+            Ok(new_revision) => new_revision,
+            Err(e) if e.kind() == kv::UpdateErrorKind::WrongLastRevision => {
+                let actual = self.get(key).await?.map_or(0, |kv_value| kv_value.revision);
+                return Err(Error::kv_revision_mismatch(key_str, revision, actual));
+            }
+            Err(e) => return Err(Error::operation("kv_update", e.to_string())),
+        };
 
         tracing::debug!(
             target: TRACING_TARGET_KV,
@@ -301,6 +329,38 @@ where
     }
 }
 
+#[async_trait::async_trait]
+impl<K, V, B> KeyValueStore<K, V> for KvStore<K, V, B>
+where
+    K: KvKey,
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+    B: KvBucket,
+{
+    async fn get(&self, key: &K) -> CacheResult<Option<CacheEntry<V>>> {
+        let kv_value = KvStore::get(self, key).await?;
+        Ok(kv_value.map(|kv_value| CacheEntry {
+            value: kv_value.value,
+            revision: kv_value.revision,
+        }))
+    }
+
+    async fn put(&self, key: &K, value: &V) -> CacheResult<u64> {
+        Ok(KvStore::put(self, key, value).await?.revision)
+    }
+
+    async fn delete(&self, key: &K) -> CacheResult<()> {
+        Ok(KvStore::delete(self, key).await?)
+    }
+
+    async fn ttl(&self, key: &K) -> CacheResult<Option<Duration>> {
+        Ok(KvStore::ttl(self, key).await?)
+    }
+
+    async fn compare_and_swap(&self, key: &K, value: &V, revision: u64) -> CacheResult<u64> {
+        Ok(KvStore::update(self, key, value, revision).await?.revision)
+    }
+}
+
 /// KV entry metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KvEntry {
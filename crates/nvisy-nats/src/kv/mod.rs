@@ -20,12 +20,25 @@
 //! let session = store.get_value(&key).await?;
 //! ```
 
+mod api_key;
 mod api_token;
+mod document_password;
 mod kv_bucket;
 mod kv_key;
 mod kv_store;
+mod oidc_login;
+mod privacy_budget;
+mod processed_message;
 
+pub use api_key::ApiKey;
 pub use api_token::{ApiToken, ApiTokenType};
-pub use kv_bucket::{ApiTokensBucket, ChatHistoryBucket, KvBucket};
-pub use kv_key::{KvKey, SessionKey, TokenKey};
+pub use document_password::DocumentPassword;
+pub use kv_bucket::{
+    ApiKeysBucket, ApiTokensBucket, ChatHistoryBucket, DocumentPasswordsBucket, KvBucket,
+    OidcLoginsBucket, PrivacyBudgetsBucket, ProcessedMessagesBucket,
+};
+pub use kv_key::{DigestKey, DocumentKey, InboxKey, KvKey, SessionKey, TokenKey, WorkspaceKey};
 pub use kv_store::{KvEntry, KvStore, KvValue};
+pub use oidc_login::OidcLogin;
+pub use privacy_budget::PrivacyBudget;
+pub use processed_message::ProcessedMessage;
@@ -0,0 +1,23 @@
+//! Pending single sign-on login type.
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Authorization request started by an OIDC login, awaiting its callback.
+///
+/// Entries are keyed by the `state` parameter sent to the provider and are
+/// consumed by the callback, so each authorization response can be redeemed
+/// once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OidcLogin {
+    /// Nonce the ID token must echo back.
+    pub nonce: String,
+    /// PKCE code verifier for the token exchange.
+    pub code_verifier: String,
+    /// Client-supplied location to return to after signing in.
+    pub redirect_to: Option<String>,
+    /// Whether the resulting session should be long-lived.
+    pub remember_me: bool,
+    /// Timestamp when the login was started.
+    pub started_at: Timestamp,
+}
@@ -0,0 +1,105 @@
+//! Differential privacy budget ledger.
+
+use std::time::Duration;
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Privacy loss spent by a workspace within the current budget window.
+///
+/// Every noised aggregate consumes part of the workspace's epsilon budget.
+/// Once the window elapses the ledger starts over; the lifetime total is kept
+/// for auditing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivacyBudget {
+    /// Start of the current budget window.
+    pub window_start: Timestamp,
+    /// Epsilon spent within the current window.
+    pub epsilon_spent: f64,
+    /// Number of noised queries answered within the current window.
+    pub queries: u64,
+    /// Epsilon spent across all windows.
+    pub lifetime_epsilon: f64,
+}
+
+impl PrivacyBudget {
+    /// Creates an empty ledger whose window starts at `now`.
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            window_start: now,
+            epsilon_spent: 0.0,
+            queries: 0,
+            lifetime_epsilon: 0.0,
+        }
+    }
+
+    /// Returns when the current window ends.
+    pub fn resets_at(&self, window: Duration) -> Timestamp {
+        self.window_start
+            .checked_add(window)
+            .unwrap_or(Timestamp::MAX)
+    }
+
+    /// Starts a new window if the current one has elapsed at `now`.
+    #[must_use]
+    pub fn roll(mut self, window: Duration, now: Timestamp) -> Self {
+        if now >= self.resets_at(window) {
+            self.window_start = now;
+            self.epsilon_spent = 0.0;
+            self.queries = 0;
+        }
+        self
+    }
+
+    /// Returns the epsilon left in the current window under `limit`.
+    pub fn remaining(&self, limit: f64) -> f64 {
+        (limit - self.epsilon_spent).max(0.0)
+    }
+
+    /// Returns whether `epsilon` can be spent without exceeding `limit`.
+    pub fn can_spend(&self, epsilon: f64, limit: f64) -> bool {
+        self.epsilon_spent + epsilon <= limit
+    }
+
+    /// Records one query that consumed `epsilon`.
+    pub fn spend(&mut self, epsilon: f64) {
+        self.epsilon_spent += epsilon;
+        self.lifetime_epsilon += epsilon;
+        self.queries += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn test_spend_within_window() {
+        let mut budget = PrivacyBudget::new(Timestamp::UNIX_EPOCH);
+        assert!(budget.can_spend(0.5, 1.0));
+
+        budget.spend(0.5);
+        budget.spend(0.5);
+        assert!(!budget.can_spend(0.1, 1.0));
+        assert_eq!(budget.remaining(1.0), 0.0);
+        assert_eq!(budget.queries, 2);
+    }
+
+    #[test]
+    fn test_roll_resets_window() {
+        let mut budget = PrivacyBudget::new(Timestamp::UNIX_EPOCH);
+        budget.spend(1.0);
+
+        let same = budget.clone().roll(DAY, Timestamp::UNIX_EPOCH);
+        assert_eq!(same.epsilon_spent, 1.0);
+
+        let later = budget.resets_at(DAY);
+        let rolled = budget.roll(DAY, later);
+        assert_eq!(rolled.window_start, later);
+        assert_eq!(rolled.epsilon_spent, 0.0);
+        assert_eq!(rolled.queries, 0);
+        assert_eq!(rolled.lifetime_epsilon, 1.0);
+    }
+}
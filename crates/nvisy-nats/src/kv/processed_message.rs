@@ -0,0 +1,23 @@
+//! Processed message marker type.
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Record that a consumer finished processing a message.
+///
+/// Entries are keyed by consumer and message id; only their presence
+/// matters, so redeliveries arriving before they expire are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessedMessage {
+    /// Timestamp when the handler completed.
+    pub processed_at: Timestamp,
+}
+
+impl ProcessedMessage {
+    /// Creates a marker for a message processed now.
+    pub fn now() -> Self {
+        Self {
+            processed_at: Timestamp::now(),
+        }
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! This module provides object storage capabilities using NATS JetStream
 //! as the underlying storage mechanism, with streaming upload support and
-//! on-the-fly SHA-256 hash computation.
+//! on-the-fly SHA-256 hash computation. Downloads are verified against that
+//! hash as they stream.
 //!
 //! # Architecture
 //!
@@ -12,26 +13,42 @@
 //! ## Key Types
 //! - [`FileKey`] - Unique key for files (workspace + object ID)
 //! - [`AccountKey`] - Key for account-scoped objects (account ID)
+//! - [`ResultKey`] - Key for operation result artifacts (workspace + operation ID)
+//! - [`KeyVersion`] - Layout of a stored key; legacy file keys still parse
 //!
 //! ## Bucket Types
-//! - [`FilesBucket`] - Primary file storage (no expiration)
+//! - [`FilesBucket`] - Primary file storage (no expiration, versioned)
 //! - [`IntermediatesBucket`] - Temporary processing artifacts (7 day TTL)
+//! - [`AnalysesBucket`] - Pipeline run analyses (removed with their run)
 //! - [`ThumbnailsBucket`] - Document thumbnails (no expiration)
 //! - [`AvatarsBucket`] - Account avatars (no expiration)
+//! - [`OperationResultsBucket`] - Operation result artifacts (removed with their operation)
 //!
 //! ## Common Types
 //! - [`PutResult`] - Result of upload operations with size and SHA-256 hash
 //! - [`GetResult`] - Result of download operations with streaming reader
+//! - [`VerifiedReader`] - Download reader that checks the content's SHA-256
+//! - [`DeleteOptions`] / [`DeleteOutput`] - Dry runs, progress and totals of
+//!   bulk deletion by prefix and age
+//! - [`BucketLifecycle`] - Declared and enforced expiration of a bucket
 
 mod object_bucket;
 mod object_data;
+mod object_delete;
+mod object_digest;
 mod object_key;
+mod object_lifecycle;
 mod object_store;
 
 pub use object_bucket::{
-    AvatarsBucket, ContextFilesBucket, FilesBucket, IntermediatesBucket, ObjectBucket,
-    ThumbnailsBucket,
+    AnalysesBucket, AvatarsBucket, ContextFilesBucket, FilesBucket, IntermediatesBucket,
+    ObjectBucket, OperationResultsBucket, ThumbnailsBucket,
 };
 pub use object_data::{GetResult, PutResult};
-pub use object_key::{AccountKey, ContextKey, FileKey, IntermediateKey, ObjectKey};
+pub use object_delete::{DeleteOptions, DeleteOutput};
+pub use object_digest::VerifiedReader;
+pub use object_key::{
+    AccountKey, ContextKey, FileKey, IntermediateKey, KeyVersion, ObjectKey, ResultKey,
+};
+pub use object_lifecycle::BucketLifecycle;
 pub use object_store::ObjectStore;
@@ -13,17 +13,27 @@ pub trait ObjectBucket: Clone + Send + Sync + 'static {
     /// Maximum age for objects in this bucket.
     /// Returns `None` for buckets where objects should not expire.
     const MAX_AGE: Option<Duration>;
+
+    /// Whether objects in this bucket are versioned.
+    ///
+    /// A versioned bucket never replaces an object: each version of the
+    /// content is written under its own key and identified by the version ID
+    /// returned on upload, so earlier versions stay available until they are
+    /// deleted.
+    const VERSIONED: bool = false;
 }
 
 /// Primary file storage for uploaded and processed files.
 ///
-/// No expiration, files are retained indefinitely.
+/// No expiration, files are retained indefinitely. Versioned: every version
+/// of a document is stored as its own object.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct FilesBucket;
 
 impl ObjectBucket for FilesBucket {
     const MAX_AGE: Option<Duration> = None;
     const NAME: &'static str = "DOCUMENT_FILES";
+    const VERSIONED: bool = true;
 }
 
 /// Temporary storage for intermediate processing artifacts.
@@ -37,6 +47,19 @@ impl ObjectBucket for IntermediatesBucket {
     const NAME: &'static str = "DOCUMENT_INTERMEDIATES";
 }
 
+/// Storage for encrypted pipeline run analyses.
+///
+/// No expiration: an analysis is read by reviews and redaction for as long
+/// as its run exists, so it is removed with its run by retention and garbage
+/// collection rather than by age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AnalysesBucket;
+
+impl ObjectBucket for AnalysesBucket {
+    const MAX_AGE: Option<Duration> = None;
+    const NAME: &'static str = "DOCUMENT_ANALYSES";
+}
+
 /// Storage for document thumbnails.
 ///
 /// No expiration, thumbnails are retained indefinitely.
@@ -70,6 +93,18 @@ impl ObjectBucket for ContextFilesBucket {
     const NAME: &'static str = "CONTEXT_FILES";
 }
 
+/// Storage for encrypted long-running operation result artifacts.
+///
+/// No bucket-level expiration: each artifact lives as long as its operation,
+/// whose configured retention decides when cleanup removes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OperationResultsBucket;
+
+impl ObjectBucket for OperationResultsBucket {
+    const MAX_AGE: Option<Duration> = None;
+    const NAME: &'static str = "OPERATION_RESULTS";
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,9 +113,11 @@ mod tests {
     fn test_bucket_names() {
         assert_eq!(FilesBucket::NAME, "DOCUMENT_FILES");
         assert_eq!(IntermediatesBucket::NAME, "DOCUMENT_INTERMEDIATES");
+        assert_eq!(AnalysesBucket::NAME, "DOCUMENT_ANALYSES");
         assert_eq!(ThumbnailsBucket::NAME, "DOCUMENT_THUMBNAILS");
         assert_eq!(AvatarsBucket::NAME, "ACCOUNT_AVATARS");
         assert_eq!(ContextFilesBucket::NAME, "CONTEXT_FILES");
+        assert_eq!(OperationResultsBucket::NAME, "OPERATION_RESULTS");
     }
 
     #[test]
@@ -90,8 +127,21 @@ mod tests {
             IntermediatesBucket::MAX_AGE,
             Some(Duration::from_secs(7 * 24 * 60 * 60))
         );
+        assert_eq!(AnalysesBucket::MAX_AGE, None);
         assert_eq!(ThumbnailsBucket::MAX_AGE, None);
         assert_eq!(AvatarsBucket::MAX_AGE, None);
         assert_eq!(ContextFilesBucket::MAX_AGE, None);
+        assert_eq!(OperationResultsBucket::MAX_AGE, None);
+    }
+
+    #[test]
+    fn test_bucket_versioning() {
+        assert!(FilesBucket::VERSIONED);
+        assert!(!IntermediatesBucket::VERSIONED);
+        assert!(!AnalysesBucket::VERSIONED);
+        assert!(!ThumbnailsBucket::VERSIONED);
+        assert!(!AvatarsBucket::VERSIONED);
+        assert!(!ContextFilesBucket::VERSIONED);
+        assert!(!OperationResultsBucket::VERSIONED);
     }
 }
@@ -1,6 +1,9 @@
 //! Result types for object store operations.
 
 use async_nats::jetstream::object_store::{self, ObjectInfo};
+use nvisy_core::crypto::Sha256Context;
+
+use super::object_digest::VerifiedReader;
 
 /// Result of a put operation containing upload metadata.
 ///
@@ -30,22 +33,38 @@ impl PutResult {
     pub fn nuid(&self) -> &str {
         &self.nuid
     }
+
+    /// Returns the version ID of the stored content.
+    ///
+    /// This is the object's NATS unique identifier, which every write
+    /// assigns anew.
+    #[inline]
+    pub fn version(&self) -> &str {
+        &self.nuid
+    }
 }
 
 /// Result of a get operation with streaming reader.
 ///
 /// Provides access to the object content via an async reader
-/// and metadata about the stored object.
+/// and metadata about the stored object. The reader checks the content
+/// against the digest recorded at upload and fails its final read on a
+/// mismatch.
 pub struct GetResult {
     /// The async reader for streaming the object content.
-    reader: object_store::Object,
+    reader: VerifiedReader<object_store::Object>,
     /// Object metadata including size.
     info: ObjectInfo,
 }
 
 impl GetResult {
-    /// Creates a new get result.
-    pub(crate) fn new(reader: object_store::Object, info: ObjectInfo) -> Self {
+    /// Creates a new get result, verifying the content with `hasher`.
+    pub(crate) fn new(
+        reader: object_store::Object,
+        info: ObjectInfo,
+        hasher: Box<dyn Sha256Context>,
+    ) -> Self {
+        let reader = VerifiedReader::new(reader, &info.name, info.digest.as_deref(), hasher);
         Self { reader, info }
     }
 
@@ -53,13 +72,13 @@ impl GetResult {
     ///
     /// The reader implements `AsyncRead` for streaming the content.
     #[inline]
-    pub fn reader(&mut self) -> &mut object_store::Object {
+    pub fn reader(&mut self) -> &mut VerifiedReader<object_store::Object> {
         &mut self.reader
     }
 
     /// Consumes self and returns the reader.
     #[inline]
-    pub fn into_reader(self) -> object_store::Object {
+    pub fn into_reader(self) -> VerifiedReader<object_store::Object> {
         self.reader
     }
 
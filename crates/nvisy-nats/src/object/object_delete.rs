@@ -0,0 +1,148 @@
+//! Options and totals of bulk object deletion.
+//!
+//! [`ObjectStore::delete_by_prefix`] and [`ObjectStore::delete_older_than`]
+//! list the bucket once, then delete the matching objects in batches,
+//! reporting the running totals after each one.
+//!
+//! [`ObjectStore::delete_by_prefix`]: super::ObjectStore::delete_by_prefix
+//! [`ObjectStore::delete_older_than`]: super::ObjectStore::delete_older_than
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Error, Result};
+
+/// Number of objects deleted between progress reports.
+pub(crate) const DELETE_BATCH_SIZE: usize = 100;
+
+/// Callback invoked after every batch with the running totals.
+type ProgressFn = Arc<dyn Fn(&DeleteOutput) + Send + Sync>;
+
+/// Options for a bulk deletion.
+#[derive(Clone, Default)]
+pub struct DeleteOptions {
+    /// Count the matching objects without deleting them.
+    pub dry_run: bool,
+    progress: Option<ProgressFn>,
+}
+
+impl DeleteOptions {
+    /// Options that only count the matching objects.
+    pub fn dry_run() -> Self {
+        Self {
+            dry_run: true,
+            ..Self::default()
+        }
+    }
+
+    /// Reports the running totals after every batch.
+    #[must_use]
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(&DeleteOutput) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Reports `output` to the progress callback, if any.
+    pub(crate) fn report(&self, output: &DeleteOutput) {
+        if let Some(progress) = &self.progress {
+            progress(output);
+        }
+    }
+}
+
+impl fmt::Debug for DeleteOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeleteOptions")
+            .field("dry_run", &self.dry_run)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// Totals of a bulk deletion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeleteOutput {
+    /// Number of objects that matched the filters.
+    pub matched: u64,
+    /// Number of objects deleted; zero for a dry run.
+    pub deleted: u64,
+    /// Total size of the matched objects, in bytes.
+    pub bytes: u64,
+    /// Whether this was a dry run.
+    pub dry_run: bool,
+}
+
+impl DeleteOutput {
+    /// Adds the totals of another deletion, such as one in another region.
+    pub fn add(&mut self, other: Self) {
+        self.matched += other.matched;
+        self.deleted += other.deleted;
+        self.bytes += other.bytes;
+        self.dry_run |= other.dry_run;
+    }
+}
+
+/// Refuses an empty prefix, which would match the whole bucket.
+pub(crate) fn require_prefix(bucket: &str, prefix: &str) -> Result<()> {
+    if prefix.is_empty() {
+        return Err(Error::operation(
+            "bulk_delete",
+            format!("refusing to bulk delete from '{bucket}' without a prefix"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_empty_prefix_is_refused() {
+        assert!(require_prefix("DOCUMENT_INTERMEDIATES", "").is_err());
+        assert!(require_prefix("DOCUMENT_INTERMEDIATES", "intermediate_").is_ok());
+    }
+
+    #[test]
+    fn test_progress_receives_totals() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let options = DeleteOptions::dry_run()
+            .with_progress(move |output| sink.lock().unwrap().push(*output));
+
+        let output = DeleteOutput {
+            matched: 2,
+            bytes: 6,
+            dry_run: true,
+            ..DeleteOutput::default()
+        };
+        options.report(&output);
+        assert_eq!(reports.lock().unwrap().as_slice(), [output]);
+    }
+
+    #[test]
+    fn test_add_totals() {
+        let mut total = DeleteOutput::default();
+        total.add(DeleteOutput {
+            matched: 3,
+            deleted: 3,
+            bytes: 30,
+            dry_run: false,
+        });
+        total.add(DeleteOutput {
+            matched: 1,
+            deleted: 1,
+            bytes: 5,
+            dry_run: false,
+        });
+        assert_eq!(total.matched, 4);
+        assert_eq!(total.deleted, 4);
+        assert_eq!(total.bytes, 35);
+        assert!(!total.dry_run);
+    }
+}
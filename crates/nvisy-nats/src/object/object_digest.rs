@@ -0,0 +1,175 @@
+//! Download-side verification of the SHA-256 digest NATS records on upload.
+//!
+//! Content is hashed with the configured [`CryptoProvider`], so downloads
+//! are verified with FIPS validated primitives when the deployment runs
+//! under the FIPS policy.
+//!
+//! [`CryptoProvider`]: nvisy_core::crypto::CryptoProvider
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use base64::Engine;
+use base64::engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD};
+use nvisy_core::crypto::{SHA256_LEN, Sha256Context};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Prefix of the digest NATS stores in an object's info.
+const DIGEST_PREFIX: &str = "SHA-256=";
+
+/// Reader that hashes an object as it streams and fails at the end of the
+/// content if it does not match the digest recorded at upload.
+///
+/// The mismatch surfaces as an [`io::ErrorKind::InvalidData`] error from the
+/// final read, so callers that read to the end never accept corrupted
+/// content. Objects without a recorded digest are passed through unchecked.
+pub struct VerifiedReader<R> {
+    inner: R,
+    name: String,
+    hasher: Box<dyn Sha256Context>,
+    expected: Option<Vec<u8>>,
+}
+
+impl<R> VerifiedReader<R> {
+    /// Wraps `inner`, checking it against `digest` from the object's info
+    /// with `hasher`.
+    pub(crate) fn new(
+        inner: R,
+        name: &str,
+        digest: Option<&str>,
+        hasher: Box<dyn Sha256Context>,
+    ) -> Self {
+        let expected = digest.and_then(parse_digest);
+        if expected.is_none() {
+            tracing::debug!(
+                target: super::object_store::TRACING_TARGET,
+                key = %name,
+                digest = ?digest,
+                "Object has no usable digest, skipping verification"
+            );
+        }
+
+        Self {
+            inner,
+            name: name.to_owned(),
+            hasher,
+            expected,
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for VerifiedReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let wants_data = buf.remaining() > 0;
+        let filled = buf.filled().len();
+
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(())) => {}
+            other => return other,
+        }
+
+        let read = &buf.filled()[filled..];
+        if !read.is_empty() {
+            this.hasher.update(read);
+            return Poll::Ready(Ok(()));
+        }
+
+        // An empty read into a non-empty buffer marks the end of the content.
+        if !wants_data {
+            return Poll::Ready(Ok(()));
+        }
+        let Some(expected) = this.expected.take() else {
+            return Poll::Ready(Ok(()));
+        };
+
+        let actual = this.hasher.digest();
+        if actual.as_slice() == expected.as_slice() {
+            return Poll::Ready(Ok(()));
+        }
+
+        tracing::error!(
+            target: super::object_store::TRACING_TARGET,
+            key = %this.name,
+            expected = %URL_SAFE.encode(&expected),
+            actual = %URL_SAFE.encode(actual),
+            "Object content does not match its digest"
+        );
+        Poll::Ready(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("object '{}' does not match its SHA-256 digest", this.name),
+        )))
+    }
+}
+
+/// Decodes a `SHA-256=<base64url>` digest, with or without padding.
+fn parse_digest(digest: &str) -> Option<Vec<u8>> {
+    let encoded = digest.strip_prefix(DIGEST_PREFIX)?;
+    URL_SAFE
+        .decode(encoded)
+        .or_else(|_| URL_SAFE_NO_PAD.decode(encoded))
+        .ok()
+        .filter(|bytes| bytes.len() == SHA256_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_core::crypto::{CryptoProvider, RustCryptoProvider};
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    fn digest_of(content: &[u8]) -> String {
+        format!(
+            "{DIGEST_PREFIX}{}",
+            URL_SAFE.encode(RustCryptoProvider.sha256(content))
+        )
+    }
+
+    fn reader<'a>(content: &'a [u8], digest: Option<&str>) -> VerifiedReader<&'a [u8]> {
+        VerifiedReader::new(content, "key", digest, RustCryptoProvider.sha256_context())
+    }
+
+    #[tokio::test]
+    async fn test_matching_content_reads_through() {
+        let digest = digest_of(b"hello world");
+        let mut reader = reader(b"hello world", Some(&digest));
+
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content).await.unwrap();
+        assert_eq!(content, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_content_fails_at_the_end() {
+        let digest = digest_of(b"hello world");
+        let mut reader = reader(b"hello w0rld", Some(&digest));
+
+        let mut content = Vec::new();
+        let err = reader.read_to_end(&mut content).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_missing_digest_is_not_checked() {
+        let mut reader = reader(b"hello world", None);
+
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content).await.unwrap();
+        assert_eq!(content, b"hello world");
+    }
+
+    #[test]
+    fn test_parse_digest_accepts_unpadded() {
+        let padded = digest_of(b"abc");
+        let unpadded = padded.trim_end_matches('=');
+        assert_eq!(parse_digest(&padded), parse_digest(unpadded));
+        assert!(parse_digest(&padded).is_some());
+        assert_eq!(parse_digest("MD5=abc"), None);
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt;
 use std::str::FromStr;
 
 use base64::prelude::*;
+use nvisy_core::id::{IdGenerator, RandomIds};
 use uuid::Uuid;
 
 use crate::{Error, Result};
@@ -17,6 +18,45 @@ pub trait ObjectKey: fmt::Display + FromStr + Clone + Send + Sync + 'static {
     const PREFIX: &'static str;
 }
 
+/// Layout version of a stored object key.
+///
+/// Keys are always written in [`KeyVersion::CURRENT`]; older layouts are
+/// still parsed so that objects stored under them stay reachable until they
+/// are rewritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyVersion {
+    /// Legacy layout: the hyphenated workspace and object UUIDs separated by
+    /// a slash, without a type prefix (`<workspace_id>/<object_id>`).
+    V1,
+    /// Current layout: a type prefix followed by URL-safe base64 of the
+    /// concatenated IDs.
+    V2,
+}
+
+impl KeyVersion {
+    /// The layout new keys are written in.
+    pub const CURRENT: Self = Self::V2;
+
+    /// Detects the layout of a stored key with the given type prefix.
+    ///
+    /// Returns `None` when the key matches no known layout.
+    pub fn detect(key: &str, prefix: &str) -> Option<Self> {
+        if key.starts_with(prefix) {
+            Some(Self::V2)
+        } else if key.split('/').count() == 2 {
+            Some(Self::V1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether this layout is no longer written.
+    #[inline]
+    pub fn is_legacy(self) -> bool {
+        self != Self::CURRENT
+    }
+}
+
 /// A validated key for file objects in NATS object storage.
 ///
 /// The key is encoded as `file_` prefix followed by URL-safe base64 of the
@@ -27,10 +67,16 @@ pub trait ObjectKey: fmt::Display + FromStr + Clone + Send + Sync + 'static {
 /// - Time-ordered keys for efficient storage and retrieval
 /// - Guaranteed uniqueness within the workspace
 /// - No collision with database-generated IDs
+///
+/// Parsing also accepts the legacy [`KeyVersion::V1`] layout. A parsed key
+/// remembers its layout and displays in it, so it keeps addressing the
+/// object it was read from; [`FileKey::upgraded`] gives the key the object
+/// is moved to.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FileKey {
     pub workspace_id: Uuid,
     pub object_id: Uuid,
+    version: KeyVersion,
 }
 
 impl ObjectKey for FileKey {
@@ -43,10 +89,12 @@ impl FileKey {
     /// Uses UUID v7 which is time-ordered and contains randomness,
     /// making keys both sortable and collision-resistant.
     pub fn generate(workspace_id: Uuid) -> Self {
-        Self {
-            workspace_id,
-            object_id: Uuid::now_v7(),
-        }
+        Self::generate_with(workspace_id, &RandomIds)
+    }
+
+    /// Generates a new file key with an object ID from `ids`.
+    pub fn generate_with(workspace_id: Uuid, ids: &dyn IdGenerator) -> Self {
+        Self::from_parts(workspace_id, ids.generate())
     }
 
     /// Creates a file key from existing IDs (for parsing stored keys).
@@ -54,15 +102,62 @@ impl FileKey {
         Self {
             workspace_id,
             object_id,
+            version: KeyVersion::CURRENT,
         }
     }
 
     /// Regenerates the object ID with a fresh UUID v7.
     ///
     /// This is useful when creating a new version of a file
-    /// while keeping the same workspace association.
+    /// while keeping the same workspace association. The regenerated key
+    /// uses the current layout.
     pub fn regenerate(&mut self) {
         self.object_id = Uuid::now_v7();
+        self.version = KeyVersion::CURRENT;
+    }
+
+    /// Returns the layout this key is written in.
+    #[inline]
+    pub fn version(&self) -> KeyVersion {
+        self.version
+    }
+
+    /// Returns the same key in the current layout.
+    pub fn upgraded(&self) -> Self {
+        Self::from_parts(self.workspace_id, self.object_id)
+    }
+
+    /// Parses a key in the current layout only.
+    pub fn parse_v2(s: &str) -> Result<Self> {
+        let payload = s.strip_prefix(Self::PREFIX).ok_or_else(|| {
+            Error::operation(
+                "parse_key",
+                format!("Invalid key prefix: expected '{}'", Self::PREFIX),
+            )
+        })?;
+        Self::decode_payload(payload)
+    }
+
+    /// Parses a key in the legacy `<workspace_id>/<object_id>` layout only.
+    pub fn parse_v1(s: &str) -> Result<Self> {
+        let (workspace_id, object_id) = s.split_once('/').ok_or_else(|| {
+            Error::operation(
+                "parse_key",
+                "Invalid legacy key: expected '<workspace_id>/<object_id>'",
+            )
+        })?;
+
+        let workspace_id = Uuid::parse_str(workspace_id)
+            .map_err(|e| Error::operation("parse_key", format!("Invalid workspace UUID: {}", e)))?;
+
+        let object_id = Uuid::parse_str(object_id)
+            .map_err(|e| Error::operation("parse_key", format!("Invalid object UUID: {}", e)))?;
+
+        Ok(Self {
+            workspace_id,
+            object_id,
+            version: KeyVersion::V1,
+        })
     }
 
     /// Encodes the key payload as URL-safe base64.
@@ -98,21 +193,26 @@ impl FileKey {
 
 impl fmt::Display for FileKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", Self::PREFIX, self.encode_payload())
+        match self.version {
+            KeyVersion::V1 => write!(f, "{}/{}", self.workspace_id, self.object_id),
+            KeyVersion::V2 => write!(f, "{}{}", Self::PREFIX, self.encode_payload()),
+        }
     }
 }
 
 impl FromStr for FileKey {
     type Err = Error;
 
+    /// Parses a key in any supported layout, detecting which one it uses.
     fn from_str(s: &str) -> Result<Self> {
-        let payload = s.strip_prefix(Self::PREFIX).ok_or_else(|| {
-            Error::operation(
+        match KeyVersion::detect(s, Self::PREFIX) {
+            Some(KeyVersion::V2) => Self::parse_v2(s),
+            Some(KeyVersion::V1) => Self::parse_v1(s),
+            None => Err(Error::operation(
                 "parse_key",
                 format!("Invalid key prefix: expected '{}'", Self::PREFIX),
-            )
-        })?;
-        Self::decode_payload(payload)
+            )),
+        }
     }
 }
 
@@ -258,9 +358,14 @@ impl ObjectKey for IntermediateKey {
 impl IntermediateKey {
     /// Generates a new intermediate key with a fresh UUID v7 object ID.
     pub fn generate(workspace_id: Uuid) -> Self {
+        Self::generate_with(workspace_id, &RandomIds)
+    }
+
+    /// Generates a new intermediate key with an object ID from `ids`.
+    pub fn generate_with(workspace_id: Uuid, ids: &dyn IdGenerator) -> Self {
         Self {
             workspace_id,
-            object_id: Uuid::now_v7(),
+            object_id: ids.generate(),
         }
     }
 
@@ -323,11 +428,91 @@ impl FromStr for IntermediateKey {
     }
 }
 
+/// A validated key for operation result artifacts in NATS object storage.
+///
+/// Each operation holds at most one artifact, so the key is the owning
+/// workspace and operation: a `result_` prefix followed by URL-safe base64 of
+/// the two concatenated IDs (32 bytes -> base64).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultKey {
+    pub workspace_id: Uuid,
+    pub operation_id: Uuid,
+}
+
+impl ObjectKey for ResultKey {
+    const PREFIX: &'static str = "result_";
+}
+
+impl ResultKey {
+    /// Creates a new result key from workspace and operation IDs.
+    pub fn new(workspace_id: Uuid, operation_id: Uuid) -> Self {
+        Self {
+            workspace_id,
+            operation_id,
+        }
+    }
+
+    /// Encodes the key payload as URL-safe base64.
+    fn encode_payload(&self) -> String {
+        let mut bytes = [0u8; 32];
+        bytes[..16].copy_from_slice(self.workspace_id.as_bytes());
+        bytes[16..].copy_from_slice(self.operation_id.as_bytes());
+        BASE64_URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// Decodes a key payload from URL-safe base64.
+    fn decode_payload(s: &str) -> Result<Self> {
+        let bytes = BASE64_URL_SAFE_NO_PAD.decode(s).map_err(|e| {
+            Error::operation("parse_key", format!("Invalid base64 encoding: {}", e))
+        })?;
+
+        if bytes.len() != 32 {
+            return Err(Error::operation(
+                "parse_key",
+                format!("Invalid key length: expected 32 bytes, got {}", bytes.len()),
+            ));
+        }
+
+        let workspace_id = Uuid::from_slice(&bytes[..16])
+            .map_err(|e| Error::operation("parse_key", format!("Invalid workspace UUID: {}", e)))?;
+
+        let operation_id = Uuid::from_slice(&bytes[16..])
+            .map_err(|e| Error::operation("parse_key", format!("Invalid operation UUID: {}", e)))?;
+
+        Ok(Self::new(workspace_id, operation_id))
+    }
+}
+
+impl fmt::Display for ResultKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Self::PREFIX, self.encode_payload())
+    }
+}
+
+impl FromStr for ResultKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let payload = s.strip_prefix(Self::PREFIX).ok_or_else(|| {
+            Error::operation(
+                "parse_key",
+                format!("Invalid key prefix: expected '{}'", Self::PREFIX),
+            )
+        })?;
+        Self::decode_payload(payload)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     mod file_key {
+        use std::sync::Arc;
+
+        use nvisy_core::clock::ManualClock;
+        use nvisy_core::id::SequentialIds;
+
         use super::*;
 
         #[test]
@@ -344,6 +529,18 @@ mod tests {
             assert_eq!(key.object_id.get_version_num(), 7);
         }
 
+        #[test]
+        fn test_generate_with() {
+            let workspace_id = Uuid::new_v4();
+            let generate = || {
+                let ids = SequentialIds::new(Arc::new(ManualClock::default()));
+                FileKey::generate_with(workspace_id, &ids)
+            };
+
+            assert_eq!(generate(), generate());
+            assert_eq!(generate().object_id.get_version_num(), 7);
+        }
+
         #[test]
         fn test_from_parts() {
             let workspace_id = Uuid::new_v4();
@@ -384,6 +581,57 @@ mod tests {
             assert!(FileKey::from_str("account_abc").is_err());
             assert!(FileKey::from_str("abc").is_err());
         }
+
+        #[test]
+        fn test_detect_version() {
+            let key = FileKey::generate(Uuid::new_v4());
+            let legacy = format!("{}/{}", key.workspace_id, key.object_id);
+
+            assert_eq!(
+                KeyVersion::detect(&key.to_string(), FileKey::PREFIX),
+                Some(KeyVersion::V2)
+            );
+            assert_eq!(
+                KeyVersion::detect(&legacy, FileKey::PREFIX),
+                Some(KeyVersion::V1)
+            );
+            assert_eq!(KeyVersion::detect("a/b/c", FileKey::PREFIX), None);
+            assert_eq!(KeyVersion::detect("abc", FileKey::PREFIX), None);
+        }
+
+        #[test]
+        fn test_legacy_roundtrip() {
+            let workspace_id = Uuid::new_v4();
+            let object_id = Uuid::new_v4();
+            let legacy = format!("{}/{}", workspace_id, object_id);
+
+            let key: FileKey = legacy.parse().unwrap();
+            assert_eq!(key.workspace_id, workspace_id);
+            assert_eq!(key.object_id, object_id);
+            assert_eq!(key.version(), KeyVersion::V1);
+            assert!(key.version().is_legacy());
+            assert_eq!(key.to_string(), legacy);
+        }
+
+        #[test]
+        fn test_upgraded() {
+            let workspace_id = Uuid::new_v4();
+            let object_id = Uuid::new_v4();
+            let key = FileKey::parse_v1(&format!("{}/{}", workspace_id, object_id)).unwrap();
+
+            let upgraded = key.upgraded();
+            assert_eq!(upgraded.version(), KeyVersion::CURRENT);
+            assert_eq!(upgraded, FileKey::from_parts(workspace_id, object_id));
+            assert_ne!(upgraded, key);
+            assert!(upgraded.to_string().starts_with("file_"));
+        }
+
+        #[test]
+        fn test_parse_v1_invalid() {
+            assert!(FileKey::parse_v1("not-a-uuid/also-not").is_err());
+            assert!(FileKey::parse_v1(&Uuid::new_v4().to_string()).is_err());
+            assert!(FileKey::parse_v2(&format!("{}/{}", Uuid::new_v4(), Uuid::new_v4())).is_err());
+        }
     }
 
     mod account_key {
@@ -485,4 +733,33 @@ mod tests {
             assert!(ContextKey::from_str("abc").is_err());
         }
     }
+
+    mod result_key {
+        use super::*;
+
+        #[test]
+        fn test_prefix() {
+            assert_eq!(ResultKey::PREFIX, "result_");
+        }
+
+        #[test]
+        fn test_roundtrip() {
+            let workspace_id = Uuid::new_v4();
+            let operation_id = Uuid::new_v4();
+
+            let key = ResultKey::new(workspace_id, operation_id);
+            let encoded = key.to_string();
+            assert!(encoded.starts_with("result_"));
+
+            let decoded: ResultKey = encoded.parse().unwrap();
+            assert_eq!(decoded.workspace_id, workspace_id);
+            assert_eq!(decoded.operation_id, operation_id);
+        }
+
+        #[test]
+        fn test_from_str_invalid_prefix() {
+            assert!(ResultKey::from_str("ctx_abc").is_err());
+            assert!(ResultKey::from_str("abc").is_err());
+        }
+    }
 }
@@ -0,0 +1,65 @@
+//! Expiration status of object buckets.
+//!
+//! A bucket's lifecycle rule is its [`ObjectBucket::MAX_AGE`], applied as
+//! the `max_age` of the JetStream stream backing it. [`BucketLifecycle`]
+//! reports the declared rule next to the one the server enforces, so an
+//! operator can tell whether a bucket expires its objects as declared.
+//!
+//! [`ObjectBucket::MAX_AGE`]: super::ObjectBucket::MAX_AGE
+
+use std::time::Duration;
+
+/// Declared and enforced expiration of one object bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BucketLifecycle {
+    /// Bucket name.
+    pub bucket: &'static str,
+    /// Age at which the bucket's objects should expire, if they do.
+    pub declared_max_age: Option<Duration>,
+    /// Age at which the server expires the bucket's objects, if it does.
+    pub applied_max_age: Option<Duration>,
+}
+
+impl BucketLifecycle {
+    /// Creates the status from a bucket's declared TTL and its stream's
+    /// `max_age`, where zero means no expiration.
+    pub(crate) fn new(
+        bucket: &'static str,
+        declared_max_age: Option<Duration>,
+        stream_max_age: Duration,
+    ) -> Self {
+        Self {
+            bucket,
+            declared_max_age,
+            applied_max_age: (!stream_max_age.is_zero()).then_some(stream_max_age),
+        }
+    }
+
+    /// Whether the server expires the bucket's objects as declared.
+    pub fn is_applied(&self) -> bool {
+        self.declared_max_age == self.applied_max_age
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+    #[test]
+    fn test_zero_max_age_never_expires() {
+        let lifecycle = BucketLifecycle::new("DOCUMENT_FILES", None, Duration::ZERO);
+        assert_eq!(lifecycle.applied_max_age, None);
+        assert!(lifecycle.is_applied());
+    }
+
+    #[test]
+    fn test_missing_ttl_is_not_applied() {
+        let lifecycle = BucketLifecycle::new("DOCUMENT_INTERMEDIATES", Some(WEEK), Duration::ZERO);
+        assert!(!lifecycle.is_applied());
+
+        let lifecycle = BucketLifecycle::new("DOCUMENT_INTERMEDIATES", Some(WEEK), WEEK);
+        assert!(lifecycle.is_applied());
+    }
+}
@@ -1,20 +1,27 @@
 //! Generic object store for NATS JetStream.
 
+use std::collections::HashMap;
+use std::io;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_nats::jetstream;
 use async_nats::jetstream::context::ObjectStoreErrorKind;
 use async_nats::jetstream::object_store::{self, ObjectInfo};
+use futures::StreamExt;
+use nvisy_core::crypto::CryptoProvider;
 use tokio::io::AsyncRead;
 
 use super::object_bucket::ObjectBucket;
 use super::object_data::{GetResult, PutResult};
+use super::object_delete::{DELETE_BATCH_SIZE, DeleteOptions, DeleteOutput, require_prefix};
 use super::object_key::ObjectKey;
+use super::object_lifecycle::BucketLifecycle;
 use crate::{Error, Result};
 
 /// Tracing target for object store operations.
-const TRACING_TARGET: &str = "nvisy_nats::object_store";
+pub(crate) const TRACING_TARGET: &str = "nvisy_nats::object_store";
 
 /// A type-safe object store that manages objects in NATS object storage.
 ///
@@ -30,6 +37,7 @@ where
     K: ObjectKey,
 {
     inner: Arc<object_store::ObjectStore>,
+    crypto: Arc<dyn CryptoProvider>,
     _marker: PhantomData<(B, K)>,
 }
 
@@ -38,8 +46,21 @@ where
     B: ObjectBucket,
     K: ObjectKey,
 {
-    /// Creates a new object store for the specified bucket type.
-    pub(crate) async fn new(jetstream: &jetstream::Context) -> Result<Self> {
+    /// Creates a new object store for the specified bucket type, verifying
+    /// downloads with hashes from `crypto`.
+    pub(crate) async fn new(
+        jetstream: &jetstream::Context,
+        crypto: Arc<dyn CryptoProvider>,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner: Arc::new(Self::open(jetstream).await?),
+            crypto,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Gets the bucket, creating it with its TTL if it does not exist.
+    async fn open(jetstream: &jetstream::Context) -> Result<object_store::ObjectStore> {
         tracing::debug!(
             target: TRACING_TARGET,
             bucket = %B::NAME,
@@ -89,10 +110,65 @@ where
             }
         };
 
-        Ok(Self {
-            inner: Arc::new(store),
-            _marker: PhantomData,
-        })
+        Ok(store)
+    }
+
+    /// Opens the store and brings its TTL in line with [`ObjectBucket::MAX_AGE`].
+    ///
+    /// [`new`](Self::new) only applies the TTL when it creates the bucket, so
+    /// a bucket created before its TTL was declared, or with a different one,
+    /// would otherwise keep its objects forever. The TTL is the backing
+    /// stream's `max_age`, which JetStream enforces on every stored chunk.
+    ///
+    /// Returns the bucket's lifecycle as the server now enforces it.
+    pub(crate) async fn sync_max_age(jetstream: &jetstream::Context) -> Result<BucketLifecycle> {
+        Self::open(jetstream).await?;
+
+        let stream_name = format!("OBJ_{}", B::NAME);
+        let stream = jetstream
+            .get_stream(&stream_name)
+            .await
+            .map_err(|e| Error::stream_error(&stream_name, e.to_string()))?;
+
+        let max_age = B::MAX_AGE.unwrap_or_default();
+        let mut config = stream.cached_info().config.clone();
+        if config.max_age == max_age {
+            return Ok(BucketLifecycle::new(B::NAME, B::MAX_AGE, max_age));
+        }
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            bucket = %B::NAME,
+            from_secs = config.max_age.as_secs(),
+            to_secs = max_age.as_secs(),
+            "Updating object store max age"
+        );
+
+        config.max_age = max_age;
+        jetstream.update_stream(&config).await.map_err(|e| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                bucket = %B::NAME,
+                error = %e,
+                "Failed to update object store max age"
+            );
+            Error::stream_error(&stream_name, e.to_string())
+        })?;
+
+        Ok(BucketLifecycle::new(B::NAME, B::MAX_AGE, max_age))
+    }
+
+    /// Reads the bucket's lifecycle as the server enforces it, without
+    /// changing it.
+    pub(crate) async fn lifecycle(jetstream: &jetstream::Context) -> Result<BucketLifecycle> {
+        let stream_name = format!("OBJ_{}", B::NAME);
+        let stream = jetstream
+            .get_stream(&stream_name)
+            .await
+            .map_err(|e| Error::stream_error(&stream_name, e.to_string()))?;
+
+        let max_age = stream.cached_info().config.max_age;
+        Ok(BucketLifecycle::new(B::NAME, B::MAX_AGE, max_age))
     }
 
     /// Returns the bucket name.
@@ -102,7 +178,27 @@ where
     }
 
     /// Streams data to the store without buffering it, suitable for large files.
-    pub async fn put<R>(&self, key: &K, mut reader: R) -> Result<PutResult>
+    pub async fn put<R>(&self, key: &K, reader: R) -> Result<PutResult>
+    where
+        R: AsyncRead + Unpin,
+    {
+        self.put_with_metadata(key, reader, HashMap::new()).await
+    }
+
+    /// Streams data to the store with user metadata attached to the object.
+    ///
+    /// The metadata is returned with the object's info on every read, so it
+    /// suits small values needed to interpret the content, such as how it
+    /// was encrypted.
+    ///
+    /// In a [versioned](ObjectBucket::VERSIONED) bucket, refuses to replace
+    /// an existing object.
+    pub async fn put_with_metadata<R>(
+        &self,
+        key: &K,
+        mut reader: R,
+        metadata: HashMap<String, String>,
+    ) -> Result<PutResult>
     where
         R: AsyncRead + Unpin,
     {
@@ -115,8 +211,20 @@ where
             "Starting streaming upload"
         );
 
+        // A new version of a versioned object is written under a new key.
+        if B::VERSIONED && self.exists(key).await? {
+            return Err(Error::operation(
+                "put",
+                format!(
+                    "refusing to replace '{key_str}' in versioned bucket '{}'",
+                    B::NAME
+                ),
+            ));
+        }
+
         let meta = object_store::ObjectMetadata {
             name: key_str.clone(),
+            metadata,
             ..Default::default()
         };
 
@@ -170,7 +278,8 @@ where
                     "Object stream opened"
                 );
 
-                Ok(Some(GetResult::new(reader, info)))
+                let hasher = self.crypto.sha256_context();
+                Ok(Some(GetResult::new(reader, info, hasher)))
             }
             Err(e) => {
                 let error_str = e.to_string();
@@ -241,6 +350,170 @@ where
         Ok(())
     }
 
+    /// Copies an object to another key in the same bucket, streaming it.
+    ///
+    /// Returns `None` if the source object doesn't exist. The copy keeps the
+    /// source's metadata. The source is left in place; callers moving an
+    /// object delete it once the copy is verified.
+    pub async fn copy(&self, from: &K, to: &K) -> Result<Option<PutResult>> {
+        let Some(source) = self.get(from).await? else {
+            return Ok(None);
+        };
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            from = %from,
+            to = %to,
+            bucket = %B::NAME,
+            "Copying object"
+        );
+
+        let metadata = source.info().metadata.clone();
+        let mut reader = source.into_reader();
+        self.put_with_metadata(to, &mut reader, metadata)
+            .await
+            .map(Some)
+    }
+
+    /// Lists the info of every object in the bucket.
+    ///
+    /// Only metadata is loaded, but the whole bucket is listed at once; it
+    /// suits periodic sweeps rather than request paths.
+    pub async fn list(&self) -> Result<Vec<ObjectInfo>> {
+        let mut objects = self.inner.list().await.map_err(|e| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                bucket = %B::NAME,
+                error = %e,
+                "Failed to list objects"
+            );
+            Error::operation("list", e.to_string())
+        })?;
+
+        let mut infos = Vec::new();
+        while let Some(info) = objects.next().await {
+            let info = info.map_err(|e| Error::operation("list", e.to_string()))?;
+            if !info.deleted {
+                infos.push(info);
+            }
+        }
+
+        Ok(infos)
+    }
+
+    /// Deletes every object whose name starts with `prefix`.
+    ///
+    /// Refuses an empty prefix, which would wipe the whole bucket.
+    pub async fn delete_by_prefix(
+        &self,
+        prefix: &str,
+        options: &DeleteOptions,
+    ) -> Result<DeleteOutput> {
+        self.delete_matching(prefix, options, |_| true).await
+    }
+
+    /// Deletes the objects whose name starts with `prefix` and that were
+    /// last modified more than `age` ago.
+    ///
+    /// Objects without a modification time are never considered old. Refuses
+    /// an empty prefix, which would sweep the whole bucket.
+    pub async fn delete_older_than(
+        &self,
+        prefix: &str,
+        age: Duration,
+        options: &DeleteOptions,
+    ) -> Result<DeleteOutput> {
+        let cutoff = SystemTime::now()
+            .checked_sub(age)
+            .and_then(|cutoff| cutoff.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+
+        self.delete_matching(prefix, options, |info| {
+            info.modified
+                .is_some_and(|modified| modified.unix_timestamp() <= cutoff)
+        })
+        .await
+    }
+
+    /// Deletes the objects under `prefix` accepted by `filter`, in batches.
+    async fn delete_matching(
+        &self,
+        prefix: &str,
+        options: &DeleteOptions,
+        filter: impl Fn(&ObjectInfo) -> bool,
+    ) -> Result<DeleteOutput> {
+        require_prefix(B::NAME, prefix)?;
+
+        let matching: Vec<ObjectInfo> = self
+            .list()
+            .await?
+            .into_iter()
+            .filter(|info| info.name.starts_with(prefix) && filter(info))
+            .collect();
+
+        let mut output = DeleteOutput {
+            dry_run: options.dry_run,
+            ..DeleteOutput::default()
+        };
+        for batch in matching.chunks(DELETE_BATCH_SIZE) {
+            for info in batch {
+                output.matched += 1;
+                output.bytes += info.size as u64;
+                if options.dry_run {
+                    continue;
+                }
+
+                match self.inner.delete(&info.name).await {
+                    Ok(()) => output.deleted += 1,
+                    // Deleted concurrently, e.g. by the bucket's TTL.
+                    Err(e) if e.to_string().contains("not found") => {}
+                    Err(e) => {
+                        tracing::error!(
+                            target: TRACING_TARGET,
+                            key = %info.name,
+                            bucket = %B::NAME,
+                            error = %e,
+                            "Failed to bulk delete object"
+                        );
+                        return Err(Error::operation("bulk_delete", e.to_string()));
+                    }
+                }
+            }
+            options.report(&output);
+        }
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            bucket = %B::NAME,
+            prefix = %prefix,
+            matched = output.matched,
+            deleted = output.deleted,
+            bytes = output.bytes,
+            dry_run = output.dry_run,
+            "Bulk delete finished"
+        );
+
+        Ok(output)
+    }
+
+    /// Streams an object and checks it against the digest recorded at upload.
+    ///
+    /// Returns `None` if the object doesn't exist and `Some(false)` if its
+    /// content no longer matches, for background integrity sweeps. Objects
+    /// without a recorded digest count as intact.
+    pub async fn verify(&self, key: &K) -> Result<Option<bool>> {
+        let Some(object) = self.get(key).await? else {
+            return Ok(None);
+        };
+
+        let mut reader = object.into_reader();
+        match tokio::io::copy(&mut reader, &mut tokio::io::sink()).await {
+            Ok(_) => Ok(Some(true)),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => Ok(Some(false)),
+            Err(e) => Err(Error::operation("verify", e.to_string())),
+        }
+    }
+
     /// Checks if an object exists.
     pub async fn exists(&self, key: &K) -> Result<bool> {
         Ok(self.info(key).await?.is_some())
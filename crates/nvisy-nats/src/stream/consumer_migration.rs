@@ -0,0 +1,462 @@
+//! Zero-downtime migration of durable consumers between configurations.
+//!
+//! JetStream rejects most edits to an existing durable consumer, and deleting
+//! and recreating one loses its position in the stream. Instead, each
+//! configuration change creates a new consumer *version* next to the old one
+//! (`webhook-worker`, `webhook-worker-v2`, ...) and hands the stream position
+//! over:
+//!
+//! 1. Subscribers following the [`ActiveConsumer`] handle are paused.
+//! 2. The old consumer drains: in-flight messages are acked or time out.
+//! 3. The new version is created, starting just past the old ack floor.
+//! 4. Subscribers are switched to the new version in one step.
+//! 5. The old version is deleted.
+//!
+//! Delivery stays at-least-once across the handoff: messages acked above the
+//! old ack floor may be redelivered, but none are skipped. A migration
+//! interrupted after step 3 is finished by the next reconcile, which sees the
+//! new version already matches and removes the leftovers.
+
+use std::time::Duration;
+
+use async_nats::jetstream::consumer::{self, AckPolicy, DeliverPolicy};
+use async_nats::jetstream::{Context, stream};
+use futures::TryStreamExt;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use super::event_stream::EventStream;
+use crate::{Error, Result, TRACING_TARGET_STREAM};
+
+/// Default time to wait for in-flight messages before handing over.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often the draining consumer is polled.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Desired configuration of a durable pull consumer.
+///
+/// Defaults mirror what the server fills in for an unconfigured consumer, so
+/// consumers created before versioning existed reconcile as unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use = "spec does nothing unless you reconcile it"]
+pub struct ConsumerSpec {
+    /// Stream the consumer reads from.
+    pub stream_name: String,
+    /// Consumer name without a version suffix.
+    pub base_name: String,
+    /// Subject filter applied by the consumer.
+    pub filter_subject: String,
+    /// How long a delivered message may go unacknowledged.
+    pub ack_wait: Duration,
+    /// Maximum delivery attempts per message (-1 for unlimited).
+    pub max_deliver: i64,
+    /// Maximum number of unacknowledged messages in flight.
+    pub max_ack_pending: i64,
+}
+
+impl ConsumerSpec {
+    /// Returns the default spec for a stream's durable consumer.
+    pub fn for_stream<S: EventStream>() -> Self {
+        Self {
+            stream_name: S::NAME.to_owned(),
+            base_name: S::CONSUMER_NAME.to_owned(),
+            filter_subject: format!("{}.>", S::NAME),
+            ack_wait: Duration::from_secs(30),
+            max_deliver: -1,
+            max_ack_pending: 1000,
+        }
+    }
+
+    /// Sets the acknowledgement timeout.
+    pub fn with_ack_wait(mut self, ack_wait: Duration) -> Self {
+        self.ack_wait = ack_wait;
+        self
+    }
+
+    /// Sets the maximum delivery attempts per message.
+    pub fn with_max_deliver(mut self, max_deliver: i64) -> Self {
+        self.max_deliver = max_deliver;
+        self
+    }
+
+    /// Sets the maximum number of unacknowledged messages in flight.
+    pub fn with_max_ack_pending(mut self, max_ack_pending: i64) -> Self {
+        self.max_ack_pending = max_ack_pending;
+        self
+    }
+
+    /// Returns the consumer name for a version.
+    ///
+    /// Version 0 is the unversioned legacy name.
+    pub fn versioned_name(&self, version: u32) -> String {
+        match version {
+            0 => self.base_name.clone(),
+            n => format!("{}-v{n}", self.base_name),
+        }
+    }
+
+    /// Returns the version encoded in a consumer name, if it belongs to this
+    /// spec.
+    pub fn parse_version(&self, name: &str) -> Option<u32> {
+        if name == self.base_name {
+            return Some(0);
+        }
+
+        name.strip_prefix(self.base_name.as_str())?
+            .strip_prefix("-v")?
+            .parse()
+            .ok()
+            .filter(|version| *version > 0)
+    }
+
+    /// Returns whether an existing consumer already has this configuration.
+    pub fn matches(&self, config: &consumer::Config) -> bool {
+        config.filter_subject == self.filter_subject
+            && config.ack_policy == AckPolicy::Explicit
+            && config.ack_wait == self.ack_wait
+            && config.max_deliver == self.max_deliver
+            && config.max_ack_pending == self.max_ack_pending
+    }
+
+    fn to_config(&self, name: String, deliver_policy: DeliverPolicy) -> consumer::pull::Config {
+        consumer::pull::Config {
+            description: Some(format!("Consumer for stream {}", self.stream_name)),
+            durable_name: Some(name),
+            ack_policy: AckPolicy::Explicit,
+            deliver_policy,
+            filter_subject: self.filter_subject.clone(),
+            ack_wait: self.ack_wait,
+            max_deliver: self.max_deliver,
+            max_ack_pending: self.max_ack_pending,
+            ..Default::default()
+        }
+    }
+}
+
+/// The consumer version subscribers should read from.
+///
+/// Cheap to clone; all clones observe the same switch. `None` means a
+/// migration is draining the old version and subscribers should hold off.
+#[derive(Debug, Clone)]
+pub struct ActiveConsumer {
+    sender: watch::Sender<Option<String>>,
+}
+
+impl ActiveConsumer {
+    /// Creates a handle pointing at `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            sender: watch::Sender::new(Some(name.into())),
+        }
+    }
+
+    /// Creates a handle with no active consumer yet.
+    pub fn paused() -> Self {
+        Self {
+            sender: watch::Sender::new(None),
+        }
+    }
+
+    /// Returns the active consumer name, or `None` while paused.
+    pub fn name(&self) -> Option<String> {
+        self.sender.borrow().clone()
+    }
+
+    /// Returns a receiver notified on every switch.
+    pub fn watch(&self) -> watch::Receiver<Option<String>> {
+        self.sender.subscribe()
+    }
+
+    /// Waits until a consumer is active and returns its name.
+    pub async fn wait_active(receiver: &mut watch::Receiver<Option<String>>) -> Result<String> {
+        let name = receiver
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| Error::operation("active_consumer", "consumer handle dropped"))?;
+        Ok(name.clone().unwrap_or_default())
+    }
+
+    pub(crate) fn pause(&self) {
+        self.sender.send_replace(None);
+    }
+
+    pub(crate) fn switch(&self, name: String) {
+        self.sender.send_replace(Some(name));
+    }
+}
+
+/// What a reconcile did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationOutcome {
+    /// No consumer existed; the first version was created.
+    Created { consumer: String },
+    /// The newest version already matched the spec.
+    Unchanged { consumer: String },
+    /// Subscribers were moved to a new version.
+    Migrated {
+        from: String,
+        to: String,
+        /// First stream sequence delivered by the new version.
+        handoff_sequence: u64,
+    },
+}
+
+impl MigrationOutcome {
+    /// Returns the consumer subscribers are now reading from.
+    pub fn consumer(&self) -> &str {
+        match self {
+            Self::Created { consumer } | Self::Unchanged { consumer } => consumer,
+            Self::Migrated { to, .. } => to,
+        }
+    }
+}
+
+/// Moves a durable consumer to a new configuration without losing messages.
+#[derive(Debug, Clone)]
+pub struct ConsumerMigrator {
+    jetstream: Context,
+    drain_timeout: Duration,
+}
+
+impl ConsumerMigrator {
+    /// Creates a migrator with the default drain timeout.
+    pub fn new(jetstream: Context) -> Self {
+        Self {
+            jetstream,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for in-flight messages before handing over.
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Brings the consumer in line with `spec`, switching `active` to the
+    /// resulting version.
+    #[tracing::instrument(skip_all, target = TRACING_TARGET_STREAM, fields(consumer = %spec.base_name))]
+    pub async fn reconcile(
+        &self,
+        spec: &ConsumerSpec,
+        active: &ActiveConsumer,
+    ) -> Result<MigrationOutcome> {
+        let stream = self.get_stream(spec).await?;
+        let mut versions = Self::versions(&stream, spec).await?;
+
+        let Some((current_version, current_name)) = versions.last().cloned() else {
+            let name = spec.versioned_name(0);
+            Self::create(&stream, spec, name.clone(), DeliverPolicy::All).await?;
+            active.switch(name.clone());
+
+            tracing::info!(
+                target: TRACING_TARGET_STREAM,
+                consumer = %name,
+                "Created durable consumer"
+            );
+            return Ok(MigrationOutcome::Created { consumer: name });
+        };
+
+        let mut current = Self::get(&stream, &current_name).await?;
+        let current_info = current
+            .info()
+            .await
+            .map_err(|e| Error::consumer_error(&current_name, e.to_string()))?;
+
+        if spec.matches(&current_info.config) {
+            active.switch(current_name.clone());
+            versions.pop();
+            Self::delete_stale(&stream, &versions).await;
+            return Ok(MigrationOutcome::Unchanged {
+                consumer: current_name,
+            });
+        }
+
+        // Pause subscribers and let in-flight deliveries settle, so the ack
+        // floor is as close as possible to everything actually processed.
+        active.pause();
+        let handoff_sequence = match self.drain(&mut current, &current_name).await {
+            Ok(ack_floor) => ack_floor + 1,
+            Err(err) => {
+                active.switch(current_name);
+                return Err(err);
+            }
+        };
+
+        let next_name = spec.versioned_name(current_version + 1);
+        let deliver_policy = DeliverPolicy::ByStartSequence {
+            start_sequence: handoff_sequence,
+        };
+        if let Err(err) = Self::create(&stream, spec, next_name.clone(), deliver_policy).await {
+            active.switch(current_name);
+            return Err(err);
+        }
+
+        active.switch(next_name.clone());
+        Self::delete_stale(&stream, &versions).await;
+
+        tracing::info!(
+            target: TRACING_TARGET_STREAM,
+            from = %current_name,
+            to = %next_name,
+            handoff_sequence,
+            "Migrated durable consumer"
+        );
+
+        Ok(MigrationOutcome::Migrated {
+            from: current_name,
+            to: next_name,
+            handoff_sequence,
+        })
+    }
+
+    /// Returns the newest existing version of the consumer, if any.
+    pub async fn latest(&self, spec: &ConsumerSpec) -> Result<Option<String>> {
+        let stream = self.get_stream(spec).await?;
+        let versions = Self::versions(&stream, spec).await?;
+        Ok(versions.into_iter().last().map(|(_, name)| name))
+    }
+
+    /// Waits for the consumer's in-flight messages to be acknowledged and
+    /// returns its ack floor.
+    ///
+    /// On timeout the current floor is used anyway: unacknowledged messages
+    /// sit above it and are redelivered by the next version.
+    async fn drain(
+        &self,
+        consumer: &mut consumer::Consumer<consumer::pull::Config>,
+        name: &str,
+    ) -> Result<u64> {
+        let deadline = Instant::now() + self.drain_timeout;
+
+        loop {
+            let info = consumer
+                .info()
+                .await
+                .map_err(|e| Error::consumer_error(name, e.to_string()))?;
+
+            if info.num_ack_pending == 0 || Instant::now() >= deadline {
+                if info.num_ack_pending > 0 {
+                    tracing::warn!(
+                        target: TRACING_TARGET_STREAM,
+                        consumer = %name,
+                        ack_pending = info.num_ack_pending,
+                        "Drain timed out, unacknowledged messages will be redelivered"
+                    );
+                }
+                return Ok(info.ack_floor.stream_sequence);
+            }
+
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn get_stream(&self, spec: &ConsumerSpec) -> Result<stream::Stream> {
+        self.jetstream
+            .get_stream(&spec.stream_name)
+            .await
+            .map_err(|e| Error::stream_error(&spec.stream_name, e.to_string()))
+    }
+
+    /// Lists existing versions of the consumer, oldest first.
+    async fn versions(stream: &stream::Stream, spec: &ConsumerSpec) -> Result<Vec<(u32, String)>> {
+        let names: Vec<String> = stream
+            .consumer_names()
+            .try_collect()
+            .await
+            .map_err(|e| Error::stream_error(&spec.stream_name, e.to_string()))?;
+
+        let mut versions: Vec<_> = names
+            .into_iter()
+            .filter_map(|name| spec.parse_version(&name).map(|version| (version, name)))
+            .collect();
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    async fn get(
+        stream: &stream::Stream,
+        name: &str,
+    ) -> Result<consumer::Consumer<consumer::pull::Config>> {
+        stream
+            .get_consumer(name)
+            .await
+            .map_err(|e| Error::consumer_error(name, e.to_string()))
+    }
+
+    async fn create(
+        stream: &stream::Stream,
+        spec: &ConsumerSpec,
+        name: String,
+        deliver_policy: DeliverPolicy,
+    ) -> Result<()> {
+        stream
+            .create_consumer(spec.to_config(name.clone(), deliver_policy))
+            .await
+            .map_err(|e| Error::consumer_error(&name, e.to_string()))?;
+        Ok(())
+    }
+
+    /// Deletes superseded versions. Failures only leave an idle consumer
+    /// behind, which the next reconcile retries.
+    async fn delete_stale(stream: &stream::Stream, versions: &[(u32, String)]) {
+        for (_, name) in versions {
+            if let Err(err) = stream.delete_consumer(name).await {
+                tracing::warn!(
+                    target: TRACING_TARGET_STREAM,
+                    consumer = %name,
+                    error = %err,
+                    "Failed to delete superseded consumer"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::WebhookStream;
+
+    #[test]
+    fn test_versioned_names_roundtrip() {
+        let spec = ConsumerSpec::for_stream::<WebhookStream>();
+        assert_eq!(spec.versioned_name(0), "webhook-worker");
+        assert_eq!(spec.versioned_name(3), "webhook-worker-v3");
+        assert_eq!(spec.parse_version("webhook-worker"), Some(0));
+        assert_eq!(spec.parse_version("webhook-worker-v3"), Some(3));
+        assert_eq!(spec.parse_version("webhook-worker-v0"), None);
+        assert_eq!(spec.parse_version("webhook-worker-old"), None);
+        assert_eq!(spec.parse_version("run-progress-watcher"), None);
+    }
+
+    #[test]
+    fn test_matches_server_defaults() {
+        let spec = ConsumerSpec::for_stream::<WebhookStream>();
+        let config = consumer::Config {
+            durable_name: Some("webhook-worker".to_owned()),
+            ack_policy: AckPolicy::Explicit,
+            filter_subject: "WEBHOOKS.>".to_owned(),
+            ack_wait: Duration::from_secs(30),
+            max_deliver: -1,
+            max_ack_pending: 1000,
+            ..Default::default()
+        };
+        assert!(spec.matches(&config));
+
+        let spec = spec.with_max_deliver(10);
+        assert!(!spec.matches(&config));
+    }
+
+    #[tokio::test]
+    async fn test_active_consumer_switch() {
+        let active = ActiveConsumer::paused();
+        let mut receiver = active.watch();
+        assert_eq!(active.name(), None);
+
+        active.switch("webhook-worker-v2".to_owned());
+        let name = ActiveConsumer::wait_active(&mut receiver).await.unwrap();
+        assert_eq!(name, "webhook-worker-v2");
+    }
+}
@@ -54,6 +54,21 @@ where
         self.publisher.publish(&subject, event).await
     }
 
+    /// Publish an event under a sub-subject with a deduplication message id.
+    ///
+    /// See [`StreamPublisher::publish_with_id`].
+    pub async fn publish_to_with_id(
+        &self,
+        sub_subject: &str,
+        message_id: &str,
+        event: &T,
+    ) -> Result<()> {
+        let subject = format!("{}.{}", S::SUBJECT, sub_subject);
+        self.publisher
+            .publish_with_id(&subject, message_id, event)
+            .await
+    }
+
     /// Publish multiple events to the stream's configured subject.
     pub async fn publish_batch(&self, events: &[T]) -> Result<()>
     where
@@ -33,6 +33,34 @@ impl EventStream for WebhookStream {
     const SUBJECT: &'static str = "webhooks";
 }
 
+/// Stream for pipeline run progress updates.
+///
+/// Updates are short-lived: only the latest per run is of interest, so
+/// messages expire after 1 hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RunProgressStream;
+
+impl EventStream for RunProgressStream {
+    const CONSUMER_NAME: &'static str = "run-progress-watcher";
+    const MAX_AGE: Option<Duration> = Some(Duration::from_secs(60 * 60));
+    const NAME: &'static str = "RUN_PROGRESS";
+    const SUBJECT: &'static str = "progress";
+}
+
+/// Stream for workspace domain events.
+///
+/// Clients that reconnect resume from the last event they saw, so messages
+/// are retained for 1 day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WorkspaceEventStream;
+
+impl EventStream for WorkspaceEventStream {
+    const CONSUMER_NAME: &'static str = "workspace-event-watcher";
+    const MAX_AGE: Option<Duration> = Some(Duration::from_secs(24 * 60 * 60));
+    const NAME: &'static str = "WORKSPACE_EVENTS";
+    const SUBJECT: &'static str = "events";
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,4 +75,24 @@ mod tests {
         );
         assert_eq!(WebhookStream::CONSUMER_NAME, "webhook-worker");
     }
+
+    #[test]
+    fn test_run_progress_stream() {
+        assert_eq!(RunProgressStream::NAME, "RUN_PROGRESS");
+        assert_eq!(RunProgressStream::SUBJECT, "progress");
+        assert_eq!(
+            RunProgressStream::MAX_AGE,
+            Some(Duration::from_secs(60 * 60))
+        );
+    }
+
+    #[test]
+    fn test_workspace_event_stream() {
+        assert_eq!(WorkspaceEventStream::NAME, "WORKSPACE_EVENTS");
+        assert_eq!(WorkspaceEventStream::SUBJECT, "events");
+        assert_eq!(
+            WorkspaceEventStream::MAX_AGE,
+            Some(Duration::from_secs(24 * 60 * 60))
+        );
+    }
 }
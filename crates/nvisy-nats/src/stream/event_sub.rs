@@ -3,11 +3,13 @@
 use std::marker::PhantomData;
 
 use async_nats::jetstream::Context;
+use async_nats::jetstream::consumer::DeliverPolicy;
 use derive_more::{Deref, DerefMut};
 use serde::de::DeserializeOwned;
 
+use super::consumer_migration::{ActiveConsumer, ConsumerMigrator, ConsumerSpec};
 use super::event_stream::EventStream;
-use super::stream_sub::StreamSubscriber;
+use super::stream_sub::{StreamSubscriber, TypedMessageStream};
 use crate::Result;
 
 /// Generic event subscriber for consuming typed events.
@@ -47,6 +49,61 @@ where
         })
     }
 
+    /// Subscribe through the consumer version `active` points at.
+    ///
+    /// Waits while a migration has subscribers paused. If the version no
+    /// longer exists because another process migrated it, the newest version
+    /// is looked up and `active` is switched to it.
+    pub async fn subscribe_active(&self, active: &ActiveConsumer) -> Result<TypedMessageStream<T>> {
+        let name = ActiveConsumer::wait_active(&mut active.watch()).await?;
+        match self.subscriber.subscribe_consumer(&name).await {
+            Ok(stream) => Ok(stream),
+            Err(err) => {
+                let spec = ConsumerSpec::for_stream::<S>();
+                let migrator = ConsumerMigrator::new(self.subscriber.jetstream().clone());
+                match migrator.latest(&spec).await? {
+                    Some(latest) if latest != name => {
+                        active.switch(latest.clone());
+                        self.subscriber.subscribe_consumer(&latest).await
+                    }
+                    _ => Err(err),
+                }
+            }
+        }
+    }
+
+    /// Watch a single sub-subject with an ephemeral consumer.
+    ///
+    /// Receives events published to `{stream_subject}.{sub_subject}`, starting
+    /// from the most recent one, without affecting the durable consumer.
+    pub async fn watch(&self, sub_subject: &str) -> Result<TypedMessageStream<T>> {
+        let subject = format!("{}.{}.{}", S::NAME, S::SUBJECT, sub_subject);
+        self.subscriber.subscribe_subject(&subject).await
+    }
+
+    /// Follow a sub-subject (wildcards allowed) with an ephemeral consumer.
+    ///
+    /// Delivers only new events, or — when `after_sequence` is given — every
+    /// retained event with a greater stream sequence, so a reconnecting
+    /// client can resume where it left off.
+    pub async fn follow(
+        &self,
+        sub_subject: &str,
+        after_sequence: Option<u64>,
+    ) -> Result<TypedMessageStream<T>> {
+        let subject = format!("{}.{}.{}", S::NAME, S::SUBJECT, sub_subject);
+        let deliver_policy = match after_sequence {
+            Some(sequence) => DeliverPolicy::ByStartSequence {
+                start_sequence: sequence.saturating_add(1),
+            },
+            None => DeliverPolicy::New,
+        };
+
+        self.subscriber
+            .subscribe_subject_with(&subject, deliver_policy)
+            .await
+    }
+
     /// Returns the stream name.
     #[inline]
     pub fn stream_name(&self) -> &'static str {
@@ -0,0 +1,165 @@
+//! Consumer-side deduplication of redelivered messages.
+//!
+//! JetStream delivers at least once: a message whose acknowledgement is lost,
+//! or whose consumer dies after acting on it, comes back. An [`Inbox`]
+//! records the ids of the messages a consumer has processed, and
+//! [`Inbox::process_once`] acknowledges redeliveries of those without
+//! running the handler again. Together with publishing through the outbox,
+//! whose message ids are stable across retries, an effect is applied once.
+//!
+//! The record is written after the handler succeeds and before the message
+//! is acknowledged. A crash between the handler and the record still
+//! redelivers the message, so handlers writing to Postgres should record the
+//! id in the same transaction through an [`InboxStore`] of their own.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use super::stream_sub::TypedMessage;
+use crate::kv::{InboxKey, KvStore, ProcessedMessage, ProcessedMessagesBucket};
+use crate::{Result, TRACING_TARGET_STREAM};
+
+/// Storage for the ids of processed messages.
+#[async_trait::async_trait]
+pub trait InboxStore: Send + Sync {
+    /// Returns whether `consumer` already processed `message_id`.
+    async fn is_processed(&self, consumer: &str, message_id: &str) -> Result<bool>;
+
+    /// Records that `consumer` processed `message_id`.
+    async fn mark_processed(&self, consumer: &str, message_id: &str) -> Result<()>;
+}
+
+/// [`InboxStore`] backed by the processed messages KV bucket.
+#[derive(Clone)]
+pub struct KvInboxStore {
+    store: KvStore<InboxKey, ProcessedMessage, ProcessedMessagesBucket>,
+}
+
+impl KvInboxStore {
+    /// Wraps the processed messages store.
+    pub fn new(store: KvStore<InboxKey, ProcessedMessage, ProcessedMessagesBucket>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait::async_trait]
+impl InboxStore for KvInboxStore {
+    async fn is_processed(&self, consumer: &str, message_id: &str) -> Result<bool> {
+        self.store
+            .exists(&InboxKey::new(consumer, message_id))
+            .await
+    }
+
+    async fn mark_processed(&self, consumer: &str, message_id: &str) -> Result<()> {
+        self.store
+            .put(
+                &InboxKey::new(consumer, message_id),
+                &ProcessedMessage::now(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Outcome of [`Inbox::process_once`].
+#[derive(Debug)]
+pub enum InboxOutcome<E> {
+    /// The handler ran and the message was acknowledged.
+    Handled,
+    /// The message was processed before; it was acknowledged without
+    /// running the handler.
+    Duplicate,
+    /// The handler failed; the message was negatively acknowledged for
+    /// redelivery.
+    Failed(E),
+}
+
+impl<E> InboxOutcome<E> {
+    /// Returns whether the message was skipped as a redelivery.
+    #[inline]
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, Self::Duplicate)
+    }
+}
+
+/// Processed message record for one consumer.
+///
+/// Cheap to clone (the store is shared through an `Arc`).
+#[derive(Clone)]
+pub struct Inbox {
+    consumer: String,
+    store: Arc<dyn InboxStore>,
+}
+
+impl Inbox {
+    /// Creates the inbox of `consumer` over `store`.
+    pub fn new(consumer: impl Into<String>, store: impl InboxStore + 'static) -> Self {
+        Self {
+            consumer: consumer.into(),
+            store: Arc::new(store),
+        }
+    }
+
+    /// Returns the consumer name.
+    #[inline]
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
+    /// Runs `handler` on `message` unless it was processed before, then
+    /// acknowledges it.
+    ///
+    /// A failing handler leaves the message unrecorded and negatively
+    /// acknowledged, so the redelivery runs it again. A failure to record a
+    /// successful run is logged rather than returned: the message is still
+    /// acknowledged, since the record only matters if it comes back.
+    pub async fn process_once<'m, T, F, Fut, E>(
+        &self,
+        message: &'m TypedMessage<T>,
+        handler: F,
+    ) -> Result<InboxOutcome<E>>
+    where
+        F: FnOnce(&'m T) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+    {
+        let message_id = message.message_id()?;
+
+        if self.store.is_processed(&self.consumer, &message_id).await? {
+            tracing::debug!(
+                target: TRACING_TARGET_STREAM,
+                consumer = %self.consumer,
+                message_id = %message_id,
+                "Skipping already processed message"
+            );
+            message.ack().await?;
+            return Ok(InboxOutcome::Duplicate);
+        }
+
+        if let Err(err) = handler(message.payload()).await {
+            message.nack().await?;
+            return Ok(InboxOutcome::Failed(err));
+        }
+
+        if let Err(err) = self.store.mark_processed(&self.consumer, &message_id).await {
+            tracing::warn!(
+                target: TRACING_TARGET_STREAM,
+                consumer = %self.consumer,
+                message_id = %message_id,
+                error = %err,
+                "Failed to record processed message"
+            );
+        }
+
+        message.ack().await?;
+        Ok(InboxOutcome::Handled)
+    }
+}
+
+impl fmt::Debug for Inbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inbox")
+            .field("consumer", &self.consumer)
+            .finish_non_exhaustive()
+    }
+}
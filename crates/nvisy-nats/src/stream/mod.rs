@@ -1,16 +1,40 @@
 //! JetStream streams for real-time updates and distributed job processing.
 //!
 //! This module provides type-safe streaming capabilities: generic event
-//! publishing and subscribing over a stream configured via [`EventStream`].
+//! publishing and subscribing over a stream configured via [`EventStream`],
+//! progress reporting for long-running pipeline runs ([`RunProgress`]), and
+//! real-time workspace domain events ([`WorkspaceEvent`]).
+//!
+//! Durable consumers are versioned: [`ConsumerMigrator`] moves one to a new
+//! configuration by handing its stream position to a new version and
+//! switching subscribers through an [`ActiveConsumer`] handle.
+//!
+//! Consumers whose handlers have effects deduplicate redeliveries through an
+//! [`Inbox`].
 
+mod consumer_migration;
 mod event_pub;
 mod event_stream;
 mod event_sub;
+mod inbox;
+mod run_progress;
 mod stream_pub;
 mod stream_sub;
+mod workspace_event;
 
+pub use consumer_migration::{
+    ActiveConsumer, ConsumerMigrator, ConsumerSpec, DEFAULT_DRAIN_TIMEOUT, MigrationOutcome,
+};
 pub use event_pub::EventPublisher;
-pub use event_stream::{EventStream, WebhookStream};
+pub use event_stream::{EventStream, RunProgressStream, WebhookStream, WorkspaceEventStream};
 pub use event_sub::EventSubscriber;
+pub use inbox::{Inbox, InboxOutcome, InboxStore, KvInboxStore};
+pub use run_progress::{
+    DEFAULT_PROGRESS_INTERVAL, ProgressReporter, RunProgress, RunProgressPublisher, RunStage,
+    progress_subject,
+};
 pub use stream_pub::StreamPublisher;
 pub use stream_sub::{StreamSubscriber, TypedBatchStream, TypedMessage, TypedMessageStream};
+pub use workspace_event::{
+    WorkspaceEvent, WorkspaceEventPublisher, workspace_event_subject, workspace_events_filter,
+};
@@ -0,0 +1,406 @@
+//! Progress updates for long-running pipeline runs.
+//!
+//! Workers publish [`RunProgress`] snapshots through a [`ProgressReporter`]
+//! as a run enters each stage and while it works through pages; subscribers
+//! watch the run's subject to stream them to clients.
+
+use std::time::{Duration, Instant};
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::event_pub::EventPublisher;
+use super::event_stream::RunProgressStream;
+use crate::{Result, TRACING_TARGET_STREAM};
+
+/// Type alias for the run progress publisher.
+pub type RunProgressPublisher = EventPublisher<RunProgress, RunProgressStream>;
+
+/// Default minimum interval between two published page-level updates.
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Processing stage a run is currently in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStage {
+    /// Accepted but not yet picked up.
+    #[default]
+    Queued,
+    /// Fetching and decrypting the source document.
+    Loading,
+    /// Extracting text from pages (OCR).
+    Extracting,
+    /// Running recognizers over the extracted content.
+    Analyzing,
+    /// Detection finished; the run awaits reviewer verification.
+    Analyzed,
+    /// Applying redaction policies.
+    Redacting,
+    /// Persisting results and artifacts.
+    Storing,
+    /// The run finished successfully.
+    Completed,
+    /// The run failed.
+    Failed,
+}
+
+impl RunStage {
+    /// Returns whether no further updates follow this stage.
+    #[inline]
+    pub const fn is_terminal(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+
+    /// Returns whether processing has stopped, either because the run
+    /// finished or because it awaits review.
+    #[inline]
+    pub const fn is_settled(self) -> bool {
+        matches!(self, Self::Analyzed | Self::Completed | Self::Failed)
+    }
+
+    /// Returns the overall completion reached on entering this stage.
+    ///
+    /// Detection (loading, extracting, analyzing) and redaction (redacting,
+    /// storing) each end in a settled stage at 100.
+    pub const fn percent(self) -> u8 {
+        match self {
+            Self::Queued | Self::Failed => 0,
+            Self::Loading => 10,
+            Self::Extracting => 20,
+            Self::Analyzing => 30,
+            Self::Redacting => 50,
+            Self::Storing => 80,
+            Self::Analyzed | Self::Completed => 100,
+        }
+    }
+
+    /// Returns the overall completion reached on leaving this stage.
+    ///
+    /// Page-level progress within the stage moves between [`percent`] and
+    /// this value.
+    ///
+    /// [`percent`]: Self::percent
+    pub const fn ceiling(self) -> u8 {
+        match self {
+            Self::Queued => 10,
+            Self::Loading => 20,
+            Self::Extracting => 30,
+            Self::Analyzing | Self::Redacting => 80,
+            Self::Failed => 0,
+            Self::Storing | Self::Analyzed | Self::Completed => 100,
+        }
+    }
+}
+
+/// A point-in-time progress snapshot of a pipeline run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunProgress {
+    /// Run this update belongs to.
+    pub run_id: Uuid,
+    /// Workspace that owns the run.
+    pub workspace_id: Uuid,
+    /// Current processing stage.
+    pub stage: RunStage,
+    /// Overall completion, from 0 to 100.
+    pub percent: u8,
+    /// Pages processed so far, when the page count is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages_done: Option<u32>,
+    /// Total pages to process, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages_total: Option<u32>,
+    /// Estimated seconds until the current phase settles, when it can be
+    /// estimated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<u64>,
+    /// When this update was produced.
+    pub updated_at: Timestamp,
+}
+
+impl RunProgress {
+    /// Creates an update for entering the given stage with no page
+    /// information.
+    pub fn new(workspace_id: Uuid, run_id: Uuid, stage: RunStage) -> Self {
+        Self {
+            run_id,
+            workspace_id,
+            stage,
+            percent: stage.percent(),
+            pages_done: None,
+            pages_total: None,
+            eta_seconds: None,
+            updated_at: Timestamp::now(),
+        }
+    }
+
+    /// Attaches page counts, moving the percentage through the stage's range
+    /// in proportion to the pages done.
+    #[must_use]
+    pub fn with_pages(mut self, pages_done: u32, pages_total: u32) -> Self {
+        let pages_done = pages_done.min(pages_total);
+        self.pages_done = Some(pages_done);
+        self.pages_total = Some(pages_total);
+
+        let floor = self.stage.percent();
+        let span = self.stage.ceiling().saturating_sub(floor);
+        self.percent = floor + percent_of(span, pages_done, pages_total);
+        self
+    }
+
+    /// Attaches an ETA extrapolated from the time elapsed since the run
+    /// started and the completion reached so far.
+    ///
+    /// Settled stages have nothing left to wait for and get no ETA.
+    #[must_use]
+    pub fn with_eta(mut self, elapsed: Duration) -> Self {
+        self.eta_seconds = if self.stage.is_settled() {
+            None
+        } else {
+            estimate_eta(elapsed, u32::from(self.percent), 100).map(|eta| eta.as_secs())
+        };
+        self
+    }
+
+    /// Returns the subject suffix this update is published under.
+    ///
+    /// Updates are routed per run as `{workspace_id}.{run_id}`.
+    pub fn sub_subject(&self) -> String {
+        progress_subject(self.workspace_id, self.run_id)
+    }
+}
+
+/// Returns the subject suffix carrying updates for one run.
+pub fn progress_subject(workspace_id: Uuid, run_id: Uuid) -> String {
+    format!("{workspace_id}.{run_id}")
+}
+
+/// Returns `done / total` of `span`, clamped to `span`.
+fn percent_of(span: u8, done: u32, total: u32) -> u8 {
+    if total == 0 {
+        return 0;
+    }
+
+    ((u64::from(span) * u64::from(done)) / u64::from(total)).min(u64::from(span)) as u8
+}
+
+/// Estimates the remaining time by linear extrapolation of the elapsed time.
+///
+/// Returns `None` until at least one unit of work has completed.
+fn estimate_eta(elapsed: Duration, done: u32, total: u32) -> Option<Duration> {
+    if done == 0 || total == 0 {
+        return None;
+    }
+
+    let remaining = total.saturating_sub(done);
+    Some(elapsed.mul_f64(f64::from(remaining) / f64::from(done)))
+}
+
+/// Publisher helper that workers call as a run enters each stage and while
+/// it works through pages.
+///
+/// Every update carries an ETA measured from when the reporter was created,
+/// and the page count once it is known. Page-level updates are throttled to
+/// at most one per `min_interval`; stage transitions and terminal updates are
+/// always published.
+pub struct ProgressReporter {
+    publisher: RunProgressPublisher,
+    workspace_id: Uuid,
+    run_id: Uuid,
+    started_at: Instant,
+    min_interval: Duration,
+    pages_total: Option<u32>,
+    last_stage: Option<RunStage>,
+    last_published: Option<Instant>,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter for one run, starting its clock now.
+    pub fn new(publisher: RunProgressPublisher, workspace_id: Uuid, run_id: Uuid) -> Self {
+        Self {
+            publisher,
+            workspace_id,
+            run_id,
+            started_at: Instant::now(),
+            min_interval: DEFAULT_PROGRESS_INTERVAL,
+            pages_total: None,
+            last_stage: None,
+            last_published: None,
+        }
+    }
+
+    /// Sets the minimum interval between page-level updates.
+    #[must_use]
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Records the document's page count, attached to every later update.
+    pub fn set_pages_total(&mut self, pages_total: Option<u32>) {
+        self.pages_total = pages_total;
+    }
+
+    /// Reports entering a stage.
+    ///
+    /// With a known page count, stages before storing report no pages done
+    /// and storing and the settled stages after it report all of them.
+    pub async fn stage(&mut self, stage: RunStage) -> Result<()> {
+        let mut progress = RunProgress::new(self.workspace_id, self.run_id, stage);
+        if let Some(pages_total) = self.pages_total {
+            let pages_done = if stage.percent() >= RunStage::Storing.percent() {
+                pages_total
+            } else {
+                0
+            };
+            progress.pages_done = Some(pages_done);
+            progress.pages_total = Some(pages_total);
+        }
+
+        self.publish(progress.with_eta(self.started_at.elapsed()))
+            .await
+    }
+
+    /// Reports page-level progress within a stage.
+    ///
+    /// Returns `false` when the update was dropped by throttling.
+    pub async fn pages(
+        &mut self,
+        stage: RunStage,
+        pages_done: u32,
+        pages_total: u32,
+    ) -> Result<bool> {
+        let stage_changed = self.last_stage != Some(stage);
+        let finished = pages_done >= pages_total;
+        let throttled = self
+            .last_published
+            .is_some_and(|last| last.elapsed() < self.min_interval);
+
+        if throttled && !stage_changed && !finished {
+            return Ok(false);
+        }
+
+        self.pages_total = Some(pages_total);
+        let progress = RunProgress::new(self.workspace_id, self.run_id, stage)
+            .with_pages(pages_done, pages_total)
+            .with_eta(self.started_at.elapsed());
+        self.publish(progress).await?;
+        Ok(true)
+    }
+
+    /// Reports that the run completed successfully.
+    pub async fn complete(&mut self) -> Result<()> {
+        self.stage(RunStage::Completed).await
+    }
+
+    /// Reports that the run failed.
+    pub async fn fail(&mut self) -> Result<()> {
+        self.stage(RunStage::Failed).await
+    }
+
+    async fn publish(&mut self, progress: RunProgress) -> Result<()> {
+        self.publisher
+            .publish_to(&progress.sub_subject(), &progress)
+            .await?;
+
+        tracing::trace!(
+            target: TRACING_TARGET_STREAM,
+            run_id = %self.run_id,
+            stage = ?progress.stage,
+            percent = progress.percent,
+            eta_seconds = progress.eta_seconds,
+            "Published run progress"
+        );
+
+        self.last_stage = Some(progress.stage);
+        self.last_published = Some(Instant::now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_percent() {
+        let progress = RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Analyzing);
+        assert_eq!(progress.percent, 30);
+        assert_eq!(RunStage::Completed.percent(), 100);
+        assert!(RunStage::Loading.percent() < RunStage::Storing.percent());
+    }
+
+    #[test]
+    fn test_percent_of() {
+        assert_eq!(percent_of(100, 0, 0), 0);
+        assert_eq!(percent_of(100, 0, 10), 0);
+        assert_eq!(percent_of(100, 5, 10), 50);
+        assert_eq!(percent_of(50, 10, 10), 50);
+        assert_eq!(percent_of(100, 1, 3), 33);
+    }
+
+    #[test]
+    fn test_estimate_eta() {
+        assert_eq!(estimate_eta(Duration::from_secs(10), 0, 10), None);
+        assert_eq!(
+            estimate_eta(Duration::from_secs(10), 5, 10),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            estimate_eta(Duration::from_secs(10), 10, 10),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_with_pages_stays_within_stage() {
+        let progress =
+            RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Extracting).with_pages(12, 10);
+        assert_eq!(progress.pages_done, Some(10));
+        assert_eq!(progress.percent, RunStage::Extracting.ceiling());
+
+        let progress =
+            RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Analyzing).with_pages(5, 10);
+        assert_eq!(progress.percent, 55);
+    }
+
+    #[test]
+    fn test_with_eta() {
+        let progress = RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Storing)
+            .with_eta(Duration::from_secs(40));
+        assert_eq!(progress.eta_seconds, Some(10));
+
+        let progress = RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Queued)
+            .with_eta(Duration::from_secs(40));
+        assert_eq!(progress.eta_seconds, None);
+
+        let progress = RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Analyzed)
+            .with_eta(Duration::from_secs(40));
+        assert_eq!(progress.eta_seconds, None);
+    }
+
+    #[test]
+    fn test_progress_serialization() {
+        let progress = RunProgress::new(Uuid::nil(), Uuid::nil(), RunStage::Analyzing);
+        let json = serde_json::to_value(&progress).unwrap();
+        assert_eq!(json["stage"], "analyzing");
+        assert_eq!(json["percent"], 30);
+        assert!(json.get("pagesDone").is_none());
+        assert!(json.get("etaSeconds").is_none());
+
+        let json = serde_json::to_value(progress.with_pages(2, 4)).unwrap();
+        assert_eq!(json["pagesDone"], 2);
+        assert_eq!(json["pagesTotal"], 4);
+    }
+
+    #[test]
+    fn test_terminal_stages() {
+        assert!(RunStage::Completed.is_terminal());
+        assert!(RunStage::Failed.is_terminal());
+        assert!(!RunStage::Extracting.is_terminal());
+        assert!(!RunStage::Analyzed.is_terminal());
+        assert!(RunStage::Analyzed.is_settled());
+    }
+}
@@ -4,6 +4,8 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_nats::HeaderMap;
+use async_nats::header::NATS_MESSAGE_ID;
 use async_nats::jetstream::{Context, stream};
 use serde::Serialize;
 use tokio::sync::Semaphore;
@@ -103,6 +105,37 @@ where
         Ok(())
     }
 
+    /// Publish an event with a message id for server-side deduplication.
+    ///
+    /// JetStream drops a message whose id it has already seen within the
+    /// stream's duplicate window, making retried publishes idempotent.
+    #[tracing::instrument(skip(self, event), target = TRACING_TARGET_STREAM)]
+    pub async fn publish_with_id(&self, subject: &str, message_id: &str, event: &T) -> Result<()> {
+        let full_subject = format!("{}.{}", self.inner.stream_name, subject);
+        let payload = serde_json::to_vec(event).map_err(Error::Serialization)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(NATS_MESSAGE_ID, message_id);
+
+        let ack = self
+            .inner
+            .jetstream
+            .publish_with_headers(full_subject.clone(), headers, payload.into())
+            .await
+            .map_err(|e| Error::delivery_failed(&full_subject, e.to_string()))?
+            .await
+            .map_err(|e| Error::operation("stream_publish", e.to_string()))?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_STREAM,
+            subject = %full_subject,
+            message_id = %message_id,
+            duplicate = ack.duplicate,
+            "Published typed event with message id"
+        );
+        Ok(())
+    }
+
     /// Publish multiple events in batch with parallel processing
     #[tracing::instrument(skip(self, events), target = TRACING_TARGET_STREAM)]
     pub async fn publish_batch(&self, subject: &str, events: &[T]) -> Result<()>
@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_nats::header::NATS_MESSAGE_ID;
 use async_nats::jetstream::consumer::{self, Consumer};
 use async_nats::jetstream::{self, Context, Message, stream};
 use futures::StreamExt;
@@ -208,6 +209,107 @@ where
         })
     }
 
+    /// Subscribe through an existing durable consumer by name.
+    ///
+    /// Unlike [`subscribe`](Self::subscribe), the consumer is not created: it
+    /// must already have been provisioned, e.g. by a [`ConsumerMigrator`].
+    ///
+    /// [`ConsumerMigrator`]: super::ConsumerMigrator
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_STREAM)]
+    pub async fn subscribe_consumer(&self, consumer_name: &str) -> Result<TypedMessageStream<T>> {
+        let stream = self
+            .inner
+            .jetstream
+            .get_stream(&self.inner.stream_name)
+            .await
+            .map_err(|e| {
+                Error::stream_error(
+                    &self.inner.stream_name,
+                    format!("Failed to get stream: {}", e),
+                )
+            })?;
+
+        let consumer = stream
+            .get_consumer::<consumer::pull::Config>(consumer_name)
+            .await
+            .map_err(|e| {
+                Error::consumer_error(consumer_name, format!("Failed to get consumer: {}", e))
+            })?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_STREAM,
+            stream = %self.inner.stream_name,
+            consumer = %consumer_name,
+            "Subscribed to stream through provisioned consumer"
+        );
+
+        Ok(TypedMessageStream {
+            consumer,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Subscribe to a single subject with an ephemeral consumer.
+    ///
+    /// Unlike [`subscribe`](Self::subscribe), the consumer is not durable: it
+    /// is not acknowledged against and the server removes it once idle.
+    /// Delivery starts from the last message on the subject, so a late
+    /// subscriber immediately observes the current state.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_STREAM)]
+    pub async fn subscribe_subject(&self, subject: &str) -> Result<TypedMessageStream<T>> {
+        self.subscribe_subject_with(subject, consumer::DeliverPolicy::LastPerSubject)
+            .await
+    }
+
+    /// Subscribe to a subject (wildcards allowed) with an ephemeral consumer
+    /// that starts delivery according to `deliver_policy`.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_STREAM)]
+    pub async fn subscribe_subject_with(
+        &self,
+        subject: &str,
+        deliver_policy: consumer::DeliverPolicy,
+    ) -> Result<TypedMessageStream<T>> {
+        let consumer_config = consumer::pull::Config {
+            description: Some(format!(
+                "Ephemeral consumer for stream {}",
+                self.inner.stream_name
+            )),
+            ack_policy: consumer::AckPolicy::None,
+            deliver_policy,
+            filter_subject: subject.to_string(),
+            inactive_threshold: Duration::from_secs(60),
+            ..Default::default()
+        };
+
+        let stream = self
+            .inner
+            .jetstream
+            .get_stream(&self.inner.stream_name)
+            .await
+            .map_err(|e| {
+                Error::stream_error(
+                    &self.inner.stream_name,
+                    format!("Failed to get stream: {}", e),
+                )
+            })?;
+
+        let consumer = stream.create_consumer(consumer_config).await.map_err(|e| {
+            Error::consumer_error(subject, format!("Failed to create consumer: {}", e))
+        })?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_STREAM,
+            stream = %self.inner.stream_name,
+            subject = %subject,
+            "Subscribed to subject with ephemeral consumer"
+        );
+
+        Ok(TypedMessageStream {
+            consumer,
+            _marker: PhantomData,
+        })
+    }
+
     /// Get the stream name.
     #[inline]
     pub fn stream_name(&self) -> &str {
@@ -220,6 +322,12 @@ where
         &self.inner.consumer_name
     }
 
+    /// Get the JetStream context.
+    #[inline]
+    pub(crate) fn jetstream(&self) -> &Context {
+        &self.inner.jetstream
+    }
+
     /// Check if the stream and consumer are healthy and accessible.
     #[tracing::instrument(skip(self), target = TRACING_TARGET_STREAM)]
     pub async fn health_check(&self) -> Result<bool> {
@@ -490,7 +598,7 @@ impl<T> TypedMessage<T> {
     }
 
     /// Acknowledge the message.
-    pub async fn ack(&mut self) -> Result<()> {
+    pub async fn ack(&self) -> Result<()> {
         self.message
             .ack()
             .await
@@ -498,7 +606,7 @@ impl<T> TypedMessage<T> {
     }
 
     /// Negative acknowledge the message (trigger redelivery).
-    pub async fn nack(&mut self) -> Result<()> {
+    pub async fn nack(&self) -> Result<()> {
         self.message
             .ack_with(jetstream::AckKind::Nak(None))
             .await
@@ -520,6 +628,23 @@ impl<T> TypedMessage<T> {
         self.message.headers.as_ref()
     }
 
+    /// Returns the id identifying this message across redeliveries.
+    ///
+    /// Uses the publisher's `Nats-Msg-Id` header when present, so copies
+    /// published again under the same id share it; otherwise the stream name
+    /// and sequence.
+    pub fn message_id(&self) -> Result<String> {
+        if let Some(id) = self
+            .headers()
+            .and_then(|headers| headers.get(NATS_MESSAGE_ID))
+        {
+            return Ok(id.as_str().to_owned());
+        }
+
+        let info = self.info()?;
+        Ok(format!("{}:{}", info.stream, info.stream_sequence))
+    }
+
     /// Get message sequence number.
     pub fn sequence(&self) -> Result<u64> {
         self.info()
@@ -0,0 +1,82 @@
+//! Real-time workspace domain events.
+//!
+//! Every domain event (a file uploaded, a member removed, ...) is published
+//! as a [`WorkspaceEvent`] under `{workspace_id}.{event_subject}`, so that
+//! subscribers can follow a single workspace with a wildcard filter.
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::event_pub::EventPublisher;
+use super::event_stream::WorkspaceEventStream;
+
+/// Type alias for the workspace event publisher.
+pub type WorkspaceEventPublisher = EventPublisher<WorkspaceEvent, WorkspaceEventStream>;
+
+/// A domain event that occurred within a workspace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEvent {
+    /// Workspace the event occurred in.
+    pub workspace_id: Uuid,
+    /// Event name (e.g. `file:created`).
+    pub event: String,
+    /// Category of the affected resource (e.g. `file`).
+    pub resource_type: String,
+    /// Affected resource.
+    pub resource_id: Uuid,
+    /// Account that triggered the event, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triggered_by: Option<Uuid>,
+    /// Additional event-specific data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    /// When the event occurred.
+    pub occurred_at: Timestamp,
+}
+
+/// Returns the subject suffix an event is published under.
+///
+/// `event_subject` is the dot-separated event name (e.g. `file.created`).
+pub fn workspace_event_subject(workspace_id: Uuid, event_subject: &str) -> String {
+    format!("{workspace_id}.{event_subject}")
+}
+
+/// Returns the subject suffix matching every event of one workspace.
+pub fn workspace_events_filter(workspace_id: Uuid) -> String {
+    format!("{workspace_id}.>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subjects() {
+        let id = Uuid::nil();
+        assert_eq!(
+            workspace_event_subject(id, "file.created"),
+            format!("{id}.file.created")
+        );
+        assert_eq!(workspace_events_filter(id), format!("{id}.>"));
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = WorkspaceEvent {
+            workspace_id: Uuid::nil(),
+            event: "file:created".to_owned(),
+            resource_type: "file".to_owned(),
+            resource_id: Uuid::nil(),
+            triggered_by: None,
+            data: None,
+            occurred_at: Timestamp::UNIX_EPOCH,
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "file:created");
+        assert_eq!(json["resourceType"], "file");
+        assert!(json.get("triggeredBy").is_none());
+    }
+}
@@ -0,0 +1,267 @@
+//! Bulk deletion of objects under a prefix.
+//!
+//! Matching objects are listed and deleted in batches through
+//! [`ObjectStore::delete_stream`], which backends such as S3 serve with one
+//! `DeleteObjects` request per batch instead of a request per object.
+//!
+//! [`ObjectStore::delete_stream`]: object_store::ObjectStore::delete_stream
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use futures::{StreamExt, TryStreamExt, stream};
+use object_store::ObjectMeta;
+use object_store::path::Path;
+
+use super::{ObjectStoreClient, from_object_store};
+use crate::types::Error;
+
+/// Number of objects deleted per batch, the `DeleteObjects` maximum.
+const DELETE_BATCH_SIZE: usize = 1000;
+
+/// Callback invoked after every batch with the running totals.
+type ProgressFn = Arc<dyn Fn(&DeleteOutput) + Send + Sync>;
+
+/// Options for [`ObjectStoreClient::delete_by_prefix`] and
+/// [`ObjectStoreClient::delete_older_than`].
+#[derive(Clone, Default)]
+pub struct DeleteOptions {
+    /// Count the matching objects without deleting them.
+    pub dry_run: bool,
+    progress: Option<ProgressFn>,
+}
+
+impl DeleteOptions {
+    /// Options that only count the matching objects.
+    pub fn dry_run() -> Self {
+        Self {
+            dry_run: true,
+            ..Self::default()
+        }
+    }
+
+    /// Reports the running totals after every batch.
+    #[must_use]
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(&DeleteOutput) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+}
+
+impl fmt::Debug for DeleteOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeleteOptions")
+            .field("dry_run", &self.dry_run)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// Result of a bulk deletion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeleteOutput {
+    /// Number of objects that matched the filters.
+    pub matched: u64,
+    /// Number of objects deleted; zero for a dry run.
+    pub deleted: u64,
+    /// Total size of the matched objects, in bytes.
+    pub bytes: u64,
+    /// Whether this was a dry run.
+    pub dry_run: bool,
+}
+
+impl ObjectStoreClient {
+    /// Delete every object under `prefix`.
+    ///
+    /// Refuses an empty prefix, which would wipe the whole bucket.
+    #[tracing::instrument(name = "object.delete_by_prefix", skip(self, options), fields(prefix, dry_run = options.dry_run))]
+    pub async fn delete_by_prefix(
+        &self,
+        prefix: &str,
+        options: &DeleteOptions,
+    ) -> Result<DeleteOutput, Error> {
+        self.delete_matching(prefix, options, |_| true).await
+    }
+
+    /// Delete the objects under `prefix` last modified more than `age` ago.
+    ///
+    /// Refuses an empty prefix, which would sweep the whole bucket.
+    #[tracing::instrument(name = "object.delete_older_than", skip(self, options), fields(prefix, dry_run = options.dry_run))]
+    pub async fn delete_older_than(
+        &self,
+        prefix: &str,
+        age: Duration,
+        options: &DeleteOptions,
+    ) -> Result<DeleteOutput, Error> {
+        let cutoff = SystemTime::now()
+            .checked_sub(age)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.delete_matching(prefix, options, |meta| {
+            SystemTime::from(meta.last_modified) <= cutoff
+        })
+        .await
+    }
+
+    /// Delete the objects under `prefix` accepted by `filter`, in batches.
+    async fn delete_matching(
+        &self,
+        prefix: &str,
+        options: &DeleteOptions,
+        filter: impl Fn(&ObjectMeta) -> bool,
+    ) -> Result<DeleteOutput, Error> {
+        let prefix = Path::from(prefix);
+        if prefix.as_ref().is_empty() {
+            return Err(Error::runtime(
+                "refusing to bulk delete without a prefix",
+                "object-store",
+                false,
+            ));
+        }
+
+        let mut output = DeleteOutput {
+            dry_run: options.dry_run,
+            ..DeleteOutput::default()
+        };
+        let mut batch = Vec::with_capacity(DELETE_BATCH_SIZE);
+        let mut listing = self.store.list(Some(&prefix));
+
+        while let Some(meta) = listing.try_next().await.map_err(from_object_store)? {
+            if !filter(&meta) {
+                continue;
+            }
+
+            output.matched += 1;
+            output.bytes += meta.size;
+            batch.push(meta.location);
+
+            if batch.len() == DELETE_BATCH_SIZE {
+                self.delete_batch(&mut batch, options, &mut output).await?;
+            }
+        }
+        self.delete_batch(&mut batch, options, &mut output).await?;
+
+        tracing::info!(
+            prefix = %prefix,
+            matched = output.matched,
+            deleted = output.deleted,
+            bytes = output.bytes,
+            dry_run = output.dry_run,
+            "Bulk delete finished"
+        );
+        Ok(output)
+    }
+
+    /// Delete the objects in `batch` unless dry-running, then report progress.
+    async fn delete_batch(
+        &self,
+        batch: &mut Vec<Path>,
+        options: &DeleteOptions,
+        output: &mut DeleteOutput,
+    ) -> Result<(), Error> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let locations = std::mem::take(batch);
+        if !options.dry_run {
+            let locations = stream::iter(locations.into_iter().map(Ok)).boxed();
+            let mut deleted = self.store.delete_stream(locations);
+            while let Some(result) = deleted.next().await {
+                match result {
+                    Ok(_) => output.deleted += 1,
+                    // Deleted concurrently, e.g. by another sweep.
+                    Err(object_store::Error::NotFound { .. }) => {}
+                    Err(e) => return Err(from_object_store(e)),
+                }
+            }
+        }
+
+        if let Some(progress) = &options.progress {
+            progress(output);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use bytes::Bytes;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    async fn seeded_client() -> ObjectStoreClient {
+        let client = ObjectStoreClient::new(InMemory::new());
+        for key in ["stage/a.bin", "stage/b.bin", "keep/c.bin"] {
+            client.put(key, Bytes::from("xyz"), None).await.unwrap();
+        }
+        client
+    }
+
+    #[tokio::test]
+    async fn delete_by_prefix() {
+        let client = seeded_client().await;
+        let output = client
+            .delete_by_prefix("stage/", &DeleteOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(output.matched, 2);
+        assert_eq!(output.deleted, 2);
+        assert_eq!(output.bytes, 6);
+        assert!(client.list("stage/").await.unwrap().is_empty());
+        assert_eq!(client.list("keep/").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn delete_by_prefix_dry_run() {
+        let client = seeded_client().await;
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let options = DeleteOptions::dry_run()
+            .with_progress(move |output| sink.lock().unwrap().push(output.clone()));
+
+        let output = client.delete_by_prefix("stage", &options).await.unwrap();
+        assert_eq!(output.matched, 2);
+        assert_eq!(output.deleted, 0);
+        assert_eq!(reports.lock().unwrap().as_slice(), [output]);
+        assert_eq!(client.list("stage/").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn delete_refuses_empty_prefix() {
+        let client = seeded_client().await;
+        for prefix in ["", "/"] {
+            let err = client
+                .delete_by_prefix(prefix, &DeleteOptions::default())
+                .await
+                .unwrap_err();
+            assert!(!err.is_retryable());
+        }
+        assert_eq!(client.list("").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn delete_older_than() {
+        let client = seeded_client().await;
+        let options = DeleteOptions::default();
+
+        let output = client
+            .delete_older_than("stage/", Duration::from_secs(3600), &options)
+            .await
+            .unwrap();
+        assert_eq!(output.matched, 0);
+
+        let output = client
+            .delete_older_than("stage/", Duration::ZERO, &options)
+            .await
+            .unwrap();
+        assert_eq!(output.deleted, 2);
+    }
+}
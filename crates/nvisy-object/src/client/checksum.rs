@@ -0,0 +1,185 @@
+//! End-to-end content integrity checks.
+//!
+//! [`ObjectStoreClient::put_opts`] records the SHA-256 of every upload as a
+//! `sha256` user metadata attribute. [`ObjectStoreClient::get`] recomputes it
+//! on download and applies the client's [`ChecksumPolicy`];
+//! [`ObjectStoreClient::verify_object`] reports a [`ChecksumStatus`] instead,
+//! for background integrity sweeps. Objects uploaded without a checksum are
+//! never rejected. Checksums are computed with the client's
+//! [`CryptoProvider`], so they follow the deployment's crypto policy.
+//!
+//! Documents the server stores in NATS do not pass through this client; their
+//! downloads are checked against the digest NATS records on upload instead.
+
+use std::borrow::Cow;
+
+use nvisy_core::crypto::CryptoProvider;
+use object_store::{Attribute, Attributes, GetOptions};
+
+use super::{GetOutput, ObjectStoreClient};
+use crate::types::Error;
+
+/// Metadata attribute holding the hex SHA-256 of the content.
+pub(super) const ATTRIBUTE: Attribute = Attribute::Metadata(Cow::Borrowed("sha256"));
+
+/// How downloads react to content that does not match its checksum.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumPolicy {
+    /// Do not verify downloads.
+    Skip,
+    /// Log a warning and return the content anyway.
+    Warn,
+    /// Fail the download.
+    #[default]
+    Enforce,
+}
+
+/// Outcome of checking an object against its recorded checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    /// The content matches its checksum.
+    Verified,
+    /// The object was uploaded without a checksum.
+    Missing,
+    /// The content does not match its checksum.
+    Mismatch {
+        /// Hex SHA-256 recorded at upload.
+        expected: String,
+        /// Hex SHA-256 of the downloaded content.
+        actual: String,
+    },
+}
+
+impl ChecksumStatus {
+    /// Checks the content of `output` against its recorded checksum.
+    fn of(provider: &dyn CryptoProvider, output: &GetOutput) -> Self {
+        let Some(expected) = &output.checksum else {
+            return Self::Missing;
+        };
+
+        let actual = sha256_hex(provider, &output.data);
+        if actual.eq_ignore_ascii_case(expected) {
+            Self::Verified
+        } else {
+            Self::Mismatch {
+                expected: expected.clone(),
+                actual,
+            }
+        }
+    }
+}
+
+impl ObjectStoreClient {
+    /// Download the object at `key` and check it against its checksum.
+    ///
+    /// Ignores the client's [`ChecksumPolicy`]: a mismatch is reported in
+    /// the returned status rather than as an error.
+    #[tracing::instrument(name = "object.verify_object", skip(self), fields(key))]
+    pub async fn verify_object(&self, key: &str) -> Result<ChecksumStatus, Error> {
+        let output = self.get_unverified(key, GetOptions::default()).await?;
+        Ok(ChecksumStatus::of(self.crypto.as_ref(), &output))
+    }
+
+    /// Applies the client's [`ChecksumPolicy`] to a download.
+    pub(super) fn enforce_checksum(&self, key: &str, output: &GetOutput) -> Result<(), Error> {
+        if self.checksum_policy == ChecksumPolicy::Skip {
+            return Ok(());
+        }
+
+        let ChecksumStatus::Mismatch { expected, actual } =
+            ChecksumStatus::of(self.crypto.as_ref(), output)
+        else {
+            return Ok(());
+        };
+
+        if self.checksum_policy == ChecksumPolicy::Warn {
+            tracing::warn!(key, %expected, %actual, "object checksum mismatch");
+            return Ok(());
+        }
+
+        Err(Error::runtime(
+            format!("checksum mismatch for '{key}': expected {expected}, got {actual}"),
+            "object-store",
+            false,
+        ))
+    }
+}
+
+/// Returns the checksum recorded in `attributes`, if any.
+pub(super) fn stored(attributes: &Attributes) -> Option<String> {
+    attributes.get(&ATTRIBUTE).map(|v| v.to_string())
+}
+
+/// Returns the hex SHA-256 of `data`, computed by `provider`.
+pub(super) fn sha256_hex(provider: &dyn CryptoProvider, data: &[u8]) -> String {
+    hex::encode(provider.sha256(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{ObjectStoreExt, PutOptions};
+
+    use super::*;
+
+    /// Writes `data` at `key` with a recorded checksum of `sha256`.
+    async fn put_raw(client: &ObjectStoreClient, key: &str, data: &'static str, sha256: &str) {
+        let mut opts = PutOptions::default();
+        opts.attributes.insert(ATTRIBUTE, sha256.to_string().into());
+        client
+            .store
+            .put_opts(&Path::from(key), Bytes::from(data).into(), opts)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn put_records_checksum() {
+        let client = ObjectStoreClient::new(InMemory::new());
+        client.put("a.txt", Bytes::from("abc"), None).await.unwrap();
+
+        let output = client.get("a.txt").await.unwrap();
+        assert_eq!(
+            output.checksum.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            client.verify_object("a.txt").await.unwrap(),
+            ChecksumStatus::Verified
+        );
+    }
+
+    #[tokio::test]
+    async fn get_applies_policy() {
+        let client = ObjectStoreClient::new(InMemory::new());
+        put_raw(&client, "bad.txt", "abc", "00").await;
+
+        let err = client.get("bad.txt").await.unwrap_err();
+        assert!(!err.is_retryable());
+
+        let client = client.with_checksum_policy(ChecksumPolicy::Warn);
+        assert_eq!(client.get("bad.txt").await.unwrap().data, "abc");
+        assert!(matches!(
+            client.verify_object("bad.txt").await.unwrap(),
+            ChecksumStatus::Mismatch { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_checksum_is_accepted() {
+        let client = ObjectStoreClient::new(InMemory::new());
+        client
+            .store
+            .put(&Path::from("old.txt"), Bytes::from("legacy").into())
+            .await
+            .unwrap();
+
+        assert_eq!(client.get("old.txt").await.unwrap().data, "legacy");
+        assert_eq!(
+            client.verify_object("old.txt").await.unwrap(),
+            ChecksumStatus::Missing
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Transparent zstd compression of objects at rest.
+//!
+//! With a [`CompressionPolicy`] other than `Disabled`, uploads at least as
+//! large as its threshold are stored zstd-compressed and tagged with a
+//! `compression` user metadata attribute. It is not recorded as the HTTP
+//! content encoding, which clients could decode on their own. Downloads
+//! decompress any object tagged this way, whatever the client's own policy,
+//! so readers never see compressed bytes. Checksums always cover the
+//! uncompressed content.
+
+use std::borrow::Cow;
+
+use bytes::Bytes;
+use object_store::{Attribute, Attributes};
+
+use crate::types::Error;
+
+/// Metadata attribute naming the compression of the stored content.
+const ATTRIBUTE: Attribute = Attribute::Metadata(Cow::Borrowed("compression"));
+
+/// Attribute value of zstd-compressed content.
+const ZSTD: &str = "zstd";
+
+/// zstd level used for uploads, the library default.
+const ZSTD_LEVEL: i32 = 3;
+
+/// When uploads are compressed before they are stored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionPolicy {
+    /// Store content as is.
+    #[default]
+    Disabled,
+    /// Compress content of at least `min_size` bytes with zstd.
+    Zstd {
+        /// Smallest upload worth compressing, in bytes.
+        min_size: usize,
+    },
+}
+
+impl CompressionPolicy {
+    /// Compresses `data` if the policy asks for it and it pays off.
+    ///
+    /// Returns the bytes to store and whether they are compressed.
+    pub(super) async fn compress(self, data: Bytes) -> Result<(Bytes, bool), Error> {
+        let Self::Zstd { min_size } = self else {
+            return Ok((data, false));
+        };
+        if data.len() < min_size {
+            return Ok((data, false));
+        }
+
+        let original = data.clone();
+        let compressed = blocking(move || zstd::encode_all(&data[..], ZSTD_LEVEL)).await?;
+        if compressed.len() >= original.len() {
+            return Ok((original, false));
+        }
+        Ok((Bytes::from(compressed), true))
+    }
+}
+
+/// Records that the stored content is zstd-compressed.
+pub(super) fn mark(attributes: &mut Attributes) {
+    attributes.insert(ATTRIBUTE, ZSTD.into());
+}
+
+/// Decompresses `data` if `attributes` mark it as zstd-compressed.
+pub(super) async fn decompress(attributes: &Attributes, data: Bytes) -> Result<Bytes, Error> {
+    let compressed = attributes
+        .get(&ATTRIBUTE)
+        .is_some_and(|compression| compression.as_ref() == ZSTD);
+    if !compressed {
+        return Ok(data);
+    }
+
+    let decompressed = blocking(move || zstd::decode_all(&data[..])).await?;
+    Ok(Bytes::from(decompressed))
+}
+
+/// Runs a zstd call off the async runtime.
+async fn blocking(
+    f: impl FnOnce() -> std::io::Result<Vec<u8>> + Send + 'static,
+) -> Result<Vec<u8>, Error> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::runtime(e.to_string(), "object-store", true))?
+        .map_err(|e| Error::runtime(format!("zstd: {e}"), "object-store", false))
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+
+    use super::*;
+    use crate::client::{ChecksumStatus, ObjectStoreClient};
+
+    #[tokio::test]
+    async fn compresses_large_uploads() {
+        let client = ObjectStoreClient::new(InMemory::new())
+            .with_compression(CompressionPolicy::Zstd { min_size: 64 });
+        let large = Bytes::from("ocr ".repeat(1024));
+        client.put("large.json", large.clone(), None).await.unwrap();
+        client
+            .put("small.json", Bytes::from("{}"), None)
+            .await
+            .unwrap();
+
+        let stored = client.store().get(&Path::from("large.json")).await.unwrap();
+        assert!(stored.meta.size < large.len() as u64);
+        assert_eq!(client.get("large.json").await.unwrap().data, large);
+
+        let stored = client.store().get(&Path::from("small.json")).await.unwrap();
+        assert_eq!(stored.meta.size, 2);
+        assert_eq!(client.get("small.json").await.unwrap().data, "{}");
+    }
+
+    #[tokio::test]
+    async fn reads_compressed_objects_regardless_of_policy() {
+        let writer = ObjectStoreClient::new(InMemory::new())
+            .with_compression(CompressionPolicy::Zstd { min_size: 0 });
+        let data = Bytes::from("annotation ".repeat(100));
+        writer.put("a.json", data.clone(), None).await.unwrap();
+
+        let reader = writer.with_compression(CompressionPolicy::Disabled);
+        assert_eq!(reader.get("a.json").await.unwrap().data, data);
+        assert_eq!(
+            reader.verify_object("a.json").await.unwrap(),
+            ChecksumStatus::Verified
+        );
+    }
+}
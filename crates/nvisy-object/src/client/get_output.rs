@@ -10,10 +10,13 @@ use object_store::ObjectMeta;
 /// [`ObjectStoreClient::get`]: super::ObjectStoreClient::get
 #[derive(Debug)]
 pub struct GetOutput {
-    /// Raw bytes of the retrieved object.
+    /// Raw bytes of the retrieved object, decompressed if stored compressed.
     pub data: Bytes,
+    /// Hex SHA-256 recorded at upload, if the object has one.
+    pub checksum: Option<String>,
     /// MIME content-type, if the backend provides one.
     pub content_type: Option<String>,
-    /// Object metadata (size, etag, last_modified, location).
+    /// Object metadata (size, etag, last_modified, location); the size is
+    /// that of the stored, possibly compressed, content.
     pub meta: ObjectMeta,
 }
@@ -4,22 +4,35 @@
 //! `Arc<dyn ObjectStore>` that provides convenience methods for the most
 //! common operations. Every public method is instrumented with
 //! [`tracing`] for observability.
+//!
+//! Uploads record a SHA-256 of their content in the object metadata, which
+//! downloads verify according to the client's [`ChecksumPolicy`], hashing
+//! with its [`CryptoProvider`]. Large
+//! uploads can be stored zstd-compressed under a [`CompressionPolicy`].
 
 use std::sync::Arc;
 
 use bytes::Bytes;
 use futures::TryStreamExt;
 use futures::stream::BoxStream;
+use nvisy_core::crypto::{CryptoProvider, RustCryptoProvider};
 use object_store::path::Path;
 use object_store::{
-    Attribute, ObjectMeta, ObjectStore, ObjectStoreExt, PutMode, PutOptions, PutPayload,
+    Attribute, GetOptions, ObjectMeta, ObjectStore, ObjectStoreExt, PutMode, PutOptions, PutPayload,
 };
 
 use crate::types::Error;
 
+mod bulk_delete;
+mod checksum;
+mod compression;
 mod get_output;
 mod put_output;
+mod versions;
 
+pub use bulk_delete::{DeleteOptions, DeleteOutput};
+pub use checksum::{ChecksumPolicy, ChecksumStatus};
+pub use compression::CompressionPolicy;
 pub use get_output::GetOutput;
 pub use put_output::PutOutput;
 
@@ -28,12 +41,53 @@ pub use put_output::PutOutput;
 /// All methods accept human-readable string keys and convert them to
 /// [`object_store::path::Path`] internally.
 #[derive(Clone, Debug)]
-pub struct ObjectStoreClient(pub Arc<dyn ObjectStore>);
+pub struct ObjectStoreClient {
+    store: Arc<dyn ObjectStore>,
+    checksum_policy: ChecksumPolicy,
+    compression_policy: CompressionPolicy,
+    crypto: Arc<dyn CryptoProvider>,
+}
 
 impl ObjectStoreClient {
     /// Wrap a concrete [`ObjectStore`] implementation.
     pub fn new(store: impl ObjectStore) -> Self {
-        Self(Arc::new(store))
+        Self::from_arc(Arc::new(store))
+    }
+
+    /// Wrap an already shared [`ObjectStore`].
+    pub fn from_arc(store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            store,
+            checksum_policy: ChecksumPolicy::default(),
+            compression_policy: CompressionPolicy::default(),
+            crypto: Arc::new(RustCryptoProvider),
+        }
+    }
+
+    /// Set how downloads react to a checksum mismatch.
+    #[must_use]
+    pub fn with_checksum_policy(mut self, policy: ChecksumPolicy) -> Self {
+        self.checksum_policy = policy;
+        self
+    }
+
+    /// Set which uploads are stored compressed.
+    #[must_use]
+    pub fn with_compression(mut self, policy: CompressionPolicy) -> Self {
+        self.compression_policy = policy;
+        self
+    }
+
+    /// Set the crypto provider computing checksums.
+    #[must_use]
+    pub fn with_crypto_provider(mut self, provider: Arc<dyn CryptoProvider>) -> Self {
+        self.crypto = provider;
+        self
+    }
+
+    /// Returns the underlying [`ObjectStore`].
+    pub fn store(&self) -> &Arc<dyn ObjectStore> {
+        &self.store
     }
 
     /// Verify that the backing store is reachable.
@@ -43,7 +97,7 @@ impl ObjectStoreClient {
     #[tracing::instrument(name = "object.verify", skip(self))]
     pub async fn verify_reachable(&self) -> Result<(), Error> {
         let path = Path::from("_nvisy_verify_probe");
-        match self.0.head(&path).await {
+        match self.store.head(&path).await {
             Ok(_) => Ok(()),
             Err(object_store::Error::NotFound { .. }) => Ok(()),
             Err(e) => Err(from_object_store(e)),
@@ -63,7 +117,7 @@ impl ObjectStoreClient {
         } else {
             Some(Path::from(prefix))
         };
-        self.0
+        self.store
             .list(prefix.as_ref())
             .try_collect()
             .await
@@ -78,21 +132,39 @@ impl ObjectStoreClient {
         } else {
             Some(Path::from(prefix))
         };
-        Box::pin(self.0.list(prefix.as_ref()).map_err(from_object_store))
+        Box::pin(self.store.list(prefix.as_ref()).map_err(from_object_store))
     }
 
     /// Retrieve the raw bytes, content-type, and metadata stored at `key`.
+    ///
+    /// The content is checked against its recorded checksum as the client's
+    /// [`ChecksumPolicy`] dictates.
     #[tracing::instrument(name = "object.get", skip(self), fields(key))]
     pub async fn get(&self, key: &str) -> Result<GetOutput, Error> {
+        let output = self.get_unverified(key, GetOptions::default()).await?;
+        self.enforce_checksum(key, &output)?;
+        Ok(output)
+    }
+
+    /// Retrieve an object without checking its checksum.
+    async fn get_unverified(&self, key: &str, options: GetOptions) -> Result<GetOutput, Error> {
         let path = Path::from(key);
-        let result = self.0.get(&path).await.map_err(from_object_store)?;
+        let result = self
+            .store
+            .get_opts(&path, options)
+            .await
+            .map_err(from_object_store)?;
         let meta = result.meta.clone();
         let content_type = result
             .attributes
             .get(&Attribute::ContentType)
             .map(|v| v.to_string());
+        let checksum = checksum::stored(&result.attributes);
+        let attributes = result.attributes.clone();
         let data = result.bytes().await.map_err(from_object_store)?;
+        let data = compression::decompress(&attributes, data).await?;
         Ok(GetOutput {
+            checksum,
             data,
             content_type,
             meta,
@@ -120,17 +192,23 @@ impl ObjectStoreClient {
         content_type: Option<&str>,
     ) -> Result<PutOutput, Error> {
         let path = Path::from(key);
+        let sha256 = checksum::sha256_hex(self.crypto.as_ref(), &data);
+        let (data, compressed) = self.compression_policy.compress(data).await?;
         let payload = PutPayload::from(data);
         let mut opts = PutOptions {
             mode,
             ..Default::default()
         };
+        if compressed {
+            compression::mark(&mut opts.attributes);
+        }
         if let Some(ct) = content_type {
             opts.attributes
                 .insert(Attribute::ContentType, ct.to_string().into());
         }
+        opts.attributes.insert(checksum::ATTRIBUTE, sha256.into());
         let result = self
-            .0
+            .store
             .put_opts(&path, payload, opts)
             .await
             .map_err(from_object_store)?;
@@ -141,14 +219,14 @@ impl ObjectStoreClient {
     #[tracing::instrument(name = "object.head", skip(self), fields(key))]
     pub async fn head(&self, key: &str) -> Result<ObjectMeta, Error> {
         let path = Path::from(key);
-        self.0.head(&path).await.map_err(from_object_store)
+        self.store.head(&path).await.map_err(from_object_store)
     }
 
     /// Delete the object at `key`.
     #[tracing::instrument(name = "object.delete", skip(self), fields(key))]
     pub async fn delete(&self, key: &str) -> Result<(), Error> {
         let path = Path::from(key);
-        self.0.delete(&path).await.map_err(from_object_store)
+        self.store.delete(&path).await.map_err(from_object_store)
     }
 
     /// Copy an object from `src` to `dst` within the same store.
@@ -156,7 +234,7 @@ impl ObjectStoreClient {
     pub async fn copy(&self, src: &str, dst: &str) -> Result<(), Error> {
         let from = Path::from(src);
         let to = Path::from(dst);
-        self.0.copy(&from, &to).await.map_err(from_object_store)
+        self.store.copy(&from, &to).await.map_err(from_object_store)
     }
 }
 
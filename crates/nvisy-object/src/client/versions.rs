@@ -0,0 +1,218 @@
+//! Access to earlier versions of an object.
+//!
+//! On a bucket with versioning enabled, every overwrite keeps the previous
+//! content under its own version ID, reported in [`PutOutput::version`] and
+//! [`ObjectMeta::version`]. Versioning itself is bucket configuration, set up
+//! with the provider's tooling; `object_store` cannot enable it or list the
+//! versions of a key. Backends without versioning ignore the requested
+//! version and serve the current content, so a download that does not come
+//! back as the requested version is refused.
+//!
+//! [`ObjectMeta::version`]: object_store::ObjectMeta::version
+
+use object_store::{GetOptions, PutMode};
+
+use super::{GetOutput, ObjectStoreClient, PutOutput};
+use crate::types::Error;
+
+impl ObjectStoreClient {
+    /// Retrieve `version` of the object at `key`.
+    ///
+    /// The content is checked against the checksum recorded for that version.
+    /// Fails if the backend does not serve that version, as when the bucket
+    /// is not versioned.
+    #[tracing::instrument(name = "object.get_version", skip(self), fields(key, version))]
+    pub async fn get_version(&self, key: &str, version: &str) -> Result<GetOutput, Error> {
+        let options = GetOptions {
+            version: Some(version.to_owned()),
+            ..GetOptions::default()
+        };
+        let output = self.get_unverified(key, options).await?;
+        if output.meta.version.as_deref() != Some(version) {
+            return Err(Error::runtime(
+                format!("the object store did not serve version '{version}' of '{key}'"),
+                "object-store",
+                false,
+            ));
+        }
+
+        self.enforce_checksum(key, &output)?;
+        Ok(output)
+    }
+
+    /// Make `version` the current content of `key`.
+    ///
+    /// The old content is written back as a new version, so the versions
+    /// written since stay available.
+    #[tracing::instrument(name = "object.restore_version", skip(self), fields(key, version))]
+    pub async fn restore_version(&self, key: &str, version: &str) -> Result<PutOutput, Error> {
+        let output = self.get_version(key, version).await?;
+        self.put_opts(
+            key,
+            output.data,
+            PutMode::Overwrite,
+            output.content_type.as_deref(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use futures::stream::BoxStream;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{
+        CopyOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+        PutMultipartOptions, PutOptions, PutPayload, PutResult,
+    };
+
+    use super::*;
+
+    /// An in-memory store that keeps every write as a numbered version.
+    #[derive(Debug, Default)]
+    struct VersionedStore {
+        inner: InMemory,
+        next_version: AtomicU64,
+    }
+
+    impl VersionedStore {
+        fn archived(location: &Path, version: &str) -> Path {
+            Path::from(format!("_versions/{version}/{location}"))
+        }
+    }
+
+    impl fmt::Display for VersionedStore {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "VersionedStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for VersionedStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> object_store::Result<PutResult> {
+            let version = self
+                .next_version
+                .fetch_add(1, Ordering::Relaxed)
+                .to_string();
+            let archived = PutOptions {
+                attributes: opts.attributes.clone(),
+                ..PutOptions::default()
+            };
+            self.inner
+                .put_opts(
+                    &Self::archived(location, &version),
+                    payload.clone(),
+                    archived,
+                )
+                .await?;
+
+            let mut result = self.inner.put_opts(location, payload, opts).await?;
+            result.version = Some(version);
+            Ok(result)
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOptions,
+        ) -> object_store::Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            mut options: GetOptions,
+        ) -> object_store::Result<GetResult> {
+            let Some(version) = options.version.take() else {
+                return self.inner.get_opts(location, options).await;
+            };
+
+            let archived = Self::archived(location, &version);
+            let mut result = self.inner.get_opts(&archived, options).await?;
+            result.meta.location = location.clone();
+            result.meta.version = Some(version);
+            Ok(result)
+        }
+
+        fn delete_stream(
+            &self,
+            locations: BoxStream<'static, object_store::Result<Path>>,
+        ) -> BoxStream<'static, object_store::Result<Path>> {
+            self.inner.delete_stream(locations)
+        }
+
+        fn list(
+            &self,
+            prefix: Option<&Path>,
+        ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(
+            &self,
+            prefix: Option<&Path>,
+        ) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy_opts(
+            &self,
+            from: &Path,
+            to: &Path,
+            options: CopyOptions,
+        ) -> object_store::Result<()> {
+            self.inner.copy_opts(from, to, options).await
+        }
+    }
+
+    #[tokio::test]
+    async fn restore_version_brings_back_old_content() {
+        let client = ObjectStoreClient::new(VersionedStore::default());
+        let first = client
+            .put("doc.txt", Bytes::from("v1"), Some("text/plain"))
+            .await
+            .unwrap();
+        client
+            .put("doc.txt", Bytes::from("v2"), Some("text/plain"))
+            .await
+            .unwrap();
+        let first = first.version.unwrap();
+        assert_eq!(
+            client.get_version("doc.txt", &first).await.unwrap().data,
+            "v1"
+        );
+
+        let restored = client.restore_version("doc.txt", &first).await.unwrap();
+        assert_ne!(restored.version.as_deref(), Some(first.as_str()));
+
+        let output = client.get("doc.txt").await.unwrap();
+        assert_eq!(output.data, "v1");
+        assert_eq!(output.content_type.as_deref(), Some("text/plain"));
+    }
+
+    #[tokio::test]
+    async fn unversioned_backends_are_refused() {
+        // The in-memory store has no versions and serves the current content.
+        let client = ObjectStoreClient::new(InMemory::new());
+        client
+            .put("doc.txt", Bytes::from("v1"), Some("text/plain"))
+            .await
+            .unwrap();
+
+        let err = client.restore_version("doc.txt", "1").await.unwrap_err();
+        assert!(!err.is_retryable());
+        assert_eq!(client.get("doc.txt").await.unwrap().data, "v1");
+    }
+}
@@ -0,0 +1,213 @@
+//! Relay for the workspace event outbox.
+//!
+//! The application records its events in the `workspace_change_events`
+//! outbox with the change they describe, and database triggers record row
+//! changes made outside the application; either way the commit `NOTIFY`s the
+//! [`CHANGE_EVENTS_CHANNEL`]. The notification only wakes the listener:
+//! events are always read back from the outbox in id order, so changes made
+//! while the listener was disconnected (or while the sink was failing) are
+//! delivered once it catches up.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use diesel_async::{AsyncConnection, AsyncPgConnection, SimpleAsyncConnection};
+use futures::StreamExt;
+use jiff::{SignedDuration, Timestamp};
+
+use crate::model::WorkspaceChangeEvent;
+use crate::query::{AdminScope, WorkspaceChangeEventRepository};
+use crate::{PgClient, PgResult, TRACING_TARGET_CONNECTION};
+
+/// Channel the change event triggers notify.
+pub const CHANGE_EVENTS_CHANNEL: &str = "workspace_change_events";
+
+/// Maximum number of pending events read per batch.
+const DRAIN_BATCH_SIZE: i64 = 100;
+
+/// Interval at which the outbox is drained even without notifications.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum interval between prunes of published events.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Delay before re-establishing a lost listener connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How long published events are kept before being pruned.
+const PUBLISHED_RETENTION: SignedDuration = SignedDuration::from_hours(7 * 24);
+
+/// Callback invoked whenever the listener makes progress.
+type ProgressFn = Arc<dyn Fn() + Send + Sync>;
+
+/// Listens for outbox events and hands them to a sink.
+///
+/// Delivery is at-least-once: an event is marked published only after the
+/// sink accepts it, so a crash between the two re-delivers the event. Sinks
+/// should deduplicate on [`WorkspaceChangeEvent::id`].
+#[derive(Clone)]
+pub struct PgChangeListener {
+    client: PgClient,
+    on_progress: Option<ProgressFn>,
+}
+
+impl PgChangeListener {
+    /// Creates a listener reading the outbox through `client`.
+    pub fn new(client: PgClient) -> Self {
+        Self {
+            client,
+            on_progress: None,
+        }
+    }
+
+    /// Registers a callback invoked on every wake-up (notification, sweep or
+    /// reconnect attempt) and after every delivered event.
+    ///
+    /// An idle listener still wakes at least once per sweep interval, so a
+    /// callback that stops firing means the listener is blocked.
+    pub fn on_progress(mut self, on_progress: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    fn progress(&self) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress();
+        }
+    }
+
+    /// Delivers change events to `sink` until the task is cancelled.
+    ///
+    /// Holds a dedicated (non-pooled) connection for `LISTEN` and reconnects
+    /// whenever it is lost. A sink error leaves the event pending; delivery
+    /// resumes from it on the next notification or sweep.
+    pub async fn run<F, Fut, E>(self, mut sink: F)
+    where
+        F: FnMut(WorkspaceChangeEvent) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: fmt::Display,
+    {
+        loop {
+            self.progress();
+            if let Err(err) = self.listen(&mut sink).await {
+                tracing::warn!(
+                    target: TRACING_TARGET_CONNECTION,
+                    error = %err,
+                    "Change event listener disconnected"
+                );
+            }
+
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Listens on one connection until it fails.
+    async fn listen<F, Fut, E>(&self, sink: &mut F) -> PgResult<()>
+    where
+        F: FnMut(WorkspaceChangeEvent) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: fmt::Display,
+    {
+        let mut conn = AsyncPgConnection::establish(&self.client.config().postgres_url).await?;
+        conn.batch_execute(&format!("LISTEN {CHANGE_EVENTS_CHANNEL}"))
+            .await?;
+
+        // The outbox spans every workspace; the scope is opened once per
+        // connection rather than per batch.
+        let admin = AdminScope::open(&mut conn, "relay workspace change events").await?;
+
+        tracing::info!(
+            target: TRACING_TARGET_CONNECTION,
+            channel = CHANGE_EVENTS_CHANNEL,
+            "Change event listener connected"
+        );
+
+        // Catch up on anything recorded while no listener was connected.
+        self.drain(&admin, sink).await?;
+
+        // Pruned on a timer of its own: a busy outbox may never go a sweep
+        // interval without a notification.
+        let mut last_prune = Instant::now();
+        let mut notifications = pin!(conn.notifications_stream());
+        loop {
+            self.progress();
+            match tokio::time::timeout(SWEEP_INTERVAL, notifications.next()).await {
+                Ok(Some(Ok(_))) | Err(_) => self.drain(&admin, sink).await?,
+                Ok(Some(Err(err))) => return Err(err.into()),
+                Ok(None) => return Ok(()),
+            }
+
+            if last_prune.elapsed() >= PRUNE_INTERVAL {
+                self.prune(&admin).await?;
+                last_prune = Instant::now();
+            }
+        }
+    }
+
+    /// Delivers pending events in order, stopping at the first sink error.
+    async fn drain<F, Fut, E>(&self, admin: &AdminScope, sink: &mut F) -> PgResult<()>
+    where
+        F: FnMut(WorkspaceChangeEvent) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: fmt::Display,
+    {
+        let mut conn = self.client.get_connection().await?;
+
+        loop {
+            let events = conn
+                .list_pending_change_events(admin, DRAIN_BATCH_SIZE)
+                .await?;
+            let exhausted = (events.len() as i64) < DRAIN_BATCH_SIZE;
+
+            for event in events {
+                let event_id = event.id;
+                if let Err(err) = sink(event).await {
+                    tracing::warn!(
+                        target: TRACING_TARGET_CONNECTION,
+                        event_id,
+                        error = %err,
+                        "Failed to deliver change event, will retry"
+                    );
+                    return Ok(());
+                }
+
+                conn.mark_change_event_published(admin, event_id).await?;
+                self.progress();
+            }
+
+            if exhausted {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Deletes published events past their retention.
+    async fn prune(&self, admin: &AdminScope) -> PgResult<()> {
+        let mut conn = self.client.get_connection().await?;
+        let pruned = conn
+            .prune_published_change_events(admin, Timestamp::now() - PUBLISHED_RETENTION)
+            .await?;
+
+        if pruned > 0 {
+            tracing::debug!(
+                target: TRACING_TARGET_CONNECTION,
+                pruned,
+                "Pruned published change events"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PgChangeListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgChangeListener")
+            .field("client", &self.client)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
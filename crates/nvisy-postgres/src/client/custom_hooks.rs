@@ -3,14 +3,23 @@
 use std::time::Instant;
 
 use deadpool::managed::{HookResult, Metrics};
-use diesel::ConnectionResult;
+use diesel::{ConnectionError, ConnectionResult};
 use diesel_async::pooled_connection::{PoolError, PoolableConnection};
-use diesel_async::{AsyncConnection, AsyncPgConnection};
+use diesel_async::{AsyncConnection, AsyncPgConnection, SimpleAsyncConnection};
 use futures::FutureExt;
 use futures::future::BoxFuture;
 
 use crate::TRACING_TARGET_CONNECTION;
 
+/// Marks a session's row changes as application-originated.
+///
+/// The change event triggers skip such changes, since the application emits
+/// its own events for them.
+pub(crate) const SET_APPLICATION_CHANGE_SOURCE: &str = "SET nvisy.change_source = 'application'";
+
+/// Clears the application marker so the session's changes are recorded.
+pub(crate) const RESET_CHANGE_SOURCE: &str = "RESET nvisy.change_source";
+
 /// Masks sensitive information (password) in a database URL for safe logging.
 fn mask_url(url: &str) -> String {
     if let Some(at_pos) = url.find('@')
@@ -44,7 +53,14 @@ where
     );
 
     async move {
-        let result = C::establish(addr).await;
+        let result = match C::establish(addr).await {
+            Ok(mut conn) => conn
+                .batch_execute(SET_APPLICATION_CHANGE_SOURCE)
+                .await
+                .map(|()| conn)
+                .map_err(ConnectionError::CouldntSetupConfiguration),
+            Err(err) => Err(err),
+        };
         let elapsed = start.elapsed();
 
         match &result {
@@ -1,6 +1,6 @@
 //! [`HealthCheck`] implementation for [`PgClient`].
 
-use nvisy_core::health::{ComponentHealth, HealthCheck};
+use nvisy_core::health::{ComponentHealth, HealthCheck, HealthStatus};
 
 use super::PgClient;
 use crate::TRACING_TARGET_CONNECTION;
@@ -10,10 +10,27 @@ const COMPONENT_NAME: &str = "postgres";
 
 #[async_trait::async_trait]
 impl HealthCheck for PgClient {
-    /// Probes Postgres by acquiring a connection from the pool.
+    /// Probes Postgres by acquiring a connection from the primary pool and
+    /// from each read replica.
+    ///
+    /// A reachable primary with an unreachable replica reports degraded:
+    /// reads still succeed by failing over to the primary.
     async fn check_health(&self) -> ComponentHealth {
         match self.get_connection().await {
             Ok(_) => {
+                let replicas_down = self.probe_replicas().await;
+                if replicas_down > 0 {
+                    tracing::warn!(
+                        target: TRACING_TARGET_CONNECTION,
+                        replicas_down,
+                        "Postgres health check degraded: read replicas unavailable"
+                    );
+                    return ComponentHealth {
+                        status: HealthStatus::Degraded,
+                        ..ComponentHealth::healthy(COMPONENT_NAME)
+                    };
+                }
+
                 tracing::debug!(target: TRACING_TARGET_CONNECTION, "Postgres health check passed");
                 ComponentHealth::healthy(COMPONENT_NAME)
             }
@@ -5,8 +5,8 @@
 //! from the core database client implementation.
 
 use super::{
-    MigrationResult, MigrationStatus, get_migration_status, run_pending_migrations,
-    verify_schema_integrity,
+    MigrationPrivileges, MigrationResult, MigrationStatus, get_migration_privileges,
+    get_migration_status, run_pending_migrations, verify_schema_integrity,
 };
 use crate::{PgClient, PgResult};
 
@@ -58,6 +58,17 @@ pub trait PgClientMigrationExt {
     /// Returns an error if schema integrity issues are detected or if
     /// verification cannot be completed.
     fn verify_schema_integrity(&self) -> impl Future<Output = PgResult<()>>;
+
+    /// Checks whether the connected role can apply migrations.
+    ///
+    /// Useful before startup to report missing grants up front instead of
+    /// failing midway through a migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are connectivity issues or if the privilege
+    /// query fails.
+    fn get_migration_privileges(&self) -> impl Future<Output = PgResult<MigrationPrivileges>>;
 }
 
 impl PgClientMigrationExt for PgClient {
@@ -74,4 +85,9 @@ impl PgClientMigrationExt for PgClient {
         let mut conn = self.get_pooled_connection().await?;
         verify_schema_integrity(&mut conn).await
     }
+
+    async fn get_migration_privileges(&self) -> PgResult<MigrationPrivileges> {
+        let mut conn = self.get_pooled_connection().await?;
+        get_migration_privileges(&mut conn).await
+    }
 }
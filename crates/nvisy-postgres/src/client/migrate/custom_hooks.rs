@@ -4,10 +4,11 @@
 
 use std::time::Instant;
 
-use diesel_async::AsyncPgConnection;
 use diesel_async::pooled_connection::PoolableConnection;
+use diesel_async::{AsyncPgConnection, SimpleAsyncConnection};
 
-use crate::{PgResult, TRACING_TARGET_MIGRATION};
+use crate::client::custom_hooks::{RESET_CHANGE_SOURCE, SET_APPLICATION_CHANGE_SOURCE};
+use crate::{PgError, PgResult, TRACING_TARGET_MIGRATION};
 
 /// Custom hook called before a connection has been used to run migrations.
 ///
@@ -33,6 +34,12 @@ pub async fn pre_migrate(conn: &mut AsyncPgConnection) -> PgResult<()> {
         );
     }
 
+    // Data changed by migrations is bridged to change events like any other
+    // out-of-band change.
+    conn.batch_execute(RESET_CHANGE_SOURCE)
+        .await
+        .map_err(PgError::from)?;
+
     Ok(())
 }
 
@@ -60,5 +67,10 @@ pub async fn post_migrate(conn: &mut AsyncPgConnection) -> PgResult<()> {
         );
     }
 
+    // The connection returns to the pool; restore the application marker.
+    conn.batch_execute(SET_APPLICATION_CHANGE_SOURCE)
+        .await
+        .map_err(PgError::from)?;
+
     Ok(())
 }
@@ -164,6 +164,42 @@ impl MigrationResult {
     }
 }
 
+/// Privileges of the connected role that migrations depend on.
+///
+/// Migrations create tables, types and triggers in the current schema and
+/// enable trusted extensions (`pg_trgm`, `pgcrypto`) in the current database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationPrivileges {
+    /// The role the connection is authenticated as.
+    pub role: String,
+    /// Whether the role may create objects in the current schema.
+    pub create_in_schema: bool,
+    /// Whether the role may create extensions in the current database.
+    pub create_in_database: bool,
+    /// Whether the role may record applied migrations (true when the
+    /// migration table does not exist yet and will be created).
+    pub write_migration_table: bool,
+}
+
+impl MigrationPrivileges {
+    /// Returns whether the role can apply migrations.
+    pub fn can_migrate(&self) -> bool {
+        self.create_in_schema && self.create_in_database && self.write_migration_table
+    }
+
+    /// Returns the names of the missing privileges.
+    pub fn missing(&self) -> Vec<&'static str> {
+        [
+            (!self.create_in_schema).then_some("CREATE on schema"),
+            (!self.create_in_database).then_some("CREATE on database"),
+            (!self.write_migration_table).then_some("INSERT on __diesel_schema_migrations"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -200,4 +236,20 @@ mod tests {
         assert_eq!(result.average_time_per_migration(), None);
         assert_eq!(result.last_processed_version(), None);
     }
+
+    #[test]
+    fn test_migration_privileges_missing() {
+        let mut privileges = MigrationPrivileges {
+            role: "nvisy".to_string(),
+            create_in_schema: true,
+            create_in_database: true,
+            write_migration_table: true,
+        };
+        assert!(privileges.can_migrate());
+        assert!(privileges.missing().is_empty());
+
+        privileges.create_in_database = false;
+        assert!(!privileges.can_migrate());
+        assert_eq!(privileges.missing(), ["CREATE on database"]);
+    }
 }
@@ -20,6 +20,6 @@ mod run_utility;
 
 // Re-export main types for convenience
 pub use client_ext::PgClientMigrationExt;
-pub use migrate_result::{MigrationResult, MigrationStatus};
+pub use migrate_result::{MigrationPrivileges, MigrationResult, MigrationStatus};
 pub use run_migration::run_pending_migrations;
-pub use run_utility::{get_migration_status, verify_schema_integrity};
+pub use run_utility::{get_migration_privileges, get_migration_status, verify_schema_integrity};
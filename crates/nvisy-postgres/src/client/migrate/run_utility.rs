@@ -1,6 +1,6 @@
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
-use super::MigrationStatus;
+use super::{MigrationPrivileges, MigrationStatus};
 use crate::{PgError, PgResult, TRACING_TARGET_MIGRATION};
 
 /// Gets the current migration status of the database.
@@ -75,6 +75,56 @@ pub async fn verify_schema_integrity(conn: &mut AsyncPgConnection) -> PgResult<(
     Ok(())
 }
 
+/// Checks whether the connected role holds the privileges migrations need.
+#[tracing::instrument(skip(conn), target = TRACING_TARGET_MIGRATION)]
+pub async fn get_migration_privileges(
+    conn: &mut AsyncPgConnection,
+) -> PgResult<MigrationPrivileges> {
+    use diesel::sql_query;
+
+    #[derive(diesel::QueryableByName)]
+    struct PrivilegesRow {
+        #[diesel(sql_type = diesel::sql_types::Text)]
+        role: String,
+        #[diesel(sql_type = diesel::sql_types::Bool)]
+        create_in_schema: bool,
+        #[diesel(sql_type = diesel::sql_types::Bool)]
+        create_in_database: bool,
+        #[diesel(sql_type = diesel::sql_types::Bool)]
+        write_migration_table: bool,
+    }
+
+    // `current_schema()` is NULL when the search path names no existing
+    // schema, in which case nothing can be created.
+    let row = sql_query(
+        "SELECT
+            current_user::text AS role,
+            COALESCE(has_schema_privilege(current_schema(), 'CREATE'), false) AS create_in_schema,
+            has_database_privilege(current_database(), 'CREATE') AS create_in_database,
+            (to_regclass('__diesel_schema_migrations') IS NULL
+                OR has_table_privilege('__diesel_schema_migrations', 'INSERT')) AS write_migration_table",
+    )
+    .get_result::<PrivilegesRow>(conn)
+    .await
+    .map_err(|e| PgError::Migration(format!("Failed to check migration privileges: {}", e).into()))?;
+
+    let privileges = MigrationPrivileges {
+        role: row.role,
+        create_in_schema: row.create_in_schema,
+        create_in_database: row.create_in_database,
+        write_migration_table: row.write_migration_table,
+    };
+
+    tracing::debug!(
+        target: TRACING_TARGET_MIGRATION,
+        role = %privileges.role,
+        can_migrate = privileges.can_migrate(),
+        "Migration privileges retrieved"
+    );
+
+    Ok(privileges)
+}
+
 /// Gets list of applied migration versions from the database.
 ///
 /// On a fresh database the `__diesel_schema_migrations` table does not exist
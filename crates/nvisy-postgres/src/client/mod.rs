@@ -4,18 +4,24 @@
 //! managing connection pools, and handling database migrations. It includes comprehensive
 //! error handling, observability through tracing, and production-ready configuration.
 
+mod change_listener;
 pub(crate) mod custom_hooks;
 mod health;
 pub mod migrate;
 mod pg_client;
 mod pg_config;
+mod pg_replica;
+mod query_metrics;
 
+pub use change_listener::{CHANGE_EVENTS_CHANNEL, PgChangeListener};
 use deadpool::managed::{Object, Pool};
 use diesel_async::AsyncPgConnection;
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
-pub use migrate::{MigrationResult, MigrationStatus, PgClientMigrationExt};
-pub use pg_client::{PgClient, PgConn, PgPoolStatus};
+pub use migrate::{MigrationPrivileges, MigrationResult, MigrationStatus, PgClientMigrationExt};
+pub use pg_client::{PgClient, PgConn, PgPoolRole, PgPoolStatus};
 pub use pg_config::PgConfig;
+pub(crate) use query_metrics::QueryTimer;
+pub use query_metrics::{QueryMetricsSnapshot, QueryStats, query_metrics, reset_query_metrics};
 
 /// Type alias for the connection pool used throughout the application.
 pub type ConnectionPool = Pool<AsyncDieselConnectionManager<AsyncPgConnection>>;
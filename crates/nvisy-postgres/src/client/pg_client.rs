@@ -1,5 +1,6 @@
 use std::fmt;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
 use deadpool::Runtime;
@@ -9,13 +10,34 @@ use diesel_async::RunQueryDsl;
 use diesel_async::pooled_connection::{AsyncDieselConnectionManager, ManagerConfig};
 
 use super::custom_hooks;
+use super::pg_replica::ReplicaPool;
+use super::query_metrics::{self, QueryMetricsSnapshot};
 use crate::{
     ConnectionPool, PgConfig, PgError, PgResult, PooledConnection, TRACING_TARGET_CONNECTION,
 };
 
+/// Create timeout for replica connections when none is configured, so a dead
+/// replica fails over to the primary quickly instead of stalling the read.
+const REPLICA_CREATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Role of a connection pool within a [`PgClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgPoolRole {
+    /// The writer pool; serves all writes and any read without a replica.
+    Primary,
+    /// A read replica pool; serves [`PgClient::read`] connections.
+    Replica,
+}
+
 /// Connection pool status information.
 #[derive(Debug, Clone)]
 pub struct PgPoolStatus {
+    /// Whether this is the primary or a replica pool
+    pub role: PgPoolRole,
+    /// Database URL of the pool, with the password masked
+    pub url: String,
+    /// Whether the pool is in rotation (always true for the primary)
+    pub healthy: bool,
     /// Maximum number of connections in the pool
     pub max_size: usize,
     /// Current number of connections in the pool
@@ -27,6 +49,19 @@ pub struct PgPoolStatus {
 }
 
 impl PgPoolStatus {
+    fn new(role: PgPoolRole, url: String, healthy: bool, pool: &ConnectionPool) -> Self {
+        let status = pool.status();
+        Self {
+            role,
+            url,
+            healthy,
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+            waiting: status.waiting,
+        }
+    }
+
     /// Returns the utilization percentage of the pool (0.0 to 1.0).
     #[inline]
     pub fn utilization(&self) -> f64 {
@@ -56,6 +91,8 @@ pub struct PgClient {
 /// Inner data for PgClient
 struct PgClientInner {
     pool: ConnectionPool,
+    replicas: Vec<ReplicaPool>,
+    next_replica: AtomicUsize,
     config: PgConfig,
 }
 
@@ -79,17 +116,56 @@ impl PgClient {
         fields(database_url = %config.database_url_masked())
     )]
     pub fn new(config: PgConfig) -> PgResult<Self> {
-        tracing::info!(target: TRACING_TARGET_CONNECTION, "Initializing database client");
+        tracing::info!(
+            target: TRACING_TARGET_CONNECTION,
+            replicas = config.postgres_replica_urls.len(),
+            "Initializing database client"
+        );
+
+        query_metrics::set_slow_query_threshold(config.postgres_slow_query_threshold);
 
+        let pool = Self::build_pool(
+            &config,
+            &config.postgres_url,
+            config.postgres_connection_timeout,
+        )?;
+
+        let replica_create_timeout = config
+            .postgres_connection_timeout
+            .unwrap_or(REPLICA_CREATE_TIMEOUT);
+        let replicas = config
+            .postgres_replica_urls
+            .iter()
+            .map(|url| {
+                let pool = Self::build_pool(&config, url, Some(replica_create_timeout))?;
+                Ok(ReplicaPool::new(pool, PgConfig::mask_url(url)))
+            })
+            .collect::<PgResult<Vec<_>>>()?;
+
+        Ok(Self {
+            inner: Arc::new(PgClientInner {
+                pool,
+                replicas,
+                next_replica: AtomicUsize::new(0),
+                config,
+            }),
+        })
+    }
+
+    /// Builds a connection pool for `url` with the configured pool settings.
+    fn build_pool(
+        config: &PgConfig,
+        url: &str,
+        create_timeout: Option<Duration>,
+    ) -> PgResult<ConnectionPool> {
         let mut manager_config = ManagerConfig::default();
         manager_config.custom_setup = Box::new(custom_hooks::setup_callback);
-        let manager =
-            AsyncDieselConnectionManager::new_with_config(&config.postgres_url, manager_config);
+        let manager = AsyncDieselConnectionManager::new_with_config(url, manager_config);
 
-        let pool = Pool::builder(manager)
+        Pool::builder(manager)
             .max_size(config.postgres_max_connections as usize)
             .wait_timeout(config.postgres_connection_timeout)
-            .create_timeout(config.postgres_connection_timeout)
+            .create_timeout(create_timeout)
             .recycle_timeout(config.postgres_idle_timeout)
             .runtime(Runtime::Tokio1)
             .post_create(Hook::sync_fn(custom_hooks::post_create))
@@ -99,11 +175,7 @@ impl PgClient {
             .map_err(|e| {
                 tracing::error!(target: TRACING_TARGET_CONNECTION, error = %e, "Failed to create connection pool");
                 PgError::Unexpected(format!("Failed to build connection pool: {}", e).into())
-            })?;
-
-        Ok(Self {
-            inner: Arc::new(PgClientInner { pool, config }),
-        })
+            })
     }
 
     /// Creates a new database client with the provided configuration.
@@ -204,6 +276,50 @@ impl PgClient {
         Ok(PgConn::new(conn))
     }
 
+    /// Gets a connection for read-only queries.
+    ///
+    /// Connections come from the read replicas in round-robin order, skipping
+    /// replicas taken out of rotation after a failed acquisition. Falls back
+    /// to the primary when no replica is configured or none can serve the
+    /// read. Replicas lag the primary, so reads that must observe a write made
+    /// moments earlier should use [`get_connection`](Self::get_connection).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither a replica nor the primary can provide a
+    /// connection within the timeout period.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CONNECTION)]
+    pub async fn read(&self) -> PgResult<PgConn> {
+        let replicas = &self.inner.replicas;
+        if replicas.is_empty() {
+            return self.get_connection().await;
+        }
+
+        let start = self.inner.next_replica.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..replicas.len() {
+            let replica = &replicas[(start + offset) % replicas.len()];
+            if !replica.is_available() {
+                continue;
+            }
+
+            if let Ok(conn) = replica.get().await {
+                tracing::debug!(
+                    target: TRACING_TARGET_CONNECTION,
+                    replica = %replica.url_masked(),
+                    "Read connection acquired from replica"
+                );
+                return Ok(PgConn::new(conn));
+            }
+        }
+
+        tracing::warn!(
+            target: TRACING_TARGET_CONNECTION,
+            replicas = replicas.len(),
+            "No read replica available, falling back to primary"
+        );
+        self.get_connection().await
+    }
+
     /// Gets a raw pooled connection from the pool.
     ///
     /// This is intended for internal use by the migration module.
@@ -212,19 +328,60 @@ impl PgClient {
         Ok(conn)
     }
 
-    /// Gets the current pool status and statistics.
+    /// Gets the current primary pool status and statistics.
     ///
     /// This method provides insights into the connection pool state for monitoring
     /// and debugging purposes.
     #[inline]
     pub fn pool_status(&self) -> PgPoolStatus {
-        let status = self.inner.pool.status();
-        PgPoolStatus {
-            max_size: status.max_size,
-            size: status.size,
-            available: status.available,
-            waiting: status.waiting,
+        PgPoolStatus::new(
+            PgPoolRole::Primary,
+            self.inner.config.database_url_masked(),
+            true,
+            &self.inner.pool,
+        )
+    }
+
+    /// Gets the status of every pool: the primary first, then each replica.
+    pub fn pool_statuses(&self) -> Vec<PgPoolStatus> {
+        let replicas = self.inner.replicas.iter().map(|replica| {
+            PgPoolStatus::new(
+                PgPoolRole::Replica,
+                replica.url_masked().to_owned(),
+                replica.is_healthy(),
+                replica.pool(),
+            )
+        });
+
+        std::iter::once(self.pool_status())
+            .chain(replicas)
+            .collect()
+    }
+
+    /// Acquires a connection from every replica, refreshing their health, and
+    /// returns how many failed.
+    pub(crate) async fn probe_replicas(&self) -> usize {
+        let mut failed = 0;
+        for replica in &self.inner.replicas {
+            if replica.get().await.is_err() {
+                failed += 1;
+            }
         }
+        failed
+    }
+
+    /// Returns a snapshot of the per-query timing metrics.
+    ///
+    /// Metrics are process-wide, so every client reports the same snapshot.
+    #[inline]
+    pub fn query_metrics(&self) -> QueryMetricsSnapshot {
+        query_metrics::query_metrics()
+    }
+
+    /// Returns whether any read replicas are configured.
+    #[inline]
+    pub fn has_replicas(&self) -> bool {
+        !self.inner.replicas.is_empty()
     }
 
     /// Gets the database configuration used by this client.
@@ -246,6 +403,7 @@ impl fmt::Debug for PgClient {
             .field("pool_current_size", &pool_status.size)
             .field("pool_available", &pool_status.available)
             .field("pool_waiting", &pool_status.waiting)
+            .field("replicas", &self.inner.replicas.len())
             .field(
                 "connection_timeout",
                 &self.inner.config.postgres_connection_timeout,
@@ -0,0 +1,95 @@
+//! Read replica pools with health-based failover.
+//!
+//! Each configured replica gets its own connection pool. A replica that fails
+//! to hand out a connection is taken out of rotation for a cooldown period,
+//! after which the next read tries it again; reads skip unavailable replicas
+//! and fall back to the primary when none are left.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{ConnectionPool, PgError, PgResult, PooledConnection, TRACING_TARGET_CONNECTION};
+
+/// How long a replica stays out of rotation after a failed acquisition.
+const REPLICA_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// A read replica connection pool and its health state.
+pub(crate) struct ReplicaPool {
+    pool: ConnectionPool,
+    url_masked: String,
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl ReplicaPool {
+    /// Wraps a replica pool, initially in rotation.
+    pub(crate) fn new(pool: ConnectionPool, url_masked: String) -> Self {
+        Self {
+            pool,
+            url_masked,
+            unhealthy_until: Mutex::new(None),
+        }
+    }
+
+    /// Returns the underlying connection pool.
+    #[inline]
+    pub(crate) fn pool(&self) -> &ConnectionPool {
+        &self.pool
+    }
+
+    /// Returns the replica URL with the password masked.
+    #[inline]
+    pub(crate) fn url_masked(&self) -> &str {
+        &self.url_masked
+    }
+
+    /// Whether the replica is in rotation (healthy, or its cooldown elapsed).
+    pub(crate) fn is_available(&self) -> bool {
+        let unhealthy_until = self.unhealthy_until.lock().expect("replica health lock");
+        unhealthy_until.is_none_or(|until| Instant::now() >= until)
+    }
+
+    /// Whether the replica's last acquisition succeeded.
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.unhealthy_until
+            .lock()
+            .expect("replica health lock")
+            .is_none()
+    }
+
+    /// Acquires a connection, updating the replica's health from the outcome.
+    pub(crate) async fn get(&self) -> PgResult<PooledConnection> {
+        match self.pool.get().await {
+            Ok(conn) => {
+                self.mark_healthy();
+                Ok(conn)
+            }
+            Err(e) => {
+                self.mark_unhealthy();
+                tracing::warn!(
+                    target: TRACING_TARGET_CONNECTION,
+                    replica = %self.url_masked,
+                    error = %e,
+                    cooldown = ?REPLICA_COOLDOWN,
+                    "Read replica unavailable, removing from rotation"
+                );
+                Err(PgError::from(e))
+            }
+        }
+    }
+
+    fn mark_healthy(&self) {
+        let mut unhealthy_until = self.unhealthy_until.lock().expect("replica health lock");
+        if unhealthy_until.take().is_some() {
+            tracing::info!(
+                target: TRACING_TARGET_CONNECTION,
+                replica = %self.url_masked,
+                "Read replica recovered, returning to rotation"
+            );
+        }
+    }
+
+    fn mark_unhealthy(&self) {
+        let mut unhealthy_until = self.unhealthy_until.lock().expect("replica health lock");
+        *unhealthy_until = Some(Instant::now() + REPLICA_COOLDOWN);
+    }
+}
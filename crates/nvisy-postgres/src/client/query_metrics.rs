@@ -0,0 +1,269 @@
+//! Query timing instrumentation.
+//!
+//! Every repository method starts a [`QueryTimer`] labeled with its own name.
+//! When the timer drops, the elapsed time lands in a process-wide latency
+//! histogram for that label, and calls slower than the configured threshold
+//! are logged. [`query_metrics`] returns a point-in-time snapshot for
+//! monitoring.
+//!
+//! Metrics are process-wide rather than per [`PgClient`]: repository methods
+//! run on a bare connection and have no handle back to the client.
+//!
+//! [`PgClient`]: crate::PgClient
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::TRACING_TARGET_QUERY;
+
+/// Upper bounds of the latency histogram buckets, in milliseconds.
+///
+/// A final overflow bucket counts everything slower than the last bound.
+const BUCKET_BOUNDS_MS: [u64; 11] = [1, 2, 5, 10, 25, 50, 100, 250, 500, 1_000, 5_000];
+
+/// Default slow query threshold.
+pub(crate) const DEFAULT_SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Process-wide query metrics registry.
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+/// Slow query threshold in milliseconds; zero disables slow query logging.
+static SLOW_QUERY_THRESHOLD_MS: AtomicU64 =
+    AtomicU64::new(DEFAULT_SLOW_QUERY_THRESHOLD.as_millis() as u64);
+
+/// Per-label running statistics.
+#[derive(Debug, Clone, Default)]
+struct LabelStats {
+    count: u64,
+    total: Duration,
+    max: Duration,
+    buckets: [u64; BUCKET_BOUNDS_MS.len() + 1],
+}
+
+impl LabelStats {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+
+        let elapsed_ms = elapsed.as_millis();
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| elapsed_ms <= u128::from(bound))
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket] += 1;
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    labels: Mutex<HashMap<&'static str, LabelStats>>,
+}
+
+impl Registry {
+    fn record(&self, label: &'static str, elapsed: Duration) {
+        let mut labels = self.labels.lock().expect("query metrics lock");
+        labels.entry(label).or_default().record(elapsed);
+    }
+
+    fn snapshot(&self) -> QueryMetricsSnapshot {
+        let labels = self.labels.lock().expect("query metrics lock");
+        let mut queries: Vec<QueryStats> = labels
+            .iter()
+            .map(|(&label, stats)| QueryStats::new(label, stats))
+            .collect();
+        drop(labels);
+
+        queries.sort_by(|a, b| b.total.cmp(&a.total).then(a.label.cmp(b.label)));
+        QueryMetricsSnapshot { queries }
+    }
+
+    fn reset(&self) {
+        self.labels.lock().expect("query metrics lock").clear();
+    }
+}
+
+/// Sets the threshold above which queries are logged as slow.
+///
+/// `None` or a zero threshold disables slow query logging. Applies
+/// process-wide.
+pub(crate) fn set_slow_query_threshold(threshold: Option<Duration>) {
+    let millis = threshold.map_or(0, |t| t.as_millis() as u64);
+    SLOW_QUERY_THRESHOLD_MS.store(millis, Ordering::Relaxed);
+}
+
+/// Returns a snapshot of the query metrics recorded so far.
+pub fn query_metrics() -> QueryMetricsSnapshot {
+    REGISTRY.snapshot()
+}
+
+/// Clears all recorded query metrics.
+pub fn reset_query_metrics() {
+    REGISTRY.reset();
+}
+
+/// Times one repository call, recording it under `label` when dropped.
+///
+/// Dropping covers every exit path, including early returns through `?`.
+pub(crate) struct QueryTimer {
+    label: &'static str,
+    start: Instant,
+}
+
+impl QueryTimer {
+    /// Starts timing the query named `label`.
+    #[inline]
+    pub(crate) fn start(label: &'static str) -> Self {
+        Self {
+            label,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for QueryTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        REGISTRY.record(self.label, elapsed);
+
+        let threshold_ms = SLOW_QUERY_THRESHOLD_MS.load(Ordering::Relaxed);
+        if threshold_ms > 0 && elapsed.as_millis() >= u128::from(threshold_ms) {
+            tracing::warn!(
+                target: TRACING_TARGET_QUERY,
+                query = self.label,
+                elapsed_ms = elapsed.as_millis() as u64,
+                threshold_ms,
+                "Slow query"
+            );
+        }
+    }
+}
+
+/// Point-in-time copy of the query metrics, ordered by total time spent
+/// (descending).
+#[derive(Debug, Clone, Default)]
+pub struct QueryMetricsSnapshot {
+    /// Statistics per query label.
+    pub queries: Vec<QueryStats>,
+}
+
+impl QueryMetricsSnapshot {
+    /// Returns the statistics for one label, if it has been recorded.
+    pub fn get(&self, label: &str) -> Option<&QueryStats> {
+        self.queries.iter().find(|stats| stats.label == label)
+    }
+
+    /// Total number of queries recorded across all labels.
+    pub fn total_count(&self) -> u64 {
+        self.queries.iter().map(|stats| stats.count).sum()
+    }
+}
+
+/// Latency statistics for one query label.
+#[derive(Debug, Clone)]
+pub struct QueryStats {
+    /// The repository method name.
+    pub label: &'static str,
+    /// Number of calls recorded.
+    pub count: u64,
+    /// Sum of all call durations.
+    pub total: Duration,
+    /// Slowest call recorded.
+    pub max: Duration,
+    /// Latency histogram as `(upper bound, count)` pairs; the final bucket has
+    /// no upper bound (`None`) and counts everything slower.
+    pub buckets: Vec<(Option<Duration>, u64)>,
+}
+
+impl QueryStats {
+    fn new(label: &'static str, stats: &LabelStats) -> Self {
+        let bounds = BUCKET_BOUNDS_MS
+            .iter()
+            .map(|&ms| Some(Duration::from_millis(ms)))
+            .chain(std::iter::once(None));
+
+        Self {
+            label,
+            count: stats.count,
+            total: stats.total,
+            max: stats.max,
+            buckets: bounds.zip(stats.buckets.iter().copied()).collect(),
+        }
+    }
+
+    /// Mean call duration.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.count as u32
+        }
+    }
+
+    /// Approximate latency at quantile `q` (0.0 to 1.0), as the upper bound of
+    /// the bucket it falls in. Returns [`max`](Self::max) for the overflow
+    /// bucket.
+    pub fn quantile(&self, q: f64) -> Duration {
+        let rank = ((self.count as f64) * q.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for &(bound, count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return bound.unwrap_or(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(durations_ms: &[u64]) -> QueryStats {
+        let mut stats = LabelStats::default();
+        for &ms in durations_ms {
+            stats.record(Duration::from_millis(ms));
+        }
+        QueryStats::new("test", &stats)
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let stats = stats(&[0, 3, 3, 40, 9_000]);
+
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.max, Duration::from_millis(9_000));
+        assert_eq!(stats.buckets[0], (Some(Duration::from_millis(1)), 1));
+        assert_eq!(stats.buckets[2], (Some(Duration::from_millis(5)), 2));
+        assert_eq!(stats.buckets[5], (Some(Duration::from_millis(50)), 1));
+        assert_eq!(stats.buckets.last(), Some(&(None, 1)));
+    }
+
+    #[test]
+    fn test_mean_and_quantile() {
+        let stats = stats(&[10, 10, 10, 10, 200]);
+
+        assert_eq!(stats.mean(), Duration::from_millis(48));
+        assert_eq!(stats.quantile(0.5), Duration::from_millis(10));
+        assert_eq!(stats.quantile(1.0), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_registry_snapshot_ordering() {
+        let registry = Registry::default();
+        registry.record("fast", Duration::from_millis(1));
+        registry.record("slow", Duration::from_millis(100));
+        registry.record("fast", Duration::from_millis(1));
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.queries[0].label, "slow");
+        assert_eq!(snapshot.get("fast").map(|s| s.count), Some(2));
+        assert_eq!(snapshot.total_count(), 3);
+
+        registry.reset();
+        assert!(registry.snapshot().queries.is_empty());
+    }
+}
@@ -10,7 +10,7 @@ use diesel::result::{ConnectionError, Error};
 use diesel_async::pooled_connection::PoolError as DieselPoolError;
 use diesel_async::pooled_connection::deadpool::PoolError as DeadpoolError;
 
-use crate::types::ConstraintViolation;
+use crate::types::{ConstraintViolation, CredentialError};
 
 /// Type-erased error type for dynamic error handling.
 pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
@@ -64,6 +64,13 @@ pub enum PgError {
     #[error("Unexpected error: {0}")]
     Unexpected(Cow<'static, str>),
 
+    /// Stored credentials could not be sealed or opened.
+    ///
+    /// This occurs when a credential key version is missing from the
+    /// configured keyring or a stored value fails authentication.
+    #[error("Credential vault error: {0}")]
+    Credential(#[from] CredentialError),
+
     /// Timestamp arithmetic error.
     ///
     /// This occurs when timestamp arithmetic (addition or subtraction) results
@@ -22,6 +22,11 @@ pub const TRACING_TARGET_MIGRATION: &str = "nvisy_postgres::migrations";
 /// configuration, and connection errors.
 pub const TRACING_TARGET_CONNECTION: &str = "nvisy_postgres::connection";
 
+/// Tracing target for tenant scoping.
+///
+/// Use this target for auditing queries that run outside a single workspace.
+pub const TRACING_TARGET_TENANCY: &str = "nvisy_postgres::tenancy";
+
 mod client;
 mod error;
 pub mod model;
@@ -34,7 +39,8 @@ pub use jiff_diesel::Timestamp as JiffTimestamp;
 
 pub(crate) use crate::client::PooledConnection;
 pub use crate::client::{
-    ConnectionPool, MigrationResult, MigrationStatus, PgClient, PgClientMigrationExt, PgConfig,
-    PgConn, PgPoolStatus,
+    CHANGE_EVENTS_CHANNEL, ConnectionPool, MigrationPrivileges, MigrationResult, MigrationStatus,
+    PgChangeListener, PgClient, PgClientMigrationExt, PgConfig, PgConn, PgPoolRole, PgPoolStatus,
+    QueryMetricsSnapshot, QueryStats, query_metrics, reset_query_metrics,
 };
 pub use crate::error::{DieselError, PgError, PgResult};
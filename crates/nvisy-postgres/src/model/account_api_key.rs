@@ -0,0 +1,121 @@
+//! Account API key model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::account_api_keys;
+use crate::types::{ApiKeyScope, HasCreatedAt, HasExpiresAt, WorkspaceRole};
+
+/// Account API key model representing a long-lived, scoped credential.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = account_api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AccountApiKey {
+    /// Unique identifier for the key.
+    pub id: Uuid,
+    /// Reference to the account this key belongs to.
+    pub account_id: Uuid,
+    /// Human-readable name for the key.
+    pub name: String,
+    /// Permission scopes granted to the key.
+    pub scopes: Vec<Option<ApiKeyScope>>,
+    /// Leading characters of the key, for identification in listings.
+    pub key_prefix: String,
+    /// SHA-256 hash of the full key.
+    pub key_hash: Vec<u8>,
+    /// Key issued when this key was rotated.
+    pub replaced_by: Option<Uuid>,
+    /// Timestamp of key creation.
+    pub created_at: Timestamp,
+    /// Timestamp when the key expires (None = never expires).
+    pub expired_at: Option<Timestamp>,
+    /// Timestamp of most recent key usage.
+    pub last_used_at: Option<Timestamp>,
+    /// Timestamp when the key was revoked.
+    pub deleted_at: Option<Timestamp>,
+}
+
+/// Data for creating a new account API key.
+#[derive(Debug, Default, Clone, Insertable)]
+#[diesel(table_name = account_api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewAccountApiKey {
+    /// Reference to the account this key belongs to.
+    pub account_id: Uuid,
+    /// Human-readable name for the key.
+    pub name: String,
+    /// Permission scopes granted to the key.
+    pub scopes: Vec<Option<ApiKeyScope>>,
+    /// Leading characters of the key, for identification in listings.
+    pub key_prefix: String,
+    /// SHA-256 hash of the full key.
+    pub key_hash: Vec<u8>,
+    /// Timestamp when the key expires.
+    pub expired_at: Option<Timestamp>,
+}
+
+/// Data for updating an account API key.
+#[derive(Debug, Default, Clone, AsChangeset)]
+#[diesel(table_name = account_api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct UpdateAccountApiKey {
+    /// Updated name for the key.
+    pub name: Option<String>,
+    /// Key issued when this key was rotated.
+    pub replaced_by: Option<Option<Uuid>>,
+    /// Timestamp when the key expires.
+    pub expired_at: Option<Option<Timestamp>>,
+    /// Timestamp of most recent key usage.
+    pub last_used_at: Option<Option<Timestamp>>,
+    /// Timestamp when the key was revoked.
+    pub deleted_at: Option<Option<Timestamp>>,
+}
+
+impl AccountApiKey {
+    /// Returns whether the key can currently authenticate.
+    pub fn is_valid(&self) -> bool {
+        !self.is_expired() && !self.is_deleted()
+    }
+
+    /// Returns whether the key has expired.
+    /// Returns false if the key never expires (expired_at is None).
+    pub fn is_expired(&self) -> bool {
+        match self.expired_at {
+            Some(expired_at) => jiff::Timestamp::now() > jiff::Timestamp::from(expired_at),
+            None => false,
+        }
+    }
+
+    /// Returns whether the key has been revoked.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Returns whether the key has been rotated and is in its grace window.
+    pub fn is_rotated(&self) -> bool {
+        self.replaced_by.is_some()
+    }
+
+    /// Returns the scopes granted to the key.
+    pub fn granted_scopes(&self) -> Vec<ApiKeyScope> {
+        self.scopes.iter().filter_map(|scope| *scope).collect()
+    }
+
+    /// Returns whether the key may exercise permissions requiring `role`.
+    pub fn covers(&self, role: WorkspaceRole) -> bool {
+        self.scopes.iter().flatten().any(|scope| scope.covers(role))
+    }
+}
+
+impl HasCreatedAt for AccountApiKey {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
+
+impl HasExpiresAt for AccountApiKey {
+    fn expires_at(&self) -> Option<jiff::Timestamp> {
+        self.expired_at.map(Into::into)
+    }
+}
@@ -45,6 +45,8 @@ pub struct AccountApiToken {
 #[diesel(table_name = account_api_tokens)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct NewAccountApiToken {
+    /// Unique token identifier, assigned by the database when absent.
+    pub id: Option<Uuid>,
     /// Reference to the account this token belongs to.
     pub account_id: Uuid,
     /// Human-readable name for the API token.
@@ -57,6 +59,9 @@ pub struct NewAccountApiToken {
     pub user_agent: Option<String>,
     /// Flag indicating if this is a "remember me" extended token.
     pub is_remembered: Option<bool>,
+    /// Timestamp when the token was issued, the database's current time
+    /// when absent.
+    pub issued_at: Option<Timestamp>,
     /// Timestamp when the token expires and becomes invalid.
     pub expired_at: Option<Timestamp>,
 }
@@ -0,0 +1,50 @@
+//! Account identity model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::account_identities;
+use crate::types::HasCreatedAt;
+
+/// Account identity model linking an account to an external OIDC subject.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = account_identities)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AccountIdentity {
+    /// Unique identifier for the identity.
+    pub id: Uuid,
+    /// Reference to the linked account.
+    pub account_id: Uuid,
+    /// Issuer URL of the identity provider.
+    pub issuer: String,
+    /// Subject identifier assigned by the provider.
+    pub subject: String,
+    /// Email address reported by the provider at the last login.
+    pub email_address: Option<String>,
+    /// Timestamp when the identity was linked.
+    pub created_at: Timestamp,
+    /// Timestamp of the most recent login through this identity.
+    pub last_login_at: Timestamp,
+}
+
+/// Data for linking a new account identity.
+#[derive(Debug, Default, Clone, Insertable)]
+#[diesel(table_name = account_identities)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewAccountIdentity {
+    /// Reference to the linked account.
+    pub account_id: Uuid,
+    /// Issuer URL of the identity provider.
+    pub issuer: String,
+    /// Subject identifier assigned by the provider.
+    pub subject: String,
+    /// Email address reported by the provider.
+    pub email_address: Option<String>,
+}
+
+impl HasCreatedAt for AccountIdentity {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
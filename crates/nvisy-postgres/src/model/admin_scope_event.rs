@@ -0,0 +1,40 @@
+//! Admin scope event model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::admin_scope_events;
+use crate::types::HasCreatedAt;
+
+/// Record of a query scope opened across workspaces.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = admin_scope_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AdminScopeEvent {
+    /// Unique event identifier.
+    pub id: Uuid,
+    /// Reason given for crossing workspaces.
+    pub reason: String,
+    /// Source location that opened the scope.
+    pub caller: String,
+    /// Timestamp when the scope was opened.
+    pub created_at: Timestamp,
+}
+
+/// Data for recording an opened admin scope.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = admin_scope_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewAdminScopeEvent {
+    /// Reason given for crossing workspaces.
+    pub reason: String,
+    /// Source location that opened the scope.
+    pub caller: String,
+}
+
+impl HasCreatedAt for AdminScopeEvent {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
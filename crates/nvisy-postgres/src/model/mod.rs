@@ -4,33 +4,55 @@
 //! including structs for querying, inserting, and updating records.
 
 mod account;
+mod account_api_key;
 mod account_api_token;
+mod account_identity;
 mod account_notification;
+mod admin_scope_event;
 mod pipeline_reference;
+mod storage_price;
 mod workspace;
 mod workspace_activity;
+mod workspace_change_event;
 mod workspace_connection;
 mod workspace_connection_run;
 mod workspace_context;
+mod workspace_custom_role;
+mod workspace_detection_review;
 mod workspace_file;
+mod workspace_file_access;
+mod workspace_file_share;
+mod workspace_inbound_webhook;
 mod workspace_invite;
+mod workspace_legal_hold;
 mod workspace_member;
+mod workspace_operation;
 mod workspace_pipeline;
 mod workspace_pipeline_artifact;
 mod workspace_pipeline_run;
 mod workspace_policy;
+mod workspace_retention_policy;
+mod workspace_storage_cost;
+mod workspace_temporary_object;
 mod workspace_webhook;
+mod workspace_webhook_delivery;
 
 // Account models
 pub use account::{Account, NewAccount, UpdateAccount};
+pub use account_api_key::{AccountApiKey, NewAccountApiKey, UpdateAccountApiKey};
 pub use account_api_token::{AccountApiToken, NewAccountApiToken, UpdateAccountApiToken};
+pub use account_identity::{AccountIdentity, NewAccountIdentity};
 pub use account_notification::{
     AccountNotification, NewAccountNotification, UpdateAccountNotification,
 };
+pub use admin_scope_event::{AdminScopeEvent, NewAdminScopeEvent};
 pub use pipeline_reference::{PipelineContext, PipelinePolicy};
+// Storage cost models
+pub use storage_price::{NewStoragePrice, StoragePrice};
 // Workspace models
 pub use workspace::{NewWorkspace, UpdateWorkspace, Workspace};
 pub use workspace_activity::{NewWorkspaceActivity, WorkspaceActivity};
+pub use workspace_change_event::{NewWorkspaceChangeEvent, WorkspaceChangeEvent};
 pub use workspace_connection::{
     NewWorkspaceConnection, UpdateWorkspaceConnection, WorkspaceConnection,
 };
@@ -38,9 +60,22 @@ pub use workspace_connection_run::{
     NewWorkspaceConnectionRun, UpdateWorkspaceConnectionRun, WorkspaceConnectionRun,
 };
 pub use workspace_context::{NewWorkspaceContext, UpdateWorkspaceContext, WorkspaceContext};
+pub use workspace_custom_role::{
+    NewWorkspaceCustomRole, UpdateWorkspaceCustomRole, WorkspaceCustomRole,
+};
+pub use workspace_detection_review::{NewWorkspaceDetectionReview, WorkspaceDetectionReview};
 pub use workspace_file::{NewWorkspaceFile, UpdateWorkspaceFile, WorkspaceFile};
+pub use workspace_file_access::{
+    NewWorkspaceFileAccess, WorkspaceFileAccessStats, WorkspaceFileAccessSummary,
+};
+pub use workspace_file_share::{NewWorkspaceFileShare, WorkspaceFileShare};
+pub use workspace_inbound_webhook::{NewWorkspaceInboundWebhook, WorkspaceInboundWebhook};
 pub use workspace_invite::{NewWorkspaceInvite, UpdateWorkspaceInvite, WorkspaceInvite};
+pub use workspace_legal_hold::{NewWorkspaceLegalHold, WorkspaceLegalHold};
 pub use workspace_member::{NewWorkspaceMember, UpdateWorkspaceMember, WorkspaceMember};
+pub use workspace_operation::{
+    NewWorkspaceOperation, UpdateWorkspaceOperation, WorkspaceOperation,
+};
 pub use workspace_pipeline::{NewWorkspacePipeline, UpdateWorkspacePipeline, WorkspacePipeline};
 // Pipeline models
 pub use workspace_pipeline_artifact::{NewWorkspacePipelineArtifact, WorkspacePipelineArtifact};
@@ -48,4 +83,8 @@ pub use workspace_pipeline_run::{
     NewWorkspacePipelineRun, UpdateWorkspacePipelineRun, WorkspacePipelineRun,
 };
 pub use workspace_policy::{NewWorkspacePolicy, UpdateWorkspacePolicy, WorkspacePolicy};
+pub use workspace_retention_policy::{NewWorkspaceRetentionPolicy, WorkspaceRetentionPolicy};
+pub use workspace_storage_cost::{NewWorkspaceStorageSample, WorkspaceStorageCost};
+pub use workspace_temporary_object::{NewWorkspaceTemporaryObject, WorkspaceTemporaryObject};
 pub use workspace_webhook::{NewWorkspaceWebhook, UpdateWorkspaceWebhook, WorkspaceWebhook};
+pub use workspace_webhook_delivery::{NewWorkspaceWebhookDelivery, WorkspaceWebhookDeliveryStats};
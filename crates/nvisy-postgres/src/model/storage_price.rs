@@ -0,0 +1,55 @@
+//! Storage price model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::{Date, Timestamp};
+use uuid::Uuid;
+
+use crate::schema::storage_prices;
+use crate::types::{DataRegion, HasCreatedAt, StorageClass};
+
+/// Price of object storage in a region and storage class.
+///
+/// Prices are never edited: a change is a new row with a later effective
+/// date, so past reports can be traced to the price they applied.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = storage_prices)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StoragePrice {
+    /// Unique price identifier.
+    pub id: Uuid,
+    /// Region whose backends the price applies to.
+    pub data_region: DataRegion,
+    /// Storage class the price applies to.
+    pub storage_class: StorageClass,
+    /// ISO 4217 currency code.
+    pub currency: String,
+    /// Price of one GiB stored for a month, in millionths of the currency.
+    pub price_micros_per_gib_month: i64,
+    /// First day the price applies.
+    pub effective_from: Date,
+    /// Timestamp when the price was recorded.
+    pub created_at: Timestamp,
+}
+
+/// Data for recording a new storage price.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = storage_prices)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewStoragePrice {
+    /// Region the price applies to.
+    pub data_region: DataRegion,
+    /// Storage class the price applies to.
+    pub storage_class: StorageClass,
+    /// ISO 4217 currency code.
+    pub currency: String,
+    /// Price of one GiB stored for a month, in millionths of the currency.
+    pub price_micros_per_gib_month: i64,
+    /// First day the price applies.
+    pub effective_from: Date,
+}
+
+impl HasCreatedAt for StoragePrice {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
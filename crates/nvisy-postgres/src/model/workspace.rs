@@ -5,7 +5,9 @@ use jiff_diesel::Timestamp;
 use uuid::Uuid;
 
 use crate::schema::workspaces;
-use crate::types::{HasCreatedAt, HasDeletedAt, HasOwnership, HasUpdatedAt, Slug, Tags};
+use crate::types::{
+    DataRegion, HasCreatedAt, HasDeletedAt, HasOwnership, HasUpdatedAt, Slug, Tags,
+};
 
 /// Main workspace model representing a workspace workspace.
 #[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
@@ -38,6 +40,8 @@ pub struct Workspace {
     pub updated_at: Timestamp,
     /// Timestamp when the workspace was soft-deleted.
     pub deleted_at: Option<Timestamp>,
+    /// Region the workspace's data is pinned to.
+    pub data_region: DataRegion,
 }
 
 /// Data for creating a new workspace.
@@ -63,6 +67,8 @@ pub struct NewWorkspace {
     pub settings: Option<serde_json::Value>,
     /// Created by.
     pub created_by: Uuid,
+    /// Data residency region (defaults to global).
+    pub data_region: Option<DataRegion>,
 }
 
 /// Data for updating a workspace.
@@ -2,10 +2,17 @@
 //!
 //! This module provides models for tracking and managing workspace activity logs,
 //! which record all significant actions performed within workspaces.
+//!
+//! Activities form a hash chain per workspace: every record is numbered and
+//! carries a SHA-256 hash over its contents and the previous record's hash,
+//! so modified, removed or reordered records are detectable.
 
 use diesel::prelude::*;
 use ipnet::IpNet;
 use jiff_diesel::Timestamp;
+use nvisy_core::clock::Clock;
+use nvisy_core::crypto::{CryptoProvider, SHA256_LEN};
+use nvisy_core::id::IdGenerator;
 use uuid::Uuid;
 
 use crate::schema::workspace_activities;
@@ -39,15 +46,22 @@ pub struct WorkspaceActivity {
     pub user_agent: Option<String>,
     /// Timestamp when the activity occurred.
     pub created_at: Timestamp,
+    /// Position of the activity in its workspace's chain, starting at 1.
+    pub sequence_number: i64,
+    /// Resource the activity acted on, if any.
+    pub resource_id: Option<Uuid>,
+    /// Hash of the previous record in the chain.
+    pub previous_hash: Option<Vec<u8>>,
+    /// Hash of this record, or `None` for records predating the chain.
+    pub record_hash: Option<Vec<u8>>,
 }
 
 /// Data structure for creating a new workspace activity entry.
 ///
 /// Contains all the necessary information to log a new activity in the workspace
-/// activity log. The ID and creation timestamp are automatically generated.
-#[derive(Debug, Default, Clone, Insertable)]
-#[diesel(table_name = workspace_activities)]
-#[diesel(check_for_backend(diesel::pg::Pg))]
+/// activity log. The ID, creation timestamp, sequence number and hashes are
+/// assigned when the activity is appended to the chain.
+#[derive(Debug, Default, Clone)]
 pub struct NewWorkspaceActivity {
     /// Reference to the workspace where the activity occurred.
     pub workspace_id: Uuid,
@@ -55,6 +69,8 @@ pub struct NewWorkspaceActivity {
     pub account_id: Option<Uuid>,
     /// Type of activity being logged.
     pub activity_type: ActivityType,
+    /// Resource the activity acted on.
+    pub resource_id: Option<Uuid>,
     /// Human-readable description of what occurred.
     pub description: Option<String>,
     /// Additional structured data about the activity.
@@ -65,6 +81,185 @@ pub struct NewWorkspaceActivity {
     pub user_agent: Option<String>,
 }
 
+impl NewWorkspaceActivity {
+    /// Links the activity into its workspace chain after the record at
+    /// `previous`, given as its sequence number and hash.
+    ///
+    /// The record takes its ID from `ids` and is stamped with the time of
+    /// `clock`, truncated to the microsecond precision Postgres stores, so
+    /// the hash computed here matches the one recomputed from the stored row.
+    pub(crate) fn into_chained(
+        self,
+        previous: Option<(i64, Option<Vec<u8>>)>,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> ChainedWorkspaceActivity {
+        let now = clock.now();
+        let created_at = jiff::Timestamp::from_microsecond(now.as_microsecond())
+            .expect("truncated timestamp is in range");
+        let (previous_sequence_number, previous_hash) = previous.unwrap_or_default();
+
+        let mut activity = ChainedWorkspaceActivity {
+            id: ids.generate(),
+            workspace_id: self.workspace_id,
+            account_id: self.account_id,
+            activity_type: self.activity_type,
+            description: self.description.unwrap_or_default(),
+            metadata: canonical_metadata(
+                self.metadata
+                    .unwrap_or_else(|| serde_json::Value::Object(Default::default())),
+            ),
+            ip_address: self.ip_address,
+            user_agent: self.user_agent,
+            created_at: created_at.into(),
+            sequence_number: previous_sequence_number + 1,
+            resource_id: self.resource_id,
+            previous_hash,
+            record_hash: Vec::new(),
+        };
+
+        activity.record_hash = provider.sha256(&activity.chain_fields().encode()).to_vec();
+        activity
+    }
+}
+
+/// A new activity linked into its workspace chain, ready to insert.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_activities)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub(crate) struct ChainedWorkspaceActivity {
+    id: Uuid,
+    workspace_id: Uuid,
+    account_id: Option<Uuid>,
+    activity_type: ActivityType,
+    description: String,
+    metadata: serde_json::Value,
+    ip_address: Option<IpNet>,
+    user_agent: Option<String>,
+    created_at: Timestamp,
+    sequence_number: i64,
+    resource_id: Option<Uuid>,
+    previous_hash: Option<Vec<u8>>,
+    record_hash: Vec<u8>,
+}
+
+impl ChainedWorkspaceActivity {
+    fn chain_fields(&self) -> ChainFields<'_> {
+        ChainFields {
+            id: self.id,
+            workspace_id: self.workspace_id,
+            sequence_number: self.sequence_number,
+            account_id: self.account_id,
+            activity_type: self.activity_type,
+            resource_id: self.resource_id,
+            description: &self.description,
+            metadata: &self.metadata,
+            ip_address: self.ip_address,
+            user_agent: self.user_agent.as_deref(),
+            created_at: self.created_at.into(),
+            previous_hash: self.previous_hash.as_deref(),
+        }
+    }
+}
+
+/// Domain separator prefixed to every hashed activity record.
+const CHAIN_DOMAIN: &[u8] = b"nvisy.workspace_activity.v1";
+
+/// The hashed fields of an activity record.
+struct ChainFields<'a> {
+    id: Uuid,
+    workspace_id: Uuid,
+    sequence_number: i64,
+    account_id: Option<Uuid>,
+    activity_type: ActivityType,
+    resource_id: Option<Uuid>,
+    description: &'a str,
+    metadata: &'a serde_json::Value,
+    ip_address: Option<IpNet>,
+    user_agent: Option<&'a str>,
+    created_at: jiff::Timestamp,
+    previous_hash: Option<&'a [u8]>,
+}
+
+impl ChainFields<'_> {
+    /// Encodes the fields unambiguously: every field is length-prefixed and
+    /// optional fields carry a presence byte.
+    fn encode(&self) -> Vec<u8> {
+        fn field(buf: &mut Vec<u8>, bytes: &[u8]) {
+            buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            buf.extend_from_slice(bytes);
+        }
+
+        fn optional(buf: &mut Vec<u8>, bytes: Option<&[u8]>) {
+            match bytes {
+                Some(bytes) => {
+                    buf.push(1);
+                    field(buf, bytes);
+                }
+                None => buf.push(0),
+            }
+        }
+
+        let activity_type = serde_json::to_string(&self.activity_type).unwrap_or_default();
+        let metadata = canonical_metadata(self.metadata.clone()).to_string();
+        let ip_address = self.ip_address.map(|ip| ip.to_string());
+
+        let mut buf = Vec::with_capacity(256 + metadata.len());
+        field(&mut buf, CHAIN_DOMAIN);
+        field(&mut buf, self.id.as_bytes());
+        field(&mut buf, self.workspace_id.as_bytes());
+        field(&mut buf, &self.sequence_number.to_be_bytes());
+        optional(
+            &mut buf,
+            self.account_id.as_ref().map(|id| id.as_bytes().as_slice()),
+        );
+        field(&mut buf, activity_type.as_bytes());
+        optional(
+            &mut buf,
+            self.resource_id.as_ref().map(|id| id.as_bytes().as_slice()),
+        );
+        field(&mut buf, self.description.as_bytes());
+        field(&mut buf, metadata.as_bytes());
+        optional(&mut buf, ip_address.as_deref().map(str::as_bytes));
+        optional(&mut buf, self.user_agent.map(str::as_bytes));
+        field(&mut buf, &self.created_at.as_microsecond().to_be_bytes());
+        optional(&mut buf, self.previous_hash);
+        buf
+    }
+}
+
+/// Brings metadata into the form Postgres returns it in.
+///
+/// Object keys are already sorted by `serde_json`; integral floats are
+/// rewritten as integers, since `jsonb` keeps `2.0` and `1e19` as numerics
+/// that read back as integers.
+fn canonical_metadata(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::{Number, Value};
+
+    match value {
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if !number.is_i64() && !number.is_u64() && float.fract() == 0.0 => {
+                if float >= i64::MIN as f64 && float < i64::MAX as f64 {
+                    Value::Number(Number::from(float as i64))
+                } else if float >= 0.0 && float < u64::MAX as f64 {
+                    Value::Number(Number::from(float as u64))
+                } else {
+                    Value::Number(number)
+                }
+            }
+            _ => Value::Number(number),
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical_metadata).collect()),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, canonical_metadata(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
 impl WorkspaceActivity {
     /// Returns whether this activity was performed by a system process.
     pub fn is_system_activity(&self) -> bool {
@@ -100,7 +295,7 @@ impl WorkspaceActivity {
     pub fn is_high_priority(&self) -> bool {
         matches!(
             self.category(),
-            ActivityCategory::Member | ActivityCategory::Workspace
+            ActivityCategory::Member | ActivityCategory::Workspace | ActivityCategory::LegalHold
         )
     }
 
@@ -108,7 +303,10 @@ impl WorkspaceActivity {
     pub fn is_user_action(&self) -> bool {
         matches!(
             self.category(),
-            ActivityCategory::Member | ActivityCategory::File
+            ActivityCategory::Member
+                | ActivityCategory::File
+                | ActivityCategory::Share
+                | ActivityCategory::LegalHold
         )
     }
 
@@ -138,6 +336,38 @@ impl WorkspaceActivity {
     pub fn ip_address_string(&self) -> Option<String> {
         self.ip_address.map(|ip| ip.to_string())
     }
+
+    /// Returns whether the record is part of the hash chain.
+    ///
+    /// Records written before chaining was introduced carry no hash.
+    pub fn is_chained(&self) -> bool {
+        self.record_hash.is_some()
+    }
+
+    /// Recomputes the record's hash from its stored contents.
+    ///
+    /// A chained record is intact when this equals its `record_hash`.
+    /// Records are only hashed once written; an account removed outright
+    /// (rather than soft-deleted) clears `account_id` and invalidates the
+    /// hashes of the activities it performed.
+    pub fn compute_hash(&self, provider: &dyn CryptoProvider) -> [u8; SHA256_LEN] {
+        let fields = ChainFields {
+            id: self.id,
+            workspace_id: self.workspace_id,
+            sequence_number: self.sequence_number,
+            account_id: self.account_id,
+            activity_type: self.activity_type,
+            resource_id: self.resource_id,
+            description: &self.description,
+            metadata: &self.metadata,
+            ip_address: self.ip_address,
+            user_agent: self.user_agent.as_deref(),
+            created_at: self.created_at.into(),
+            previous_hash: self.previous_hash.as_deref(),
+        };
+
+        provider.sha256(&fields.encode())
+    }
 }
 
 impl HasCreatedAt for WorkspaceActivity {
@@ -0,0 +1,93 @@
+//! Workspace change event model for PostgreSQL database operations.
+//!
+//! The change event table is the outbox for workspace events. The
+//! application records its events in the transaction that makes the change;
+//! database triggers record row changes made outside the application (admin
+//! tools, manual SQL, data migrations). The change listener drains both into
+//! NATS.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::schema::workspace_change_events;
+use crate::types::{ChangeEventSource, HasCreatedAt, WebhookEvent};
+
+/// A workspace event recorded in the outbox, awaiting publication.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_change_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceChangeEvent {
+    /// Monotonic change identifier, also the publication order.
+    pub id: i64,
+    /// Workspace the changed row belongs to.
+    pub workspace_id: Uuid,
+    /// Identifier of the changed row.
+    pub resource_id: Uuid,
+    /// Event the change corresponds to.
+    pub event: WebhookEvent,
+    /// Timestamp when the change was recorded.
+    pub created_at: Timestamp,
+    /// Timestamp when the change was published, if it has been.
+    pub published_at: Option<Timestamp>,
+    /// Who recorded the change.
+    pub source: ChangeEventSource,
+    /// Account that triggered the change, if any.
+    pub triggered_by: Option<Uuid>,
+    /// Event-specific payload, if any.
+    pub data: Option<JsonValue>,
+}
+
+/// An application event to record in the outbox.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_change_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceChangeEvent {
+    /// Workspace the event occurred in.
+    pub workspace_id: Uuid,
+    /// Affected resource.
+    pub resource_id: Uuid,
+    /// Event that occurred.
+    pub event: WebhookEvent,
+    /// Who recorded the event.
+    pub source: ChangeEventSource,
+    /// Account that triggered the event, if any.
+    pub triggered_by: Option<Uuid>,
+    /// Event-specific payload, if any.
+    pub data: Option<JsonValue>,
+}
+
+impl WorkspaceChangeEvent {
+    /// Returns whether the change has been published.
+    #[inline]
+    pub fn is_published(&self) -> bool {
+        self.published_at.is_some()
+    }
+}
+
+impl NewWorkspaceChangeEvent {
+    /// Creates an application event.
+    pub fn application(
+        workspace_id: Uuid,
+        event: WebhookEvent,
+        resource_id: Uuid,
+        triggered_by: Option<Uuid>,
+        data: Option<JsonValue>,
+    ) -> Self {
+        Self {
+            workspace_id,
+            resource_id,
+            event,
+            source: ChangeEventSource::Application,
+            triggered_by,
+            data,
+        }
+    }
+}
+
+impl HasCreatedAt for WorkspaceChangeEvent {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
@@ -1,5 +1,7 @@
 //! Workspace connection model for PostgreSQL database operations.
 
+use std::fmt;
+
 use diesel::prelude::*;
 use jiff_diesel::Timestamp;
 use serde_json::Value as JsonValue;
@@ -11,8 +13,12 @@ use crate::types::{HasCreatedAt, HasDeletedAt, HasUpdatedAt};
 /// Workspace connection model representing encrypted provider connections.
 ///
 /// Connections store both credentials and context (resumption state) for
-/// external providers like databases, cloud storage, and AI services.
-#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+/// external providers like databases, cloud storage, and AI services. The
+/// credentials stay sealed here; read them through
+/// [`find_connection_credentials`], which opens them.
+///
+/// [`find_connection_credentials`]: crate::query::WorkspaceConnectionRepository::find_connection_credentials
+#[derive(Clone, PartialEq, Queryable, Selectable)]
 #[diesel(table_name = workspace_connections)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct WorkspaceConnection {
@@ -26,8 +32,8 @@ pub struct WorkspaceConnection {
     pub name: String,
     /// Provider type for indexing (e.g., "openai", "postgres", "s3").
     pub provider: String,
-    /// Encrypted connection data (XChaCha20-Poly1305 encrypted JSON).
-    /// Contains credentials and context for resumption.
+    /// Connection data sealed by the credential vault (AES-256-GCM encrypted
+    /// JSON). Contains credentials and context for resumption.
     pub encrypted_data: Vec<u8>,
     /// Whether the connection is enabled for syncing.
     pub is_active: bool,
@@ -39,9 +45,14 @@ pub struct WorkspaceConnection {
     pub updated_at: Timestamp,
     /// Timestamp when the connection was soft-deleted.
     pub deleted_at: Option<Timestamp>,
+    /// Vault key version the data is sealed under (0 = legacy workspace key).
+    pub credential_key_version: i32,
 }
 
 /// Data for creating a new workspace connection.
+///
+/// Carries no credentials: the repository seals those and adds them to the
+/// row itself.
 #[derive(Debug, Clone, Insertable)]
 #[diesel(table_name = workspace_connections)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -54,8 +65,6 @@ pub struct NewWorkspaceConnection {
     pub name: String,
     /// Provider type for indexing.
     pub provider: String,
-    /// Encrypted connection data.
-    pub encrypted_data: Vec<u8>,
     /// Whether the connection is enabled for syncing.
     pub is_active: Option<bool>,
     /// Non-encrypted metadata for filtering/display.
@@ -63,6 +72,11 @@ pub struct NewWorkspaceConnection {
 }
 
 /// Data for updating a workspace connection.
+///
+/// Credentials are replaced separately, through
+/// [`store_connection_credentials`].
+///
+/// [`store_connection_credentials`]: crate::query::WorkspaceConnectionRepository::store_connection_credentials
 #[derive(Debug, Clone, Default, AsChangeset)]
 #[diesel(table_name = workspace_connections)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -71,8 +85,6 @@ pub struct UpdateWorkspaceConnection {
     pub name: Option<String>,
     /// Provider type.
     pub provider: Option<String>,
-    /// Encrypted connection data.
-    pub encrypted_data: Option<Vec<u8>>,
     /// Whether the connection is enabled for syncing.
     pub is_active: Option<bool>,
     /// Non-encrypted metadata for filtering/display.
@@ -88,6 +100,25 @@ impl WorkspaceConnection {
     }
 }
 
+impl fmt::Debug for WorkspaceConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkspaceConnection")
+            .field("id", &self.id)
+            .field("workspace_id", &self.workspace_id)
+            .field("account_id", &self.account_id)
+            .field("name", &self.name)
+            .field("provider", &self.provider)
+            .field("encrypted_data", &"[REDACTED]")
+            .field("is_active", &self.is_active)
+            .field("metadata", &self.metadata)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("deleted_at", &self.deleted_at)
+            .field("credential_key_version", &self.credential_key_version)
+            .finish()
+    }
+}
+
 impl HasCreatedAt for WorkspaceConnection {
     fn created_at(&self) -> jiff::Timestamp {
         self.created_at.into()
@@ -0,0 +1,77 @@
+//! Workspace custom role model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_custom_roles;
+use crate::types::{HasCreatedAt, HasUpdatedAt};
+
+/// Workspace-defined role granting a set of permissions.
+///
+/// Assigned to a member, the role's permissions are granted on top of the
+/// member's built-in role. Permissions are stored as `resource:action` keys
+/// interpreted by the server.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_custom_roles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceCustomRole {
+    /// Unique role identifier.
+    pub id: Uuid,
+    /// Reference to the workspace this role belongs to.
+    pub workspace_id: Uuid,
+    /// Reference to the account that created this role.
+    pub account_id: Uuid,
+    /// Role name, unique within the workspace.
+    pub name: String,
+    /// Role description.
+    pub description: Option<String>,
+    /// Granted permission keys.
+    pub permissions: Vec<String>,
+    /// Timestamp when the role was created.
+    pub created_at: Timestamp,
+    /// Timestamp when the role was last updated.
+    pub updated_at: Timestamp,
+}
+
+/// Data for creating a new workspace custom role.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_custom_roles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceCustomRole {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Account ID (required).
+    pub account_id: Uuid,
+    /// Role name.
+    pub name: String,
+    /// Role description.
+    pub description: Option<String>,
+    /// Granted permission keys.
+    pub permissions: Vec<String>,
+}
+
+/// Data for updating a workspace custom role.
+#[derive(Debug, Clone, Default, AsChangeset)]
+#[diesel(table_name = workspace_custom_roles)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct UpdateWorkspaceCustomRole {
+    /// Role name.
+    pub name: Option<String>,
+    /// Role description.
+    pub description: Option<Option<String>>,
+    /// Granted permission keys.
+    pub permissions: Option<Vec<String>>,
+}
+
+impl HasCreatedAt for WorkspaceCustomRole {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
+
+impl HasUpdatedAt for WorkspaceCustomRole {
+    fn updated_at(&self) -> jiff::Timestamp {
+        self.updated_at.into()
+    }
+}
@@ -0,0 +1,73 @@
+//! Workspace detection review model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_detection_reviews;
+use crate::types::{HasCreatedAt, ReviewStatus};
+
+/// One version of a reviewer's decision on a detected finding.
+///
+/// Every decision on a detection is recorded as a new version; the version it
+/// replaces stays in place with a `superseded` status, so a detection's
+/// history runs from its first review to its current decision.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_detection_reviews)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceDetectionReview {
+    /// Unique review identifier.
+    pub id: Uuid,
+    /// Run whose finding was reviewed.
+    pub run_id: Uuid,
+    /// Account that recorded the decision.
+    pub reviewer_id: Option<Uuid>,
+    /// Detection identifier within the run's analysis.
+    pub detection_id: String,
+    /// Version of the detection's review, starting at 1.
+    pub version: i32,
+    /// Decision recorded by this version.
+    pub decision: ReviewStatus,
+    /// The decision while current, `superseded` once replaced.
+    pub status: ReviewStatus,
+    /// Reviewer comment.
+    pub comment: Option<String>,
+    /// Timestamp when the decision was recorded.
+    pub created_at: Timestamp,
+    /// Timestamp when a later version replaced this one.
+    pub superseded_at: Option<Timestamp>,
+}
+
+/// Data for recording a new review version.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_detection_reviews)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceDetectionReview {
+    /// Run ID (required).
+    pub run_id: Uuid,
+    /// Reviewer account ID.
+    pub reviewer_id: Option<Uuid>,
+    /// Detection identifier.
+    pub detection_id: String,
+    /// Version number.
+    pub version: i32,
+    /// Recorded decision.
+    pub decision: ReviewStatus,
+    /// Initial status, equal to the decision.
+    pub status: ReviewStatus,
+    /// Reviewer comment.
+    pub comment: Option<String>,
+}
+
+impl WorkspaceDetectionReview {
+    /// Returns whether this is the detection's current decision.
+    pub fn is_current(&self) -> bool {
+        !self.status.is_superseded()
+    }
+}
+
+impl HasCreatedAt for WorkspaceDetectionReview {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
@@ -5,7 +5,9 @@ use jiff_diesel::Timestamp;
 use uuid::Uuid;
 
 use crate::schema::workspace_files;
-use crate::types::{FileSource, HasCreatedAt, HasDeletedAt, HasUpdatedAt, RECENTLY_UPLOADED_HOURS};
+use crate::types::{
+    DataSensitivity, FileSource, HasCreatedAt, HasDeletedAt, HasUpdatedAt, RECENTLY_UPLOADED_HOURS,
+};
 
 /// Workspace file model representing a file stored in the system.
 #[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
@@ -50,6 +52,14 @@ pub struct WorkspaceFile {
     pub updated_at: Timestamp,
     /// Timestamp when the file was soft-deleted.
     pub deleted_at: Option<Timestamp>,
+    /// Whether the document is encrypted and needs a password to be processed.
+    pub password_protected: bool,
+    /// Stored document password, encrypted under the workspace key.
+    pub encrypted_password: Option<Vec<u8>>,
+    /// Content sensitivity, fixed at upload.
+    pub sensitivity: DataSensitivity,
+    /// Version ID of the stored content, if recorded.
+    pub storage_version: Option<String>,
 }
 
 /// Data for creating a new workspace file.
@@ -85,6 +95,12 @@ pub struct NewWorkspaceFile {
     pub storage_bucket: String,
     /// Metadata.
     pub metadata: Option<serde_json::Value>,
+    /// Whether the document is password-protected.
+    pub password_protected: Option<bool>,
+    /// Content sensitivity.
+    pub sensitivity: Option<DataSensitivity>,
+    /// Version ID of the stored content.
+    pub storage_version: Option<String>,
 }
 
 /// Data for updating a workspace file.
@@ -106,6 +122,8 @@ pub struct UpdateWorkspaceFile {
     pub metadata: Option<serde_json::Value>,
     /// Soft delete timestamp.
     pub deleted_at: Option<Option<Timestamp>>,
+    /// Stored document password, encrypted under the workspace key.
+    pub encrypted_password: Option<Option<Vec<u8>>>,
 }
 
 impl WorkspaceFile {
@@ -119,6 +137,16 @@ impl WorkspaceFile {
         self.deleted_at.is_some()
     }
 
+    /// Returns whether the document needs a password to be processed.
+    pub fn is_password_protected(&self) -> bool {
+        self.password_protected
+    }
+
+    /// Returns whether a document password is stored for the file.
+    pub fn has_stored_password(&self) -> bool {
+        self.encrypted_password.is_some()
+    }
+
     /// Returns the file size in a human-readable format.
     pub fn file_size_human(&self) -> String {
         let bytes = self.file_size_bytes as f64;
@@ -0,0 +1,141 @@
+//! Workspace file access statistics models for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff::tz::TimeZone;
+use jiff_diesel::Date;
+use uuid::Uuid;
+
+use crate::schema::workspace_file_access_stats;
+
+/// A document's access counters for one day.
+///
+/// Only counts are kept; nothing identifies who accessed the document or
+/// when within the day.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_file_access_stats)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceFileAccessStats {
+    /// Workspace the file belongs to.
+    pub workspace_id: Uuid,
+    /// Accessed file.
+    pub file_id: Uuid,
+    /// Day the accesses happened on, in UTC.
+    pub access_date: Date,
+    /// Number of times the file was opened in the workspace.
+    pub view_count: i32,
+    /// Number of times the file content was downloaded.
+    pub download_count: i32,
+    /// Number of opens or downloads that followed a search.
+    pub search_open_count: i32,
+    /// Number of times the file was opened through a share link.
+    pub share_view_count: i32,
+}
+
+/// One access to a document, added to the counters of the current day.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_file_access_stats)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceFileAccess {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Accessed file (required).
+    pub file_id: Uuid,
+    /// Day the access happened on, in UTC.
+    pub access_date: Date,
+    /// Views to add.
+    pub view_count: i32,
+    /// Downloads to add.
+    pub download_count: i32,
+    /// Opens that followed a search to add.
+    pub search_open_count: i32,
+    /// Share link views to add.
+    pub share_view_count: i32,
+}
+
+impl NewWorkspaceFileAccess {
+    /// Creates an access with no counters set, dated today.
+    fn today(workspace_id: Uuid, file_id: Uuid) -> Self {
+        let today = jiff::Timestamp::now().to_zoned(TimeZone::UTC).date();
+        Self {
+            workspace_id,
+            file_id,
+            access_date: today.into(),
+            view_count: 0,
+            download_count: 0,
+            search_open_count: 0,
+            share_view_count: 0,
+        }
+    }
+
+    /// Records the file being opened in the workspace.
+    pub fn view(workspace_id: Uuid, file_id: Uuid) -> Self {
+        Self {
+            view_count: 1,
+            ..Self::today(workspace_id, file_id)
+        }
+    }
+
+    /// Records the file content being downloaded.
+    pub fn download(workspace_id: Uuid, file_id: Uuid) -> Self {
+        Self {
+            download_count: 1,
+            ..Self::today(workspace_id, file_id)
+        }
+    }
+
+    /// Records the file being opened through a share link.
+    pub fn share_view(workspace_id: Uuid, file_id: Uuid) -> Self {
+        Self {
+            share_view_count: 1,
+            ..Self::today(workspace_id, file_id)
+        }
+    }
+
+    /// Marks the access as following a search.
+    pub fn from_search(self, from_search: bool) -> Self {
+        Self {
+            search_open_count: i32::from(from_search),
+            ..self
+        }
+    }
+}
+
+/// A document's access counters summed over a period.
+///
+/// Search opens are also counted as views or downloads, so they are not
+/// part of [`total`](Self::total).
+#[derive(Debug, Clone, PartialEq, QueryableByName)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceFileAccessSummary {
+    /// Accessed file.
+    #[diesel(sql_type = diesel::sql_types::Uuid)]
+    pub file_id: Uuid,
+    /// Display name of the file.
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub display_name: String,
+    /// Timestamp when the file was uploaded.
+    #[diesel(sql_type = diesel::sql_types::Timestamptz)]
+    pub created_at: jiff_diesel::Timestamp,
+    /// Views in the period.
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub view_count: i64,
+    /// Downloads in the period.
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub download_count: i64,
+    /// Opens that followed a search in the period.
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub search_open_count: i64,
+    /// Share link views in the period.
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub share_view_count: i64,
+    /// Last day the file was accessed on, in or before the period.
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Date>)]
+    pub last_access_date: Option<Date>,
+}
+
+impl WorkspaceFileAccessSummary {
+    /// Returns the number of accesses in the period.
+    pub fn total(&self) -> i64 {
+        self.view_count + self.download_count + self.share_view_count
+    }
+}
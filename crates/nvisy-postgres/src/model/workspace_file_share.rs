@@ -0,0 +1,128 @@
+//! Workspace file share link model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_file_shares;
+use crate::types::{HasCreatedAt, HasExpiresAt};
+
+/// A link granting read access to a single document.
+///
+/// The link's token is an opaque secret; only its SHA-256 hash is stored.
+/// A link stops working when it expires, is revoked, or has been opened
+/// `max_views` times.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_file_shares)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceFileShare {
+    /// Unique share link identifier.
+    pub id: Uuid,
+    /// Workspace the shared file belongs to.
+    pub workspace_id: Uuid,
+    /// Shared file.
+    pub file_id: Uuid,
+    /// Account that created the link.
+    pub account_id: Option<Uuid>,
+    /// Who the link was issued to, stamped into watermarks.
+    pub recipient: String,
+    /// One-based pages the link grants; `None` grants the whole document.
+    pub pages: Option<Vec<Option<i32>>>,
+    /// Whether served content is watermarked with the recipient.
+    pub watermark: bool,
+    /// Leading characters of the token, for identification in listings.
+    pub token_prefix: String,
+    /// SHA-256 hash of the full token.
+    pub token_hash: Vec<u8>,
+    /// Number of times the link may be opened (`None` = unlimited).
+    pub max_views: Option<i32>,
+    /// Number of times the link has been opened.
+    pub view_count: i32,
+    /// Timestamp when the link was created.
+    pub created_at: Timestamp,
+    /// Timestamp when the link stops working.
+    pub expires_at: Timestamp,
+    /// Timestamp when the link was last opened.
+    pub last_viewed_at: Option<Timestamp>,
+    /// Timestamp when the link was revoked.
+    pub revoked_at: Option<Timestamp>,
+}
+
+/// Data for creating a new share link.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_file_shares)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceFileShare {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Shared file (required).
+    pub file_id: Uuid,
+    /// Account creating the link.
+    pub account_id: Option<Uuid>,
+    /// Who the link is issued to.
+    pub recipient: String,
+    /// One-based pages to grant, or `None` for the whole document.
+    pub pages: Option<Vec<Option<i32>>>,
+    /// Whether served content is watermarked.
+    pub watermark: bool,
+    /// Leading characters of the token.
+    pub token_prefix: String,
+    /// SHA-256 hash of the full token.
+    pub token_hash: Vec<u8>,
+    /// Number of times the link may be opened.
+    pub max_views: Option<i32>,
+    /// Timestamp when the link stops working.
+    pub expires_at: Timestamp,
+}
+
+impl WorkspaceFileShare {
+    /// Returns whether the link can currently be opened.
+    pub fn is_usable(&self) -> bool {
+        !self.is_revoked() && !self.is_expired() && !self.is_exhausted()
+    }
+
+    /// Returns whether the link has been revoked.
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Returns whether the link has expired.
+    pub fn is_expired(&self) -> bool {
+        jiff::Timestamp::now() >= jiff::Timestamp::from(self.expires_at)
+    }
+
+    /// Returns whether the link has used up its views.
+    pub fn is_exhausted(&self) -> bool {
+        self.max_views
+            .is_some_and(|max_views| self.view_count >= max_views)
+    }
+
+    /// Returns the number of views left, or `None` if unlimited.
+    pub fn remaining_views(&self) -> Option<i32> {
+        self.max_views
+            .map(|max_views| (max_views - self.view_count).max(0))
+    }
+
+    /// Returns the granted pages, or `None` if the whole document is granted.
+    pub fn granted_pages(&self) -> Option<Vec<u32>> {
+        self.pages.as_ref().map(|pages| {
+            pages
+                .iter()
+                .flatten()
+                .filter_map(|page| u32::try_from(*page).ok())
+                .collect()
+        })
+    }
+}
+
+impl HasCreatedAt for WorkspaceFileShare {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
+
+impl HasExpiresAt for WorkspaceFileShare {
+    fn expires_at(&self) -> Option<jiff::Timestamp> {
+        Some(self.expires_at.into())
+    }
+}
@@ -0,0 +1,96 @@
+//! Workspace inbound webhook model for PostgreSQL database operations.
+//!
+//! This module provides models for the endpoints through which third-party
+//! services notify a workspace of their events.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_inbound_webhooks;
+use crate::types::{
+    HasCreatedAt, HasDeletedAt, HasOwnership, HasUpdatedAt, InboundWebhookProvider,
+};
+
+/// Workspace inbound webhook model representing an endpoint receiving events.
+///
+/// The provider decides how requests to the endpoint are verified against
+/// the secret and normalized into workspace events.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_inbound_webhooks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceInboundWebhook {
+    /// Unique inbound webhook identifier.
+    pub id: Uuid,
+    /// Reference to the workspace the events are routed to.
+    pub workspace_id: Uuid,
+    /// Sender of the requests.
+    pub provider: InboundWebhookProvider,
+    /// Human-readable name for the inbound webhook.
+    pub display_name: String,
+    /// Verification secret, encrypted under the workspace key.
+    pub encrypted_secret: Vec<u8>,
+    /// Timestamp of the last accepted request.
+    pub last_received_at: Option<Timestamp>,
+    /// Account that created this inbound webhook.
+    pub created_by: Uuid,
+    /// Timestamp when this inbound webhook was created.
+    pub created_at: Timestamp,
+    /// Timestamp when this inbound webhook was last modified.
+    pub updated_at: Timestamp,
+    /// Timestamp when this inbound webhook was soft-deleted.
+    pub deleted_at: Option<Timestamp>,
+}
+
+/// Data structure for creating a new workspace inbound webhook.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_inbound_webhooks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceInboundWebhook {
+    /// Reference to the workspace the events will be routed to.
+    pub workspace_id: Uuid,
+    /// Sender of the requests.
+    pub provider: InboundWebhookProvider,
+    /// Human-readable name for the inbound webhook.
+    pub display_name: String,
+    /// Verification secret, encrypted under the workspace key.
+    pub encrypted_secret: Vec<u8>,
+    /// Account creating this inbound webhook.
+    pub created_by: Uuid,
+}
+
+impl WorkspaceInboundWebhook {
+    /// Returns whether the inbound webhook is accepting requests.
+    pub fn is_active(&self) -> bool {
+        self.deleted_at.is_none()
+    }
+
+    /// Returns whether the inbound webhook has accepted at least one request.
+    pub fn has_received(&self) -> bool {
+        self.last_received_at.is_some()
+    }
+}
+
+impl HasCreatedAt for WorkspaceInboundWebhook {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
+
+impl HasUpdatedAt for WorkspaceInboundWebhook {
+    fn updated_at(&self) -> jiff::Timestamp {
+        self.updated_at.into()
+    }
+}
+
+impl HasDeletedAt for WorkspaceInboundWebhook {
+    fn deleted_at(&self) -> Option<jiff::Timestamp> {
+        self.deleted_at.map(Into::into)
+    }
+}
+
+impl HasOwnership for WorkspaceInboundWebhook {
+    fn created_by(&self) -> Uuid {
+        self.created_by
+    }
+}
@@ -0,0 +1,65 @@
+//! Workspace legal hold model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_legal_holds;
+use crate::types::HasCreatedAt;
+
+/// A legal hold suspending retention for a workspace or one of its files.
+///
+/// While the hold is active the purge job removes nothing it covers, and a
+/// held file cannot be deleted.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_legal_holds)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceLegalHold {
+    /// Unique hold identifier.
+    pub id: Uuid,
+    /// Workspace the hold belongs to.
+    pub workspace_id: Uuid,
+    /// Held file; `None` holds the whole workspace.
+    pub file_id: Option<Uuid>,
+    /// Account that placed the hold.
+    pub account_id: Option<Uuid>,
+    /// Why the data is held.
+    pub reason: String,
+    /// Timestamp when the hold was placed.
+    pub created_at: Timestamp,
+    /// Timestamp when the hold was released.
+    pub released_at: Option<Timestamp>,
+}
+
+/// Data for placing a new legal hold.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_legal_holds)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceLegalHold {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Held file, or `None` for the whole workspace.
+    pub file_id: Option<Uuid>,
+    /// Account placing the hold.
+    pub account_id: Option<Uuid>,
+    /// Why the data is held.
+    pub reason: String,
+}
+
+impl WorkspaceLegalHold {
+    /// Returns whether the hold is still in force.
+    pub fn is_active(&self) -> bool {
+        self.released_at.is_none()
+    }
+
+    /// Returns whether the hold covers the whole workspace.
+    pub fn is_workspace_wide(&self) -> bool {
+        self.file_id.is_none()
+    }
+}
+
+impl HasCreatedAt for WorkspaceLegalHold {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
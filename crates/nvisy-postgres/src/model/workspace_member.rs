@@ -32,6 +32,8 @@ pub struct WorkspaceMember {
     pub created_at: Timestamp,
     /// Timestamp when membership was last updated.
     pub updated_at: Timestamp,
+    /// Custom role granting permissions beyond the member's role.
+    pub custom_role_id: Option<Uuid>,
 }
 
 /// Data for creating a new workspace member.
@@ -55,6 +57,8 @@ pub struct NewWorkspaceMember {
     pub created_by: Uuid,
     /// Updated by.
     pub updated_by: Uuid,
+    /// Custom role.
+    pub custom_role_id: Option<Uuid>,
 }
 
 impl NewWorkspaceMember {
@@ -91,6 +95,8 @@ pub struct UpdateWorkspaceMember {
     pub notification_events_email: Option<Vec<Option<NotificationEvent>>>,
     /// Updated by.
     pub updated_by: Option<Uuid>,
+    /// Custom role.
+    pub custom_role_id: Option<Option<Uuid>>,
 }
 
 impl WorkspaceMember {
@@ -0,0 +1,138 @@
+//! Workspace operation model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::schema::workspace_operations;
+use crate::types::{HasCreatedAt, HasUpdatedAt, OperationKind, OperationStatus};
+
+/// A long-running operation: an asynchronous job and its outcome.
+///
+/// The request that starts a job answers with the operation; the job then
+/// reports progress on it and finally records either a result or an error.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_operations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceOperation {
+    /// Unique operation identifier.
+    pub id: Uuid,
+    /// Workspace the operation runs in.
+    pub workspace_id: Uuid,
+    /// Account that started the operation (optional).
+    pub account_id: Option<Uuid>,
+    /// Kind of job the operation tracks.
+    pub kind: OperationKind,
+    /// Current lifecycle status.
+    pub status: OperationStatus,
+    /// Completion estimate in percent.
+    pub progress: i16,
+    /// Resource the operation acts on or produces.
+    pub target_id: Option<Uuid>,
+    /// Job-specific result, set on success.
+    pub result: Option<JsonValue>,
+    /// Error code and message, set on failure.
+    pub error: Option<JsonValue>,
+    /// When the operation was accepted.
+    pub created_at: Timestamp,
+    /// When the status or progress last changed.
+    pub updated_at: Timestamp,
+    /// When the job started running.
+    pub started_at: Option<Timestamp>,
+    /// When the operation reached a terminal status.
+    pub completed_at: Option<Timestamp>,
+    /// Object storage key of the result artifact.
+    pub artifact_key: Option<String>,
+    /// File name the artifact downloads as.
+    pub artifact_name: Option<String>,
+    /// Media type of the artifact.
+    pub artifact_content_type: Option<String>,
+    /// Size of the artifact in bytes.
+    pub artifact_size: Option<i64>,
+    /// When the operation and its artifact are removed.
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Data for creating a new workspace operation.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_operations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceOperation {
+    /// Operation ID, assigned by the database when absent.
+    pub id: Option<Uuid>,
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Account ID (optional).
+    pub account_id: Option<Uuid>,
+    /// Kind of job (required).
+    pub kind: OperationKind,
+    /// Resource the operation acts on or produces.
+    pub target_id: Option<Uuid>,
+}
+
+/// Data for updating a workspace operation.
+#[derive(Debug, Clone, Default, AsChangeset)]
+#[diesel(table_name = workspace_operations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct UpdateWorkspaceOperation {
+    /// Lifecycle status.
+    pub status: Option<OperationStatus>,
+    /// Completion estimate in percent.
+    pub progress: Option<i16>,
+    /// Resource the operation acts on or produces.
+    pub target_id: Option<Option<Uuid>>,
+    /// Job-specific result.
+    pub result: Option<Option<JsonValue>>,
+    /// Error code and message.
+    pub error: Option<Option<JsonValue>>,
+    /// When the job started running.
+    pub started_at: Option<Option<Timestamp>>,
+    /// When the operation reached a terminal status.
+    pub completed_at: Option<Option<Timestamp>>,
+    /// Object storage key of the result artifact.
+    pub artifact_key: Option<Option<String>>,
+    /// File name the artifact downloads as.
+    pub artifact_name: Option<Option<String>>,
+    /// Media type of the artifact.
+    pub artifact_content_type: Option<Option<String>>,
+    /// Size of the artifact in bytes.
+    pub artifact_size: Option<Option<i64>>,
+    /// When the operation and its artifact are removed.
+    pub expires_at: Option<Option<Timestamp>>,
+}
+
+impl WorkspaceOperation {
+    /// Returns whether the operation has not finished yet.
+    pub fn is_in_progress(&self) -> bool {
+        self.status.is_in_progress()
+    }
+
+    /// Returns whether the operation reached a terminal status.
+    pub fn is_terminal(&self) -> bool {
+        self.status.is_terminal()
+    }
+
+    /// Returns whether the operation left a downloadable artifact.
+    pub fn has_artifact(&self) -> bool {
+        self.artifact_key.is_some()
+    }
+
+    /// Returns whether the operation is past its retention period.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| jiff::Timestamp::from(expires_at) <= jiff::Timestamp::now())
+    }
+}
+
+impl HasCreatedAt for WorkspaceOperation {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
+
+impl HasUpdatedAt for WorkspaceOperation {
+    fn updated_at(&self) -> jiff::Timestamp {
+        self.updated_at.into()
+    }
+}
@@ -5,7 +5,7 @@ use jiff_diesel::Timestamp;
 use uuid::Uuid;
 
 use crate::schema::workspace_pipelines;
-use crate::types::{HasCreatedAt, HasDeletedAt, HasUpdatedAt, PipelineStatus, Slug};
+use crate::types::{ArtifactType, HasCreatedAt, HasDeletedAt, HasUpdatedAt, PipelineStatus, Slug};
 
 /// Workspace pipeline model representing a workflow definition in the system.
 #[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
@@ -42,6 +42,12 @@ pub struct WorkspacePipeline {
     pub updated_at: Timestamp,
     /// Timestamp when the pipeline was soft-deleted.
     pub deleted_at: Option<Timestamp>,
+    /// Days input artifacts of finished runs are kept.
+    pub input_retention_days: Option<i32>,
+    /// Days intermediate artifacts of finished runs are kept.
+    pub intermediate_retention_days: Option<i32>,
+    /// Days output artifacts of finished runs are kept.
+    pub output_retention_days: Option<i32>,
 }
 
 /// Data for creating a new workspace pipeline.
@@ -71,6 +77,12 @@ pub struct NewWorkspacePipeline {
     pub schedule_tz: Option<String>,
     /// Next scheduled run time.
     pub next_run_at: Option<Timestamp>,
+    /// Days input artifacts are kept.
+    pub input_retention_days: Option<i32>,
+    /// Days intermediate artifacts are kept.
+    pub intermediate_retention_days: Option<i32>,
+    /// Days output artifacts are kept.
+    pub output_retention_days: Option<i32>,
 }
 
 /// Data for updating a workspace pipeline.
@@ -96,6 +108,12 @@ pub struct UpdateWorkspacePipeline {
     pub next_run_at: Option<Option<Timestamp>>,
     /// Soft delete timestamp.
     pub deleted_at: Option<Option<Timestamp>>,
+    /// Days input artifacts are kept.
+    pub input_retention_days: Option<Option<i32>>,
+    /// Days intermediate artifacts are kept.
+    pub intermediate_retention_days: Option<Option<i32>>,
+    /// Days output artifacts are kept.
+    pub output_retention_days: Option<Option<i32>>,
 }
 
 impl WorkspacePipeline {
@@ -128,6 +146,15 @@ impl WorkspacePipeline {
     pub fn is_scheduled(&self) -> bool {
         self.schedule_cron.is_some()
     }
+
+    /// Returns the days artifacts of the given type are kept, if limited.
+    pub fn artifact_retention_days(&self, artifact_type: ArtifactType) -> Option<i32> {
+        match artifact_type {
+            ArtifactType::Input => self.input_retention_days,
+            ArtifactType::Intermediate => self.intermediate_retention_days,
+            ArtifactType::Output => self.output_retention_days,
+        }
+    }
 }
 
 impl HasCreatedAt for WorkspacePipeline {
@@ -39,6 +39,9 @@ pub struct WorkspacePipelineRun {
     pub started_at: Timestamp,
     /// When the run completed.
     pub completed_at: Option<Timestamp>,
+    /// SHA-256 of the inputs the analysis depends on. Set with the analysis
+    /// and never changed; runs with equal digests reproduce the same output.
+    pub input_digest: Option<Vec<u8>>,
 }
 
 /// Data for creating a new workspace pipeline run.
@@ -77,6 +80,8 @@ pub struct UpdateWorkspacePipelineRun {
     pub metadata: Option<serde_json::Value>,
     /// When the run completed.
     pub completed_at: Option<Option<Timestamp>>,
+    /// Digest of the analysis inputs (set once, with the analysis).
+    pub input_digest: Option<Option<Vec<u8>>>,
 }
 
 impl WorkspacePipelineRun {
@@ -137,4 +142,10 @@ impl WorkspacePipelineRun {
     pub fn is_retriable(&self) -> bool {
         self.status.is_retriable()
     }
+
+    /// Returns the input digest as a hex string, once the run is analyzed.
+    pub fn input_digest_hex(&self) -> Option<String> {
+        let digest = self.input_digest.as_ref()?;
+        Some(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
 }
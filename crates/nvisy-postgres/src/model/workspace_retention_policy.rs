@@ -0,0 +1,73 @@
+//! Workspace retention policy model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_retention_policies;
+use crate::types::{HasCreatedAt, HasUpdatedAt};
+
+/// How long a workspace keeps its data before the purge job removes it.
+///
+/// Each period is in days; `None` keeps the data indefinitely (audit records
+/// then fall back to the deployment-wide retention).
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_retention_policies)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceRetentionPolicy {
+    /// Workspace the policy applies to.
+    pub workspace_id: Uuid,
+    /// Account that last changed the policy.
+    pub account_id: Option<Uuid>,
+    /// Days files are kept after upload.
+    pub file_retention_days: Option<i32>,
+    /// Days finished pipeline runs and their analyses are kept.
+    pub run_retention_days: Option<i32>,
+    /// Days audit records are kept.
+    pub activity_retention_days: Option<i32>,
+    /// Timestamp when the policy was created.
+    pub created_at: Timestamp,
+    /// Timestamp when the policy was last updated.
+    pub updated_at: Timestamp,
+}
+
+/// Data for setting a workspace's retention policy.
+///
+/// Replaces the whole policy: a `None` period clears it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[diesel(table_name = workspace_retention_policies)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(treat_none_as_null = true)]
+pub struct NewWorkspaceRetentionPolicy {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Account setting the policy.
+    pub account_id: Option<Uuid>,
+    /// Days files are kept after upload.
+    pub file_retention_days: Option<i32>,
+    /// Days finished pipeline runs are kept.
+    pub run_retention_days: Option<i32>,
+    /// Days audit records are kept.
+    pub activity_retention_days: Option<i32>,
+}
+
+impl WorkspaceRetentionPolicy {
+    /// Returns whether any retention period is set.
+    pub fn is_active(&self) -> bool {
+        self.file_retention_days.is_some()
+            || self.run_retention_days.is_some()
+            || self.activity_retention_days.is_some()
+    }
+}
+
+impl HasCreatedAt for WorkspaceRetentionPolicy {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
+
+impl HasUpdatedAt for WorkspaceRetentionPolicy {
+    fn updated_at(&self) -> jiff::Timestamp {
+        self.updated_at.into()
+    }
+}
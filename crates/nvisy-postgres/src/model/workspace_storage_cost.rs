@@ -0,0 +1,126 @@
+//! Workspace storage cost models for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff::civil;
+use jiff_diesel::{Date, Timestamp};
+use uuid::Uuid;
+
+use crate::model::StoragePrice;
+use crate::schema::workspace_storage_costs;
+use crate::types::{DataRegion, StorageClass, StorageStage};
+
+/// A workspace's storage usage and cost for one month, stage and class.
+///
+/// Usage is accrued from daily samples: each sampled day adds the bytes
+/// stored that day, and the cost charges each of them a day's share of the
+/// monthly price.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_storage_costs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceStorageCost {
+    /// First day of the reported month.
+    pub period_start: Date,
+    /// Workspace the storage is attributed to.
+    pub workspace_id: Uuid,
+    /// Region whose backends store the objects.
+    pub data_region: DataRegion,
+    /// Pipeline stage the objects are stored for.
+    pub stage: StorageStage,
+    /// Storage class the objects are priced at.
+    pub storage_class: StorageClass,
+    /// Objects stored at the latest sample.
+    pub object_count: i64,
+    /// Bytes stored at the latest sample.
+    pub byte_count: i64,
+    /// Sum of the bytes stored at each daily sample.
+    pub byte_days: i64,
+    /// Days of the month sampled.
+    pub sampled_days: i32,
+    /// Days in the month.
+    pub period_days: i32,
+    /// Day of the latest sample.
+    pub last_sampled_on: Date,
+    /// Currency of the price applied, if any price was in effect.
+    pub currency: Option<String>,
+    /// Price applied at the latest sample, in millionths of the currency per
+    /// GiB-month.
+    pub price_micros_per_gib_month: Option<i64>,
+    /// Cost accrued over the sampled days, in millionths of the currency.
+    pub cost_micros: Option<i64>,
+    /// Timestamp when the latest sample was recorded.
+    pub updated_at: Timestamp,
+}
+
+/// One day's storage usage, accrued into the month's report.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_storage_costs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceStorageSample {
+    /// First day of the month the sample falls in.
+    pub period_start: Date,
+    /// Workspace the storage is attributed to.
+    pub workspace_id: Uuid,
+    /// Region whose backends store the objects.
+    pub data_region: DataRegion,
+    /// Pipeline stage the objects are stored for.
+    pub stage: StorageStage,
+    /// Storage class the objects are priced at.
+    pub storage_class: StorageClass,
+    /// Objects stored.
+    pub object_count: i64,
+    /// Bytes stored.
+    pub byte_count: i64,
+    /// Bytes accrued by this sample: the bytes stored, for one day.
+    pub byte_days: i64,
+    /// Days accrued by this sample.
+    pub sampled_days: i32,
+    /// Days in the month.
+    pub period_days: i32,
+    /// Day of the sample.
+    pub last_sampled_on: Date,
+    /// Currency of the price in effect.
+    pub currency: Option<String>,
+    /// Price in effect, in millionths of the currency per GiB-month.
+    pub price_micros_per_gib_month: Option<i64>,
+}
+
+impl NewWorkspaceStorageSample {
+    /// Creates an empty, unpriced sample taken on `day`.
+    pub fn new(
+        day: civil::Date,
+        workspace_id: Uuid,
+        data_region: DataRegion,
+        stage: StorageStage,
+        storage_class: StorageClass,
+    ) -> Self {
+        Self {
+            period_start: day.first_of_month().into(),
+            workspace_id,
+            data_region,
+            stage,
+            storage_class,
+            object_count: 0,
+            byte_count: 0,
+            byte_days: 0,
+            sampled_days: 1,
+            period_days: i32::from(day.days_in_month()),
+            last_sampled_on: day.into(),
+            currency: None,
+            price_micros_per_gib_month: None,
+        }
+    }
+
+    /// Counts one stored object of `size` bytes.
+    pub fn add_object(&mut self, size: i64) {
+        self.object_count += 1;
+        self.byte_count += size;
+        self.byte_days += size;
+    }
+
+    /// Prices the sample at `price`.
+    pub fn with_price(mut self, price: Option<&StoragePrice>) -> Self {
+        self.currency = price.map(|price| price.currency.clone());
+        self.price_micros_per_gib_month = price.map(|price| price.price_micros_per_gib_month);
+        self
+    }
+}
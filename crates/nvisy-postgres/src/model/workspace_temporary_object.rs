@@ -0,0 +1,63 @@
+//! Workspace temporary object model for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff_diesel::Timestamp;
+use uuid::Uuid;
+
+use crate::schema::workspace_temporary_objects;
+use crate::types::HasCreatedAt;
+
+/// A stored object that no row refers to yet.
+///
+/// The object is registered before it is written and released once a row
+/// refers to it. A registration still present after it expires marks an
+/// abandoned object, which garbage collection removes.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_temporary_objects)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceTemporaryObject {
+    /// Unique registration identifier.
+    pub id: Uuid,
+    /// Workspace the object belongs to.
+    pub workspace_id: Uuid,
+    /// Account that wrote the object.
+    pub account_id: Option<Uuid>,
+    /// Object storage bucket.
+    pub bucket: String,
+    /// Object key within the bucket.
+    pub object_key: String,
+    /// Timestamp when the object was registered.
+    pub created_at: Timestamp,
+    /// Timestamp after which an unreleased object is abandoned.
+    pub expires_at: Timestamp,
+}
+
+/// Data for registering a new temporary object.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_temporary_objects)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceTemporaryObject {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Account writing the object.
+    pub account_id: Option<Uuid>,
+    /// Object storage bucket.
+    pub bucket: String,
+    /// Object key within the bucket.
+    pub object_key: String,
+    /// When the object is abandoned unless released.
+    pub expires_at: Timestamp,
+}
+
+impl WorkspaceTemporaryObject {
+    /// Returns whether the object has outlived its registration.
+    pub fn is_expired(&self) -> bool {
+        jiff::Timestamp::from(self.expires_at) <= jiff::Timestamp::now()
+    }
+}
+
+impl HasCreatedAt for WorkspaceTemporaryObject {
+    fn created_at(&self) -> jiff::Timestamp {
+        self.created_at.into()
+    }
+}
@@ -51,6 +51,8 @@ pub struct WorkspaceWebhook {
     pub updated_at: Timestamp,
     /// Timestamp when this webhook was soft-deleted.
     pub deleted_at: Option<Timestamp>,
+    /// JSONPath queries that must all match a payload for it to be delivered.
+    pub payload_filters: Vec<Option<String>>,
 }
 
 /// Data structure for creating a new workspace webhook.
@@ -76,6 +78,8 @@ pub struct NewWorkspaceWebhook {
     pub status: Option<WebhookStatus>,
     /// Account creating this webhook.
     pub created_by: Uuid,
+    /// JSONPath queries that must all match a payload for it to be delivered.
+    pub payload_filters: Vec<Option<String>>,
 }
 
 /// Data structure for updating an existing workspace webhook.
@@ -93,6 +97,8 @@ pub struct UpdateWorkspaceWebhook {
     pub events: Option<Vec<Option<WebhookEvent>>>,
     /// Updated custom headers.
     pub headers: Option<serde_json::Value>,
+    /// Updated payload filters.
+    pub payload_filters: Option<Vec<Option<String>>>,
     /// Updated status.
     pub status: Option<WebhookStatus>,
     /// Updated last triggered timestamp.
@@ -127,6 +133,11 @@ impl WorkspaceWebhook {
         self.events.iter().filter_map(|e| *e).collect()
     }
 
+    /// Returns the payload filters.
+    pub fn payload_filters(&self) -> Vec<String> {
+        self.payload_filters.iter().flatten().cloned().collect()
+    }
+
     /// Returns the custom headers as a `HashMap<String, String>`.
     pub fn parsed_headers(&self) -> HashMap<String, String> {
         serde_json::from_value(self.headers.clone()).unwrap_or_default()
@@ -0,0 +1,98 @@
+//! Workspace webhook delivery statistics models for PostgreSQL database operations.
+
+use diesel::prelude::*;
+use jiff::tz::TimeZone;
+use jiff_diesel::Date;
+use uuid::Uuid;
+
+use crate::schema::workspace_webhook_delivery_stats;
+
+/// A webhook's delivery counters for one day.
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable)]
+#[diesel(table_name = workspace_webhook_delivery_stats)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkspaceWebhookDeliveryStats {
+    /// Workspace the webhook belongs to.
+    pub workspace_id: Uuid,
+    /// Webhook the requests were addressed to.
+    pub webhook_id: Uuid,
+    /// Day the deliveries happened on, in UTC.
+    pub delivery_date: Date,
+    /// Number of requests the endpoint accepted with a 2xx status.
+    pub success_count: i32,
+    /// Number of delivery attempts that failed or returned another status.
+    pub failure_count: i32,
+    /// Number of events not delivered because the payload filters did not match.
+    pub filtered_count: i32,
+    /// Total response time of the successful deliveries, in milliseconds.
+    pub response_time_ms: i64,
+}
+
+impl WorkspaceWebhookDeliveryStats {
+    /// Returns the average response time of the day's successful deliveries.
+    pub fn average_response_time_ms(&self) -> Option<i64> {
+        (self.success_count > 0).then(|| self.response_time_ms / i64::from(self.success_count))
+    }
+}
+
+/// One delivery outcome, added to the counters of the current day.
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = workspace_webhook_delivery_stats)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewWorkspaceWebhookDelivery {
+    /// Workspace ID (required).
+    pub workspace_id: Uuid,
+    /// Webhook ID (required).
+    pub webhook_id: Uuid,
+    /// Day the delivery happened on, in UTC.
+    pub delivery_date: Date,
+    /// Successful deliveries to add.
+    pub success_count: i32,
+    /// Failed deliveries to add.
+    pub failure_count: i32,
+    /// Filtered out events to add.
+    pub filtered_count: i32,
+    /// Response time to add, in milliseconds.
+    pub response_time_ms: i64,
+}
+
+impl NewWorkspaceWebhookDelivery {
+    /// Creates a delivery with no counters set, dated today.
+    fn today(workspace_id: Uuid, webhook_id: Uuid) -> Self {
+        let today = jiff::Timestamp::now().to_zoned(TimeZone::UTC).date();
+        Self {
+            workspace_id,
+            webhook_id,
+            delivery_date: today.into(),
+            success_count: 0,
+            failure_count: 0,
+            filtered_count: 0,
+            response_time_ms: 0,
+        }
+    }
+
+    /// Records a request the endpoint accepted after `response_time_ms`.
+    pub fn succeeded(workspace_id: Uuid, webhook_id: Uuid, response_time_ms: i64) -> Self {
+        Self {
+            success_count: 1,
+            response_time_ms,
+            ..Self::today(workspace_id, webhook_id)
+        }
+    }
+
+    /// Records a delivery attempt that failed or was rejected.
+    pub fn failed(workspace_id: Uuid, webhook_id: Uuid) -> Self {
+        Self {
+            failure_count: 1,
+            ..Self::today(workspace_id, webhook_id)
+        }
+    }
+
+    /// Records an event left undelivered by the webhook's payload filters.
+    pub fn filtered(workspace_id: Uuid, webhook_id: Uuid) -> Self {
+        Self {
+            filtered_count: 1,
+            ..Self::today(workspace_id, webhook_id)
+        }
+    }
+}
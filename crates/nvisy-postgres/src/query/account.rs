@@ -6,6 +6,7 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{Account, NewAccount, UpdateAccount};
 use crate::types::Username;
 use crate::{PgConnection, PgError, PgResult, schema};
@@ -141,6 +142,8 @@ impl AccountRepository for PgConnection {
     async fn create_account(&mut self, mut new_account: NewAccount) -> PgResult<Account> {
         use schema::accounts;
 
+        let _timer = QueryTimer::start("create_account");
+
         // Normalize fields: trim whitespace
         if let Some(ref mut name) = new_account.display_name {
             *name = name.trim().to_owned();
@@ -158,6 +161,8 @@ impl AccountRepository for PgConnection {
     async fn find_account_by_id(&mut self, account_id: Uuid) -> PgResult<Option<Account>> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("find_account_by_id");
+
         accounts::table
             .filter(dsl::id.eq(account_id))
             .filter(dsl::deleted_at.is_null())
@@ -171,6 +176,8 @@ impl AccountRepository for PgConnection {
     async fn find_account_by_email(&mut self, email: &str) -> PgResult<Option<Account>> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("find_account_by_email");
+
         accounts::table
             .filter(dsl::email_address.eq(email.trim().to_lowercase()))
             .filter(dsl::deleted_at.is_null())
@@ -184,6 +191,8 @@ impl AccountRepository for PgConnection {
     async fn find_account_by_username(&mut self, username: &Username) -> PgResult<Option<Account>> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("find_account_by_username");
+
         accounts::table
             .filter(dsl::username.eq(username.as_str()))
             .filter(dsl::deleted_at.is_null())
@@ -195,6 +204,8 @@ impl AccountRepository for PgConnection {
     }
 
     async fn find_account_by_identifier(&mut self, identifier: &str) -> PgResult<Option<Account>> {
+        let _timer = QueryTimer::start("find_account_by_identifier");
+
         if identifier.contains('@') {
             return self.find_account_by_email(identifier).await;
         }
@@ -216,6 +227,8 @@ impl AccountRepository for PgConnection {
     ) -> PgResult<Account> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("update_account");
+
         // Normalize fields: trim whitespace
         // Some(None) clears, Some(Some(value)) sets, None skips
         if let Some(Some(name)) = updates.display_name.as_mut() {
@@ -237,6 +250,8 @@ impl AccountRepository for PgConnection {
         use diesel::dsl::now;
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_account");
+
         diesel::update(accounts::table.filter(dsl::id.eq(account_id)))
             .set(dsl::deleted_at.eq(now))
             .returning(Account::as_returning())
@@ -247,6 +262,8 @@ impl AccountRepository for PgConnection {
     }
 
     async fn verify_account(&mut self, account_id: Uuid) -> PgResult<Account> {
+        let _timer = QueryTimer::start("verify_account");
+
         self.update_account(
             account_id,
             UpdateAccount {
@@ -258,6 +275,8 @@ impl AccountRepository for PgConnection {
     }
 
     async fn suspend_account(&mut self, account_id: Uuid) -> PgResult<Account> {
+        let _timer = QueryTimer::start("suspend_account");
+
         self.update_account(
             account_id,
             UpdateAccount {
@@ -269,6 +288,8 @@ impl AccountRepository for PgConnection {
     }
 
     async fn unsuspend_account(&mut self, account_id: Uuid) -> PgResult<Account> {
+        let _timer = QueryTimer::start("unsuspend_account");
+
         self.update_account(
             account_id,
             UpdateAccount {
@@ -282,6 +303,8 @@ impl AccountRepository for PgConnection {
     async fn email_exists(&mut self, email: &str) -> PgResult<bool> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("email_exists");
+
         let count: i64 = accounts::table
             .filter(dsl::email_address.eq(email.trim().to_lowercase()))
             .filter(dsl::deleted_at.is_null())
@@ -300,6 +323,8 @@ impl AccountRepository for PgConnection {
     ) -> PgResult<bool> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("email_exists_for_other");
+
         let count: i64 = accounts::table
             .filter(dsl::email_address.eq(email.trim().to_lowercase()))
             .filter(dsl::id.ne(exclude_account_id))
@@ -315,6 +340,8 @@ impl AccountRepository for PgConnection {
     async fn username_exists(&mut self, username: &Username) -> PgResult<bool> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("username_exists");
+
         let count: i64 = accounts::table
             .filter(dsl::username.eq(username.as_str()))
             .filter(dsl::deleted_at.is_null())
@@ -333,6 +360,8 @@ impl AccountRepository for PgConnection {
     ) -> PgResult<bool> {
         use schema::accounts::{self, dsl};
 
+        let _timer = QueryTimer::start("username_exists_for_other");
+
         let count: i64 = accounts::table
             .filter(dsl::username.eq(username.as_str()))
             .filter(dsl::id.ne(exclude_account_id))
@@ -0,0 +1,284 @@
+//! Account API key repository for managing scoped API keys.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use jiff::Timestamp;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{AccountApiKey, NewAccountApiKey, UpdateAccountApiKey};
+use crate::types::{CursorPage, CursorPagination};
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for account API key database operations.
+///
+/// Keys are looked up by the SHA-256 hash of their secret on the
+/// authentication path; the secret itself is never stored. Rotation is
+/// expressed as creating the replacement and then calling
+/// [`retire_account_api_key`], ideally within one transaction.
+///
+/// [`retire_account_api_key`]: AccountApiKeyRepository::retire_account_api_key
+pub trait AccountApiKeyRepository {
+    /// Creates a new account API key.
+    fn create_account_api_key(
+        &mut self,
+        new_key: NewAccountApiKey,
+    ) -> impl Future<Output = PgResult<AccountApiKey>> + Send;
+
+    /// Finds a non-revoked API key owned by the given account.
+    fn find_account_api_key(
+        &mut self,
+        account_id: Uuid,
+        key_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<AccountApiKey>>> + Send;
+
+    /// Finds the API key with the given secret hash, if it can still
+    /// authenticate (not revoked and not expired).
+    fn find_active_account_api_key_by_hash(
+        &mut self,
+        key_hash: &[u8],
+    ) -> impl Future<Output = PgResult<Option<AccountApiKey>>> + Send;
+
+    /// Lists an account's non-revoked API keys with cursor pagination.
+    ///
+    /// Keys retired by rotation stay listed until their grace window ends.
+    fn cursor_list_account_api_keys(
+        &mut self,
+        account_id: Uuid,
+        pagination: CursorPagination,
+    ) -> impl Future<Output = PgResult<CursorPage<AccountApiKey>>> + Send;
+
+    /// Updates an account API key.
+    fn update_account_api_key(
+        &mut self,
+        key_id: Uuid,
+        updates: UpdateAccountApiKey,
+    ) -> impl Future<Output = PgResult<AccountApiKey>> + Send;
+
+    /// Marks a key as replaced by `replaced_by`, keeping it usable until
+    /// `grace_until` (or its own expiry, whichever comes first).
+    fn retire_account_api_key(
+        &mut self,
+        key_id: Uuid,
+        replaced_by: Uuid,
+        grace_until: Timestamp,
+    ) -> impl Future<Output = PgResult<AccountApiKey>> + Send;
+
+    /// Updates the API key's last used timestamp.
+    fn touch_account_api_key(
+        &mut self,
+        key_id: Uuid,
+    ) -> impl Future<Output = PgResult<AccountApiKey>> + Send;
+
+    /// Revokes (soft deletes) an account API key, effective immediately.
+    fn revoke_account_api_key(
+        &mut self,
+        key_id: Uuid,
+    ) -> impl Future<Output = PgResult<bool>> + Send;
+}
+
+impl AccountApiKeyRepository for PgConnection {
+    async fn create_account_api_key(
+        &mut self,
+        new_key: NewAccountApiKey,
+    ) -> PgResult<AccountApiKey> {
+        use schema::account_api_keys;
+
+        let _timer = QueryTimer::start("create_account_api_key");
+
+        diesel::insert_into(account_api_keys::table)
+            .values(&new_key)
+            .returning(AccountApiKey::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)
+    }
+
+    async fn find_account_api_key(
+        &mut self,
+        account_id: Uuid,
+        key_id: Uuid,
+    ) -> PgResult<Option<AccountApiKey>> {
+        use schema::account_api_keys::{self, dsl};
+
+        let _timer = QueryTimer::start("find_account_api_key");
+
+        account_api_keys::table
+            .filter(dsl::id.eq(key_id))
+            .filter(dsl::account_id.eq(account_id))
+            .filter(dsl::deleted_at.is_null())
+            .select(AccountApiKey::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)
+    }
+
+    async fn find_active_account_api_key_by_hash(
+        &mut self,
+        key_hash: &[u8],
+    ) -> PgResult<Option<AccountApiKey>> {
+        use diesel::dsl::now;
+        use schema::account_api_keys::{self, dsl};
+
+        let _timer = QueryTimer::start("find_active_account_api_key_by_hash");
+
+        account_api_keys::table
+            .filter(dsl::key_hash.eq(key_hash))
+            .filter(dsl::deleted_at.is_null())
+            .filter(dsl::expired_at.is_null().or(dsl::expired_at.gt(now)))
+            .select(AccountApiKey::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)
+    }
+
+    async fn cursor_list_account_api_keys(
+        &mut self,
+        account_id: Uuid,
+        pagination: CursorPagination,
+    ) -> PgResult<CursorPage<AccountApiKey>> {
+        use diesel::dsl::{count_star, now};
+        use schema::account_api_keys::{self, dsl};
+
+        let _timer = QueryTimer::start("cursor_list_account_api_keys");
+
+        let base_filter = dsl::account_id
+            .eq(account_id)
+            .and(dsl::deleted_at.is_null())
+            .and(dsl::expired_at.is_null().or(dsl::expired_at.gt(now)));
+
+        let total = if pagination.include_count {
+            Some(
+                account_api_keys::table
+                    .filter(base_filter)
+                    .select(count_star())
+                    .get_result(self)
+                    .await
+                    .map_err(PgError::from)?,
+            )
+        } else {
+            None
+        };
+
+        let items = if let Some(cursor) = &pagination.after {
+            let cursor_ts = jiff_diesel::Timestamp::from(cursor.timestamp);
+            account_api_keys::table
+                .filter(base_filter)
+                .filter(
+                    dsl::created_at
+                        .lt(cursor_ts)
+                        .or(dsl::created_at.eq(cursor_ts).and(dsl::id.lt(cursor.id))),
+                )
+                .order((dsl::created_at.desc(), dsl::id.desc()))
+                .limit(pagination.fetch_limit())
+                .select(AccountApiKey::as_select())
+                .load(self)
+                .await
+                .map_err(PgError::from)?
+        } else {
+            account_api_keys::table
+                .filter(base_filter)
+                .order((dsl::created_at.desc(), dsl::id.desc()))
+                .limit(pagination.fetch_limit())
+                .select(AccountApiKey::as_select())
+                .load(self)
+                .await
+                .map_err(PgError::from)?
+        };
+
+        Ok(CursorPage::new(items, total, pagination.limit, |k| {
+            (k.created_at.into(), k.id)
+        }))
+    }
+
+    async fn update_account_api_key(
+        &mut self,
+        key_id: Uuid,
+        updates: UpdateAccountApiKey,
+    ) -> PgResult<AccountApiKey> {
+        use schema::account_api_keys::{self, dsl};
+
+        let _timer = QueryTimer::start("update_account_api_key");
+
+        diesel::update(
+            account_api_keys::table
+                .filter(dsl::id.eq(key_id))
+                .filter(dsl::deleted_at.is_null()),
+        )
+        .set(&updates)
+        .returning(AccountApiKey::as_returning())
+        .get_result(self)
+        .await
+        .map_err(PgError::from)
+    }
+
+    async fn retire_account_api_key(
+        &mut self,
+        key_id: Uuid,
+        replaced_by: Uuid,
+        grace_until: Timestamp,
+    ) -> PgResult<AccountApiKey> {
+        use diesel::dsl::sql;
+        use diesel::sql_types::{Nullable, Timestamptz};
+        use schema::account_api_keys::{self, dsl};
+
+        let _timer = QueryTimer::start("retire_account_api_key");
+
+        // Never extend a key's life: keep the earlier of its own expiry and
+        // the end of the grace window.
+        let grace_until = jiff_diesel::Timestamp::from(grace_until);
+        let expired_at = sql::<Nullable<Timestamptz>>("LEAST(expired_at, ")
+            .bind::<Timestamptz, _>(grace_until)
+            .sql(")");
+
+        diesel::update(
+            account_api_keys::table
+                .filter(dsl::id.eq(key_id))
+                .filter(dsl::deleted_at.is_null()),
+        )
+        .set((
+            dsl::replaced_by.eq(Some(replaced_by)),
+            dsl::expired_at.eq(expired_at),
+        ))
+        .returning(AccountApiKey::as_returning())
+        .get_result(self)
+        .await
+        .map_err(PgError::from)
+    }
+
+    async fn touch_account_api_key(&mut self, key_id: Uuid) -> PgResult<AccountApiKey> {
+        let _timer = QueryTimer::start("touch_account_api_key");
+
+        self.update_account_api_key(
+            key_id,
+            UpdateAccountApiKey {
+                last_used_at: Some(Some(jiff_diesel::Timestamp::from(Timestamp::now()))),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    async fn revoke_account_api_key(&mut self, key_id: Uuid) -> PgResult<bool> {
+        use diesel::dsl::now;
+        use schema::account_api_keys::{self, dsl};
+
+        let _timer = QueryTimer::start("revoke_account_api_key");
+
+        let rows_affected = diesel::update(
+            account_api_keys::table
+                .filter(dsl::id.eq(key_id))
+                .filter(dsl::deleted_at.is_null()),
+        )
+        .set(dsl::deleted_at.eq(now))
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(rows_affected > 0)
+    }
+}
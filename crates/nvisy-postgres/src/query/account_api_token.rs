@@ -7,6 +7,7 @@ use diesel_async::RunQueryDsl;
 use jiff::Timestamp;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{AccountApiToken, NewAccountApiToken, UpdateAccountApiToken};
 use crate::types::{ApiTokenType, CursorPage, CursorPagination, OffsetPagination};
 use crate::{PgConnection, PgError, PgResult, schema};
@@ -103,6 +104,8 @@ impl AccountApiTokenRepository for PgConnection {
     ) -> PgResult<AccountApiToken> {
         use schema::account_api_tokens;
 
+        let _timer = QueryTimer::start("create_account_api_token");
+
         diesel::insert_into(account_api_tokens::table)
             .values(&new_token)
             .returning(AccountApiToken::as_returning())
@@ -117,6 +120,8 @@ impl AccountApiTokenRepository for PgConnection {
     ) -> PgResult<Option<AccountApiToken>> {
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("find_account_api_token_by_id");
+
         account_api_tokens::table
             .filter(dsl::id.eq(token_id))
             .filter(dsl::deleted_at.is_null())
@@ -135,6 +140,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::{exists, select};
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("account_api_token_is_active");
+
         select(exists(
             account_api_tokens::table
                 .filter(dsl::id.eq(token_id))
@@ -153,6 +160,8 @@ impl AccountApiTokenRepository for PgConnection {
     ) -> PgResult<AccountApiToken> {
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("update_account_api_token");
+
         diesel::update(
             account_api_tokens::table
                 .filter(dsl::id.eq(token_id))
@@ -166,6 +175,8 @@ impl AccountApiTokenRepository for PgConnection {
     }
 
     async fn touch_account_api_token(&mut self, token_id: Uuid) -> PgResult<AccountApiToken> {
+        let _timer = QueryTimer::start("touch_account_api_token");
+
         self.update_account_api_token(
             token_id,
             UpdateAccountApiToken {
@@ -180,6 +191,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_account_api_token");
+
         let rows_affected = diesel::update(account_api_tokens::table.filter(dsl::id.eq(token_id)))
             .set(dsl::deleted_at.eq(now))
             .execute(self)
@@ -193,6 +206,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_all_account_api_tokens");
+
         diesel::update(
             account_api_tokens::table
                 .filter(dsl::account_id.eq(account_id))
@@ -214,6 +229,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_account_api_tokens_by_type");
+
         let mut query = diesel::update(
             account_api_tokens::table
                 .filter(dsl::account_id.eq(account_id))
@@ -242,6 +259,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_account_api_tokens");
+
         account_api_tokens::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::deleted_at.is_null())
@@ -263,6 +282,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::{count_star, now};
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("cursor_list_account_api_tokens");
+
         let base_filter = dsl::account_id
             .eq(account_id)
             .and(dsl::deleted_at.is_null())
@@ -319,6 +340,8 @@ impl AccountApiTokenRepository for PgConnection {
     ) -> PgResult<Vec<AccountApiToken>> {
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_all_account_api_tokens");
+
         account_api_tokens::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::deleted_at.is_null())
@@ -335,6 +358,8 @@ impl AccountApiTokenRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_api_tokens::{self, dsl};
 
+        let _timer = QueryTimer::start("cleanup_expired_account_api_tokens");
+
         diesel::update(
             account_api_tokens::table
                 .filter(dsl::expired_at.is_not_null())
@@ -0,0 +1,165 @@
+//! Account identity repository for single sign-on and provisioning links.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{AccountIdentity, NewAccountIdentity};
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for account identity database operations.
+///
+/// An identity is the `(issuer, subject)` pair an OpenID Connect provider
+/// asserts in its ID tokens; each one resolves to exactly one account.
+pub trait AccountIdentityRepository {
+    /// Links a new identity to an account.
+    fn create_account_identity(
+        &mut self,
+        new_identity: NewAccountIdentity,
+    ) -> impl Future<Output = PgResult<AccountIdentity>> + Send;
+
+    /// Finds the identity a provider asserted, if it is linked.
+    fn find_account_identity(
+        &mut self,
+        issuer: &str,
+        subject: &str,
+    ) -> impl Future<Output = PgResult<Option<AccountIdentity>>> + Send;
+
+    /// Lists the identities linked to an account.
+    fn list_account_identities(
+        &mut self,
+        account_id: Uuid,
+    ) -> impl Future<Output = PgResult<Vec<AccountIdentity>>> + Send;
+
+    /// Lists the identities asserted by an issuer, oldest first.
+    fn list_account_identities_by_issuer(
+        &mut self,
+        issuer: &str,
+    ) -> impl Future<Output = PgResult<Vec<AccountIdentity>>> + Send;
+
+    /// Records a login through the identity, refreshing its email address.
+    fn touch_account_identity(
+        &mut self,
+        identity_id: Uuid,
+        email_address: Option<String>,
+    ) -> impl Future<Output = PgResult<AccountIdentity>> + Send;
+
+    /// Unlinks an identity, returning whether it existed.
+    fn delete_account_identity(
+        &mut self,
+        issuer: &str,
+        subject: &str,
+    ) -> impl Future<Output = PgResult<bool>> + Send;
+}
+
+impl AccountIdentityRepository for PgConnection {
+    async fn create_account_identity(
+        &mut self,
+        new_identity: NewAccountIdentity,
+    ) -> PgResult<AccountIdentity> {
+        use schema::account_identities;
+
+        let _timer = QueryTimer::start("create_account_identity");
+
+        diesel::insert_into(account_identities::table)
+            .values(&new_identity)
+            .returning(AccountIdentity::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)
+    }
+
+    async fn find_account_identity(
+        &mut self,
+        issuer: &str,
+        subject: &str,
+    ) -> PgResult<Option<AccountIdentity>> {
+        use schema::account_identities::{self, dsl};
+
+        let _timer = QueryTimer::start("find_account_identity");
+
+        account_identities::table
+            .filter(dsl::issuer.eq(issuer))
+            .filter(dsl::subject.eq(subject))
+            .select(AccountIdentity::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)
+    }
+
+    async fn list_account_identities(
+        &mut self,
+        account_id: Uuid,
+    ) -> PgResult<Vec<AccountIdentity>> {
+        use schema::account_identities::{self, dsl};
+
+        let _timer = QueryTimer::start("list_account_identities");
+
+        account_identities::table
+            .filter(dsl::account_id.eq(account_id))
+            .order(dsl::created_at.asc())
+            .select(AccountIdentity::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)
+    }
+
+    async fn list_account_identities_by_issuer(
+        &mut self,
+        issuer: &str,
+    ) -> PgResult<Vec<AccountIdentity>> {
+        use schema::account_identities::{self, dsl};
+
+        let _timer = QueryTimer::start("list_account_identities_by_issuer");
+
+        account_identities::table
+            .filter(dsl::issuer.eq(issuer))
+            .order(dsl::created_at.asc())
+            .select(AccountIdentity::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)
+    }
+
+    async fn touch_account_identity(
+        &mut self,
+        identity_id: Uuid,
+        email_address: Option<String>,
+    ) -> PgResult<AccountIdentity> {
+        use diesel::dsl::now;
+        use schema::account_identities::{self, dsl};
+
+        let _timer = QueryTimer::start("touch_account_identity");
+
+        diesel::update(account_identities::table.filter(dsl::id.eq(identity_id)))
+            .set((
+                dsl::last_login_at.eq(now),
+                dsl::email_address.eq(email_address),
+            ))
+            .returning(AccountIdentity::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)
+    }
+
+    async fn delete_account_identity(&mut self, issuer: &str, subject: &str) -> PgResult<bool> {
+        use schema::account_identities::{self, dsl};
+
+        let _timer = QueryTimer::start("delete_account_identity");
+
+        let deleted = diesel::delete(
+            account_identities::table
+                .filter(dsl::issuer.eq(issuer))
+                .filter(dsl::subject.eq(subject)),
+        )
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(deleted > 0)
+    }
+}
@@ -7,6 +7,7 @@ use diesel_async::RunQueryDsl;
 use jiff::Timestamp;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{AccountNotification, NewAccountNotification, UpdateAccountNotification};
 use crate::types::{CursorPage, CursorPagination, OffsetPagination};
 use crate::{PgConnection, PgError, PgResult, schema};
@@ -75,6 +76,8 @@ impl AccountNotificationRepository for PgConnection {
     ) -> PgResult<AccountNotification> {
         use schema::account_notifications;
 
+        let _timer = QueryTimer::start("create_account_notification");
+
         diesel::insert_into(account_notifications::table)
             .values(&new_notification)
             .returning(AccountNotification::as_returning())
@@ -89,6 +92,8 @@ impl AccountNotificationRepository for PgConnection {
     ) -> PgResult<Option<AccountNotification>> {
         use schema::account_notifications::{self, dsl};
 
+        let _timer = QueryTimer::start("find_account_notification_by_id");
+
         account_notifications::table
             .filter(dsl::id.eq(notification_id))
             .select(AccountNotification::as_select())
@@ -106,6 +111,8 @@ impl AccountNotificationRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_notifications::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_account_notifications");
+
         account_notifications::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::expires_at.is_null().or(dsl::expires_at.gt(now)))
@@ -126,6 +133,8 @@ impl AccountNotificationRepository for PgConnection {
         use diesel::dsl::{count_star, now};
         use schema::account_notifications::{self, dsl};
 
+        let _timer = QueryTimer::start("cursor_list_account_notifications");
+
         let base_filter = dsl::account_id
             .eq(acct_id)
             .and(dsl::expires_at.is_null().or(dsl::expires_at.gt(now)));
@@ -180,6 +189,8 @@ impl AccountNotificationRepository for PgConnection {
     ) -> PgResult<usize> {
         use schema::account_notifications::{self, dsl};
 
+        let _timer = QueryTimer::start("mark_all_account_notifications_as_read");
+
         let update_data = UpdateAccountNotification {
             is_read: Some(true),
             read_at: Some(Some(jiff_diesel::Timestamp::from(Timestamp::now()))),
@@ -200,6 +211,8 @@ impl AccountNotificationRepository for PgConnection {
         use diesel::dsl::now;
         use schema::account_notifications::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_expired_account_notifications");
+
         diesel::delete(
             account_notifications::table
                 .filter(dsl::expires_at.is_not_null())
@@ -214,6 +227,8 @@ impl AccountNotificationRepository for PgConnection {
         use diesel::dsl::{count_star, now};
         use schema::account_notifications::{self, dsl};
 
+        let _timer = QueryTimer::start("count_unread_account_notifications");
+
         account_notifications::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::is_read.eq(false))
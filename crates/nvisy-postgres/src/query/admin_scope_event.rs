@@ -0,0 +1,88 @@
+//! Admin scope event repository for the audit trail of cross-tenant queries.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use jiff::Timestamp;
+
+use crate::client::QueryTimer;
+use crate::model::AdminScopeEvent;
+use crate::query::AdminScope;
+use crate::types::OffsetPagination;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for the events recorded by [`AdminScope::open`].
+///
+/// Events span every workspace, so all operations take an [`AdminScope`].
+pub trait AdminScopeEventRepository {
+    /// Lists recorded events, newest first.
+    fn list_admin_scope_events(
+        &mut self,
+        admin: &AdminScope,
+        pagination: OffsetPagination,
+    ) -> impl Future<Output = PgResult<Vec<AdminScopeEvent>>> + Send;
+
+    /// Deletes up to `limit` events recorded before `cutoff`.
+    fn cleanup_old_admin_scope_events(
+        &mut self,
+        admin: &AdminScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+}
+
+impl AdminScopeEventRepository for PgConnection {
+    async fn list_admin_scope_events(
+        &mut self,
+        _admin: &AdminScope,
+        pagination: OffsetPagination,
+    ) -> PgResult<Vec<AdminScopeEvent>> {
+        use schema::admin_scope_events::{self, dsl};
+
+        let _timer = QueryTimer::start("list_admin_scope_events");
+
+        let events = admin_scope_events::table
+            .select(AdminScopeEvent::as_select())
+            .order(dsl::created_at.desc())
+            .limit(pagination.limit)
+            .offset(pagination.offset)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(events)
+    }
+
+    async fn cleanup_old_admin_scope_events(
+        &mut self,
+        _admin: &AdminScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> PgResult<usize> {
+        use schema::admin_scope_events::{self, dsl};
+
+        let _timer = QueryTimer::start("cleanup_old_admin_scope_events");
+
+        // The batch is selected through an alias: a subquery cannot name the
+        // table the delete targets.
+        let expired = diesel::alias!(admin_scope_events as expired);
+        let batch = expired
+            .filter(
+                expired
+                    .field(dsl::created_at)
+                    .lt(jiff_diesel::Timestamp::from(cutoff)),
+            )
+            .order(expired.field(dsl::created_at).asc())
+            .limit(limit)
+            .select(expired.field(dsl::id));
+
+        let deleted = diesel::delete(admin_scope_events::table)
+            .filter(dsl::id.eq_any(batch))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(deleted)
+    }
+}
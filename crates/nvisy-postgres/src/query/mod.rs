@@ -10,41 +10,77 @@
 //! - [`CursorPagination`]: Preferred for API endpoints, infinite scroll, and large datasets
 //! - [`OffsetPagination`]: For admin dashboards or when random page access is needed
 //!
+//! # Tenant scoping
+//!
+//! Queries over workspace-owned rows take a [`TenantScope`]; the few that
+//! intentionally span workspaces take an [`AdminScope`], whose opening is
+//! recorded in the `admin_scope_events` table.
+//!
 //! [`CursorPagination`]: crate::types::CursorPagination
 //! [`OffsetPagination`]: crate::types::OffsetPagination
 
 mod account;
+mod account_api_key;
 mod account_api_token;
+mod account_identity;
 mod account_notification;
+mod admin_scope_event;
 mod pipeline_reference;
+mod scope;
+mod storage_cost;
 mod workspace;
 mod workspace_activity;
+mod workspace_change_event;
 mod workspace_connection;
 mod workspace_connection_run;
 mod workspace_context;
+mod workspace_custom_role;
+mod workspace_detection_review;
 mod workspace_file;
+mod workspace_file_access;
+mod workspace_file_share;
+mod workspace_inbound_webhook;
 mod workspace_invite;
 mod workspace_member;
+mod workspace_operation;
 mod workspace_pipeline;
 mod workspace_pipeline_artifact;
 mod workspace_pipeline_run;
 mod workspace_policy;
+mod workspace_retention;
+mod workspace_temporary_object;
 mod workspace_webhook;
+mod workspace_webhook_delivery;
 
 pub use account::AccountRepository;
+pub use account_api_key::AccountApiKeyRepository;
 pub use account_api_token::AccountApiTokenRepository;
+pub use account_identity::AccountIdentityRepository;
 pub use account_notification::AccountNotificationRepository;
+pub use admin_scope_event::AdminScopeEventRepository;
 pub use pipeline_reference::PipelineReferenceRepository;
+pub use scope::{AdminScope, TenantColumn, TenantScope};
+pub use storage_cost::StorageCostRepository;
 pub use workspace::WorkspaceRepository;
 pub use workspace_activity::WorkspaceActivityRepository;
+pub use workspace_change_event::WorkspaceChangeEventRepository;
 pub use workspace_connection::WorkspaceConnectionRepository;
 pub use workspace_connection_run::WorkspaceConnectionRunRepository;
 pub use workspace_context::WorkspaceContextRepository;
+pub use workspace_custom_role::WorkspaceCustomRoleRepository;
+pub use workspace_detection_review::WorkspaceDetectionReviewRepository;
 pub use workspace_file::WorkspaceFileRepository;
+pub use workspace_file_access::WorkspaceFileAccessRepository;
+pub use workspace_file_share::WorkspaceFileShareRepository;
+pub use workspace_inbound_webhook::WorkspaceInboundWebhookRepository;
 pub use workspace_invite::WorkspaceInviteRepository;
 pub use workspace_member::WorkspaceMemberRepository;
+pub use workspace_operation::WorkspaceOperationRepository;
 pub use workspace_pipeline::WorkspacePipelineRepository;
 pub use workspace_pipeline_artifact::WorkspacePipelineArtifactRepository;
 pub use workspace_pipeline_run::WorkspacePipelineRunRepository;
 pub use workspace_policy::WorkspacePolicyRepository;
+pub use workspace_retention::WorkspaceRetentionRepository;
+pub use workspace_temporary_object::WorkspaceTemporaryObjectRepository;
 pub use workspace_webhook::WorkspaceWebhookRepository;
+pub use workspace_webhook_delivery::WorkspaceWebhookDeliveryRepository;
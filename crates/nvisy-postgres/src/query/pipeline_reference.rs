@@ -12,7 +12,9 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{PipelineContext, PipelinePolicy};
+use crate::query::TenantScope;
 use crate::types::Slug;
 use crate::{PgConnection, PgError, PgResult, schema};
 
@@ -24,7 +26,7 @@ pub trait PipelineReferenceRepository {
     /// transaction with the pipeline write so the two stay consistent.
     fn replace_workspace_pipeline_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pipeline_id: Uuid,
         policy_ids: &[Uuid],
     ) -> impl Future<Output = PgResult<()>> + Send;
@@ -32,7 +34,7 @@ pub trait PipelineReferenceRepository {
     /// Replaces a pipeline's context references with the given set.
     fn replace_workspace_pipeline_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pipeline_id: Uuid,
         context_ids: &[Uuid],
     ) -> impl Future<Output = PgResult<()>> + Send;
@@ -71,14 +73,14 @@ pub trait PipelineReferenceRepository {
     /// unknown reference.
     fn resolve_policy_slugs(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slugs: &[Slug],
     ) -> impl Future<Output = PgResult<Option<Vec<Uuid>>>> + Send;
 
     /// Resolves context slugs to their ids within a workspace, preserving order.
     fn resolve_context_slugs(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slugs: &[Slug],
     ) -> impl Future<Output = PgResult<Option<Vec<Uuid>>>> + Send;
 }
@@ -86,13 +88,17 @@ pub trait PipelineReferenceRepository {
 impl PipelineReferenceRepository for PgConnection {
     async fn replace_workspace_pipeline_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pipeline_id: Uuid,
         policy_ids: &[Uuid],
     ) -> PgResult<()> {
         use schema::workspace_pipeline_policies::{self, dsl};
 
-        diesel::delete(workspace_pipeline_policies::table.filter(dsl::pipeline_id.eq(pipeline_id)))
+        let _timer = QueryTimer::start("replace_workspace_pipeline_policies");
+
+        diesel::delete(workspace_pipeline_policies::table)
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::pipeline_id.eq(pipeline_id))
             .execute(self)
             .await
             .map_err(PgError::from)?;
@@ -101,7 +107,7 @@ impl PipelineReferenceRepository for PgConnection {
             let rows: Vec<PipelinePolicy> = dedup(policy_ids)
                 .into_iter()
                 .map(|policy_id| PipelinePolicy {
-                    workspace_id,
+                    workspace_id: scope.workspace_id(),
                     pipeline_id,
                     policy_id,
                 })
@@ -119,13 +125,17 @@ impl PipelineReferenceRepository for PgConnection {
 
     async fn replace_workspace_pipeline_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pipeline_id: Uuid,
         context_ids: &[Uuid],
     ) -> PgResult<()> {
         use schema::workspace_pipeline_contexts::{self, dsl};
 
-        diesel::delete(workspace_pipeline_contexts::table.filter(dsl::pipeline_id.eq(pipeline_id)))
+        let _timer = QueryTimer::start("replace_workspace_pipeline_contexts");
+
+        diesel::delete(workspace_pipeline_contexts::table)
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::pipeline_id.eq(pipeline_id))
             .execute(self)
             .await
             .map_err(PgError::from)?;
@@ -134,7 +144,7 @@ impl PipelineReferenceRepository for PgConnection {
             let rows: Vec<PipelineContext> = dedup(context_ids)
                 .into_iter()
                 .map(|context_id| PipelineContext {
-                    workspace_id,
+                    workspace_id: scope.workspace_id(),
                     pipeline_id,
                     context_id,
                 })
@@ -153,6 +163,8 @@ impl PipelineReferenceRepository for PgConnection {
     async fn list_pipeline_policy_ids(&mut self, pipeline_id: Uuid) -> PgResult<Vec<Uuid>> {
         use schema::{workspace_pipeline_policies, workspace_policies};
 
+        let _timer = QueryTimer::start("list_pipeline_policy_ids");
+
         let ids = workspace_pipeline_policies::table
             .inner_join(
                 workspace_policies::table
@@ -171,6 +183,8 @@ impl PipelineReferenceRepository for PgConnection {
     async fn list_pipeline_context_ids(&mut self, pipeline_id: Uuid) -> PgResult<Vec<Uuid>> {
         use schema::{workspace_contexts, workspace_pipeline_contexts};
 
+        let _timer = QueryTimer::start("list_pipeline_context_ids");
+
         let ids = workspace_pipeline_contexts::table
             .inner_join(
                 workspace_contexts::table
@@ -189,6 +203,8 @@ impl PipelineReferenceRepository for PgConnection {
     async fn list_pipeline_policy_slugs(&mut self, pipeline_id: Uuid) -> PgResult<Vec<Slug>> {
         use schema::{workspace_pipeline_policies, workspace_policies};
 
+        let _timer = QueryTimer::start("list_pipeline_policy_slugs");
+
         // Join to the parent so soft-deleted policies (deleted_at set, join row
         // still present since CASCADE only fires on hard delete) are excluded.
         let slugs = workspace_pipeline_policies::table
@@ -209,6 +225,8 @@ impl PipelineReferenceRepository for PgConnection {
     async fn list_pipeline_context_slugs(&mut self, pipeline_id: Uuid) -> PgResult<Vec<Slug>> {
         use schema::{workspace_contexts, workspace_pipeline_contexts};
 
+        let _timer = QueryTimer::start("list_pipeline_context_slugs");
+
         let slugs = workspace_pipeline_contexts::table
             .inner_join(
                 workspace_contexts::table
@@ -226,18 +244,20 @@ impl PipelineReferenceRepository for PgConnection {
 
     async fn resolve_policy_slugs(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slugs: &[Slug],
     ) -> PgResult<Option<Vec<Uuid>>> {
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("resolve_policy_slugs");
+
         if slugs.is_empty() {
             return Ok(Some(Vec::new()));
         }
 
         let wanted: Vec<String> = slugs.iter().map(|slug| slug.as_str().to_owned()).collect();
         let found: Vec<(Slug, Uuid)> = workspace_policies::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .filter(dsl::slug.eq_any(&wanted))
             .select((dsl::slug, dsl::id))
@@ -250,18 +270,20 @@ impl PipelineReferenceRepository for PgConnection {
 
     async fn resolve_context_slugs(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slugs: &[Slug],
     ) -> PgResult<Option<Vec<Uuid>>> {
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("resolve_context_slugs");
+
         if slugs.is_empty() {
             return Ok(Some(Vec::new()));
         }
 
         let wanted: Vec<String> = slugs.iter().map(|slug| slug.as_str().to_owned()).collect();
         let found: Vec<(Slug, Uuid)> = workspace_contexts::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .filter(dsl::slug.eq_any(&wanted))
             .select((dsl::slug, dsl::id))
@@ -0,0 +1,214 @@
+//! Row-level tenant scoping for workspace-owned tables.
+//!
+//! Repository methods that read workspace-owned rows take a [`TenantScope`]
+//! instead of a bare workspace id, and build their `workspace_id` predicate
+//! through [`TenantScope::predicate`]. The predicate only accepts a column
+//! marked [`TenantColumn`], so a scoped query cannot silently filter on the
+//! wrong column, and a caller cannot reach these methods without first
+//! committing to the workspace it was authorized for.
+//!
+//! A scope is never built from a bare id. It is returned by the queries that
+//! establish access to a workspace: the membership lookup used for
+//! authorization ([`find_workspace_member_with_custom_role`]), and the invite
+//! and share link token lookups ([`find_workspace_invite_by_token`],
+//! [`find_workspace_file_share_by_hash`]), where holding the token is the
+//! credential. It can also be narrowed from an [`AdminScope`]
+//! ([`AdminScope::tenant`]).
+//!
+//! [`find_workspace_member_with_custom_role`]: crate::query::WorkspaceCustomRoleRepository::find_workspace_member_with_custom_role
+//! [`find_workspace_invite_by_token`]: crate::query::WorkspaceInviteRepository::find_workspace_invite_by_token
+//! [`find_workspace_file_share_by_hash`]: crate::query::WorkspaceFileShareRepository::find_workspace_file_share_by_hash
+//!
+//! Lookups that deliberately cross tenants (resolving a row to its workspace
+//! before authorization, maintenance jobs) take an [`AdminScope`] instead,
+//! which can only be opened by recording an event in the
+//! `admin_scope_events` table.
+
+use std::future::Future;
+use std::panic::Location;
+
+use diesel::dsl::Eq;
+use diesel::prelude::*;
+use diesel::sql_types;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::model::NewAdminScopeEvent;
+use crate::{PgConnection, PgError, PgResult, TRACING_TARGET_TENANCY, schema};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Marker for the `workspace_id` column of a workspace-owned table, and for
+/// the `id` column of `workspaces` itself.
+///
+/// Implemented only for tenant tables in this crate; it cannot be implemented
+/// downstream.
+pub trait TenantColumn: Expression<SqlType = sql_types::Uuid> + sealed::Sealed {}
+
+macro_rules! tenant_columns {
+    ($($table:ident),+ $(,)?) => {
+        $(
+            impl sealed::Sealed for schema::$table::workspace_id {}
+            impl TenantColumn for schema::$table::workspace_id {}
+        )+
+    };
+}
+
+tenant_columns!(
+    workspace_activities,
+    workspace_change_events,
+    workspace_connections,
+    workspace_contexts,
+    workspace_custom_roles,
+    workspace_file_access_stats,
+    workspace_file_shares,
+    workspace_files,
+    workspace_inbound_webhooks,
+    workspace_invites,
+    workspace_legal_holds,
+    workspace_members,
+    workspace_operations,
+    workspace_pipeline_contexts,
+    workspace_pipeline_policies,
+    workspace_pipelines,
+    workspace_policies,
+    workspace_retention_policies,
+    workspace_temporary_objects,
+    workspace_webhook_delivery_stats,
+    workspace_webhooks,
+);
+
+impl sealed::Sealed for schema::workspaces::id {}
+impl TenantColumn for schema::workspaces::id {}
+
+/// Restricts queries to the rows of a single workspace.
+///
+/// Obtained from the membership a request was authorized with, or from an
+/// [`AdminScope`], and passed to the workspace-scoped repository methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TenantScope {
+    workspace_id: Uuid,
+}
+
+impl TenantScope {
+    /// Creates a scope for the given workspace.
+    #[inline]
+    pub(crate) const fn new(workspace_id: Uuid) -> Self {
+        Self { workspace_id }
+    }
+
+    /// Returns the workspace this scope is restricted to.
+    #[inline]
+    pub const fn workspace_id(self) -> Uuid {
+        self.workspace_id
+    }
+
+    /// Returns the `column = workspace_id` predicate for a tenant table.
+    #[inline]
+    pub fn predicate<C>(self, column: C) -> Eq<C, Uuid>
+    where
+        C: TenantColumn + ExpressionMethods,
+    {
+        column.eq(self.workspace_id)
+    }
+}
+
+/// Permission to run a query across workspaces.
+///
+/// Opening one records an event naming the reason and the calling location
+/// in the `admin_scope_events` table, so unscoped access is auditable.
+#[derive(Debug, Clone, Copy)]
+pub struct AdminScope {
+    reason: &'static str,
+}
+
+impl AdminScope {
+    /// Opens a cross-tenant scope, recording `reason` and the calling
+    /// location before the scope is handed out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be recorded; no scope is opened
+    /// without its audit record.
+    #[track_caller]
+    pub fn open<'a>(
+        conn: &'a mut PgConnection,
+        reason: &'static str,
+    ) -> impl Future<Output = PgResult<Self>> + Send + 'a {
+        // Captured here: `#[track_caller]` does not reach into the future.
+        let caller = Location::caller();
+
+        async move {
+            use schema::admin_scope_events;
+
+            let event = NewAdminScopeEvent {
+                reason: reason.to_owned(),
+                caller: caller.to_string(),
+            };
+
+            diesel::insert_into(admin_scope_events::table)
+                .values(&event)
+                .execute(conn)
+                .await
+                .map_err(PgError::from)?;
+
+            tracing::info!(
+                target: TRACING_TARGET_TENANCY,
+                reason,
+                caller = %caller,
+                "Cross-tenant query scope opened"
+            );
+
+            Ok(Self { reason })
+        }
+    }
+
+    /// Returns the reason given when the scope was opened.
+    #[inline]
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    /// Narrows the scope to a single workspace.
+    ///
+    /// For maintenance jobs and global administrators acting on one
+    /// workspace; the opening of this scope already recorded the access.
+    #[inline]
+    pub fn tenant(&self, workspace_id: Uuid) -> TenantScope {
+        TenantScope::new(workspace_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::debug_query;
+    use diesel::pg::Pg;
+
+    use super::*;
+
+    #[test]
+    fn test_predicate_filters_on_workspace() {
+        use schema::workspace_webhooks::dsl;
+
+        let workspace_id = Uuid::new_v4();
+        let scope = TenantScope::new(workspace_id);
+        let query = dsl::workspace_webhooks
+            .filter(scope.predicate(dsl::workspace_id))
+            .select(dsl::id);
+
+        let sql = debug_query::<Pg, _>(&query).to_string();
+        assert!(sql.contains("\"workspace_webhooks\".\"workspace_id\" = $1"));
+        assert!(sql.contains(&workspace_id.to_string()));
+    }
+
+    #[test]
+    fn test_admin_scope_keeps_reason() {
+        let admin = AdminScope {
+            reason: "resolve file workspace",
+        };
+        assert_eq!(admin.reason(), "resolve file workspace");
+        assert_eq!(admin.tenant(Uuid::nil()), TenantScope::new(Uuid::nil()));
+    }
+}
@@ -0,0 +1,241 @@
+//! Storage cost repository for storage prices and the monthly per-workspace
+//! storage cost reports.
+
+use std::collections::HashSet;
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use jiff::civil::Date;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{
+    NewStoragePrice, NewWorkspaceStorageSample, StoragePrice, WorkspaceStorageCost,
+};
+use crate::query::AdminScope;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Maximum number of samples written per statement.
+///
+/// Keeps each insert well under PostgreSQL's limit of bind parameters.
+const SAMPLE_BATCH_SIZE: usize = 1000;
+
+/// Repository for storage prices and storage cost reports.
+///
+/// Reports span every workspace, so all operations take an [`AdminScope`].
+pub trait StorageCostRepository {
+    /// Accrues daily samples into their month's reports.
+    ///
+    /// A sample for a day already accrued is ignored, so several instances
+    /// sampling the same day count it only once. Samples of workspaces that
+    /// no longer exist are dropped. Returns the number of reports updated.
+    fn record_storage_samples(
+        &mut self,
+        admin: &AdminScope,
+        samples: Vec<NewWorkspaceStorageSample>,
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+
+    /// Lists the storage cost reports for the month starting on
+    /// `period_start`, optionally for a single workspace.
+    fn list_storage_costs(
+        &mut self,
+        admin: &AdminScope,
+        period_start: Date,
+        workspace_id: Option<Uuid>,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceStorageCost>>> + Send;
+
+    /// Records a storage price, replacing any price for the same region,
+    /// class and effective date.
+    fn create_storage_price(
+        &mut self,
+        admin: &AdminScope,
+        price: NewStoragePrice,
+    ) -> impl Future<Output = PgResult<StoragePrice>> + Send;
+
+    /// Lists every recorded storage price, latest first.
+    fn list_storage_prices(
+        &mut self,
+        admin: &AdminScope,
+    ) -> impl Future<Output = PgResult<Vec<StoragePrice>>> + Send;
+
+    /// Returns the price in effect on `day` for each region and class that
+    /// has one.
+    fn find_storage_prices_in_effect(
+        &mut self,
+        admin: &AdminScope,
+        day: Date,
+    ) -> impl Future<Output = PgResult<Vec<StoragePrice>>> + Send;
+}
+
+impl StorageCostRepository for PgConnection {
+    async fn record_storage_samples(
+        &mut self,
+        _admin: &AdminScope,
+        samples: Vec<NewWorkspaceStorageSample>,
+    ) -> PgResult<usize> {
+        use diesel::dsl::now;
+        use diesel::upsert::excluded;
+        use schema::workspace_storage_costs::{self, dsl};
+        use schema::workspaces;
+
+        let _timer = QueryTimer::start("record_storage_samples");
+
+        // Objects can outlive their workspace until garbage collection
+        // removes them; they are no one's to bill.
+        let mut workspace_ids: Vec<Uuid> = samples.iter().map(|s| s.workspace_id).collect();
+        workspace_ids.sort_unstable();
+        workspace_ids.dedup();
+        let existing: HashSet<Uuid> = workspaces::table
+            .filter(workspaces::id.eq_any(&workspace_ids))
+            .select(workspaces::id)
+            .load::<Uuid>(self)
+            .await
+            .map_err(PgError::from)?
+            .into_iter()
+            .collect();
+
+        let samples: Vec<_> = samples
+            .into_iter()
+            .filter(|sample| existing.contains(&sample.workspace_id))
+            .collect();
+
+        let mut updated = 0;
+        for batch in samples.chunks(SAMPLE_BATCH_SIZE) {
+            let upsert = diesel::insert_into(workspace_storage_costs::table)
+                .values(batch)
+                .on_conflict((
+                    dsl::period_start,
+                    dsl::workspace_id,
+                    dsl::data_region,
+                    dsl::stage,
+                    dsl::storage_class,
+                ))
+                .do_update()
+                .set((
+                    dsl::object_count.eq(excluded(dsl::object_count)),
+                    dsl::byte_count.eq(excluded(dsl::byte_count)),
+                    dsl::byte_days.eq(dsl::byte_days + excluded(dsl::byte_days)),
+                    dsl::sampled_days.eq(dsl::sampled_days + excluded(dsl::sampled_days)),
+                    dsl::last_sampled_on.eq(excluded(dsl::last_sampled_on)),
+                    dsl::currency.eq(excluded(dsl::currency)),
+                    dsl::price_micros_per_gib_month.eq(excluded(dsl::price_micros_per_gib_month)),
+                    dsl::updated_at.eq(now),
+                ));
+
+            // `ON CONFLICT ... WHERE` is only reachable through `FilterDsl`.
+            updated += diesel::query_dsl::methods::FilterDsl::filter(
+                upsert,
+                dsl::last_sampled_on.lt(excluded(dsl::last_sampled_on)),
+            )
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+        }
+
+        Ok(updated)
+    }
+
+    async fn list_storage_costs(
+        &mut self,
+        _admin: &AdminScope,
+        period_start: Date,
+        workspace_id: Option<Uuid>,
+    ) -> PgResult<Vec<WorkspaceStorageCost>> {
+        use schema::workspace_storage_costs::{self, dsl};
+
+        let _timer = QueryTimer::start("list_storage_costs");
+
+        let mut query = workspace_storage_costs::table
+            .filter(dsl::period_start.eq(jiff_diesel::Date::from(period_start)))
+            .into_boxed();
+
+        if let Some(workspace_id) = workspace_id {
+            query = query.filter(dsl::workspace_id.eq(workspace_id));
+        }
+
+        let costs = query
+            .select(WorkspaceStorageCost::as_select())
+            .order((
+                dsl::workspace_id.asc(),
+                dsl::data_region.asc(),
+                dsl::stage.asc(),
+                dsl::storage_class.asc(),
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(costs)
+    }
+
+    async fn create_storage_price(
+        &mut self,
+        _admin: &AdminScope,
+        price: NewStoragePrice,
+    ) -> PgResult<StoragePrice> {
+        use diesel::upsert::excluded;
+        use schema::storage_prices::{self, dsl};
+
+        let _timer = QueryTimer::start("create_storage_price");
+
+        let price = diesel::insert_into(storage_prices::table)
+            .values(&price)
+            .on_conflict((dsl::data_region, dsl::storage_class, dsl::effective_from))
+            .do_update()
+            .set((
+                dsl::currency.eq(excluded(dsl::currency)),
+                dsl::price_micros_per_gib_month.eq(excluded(dsl::price_micros_per_gib_month)),
+            ))
+            .returning(StoragePrice::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(price)
+    }
+
+    async fn list_storage_prices(&mut self, _admin: &AdminScope) -> PgResult<Vec<StoragePrice>> {
+        use schema::storage_prices::{self, dsl};
+
+        let _timer = QueryTimer::start("list_storage_prices");
+
+        let prices = storage_prices::table
+            .select(StoragePrice::as_select())
+            .order((
+                dsl::effective_from.desc(),
+                dsl::data_region.asc(),
+                dsl::storage_class.asc(),
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(prices)
+    }
+
+    async fn find_storage_prices_in_effect(
+        &mut self,
+        _admin: &AdminScope,
+        day: Date,
+    ) -> PgResult<Vec<StoragePrice>> {
+        use schema::storage_prices::{self, dsl};
+
+        let _timer = QueryTimer::start("find_storage_prices_in_effect");
+
+        let prices = storage_prices::table
+            .filter(dsl::effective_from.le(jiff_diesel::Date::from(day)))
+            .distinct_on((dsl::data_region, dsl::storage_class))
+            .select(StoragePrice::as_select())
+            .order((
+                dsl::data_region.asc(),
+                dsl::storage_class.asc(),
+                dsl::effective_from.desc(),
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(prices)
+    }
+}
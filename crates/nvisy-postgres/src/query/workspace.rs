@@ -6,10 +6,11 @@ use diesel::dsl::now;
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use pgtrgm::expression_methods::TrgmExpressionMethods;
-use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspace, UpdateWorkspace, Workspace};
-use crate::types::{OffsetPagination, Username};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::{DataRegion, OffsetPagination, Username};
 use crate::{PgConnection, PgError, PgResult, schema};
 
 /// Maximum number of slug candidates tried before giving up when generating a
@@ -19,7 +20,8 @@ const MAX_SLUG_ATTEMPTS: u32 = 100;
 /// Repository for workspace database operations.
 ///
 /// Handles workspace lifecycle management including creation, updates,
-/// and search functionality.
+/// and search functionality. Listings and searches span every workspace and
+/// take an [`AdminScope`].
 pub trait WorkspaceRepository {
     /// Creates a new workspace.
     ///
@@ -41,28 +43,31 @@ pub trait WorkspaceRepository {
         workspace: NewWorkspace,
     ) -> impl Future<Output = PgResult<Workspace>> + Send;
 
-    /// Finds a workspace by ID, excluding soft-deleted workspaces.
+    /// Finds the scoped workspace, excluding soft-deleted workspaces.
     fn find_workspace_by_id(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
     ) -> impl Future<Output = PgResult<Option<Workspace>>> + Send;
 
     /// Finds a workspace by slug, with the handle of the account that created
     /// it, excluding soft-deleted workspaces.
+    ///
+    /// Not scoped: this resolves the workspace a request addresses, before
+    /// the request is authorized for it.
     fn find_workspace_by_slug(
         &mut self,
         slug: &str,
     ) -> impl Future<Output = PgResult<Option<(Workspace, Username)>>> + Send;
 
-    /// Updates a workspace with partial changes.
+    /// Updates the scoped workspace with partial changes.
     fn update_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         changes: UpdateWorkspace,
     ) -> impl Future<Output = PgResult<Workspace>> + Send;
 
-    /// Soft deletes a workspace by setting the deletion timestamp.
-    fn delete_workspace(&mut self, workspace_id: Uuid)
+    /// Soft deletes the scoped workspace by setting the deletion timestamp.
+    fn delete_workspace(&mut self, scope: TenantScope)
     -> impl Future<Output = PgResult<()>> + Send;
 
     /// Lists workspaces.
@@ -70,6 +75,7 @@ pub trait WorkspaceRepository {
     /// Returns workspaces ordered by update time with most recent first.
     fn list_workspaces(
         &mut self,
+        admin: &AdminScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<Workspace>>> + Send;
 
@@ -78,6 +84,7 @@ pub trait WorkspaceRepository {
     /// Performs case-insensitive search across workspace names and descriptions.
     fn search_workspaces(
         &mut self,
+        admin: &AdminScope,
         search_query: &str,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<Workspace>>> + Send;
@@ -85,15 +92,24 @@ pub trait WorkspaceRepository {
     /// Finds workspaces with overlapping tags.
     fn find_workspaces_by_tags(
         &mut self,
+        admin: &AdminScope,
         search_tags: &[String],
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<Workspace>>> + Send;
+
+    /// Lists the distinct data regions that active workspaces are pinned to.
+    fn list_workspace_data_regions(
+        &mut self,
+        admin: &AdminScope,
+    ) -> impl Future<Output = PgResult<Vec<DataRegion>>> + Send;
 }
 
 impl WorkspaceRepository for PgConnection {
     async fn create_workspace(&mut self, workspace: NewWorkspace) -> PgResult<Workspace> {
         use schema::workspaces;
 
+        let _timer = QueryTimer::start("create_workspace");
+
         let workspace = diesel::insert_into(workspaces::table)
             .values(&workspace)
             .returning(Workspace::as_returning())
@@ -108,6 +124,8 @@ impl WorkspaceRepository for PgConnection {
         &mut self,
         workspace: NewWorkspace,
     ) -> PgResult<Workspace> {
+        let _timer = QueryTimer::start("create_workspace_with_unique_slug");
+
         let preferred = workspace.slug.clone();
 
         // Attempt the preferred slug first, then `-2`, `-3`, … on collision.
@@ -140,11 +158,13 @@ impl WorkspaceRepository for PgConnection {
         ))
     }
 
-    async fn find_workspace_by_id(&mut self, workspace_id: Uuid) -> PgResult<Option<Workspace>> {
+    async fn find_workspace_by_id(&mut self, scope: TenantScope) -> PgResult<Option<Workspace>> {
         use schema::workspaces::dsl::*;
 
+        let _timer = QueryTimer::start("find_workspace_by_id");
+
         let workspace = workspaces
-            .filter(id.eq(workspace_id))
+            .filter(scope.predicate(id))
             .filter(deleted_at.is_null())
             .select(Workspace::as_select())
             .first(self)
@@ -162,6 +182,8 @@ impl WorkspaceRepository for PgConnection {
         use schema::workspaces::dsl;
         use schema::{accounts, workspaces};
 
+        let _timer = QueryTimer::start("find_workspace_by_slug");
+
         let workspace = workspaces::table
             .inner_join(accounts::table)
             .filter(dsl::slug.eq(slug_value))
@@ -177,13 +199,15 @@ impl WorkspaceRepository for PgConnection {
 
     async fn update_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         changes: UpdateWorkspace,
     ) -> PgResult<Workspace> {
         use schema::workspaces::dsl::*;
 
+        let _timer = QueryTimer::start("update_workspace");
+
         let workspace = diesel::update(workspaces)
-            .filter(id.eq(workspace_id))
+            .filter(scope.predicate(id))
             .filter(deleted_at.is_null())
             .set(&changes)
             .returning(Workspace::as_returning())
@@ -194,11 +218,13 @@ impl WorkspaceRepository for PgConnection {
         Ok(workspace)
     }
 
-    async fn delete_workspace(&mut self, workspace_id: Uuid) -> PgResult<()> {
+    async fn delete_workspace(&mut self, scope: TenantScope) -> PgResult<()> {
         use schema::workspaces::dsl::*;
 
+        let _timer = QueryTimer::start("delete_workspace");
+
         diesel::update(workspaces)
-            .filter(id.eq(workspace_id))
+            .filter(scope.predicate(id))
             .filter(deleted_at.is_null())
             .set(deleted_at.eq(now))
             .execute(self)
@@ -208,9 +234,15 @@ impl WorkspaceRepository for PgConnection {
         Ok(())
     }
 
-    async fn list_workspaces(&mut self, pagination: OffsetPagination) -> PgResult<Vec<Workspace>> {
+    async fn list_workspaces(
+        &mut self,
+        _admin: &AdminScope,
+        pagination: OffsetPagination,
+    ) -> PgResult<Vec<Workspace>> {
         use schema::workspaces::dsl::*;
 
+        let _timer = QueryTimer::start("list_workspaces");
+
         let workspace_list = workspaces
             .filter(deleted_at.is_null())
             .select(Workspace::as_select())
@@ -226,11 +258,14 @@ impl WorkspaceRepository for PgConnection {
 
     async fn search_workspaces(
         &mut self,
+        _admin: &AdminScope,
         search_query: &str,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<Workspace>> {
         use schema::workspaces::dsl::*;
 
+        let _timer = QueryTimer::start("search_workspaces");
+
         let workspace_list = workspaces
             .filter(deleted_at.is_null())
             .filter(display_name.trgm_similar_to(search_query))
@@ -247,11 +282,14 @@ impl WorkspaceRepository for PgConnection {
 
     async fn find_workspaces_by_tags(
         &mut self,
+        _admin: &AdminScope,
         search_tags: &[String],
         pagination: OffsetPagination,
     ) -> PgResult<Vec<Workspace>> {
         use schema::workspaces::dsl::*;
 
+        let _timer = QueryTimer::start("find_workspaces_by_tags");
+
         let workspace_list = workspaces
             .filter(tags.overlaps_with(search_tags))
             .filter(deleted_at.is_null())
@@ -265,4 +303,23 @@ impl WorkspaceRepository for PgConnection {
 
         Ok(workspace_list)
     }
+
+    async fn list_workspace_data_regions(
+        &mut self,
+        _admin: &AdminScope,
+    ) -> PgResult<Vec<DataRegion>> {
+        use schema::workspaces::dsl::*;
+
+        let _timer = QueryTimer::start("list_workspace_data_regions");
+
+        let regions = workspaces
+            .filter(deleted_at.is_null())
+            .select(data_region)
+            .distinct()
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(regions)
+    }
 }
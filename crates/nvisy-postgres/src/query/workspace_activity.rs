@@ -3,13 +3,21 @@
 use std::future::Future;
 
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
+use diesel::sql_types;
+use diesel_async::{AsyncConnection, RunQueryDsl};
 use ipnet::IpNet;
 use jiff::{Span, Timestamp};
+use nvisy_core::clock::Clock;
+use nvisy_core::crypto::CryptoProvider;
+use nvisy_core::id::IdGenerator;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspaceActivity, WorkspaceActivity};
-use crate::types::{ActivityType, CursorPage, CursorPagination, OffsetPagination, Username};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::{
+    ActivityFilter, ActivityType, CursorPage, CursorPagination, OffsetPagination, Username,
+};
 use crate::{PgConnection, PgError, PgResult, schema};
 
 /// Parameters for logging entity-specific activities.
@@ -19,6 +27,8 @@ pub struct LogEntityActivityParams {
     pub account_id: Option<Uuid>,
     /// The type of activity being logged.
     pub activity_type: ActivityType,
+    /// The resource the activity acted on.
+    pub resource_id: Option<Uuid>,
     /// Human-readable description.
     pub description: String,
     /// Structured metadata with activity details.
@@ -31,18 +41,28 @@ pub struct LogEntityActivityParams {
 
 /// Repository for workspace activity log database operations.
 ///
-/// Handles activity logging, querying, and audit trail management.
+/// Handles activity logging, querying, and audit trail management. Activities
+/// are append-only: the database rejects updates, and deletes outside of
+/// [`cleanup_old_activities`](Self::cleanup_old_activities) and workspace
+/// removal.
 pub trait WorkspaceActivityRepository {
-    /// Logs a new activity in the workspace activity log.
+    /// Appends a new activity to its workspace's hash chain.
+    ///
+    /// Appends to the same workspace are serialized, so every record links
+    /// to the one written before it. The record's ID comes from `ids` and
+    /// its timestamp from `clock`.
     fn log_activity(
         &mut self,
         activity: NewWorkspaceActivity,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> impl Future<Output = PgResult<WorkspaceActivity>> + Send;
 
     /// Lists activities for a specific workspace with offset pagination.
     fn offset_list_workspace_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceActivity>>> + Send;
 
@@ -50,10 +70,20 @@ pub trait WorkspaceActivityRepository {
     /// paired with the handle of the account that performed it, if any.
     fn cursor_list_workspace_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
+        filter: ActivityFilter,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceActivity, Option<Username>)>>> + Send;
 
+    /// Lists a workspace's activities in chain order, starting after the
+    /// given sequence number.
+    fn list_workspace_activity_chain(
+        &mut self,
+        scope: TenantScope,
+        after_sequence_number: i64,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceActivity>>> + Send;
+
     /// Gets recent activities across all workspaces for a specific user.
     fn get_account_recent_activity(
         &mut self,
@@ -64,7 +94,7 @@ pub trait WorkspaceActivityRepository {
     /// Gets activities of a specific type within a workspace.
     fn get_activity_by_type(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         activity_type_filter: ActivityType,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceActivity>>> + Send;
@@ -79,28 +109,37 @@ pub trait WorkspaceActivityRepository {
     /// Logs integration-related activity using standardized parameters.
     fn log_integration_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         params: LogEntityActivityParams,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> impl Future<Output = PgResult<WorkspaceActivity>> + Send;
 
     /// Logs workspace member-related activity using standardized parameters.
     fn log_member_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         params: LogEntityActivityParams,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> impl Future<Output = PgResult<WorkspaceActivity>> + Send;
 
     /// Logs document-related activity using standardized parameters.
     fn log_document_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         params: LogEntityActivityParams,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> impl Future<Output = PgResult<WorkspaceActivity>> + Send;
 
     /// Gets the most active users in a workspace ranked by activity count.
     fn get_most_active_accounts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         hours: Option<i64>,
         limit: i64,
     ) -> impl Future<Output = PgResult<Vec<(Option<Uuid>, i64)>>> + Send;
@@ -108,43 +147,105 @@ pub trait WorkspaceActivityRepository {
     /// Gets a breakdown of activities by type for analytical reporting.
     fn get_activity_type_breakdown(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         hours: Option<i64>,
     ) -> impl Future<Output = PgResult<Vec<(ActivityType, i64)>>> + Send;
 
+    /// Gets activity counts by account and type, for breakdowns that bound
+    /// each account's contribution.
+    ///
+    /// System activities are counted under a `None` account.
+    fn get_activity_type_breakdown_by_account(
+        &mut self,
+        scope: TenantScope,
+        hours: Option<i64>,
+    ) -> impl Future<Output = PgResult<Vec<(Option<Uuid>, ActivityType, i64)>>> + Send;
+
     /// Gets system-generated activities that have no associated user account.
     fn get_system_activities(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceActivity>>> + Send;
 
     /// Gets activities originating from a specific IP address for security analysis.
     fn get_activities_by_ip(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         ip_addr: IpNet,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceActivity>>> + Send;
 
-    /// Cleans up old activity logs to manage database size and performance.
+    /// Deletes up to `limit` activities created before `cutoff`.
+    ///
+    /// The newest record of every workspace is kept regardless of age, so
+    /// the chain stays anchored and new records keep linking to it.
+    /// Workspaces with their own audit retention or under a workspace-wide
+    /// legal hold are skipped.
     fn cleanup_old_activities(
         &mut self,
-        days_to_keep: i64,
+        admin: &AdminScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+
+    /// Deletes up to `limit` of a workspace's activities created before
+    /// `cutoff`, keeping its newest record.
+    ///
+    /// Deletes nothing while the workspace is under a workspace-wide legal
+    /// hold.
+    fn cleanup_workspace_activities(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
     ) -> impl Future<Output = PgResult<usize>> + Send;
+
+    /// Counts the activities [`cleanup_workspace_activities`] would delete.
+    ///
+    /// [`cleanup_workspace_activities`]: Self::cleanup_workspace_activities
+    fn count_expired_workspace_activities(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
 }
 
 impl WorkspaceActivityRepository for PgConnection {
     async fn log_activity(
         &mut self,
         activity: NewWorkspaceActivity,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> PgResult<WorkspaceActivity> {
-        use schema::workspace_activities;
+        use schema::workspace_activities::{self, dsl};
 
-        let activity = diesel::insert_into(workspace_activities::table)
-            .values(&activity)
-            .returning(WorkspaceActivity::as_returning())
-            .get_result(self)
+        let _timer = QueryTimer::start("log_activity");
+
+        let activity = self
+            .transaction(async |conn| {
+                // Held until commit, so concurrent appends to the workspace
+                // cannot link to the same predecessor.
+                diesel::sql_query("SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))")
+                    .bind::<sql_types::Uuid, _>(activity.workspace_id)
+                    .execute(conn)
+                    .await?;
+
+                let previous: Option<(i64, Option<Vec<u8>>)> = workspace_activities::table
+                    .filter(dsl::workspace_id.eq(activity.workspace_id))
+                    .select((dsl::sequence_number, dsl::record_hash))
+                    .order(dsl::sequence_number.desc())
+                    .first(conn)
+                    .await
+                    .optional()?;
+
+                diesel::insert_into(workspace_activities::table)
+                    .values(&activity.into_chained(previous, provider, clock, ids))
+                    .returning(WorkspaceActivity::as_returning())
+                    .get_result(conn)
+                    .await
+            })
             .await
             .map_err(PgError::from)?;
 
@@ -153,13 +254,15 @@ impl WorkspaceActivityRepository for PgConnection {
 
     async fn offset_list_workspace_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceActivity>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_activity");
+
         let activities = workspace_activities::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .select(WorkspaceActivity::as_select())
             .order(dsl::created_at.desc())
             .limit(pagination.limit)
@@ -173,18 +276,45 @@ impl WorkspaceActivityRepository for PgConnection {
 
     async fn cursor_list_workspace_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
+        filter: ActivityFilter,
     ) -> PgResult<CursorPage<(WorkspaceActivity, Option<Username>)>> {
         use diesel::dsl::count_star;
         use schema::workspace_activities::dsl;
         use schema::{accounts, workspace_activities};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_activity");
+
+        let filtered = || {
+            let mut query = workspace_activities::table
+                .left_join(accounts::table)
+                .filter(scope.predicate(dsl::workspace_id))
+                .into_boxed();
+
+            if let Some(actor) = filter.actor.clone() {
+                query = query.filter(accounts::username.eq(actor));
+            }
+            if let Some(activity_types) = filter.activity_types() {
+                query = query.filter(dsl::activity_type.eq_any(activity_types));
+            }
+            if let Some(resource_id) = filter.resource_id {
+                query = query.filter(dsl::resource_id.eq(resource_id));
+            }
+            if let Some(from) = filter.from {
+                query = query.filter(dsl::created_at.ge(jiff_diesel::Timestamp::from(from)));
+            }
+            if let Some(to) = filter.to {
+                query = query.filter(dsl::created_at.lt(jiff_diesel::Timestamp::from(to)));
+            }
+
+            query
+        };
+
         // Get total count only if requested
         let total = if pagination.include_count {
             Some(
-                workspace_activities::table
-                    .filter(dsl::workspace_id.eq(workspace_id))
+                filtered()
                     .select(count_star())
                     .get_result(self)
                     .await
@@ -195,10 +325,7 @@ impl WorkspaceActivityRepository for PgConnection {
         };
 
         // Build query with cursor
-        let mut query = workspace_activities::table
-            .left_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
-            .into_boxed();
+        let mut query = filtered();
 
         if let Some(cursor) = &pagination.after {
             let cursor_ts = jiff_diesel::Timestamp::from(cursor.timestamp);
@@ -225,6 +352,29 @@ impl WorkspaceActivityRepository for PgConnection {
         }))
     }
 
+    async fn list_workspace_activity_chain(
+        &mut self,
+        scope: TenantScope,
+        after_sequence_number: i64,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceActivity>> {
+        use schema::workspace_activities::{self, dsl};
+
+        let _timer = QueryTimer::start("list_workspace_activity_chain");
+
+        let activities = workspace_activities::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::sequence_number.gt(after_sequence_number))
+            .select(WorkspaceActivity::as_select())
+            .order(dsl::sequence_number.asc())
+            .limit(limit)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(activities)
+    }
+
     async fn get_account_recent_activity(
         &mut self,
         account_id: Uuid,
@@ -232,6 +382,8 @@ impl WorkspaceActivityRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceActivity>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_account_recent_activity");
+
         let activities = workspace_activities::table
             .filter(dsl::account_id.eq(account_id))
             .select(WorkspaceActivity::as_select())
@@ -247,14 +399,16 @@ impl WorkspaceActivityRepository for PgConnection {
 
     async fn get_activity_by_type(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         activity_type_filter: ActivityType,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceActivity>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_activity_by_type");
+
         let activities = workspace_activities::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::activity_type.eq(activity_type_filter))
             .select(WorkspaceActivity::as_select())
             .order(dsl::created_at.desc())
@@ -274,6 +428,8 @@ impl WorkspaceActivityRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceActivity>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_recent_account_activity");
+
         let cutoff_time = jiff_diesel::Timestamp::from(Timestamp::now() - Span::new().hours(hours));
 
         let activities = workspace_activities::table
@@ -291,71 +447,91 @@ impl WorkspaceActivityRepository for PgConnection {
 
     async fn log_integration_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         params: LogEntityActivityParams,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> PgResult<WorkspaceActivity> {
+        let _timer = QueryTimer::start("log_integration_activity");
+
         let activity = NewWorkspaceActivity {
-            workspace_id,
+            workspace_id: scope.workspace_id(),
             account_id: params.account_id,
             activity_type: params.activity_type,
+            resource_id: params.resource_id,
             description: Some(params.description),
             metadata: Some(params.metadata),
             ip_address: params.ip_address,
             user_agent: params.user_agent,
         };
 
-        self.log_activity(activity).await
+        self.log_activity(activity, provider, clock, ids).await
     }
 
     async fn log_member_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         params: LogEntityActivityParams,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> PgResult<WorkspaceActivity> {
+        let _timer = QueryTimer::start("log_member_activity");
+
         let activity = NewWorkspaceActivity {
-            workspace_id,
+            workspace_id: scope.workspace_id(),
             account_id: params.account_id,
             activity_type: params.activity_type,
+            resource_id: params.resource_id,
             description: Some(params.description),
             metadata: Some(params.metadata),
             ip_address: params.ip_address,
             user_agent: params.user_agent,
         };
 
-        self.log_activity(activity).await
+        self.log_activity(activity, provider, clock, ids).await
     }
 
     async fn log_document_activity(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         params: LogEntityActivityParams,
+        provider: &dyn CryptoProvider,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
     ) -> PgResult<WorkspaceActivity> {
+        let _timer = QueryTimer::start("log_document_activity");
+
         let activity = NewWorkspaceActivity {
-            workspace_id,
+            workspace_id: scope.workspace_id(),
             account_id: params.account_id,
             activity_type: params.activity_type,
+            resource_id: params.resource_id,
             description: Some(params.description),
             metadata: Some(params.metadata),
             ip_address: params.ip_address,
             user_agent: params.user_agent,
         };
 
-        self.log_activity(activity).await
+        self.log_activity(activity, provider, clock, ids).await
     }
 
     async fn get_most_active_accounts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         hours: Option<i64>,
         limit: i64,
     ) -> PgResult<Vec<(Option<Uuid>, i64)>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_most_active_accounts");
+
         let results = if let Some(time_window) = hours {
             let cutoff_time =
                 jiff_diesel::Timestamp::from(Timestamp::now() - Span::new().hours(time_window));
             workspace_activities::table
-                .filter(dsl::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(dsl::workspace_id))
                 .filter(dsl::account_id.is_not_null())
                 .filter(dsl::created_at.gt(cutoff_time))
                 .group_by(dsl::account_id)
@@ -367,7 +543,7 @@ impl WorkspaceActivityRepository for PgConnection {
                 .map_err(PgError::from)?
         } else {
             workspace_activities::table
-                .filter(dsl::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(dsl::workspace_id))
                 .filter(dsl::account_id.is_not_null())
                 .group_by(dsl::account_id)
                 .select((dsl::account_id, diesel::dsl::count(dsl::id)))
@@ -383,16 +559,18 @@ impl WorkspaceActivityRepository for PgConnection {
 
     async fn get_activity_type_breakdown(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         hours: Option<i64>,
     ) -> PgResult<Vec<(ActivityType, i64)>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_activity_type_breakdown");
+
         let results = if let Some(time_window) = hours {
             let cutoff_time =
                 jiff_diesel::Timestamp::from(Timestamp::now() - Span::new().hours(time_window));
             workspace_activities::table
-                .filter(dsl::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(dsl::workspace_id))
                 .filter(dsl::created_at.gt(cutoff_time))
                 .group_by(dsl::activity_type)
                 .select((dsl::activity_type, diesel::dsl::count(dsl::id)))
@@ -402,7 +580,7 @@ impl WorkspaceActivityRepository for PgConnection {
                 .map_err(PgError::from)?
         } else {
             workspace_activities::table
-                .filter(dsl::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(dsl::workspace_id))
                 .group_by(dsl::activity_type)
                 .select((dsl::activity_type, diesel::dsl::count(dsl::id)))
                 .order(diesel::dsl::count(dsl::id).desc())
@@ -414,15 +592,55 @@ impl WorkspaceActivityRepository for PgConnection {
         Ok(results)
     }
 
+    async fn get_activity_type_breakdown_by_account(
+        &mut self,
+        scope: TenantScope,
+        hours: Option<i64>,
+    ) -> PgResult<Vec<(Option<Uuid>, ActivityType, i64)>> {
+        use schema::workspace_activities::{self, dsl};
+
+        let _timer = QueryTimer::start("get_activity_type_breakdown_by_account");
+
+        let select = (
+            dsl::account_id,
+            dsl::activity_type,
+            diesel::dsl::count(dsl::id),
+        );
+        let results = if let Some(time_window) = hours {
+            let cutoff_time =
+                jiff_diesel::Timestamp::from(Timestamp::now() - Span::new().hours(time_window));
+            workspace_activities::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::created_at.gt(cutoff_time))
+                .group_by((dsl::account_id, dsl::activity_type))
+                .select(select)
+                .load::<(Option<Uuid>, ActivityType, i64)>(self)
+                .await
+                .map_err(PgError::from)?
+        } else {
+            workspace_activities::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .group_by((dsl::account_id, dsl::activity_type))
+                .select(select)
+                .load::<(Option<Uuid>, ActivityType, i64)>(self)
+                .await
+                .map_err(PgError::from)?
+        };
+
+        Ok(results)
+    }
+
     async fn get_system_activities(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceActivity>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_system_activities");
+
         let activities = workspace_activities::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::account_id.is_null())
             .select(WorkspaceActivity::as_select())
             .order(dsl::created_at.desc())
@@ -437,14 +655,16 @@ impl WorkspaceActivityRepository for PgConnection {
 
     async fn get_activities_by_ip(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         ip_addr: IpNet,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceActivity>> {
         use schema::workspace_activities::{self, dsl};
 
+        let _timer = QueryTimer::start("get_activities_by_ip");
+
         let activities = workspace_activities::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::ip_address.eq(ip_addr))
             .select(WorkspaceActivity::as_select())
             .order(dsl::created_at.desc())
@@ -457,18 +677,157 @@ impl WorkspaceActivityRepository for PgConnection {
         Ok(activities)
     }
 
-    async fn cleanup_old_activities(&mut self, days_to_keep: i64) -> PgResult<usize> {
-        use schema::workspace_activities::dsl::*;
+    async fn cleanup_old_activities(
+        &mut self,
+        _admin: &AdminScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> PgResult<usize> {
+        let _timer = QueryTimer::start("cleanup_old_activities");
+
+        let deleted_count = self
+            .transaction(async |conn| {
+                // Lets the deletes past the append-only trigger for this
+                // transaction only.
+                diesel::sql_query("SET LOCAL nvisy.activity_retention = 'on'")
+                    .execute(conn)
+                    .await?;
+
+                diesel::sql_query(
+                    "DELETE FROM workspace_activities
+                     WHERE id IN (
+                         SELECT expired.id
+                         FROM workspace_activities AS expired
+                         WHERE expired.created_at < $1
+                           AND expired.sequence_number < (
+                               SELECT max(head.sequence_number)
+                               FROM workspace_activities AS head
+                               WHERE head.workspace_id = expired.workspace_id
+                           )
+                           AND NOT EXISTS (
+                               SELECT 1
+                               FROM workspace_retention_policies AS policy
+                               WHERE policy.workspace_id = expired.workspace_id
+                                 AND policy.activity_retention_days IS NOT NULL
+                           )
+                           AND NOT EXISTS (
+                               SELECT 1
+                               FROM workspace_legal_holds AS hold
+                               WHERE hold.workspace_id = expired.workspace_id
+                                 AND hold.file_id IS NULL
+                                 AND hold.released_at IS NULL
+                           )
+                         ORDER BY expired.created_at
+                         LIMIT $2
+                     )",
+                )
+                .bind::<sql_types::Timestamptz, _>(jiff_diesel::Timestamp::from(cutoff))
+                .bind::<sql_types::BigInt, _>(limit)
+                .execute(conn)
+                .await
+            })
+            .await
+            .map_err(PgError::from)?;
 
-        let cutoff_date =
-            jiff_diesel::Timestamp::from(Timestamp::now() - Span::new().days(days_to_keep));
+        Ok(deleted_count)
+    }
 
-        let deleted_count = diesel::delete(workspace_activities)
-            .filter(created_at.lt(cutoff_date))
-            .execute(self)
+    async fn cleanup_workspace_activities(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> PgResult<usize> {
+        let _timer = QueryTimer::start("cleanup_workspace_activities");
+
+        let deleted_count = self
+            .transaction(async |conn| {
+                diesel::sql_query("SET LOCAL nvisy.activity_retention = 'on'")
+                    .execute(conn)
+                    .await?;
+
+                diesel::sql_query(
+                    "DELETE FROM workspace_activities
+                     WHERE id IN (
+                         SELECT expired.id
+                         FROM workspace_activities AS expired
+                         WHERE expired.workspace_id = $1
+                           AND expired.created_at < $2
+                           AND expired.sequence_number < (
+                               SELECT max(head.sequence_number)
+                               FROM workspace_activities AS head
+                               WHERE head.workspace_id = $1
+                           )
+                           AND NOT EXISTS (
+                               SELECT 1
+                               FROM workspace_legal_holds AS hold
+                               WHERE hold.workspace_id = $1
+                                 AND hold.file_id IS NULL
+                                 AND hold.released_at IS NULL
+                           )
+                         ORDER BY expired.sequence_number
+                         LIMIT $3
+                     )",
+                )
+                .bind::<sql_types::Uuid, _>(scope.workspace_id())
+                .bind::<sql_types::Timestamptz, _>(jiff_diesel::Timestamp::from(cutoff))
+                .bind::<sql_types::BigInt, _>(limit)
+                .execute(conn)
+                .await
+            })
             .await
             .map_err(PgError::from)?;
 
         Ok(deleted_count)
     }
+
+    async fn count_expired_workspace_activities(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+    ) -> PgResult<i64> {
+        use diesel::dsl::{exists, not};
+        use schema::workspace_activities::{self, dsl};
+        use schema::workspace_legal_holds::dsl as hold_dsl;
+
+        let _timer = QueryTimer::start("count_expired_workspace_activities");
+
+        let held = diesel::select(exists(
+            schema::workspace_legal_holds::table
+                .filter(scope.predicate(hold_dsl::workspace_id))
+                .filter(hold_dsl::file_id.is_null())
+                .filter(hold_dsl::released_at.is_null()),
+        ))
+        .get_result::<bool>(self)
+        .await
+        .map_err(PgError::from)?;
+        if held {
+            return Ok(0);
+        }
+
+        let expired: i64 = workspace_activities::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::created_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .count()
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        // The newest record is kept, so it never counts as expired.
+        let newest_expired = not(exists(
+            workspace_activities::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::created_at.ge(jiff_diesel::Timestamp::from(cutoff))),
+        ));
+        let newest_expired: bool = diesel::select(newest_expired)
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(if newest_expired && expired > 0 {
+            expired - 1
+        } else {
+            expired
+        })
+    }
 }
@@ -0,0 +1,128 @@
+//! Workspace change event repository: the outbox for workspace events.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use jiff::Timestamp;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceChangeEvent, WorkspaceChangeEvent};
+use crate::query::AdminScope;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for the workspace change event outbox.
+///
+/// Events are read in id order and marked published once delivered, so a
+/// consumer that restarts resumes from the first unpublished change. The
+/// outbox spans every workspace, so all operations past recording take an
+/// [`AdminScope`].
+pub trait WorkspaceChangeEventRepository {
+    /// Records an application event in the outbox.
+    ///
+    /// Call it on the connection (or in the transaction) that makes the
+    /// change, so the event is committed if and only if the change is.
+    fn record_change_event(
+        &mut self,
+        event: NewWorkspaceChangeEvent,
+    ) -> impl Future<Output = PgResult<WorkspaceChangeEvent>> + Send;
+
+    /// Lists up to `limit` unpublished change events, oldest first.
+    fn list_pending_change_events(
+        &mut self,
+        admin: &AdminScope,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceChangeEvent>>> + Send;
+
+    /// Marks a change event as published.
+    fn mark_change_event_published(
+        &mut self,
+        admin: &AdminScope,
+        event_id: i64,
+    ) -> impl Future<Output = PgResult<()>> + Send;
+
+    /// Deletes change events published before `cutoff`.
+    fn prune_published_change_events(
+        &mut self,
+        admin: &AdminScope,
+        cutoff: Timestamp,
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+}
+
+impl WorkspaceChangeEventRepository for PgConnection {
+    async fn record_change_event(
+        &mut self,
+        event: NewWorkspaceChangeEvent,
+    ) -> PgResult<WorkspaceChangeEvent> {
+        use schema::workspace_change_events;
+
+        let _timer = QueryTimer::start("record_change_event");
+
+        let event = diesel::insert_into(workspace_change_events::table)
+            .values(&event)
+            .returning(WorkspaceChangeEvent::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(event)
+    }
+
+    async fn list_pending_change_events(
+        &mut self,
+        _admin: &AdminScope,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceChangeEvent>> {
+        use schema::workspace_change_events::{self, dsl};
+
+        let _timer = QueryTimer::start("list_pending_change_events");
+
+        let events = workspace_change_events::table
+            .filter(dsl::published_at.is_null())
+            .order(dsl::id.asc())
+            .limit(limit)
+            .select(WorkspaceChangeEvent::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(events)
+    }
+
+    async fn mark_change_event_published(
+        &mut self,
+        _admin: &AdminScope,
+        event_id: i64,
+    ) -> PgResult<()> {
+        use schema::workspace_change_events::{self, dsl};
+
+        let _timer = QueryTimer::start("mark_change_event_published");
+
+        diesel::update(workspace_change_events::table.filter(dsl::id.eq(event_id)))
+            .filter(dsl::published_at.is_null())
+            .set(dsl::published_at.eq(Some(jiff_diesel::Timestamp::from(Timestamp::now()))))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(())
+    }
+
+    async fn prune_published_change_events(
+        &mut self,
+        _admin: &AdminScope,
+        cutoff: Timestamp,
+    ) -> PgResult<usize> {
+        use schema::workspace_change_events::{self, dsl};
+
+        let _timer = QueryTimer::start("prune_published_change_events");
+
+        let deleted_count = diesel::delete(workspace_change_events::table)
+            .filter(dsl::published_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(deleted_count)
+    }
+}
@@ -6,24 +6,87 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspaceConnection, UpdateWorkspaceConnection, WorkspaceConnection};
-use crate::types::{CursorPage, CursorPagination, OffsetPagination, Username};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::{
+    ConnectionCredentials, CredentialCipher, CursorPage, CursorPagination, OffsetPagination,
+    Username,
+};
 use crate::{PgConnection, PgError, PgResult, schema};
 
 /// Repository for workspace connection database operations.
 ///
 /// Handles connection lifecycle management including creation, updates,
-/// and workspace-scoped queries.
+/// and workspace-scoped queries. Credentials are sealed with the given
+/// [`CredentialCipher`] on the way in and opened with it on the way out.
 pub trait WorkspaceConnectionRepository {
-    /// Creates a new workspace connection record.
+    /// Creates a new workspace connection record with its credentials.
     fn create_workspace_connection(
         &mut self,
         new_connection: NewWorkspaceConnection,
+        credentials: &ConnectionCredentials,
+        cipher: &dyn CredentialCipher,
     ) -> impl Future<Output = PgResult<WorkspaceConnection>> + Send;
 
+    /// Finds and opens a connection's credentials.
+    ///
+    /// Excludes soft-deleted connections.
+    fn find_connection_credentials(
+        &mut self,
+        scope: TenantScope,
+        connection_id: Uuid,
+        cipher: &dyn CredentialCipher,
+    ) -> impl Future<Output = PgResult<Option<ConnectionCredentials>>> + Send;
+
+    /// Replaces a connection's credentials.
+    ///
+    /// Returns `None` if the connection does not exist or is deleted.
+    fn store_connection_credentials(
+        &mut self,
+        scope: TenantScope,
+        connection_id: Uuid,
+        credentials: &ConnectionCredentials,
+        cipher: &dyn CredentialCipher,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceConnection>>> + Send;
+
+    /// Counts connections, deleted or not, whose credentials are sealed under
+    /// a key version other than `key_version`.
+    fn count_connections_to_reseal(
+        &mut self,
+        admin: &AdminScope,
+        key_version: i32,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
+
+    /// Lists up to `limit` connections, deleted or not, whose credentials
+    /// are sealed under a key version other than `key_version`.
+    fn list_connections_to_reseal(
+        &mut self,
+        admin: &AdminScope,
+        key_version: i32,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceConnection>>> + Send;
+
+    /// Reseals a connection's credentials under the cipher's current key
+    /// version.
+    ///
+    /// The write only applies if the row still holds the credentials that
+    /// were read, so a concurrent update is never overwritten. Returns
+    /// whether the row was resealed.
+    fn reseal_connection_credentials(
+        &mut self,
+        admin: &AdminScope,
+        connection: &WorkspaceConnection,
+        cipher: &dyn CredentialCipher,
+    ) -> impl Future<Output = PgResult<bool>> + Send;
+
     /// Finds a connection by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_connection_by_id(
         &mut self,
+        admin: &AdminScope,
         connection_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceConnection>>> + Send;
 
@@ -32,7 +95,7 @@ pub trait WorkspaceConnectionRepository {
     /// Provides workspace-scoped access control at the database level.
     fn find_connection_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         connection_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceConnection>>> + Send;
 
@@ -42,21 +105,21 @@ pub trait WorkspaceConnectionRepository {
     /// Excludes soft-deleted connections.
     fn find_connection_in_workspace_with_creator(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         connection_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceConnection, Username)>>> + Send;
 
     /// Finds connections by provider type within a workspace.
     fn find_workspace_connections_by_provider(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         provider: &str,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceConnection>>> + Send;
 
     /// Lists all connections in a workspace with offset pagination.
     fn offset_list_workspace_connections(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceConnection>>> + Send;
 
@@ -64,7 +127,7 @@ pub trait WorkspaceConnectionRepository {
     /// with the handle of the account that created it.
     fn cursor_list_workspace_connections(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         provider_filter: Option<&str>,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceConnection, Username)>>> + Send;
@@ -85,13 +148,13 @@ pub trait WorkspaceConnectionRepository {
     /// Counts connections in a workspace.
     fn count_workspace_connections(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
     ) -> impl Future<Output = PgResult<i64>> + Send;
 
     /// Counts connections by provider in a workspace.
     fn count_workspace_connections_by_provider(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         provider: &str,
     ) -> impl Future<Output = PgResult<i64>> + Send;
 }
@@ -100,11 +163,21 @@ impl WorkspaceConnectionRepository for PgConnection {
     async fn create_workspace_connection(
         &mut self,
         new_connection: NewWorkspaceConnection,
+        credentials: &ConnectionCredentials,
+        cipher: &dyn CredentialCipher,
     ) -> PgResult<WorkspaceConnection> {
-        use schema::workspace_connections;
+        use schema::workspace_connections::{self, dsl};
+
+        let _timer = QueryTimer::start("create_workspace_connection");
+
+        let sealed = cipher.seal(new_connection.workspace_id, &credentials.to_bytes()?)?;
 
         let connection = diesel::insert_into(workspace_connections::table)
-            .values(&new_connection)
+            .values((
+                &new_connection,
+                dsl::encrypted_data.eq(sealed.ciphertext),
+                dsl::credential_key_version.eq(sealed.key_version),
+            ))
             .returning(WorkspaceConnection::as_returning())
             .get_result(self)
             .await
@@ -113,12 +186,158 @@ impl WorkspaceConnectionRepository for PgConnection {
         Ok(connection)
     }
 
+    async fn find_connection_credentials(
+        &mut self,
+        scope: TenantScope,
+        connection_id: Uuid,
+        cipher: &dyn CredentialCipher,
+    ) -> PgResult<Option<ConnectionCredentials>> {
+        use schema::workspace_connections::{self, dsl};
+
+        let _timer = QueryTimer::start("find_connection_credentials");
+
+        let sealed: Option<(Uuid, i32, Vec<u8>)> = workspace_connections::table
+            .filter(dsl::id.eq(connection_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::deleted_at.is_null())
+            .select((
+                dsl::workspace_id,
+                dsl::credential_key_version,
+                dsl::encrypted_data,
+            ))
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        let Some((workspace_id, key_version, ciphertext)) = sealed else {
+            return Ok(None);
+        };
+
+        let plaintext = cipher.open(workspace_id, key_version, &ciphertext)?;
+        Ok(Some(ConnectionCredentials::from_bytes(&plaintext)?))
+    }
+
+    async fn store_connection_credentials(
+        &mut self,
+        scope: TenantScope,
+        connection_id: Uuid,
+        credentials: &ConnectionCredentials,
+        cipher: &dyn CredentialCipher,
+    ) -> PgResult<Option<WorkspaceConnection>> {
+        use schema::workspace_connections::{self, dsl};
+
+        let _timer = QueryTimer::start("store_connection_credentials");
+
+        let sealed = cipher.seal(scope.workspace_id(), &credentials.to_bytes()?)?;
+
+        let connection = diesel::update(
+            workspace_connections::table
+                .filter(dsl::id.eq(connection_id))
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::deleted_at.is_null()),
+        )
+        .set((
+            dsl::encrypted_data.eq(sealed.ciphertext),
+            dsl::credential_key_version.eq(sealed.key_version),
+        ))
+        .returning(WorkspaceConnection::as_returning())
+        .get_result(self)
+        .await
+        .optional()
+        .map_err(PgError::from)?;
+
+        Ok(connection)
+    }
+
+    async fn count_connections_to_reseal(
+        &mut self,
+        _admin: &AdminScope,
+        key_version: i32,
+    ) -> PgResult<i64> {
+        use schema::workspace_connections::{self, dsl};
+
+        let _timer = QueryTimer::start("count_connections_to_reseal");
+
+        let count = workspace_connections::table
+            .filter(dsl::credential_key_version.ne(key_version))
+            .count()
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(count)
+    }
+
+    async fn list_connections_to_reseal(
+        &mut self,
+        _admin: &AdminScope,
+        key_version: i32,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceConnection>> {
+        use schema::workspace_connections::{self, dsl};
+
+        let _timer = QueryTimer::start("list_connections_to_reseal");
+
+        let connections = workspace_connections::table
+            .filter(dsl::credential_key_version.ne(key_version))
+            .order((dsl::credential_key_version.asc(), dsl::id.asc()))
+            .limit(limit)
+            .select(WorkspaceConnection::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(connections)
+    }
+
+    async fn reseal_connection_credentials(
+        &mut self,
+        _admin: &AdminScope,
+        connection: &WorkspaceConnection,
+        cipher: &dyn CredentialCipher,
+    ) -> PgResult<bool> {
+        use schema::workspace_connections::{self, dsl};
+
+        let _timer = QueryTimer::start("reseal_connection_credentials");
+
+        if connection.credential_key_version == cipher.key_version() {
+            return Ok(false);
+        }
+
+        let plaintext = cipher.open(
+            connection.workspace_id,
+            connection.credential_key_version,
+            &connection.encrypted_data,
+        )?;
+        let sealed = cipher.seal(connection.workspace_id, &plaintext)?;
+
+        let resealed = diesel::update(
+            workspace_connections::table
+                .filter(dsl::id.eq(connection.id))
+                .filter(dsl::credential_key_version.eq(connection.credential_key_version))
+                .filter(dsl::encrypted_data.eq(&connection.encrypted_data)),
+        )
+        .set((
+            dsl::encrypted_data.eq(sealed.ciphertext),
+            dsl::credential_key_version.eq(sealed.key_version),
+        ))
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(resealed > 0)
+    }
+
     async fn find_workspace_connection_by_id(
         &mut self,
+        _admin: &AdminScope,
         connection_id: Uuid,
     ) -> PgResult<Option<WorkspaceConnection>> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_connection_by_id");
+
         let connection = workspace_connections::table
             .filter(dsl::id.eq(connection_id))
             .filter(dsl::deleted_at.is_null())
@@ -133,14 +352,16 @@ impl WorkspaceConnectionRepository for PgConnection {
 
     async fn find_connection_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         connection_id: Uuid,
     ) -> PgResult<Option<WorkspaceConnection>> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("find_connection_in_workspace");
+
         let connection = workspace_connections::table
             .filter(dsl::id.eq(connection_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspaceConnection::as_select())
             .first(self)
@@ -153,15 +374,17 @@ impl WorkspaceConnectionRepository for PgConnection {
 
     async fn find_connection_in_workspace_with_creator(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         connection_id: Uuid,
     ) -> PgResult<Option<(WorkspaceConnection, Username)>> {
         use schema::workspace_connections::dsl;
         use schema::{accounts, workspace_connections};
 
+        let _timer = QueryTimer::start("find_connection_in_workspace_with_creator");
+
         let connection = workspace_connections::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::id.eq(connection_id))
             .filter(dsl::deleted_at.is_null())
             .select((WorkspaceConnection::as_select(), accounts::username))
@@ -175,13 +398,15 @@ impl WorkspaceConnectionRepository for PgConnection {
 
     async fn find_workspace_connections_by_provider(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         provider: &str,
     ) -> PgResult<Vec<WorkspaceConnection>> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_connections_by_provider");
+
         let connections = workspace_connections::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::provider.eq(provider))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::name.asc())
@@ -195,13 +420,15 @@ impl WorkspaceConnectionRepository for PgConnection {
 
     async fn offset_list_workspace_connections(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceConnection>> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_connections");
+
         let connections = workspace_connections::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::created_at.desc())
             .limit(pagination.limit)
@@ -216,16 +443,18 @@ impl WorkspaceConnectionRepository for PgConnection {
 
     async fn cursor_list_workspace_connections(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         provider_filter: Option<&str>,
     ) -> PgResult<CursorPage<(WorkspaceConnection, Username)>> {
         use schema::workspace_connections::dsl;
         use schema::{accounts, workspace_connections};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_connections");
+
         // Build base query with filters
         let mut base_query = workspace_connections::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -249,7 +478,7 @@ impl WorkspaceConnectionRepository for PgConnection {
         // Rebuild query for fetching items
         let mut query = workspace_connections::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -299,6 +528,8 @@ impl WorkspaceConnectionRepository for PgConnection {
     ) -> PgResult<WorkspaceConnection> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("update_workspace_connection");
+
         let connection =
             diesel::update(workspace_connections::table.filter(dsl::id.eq(connection_id)))
                 .set(&updates)
@@ -314,6 +545,8 @@ impl WorkspaceConnectionRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_workspace_connection");
+
         diesel::update(workspace_connections::table.filter(dsl::id.eq(connection_id)))
             .set(dsl::deleted_at.eq(now))
             .execute(self)
@@ -323,11 +556,13 @@ impl WorkspaceConnectionRepository for PgConnection {
         Ok(())
     }
 
-    async fn count_workspace_connections(&mut self, workspace_id: Uuid) -> PgResult<i64> {
+    async fn count_workspace_connections(&mut self, scope: TenantScope) -> PgResult<i64> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("count_workspace_connections");
+
         let count = workspace_connections::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .count()
             .get_result(self)
@@ -339,13 +574,15 @@ impl WorkspaceConnectionRepository for PgConnection {
 
     async fn count_workspace_connections_by_provider(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         provider: &str,
     ) -> PgResult<i64> {
         use schema::workspace_connections::{self, dsl};
 
+        let _timer = QueryTimer::start("count_workspace_connections_by_provider");
+
         let count = workspace_connections::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::provider.eq(provider))
             .filter(dsl::deleted_at.is_null())
             .count()
@@ -6,16 +6,19 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{
     NewWorkspaceConnectionRun, UpdateWorkspaceConnectionRun, WorkspaceConnectionRun,
 };
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{CursorPage, CursorPagination, SyncStatus, Username};
 use crate::{PgConnection, PgError, PgResult, schema};
 
 /// Repository for workspace connection run database operations.
 ///
 /// Handles sync run lifecycle management including creation, status updates,
-/// completion tracking, and queries.
+/// completion tracking, and queries. Runs carry no workspace column, so every
+/// method past creation is scoped through the run's connection.
 pub trait WorkspaceConnectionRunRepository {
     /// Creates a new workspace connection run record.
     fn create_workspace_connection_run(
@@ -24,8 +27,12 @@ pub trait WorkspaceConnectionRunRepository {
     ) -> impl Future<Output = PgResult<WorkspaceConnectionRun>> + Send;
 
     /// Finds a workspace connection run by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// run's connection itself.
     fn find_workspace_connection_run_by_id(
         &mut self,
+        admin: &AdminScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceConnectionRun>>> + Send;
 
@@ -36,7 +43,7 @@ pub trait WorkspaceConnectionRunRepository {
     /// is not found.
     fn find_connection_run_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceConnectionRun>>> + Send;
 
@@ -48,7 +55,7 @@ pub trait WorkspaceConnectionRunRepository {
     /// is not found.
     fn find_connection_run_by_id(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceConnectionRun, Option<Username>)>>> + Send;
 
@@ -61,6 +68,7 @@ pub trait WorkspaceConnectionRunRepository {
     /// so a page of connections costs one round-trip, not one per connection.
     fn last_successful_sync_at(
         &mut self,
+        scope: TenantScope,
         connection_ids: &[Uuid],
     ) -> impl Future<Output = PgResult<Vec<(Uuid, jiff_diesel::Timestamp)>>> + Send;
 
@@ -68,6 +76,7 @@ pub trait WorkspaceConnectionRunRepository {
     /// with the handle of the account that triggered it, if any.
     fn cursor_list_workspace_connection_runs(
         &mut self,
+        scope: TenantScope,
         connection_id: Uuid,
         pagination: CursorPagination,
         status_filter: Option<SyncStatus>,
@@ -83,7 +92,7 @@ pub trait WorkspaceConnectionRunRepository {
     /// [`cursor_list_workspace_connection_runs`]: Self::cursor_list_workspace_connection_runs
     fn cursor_list_workspace_connection_runs_all(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         status_filter: Option<SyncStatus>,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceConnectionRun, Uuid, Option<Username>)>>>
@@ -92,12 +101,14 @@ pub trait WorkspaceConnectionRunRepository {
     /// Gets the most recent run for a connection (its current sync state).
     fn find_latest_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         connection_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceConnectionRun>>> + Send;
 
     /// Updates a workspace connection run with new data.
     fn update_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         updates: UpdateWorkspaceConnectionRun,
     ) -> impl Future<Output = PgResult<WorkspaceConnectionRun>> + Send;
@@ -105,12 +116,14 @@ pub trait WorkspaceConnectionRunRepository {
     /// Marks a run as completed successfully.
     fn complete_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<WorkspaceConnectionRun>> + Send;
 
     /// Marks a run as failed, recording the error detail.
     fn fail_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         error_message: &str,
     ) -> impl Future<Output = PgResult<WorkspaceConnectionRun>> + Send;
@@ -118,6 +131,7 @@ pub trait WorkspaceConnectionRunRepository {
     /// Marks a run as cancelled.
     fn cancel_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<WorkspaceConnectionRun>> + Send;
 }
@@ -129,6 +143,8 @@ impl WorkspaceConnectionRunRepository for PgConnection {
     ) -> PgResult<WorkspaceConnectionRun> {
         use schema::workspace_connection_runs;
 
+        let _timer = QueryTimer::start("create_workspace_connection_run");
+
         let run = diesel::insert_into(workspace_connection_runs::table)
             .values(&new_run)
             .returning(WorkspaceConnectionRun::as_returning())
@@ -141,10 +157,13 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn find_workspace_connection_run_by_id(
         &mut self,
+        _admin: &AdminScope,
         run_id: Uuid,
     ) -> PgResult<Option<WorkspaceConnectionRun>> {
         use schema::workspace_connection_runs::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_connection_run_by_id");
+
         let run = workspace_connection_runs::table
             .filter(dsl::id.eq(run_id))
             .select(WorkspaceConnectionRun::as_select())
@@ -158,16 +177,18 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn find_connection_run_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<Option<WorkspaceConnectionRun>> {
         use schema::workspace_connection_runs::dsl as runs;
         use schema::workspace_connections::dsl as connections;
 
+        let _timer = QueryTimer::start("find_connection_run_in_workspace");
+
         let run = runs::workspace_connection_runs
             .inner_join(connections::workspace_connections)
             .filter(runs::id.eq(run_id))
-            .filter(connections::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(connections::workspace_id))
             .select(WorkspaceConnectionRun::as_select())
             .first(self)
             .await
@@ -179,19 +200,21 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn find_connection_run_by_id(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<Option<(WorkspaceConnectionRun, Option<Username>)>> {
         use schema::workspace_connection_runs::dsl as runs;
         use schema::{accounts, workspace_connection_runs, workspace_connections};
 
+        let _timer = QueryTimer::start("find_connection_run_by_id");
+
         // Runs carry no workspace column; scope through the owning connection so
         // the id resolves only within its workspace.
         let run = workspace_connection_runs::table
             .inner_join(workspace_connections::table)
             .left_join(accounts::table)
             .filter(runs::id.eq(run_id))
-            .filter(workspace_connections::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_connections::workspace_id))
             .select((
                 WorkspaceConnectionRun::as_select(),
                 accounts::username.nullable(),
@@ -206,10 +229,19 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn last_successful_sync_at(
         &mut self,
+        scope: TenantScope,
         connection_ids: &[Uuid],
     ) -> PgResult<Vec<(Uuid, jiff_diesel::Timestamp)>> {
         use diesel::dsl::max;
         use schema::workspace_connection_runs::{self, dsl};
+        use schema::workspace_connections;
+
+        let _timer = QueryTimer::start("last_successful_sync_at");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
 
         if connection_ids.is_empty() {
             return Ok(Vec::new());
@@ -220,6 +252,7 @@ impl WorkspaceConnectionRunRepository for PgConnection {
         // in a terminal state, so the grouped MAX is present for every group.
         workspace_connection_runs::table
             .filter(dsl::connection_id.eq_any(connection_ids))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .filter(dsl::status.eq(SyncStatus::Completed))
             .group_by(dsl::connection_id)
             .select((dsl::connection_id, max(dsl::completed_at).assume_not_null()))
@@ -230,15 +263,24 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn cursor_list_workspace_connection_runs(
         &mut self,
+        scope: TenantScope,
         connection_id: Uuid,
         pagination: CursorPagination,
         status_filter: Option<SyncStatus>,
     ) -> PgResult<CursorPage<(WorkspaceConnectionRun, Option<Username>)>> {
         use schema::workspace_connection_runs::dsl;
-        use schema::{accounts, workspace_connection_runs};
+        use schema::{accounts, workspace_connection_runs, workspace_connections};
+
+        let _timer = QueryTimer::start("cursor_list_workspace_connection_runs");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
 
         let mut base_query = workspace_connection_runs::table
             .filter(dsl::connection_id.eq(connection_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections.clone()))
             .into_boxed();
 
         if let Some(status) = status_filter {
@@ -260,6 +302,7 @@ impl WorkspaceConnectionRunRepository for PgConnection {
         let mut query = workspace_connection_runs::table
             .left_join(accounts::table)
             .filter(dsl::connection_id.eq(connection_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .into_boxed();
 
         if let Some(status) = status_filter {
@@ -310,7 +353,7 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn cursor_list_workspace_connection_runs_all(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         status_filter: Option<SyncStatus>,
     ) -> PgResult<CursorPage<(WorkspaceConnectionRun, Uuid, Option<Username>)>> {
@@ -318,6 +361,8 @@ impl WorkspaceConnectionRunRepository for PgConnection {
         use schema::workspace_connection_runs::dsl as runs;
         use schema::workspace_connections::dsl as connections;
 
+        let _timer = QueryTimer::start("cursor_list_workspace_connection_runs_all");
+
         // Runs have no workspace column; scope them through the owning
         // connection. The owning connection's id and the triggering account's
         // handle are selected alongside each run so the cross-connection response
@@ -326,7 +371,7 @@ impl WorkspaceConnectionRunRepository for PgConnection {
             let mut query = runs::workspace_connection_runs
                 .inner_join(connections::workspace_connections)
                 .left_join(accounts::accounts)
-                .filter(connections::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(connections::workspace_id))
                 .into_boxed();
             if let Some(status) = status_filter {
                 query = query.filter(runs::status.eq(status));
@@ -391,12 +436,22 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn find_latest_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         connection_id: Uuid,
     ) -> PgResult<Option<WorkspaceConnectionRun>> {
         use schema::workspace_connection_runs::{self, dsl};
+        use schema::workspace_connections;
+
+        let _timer = QueryTimer::start("find_latest_workspace_connection_run");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
 
         let run = workspace_connection_runs::table
             .filter(dsl::connection_id.eq(connection_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .order(dsl::started_at.desc())
             .select(WorkspaceConnectionRun::as_select())
             .first(self)
@@ -409,12 +464,23 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn update_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         updates: UpdateWorkspaceConnectionRun,
     ) -> PgResult<WorkspaceConnectionRun> {
         use schema::workspace_connection_runs::{self, dsl};
+        use schema::workspace_connections;
 
-        let run = diesel::update(workspace_connection_runs::table.filter(dsl::id.eq(run_id)))
+        let _timer = QueryTimer::start("update_workspace_connection_run");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
+
+        let run = diesel::update(workspace_connection_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .set(&updates)
             .returning(WorkspaceConnectionRun::as_returning())
             .get_result(self)
@@ -426,12 +492,23 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn complete_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<WorkspaceConnectionRun> {
         use diesel::dsl::now;
         use schema::workspace_connection_runs::{self, dsl};
+        use schema::workspace_connections;
 
-        let run = diesel::update(workspace_connection_runs::table.filter(dsl::id.eq(run_id)))
+        let _timer = QueryTimer::start("complete_workspace_connection_run");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
+
+        let run = diesel::update(workspace_connection_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .set((
                 dsl::status.eq(SyncStatus::Completed),
                 dsl::completed_at.eq(now),
@@ -446,13 +523,24 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn fail_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         error_message: &str,
     ) -> PgResult<WorkspaceConnectionRun> {
         use diesel::dsl::now;
         use schema::workspace_connection_runs::{self, dsl};
+        use schema::workspace_connections;
 
-        let run = diesel::update(workspace_connection_runs::table.filter(dsl::id.eq(run_id)))
+        let _timer = QueryTimer::start("fail_workspace_connection_run");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
+
+        let run = diesel::update(workspace_connection_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .set((
                 dsl::status.eq(SyncStatus::Failed),
                 dsl::error_message.eq(error_message),
@@ -468,12 +556,23 @@ impl WorkspaceConnectionRunRepository for PgConnection {
 
     async fn cancel_workspace_connection_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<WorkspaceConnectionRun> {
         use diesel::dsl::now;
         use schema::workspace_connection_runs::{self, dsl};
+        use schema::workspace_connections;
 
-        let run = diesel::update(workspace_connection_runs::table.filter(dsl::id.eq(run_id)))
+        let _timer = QueryTimer::start("cancel_workspace_connection_run");
+
+        // Runs carry no workspace column; scope them through their connection.
+        let scoped_connections = workspace_connections::table
+            .filter(scope.predicate(workspace_connections::workspace_id))
+            .select(workspace_connections::id);
+
+        let run = diesel::update(workspace_connection_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::connection_id.eq_any(scoped_connections))
             .set((
                 dsl::status.eq(SyncStatus::Cancelled),
                 dsl::completed_at.eq(now),
@@ -6,7 +6,9 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspaceContext, UpdateWorkspaceContext, WorkspaceContext};
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{CursorPage, CursorPagination, OffsetPagination, Username};
 use crate::{PgConnection, PgError, PgResult, schema};
 
@@ -19,15 +21,19 @@ pub trait WorkspaceContextRepository {
     ) -> impl Future<Output = PgResult<WorkspaceContext>> + Send;
 
     /// Finds a context by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_context_by_id(
         &mut self,
+        admin: &AdminScope,
         context_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceContext>>> + Send;
 
     /// Finds a context by ID within a specific workspace.
     fn find_context_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         context_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceContext>>> + Send;
 
@@ -37,14 +43,14 @@ pub trait WorkspaceContextRepository {
     /// Excludes soft-deleted contexts.
     fn find_context_in_workspace_by_slug(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slug: &str,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceContext, Username)>>> + Send;
 
     /// Lists all contexts in a workspace with offset pagination.
     fn offset_list_workspace_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceContext>>> + Send;
 
@@ -52,7 +58,7 @@ pub trait WorkspaceContextRepository {
     /// with the handle of the account that created it.
     fn cursor_list_workspace_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceContext, Username)>>> + Send;
 
@@ -72,7 +78,7 @@ pub trait WorkspaceContextRepository {
     /// Counts contexts in a workspace.
     fn count_workspace_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
     ) -> impl Future<Output = PgResult<i64>> + Send;
 }
 
@@ -83,6 +89,8 @@ impl WorkspaceContextRepository for PgConnection {
     ) -> PgResult<WorkspaceContext> {
         use schema::workspace_contexts;
 
+        let _timer = QueryTimer::start("create_workspace_context");
+
         let context = diesel::insert_into(workspace_contexts::table)
             .values(&new_context)
             .returning(WorkspaceContext::as_returning())
@@ -95,10 +103,13 @@ impl WorkspaceContextRepository for PgConnection {
 
     async fn find_workspace_context_by_id(
         &mut self,
+        _admin: &AdminScope,
         context_id: Uuid,
     ) -> PgResult<Option<WorkspaceContext>> {
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_context_by_id");
+
         let context = workspace_contexts::table
             .filter(dsl::id.eq(context_id))
             .filter(dsl::deleted_at.is_null())
@@ -113,14 +124,16 @@ impl WorkspaceContextRepository for PgConnection {
 
     async fn find_context_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         context_id: Uuid,
     ) -> PgResult<Option<WorkspaceContext>> {
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("find_context_in_workspace");
+
         let context = workspace_contexts::table
             .filter(dsl::id.eq(context_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspaceContext::as_select())
             .first(self)
@@ -133,15 +146,17 @@ impl WorkspaceContextRepository for PgConnection {
 
     async fn find_context_in_workspace_by_slug(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slug: &str,
     ) -> PgResult<Option<(WorkspaceContext, Username)>> {
         use schema::workspace_contexts::dsl;
         use schema::{accounts, workspace_contexts};
 
+        let _timer = QueryTimer::start("find_context_in_workspace_by_slug");
+
         let context = workspace_contexts::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::slug.eq(slug))
             .filter(dsl::deleted_at.is_null())
             .select((WorkspaceContext::as_select(), accounts::username))
@@ -155,13 +170,15 @@ impl WorkspaceContextRepository for PgConnection {
 
     async fn offset_list_workspace_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceContext>> {
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_contexts");
+
         let contexts = workspace_contexts::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::created_at.desc())
             .limit(pagination.limit)
@@ -176,16 +193,18 @@ impl WorkspaceContextRepository for PgConnection {
 
     async fn cursor_list_workspace_contexts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
     ) -> PgResult<CursorPage<(WorkspaceContext, Username)>> {
         use schema::workspace_contexts::dsl;
         use schema::{accounts, workspace_contexts};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_contexts");
+
         let total = if pagination.include_count {
             Some(
                 workspace_contexts::table
-                    .filter(dsl::workspace_id.eq(workspace_id))
+                    .filter(scope.predicate(dsl::workspace_id))
                     .filter(dsl::deleted_at.is_null())
                     .count()
                     .get_result::<i64>(self)
@@ -198,7 +217,7 @@ impl WorkspaceContextRepository for PgConnection {
 
         let query = workspace_contexts::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -244,6 +263,8 @@ impl WorkspaceContextRepository for PgConnection {
     ) -> PgResult<WorkspaceContext> {
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("update_workspace_context");
+
         let context = diesel::update(workspace_contexts::table.filter(dsl::id.eq(context_id)))
             .set(&updates)
             .returning(WorkspaceContext::as_returning())
@@ -258,6 +279,8 @@ impl WorkspaceContextRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_workspace_context");
+
         diesel::update(workspace_contexts::table.filter(dsl::id.eq(context_id)))
             .set(dsl::deleted_at.eq(now))
             .execute(self)
@@ -267,11 +290,13 @@ impl WorkspaceContextRepository for PgConnection {
         Ok(())
     }
 
-    async fn count_workspace_contexts(&mut self, workspace_id: Uuid) -> PgResult<i64> {
+    async fn count_workspace_contexts(&mut self, scope: TenantScope) -> PgResult<i64> {
         use schema::workspace_contexts::{self, dsl};
 
+        let _timer = QueryTimer::start("count_workspace_contexts");
+
         let count = workspace_contexts::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .count()
             .get_result(self)
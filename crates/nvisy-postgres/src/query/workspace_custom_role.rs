@@ -0,0 +1,222 @@
+//! Workspace custom roles repository for managing workspace-defined roles.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{
+    NewWorkspaceCustomRole, UpdateWorkspaceCustomRole, WorkspaceCustomRole, WorkspaceMember,
+};
+use crate::query::TenantScope;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for workspace custom role database operations.
+pub trait WorkspaceCustomRoleRepository {
+    /// Creates a new custom role.
+    fn create_workspace_custom_role(
+        &mut self,
+        new_role: NewWorkspaceCustomRole,
+    ) -> impl Future<Output = PgResult<WorkspaceCustomRole>> + Send;
+
+    /// Finds a custom role by ID within a specific workspace.
+    fn find_custom_role_in_workspace(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceCustomRole>>> + Send;
+
+    /// Lists all custom roles in a workspace, ordered by name.
+    fn list_workspace_custom_roles(
+        &mut self,
+        scope: TenantScope,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceCustomRole>>> + Send;
+
+    /// Updates a custom role with new data.
+    fn update_workspace_custom_role(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+        updates: UpdateWorkspaceCustomRole,
+    ) -> impl Future<Output = PgResult<WorkspaceCustomRole>> + Send;
+
+    /// Deletes a custom role, unassigning it from every member holding it.
+    fn delete_workspace_custom_role(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+    ) -> impl Future<Output = PgResult<()>> + Send;
+
+    /// Counts the members holding a custom role.
+    fn count_custom_role_members(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
+
+    /// Finds an account's membership in a workspace together with its custom
+    /// role, if one is assigned.
+    ///
+    /// This is the lookup requests are authorized with, so it also returns
+    /// the [`TenantScope`] for the workspace's rows.
+    fn find_workspace_member_with_custom_role(
+        &mut self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> impl Future<
+        Output = PgResult<Option<(WorkspaceMember, Option<WorkspaceCustomRole>, TenantScope)>>,
+    > + Send;
+}
+
+impl WorkspaceCustomRoleRepository for PgConnection {
+    async fn create_workspace_custom_role(
+        &mut self,
+        new_role: NewWorkspaceCustomRole,
+    ) -> PgResult<WorkspaceCustomRole> {
+        use schema::workspace_custom_roles;
+
+        let _timer = QueryTimer::start("create_workspace_custom_role");
+
+        let role = diesel::insert_into(workspace_custom_roles::table)
+            .values(&new_role)
+            .returning(WorkspaceCustomRole::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(role)
+    }
+
+    async fn find_custom_role_in_workspace(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+    ) -> PgResult<Option<WorkspaceCustomRole>> {
+        use schema::workspace_custom_roles::{self, dsl};
+
+        let _timer = QueryTimer::start("find_custom_role_in_workspace");
+
+        let role = workspace_custom_roles::table
+            .filter(dsl::id.eq(role_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .select(WorkspaceCustomRole::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(role)
+    }
+
+    async fn list_workspace_custom_roles(
+        &mut self,
+        scope: TenantScope,
+    ) -> PgResult<Vec<WorkspaceCustomRole>> {
+        use schema::workspace_custom_roles::{self, dsl};
+
+        let _timer = QueryTimer::start("list_workspace_custom_roles");
+
+        let roles = workspace_custom_roles::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .order((dsl::name.asc(), dsl::id.asc()))
+            .select(WorkspaceCustomRole::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(roles)
+    }
+
+    async fn update_workspace_custom_role(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+        updates: UpdateWorkspaceCustomRole,
+    ) -> PgResult<WorkspaceCustomRole> {
+        use schema::workspace_custom_roles::{self, dsl};
+
+        let _timer = QueryTimer::start("update_workspace_custom_role");
+
+        let role = diesel::update(workspace_custom_roles::table)
+            .filter(dsl::id.eq(role_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .set(&updates)
+            .returning(WorkspaceCustomRole::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(role)
+    }
+
+    async fn delete_workspace_custom_role(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+    ) -> PgResult<()> {
+        use schema::workspace_custom_roles::{self, dsl};
+
+        let _timer = QueryTimer::start("delete_workspace_custom_role");
+
+        // Members holding the role are unassigned by the foreign key.
+        diesel::delete(workspace_custom_roles::table)
+            .filter(dsl::id.eq(role_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(())
+    }
+
+    async fn count_custom_role_members(
+        &mut self,
+        scope: TenantScope,
+        role_id: Uuid,
+    ) -> PgResult<i64> {
+        use schema::workspace_members::{self, dsl};
+
+        let _timer = QueryTimer::start("count_custom_role_members");
+
+        let count = workspace_members::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::custom_role_id.eq(role_id))
+            .count()
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(count)
+    }
+
+    async fn find_workspace_member_with_custom_role(
+        &mut self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> PgResult<Option<(WorkspaceMember, Option<WorkspaceCustomRole>, TenantScope)>> {
+        use schema::workspace_members::dsl;
+        use schema::{workspace_custom_roles, workspace_members};
+
+        let _timer = QueryTimer::start("find_workspace_member_with_custom_role");
+
+        let member = workspace_members::table
+            .left_join(workspace_custom_roles::table)
+            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(dsl::account_id.eq(account_id))
+            .select((
+                WorkspaceMember::as_select(),
+                Option::<WorkspaceCustomRole>::as_select(),
+            ))
+            .first::<(WorkspaceMember, Option<WorkspaceCustomRole>)>(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(member.map(|(member, custom_role)| {
+            let scope = TenantScope::new(member.workspace_id);
+            (member, custom_role, scope)
+        }))
+    }
+}
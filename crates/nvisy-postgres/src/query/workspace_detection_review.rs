@@ -0,0 +1,240 @@
+//! Workspace detection reviews repository for versioned reviewer decisions.
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceDetectionReview, WorkspaceDetectionReview};
+use crate::query::TenantScope;
+use crate::types::{ReviewStatus, Username};
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for workspace detection review database operations.
+///
+/// Reviews carry no workspace column, so every method is scoped through the
+/// reviewed run's pipeline.
+pub trait WorkspaceDetectionReviewRepository {
+    /// Records reviewer decisions on a run's detections.
+    ///
+    /// Each decision becomes the next version of its detection's review and
+    /// supersedes the current one, all in one transaction. The run, version
+    /// and status of the given reviews are assigned here. Fails with
+    /// `NotFound` if the run is not in the scoped workspace.
+    fn record_detection_reviews(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+        reviews: Vec<NewWorkspaceDetectionReview>,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceDetectionReview>>> + Send;
+
+    /// Lists the current decision on every reviewed detection of a run, each
+    /// paired with the reviewer's handle, ordered by detection.
+    fn list_current_detection_reviews(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceDetectionReview, Option<Username>)>>> + Send;
+
+    /// Lists the current decisions of several runs at once, ordered by
+    /// detection, each paired with the reviewer's handle and the workspace
+    /// of the reviewed run.
+    ///
+    /// Callers group the reviews by run and check the workspace.
+    fn list_current_detection_reviews_for_runs(
+        &mut self,
+        run_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceDetectionReview, Option<Username>, Uuid)>>> + Send;
+
+    /// Lists every version of a detection's review, oldest first, each paired
+    /// with the reviewer's handle.
+    fn list_detection_review_history(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+        detection_id: &str,
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceDetectionReview, Option<Username>)>>> + Send;
+}
+
+impl WorkspaceDetectionReviewRepository for PgConnection {
+    async fn record_detection_reviews(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+        reviews: Vec<NewWorkspaceDetectionReview>,
+    ) -> PgResult<Vec<WorkspaceDetectionReview>> {
+        use diesel::dsl::now;
+        use schema::workspace_detection_reviews::{self, dsl};
+        use schema::workspace_pipeline_runs::{self, dsl as runs_dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("record_detection_reviews");
+
+        // Reviews carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
+
+        let detection_ids: Vec<&str> = reviews
+            .iter()
+            .map(|review| review.detection_id.as_str())
+            .collect();
+
+        let recorded = self
+            .transaction(async |conn| {
+                // Fails with `NotFound` for a run outside the workspace.
+                workspace_pipeline_runs::table
+                    .filter(runs_dsl::id.eq(run_id))
+                    .filter(runs_dsl::pipeline_id.eq_any(scoped_pipelines))
+                    .select(runs_dsl::id)
+                    .first::<Uuid>(conn)
+                    .await?;
+
+                // Locks the current versions, so a concurrent decision on the
+                // same detection waits and then numbers after this one.
+                let current: Vec<(Uuid, String, i32)> = workspace_detection_reviews::table
+                    .filter(dsl::run_id.eq(run_id))
+                    .filter(dsl::detection_id.eq_any(&detection_ids))
+                    .filter(dsl::status.ne(ReviewStatus::Superseded))
+                    .select((dsl::id, dsl::detection_id, dsl::version))
+                    .for_update()
+                    .load(conn)
+                    .await?;
+
+                let superseded: Vec<Uuid> = current.iter().map(|(id, ..)| *id).collect();
+                diesel::update(
+                    workspace_detection_reviews::table.filter(dsl::id.eq_any(&superseded)),
+                )
+                .set((
+                    dsl::status.eq(ReviewStatus::Superseded),
+                    dsl::superseded_at.eq(now),
+                ))
+                .execute(conn)
+                .await?;
+
+                let versions: HashMap<String, i32> = current
+                    .into_iter()
+                    .map(|(_, detection_id, version)| (detection_id, version))
+                    .collect();
+                let reviews: Vec<NewWorkspaceDetectionReview> = reviews
+                    .into_iter()
+                    .map(|mut review| {
+                        review.run_id = run_id;
+                        review.version = versions.get(&review.detection_id).map_or(1, |v| v + 1);
+                        review.status = review.decision;
+                        review
+                    })
+                    .collect();
+
+                diesel::insert_into(workspace_detection_reviews::table)
+                    .values(&reviews)
+                    .returning(WorkspaceDetectionReview::as_returning())
+                    .get_results(conn)
+                    .await
+            })
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(recorded)
+    }
+
+    async fn list_current_detection_reviews(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+    ) -> PgResult<Vec<(WorkspaceDetectionReview, Option<Username>)>> {
+        use schema::workspace_detection_reviews::{self, dsl};
+        use schema::{accounts, workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_current_detection_reviews");
+
+        // Reviews carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
+
+        let reviews = workspace_detection_reviews::table
+            .left_join(accounts::table)
+            .filter(dsl::run_id.eq(run_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
+            .filter(dsl::status.ne(ReviewStatus::Superseded))
+            .order(dsl::detection_id.asc())
+            .select((
+                WorkspaceDetectionReview::as_select(),
+                accounts::username.nullable(),
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(reviews)
+    }
+
+    async fn list_current_detection_reviews_for_runs(
+        &mut self,
+        run_ids: &[Uuid],
+    ) -> PgResult<Vec<(WorkspaceDetectionReview, Option<Username>, Uuid)>> {
+        use schema::workspace_detection_reviews::{self, dsl};
+        use schema::{accounts, workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_current_detection_reviews_for_runs");
+
+        let reviews = workspace_detection_reviews::table
+            .inner_join(workspace_pipeline_runs::table.inner_join(workspace_pipelines::table))
+            .left_join(accounts::table)
+            .filter(dsl::run_id.eq_any(run_ids))
+            .filter(dsl::status.ne(ReviewStatus::Superseded))
+            .order(dsl::detection_id.asc())
+            .select((
+                WorkspaceDetectionReview::as_select(),
+                accounts::username.nullable(),
+                workspace_pipelines::workspace_id,
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(reviews)
+    }
+
+    async fn list_detection_review_history(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+        detection_id: &str,
+    ) -> PgResult<Vec<(WorkspaceDetectionReview, Option<Username>)>> {
+        use schema::workspace_detection_reviews::{self, dsl};
+        use schema::{accounts, workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_detection_review_history");
+
+        // Reviews carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
+
+        let reviews = workspace_detection_reviews::table
+            .left_join(accounts::table)
+            .filter(dsl::run_id.eq(run_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
+            .filter(dsl::detection_id.eq(detection_id))
+            .order(dsl::version.asc())
+            .select((
+                WorkspaceDetectionReview::as_select(),
+                accounts::username.nullable(),
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(reviews)
+    }
+}
@@ -8,7 +8,9 @@ use diesel_async::RunQueryDsl;
 use pgtrgm::expression_methods::TrgmExpressionMethods;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspaceFile, UpdateWorkspaceFile, WorkspaceFile};
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{
     CursorPage, CursorPagination, FileFilter, FileSortBy, FileSortField, OffsetPagination,
     SortOrder, Username,
@@ -27,8 +29,12 @@ pub trait WorkspaceFileRepository {
     ) -> impl Future<Output = PgResult<WorkspaceFile>> + Send;
 
     /// Finds a workspace file by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_file_by_id(
         &mut self,
+        admin: &AdminScope,
         file_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceFile>>> + Send;
 
@@ -37,7 +43,7 @@ pub trait WorkspaceFileRepository {
     /// Provides workspace-scoped access control at the database level.
     fn find_file_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         file_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceFile>>> + Send;
 
@@ -47,7 +53,7 @@ pub trait WorkspaceFileRepository {
     /// Provides workspace-scoped access control at the database level.
     fn find_file_in_workspace_with_creator(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         file_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceFile, Username)>>> + Send;
 
@@ -74,7 +80,7 @@ pub trait WorkspaceFileRepository {
     /// Returns the number of files deleted.
     fn delete_workspace_files(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         file_ids: &[Uuid],
     ) -> impl Future<Output = PgResult<usize>> + Send;
 
@@ -83,7 +89,7 @@ pub trait WorkspaceFileRepository {
     /// Supports filtering by file format and sorting by name, date, or size.
     fn offset_list_workspace_files(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: FileSortBy,
         filter: FileFilter,
@@ -93,7 +99,7 @@ pub trait WorkspaceFileRepository {
     /// filtering, each paired with the handle of the account that uploaded it.
     fn cursor_list_workspace_files(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         filter: FileFilter,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceFile, Username)>>> + Send;
@@ -116,13 +122,33 @@ pub trait WorkspaceFileRepository {
         file_ids: &[Uuid],
     ) -> impl Future<Output = PgResult<Vec<WorkspaceFile>>> + Send;
 
-    /// Lists all versions of a file (the file itself and all files that have it as parent).
+    /// Lists all versions of a file within a workspace (the file itself and
+    /// all files that have it as parent), each with the handle of the account
+    /// that uploaded it.
     ///
     /// Returns files ordered by version_number descending (newest first).
-    fn list_workspace_file_versions(
+    fn list_file_versions_in_workspace(
         &mut self,
+        scope: TenantScope,
         file_id: Uuid,
-    ) -> impl Future<Output = PgResult<Vec<WorkspaceFile>>> + Send;
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceFile, Username)>>> + Send;
+
+    /// Finds multiple workspace files by their IDs, each with the handle of
+    /// the account that uploaded it.
+    fn find_workspace_files_with_creators(
+        &mut self,
+        file_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceFile, Username)>>> + Send;
+
+    /// Lists the versions of several files at once, each with the handle of
+    /// the account that uploaded it.
+    ///
+    /// Returns every file whose ID or parent ID is among `file_ids`, ordered
+    /// by version_number descending; callers group them by file.
+    fn list_workspace_file_versions_with_creators(
+        &mut self,
+        file_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceFile, Username)>>> + Send;
 
     /// Finds the latest version of a file by traversing the version chain.
     ///
@@ -138,8 +164,39 @@ pub trait WorkspaceFileRepository {
         &mut self,
         file_id: Uuid,
     ) -> impl Future<Output = PgResult<i32>> + Send;
+
+    /// Lists up to `limit` distinct storage paths not in the current key
+    /// layout, with their workspace, across all workspaces.
+    ///
+    /// Soft-deleted files are included: their content is still stored.
+    fn list_legacy_storage_paths(
+        &mut self,
+        admin: &AdminScope,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<(Uuid, String)>>> + Send;
+
+    /// Counts the distinct storage paths not in the current key layout.
+    fn count_legacy_storage_paths(
+        &mut self,
+        admin: &AdminScope,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
+
+    /// Points every file stored at `old_path` in the workspace to `new_path`,
+    /// recording the version ID of the content stored there.
+    ///
+    /// Returns the number of files updated.
+    fn rewrite_storage_path(
+        &mut self,
+        scope: TenantScope,
+        old_path: &str,
+        new_path: &str,
+        storage_version: &str,
+    ) -> impl Future<Output = PgResult<usize>> + Send;
 }
 
+/// Matches storage paths in the current key layout (`file_` prefix).
+const CURRENT_STORAGE_PATH: &str = "file\\_%";
+
 impl WorkspaceFileRepository for PgConnection {
     async fn create_workspace_file(
         &mut self,
@@ -147,6 +204,8 @@ impl WorkspaceFileRepository for PgConnection {
     ) -> PgResult<WorkspaceFile> {
         use schema::workspace_files;
 
+        let _timer = QueryTimer::start("create_workspace_file");
+
         let file = diesel::insert_into(workspace_files::table)
             .values(&new_file)
             .returning(WorkspaceFile::as_returning())
@@ -159,10 +218,13 @@ impl WorkspaceFileRepository for PgConnection {
 
     async fn find_workspace_file_by_id(
         &mut self,
+        _admin: &AdminScope,
         file_id: Uuid,
     ) -> PgResult<Option<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_file_by_id");
+
         let file = workspace_files::table
             .filter(dsl::id.eq(file_id))
             .filter(dsl::deleted_at.is_null())
@@ -177,14 +239,16 @@ impl WorkspaceFileRepository for PgConnection {
 
     async fn find_file_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         file_id: Uuid,
     ) -> PgResult<Option<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("find_file_in_workspace");
+
         let file = workspace_files::table
             .filter(dsl::id.eq(file_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspaceFile::as_select())
             .first(self)
@@ -197,16 +261,18 @@ impl WorkspaceFileRepository for PgConnection {
 
     async fn find_file_in_workspace_with_creator(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         file_id: Uuid,
     ) -> PgResult<Option<(WorkspaceFile, Username)>> {
         use schema::workspace_files::dsl;
         use schema::{accounts, workspace_files};
 
+        let _timer = QueryTimer::start("find_file_in_workspace_with_creator");
+
         let file = workspace_files::table
             .inner_join(accounts::table)
             .filter(dsl::id.eq(file_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select((WorkspaceFile::as_select(), accounts::username))
             .first(self)
@@ -224,6 +290,8 @@ impl WorkspaceFileRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_account_files");
+
         let files = workspace_files::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::deleted_at.is_null())
@@ -245,6 +313,8 @@ impl WorkspaceFileRepository for PgConnection {
     ) -> PgResult<WorkspaceFile> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("update_workspace_file");
+
         let file = diesel::update(workspace_files::table.filter(dsl::id.eq(file_id)))
             .set(&updates)
             .returning(WorkspaceFile::as_returning())
@@ -259,6 +329,8 @@ impl WorkspaceFileRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_workspace_file");
+
         diesel::update(workspace_files::table.filter(dsl::id.eq(file_id)))
             .set(dsl::deleted_at.eq(now))
             .execute(self)
@@ -270,16 +342,18 @@ impl WorkspaceFileRepository for PgConnection {
 
     async fn delete_workspace_files(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         file_ids: &[Uuid],
     ) -> PgResult<usize> {
         use diesel::dsl::now;
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_workspace_files");
+
         let count = diesel::update(
             workspace_files::table
                 .filter(dsl::id.eq_any(file_ids))
-                .filter(dsl::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(dsl::workspace_id))
                 .filter(dsl::deleted_at.is_null()),
         )
         .set(dsl::deleted_at.eq(now))
@@ -292,16 +366,18 @@ impl WorkspaceFileRepository for PgConnection {
 
     async fn offset_list_workspace_files(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: FileSortBy,
         filter: FileFilter,
     ) -> PgResult<Vec<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_files");
+
         // Build base query
         let mut query = workspace_files::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -335,20 +411,22 @@ impl WorkspaceFileRepository for PgConnection {
 
     async fn cursor_list_workspace_files(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         filter: FileFilter,
     ) -> PgResult<CursorPage<(WorkspaceFile, Username)>> {
         use schema::workspace_files::dsl;
         use schema::{accounts, workspace_files};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_files");
+
         // Precompute filter values
         let search_term = filter.search_term().map(|s| s.to_string());
         let extensions: Vec<String> = filter.extensions().iter().map(|s| s.to_string()).collect();
 
         // Build base query with filters
         let mut base_query = workspace_files::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -377,7 +455,7 @@ impl WorkspaceFileRepository for PgConnection {
         // Rebuild query for fetching items (can't reuse boxed query after count)
         let mut query = workspace_files::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -433,6 +511,8 @@ impl WorkspaceFileRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_files_by_hash");
+
         let files = workspace_files::table
             .filter(dsl::file_hash_sha256.eq(file_hash))
             .filter(dsl::deleted_at.is_null())
@@ -447,6 +527,8 @@ impl WorkspaceFileRepository for PgConnection {
     async fn get_account_storage_usage(&mut self, account_id: Uuid) -> PgResult<BigDecimal> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("get_account_storage_usage");
+
         let usage: Option<BigDecimal> = workspace_files::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::deleted_at.is_null())
@@ -464,6 +546,8 @@ impl WorkspaceFileRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_files_by_ids");
+
         let files = workspace_files::table
             .filter(dsl::id.eq_any(file_ids))
             .filter(dsl::deleted_at.is_null())
@@ -475,19 +559,68 @@ impl WorkspaceFileRepository for PgConnection {
         Ok(files)
     }
 
-    async fn list_workspace_file_versions(
+    async fn list_file_versions_in_workspace(
         &mut self,
+        scope: TenantScope,
         file_id: Uuid,
-    ) -> PgResult<Vec<WorkspaceFile>> {
-        use schema::workspace_files::{self, dsl};
+    ) -> PgResult<Vec<(WorkspaceFile, Username)>> {
+        use schema::workspace_files::dsl;
+        use schema::{accounts, workspace_files};
+
+        let _timer = QueryTimer::start("list_file_versions_in_workspace");
 
         // Get the original file and all files that have it (or its descendants) as parent
         // This query gets the file itself plus all files where parent_id = file_id
         let files = workspace_files::table
+            .inner_join(accounts::table)
             .filter(dsl::id.eq(file_id).or(dsl::parent_id.eq(file_id)))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::version_number.desc())
-            .select(WorkspaceFile::as_select())
+            .select((WorkspaceFile::as_select(), accounts::username))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(files)
+    }
+
+    async fn find_workspace_files_with_creators(
+        &mut self,
+        file_ids: &[Uuid],
+    ) -> PgResult<Vec<(WorkspaceFile, Username)>> {
+        use schema::workspace_files::dsl;
+        use schema::{accounts, workspace_files};
+
+        let _timer = QueryTimer::start("find_workspace_files_with_creators");
+
+        let files = workspace_files::table
+            .inner_join(accounts::table)
+            .filter(dsl::id.eq_any(file_ids))
+            .filter(dsl::deleted_at.is_null())
+            .select((WorkspaceFile::as_select(), accounts::username))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(files)
+    }
+
+    async fn list_workspace_file_versions_with_creators(
+        &mut self,
+        file_ids: &[Uuid],
+    ) -> PgResult<Vec<(WorkspaceFile, Username)>> {
+        use schema::workspace_files::dsl;
+        use schema::{accounts, workspace_files};
+
+        let _timer = QueryTimer::start("list_workspace_file_versions_with_creators");
+
+        let files = workspace_files::table
+            .inner_join(accounts::table)
+            .filter(dsl::id.eq_any(file_ids).or(dsl::parent_id.eq_any(file_ids)))
+            .filter(dsl::deleted_at.is_null())
+            .order(dsl::version_number.desc())
+            .select((WorkspaceFile::as_select(), accounts::username))
             .load(self)
             .await
             .map_err(PgError::from)?;
@@ -501,6 +634,8 @@ impl WorkspaceFileRepository for PgConnection {
     ) -> PgResult<Option<WorkspaceFile>> {
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("find_latest_workspace_file_version");
+
         // Find the file with highest version_number that has file_id as parent,
         // or the file itself if no newer versions exist
         let latest = workspace_files::table
@@ -520,6 +655,8 @@ impl WorkspaceFileRepository for PgConnection {
         use diesel::dsl::max;
         use schema::workspace_files::{self, dsl};
 
+        let _timer = QueryTimer::start("get_next_workspace_file_version_number");
+
         // Get the max version_number from the file and its versions
         let max_version: Option<i32> = workspace_files::table
             .filter(dsl::id.eq(file_id).or(dsl::parent_id.eq(file_id)))
@@ -531,4 +668,69 @@ impl WorkspaceFileRepository for PgConnection {
 
         Ok(max_version.unwrap_or(0) + 1)
     }
+
+    async fn list_legacy_storage_paths(
+        &mut self,
+        _admin: &AdminScope,
+        limit: i64,
+    ) -> PgResult<Vec<(Uuid, String)>> {
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("list_legacy_storage_paths");
+
+        let paths = workspace_files::table
+            .filter(dsl::storage_path.not_like(CURRENT_STORAGE_PATH))
+            .select((dsl::workspace_id, dsl::storage_path))
+            .distinct()
+            .order((dsl::workspace_id, dsl::storage_path))
+            .limit(limit)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(paths)
+    }
+
+    async fn count_legacy_storage_paths(&mut self, _admin: &AdminScope) -> PgResult<i64> {
+        use diesel::dsl::count_distinct;
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("count_legacy_storage_paths");
+
+        let count = workspace_files::table
+            .filter(dsl::storage_path.not_like(CURRENT_STORAGE_PATH))
+            .select(count_distinct(dsl::storage_path))
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(count)
+    }
+
+    async fn rewrite_storage_path(
+        &mut self,
+        scope: TenantScope,
+        old_path: &str,
+        new_path: &str,
+        storage_version: &str,
+    ) -> PgResult<usize> {
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("rewrite_storage_path");
+
+        let updated = diesel::update(
+            workspace_files::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::storage_path.eq(old_path)),
+        )
+        .set((
+            dsl::storage_path.eq(new_path),
+            dsl::storage_version.eq(storage_version),
+        ))
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(updated)
+    }
 }
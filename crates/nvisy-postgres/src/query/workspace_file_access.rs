@@ -0,0 +1,178 @@
+//! Workspace file access statistics repository.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel::sql_types;
+use diesel_async::RunQueryDsl;
+use jiff::civil::Date;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceFileAccess, WorkspaceFileAccessStats, WorkspaceFileAccessSummary};
+use crate::query::TenantScope;
+use crate::types::SortOrder;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for document access statistics.
+///
+/// Accesses are folded into one row of counters per document and day as
+/// they are recorded, so no individual access is ever stored.
+pub trait WorkspaceFileAccessRepository {
+    /// Adds an access to its document's counters for the day.
+    fn record_file_access(
+        &mut self,
+        access: NewWorkspaceFileAccess,
+    ) -> impl Future<Output = PgResult<()>> + Send;
+
+    /// Lists a file's daily counters from `since` onwards, oldest first.
+    ///
+    /// Days without any access have no row.
+    fn list_file_access_stats(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+        since: Date,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceFileAccessStats>>> + Send;
+
+    /// Returns the last day a file was accessed on, if ever.
+    fn find_last_file_access_date(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<Date>>> + Send;
+
+    /// Ranks the workspace's files by their accesses from `since` onwards.
+    ///
+    /// Files never accessed in the period are included with zero counts, so
+    /// ascending order lists the least read content first. Ties go to the
+    /// oldest file. Deleted files are left out.
+    fn rank_file_access(
+        &mut self,
+        scope: TenantScope,
+        since: Date,
+        order: SortOrder,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceFileAccessSummary>>> + Send;
+}
+
+impl WorkspaceFileAccessRepository for PgConnection {
+    async fn record_file_access(&mut self, access: NewWorkspaceFileAccess) -> PgResult<()> {
+        use diesel::upsert::excluded;
+        use schema::workspace_file_access_stats::{self, dsl};
+
+        let _timer = QueryTimer::start("record_file_access");
+
+        diesel::insert_into(workspace_file_access_stats::table)
+            .values(&access)
+            .on_conflict((dsl::file_id, dsl::access_date))
+            .do_update()
+            .set((
+                dsl::view_count.eq(dsl::view_count + excluded(dsl::view_count)),
+                dsl::download_count.eq(dsl::download_count + excluded(dsl::download_count)),
+                dsl::search_open_count
+                    .eq(dsl::search_open_count + excluded(dsl::search_open_count)),
+                dsl::share_view_count.eq(dsl::share_view_count + excluded(dsl::share_view_count)),
+            ))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(())
+    }
+
+    async fn list_file_access_stats(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+        since: Date,
+    ) -> PgResult<Vec<WorkspaceFileAccessStats>> {
+        use schema::workspace_file_access_stats::{self, dsl};
+
+        let _timer = QueryTimer::start("list_file_access_stats");
+
+        let stats = workspace_file_access_stats::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::file_id.eq(file_id))
+            .filter(dsl::access_date.ge(jiff_diesel::Date::from(since)))
+            .select(WorkspaceFileAccessStats::as_select())
+            .order(dsl::access_date.asc())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(stats)
+    }
+
+    async fn find_last_file_access_date(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> PgResult<Option<Date>> {
+        use diesel::dsl::max;
+        use schema::workspace_file_access_stats::{self, dsl};
+
+        let _timer = QueryTimer::start("find_last_file_access_date");
+
+        let last: Option<jiff_diesel::Date> = workspace_file_access_stats::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::file_id.eq(file_id))
+            .select(max(dsl::access_date))
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(last.map(Into::into))
+    }
+
+    async fn rank_file_access(
+        &mut self,
+        scope: TenantScope,
+        since: Date,
+        order: SortOrder,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceFileAccessSummary>> {
+        let _timer = QueryTimer::start("rank_file_access");
+
+        let direction = match order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+
+        let summaries = diesel::sql_query(format!(
+            "SELECT file.id AS file_id,
+                    file.display_name,
+                    file.created_at,
+                    COALESCE(SUM(stats.view_count), 0)::BIGINT AS view_count,
+                    COALESCE(SUM(stats.download_count), 0)::BIGINT AS download_count,
+                    COALESCE(SUM(stats.search_open_count), 0)::BIGINT AS search_open_count,
+                    COALESCE(SUM(stats.share_view_count), 0)::BIGINT AS share_view_count,
+                    (
+                        SELECT max(last.access_date)
+                        FROM workspace_file_access_stats AS last
+                        WHERE last.file_id = file.id
+                    ) AS last_access_date
+             FROM workspace_files AS file
+             LEFT JOIN workspace_file_access_stats AS stats
+                 ON stats.file_id = file.id AND stats.access_date >= $2
+             WHERE file.workspace_id = $1
+               AND file.deleted_at IS NULL
+             GROUP BY file.id
+             ORDER BY COALESCE(
+                          SUM(stats.view_count + stats.download_count + stats.share_view_count),
+                          0
+                      ) {direction},
+                      file.created_at ASC,
+                      file.id ASC
+             LIMIT $3"
+        ))
+        .bind::<sql_types::Uuid, _>(scope.workspace_id())
+        .bind::<sql_types::Date, _>(jiff_diesel::Date::from(since))
+        .bind::<sql_types::BigInt, _>(limit)
+        .load(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(summaries)
+    }
+}
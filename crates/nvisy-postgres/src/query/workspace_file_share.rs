@@ -0,0 +1,214 @@
+//! Workspace file share link repository.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceFileShare, WorkspaceFileShare};
+use crate::query::TenantScope;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for share link database operations.
+///
+/// Links are resolved by the SHA-256 hash of their token before the caller
+/// knows which workspace they belong to. Holding the token grants access to
+/// its link, so that lookup returns the link's [`TenantScope`], like the
+/// invite token lookup; everything after it is tenant-scoped.
+pub trait WorkspaceFileShareRepository {
+    /// Creates a new share link.
+    fn create_workspace_file_share(
+        &mut self,
+        share: NewWorkspaceFileShare,
+    ) -> impl Future<Output = PgResult<WorkspaceFileShare>> + Send;
+
+    /// Finds a share link of the workspace.
+    fn find_workspace_file_share(
+        &mut self,
+        scope: TenantScope,
+        share_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceFileShare>>> + Send;
+
+    /// Finds the share link with the given token hash, whether or not it is
+    /// still usable, so a refused access can be attributed to its link.
+    ///
+    /// Also returns the [`TenantScope`] of the link's workspace.
+    fn find_workspace_file_share_by_hash(
+        &mut self,
+        token_hash: &[u8],
+    ) -> impl Future<Output = PgResult<Option<(WorkspaceFileShare, TenantScope)>>> + Send;
+
+    /// Lists a file's share links, newest first, including unusable ones.
+    fn list_workspace_file_shares(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceFileShare>>> + Send;
+
+    /// Counts one view of a share link.
+    ///
+    /// The view is counted only if the link is still usable, in the same
+    /// statement that checks it, so concurrent requests cannot exceed
+    /// `max_views`. Returns `None` if the link can no longer be opened.
+    fn record_workspace_file_share_view(
+        &mut self,
+        scope: TenantScope,
+        share_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceFileShare>>> + Send;
+
+    /// Revokes a share link, effective immediately.
+    ///
+    /// Returns `None` if the link does not exist or is already revoked.
+    fn revoke_workspace_file_share(
+        &mut self,
+        scope: TenantScope,
+        share_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceFileShare>>> + Send;
+}
+
+impl WorkspaceFileShareRepository for PgConnection {
+    async fn create_workspace_file_share(
+        &mut self,
+        share: NewWorkspaceFileShare,
+    ) -> PgResult<WorkspaceFileShare> {
+        use schema::workspace_file_shares;
+
+        let _timer = QueryTimer::start("create_workspace_file_share");
+
+        let share = diesel::insert_into(workspace_file_shares::table)
+            .values(&share)
+            .returning(WorkspaceFileShare::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(share)
+    }
+
+    async fn find_workspace_file_share(
+        &mut self,
+        scope: TenantScope,
+        share_id: Uuid,
+    ) -> PgResult<Option<WorkspaceFileShare>> {
+        use schema::workspace_file_shares::{self, dsl};
+
+        let _timer = QueryTimer::start("find_workspace_file_share");
+
+        let share = workspace_file_shares::table
+            .filter(dsl::id.eq(share_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .select(WorkspaceFileShare::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(share)
+    }
+
+    async fn find_workspace_file_share_by_hash(
+        &mut self,
+        token_hash: &[u8],
+    ) -> PgResult<Option<(WorkspaceFileShare, TenantScope)>> {
+        use schema::workspace_file_shares::{self, dsl};
+
+        let _timer = QueryTimer::start("find_workspace_file_share_by_hash");
+
+        let share = workspace_file_shares::table
+            .filter(dsl::token_hash.eq(token_hash))
+            .select(WorkspaceFileShare::as_select())
+            .first::<WorkspaceFileShare>(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(share.map(|share| {
+            let scope = TenantScope::new(share.workspace_id);
+            (share, scope)
+        }))
+    }
+
+    async fn list_workspace_file_shares(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> PgResult<Vec<WorkspaceFileShare>> {
+        use schema::workspace_file_shares::{self, dsl};
+
+        let _timer = QueryTimer::start("list_workspace_file_shares");
+
+        let shares = workspace_file_shares::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::file_id.eq(file_id))
+            .order((dsl::created_at.desc(), dsl::id.desc()))
+            .select(WorkspaceFileShare::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(shares)
+    }
+
+    async fn record_workspace_file_share_view(
+        &mut self,
+        scope: TenantScope,
+        share_id: Uuid,
+    ) -> PgResult<Option<WorkspaceFileShare>> {
+        use diesel::dsl::now;
+        use schema::workspace_file_shares::{self, dsl};
+
+        let _timer = QueryTimer::start("record_workspace_file_share_view");
+
+        let share = diesel::update(
+            workspace_file_shares::table
+                .filter(dsl::id.eq(share_id))
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::revoked_at.is_null())
+                .filter(dsl::expires_at.gt(now))
+                .filter(
+                    dsl::max_views
+                        .is_null()
+                        .or(dsl::view_count.lt(dsl::max_views.assume_not_null())),
+                ),
+        )
+        .set((
+            dsl::view_count.eq(dsl::view_count + 1),
+            dsl::last_viewed_at.eq(now),
+        ))
+        .returning(WorkspaceFileShare::as_returning())
+        .get_result(self)
+        .await
+        .optional()
+        .map_err(PgError::from)?;
+
+        Ok(share)
+    }
+
+    async fn revoke_workspace_file_share(
+        &mut self,
+        scope: TenantScope,
+        share_id: Uuid,
+    ) -> PgResult<Option<WorkspaceFileShare>> {
+        use diesel::dsl::now;
+        use schema::workspace_file_shares::{self, dsl};
+
+        let _timer = QueryTimer::start("revoke_workspace_file_share");
+
+        let share = diesel::update(
+            workspace_file_shares::table
+                .filter(dsl::id.eq(share_id))
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::revoked_at.is_null()),
+        )
+        .set(dsl::revoked_at.eq(now))
+        .returning(WorkspaceFileShare::as_returning())
+        .get_result(self)
+        .await
+        .optional()
+        .map_err(PgError::from)?;
+
+        Ok(share)
+    }
+}
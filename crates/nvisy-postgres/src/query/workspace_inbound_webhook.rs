@@ -0,0 +1,238 @@
+//! Workspace inbound webhook repository for managing inbound webhook operations.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceInboundWebhook, WorkspaceInboundWebhook};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::{Cursor, CursorPage, CursorPagination, Username};
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for workspace inbound webhook database operations.
+pub trait WorkspaceInboundWebhookRepository {
+    /// Creates a new workspace inbound webhook.
+    fn create_workspace_inbound_webhook(
+        &mut self,
+        new_inbound_webhook: NewWorkspaceInboundWebhook,
+    ) -> impl Future<Output = PgResult<WorkspaceInboundWebhook>> + Send;
+
+    /// Finds an inbound webhook by ID, excluding soft-deleted rows.
+    ///
+    /// Not scoped to a workspace: requests to an inbound webhook carry only
+    /// its ID, and the caller must verify them before acting on the row.
+    fn find_inbound_webhook_by_id(
+        &mut self,
+        admin: &AdminScope,
+        inbound_webhook_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceInboundWebhook>>> + Send;
+
+    /// Finds an inbound webhook by id within a workspace, with the handle of
+    /// the account that created it, excluding soft-deleted rows.
+    fn find_inbound_webhook_in_workspace_with_creator(
+        &mut self,
+        scope: TenantScope,
+        inbound_webhook_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<(WorkspaceInboundWebhook, Username)>>> + Send;
+
+    /// Lists the inbound webhooks of a workspace with cursor pagination, each
+    /// paired with the handle of the account that created it.
+    fn cursor_list_workspace_inbound_webhooks(
+        &mut self,
+        scope: TenantScope,
+        pagination: CursorPagination,
+    ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceInboundWebhook, Username)>>> + Send;
+
+    /// Soft deletes an inbound webhook within a workspace.
+    ///
+    /// Returns whether a row was deleted.
+    fn delete_workspace_inbound_webhook(
+        &mut self,
+        scope: TenantScope,
+        inbound_webhook_id: Uuid,
+    ) -> impl Future<Output = PgResult<bool>> + Send;
+
+    /// Records that an inbound webhook accepted a request.
+    fn record_inbound_webhook_received(
+        &mut self,
+        inbound_webhook_id: Uuid,
+    ) -> impl Future<Output = PgResult<()>> + Send;
+}
+
+impl WorkspaceInboundWebhookRepository for PgConnection {
+    async fn create_workspace_inbound_webhook(
+        &mut self,
+        new_inbound_webhook: NewWorkspaceInboundWebhook,
+    ) -> PgResult<WorkspaceInboundWebhook> {
+        use schema::workspace_inbound_webhooks;
+
+        let _timer = QueryTimer::start("create_workspace_inbound_webhook");
+
+        let inbound_webhook = diesel::insert_into(workspace_inbound_webhooks::table)
+            .values(&new_inbound_webhook)
+            .returning(WorkspaceInboundWebhook::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(inbound_webhook)
+    }
+
+    async fn find_inbound_webhook_by_id(
+        &mut self,
+        _admin: &AdminScope,
+        inbound_webhook_id: Uuid,
+    ) -> PgResult<Option<WorkspaceInboundWebhook>> {
+        use schema::workspace_inbound_webhooks::{self, dsl};
+
+        let _timer = QueryTimer::start("find_inbound_webhook_by_id");
+
+        let inbound_webhook = workspace_inbound_webhooks::table
+            .filter(dsl::id.eq(inbound_webhook_id))
+            .filter(dsl::deleted_at.is_null())
+            .select(WorkspaceInboundWebhook::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(inbound_webhook)
+    }
+
+    async fn find_inbound_webhook_in_workspace_with_creator(
+        &mut self,
+        scope: TenantScope,
+        inbound_webhook_id: Uuid,
+    ) -> PgResult<Option<(WorkspaceInboundWebhook, Username)>> {
+        use schema::workspace_inbound_webhooks::dsl;
+        use schema::{accounts, workspace_inbound_webhooks};
+
+        let _timer = QueryTimer::start("find_inbound_webhook_in_workspace_with_creator");
+
+        let inbound_webhook = workspace_inbound_webhooks::table
+            .inner_join(accounts::table)
+            .filter(dsl::id.eq(inbound_webhook_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::deleted_at.is_null())
+            .select((WorkspaceInboundWebhook::as_select(), accounts::username))
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(inbound_webhook)
+    }
+
+    async fn cursor_list_workspace_inbound_webhooks(
+        &mut self,
+        scope: TenantScope,
+        pagination: CursorPagination,
+    ) -> PgResult<CursorPage<(WorkspaceInboundWebhook, Username)>> {
+        use schema::workspace_inbound_webhooks::dsl;
+        use schema::{accounts, workspace_inbound_webhooks};
+
+        let _timer = QueryTimer::start("cursor_list_workspace_inbound_webhooks");
+
+        let total = if pagination.include_count {
+            Some(
+                workspace_inbound_webhooks::table
+                    .filter(scope.predicate(dsl::workspace_id))
+                    .filter(dsl::deleted_at.is_null())
+                    .count()
+                    .get_result(self)
+                    .await
+                    .map_err(PgError::from)?,
+            )
+        } else {
+            None
+        };
+
+        let mut query = workspace_inbound_webhooks::table
+            .inner_join(accounts::table)
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::deleted_at.is_null())
+            .into_boxed();
+
+        if let Some(cursor) = &pagination.after {
+            let cursor_ts = jiff_diesel::Timestamp::from(cursor.timestamp);
+            query = query.filter(
+                dsl::created_at
+                    .lt(cursor_ts)
+                    .or(dsl::created_at.eq(cursor_ts).and(dsl::id.lt(cursor.id))),
+            );
+        }
+
+        let fetch_limit = pagination.fetch_limit();
+        let mut items: Vec<(WorkspaceInboundWebhook, Username)> = query
+            .select((WorkspaceInboundWebhook::as_select(), accounts::username))
+            .order((dsl::created_at.desc(), dsl::id.desc()))
+            .limit(fetch_limit)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        let has_more = items.len() as i64 > pagination.limit;
+        if has_more {
+            items.pop();
+        }
+
+        let next_cursor = if has_more {
+            items.last().map(|(w, _)| {
+                Cursor {
+                    timestamp: w.created_at.into(),
+                    id: w.id,
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(CursorPage {
+            items,
+            total,
+            next_cursor,
+        })
+    }
+
+    async fn delete_workspace_inbound_webhook(
+        &mut self,
+        scope: TenantScope,
+        inbound_webhook_id: Uuid,
+    ) -> PgResult<bool> {
+        use diesel::dsl::now;
+        use schema::workspace_inbound_webhooks::{self, dsl};
+
+        let _timer = QueryTimer::start("delete_workspace_inbound_webhook");
+
+        let deleted = diesel::update(workspace_inbound_webhooks::table)
+            .filter(dsl::id.eq(inbound_webhook_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::deleted_at.is_null())
+            .set(dsl::deleted_at.eq(now))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(deleted > 0)
+    }
+
+    async fn record_inbound_webhook_received(&mut self, inbound_webhook_id: Uuid) -> PgResult<()> {
+        use diesel::dsl::now;
+        use schema::workspace_inbound_webhooks::{self, dsl};
+
+        let _timer = QueryTimer::start("record_inbound_webhook_received");
+
+        diesel::update(workspace_inbound_webhooks::table)
+            .filter(dsl::id.eq(inbound_webhook_id))
+            .set(dsl::last_received_at.eq(now))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(())
+    }
+}
@@ -7,7 +7,9 @@ use diesel_async::RunQueryDsl;
 use jiff::Timestamp;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspaceInvite, UpdateWorkspaceInvite, WorkspaceInvite};
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{
     CursorPage, CursorPagination, InviteFilter, InviteSortBy, InviteSortField, InviteStatus,
     OffsetPagination, SortOrder,
@@ -26,21 +28,28 @@ pub trait WorkspaceInviteRepository {
     ) -> impl Future<Output = PgResult<WorkspaceInvite>> + Send;
 
     /// Finds a workspace invitation by its unique token string.
+    ///
+    /// Holding the token grants access to the invite, so this also returns
+    /// the [`TenantScope`] of the invite's workspace.
     fn find_workspace_invite_by_token(
         &mut self,
         token: &str,
-    ) -> impl Future<Output = PgResult<Option<WorkspaceInvite>>> + Send;
+    ) -> impl Future<Output = PgResult<Option<(WorkspaceInvite, TenantScope)>>> + Send;
 
     /// Finds a workspace invitation by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_invite_by_id(
         &mut self,
+        admin: &AdminScope,
         invite_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceInvite>>> + Send;
 
     /// Finds an invitation by ID, scoped to its workspace.
     fn find_invite_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         invite_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceInvite>>> + Send;
 
@@ -77,7 +86,7 @@ pub trait WorkspaceInviteRepository {
     /// Supports filtering by role and sorting by email or date.
     fn offset_list_workspace_invites(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: InviteSortBy,
         filter: InviteFilter,
@@ -86,7 +95,7 @@ pub trait WorkspaceInviteRepository {
     /// Lists workspace invitations with cursor pagination.
     fn cursor_list_workspace_invites(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         sort_by: InviteSortBy,
         filter: InviteFilter,
@@ -114,7 +123,7 @@ pub trait WorkspaceInviteRepository {
     /// Finds a pending workspace invitation by workspace and email.
     fn find_pending_workspace_invite_by_email(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         email: &str,
     ) -> impl Future<Output = PgResult<Option<WorkspaceInvite>>> + Send;
 }
@@ -126,6 +135,8 @@ impl WorkspaceInviteRepository for PgConnection {
     ) -> PgResult<WorkspaceInvite> {
         use schema::workspace_invites;
 
+        let _timer = QueryTimer::start("create_workspace_invite");
+
         let invite = diesel::insert_into(workspace_invites::table)
             .values(&invite)
             .returning(WorkspaceInvite::as_returning())
@@ -139,26 +150,34 @@ impl WorkspaceInviteRepository for PgConnection {
     async fn find_workspace_invite_by_token(
         &mut self,
         token: &str,
-    ) -> PgResult<Option<WorkspaceInvite>> {
+    ) -> PgResult<Option<(WorkspaceInvite, TenantScope)>> {
         use schema::workspace_invites::dsl::*;
 
+        let _timer = QueryTimer::start("find_workspace_invite_by_token");
+
         let invite = workspace_invites
             .filter(invite_token.eq(token))
             .select(WorkspaceInvite::as_select())
-            .first(self)
+            .first::<WorkspaceInvite>(self)
             .await
             .optional()
             .map_err(PgError::from)?;
 
-        Ok(invite)
+        Ok(invite.map(|invite| {
+            let scope = TenantScope::new(invite.workspace_id);
+            (invite, scope)
+        }))
     }
 
     async fn find_workspace_invite_by_id(
         &mut self,
+        _admin: &AdminScope,
         invite_id: Uuid,
     ) -> PgResult<Option<WorkspaceInvite>> {
         use schema::workspace_invites::dsl::*;
 
+        let _timer = QueryTimer::start("find_workspace_invite_by_id");
+
         let invite = workspace_invites
             .filter(id.eq(invite_id))
             .select(WorkspaceInvite::as_select())
@@ -172,14 +191,16 @@ impl WorkspaceInviteRepository for PgConnection {
 
     async fn find_invite_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         invite_id: Uuid,
     ) -> PgResult<Option<WorkspaceInvite>> {
         use schema::workspace_invites::{self, dsl};
 
+        let _timer = QueryTimer::start("find_invite_in_workspace");
+
         let invite = workspace_invites::table
             .filter(dsl::id.eq(invite_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .select(WorkspaceInvite::as_select())
             .first(self)
             .await
@@ -196,6 +217,8 @@ impl WorkspaceInviteRepository for PgConnection {
     ) -> PgResult<WorkspaceInvite> {
         use schema::workspace_invites::dsl::*;
 
+        let _timer = QueryTimer::start("update_workspace_invite");
+
         let invite = diesel::update(workspace_invites)
             .filter(id.eq(invite_id))
             .set(&changes)
@@ -212,6 +235,8 @@ impl WorkspaceInviteRepository for PgConnection {
         invite_id: Uuid,
         _acceptor_id: Uuid,
     ) -> PgResult<WorkspaceInvite> {
+        let _timer = QueryTimer::start("accept_workspace_invite");
+
         let changes = UpdateWorkspaceInvite {
             invite_status: Some(InviteStatus::Accepted),
             responded_at: Some(Some(jiff_diesel::Timestamp::from(Timestamp::now()))),
@@ -226,6 +251,8 @@ impl WorkspaceInviteRepository for PgConnection {
         invite_id: Uuid,
         updated_by_id: Uuid,
     ) -> PgResult<WorkspaceInvite> {
+        let _timer = QueryTimer::start("reject_workspace_invite");
+
         let changes = UpdateWorkspaceInvite {
             invite_status: Some(InviteStatus::Declined),
             updated_by: Some(updated_by_id),
@@ -240,6 +267,8 @@ impl WorkspaceInviteRepository for PgConnection {
         invite_id: Uuid,
         updated_by_id: Uuid,
     ) -> PgResult<WorkspaceInvite> {
+        let _timer = QueryTimer::start("cancel_workspace_invite");
+
         let changes = UpdateWorkspaceInvite {
             invite_status: Some(InviteStatus::Canceled),
             updated_by: Some(updated_by_id),
@@ -251,15 +280,17 @@ impl WorkspaceInviteRepository for PgConnection {
 
     async fn offset_list_workspace_invites(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: InviteSortBy,
         filter: InviteFilter,
     ) -> PgResult<Vec<WorkspaceInvite>> {
         use schema::workspace_invites;
 
+        let _timer = QueryTimer::start("offset_list_workspace_invites");
+
         let mut query = workspace_invites::table
-            .filter(workspace_invites::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_invites::workspace_id))
             .filter(workspace_invites::invite_status.ne(InviteStatus::Canceled))
             .into_boxed();
 
@@ -297,7 +328,7 @@ impl WorkspaceInviteRepository for PgConnection {
 
     async fn cursor_list_workspace_invites(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         sort_by: InviteSortBy,
         filter: InviteFilter,
@@ -305,10 +336,12 @@ impl WorkspaceInviteRepository for PgConnection {
         use diesel::dsl::count_star;
         use schema::workspace_invites::{self, dsl};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_invites");
+
         let sort_by_email = matches!(sort_by.field, InviteSortField::Email);
 
-        let base_filter = dsl::workspace_id
-            .eq(workspace_id)
+        let base_filter = scope
+            .predicate(dsl::workspace_id)
             .and(dsl::invite_status.ne(InviteStatus::Canceled));
 
         // Build filtered query
@@ -381,6 +414,8 @@ impl WorkspaceInviteRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_invites::dsl::*;
 
+        let _timer = QueryTimer::start("cleanup_expired_workspace_invites");
+
         let updated_count = diesel::update(workspace_invites)
             .filter(expires_at.lt(now))
             .filter(invite_status.eq(InviteStatus::Pending))
@@ -399,6 +434,8 @@ impl WorkspaceInviteRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceInvite>> {
         use schema::workspace_invites::dsl::*;
 
+        let _timer = QueryTimer::start("find_workspace_invites_by_status");
+
         let invites = workspace_invites
             .filter(invite_status.eq(status))
             .select(WorkspaceInvite::as_select())
@@ -418,6 +455,8 @@ impl WorkspaceInviteRepository for PgConnection {
         updated_by_id: Uuid,
         _reason: Option<String>,
     ) -> PgResult<WorkspaceInvite> {
+        let _timer = QueryTimer::start("revoke_workspace_invite");
+
         let changes = UpdateWorkspaceInvite {
             invite_status: Some(InviteStatus::Revoked),
             updated_by: Some(updated_by_id),
@@ -429,14 +468,16 @@ impl WorkspaceInviteRepository for PgConnection {
 
     async fn find_pending_workspace_invite_by_email(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         email: &str,
     ) -> PgResult<Option<WorkspaceInvite>> {
         use diesel::dsl::now;
         use schema::workspace_invites::{self, dsl};
 
+        let _timer = QueryTimer::start("find_pending_workspace_invite_by_email");
+
         let invite = workspace_invites::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::invitee_email.eq(email))
             .filter(dsl::invite_status.eq(InviteStatus::Pending))
             .filter(dsl::expires_at.gt(now))
@@ -6,9 +6,11 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{
     Account, NewWorkspaceMember, UpdateWorkspaceMember, Workspace, WorkspaceMember,
 };
+use crate::query::TenantScope;
 use crate::types::{
     Cursor, CursorPage, CursorPagination, MemberFilter, MemberSortBy, MemberSortField,
     OffsetPagination, SortOrder, Username, WorkspaceRole,
@@ -29,14 +31,14 @@ pub trait WorkspaceMemberRepository {
     /// Finds a workspace member by workspace and account IDs.
     fn find_workspace_member(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceMember>>> + Send;
 
     /// Updates a workspace member with partial changes.
     fn update_workspace_member(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
         changes: UpdateWorkspaceMember,
     ) -> impl Future<Output = PgResult<WorkspaceMember>> + Send;
@@ -44,7 +46,7 @@ pub trait WorkspaceMemberRepository {
     /// Permanently removes a member from a workspace.
     fn remove_workspace_member(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
     ) -> impl Future<Output = PgResult<()>> + Send;
 
@@ -53,7 +55,7 @@ pub trait WorkspaceMemberRepository {
     /// Supports filtering by role and 2FA status, and sorting by name or date.
     fn offset_list_workspace_members(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: MemberSortBy,
         filter: MemberFilter,
@@ -62,7 +64,7 @@ pub trait WorkspaceMemberRepository {
     /// Lists members of a workspace with cursor pagination.
     fn cursor_list_workspace_members(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         filter: MemberFilter,
     ) -> impl Future<Output = PgResult<CursorPage<WorkspaceMember>>> + Send;
@@ -97,21 +99,21 @@ pub trait WorkspaceMemberRepository {
     /// Returns the role if the user is a member, None otherwise.
     fn check_account_role(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         account_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceRole>>> + Send;
 
     /// Finds all members with a specific role.
     fn find_members_by_role(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         role: WorkspaceRole,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceMember>>> + Send;
 
     /// Checks if a user has any access to a workspace.
     fn check_workspace_access(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         account_id: Uuid,
     ) -> impl Future<Output = PgResult<bool>> + Send;
 
@@ -120,18 +122,27 @@ pub trait WorkspaceMemberRepository {
     /// Returns members with their associated account information (email, display name).
     fn offset_list_workspace_members_with_accounts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: MemberSortBy,
         filter: MemberFilter,
     ) -> impl Future<Output = PgResult<Vec<(WorkspaceMember, Account)>>> + Send;
 
+    /// Lists every member of a workspace with account details, oldest first.
+    ///
+    /// Unpaginated; meant for callers that filter in memory, such as SCIM
+    /// provisioning.
+    fn list_workspace_members_with_accounts(
+        &mut self,
+        scope: TenantScope,
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceMember, Account)>>> + Send;
+
     /// Lists members of a workspace with account details using cursor pagination.
     ///
     /// Returns members with their associated account information (email, display name).
     fn cursor_list_workspace_members_with_accounts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         filter: MemberFilter,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceMember, Account)>>> + Send;
@@ -139,7 +150,7 @@ pub trait WorkspaceMemberRepository {
     /// Finds a workspace member with account details.
     fn find_workspace_member_with_account(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceMember, Account)>>> + Send;
 
@@ -148,7 +159,7 @@ pub trait WorkspaceMemberRepository {
     /// Performs a JOIN with accounts to match by email.
     fn find_workspace_member_by_email(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         email: &str,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceMember, Account)>>> + Send;
 
@@ -170,6 +181,8 @@ impl WorkspaceMemberRepository for PgConnection {
     ) -> PgResult<WorkspaceMember> {
         use schema::workspace_members;
 
+        let _timer = QueryTimer::start("add_workspace_member");
+
         let member = diesel::insert_into(workspace_members::table)
             .values(&member)
             .returning(WorkspaceMember::as_returning())
@@ -182,13 +195,15 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn find_workspace_member(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
     ) -> PgResult<Option<WorkspaceMember>> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_member");
+
         let member = workspace_members::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::account_id.eq(member_account_id))
             .select(WorkspaceMember::as_select())
             .first(self)
@@ -201,14 +216,16 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn update_workspace_member(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
         changes: UpdateWorkspaceMember,
     ) -> PgResult<WorkspaceMember> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("update_workspace_member");
+
         let member = diesel::update(workspace_members::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::account_id.eq(member_account_id))
             .set(&changes)
             .returning(WorkspaceMember::as_returning())
@@ -221,13 +238,15 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn remove_workspace_member(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
     ) -> PgResult<()> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("remove_workspace_member");
+
         diesel::delete(workspace_members::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::account_id.eq(member_account_id))
             .execute(self)
             .await
@@ -238,17 +257,19 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn offset_list_workspace_members(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: MemberSortBy,
         filter: MemberFilter,
     ) -> PgResult<Vec<WorkspaceMember>> {
         use schema::{accounts, workspace_members};
 
+        let _timer = QueryTimer::start("offset_list_workspace_members");
+
         // Build base query with JOIN for name sorting
         let mut query = workspace_members::table
             .inner_join(accounts::table.on(accounts::id.eq(workspace_members::account_id)))
-            .filter(workspace_members::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_members::workspace_id))
             .into_boxed();
 
         // Apply role filter
@@ -284,16 +305,18 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn cursor_list_workspace_members(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         filter: MemberFilter,
     ) -> PgResult<CursorPage<WorkspaceMember>> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_members");
+
         // Get total count only if requested
         let total = if pagination.include_count {
             let mut count_query = workspace_members::table
-                .filter(dsl::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(dsl::workspace_id))
                 .into_boxed();
 
             if let Some(role) = filter.role {
@@ -313,7 +336,7 @@ impl WorkspaceMemberRepository for PgConnection {
 
         // Build query with cursor
         let mut query = workspace_members::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .into_boxed();
 
         if let Some(role) = filter.role {
@@ -369,6 +392,8 @@ impl WorkspaceMemberRepository for PgConnection {
     ) -> PgResult<Vec<WorkspaceMember>> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("list_account_workspaces");
+
         let memberships = workspace_members::table
             .filter(dsl::account_id.eq(account_id))
             .select(WorkspaceMember::as_select())
@@ -389,6 +414,8 @@ impl WorkspaceMemberRepository for PgConnection {
     ) -> PgResult<Vec<(Workspace, WorkspaceMember)>> {
         use schema::{workspace_members, workspaces};
 
+        let _timer = QueryTimer::start("list_account_workspaces_with_details");
+
         let results = workspace_members::table
             .inner_join(workspaces::table.on(workspaces::id.eq(workspace_members::workspace_id)))
             .filter(workspace_members::account_id.eq(account_id))
@@ -412,6 +439,8 @@ impl WorkspaceMemberRepository for PgConnection {
         use diesel::dsl::count_star;
         use schema::{accounts, workspace_members, workspaces};
 
+        let _timer = QueryTimer::start("cursor_list_account_workspaces_with_details");
+
         // Build base filter
         let base_filter = workspace_members::account_id
             .eq(account_id)
@@ -480,13 +509,15 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn check_account_role(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         account_id: Uuid,
     ) -> PgResult<Option<WorkspaceRole>> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("check_account_role");
+
         let role = workspace_members::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::account_id.eq(account_id))
             .select(dsl::member_role)
             .first(self)
@@ -499,13 +530,15 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn find_members_by_role(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         role: WorkspaceRole,
     ) -> PgResult<Vec<WorkspaceMember>> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("find_members_by_role");
+
         let members = workspace_members::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::member_role.eq(role))
             .select(WorkspaceMember::as_select())
             .order(dsl::created_at.asc())
@@ -518,13 +551,15 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn check_workspace_access(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         account_id: Uuid,
     ) -> PgResult<bool> {
         use schema::workspace_members::{self, dsl};
 
+        let _timer = QueryTimer::start("check_workspace_access");
+
         let is_member = workspace_members::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::account_id.eq(account_id))
             .select(dsl::account_id)
             .first::<Uuid>(self)
@@ -538,16 +573,18 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn offset_list_workspace_members_with_accounts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
         sort_by: MemberSortBy,
         filter: MemberFilter,
     ) -> PgResult<Vec<(WorkspaceMember, Account)>> {
         use schema::{accounts, workspace_members};
 
+        let _timer = QueryTimer::start("offset_list_workspace_members_with_accounts");
+
         let mut query = workspace_members::table
             .inner_join(accounts::table.on(accounts::id.eq(workspace_members::account_id)))
-            .filter(workspace_members::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_members::workspace_id))
             .filter(accounts::deleted_at.is_null())
             .into_boxed();
 
@@ -577,18 +614,39 @@ impl WorkspaceMemberRepository for PgConnection {
         Ok(results)
     }
 
+    async fn list_workspace_members_with_accounts(
+        &mut self,
+        scope: TenantScope,
+    ) -> PgResult<Vec<(WorkspaceMember, Account)>> {
+        use schema::{accounts, workspace_members};
+
+        let _timer = QueryTimer::start("list_workspace_members_with_accounts");
+
+        workspace_members::table
+            .inner_join(accounts::table.on(accounts::id.eq(workspace_members::account_id)))
+            .filter(scope.predicate(workspace_members::workspace_id))
+            .filter(accounts::deleted_at.is_null())
+            .order(workspace_members::created_at.asc())
+            .select((WorkspaceMember::as_select(), Account::as_select()))
+            .load(self)
+            .await
+            .map_err(PgError::from)
+    }
+
     async fn cursor_list_workspace_members_with_accounts(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         filter: MemberFilter,
     ) -> PgResult<CursorPage<(WorkspaceMember, Account)>> {
         use diesel::dsl::count_star;
         use schema::{accounts, workspace_members};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_members_with_accounts");
+
         // Build base filter
-        let base_filter = workspace_members::workspace_id
-            .eq(workspace_id)
+        let base_filter = scope
+            .predicate(workspace_members::workspace_id)
             .and(accounts::deleted_at.is_null());
 
         // Get total count only if requested
@@ -663,14 +721,16 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn find_workspace_member_with_account(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         member_account_id: Uuid,
     ) -> PgResult<Option<(WorkspaceMember, Account)>> {
         use schema::{accounts, workspace_members};
 
+        let _timer = QueryTimer::start("find_workspace_member_with_account");
+
         let result = workspace_members::table
             .inner_join(accounts::table.on(accounts::id.eq(workspace_members::account_id)))
-            .filter(workspace_members::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_members::workspace_id))
             .filter(workspace_members::account_id.eq(member_account_id))
             .filter(accounts::deleted_at.is_null())
             .select((WorkspaceMember::as_select(), Account::as_select()))
@@ -684,14 +744,16 @@ impl WorkspaceMemberRepository for PgConnection {
 
     async fn find_workspace_member_by_email(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         email: &str,
     ) -> PgResult<Option<(WorkspaceMember, Account)>> {
         use schema::{accounts, workspace_members};
 
+        let _timer = QueryTimer::start("find_workspace_member_by_email");
+
         let result = workspace_members::table
             .inner_join(accounts::table.on(accounts::id.eq(workspace_members::account_id)))
-            .filter(workspace_members::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_members::workspace_id))
             .filter(accounts::email_address.eq(email))
             .filter(accounts::deleted_at.is_null())
             .select((WorkspaceMember::as_select(), Account::as_select()))
@@ -711,6 +773,8 @@ impl WorkspaceMemberRepository for PgConnection {
         use diesel::dsl::exists;
         use schema::workspace_members;
 
+        let _timer = QueryTimer::start("accounts_share_workspace");
+
         // Self-check: an account always "shares" with itself
         if account_id_a == account_id_b {
             return Ok(true);
@@ -0,0 +1,313 @@
+//! Workspace operations repository for tracking long-running jobs.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceOperation, UpdateWorkspaceOperation, WorkspaceOperation};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::{CursorPage, CursorPagination, OperationStatus};
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for workspace operation database operations.
+///
+/// Handles the lifecycle of long-running operations: creation when a job is
+/// accepted, progress and outcome updates, and lookups for polling clients.
+pub trait WorkspaceOperationRepository {
+    /// Creates a new pending operation.
+    fn create_workspace_operation(
+        &mut self,
+        new_operation: NewWorkspaceOperation,
+    ) -> impl Future<Output = PgResult<WorkspaceOperation>> + Send;
+
+    /// Finds an operation by ID regardless of workspace.
+    ///
+    /// Operations are addressed by id alone, so the workspace to authorize
+    /// against is only known after this lookup.
+    fn find_workspace_operation_by_id(
+        &mut self,
+        admin: &AdminScope,
+        operation_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceOperation>>> + Send;
+
+    /// Finds an operation by ID within a specific workspace.
+    fn find_workspace_operation(
+        &mut self,
+        scope: TenantScope,
+        operation_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceOperation>>> + Send;
+
+    /// Finds the newest operation still in progress that acts on a resource.
+    fn find_in_progress_workspace_operation_by_target(
+        &mut self,
+        scope: TenantScope,
+        target_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceOperation>>> + Send;
+
+    /// Lists a workspace's operations with cursor pagination, newest first.
+    fn cursor_list_workspace_operations(
+        &mut self,
+        scope: TenantScope,
+        pagination: CursorPagination,
+        status_filter: Option<OperationStatus>,
+    ) -> impl Future<Output = PgResult<CursorPage<WorkspaceOperation>>> + Send;
+
+    /// Updates an operation with new data.
+    fn update_workspace_operation(
+        &mut self,
+        operation_id: Uuid,
+        updates: UpdateWorkspaceOperation,
+    ) -> impl Future<Output = PgResult<WorkspaceOperation>> + Send;
+
+    /// Updates an operation that has not reached a terminal status yet.
+    ///
+    /// Returns `None` if it already has, so an operation finishes once: a job
+    /// completing after it was cancelled does not overwrite the cancellation.
+    fn update_in_progress_workspace_operation(
+        &mut self,
+        operation_id: Uuid,
+        updates: UpdateWorkspaceOperation,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceOperation>>> + Send;
+
+    /// Lists operations past their expiry across all workspaces, oldest
+    /// expiry first.
+    fn list_expired_workspace_operations(
+        &mut self,
+        admin: &AdminScope,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceOperation>>> + Send;
+
+    /// Deletes operations by ID across all workspaces.
+    ///
+    /// Returns the count of deleted operations.
+    fn delete_workspace_operations(
+        &mut self,
+        admin: &AdminScope,
+        operation_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+}
+
+impl WorkspaceOperationRepository for PgConnection {
+    async fn create_workspace_operation(
+        &mut self,
+        new_operation: NewWorkspaceOperation,
+    ) -> PgResult<WorkspaceOperation> {
+        use schema::workspace_operations;
+
+        let _timer = QueryTimer::start("create_workspace_operation");
+
+        let operation = diesel::insert_into(workspace_operations::table)
+            .values(&new_operation)
+            .returning(WorkspaceOperation::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(operation)
+    }
+
+    async fn find_workspace_operation_by_id(
+        &mut self,
+        _admin: &AdminScope,
+        operation_id: Uuid,
+    ) -> PgResult<Option<WorkspaceOperation>> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("find_workspace_operation_by_id");
+
+        let operation = workspace_operations::table
+            .filter(dsl::id.eq(operation_id))
+            .select(WorkspaceOperation::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(operation)
+    }
+
+    async fn find_workspace_operation(
+        &mut self,
+        scope: TenantScope,
+        operation_id: Uuid,
+    ) -> PgResult<Option<WorkspaceOperation>> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("find_workspace_operation");
+
+        let operation = workspace_operations::table
+            .filter(dsl::id.eq(operation_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .select(WorkspaceOperation::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(operation)
+    }
+
+    async fn find_in_progress_workspace_operation_by_target(
+        &mut self,
+        scope: TenantScope,
+        target_id: Uuid,
+    ) -> PgResult<Option<WorkspaceOperation>> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("find_in_progress_workspace_operation_by_target");
+
+        let operation = workspace_operations::table
+            .filter(dsl::target_id.eq(target_id))
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::status.eq_any([OperationStatus::Pending, OperationStatus::Running]))
+            .order(dsl::created_at.desc())
+            .select(WorkspaceOperation::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(operation)
+    }
+
+    async fn cursor_list_workspace_operations(
+        &mut self,
+        scope: TenantScope,
+        pagination: CursorPagination,
+        status_filter: Option<OperationStatus>,
+    ) -> PgResult<CursorPage<WorkspaceOperation>> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("cursor_list_workspace_operations");
+
+        let scoped = || {
+            let mut query = workspace_operations::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .into_boxed();
+            if let Some(status) = status_filter {
+                query = query.filter(dsl::status.eq(status));
+            }
+            query
+        };
+
+        let total = if pagination.include_count {
+            Some(
+                scoped()
+                    .count()
+                    .get_result::<i64>(self)
+                    .await
+                    .map_err(PgError::from)?,
+            )
+        } else {
+            None
+        };
+
+        let mut query = scoped();
+        if let Some(cursor) = &pagination.after {
+            let cursor_time = jiff_diesel::Timestamp::from(cursor.timestamp);
+            query = query.filter(
+                dsl::created_at
+                    .lt(cursor_time)
+                    .or(dsl::created_at.eq(cursor_time).and(dsl::id.lt(cursor.id))),
+            );
+        }
+
+        let items = query
+            .select(WorkspaceOperation::as_select())
+            .order((dsl::created_at.desc(), dsl::id.desc()))
+            .limit(pagination.limit + 1)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(CursorPage::new(
+            items,
+            total,
+            pagination.limit,
+            |operation: &WorkspaceOperation| (operation.created_at.into(), operation.id),
+        ))
+    }
+
+    async fn update_workspace_operation(
+        &mut self,
+        operation_id: Uuid,
+        updates: UpdateWorkspaceOperation,
+    ) -> PgResult<WorkspaceOperation> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("update_workspace_operation");
+
+        let operation =
+            diesel::update(workspace_operations::table.filter(dsl::id.eq(operation_id)))
+                .set(&updates)
+                .returning(WorkspaceOperation::as_returning())
+                .get_result(self)
+                .await
+                .map_err(PgError::from)?;
+
+        Ok(operation)
+    }
+
+    async fn update_in_progress_workspace_operation(
+        &mut self,
+        operation_id: Uuid,
+        updates: UpdateWorkspaceOperation,
+    ) -> PgResult<Option<WorkspaceOperation>> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("update_in_progress_workspace_operation");
+
+        let operation = diesel::update(workspace_operations::table)
+            .filter(dsl::id.eq(operation_id))
+            .filter(dsl::status.eq_any([OperationStatus::Pending, OperationStatus::Running]))
+            .set(&updates)
+            .returning(WorkspaceOperation::as_returning())
+            .get_result(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(operation)
+    }
+
+    async fn list_expired_workspace_operations(
+        &mut self,
+        _admin: &AdminScope,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceOperation>> {
+        use diesel::dsl::now;
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("list_expired_workspace_operations");
+
+        let operations = workspace_operations::table
+            .filter(dsl::expires_at.is_not_null())
+            .filter(dsl::expires_at.le(now))
+            .select(WorkspaceOperation::as_select())
+            .order(dsl::expires_at.asc())
+            .limit(limit)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(operations)
+    }
+
+    async fn delete_workspace_operations(
+        &mut self,
+        _admin: &AdminScope,
+        operation_ids: &[Uuid],
+    ) -> PgResult<usize> {
+        use schema::workspace_operations::{self, dsl};
+
+        let _timer = QueryTimer::start("delete_workspace_operations");
+
+        diesel::delete(workspace_operations::table.filter(dsl::id.eq_any(operation_ids)))
+            .execute(self)
+            .await
+            .map_err(PgError::from)
+    }
+}
@@ -7,7 +7,9 @@ use diesel_async::RunQueryDsl;
 use pgtrgm::expression_methods::TrgmExpressionMethods;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspacePipeline, UpdateWorkspacePipeline, WorkspacePipeline};
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{CursorPage, CursorPagination, OffsetPagination, PipelineStatus, Username};
 use crate::{PgConnection, PgError, PgResult, schema};
 
@@ -23,8 +25,12 @@ pub trait WorkspacePipelineRepository {
     ) -> impl Future<Output = PgResult<WorkspacePipeline>> + Send;
 
     /// Finds a pipeline by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_pipeline_by_id(
         &mut self,
+        admin: &AdminScope,
         pipeline_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePipeline>>> + Send;
 
@@ -33,7 +39,7 @@ pub trait WorkspacePipelineRepository {
     /// Provides workspace-scoped access control at the database level.
     fn find_pipeline_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePipeline>>> + Send;
 
@@ -43,14 +49,14 @@ pub trait WorkspacePipelineRepository {
     /// Excludes soft-deleted pipelines.
     fn find_pipeline_in_workspace_by_slug(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slug: &str,
     ) -> impl Future<Output = PgResult<Option<(WorkspacePipeline, Username)>>> + Send;
 
     /// Lists all pipelines in a workspace with offset pagination.
     fn offset_list_workspace_pipelines(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipeline>>> + Send;
 
@@ -58,7 +64,7 @@ pub trait WorkspacePipelineRepository {
     /// with the handle of the account that created it.
     fn cursor_list_workspace_pipelines(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         status_filter: Option<PipelineStatus>,
         search_term: Option<&str>,
@@ -74,7 +80,7 @@ pub trait WorkspacePipelineRepository {
     /// Lists enabled pipelines in a workspace.
     fn list_enabled_workspace_pipelines(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipeline>>> + Send;
 
     /// Updates a pipeline with new data.
@@ -93,14 +99,14 @@ pub trait WorkspacePipelineRepository {
     /// Counts pipelines in a workspace by status.
     fn count_workspace_pipelines_by_status(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         status: PipelineStatus,
     ) -> impl Future<Output = PgResult<i64>> + Send;
 
     /// Searches pipelines by name using trigram similarity.
     fn search_pipelines_by_name(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         search_term: &str,
         limit: i64,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipeline>>> + Send;
@@ -113,6 +119,8 @@ impl WorkspacePipelineRepository for PgConnection {
     ) -> PgResult<WorkspacePipeline> {
         use schema::workspace_pipelines;
 
+        let _timer = QueryTimer::start("create_workspace_pipeline");
+
         let pipeline = diesel::insert_into(workspace_pipelines::table)
             .values(&new_pipeline)
             .returning(WorkspacePipeline::as_returning())
@@ -125,10 +133,13 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn find_workspace_pipeline_by_id(
         &mut self,
+        _admin: &AdminScope,
         pipeline_id: Uuid,
     ) -> PgResult<Option<WorkspacePipeline>> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_pipeline_by_id");
+
         let pipeline = workspace_pipelines::table
             .filter(dsl::id.eq(pipeline_id))
             .filter(dsl::deleted_at.is_null())
@@ -143,14 +154,16 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn find_pipeline_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> PgResult<Option<WorkspacePipeline>> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("find_pipeline_in_workspace");
+
         let pipeline = workspace_pipelines::table
             .filter(dsl::id.eq(pipeline_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspacePipeline::as_select())
             .first(self)
@@ -163,15 +176,17 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn find_pipeline_in_workspace_by_slug(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slug: &str,
     ) -> PgResult<Option<(WorkspacePipeline, Username)>> {
         use schema::workspace_pipelines::dsl;
         use schema::{accounts, workspace_pipelines};
 
+        let _timer = QueryTimer::start("find_pipeline_in_workspace_by_slug");
+
         let pipeline = workspace_pipelines::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::slug.eq(slug))
             .filter(dsl::deleted_at.is_null())
             .select((WorkspacePipeline::as_select(), accounts::username))
@@ -185,13 +200,15 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn offset_list_workspace_pipelines(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspacePipeline>> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_pipelines");
+
         let pipelines = workspace_pipelines::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::created_at.desc())
             .limit(pagination.limit)
@@ -206,7 +223,7 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn cursor_list_workspace_pipelines(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         status_filter: Option<PipelineStatus>,
         search_term: Option<&str>,
@@ -214,9 +231,11 @@ impl WorkspacePipelineRepository for PgConnection {
         use schema::workspace_pipelines::dsl;
         use schema::{accounts, workspace_pipelines};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_pipelines");
+
         // Build base query with filters
         let mut base_query = workspace_pipelines::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -245,7 +264,7 @@ impl WorkspacePipelineRepository for PgConnection {
         // Rebuild query for fetching items
         let mut query = workspace_pipelines::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -299,6 +318,8 @@ impl WorkspacePipelineRepository for PgConnection {
     ) -> PgResult<Vec<WorkspacePipeline>> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_account_pipelines");
+
         let pipelines = workspace_pipelines::table
             .filter(dsl::account_id.eq(account_id))
             .filter(dsl::deleted_at.is_null())
@@ -315,12 +336,14 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn list_enabled_workspace_pipelines(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
     ) -> PgResult<Vec<WorkspacePipeline>> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("list_enabled_workspace_pipelines");
+
         let pipelines = workspace_pipelines::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::status.eq(PipelineStatus::Enabled))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::name.asc())
@@ -339,6 +362,8 @@ impl WorkspacePipelineRepository for PgConnection {
     ) -> PgResult<WorkspacePipeline> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("update_workspace_pipeline");
+
         let pipeline = diesel::update(workspace_pipelines::table.filter(dsl::id.eq(pipeline_id)))
             .set(&updates)
             .returning(WorkspacePipeline::as_returning())
@@ -353,6 +378,8 @@ impl WorkspacePipelineRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_workspace_pipeline");
+
         diesel::update(workspace_pipelines::table.filter(dsl::id.eq(pipeline_id)))
             .set(dsl::deleted_at.eq(now))
             .execute(self)
@@ -364,13 +391,15 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn count_workspace_pipelines_by_status(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         status: PipelineStatus,
     ) -> PgResult<i64> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("count_workspace_pipelines_by_status");
+
         let count = workspace_pipelines::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::status.eq(status))
             .filter(dsl::deleted_at.is_null())
             .count()
@@ -383,14 +412,16 @@ impl WorkspacePipelineRepository for PgConnection {
 
     async fn search_pipelines_by_name(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         search_term: &str,
         limit: i64,
     ) -> PgResult<Vec<WorkspacePipeline>> {
         use schema::workspace_pipelines::{self, dsl};
 
+        let _timer = QueryTimer::start("search_pipelines_by_name");
+
         let pipelines = workspace_pipelines::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::name.trgm_similar_to(search_term))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::name.asc())
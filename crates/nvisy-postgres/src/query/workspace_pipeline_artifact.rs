@@ -6,14 +6,18 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspacePipelineArtifact, WorkspacePipelineArtifact};
+use crate::query::TenantScope;
 use crate::types::ArtifactType;
 use crate::{PgConnection, PgError, PgResult, schema};
 
 /// Repository for workspace pipeline artifact database operations.
 ///
 /// Handles artifact lifecycle management including creation and queries
-/// for pipeline run inputs, outputs, and intermediate artifacts.
+/// for pipeline run inputs, outputs, and intermediate artifacts. Artifacts
+/// carry no workspace column, so every method past creation is scoped through
+/// the artifact's run and its pipeline.
 pub trait WorkspacePipelineArtifactRepository {
     /// Creates a new workspace pipeline artifact record.
     fn create_workspace_pipeline_artifact(
@@ -30,24 +34,28 @@ pub trait WorkspacePipelineArtifactRepository {
     /// Finds an artifact by its unique identifier.
     fn find_workspace_pipeline_artifact_by_id(
         &mut self,
+        scope: TenantScope,
         artifact_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePipelineArtifact>>> + Send;
 
     /// Finds an artifact by its file ID.
     fn find_workspace_pipeline_artifact_by_file_id(
         &mut self,
+        scope: TenantScope,
         file_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePipelineArtifact>>> + Send;
 
     /// Lists all artifacts for a pipeline run.
     fn list_workspace_pipeline_run_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineArtifact>>> + Send;
 
     /// Lists artifacts for a pipeline run filtered by type.
     fn list_workspace_pipeline_run_artifacts_by_type(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         artifact_type: ArtifactType,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineArtifact>>> + Send;
@@ -55,30 +63,35 @@ pub trait WorkspacePipelineArtifactRepository {
     /// Lists input artifacts for a pipeline run.
     fn list_workspace_pipeline_run_input_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineArtifact>>> + Send;
 
     /// Lists output artifacts for a pipeline run.
     fn list_workspace_pipeline_run_output_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineArtifact>>> + Send;
 
     /// Deletes all artifacts for a pipeline run.
     fn delete_workspace_pipeline_run_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<u64>> + Send;
 
     /// Counts artifacts for a pipeline run.
     fn count_workspace_pipeline_run_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<i64>> + Send;
 
     /// Lists all artifacts for a pipeline (across all runs).
     fn list_workspace_pipeline_artifacts(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineArtifact>>> + Send;
 }
@@ -90,6 +103,8 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
     ) -> PgResult<WorkspacePipelineArtifact> {
         use schema::workspace_pipeline_artifacts;
 
+        let _timer = QueryTimer::start("create_workspace_pipeline_artifact");
+
         let artifact = diesel::insert_into(workspace_pipeline_artifacts::table)
             .values(&new_artifact)
             .returning(WorkspacePipelineArtifact::as_returning())
@@ -106,6 +121,8 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
     ) -> PgResult<Vec<WorkspacePipelineArtifact>> {
         use schema::workspace_pipeline_artifacts;
 
+        let _timer = QueryTimer::start("create_workspace_pipeline_artifacts");
+
         let artifacts = diesel::insert_into(workspace_pipeline_artifacts::table)
             .values(&new_artifacts)
             .returning(WorkspacePipelineArtifact::as_returning())
@@ -118,12 +135,24 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
 
     async fn find_workspace_pipeline_artifact_by_id(
         &mut self,
+        scope: TenantScope,
         artifact_id: Uuid,
     ) -> PgResult<Option<WorkspacePipelineArtifact>> {
         use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::{workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("find_workspace_pipeline_artifact_by_id");
+
+        // Artifacts carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
 
         let artifact = workspace_pipeline_artifacts::table
             .filter(dsl::id.eq(artifact_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
             .select(WorkspacePipelineArtifact::as_select())
             .first(self)
             .await
@@ -135,12 +164,24 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
 
     async fn find_workspace_pipeline_artifact_by_file_id(
         &mut self,
+        scope: TenantScope,
         file_id: Uuid,
     ) -> PgResult<Option<WorkspacePipelineArtifact>> {
         use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::{workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("find_workspace_pipeline_artifact_by_file_id");
+
+        // Artifacts carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
 
         let artifact = workspace_pipeline_artifacts::table
             .filter(dsl::file_id.eq(file_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
             .select(WorkspacePipelineArtifact::as_select())
             .first(self)
             .await
@@ -152,12 +193,24 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
 
     async fn list_workspace_pipeline_run_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<Vec<WorkspacePipelineArtifact>> {
         use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::{workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_workspace_pipeline_run_artifacts");
+
+        // Artifacts carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
 
         let artifacts = workspace_pipeline_artifacts::table
             .filter(dsl::run_id.eq(run_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
             .order(dsl::created_at.asc())
             .select(WorkspacePipelineArtifact::as_select())
             .load(self)
@@ -169,13 +222,25 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
 
     async fn list_workspace_pipeline_run_artifacts_by_type(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         artifact_type: ArtifactType,
     ) -> PgResult<Vec<WorkspacePipelineArtifact>> {
         use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::{workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_workspace_pipeline_run_artifacts_by_type");
+
+        // Artifacts carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
 
         let artifacts = workspace_pipeline_artifacts::table
             .filter(dsl::run_id.eq(run_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
             .filter(dsl::artifact_type.eq(artifact_type))
             .order(dsl::created_at.asc())
             .select(WorkspacePipelineArtifact::as_select())
@@ -188,37 +253,73 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
 
     async fn list_workspace_pipeline_run_input_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<Vec<WorkspacePipelineArtifact>> {
-        self.list_workspace_pipeline_run_artifacts_by_type(run_id, ArtifactType::Input)
+        let _timer = QueryTimer::start("list_workspace_pipeline_run_input_artifacts");
+
+        self.list_workspace_pipeline_run_artifacts_by_type(scope, run_id, ArtifactType::Input)
             .await
     }
 
     async fn list_workspace_pipeline_run_output_artifacts(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<Vec<WorkspacePipelineArtifact>> {
-        self.list_workspace_pipeline_run_artifacts_by_type(run_id, ArtifactType::Output)
+        let _timer = QueryTimer::start("list_workspace_pipeline_run_output_artifacts");
+
+        self.list_workspace_pipeline_run_artifacts_by_type(scope, run_id, ArtifactType::Output)
             .await
     }
 
-    async fn delete_workspace_pipeline_run_artifacts(&mut self, run_id: Uuid) -> PgResult<u64> {
+    async fn delete_workspace_pipeline_run_artifacts(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+    ) -> PgResult<u64> {
         use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::{workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("delete_workspace_pipeline_run_artifacts");
+
+        // Artifacts carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
 
-        let deleted =
-            diesel::delete(workspace_pipeline_artifacts::table.filter(dsl::run_id.eq(run_id)))
-                .execute(self)
-                .await
-                .map_err(PgError::from)?;
+        let deleted = diesel::delete(workspace_pipeline_artifacts::table)
+            .filter(dsl::run_id.eq(run_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
 
         Ok(deleted as u64)
     }
 
-    async fn count_workspace_pipeline_run_artifacts(&mut self, run_id: Uuid) -> PgResult<i64> {
+    async fn count_workspace_pipeline_run_artifacts(
+        &mut self,
+        scope: TenantScope,
+        run_id: Uuid,
+    ) -> PgResult<i64> {
         use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::{workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("count_workspace_pipeline_run_artifacts");
+
+        // Artifacts carry no workspace column; scope them through their run's
+        // pipeline.
+        let scoped_runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipeline_runs::id);
 
         let count = workspace_pipeline_artifacts::table
             .filter(dsl::run_id.eq(run_id))
+            .filter(dsl::run_id.eq_any(scoped_runs))
             .count()
             .get_result(self)
             .await
@@ -229,13 +330,21 @@ impl WorkspacePipelineArtifactRepository for PgConnection {
 
     async fn list_workspace_pipeline_artifacts(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> PgResult<Vec<WorkspacePipelineArtifact>> {
-        use schema::{workspace_pipeline_artifacts, workspace_pipeline_runs};
+        use schema::{workspace_pipeline_artifacts, workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_workspace_pipeline_artifacts");
+
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         let artifacts = workspace_pipeline_artifacts::table
             .inner_join(workspace_pipeline_runs::table)
             .filter(workspace_pipeline_runs::pipeline_id.eq(pipeline_id))
+            .filter(workspace_pipeline_runs::pipeline_id.eq_any(scoped_pipelines))
             .select(WorkspacePipelineArtifact::as_select())
             .order(workspace_pipeline_artifacts::created_at.desc())
             .load(self)
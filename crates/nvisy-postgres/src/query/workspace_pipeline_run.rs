@@ -1,14 +1,18 @@
 //! Workspace pipeline runs repository for managing pipeline execution instances.
 
+use std::collections::HashMap;
 use std::future::Future;
 
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{
-    NewWorkspacePipelineRun, UpdateWorkspacePipelineRun, WorkspacePipeline, WorkspacePipelineRun,
+    NewWorkspacePipelineRun, UpdateWorkspacePipelineRun, WorkspacePipeline,
+    WorkspacePipelineArtifact, WorkspacePipelineRun,
 };
+use crate::query::TenantScope;
 use crate::types::{
     CursorPage, CursorPagination, OffsetPagination, PipelineRunStatus, Slug, Username,
 };
@@ -17,7 +21,8 @@ use crate::{PgConnection, PgError, PgResult, schema};
 /// Repository for workspace pipeline run database operations.
 ///
 /// Handles pipeline run lifecycle management including creation, status updates,
-/// completion tracking, and queries.
+/// completion tracking, and queries. Runs carry no workspace column, so every
+/// method past creation is scoped through the run's pipeline.
 pub trait WorkspacePipelineRunRepository {
     /// Creates a new workspace pipeline run record.
     fn create_workspace_pipeline_run(
@@ -33,7 +38,7 @@ pub trait WorkspacePipelineRunRepository {
     /// soft-deleted pipelines.
     fn find_workspace_run_by_id(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<
         Output = PgResult<Option<(WorkspacePipelineRun, WorkspacePipeline, Option<Username>)>>,
@@ -42,6 +47,7 @@ pub trait WorkspacePipelineRunRepository {
     /// Finds a run by its `(pipeline, idempotency key)` pair, for detect replay.
     fn find_pipeline_run_by_idempotency_key(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         idempotency_key: &str,
     ) -> impl Future<Output = PgResult<Option<WorkspacePipelineRun>>> + Send;
@@ -49,6 +55,7 @@ pub trait WorkspacePipelineRunRepository {
     /// Lists all runs for a specific pipeline with offset pagination.
     fn offset_list_workspace_pipeline_runs(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineRun>>> + Send;
@@ -57,6 +64,7 @@ pub trait WorkspacePipelineRunRepository {
     /// paired with the handle of the account that triggered it, if any.
     fn cursor_list_workspace_pipeline_runs(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         pagination: CursorPagination,
         status_filter: Option<PipelineRunStatus>,
@@ -72,7 +80,7 @@ pub trait WorkspacePipelineRunRepository {
     /// [`cursor_list_workspace_pipeline_runs`]: Self::cursor_list_workspace_pipeline_runs
     fn cursor_list_workspace_runs(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         status_filter: Option<PipelineRunStatus>,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspacePipelineRun, Slug, Option<Username>)>>> + Send;
@@ -80,12 +88,16 @@ pub trait WorkspacePipelineRunRepository {
     /// Lists active runs (queued or running) for a specific pipeline.
     fn list_active_workspace_pipeline_runs(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineRun>>> + Send;
 
     /// Updates a workspace pipeline run with new data.
+    ///
+    /// A cancelled run is final: updating one fails as not found.
     fn update_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         updates: UpdateWorkspacePipelineRun,
     ) -> impl Future<Output = PgResult<WorkspacePipelineRun>> + Send;
@@ -93,30 +105,37 @@ pub trait WorkspacePipelineRunRepository {
     /// Marks a run as started.
     fn start_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<WorkspacePipelineRun>> + Send;
 
     /// Marks a run as completed successfully.
     fn complete_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<WorkspacePipelineRun>> + Send;
 
     /// Marks a run as failed.
     fn fail_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> impl Future<Output = PgResult<WorkspacePipelineRun>> + Send;
 
-    /// Marks a run as cancelled.
+    /// Marks an active run (running or awaiting review) as cancelled.
+    ///
+    /// Returns `None` if the run does not exist or has already finished.
     fn cancel_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
-    ) -> impl Future<Output = PgResult<WorkspacePipelineRun>> + Send;
+    ) -> impl Future<Output = PgResult<Option<WorkspacePipelineRun>>> + Send;
 
     /// Counts runs for a pipeline by status.
     fn count_workspace_pipeline_runs_by_status(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         status: PipelineRunStatus,
     ) -> impl Future<Output = PgResult<i64>> + Send;
@@ -124,8 +143,38 @@ pub trait WorkspacePipelineRunRepository {
     /// Gets the most recent run for a pipeline.
     fn find_latest_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePipelineRun>>> + Send;
+
+    /// Gets the most recent run over a file that holds an analysis.
+    fn find_latest_analyzed_file_run(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspacePipelineRun>>> + Send;
+
+    /// Lists the runs over several files at once, newest first, each with its
+    /// owning pipeline and the triggering account's handle.
+    ///
+    /// Runs of soft-deleted pipelines are hidden. Callers group the runs by
+    /// file and check the pipeline's workspace.
+    fn list_workspace_file_runs_with_details(
+        &mut self,
+        file_ids: &[Uuid],
+    ) -> impl Future<
+        Output = PgResult<Vec<(WorkspacePipelineRun, WorkspacePipeline, Option<Username>)>>,
+    > + Send;
+
+    /// Lists the output sets produced for a file, most recent first.
+    ///
+    /// An output set is an analyzed run, addressed by its input digest,
+    /// together with the artifacts recorded for it.
+    fn list_file_output_sets(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> impl Future<Output = PgResult<Vec<(WorkspacePipelineRun, Vec<WorkspacePipelineArtifact>)>>> + Send;
 }
 
 impl WorkspacePipelineRunRepository for PgConnection {
@@ -135,6 +184,8 @@ impl WorkspacePipelineRunRepository for PgConnection {
     ) -> PgResult<WorkspacePipelineRun> {
         use schema::workspace_pipeline_runs;
 
+        let _timer = QueryTimer::start("create_workspace_pipeline_run");
+
         let run = diesel::insert_into(workspace_pipeline_runs::table)
             .values(&new_run)
             .returning(WorkspacePipelineRun::as_returning())
@@ -147,12 +198,14 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn find_workspace_run_by_id(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<Option<(WorkspacePipelineRun, WorkspacePipeline, Option<Username>)>> {
         use schema::workspace_pipeline_runs::dsl as runs;
         use schema::{accounts, workspace_pipeline_runs, workspace_pipelines};
 
+        let _timer = QueryTimer::start("find_workspace_run_by_id");
+
         // Runs carry no workspace column; scope through the owning pipeline so
         // the id resolves only within its workspace, and only while that
         // pipeline is live (a soft-deleted pipeline hides its runs). The
@@ -161,7 +214,7 @@ impl WorkspacePipelineRunRepository for PgConnection {
             .inner_join(workspace_pipelines::table)
             .left_join(accounts::table)
             .filter(runs::id.eq(run_id))
-            .filter(workspace_pipelines::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
             .filter(workspace_pipelines::deleted_at.is_null())
             .select((
                 WorkspacePipelineRun::as_select(),
@@ -178,13 +231,23 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn find_pipeline_run_by_idempotency_key(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         idempotency_key: &str,
     ) -> PgResult<Option<WorkspacePipelineRun>> {
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("find_pipeline_run_by_idempotency_key");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         let run = workspace_pipeline_runs::table
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .filter(dsl::idempotency_key.eq(idempotency_key))
             .select(WorkspacePipelineRun::as_select())
             .first(self)
@@ -197,13 +260,23 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn offset_list_workspace_pipeline_runs(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspacePipelineRun>> {
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("offset_list_workspace_pipeline_runs");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         let runs = workspace_pipeline_runs::table
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .order(dsl::started_at.desc())
             .limit(pagination.limit)
             .offset(pagination.offset)
@@ -217,16 +290,25 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn cursor_list_workspace_pipeline_runs(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         pagination: CursorPagination,
         status_filter: Option<PipelineRunStatus>,
     ) -> PgResult<CursorPage<(WorkspacePipelineRun, Option<Username>)>> {
         use schema::workspace_pipeline_runs::dsl;
-        use schema::{accounts, workspace_pipeline_runs};
+        use schema::{accounts, workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("cursor_list_workspace_pipeline_runs");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         // Build base query with filters
         let mut base_query = workspace_pipeline_runs::table
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines.clone()))
             .into_boxed();
 
         if let Some(status) = status_filter {
@@ -249,6 +331,7 @@ impl WorkspacePipelineRunRepository for PgConnection {
         let mut query = workspace_pipeline_runs::table
             .left_join(accounts::table)
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .into_boxed();
 
         if let Some(status) = status_filter {
@@ -299,7 +382,7 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn cursor_list_workspace_runs(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
         status_filter: Option<PipelineRunStatus>,
     ) -> PgResult<CursorPage<(WorkspacePipelineRun, Slug, Option<Username>)>> {
@@ -307,6 +390,8 @@ impl WorkspacePipelineRunRepository for PgConnection {
         use schema::workspace_pipeline_runs::dsl as runs;
         use schema::workspace_pipelines::dsl as pipelines;
 
+        let _timer = QueryTimer::start("cursor_list_workspace_runs");
+
         // Runs have no workspace column; scope them through the owning pipeline.
         // The owning pipeline's slug and the triggering account's handle are
         // selected alongside each run so the cross-pipeline response can name
@@ -315,7 +400,7 @@ impl WorkspacePipelineRunRepository for PgConnection {
             let mut query = runs::workspace_pipeline_runs
                 .inner_join(pipelines::workspace_pipelines)
                 .left_join(accounts::accounts)
-                .filter(pipelines::workspace_id.eq(workspace_id))
+                .filter(scope.predicate(pipelines::workspace_id))
                 .into_boxed();
             if let Some(status) = status_filter {
                 query = query.filter(runs::status.eq(status));
@@ -380,12 +465,22 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn list_active_workspace_pipeline_runs(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> PgResult<Vec<WorkspacePipelineRun>> {
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("list_active_workspace_pipeline_runs");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         let runs = workspace_pipeline_runs::table
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .filter(
                 dsl::status
                     .eq(PipelineRunStatus::Running)
@@ -402,12 +497,24 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn update_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
         updates: UpdateWorkspacePipelineRun,
     ) -> PgResult<WorkspacePipelineRun> {
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("update_workspace_pipeline_run");
 
-        let run = diesel::update(workspace_pipeline_runs::table.filter(dsl::id.eq(run_id)))
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
+
+        let run = diesel::update(workspace_pipeline_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
+            .filter(dsl::status.ne(PipelineRunStatus::Cancelled))
             .set(&updates)
             .returning(WorkspacePipelineRun::as_returning())
             .get_result(self)
@@ -419,12 +526,23 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn start_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<WorkspacePipelineRun> {
         use diesel::dsl::now;
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("start_workspace_pipeline_run");
 
-        let run = diesel::update(workspace_pipeline_runs::table.filter(dsl::id.eq(run_id)))
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
+
+        let run = diesel::update(workspace_pipeline_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .set((
                 dsl::status.eq(PipelineRunStatus::Running),
                 dsl::started_at.eq(now),
@@ -439,12 +557,23 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn complete_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<WorkspacePipelineRun> {
         use diesel::dsl::now;
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("complete_workspace_pipeline_run");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
-        let run = diesel::update(workspace_pipeline_runs::table.filter(dsl::id.eq(run_id)))
+        let run = diesel::update(workspace_pipeline_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .set((
                 dsl::status.eq(PipelineRunStatus::Completed),
                 dsl::completed_at.eq(now),
@@ -459,12 +588,23 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn fail_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
     ) -> PgResult<WorkspacePipelineRun> {
         use diesel::dsl::now;
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
 
-        let run = diesel::update(workspace_pipeline_runs::table.filter(dsl::id.eq(run_id)))
+        let _timer = QueryTimer::start("fail_workspace_pipeline_run");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
+
+        let run = diesel::update(workspace_pipeline_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .set((
                 dsl::status.eq(PipelineRunStatus::Failed),
                 dsl::completed_at.eq(now),
@@ -479,12 +619,24 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn cancel_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         run_id: Uuid,
-    ) -> PgResult<WorkspacePipelineRun> {
+    ) -> PgResult<Option<WorkspacePipelineRun>> {
         use diesel::dsl::now;
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("cancel_workspace_pipeline_run");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
-        let run = diesel::update(workspace_pipeline_runs::table.filter(dsl::id.eq(run_id)))
+        let run = diesel::update(workspace_pipeline_runs::table)
+            .filter(dsl::id.eq(run_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
+            .filter(dsl::status.eq_any([PipelineRunStatus::Running, PipelineRunStatus::Analyzed]))
             .set((
                 dsl::status.eq(PipelineRunStatus::Cancelled),
                 dsl::completed_at.eq(now),
@@ -492,6 +644,7 @@ impl WorkspacePipelineRunRepository for PgConnection {
             .returning(WorkspacePipelineRun::as_returning())
             .get_result(self)
             .await
+            .optional()
             .map_err(PgError::from)?;
 
         Ok(run)
@@ -499,13 +652,23 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn count_workspace_pipeline_runs_by_status(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
         status: PipelineRunStatus,
     ) -> PgResult<i64> {
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("count_workspace_pipeline_runs_by_status");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         let count = workspace_pipeline_runs::table
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
             .filter(dsl::status.eq(status))
             .count()
             .get_result(self)
@@ -517,12 +680,51 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
     async fn find_latest_workspace_pipeline_run(
         &mut self,
+        scope: TenantScope,
         pipeline_id: Uuid,
     ) -> PgResult<Option<WorkspacePipelineRun>> {
         use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("find_latest_workspace_pipeline_run");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
 
         let run = workspace_pipeline_runs::table
             .filter(dsl::pipeline_id.eq(pipeline_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
+            .order(dsl::started_at.desc())
+            .select(WorkspacePipelineRun::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(run)
+    }
+
+    async fn find_latest_analyzed_file_run(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> PgResult<Option<WorkspacePipelineRun>> {
+        use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("find_latest_analyzed_file_run");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
+
+        let run = workspace_pipeline_runs::table
+            .filter(dsl::file_id.eq(file_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
+            .filter(dsl::analyzed_document_key.is_not_null())
             .order(dsl::started_at.desc())
             .select(WorkspacePipelineRun::as_select())
             .first(self)
@@ -532,4 +734,80 @@ impl WorkspacePipelineRunRepository for PgConnection {
 
         Ok(run)
     }
+
+    async fn list_workspace_file_runs_with_details(
+        &mut self,
+        file_ids: &[Uuid],
+    ) -> PgResult<Vec<(WorkspacePipelineRun, WorkspacePipeline, Option<Username>)>> {
+        use schema::workspace_pipeline_runs::dsl;
+        use schema::{accounts, workspace_pipeline_runs, workspace_pipelines};
+
+        let _timer = QueryTimer::start("list_workspace_file_runs_with_details");
+
+        let runs = workspace_pipeline_runs::table
+            .inner_join(workspace_pipelines::table)
+            .left_join(accounts::table)
+            .filter(dsl::file_id.eq_any(file_ids))
+            .filter(workspace_pipelines::deleted_at.is_null())
+            .order(dsl::started_at.desc())
+            .select((
+                WorkspacePipelineRun::as_select(),
+                WorkspacePipeline::as_select(),
+                accounts::username.nullable(),
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(runs)
+    }
+
+    async fn list_file_output_sets(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> PgResult<Vec<(WorkspacePipelineRun, Vec<WorkspacePipelineArtifact>)>> {
+        use schema::workspace_pipeline_artifacts::{self, dsl as artifact_dsl};
+        use schema::workspace_pipeline_runs::{self, dsl};
+        use schema::workspace_pipelines;
+
+        let _timer = QueryTimer::start("list_file_output_sets");
+
+        // Runs carry no workspace column; scope them through their pipeline.
+        let scoped_pipelines = workspace_pipelines::table
+            .filter(scope.predicate(workspace_pipelines::workspace_id))
+            .select(workspace_pipelines::id);
+
+        let runs: Vec<WorkspacePipelineRun> = workspace_pipeline_runs::table
+            .filter(dsl::file_id.eq(file_id))
+            .filter(dsl::pipeline_id.eq_any(scoped_pipelines))
+            .filter(dsl::input_digest.is_not_null())
+            .order(dsl::started_at.desc())
+            .select(WorkspacePipelineRun::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        let run_ids: Vec<Uuid> = runs.iter().map(|run| run.id).collect();
+        let artifacts: Vec<WorkspacePipelineArtifact> = workspace_pipeline_artifacts::table
+            .filter(artifact_dsl::run_id.eq_any(&run_ids))
+            .order(artifact_dsl::created_at.asc())
+            .select(WorkspacePipelineArtifact::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        let mut by_run: HashMap<Uuid, Vec<WorkspacePipelineArtifact>> = HashMap::new();
+        for artifact in artifacts {
+            by_run.entry(artifact.run_id).or_default().push(artifact);
+        }
+
+        Ok(runs
+            .into_iter()
+            .map(|run| {
+                let artifacts = by_run.remove(&run.id).unwrap_or_default();
+                (run, artifacts)
+            })
+            .collect())
+    }
 }
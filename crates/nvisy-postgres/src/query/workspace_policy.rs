@@ -6,7 +6,9 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspacePolicy, UpdateWorkspacePolicy, WorkspacePolicy};
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{CursorPage, CursorPagination, OffsetPagination, Username};
 use crate::{PgConnection, PgError, PgResult, schema};
 
@@ -19,15 +21,19 @@ pub trait WorkspacePolicyRepository {
     ) -> impl Future<Output = PgResult<WorkspacePolicy>> + Send;
 
     /// Finds a policy by its unique identifier.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_policy_by_id(
         &mut self,
+        admin: &AdminScope,
         policy_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePolicy>>> + Send;
 
     /// Finds a policy by ID within a specific workspace.
     fn find_policy_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         policy_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspacePolicy>>> + Send;
 
@@ -35,14 +41,14 @@ pub trait WorkspacePolicyRepository {
     /// the account that created it.
     fn find_policy_in_workspace_by_slug(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slug: &str,
     ) -> impl Future<Output = PgResult<Option<(WorkspacePolicy, Username)>>> + Send;
 
     /// Lists all policies in a workspace with offset pagination.
     fn offset_list_workspace_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspacePolicy>>> + Send;
 
@@ -50,7 +56,7 @@ pub trait WorkspacePolicyRepository {
     /// with the handle of the account that created it.
     fn cursor_list_workspace_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspacePolicy, Username)>>> + Send;
 
@@ -70,7 +76,7 @@ pub trait WorkspacePolicyRepository {
     /// Counts policies in a workspace.
     fn count_workspace_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
     ) -> impl Future<Output = PgResult<i64>> + Send;
 }
 
@@ -81,6 +87,8 @@ impl WorkspacePolicyRepository for PgConnection {
     ) -> PgResult<WorkspacePolicy> {
         use schema::workspace_policies;
 
+        let _timer = QueryTimer::start("create_workspace_policy");
+
         let policy = diesel::insert_into(workspace_policies::table)
             .values(&new_policy)
             .returning(WorkspacePolicy::as_returning())
@@ -93,10 +101,13 @@ impl WorkspacePolicyRepository for PgConnection {
 
     async fn find_workspace_policy_by_id(
         &mut self,
+        _admin: &AdminScope,
         policy_id: Uuid,
     ) -> PgResult<Option<WorkspacePolicy>> {
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("find_workspace_policy_by_id");
+
         let policy = workspace_policies::table
             .filter(dsl::id.eq(policy_id))
             .filter(dsl::deleted_at.is_null())
@@ -111,14 +122,16 @@ impl WorkspacePolicyRepository for PgConnection {
 
     async fn find_policy_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         policy_id: Uuid,
     ) -> PgResult<Option<WorkspacePolicy>> {
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("find_policy_in_workspace");
+
         let policy = workspace_policies::table
             .filter(dsl::id.eq(policy_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspacePolicy::as_select())
             .first(self)
@@ -131,15 +144,17 @@ impl WorkspacePolicyRepository for PgConnection {
 
     async fn find_policy_in_workspace_by_slug(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         slug: &str,
     ) -> PgResult<Option<(WorkspacePolicy, Username)>> {
         use schema::workspace_policies::dsl;
         use schema::{accounts, workspace_policies};
 
+        let _timer = QueryTimer::start("find_policy_in_workspace_by_slug");
+
         let policy = workspace_policies::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::slug.eq(slug))
             .filter(dsl::deleted_at.is_null())
             .select((WorkspacePolicy::as_select(), accounts::username))
@@ -153,13 +168,15 @@ impl WorkspacePolicyRepository for PgConnection {
 
     async fn offset_list_workspace_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspacePolicy>> {
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_policies");
+
         let policies = workspace_policies::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .order(dsl::created_at.desc())
             .limit(pagination.limit)
@@ -174,16 +191,18 @@ impl WorkspacePolicyRepository for PgConnection {
 
     async fn cursor_list_workspace_policies(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
     ) -> PgResult<CursorPage<(WorkspacePolicy, Username)>> {
         use schema::workspace_policies::dsl;
         use schema::{accounts, workspace_policies};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_policies");
+
         let total = if pagination.include_count {
             Some(
                 workspace_policies::table
-                    .filter(dsl::workspace_id.eq(workspace_id))
+                    .filter(scope.predicate(dsl::workspace_id))
                     .filter(dsl::deleted_at.is_null())
                     .count()
                     .get_result::<i64>(self)
@@ -196,7 +215,7 @@ impl WorkspacePolicyRepository for PgConnection {
 
         let query = workspace_policies::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -242,6 +261,8 @@ impl WorkspacePolicyRepository for PgConnection {
     ) -> PgResult<WorkspacePolicy> {
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("update_workspace_policy");
+
         let policy = diesel::update(workspace_policies::table.filter(dsl::id.eq(policy_id)))
             .set(&updates)
             .returning(WorkspacePolicy::as_returning())
@@ -256,6 +277,8 @@ impl WorkspacePolicyRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("delete_workspace_policy");
+
         diesel::update(workspace_policies::table.filter(dsl::id.eq(policy_id)))
             .set(dsl::deleted_at.eq(now))
             .execute(self)
@@ -265,11 +288,13 @@ impl WorkspacePolicyRepository for PgConnection {
         Ok(())
     }
 
-    async fn count_workspace_policies(&mut self, workspace_id: Uuid) -> PgResult<i64> {
+    async fn count_workspace_policies(&mut self, scope: TenantScope) -> PgResult<i64> {
         use schema::workspace_policies::{self, dsl};
 
+        let _timer = QueryTimer::start("count_workspace_policies");
+
         let count = workspace_policies::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .count()
             .get_result(self)
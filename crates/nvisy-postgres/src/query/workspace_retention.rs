@@ -0,0 +1,673 @@
+//! Workspace retention repository for retention policies, legal holds and
+//! the expired data they govern.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use jiff::Timestamp;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{
+    NewWorkspaceLegalHold, NewWorkspaceRetentionPolicy, WorkspaceFile, WorkspaceLegalHold,
+    WorkspacePipeline, WorkspacePipelineRun, WorkspaceRetentionPolicy,
+};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::{ArtifactType, PipelineRunStatus, ReviewStatus};
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Run statuses that no longer change and may be purged.
+const FINISHED_RUN_STATUSES: [PipelineRunStatus; 3] = [
+    PipelineRunStatus::Completed,
+    PipelineRunStatus::Failed,
+    PipelineRunStatus::Cancelled,
+];
+
+/// Repository for workspace retention database operations.
+///
+/// Expiry queries never return data covered by an active legal hold: a
+/// workspace-wide hold matches nothing, and a file hold excludes the file
+/// and its runs. Expired artifacts additionally exclude runs with findings
+/// still awaiting review.
+pub trait WorkspaceRetentionRepository {
+    /// Finds a workspace's retention policy.
+    fn find_workspace_retention_policy(
+        &mut self,
+        scope: TenantScope,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceRetentionPolicy>>> + Send;
+
+    /// Sets a workspace's retention policy, replacing any existing one.
+    fn set_workspace_retention_policy(
+        &mut self,
+        policy: NewWorkspaceRetentionPolicy,
+    ) -> impl Future<Output = PgResult<WorkspaceRetentionPolicy>> + Send;
+
+    /// Lists the retention policies of every workspace that sets a period.
+    fn list_active_retention_policies(
+        &mut self,
+        admin: &AdminScope,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceRetentionPolicy>>> + Send;
+
+    /// Places a new legal hold.
+    fn create_workspace_legal_hold(
+        &mut self,
+        hold: NewWorkspaceLegalHold,
+    ) -> impl Future<Output = PgResult<WorkspaceLegalHold>> + Send;
+
+    /// Lists a workspace's legal holds, newest first, including released ones.
+    fn list_workspace_legal_holds(
+        &mut self,
+        scope: TenantScope,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceLegalHold>>> + Send;
+
+    /// Releases an active legal hold.
+    ///
+    /// Returns `None` if the hold does not exist or is already released.
+    fn release_workspace_legal_hold(
+        &mut self,
+        scope: TenantScope,
+        hold_id: Uuid,
+    ) -> impl Future<Output = PgResult<Option<WorkspaceLegalHold>>> + Send;
+
+    /// Returns whether an active legal hold covers a file, either directly
+    /// or through a workspace-wide hold.
+    fn is_file_under_legal_hold(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> impl Future<Output = PgResult<bool>> + Send;
+
+    /// Lists up to `limit` files uploaded before `cutoff`, oldest first.
+    ///
+    /// Soft-deleted files are included: their stored objects are only
+    /// removed here.
+    fn list_expired_workspace_files(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceFile>>> + Send;
+
+    /// Counts the files uploaded before `cutoff`.
+    fn count_expired_workspace_files(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
+
+    /// Lists up to `limit` finished runs started before `cutoff`, oldest first.
+    fn list_expired_pipeline_runs(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspacePipelineRun>>> + Send;
+
+    /// Counts the finished runs started before `cutoff`.
+    fn count_expired_pipeline_runs(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
+
+    /// Lists the analysis object keys of every run over the given files.
+    fn list_file_analysis_keys(
+        &mut self,
+        scope: TenantScope,
+        file_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<Vec<String>>> + Send;
+
+    /// Permanently deletes files, together with their runs and artifacts.
+    fn purge_workspace_files(
+        &mut self,
+        scope: TenantScope,
+        file_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+
+    /// Permanently deletes runs, together with their artifacts.
+    fn purge_pipeline_runs(
+        &mut self,
+        scope: TenantScope,
+        run_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+
+    /// Lists every pipeline that limits how long an artifact stage is kept,
+    /// including soft-deleted ones.
+    fn list_artifact_retention_pipelines(
+        &mut self,
+        admin: &AdminScope,
+    ) -> impl Future<Output = PgResult<Vec<WorkspacePipeline>>> + Send;
+
+    /// Lists a workspace's pipelines that limit how long an artifact stage
+    /// is kept, including soft-deleted ones.
+    fn list_workspace_artifact_retention_pipelines(
+        &mut self,
+        scope: TenantScope,
+    ) -> impl Future<Output = PgResult<Vec<WorkspacePipeline>>> + Send;
+
+    /// Lists up to `limit` files storing artifacts of one stage of a
+    /// pipeline's finished runs, created before `cutoff`, oldest first.
+    ///
+    /// Files that are also the subject of a run are left to file retention.
+    fn list_expired_pipeline_artifact_files(
+        &mut self,
+        scope: TenantScope,
+        pipeline_id: Uuid,
+        artifact_type: ArtifactType,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceFile>>> + Send;
+
+    /// Counts the files storing artifacts of one stage of a pipeline's
+    /// finished runs, created before `cutoff`.
+    fn count_expired_pipeline_artifact_files(
+        &mut self,
+        scope: TenantScope,
+        pipeline_id: Uuid,
+        artifact_type: ArtifactType,
+        cutoff: Timestamp,
+    ) -> impl Future<Output = PgResult<i64>> + Send;
+}
+
+/// Loads the file ids a workspace's active holds cover.
+///
+/// Returns `None` when a workspace-wide hold covers every file.
+async fn held_file_ids(conn: &mut PgConnection, scope: TenantScope) -> PgResult<Option<Vec<Uuid>>> {
+    use schema::workspace_legal_holds::{self, dsl};
+
+    let holds: Vec<Option<Uuid>> = workspace_legal_holds::table
+        .filter(scope.predicate(dsl::workspace_id))
+        .filter(dsl::released_at.is_null())
+        .select(dsl::file_id)
+        .load(conn)
+        .await
+        .map_err(PgError::from)?;
+
+    Ok(holds.into_iter().collect())
+}
+
+impl WorkspaceRetentionRepository for PgConnection {
+    async fn find_workspace_retention_policy(
+        &mut self,
+        scope: TenantScope,
+    ) -> PgResult<Option<WorkspaceRetentionPolicy>> {
+        use schema::workspace_retention_policies::{self, dsl};
+
+        let _timer = QueryTimer::start("find_workspace_retention_policy");
+
+        let policy = workspace_retention_policies::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .select(WorkspaceRetentionPolicy::as_select())
+            .first(self)
+            .await
+            .optional()
+            .map_err(PgError::from)?;
+
+        Ok(policy)
+    }
+
+    async fn set_workspace_retention_policy(
+        &mut self,
+        policy: NewWorkspaceRetentionPolicy,
+    ) -> PgResult<WorkspaceRetentionPolicy> {
+        use schema::workspace_retention_policies::{self, dsl};
+
+        let _timer = QueryTimer::start("set_workspace_retention_policy");
+
+        let policy = diesel::insert_into(workspace_retention_policies::table)
+            .values(&policy)
+            .on_conflict(dsl::workspace_id)
+            .do_update()
+            .set(&policy)
+            .returning(WorkspaceRetentionPolicy::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(policy)
+    }
+
+    async fn list_active_retention_policies(
+        &mut self,
+        _admin: &AdminScope,
+    ) -> PgResult<Vec<WorkspaceRetentionPolicy>> {
+        use schema::workspace_retention_policies::{self, dsl};
+
+        let _timer = QueryTimer::start("list_active_retention_policies");
+
+        let policies = workspace_retention_policies::table
+            .filter(
+                dsl::file_retention_days
+                    .is_not_null()
+                    .or(dsl::run_retention_days.is_not_null())
+                    .or(dsl::activity_retention_days.is_not_null()),
+            )
+            .order(dsl::workspace_id.asc())
+            .select(WorkspaceRetentionPolicy::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(policies)
+    }
+
+    async fn create_workspace_legal_hold(
+        &mut self,
+        hold: NewWorkspaceLegalHold,
+    ) -> PgResult<WorkspaceLegalHold> {
+        use schema::workspace_legal_holds;
+
+        let _timer = QueryTimer::start("create_workspace_legal_hold");
+
+        let hold = diesel::insert_into(workspace_legal_holds::table)
+            .values(&hold)
+            .returning(WorkspaceLegalHold::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(hold)
+    }
+
+    async fn list_workspace_legal_holds(
+        &mut self,
+        scope: TenantScope,
+    ) -> PgResult<Vec<WorkspaceLegalHold>> {
+        use schema::workspace_legal_holds::{self, dsl};
+
+        let _timer = QueryTimer::start("list_workspace_legal_holds");
+
+        let holds = workspace_legal_holds::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .order((dsl::created_at.desc(), dsl::id.desc()))
+            .select(WorkspaceLegalHold::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(holds)
+    }
+
+    async fn release_workspace_legal_hold(
+        &mut self,
+        scope: TenantScope,
+        hold_id: Uuid,
+    ) -> PgResult<Option<WorkspaceLegalHold>> {
+        use diesel::dsl::now;
+        use schema::workspace_legal_holds::{self, dsl};
+
+        let _timer = QueryTimer::start("release_workspace_legal_hold");
+
+        let hold = diesel::update(
+            workspace_legal_holds::table
+                .filter(dsl::id.eq(hold_id))
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::released_at.is_null()),
+        )
+        .set(dsl::released_at.eq(now))
+        .returning(WorkspaceLegalHold::as_returning())
+        .get_result(self)
+        .await
+        .optional()
+        .map_err(PgError::from)?;
+
+        Ok(hold)
+    }
+
+    async fn is_file_under_legal_hold(
+        &mut self,
+        scope: TenantScope,
+        file_id: Uuid,
+    ) -> PgResult<bool> {
+        use diesel::dsl::{exists, select};
+        use schema::workspace_legal_holds::{self, dsl};
+
+        let _timer = QueryTimer::start("is_file_under_legal_hold");
+
+        let held = select(exists(
+            workspace_legal_holds::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::released_at.is_null())
+                .filter(dsl::file_id.is_null().or(dsl::file_id.eq(file_id))),
+        ))
+        .get_result(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(held)
+    }
+
+    async fn list_expired_workspace_files(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceFile>> {
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("list_expired_workspace_files");
+
+        let Some(held) = held_file_ids(self, scope).await? else {
+            return Ok(Vec::new());
+        };
+
+        let files = workspace_files::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::created_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .filter(dsl::id.ne_all(held))
+            .order((dsl::created_at.asc(), dsl::id.asc()))
+            .limit(limit)
+            .select(WorkspaceFile::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(files)
+    }
+
+    async fn count_expired_workspace_files(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+    ) -> PgResult<i64> {
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("count_expired_workspace_files");
+
+        let Some(held) = held_file_ids(self, scope).await? else {
+            return Ok(0);
+        };
+
+        let count = workspace_files::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::created_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .filter(dsl::id.ne_all(held))
+            .count()
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(count)
+    }
+
+    async fn list_expired_pipeline_runs(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspacePipelineRun>> {
+        use schema::workspace_files::dsl as file_dsl;
+        use schema::workspace_pipeline_runs::{self, dsl};
+
+        let _timer = QueryTimer::start("list_expired_pipeline_runs");
+
+        let Some(held) = held_file_ids(self, scope).await? else {
+            return Ok(Vec::new());
+        };
+
+        let runs = workspace_pipeline_runs::table
+            .inner_join(schema::workspace_files::table)
+            .filter(scope.predicate(file_dsl::workspace_id))
+            .filter(dsl::status.eq_any(FINISHED_RUN_STATUSES))
+            .filter(dsl::started_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .filter(dsl::file_id.ne_all(held))
+            .order((dsl::started_at.asc(), dsl::id.asc()))
+            .limit(limit)
+            .select(WorkspacePipelineRun::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(runs)
+    }
+
+    async fn count_expired_pipeline_runs(
+        &mut self,
+        scope: TenantScope,
+        cutoff: Timestamp,
+    ) -> PgResult<i64> {
+        use schema::workspace_files::dsl as file_dsl;
+        use schema::workspace_pipeline_runs::{self, dsl};
+
+        let _timer = QueryTimer::start("count_expired_pipeline_runs");
+
+        let Some(held) = held_file_ids(self, scope).await? else {
+            return Ok(0);
+        };
+
+        let count = workspace_pipeline_runs::table
+            .inner_join(schema::workspace_files::table)
+            .filter(scope.predicate(file_dsl::workspace_id))
+            .filter(dsl::status.eq_any(FINISHED_RUN_STATUSES))
+            .filter(dsl::started_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .filter(dsl::file_id.ne_all(held))
+            .count()
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(count)
+    }
+
+    async fn list_file_analysis_keys(
+        &mut self,
+        scope: TenantScope,
+        file_ids: &[Uuid],
+    ) -> PgResult<Vec<String>> {
+        use schema::workspace_files::dsl as file_dsl;
+        use schema::workspace_pipeline_runs::{self, dsl};
+
+        let _timer = QueryTimer::start("list_file_analysis_keys");
+
+        let keys = workspace_pipeline_runs::table
+            .inner_join(schema::workspace_files::table)
+            .filter(scope.predicate(file_dsl::workspace_id))
+            .filter(dsl::file_id.eq_any(file_ids))
+            .filter(dsl::analyzed_document_key.is_not_null())
+            .select(dsl::analyzed_document_key.assume_not_null())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(keys)
+    }
+
+    async fn purge_workspace_files(
+        &mut self,
+        scope: TenantScope,
+        file_ids: &[Uuid],
+    ) -> PgResult<usize> {
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("purge_workspace_files");
+
+        // Runs and artifacts go with the file through their foreign keys;
+        // later versions keep their rows and lose the parent link.
+        let deleted = diesel::delete(
+            workspace_files::table
+                .filter(scope.predicate(dsl::workspace_id))
+                .filter(dsl::id.eq_any(file_ids)),
+        )
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(deleted)
+    }
+
+    async fn purge_pipeline_runs(
+        &mut self,
+        scope: TenantScope,
+        run_ids: &[Uuid],
+    ) -> PgResult<usize> {
+        use schema::workspace_files::dsl as file_dsl;
+        use schema::workspace_pipeline_runs::{self, dsl};
+
+        let _timer = QueryTimer::start("purge_pipeline_runs");
+
+        let scoped_files = schema::workspace_files::table
+            .filter(scope.predicate(file_dsl::workspace_id))
+            .select(file_dsl::id);
+
+        let deleted = diesel::delete(
+            workspace_pipeline_runs::table
+                .filter(dsl::id.eq_any(run_ids))
+                .filter(dsl::file_id.eq_any(scoped_files)),
+        )
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(deleted)
+    }
+
+    async fn list_artifact_retention_pipelines(
+        &mut self,
+        _admin: &AdminScope,
+    ) -> PgResult<Vec<WorkspacePipeline>> {
+        use schema::workspace_pipelines::{self, dsl};
+
+        let _timer = QueryTimer::start("list_artifact_retention_pipelines");
+
+        let pipelines = workspace_pipelines::table
+            .filter(
+                dsl::input_retention_days
+                    .is_not_null()
+                    .or(dsl::intermediate_retention_days.is_not_null())
+                    .or(dsl::output_retention_days.is_not_null()),
+            )
+            .order((dsl::workspace_id.asc(), dsl::id.asc()))
+            .select(WorkspacePipeline::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(pipelines)
+    }
+
+    async fn list_workspace_artifact_retention_pipelines(
+        &mut self,
+        scope: TenantScope,
+    ) -> PgResult<Vec<WorkspacePipeline>> {
+        use schema::workspace_pipelines::{self, dsl};
+
+        let _timer = QueryTimer::start("list_workspace_artifact_retention_pipelines");
+
+        let pipelines = workspace_pipelines::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(
+                dsl::input_retention_days
+                    .is_not_null()
+                    .or(dsl::intermediate_retention_days.is_not_null())
+                    .or(dsl::output_retention_days.is_not_null()),
+            )
+            .order(dsl::id.asc())
+            .select(WorkspacePipeline::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(pipelines)
+    }
+
+    async fn list_expired_pipeline_artifact_files(
+        &mut self,
+        scope: TenantScope,
+        pipeline_id: Uuid,
+        artifact_type: ArtifactType,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> PgResult<Vec<WorkspaceFile>> {
+        use schema::workspace_detection_reviews::dsl as review_dsl;
+        use schema::workspace_files::dsl as file_dsl;
+        use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::workspace_pipeline_runs::dsl as run_dsl;
+
+        let _timer = QueryTimer::start("list_expired_pipeline_artifact_files");
+
+        let Some(held) = held_file_ids(self, scope).await? else {
+            return Ok(Vec::new());
+        };
+
+        // Aliased: the outer query already joins the runs table.
+        let subject_runs = diesel::alias!(schema::workspace_pipeline_runs as subject_runs);
+        let run_subjects = subject_runs.select(subject_runs.field(run_dsl::file_id));
+        let runs_in_review = schema::workspace_detection_reviews::table
+            .filter(review_dsl::superseded_at.is_null())
+            .filter(review_dsl::status.eq(ReviewStatus::Proposed))
+            .select(review_dsl::run_id);
+
+        let files: Vec<WorkspaceFile> = workspace_pipeline_artifacts::table
+            .inner_join(schema::workspace_pipeline_runs::table)
+            .inner_join(schema::workspace_files::table)
+            .filter(scope.predicate(file_dsl::workspace_id))
+            .filter(run_dsl::pipeline_id.eq(pipeline_id))
+            .filter(run_dsl::status.eq_any(FINISHED_RUN_STATUSES))
+            .filter(dsl::artifact_type.eq(artifact_type))
+            .filter(dsl::created_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .filter(dsl::file_id.ne_all(&held))
+            .filter(run_dsl::file_id.ne_all(&held))
+            .filter(diesel::dsl::not(dsl::file_id.eq_any(run_subjects)))
+            .filter(diesel::dsl::not(dsl::run_id.eq_any(runs_in_review)))
+            .order((dsl::created_at.asc(), dsl::id.asc()))
+            .limit(limit)
+            .select(WorkspaceFile::as_select())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        // A file may store more than one artifact.
+        let mut seen = std::collections::HashSet::with_capacity(files.len());
+        Ok(files
+            .into_iter()
+            .filter(|file| seen.insert(file.id))
+            .collect())
+    }
+
+    async fn count_expired_pipeline_artifact_files(
+        &mut self,
+        scope: TenantScope,
+        pipeline_id: Uuid,
+        artifact_type: ArtifactType,
+        cutoff: Timestamp,
+    ) -> PgResult<i64> {
+        use schema::workspace_detection_reviews::dsl as review_dsl;
+        use schema::workspace_files::dsl as file_dsl;
+        use schema::workspace_pipeline_artifacts::{self, dsl};
+        use schema::workspace_pipeline_runs::dsl as run_dsl;
+
+        let _timer = QueryTimer::start("count_expired_pipeline_artifact_files");
+
+        let Some(held) = held_file_ids(self, scope).await? else {
+            return Ok(0);
+        };
+
+        // Aliased: the outer query already joins the runs table.
+        let subject_runs = diesel::alias!(schema::workspace_pipeline_runs as subject_runs);
+        let run_subjects = subject_runs.select(subject_runs.field(run_dsl::file_id));
+        let runs_in_review = schema::workspace_detection_reviews::table
+            .filter(review_dsl::superseded_at.is_null())
+            .filter(review_dsl::status.eq(ReviewStatus::Proposed))
+            .select(review_dsl::run_id);
+
+        let count = workspace_pipeline_artifacts::table
+            .inner_join(schema::workspace_pipeline_runs::table)
+            .inner_join(schema::workspace_files::table)
+            .filter(scope.predicate(file_dsl::workspace_id))
+            .filter(run_dsl::pipeline_id.eq(pipeline_id))
+            .filter(run_dsl::status.eq_any(FINISHED_RUN_STATUSES))
+            .filter(dsl::artifact_type.eq(artifact_type))
+            .filter(dsl::created_at.lt(jiff_diesel::Timestamp::from(cutoff)))
+            .filter(dsl::file_id.ne_all(&held))
+            .filter(run_dsl::file_id.ne_all(&held))
+            .filter(diesel::dsl::not(dsl::file_id.eq_any(run_subjects)))
+            .filter(diesel::dsl::not(dsl::run_id.eq_any(runs_in_review)))
+            .select(diesel::dsl::count_distinct(dsl::file_id))
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(count)
+    }
+}
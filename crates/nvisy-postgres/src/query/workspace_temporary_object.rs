@@ -0,0 +1,223 @@
+//! Workspace temporary object repository for object registrations and the
+//! references garbage collection reconciles stored objects against.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceTemporaryObject, WorkspaceTemporaryObject};
+use crate::query::{AdminScope, TenantScope};
+use crate::types::DataRegion;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for workspace temporary object database operations.
+pub trait WorkspaceTemporaryObjectRepository {
+    /// Registers an object about to be written.
+    fn create_temporary_object(
+        &mut self,
+        object: NewWorkspaceTemporaryObject,
+    ) -> impl Future<Output = PgResult<WorkspaceTemporaryObject>> + Send;
+
+    /// Releases an object's registration once a row refers to it.
+    ///
+    /// Returns whether the registration existed.
+    fn release_temporary_object(
+        &mut self,
+        scope: TenantScope,
+        object_id: Uuid,
+    ) -> impl Future<Output = PgResult<bool>> + Send;
+
+    /// Lists up to `limit` expired registrations across all workspaces,
+    /// oldest first, with the data region their objects are stored in.
+    fn list_expired_temporary_objects(
+        &mut self,
+        admin: &AdminScope,
+        limit: i64,
+    ) -> impl Future<Output = PgResult<Vec<(WorkspaceTemporaryObject, DataRegion)>>> + Send;
+
+    /// Deletes registrations whose objects are gone.
+    fn delete_temporary_objects(
+        &mut self,
+        admin: &AdminScope,
+        object_ids: &[Uuid],
+    ) -> impl Future<Output = PgResult<usize>> + Send;
+
+    /// Returns which of `keys` in `bucket` are registered and not yet
+    /// expired.
+    fn find_live_temporary_object_keys(
+        &mut self,
+        admin: &AdminScope,
+        bucket: &str,
+        keys: &[String],
+    ) -> impl Future<Output = PgResult<Vec<String>>> + Send;
+
+    /// Returns which of `paths` are the storage path of any file.
+    ///
+    /// Soft-deleted files are included: their content is still stored.
+    fn find_referenced_storage_paths(
+        &mut self,
+        admin: &AdminScope,
+        paths: &[String],
+    ) -> impl Future<Output = PgResult<Vec<String>>> + Send;
+
+    /// Returns which of `keys` are the analysis key of any pipeline run.
+    fn find_referenced_analysis_keys(
+        &mut self,
+        admin: &AdminScope,
+        keys: &[String],
+    ) -> impl Future<Output = PgResult<Vec<String>>> + Send;
+}
+
+impl WorkspaceTemporaryObjectRepository for PgConnection {
+    async fn create_temporary_object(
+        &mut self,
+        object: NewWorkspaceTemporaryObject,
+    ) -> PgResult<WorkspaceTemporaryObject> {
+        use schema::workspace_temporary_objects;
+
+        let _timer = QueryTimer::start("create_temporary_object");
+
+        let object = diesel::insert_into(workspace_temporary_objects::table)
+            .values(&object)
+            .returning(WorkspaceTemporaryObject::as_returning())
+            .get_result(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(object)
+    }
+
+    async fn release_temporary_object(
+        &mut self,
+        scope: TenantScope,
+        object_id: Uuid,
+    ) -> PgResult<bool> {
+        use schema::workspace_temporary_objects::{self, dsl};
+
+        let _timer = QueryTimer::start("release_temporary_object");
+
+        let deleted = diesel::delete(
+            workspace_temporary_objects::table
+                .filter(dsl::id.eq(object_id))
+                .filter(scope.predicate(dsl::workspace_id)),
+        )
+        .execute(self)
+        .await
+        .map_err(PgError::from)?;
+
+        Ok(deleted > 0)
+    }
+
+    async fn list_expired_temporary_objects(
+        &mut self,
+        _admin: &AdminScope,
+        limit: i64,
+    ) -> PgResult<Vec<(WorkspaceTemporaryObject, DataRegion)>> {
+        use diesel::dsl::now;
+        use schema::workspace_temporary_objects::{self, dsl};
+        use schema::workspaces;
+
+        let _timer = QueryTimer::start("list_expired_temporary_objects");
+
+        // Soft-deleted workspaces are included: their objects still need
+        // removing.
+        let objects = workspace_temporary_objects::table
+            .inner_join(workspaces::table)
+            .filter(dsl::expires_at.le(now))
+            .order(dsl::expires_at.asc())
+            .limit(limit)
+            .select((
+                WorkspaceTemporaryObject::as_select(),
+                workspaces::data_region,
+            ))
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(objects)
+    }
+
+    async fn delete_temporary_objects(
+        &mut self,
+        _admin: &AdminScope,
+        object_ids: &[Uuid],
+    ) -> PgResult<usize> {
+        use schema::workspace_temporary_objects::{self, dsl};
+
+        let _timer = QueryTimer::start("delete_temporary_objects");
+
+        let deleted = diesel::delete(workspace_temporary_objects::table)
+            .filter(dsl::id.eq_any(object_ids))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(deleted)
+    }
+
+    async fn find_live_temporary_object_keys(
+        &mut self,
+        _admin: &AdminScope,
+        bucket: &str,
+        keys: &[String],
+    ) -> PgResult<Vec<String>> {
+        use diesel::dsl::now;
+        use schema::workspace_temporary_objects::{self, dsl};
+
+        let _timer = QueryTimer::start("find_live_temporary_object_keys");
+
+        let live = workspace_temporary_objects::table
+            .filter(dsl::bucket.eq(bucket))
+            .filter(dsl::object_key.eq_any(keys))
+            .filter(dsl::expires_at.gt(now))
+            .select(dsl::object_key)
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(live)
+    }
+
+    async fn find_referenced_storage_paths(
+        &mut self,
+        _admin: &AdminScope,
+        paths: &[String],
+    ) -> PgResult<Vec<String>> {
+        use schema::workspace_files::{self, dsl};
+
+        let _timer = QueryTimer::start("find_referenced_storage_paths");
+
+        let referenced = workspace_files::table
+            .filter(dsl::storage_path.eq_any(paths))
+            .select(dsl::storage_path)
+            .distinct()
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(referenced)
+    }
+
+    async fn find_referenced_analysis_keys(
+        &mut self,
+        _admin: &AdminScope,
+        keys: &[String],
+    ) -> PgResult<Vec<String>> {
+        use schema::workspace_pipeline_runs::{self, dsl};
+
+        let _timer = QueryTimer::start("find_referenced_analysis_keys");
+
+        let referenced = workspace_pipeline_runs::table
+            .filter(dsl::analyzed_document_key.eq_any(keys))
+            .select(dsl::analyzed_document_key.assume_not_null())
+            .distinct()
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(referenced)
+    }
+}
@@ -6,7 +6,9 @@ use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
+use crate::client::QueryTimer;
 use crate::model::{NewWorkspaceWebhook, UpdateWorkspaceWebhook, WorkspaceWebhook};
+use crate::query::{AdminScope, TenantScope};
 use crate::types::{
     Cursor, CursorPage, CursorPagination, OffsetPagination, Username, WebhookEvent, WebhookStatus,
 };
@@ -23,15 +25,19 @@ pub trait WorkspaceWebhookRepository {
     ) -> impl Future<Output = PgResult<WorkspaceWebhook>> + Send;
 
     /// Finds a workspace webhook by ID.
+    ///
+    /// Not scoped to a workspace: the caller must authorize access to the
+    /// returned row's workspace itself.
     fn find_workspace_webhook_by_id(
         &mut self,
+        admin: &AdminScope,
         webhook_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceWebhook>>> + Send;
 
     /// Finds a webhook by ID, scoped to its workspace.
     fn find_webhook_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         webhook_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<WorkspaceWebhook>>> + Send;
 
@@ -39,14 +45,14 @@ pub trait WorkspaceWebhookRepository {
     /// that created it, excluding soft-deleted rows.
     fn find_webhook_in_workspace_with_creator(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         webhook_id: Uuid,
     ) -> impl Future<Output = PgResult<Option<(WorkspaceWebhook, Username)>>> + Send;
 
     /// Lists all webhooks for a workspace with offset pagination.
     fn offset_list_workspace_webhooks(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceWebhook>>> + Send;
 
@@ -54,7 +60,7 @@ pub trait WorkspaceWebhookRepository {
     /// with the handle of the account that created it.
     fn cursor_list_workspace_webhooks(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
     ) -> impl Future<Output = PgResult<CursorPage<(WorkspaceWebhook, Username)>>> + Send;
 
@@ -110,7 +116,7 @@ pub trait WorkspaceWebhookRepository {
     /// - The webhook is not deleted
     fn find_webhooks_for_event(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         event: WebhookEvent,
     ) -> impl Future<Output = PgResult<Vec<WorkspaceWebhook>>> + Send;
 }
@@ -122,6 +128,8 @@ impl WorkspaceWebhookRepository for PgConnection {
     ) -> PgResult<WorkspaceWebhook> {
         use schema::workspace_webhooks;
 
+        let _timer = QueryTimer::start("create_workspace_webhook");
+
         let webhook = diesel::insert_into(workspace_webhooks::table)
             .values(&new_webhook)
             .returning(WorkspaceWebhook::as_returning())
@@ -134,10 +142,13 @@ impl WorkspaceWebhookRepository for PgConnection {
 
     async fn find_workspace_webhook_by_id(
         &mut self,
+        _admin: &AdminScope,
         webhook_id: Uuid,
     ) -> PgResult<Option<WorkspaceWebhook>> {
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("find_workspace_webhook_by_id");
+
         let webhook = workspace_webhooks
             .filter(id.eq(webhook_id))
             .filter(deleted_at.is_null())
@@ -152,14 +163,16 @@ impl WorkspaceWebhookRepository for PgConnection {
 
     async fn find_webhook_in_workspace(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         webhook_id: Uuid,
     ) -> PgResult<Option<WorkspaceWebhook>> {
         use schema::workspace_webhooks::{self, dsl};
 
+        let _timer = QueryTimer::start("find_webhook_in_workspace");
+
         let webhook = workspace_webhooks::table
             .filter(dsl::id.eq(webhook_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspaceWebhook::as_select())
             .first(self)
@@ -172,16 +185,18 @@ impl WorkspaceWebhookRepository for PgConnection {
 
     async fn find_webhook_in_workspace_with_creator(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         webhook_id: Uuid,
     ) -> PgResult<Option<(WorkspaceWebhook, Username)>> {
         use schema::workspace_webhooks::dsl;
         use schema::{accounts, workspace_webhooks};
 
+        let _timer = QueryTimer::start("find_webhook_in_workspace_with_creator");
+
         let webhook = workspace_webhooks::table
             .inner_join(accounts::table)
             .filter(dsl::id.eq(webhook_id))
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select((WorkspaceWebhook::as_select(), accounts::username))
             .first(self)
@@ -194,13 +209,15 @@ impl WorkspaceWebhookRepository for PgConnection {
 
     async fn offset_list_workspace_webhooks(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: OffsetPagination,
     ) -> PgResult<Vec<WorkspaceWebhook>> {
         use schema::workspace_webhooks::{self, dsl};
 
+        let _timer = QueryTimer::start("offset_list_workspace_webhooks");
+
         let webhooks = workspace_webhooks::table
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .select(WorkspaceWebhook::as_select())
             .order(dsl::created_at.desc())
@@ -215,17 +232,19 @@ impl WorkspaceWebhookRepository for PgConnection {
 
     async fn cursor_list_workspace_webhooks(
         &mut self,
-        workspace_id: Uuid,
+        scope: TenantScope,
         pagination: CursorPagination,
     ) -> PgResult<CursorPage<(WorkspaceWebhook, Username)>> {
         use schema::workspace_webhooks::dsl;
         use schema::{accounts, workspace_webhooks};
 
+        let _timer = QueryTimer::start("cursor_list_workspace_webhooks");
+
         // Get total count only if requested
         let total = if pagination.include_count {
             Some(
                 workspace_webhooks::table
-                    .filter(dsl::workspace_id.eq(workspace_id))
+                    .filter(scope.predicate(dsl::workspace_id))
                     .filter(dsl::deleted_at.is_null())
                     .count()
                     .get_result(self)
@@ -239,7 +258,7 @@ impl WorkspaceWebhookRepository for PgConnection {
         // Build query with cursor
         let mut query = workspace_webhooks::table
             .inner_join(accounts::table)
-            .filter(dsl::workspace_id.eq(workspace_id))
+            .filter(scope.predicate(dsl::workspace_id))
             .filter(dsl::deleted_at.is_null())
             .into_boxed();
 
@@ -292,6 +311,8 @@ impl WorkspaceWebhookRepository for PgConnection {
     ) -> PgResult<WorkspaceWebhook> {
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("update_workspace_webhook");
+
         let webhook = diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(&changes)
@@ -307,6 +328,8 @@ impl WorkspaceWebhookRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("delete_workspace_webhook");
+
         diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(deleted_at.eq(now))
@@ -321,6 +344,8 @@ impl WorkspaceWebhookRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("record_webhook_success");
+
         let webhook = diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(last_triggered_at.eq(now))
@@ -336,6 +361,8 @@ impl WorkspaceWebhookRepository for PgConnection {
         use diesel::dsl::now;
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("record_webhook_failure");
+
         let webhook = diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(last_triggered_at.eq(now))
@@ -350,6 +377,8 @@ impl WorkspaceWebhookRepository for PgConnection {
     async fn pause_webhook(&mut self, webhook_id: Uuid) -> PgResult<WorkspaceWebhook> {
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("pause_webhook");
+
         let webhook = diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(status.eq(WebhookStatus::Paused))
@@ -364,6 +393,8 @@ impl WorkspaceWebhookRepository for PgConnection {
     async fn resume_webhook(&mut self, webhook_id: Uuid) -> PgResult<WorkspaceWebhook> {
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("resume_webhook");
+
         let webhook = diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(status.eq(WebhookStatus::Active))
@@ -378,6 +409,8 @@ impl WorkspaceWebhookRepository for PgConnection {
     async fn disable_webhook(&mut self, webhook_id: Uuid) -> PgResult<WorkspaceWebhook> {
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("disable_webhook");
+
         let webhook = diesel::update(workspace_webhooks)
             .filter(id.eq(webhook_id))
             .set(status.eq(WebhookStatus::Disabled))
@@ -391,13 +424,15 @@ impl WorkspaceWebhookRepository for PgConnection {
 
     async fn find_webhooks_for_event(
         &mut self,
-        ws_id: Uuid,
+        scope: TenantScope,
         event: WebhookEvent,
     ) -> PgResult<Vec<WorkspaceWebhook>> {
         use diesel::dsl::sql;
         use diesel::sql_types::Bool;
         use schema::workspace_webhooks::dsl::*;
 
+        let _timer = QueryTimer::start("find_webhooks_for_event");
+
         // Query webhooks where the events array contains the target event.
         // Uses PostgreSQL's `@>` (array contains) operator via raw SQL.
         // The events column is Array<Nullable<WebhookEvent>>, so we check if
@@ -407,7 +442,7 @@ impl WorkspaceWebhookRepository for PgConnection {
             sql::<Bool>(&format!("events @> ARRAY[{}]::WEBHOOK_EVENT[]", event_str));
 
         let webhooks = workspace_webhooks
-            .filter(workspace_id.eq(ws_id))
+            .filter(scope.predicate(workspace_id))
             .filter(status.eq(WebhookStatus::Active))
             .filter(deleted_at.is_null())
             .filter(contains_event)
@@ -0,0 +1,86 @@
+//! Workspace webhook delivery statistics repository.
+
+use std::future::Future;
+
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use jiff::civil::Date;
+use uuid::Uuid;
+
+use crate::client::QueryTimer;
+use crate::model::{NewWorkspaceWebhookDelivery, WorkspaceWebhookDeliveryStats};
+use crate::query::TenantScope;
+use crate::{PgConnection, PgError, PgResult, schema};
+
+/// Repository for webhook delivery statistics.
+///
+/// Delivery outcomes are folded into one row of counters per webhook and
+/// day as they are recorded; individual requests are not stored.
+pub trait WorkspaceWebhookDeliveryRepository {
+    /// Adds a delivery outcome to its webhook's counters for the day.
+    fn record_webhook_delivery(
+        &mut self,
+        delivery: NewWorkspaceWebhookDelivery,
+    ) -> impl Future<Output = PgResult<()>> + Send;
+
+    /// Lists a webhook's daily counters from `since` onwards, oldest first.
+    ///
+    /// Days without any delivery have no row.
+    fn list_webhook_delivery_stats(
+        &mut self,
+        scope: TenantScope,
+        webhook_id: Uuid,
+        since: Date,
+    ) -> impl Future<Output = PgResult<Vec<WorkspaceWebhookDeliveryStats>>> + Send;
+}
+
+impl WorkspaceWebhookDeliveryRepository for PgConnection {
+    async fn record_webhook_delivery(
+        &mut self,
+        delivery: NewWorkspaceWebhookDelivery,
+    ) -> PgResult<()> {
+        use diesel::upsert::excluded;
+        use schema::workspace_webhook_delivery_stats::{self, dsl};
+
+        let _timer = QueryTimer::start("record_webhook_delivery");
+
+        diesel::insert_into(workspace_webhook_delivery_stats::table)
+            .values(&delivery)
+            .on_conflict((dsl::webhook_id, dsl::delivery_date))
+            .do_update()
+            .set((
+                dsl::success_count.eq(dsl::success_count + excluded(dsl::success_count)),
+                dsl::failure_count.eq(dsl::failure_count + excluded(dsl::failure_count)),
+                dsl::filtered_count.eq(dsl::filtered_count + excluded(dsl::filtered_count)),
+                dsl::response_time_ms.eq(dsl::response_time_ms + excluded(dsl::response_time_ms)),
+            ))
+            .execute(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(())
+    }
+
+    async fn list_webhook_delivery_stats(
+        &mut self,
+        scope: TenantScope,
+        webhook_id: Uuid,
+        since: Date,
+    ) -> PgResult<Vec<WorkspaceWebhookDeliveryStats>> {
+        use schema::workspace_webhook_delivery_stats::{self, dsl};
+
+        let _timer = QueryTimer::start("list_webhook_delivery_stats");
+
+        let stats = workspace_webhook_delivery_stats::table
+            .filter(scope.predicate(dsl::workspace_id))
+            .filter(dsl::webhook_id.eq(webhook_id))
+            .filter(dsl::delivery_date.ge(jiff_diesel::Date::from(since)))
+            .select(WorkspaceWebhookDeliveryStats::as_select())
+            .order(dsl::delivery_date.asc())
+            .load(self)
+            .await
+            .map_err(PgError::from)?;
+
+        Ok(stats)
+    }
+}
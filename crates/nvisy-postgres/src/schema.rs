@@ -5,6 +5,10 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "activity_type"))]
     pub struct ActivityType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "api_key_scope"))]
+    pub struct ApiKeyScope;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "api_token_type"))]
     pub struct ApiTokenType;
@@ -13,10 +17,26 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "artifact_type"))]
     pub struct ArtifactType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "change_event_source"))]
+    pub struct ChangeEventSource;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "data_region"))]
+    pub struct DataRegion;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "data_sensitivity"))]
+    pub struct DataSensitivity;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "file_source"))]
     pub struct FileSource;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "inbound_webhook_provider"))]
+    pub struct InboundWebhookProvider;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "invite_status"))]
     pub struct InviteStatus;
@@ -25,6 +45,14 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "notification_event"))]
     pub struct NotificationEvent;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "operation_kind"))]
+    pub struct OperationKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "operation_status"))]
+    pub struct OperationStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "pipeline_run_status"))]
     pub struct PipelineRunStatus;
@@ -37,6 +65,18 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "pipeline_trigger_type"))]
     pub struct PipelineTriggerType;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "review_status"))]
+    pub struct ReviewStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "storage_class"))]
+    pub struct StorageClass;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "storage_stage"))]
+    pub struct StorageStage;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "sync_status"))]
     pub struct SyncStatus;
@@ -58,6 +98,25 @@ pub mod sql_types {
     pub struct WorkspaceRole;
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ApiKeyScope;
+
+    account_api_keys (id) {
+        id -> Uuid,
+        account_id -> Uuid,
+        name -> Text,
+        scopes -> Array<Nullable<ApiKeyScope>>,
+        key_prefix -> Text,
+        key_hash -> Bytea,
+        replaced_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        expired_at -> Nullable<Timestamptz>,
+        last_used_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ApiTokenType;
@@ -77,6 +136,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    account_identities (id) {
+        id -> Uuid,
+        account_id -> Uuid,
+        issuer -> Text,
+        subject -> Text,
+        email_address -> Nullable<Text>,
+        created_at -> Timestamptz,
+        last_login_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::NotificationEvent;
@@ -119,6 +192,33 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    admin_scope_events (id) {
+        id -> Uuid,
+        reason -> Text,
+        caller -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DataRegion;
+    use super::sql_types::StorageClass;
+
+    storage_prices (id) {
+        id -> Uuid,
+        data_region -> DataRegion,
+        storage_class -> StorageClass,
+        currency -> Text,
+        price_micros_per_gib_month -> Int8,
+        effective_from -> Date,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ActivityType;
@@ -133,6 +233,42 @@ diesel::table! {
         ip_address -> Nullable<Inet>,
         user_agent -> Nullable<Text>,
         created_at -> Timestamptz,
+        sequence_number -> Int8,
+        resource_id -> Nullable<Uuid>,
+        previous_hash -> Nullable<Bytea>,
+        record_hash -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_temporary_objects (id) {
+        id -> Uuid,
+        workspace_id -> Uuid,
+        account_id -> Nullable<Uuid>,
+        bucket -> Text,
+        object_key -> Text,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ChangeEventSource;
+    use super::sql_types::WebhookEvent;
+
+    workspace_change_events (id) {
+        id -> Int8,
+        workspace_id -> Uuid,
+        resource_id -> Uuid,
+        event -> WebhookEvent,
+        created_at -> Timestamptz,
+        published_at -> Nullable<Timestamptz>,
+        source -> ChangeEventSource,
+        triggered_by -> Nullable<Uuid>,
+        data -> Nullable<Jsonb>,
     }
 }
 
@@ -170,6 +306,7 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
+        credential_key_version -> Int4,
     }
 }
 
@@ -192,9 +329,79 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_custom_roles (id) {
+        id -> Uuid,
+        workspace_id -> Uuid,
+        account_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        permissions -> Array<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ReviewStatus;
+
+    workspace_detection_reviews (id) {
+        id -> Uuid,
+        run_id -> Uuid,
+        reviewer_id -> Nullable<Uuid>,
+        detection_id -> Text,
+        version -> Int4,
+        decision -> ReviewStatus,
+        status -> ReviewStatus,
+        comment -> Nullable<Text>,
+        created_at -> Timestamptz,
+        superseded_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_file_access_stats (file_id, access_date) {
+        workspace_id -> Uuid,
+        file_id -> Uuid,
+        access_date -> Date,
+        view_count -> Int4,
+        download_count -> Int4,
+        search_open_count -> Int4,
+        share_view_count -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_file_shares (id) {
+        id -> Uuid,
+        workspace_id -> Uuid,
+        file_id -> Uuid,
+        account_id -> Nullable<Uuid>,
+        recipient -> Text,
+        pages -> Nullable<Array<Nullable<Int4>>>,
+        watermark -> Bool,
+        token_prefix -> Text,
+        token_hash -> Bytea,
+        max_views -> Nullable<Int4>,
+        view_count -> Int4,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        last_viewed_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::FileSource;
+    use super::sql_types::DataSensitivity;
 
     workspace_files (id) {
         id -> Uuid,
@@ -216,6 +423,28 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
+        password_protected -> Bool,
+        encrypted_password -> Nullable<Bytea>,
+        sensitivity -> DataSensitivity,
+        storage_version -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::InboundWebhookProvider;
+
+    workspace_inbound_webhooks (id) {
+        id -> Uuid,
+        workspace_id -> Uuid,
+        provider -> InboundWebhookProvider,
+        display_name -> Text,
+        encrypted_secret -> Bytea,
+        last_received_at -> Nullable<Timestamptz>,
+        created_by -> Uuid,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -240,6 +469,47 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_legal_holds (id) {
+        id -> Uuid,
+        workspace_id -> Uuid,
+        file_id -> Nullable<Uuid>,
+        account_id -> Nullable<Uuid>,
+        reason -> Text,
+        created_at -> Timestamptz,
+        released_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::OperationKind;
+    use super::sql_types::OperationStatus;
+
+    workspace_operations (id) {
+        id -> Uuid,
+        workspace_id -> Uuid,
+        account_id -> Nullable<Uuid>,
+        kind -> OperationKind,
+        status -> OperationStatus,
+        progress -> Int2,
+        target_id -> Nullable<Uuid>,
+        result -> Nullable<Jsonb>,
+        error -> Nullable<Jsonb>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        started_at -> Nullable<Timestamptz>,
+        completed_at -> Nullable<Timestamptz>,
+        artifact_key -> Nullable<Text>,
+        artifact_name -> Nullable<Text>,
+        artifact_content_type -> Nullable<Text>,
+        artifact_size -> Nullable<Int8>,
+        expires_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::WorkspaceRole;
@@ -256,6 +526,7 @@ diesel::table! {
         updated_by -> Uuid,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        custom_role_id -> Nullable<Uuid>,
     }
 }
 
@@ -310,6 +581,7 @@ diesel::table! {
         metadata -> Jsonb,
         started_at -> Timestamptz,
         completed_at -> Nullable<Timestamptz>,
+        input_digest -> Nullable<Bytea>,
     }
 }
 
@@ -333,6 +605,9 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
+        input_retention_days -> Nullable<Int4>,
+        intermediate_retention_days -> Nullable<Int4>,
+        output_retention_days -> Nullable<Int4>,
     }
 }
 
@@ -355,6 +630,59 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_retention_policies (workspace_id) {
+        workspace_id -> Uuid,
+        account_id -> Nullable<Uuid>,
+        file_retention_days -> Nullable<Int4>,
+        run_retention_days -> Nullable<Int4>,
+        activity_retention_days -> Nullable<Int4>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DataRegion;
+    use super::sql_types::StorageStage;
+    use super::sql_types::StorageClass;
+
+    workspace_storage_costs (period_start, workspace_id, data_region, stage, storage_class) {
+        period_start -> Date,
+        workspace_id -> Uuid,
+        data_region -> DataRegion,
+        stage -> StorageStage,
+        storage_class -> StorageClass,
+        object_count -> Int8,
+        byte_count -> Int8,
+        byte_days -> Int8,
+        sampled_days -> Int4,
+        period_days -> Int4,
+        last_sampled_on -> Date,
+        currency -> Nullable<Text>,
+        price_micros_per_gib_month -> Nullable<Int8>,
+        cost_micros -> Nullable<Int8>,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+
+    workspace_webhook_delivery_stats (webhook_id, delivery_date) {
+        workspace_id -> Uuid,
+        webhook_id -> Uuid,
+        delivery_date -> Date,
+        success_count -> Int4,
+        failure_count -> Int4,
+        filtered_count -> Int4,
+        response_time_ms -> Int8,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::WebhookEvent;
@@ -375,11 +703,13 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
+        payload_filters -> Array<Nullable<Text>>,
     }
 }
 
 diesel::table! {
     use diesel::sql_types::*;
+    use super::sql_types::DataRegion;
 
     workspaces (id) {
         id -> Uuid,
@@ -395,10 +725,13 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         deleted_at -> Nullable<Timestamptz>,
+        data_region -> DataRegion,
     }
 }
 
+diesel::joinable!(account_api_keys -> accounts (account_id));
 diesel::joinable!(account_api_tokens -> accounts (account_id));
+diesel::joinable!(account_identities -> accounts (account_id));
 diesel::joinable!(account_notifications -> accounts (account_id));
 diesel::joinable!(workspace_activities -> accounts (account_id));
 diesel::joinable!(workspace_activities -> workspaces (workspace_id));
@@ -408,10 +741,27 @@ diesel::joinable!(workspace_connections -> accounts (account_id));
 diesel::joinable!(workspace_connections -> workspaces (workspace_id));
 diesel::joinable!(workspace_contexts -> accounts (account_id));
 diesel::joinable!(workspace_contexts -> workspaces (workspace_id));
+diesel::joinable!(workspace_custom_roles -> accounts (account_id));
+diesel::joinable!(workspace_custom_roles -> workspaces (workspace_id));
+diesel::joinable!(workspace_detection_reviews -> accounts (reviewer_id));
+diesel::joinable!(workspace_detection_reviews -> workspace_pipeline_runs (run_id));
+diesel::joinable!(workspace_file_access_stats -> workspace_files (file_id));
+diesel::joinable!(workspace_file_access_stats -> workspaces (workspace_id));
+diesel::joinable!(workspace_file_shares -> accounts (account_id));
+diesel::joinable!(workspace_file_shares -> workspace_files (file_id));
+diesel::joinable!(workspace_file_shares -> workspaces (workspace_id));
 diesel::joinable!(workspace_files -> accounts (account_id));
 diesel::joinable!(workspace_files -> workspaces (workspace_id));
+diesel::joinable!(workspace_inbound_webhooks -> accounts (created_by));
+diesel::joinable!(workspace_inbound_webhooks -> workspaces (workspace_id));
 diesel::joinable!(workspace_invites -> workspaces (workspace_id));
+diesel::joinable!(workspace_legal_holds -> accounts (account_id));
+diesel::joinable!(workspace_legal_holds -> workspace_files (file_id));
+diesel::joinable!(workspace_legal_holds -> workspaces (workspace_id));
+diesel::joinable!(workspace_members -> workspace_custom_roles (custom_role_id));
 diesel::joinable!(workspace_members -> workspaces (workspace_id));
+diesel::joinable!(workspace_operations -> accounts (account_id));
+diesel::joinable!(workspace_operations -> workspaces (workspace_id));
 diesel::joinable!(workspace_pipeline_artifacts -> workspace_files (file_id));
 diesel::joinable!(workspace_pipeline_artifacts -> workspace_pipeline_runs (run_id));
 diesel::joinable!(workspace_pipeline_contexts -> workspaces (workspace_id));
@@ -423,27 +773,50 @@ diesel::joinable!(workspace_pipelines -> accounts (account_id));
 diesel::joinable!(workspace_pipelines -> workspaces (workspace_id));
 diesel::joinable!(workspace_policies -> accounts (account_id));
 diesel::joinable!(workspace_policies -> workspaces (workspace_id));
+diesel::joinable!(workspace_retention_policies -> accounts (account_id));
+diesel::joinable!(workspace_retention_policies -> workspaces (workspace_id));
+diesel::joinable!(workspace_storage_costs -> workspaces (workspace_id));
+diesel::joinable!(workspace_temporary_objects -> accounts (account_id));
+diesel::joinable!(workspace_temporary_objects -> workspaces (workspace_id));
+diesel::joinable!(workspace_webhook_delivery_stats -> workspace_webhooks (webhook_id));
+diesel::joinable!(workspace_webhook_delivery_stats -> workspaces (workspace_id));
 diesel::joinable!(workspace_webhooks -> accounts (created_by));
 diesel::joinable!(workspace_webhooks -> workspaces (workspace_id));
 diesel::joinable!(workspaces -> accounts (created_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    account_api_keys,
     account_api_tokens,
+    account_identities,
     account_notifications,
     accounts,
+    admin_scope_events,
+    storage_prices,
     workspace_activities,
+    workspace_change_events,
     workspace_connection_runs,
     workspace_connections,
     workspace_contexts,
+    workspace_custom_roles,
+    workspace_detection_reviews,
+    workspace_file_access_stats,
+    workspace_file_shares,
     workspace_files,
+    workspace_inbound_webhooks,
     workspace_invites,
+    workspace_legal_holds,
     workspace_members,
+    workspace_operations,
     workspace_pipeline_artifacts,
     workspace_pipeline_contexts,
     workspace_pipeline_policies,
     workspace_pipeline_runs,
     workspace_pipelines,
     workspace_policies,
+    workspace_retention_policies,
+    workspace_storage_costs,
+    workspace_temporary_objects,
+    workspace_webhook_delivery_stats,
     workspace_webhooks,
     workspaces,
 );
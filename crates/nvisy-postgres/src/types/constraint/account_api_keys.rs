@@ -0,0 +1,73 @@
+//! Account API keys table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Account API keys table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum AccountApiKeyConstraints {
+    // Key validation constraints
+    #[strum(serialize = "account_api_keys_name_not_empty")]
+    NameNotEmpty,
+    #[strum(serialize = "account_api_keys_name_length")]
+    NameLength,
+    #[strum(serialize = "account_api_keys_scopes_not_empty")]
+    ScopesNotEmpty,
+    #[strum(serialize = "account_api_keys_key_hash_length")]
+    KeyHashLength,
+
+    // Key uniqueness constraints
+    #[strum(serialize = "account_api_keys_key_hash_unique")]
+    KeyHashUnique,
+
+    // Key chronological constraints
+    #[strum(serialize = "account_api_keys_expired_after_created")]
+    ExpiredAfterCreated,
+    #[strum(serialize = "account_api_keys_deleted_after_created")]
+    DeletedAfterCreated,
+    #[strum(serialize = "account_api_keys_last_used_after_created")]
+    LastUsedAfterCreated,
+}
+
+impl AccountApiKeyConstraints {
+    /// Creates a new [`AccountApiKeyConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            AccountApiKeyConstraints::NameNotEmpty
+            | AccountApiKeyConstraints::NameLength
+            | AccountApiKeyConstraints::ScopesNotEmpty
+            | AccountApiKeyConstraints::KeyHashLength => ConstraintCategory::Validation,
+
+            AccountApiKeyConstraints::KeyHashUnique => ConstraintCategory::Uniqueness,
+
+            AccountApiKeyConstraints::ExpiredAfterCreated
+            | AccountApiKeyConstraints::DeletedAfterCreated
+            | AccountApiKeyConstraints::LastUsedAfterCreated => ConstraintCategory::Chronological,
+        }
+    }
+}
+
+impl From<AccountApiKeyConstraints> for String {
+    #[inline]
+    fn from(val: AccountApiKeyConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for AccountApiKeyConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,67 @@
+//! Account identities table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Account identities table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum AccountIdentityConstraints {
+    // Identity validation constraints
+    #[strum(serialize = "account_identities_issuer_not_empty")]
+    IssuerNotEmpty,
+    #[strum(serialize = "account_identities_issuer_length")]
+    IssuerLength,
+    #[strum(serialize = "account_identities_subject_not_empty")]
+    SubjectNotEmpty,
+    #[strum(serialize = "account_identities_subject_length")]
+    SubjectLength,
+
+    // Identity uniqueness constraints
+    #[strum(serialize = "account_identities_issuer_subject_unique")]
+    IssuerSubjectUnique,
+
+    // Identity chronological constraints
+    #[strum(serialize = "account_identities_last_login_after_created")]
+    LastLoginAfterCreated,
+}
+
+impl AccountIdentityConstraints {
+    /// Creates a new [`AccountIdentityConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            AccountIdentityConstraints::IssuerNotEmpty
+            | AccountIdentityConstraints::IssuerLength
+            | AccountIdentityConstraints::SubjectNotEmpty
+            | AccountIdentityConstraints::SubjectLength => ConstraintCategory::Validation,
+
+            AccountIdentityConstraints::IssuerSubjectUnique => ConstraintCategory::Uniqueness,
+
+            AccountIdentityConstraints::LastLoginAfterCreated => ConstraintCategory::Chronological,
+        }
+    }
+}
+
+impl From<AccountIdentityConstraints> for String {
+    #[inline]
+    fn from(val: AccountIdentityConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for AccountIdentityConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
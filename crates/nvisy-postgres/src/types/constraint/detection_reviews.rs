@@ -0,0 +1,83 @@
+//! Detection reviews table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Detection reviews table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceDetectionReviewConstraints {
+    // Validation constraints
+    #[strum(serialize = "workspace_detection_reviews_detection_id_length")]
+    DetectionIdLength,
+    #[strum(serialize = "workspace_detection_reviews_version_min")]
+    VersionMin,
+    #[strum(serialize = "workspace_detection_reviews_comment_length")]
+    CommentLength,
+
+    // Business logic constraints
+    #[strum(serialize = "workspace_detection_reviews_decision_valid")]
+    DecisionValid,
+    #[strum(serialize = "workspace_detection_reviews_status_valid")]
+    StatusValid,
+    #[strum(serialize = "workspace_detection_reviews_superseded_consistent")]
+    SupersededConsistent,
+
+    // Uniqueness constraints
+    #[strum(serialize = "workspace_detection_reviews_run_id_detection_id_version_key")]
+    VersionUnique,
+    #[strum(serialize = "workspace_detection_reviews_current_idx")]
+    CurrentUnique,
+
+    // Chronological constraints
+    #[strum(serialize = "workspace_detection_reviews_superseded_after_created")]
+    SupersededAfterCreated,
+}
+
+impl WorkspaceDetectionReviewConstraints {
+    /// Creates a new [`WorkspaceDetectionReviewConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceDetectionReviewConstraints::DetectionIdLength
+            | WorkspaceDetectionReviewConstraints::VersionMin
+            | WorkspaceDetectionReviewConstraints::CommentLength => ConstraintCategory::Validation,
+
+            WorkspaceDetectionReviewConstraints::DecisionValid
+            | WorkspaceDetectionReviewConstraints::StatusValid
+            | WorkspaceDetectionReviewConstraints::SupersededConsistent => {
+                ConstraintCategory::BusinessLogic
+            }
+
+            WorkspaceDetectionReviewConstraints::VersionUnique
+            | WorkspaceDetectionReviewConstraints::CurrentUnique => ConstraintCategory::Uniqueness,
+
+            WorkspaceDetectionReviewConstraints::SupersededAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceDetectionReviewConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceDetectionReviewConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceDetectionReviewConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
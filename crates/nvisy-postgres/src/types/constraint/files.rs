@@ -40,6 +40,10 @@ pub enum WorkspaceFileConstraints {
     #[strum(serialize = "workspace_files_version_number_min")]
     VersionNumberMin,
 
+    // File protection constraints
+    #[strum(serialize = "workspace_files_encrypted_password_protected")]
+    EncryptedPasswordProtected,
+
     // Uniqueness constraints
     #[strum(serialize = "workspace_files_workspace_id_id_key")]
     WorkspaceIdIdUnique,
@@ -74,6 +78,10 @@ impl WorkspaceFileConstraints {
             | WorkspaceFileConstraints::MetadataSize
             | WorkspaceFileConstraints::VersionNumberMin => ConstraintCategory::Validation,
 
+            WorkspaceFileConstraints::EncryptedPasswordProtected => {
+                ConstraintCategory::BusinessLogic
+            }
+
             WorkspaceFileConstraints::WorkspaceIdIdUnique => ConstraintCategory::Uniqueness,
 
             WorkspaceFileConstraints::UpdatedAfterCreated
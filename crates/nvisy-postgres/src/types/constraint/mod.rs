@@ -4,21 +4,31 @@
 //! organized into logical groups for better maintainability.
 
 // Account-related constraint modules
+mod account_api_keys;
 mod account_api_tokens;
+mod account_identities;
 mod account_notifications;
 mod accounts;
 
 // Workspace-related constraint modules
 mod workspace_activities;
+mod workspace_custom_roles;
+mod workspace_inbound_webhooks;
 mod workspace_invites;
+mod workspace_legal_holds;
 mod workspace_members;
+mod workspace_operations;
+mod workspace_retention_policies;
 mod workspace_webhooks;
 mod workspaces;
 
 // File-related constraint modules
 mod files;
+mod workspace_file_shares;
+mod workspace_temporary_objects;
 
 // Pipeline-related constraint modules
+mod detection_reviews;
 mod pipeline_artifacts;
 mod pipeline_references;
 mod pipeline_runs;
@@ -33,9 +43,12 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+pub use self::account_api_keys::AccountApiKeyConstraints;
 pub use self::account_api_tokens::AccountApiTokenConstraints;
+pub use self::account_identities::AccountIdentityConstraints;
 pub use self::account_notifications::AccountNotificationConstraints;
 pub use self::accounts::AccountConstraints;
+pub use self::detection_reviews::WorkspaceDetectionReviewConstraints;
 pub use self::files::WorkspaceFileConstraints;
 pub use self::pipeline_artifacts::WorkspacePipelineArtifactConstraints;
 pub use self::pipeline_references::WorkspacePipelineReferenceConstraints;
@@ -45,9 +58,16 @@ pub use self::workspace_activities::WorkspaceActivitiesConstraints;
 pub use self::workspace_connection_runs::WorkspaceConnectionRunConstraints;
 pub use self::workspace_connections::WorkspaceConnectionConstraints;
 pub use self::workspace_contexts::WorkspaceContextConstraints;
+pub use self::workspace_custom_roles::WorkspaceCustomRoleConstraints;
+pub use self::workspace_file_shares::WorkspaceFileShareConstraints;
+pub use self::workspace_inbound_webhooks::WorkspaceInboundWebhookConstraints;
 pub use self::workspace_invites::WorkspaceInviteConstraints;
+pub use self::workspace_legal_holds::WorkspaceLegalHoldConstraints;
 pub use self::workspace_members::WorkspaceMemberConstraints;
+pub use self::workspace_operations::WorkspaceOperationConstraints;
 pub use self::workspace_policies::WorkspacePolicyConstraints;
+pub use self::workspace_retention_policies::WorkspaceRetentionPolicyConstraints;
+pub use self::workspace_temporary_objects::WorkspaceTemporaryObjectConstraints;
 pub use self::workspace_webhooks::WorkspaceWebhookConstraints;
 pub use self::workspaces::WorkspaceConstraints;
 
@@ -63,22 +83,32 @@ pub enum ConstraintViolation {
     Account(AccountConstraints),
     AccountNotification(AccountNotificationConstraints),
     AccountApiToken(AccountApiTokenConstraints),
+    AccountApiKey(AccountApiKeyConstraints),
+    AccountIdentity(AccountIdentityConstraints),
 
     // Workspace-related constraints
     Workspace(WorkspaceConstraints),
     WorkspaceMember(WorkspaceMemberConstraints),
+    WorkspaceCustomRole(WorkspaceCustomRoleConstraints),
     WorkspaceInvite(WorkspaceInviteConstraints),
     WorkspaceActivityLog(WorkspaceActivitiesConstraints),
     WorkspaceWebhook(WorkspaceWebhookConstraints),
+    WorkspaceInboundWebhook(WorkspaceInboundWebhookConstraints),
+    WorkspaceOperation(WorkspaceOperationConstraints),
+    WorkspaceRetentionPolicy(WorkspaceRetentionPolicyConstraints),
+    WorkspaceLegalHold(WorkspaceLegalHoldConstraints),
 
     // File-related constraints
     WorkspaceFile(WorkspaceFileConstraints),
+    WorkspaceFileShare(WorkspaceFileShareConstraints),
+    WorkspaceTemporaryObject(WorkspaceTemporaryObjectConstraints),
 
     // Pipeline-related constraints
     WorkspacePipeline(WorkspacePipelineConstraints),
     WorkspacePipelineRun(WorkspacePipelineRunConstraints),
     WorkspacePipelineArtifact(WorkspacePipelineArtifactConstraints),
     WorkspacePipelineReference(WorkspacePipelineReferenceConstraints),
+    WorkspaceDetectionReview(WorkspaceDetectionReviewConstraints),
     WorkspaceConnection(WorkspaceConnectionConstraints),
     WorkspaceConnectionRun(WorkspaceConnectionRunConstraints),
     WorkspaceContext(WorkspaceContextConstraints),
@@ -140,6 +170,8 @@ impl ConstraintViolation {
             "account" => try_parse! {
                 AccountNotificationConstraints::new => AccountNotification,
                 AccountApiTokenConstraints::new => AccountApiToken,
+                AccountApiKeyConstraints::new => AccountApiKey,
+                AccountIdentityConstraints::new => AccountIdentity,
             },
             "workspaces" => try_parse!(WorkspaceConstraints::new => Workspace),
             // Every workspace-owned table is prefixed `workspace_*`, so all of
@@ -147,18 +179,26 @@ impl ConstraintViolation {
             // the order of these parsers does not matter.
             "workspace" => try_parse! {
                 WorkspaceMemberConstraints::new => WorkspaceMember,
+                WorkspaceCustomRoleConstraints::new => WorkspaceCustomRole,
                 WorkspaceInviteConstraints::new => WorkspaceInvite,
                 WorkspaceActivitiesConstraints::new => WorkspaceActivityLog,
                 WorkspaceWebhookConstraints::new => WorkspaceWebhook,
+                WorkspaceInboundWebhookConstraints::new => WorkspaceInboundWebhook,
+                WorkspaceOperationConstraints::new => WorkspaceOperation,
+                WorkspaceRetentionPolicyConstraints::new => WorkspaceRetentionPolicy,
+                WorkspaceLegalHoldConstraints::new => WorkspaceLegalHold,
                 WorkspaceConnectionRunConstraints::new => WorkspaceConnectionRun,
                 WorkspaceConnectionConstraints::new => WorkspaceConnection,
                 WorkspaceContextConstraints::new => WorkspaceContext,
                 WorkspacePolicyConstraints::new => WorkspacePolicy,
                 WorkspaceFileConstraints::new => WorkspaceFile,
+                WorkspaceFileShareConstraints::new => WorkspaceFileShare,
+                WorkspaceTemporaryObjectConstraints::new => WorkspaceTemporaryObject,
                 WorkspacePipelineRunConstraints::new => WorkspacePipelineRun,
                 WorkspacePipelineConstraints::new => WorkspacePipeline,
                 WorkspacePipelineArtifactConstraints::new => WorkspacePipelineArtifact,
                 WorkspacePipelineReferenceConstraints::new => WorkspacePipelineReference,
+                WorkspaceDetectionReviewConstraints::new => WorkspaceDetectionReview,
             },
             _ => None,
         }
@@ -173,22 +213,32 @@ impl ConstraintViolation {
             ConstraintViolation::Account(_) => "accounts",
             ConstraintViolation::AccountNotification(_) => "account_notifications",
             ConstraintViolation::AccountApiToken(_) => "account_api_tokens",
+            ConstraintViolation::AccountApiKey(_) => "account_api_keys",
+            ConstraintViolation::AccountIdentity(_) => "account_identities",
 
             // Workspace-related tables
             ConstraintViolation::Workspace(_) => "workspaces",
             ConstraintViolation::WorkspaceMember(_) => "workspace_members",
+            ConstraintViolation::WorkspaceCustomRole(_) => "workspace_custom_roles",
             ConstraintViolation::WorkspaceInvite(_) => "workspace_invites",
             ConstraintViolation::WorkspaceActivityLog(_) => "workspace_activities",
             ConstraintViolation::WorkspaceWebhook(_) => "workspace_webhooks",
+            ConstraintViolation::WorkspaceInboundWebhook(_) => "workspace_inbound_webhooks",
+            ConstraintViolation::WorkspaceOperation(_) => "workspace_operations",
+            ConstraintViolation::WorkspaceRetentionPolicy(_) => "workspace_retention_policies",
+            ConstraintViolation::WorkspaceLegalHold(_) => "workspace_legal_holds",
 
             // File-related tables
             ConstraintViolation::WorkspaceFile(_) => "workspace_files",
+            ConstraintViolation::WorkspaceFileShare(_) => "workspace_file_shares",
+            ConstraintViolation::WorkspaceTemporaryObject(_) => "workspace_temporary_objects",
 
             // Pipeline-related tables
             ConstraintViolation::WorkspacePipeline(_) => "workspace_pipelines",
             ConstraintViolation::WorkspacePipelineRun(_) => "workspace_pipeline_runs",
             ConstraintViolation::WorkspacePipelineArtifact(_) => "workspace_pipeline_artifacts",
             ConstraintViolation::WorkspacePipelineReference(_) => "pipeline_references",
+            ConstraintViolation::WorkspaceDetectionReview(_) => "workspace_detection_reviews",
             ConstraintViolation::WorkspaceConnection(_) => "workspace_connections",
             ConstraintViolation::WorkspaceConnectionRun(_) => "workspace_connection_runs",
             ConstraintViolation::WorkspaceContext(_) => "workspace_contexts",
@@ -203,20 +253,31 @@ impl ConstraintViolation {
         match self {
             ConstraintViolation::Account(_)
             | ConstraintViolation::AccountNotification(_)
-            | ConstraintViolation::AccountApiToken(_) => "accounts",
+            | ConstraintViolation::AccountApiToken(_)
+            | ConstraintViolation::AccountApiKey(_)
+            | ConstraintViolation::AccountIdentity(_) => "accounts",
 
             ConstraintViolation::Workspace(_)
             | ConstraintViolation::WorkspaceMember(_)
+            | ConstraintViolation::WorkspaceCustomRole(_)
             | ConstraintViolation::WorkspaceInvite(_)
             | ConstraintViolation::WorkspaceActivityLog(_)
-            | ConstraintViolation::WorkspaceWebhook(_) => "workspaces",
+            | ConstraintViolation::WorkspaceWebhook(_)
+            | ConstraintViolation::WorkspaceInboundWebhook(_)
+            | ConstraintViolation::WorkspaceOperation(_) => "workspaces",
 
-            ConstraintViolation::WorkspaceFile(_) => "files",
+            ConstraintViolation::WorkspaceRetentionPolicy(_)
+            | ConstraintViolation::WorkspaceLegalHold(_) => "retention",
+
+            ConstraintViolation::WorkspaceFile(_)
+            | ConstraintViolation::WorkspaceFileShare(_)
+            | ConstraintViolation::WorkspaceTemporaryObject(_) => "files",
 
             ConstraintViolation::WorkspacePipeline(_)
             | ConstraintViolation::WorkspacePipelineRun(_)
             | ConstraintViolation::WorkspacePipelineArtifact(_)
-            | ConstraintViolation::WorkspacePipelineReference(_) => "pipelines",
+            | ConstraintViolation::WorkspacePipelineReference(_)
+            | ConstraintViolation::WorkspaceDetectionReview(_) => "pipelines",
 
             ConstraintViolation::WorkspaceConnection(_)
             | ConstraintViolation::WorkspaceConnectionRun(_) => "connections",
@@ -233,19 +294,29 @@ impl ConstraintViolation {
             ConstraintViolation::Account(c) => c.categorize(),
             ConstraintViolation::AccountNotification(c) => c.categorize(),
             ConstraintViolation::AccountApiToken(c) => c.categorize(),
+            ConstraintViolation::AccountApiKey(c) => c.categorize(),
+            ConstraintViolation::AccountIdentity(c) => c.categorize(),
 
             ConstraintViolation::Workspace(c) => c.categorize(),
             ConstraintViolation::WorkspaceMember(c) => c.categorize(),
+            ConstraintViolation::WorkspaceCustomRole(c) => c.categorize(),
             ConstraintViolation::WorkspaceInvite(c) => c.categorize(),
             ConstraintViolation::WorkspaceActivityLog(c) => c.categorize(),
             ConstraintViolation::WorkspaceWebhook(c) => c.categorize(),
+            ConstraintViolation::WorkspaceInboundWebhook(c) => c.categorize(),
+            ConstraintViolation::WorkspaceOperation(c) => c.categorize(),
+            ConstraintViolation::WorkspaceRetentionPolicy(c) => c.categorize(),
+            ConstraintViolation::WorkspaceLegalHold(c) => c.categorize(),
 
             ConstraintViolation::WorkspaceFile(c) => c.categorize(),
+            ConstraintViolation::WorkspaceFileShare(c) => c.categorize(),
+            ConstraintViolation::WorkspaceTemporaryObject(c) => c.categorize(),
 
             ConstraintViolation::WorkspacePipeline(c) => c.categorize(),
             ConstraintViolation::WorkspacePipelineRun(c) => c.categorize(),
             ConstraintViolation::WorkspacePipelineArtifact(c) => c.categorize(),
             ConstraintViolation::WorkspacePipelineReference(c) => c.categorize(),
+            ConstraintViolation::WorkspaceDetectionReview(c) => c.categorize(),
             ConstraintViolation::WorkspaceConnection(c) => c.categorize(),
             ConstraintViolation::WorkspaceConnectionRun(c) => c.categorize(),
             ConstraintViolation::WorkspaceContext(c) => c.categorize(),
@@ -266,19 +337,29 @@ impl fmt::Display for ConstraintViolation {
             ConstraintViolation::Account(c) => write!(f, "{}", c),
             ConstraintViolation::AccountNotification(c) => write!(f, "{}", c),
             ConstraintViolation::AccountApiToken(c) => write!(f, "{}", c),
+            ConstraintViolation::AccountApiKey(c) => write!(f, "{}", c),
+            ConstraintViolation::AccountIdentity(c) => write!(f, "{}", c),
 
             ConstraintViolation::Workspace(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceMember(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceCustomRole(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceInvite(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceActivityLog(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceWebhook(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceInboundWebhook(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceOperation(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceRetentionPolicy(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceLegalHold(c) => write!(f, "{}", c),
 
             ConstraintViolation::WorkspaceFile(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceFileShare(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceTemporaryObject(c) => write!(f, "{}", c),
 
             ConstraintViolation::WorkspacePipeline(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspacePipelineRun(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspacePipelineArtifact(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspacePipelineReference(c) => write!(f, "{}", c),
+            ConstraintViolation::WorkspaceDetectionReview(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceConnection(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceConnectionRun(c) => write!(f, "{}", c),
             ConstraintViolation::WorkspaceContext(c) => write!(f, "{}", c),
@@ -335,6 +416,48 @@ mod tests {
                 WorkspacePolicyConstraints::WorkspaceIdIdUnique
             ))
         );
+        assert_eq!(
+            ConstraintViolation::new("workspace_custom_roles_name_unique_idx"),
+            Some(ConstraintViolation::WorkspaceCustomRole(
+                WorkspaceCustomRoleConstraints::NameUnique
+            ))
+        );
+        assert_eq!(
+            ConstraintViolation::new("workspace_detection_reviews_current_idx"),
+            Some(ConstraintViolation::WorkspaceDetectionReview(
+                WorkspaceDetectionReviewConstraints::CurrentUnique
+            ))
+        );
+        assert_eq!(
+            ConstraintViolation::new("workspace_pipeline_runs_outputs_immutable"),
+            Some(ConstraintViolation::WorkspacePipelineRun(
+                WorkspacePipelineRunConstraints::OutputsImmutable
+            ))
+        );
+        assert_eq!(
+            ConstraintViolation::new("workspace_retention_policies_run_retention_days_range"),
+            Some(ConstraintViolation::WorkspaceRetentionPolicy(
+                WorkspaceRetentionPolicyConstraints::RunRetentionDaysRange
+            ))
+        );
+        assert_eq!(
+            ConstraintViolation::new("workspace_legal_holds_file_held"),
+            Some(ConstraintViolation::WorkspaceLegalHold(
+                WorkspaceLegalHoldConstraints::FileHeld
+            ))
+        );
+        assert_eq!(
+            ConstraintViolation::new("workspace_file_shares_view_count_range"),
+            Some(ConstraintViolation::WorkspaceFileShare(
+                WorkspaceFileShareConstraints::ViewCountRange
+            ))
+        );
+        assert_eq!(
+            ConstraintViolation::new("workspace_temporary_objects_object_unique"),
+            Some(ConstraintViolation::WorkspaceTemporaryObject(
+                WorkspaceTemporaryObjectConstraints::ObjectUnique
+            ))
+        );
         assert_eq!(ConstraintViolation::new("unknown_constraint"), None);
     }
 
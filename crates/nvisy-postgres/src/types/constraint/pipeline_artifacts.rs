@@ -13,6 +13,10 @@ pub enum WorkspacePipelineArtifactConstraints {
     // Metadata validation constraints
     #[strum(serialize = "workspace_pipeline_artifacts_metadata_size")]
     MetadataSize,
+
+    // Business logic constraints
+    #[strum(serialize = "workspace_pipeline_artifacts_immutable")]
+    Immutable,
 }
 
 impl WorkspacePipelineArtifactConstraints {
@@ -25,6 +29,7 @@ impl WorkspacePipelineArtifactConstraints {
     pub fn categorize(&self) -> ConstraintCategory {
         match self {
             WorkspacePipelineArtifactConstraints::MetadataSize => ConstraintCategory::Validation,
+            WorkspacePipelineArtifactConstraints::Immutable => ConstraintCategory::BusinessLogic,
         }
     }
 }
@@ -17,6 +17,14 @@ pub enum WorkspacePipelineRunConstraints {
     MetadataSize,
     #[strum(serialize = "workspace_pipeline_runs_idempotency_key_length")]
     IdempotencyKeyLength,
+    #[strum(serialize = "workspace_pipeline_runs_input_digest_length")]
+    InputDigestLength,
+
+    // Business logic constraints
+    #[strum(serialize = "workspace_pipeline_runs_input_digest_analyzed")]
+    InputDigestAnalyzed,
+    #[strum(serialize = "workspace_pipeline_runs_outputs_immutable")]
+    OutputsImmutable,
 
     // Chronological constraints
     #[strum(serialize = "workspace_pipeline_runs_completed_after_started")]
@@ -34,8 +42,12 @@ impl WorkspacePipelineRunConstraints {
         match self {
             WorkspacePipelineRunConstraints::AnalyzedDocumentKeyLength
             | WorkspacePipelineRunConstraints::MetadataSize
-            | WorkspacePipelineRunConstraints::IdempotencyKeyLength => {
-                ConstraintCategory::Validation
+            | WorkspacePipelineRunConstraints::IdempotencyKeyLength
+            | WorkspacePipelineRunConstraints::InputDigestLength => ConstraintCategory::Validation,
+
+            WorkspacePipelineRunConstraints::InputDigestAnalyzed
+            | WorkspacePipelineRunConstraints::OutputsImmutable => {
+                ConstraintCategory::BusinessLogic
             }
 
             WorkspacePipelineRunConstraints::CompletedAfterStarted => {
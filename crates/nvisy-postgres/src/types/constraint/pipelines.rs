@@ -40,6 +40,14 @@ pub enum WorkspacePipelineConstraints {
     #[strum(serialize = "workspace_pipelines_schedule_tz_length")]
     ScheduleTzLength,
 
+    // Pipeline artifact retention constraints
+    #[strum(serialize = "workspace_pipelines_input_retention_days_range")]
+    InputRetentionDaysRange,
+    #[strum(serialize = "workspace_pipelines_intermediate_retention_days_range")]
+    IntermediateRetentionDaysRange,
+    #[strum(serialize = "workspace_pipelines_output_retention_days_range")]
+    OutputRetentionDaysRange,
+
     // Uniqueness constraints
     #[strum(serialize = "workspace_pipelines_workspace_id_id_key")]
     WorkspaceIdIdUnique,
@@ -70,7 +78,12 @@ impl WorkspacePipelineConstraints {
             | WorkspacePipelineConstraints::MetadataSize
             | WorkspacePipelineConstraints::ScheduleCronLength
             | WorkspacePipelineConstraints::ScheduleRequiresCron
-            | WorkspacePipelineConstraints::ScheduleTzLength => ConstraintCategory::Validation,
+            | WorkspacePipelineConstraints::ScheduleTzLength
+            | WorkspacePipelineConstraints::InputRetentionDaysRange
+            | WorkspacePipelineConstraints::IntermediateRetentionDaysRange
+            | WorkspacePipelineConstraints::OutputRetentionDaysRange => {
+                ConstraintCategory::Validation
+            }
 
             WorkspacePipelineConstraints::WorkspaceIdIdUnique
             | WorkspacePipelineConstraints::SlugUnique => ConstraintCategory::Uniqueness,
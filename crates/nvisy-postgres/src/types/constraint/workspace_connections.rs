@@ -21,6 +21,8 @@ pub enum WorkspaceConnectionConstraints {
     // Data validation constraints
     #[strum(serialize = "workspace_connections_data_size")]
     DataSize,
+    #[strum(serialize = "workspace_connections_credential_key_version_min")]
+    CredentialKeyVersionMin,
 
     // Metadata validation constraints
     #[strum(serialize = "workspace_connections_metadata_size")]
@@ -51,6 +53,7 @@ impl WorkspaceConnectionConstraints {
             WorkspaceConnectionConstraints::NameLength
             | WorkspaceConnectionConstraints::ProviderLength
             | WorkspaceConnectionConstraints::DataSize
+            | WorkspaceConnectionConstraints::CredentialKeyVersionMin
             | WorkspaceConnectionConstraints::MetadataSize => ConstraintCategory::Validation,
 
             WorkspaceConnectionConstraints::WorkspaceIdIdUnique
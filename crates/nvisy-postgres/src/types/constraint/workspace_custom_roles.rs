@@ -0,0 +1,72 @@
+//! Workspace custom roles table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace custom roles table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceCustomRoleConstraints {
+    // Validation constraints
+    #[strum(serialize = "workspace_custom_roles_name_length")]
+    NameLength,
+    #[strum(serialize = "workspace_custom_roles_description_length")]
+    DescriptionLength,
+    #[strum(serialize = "workspace_custom_roles_permissions_count")]
+    PermissionsCount,
+    #[strum(serialize = "workspace_custom_roles_permissions_format")]
+    PermissionsFormat,
+
+    // Uniqueness constraints
+    #[strum(serialize = "workspace_custom_roles_workspace_id_id_key")]
+    WorkspaceIdIdUnique,
+    #[strum(serialize = "workspace_custom_roles_name_unique_idx")]
+    NameUnique,
+
+    // Chronological constraints
+    #[strum(serialize = "workspace_custom_roles_updated_after_created")]
+    UpdatedAfterCreated,
+}
+
+impl WorkspaceCustomRoleConstraints {
+    /// Creates a new [`WorkspaceCustomRoleConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceCustomRoleConstraints::NameLength
+            | WorkspaceCustomRoleConstraints::DescriptionLength
+            | WorkspaceCustomRoleConstraints::PermissionsCount
+            | WorkspaceCustomRoleConstraints::PermissionsFormat => ConstraintCategory::Validation,
+
+            WorkspaceCustomRoleConstraints::WorkspaceIdIdUnique
+            | WorkspaceCustomRoleConstraints::NameUnique => ConstraintCategory::Uniqueness,
+
+            WorkspaceCustomRoleConstraints::UpdatedAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceCustomRoleConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceCustomRoleConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceCustomRoleConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,78 @@
+//! Workspace file shares table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace file shares table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceFileShareConstraints {
+    // Validation constraints
+    #[strum(serialize = "workspace_file_shares_recipient_length")]
+    RecipientLength,
+    #[strum(serialize = "workspace_file_shares_pages_range")]
+    PagesRange,
+    #[strum(serialize = "workspace_file_shares_token_hash_length")]
+    TokenHashLength,
+    #[strum(serialize = "workspace_file_shares_max_views_min")]
+    MaxViewsMin,
+    #[strum(serialize = "workspace_file_shares_view_count_range")]
+    ViewCountRange,
+
+    // Uniqueness constraints
+    #[strum(serialize = "workspace_file_shares_token_hash_unique")]
+    TokenHashUnique,
+
+    // Chronological constraints
+    #[strum(serialize = "workspace_file_shares_expires_after_created")]
+    ExpiresAfterCreated,
+    #[strum(serialize = "workspace_file_shares_last_viewed_after_created")]
+    LastViewedAfterCreated,
+    #[strum(serialize = "workspace_file_shares_revoked_after_created")]
+    RevokedAfterCreated,
+}
+
+impl WorkspaceFileShareConstraints {
+    /// Creates a new [`WorkspaceFileShareConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceFileShareConstraints::RecipientLength
+            | WorkspaceFileShareConstraints::PagesRange
+            | WorkspaceFileShareConstraints::TokenHashLength
+            | WorkspaceFileShareConstraints::MaxViewsMin
+            | WorkspaceFileShareConstraints::ViewCountRange => ConstraintCategory::Validation,
+
+            WorkspaceFileShareConstraints::TokenHashUnique => ConstraintCategory::Uniqueness,
+
+            WorkspaceFileShareConstraints::ExpiresAfterCreated
+            | WorkspaceFileShareConstraints::LastViewedAfterCreated
+            | WorkspaceFileShareConstraints::RevokedAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceFileShareConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceFileShareConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceFileShareConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,57 @@
+//! Workspace inbound webhooks table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace inbound webhooks table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceInboundWebhookConstraints {
+    // Inbound webhook validation constraints
+    #[strum(serialize = "workspace_inbound_webhooks_display_name_length")]
+    DisplayNameLength,
+
+    // Inbound webhook chronological constraints
+    #[strum(serialize = "workspace_inbound_webhooks_updated_after_created")]
+    UpdatedAfterCreated,
+    #[strum(serialize = "workspace_inbound_webhooks_deleted_after_created")]
+    DeletedAfterCreated,
+}
+
+impl WorkspaceInboundWebhookConstraints {
+    /// Creates a new [`WorkspaceInboundWebhookConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceInboundWebhookConstraints::DisplayNameLength => ConstraintCategory::Validation,
+
+            WorkspaceInboundWebhookConstraints::UpdatedAfterCreated
+            | WorkspaceInboundWebhookConstraints::DeletedAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceInboundWebhookConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceInboundWebhookConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceInboundWebhookConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,63 @@
+//! Workspace legal holds table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace legal holds table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceLegalHoldConstraints {
+    // Validation constraints
+    #[strum(serialize = "workspace_legal_holds_reason_length")]
+    ReasonLength,
+
+    // Chronological constraints
+    #[strum(serialize = "workspace_legal_holds_released_after_created")]
+    ReleasedAfterCreated,
+
+    // Business logic constraints (raised by the hold triggers)
+    #[strum(serialize = "workspace_legal_holds_file_held")]
+    FileHeld,
+    #[strum(serialize = "workspace_legal_holds_workspace_held")]
+    WorkspaceHeld,
+}
+
+impl WorkspaceLegalHoldConstraints {
+    /// Creates a new [`WorkspaceLegalHoldConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceLegalHoldConstraints::ReasonLength => ConstraintCategory::Validation,
+
+            WorkspaceLegalHoldConstraints::ReleasedAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+
+            WorkspaceLegalHoldConstraints::FileHeld
+            | WorkspaceLegalHoldConstraints::WorkspaceHeld => ConstraintCategory::BusinessLogic,
+        }
+    }
+}
+
+impl From<WorkspaceLegalHoldConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceLegalHoldConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceLegalHoldConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
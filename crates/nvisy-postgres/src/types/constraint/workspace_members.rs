@@ -13,6 +13,10 @@ pub enum WorkspaceMemberConstraints {
     // Member chronological constraints
     #[strum(serialize = "workspace_members_updated_after_created")]
     UpdatedAfterCreated,
+
+    // Member foreign-key constraints (custom role must exist in the workspace)
+    #[strum(serialize = "workspace_members_custom_role_fkey")]
+    CustomRoleReference,
 }
 
 impl WorkspaceMemberConstraints {
@@ -25,6 +29,7 @@ impl WorkspaceMemberConstraints {
     pub fn categorize(&self) -> ConstraintCategory {
         match self {
             WorkspaceMemberConstraints::UpdatedAfterCreated => ConstraintCategory::Chronological,
+            WorkspaceMemberConstraints::CustomRoleReference => ConstraintCategory::BusinessLogic,
         }
     }
 }
@@ -0,0 +1,78 @@
+//! Workspace operations table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace operations table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceOperationConstraints {
+    // Operation validation constraints
+    #[strum(serialize = "workspace_operations_progress_range")]
+    ProgressRange,
+    #[strum(serialize = "workspace_operations_result_size")]
+    ResultSize,
+    #[strum(serialize = "workspace_operations_error_size")]
+    ErrorSize,
+    #[strum(serialize = "workspace_operations_artifact_complete")]
+    ArtifactComplete,
+    #[strum(serialize = "workspace_operations_artifact_name_length")]
+    ArtifactNameLength,
+    #[strum(serialize = "workspace_operations_artifact_size_min")]
+    ArtifactSizeMin,
+
+    // Operation chronological constraints
+    #[strum(serialize = "workspace_operations_updated_after_created")]
+    UpdatedAfterCreated,
+    #[strum(serialize = "workspace_operations_started_after_created")]
+    StartedAfterCreated,
+    #[strum(serialize = "workspace_operations_completed_after_created")]
+    CompletedAfterCreated,
+    #[strum(serialize = "workspace_operations_expires_after_created")]
+    ExpiresAfterCreated,
+}
+
+impl WorkspaceOperationConstraints {
+    /// Creates a new [`WorkspaceOperationConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceOperationConstraints::ProgressRange
+            | WorkspaceOperationConstraints::ResultSize
+            | WorkspaceOperationConstraints::ErrorSize
+            | WorkspaceOperationConstraints::ArtifactComplete
+            | WorkspaceOperationConstraints::ArtifactNameLength
+            | WorkspaceOperationConstraints::ArtifactSizeMin => ConstraintCategory::Validation,
+
+            WorkspaceOperationConstraints::UpdatedAfterCreated
+            | WorkspaceOperationConstraints::StartedAfterCreated
+            | WorkspaceOperationConstraints::CompletedAfterCreated
+            | WorkspaceOperationConstraints::ExpiresAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceOperationConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceOperationConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceOperationConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,62 @@
+//! Workspace retention policies table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace retention policies table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceRetentionPolicyConstraints {
+    // Validation constraints
+    #[strum(serialize = "workspace_retention_policies_file_retention_days_range")]
+    FileRetentionDaysRange,
+    #[strum(serialize = "workspace_retention_policies_run_retention_days_range")]
+    RunRetentionDaysRange,
+    #[strum(serialize = "workspace_retention_policies_activity_retention_days_range")]
+    ActivityRetentionDaysRange,
+
+    // Chronological constraints
+    #[strum(serialize = "workspace_retention_policies_updated_after_created")]
+    UpdatedAfterCreated,
+}
+
+impl WorkspaceRetentionPolicyConstraints {
+    /// Creates a new [`WorkspaceRetentionPolicyConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceRetentionPolicyConstraints::FileRetentionDaysRange
+            | WorkspaceRetentionPolicyConstraints::RunRetentionDaysRange
+            | WorkspaceRetentionPolicyConstraints::ActivityRetentionDaysRange => {
+                ConstraintCategory::Validation
+            }
+
+            WorkspaceRetentionPolicyConstraints::UpdatedAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceRetentionPolicyConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceRetentionPolicyConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceRetentionPolicyConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
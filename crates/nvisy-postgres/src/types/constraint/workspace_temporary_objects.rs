@@ -0,0 +1,65 @@
+//! Workspace temporary objects table constraint violations.
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::ConstraintCategory;
+
+/// Workspace temporary objects table constraint violations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Display, EnumIter, EnumString)]
+#[serde(into = "String", try_from = "String")]
+pub enum WorkspaceTemporaryObjectConstraints {
+    // Validation constraints
+    #[strum(serialize = "workspace_temporary_objects_bucket_length")]
+    BucketLength,
+    #[strum(serialize = "workspace_temporary_objects_object_key_length")]
+    ObjectKeyLength,
+
+    // Uniqueness constraints
+    #[strum(serialize = "workspace_temporary_objects_object_unique")]
+    ObjectUnique,
+
+    // Chronological constraints
+    #[strum(serialize = "workspace_temporary_objects_expires_after_created")]
+    ExpiresAfterCreated,
+}
+
+impl WorkspaceTemporaryObjectConstraints {
+    /// Creates a new [`WorkspaceTemporaryObjectConstraints`] from the constraint name.
+    pub fn new(constraint: &str) -> Option<Self> {
+        constraint.parse().ok()
+    }
+
+    /// Returns the category of this constraint violation.
+    pub fn categorize(&self) -> ConstraintCategory {
+        match self {
+            WorkspaceTemporaryObjectConstraints::BucketLength
+            | WorkspaceTemporaryObjectConstraints::ObjectKeyLength => {
+                ConstraintCategory::Validation
+            }
+
+            WorkspaceTemporaryObjectConstraints::ObjectUnique => ConstraintCategory::Uniqueness,
+
+            WorkspaceTemporaryObjectConstraints::ExpiresAfterCreated => {
+                ConstraintCategory::Chronological
+            }
+        }
+    }
+}
+
+impl From<WorkspaceTemporaryObjectConstraints> for String {
+    #[inline]
+    fn from(val: WorkspaceTemporaryObjectConstraints) -> Self {
+        val.to_string()
+    }
+}
+
+impl TryFrom<String> for WorkspaceTemporaryObjectConstraints {
+    type Error = strum::ParseError;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -27,6 +27,8 @@ pub enum WorkspaceWebhookConstraints {
     EventsNotEmpty,
     #[strum(serialize = "workspace_webhooks_headers_size")]
     HeadersSize,
+    #[strum(serialize = "workspace_webhooks_payload_filters_count")]
+    PayloadFiltersCount,
 
     // Webhook chronological constraints
     #[strum(serialize = "workspace_webhooks_updated_after_created")]
@@ -51,7 +53,8 @@ impl WorkspaceWebhookConstraints {
             | WorkspaceWebhookConstraints::UrlLength
             | WorkspaceWebhookConstraints::UrlFormat
             | WorkspaceWebhookConstraints::EventsNotEmpty
-            | WorkspaceWebhookConstraints::HeadersSize => ConstraintCategory::Validation,
+            | WorkspaceWebhookConstraints::HeadersSize
+            | WorkspaceWebhookConstraints::PayloadFiltersCount => ConstraintCategory::Validation,
 
             WorkspaceWebhookConstraints::UpdatedAfterCreated
             | WorkspaceWebhookConstraints::DeletedAfterCreated => ConstraintCategory::Chronological,
@@ -0,0 +1,184 @@
+//! Connection credentials and the cipher that seals them at rest.
+//!
+//! Credentials never reach the database in the clear: repository methods
+//! that write them seal them with a [`CredentialCipher`] first, and methods
+//! that read them open them again, so callers only ever handle
+//! [`ConnectionCredentials`]. Each sealed value records the key version it
+//! was sealed under, which lets a cipher keep opening values sealed under
+//! retired keys while they are resealed under the current one.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+/// Placeholder written in place of credentials in debug and serialized
+/// output.
+const REDACTED: &str = "[REDACTED]";
+
+/// Result type for credential sealing and opening.
+pub type CredentialResult<T> = Result<T, CredentialError>;
+
+/// Errors sealing or opening stored credentials.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CredentialError {
+    /// The credentials were sealed under a key version the cipher does not
+    /// hold.
+    #[error("credential key version {0} is not available")]
+    UnknownKeyVersion(i32),
+    /// The sealed value is truncated or its header does not match its row.
+    #[error("sealed credentials are malformed")]
+    Malformed,
+    /// Sealing failed.
+    #[error("credentials could not be sealed")]
+    SealFailed,
+    /// Opening failed: the value was tampered with or sealed for another
+    /// workspace.
+    #[error("credentials could not be opened")]
+    OpenFailed,
+    /// The opened credentials are not valid JSON.
+    #[error("credentials are not valid JSON: {0}")]
+    Json(String),
+}
+
+/// Credentials sealed under a vault key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SealedCredentials {
+    /// Version of the key the credentials were sealed under.
+    pub key_version: i32,
+    /// The sealed credentials, as stored.
+    pub ciphertext: Vec<u8>,
+}
+
+impl fmt::Debug for SealedCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SealedCredentials")
+            .field("key_version", &self.key_version)
+            .field(
+                "ciphertext",
+                &format_args!("[{} bytes]", self.ciphertext.len()),
+            )
+            .finish()
+    }
+}
+
+/// Seals and opens connection credentials for the repository layer.
+///
+/// Implementations hold the key material; the repository only decides
+/// when to call them. Credentials are bound to their workspace, so a value
+/// copied to another workspace's row does not open.
+pub trait CredentialCipher: Send + Sync {
+    /// Returns the key version new credentials are sealed under.
+    fn key_version(&self) -> i32;
+
+    /// Seals `plaintext` for `workspace_id` under the current key version.
+    fn seal(&self, workspace_id: Uuid, plaintext: &[u8]) -> CredentialResult<SealedCredentials>;
+
+    /// Opens `ciphertext`, sealed for `workspace_id` under `key_version`.
+    fn open(
+        &self,
+        workspace_id: Uuid,
+        key_version: i32,
+        ciphertext: &[u8],
+    ) -> CredentialResult<Vec<u8>>;
+}
+
+/// Plaintext connection credentials: provider credentials and sync context.
+///
+/// Deserializes from any JSON value, but never shows its contents: debug
+/// output and serialization both yield a placeholder. Use
+/// [`expose`](Self::expose) where the actual values are needed.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct ConnectionCredentials(serde_json::Value);
+
+impl ConnectionCredentials {
+    /// Wraps credential values.
+    #[inline]
+    pub fn new(value: serde_json::Value) -> Self {
+        Self(value)
+    }
+
+    /// Returns the credential values.
+    #[inline]
+    pub fn expose(&self) -> &serde_json::Value {
+        &self.0
+    }
+
+    /// Returns the credential values, consuming the wrapper.
+    #[inline]
+    pub fn into_inner(self) -> serde_json::Value {
+        self.0
+    }
+
+    /// Encodes the credentials as JSON for sealing.
+    pub(crate) fn to_bytes(&self) -> CredentialResult<Vec<u8>> {
+        serde_json::to_vec(&self.0).map_err(|err| CredentialError::Json(err.to_string()))
+    }
+
+    /// Decodes opened credentials.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> CredentialResult<Self> {
+        serde_json::from_slice(bytes)
+            .map(Self)
+            .map_err(|err| CredentialError::Json(err.to_string()))
+    }
+}
+
+impl fmt::Debug for ConnectionCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConnectionCredentials")
+            .field(&format_args!("{REDACTED}"))
+            .finish()
+    }
+}
+
+impl Serialize for ConnectionCredentials {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for ConnectionCredentials {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "ConnectionCredentials".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Provider credentials and sync context; the shape depends on the provider. Write-only.",
+            "writeOnly": true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn credentials_are_redacted() {
+        let credentials: ConnectionCredentials =
+            serde_json::from_value(json!({ "password": "hunter2" })).unwrap();
+
+        assert_eq!(credentials.expose()["password"], "hunter2");
+        assert!(!format!("{credentials:?}").contains("hunter2"));
+        assert_eq!(serde_json::to_value(&credentials).unwrap(), json!(REDACTED));
+    }
+
+    #[test]
+    fn credentials_round_trip_through_bytes() {
+        let credentials = ConnectionCredentials::new(json!({ "token": "abc", "cursor": 42 }));
+        let bytes = credentials.to_bytes().unwrap();
+        assert_eq!(
+            ConnectionCredentials::from_bytes(&bytes).unwrap(),
+            credentials
+        );
+        assert!(matches!(
+            ConnectionCredentials::from_bytes(b"{"),
+            Err(CredentialError::Json(_))
+        ));
+    }
+}
@@ -137,6 +137,33 @@ pub enum ActivityType {
     #[serde(rename = "file:verified")]
     FileVerified,
 
+    // Share link activities
+    /// Share link was created
+    #[db_rename = "share:created"]
+    #[serde(rename = "share:created")]
+    ShareCreated,
+
+    /// Share link was opened, or an attempt to open it was refused
+    #[db_rename = "share:accessed"]
+    #[serde(rename = "share:accessed")]
+    ShareAccessed,
+
+    /// Share link was revoked
+    #[db_rename = "share:revoked"]
+    #[serde(rename = "share:revoked")]
+    ShareRevoked,
+
+    // Legal hold activities
+    /// Legal hold was placed on the workspace or a file
+    #[db_rename = "legal_hold:placed"]
+    #[serde(rename = "legal_hold:placed")]
+    LegalHoldPlaced,
+
+    /// Legal hold was released
+    #[db_rename = "legal_hold:released"]
+    #[serde(rename = "legal_hold:released")]
+    LegalHoldReleased,
+
     // Custom activities
     /// Custom activity type for extensibility
     #[db_rename = "custom"]
@@ -178,6 +205,14 @@ impl ActivityType {
             | ActivityType::FileDeleted
             | ActivityType::FileVerified => ActivityCategory::File,
 
+            ActivityType::ShareCreated
+            | ActivityType::ShareAccessed
+            | ActivityType::ShareRevoked => ActivityCategory::Share,
+
+            ActivityType::LegalHoldPlaced | ActivityType::LegalHoldReleased => {
+                ActivityCategory::LegalHold
+            }
+
             ActivityType::Custom => ActivityCategory::Custom,
         }
     }
@@ -192,6 +227,7 @@ impl ActivityType {
                 | ActivityType::ConnectionCreated
                 | ActivityType::WebhookCreated
                 | ActivityType::FileCreated
+                | ActivityType::ShareCreated
         )
     }
 
@@ -213,13 +249,22 @@ impl ActivityType {
     pub fn is_security_sensitive(self) -> bool {
         matches!(
             self.category(),
-            ActivityCategory::Member | ActivityCategory::Invite
+            ActivityCategory::Member
+                | ActivityCategory::Invite
+                | ActivityCategory::Share
+                | ActivityCategory::LegalHold
         )
     }
 }
 
 /// Categories for grouping activity types.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+///
+/// Each category names the kind of resource its activities act on.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 pub enum ActivityCategory {
     /// Workspace-related activities
     Workspace,
@@ -233,6 +278,10 @@ pub enum ActivityCategory {
     Webhook,
     /// File-related activities
     File,
+    /// Share link activities
+    Share,
+    /// Legal hold activities
+    LegalHold,
     /// Custom activities
     Custom,
 }
@@ -0,0 +1,72 @@
+//! API key scope enumeration for programmatic access control.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+use super::WorkspaceRole;
+
+/// Defines a permission scope granted to an API key.
+///
+/// This enumeration corresponds to the `API_KEY_SCOPE` PostgreSQL enum. A key
+/// acts with its owner's workspace roles, capped by its broadest scope: a
+/// `read` key can only exercise permissions open to guests, even when its owner
+/// is a workspace admin.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::ApiKeyScope"]
+pub enum ApiKeyScope {
+    /// View and download workspace resources
+    #[db_rename = "read"]
+    #[serde(rename = "read")]
+    #[strum(serialize = "read")]
+    #[default]
+    Read,
+
+    /// Create, update and run workspace resources
+    #[db_rename = "write"]
+    #[serde(rename = "write")]
+    #[strum(serialize = "write")]
+    Write,
+
+    /// Delete resources and manage members, roles and settings
+    #[db_rename = "admin"]
+    #[serde(rename = "admin")]
+    #[strum(serialize = "admin")]
+    Admin,
+}
+
+impl ApiKeyScope {
+    /// Returns the highest workspace role whose permissions this scope covers.
+    #[inline]
+    pub const fn max_role(self) -> WorkspaceRole {
+        match self {
+            ApiKeyScope::Read => WorkspaceRole::Guest,
+            ApiKeyScope::Write => WorkspaceRole::Member,
+            ApiKeyScope::Admin => WorkspaceRole::Owner,
+        }
+    }
+
+    /// Returns whether this scope covers permissions requiring `role`.
+    #[inline]
+    pub const fn covers(self, role: WorkspaceRole) -> bool {
+        self.max_role().has_permission_level_of(role)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_covers_roles() {
+        assert!(ApiKeyScope::Read.covers(WorkspaceRole::Guest));
+        assert!(!ApiKeyScope::Read.covers(WorkspaceRole::Member));
+        assert!(ApiKeyScope::Write.covers(WorkspaceRole::Member));
+        assert!(!ApiKeyScope::Write.covers(WorkspaceRole::Admin));
+        assert!(ApiKeyScope::Admin.covers(WorkspaceRole::Owner));
+    }
+}
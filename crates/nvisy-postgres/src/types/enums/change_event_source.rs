@@ -0,0 +1,40 @@
+//! Change event source enumeration indicating who recorded an event.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines who recorded a workspace change event.
+///
+/// This enumeration corresponds to the `CHANGE_EVENT_SOURCE` PostgreSQL enum.
+/// Application events are recorded with the change they describe and fan out
+/// to webhooks; database events are recorded by triggers for changes made
+/// outside the application and only reach real-time subscribers.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::ChangeEventSource"]
+pub enum ChangeEventSource {
+    /// Recorded by a trigger for a change made outside the application
+    #[db_rename = "database"]
+    #[serde(rename = "database")]
+    #[strum(serialize = "database")]
+    #[default]
+    Database,
+
+    /// Recorded by the application with the change it describes
+    #[db_rename = "application"]
+    #[serde(rename = "application")]
+    #[strum(serialize = "application")]
+    Application,
+}
+
+impl ChangeEventSource {
+    /// Returns whether the event was recorded by the application.
+    #[inline]
+    pub fn is_application(self) -> bool {
+        matches!(self, ChangeEventSource::Application)
+    }
+}
@@ -0,0 +1,58 @@
+//! Data region enumeration for workspace data residency.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines where a workspace's data is stored and processed.
+///
+/// This enumeration corresponds to the `DATA_REGION` PostgreSQL enum. A
+/// workspace's region is chosen at creation and routes its object storage and
+/// inference to backends pinned to that region.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::DataRegion"]
+pub enum DataRegion {
+    /// No residency requirement; served by the default backends
+    #[db_rename = "global"]
+    #[serde(rename = "global")]
+    #[strum(serialize = "global")]
+    #[default]
+    Global,
+
+    /// European Union
+    #[db_rename = "eu"]
+    #[serde(rename = "eu")]
+    #[strum(serialize = "eu")]
+    Eu,
+
+    /// United States
+    #[db_rename = "us"]
+    #[serde(rename = "us")]
+    #[strum(serialize = "us")]
+    Us,
+}
+
+impl DataRegion {
+    /// Returns whether the workspace's data is pinned to a specific region.
+    #[inline]
+    pub fn is_pinned(self) -> bool {
+        !matches!(self, DataRegion::Global)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_region_roundtrip() {
+        assert_eq!(DataRegion::Eu.to_string(), "eu");
+        assert_eq!("us".parse::<DataRegion>().unwrap(), DataRegion::Us);
+        assert!(!DataRegion::Global.is_pinned());
+        assert!(DataRegion::Eu.is_pinned());
+    }
+}
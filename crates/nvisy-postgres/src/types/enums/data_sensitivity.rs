@@ -0,0 +1,69 @@
+//! Data sensitivity enumeration for file content protection.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines how sensitive a file's content is.
+///
+/// This enumeration corresponds to the `DATA_SENSITIVITY` PostgreSQL enum.
+/// All content is encrypted at rest; high and critical content additionally
+/// gets its own data key, wrapped through the key management service.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::DataSensitivity"]
+pub enum DataSensitivity {
+    /// Public or non-sensitive content
+    #[db_rename = "low"]
+    #[serde(rename = "low")]
+    #[strum(serialize = "low")]
+    Low,
+
+    /// Internal content
+    #[db_rename = "medium"]
+    #[serde(rename = "medium")]
+    #[strum(serialize = "medium")]
+    #[default]
+    Medium,
+
+    /// Confidential content
+    #[db_rename = "high"]
+    #[serde(rename = "high")]
+    #[strum(serialize = "high")]
+    High,
+
+    /// Restricted content
+    #[db_rename = "critical"]
+    #[serde(rename = "critical")]
+    #[strum(serialize = "critical")]
+    Critical,
+}
+
+impl DataSensitivity {
+    /// Returns whether content at this level is envelope encrypted with a
+    /// per-object data key.
+    #[inline]
+    pub fn requires_envelope_encryption(self) -> bool {
+        self >= DataSensitivity::High
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_sensitivity_envelope() {
+        assert!(!DataSensitivity::Low.requires_envelope_encryption());
+        assert!(!DataSensitivity::default().requires_envelope_encryption());
+        assert!(DataSensitivity::High.requires_envelope_encryption());
+        assert!(DataSensitivity::Critical.requires_envelope_encryption());
+        assert_eq!(
+            "critical".parse::<DataSensitivity>().unwrap(),
+            DataSensitivity::Critical
+        );
+    }
+}
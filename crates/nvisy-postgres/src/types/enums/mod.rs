@@ -5,12 +5,20 @@
 //! and database integration through Diesel.
 
 // Account-related enumerations
+pub mod api_key_scope;
 pub mod api_token_type;
 pub mod notification_event;
 
 // Workspace-related enumerations
 pub mod activity_type;
+pub mod change_event_source;
+pub mod data_region;
+pub mod inbound_webhook_provider;
 pub mod invite_status;
+pub mod operation_kind;
+pub mod operation_status;
+pub mod storage_class;
+pub mod storage_stage;
 pub mod sync_status;
 pub mod sync_trigger_type;
 pub mod webhook_event;
@@ -18,6 +26,7 @@ pub mod webhook_status;
 pub mod workspace_role;
 
 // File-related enumerations
+pub mod data_sensitivity;
 pub mod file_source;
 
 // Pipeline-related enumerations
@@ -25,16 +34,27 @@ pub mod artifact_type;
 pub mod pipeline_run_status;
 pub mod pipeline_status;
 pub mod pipeline_trigger_type;
+pub mod review_status;
 
 pub use activity_type::{ActivityCategory, ActivityType};
+pub use api_key_scope::ApiKeyScope;
 pub use api_token_type::ApiTokenType;
 pub use artifact_type::ArtifactType;
+pub use change_event_source::ChangeEventSource;
+pub use data_region::DataRegion;
+pub use data_sensitivity::DataSensitivity;
 pub use file_source::FileSource;
+pub use inbound_webhook_provider::InboundWebhookProvider;
 pub use invite_status::InviteStatus;
 pub use notification_event::NotificationEvent;
+pub use operation_kind::OperationKind;
+pub use operation_status::OperationStatus;
 pub use pipeline_run_status::PipelineRunStatus;
 pub use pipeline_status::PipelineStatus;
 pub use pipeline_trigger_type::PipelineTriggerType;
+pub use review_status::ReviewStatus;
+pub use storage_class::StorageClass;
+pub use storage_stage::StorageStage;
 pub use sync_status::SyncStatus;
 pub use sync_trigger_type::SyncTriggerType;
 pub use webhook_event::WebhookEvent;
@@ -0,0 +1,23 @@
+//! Operation kind enumeration for long-running operations.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines the kind of job a long-running operation tracks.
+///
+/// This enumeration corresponds to the `OPERATION_KIND` PostgreSQL enum. The
+/// kind tells clients how to interpret the operation's target and result.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::OperationKind"]
+pub enum OperationKind {
+    /// Detect pass of a pipeline run; the target is the run
+    #[db_rename = "pipeline_detect"]
+    #[serde(rename = "pipeline_detect")]
+    #[strum(serialize = "pipeline_detect")]
+    PipelineDetect,
+}
@@ -0,0 +1,69 @@
+//! Operation status enumeration for long-running operations.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines the lifecycle status of a long-running operation.
+///
+/// This enumeration corresponds to the `OPERATION_STATUS` PostgreSQL enum.
+/// Operations move from pending to running and end in exactly one of the
+/// terminal statuses.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::OperationStatus"]
+pub enum OperationStatus {
+    /// Operation is accepted but has not started
+    #[db_rename = "pending"]
+    #[serde(rename = "pending")]
+    #[strum(serialize = "pending")]
+    #[default]
+    Pending,
+
+    /// Operation is in progress
+    #[db_rename = "running"]
+    #[serde(rename = "running")]
+    #[strum(serialize = "running")]
+    Running,
+
+    /// Operation finished with a result
+    #[db_rename = "succeeded"]
+    #[serde(rename = "succeeded")]
+    #[strum(serialize = "succeeded")]
+    Succeeded,
+
+    /// Operation finished with an error
+    #[db_rename = "failed"]
+    #[serde(rename = "failed")]
+    #[strum(serialize = "failed")]
+    Failed,
+
+    /// Operation was stopped before finishing
+    #[db_rename = "cancelled"]
+    #[serde(rename = "cancelled")]
+    #[strum(serialize = "cancelled")]
+    Cancelled,
+}
+
+impl OperationStatus {
+    /// Returns whether the operation has not reached a terminal status.
+    #[inline]
+    pub fn is_in_progress(self) -> bool {
+        matches!(self, OperationStatus::Pending | OperationStatus::Running)
+    }
+
+    /// Returns whether the operation reached a terminal status.
+    #[inline]
+    pub fn is_terminal(self) -> bool {
+        !self.is_in_progress()
+    }
+
+    /// Returns whether the operation finished with a result.
+    #[inline]
+    pub fn is_succeeded(self) -> bool {
+        matches!(self, OperationStatus::Succeeded)
+    }
+}
@@ -0,0 +1,69 @@
+//! Review status enumeration for detected findings.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines the review status of a finding detected by a pipeline run.
+///
+/// This enumeration corresponds to the `REVIEW_STATUS` PostgreSQL enum.
+/// A finding is proposed by detection until a reviewer accepts or rejects
+/// it; every later decision supersedes the one before it.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::ReviewStatus"]
+pub enum ReviewStatus {
+    /// Finding was proposed by detection and awaits a decision
+    #[db_rename = "proposed"]
+    #[serde(rename = "proposed")]
+    #[strum(serialize = "proposed")]
+    #[default]
+    Proposed,
+
+    /// Finding was accepted by a reviewer
+    #[db_rename = "accepted"]
+    #[serde(rename = "accepted")]
+    #[strum(serialize = "accepted")]
+    Accepted,
+
+    /// Finding was rejected by a reviewer
+    #[db_rename = "rejected"]
+    #[serde(rename = "rejected")]
+    #[strum(serialize = "rejected")]
+    Rejected,
+
+    /// Decision was replaced by a later one
+    #[db_rename = "superseded"]
+    #[serde(rename = "superseded")]
+    #[strum(serialize = "superseded")]
+    Superseded,
+}
+
+impl ReviewStatus {
+    /// Returns whether a reviewer can record this status as a decision.
+    #[inline]
+    pub fn is_decision(self) -> bool {
+        !self.is_superseded()
+    }
+
+    /// Returns whether the finding was accepted.
+    #[inline]
+    pub fn is_accepted(self) -> bool {
+        matches!(self, ReviewStatus::Accepted)
+    }
+
+    /// Returns whether the finding was rejected.
+    #[inline]
+    pub fn is_rejected(self) -> bool {
+        matches!(self, ReviewStatus::Rejected)
+    }
+
+    /// Returns whether the decision was replaced by a later one.
+    #[inline]
+    pub fn is_superseded(self) -> bool {
+        matches!(self, ReviewStatus::Superseded)
+    }
+}
@@ -0,0 +1,31 @@
+//! Storage class enumeration for storage cost attribution.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines the storage class an object is priced at.
+///
+/// This enumeration corresponds to the `STORAGE_CLASS` PostgreSQL enum. The
+/// class is assigned by age: objects left untouched past the cold threshold
+/// are priced as cold storage.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::StorageClass"]
+pub enum StorageClass {
+    /// Recently written objects
+    #[db_rename = "hot"]
+    #[serde(rename = "hot")]
+    #[strum(serialize = "hot")]
+    #[default]
+    Hot,
+
+    /// Objects left untouched past the cold threshold
+    #[db_rename = "cold"]
+    #[serde(rename = "cold")]
+    #[strum(serialize = "cold")]
+    Cold,
+}
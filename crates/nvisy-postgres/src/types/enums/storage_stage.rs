@@ -0,0 +1,48 @@
+//! Storage stage enumeration for storage cost attribution.
+
+use diesel_derive_enum::DbEnum;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Defines the pipeline stage an object is stored for.
+///
+/// This enumeration corresponds to the `STORAGE_STAGE` PostgreSQL enum. Each
+/// object storage bucket holds the objects of one stage, so storage costs
+/// can be split by what the storage is used for.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, DbEnum, Display, EnumIter, EnumString)]
+#[ExistingTypePath = "crate::schema::sql_types::StorageStage"]
+pub enum StorageStage {
+    /// Uploaded and imported document content
+    #[db_rename = "upload"]
+    #[serde(rename = "upload")]
+    #[strum(serialize = "upload")]
+    Upload,
+
+    /// Analyses held between pipeline steps
+    #[db_rename = "intermediate"]
+    #[serde(rename = "intermediate")]
+    #[strum(serialize = "intermediate")]
+    Intermediate,
+
+    /// Document previews
+    #[db_rename = "thumbnail"]
+    #[serde(rename = "thumbnail")]
+    #[strum(serialize = "thumbnail")]
+    Thumbnail,
+
+    /// Detection context files
+    #[db_rename = "context"]
+    #[serde(rename = "context")]
+    #[strum(serialize = "context")]
+    Context,
+
+    /// Operation result artifacts
+    #[db_rename = "result"]
+    #[serde(rename = "result")]
+    #[strum(serialize = "result")]
+    Result,
+}
@@ -47,6 +47,28 @@ pub enum WebhookEvent {
     #[serde(rename = "member:updated")]
     MemberUpdated,
 
+    /// A member's role was changed
+    #[db_rename = "member:role_changed"]
+    #[serde(rename = "member:role_changed")]
+    MemberRoleChanged,
+
+    // Invite events
+    /// An invitation or invite code was created
+    #[db_rename = "invite:created"]
+    #[serde(rename = "invite:created")]
+    InviteCreated,
+
+    /// An invitation or invite code was accepted
+    #[db_rename = "invite:accepted"]
+    #[serde(rename = "invite:accepted")]
+    InviteAccepted,
+
+    // Workspace events
+    /// The workspace's settings were updated
+    #[db_rename = "workspace:updated"]
+    #[serde(rename = "workspace:updated")]
+    WorkspaceUpdated,
+
     // Connection events
     /// A connection was created
     #[db_rename = "connection:created"]
@@ -72,6 +94,17 @@ pub enum WebhookEvent {
     #[db_rename = "connection:desynced"]
     #[serde(rename = "connection:desynced")]
     ConnectionDesynced,
+
+    // Operation events
+    /// A long-running operation finished successfully
+    #[db_rename = "operation:succeeded"]
+    #[serde(rename = "operation:succeeded")]
+    OperationSucceeded,
+
+    /// A long-running operation failed
+    #[db_rename = "operation:failed"]
+    #[serde(rename = "operation:failed")]
+    OperationFailed,
 }
 
 impl WebhookEvent {
@@ -89,10 +122,28 @@ impl WebhookEvent {
     pub fn is_member_event(self) -> bool {
         matches!(
             self,
-            WebhookEvent::MemberAdded | WebhookEvent::MemberDeleted | WebhookEvent::MemberUpdated
+            WebhookEvent::MemberAdded
+                | WebhookEvent::MemberDeleted
+                | WebhookEvent::MemberUpdated
+                | WebhookEvent::MemberRoleChanged
+        )
+    }
+
+    /// Returns whether this is an invite-related event.
+    #[inline]
+    pub fn is_invite_event(self) -> bool {
+        matches!(
+            self,
+            WebhookEvent::InviteCreated | WebhookEvent::InviteAccepted
         )
     }
 
+    /// Returns whether this is a workspace-related event.
+    #[inline]
+    pub fn is_workspace_event(self) -> bool {
+        matches!(self, WebhookEvent::WorkspaceUpdated)
+    }
+
     /// Returns whether this is a connection-related event.
     #[inline]
     pub fn is_connection_event(self) -> bool {
@@ -106,6 +157,15 @@ impl WebhookEvent {
         )
     }
 
+    /// Returns whether this is an operation-related event.
+    #[inline]
+    pub fn is_operation_event(self) -> bool {
+        matches!(
+            self,
+            WebhookEvent::OperationSucceeded | WebhookEvent::OperationFailed
+        )
+    }
+
     /// Returns the event category as a string.
     pub fn category(&self) -> &'static str {
         match self {
@@ -114,12 +174,16 @@ impl WebhookEvent {
             }
             WebhookEvent::MemberAdded
             | WebhookEvent::MemberDeleted
-            | WebhookEvent::MemberUpdated => "member",
+            | WebhookEvent::MemberUpdated
+            | WebhookEvent::MemberRoleChanged => "member",
+            WebhookEvent::InviteCreated | WebhookEvent::InviteAccepted => "invite",
+            WebhookEvent::WorkspaceUpdated => "workspace",
             WebhookEvent::ConnectionCreated
             | WebhookEvent::ConnectionUpdated
             | WebhookEvent::ConnectionDeleted
             | WebhookEvent::ConnectionSynced
             | WebhookEvent::ConnectionDesynced => "connection",
+            WebhookEvent::OperationSucceeded | WebhookEvent::OperationFailed => "operation",
         }
     }
 
@@ -134,11 +198,43 @@ impl WebhookEvent {
             WebhookEvent::MemberAdded => "member.added",
             WebhookEvent::MemberDeleted => "member.deleted",
             WebhookEvent::MemberUpdated => "member.updated",
+            WebhookEvent::MemberRoleChanged => "member.role_changed",
+            WebhookEvent::InviteCreated => "invite.created",
+            WebhookEvent::InviteAccepted => "invite.accepted",
+            WebhookEvent::WorkspaceUpdated => "workspace.updated",
             WebhookEvent::ConnectionCreated => "connection.created",
             WebhookEvent::ConnectionUpdated => "connection.updated",
             WebhookEvent::ConnectionDeleted => "connection.deleted",
             WebhookEvent::ConnectionSynced => "connection.synced",
             WebhookEvent::ConnectionDesynced => "connection.desynced",
+            WebhookEvent::OperationSucceeded => "operation.succeeded",
+            WebhookEvent::OperationFailed => "operation.failed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_subject_matches_serialized_name() {
+        for event in WebhookEvent::iter() {
+            let name = serde_json::to_value(event).unwrap();
+            let name = name.as_str().unwrap();
+
+            assert_eq!(event.as_subject(), name.replace(':', "."));
+            assert_eq!(name.split(':').next(), Some(event.category()));
         }
     }
+
+    #[test]
+    fn test_membership_event_kinds() {
+        assert!(WebhookEvent::MemberRoleChanged.is_member_event());
+        assert!(WebhookEvent::InviteAccepted.is_invite_event());
+        assert!(!WebhookEvent::InviteAccepted.is_member_event());
+        assert!(WebhookEvent::WorkspaceUpdated.is_workspace_event());
+    }
 }
@@ -0,0 +1,105 @@
+//! Filtering options for workspace activity queries.
+
+use jiff::Timestamp;
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::types::{ActivityCategory, ActivityType, Username};
+
+/// Filter options for workspace activities.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ActivityFilter {
+    /// Filter by the handle of the account that performed the activity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<Username>,
+    /// Filter by activity type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_type: Option<ActivityType>,
+    /// Filter by the kind of resource acted on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<ActivityCategory>,
+    /// Filter by the resource acted on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<Uuid>,
+    /// Only include activities at or after this time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<Timestamp>,
+    /// Only include activities before this time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Timestamp>,
+}
+
+impl ActivityFilter {
+    /// Creates a new empty filter.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filters by actor.
+    #[inline]
+    pub fn with_actor(mut self, actor: Username) -> Self {
+        self.actor = Some(actor);
+        self
+    }
+
+    /// Filters by activity type.
+    #[inline]
+    pub fn with_activity_type(mut self, activity_type: ActivityType) -> Self {
+        self.activity_type = Some(activity_type);
+        self
+    }
+
+    /// Filters by resource kind.
+    #[inline]
+    pub fn with_category(mut self, category: ActivityCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Filters by resource.
+    #[inline]
+    pub fn with_resource_id(mut self, resource_id: Uuid) -> Self {
+        self.resource_id = Some(resource_id);
+        self
+    }
+
+    /// Restricts to the time range `[from, to)`; either bound may be open.
+    #[inline]
+    pub fn with_time_range(mut self, from: Option<Timestamp>, to: Option<Timestamp>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    /// Returns the activity types matching the type and category filters,
+    /// or `None` if neither is set.
+    pub fn activity_types(&self) -> Option<Vec<ActivityType>> {
+        use strum::IntoEnumIterator;
+
+        if self.activity_type.is_none() && self.category.is_none() {
+            return None;
+        }
+
+        let types = ActivityType::iter()
+            .filter(|t| self.activity_type.is_none_or(|wanted| *t == wanted))
+            .filter(|t| self.category.is_none_or(|wanted| t.category() == wanted))
+            .collect();
+
+        Some(types)
+    }
+
+    /// Returns whether no filter is active.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.actor.is_none()
+            && self.activity_type.is_none()
+            && self.category.is_none()
+            && self.resource_id.is_none()
+            && self.from.is_none()
+            && self.to.is_none()
+    }
+}
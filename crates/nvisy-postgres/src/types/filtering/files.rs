@@ -28,6 +28,28 @@ pub enum FileFormat {
 }
 
 impl FileFormat {
+    /// All file formats.
+    pub const ALL: [Self; 8] = [
+        Self::Pdf,
+        Self::Doc,
+        Self::Txt,
+        Self::Md,
+        Self::Csv,
+        Self::Json,
+        Self::Png,
+        Self::Jpeg,
+    ];
+
+    /// Returns the format a file extension (without the dot) belongs to.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| {
+            format
+                .extensions()
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        })
+    }
+
     /// Returns the file extensions associated with this format.
     pub fn extensions(&self) -> &'static [&'static str] {
         match self {
@@ -1,9 +1,11 @@
 //! Filtering options for database queries.
 
+mod activities;
 mod files;
 mod invites;
 mod members;
 
+pub use activities::ActivityFilter;
 pub use files::{FileFilter, FileFormat};
 pub use invites::InviteFilter;
 pub use members::MemberFilter;
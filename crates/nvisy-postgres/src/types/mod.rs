@@ -2,6 +2,7 @@
 
 mod constants;
 mod constraint;
+mod credential;
 mod enums;
 mod filtering;
 mod pagination;
@@ -16,22 +17,33 @@ pub use constants::{
     RECENTLY_SENT_HOURS, RECENTLY_UPLOADED_HOURS,
 };
 pub use constraint::{
-    AccountApiTokenConstraints, AccountConstraints, AccountNotificationConstraints,
-    ConstraintCategory, ConstraintViolation, WorkspaceActivitiesConstraints,
-    WorkspaceConnectionConstraints, WorkspaceConnectionRunConstraints, WorkspaceConstraints,
-    WorkspaceContextConstraints, WorkspaceFileConstraints, WorkspaceInviteConstraints,
-    WorkspaceMemberConstraints, WorkspacePipelineArtifactConstraints, WorkspacePipelineConstraints,
+    AccountApiKeyConstraints, AccountApiTokenConstraints, AccountConstraints,
+    AccountIdentityConstraints, AccountNotificationConstraints, ConstraintCategory,
+    ConstraintViolation, WorkspaceActivitiesConstraints, WorkspaceConnectionConstraints,
+    WorkspaceConnectionRunConstraints, WorkspaceConstraints, WorkspaceContextConstraints,
+    WorkspaceCustomRoleConstraints, WorkspaceDetectionReviewConstraints, WorkspaceFileConstraints,
+    WorkspaceFileShareConstraints, WorkspaceInboundWebhookConstraints, WorkspaceInviteConstraints,
+    WorkspaceLegalHoldConstraints, WorkspaceMemberConstraints, WorkspaceOperationConstraints,
+    WorkspacePipelineArtifactConstraints, WorkspacePipelineConstraints,
     WorkspacePipelineReferenceConstraints, WorkspacePipelineRunConstraints,
-    WorkspacePolicyConstraints, WorkspaceWebhookConstraints,
+    WorkspacePolicyConstraints, WorkspaceRetentionPolicyConstraints,
+    WorkspaceTemporaryObjectConstraints, WorkspaceWebhookConstraints,
+};
+pub use credential::{
+    ConnectionCredentials, CredentialCipher, CredentialError, CredentialResult, SealedCredentials,
 };
 pub use enums::{
-    ActivityCategory, ActivityType, ApiTokenType, ArtifactType, FileSource, InviteStatus,
-    NotificationEvent, PipelineRunStatus, PipelineStatus, PipelineTriggerType, SyncStatus,
-    SyncTriggerType, WebhookEvent, WebhookStatus, WorkspaceRole,
+    ActivityCategory, ActivityType, ApiKeyScope, ApiTokenType, ArtifactType, ChangeEventSource,
+    DataRegion, DataSensitivity, FileSource, InboundWebhookProvider, InviteStatus,
+    NotificationEvent, OperationKind, OperationStatus, PipelineRunStatus, PipelineStatus,
+    PipelineTriggerType, ReviewStatus, StorageClass, StorageStage, SyncStatus, SyncTriggerType,
+    WebhookEvent, WebhookStatus, WorkspaceRole,
 };
-pub use filtering::{FileFilter, FileFormat, InviteFilter, MemberFilter};
+pub use filtering::{ActivityFilter, FileFilter, FileFormat, InviteFilter, MemberFilter};
 pub use pagination::{Cursor, CursorPage, CursorPagination, OffsetPage, OffsetPagination};
-pub use prefixed_id::{ConnectionId, PrefixedIdError, RunId, WebhookId};
+pub use prefixed_id::{
+    ConnectionId, InboundWebhookId, OperationId, PrefixedIdError, RoleId, RunId, WebhookId,
+};
 pub use slug::{SLUG_MAX_LENGTH, SLUG_MIN_LENGTH, Slug, SlugError};
 pub use sorting::{
     FileSortBy, FileSortField, InviteSortBy, InviteSortField, MemberSortBy, MemberSortField,
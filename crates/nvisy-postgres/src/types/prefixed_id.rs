@@ -136,11 +136,26 @@ prefixed_id! {
     WebhookId, "whk"
 }
 
+prefixed_id! {
+    /// Opaque identifier for a workspace inbound webhook (`inb_<uuid>`).
+    InboundWebhookId, "inb"
+}
+
 prefixed_id! {
     /// Opaque identifier for a pipeline run (`run_<uuid>`).
     RunId, "run"
 }
 
+prefixed_id! {
+    /// Opaque identifier for a long-running operation (`op_<uuid>`).
+    OperationId, "op"
+}
+
+prefixed_id! {
+    /// Opaque identifier for a workspace custom role (`role_<uuid>`).
+    RoleId, "role"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,34 @@
+//! [`HealthCheck`] implementation for [`RedisClient`].
+
+use nvisy_core::health::{ComponentHealth, HealthCheck};
+
+use super::RedisClient;
+use crate::TRACING_TARGET_CONNECTION;
+
+/// Component name reported for the Redis health check.
+const COMPONENT_NAME: &str = "redis";
+
+#[async_trait::async_trait]
+impl HealthCheck for RedisClient {
+    /// Probes Redis by pinging the server.
+    async fn check_health(&self) -> ComponentHealth {
+        match self.ping().await {
+            Ok(latency) => {
+                tracing::debug!(
+                    target: TRACING_TARGET_CONNECTION,
+                    ping_ms = latency.as_millis(),
+                    "Redis health check passed"
+                );
+                ComponentHealth::healthy(COMPONENT_NAME).with_latency(latency)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    target: TRACING_TARGET_CONNECTION,
+                    error = %e,
+                    "Redis health check failed"
+                );
+                ComponentHealth::unhealthy(COMPONENT_NAME)
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Redis client connection management and configuration.
+
+mod health;
+mod redis_client;
+mod redis_config;
+
+pub use redis_client::RedisClient;
+pub(crate) use redis_client::RedisConnection;
+pub use redis_config::RedisConfig;
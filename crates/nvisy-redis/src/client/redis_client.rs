@@ -0,0 +1,287 @@
+//! Redis client wrapper and connection management.
+//!
+//! # Connection Management
+//!
+//! A single server is reached through a [`ConnectionManager`]: one
+//! multiplexed connection shared by every clone of the client, reconnected
+//! in the background when it drops. With the `cluster` feature, a
+//! comma-separated list of URLs connects to a Redis Cluster instead,
+//! routing each key to the node owning its slot.
+//!
+//! The stores mirror those of `NatsClient` getter for getter, so code written
+//! against one client compiles against the other.
+//!
+//! [`ConnectionManager`]: redis::aio::ConnectionManager
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use nvisy_nats::kv::{
+    ApiKey, ApiKeysBucket, ApiToken, ApiTokensBucket, ChatHistoryBucket, DigestKey, InboxKey,
+    KvBucket, KvKey, OidcLogin, OidcLoginsBucket, PrivacyBudget, PrivacyBudgetsBucket,
+    ProcessedMessage, ProcessedMessagesBucket, SessionKey, TokenKey, WorkspaceKey,
+};
+use redis::aio::{ConnectionLike, ConnectionManager, ConnectionManagerConfig};
+use redis::{Cmd, IntoConnectionInfo, Pipeline, RedisFuture, Value};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::time::timeout;
+
+use super::redis_config::RedisConfig;
+use crate::kv::KvStore;
+use crate::{Error, Result, TRACING_TARGET_CLIENT, TRACING_TARGET_CONNECTION};
+
+/// Redis client wrapper with connection management.
+///
+/// This wrapper is cheaply cloneable and thread-safe.
+/// Clones share the same underlying connection.
+#[derive(Clone)]
+pub struct RedisClient {
+    inner: Arc<RedisClientInner>,
+}
+
+/// Inner data for Redis client
+struct RedisClientInner {
+    connection: RedisConnection,
+    /// Client of a single server, used to open keyspace notification
+    /// subscriptions; `None` for clusters.
+    pubsub: Option<redis::Client>,
+    config: RedisConfig,
+}
+
+/// Connection to a single server or to a cluster.
+#[derive(Clone)]
+pub(crate) enum RedisConnection {
+    Single(ConnectionManager),
+    #[cfg(feature = "cluster")]
+    Cluster(redis::cluster_async::ClusterConnection),
+}
+
+impl RedisConnection {
+    /// Returns whether this is a connection to a cluster.
+    #[inline]
+    pub(crate) fn is_cluster(&self) -> bool {
+        match self {
+            Self::Single(_) => false,
+            #[cfg(feature = "cluster")]
+            Self::Cluster(_) => true,
+        }
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Self::Single(conn) => conn.req_packed_command(cmd),
+            #[cfg(feature = "cluster")]
+            Self::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Self::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            #[cfg(feature = "cluster")]
+            Self::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Self::Single(conn) => conn.get_db(),
+            #[cfg(feature = "cluster")]
+            Self::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+impl std::fmt::Debug for RedisClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisClient")
+            .field("servers", &self.inner.config.servers())
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisClient {
+    /// Create a new Redis client and connect
+    #[tracing::instrument(skip(config))]
+    pub async fn connect(config: RedisConfig) -> Result<Self> {
+        config.validate().map_err(Error::invalid_config)?;
+        tracing::info!(
+            target: TRACING_TARGET_CONNECTION,
+            servers = config.servers().len(),
+            cluster = config.is_cluster(),
+            "Connecting to Redis"
+        );
+
+        let connect_timeout = config.connect_timeout();
+        let (connection, pubsub) = timeout(connect_timeout, Self::open(&config))
+            .await
+            .map_err(|_| Error::timeout(connect_timeout))??;
+
+        tracing::info!(
+            target: TRACING_TARGET_CONNECTION,
+            "Successfully connected to Redis"
+        );
+
+        Ok(Self {
+            inner: Arc::new(RedisClientInner {
+                connection,
+                pubsub,
+                config,
+            }),
+        })
+    }
+
+    /// Opens the connection described by `config`.
+    async fn open(config: &RedisConfig) -> Result<(RedisConnection, Option<redis::Client>)> {
+        #[cfg(feature = "cluster")]
+        if config.is_cluster() {
+            let mut builder = redis::cluster::ClusterClientBuilder::new(config.servers())
+                .connection_timeout(config.connect_timeout())
+                .response_timeout(config.response_timeout());
+            if let Some(password) = &config.redis_password {
+                builder = builder.password(password.clone());
+            }
+
+            let connection = builder.build()?.get_async_connection().await?;
+            return Ok((RedisConnection::Cluster(connection), None));
+        }
+
+        let mut info = config.redis_url.as_str().into_connection_info()?;
+        if let Some(password) = &config.redis_password {
+            info.redis.password = Some(password.clone());
+        }
+
+        let client = redis::Client::open(info)?;
+        let manager_config = ConnectionManagerConfig::new()
+            .set_connection_timeout(config.connect_timeout())
+            .set_response_timeout(config.response_timeout());
+        let manager = ConnectionManager::new_with_config(client.clone(), manager_config).await?;
+
+        Ok((RedisConnection::Single(manager), Some(client)))
+    }
+
+    /// Get the configuration
+    #[must_use]
+    pub fn config(&self) -> &RedisConfig {
+        &self.inner.config
+    }
+
+    /// Test connectivity with a ping
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CONNECTION)]
+    pub async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        let mut connection = self.inner.connection.clone();
+
+        timeout(
+            Duration::from_secs(10),
+            redis::cmd("PING").query_async::<String>(&mut connection),
+        )
+        .await
+        .map_err(|_| Error::timeout(Duration::from_secs(10)))??;
+
+        let ping_time = start.elapsed();
+        tracing::debug!(
+            target: TRACING_TARGET_CLIENT,
+            duration_ms = ping_time.as_millis(),
+            "Redis ping successful"
+        );
+        Ok(ping_time)
+    }
+}
+
+// Key-value store getters
+impl RedisClient {
+    /// Get a KV store for the specified key, value, and bucket types.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn kv_store<K, V, B>(&self) -> Result<KvStore<K, V, B>>
+    where
+        K: KvKey,
+        V: Serialize + DeserializeOwned + Send + Sync + 'static,
+        B: KvBucket,
+    {
+        self.kv_store_with_ttl(B::TTL.unwrap_or_default()).await
+    }
+
+    /// Get a KV store with custom TTL.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn kv_store_with_ttl<K, V, B>(&self, ttl: Duration) -> Result<KvStore<K, V, B>>
+    where
+        K: KvKey,
+        V: Serialize + DeserializeOwned + Send + Sync + 'static,
+        B: KvBucket,
+    {
+        Ok(KvStore::new(
+            self.inner.connection.clone(),
+            self.inner.pubsub.clone(),
+            ttl,
+        ))
+    }
+
+    /// Get an API token store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn api_token_store(
+        &self,
+        ttl: Duration,
+    ) -> Result<KvStore<TokenKey, ApiToken, ApiTokensBucket>> {
+        self.kv_store_with_ttl(ttl).await
+    }
+
+    /// Get the account API key lookup cache.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn api_key_store(&self) -> Result<KvStore<DigestKey, ApiKey, ApiKeysBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get the pending OIDC login store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn oidc_login_store(
+        &self,
+    ) -> Result<KvStore<DigestKey, OidcLogin, OidcLoginsBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get the per-workspace privacy budget store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn privacy_budget_store(
+        &self,
+    ) -> Result<KvStore<WorkspaceKey, PrivacyBudget, PrivacyBudgetsBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get the store of processed message ids.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn processed_message_store(
+        &self,
+    ) -> Result<KvStore<InboxKey, ProcessedMessage, ProcessedMessagesBucket>> {
+        self.kv_store().await
+    }
+
+    /// Get a chat history store with default TTL.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn chat_history_store<V>(&self) -> Result<KvStore<SessionKey, V, ChatHistoryBucket>>
+    where
+        V: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        self.kv_store().await
+    }
+
+    /// Get a chat history store with custom TTL.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_CLIENT)]
+    pub async fn chat_history_store_with_ttl<V>(
+        &self,
+        ttl: Duration,
+    ) -> Result<KvStore<SessionKey, V, ChatHistoryBucket>>
+    where
+        V: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        self.kv_store_with_ttl(ttl).await
+    }
+}
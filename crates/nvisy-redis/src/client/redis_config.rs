@@ -0,0 +1,160 @@
+//! Redis connection configuration.
+
+use std::time::Duration;
+
+/// Configuration for Redis connections with sensible defaults.
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    /// Redis server URL (comma-separated for cluster nodes)
+    pub redis_url: String,
+
+    /// Password, overriding one embedded in the URL (optional).
+    pub redis_password: Option<String>,
+
+    /// Connection timeout (optional).
+    pub redis_connect_timeout: Option<Duration>,
+
+    /// Response timeout of a single command (optional).
+    pub redis_response_timeout: Option<Duration>,
+}
+
+// Default values
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_RESPONSE_TIMEOUT_SECS: u64 = 5;
+
+impl RedisConfig {
+    /// Create a new configuration with a single server URL.
+    pub fn new(server_url: impl Into<String>) -> Self {
+        Self {
+            redis_url: server_url.into(),
+            redis_password: None,
+            redis_connect_timeout: None,
+            redis_response_timeout: None,
+        }
+    }
+
+    /// Returns the server URLs as a vector (splits comma-separated URLs).
+    pub fn servers(&self) -> Vec<&str> {
+        self.redis_url.split(',').map(str::trim).collect()
+    }
+
+    /// Returns whether the URLs name the nodes of a Redis Cluster.
+    #[inline]
+    pub fn is_cluster(&self) -> bool {
+        self.servers().len() > 1
+    }
+
+    /// Returns the connection timeout, using the default if not set.
+    #[inline]
+    pub fn connect_timeout(&self) -> Duration {
+        self.redis_connect_timeout
+            .unwrap_or(Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS))
+    }
+
+    /// Returns the response timeout, using the default if not set.
+    #[inline]
+    pub fn response_timeout(&self) -> Duration {
+        self.redis_response_timeout
+            .unwrap_or(Duration::from_secs(DEFAULT_RESPONSE_TIMEOUT_SECS))
+    }
+
+    /// Set server URL(s).
+    #[must_use]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.redis_url = url.into();
+        self
+    }
+
+    /// Set the password.
+    #[must_use]
+    pub fn with_password(mut self, password: impl Into<String>) -> Self {
+        self.redis_password = Some(password.into());
+        self
+    }
+
+    /// Set the connection timeout.
+    #[must_use]
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.redis_connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the response timeout.
+    #[must_use]
+    pub fn with_response_timeout(mut self, timeout: Duration) -> Self {
+        self.redis_response_timeout = Some(timeout);
+        self
+    }
+
+    /// Validate the configuration and return any issues.
+    pub fn validate(&self) -> Result<(), String> {
+        for server in self.servers() {
+            if server.is_empty() {
+                return Err("Server URL cannot be empty".to_string());
+            }
+            if !server.starts_with("redis://") && !server.starts_with("rediss://") {
+                return Err(format!("Invalid server URL format: {}", server));
+            }
+        }
+
+        if self.is_cluster() && !cfg!(feature = "cluster") {
+            return Err("Several server URLs require the `cluster` feature".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_config() {
+        let config = RedisConfig::new("redis://localhost:6379");
+        assert_eq!(config.servers(), vec!["redis://localhost:6379"]);
+        assert!(!config.is_cluster());
+        assert_eq!(config.redis_password, None);
+        assert_eq!(config.connect_timeout(), Duration::from_secs(10));
+        assert_eq!(config.response_timeout(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let config = RedisConfig::new("redis://localhost:6379")
+            .with_password("secret")
+            .with_connect_timeout(Duration::from_secs(3))
+            .with_response_timeout(Duration::from_secs(1));
+
+        assert_eq!(config.redis_password.as_deref(), Some("secret"));
+        assert_eq!(config.connect_timeout(), Duration::from_secs(3));
+        assert_eq!(config.response_timeout(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(
+            RedisConfig::new("redis://localhost:6379")
+                .validate()
+                .is_ok()
+        );
+        assert!(RedisConfig::new("rediss://cache:6380").validate().is_ok());
+        assert!(RedisConfig::new("").validate().is_err());
+        assert!(
+            RedisConfig::new("nats://localhost:4222")
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cluster_servers() {
+        let config = RedisConfig::new("redis://node-1:6379, redis://node-2:6379");
+        assert_eq!(
+            config.servers(),
+            vec!["redis://node-1:6379", "redis://node-2:6379"]
+        );
+        assert!(config.is_cluster());
+        assert_eq!(config.validate().is_ok(), cfg!(feature = "cluster"));
+    }
+}
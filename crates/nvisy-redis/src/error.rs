@@ -0,0 +1,106 @@
+//! Error types and utilities for Redis operations.
+
+use std::time::Duration;
+
+use nvisy_core::cache::CacheError;
+
+/// Result type for all Redis operations in this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Unified error type for Redis operations.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Redis client/connection errors
+    #[error("Redis connection error: {0}")]
+    Connection(#[from] redis::RedisError),
+
+    /// Serialization errors when storing or reading values
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Operation timeout
+    #[error("Operation timed out after {timeout:?}")]
+    Timeout { timeout: Duration },
+
+    /// KV key not found
+    #[error("Key '{key}' not found in bucket '{bucket}'")]
+    KvKeyNotFound { bucket: String, key: String },
+
+    /// KV revision mismatch (optimistic concurrency failure)
+    #[error("Revision mismatch for key '{key}': expected {expected}, got {actual}")]
+    KvRevisionMismatch {
+        key: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    /// Invalid configuration
+    #[error("Invalid configuration: {reason}")]
+    InvalidConfig { reason: String },
+
+    /// Operation the connected deployment does not support
+    #[error("Unsupported operation: {operation} - {reason}")]
+    Unsupported { operation: String, reason: String },
+
+    /// Generic operation error with context
+    #[error("Redis operation failed: {operation} - {details}")]
+    Operation { operation: String, details: String },
+}
+
+impl Error {
+    /// Create an operation error with context
+    pub fn operation(op: impl Into<String>, details: impl Into<String>) -> Self {
+        Self::Operation {
+            operation: op.into(),
+            details: details.into(),
+        }
+    }
+
+    /// Create a KV key not found error
+    pub fn kv_key_not_found(bucket: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::KvKeyNotFound {
+            bucket: bucket.into(),
+            key: key.into(),
+        }
+    }
+
+    /// Create a KV revision mismatch error
+    pub fn kv_revision_mismatch(key: impl Into<String>, expected: u64, actual: u64) -> Self {
+        Self::KvRevisionMismatch {
+            key: key.into(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Create an invalid configuration error
+    pub fn invalid_config(reason: impl Into<String>) -> Self {
+        Self::InvalidConfig {
+            reason: reason.into(),
+        }
+    }
+
+    /// Create an unsupported operation error
+    pub fn unsupported(op: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Unsupported {
+            operation: op.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Create a timeout error with the given duration
+    pub fn timeout(duration: Duration) -> Self {
+        Self::Timeout { timeout: duration }
+    }
+}
+
+impl From<Error> for CacheError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::KvRevisionMismatch { key, expected, .. } => {
+                CacheError::revision_mismatch(key, expected)
+            }
+            error => CacheError::backend("Redis", error),
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Type-safe Redis KV store.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+use futures::stream::BoxStream;
+use futures::{StreamExt, future};
+use nvisy_core::cache::{CacheEntry, CacheResult, KeyValueStore};
+use nvisy_nats::kv::{KvBucket, KvEntry, KvKey, KvValue};
+use redis::Script;
+use redis::aio::ConnectionLike;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::client::RedisConnection;
+use crate::{Error, Result, TRACING_TARGET_KV};
+
+/// Number of keys requested per `SCAN` round trip.
+const SCAN_BATCH: usize = 100;
+
+/// Writes an entry and bumps its revision.
+///
+/// `ARGV`: value, creation time in milliseconds, TTL in milliseconds (zero
+/// for none).
+static PUT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local revision = redis.call('HINCRBY', KEYS[1], 'revision', 1)
+        redis.call('HSET', KEYS[1], 'value', ARGV[1], 'created', ARGV[2])
+        if tonumber(ARGV[3]) > 0 then
+            redis.call('PEXPIRE', KEYS[1], ARGV[3])
+        else
+            redis.call('PERSIST', KEYS[1])
+        end
+        return revision
+        ",
+    )
+});
+
+/// Writes an entry if its revision still is `ARGV[4]`, zero meaning absent.
+///
+/// Returns `{1, revision}` with the new revision, or `{0, revision}` with the
+/// current one when the entry changed concurrently.
+static UPDATE: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+        local current = tonumber(redis.call('HGET', KEYS[1], 'revision') or '0')
+        if current ~= tonumber(ARGV[4]) then
+            return {0, current}
+        end
+        local revision = redis.call('HINCRBY', KEYS[1], 'revision', 1)
+        redis.call('HSET', KEYS[1], 'value', ARGV[1], 'created', ARGV[2])
+        if tonumber(ARGV[3]) > 0 then
+            redis.call('PEXPIRE', KEYS[1], ARGV[3])
+        else
+            redis.call('PERSIST', KEYS[1])
+        end
+        return {1, revision}
+        ",
+    )
+});
+
+/// Type-safe Redis KV store.
+///
+/// Mirrors the NATS `KvStore` of `nvisy-nats` and is generic over the same
+/// types:
+/// - `K`: The key type (determines prefix)
+/// - `V`: The value type to store (must be serializable)
+/// - `B`: The bucket configuration (determines name and TTL)
+///
+/// Each entry is a hash under `{bucket}:{key}` holding the JSON value, its
+/// revision and its creation time. Writes go through Lua scripts, so the
+/// revision is bumped atomically with the value, and every write restarts
+/// the entry's TTL like a new NATS KV revision does.
+#[derive(Clone)]
+pub struct KvStore<K, V, B>
+where
+    K: KvKey,
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+    B: KvBucket,
+{
+    connection: RedisConnection,
+    pubsub: Option<redis::Client>,
+    ttl: Duration,
+    _key: PhantomData<K>,
+    _value: PhantomData<V>,
+    _bucket: PhantomData<B>,
+}
+
+impl<K, V, B> KvStore<K, V, B>
+where
+    K: KvKey,
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+    B: KvBucket,
+{
+    /// Creates a store over `connection` whose entries live for `ttl`.
+    pub(crate) fn new(
+        connection: RedisConnection,
+        pubsub: Option<redis::Client>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            connection,
+            pubsub,
+            ttl,
+            _key: PhantomData,
+            _value: PhantomData,
+            _bucket: PhantomData,
+        }
+    }
+
+    /// Returns the bucket name.
+    #[inline]
+    pub fn bucket_name(&self) -> &'static str {
+        B::NAME
+    }
+
+    /// Returns the Redis key of `key`.
+    fn redis_key(key: &K) -> String {
+        format!("{}:{key}", B::NAME)
+    }
+
+    /// Returns the TTL in milliseconds, zero for entries that never expire.
+    fn ttl_millis(&self) -> u64 {
+        self.ttl.as_millis().try_into().unwrap_or(u64::MAX)
+    }
+
+    /// Put a value into the store.
+    #[tracing::instrument(skip(self, value), target = TRACING_TARGET_KV)]
+    pub async fn put(&self, key: &K, value: &V) -> Result<KvEntry> {
+        let key_str = key.to_string();
+        let json = serde_json::to_vec(value)?;
+        let size = json.len();
+        let revision: u64 = PUT
+            .key(Self::redis_key(key))
+            .arg(json)
+            .arg(now_millis())
+            .arg(self.ttl_millis())
+            .invoke_async(&mut self.connection.clone())
+            .await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            key = %key_str,
+            revision = revision,
+            size_bytes = size,
+            "Put value to KV store"
+        );
+
+        Ok(KvEntry {
+            key: key_str,
+            revision,
+            size: size as u64,
+        })
+    }
+
+    /// Get a value from the store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn get(&self, key: &K) -> Result<Option<KvValue<V>>> {
+        let key_str = key.to_string();
+        let (value, revision, created): (Option<Vec<u8>>, Option<u64>, Option<u64>) =
+            redis::cmd("HMGET")
+                .arg(Self::redis_key(key))
+                .arg("value")
+                .arg("revision")
+                .arg("created")
+                .query_async(&mut self.connection.clone())
+                .await?;
+
+        let (Some(value), Some(revision)) = (value, revision) else {
+            tracing::debug!(
+                target: TRACING_TARGET_KV,
+                key = %key_str,
+                "Key not found in KV store"
+            );
+            return Ok(None);
+        };
+
+        let size = value.len();
+        let deserialized = serde_json::from_slice(&value)?;
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            key = %key_str,
+            size_bytes = size,
+            revision = revision,
+            "Retrieved value from KV store"
+        );
+
+        Ok(Some(KvValue {
+            key: key_str,
+            value: deserialized,
+            revision,
+            size: size as u64,
+            created: SystemTime::UNIX_EPOCH + Duration::from_millis(created.unwrap_or_default()),
+        }))
+    }
+
+    /// Get a value, returning just the data.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn get_value(&self, key: &K) -> Result<Option<V>> {
+        Ok(self.get(key).await?.map(|kv| kv.value))
+    }
+
+    /// Delete a key from the store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn delete(&self, key: &K) -> Result<()> {
+        redis::cmd("DEL")
+            .arg(Self::redis_key(key))
+            .query_async::<()>(&mut self.connection.clone())
+            .await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            key = %key,
+            "Deleted key from KV store"
+        );
+        Ok(())
+    }
+
+    /// Check if a key exists in the store.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn exists(&self, key: &K) -> Result<bool> {
+        let exists = redis::cmd("EXISTS")
+            .arg(Self::redis_key(key))
+            .query_async(&mut self.connection.clone())
+            .await?;
+        Ok(exists)
+    }
+
+    /// Returns how long a key has left before it expires.
+    ///
+    /// Returns `None` for missing keys and keys that never expire.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn ttl(&self, key: &K) -> Result<Option<Duration>> {
+        // PTTL answers -2 for a missing key and -1 for one without expiry.
+        let millis: i64 = redis::cmd("PTTL")
+            .arg(Self::redis_key(key))
+            .query_async(&mut self.connection.clone())
+            .await?;
+        Ok(u64::try_from(millis).ok().map(Duration::from_millis))
+    }
+
+    /// Touches a key to reset its TTL by re-putting the same value.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn touch(&self, key: &K) -> Result<KvEntry> {
+        let kv_value = self
+            .get(key)
+            .await?
+            .ok_or_else(|| Error::kv_key_not_found(B::NAME, key.to_string()))?;
+
+        self.put(key, &kv_value.value).await
+    }
+
+    /// Get all keys in the bucket with the expected prefix.
+    ///
+    /// A cluster spreads keys over its nodes, so listing them is only
+    /// supported on a single server.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn keys(&self) -> Result<Vec<K>> {
+        if self.connection.is_cluster() {
+            return Err(Error::unsupported(
+                "kv_keys",
+                "keys cannot be listed across cluster nodes",
+            ));
+        }
+
+        let prefix = format!("{}:", B::NAME);
+        let mut connection = self.connection.clone();
+        let mut keys = Vec::new();
+        let mut cursor = 0_u64;
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(format!("{prefix}*"))
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(&mut connection)
+                .await?;
+
+            keys.extend(
+                batch
+                    .iter()
+                    .filter_map(|key| key.strip_prefix(&prefix)?.parse::<K>().ok()),
+            );
+
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            count = keys.len(),
+            bucket = %B::NAME,
+            "Retrieved keys from bucket"
+        );
+        Ok(keys)
+    }
+
+    /// Purge all keys in the bucket.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn purge_all(&self) -> Result<()> {
+        let keys = self.keys().await?;
+        let count = keys.len();
+        for key in keys {
+            self.delete(&key).await?;
+        }
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            count = count,
+            bucket = %B::NAME,
+            "Purged all keys from bucket"
+        );
+        Ok(())
+    }
+
+    /// Update a value only if the revision matches (optimistic concurrency).
+    ///
+    /// Revision zero only succeeds if the key does not exist yet.
+    #[tracing::instrument(skip(self, value), target = TRACING_TARGET_KV)]
+    pub async fn update(&self, key: &K, value: &V, revision: u64) -> Result<KvEntry> {
+        let key_str = key.to_string();
+        let json = serde_json::to_vec(value)?;
+        let size = json.len();
+        let (updated, new_revision): (u8, u64) = UPDATE
+            .key(Self::redis_key(key))
+            .arg(json)
+            .arg(now_millis())
+            .arg(self.ttl_millis())
+            .arg(revision)
+            .invoke_async(&mut self.connection.clone())
+            .await?;
+
+        if updated == 0 {
+            return Err(Error::kv_revision_mismatch(key_str, revision, new_revision));
+        }
+
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            key = %key_str,
+            old_revision = revision,
+            new_revision = new_revision,
+            size_bytes = size,
+            "Updated value in KV store"
+        );
+
+        Ok(KvEntry {
+            key: key_str,
+            revision: new_revision,
+            size: size as u64,
+        })
+    }
+
+    /// Get or compute a value using the cache-aside pattern.
+    #[tracing::instrument(skip(self, compute_fn), target = TRACING_TARGET_KV)]
+    pub async fn get_or_compute<F, Fut>(&self, key: &K, compute_fn: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<V>> + Send,
+        V: Clone,
+    {
+        if let Some(existing) = self.get_value(key).await? {
+            return Ok(existing);
+        }
+
+        let value = compute_fn().await?;
+        self.put(key, &value).await?;
+        Ok(value)
+    }
+
+    /// Watches the bucket's keys for changes.
+    ///
+    /// Changes are read from keyspace notifications, which the server only
+    /// sends when `notify-keyspace-events` includes `Kghxe`. Notifications
+    /// are not persisted: changes made while nobody listens are not replayed.
+    /// Clusters send them per node and are not supported.
+    #[tracing::instrument(skip(self), target = TRACING_TARGET_KV)]
+    pub async fn watch(&self) -> Result<BoxStream<'static, KvWatchEvent<K>>> {
+        let Some(client) = &self.pubsub else {
+            return Err(Error::unsupported(
+                "kv_watch",
+                "keyspace notifications are sent per cluster node",
+            ));
+        };
+
+        let prefix = format!("__keyspace@{}__:{}:", self.connection.get_db(), B::NAME);
+        let mut pubsub = client.get_async_pubsub().await?;
+        pubsub.psubscribe(format!("{prefix}*")).await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET_KV,
+            bucket = %B::NAME,
+            "Watching bucket for changes"
+        );
+
+        Ok(pubsub
+            .into_on_message()
+            .filter_map(move |message| {
+                let event = message
+                    .get_channel_name()
+                    .strip_prefix(&prefix)
+                    .and_then(|key| key.parse::<K>().ok())
+                    .zip(message.get_payload::<String>().ok())
+                    .and_then(|(key, command)| {
+                        KvOperation::from_command(&command)
+                            .map(|operation| KvWatchEvent { key, operation })
+                    });
+                future::ready(event)
+            })
+            .boxed())
+    }
+}
+
+#[async_trait::async_trait]
+impl<K, V, B> KeyValueStore<K, V> for KvStore<K, V, B>
+where
+    K: KvKey,
+    V: Serialize + DeserializeOwned + Send + Sync + 'static,
+    B: KvBucket,
+{
+    async fn get(&self, key: &K) -> CacheResult<Option<CacheEntry<V>>> {
+        let kv_value = KvStore::get(self, key).await?;
+        Ok(kv_value.map(|kv_value| CacheEntry {
+            value: kv_value.value,
+            revision: kv_value.revision,
+        }))
+    }
+
+    async fn put(&self, key: &K, value: &V) -> CacheResult<u64> {
+        Ok(KvStore::put(self, key, value).await?.revision)
+    }
+
+    async fn delete(&self, key: &K) -> CacheResult<()> {
+        Ok(KvStore::delete(self, key).await?)
+    }
+
+    async fn ttl(&self, key: &K) -> CacheResult<Option<Duration>> {
+        Ok(KvStore::ttl(self, key).await?)
+    }
+
+    async fn compare_and_swap(&self, key: &K, value: &V, revision: u64) -> CacheResult<u64> {
+        Ok(KvStore::update(self, key, value, revision).await?.revision)
+    }
+}
+
+/// Kind of change reported by [`KvStore::watch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvOperation {
+    /// The key was written.
+    Put,
+    /// The key was deleted.
+    Delete,
+    /// The key expired or was evicted.
+    Expire,
+}
+
+impl KvOperation {
+    /// Maps the command named by a keyspace notification to a change.
+    ///
+    /// A write runs `HINCRBY`, `HSET` and an expiry command; only `hset` is
+    /// reported so that each write yields one event.
+    fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "hset" => Some(Self::Put),
+            "del" => Some(Self::Delete),
+            "expired" | "evicted" => Some(Self::Expire),
+            _ => None,
+        }
+    }
+}
+
+/// A change to a watched key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvWatchEvent<K> {
+    /// Key that changed.
+    pub key: K,
+    /// What happened to it.
+    pub operation: KvOperation,
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_from_command() {
+        assert_eq!(KvOperation::from_command("hset"), Some(KvOperation::Put));
+        assert_eq!(KvOperation::from_command("del"), Some(KvOperation::Delete));
+        assert_eq!(
+            KvOperation::from_command("expired"),
+            Some(KvOperation::Expire)
+        );
+        assert_eq!(
+            KvOperation::from_command("evicted"),
+            Some(KvOperation::Expire)
+        );
+        assert_eq!(KvOperation::from_command("hincrby"), None);
+        assert_eq!(KvOperation::from_command("expire"), None);
+    }
+}
@@ -0,0 +1,21 @@
+//! Redis Key-Value store operations.
+//!
+//! Stores are keyed and configured by the key, value and bucket types of
+//! `nvisy_nats::kv`, which are re-exported here:
+//! - `KvStore<K, V, B>`: Generic type-safe key-value operations
+//! - `KvKey`: Trait for key types with prefix support
+//! - `KvBucket`: Trait for bucket configuration, naming the key prefix
+//!
+//! # Example
+//!
+//! ```ignore
+//! let store = redis_client.processed_message_store().await?;
+//! let key = InboxKey::new("inbound", message_id);
+//! store.put(&key, &ProcessedMessage::now()).await?;
+//! assert!(store.exists(&key).await?);
+//! ```
+
+mod kv_store;
+
+pub use kv_store::{KvOperation, KvStore, KvWatchEvent};
+pub use nvisy_nats::kv::{KvBucket, KvEntry, KvKey, KvValue};
@@ -0,0 +1,25 @@
+#![forbid(unsafe_code)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc = include_str!("../README.md")]
+
+/// Tracing target for Redis client operations.
+///
+/// Use this target for logging client initialization, configuration, and client-level errors.
+pub const TRACING_TARGET_CLIENT: &str = "nvisy_redis::client";
+
+/// Tracing target for Redis key-value store operations.
+///
+/// Use this target for logging key operations, watches, and KV-related errors.
+pub const TRACING_TARGET_KV: &str = "nvisy_redis::kv";
+
+/// Tracing target for Redis connection operations.
+///
+/// Use this target for logging connection establishment, reconnection, and connection errors.
+pub const TRACING_TARGET_CONNECTION: &str = "nvisy_redis::connection";
+
+mod client;
+mod error;
+pub mod kv;
+
+pub use client::{RedisClient, RedisConfig};
+pub use error::{Error, Result};
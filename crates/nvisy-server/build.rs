@@ -0,0 +1,34 @@
+#![forbid(unsafe_code)]
+
+/// Generates the gRPC services from `./proto` when the `grpc` feature is
+/// enabled.
+///
+/// `protoc` is vendored, so building the feature needs no system install.
+/// The descriptor set is written next to the generated code for the
+/// reflection service.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=./proto");
+
+    #[cfg(feature = "grpc")]
+    {
+        let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+
+        let mut config = prost_build::Config::new();
+        config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+        config.bytes(["."]);
+
+        tonic_prost_build::configure()
+            .build_client(false)
+            .file_descriptor_set_path(out_dir.join("nvisy_descriptor.bin"))
+            .compile_with_config(
+                config,
+                &[
+                    "./proto/nvisy/v1/files.proto",
+                    "./proto/nvisy/v1/operations.proto",
+                ],
+                &["./proto"],
+            )?;
+    }
+
+    Ok(())
+}
@@ -5,12 +5,16 @@
 //! The trait is designed to be implemented by types that represent authenticated users.
 
 use nvisy_postgres::model::WorkspaceMember;
-use nvisy_postgres::query::{WorkspaceFileRepository, WorkspaceMemberRepository};
+use nvisy_postgres::query::{
+    AdminScope, TenantScope, WorkspaceCustomRoleRepository, WorkspaceFileRepository,
+};
+use nvisy_postgres::types::{ApiKeyScope, WorkspaceRole};
 use nvisy_postgres::{PgConn, PgError};
 use uuid::Uuid;
 
 use super::{AuthResult, Permission};
 use crate::handler::Result;
+use crate::service::rbac::{self, Actor, CustomRole, Decision, RoleGrant};
 
 /// Tracing target for authorization operations.
 const TRACING_TARGET: &str = "nvisy_server::authorization";
@@ -28,6 +32,7 @@ const TRACING_TARGET: &str = "nvisy_server::authorization";
 ///
 /// # Authorization Levels
 ///
+/// - **API Key Scope**: Caps every other level for API-key requests
 /// - **Global Admin**: Bypasses all workspace-level restrictions
 /// - **Workspace-Level**: Based on membership and role within specific workspaces
 /// - **Document-Level**: Extends workspace permissions with ownership rules
@@ -42,6 +47,29 @@ pub trait AuthProvider {
     /// Returns whether the user has global administrator privileges.
     fn is_admin(&self) -> bool;
 
+    /// Returns the scopes of the API key authenticating the request.
+    ///
+    /// `None` means the request is not API-key authenticated and is not
+    /// capped by scope.
+    fn scopes(&self) -> Option<&[ApiKeyScope]> {
+        None
+    }
+
+    /// Returns the caller as a policy [`Actor`].
+    fn actor(&self) -> Actor {
+        Actor {
+            account_id: self.account_id(),
+            is_admin: self.is_admin(),
+            scopes: self.scopes().map(<[ApiKeyScope]>::to_vec),
+        }
+    }
+
+    /// Returns whether the request's scopes allow acting with `role`.
+    fn scopes_cover(&self, role: WorkspaceRole) -> bool {
+        self.scopes()
+            .is_none_or(|scopes| scopes.iter().any(|scope| scope.covers(role)))
+    }
+
     /// Checks if a user has permission to access a workspace.
     ///
     /// # Arguments
@@ -64,25 +92,19 @@ pub trait AuthProvider {
         workspace_id: Uuid,
         permission: Permission,
     ) -> Result<AuthResult, PgError> {
-        // Global administrators bypass workspace-level permissions
-        if self.is_admin() {
-            tracing::debug!(
-                target: TRACING_TARGET,
-                account_id = %self.account_id(),
-                workspace_id = %workspace_id,
-                permission = ?permission,
-                "access granted: global administrator"
-            );
-
-            return Ok(AuthResult::granted());
+        // API key scopes cap every role; global administrators bypass the rest
+        match rbac::precheck(self, permission) {
+            Some(Decision::Allow) => return Ok(AuthResult::granted()),
+            Some(Decision::Deny(reason)) => return Ok(AuthResult::denied(reason)),
+            None => {}
         }
 
         // Check workspace membership
-        let member = conn
-            .find_workspace_member(workspace_id, self.account_id())
+        let membership = conn
+            .find_workspace_member_with_custom_role(workspace_id, self.account_id())
             .await?;
 
-        let Some(member) = member else {
+        let Some((member, custom_role, scope)) = membership else {
             tracing::warn!(
                 target: TRACING_TARGET,
                 account_id = %self.account_id(),
@@ -94,32 +116,37 @@ pub trait AuthProvider {
             return Ok(AuthResult::denied("Not a workspace member"));
         };
 
-        // Check role permission
-        if permission.is_permitted_by_role(member.member_role) {
-            tracing::debug!(
-                target: TRACING_TARGET,
-                account_id = %self.account_id(),
-                workspace_id = %workspace_id,
-                permission = ?permission,
-                role = ?member.member_role,
-                "Access granted: sufficient role"
-            );
+        // Check the built-in role, then the custom role's extra permissions
+        let grant =
+            RoleGrant::new(member.member_role).with_custom(custom_role.map(CustomRole::from));
 
-            Ok(AuthResult::granted_with_member(member))
-        } else {
-            tracing::warn!(
-                target: TRACING_TARGET,
-                account_id = %self.account_id(),
-                workspace_id = %workspace_id,
-                permission = ?permission,
-                role = ?member.member_role,
-                "Access denied: insufficient role"
-            );
+        match rbac::decide(Some(&grant), permission) {
+            Decision::Allow => {
+                tracing::debug!(
+                    target: TRACING_TARGET,
+                    account_id = %self.account_id(),
+                    workspace_id = %workspace_id,
+                    permission = ?permission,
+                    role = ?member.member_role,
+                    custom_role_id = ?member.custom_role_id,
+                    "Access granted: sufficient role"
+                );
 
-            Ok(AuthResult::denied(format!(
-                "Role {member_role:?} insufficient for {permission:?} permission",
-                member_role = member.member_role
-            )))
+                Ok(AuthResult::granted_with_member(member, scope))
+            }
+            Decision::Deny(reason) => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    account_id = %self.account_id(),
+                    workspace_id = %workspace_id,
+                    permission = ?permission,
+                    role = ?member.member_role,
+                    custom_role_id = ?member.custom_role_id,
+                    "Access denied: insufficient role"
+                );
+
+                Ok(AuthResult::denied(reason))
+            }
         }
     }
 
@@ -148,8 +175,10 @@ pub trait AuthProvider {
         file_id: Uuid,
         permission: Permission,
     ) -> Result<AuthResult, PgError> {
-        // Get the file to find its workspace
-        let file = conn.find_workspace_file_by_id(file_id).await?;
+        // Get the file to find its workspace; this lookup precedes any
+        // workspace authorization, so it cannot be tenant-scoped.
+        let admin = AdminScope::open(conn, "resolve file workspace for authorization").await?;
+        let file = conn.find_workspace_file_by_id(&admin, file_id).await?;
 
         let Some(file) = file else {
             tracing::warn!(
@@ -277,6 +306,35 @@ pub trait AuthProvider {
         auth_result.into_result()
     }
 
+    /// Authorizes workspace access and returns the scope for its rows.
+    ///
+    /// Members get the scope of the membership they were authorized with. A
+    /// global administrator who is not a member opens an [`AdminScope`], so
+    /// the access is recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Forbidden` error if access is denied, or propagates database errors.
+    #[allow(async_fn_in_trait)]
+    async fn authorize_tenant(
+        &self,
+        conn: &mut PgConn,
+        workspace_id: Uuid,
+        permission: Permission,
+    ) -> Result<TenantScope> {
+        let auth_result = self
+            .check_workspace_permission(conn, workspace_id, permission)
+            .await?;
+
+        match auth_result.into_scope()? {
+            Some(scope) => Ok(scope),
+            None => {
+                let admin = AdminScope::open(conn, "global administrator workspace access").await?;
+                Ok(admin.tenant(workspace_id))
+            }
+        }
+    }
+
     /// Authorizes file access with ownership and workspace-level checks.
     ///
     /// This convenience method handles complex file authorization logic:
@@ -327,6 +385,31 @@ pub trait AuthProvider {
         auth_result.into_result().map(|_| ())
     }
 
+    /// Authorizes an operation that requires at least the given API key scope.
+    ///
+    /// Always succeeds for session tokens. Used for account-level operations
+    /// that are not tied to a workspace role, such as managing API keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Forbidden` error if the request's API key lacks the scope.
+    fn authorize_scope(&self, scope: ApiKeyScope) -> Result<()> {
+        if self.scopes_cover(scope.max_role()) {
+            return Ok(());
+        }
+
+        tracing::warn!(
+            target: TRACING_TARGET,
+            account_id = %self.account_id(),
+            required_scope = %scope,
+            "access denied: API key scope insufficient"
+        );
+
+        AuthResult::denied(format!("API key requires the {scope} scope"))
+            .into_result()
+            .map(|_| ())
+    }
+
     /// Authorizes global administrator access.
     ///
     /// This method enforces global administrator privileges for system-level
@@ -11,6 +11,9 @@ use aide::generate::GenContext;
 use aide::openapi::Operation;
 use axum::extract::{FromRef, FromRequestParts, OptionalFromRequestParts};
 use axum::http::request::Parts;
+use axum_extra::TypedHeader;
+use axum_extra::headers::Authorization;
+use axum_extra::headers::authorization::Bearer;
 use derive_more::{Deref, DerefMut};
 use nvisy_postgres::model::Account;
 use nvisy_postgres::query::{AccountApiTokenRepository, AccountRepository};
@@ -19,7 +22,7 @@ use serde::Deserialize;
 
 use super::{AuthClaims, AuthHeader};
 use crate::handler::{Error, ErrorKind, Result};
-use crate::service::SessionKeys;
+use crate::service::{ApiKeyService, SessionKeys};
 
 /// Tracing target for authentication operations.
 const TRACING_TARGET: &str = "nvisy_server::authentication";
@@ -30,7 +33,7 @@ const TRACING_TARGET: &str = "nvisy_server::authentication";
 /// user credentials after performing extensive security checks. It guarantees
 /// that the authenticated user has:
 ///
-/// - A cryptographically valid JWT token
+/// - A cryptographically valid JWT token, or an active account API key
 /// - A verified and active account
 /// - Current privilege levels matching the database
 ///
@@ -348,12 +351,87 @@ where
     }
 }
 
+impl<T> AuthState<T>
+where
+    T: Default,
+{
+    /// Creates a new [`AuthState`] from an account API key.
+    ///
+    /// The key is resolved by digest (through the NATS KV cache), then its
+    /// account is loaded. The resulting claims carry the key's scopes, which
+    /// cap every authorization check made with them.
+    ///
+    /// # Errors
+    ///
+    /// * [`ErrorKind::Unauthorized`]: Unknown, revoked or expired key, or the
+    ///   key's account no longer exists
+    /// * [`ErrorKind::InternalServerError`]: Database connection or query failures
+    pub async fn from_api_key(
+        secret: &str,
+        pg_client: PgClient,
+        api_keys: ApiKeyService,
+    ) -> Result<Self> {
+        let mut conn = pg_client.get_connection().await.map_err(|db_error| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                error = %db_error,
+                "failed to acquire database connection for API key verification"
+            );
+            ErrorKind::InternalServerError
+                .with_message("Authentication verification encountered an error")
+                .with_resource("authentication")
+        })?;
+
+        let Some(api_key) = api_keys.resolve(&mut conn, secret).await? else {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                "authentication failed: API key is unknown, revoked or expired"
+            );
+
+            return Err(ErrorKind::Unauthorized
+                .with_message("API key is invalid")
+                .with_context("The key may have been revoked or expired")
+                .with_resource("authentication"));
+        };
+
+        let account = conn
+            .find_account_by_id(api_key.account_id)
+            .await?
+            .ok_or_else(|| {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    account_id = %api_key.account_id,
+                    key_id = %api_key.key_id,
+                    "authentication failed: account referenced by API key no longer exists"
+                );
+
+                ErrorKind::Unauthorized
+                    .with_message("Account not found")
+                    .with_context("Your account may have been deactivated")
+                    .with_resource("authentication")
+            })?;
+
+        let auth_claims = AuthClaims::from_api_key(&account, &api_key, T::default());
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            account_id = %auth_claims.account_id,
+            key_id = %api_key.key_id,
+            scopes = ?auth_claims.scopes,
+            "API key authentication completed successfully"
+        );
+
+        Ok(Self::from_verified_claims(auth_claims))
+    }
+}
+
 impl<T, S> FromRequestParts<S> for AuthState<T>
 where
-    T: Clone + for<'de> Deserialize<'de> + Send + Sync + 'static,
+    T: Clone + Default + for<'de> Deserialize<'de> + Send + Sync + 'static,
     S: Sync + Send + 'static,
     PgClient: FromRef<S>,
     SessionKeys: FromRef<S>,
+    ApiKeyService: FromRef<S>,
 {
     type Rejection = Error<'static>;
 
@@ -363,6 +441,20 @@ where
             return Ok(auth_state.clone());
         }
 
+        // API keys are opaque secrets rather than JWTs, so route them to the
+        // key lookup before the header is parsed as a token
+        type AuthBearerHeader = TypedHeader<Authorization<Bearer>>;
+        if let Ok(bearer) = AuthBearerHeader::from_request_parts(parts, state).await
+            && ApiKeyService::is_api_key(bearer.token())
+        {
+            let pg_database = PgClient::from_ref(state);
+            let api_keys = ApiKeyService::from_ref(state);
+            let auth_state = Self::from_api_key(bearer.token(), pg_database, api_keys).await?;
+
+            parts.extensions.insert(auth_state.clone());
+            return Ok(auth_state);
+        }
+
         // Extract JWT token and perform comprehensive database verification
         let auth_header = AuthHeader::from_request_parts(parts, state).await?;
         let pg_database = PgClient::from_ref(state);
@@ -376,10 +468,11 @@ where
 
 impl<T, S> OptionalFromRequestParts<S> for AuthState<T>
 where
-    T: Clone + Send + Sync + for<'de> Deserialize<'de> + 'static,
+    T: Clone + Default + Send + Sync + for<'de> Deserialize<'de> + 'static,
     S: Sync + Send + 'static,
     PgClient: FromRef<S>,
     SessionKeys: FromRef<S>,
+    ApiKeyService: FromRef<S>,
 {
     type Rejection = Error<'static>;
 
@@ -11,7 +11,9 @@ use axum_extra::headers::Authorization;
 use axum_extra::headers::authorization::Bearer;
 use jiff::{Span, Timestamp};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use nvisy_nats::kv::ApiKey;
 use nvisy_postgres::model::{Account, AccountApiToken};
+use nvisy_postgres::types::ApiKeyScope;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -58,6 +60,11 @@ pub struct AuthClaims<T = ()> {
     /// Is administrator flag.
     #[serde(rename = "adm")]
     pub is_admin: bool,
+    /// Scopes of the API key the request was authenticated with.
+    ///
+    /// `None` for session tokens, which act with the account's full roles.
+    #[serde(rename = "scp", default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<ApiKeyScope>>,
 }
 
 impl AuthClaims<()> {
@@ -118,6 +125,35 @@ impl<T> AuthClaims<T> {
                 .unwrap_or_else(|| Timestamp::now().as_second() + NEVER_EXPIRES_SECONDS),
             custom_claims,
             is_admin: account_model.is_admin,
+            scopes: None,
+        }
+    }
+
+    /// Creates claims for a request authenticated with an account API key.
+    ///
+    /// These claims are never encoded into a JWT; they let API-key requests
+    /// flow through the same authorization checks as session tokens. Global
+    /// administrator rights only carry over to keys with the `admin` scope.
+    pub fn from_api_key(account_model: &Account, api_key: &ApiKey, custom_claims: T) -> Self {
+        let scopes: Vec<ApiKeyScope> = api_key
+            .scopes
+            .iter()
+            .filter_map(|scope| scope.parse().ok())
+            .collect();
+
+        Self {
+            issued_by: Cow::Borrowed(Self::JWT_ISSUER),
+            audience: Cow::Borrowed(Self::JWT_AUDIENCE),
+            token_id: api_key.key_id,
+            account_id: account_model.id,
+            issued_at: Timestamp::now().as_second(),
+            expires_at: api_key
+                .expired_at
+                .map(|ts| ts.as_second())
+                .unwrap_or_else(|| Timestamp::now().as_second() + NEVER_EXPIRES_SECONDS),
+            custom_claims,
+            is_admin: account_model.is_admin && scopes.contains(&ApiKeyScope::Admin),
+            scopes: Some(scopes),
         }
     }
 
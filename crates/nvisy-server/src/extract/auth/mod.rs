@@ -10,6 +10,7 @@ mod jwt_claims;
 mod jwt_header;
 mod permission;
 
+use nvisy_postgres::types::ApiKeyScope;
 use uuid::Uuid;
 
 pub use self::auth_provider::AuthProvider;
@@ -26,4 +27,8 @@ impl<T> AuthProvider for AuthClaims<T> {
     fn is_admin(&self) -> bool {
         self.is_admin
     }
+
+    fn scopes(&self) -> Option<&[ApiKeyScope]> {
+        self.scopes.as_deref()
+    }
 }
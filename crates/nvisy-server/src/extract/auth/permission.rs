@@ -1,157 +1,23 @@
 //! Core authorization types and utilities.
 //!
 //! This module provides the fundamental types used for authorization throughout
-//! the nvisy system, including permissions, contexts, and results.
+//! the nvisy system, including permissions, contexts, and results. Permission
+//! definitions live in [`crate::service::rbac`] and are re-exported here.
 
 use std::borrow::Cow;
 
 use nvisy_postgres::model::WorkspaceMember;
-use nvisy_postgres::types::WorkspaceRole;
-use strum::{EnumIter, EnumString, IntoEnumIterator};
+use nvisy_postgres::query::TenantScope;
 
-use crate::handler::{ErrorKind, Result};
-
-/// Granular workspace permissions for authorization checks.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[derive(EnumIter, EnumString)]
-pub enum Permission {
-    // Workspace-level permissions
-    /// Can view workspace basic information.
-    ViewWorkspace,
-    /// Can update workspace settings and metadata.
-    UpdateWorkspace,
-    /// Can delete the entire workspace.
-    DeleteWorkspace,
-
-    // File permissions
-    /// Can view and download files.
-    ViewFiles,
-    /// Can upload new files to the workspace.
-    UploadFiles,
-    /// Can update file metadata and properties.
-    UpdateFiles,
-    /// Can download files from the workspace.
-    DownloadFiles,
-    /// Can delete files from the workspace.
-    DeleteFiles,
-
-    // Pipeline permissions
-    /// Can view pipelines in the workspace.
-    ViewPipelines,
-    /// Can create new pipelines.
-    CreatePipelines,
-    /// Can update existing pipelines.
-    UpdatePipelines,
-    /// Can delete pipelines.
-    DeletePipelines,
-    /// Can execute pipeline runs.
-    RunPipelines,
-
-    // Member management permissions
-    /// Can view workspace members and their roles.
-    ViewMembers,
-    /// Can invite new members to the workspace.
-    InviteMembers,
-    /// Can remove members from the workspace.
-    RemoveMembers,
-    /// Can change member roles and permissions.
-    ManageRoles,
-
-    // Connection permissions
-    /// Can view workspace connections.
-    ViewConnections,
-    /// Can create, modify, and manage workspace connections.
-    ManageConnections,
-
-    // Context permissions
-    /// Can view workspace contexts.
-    ViewContexts,
-    /// Can create, modify, and manage workspace contexts.
-    ManageContexts,
-
-    // Policy permissions
-    /// Can view workspace policies.
-    ViewPolicies,
-    /// Can create, modify, and manage workspace policies.
-    ManagePolicies,
-
-    // Webhook permissions
-    /// Can view workspace webhooks.
-    ViewWebhooks,
-    /// Can create new webhooks in the workspace.
-    CreateWebhooks,
-    /// Can update existing webhooks.
-    UpdateWebhooks,
-    /// Can delete webhooks from the workspace.
-    DeleteWebhooks,
-    /// Can test webhooks by sending test payloads.
-    TestWebhooks,
-}
-
-impl Permission {
-    /// Checks if the given workspace role satisfies this permission requirement.
-    ///
-    /// This method leverages the role hierarchy to determine if the given role
-    /// has sufficient permissions. A role is permitted if it has equal or higher
-    /// permission level than the minimum required role for this permission.
-    pub const fn is_permitted_by_role(self, role: WorkspaceRole) -> bool {
-        role.has_permission_level_of(self.minimum_required_role())
-    }
-
-    /// Returns the minimum role required for this permission.
-    #[must_use]
-    pub const fn minimum_required_role(self) -> WorkspaceRole {
-        match self {
-            // Guest-level permissions (read-only access)
-            Self::ViewWorkspace
-            | Self::ViewFiles
-            | Self::ViewPipelines
-            | Self::ViewMembers
-            | Self::ViewConnections
-            | Self::ViewContexts
-            | Self::ViewPolicies
-            | Self::ViewWebhooks => WorkspaceRole::Guest,
-
-            // Member-level permissions (create and modify own resources)
-            Self::UploadFiles
-            | Self::UpdateFiles
-            | Self::DownloadFiles
-            | Self::DeleteFiles
-            | Self::CreatePipelines
-            | Self::UpdatePipelines
-            | Self::DeletePipelines
-            | Self::RunPipelines => WorkspaceRole::Member,
-
-            // Admin-level permissions (manage workspace resources)
-            Self::UpdateWorkspace
-            | Self::InviteMembers
-            | Self::RemoveMembers
-            | Self::ManageConnections
-            | Self::ManageContexts
-            | Self::ManagePolicies
-            | Self::CreateWebhooks
-            | Self::UpdateWebhooks
-            | Self::DeleteWebhooks
-            | Self::TestWebhooks => WorkspaceRole::Admin,
-
-            // Owner-only permissions (highest level)
-            Self::DeleteWorkspace | Self::ManageRoles => WorkspaceRole::Owner,
-        }
-    }
-
-    /// Returns all permissions available to the given role.
-    pub fn permissions_for_role(role: WorkspaceRole) -> Vec<Self> {
-        Self::iter()
-            .filter(|perm| perm.is_permitted_by_role(role))
-            .collect()
-    }
-}
+use crate::handler::{Error, ErrorKind, Result};
+pub use crate::service::rbac::Permission;
 
 /// Result of an authorization check with detailed information.
 #[derive(Debug, Clone, PartialEq)]
 pub struct AuthResult {
     pub granted: bool,
     pub member: Option<WorkspaceMember>,
+    pub scope: Option<TenantScope>,
     pub reason: Option<Cow<'static, str>>,
 }
 
@@ -161,15 +27,18 @@ impl AuthResult {
         Self {
             granted: true,
             member: None,
+            scope: None,
             reason: None,
         }
     }
 
-    /// Creates a granted authorization result with member information.
-    pub const fn granted_with_member(member: WorkspaceMember) -> Self {
+    /// Creates a granted authorization result with member information and
+    /// the scope of the membership's workspace.
+    pub const fn granted_with_member(member: WorkspaceMember, scope: TenantScope) -> Self {
         Self {
             granted: true,
             member: Some(member),
+            scope: Some(scope),
             reason: None,
         }
     }
@@ -179,6 +48,7 @@ impl AuthResult {
         Self {
             granted: false,
             member: None,
+            scope: None,
             reason: Some(reason.into()),
         }
     }
@@ -199,11 +69,27 @@ impl AuthResult {
         if self.granted {
             Ok(self.member)
         } else {
-            let error = match self.reason {
-                Some(reason) => ErrorKind::Forbidden.with_context(reason),
-                None => ErrorKind::Forbidden.into_error(),
-            };
-            Err(error)
+            Err(self.into_error())
+        }
+    }
+
+    /// Converts the result to the granted membership's scope, returning an
+    /// error if access is denied.
+    ///
+    /// `Ok(None)` means access was granted without a membership, as for a
+    /// global administrator.
+    pub fn into_scope(self) -> Result<Option<TenantScope>> {
+        if self.granted {
+            Ok(self.scope)
+        } else {
+            Err(self.into_error())
+        }
+    }
+
+    fn into_error(self) -> Error<'static> {
+        match self.reason {
+            Some(reason) => ErrorKind::Forbidden.with_context(reason),
+            None => ErrorKind::Forbidden.into_error(),
         }
     }
 }
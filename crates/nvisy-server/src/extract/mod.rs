@@ -11,6 +11,7 @@ mod pg_connection;
 mod reject;
 mod typed_header;
 mod version;
+mod workspace_access;
 mod workspace_context;
 
 pub use crate::extract::auth::{
@@ -21,4 +22,5 @@ pub use crate::extract::pg_connection::PgPool;
 pub use crate::extract::reject::{Form, Json, Multipart, Path, Query, ValidateJson};
 pub use crate::extract::typed_header::TypedHeader;
 pub use crate::extract::version::Version;
+pub use crate::extract::workspace_access::WorkspaceAccess;
 pub use crate::extract::workspace_context::WorkspaceContext;
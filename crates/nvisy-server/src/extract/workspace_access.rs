@@ -0,0 +1,158 @@
+//! Workspace access extractor enforcing the RBAC policy.
+//!
+//! Combines [`WorkspaceContext`] and [`AuthState`] with the caller's role
+//! grant, resolved through the cached [`Policy`], so handlers check
+//! permissions without another database round trip.
+
+use aide::OperationInput;
+use aide::generate::GenContext;
+use aide::openapi::{Operation, Response};
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use nvisy_postgres::model::Workspace;
+use nvisy_postgres::query::{AdminScope, TenantScope};
+use nvisy_postgres::{PgClient, PgConn};
+
+use crate::extract::{AuthProvider, AuthState, WorkspaceContext};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::rbac::{self, Actor, Decision, Permission, Policy, RoleGrant};
+use crate::service::{ApiKeyService, SessionKeys};
+
+/// Tracing target for workspace access checks.
+const TRACING_TARGET: &str = "nvisy_server::authorization";
+
+/// An authenticated caller's access to the workspace addressed by the
+/// `{workspaceSlug}` path segment.
+///
+/// Extraction rejects callers who are neither members of the workspace nor
+/// global administrators with `403 Forbidden`. Individual permissions are
+/// then checked with [`require`](Self::require).
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct WorkspaceAccess {
+    workspace: Workspace,
+    actor: Actor,
+    grant: Option<RoleGrant>,
+    scope: Option<TenantScope>,
+}
+
+impl WorkspaceAccess {
+    /// Returns the resolved workspace.
+    #[inline]
+    pub fn workspace(&self) -> &Workspace {
+        &self.workspace
+    }
+
+    /// Returns the calling actor.
+    #[inline]
+    pub fn actor(&self) -> &Actor {
+        &self.actor
+    }
+
+    /// Returns the caller's roles, or `None` for a global administrator who
+    /// is not a member.
+    #[inline]
+    pub fn grant(&self) -> Option<&RoleGrant> {
+        self.grant.as_ref()
+    }
+
+    /// Returns the scope for the workspace's rows.
+    ///
+    /// Members get the scope of their membership. A global administrator who
+    /// is not a member opens an [`AdminScope`], so the access is recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the administrator's access cannot be recorded.
+    pub async fn scope(&self, conn: &mut PgConn) -> Result<TenantScope> {
+        if let Some(scope) = self.scope {
+            return Ok(scope);
+        }
+
+        let admin = AdminScope::open(conn, "global administrator workspace access").await?;
+        Ok(admin.tenant(self.workspace.id))
+    }
+
+    /// Checks whether the caller holds `permission` in the workspace.
+    pub fn check(&self, permission: Permission) -> Decision {
+        rbac::precheck(&self.actor, permission)
+            .unwrap_or_else(|| rbac::decide(self.grant.as_ref(), permission))
+    }
+
+    /// Requires the caller to hold `permission` in the workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Forbidden` if the permission is not held.
+    pub fn require(&self, permission: Permission) -> Result<()> {
+        let decision = self.check(permission);
+        if let Decision::Deny(reason) = &decision {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                account_id = %self.actor.account_id,
+                workspace_id = %self.workspace.id,
+                permission = %permission,
+                reason = %reason,
+                "Access denied"
+            );
+        }
+
+        decision.into_result()
+    }
+}
+
+impl<S> FromRequestParts<S> for WorkspaceAccess
+where
+    S: Sync + Send + 'static,
+    PgClient: FromRef<S>,
+    SessionKeys: FromRef<S>,
+    ApiKeyService: FromRef<S>,
+    Policy: FromRef<S>,
+{
+    type Rejection = Error<'static>;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AuthState(auth_claims) =
+            <AuthState as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+        let WorkspaceContext(workspace) =
+            <WorkspaceContext as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+
+        let actor = auth_claims.actor();
+        let (grant, scope) = Policy::from_ref(state)
+            .resolve_member(workspace.id, actor.account_id)
+            .await?
+            .unzip();
+
+        if grant.is_none() && !actor.is_admin {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                account_id = %actor.account_id,
+                workspace_id = %workspace.id,
+                "Access denied: not a workspace member"
+            );
+
+            return Err(ErrorKind::Forbidden.with_context("Not a workspace member"));
+        }
+
+        Ok(Self {
+            workspace,
+            actor,
+            grant,
+            scope,
+        })
+    }
+}
+
+impl OperationInput for WorkspaceAccess {
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        AuthState::<()>::operation_input(ctx, operation);
+        WorkspaceContext::operation_input(ctx, operation);
+    }
+
+    fn inferred_early_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<aide::openapi::StatusCode>, Response)> {
+        WorkspaceContext::inferred_early_responses(ctx, operation)
+    }
+}
@@ -28,7 +28,8 @@ use crate::handler::{Error, ErrorKind};
 /// probe which workspaces exist beyond what the caller can already reach.
 ///
 /// The resolved [`Workspace::id`] is the value handlers pass to
-/// `authorize_workspace` and the workspace-scoped repository methods.
+/// `authorize_tenant`, which returns the `TenantScope` for the
+/// workspace-scoped repository methods.
 #[must_use]
 #[derive(Debug, Clone)]
 pub struct WorkspaceContext(pub Workspace);
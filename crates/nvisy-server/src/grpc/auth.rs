@@ -0,0 +1,43 @@
+//! Credentials and workspaces of gRPC calls.
+
+use axum::extract::FromRequestParts;
+use axum::http::Request;
+use nvisy_postgres::PgConn;
+use nvisy_postgres::model::Workspace;
+use nvisy_postgres::query::WorkspaceRepository;
+use tonic::metadata::MetadataMap;
+
+use crate::extract::{AuthClaims, AuthState};
+use crate::handler::{ErrorKind, Result};
+use crate::middleware::validate_token;
+use crate::service::ServiceState;
+
+/// Verifies the bearer credential in a call's `authorization` metadata.
+///
+/// Runs the checks of the REST routes: the session token or API key must
+/// verify, and a session token must be neither expired nor revoked.
+pub(super) async fn authenticate(
+    state: &ServiceState,
+    metadata: &MetadataMap,
+) -> Result<AuthClaims> {
+    let mut request = Request::new(());
+    *request.headers_mut() = metadata.clone().into_headers();
+    let (mut parts, ()) = request.into_parts();
+
+    let AuthState(auth_claims): AuthState =
+        AuthState::from_request_parts(&mut parts, state).await?;
+    validate_token(&auth_claims, &state.postgres).await?;
+    Ok(auth_claims)
+}
+
+/// Finds a workspace by slug, as the REST routes' workspace path does.
+pub(super) async fn find_workspace(conn: &mut PgConn, workspace_slug: &str) -> Result<Workspace> {
+    conn.find_workspace_by_slug(workspace_slug)
+        .await?
+        .map(|(workspace, _)| workspace)
+        .ok_or_else(|| {
+            ErrorKind::NotFound
+                .with_message("Workspace not found")
+                .with_resource("workspace")
+        })
+}
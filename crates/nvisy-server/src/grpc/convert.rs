@@ -0,0 +1,120 @@
+//! Conversions between REST response types and gRPC messages.
+//!
+//! Messages are built from the REST responses rather than from the models,
+//! so both APIs expose the same fields. Enums travel as the same snake_case
+//! strings as in JSON.
+
+use jiff::Timestamp;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::proto;
+use crate::handler::response::{ArtifactDownload, File, Operation};
+use crate::handler::{ErrorKind, Result};
+
+impl From<File> for proto::File {
+    fn from(file: File) -> Self {
+        Self {
+            id: file.id.to_string(),
+            workspace_slug: file.workspace_slug.into(),
+            display_name: file.display_name,
+            original_filename: file.original_filename,
+            file_extension: file.file_extension,
+            mime_type: file.mime_type,
+            file_size: file.file_size,
+            tags: file.tags,
+            source: wire_name(file.source),
+            uploaded_by: file.uploaded_by.into(),
+            version_number: file.version_number,
+            parent_id: file.parent_id.map(|id| id.to_string()),
+            protection: wire_name(file.protection),
+            sensitivity: wire_name(file.sensitivity),
+            created_at: Some(timestamp(file.created_at)),
+            updated_at: Some(timestamp(file.updated_at)),
+            storage_version: file.storage_version,
+        }
+    }
+}
+
+impl From<Operation> for proto::Operation {
+    fn from(operation: Operation) -> Self {
+        Self {
+            id: operation.id.to_string(),
+            workspace_id: operation.workspace_id.to_string(),
+            kind: wire_name(operation.kind),
+            status: wire_name(operation.status),
+            progress: operation.progress.into(),
+            target_id: operation.target_id.map(|id| id.to_string()),
+            result_json: operation.result.map(|result| result.to_string()),
+            error_json: operation.error.map(|error| error.to_string()),
+            artifact: operation.artifact.map(Into::into),
+            created_at: Some(timestamp(operation.created_at)),
+            updated_at: Some(timestamp(operation.updated_at)),
+            started_at: operation.started_at.map(timestamp),
+            completed_at: operation.completed_at.map(timestamp),
+            expires_at: operation.expires_at.map(timestamp),
+        }
+    }
+}
+
+impl From<ArtifactDownload> for proto::OperationArtifact {
+    fn from(artifact: ArtifactDownload) -> Self {
+        Self {
+            name: artifact.name,
+            content_type: artifact.content_type,
+            size: artifact.size,
+            url: artifact.url,
+            url_expires_at: Some(timestamp(artifact.url_expires_at)),
+        }
+    }
+}
+
+/// Converts a timestamp to its protobuf form.
+pub(super) fn timestamp(timestamp: Timestamp) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: timestamp.as_second(),
+        nanos: timestamp.subsec_nanosecond(),
+    }
+}
+
+/// Returns the name an enum value is serialized as in JSON.
+pub(super) fn wire_name(value: impl Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+/// Parses an enum value from the name it is serialized as in JSON.
+pub(super) fn parse_wire_name<T: DeserializeOwned>(field: &str, name: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_owned())).map_err(|_| {
+        ErrorKind::BadRequest
+            .with_message(format!("Invalid {field}: {name}"))
+            .with_resource(field.to_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_postgres::types::{DataSensitivity, FileFormat, OperationStatus};
+
+    use super::*;
+
+    #[test]
+    fn enums_use_their_json_names() {
+        assert_eq!(wire_name(OperationStatus::Succeeded), "succeeded");
+        assert_eq!(wire_name(DataSensitivity::High), "high");
+
+        let format: FileFormat = parse_wire_name("format", "pdf").unwrap();
+        assert_eq!(format, FileFormat::Pdf);
+        assert!(parse_wire_name::<FileFormat>("format", "Portable").is_err());
+    }
+
+    #[test]
+    fn timestamps_keep_subsecond_precision() {
+        let at = Timestamp::new(1_760_000_000, 250_000_000).unwrap();
+        let converted = timestamp(at);
+        assert_eq!(converted.seconds, 1_760_000_000);
+        assert_eq!(converted.nanos, 250_000_000);
+    }
+}
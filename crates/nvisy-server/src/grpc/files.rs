@@ -0,0 +1,217 @@
+//! The `nvisy.v1.FileService` implementation.
+
+use futures::StreamExt;
+use nvisy_nats::object::{FileKey, FilesBucket};
+use nvisy_postgres::model::NewWorkspaceFileAccess;
+use nvisy_postgres::query::{AccountRepository, WorkspaceFileRepository};
+use nvisy_postgres::types::{DataSensitivity, Username};
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
+
+use super::TRACING_TARGET;
+use super::auth::{authenticate, find_workspace};
+use super::convert::parse_wire_name;
+use super::proto::file_service_server::FileService;
+use super::proto::upload_file_request::Part;
+use super::proto::{self, GetFileRequest, ListFilesRequest, ListFilesResponse, UploadFileRequest};
+use crate::extract::{AuthProvider, Permission};
+use crate::handler::request::{CursorPagination, ListFiles};
+use crate::handler::response::{File, FilesPage};
+use crate::handler::{
+    Error, ErrorKind, FileUploadContext, Result, record_file_access, store_uploaded_file,
+};
+use crate::service::ServiceState;
+
+/// Serves the files of workspaces.
+pub(super) struct FileApi {
+    state: ServiceState,
+}
+
+impl FileApi {
+    /// Creates the service.
+    pub fn new(state: ServiceState) -> Self {
+        Self { state }
+    }
+
+    /// Stores the file streamed by an upload call.
+    async fn upload(&self, request: Request<Streaming<UploadFileRequest>>) -> Result<File> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let mut messages = request.into_inner();
+
+        let metadata = match messages.next().await {
+            Some(Ok(UploadFileRequest {
+                part: Some(Part::Metadata(metadata)),
+            })) => metadata,
+            Some(Err(status)) => return Err(stream_error(&status)),
+            _ => {
+                return Err(ErrorKind::BadRequest
+                    .with_message("The first message of an upload must carry its metadata"));
+            }
+        };
+        if metadata.filename.is_empty() {
+            return Err(ErrorKind::BadRequest.with_message("Filename is required"));
+        }
+        let sensitivity: DataSensitivity = match metadata.sensitivity.as_deref() {
+            Some(name) => parse_wire_name("sensitivity", name)?,
+            None => DataSensitivity::default(),
+        };
+
+        let state = &self.state;
+        let mut conn = state.postgres.get_connection().await?;
+        let workspace = find_workspace(&mut conn, &metadata.workspace_slug).await?;
+
+        let scope = auth_claims
+            .authorize_tenant(&mut conn, workspace.id, Permission::UploadFiles)
+            .await?;
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            account_id = %auth_claims.account_id,
+            workspace_id = %workspace.id,
+            "Uploading file"
+        );
+
+        let nats_client = state.residency.backends(workspace.data_region)?.nats();
+        let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
+
+        let uploaded_by: Username = conn
+            .find_account_by_id(auth_claims.account_id)
+            .await?
+            .ok_or_else(|| Error::not_found("account"))?
+            .username;
+
+        let ctx = FileUploadContext {
+            scope,
+            account_id: auth_claims.account_id,
+            file_store,
+            crypto: state.crypto.clone(),
+            garbage: state.garbage.clone(),
+            webhook_emitter: state.webhook_emitter.clone(),
+            ids: state.ids.clone(),
+            sensitivity,
+        };
+
+        // Every message after the metadata must be a chunk of the content.
+        let content = messages.map(|message| match message {
+            Ok(UploadFileRequest {
+                part: Some(Part::Chunk(chunk)),
+            }) => Ok(chunk),
+            Ok(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "expected a chunk of the file's content",
+            )),
+            Err(status) => Err(std::io::Error::other(status)),
+        });
+
+        let created_file = store_uploaded_file(&mut conn, &ctx, metadata.filename, content).await?;
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            file_id = %created_file.id,
+            "File uploaded"
+        );
+
+        Ok(File::from_model(created_file, workspace.slug, uploaded_by))
+    }
+
+    /// Reads a file's metadata, counting it as a view.
+    async fn get(&self, request: Request<GetFileRequest>) -> Result<File> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let request = request.into_inner();
+        let file_id: Uuid = request
+            .file_id
+            .parse()
+            .map_err(|_| ErrorKind::BadRequest.with_message("Invalid file id"))?;
+
+        let mut conn = self.state.postgres.get_connection().await?;
+        let workspace = find_workspace(&mut conn, &request.workspace_slug).await?;
+
+        let scope = auth_claims
+            .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
+            .await?;
+
+        let (file, uploaded_by) = conn
+            .find_file_in_workspace_with_creator(scope, file_id)
+            .await?
+            .ok_or_else(|| Error::not_found("file"))?;
+
+        record_file_access(
+            &mut conn,
+            NewWorkspaceFileAccess::view(workspace.id, file.id),
+        )
+        .await;
+
+        Ok(File::from_model(file, workspace.slug, uploaded_by))
+    }
+
+    /// Lists a page of a workspace's files.
+    async fn list(&self, request: Request<ListFilesRequest>) -> Result<ListFilesResponse> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let request = request.into_inner();
+
+        let formats = request
+            .formats
+            .iter()
+            .map(|name| parse_wire_name("format", name))
+            .collect::<Result<Vec<_>>>()?;
+        let query = ListFiles {
+            search: request.search,
+            formats: (!formats.is_empty()).then_some(formats),
+        };
+        let pagination = CursorPagination {
+            limit: request.limit,
+            after: request.after,
+        };
+
+        let mut conn = self.state.postgres.get_connection().await?;
+        let workspace = find_workspace(&mut conn, &request.workspace_slug).await?;
+
+        let scope = auth_claims
+            .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
+            .await?;
+
+        let page = conn
+            .cursor_list_workspace_files(scope, pagination.into(), query.to_filter())
+            .await?;
+
+        let page = FilesPage::from_cursor_page(page, |(file, uploaded_by)| {
+            File::from_model(file, workspace.slug.clone(), uploaded_by)
+        });
+
+        Ok(ListFilesResponse {
+            files: page.items.into_iter().map(Into::into).collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl FileService for FileApi {
+    async fn upload_file(
+        &self,
+        request: Request<Streaming<UploadFileRequest>>,
+    ) -> Result<Response<proto::File>, Status> {
+        let file = self.upload(request).await?;
+        Ok(Response::new(file.into()))
+    }
+
+    async fn get_file(
+        &self,
+        request: Request<GetFileRequest>,
+    ) -> Result<Response<proto::File>, Status> {
+        let file = self.get(request).await?;
+        Ok(Response::new(file.into()))
+    }
+
+    async fn list_files(
+        &self,
+        request: Request<ListFilesRequest>,
+    ) -> Result<Response<ListFilesResponse>, Status> {
+        Ok(Response::new(self.list(request).await?))
+    }
+}
+
+/// Reports a failure to receive an upload's messages.
+fn stream_error(status: &Status) -> Error<'static> {
+    ErrorKind::BadRequest.with_message(format!("Upload interrupted: {}", status.message()))
+}
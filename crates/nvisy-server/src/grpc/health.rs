@@ -0,0 +1,122 @@
+//! The standard `grpc.health.v1.Health` service.
+//!
+//! Reports the [`HealthCache`] to gRPC health probes: serving while the
+//! server is healthy or degraded, as the REST health endpoint answers `200`,
+//! and not serving while it is unhealthy. Every service of the router shares
+//! that status.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures::Stream;
+use nvisy_core::health::HealthStatus;
+use tonic::{Request, Response, Status};
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tonic_health::pb::{HealthCheckRequest, HealthCheckResponse};
+
+use super::proto::file_service_server::SERVICE_NAME as FILE_SERVICE;
+use super::proto::operation_service_server::SERVICE_NAME as OPERATION_SERVICE;
+use crate::service::HealthCache;
+
+/// How often a watch call re-checks the server's health.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Stream of serving statuses sent by `Watch`.
+type HealthStream = Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send>>;
+
+/// Serves the server's health to gRPC health probes.
+pub(super) struct HealthApi {
+    health_cache: HealthCache,
+}
+
+impl HealthApi {
+    /// Creates the service.
+    pub fn new(health_cache: HealthCache) -> Self {
+        Self { health_cache }
+    }
+
+    /// Wraps the service in its server.
+    pub fn into_server(self) -> HealthServer<Self> {
+        HealthServer::new(self)
+    }
+}
+
+/// Returns whether health is reported for the named service; the empty name
+/// stands for the whole server.
+fn is_known(service: &str) -> bool {
+    matches!(service, "" | FILE_SERVICE | OPERATION_SERVICE)
+}
+
+/// Returns the serving status for the server's health.
+async fn serving_status(health_cache: &HealthCache) -> ServingStatus {
+    match health_cache.check().await.status {
+        HealthStatus::Healthy | HealthStatus::Degraded => ServingStatus::Serving,
+        HealthStatus::Unhealthy => ServingStatus::NotServing,
+    }
+}
+
+fn response(status: ServingStatus) -> HealthCheckResponse {
+    HealthCheckResponse {
+        status: status.into(),
+    }
+}
+
+#[tonic::async_trait]
+impl Health for HealthApi {
+    type WatchStream = HealthStream;
+
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        if !is_known(&request.get_ref().service) {
+            return Err(Status::not_found("Unknown service"));
+        }
+
+        let status = serving_status(&self.health_cache).await;
+        Ok(Response::new(response(status)))
+    }
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let known = is_known(&request.get_ref().service);
+        let health_cache = self.health_cache.clone();
+
+        // An unknown service never becomes known, so it is reported once and
+        // the call is kept open, as the protocol asks.
+        let stream = async_stream::stream! {
+            if !known {
+                yield Ok(response(ServingStatus::ServiceUnknown));
+                futures::future::pending::<()>().await;
+            }
+
+            let mut last = None;
+            loop {
+                let status = serving_status(&health_cache).await;
+                if last != Some(status) {
+                    last = Some(status);
+                    yield Ok(response(status));
+                }
+                tokio::time::sleep(WATCH_INTERVAL).await;
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_covers_the_server_and_its_services() {
+        assert!(is_known(""));
+        assert!(is_known("nvisy.v1.FileService"));
+        assert!(is_known("nvisy.v1.OperationService"));
+        assert!(!is_known("nvisy.v1.Unknown"));
+    }
+}
@@ -0,0 +1,69 @@
+//! gRPC API served alongside the REST API.
+//!
+//! The services cover the calls that benefit most from streaming and
+//! generated clients: uploading, reading and searching files, starting and
+//! cancelling pipeline runs, and following long-running operations. They
+//! share the REST server's [`ServiceState`], credentials and permission
+//! checks, and [`routes`] returns a plain axum [`Router`] to merge with the
+//! REST routes, so gRPC is served on the same port and behind the same TLS.
+//! Clients need HTTP/2, which the server negotiates through ALPN over TLS
+//! and accepts in cleartext (h2c) otherwise.
+//!
+//! The router also serves the standard `grpc.health.v1` health service,
+//! backed by the [`HealthCache`], and the `grpc.reflection.v1` reflection
+//! service, so tools such as `grpcurl` work without the `.proto` files.
+//!
+//! [`HealthCache`]: crate::service::HealthCache
+
+mod auth;
+mod convert;
+mod files;
+mod health;
+mod operations;
+mod status;
+
+use axum::Router;
+use tonic::service::Routes;
+
+use self::files::FileApi;
+use self::health::HealthApi;
+use self::operations::OperationApi;
+use self::proto::file_service_server::FileServiceServer;
+use self::proto::operation_service_server::OperationServiceServer;
+use crate::service::ServiceState;
+use crate::{Error, Result};
+
+/// Messages and services generated from `proto/nvisy/v1`.
+pub mod proto {
+    tonic::include_proto!("nvisy.v1");
+
+    /// Encoded descriptors of the `nvisy.v1` package, served by reflection.
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("nvisy_descriptor");
+}
+
+/// Tracing target for gRPC services.
+const TRACING_TARGET: &str = "nvisy_server::grpc";
+
+/// Returns a [`Router`] serving every gRPC service.
+///
+/// The router carries its own state and is meant to be merged into the REST
+/// router before the middleware is layered on.
+pub fn routes(state: ServiceState) -> Result<Router> {
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build_v1()
+        .map_err(|error| Error::config("Invalid gRPC descriptor set").with_source(error))?;
+
+    let router = Routes::new(FileServiceServer::new(FileApi::new(state.clone())))
+        .add_service(OperationServiceServer::new(OperationApi::new(
+            state.clone(),
+        )))
+        .add_service(HealthApi::new(state.health_cache).into_server())
+        .add_service(reflection)
+        .into_axum_router();
+
+    tracing::debug!(target: TRACING_TARGET, "gRPC services initialized");
+
+    Ok(router)
+}
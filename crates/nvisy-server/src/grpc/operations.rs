@@ -0,0 +1,296 @@
+//! The `nvisy.v1.OperationService` implementation.
+
+use std::pin::Pin;
+
+use futures::Stream;
+use nvisy_postgres::model::WorkspaceOperation;
+use nvisy_postgres::query::{AdminScope, TenantScope, WorkspaceOperationRepository};
+use nvisy_postgres::types::{OperationId, OperationStatus, RunId};
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::TRACING_TARGET;
+use super::auth::{authenticate, find_workspace};
+use super::convert::{parse_wire_name, timestamp, wire_name};
+use super::proto::operation_service_server::OperationService;
+use super::proto::{
+    self, CancelRunRequest, CancelRunResponse, GetOperationRequest, ListOperationsRequest,
+    ListOperationsResponse, StartRunRequest,
+};
+use crate::extract::{AuthClaims, AuthProvider, Permission};
+use crate::handler::request::{CreatePipelineRun, CursorPagination};
+use crate::handler::response::{Operation, OperationsPage};
+use crate::handler::{
+    Error, ErrorKind, Result, authorize_all_kinds, cancel_run, start_run, view_permission,
+};
+use crate::service::{OPERATION_POLL_INTERVAL, ServiceState};
+
+/// Stream of an operation's states sent by `WatchOperation`.
+type OperationStream = Pin<Box<dyn Stream<Item = Result<proto::Operation, Status>> + Send>>;
+
+/// Serves the long-running operations of workspaces.
+pub(super) struct OperationApi {
+    state: ServiceState,
+}
+
+impl OperationApi {
+    /// Creates the service.
+    pub fn new(state: ServiceState) -> Self {
+        Self { state }
+    }
+
+    /// Finds an operation the caller may view, with the scope it was
+    /// authorized in.
+    async fn find(
+        &self,
+        auth_claims: &AuthClaims,
+        operation_id: &str,
+    ) -> Result<(WorkspaceOperation, TenantScope)> {
+        let operation_id = OperationId::parse(operation_id)
+            .map_err(|_| ErrorKind::BadRequest.with_message("Invalid operation id"))?;
+
+        let mut conn = self.state.postgres.get_connection().await?;
+
+        let admin =
+            AdminScope::open(&mut conn, "resolve operation workspace for authorization").await?;
+        let operation = conn
+            .find_workspace_operation_by_id(&admin, operation_id.as_uuid())
+            .await?
+            .ok_or_else(|| Error::not_found("operation"))?;
+
+        let scope = auth_claims
+            .authorize_tenant(
+                &mut conn,
+                operation.workspace_id,
+                view_permission(operation.kind),
+            )
+            .await?;
+
+        Ok((operation, scope))
+    }
+
+    /// Lists a page of a workspace's operations.
+    async fn list(&self, request: Request<ListOperationsRequest>) -> Result<OperationsPage> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let request = request.into_inner();
+
+        let status: Option<OperationStatus> = request
+            .status
+            .as_deref()
+            .map(|name| parse_wire_name("status", name))
+            .transpose()?;
+        let pagination = CursorPagination {
+            limit: request.limit,
+            after: request.after,
+        };
+
+        let mut conn = self.state.postgres.get_connection().await?;
+        let workspace = find_workspace(&mut conn, &request.workspace_slug).await?;
+
+        let scope = authorize_all_kinds(&auth_claims, &mut conn, workspace.id).await?;
+
+        let page = conn
+            .cursor_list_workspace_operations(scope, pagination.into(), status)
+            .await?;
+
+        Ok(OperationsPage::from_cursor_page(page, |operation| {
+            let download = self.state.operations.download_link(&operation);
+            Operation::from_model(operation, download)
+        }))
+    }
+
+    /// Starts a run in the background, returning its operation.
+    async fn start(&self, request: Request<StartRunRequest>) -> Result<WorkspaceOperation> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let request = request.into_inner();
+
+        let file_id: Uuid = request
+            .file_id
+            .parse()
+            .map_err(|_| ErrorKind::BadRequest.with_message("Invalid file id"))?;
+        let run_scope = request
+            .scope_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|err| {
+                ErrorKind::BadRequest
+                    .with_message("Invalid run scope")
+                    .with_resource("scope_json")
+                    .with_context(err.to_string())
+            })?;
+        let run_request = CreatePipelineRun {
+            file_id,
+            scope: run_scope,
+            document_password: request.document_password,
+        };
+        run_request.validate()?;
+
+        let state = &self.state;
+        let mut conn = state.postgres.get_connection().await?;
+        let workspace = find_workspace(&mut conn, &request.workspace_slug).await?;
+
+        let scope = auth_claims
+            .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
+            .await?;
+
+        let backends = state.residency.backends(workspace.data_region)?;
+        start_run(
+            state,
+            &mut conn,
+            scope,
+            backends,
+            auth_claims.account_id,
+            &request.pipeline_slug,
+            run_request,
+        )
+        .await
+    }
+
+    /// Cancels a run.
+    async fn cancel(&self, request: Request<CancelRunRequest>) -> Result<CancelRunResponse> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let request = request.into_inner();
+
+        let run_id = RunId::parse(&request.run_id)
+            .map_err(|_| ErrorKind::BadRequest.with_message("Invalid run id"))?;
+
+        let state = &self.state;
+        let mut conn = state.postgres.get_connection().await?;
+        let workspace = find_workspace(&mut conn, &request.workspace_slug).await?;
+
+        let scope = auth_claims
+            .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
+            .await?;
+
+        let (_, run, _) = cancel_run(
+            &mut conn,
+            &state.nats,
+            &state.operations,
+            scope,
+            run_id.as_uuid(),
+        )
+        .await?;
+
+        Ok(CancelRunResponse {
+            run_id: RunId::from_uuid(run.id).to_string(),
+            status: wire_name(run.status),
+            completed_at: run.completed_at.map(|at| timestamp(at.into())),
+        })
+    }
+}
+
+/// Renders an operation, linking to its artifact if it has one.
+fn render(state: &ServiceState, operation: WorkspaceOperation) -> proto::Operation {
+    let download = state.operations.download_link(&operation);
+    Operation::from_model(operation, download).into()
+}
+
+/// Streams an operation from `operation` on: every change, polled at the
+/// REST polling interval, until it reaches a terminal status.
+fn watch(
+    state: ServiceState,
+    scope: TenantScope,
+    mut operation: WorkspaceOperation,
+) -> OperationStream {
+    let stream = async_stream::stream! {
+        loop {
+            let (operation_id, updated_at) = (operation.id, operation.updated_at);
+            let terminal = operation.is_terminal();
+            yield Ok(render(&state, operation));
+            if terminal {
+                break;
+            }
+
+            let next = loop {
+                tokio::time::sleep(OPERATION_POLL_INTERVAL).await;
+                match find_again(&state, scope, operation_id).await {
+                    Ok(current) if current.updated_at == updated_at => {}
+                    result => break result,
+                }
+            };
+
+            operation = match next {
+                Ok(current) => current,
+                Err(error) => {
+                    tracing::warn!(
+                        target: TRACING_TARGET,
+                        operation_id = %operation_id,
+                        error = %error,
+                        "Operation watch interrupted"
+                    );
+                    yield Err(Status::from(error));
+                    break;
+                }
+            };
+        }
+    };
+
+    Box::pin(stream)
+}
+
+/// Reads the current state of an operation already authorized for the call.
+async fn find_again(
+    state: &ServiceState,
+    scope: TenantScope,
+    operation_id: uuid::Uuid,
+) -> Result<WorkspaceOperation> {
+    let mut conn = state.postgres.get_connection().await?;
+    conn.find_workspace_operation(scope, operation_id)
+        .await?
+        .ok_or_else(|| Error::not_found("operation"))
+}
+
+#[tonic::async_trait]
+impl OperationService for OperationApi {
+    type WatchOperationStream = OperationStream;
+
+    async fn get_operation(
+        &self,
+        request: Request<GetOperationRequest>,
+    ) -> Result<Response<proto::Operation>, Status> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let (operation, _) = self
+            .find(&auth_claims, &request.get_ref().operation_id)
+            .await?;
+        Ok(Response::new(render(&self.state, operation)))
+    }
+
+    async fn list_operations(
+        &self,
+        request: Request<ListOperationsRequest>,
+    ) -> Result<Response<ListOperationsResponse>, Status> {
+        let page = self.list(request).await?;
+        Ok(Response::new(ListOperationsResponse {
+            operations: page.items.into_iter().map(Into::into).collect(),
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    async fn watch_operation(
+        &self,
+        request: Request<GetOperationRequest>,
+    ) -> Result<Response<Self::WatchOperationStream>, Status> {
+        let auth_claims = authenticate(&self.state, request.metadata()).await?;
+        let (operation, scope) = self
+            .find(&auth_claims, &request.get_ref().operation_id)
+            .await?;
+        Ok(Response::new(watch(self.state.clone(), scope, operation)))
+    }
+
+    async fn start_run(
+        &self,
+        request: Request<StartRunRequest>,
+    ) -> Result<Response<proto::Operation>, Status> {
+        let operation = self.start(request).await?;
+        Ok(Response::new(render(&self.state, operation)))
+    }
+
+    async fn cancel_run(
+        &self,
+        request: Request<CancelRunRequest>,
+    ) -> Result<Response<CancelRunResponse>, Status> {
+        Ok(Response::new(self.cancel(request).await?))
+    }
+}
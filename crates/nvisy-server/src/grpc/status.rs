@@ -0,0 +1,48 @@
+//! Handler errors as gRPC statuses.
+
+use tonic::{Code, Status};
+
+use crate::handler::{Error, ErrorKind};
+
+impl From<Error<'_>> for Status {
+    fn from(error: Error<'_>) -> Self {
+        let code = code(error.kind());
+        let response = error.into_error_response();
+        Status::new(code, response.message)
+    }
+}
+
+/// Returns the gRPC code closest to an error kind's HTTP status.
+fn code(kind: ErrorKind) -> Code {
+    match kind {
+        ErrorKind::MissingPathParam | ErrorKind::BadRequest => Code::InvalidArgument,
+        ErrorKind::MissingAuthToken | ErrorKind::MalformedAuthToken | ErrorKind::Unauthorized => {
+            Code::Unauthenticated
+        }
+        ErrorKind::Forbidden => Code::PermissionDenied,
+        ErrorKind::NotFound => Code::NotFound,
+        ErrorKind::Conflict => Code::Aborted,
+        ErrorKind::ProtectedDocument => Code::FailedPrecondition,
+        ErrorKind::TooManyRequests => Code::ResourceExhausted,
+        ErrorKind::InternalServerError => Code::Internal,
+        ErrorKind::NotImplemented => Code::Unimplemented,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_keep_the_error_meaning() {
+        let status = Status::from(Error::not_found("file"));
+        assert_eq!(status.code(), Code::NotFound);
+
+        let status = Status::from(ErrorKind::BadRequest.with_message("Invalid file id"));
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(status.message(), "Invalid file id");
+
+        let status = Status::from(ErrorKind::MissingAuthToken.into_error());
+        assert_eq!(status.code(), Code::Unauthenticated);
+    }
+}
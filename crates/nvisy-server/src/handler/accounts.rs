@@ -11,12 +11,13 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use nvisy_postgres::model::Account as AccountModel;
 use nvisy_postgres::query::{AccountRepository, WorkspaceMemberRepository};
+use nvisy_postgres::types::ApiKeyScope;
 use nvisy_postgres::{PgClient, PgConn};
 use uuid::Uuid;
 
 use super::request::{AccountPathParams, UpdateAccount};
 use super::response::{Account, ErrorResponse, PublicAccount};
-use crate::extract::{AuthState, Json, Path, ValidateJson};
+use crate::extract::{AuthProvider, AuthState, Json, Path, ValidateJson};
 use crate::handler::{Error, ErrorKind, Result};
 use crate::service::{PasswordService, ServiceState};
 
@@ -121,6 +122,8 @@ async fn update_own_account(
 ) -> Result<(StatusCode, Json<Account>)> {
     tracing::debug!(target: TRACING_TARGET, "Updating account");
 
+    auth_claims.authorize_scope(ApiKeyScope::Admin)?;
+
     let mut conn = pg_client.get_connection().await?;
     let current_account = find_account(&mut conn, auth_claims.account_id).await?;
 
@@ -200,6 +203,8 @@ async fn delete_own_account(
 ) -> Result<StatusCode> {
     tracing::debug!(target: TRACING_TARGET, "Deleting account");
 
+    auth_claims.authorize_scope(ApiKeyScope::Admin)?;
+
     let mut conn = pg_client.get_connection().await?;
     conn.delete_account(auth_claims.account_id)
         .await?
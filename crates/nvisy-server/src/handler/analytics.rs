@@ -0,0 +1,216 @@
+//! Administrator analytics handlers.
+//!
+//! Reports that span every workspace, for operators rather than workspace
+//! members: only global administrators may read them. The storage cost
+//! report attributes each month's object storage to the workspaces it is
+//! stored for, priced at the storage prices recorded here, and can be
+//! exported as CSV for finance.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::query::{AdminScope, StorageCostRepository};
+use validator::Validate;
+
+use crate::extract::{AuthProvider, AuthState, Json, Query, ValidateJson};
+use crate::handler::Result;
+use crate::handler::request::{CreateStoragePrice, StorageCostQuery};
+use crate::handler::response::{ErrorResponse, StorageCostReport, StoragePrice, StoragePrices};
+use crate::service::ServiceState;
+
+/// Tracing target for analytics operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::analytics";
+
+/// Loads a month's storage cost report.
+async fn load_storage_costs(
+    pg_client: &PgClient,
+    query: &StorageCostQuery,
+) -> Result<StorageCostReport> {
+    query.validate()?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let admin = AdminScope::open(&mut conn, "report storage costs").await?;
+    let period_start = query.period_start();
+    let costs = conn
+        .list_storage_costs(&admin, period_start, query.workspace_id)
+        .await?;
+
+    Ok(StorageCostReport::from_models(period_start, costs))
+}
+
+/// Returns a month's storage costs per workspace.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn read_storage_costs(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    Query(query): Query<StorageCostQuery>,
+) -> Result<(StatusCode, Json<StorageCostReport>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading storage costs");
+
+    auth_state.authorize_admin()?;
+
+    let report = load_storage_costs(&pg_client, &query).await?;
+    Ok((StatusCode::OK, Json(report)))
+}
+
+fn read_storage_costs_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get storage costs")
+        .description(
+            "Returns a month's object storage per workspace, data region, pipeline stage and \
+             storage class, with the cost accrued at the storage prices in effect. The \
+             current month is reported as accrued so far. Requires global administrator \
+             rights.",
+        )
+        .response::<200, Json<StorageCostReport>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Exports a month's storage costs as CSV.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn export_storage_costs(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    Query(query): Query<StorageCostQuery>,
+) -> Result<(StatusCode, HeaderMap, Body)> {
+    tracing::debug!(target: TRACING_TARGET, "Exporting storage costs");
+
+    auth_state.authorize_admin()?;
+
+    let report = load_storage_costs(&pg_client, &query).await?;
+
+    let period = report.period_start.strftime("%Y-%m");
+    let disposition = format!("attachment; filename=\"storage-costs-{period}.csv\"")
+        .parse()
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_DISPOSITION, disposition);
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/csv; charset=utf-8"),
+    );
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        period_start = %report.period_start,
+        rows = report.costs.len(),
+        "Storage costs exported"
+    );
+
+    Ok((StatusCode::OK, headers, Body::from(report.to_csv())))
+}
+
+fn export_storage_costs_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Export storage costs")
+        .description(
+            "Downloads a month's storage cost report as CSV, one row per workspace, data \
+             region, pipeline stage and storage class. Prices and costs are in whole \
+             currency units. Requires global administrator rights.",
+        )
+        .response::<200, ()>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Lists the recorded storage prices.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn list_storage_prices(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+) -> Result<(StatusCode, Json<StoragePrices>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing storage prices");
+
+    auth_state.authorize_admin()?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let admin = AdminScope::open(&mut conn, "list storage prices").await?;
+    let prices = conn.list_storage_prices(&admin).await?;
+
+    let prices = prices.into_iter().map(StoragePrice::from_model).collect();
+    Ok((StatusCode::OK, Json(prices)))
+}
+
+fn list_storage_prices_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List storage prices")
+        .description(
+            "Lists every recorded storage price, latest first. The price in effect for a \
+             region and storage class is the latest one whose effective date has passed. \
+             Requires global administrator rights.",
+        )
+        .response::<200, Json<StoragePrices>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Records a storage price.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn create_storage_price(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    ValidateJson(request): ValidateJson<CreateStoragePrice>,
+) -> Result<(StatusCode, Json<StoragePrice>)> {
+    tracing::debug!(target: TRACING_TARGET, "Recording storage price");
+
+    auth_state.authorize_admin()?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let admin = AdminScope::open(&mut conn, "record storage price").await?;
+    let price = conn
+        .create_storage_price(&admin, request.into_model())
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        data_region = %price.data_region,
+        storage_class = %price.storage_class,
+        currency = %price.currency,
+        price_micros_per_gib_month = price.price_micros_per_gib_month,
+        "Storage price recorded"
+    );
+
+    Ok((StatusCode::CREATED, Json(StoragePrice::from_model(price))))
+}
+
+fn create_storage_price_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Record storage price")
+        .description(
+            "Records the price of a GiB-month of storage in a data region and storage class \
+             from a given day. A price recorded for the same region, class and day replaces \
+             it. Samples already accrued keep the price they were taken at. Requires global \
+             administrator rights.",
+        )
+        .response::<201, Json<StoragePrice>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Returns routes for the administrator analytics reports.
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/admin/analytics/storage-costs/",
+            get_with(read_storage_costs, read_storage_costs_docs),
+        )
+        .api_route(
+            "/admin/analytics/storage-costs/export/",
+            get_with(export_storage_costs, export_storage_costs_docs),
+        )
+        .api_route(
+            "/admin/analytics/storage-prices/",
+            get_with(list_storage_prices, list_storage_prices_docs)
+                .post_with(create_storage_price, create_storage_price_docs),
+        )
+        .with_path_items(|item| item.tag("Analytics"))
+}
@@ -0,0 +1,288 @@
+//! Account API key management handlers.
+//!
+//! API keys are long-lived, scoped credentials for programmatic access. Unlike
+//! API tokens, which are signed session JWTs, a key is an opaque secret looked
+//! up by digest, capped to the scopes chosen at creation, and rotatable with a
+//! grace window during which both the old and the new key authenticate.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::StatusCode;
+use jiff::Timestamp;
+use nvisy_postgres::model::{AccountApiKey, NewAccountApiKey};
+use nvisy_postgres::query::AccountApiKeyRepository;
+use nvisy_postgres::types::ApiKeyScope;
+use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgError};
+use uuid::Uuid;
+
+use super::request::{ApiKeyPathParams, CreateApiKey, CursorPagination, RotateApiKey};
+use super::response::{ApiKey, ApiKeyCreated, ApiKeysPage, ErrorResponse};
+use crate::extract::{AuthProvider, AuthState, Json, Path, Query, ValidateJson};
+use crate::handler::{ErrorKind, Result};
+use crate::service::{ApiKeyService, ServiceState};
+
+/// Tracing target for API key operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::api_keys";
+
+/// Creates a new API key for the authenticated account.
+///
+/// The key is only returned once, in this response.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn create_api_key(
+    State(pg_client): State<PgClient>,
+    State(api_keys): State<ApiKeyService>,
+    AuthState(auth_state): AuthState,
+    ValidateJson(request): ValidateJson<CreateApiKey>,
+) -> Result<(StatusCode, Json<ApiKeyCreated>)> {
+    tracing::debug!(target: TRACING_TARGET, "Creating API key");
+
+    auth_state.authorize_scope(ApiKeyScope::Admin)?;
+
+    let issued = api_keys.issue()?;
+    let new_key = request.into_model(auth_state.account_id, &issued)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let api_key = conn.create_account_api_key(new_key).await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        key_id = %api_key.id,
+        key_prefix = %api_key.key_prefix,
+        "API key created",
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiKeyCreated::from_model(api_key, issued.secret)),
+    ))
+}
+
+fn create_api_key_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create API key")
+        .description(
+            "Creates a scoped API key for programmatic access. \
+             The key is only shown once upon creation.",
+        )
+        .response::<201, Json<ApiKeyCreated>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Lists API keys for the authenticated account.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn list_api_keys(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    Query(pagination): Query<CursorPagination>,
+) -> Result<(StatusCode, Json<ApiKeysPage>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing API keys");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let page = conn
+        .cursor_list_account_api_keys(auth_state.account_id, pagination.into())
+        .await?;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        count = page.items.len(),
+        "API keys listed",
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(ApiKeysPage::from_cursor_page(page, ApiKey::from_model)),
+    ))
+}
+
+fn list_api_keys_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List API keys")
+        .description(
+            "Returns the active API keys for the authenticated account, \
+             including rotated keys still in their grace window.",
+        )
+        .response::<200, Json<ApiKeysPage>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+}
+
+/// Gets a specific API key by ID.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn read_api_key(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    Path(path): Path<ApiKeyPathParams>,
+) -> Result<(StatusCode, Json<ApiKey>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading API key");
+
+    let mut conn = pg_client.get_connection().await?;
+    let api_key = find_account_key(&mut conn, auth_state.account_id, path.key_id).await?;
+
+    Ok((StatusCode::OK, Json(ApiKey::from_model(api_key))))
+}
+
+fn read_api_key_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get API key")
+        .description("Returns details for a specific API key.")
+        .response::<200, Json<ApiKey>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Rotates an API key.
+///
+/// Issues a replacement with the same name, scopes and expiry. The old key
+/// keeps authenticating until the grace window ends, so clients can roll
+/// over without downtime.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn rotate_api_key(
+    State(pg_client): State<PgClient>,
+    State(api_keys): State<ApiKeyService>,
+    AuthState(auth_state): AuthState,
+    Path(path): Path<ApiKeyPathParams>,
+    ValidateJson(request): ValidateJson<RotateApiKey>,
+) -> Result<(StatusCode, Json<ApiKeyCreated>)> {
+    tracing::debug!(target: TRACING_TARGET, "Rotating API key");
+
+    auth_state.authorize_scope(ApiKeyScope::Admin)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let old_key = find_account_key(&mut conn, auth_state.account_id, path.key_id).await?;
+
+    if old_key.is_rotated() {
+        return Err(ErrorKind::Conflict
+            .with_resource("api_key")
+            .with_message("API key has already been rotated"));
+    }
+
+    let issued = api_keys.issue()?;
+    let grace_until = Timestamp::now()
+        .checked_add(request.grace_period())
+        .unwrap_or(Timestamp::MAX);
+
+    let new_key = NewAccountApiKey {
+        account_id: old_key.account_id,
+        name: old_key.name.clone(),
+        scopes: old_key.scopes.clone(),
+        key_prefix: issued.key_prefix.clone(),
+        key_hash: issued.key_hash.to_vec(),
+        expired_at: old_key.expired_at,
+    };
+
+    let (new_key, old_key) = conn
+        .transaction(async |conn| {
+            let new_key = conn.create_account_api_key(new_key).await?;
+            let old_key = conn
+                .retire_account_api_key(old_key.id, new_key.id, grace_until)
+                .await?;
+            Ok::<_, PgError>((new_key, old_key))
+        })
+        .await?;
+
+    api_keys.invalidate(&old_key).await;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        key_id = %new_key.id,
+        replaced_key_id = %old_key.id,
+        grace_until = ?old_key.expired_at,
+        "API key rotated",
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiKeyCreated::from_model(new_key, issued.secret)),
+    ))
+}
+
+fn rotate_api_key_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Rotate API key")
+        .description(
+            "Issues a replacement key with the same name, scopes and expiry. \
+             The old key keeps working until the grace window ends. \
+             The new key is only shown once.",
+        )
+        .response::<201, Json<ApiKeyCreated>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Revokes an API key, effective immediately.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn revoke_api_key(
+    State(pg_client): State<PgClient>,
+    State(api_keys): State<ApiKeyService>,
+    AuthState(auth_state): AuthState,
+    Path(path): Path<ApiKeyPathParams>,
+) -> Result<StatusCode> {
+    tracing::warn!(target: TRACING_TARGET, "Revoking API key");
+
+    auth_state.authorize_scope(ApiKeyScope::Admin)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let api_key = find_account_key(&mut conn, auth_state.account_id, path.key_id).await?;
+
+    if !conn.revoke_account_api_key(api_key.id).await? {
+        return Err(ErrorKind::BadRequest
+            .with_resource("api_key")
+            .with_message("API key is already revoked"));
+    }
+
+    api_keys.invalidate(&api_key).await;
+
+    tracing::warn!(target: TRACING_TARGET, key_id = %api_key.id, "API key revoked");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn revoke_api_key_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Revoke API key")
+        .description("Revokes an API key immediately. This action cannot be undone.")
+        .response::<204, ()>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Finds a non-revoked API key owned by the specified account.
+async fn find_account_key(
+    conn: &mut PgConn,
+    account_id: Uuid,
+    key_id: Uuid,
+) -> Result<AccountApiKey> {
+    conn.find_account_api_key(account_id, key_id)
+        .await?
+        .ok_or_else(|| {
+            ErrorKind::NotFound
+                .with_resource("api_key")
+                .with_message("API key not found")
+        })
+}
+
+/// Returns routes for account API key management.
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/api-keys/",
+            post_with(create_api_key, create_api_key_docs)
+                .get_with(list_api_keys, list_api_keys_docs),
+        )
+        .api_route(
+            "/api-keys/{keyId}/",
+            get_with(read_api_key, read_api_key_docs)
+                .delete_with(revoke_api_key, revoke_api_key_docs),
+        )
+        .api_route(
+            "/api-keys/{keyId}/rotate/",
+            post_with(rotate_api_key, rotate_api_key_docs),
+        )
+        .with_path_items(|item| item.tag("API Keys"))
+}
@@ -4,12 +4,16 @@
 //! registration (signup), and logout functionality. All authentication operations
 //! follow security best practices including:
 
+use std::sync::Arc;
+
 use aide::axum::ApiRouter;
 use aide::transform::TransformOperation;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum_extra::headers::UserAgent;
 use jiff::{Span, Timestamp};
+use nvisy_core::clock::Clock;
+use nvisy_core::id::IdGenerator;
 use nvisy_postgres::model::{Account, AccountApiToken, NewAccount, NewAccountApiToken};
 use nvisy_postgres::query::{AccountApiTokenRepository, AccountRepository};
 use nvisy_postgres::types::{ApiTokenType, HasDeletedAt};
@@ -57,6 +61,8 @@ async fn login(
     State(password): State<PasswordService>,
     State(auth_keys): State<SessionKeys>,
     State(ua_parser): State<UserAgentParser>,
+    State(clock): State<Arc<dyn Clock>>,
+    State(ids): State<Arc<dyn IdGenerator>>,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
     ValidateJson(request): ValidateJson<Login>,
 ) -> Result<(StatusCode, Json<AuthToken>)> {
@@ -106,14 +112,17 @@ async fn login(
         Some(acc) => acc,
     };
 
-    let expired_at = Timestamp::now() + Span::new().hours(90 * 24);
+    let now = clock.now();
+    let expired_at = now + Span::new().hours(90 * 24);
     let new_token = NewAccountApiToken {
+        id: Some(ids.generate()),
         account_id: account.id,
         name: ua_parser.parse(user_agent.as_str()),
         ip_address: None,
         user_agent: Some(user_agent.to_string()),
         is_remembered: Some(request.remember_me),
         session_type: Some(ApiTokenType::Web),
+        issued_at: Some(now.into()),
         expired_at: Some(expired_at.into()),
     };
 
@@ -125,8 +134,8 @@ async fn login(
     let response = AuthToken {
         api_token,
         username: account.username.clone(),
-        issued_at: Timestamp::from_second(auth_claims.issued_at).unwrap_or(Timestamp::now()),
-        expires_at: Timestamp::from_second(auth_claims.expires_at).unwrap_or(Timestamp::now()),
+        issued_at: Timestamp::from_second(auth_claims.issued_at).unwrap_or(now),
+        expires_at: Timestamp::from_second(auth_claims.expires_at).unwrap_or(now),
     };
 
     tracing::info!(
@@ -154,6 +163,8 @@ async fn signup(
     State(password): State<PasswordService>,
     State(auth_keys): State<SessionKeys>,
     State(ua_parser): State<UserAgentParser>,
+    State(clock): State<Arc<dyn Clock>>,
+    State(ids): State<Arc<dyn IdGenerator>>,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
     ValidateJson(request): ValidateJson<Signup>,
 ) -> Result<(StatusCode, Json<AuthToken>)> {
@@ -198,19 +209,22 @@ async fn signup(
         "Account created",
     );
 
-    let expired_at = Timestamp::now()
+    let now = clock.now();
+    let expired_at = now
         .checked_add(Span::new().hours(90 * 24))
         .ok()
         .map(JiffTimestamp::from);
 
     let user_agent_str = user_agent.to_string();
     let new_token = NewAccountApiToken {
+        id: Some(ids.generate()),
         account_id: account.id,
         name: ua_parser.parse(&user_agent_str),
         ip_address: None,
         user_agent: Some(user_agent_str),
         is_remembered: Some(request.remember_me),
         session_type: Some(ApiTokenType::Web),
+        issued_at: Some(now.into()),
         expired_at,
     };
     let account_api_token = conn.create_account_api_token(new_token).await?;
@@ -222,8 +236,8 @@ async fn signup(
     let response = AuthToken {
         api_token,
         username: account.username.clone(),
-        issued_at: Timestamp::from_second(auth_claims.issued_at).unwrap_or(Timestamp::now()),
-        expires_at: Timestamp::from_second(auth_claims.expires_at).unwrap_or(Timestamp::now()),
+        issued_at: Timestamp::from_second(auth_claims.issued_at).unwrap_or(now),
+        expires_at: Timestamp::from_second(auth_claims.expires_at).unwrap_or(now),
     };
 
     tracing::info!(
@@ -7,9 +7,9 @@
 //!
 //! # Encryption
 //!
-//! Connection data (credentials + context) is encrypted using workspace-derived
-//! keys (HKDF-SHA256 with XChaCha20-Poly1305). The encrypted data is stored in
-//! the database and never exposed through the API.
+//! Connection data (credentials + context) is sealed by the credential vault
+//! (AES-256-GCM under a versioned, workspace-derived key) before it reaches
+//! the database, and is never exposed through the API.
 
 use std::collections::HashMap;
 
@@ -20,7 +20,9 @@ use axum::http::StatusCode;
 use nvisy_postgres::model::{
     NewWorkspaceConnection, UpdateWorkspaceConnection, WorkspaceConnection,
 };
-use nvisy_postgres::query::{WorkspaceConnectionRepository, WorkspaceConnectionRunRepository};
+use nvisy_postgres::query::{
+    TenantScope, WorkspaceConnectionRepository, WorkspaceConnectionRunRepository,
+};
 use nvisy_postgres::types::{ConnectionId, Username};
 use nvisy_postgres::{PgClient, PgConn};
 use uuid::Uuid;
@@ -60,23 +62,22 @@ async fn create_connection(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageConnections)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageConnections)
         .await?;
 
-    let encrypted_data = crypto.encrypt_json(workspace.id, &request.data)?;
-
     let new_connection = NewWorkspaceConnection {
         workspace_id: workspace.id,
         account_id: auth_state.account_id,
         name: request.name,
         provider: request.provider,
-        encrypted_data,
         is_active: None,
         metadata: None,
     };
 
-    let connection = conn.create_workspace_connection(new_connection).await?;
+    let connection = conn
+        .create_workspace_connection(new_connection, &request.data, crypto.credentials())
+        .await?;
 
     tracing::info!(
         target: TRACING_TARGET,
@@ -85,12 +86,8 @@ async fn create_connection(
         "Connection created",
     );
 
-    let (connection, creator_username, last_synced) = find_connection(
-        &mut conn,
-        workspace.id,
-        ConnectionId::from_uuid(connection.id),
-    )
-    .await?;
+    let (connection, creator_username, last_synced) =
+        find_connection(&mut conn, scope, ConnectionId::from_uuid(connection.id)).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -138,22 +135,18 @@ async fn list_connections(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewConnections)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewConnections)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_connections(
-            workspace.id,
-            pagination.into(),
-            query.provider.as_deref(),
-        )
+        .cursor_list_workspace_connections(scope, pagination.into(), query.provider.as_deref())
         .await?;
 
     // One grouped query resolves last-synced for the whole page (not per row).
     let ids: Vec<Uuid> = page.items.iter().map(|(c, _)| c.id).collect();
     let last_synced: HashMap<Uuid, jiff::Timestamp> = conn
-        .last_successful_sync_at(&ids)
+        .last_successful_sync_at(scope, &ids)
         .await?
         .into_iter()
         .map(|(id, ts)| (id, ts.into()))
@@ -210,12 +203,12 @@ async fn read_connection(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewConnections)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewConnections)
         .await?;
 
     let (connection, creator_username, last_synced) =
-        find_connection(&mut conn, workspace.id, path_params.connection_id).await?;
+        find_connection(&mut conn, scope, path_params.connection_id).await?;
 
     tracing::debug!(target: TRACING_TARGET, "Workspace connection read");
 
@@ -262,29 +255,29 @@ async fn update_connection(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageConnections)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageConnections)
         .await?;
 
-    let (existing, _, _) =
-        find_connection(&mut conn, workspace.id, path_params.connection_id).await?;
+    let (existing, _, _) = find_connection(&mut conn, scope, path_params.connection_id).await?;
 
-    let encrypted_data = request
-        .data
-        .map(|data| crypto.encrypt_json(workspace.id, &data))
-        .transpose()?;
+    if let Some(data) = &request.data {
+        conn.store_connection_credentials(scope, existing.id, data, crypto.credentials())
+            .await?;
+    }
 
-    let update_data = UpdateWorkspaceConnection {
-        name: request.name,
-        encrypted_data,
-        ..Default::default()
-    };
+    if request.name.is_some() {
+        let update_data = UpdateWorkspaceConnection {
+            name: request.name,
+            ..Default::default()
+        };
 
-    conn.update_workspace_connection(existing.id, update_data)
-        .await?;
+        conn.update_workspace_connection(existing.id, update_data)
+            .await?;
+    }
 
     let (connection, creator_username, last_synced) =
-        find_connection(&mut conn, workspace.id, path_params.connection_id).await?;
+        find_connection(&mut conn, scope, path_params.connection_id).await?;
 
     tracing::info!(target: TRACING_TARGET, "Connection updated");
 
@@ -330,12 +323,11 @@ async fn delete_connection(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageConnections)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageConnections)
         .await?;
 
-    let (existing, _, _) =
-        find_connection(&mut conn, workspace.id, path_params.connection_id).await?;
+    let (existing, _, _) = find_connection(&mut conn, scope, path_params.connection_id).await?;
 
     conn.delete_workspace_connection(existing.id).await?;
 
@@ -357,15 +349,15 @@ fn delete_connection_docs(op: TransformOperation) -> TransformOperation {
 /// returns a NotFound error.
 async fn find_connection(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     connection_id: ConnectionId,
 ) -> Result<(WorkspaceConnection, Username, Option<jiff::Timestamp>)> {
     let (connection, creator_username) = conn
-        .find_connection_in_workspace_with_creator(workspace_id, connection_id.as_uuid())
+        .find_connection_in_workspace_with_creator(scope, connection_id.as_uuid())
         .await?
         .ok_or_else(|| Error::not_found("connection"))?;
     let last_synced = conn
-        .last_successful_sync_at(&[connection.id])
+        .last_successful_sync_at(scope, &[connection.id])
         .await?
         .into_iter()
         .next()
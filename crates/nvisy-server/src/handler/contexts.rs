@@ -13,10 +13,9 @@ use axum::http::StatusCode;
 use nvisy_postgres::model::{
     NewWorkspaceContext, UpdateWorkspaceContext, WorkspaceContext as WorkspaceContextModel,
 };
-use nvisy_postgres::query::WorkspaceContextRepository;
+use nvisy_postgres::query::{TenantScope, WorkspaceContextRepository};
 use nvisy_postgres::types::Username;
 use nvisy_postgres::{PgClient, PgConn};
-use uuid::Uuid;
 
 use crate::extract::{
     AuthProvider, AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceContext,
@@ -52,8 +51,8 @@ async fn create_context(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageContexts)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageContexts)
         .await?;
 
     let definition = &request.definition;
@@ -79,8 +78,7 @@ async fn create_context(
 
     tracing::info!(target: TRACING_TARGET, context_slug = %context.slug, "Context created");
 
-    let (context, creator_username) =
-        find_context(&mut conn, workspace.id, context.slug.as_str()).await?;
+    let (context, creator_username) = find_context(&mut conn, scope, context.slug.as_str()).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -121,12 +119,12 @@ async fn list_contexts(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewContexts)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewContexts)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_contexts(workspace.id, pagination.into())
+        .cursor_list_workspace_contexts(scope, pagination.into())
         .await?;
 
     tracing::debug!(
@@ -170,12 +168,12 @@ async fn read_context(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewContexts)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewContexts)
         .await?;
 
     let (context, creator_username) =
-        find_context(&mut conn, workspace.id, &path_params.context_slug).await?;
+        find_context(&mut conn, scope, &path_params.context_slug).await?;
 
     tracing::debug!(target: TRACING_TARGET, "Workspace context read");
 
@@ -223,11 +221,11 @@ async fn update_context(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageContexts)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageContexts)
         .await?;
 
-    let (existing, _) = find_context(&mut conn, workspace.id, &path_params.context_slug).await?;
+    let (existing, _) = find_context(&mut conn, scope, &path_params.context_slug).await?;
 
     let (version, definition) = match &request.definition {
         Some(definition) => {
@@ -248,7 +246,7 @@ async fn update_context(
     conn.update_workspace_context(existing.id, updates).await?;
 
     let (context, creator_username) =
-        find_context(&mut conn, workspace.id, &path_params.context_slug).await?;
+        find_context(&mut conn, scope, &path_params.context_slug).await?;
 
     tracing::info!(target: TRACING_TARGET, "Context updated");
 
@@ -292,11 +290,11 @@ async fn delete_context(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageContexts)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageContexts)
         .await?;
 
-    let (existing, _) = find_context(&mut conn, workspace.id, &path_params.context_slug).await?;
+    let (existing, _) = find_context(&mut conn, scope, &path_params.context_slug).await?;
 
     conn.delete_workspace_context(existing.id).await?;
 
@@ -318,10 +316,10 @@ fn delete_context_docs(op: TransformOperation) -> TransformOperation {
 /// returns a NotFound error.
 async fn find_context(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     context_slug: &str,
 ) -> Result<(WorkspaceContextModel, Username)> {
-    conn.find_context_in_workspace_by_slug(workspace_id, context_slug)
+    conn.find_context_in_workspace_by_slug(scope, context_slug)
         .await?
         .ok_or_else(|| Error::not_found("context"))
 }
@@ -0,0 +1,47 @@
+//! Cache error to HTTP error conversion implementation.
+//!
+//! Cache failures surface the same way whichever backend holds the caches.
+
+use nvisy_core::cache::CacheError;
+
+use super::http_error::{Error as HttpError, ErrorKind};
+
+impl<'a> From<CacheError> for HttpError<'a> {
+    fn from(cache_error: CacheError) -> Self {
+        match cache_error {
+            // Revision conflicts -> Conflict
+            CacheError::RevisionMismatch { ref key, .. } => ErrorKind::Conflict
+                .with_message("Resource has been modified")
+                .with_resource(key.clone())
+                .with_context("The resource was modified by another request"),
+
+            // Backend failures -> Internal Server Error
+            CacheError::Backend { backend, .. } => ErrorKind::InternalServerError
+                .with_message("Service temporarily unavailable")
+                .with_context(format!("The {backend} cache could not serve the request")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revision_mismatch_conversion() {
+        let cache_err = CacheError::revision_mismatch("workspace", 3);
+        let http_err: HttpError = cache_err.into();
+
+        assert_eq!(http_err.kind(), ErrorKind::Conflict);
+        assert!(http_err.message().unwrap().contains("modified"));
+    }
+
+    #[test]
+    fn test_backend_error_conversion() {
+        let cache_err = CacheError::backend("NATS", "connection closed");
+        let http_err: HttpError = cache_err.into();
+
+        assert_eq!(http_err.kind(), ErrorKind::InternalServerError);
+        assert!(http_err.message().unwrap().contains("unavailable"));
+    }
+}
@@ -119,6 +119,36 @@ impl<'a> Error<'a> {
         self.suggestion.as_deref()
     }
 
+    /// Builds the [`ErrorResponse`] this error is rendered as.
+    ///
+    /// Used directly where an error is recorded rather than returned, so the
+    /// stored shape matches the HTTP error body.
+    pub fn into_error_response(self) -> ErrorResponse<'a> {
+        let mut response = self.kind.response();
+
+        // Set custom message if provided
+        if let Some(message) = self.message {
+            response = response.with_message(message);
+        }
+
+        // Set custom resource if provided
+        if let Some(resource) = self.resource {
+            response = response.with_resource(resource);
+        }
+
+        // Set context if present
+        if let Some(context) = self.context {
+            response = response.with_context(context);
+        }
+
+        // Set suggestion if present
+        if let Some(suggestion) = self.suggestion {
+            response = response.with_suggestion(suggestion);
+        }
+
+        response
+    }
+
     /// Converts this error into a static version by cloning all borrowed data.
     pub fn into_owned(self) -> Error<'static> {
         Error {
@@ -203,29 +233,7 @@ impl std::error::Error for Error<'_> {}
 
 impl IntoResponse for Error<'_> {
     fn into_response(self) -> Response {
-        let mut response = self.kind.response();
-
-        // Set custom message if provided
-        if let Some(message) = self.message {
-            response = response.with_message(message);
-        }
-
-        // Set custom resource if provided
-        if let Some(resource) = self.resource {
-            response = response.with_resource(resource);
-        }
-
-        // Set context if present
-        if let Some(context) = self.context {
-            response = response.with_context(context);
-        }
-
-        // Set suggestion if present
-        if let Some(suggestion) = self.suggestion {
-            response = response.with_suggestion(suggestion);
-        }
-
-        response.into_response()
+        self.into_error_response().into_response()
     }
 }
 
@@ -268,6 +276,10 @@ pub enum ErrorKind {
     NotFound,
     /// 409 Conflict - Conflicting resource state
     Conflict,
+    /// 413 Payload Too Large - Request body over its size limit
+    PayloadTooLarge,
+    /// 422 Unprocessable Entity - Document is password-protected
+    ProtectedDocument,
     /// 429 Too Many Requests - Rate limit exceeded
     TooManyRequests,
 
@@ -336,6 +348,8 @@ impl ErrorKind {
             Self::Forbidden => ErrorResponse::FORBIDDEN,
             Self::NotFound => ErrorResponse::NOT_FOUND,
             Self::Conflict => ErrorResponse::CONFLICT,
+            Self::PayloadTooLarge => ErrorResponse::PAYLOAD_TOO_LARGE,
+            Self::ProtectedDocument => ErrorResponse::PROTECTED_DOCUMENT,
             Self::TooManyRequests => ErrorResponse::TOO_MANY_REQUESTS,
             Self::InternalServerError => ErrorResponse::INTERNAL_SERVER_ERROR,
             Self::NotImplemented => ErrorResponse::NOT_IMPLEMENTED,
@@ -494,6 +508,8 @@ mod tests {
             ErrorKind::MissingPathParam,
             ErrorKind::NotFound,
             ErrorKind::NotImplemented,
+            ErrorKind::PayloadTooLarge,
+            ErrorKind::ProtectedDocument,
             ErrorKind::Unauthorized,
         ];
 
@@ -0,0 +1,34 @@
+//! Inbound webhook error to HTTP error conversion.
+//!
+//! A request failing verification gets a bare 401, so a caller probing an
+//! endpoint learns nothing about the check it failed; the reason is logged.
+//! Authentic requests the server can't make sense of are a 400.
+
+use super::http_error::{Error as HttpError, ErrorKind};
+use crate::service::InboundError;
+
+/// Tracing target for inbound webhook error conversions.
+const TRACING_TARGET: &str = "nvisy_server::handler::inbound_webhooks";
+
+impl From<InboundError> for HttpError<'static> {
+    fn from(error: InboundError) -> Self {
+        match error {
+            InboundError::MissingHeader(_)
+            | InboundError::InvalidSignature
+            | InboundError::Expired => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %error,
+                    "Rejected inbound webhook request"
+                );
+
+                ErrorKind::Unauthorized
+                    .with_message("The request could not be verified")
+                    .with_resource("inbound_webhook")
+            }
+            InboundError::MalformedPayload(_) => ErrorKind::BadRequest
+                .with_message(error.to_string())
+                .with_resource("inbound_webhook"),
+        }
+    }
+}
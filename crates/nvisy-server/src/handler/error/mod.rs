@@ -1,13 +1,18 @@
 //! [`Error`], [`ErrorKind`] and [`Result`].
 
+mod cache_error;
 mod crypto_error;
 mod http_error;
+mod inbound_error;
 mod nats_error;
+mod oidc_error;
 mod pg_account;
 mod pg_document;
 mod pg_error;
 mod pg_pipeline;
 mod pg_workspace;
+mod residency_error;
+mod scim_error;
 mod webhook_error;
 
 pub use http_error::{Error, ErrorKind, Result};
@@ -0,0 +1,52 @@
+//! Single sign-on error to HTTP error conversion.
+//!
+//! Failed or replayed logins are the caller's problem; an unreachable or
+//! misbehaving provider is logged and reported as a server error.
+
+use super::http_error::{Error as HttpError, ErrorKind};
+use crate::service::OidcError;
+
+/// Tracing target for single sign-on error conversions.
+const TRACING_TARGET: &str = "nvisy_server::handler::oidc";
+
+impl From<OidcError> for HttpError<'static> {
+    fn from(error: OidcError) -> Self {
+        match error {
+            OidcError::Disabled => ErrorKind::NotFound
+                .with_message("Single sign-on is not configured")
+                .with_resource("sso"),
+            OidcError::UnknownState => ErrorKind::BadRequest
+                .with_message("The login has expired or was already completed")
+                .with_resource("state")
+                .with_suggestion("Start the login again"),
+            OidcError::Denied(_) => ErrorKind::Unauthorized
+                .with_message(error.to_string())
+                .with_resource("sso"),
+            OidcError::InvalidIdToken(_) => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %error,
+                    "Rejected ID token"
+                );
+
+                ErrorKind::Unauthorized
+                    .with_message("The identity provider response could not be verified")
+                    .with_resource("sso")
+            }
+            OidcError::NotProvisioned(_) => ErrorKind::Forbidden
+                .with_message(error.to_string())
+                .with_resource("account"),
+            OidcError::Provider(_) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %error,
+                    "Identity provider request failed"
+                );
+
+                ErrorKind::InternalServerError
+                    .with_message("The identity provider is unavailable")
+                    .with_context(error.to_string())
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //! Account-related constraint violation error handlers.
 
 use nvisy_postgres::types::{
-    AccountApiTokenConstraints, AccountConstraints, AccountNotificationConstraints,
+    AccountApiKeyConstraints, AccountApiTokenConstraints, AccountConstraints,
+    AccountIdentityConstraints, AccountNotificationConstraints,
 };
 
 use crate::handler::{Error, ErrorKind};
@@ -75,6 +76,52 @@ impl From<AccountApiTokenConstraints> for Error<'static> {
     }
 }
 
+impl From<AccountApiKeyConstraints> for Error<'static> {
+    fn from(c: AccountApiKeyConstraints) -> Self {
+        let error = match c {
+            AccountApiKeyConstraints::NameNotEmpty => {
+                ErrorKind::BadRequest.with_message("Key name cannot be empty")
+            }
+            AccountApiKeyConstraints::NameLength => {
+                ErrorKind::BadRequest.with_message("Key name is too long")
+            }
+            AccountApiKeyConstraints::ScopesNotEmpty => {
+                ErrorKind::BadRequest.with_message("API key must have at least one scope")
+            }
+            AccountApiKeyConstraints::KeyHashLength
+            | AccountApiKeyConstraints::KeyHashUnique
+            | AccountApiKeyConstraints::ExpiredAfterCreated
+            | AccountApiKeyConstraints::DeletedAfterCreated
+            | AccountApiKeyConstraints::LastUsedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("account_api_key")
+    }
+}
+
+impl From<AccountIdentityConstraints> for Error<'static> {
+    fn from(c: AccountIdentityConstraints) -> Self {
+        let error = match c {
+            AccountIdentityConstraints::IssuerSubjectUnique => {
+                ErrorKind::Conflict.with_message("This identity is already linked to an account")
+            }
+            AccountIdentityConstraints::SubjectLength => {
+                ErrorKind::BadRequest.with_message("Identity provider subject is too long")
+            }
+            AccountIdentityConstraints::IssuerNotEmpty
+            | AccountIdentityConstraints::IssuerLength
+            | AccountIdentityConstraints::SubjectNotEmpty
+            | AccountIdentityConstraints::LastLoginAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("account_identity")
+    }
+}
+
 impl From<AccountNotificationConstraints> for Error<'static> {
     fn from(constraint: AccountNotificationConstraints) -> Self {
         let error = match constraint {
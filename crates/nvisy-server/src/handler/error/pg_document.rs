@@ -1,6 +1,8 @@
 //! File-related constraint violation error handlers.
 
-use nvisy_postgres::types::WorkspaceFileConstraints;
+use nvisy_postgres::types::{
+    WorkspaceFileConstraints, WorkspaceFileShareConstraints, WorkspaceTemporaryObjectConstraints,
+};
 
 use crate::handler::{Error, ErrorKind};
 
@@ -38,6 +40,8 @@ impl From<WorkspaceFileConstraints> for Error<'static> {
             WorkspaceFileConstraints::VersionNumberMin => {
                 ErrorKind::BadRequest.with_message("Version number must be at least 1")
             }
+            WorkspaceFileConstraints::EncryptedPasswordProtected => ErrorKind::Conflict
+                .with_message("A password can only be stored for a password-protected file"),
             WorkspaceFileConstraints::WorkspaceIdIdUnique => {
                 ErrorKind::Conflict.with_message("A file with this identifier already exists")
             }
@@ -51,3 +55,47 @@ impl From<WorkspaceFileConstraints> for Error<'static> {
         error.with_resource("file")
     }
 }
+
+impl From<WorkspaceFileShareConstraints> for Error<'static> {
+    fn from(c: WorkspaceFileShareConstraints) -> Self {
+        let error = match c {
+            WorkspaceFileShareConstraints::RecipientLength => ErrorKind::BadRequest
+                .with_message("Recipient must be between 1 and 255 characters long"),
+            WorkspaceFileShareConstraints::PagesRange => {
+                ErrorKind::BadRequest.with_message("Pages must be numbered from 1")
+            }
+            WorkspaceFileShareConstraints::MaxViewsMin => {
+                ErrorKind::BadRequest.with_message("Maximum views must be at least 1")
+            }
+            WorkspaceFileShareConstraints::ExpiresAfterCreated => {
+                ErrorKind::BadRequest.with_message("Expiration must be in the future")
+            }
+            WorkspaceFileShareConstraints::TokenHashLength
+            | WorkspaceFileShareConstraints::TokenHashUnique
+            | WorkspaceFileShareConstraints::ViewCountRange
+            | WorkspaceFileShareConstraints::LastViewedAfterCreated
+            | WorkspaceFileShareConstraints::RevokedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("share_link")
+    }
+}
+
+impl From<WorkspaceTemporaryObjectConstraints> for Error<'static> {
+    fn from(c: WorkspaceTemporaryObjectConstraints) -> Self {
+        // Temporary objects are only registered by the server itself, so any
+        // violation is a server fault rather than a bad request.
+        let error = match c {
+            WorkspaceTemporaryObjectConstraints::BucketLength
+            | WorkspaceTemporaryObjectConstraints::ObjectKeyLength
+            | WorkspaceTemporaryObjectConstraints::ObjectUnique
+            | WorkspaceTemporaryObjectConstraints::ExpiresAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("file")
+    }
+}
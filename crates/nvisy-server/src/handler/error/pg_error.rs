@@ -20,16 +20,26 @@ impl From<ConstraintViolation> for Error<'static> {
             ConstraintViolation::Account(c) => c.into(),
             ConstraintViolation::AccountNotification(c) => c.into(),
             ConstraintViolation::AccountApiToken(c) => c.into(),
+            ConstraintViolation::AccountApiKey(c) => c.into(),
+            ConstraintViolation::AccountIdentity(c) => c.into(),
             ConstraintViolation::Workspace(c) => c.into(),
             ConstraintViolation::WorkspaceMember(c) => c.into(),
+            ConstraintViolation::WorkspaceCustomRole(c) => c.into(),
             ConstraintViolation::WorkspaceInvite(c) => c.into(),
             ConstraintViolation::WorkspaceActivityLog(c) => c.into(),
             ConstraintViolation::WorkspaceWebhook(c) => c.into(),
+            ConstraintViolation::WorkspaceInboundWebhook(c) => c.into(),
+            ConstraintViolation::WorkspaceOperation(c) => c.into(),
+            ConstraintViolation::WorkspaceRetentionPolicy(c) => c.into(),
+            ConstraintViolation::WorkspaceLegalHold(c) => c.into(),
             ConstraintViolation::WorkspaceFile(c) => c.into(),
+            ConstraintViolation::WorkspaceFileShare(c) => c.into(),
+            ConstraintViolation::WorkspaceTemporaryObject(c) => c.into(),
             ConstraintViolation::WorkspacePipeline(c) => c.into(),
             ConstraintViolation::WorkspacePipelineRun(c) => c.into(),
             ConstraintViolation::WorkspacePipelineArtifact(c) => c.into(),
             ConstraintViolation::WorkspacePipelineReference(c) => c.into(),
+            ConstraintViolation::WorkspaceDetectionReview(c) => c.into(),
             ConstraintViolation::WorkspaceConnection(c) => c.into(),
             ConstraintViolation::WorkspaceConnectionRun(c) => c.into(),
             ConstraintViolation::WorkspaceContext(c) => c.into(),
@@ -103,6 +113,14 @@ impl From<PgError> for Error<'static> {
                 );
                 ErrorKind::InternalServerError.into_error()
             }
+            PgError::Credential(credential_error) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %credential_error,
+                    "credential vault error"
+                );
+                ErrorKind::InternalServerError.into_error()
+            }
             PgError::Jiff(jiff_error) => {
                 tracing::error!(
                     target: TRACING_TARGET,
@@ -2,47 +2,56 @@
 
 use nvisy_postgres::types::{
     WorkspaceConnectionConstraints, WorkspaceConnectionRunConstraints, WorkspaceContextConstraints,
-    WorkspacePipelineArtifactConstraints, WorkspacePipelineConstraints,
-    WorkspacePipelineReferenceConstraints, WorkspacePipelineRunConstraints,
-    WorkspacePolicyConstraints,
+    WorkspaceDetectionReviewConstraints, WorkspacePipelineArtifactConstraints,
+    WorkspacePipelineConstraints, WorkspacePipelineReferenceConstraints,
+    WorkspacePipelineRunConstraints, WorkspacePolicyConstraints,
 };
 
 use crate::handler::{Error, ErrorKind};
 
 impl From<WorkspacePipelineConstraints> for Error<'static> {
     fn from(c: WorkspacePipelineConstraints) -> Self {
-        let error =
-            match c {
-                WorkspacePipelineConstraints::NameLength => ErrorKind::BadRequest
-                    .with_message("Pipeline name must be between 1 and 255 characters long"),
-                WorkspacePipelineConstraints::DescriptionLength => ErrorKind::BadRequest
-                    .with_message("Pipeline description must be at most 4096 characters long"),
-                WorkspacePipelineConstraints::DefinitionSize => ErrorKind::BadRequest
-                    .with_message("Pipeline definition size exceeds maximum limit"),
-                WorkspacePipelineConstraints::MetadataSize => ErrorKind::BadRequest
-                    .with_message("Pipeline metadata size exceeds maximum limit"),
-                WorkspacePipelineConstraints::ScheduleCronLength => {
-                    ErrorKind::BadRequest.with_message("Pipeline schedule cron length is invalid")
-                }
-                WorkspacePipelineConstraints::ScheduleRequiresCron => ErrorKind::BadRequest
-                    .with_message("A scheduled pipeline requires a cron expression"),
-                WorkspacePipelineConstraints::ScheduleTzLength => ErrorKind::BadRequest
-                    .with_message("Pipeline schedule timezone length is invalid"),
-                WorkspacePipelineConstraints::SlugLength => ErrorKind::BadRequest
-                    .with_message("Pipeline slug must be between 3 and 32 characters long"),
-                WorkspacePipelineConstraints::SlugFormat => ErrorKind::BadRequest.with_message(
-                    "Pipeline slug must be lowercase alphanumeric with single internal dashes",
-                ),
-                WorkspacePipelineConstraints::SlugUnique => {
-                    ErrorKind::Conflict.with_message("A pipeline with this slug already exists")
-                }
-                WorkspacePipelineConstraints::WorkspaceIdIdUnique => ErrorKind::Conflict
-                    .with_message("A pipeline with this identifier already exists"),
-                WorkspacePipelineConstraints::UpdatedAfterCreated
-                | WorkspacePipelineConstraints::DeletedAfterCreated => {
-                    ErrorKind::InternalServerError.into_error()
-                }
-            };
+        let error = match c {
+            WorkspacePipelineConstraints::NameLength => ErrorKind::BadRequest
+                .with_message("Pipeline name must be between 1 and 255 characters long"),
+            WorkspacePipelineConstraints::DescriptionLength => ErrorKind::BadRequest
+                .with_message("Pipeline description must be at most 4096 characters long"),
+            WorkspacePipelineConstraints::DefinitionSize => {
+                ErrorKind::BadRequest.with_message("Pipeline definition size exceeds maximum limit")
+            }
+            WorkspacePipelineConstraints::MetadataSize => {
+                ErrorKind::BadRequest.with_message("Pipeline metadata size exceeds maximum limit")
+            }
+            WorkspacePipelineConstraints::ScheduleCronLength => {
+                ErrorKind::BadRequest.with_message("Pipeline schedule cron length is invalid")
+            }
+            WorkspacePipelineConstraints::ScheduleRequiresCron => ErrorKind::BadRequest
+                .with_message("A scheduled pipeline requires a cron expression"),
+            WorkspacePipelineConstraints::ScheduleTzLength => {
+                ErrorKind::BadRequest.with_message("Pipeline schedule timezone length is invalid")
+            }
+            WorkspacePipelineConstraints::InputRetentionDaysRange => ErrorKind::BadRequest
+                .with_message("Input artifact retention must be between 1 and 36500 days"),
+            WorkspacePipelineConstraints::IntermediateRetentionDaysRange => ErrorKind::BadRequest
+                .with_message("Intermediate artifact retention must be between 1 and 36500 days"),
+            WorkspacePipelineConstraints::OutputRetentionDaysRange => ErrorKind::BadRequest
+                .with_message("Output artifact retention must be between 1 and 36500 days"),
+            WorkspacePipelineConstraints::SlugLength => ErrorKind::BadRequest
+                .with_message("Pipeline slug must be between 3 and 32 characters long"),
+            WorkspacePipelineConstraints::SlugFormat => ErrorKind::BadRequest.with_message(
+                "Pipeline slug must be lowercase alphanumeric with single internal dashes",
+            ),
+            WorkspacePipelineConstraints::SlugUnique => {
+                ErrorKind::Conflict.with_message("A pipeline with this slug already exists")
+            }
+            WorkspacePipelineConstraints::WorkspaceIdIdUnique => {
+                ErrorKind::Conflict.with_message("A pipeline with this identifier already exists")
+            }
+            WorkspacePipelineConstraints::UpdatedAfterCreated
+            | WorkspacePipelineConstraints::DeletedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
 
         error.with_resource("pipeline")
     }
@@ -59,6 +68,12 @@ impl From<WorkspacePipelineRunConstraints> for Error<'static> {
             WorkspacePipelineRunConstraints::IdempotencyKeyLength => {
                 ErrorKind::BadRequest.with_message("Idempotency key must be 1 to 255 characters")
             }
+            WorkspacePipelineRunConstraints::InputDigestLength
+            | WorkspacePipelineRunConstraints::InputDigestAnalyzed => {
+                ErrorKind::InternalServerError.into_error()
+            }
+            WorkspacePipelineRunConstraints::OutputsImmutable => ErrorKind::Conflict
+                .with_message("The outputs of a pipeline run cannot be changed once stored"),
             WorkspacePipelineRunConstraints::CompletedAfterStarted => {
                 ErrorKind::InternalServerError.into_error()
             }
@@ -74,6 +89,8 @@ impl From<WorkspacePipelineArtifactConstraints> for Error<'static> {
             WorkspacePipelineArtifactConstraints::MetadataSize => {
                 ErrorKind::BadRequest.with_message("Artifact metadata size exceeds maximum limit")
             }
+            WorkspacePipelineArtifactConstraints::Immutable => ErrorKind::Conflict
+                .with_message("Pipeline artifacts cannot be changed once recorded"),
         };
 
         error.with_resource("pipeline_artifact")
@@ -105,6 +122,33 @@ impl From<WorkspacePipelineReferenceConstraints> for Error<'static> {
     }
 }
 
+impl From<WorkspaceDetectionReviewConstraints> for Error<'static> {
+    fn from(c: WorkspaceDetectionReviewConstraints) -> Self {
+        let error = match c {
+            WorkspaceDetectionReviewConstraints::DetectionIdLength => ErrorKind::BadRequest
+                .with_message("Detection identifier must be between 1 and 256 characters long"),
+            WorkspaceDetectionReviewConstraints::CommentLength => ErrorKind::BadRequest
+                .with_message("Review comment must be at most 2048 characters long"),
+            WorkspaceDetectionReviewConstraints::DecisionValid => {
+                ErrorKind::BadRequest.with_message("A review decision cannot be superseded")
+            }
+            // Two reviewers deciding on the same detection at once race for
+            // the next version; the loser can retry against the new history.
+            WorkspaceDetectionReviewConstraints::VersionUnique
+            | WorkspaceDetectionReviewConstraints::CurrentUnique => ErrorKind::Conflict
+                .with_message("The detection was reviewed concurrently, please retry"),
+            WorkspaceDetectionReviewConstraints::VersionMin
+            | WorkspaceDetectionReviewConstraints::StatusValid
+            | WorkspaceDetectionReviewConstraints::SupersededConsistent
+            | WorkspaceDetectionReviewConstraints::SupersededAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("detection_review")
+    }
+}
+
 impl From<WorkspaceConnectionConstraints> for Error<'static> {
     fn from(c: WorkspaceConnectionConstraints) -> Self {
         let error =
@@ -123,7 +167,8 @@ impl From<WorkspaceConnectionConstraints> for Error<'static> {
                 WorkspaceConnectionConstraints::NameUnique => {
                     ErrorKind::Conflict.with_message("A connection with this name already exists")
                 }
-                WorkspaceConnectionConstraints::UpdatedAfterCreated
+                WorkspaceConnectionConstraints::CredentialKeyVersionMin
+                | WorkspaceConnectionConstraints::UpdatedAfterCreated
                 | WorkspaceConnectionConstraints::DeletedAfterCreated => {
                     ErrorKind::InternalServerError.into_error()
                 }
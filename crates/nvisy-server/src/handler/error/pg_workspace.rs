@@ -1,8 +1,10 @@
 //! Workspace-related constraint violation error handlers.
 
 use nvisy_postgres::types::{
-    WorkspaceActivitiesConstraints, WorkspaceConstraints, WorkspaceInviteConstraints,
-    WorkspaceMemberConstraints, WorkspaceWebhookConstraints,
+    WorkspaceActivitiesConstraints, WorkspaceConstraints, WorkspaceCustomRoleConstraints,
+    WorkspaceInboundWebhookConstraints, WorkspaceInviteConstraints, WorkspaceLegalHoldConstraints,
+    WorkspaceMemberConstraints, WorkspaceOperationConstraints, WorkspaceRetentionPolicyConstraints,
+    WorkspaceWebhookConstraints,
 };
 
 use crate::handler::{Error, ErrorKind};
@@ -49,12 +51,44 @@ impl From<WorkspaceMemberConstraints> for Error<'static> {
             WorkspaceMemberConstraints::UpdatedAfterCreated => {
                 ErrorKind::InternalServerError.into_error()
             }
+            WorkspaceMemberConstraints::CustomRoleReference => {
+                ErrorKind::BadRequest.with_message("Custom role does not exist in this workspace")
+            }
         };
 
         error.with_resource("workspace_member")
     }
 }
 
+impl From<WorkspaceCustomRoleConstraints> for Error<'static> {
+    fn from(c: WorkspaceCustomRoleConstraints) -> Self {
+        let error = match c {
+            WorkspaceCustomRoleConstraints::NameLength => ErrorKind::BadRequest
+                .with_message("Role name must be between 1 and 64 characters long"),
+            WorkspaceCustomRoleConstraints::DescriptionLength => {
+                ErrorKind::BadRequest.with_message("Role description is too long")
+            }
+            WorkspaceCustomRoleConstraints::PermissionsCount => {
+                ErrorKind::BadRequest.with_message("Role grants too many permissions")
+            }
+            WorkspaceCustomRoleConstraints::PermissionsFormat => {
+                ErrorKind::BadRequest.with_message("Role permissions are malformed")
+            }
+            WorkspaceCustomRoleConstraints::WorkspaceIdIdUnique => {
+                ErrorKind::Conflict.with_message("A role with this identifier already exists")
+            }
+            WorkspaceCustomRoleConstraints::NameUnique => {
+                ErrorKind::Conflict.with_message("A role with this name already exists")
+            }
+            WorkspaceCustomRoleConstraints::UpdatedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("workspace_custom_role")
+    }
+}
+
 impl From<WorkspaceInviteConstraints> for Error<'static> {
     fn from(c: WorkspaceInviteConstraints) -> Self {
         let error = match c {
@@ -113,6 +147,9 @@ impl From<WorkspaceWebhookConstraints> for Error<'static> {
             WorkspaceWebhookConstraints::HeadersSize => {
                 ErrorKind::BadRequest.with_message("Webhook headers size is too large")
             }
+            WorkspaceWebhookConstraints::PayloadFiltersCount => {
+                ErrorKind::BadRequest.with_message("Webhook has too many payload filters")
+            }
             WorkspaceWebhookConstraints::WorkspaceIdIdUnique => {
                 ErrorKind::Conflict.with_message("A webhook with this identifier already exists")
             }
@@ -125,3 +162,81 @@ impl From<WorkspaceWebhookConstraints> for Error<'static> {
         error.with_resource("workspace_webhook")
     }
 }
+
+impl From<WorkspaceInboundWebhookConstraints> for Error<'static> {
+    fn from(c: WorkspaceInboundWebhookConstraints) -> Self {
+        let error = match c {
+            WorkspaceInboundWebhookConstraints::DisplayNameLength => ErrorKind::BadRequest
+                .with_message("Inbound webhook name must be between 1 and 128 characters long"),
+            WorkspaceInboundWebhookConstraints::UpdatedAfterCreated
+            | WorkspaceInboundWebhookConstraints::DeletedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("workspace_inbound_webhook")
+    }
+}
+
+impl From<WorkspaceOperationConstraints> for Error<'static> {
+    fn from(c: WorkspaceOperationConstraints) -> Self {
+        // Operations are written by the server alone, never from request input.
+        let error = match c {
+            WorkspaceOperationConstraints::ProgressRange
+            | WorkspaceOperationConstraints::ResultSize
+            | WorkspaceOperationConstraints::ErrorSize
+            | WorkspaceOperationConstraints::ArtifactComplete
+            | WorkspaceOperationConstraints::ArtifactNameLength
+            | WorkspaceOperationConstraints::ArtifactSizeMin
+            | WorkspaceOperationConstraints::UpdatedAfterCreated
+            | WorkspaceOperationConstraints::StartedAfterCreated
+            | WorkspaceOperationConstraints::CompletedAfterCreated
+            | WorkspaceOperationConstraints::ExpiresAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("workspace_operation")
+    }
+}
+
+impl From<WorkspaceRetentionPolicyConstraints> for Error<'static> {
+    fn from(c: WorkspaceRetentionPolicyConstraints) -> Self {
+        let error = match c {
+            WorkspaceRetentionPolicyConstraints::FileRetentionDaysRange => ErrorKind::BadRequest
+                .with_message("File retention must be between 1 and 36500 days"),
+            WorkspaceRetentionPolicyConstraints::RunRetentionDaysRange => {
+                ErrorKind::BadRequest.with_message("Run retention must be between 1 and 36500 days")
+            }
+            WorkspaceRetentionPolicyConstraints::ActivityRetentionDaysRange => {
+                ErrorKind::BadRequest
+                    .with_message("Audit log retention must be between 1 and 36500 days")
+            }
+            WorkspaceRetentionPolicyConstraints::UpdatedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+        };
+
+        error.with_resource("retention_policy")
+    }
+}
+
+impl From<WorkspaceLegalHoldConstraints> for Error<'static> {
+    fn from(c: WorkspaceLegalHoldConstraints) -> Self {
+        let error = match c {
+            WorkspaceLegalHoldConstraints::ReasonLength => ErrorKind::BadRequest
+                .with_message("Legal hold reason must be between 1 and 1024 characters long"),
+            WorkspaceLegalHoldConstraints::ReleasedAfterCreated => {
+                ErrorKind::InternalServerError.into_error()
+            }
+            WorkspaceLegalHoldConstraints::FileHeld => {
+                ErrorKind::Conflict.with_message("File is under a legal hold")
+            }
+            WorkspaceLegalHoldConstraints::WorkspaceHeld => {
+                ErrorKind::Conflict.with_message("Workspace is under a legal hold")
+            }
+        };
+
+        error.with_resource("legal_hold")
+    }
+}
@@ -0,0 +1,31 @@
+//! Residency error to HTTP error conversion.
+//!
+//! Asking for a region the deployment does not offer is a client error; a
+//! workspace pinned to a region without backends is a deployment fault.
+
+use super::http_error::{Error as HttpError, ErrorKind};
+use crate::service::ResidencyError;
+
+/// Tracing target for residency error conversions.
+const TRACING_TARGET: &str = "nvisy_server::handler::residency";
+
+impl From<ResidencyError> for HttpError<'static> {
+    fn from(error: ResidencyError) -> Self {
+        match error {
+            ResidencyError::UnsupportedRegion(_) => ErrorKind::BadRequest
+                .with_message(error.to_string())
+                .with_resource("data_region"),
+            ResidencyError::MissingBackends(_) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %error,
+                    "Workspace routed to an unconfigured data region"
+                );
+
+                ErrorKind::InternalServerError
+                    .with_message("Storage for the workspace's data region is unavailable")
+                    .with_context(error.to_string())
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! SCIM protocol error to HTTP error conversion.
+//!
+//! The RFC 7644 `scimType` keyword travels in the error context, where
+//! provisioning clients that log it can pick it up.
+
+use super::http_error::{Error as HttpError, ErrorKind};
+use crate::service::ScimError;
+
+impl From<ScimError> for HttpError<'static> {
+    fn from(error: ScimError) -> Self {
+        let kind = match error {
+            ScimError::Uniqueness(_) => ErrorKind::Conflict,
+            _ => ErrorKind::BadRequest,
+        };
+
+        kind.with_message(error.to_string())
+            .with_resource("scim")
+            .with_context(format!("scimType: {}", error.scim_type()))
+    }
+}
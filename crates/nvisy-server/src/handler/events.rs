@@ -0,0 +1,165 @@
+//! Real-time workspace event handlers.
+//!
+//! Streams workspace domain events to clients as Server-Sent Events so that
+//! the frontend does not need to poll for changes. Each event carries its
+//! stream sequence as the SSE id, letting a reconnecting client resume from
+//! the `Last-Event-ID` it last received.
+
+use std::time::Duration;
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use nvisy_nats::NatsClient;
+use nvisy_nats::stream::{TypedMessage, WorkspaceEvent, workspace_events_filter};
+use nvisy_postgres::PgClient;
+
+use crate::extract::{AuthProvider, AuthState, Json, Permission, WorkspaceContext};
+use crate::handler::response::ErrorResponse;
+use crate::handler::{ErrorKind, Result};
+use crate::service::ServiceState;
+
+/// Tracing target for workspace event streaming.
+const TRACING_TARGET: &str = "nvisy_server::handler::events";
+
+/// Header a reconnecting `EventSource` sends with the last id it received.
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// How long to wait for a new event before polling the stream again.
+const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Interval between heartbeat comments on an idle stream.
+///
+/// Kept below the common 30–60s idle timeouts of reverse proxies.
+const EVENT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Streams a workspace's events as Server-Sent Events.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn stream_workspace_events(
+    State(pg_client): State<PgClient>,
+    State(nats): State<NatsClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let last_event_id = last_event_id(&headers)?;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        last_event_id = ?last_event_id,
+        "Streaming workspace events"
+    );
+
+    let mut conn = pg_client.get_connection().await?;
+
+    auth_state
+        .authorize_workspace(&mut conn, workspace.id, Permission::ViewWorkspace)
+        .await?;
+
+    let subscriber = nats.workspace_event_subscriber().await?;
+    let mut events = subscriber
+        .follow(&workspace_events_filter(workspace.id), last_event_id)
+        .await?;
+
+    let events = async_stream::stream! {
+        loop {
+            match events.next_with_timeout(EVENT_POLL_INTERVAL).await {
+                Ok(Some(message)) => yield workspace_event(&message),
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::warn!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Workspace event stream interrupted"
+                    );
+                    break;
+                }
+            }
+        }
+    };
+
+    let keep_alive = KeepAlive::new().interval(EVENT_KEEP_ALIVE);
+    Ok(Sse::new(events).keep_alive(keep_alive).into_response())
+}
+
+fn stream_workspace_events_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Stream workspace events")
+        .description(
+            "Streams the workspace's domain events as Server-Sent Events of type `event`. \
+             Send `Last-Event-ID` to resume after the last received event.",
+        )
+        .response::<200, ()>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Parses the `Last-Event-ID` header into a stream sequence.
+fn last_event_id(headers: &HeaderMap) -> Result<Option<u64>> {
+    let Some(value) = headers.get(LAST_EVENT_ID_HEADER) else {
+        return Ok(None);
+    };
+
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            ErrorKind::BadRequest
+                .with_message("Last-Event-ID must be an event id received from this stream")
+                .with_resource("workspace_event")
+        })
+}
+
+/// Serializes a workspace event as an SSE `event` with its sequence as id.
+fn workspace_event(message: &TypedMessage<WorkspaceEvent>) -> Result<Event, axum::Error> {
+    let mut event = Event::default().event("event");
+    if let Ok(sequence) = message.sequence() {
+        event = event.id(sequence.to_string());
+    }
+
+    event.json_data(message.payload())
+}
+
+/// Returns a [`Router`] with all workspace event routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/workspaces/{workspaceSlug}/events/",
+            get_with(stream_workspace_events, stream_workspace_events_docs),
+        )
+        .with_path_items(|item| item.tag("Events"))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_last_event_id() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(last_event_id(&headers).unwrap(), None);
+
+        headers.insert(LAST_EVENT_ID_HEADER, HeaderValue::from_static("42"));
+        assert_eq!(last_event_id(&headers).unwrap(), Some(42));
+
+        headers.insert(LAST_EVENT_ID_HEADER, HeaderValue::from_static("abc"));
+        assert!(last_event_id(&headers).is_err());
+    }
+}
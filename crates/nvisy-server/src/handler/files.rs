@@ -6,20 +6,30 @@
 //! scanning and content validation.
 
 use std::str::FromStr;
+use std::sync::Arc;
 
 use aide::axum::ApiRouter;
 use aide::transform::TransformOperation;
 use axum::body::Body;
+use axum::extract::State;
 use axum::extract::multipart::Field;
-use axum::extract::{DefaultBodyLimit, State};
 use axum::http::{HeaderMap, HeaderValue, StatusCode};
-use futures::StreamExt;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use nvisy_core::clock::Clock;
+use nvisy_core::id::IdGenerator;
 use nvisy_nats::NatsClient;
 use nvisy_nats::object::{FileKey, FilesBucket, ObjectStore};
-use nvisy_postgres::model::{NewWorkspaceFile, WorkspaceFile as FileModel};
-use nvisy_postgres::query::{AccountRepository, WorkspaceFileRepository};
-use nvisy_postgres::types::Username;
-use nvisy_postgres::{PgClient, PgConn};
+use nvisy_postgres::model::{
+    NewWorkspaceFile, NewWorkspaceFileAccess, UpdateWorkspaceFile, WorkspaceFile as FileModel,
+};
+use nvisy_postgres::query::{
+    AccountRepository, TenantScope, WorkspaceFileAccessRepository, WorkspaceFileRepository,
+    WorkspacePipelineRunRepository, WorkspaceRetentionRepository,
+};
+use nvisy_postgres::types::{DataSensitivity, FileFormat, Username};
+use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgError};
+use tokio::io::AsyncReadExt;
 use tokio_util::io::{ReaderStream, StreamReader};
 use uuid::Uuid;
 
@@ -27,18 +37,32 @@ use crate::extract::{
     AuthProvider, AuthState, Json, Multipart, Path, Permission, Query, ValidateJson,
     WorkspaceContext,
 };
-use crate::handler::request::{CursorPagination, ListFiles, UpdateFile, WorkspaceFilePathParams};
-use crate::handler::response::{self, ErrorResponse, File, Files, FilesPage};
+use crate::handler::request::{
+    CompareFiles, CursorPagination, FieldSelection, FileAccessWindow, ListFiles, OpenFile,
+    SetFilePassword, SupplyFilePassword, UpdateFile, UploadFiles, WorkspaceFilePathParams,
+};
+use crate::handler::response::{
+    self, ComparisonUnavailable, DetectionsDiff, ErrorResponse, File, FileAccessStats,
+    FileComparison, FileIntegrity, FilePreflight, Files, FilesPage, Page, Sparse,
+    TransientFilePassword,
+};
+use crate::handler::runs::load_analyzed_document;
 use crate::handler::{Error, ErrorKind, Result};
-use crate::middleware::DEFAULT_MAX_FILE_BODY_SIZE;
-use crate::service::{CryptoService, HashingReader, ServiceState, WebhookEmitter};
+use crate::service::{
+    CryptoService, DocumentPasswords, GarbageCollectionService, HashingReader, PreflightReport,
+    ProtectionReader, ResidencyService, ServiceState, StructuralDiff, TextDiff, TextDiffLimit,
+    WebhookEmitter,
+};
 
 /// Tracing target for workspace file operations.
 const TRACING_TARGET: &str = "nvisy_server::handler::workspace_files";
 
+/// Largest file, in bytes, whose content is compared line by line.
+const MAX_COMPARED_FILE_SIZE: i64 = 4 * 1024 * 1024;
+
 /// Finds a file within a workspace or returns NotFound error.
-async fn find_file(conn: &mut PgConn, workspace_id: Uuid, file_id: Uuid) -> Result<FileModel> {
-    conn.find_file_in_workspace(workspace_id, file_id)
+async fn find_file(conn: &mut PgConn, scope: TenantScope, file_id: Uuid) -> Result<FileModel> {
+    conn.find_file_in_workspace(scope, file_id)
         .await?
         .ok_or_else(|| Error::not_found("file"))
 }
@@ -47,14 +71,61 @@ async fn find_file(conn: &mut PgConn, workspace_id: Uuid, file_id: Uuid) -> Resu
 /// NotFound error.
 async fn find_file_with_creator(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     file_id: Uuid,
 ) -> Result<(FileModel, Username)> {
-    conn.find_file_in_workspace_with_creator(workspace_id, file_id)
+    conn.find_file_in_workspace_with_creator(scope, file_id)
         .await?
         .ok_or_else(|| Error::not_found("file"))
 }
 
+/// Reads and decrypts a file's full content from object storage.
+pub(super) async fn read_file_content(
+    file_store: &ObjectStore<FilesBucket, FileKey>,
+    crypto: &CryptoService,
+    file: &FileModel,
+) -> Result<Vec<u8>> {
+    let file_key = FileKey::from_str(&file.storage_path).map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Invalid file storage path")
+            .with_context(format!("Parse error: {}", err))
+    })?;
+
+    let content = file_store
+        .get(&file_key)
+        .await?
+        .ok_or_else(|| ErrorKind::NotFound.with_message("File content not found"))?;
+
+    let content_key = crypto
+        .stored_content_key(file.workspace_id, &content.info().metadata)
+        .await?;
+    let mut reader = Box::pin(crypto.decrypt_content_reader(&content_key, content.into_reader()));
+    let mut plaintext = Vec::with_capacity(file.file_size_bytes.max(0) as usize);
+    reader.read_to_end(&mut plaintext).await.map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Failed to read file content")
+            .with_context(err.to_string())
+    })?;
+
+    Ok(plaintext)
+}
+
+/// Adds an access to a file's statistics.
+///
+/// Statistics are best effort: a failure is logged and never fails the
+/// access itself.
+pub(crate) async fn record_file_access(conn: &mut PgConn, access: NewWorkspaceFileAccess) {
+    let file_id = access.file_id;
+    if let Err(err) = conn.record_file_access(access).await {
+        tracing::warn!(
+            target: TRACING_TARGET,
+            error = %err,
+            file_id = %file_id,
+            "Failed to record file access"
+        );
+    }
+}
+
 /// Lists files in a workspace with cursor-based pagination.
 #[tracing::instrument(
     skip_all,
@@ -69,21 +140,19 @@ async fn list_files(
     AuthState(auth_claims): AuthState,
     Query(files_query): Query<ListFiles>,
     Query(cursor_pagination): Query<CursorPagination>,
-) -> Result<(StatusCode, Json<FilesPage>)> {
+    Query(selection): Query<FieldSelection>,
+) -> Result<(StatusCode, Json<Page<Sparse<File>>>)> {
     tracing::debug!(target: TRACING_TARGET, "Listing files");
 
+    let fields = selection.resolve::<File>()?;
     let mut conn = pg_client.get_connection().await?;
 
-    auth_claims
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewFiles)
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_files(
-            workspace.id,
-            cursor_pagination.into(),
-            files_query.to_filter(),
-        )
+        .cursor_list_workspace_files(scope, cursor_pagination.into(), files_query.to_filter())
         .await?;
 
     let response = FilesPage::from_cursor_page(page, |(file, uploaded_by)| {
@@ -97,26 +166,31 @@ async fn list_files(
         "Files listed"
     );
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((StatusCode::OK, Json(response.select(fields))))
 }
 
 fn list_files_docs(op: TransformOperation) -> TransformOperation {
     op.summary("List files")
         .description(
-            "Lists files in a workspace with cursor-based pagination. Use the `after` parameter with the `nextCursor` value from the response to fetch subsequent pages.",
+            "Lists files in a workspace with cursor-based pagination. Use the `after` parameter with the `nextCursor` value from the response to fetch subsequent pages. Pass `fields` to return only some fields of each file.",
         )
         .response::<200, Json<FilesPage>>()
+        .response::<400, Json<ErrorResponse>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
 }
 
 /// Context for processing a single file upload.
 #[derive(Clone)]
-struct FileUploadContext {
-    workspace_id: Uuid,
-    account_id: Uuid,
-    file_store: ObjectStore<FilesBucket, FileKey>,
-    crypto: CryptoService,
+pub(crate) struct FileUploadContext {
+    pub scope: TenantScope,
+    pub account_id: Uuid,
+    pub file_store: ObjectStore<FilesBucket, FileKey>,
+    pub crypto: CryptoService,
+    pub garbage: GarbageCollectionService,
+    pub webhook_emitter: WebhookEmitter,
+    pub ids: Arc<dyn IdGenerator>,
+    pub sensitivity: DataSensitivity,
 }
 
 /// Processes a single file from a multipart upload using streaming.
@@ -130,6 +204,23 @@ async fn process_single_file(
         .map(ToString::to_string)
         .unwrap_or_else(|| format!("file_{}.bin", Uuid::now_v7()));
 
+    let content = field.map(|result| result.map_err(std::io::Error::other));
+    store_uploaded_file(conn, ctx, filename, content).await
+}
+
+/// Encrypts and stores an uploaded file's content as it streams in, then
+/// records the file.
+///
+/// Shared by the multipart endpoint and the gRPC upload.
+pub(crate) async fn store_uploaded_file<S>(
+    conn: &mut PgConn,
+    ctx: &FileUploadContext,
+    filename: String,
+    content: S,
+) -> Result<FileModel>
+where
+    S: Stream<Item = std::io::Result<Bytes>> + Unpin + Send,
+{
     let file_extension = std::path::Path::new(&filename)
         .extension()
         .and_then(|ext| ext.to_str())
@@ -137,7 +228,7 @@ async fn process_single_file(
         .to_lowercase();
 
     // Generate file key with unique object ID for NATS storage
-    let file_key = FileKey::generate(ctx.workspace_id);
+    let file_key = FileKey::generate_with(ctx.scope.workspace_id(), ctx.ids.as_ref());
 
     tracing::debug!(
         target: TRACING_TARGET,
@@ -145,24 +236,64 @@ async fn process_single_file(
         "Streaming file to storage"
     );
 
-    // Step 1: Encrypt the plaintext as it streams to NATS. The measured reader
-    // captures the plaintext size and hash (NATS only sees ciphertext).
-    let source = StreamReader::new(field.map(|result| result.map_err(std::io::Error::other)));
-    let (measured, measurements) = HashingReader::new(source);
-    let encrypted = ctx.crypto.encrypt_reader(ctx.workspace_id, measured);
+    // Register the object first, so content left by an aborted upload is
+    // collected once the upload TTL passes.
+    let temporary = ctx
+        .garbage
+        .register_upload(
+            conn,
+            ctx.scope.workspace_id(),
+            ctx.account_id,
+            ctx.file_store.bucket(),
+            &file_key.to_string(),
+        )
+        .await?;
 
-    ctx.file_store.put(&file_key, Box::pin(encrypted)).await?;
+    // Step 1: Encrypt the plaintext as it streams to NATS. The measured reader
+    // captures the plaintext size and hash (NATS only sees ciphertext), and the
+    // probe flags password-protected documents. Sensitive files get their own
+    // data key, stored wrapped in the object's metadata.
+    let content_key = ctx
+        .crypto
+        .new_content_key(
+            ctx.scope.workspace_id(),
+            ctx.sensitivity.requires_envelope_encryption(),
+        )
+        .await?;
+    let source = StreamReader::new(content);
+    let (probed, probe) = ProtectionReader::new(source);
+    let (measured, measurements) = HashingReader::new(probed, ctx.crypto.sha256_context());
+    let encrypted = ctx.crypto.encrypt_content_reader(&content_key, measured);
+
+    let stored = ctx
+        .file_store
+        .put_with_metadata(
+            &file_key,
+            Box::pin(encrypted),
+            content_key.metadata().clone(),
+        )
+        .await?;
 
     tracing::debug!(
         target: TRACING_TARGET,
         object_id = %file_key.object_id,
+        version = stored.version(),
         size = measurements.bytes(),
         "File encrypted and streamed to storage"
     );
 
+    let password_protected = probe.is_password_protected();
+    if password_protected {
+        tracing::info!(
+            target: TRACING_TARGET,
+            object_id = %file_key.object_id,
+            "Password-protected document detected"
+        );
+    }
+
     // Step 2: Create DB record with all storage info (Postgres generates its own id)
     let file_record = NewWorkspaceFile {
-        workspace_id: ctx.workspace_id,
+        workspace_id: ctx.scope.workspace_id(),
         account_id: ctx.account_id,
         display_name: Some(filename.clone()),
         original_filename: Some(filename),
@@ -171,10 +302,35 @@ async fn process_single_file(
         file_hash_sha256: measurements.sha256().to_vec(),
         storage_path: file_key.to_string(),
         storage_bucket: ctx.file_store.bucket().to_owned(),
+        password_protected: Some(password_protected),
+        sensitivity: Some(ctx.sensitivity),
+        storage_version: Some(stored.version().to_owned()),
         ..Default::default()
     };
 
-    let created_file = conn.create_workspace_file(file_record).await?;
+    // The record, the release and the event commit together.
+    let created_file = conn
+        .transaction(async |conn| {
+            let created_file = conn.create_workspace_file(file_record).await?;
+            ctx.garbage.release(conn, ctx.scope, &temporary).await?;
+
+            let data = serde_json::json!({
+                "displayName": created_file.display_name,
+                "fileSizeBytes": created_file.file_size_bytes,
+            });
+            ctx.webhook_emitter
+                .emit_file_created(
+                    conn,
+                    ctx.scope.workspace_id(),
+                    created_file.id,
+                    Some(ctx.account_id),
+                    Some(data),
+                )
+                .await?;
+
+            Ok::<_, PgError>(created_file)
+        })
+        .await?;
 
     Ok(created_file)
 }
@@ -189,21 +345,25 @@ async fn process_single_file(
 )]
 async fn upload_file(
     State(pg_client): State<PgClient>,
-    State(nats_client): State<NatsClient>,
+    State(residency): State<ResidencyService>,
     State(webhook_emitter): State<WebhookEmitter>,
     State(crypto): State<CryptoService>,
+    State(garbage): State<GarbageCollectionService>,
+    State(ids): State<Arc<dyn IdGenerator>>,
     WorkspaceContext(workspace): WorkspaceContext,
     AuthState(auth_claims): AuthState,
+    Query(upload_query): Query<UploadFiles>,
     Multipart(mut multipart): Multipart,
 ) -> Result<(StatusCode, Json<Files>)> {
     tracing::info!(target: TRACING_TARGET, "Uploading files");
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_claims
-        .authorize_workspace(&mut conn, workspace.id, Permission::UploadFiles)
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::UploadFiles)
         .await?;
 
+    let nats_client = residency.backends(workspace.data_region)?.nats();
     let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
 
     // The uploader is the caller; resolve the handle once for every file below.
@@ -214,10 +374,14 @@ async fn upload_file(
         .username;
 
     let ctx = FileUploadContext {
-        workspace_id: workspace.id,
+        scope,
         account_id: auth_claims.account_id,
         file_store,
         crypto,
+        garbage,
+        webhook_emitter,
+        ids,
+        sensitivity: upload_query.sensitivity.unwrap_or_default(),
     };
 
     let mut uploaded_files = Vec::new();
@@ -248,30 +412,6 @@ async fn upload_file(
         return Err(ErrorKind::BadRequest.with_message("No files provided in multipart request"));
     }
 
-    // Emit webhook events for created files (fire-and-forget)
-    for file in &uploaded_files {
-        let data = serde_json::json!({
-            "displayName": file.display_name,
-            "fileSizeBytes": file.file_size,
-        });
-        if let Err(err) = webhook_emitter
-            .emit_file_created(
-                workspace.id,
-                file.id,
-                Some(auth_claims.account_id),
-                Some(data),
-            )
-            .await
-        {
-            tracing::warn!(
-                target: TRACING_TARGET,
-                error = %err,
-                file_id = %file.id,
-                "Failed to emit file:created webhook event"
-            );
-        }
-    }
-
     tracing::info!(
         target: TRACING_TARGET,
         file_count = uploaded_files.len(),
@@ -283,7 +423,7 @@ async fn upload_file(
 
 fn upload_file_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Upload files")
-        .description("Uploads one or more files to a document for processing. Files are validated, stored, and queued for processing.")
+        .description("Uploads one or more files to a document for processing. Files are validated, stored, and queued for processing. Files uploaded with `high` or `critical` sensitivity are envelope encrypted under their own data key.")
         .response::<201, Json<Files>>()
         .response::<400, Json<ErrorResponse>>()
         .response::<401, Json<ErrorResponse>>()
@@ -291,6 +431,8 @@ fn upload_file_docs(op: TransformOperation) -> TransformOperation {
 }
 
 /// Gets file metadata without downloading the content.
+///
+/// Counts as a view of the file in its access statistics.
 #[tracing::instrument(
     skip_all,
     fields(
@@ -304,17 +446,20 @@ async fn read_file(
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<WorkspaceFilePathParams>,
     AuthState(auth_claims): AuthState,
+    Query(open): Query<OpenFile>,
 ) -> Result<(StatusCode, Json<File>)> {
     tracing::debug!(target: TRACING_TARGET, "Reading file metadata");
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_claims
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewFiles)
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
         .await?;
 
-    let (file, uploaded_by) =
-        find_file_with_creator(&mut conn, workspace.id, path_params.file_id).await?;
+    let (file, uploaded_by) = find_file_with_creator(&mut conn, scope, path_params.file_id).await?;
+
+    let access = NewWorkspaceFileAccess::view(workspace.id, file.id).from_search(open.from_search);
+    record_file_access(&mut conn, access).await;
 
     tracing::debug!(target: TRACING_TARGET, "File metadata retrieved");
 
@@ -326,7 +471,10 @@ async fn read_file(
 
 fn read_file_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get file metadata")
-        .description("Returns file metadata without downloading the file content.")
+        .description(
+            "Returns file metadata without downloading the file content. Counts as a view in the \
+             file's access statistics; pass `fromSearch=true` when opening a search result.",
+        )
         .response::<200, Json<File>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
@@ -354,45 +502,41 @@ async fn update_file(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_claims
-        .authorize_workspace(&mut conn, workspace.id, Permission::UpdateFiles)
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::UpdateFiles)
         .await?;
 
     // Confirm the file exists in this workspace before mutating.
-    find_file(&mut conn, workspace.id, path_params.file_id).await?;
+    find_file(&mut conn, scope, path_params.file_id).await?;
 
     let updates = request.into_model();
 
-    conn.update_workspace_file(path_params.file_id, updates)
-        .await
-        .map_err(|err| {
-            tracing::error!(target: TRACING_TARGET, error = %err, "Failed to update file");
-            ErrorKind::InternalServerError.with_message("Failed to update file")
-        })?;
+    conn.transaction(async |conn| {
+        let updated_file = conn
+            .update_workspace_file(path_params.file_id, updates)
+            .await?;
 
-    let (updated_file, uploaded_by) =
-        find_file_with_creator(&mut conn, workspace.id, path_params.file_id).await?;
+        let data = serde_json::json!({
+            "displayName": updated_file.display_name,
+        });
+        webhook_emitter
+            .emit_file_updated(
+                conn,
+                workspace.id,
+                path_params.file_id,
+                Some(auth_claims.account_id),
+                Some(data),
+            )
+            .await
+    })
+    .await
+    .map_err(|err| {
+        tracing::error!(target: TRACING_TARGET, error = %err, "Failed to update file");
+        ErrorKind::InternalServerError.with_message("Failed to update file")
+    })?;
 
-    // Emit webhook event (fire-and-forget)
-    let data = serde_json::json!({
-        "displayName": updated_file.display_name,
-    });
-    if let Err(err) = webhook_emitter
-        .emit_file_updated(
-            workspace.id,
-            path_params.file_id,
-            Some(auth_claims.account_id),
-            Some(data),
-        )
-        .await
-    {
-        tracing::warn!(
-            target: TRACING_TARGET,
-            error = %err,
-            file_id = %path_params.file_id,
-            "Failed to emit file:updated webhook event"
-        );
-    }
+    let (updated_file, uploaded_by) =
+        find_file_with_creator(&mut conn, scope, path_params.file_id).await?;
 
     tracing::info!(target: TRACING_TARGET, "File updated");
 
@@ -416,7 +560,7 @@ fn update_file_docs(op: TransformOperation) -> TransformOperation {
         .response::<404, Json<ErrorResponse>>()
 }
 
-/// Downloads a file with streaming support for large files.
+/// Reports what a run over the file would see, without running the pipeline.
 #[tracing::instrument(
     skip_all,
     fields(
@@ -425,121 +569,57 @@ fn update_file_docs(op: TransformOperation) -> TransformOperation {
         file_id = %path_params.file_id,
     )
 )]
-async fn download_file(
+async fn preflight_file(
     State(pg_client): State<PgClient>,
-    State(nats_client): State<NatsClient>,
+    State(residency): State<ResidencyService>,
     State(crypto): State<CryptoService>,
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<WorkspaceFilePathParams>,
     AuthState(auth_claims): AuthState,
-) -> Result<(StatusCode, HeaderMap, Body)> {
-    tracing::debug!(target: TRACING_TARGET, "Downloading file");
+) -> Result<(StatusCode, Json<FilePreflight>)> {
+    tracing::debug!(target: TRACING_TARGET, "Running file preflight");
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_claims
-        .authorize_workspace(&mut conn, workspace.id, Permission::DownloadFiles)
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
         .await?;
 
-    let file = find_file(&mut conn, workspace.id, path_params.file_id).await?;
-
-    let file_store = nats_client
-        .object_store::<FilesBucket, FileKey>()
-        .await
-        .map_err(|err| {
-            tracing::error!(
-                target: TRACING_TARGET,
-                error = %err,
-                "Failed to create file store"
-            );
-            ErrorKind::InternalServerError.with_message("Failed to initialize file storage")
-        })?;
-
-    let file_key = FileKey::from_str(&file.storage_path).map_err(|err| {
-        tracing::error!(
-            target: TRACING_TARGET,
-            error = %err,
-            storage_path = %file.storage_path,
-            "Invalid storage path format"
-        );
-        ErrorKind::InternalServerError
-            .with_message("Invalid file storage path")
-            .with_context(format!("Parse error: {}", err))
-    })?;
-
-    // Get streaming content from NATS file store
-    let get_result = file_store
-        .get(&file_key)
-        .await
-        .map_err(|err| {
-            tracing::error!(
-                target: TRACING_TARGET,
-                error = %err,
-                file_id = %path_params.file_id,
-                "Failed to retrieve file from storage"
-            );
-            ErrorKind::InternalServerError
-                .with_message("Failed to retrieve file")
-                .with_context(format!("Storage retrieval failed: {}", err))
-        })?
-        .ok_or_else(|| {
-            tracing::warn!(
-                target: TRACING_TARGET,
-                file_id = %path_params.file_id,
-                "File content not found in storage"
-            );
-            ErrorKind::NotFound.with_message("File content not found")
-        })?;
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
 
-    // Set up response headers.
-    //
-    // The display name is user-controlled, so strip characters that are
-    // invalid in a quoted header value to avoid header injection and a
-    // failed parse; fall back to a generic disposition if it still fails.
-    let safe_name: String = file
-        .display_name
-        .chars()
-        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
-        .collect();
-    let disposition = format!("attachment; filename=\"{safe_name}\"")
-        .parse()
-        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+    let nats_client = residency.backends(workspace.data_region)?.nats();
+    let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
+    let content = read_file_content(&file_store, &crypto, &file).await?;
 
-    let mut headers = HeaderMap::new();
-    headers.insert("content-disposition", disposition);
-    // Content-length is the plaintext size from the record; storage holds the
-    // larger ciphertext, which the decrypting reader unwraps as it streams.
-    headers.insert(
-        "content-length",
-        file.file_size_bytes.to_string().parse().unwrap(),
-    );
-    headers.insert("content-type", "application/octet-stream".parse().unwrap());
+    let report =
+        PreflightReport::inspect(&content, &file.file_extension, file.has_stored_password());
+    let preflight = FilePreflight::from_report(&file, report);
 
     tracing::debug!(
         target: TRACING_TARGET,
-        file_id = %path_params.file_id,
-        size = file.file_size_bytes,
-        "Streaming file download"
+        ready = preflight.ready,
+        violations = preflight.violations.len(),
+        "File preflight completed"
     );
 
-    // Decrypt the stored ciphertext as it streams to the client.
-    let decrypted = crypto.decrypt_reader(file.workspace_id, get_result.into_reader());
-    let stream = ReaderStream::new(Box::pin(decrypted));
-    let body = Body::from_stream(stream);
-
-    Ok((StatusCode::OK, headers, body))
+    Ok((StatusCode::OK, Json(preflight)))
 }
 
-fn download_file_docs(op: TransformOperation) -> TransformOperation {
-    op.summary("Download file")
-        .description("Downloads a file by ID. Returns the file content as a binary stream.")
-        .response::<200, ()>()
+fn preflight_file_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Preflight file")
+        .description(
+            "Inspects a file without running a pipeline and reports its detected format, \
+             page count, password protection, estimated text quality, the processing tier \
+             and billable pages a run would use, and any violations that would stop a run. \
+             Page count and quality are estimates.",
+        )
+        .response::<200, Json<FilePreflight>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
         .response::<404, Json<ErrorResponse>>()
 }
 
-/// Deletes a file (soft delete).
+/// Checks a file's stored content against the digest recorded at upload.
 #[tracing::instrument(
     skip_all,
     fields(
@@ -548,90 +628,961 @@ fn download_file_docs(op: TransformOperation) -> TransformOperation {
         file_id = %path_params.file_id,
     )
 )]
-async fn delete_file(
+async fn verify_file_integrity(
     State(pg_client): State<PgClient>,
-    State(webhook_emitter): State<WebhookEmitter>,
+    State(residency): State<ResidencyService>,
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<WorkspaceFilePathParams>,
     AuthState(auth_claims): AuthState,
-) -> Result<StatusCode> {
-    tracing::warn!(target: TRACING_TARGET, "File Deleting");
+) -> Result<(StatusCode, Json<FileIntegrity>)> {
+    tracing::debug!(target: TRACING_TARGET, "Verifying file integrity");
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_claims
-        .authorize_workspace(&mut conn, workspace.id, Permission::DeleteFiles)
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
         .await?;
 
-    // Confirm the file exists in this workspace before deleting.
-    let file = find_file(&mut conn, workspace.id, path_params.file_id).await?;
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
 
-    conn.delete_workspace_file(path_params.file_id)
-        .await
-        .map_err(|err| {
-            tracing::error!(target: TRACING_TARGET, error = %err, "Failed to soft delete file");
-            ErrorKind::InternalServerError
-                .with_message("Failed to delete file")
-                .with_context(format!("Database error: {}", err))
-        })?;
+    let file_key = FileKey::from_str(&file.storage_path).map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Invalid file storage path")
+            .with_context(format!("Parse error: {}", err))
+    })?;
 
-    // Emit webhook event (fire-and-forget)
-    let data = serde_json::json!({
-        "displayName": file.display_name,
-    });
-    if let Err(err) = webhook_emitter
-        .emit_file_deleted(
-            workspace.id,
-            path_params.file_id,
-            Some(auth_claims.account_id),
-            Some(data),
-        )
-        .await
-    {
-        tracing::warn!(
+    let nats_client = residency.backends(workspace.data_region)?.nats();
+    let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
+    let intact = file_store
+        .verify(&file_key)
+        .await?
+        .ok_or_else(|| ErrorKind::NotFound.with_message("File content not found"))?;
+
+    if !intact {
+        tracing::error!(
             target: TRACING_TARGET,
-            error = %err,
-            file_id = %path_params.file_id,
-            "Failed to emit file:deleted webhook event"
+            file_id = %file.id,
+            "Stored file content does not match its digest"
         );
     }
 
-    tracing::info!(target: TRACING_TARGET, "File deleted");
-    Ok(StatusCode::NO_CONTENT)
+    Ok((
+        StatusCode::OK,
+        Json(FileIntegrity {
+            file_id: file.id,
+            intact,
+        }),
+    ))
 }
 
-fn delete_file_docs(op: TransformOperation) -> TransformOperation {
-    op.summary("Delete file")
-        .description("Soft deletes a file by setting a deleted timestamp. The file can be recovered within the retention period.")
-        .response::<204, ()>()
+fn verify_file_integrity_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Verify file integrity")
+        .description(
+            "Reads the file's stored content and checks it against the SHA-256 digest recorded \
+             when it was written, reporting whether it is intact. Downloads are checked the same \
+             way and fail on a mismatch; this reports it without serving the content.",
+        )
+        .response::<200, Json<FileIntegrity>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
         .response::<404, Json<ErrorResponse>>()
 }
 
-/// Returns a [`Router`] with all related routes.
-///
-/// [`Router`]: axum::routing::Router
-pub fn routes() -> ApiRouter<ServiceState> {
-    use aide::axum::routing::*;
+/// Lists every version of a file, newest first.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn list_file_versions(
+    State(pg_client): State<PgClient>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+) -> Result<(StatusCode, Json<Files>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing file versions");
 
-    ApiRouter::new()
-        // Workspace-scoped routes (require workspace context)
-        .api_route(
-            "/workspaces/{workspaceSlug}/files/",
-            post_with(upload_file, upload_file_docs)
-                .layer(DefaultBodyLimit::max(DEFAULT_MAX_FILE_BODY_SIZE))
-                .get_with(list_files, list_files_docs),
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+
+    // Versions share the root of their parent chain.
+    let versions = conn
+        .list_file_versions_in_workspace(scope, file.parent_id.unwrap_or(file.id))
+        .await?;
+
+    let versions: Files = versions
+        .into_iter()
+        .map(|(version, uploaded_by)| {
+            File::from_model(version, workspace.slug.clone(), uploaded_by)
+        })
+        .collect();
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        version_count = versions.len(),
+        "File versions listed"
+    );
+
+    Ok((StatusCode::OK, Json(versions)))
+}
+
+fn list_file_versions_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List file versions")
+        .description(
+            "Lists every version of the document the file belongs to, this one included, newest \
+             first. Each version carries the ID of its stored content; its content is downloaded \
+             through its own file ID.",
         )
-        .api_route(
-            "/workspaces/{workspaceSlug}/files/{fileId}/",
-            get_with(read_file, read_file_docs)
-                .patch_with(update_file, update_file_docs)
+        .response::<200, Json<Files>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Restores an earlier version of a file as its newest version.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn restore_file_version(
+    State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    State(garbage): State<GarbageCollectionService>,
+    State(ids): State<Arc<dyn IdGenerator>>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+) -> Result<(StatusCode, Json<File>)> {
+    tracing::info!(target: TRACING_TARGET, "Restoring file version");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::UploadFiles)
+        .await?;
+
+    let version = find_file(&mut conn, scope, path_params.file_id).await?;
+
+    let version_key = FileKey::from_str(&version.storage_path).map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Invalid file storage path")
+            .with_context(format!("Parse error: {}", err))
+    })?;
+
+    let nats_client = residency.backends(workspace.data_region)?.nats();
+    let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
+
+    // The files bucket is versioned: objects are never replaced, so the
+    // restored version is a copy of the old one under a fresh key.
+    let file_key = FileKey::generate_with(workspace.id, ids.as_ref());
+    let temporary = garbage
+        .register_upload(
+            &mut conn,
+            workspace.id,
+            auth_claims.account_id,
+            file_store.bucket(),
+            &file_key.to_string(),
+        )
+        .await?;
+
+    let stored = file_store
+        .copy(&version_key, &file_key)
+        .await?
+        .ok_or_else(|| ErrorKind::NotFound.with_message("File content not found"))?;
+
+    let uploaded_by: Username = conn
+        .find_account_by_id(auth_claims.account_id)
+        .await?
+        .ok_or_else(|| Error::not_found("account"))?
+        .username;
+
+    let file_record = NewWorkspaceFile {
+        workspace_id: workspace.id,
+        account_id: auth_claims.account_id,
+        parent_id: Some(version.parent_id.unwrap_or(version.id)),
+        display_name: Some(version.display_name.clone()),
+        original_filename: Some(version.original_filename.clone()),
+        file_extension: Some(version.file_extension.clone()),
+        mime_type: version.mime_type.clone(),
+        tags: Some(version.tags.clone()),
+        source: Some(version.source),
+        file_size_bytes: version.file_size_bytes,
+        file_hash_sha256: version.file_hash_sha256.clone(),
+        storage_path: file_key.to_string(),
+        storage_bucket: file_store.bucket().to_owned(),
+        password_protected: Some(version.password_protected),
+        sensitivity: Some(version.sensitivity),
+        storage_version: Some(stored.version().to_owned()),
+        ..Default::default()
+    };
+
+    // The record, the release and the event commit together.
+    let restored = conn
+        .transaction(async |conn| {
+            let mut restored = conn.create_workspace_file(file_record).await?;
+            if version.encrypted_password.is_some() {
+                let updates = UpdateWorkspaceFile {
+                    encrypted_password: Some(version.encrypted_password.clone()),
+                    ..Default::default()
+                };
+                restored = conn.update_workspace_file(restored.id, updates).await?;
+            }
+            garbage.release(conn, scope, &temporary).await?;
+
+            let data = serde_json::json!({
+                "displayName": restored.display_name,
+                "fileSizeBytes": restored.file_size_bytes,
+                "restoredFrom": version.id,
+            });
+            webhook_emitter
+                .emit_file_created(
+                    conn,
+                    workspace.id,
+                    restored.id,
+                    Some(auth_claims.account_id),
+                    Some(data),
+                )
+                .await?;
+
+            Ok::<_, PgError>(restored)
+        })
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        restored_file_id = %restored.id,
+        version_number = restored.version_number,
+        storage_version = stored.version(),
+        "File version restored"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(File::from_model(restored, workspace.slug, uploaded_by)),
+    ))
+}
+
+fn restore_file_version_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Restore file version")
+        .description(
+            "Copies the content of this version of a document into a new version, which becomes \
+             the newest one. Earlier versions are kept; the restored version has its own storage \
+             version ID.",
+        )
+        .response::<201, Json<File>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Compares a file with another version of the same document.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+        base_file_id = %query.base_file_id,
+    )
+)]
+async fn compare_file(
+    State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
+    State(crypto): State<CryptoService>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+    Query(query): Query<CompareFiles>,
+) -> Result<(StatusCode, Json<FileComparison>)> {
+    tracing::debug!(target: TRACING_TARGET, "Comparing file versions");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
+        .await?;
+
+    let target = find_file(&mut conn, scope, path_params.file_id).await?;
+    let base = find_file(&mut conn, scope, query.base_file_id).await?;
+
+    // Versions share the root of their parent chain.
+    if base.parent_id.unwrap_or(base.id) != target.parent_id.unwrap_or(target.id) {
+        return Err(ErrorKind::BadRequest
+            .with_message("Files are not versions of the same document")
+            .with_resource("file"));
+    }
+
+    let nats_client = residency.backends(workspace.data_region)?.nats();
+    let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
+    let text = compare_text(&file_store, &crypto, &base, &target).await?;
+
+    // Findings are pipeline output, so they are only shown to those who can
+    // view runs.
+    let can_view_runs = auth_claims
+        .check_workspace_permission(&mut conn, workspace.id, Permission::ViewPipelines)
+        .await?
+        .granted;
+    let detections = if can_view_runs {
+        compare_detections(&mut conn, scope, nats_client, &crypto, &base, &target).await?
+    } else {
+        Err(ComparisonUnavailable::NotPermitted)
+    };
+
+    let comparison = FileComparison::new(&base, &target, text, detections);
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        text_compared = comparison.text.is_some(),
+        detections_compared = comparison.detections.is_some(),
+        "File versions compared"
+    );
+
+    Ok((StatusCode::OK, Json(comparison)))
+}
+
+/// Compares the content of two versions line by line.
+///
+/// Only text formats are compared; extracting text from other formats is the
+/// engine's job and is reflected in the detections instead.
+async fn compare_text(
+    file_store: &ObjectStore<FilesBucket, FileKey>,
+    crypto: &CryptoService,
+    base: &FileModel,
+    target: &FileModel,
+) -> Result<Result<TextDiff, ComparisonUnavailable>> {
+    for file in [base, target] {
+        let format = FileFormat::from_extension(&file.file_extension);
+        if !matches!(
+            format,
+            Some(FileFormat::Txt | FileFormat::Md | FileFormat::Csv | FileFormat::Json)
+        ) {
+            return Ok(Err(ComparisonUnavailable::UnsupportedFormat));
+        }
+        if file.file_size_bytes > MAX_COMPARED_FILE_SIZE {
+            return Ok(Err(ComparisonUnavailable::TooLarge));
+        }
+    }
+
+    let old = read_file_content(file_store, crypto, base).await?;
+    let new = read_file_content(file_store, crypto, target).await?;
+    let (Ok(old), Ok(new)) = (std::str::from_utf8(&old), std::str::from_utf8(&new)) else {
+        return Ok(Err(ComparisonUnavailable::UnsupportedFormat));
+    };
+
+    Ok(TextDiff::compute(old, new).map_err(|limit| match limit {
+        TextDiffLimit::TooManyLines => ComparisonUnavailable::TooLarge,
+        TextDiffLimit::TooManyEdits => ComparisonUnavailable::TooDifferent,
+    }))
+}
+
+/// Compares the analyses of the latest analyzed run over each version.
+async fn compare_detections(
+    conn: &mut PgConn,
+    scope: TenantScope,
+    nats: &NatsClient,
+    crypto: &CryptoService,
+    base: &FileModel,
+    target: &FileModel,
+) -> Result<Result<DetectionsDiff, ComparisonUnavailable>> {
+    let base_run = conn.find_latest_analyzed_file_run(scope, base.id).await?;
+    let target_run = conn.find_latest_analyzed_file_run(scope, target.id).await?;
+    let (Some(base_run), Some(target_run)) = (base_run, target_run) else {
+        return Ok(Err(ComparisonUnavailable::NotAnalyzed));
+    };
+
+    let before = load_analyzed_document(nats, crypto, base.workspace_id, &base_run).await?;
+    let after = load_analyzed_document(nats, crypto, target.workspace_id, &target_run).await?;
+    let to_value = |analysis| {
+        serde_json::to_value(analysis).map_err(|err| {
+            ErrorKind::InternalServerError
+                .with_message("Failed to serialize analysis")
+                .with_context(err.to_string())
+        })
+    };
+
+    let diff = StructuralDiff::compute(&to_value(&before)?, &to_value(&after)?);
+    Ok(Ok(DetectionsDiff::new(base_run.id, target_run.id, diff)))
+}
+
+fn compare_file_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Compare file versions")
+        .description(
+            "Compares a file with another version of the same document, given as \
+             `baseFileId`. Text files are compared line by line; the findings of the \
+             latest analyzed run over each version are compared structurally when the \
+             caller may view pipeline runs. A side that cannot be compared is omitted \
+             and the reason is reported instead.",
+        )
+        .response::<200, Json<FileComparison>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Stores the password of a password-protected document.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn set_file_password(
+    State(pg_client): State<PgClient>,
+    State(crypto): State<CryptoService>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+    ValidateJson(request): ValidateJson<SetFilePassword>,
+) -> Result<(StatusCode, Json<File>)> {
+    tracing::debug!(target: TRACING_TARGET, "Storing document password");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::UpdateFiles)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+    if !file.is_password_protected() {
+        return Err(ErrorKind::Conflict
+            .with_message("File is not password-protected")
+            .with_resource("file"));
+    }
+
+    let encrypted_password = crypto.encrypt(workspace.id, request.password.as_bytes())?;
+    conn.update_workspace_file(
+        file.id,
+        UpdateWorkspaceFile {
+            encrypted_password: Some(Some(encrypted_password)),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let (updated_file, uploaded_by) = find_file_with_creator(&mut conn, scope, file.id).await?;
+
+    tracing::info!(target: TRACING_TARGET, "Document password stored");
+
+    Ok((
+        StatusCode::OK,
+        Json(File::from_model(updated_file, workspace.slug, uploaded_by)),
+    ))
+}
+
+fn set_file_password_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Set document password")
+        .description(
+            "Stores the password of a password-protected document, encrypted under the \
+             workspace key, so runs can open it without supplying one. To use a password \
+             without storing it, supply it transiently or pass `documentPassword` when \
+             starting a run.",
+        )
+        .response::<200, Json<File>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Removes the stored password of a password-protected document.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn delete_file_password(
+    State(pg_client): State<PgClient>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+) -> Result<StatusCode> {
+    tracing::debug!(target: TRACING_TARGET, "Removing document password");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::UpdateFiles)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+    if !file.has_stored_password() {
+        return Err(Error::not_found("file_password"));
+    }
+
+    conn.update_workspace_file(
+        file.id,
+        UpdateWorkspaceFile {
+            encrypted_password: Some(None),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    tracing::info!(target: TRACING_TARGET, "Document password removed");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn delete_file_password_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Remove document password")
+        .description(
+            "Removes the stored password of a password-protected document. Runs over \
+             the file must then supply the password themselves.",
+        )
+        .response::<204, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Supplies the password of a password-protected document without storing it.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn supply_file_password(
+    State(pg_client): State<PgClient>,
+    State(document_passwords): State<DocumentPasswords>,
+    State(clock): State<Arc<dyn Clock>>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+    ValidateJson(request): ValidateJson<SupplyFilePassword>,
+) -> Result<(StatusCode, Json<TransientFilePassword>)> {
+    tracing::debug!(target: TRACING_TARGET, "Supplying transient document password");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+    if !file.is_password_protected() {
+        return Err(ErrorKind::Conflict
+            .with_message("File is not password-protected")
+            .with_resource("file"));
+    }
+
+    let held_for = document_passwords.supply(&file, &request.password).await?;
+    let expires_at = held_for.and_then(|ttl| clock.now().checked_add(ttl).ok());
+
+    tracing::info!(target: TRACING_TARGET, "Transient document password supplied");
+
+    Ok((
+        StatusCode::OK,
+        Json(TransientFilePassword {
+            file_id: file.id,
+            expires_at,
+        }),
+    ))
+}
+
+fn supply_file_password_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Supply document password")
+        .description(
+            "Supplies the password of a password-protected document without storing it. \
+             The password is held in memory only, encrypted under the workspace key, and \
+             used by runs over the file until `expiresAt`; it is never written to the \
+             database. A password stored on the file is not changed.",
+        )
+        .response::<200, Json<TransientFilePassword>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Drops the transiently held password of a password-protected document.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn forget_file_password(
+    State(pg_client): State<PgClient>,
+    State(document_passwords): State<DocumentPasswords>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+) -> Result<StatusCode> {
+    tracing::debug!(target: TRACING_TARGET, "Dropping transient document password");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+    document_passwords.forget(&file).await?;
+
+    tracing::info!(target: TRACING_TARGET, "Transient document password dropped");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn forget_file_password_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Drop supplied document password")
+        .description(
+            "Drops a transiently held document password before it expires. Dropping a \
+             password that is not held is not an error.",
+        )
+        .response::<204, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Downloads a file with streaming support for large files.
+///
+/// Counts as a download of the file in its access statistics.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn download_file(
+    State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
+    State(crypto): State<CryptoService>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+    Query(open): Query<OpenFile>,
+) -> Result<(StatusCode, HeaderMap, Body)> {
+    tracing::debug!(target: TRACING_TARGET, "Downloading file");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::DownloadFiles)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+
+    let nats_client = residency.backends(workspace.data_region)?.nats();
+    let file_store = nats_client
+        .object_store::<FilesBucket, FileKey>()
+        .await
+        .map_err(|err| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Failed to create file store"
+            );
+            ErrorKind::InternalServerError.with_message("Failed to initialize file storage")
+        })?;
+
+    let file_key = FileKey::from_str(&file.storage_path).map_err(|err| {
+        tracing::error!(
+            target: TRACING_TARGET,
+            error = %err,
+            storage_path = %file.storage_path,
+            "Invalid storage path format"
+        );
+        ErrorKind::InternalServerError
+            .with_message("Invalid file storage path")
+            .with_context(format!("Parse error: {}", err))
+    })?;
+
+    // Get streaming content from NATS file store
+    let get_result = file_store
+        .get(&file_key)
+        .await
+        .map_err(|err| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                file_id = %path_params.file_id,
+                "Failed to retrieve file from storage"
+            );
+            ErrorKind::InternalServerError
+                .with_message("Failed to retrieve file")
+                .with_context(format!("Storage retrieval failed: {}", err))
+        })?
+        .ok_or_else(|| {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                file_id = %path_params.file_id,
+                "File content not found in storage"
+            );
+            ErrorKind::NotFound.with_message("File content not found")
+        })?;
+
+    let access =
+        NewWorkspaceFileAccess::download(workspace.id, file.id).from_search(open.from_search);
+    record_file_access(&mut conn, access).await;
+
+    // Set up response headers.
+    //
+    // The display name is user-controlled, so strip characters that are
+    // invalid in a quoted header value to avoid header injection and a
+    // failed parse; fall back to a generic disposition if it still fails.
+    let safe_name: String = file
+        .display_name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect();
+    let disposition = format!("attachment; filename=\"{safe_name}\"")
+        .parse()
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+
+    let mut headers = HeaderMap::new();
+    headers.insert("content-disposition", disposition);
+    // Content-length is the plaintext size from the record; storage holds the
+    // larger ciphertext, which the decrypting reader unwraps as it streams.
+    headers.insert(
+        "content-length",
+        file.file_size_bytes.to_string().parse().unwrap(),
+    );
+    headers.insert("content-type", "application/octet-stream".parse().unwrap());
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        file_id = %path_params.file_id,
+        size = file.file_size_bytes,
+        "Streaming file download"
+    );
+
+    // Decrypt the stored ciphertext as it streams to the client, unwrapping
+    // the object's own data key first if it has one.
+    let content_key = crypto
+        .stored_content_key(file.workspace_id, &get_result.info().metadata)
+        .await?;
+    let decrypted = crypto.decrypt_content_reader(&content_key, get_result.into_reader());
+    let stream = ReaderStream::new(Box::pin(decrypted));
+    let body = Body::from_stream(stream);
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+fn download_file_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Download file")
+        .description(
+            "Downloads a file by ID. Returns the file content as a binary stream. Counts as a \
+             download in the file's access statistics; pass `fromSearch=true` when downloading \
+             a search result.",
+        )
+        .response::<200, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Returns a file's access statistics.
+///
+/// Accesses are kept as daily counts without the accounts that made them.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn read_file_access(
+    State(pg_client): State<PgClient>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+    Query(window): Query<FileAccessWindow>,
+) -> Result<(StatusCode, Json<FileAccessStats>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading file access statistics");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewFiles)
+        .await?;
+
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+
+    let since = window.since();
+    let stats = conn.list_file_access_stats(scope, file.id, since).await?;
+    let last_accessed_on = conn.find_last_file_access_date(scope, file.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(FileAccessStats::from_models(
+            file.id,
+            since,
+            stats,
+            last_accessed_on,
+        )),
+    ))
+}
+
+fn read_file_access_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get file access statistics")
+        .description(
+            "Returns how often the file was viewed, downloaded, opened from search results and \
+             opened through share links, per day over the requested period. Only daily counts \
+             are kept: the statistics do not record who accessed the file.",
+        )
+        .response::<200, Json<FileAccessStats>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Deletes a file (soft delete).
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn delete_file(
+    State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    AuthState(auth_claims): AuthState,
+) -> Result<StatusCode> {
+    tracing::warn!(target: TRACING_TARGET, "File Deleting");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_claims
+        .authorize_tenant(&mut conn, workspace.id, Permission::DeleteFiles)
+        .await?;
+
+    // Confirm the file exists in this workspace before deleting.
+    let file = find_file(&mut conn, scope, path_params.file_id).await?;
+
+    if conn.is_file_under_legal_hold(scope, file.id).await? {
+        return Err(ErrorKind::Conflict
+            .with_message("File is under a legal hold")
+            .with_resource("file"));
+    }
+
+    conn.transaction(async |conn| {
+        conn.delete_workspace_file(path_params.file_id).await?;
+
+        let data = serde_json::json!({
+            "displayName": file.display_name,
+        });
+        webhook_emitter
+            .emit_file_deleted(
+                conn,
+                workspace.id,
+                path_params.file_id,
+                Some(auth_claims.account_id),
+                Some(data),
+            )
+            .await
+    })
+    .await
+    .map_err(|err| {
+        tracing::error!(target: TRACING_TARGET, error = %err, "Failed to soft delete file");
+        ErrorKind::InternalServerError
+            .with_message("Failed to delete file")
+            .with_context(format!("Database error: {}", err))
+    })?;
+
+    tracing::info!(target: TRACING_TARGET, "File deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn delete_file_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Delete file")
+        .description("Soft deletes a file by setting a deleted timestamp. The file can be recovered within the retention period. Files under a legal hold cannot be deleted.")
+        .response::<204, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Returns a [`Router`] with all related routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        // Workspace-scoped routes (require workspace context)
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/",
+            post_with(upload_file, upload_file_docs).get_with(list_files, list_files_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/",
+            get_with(read_file, read_file_docs)
+                .patch_with(update_file, update_file_docs)
                 .delete_with(delete_file, delete_file_docs),
         )
         .api_route(
             "/workspaces/{workspaceSlug}/files/{fileId}/content/",
             get_with(download_file, download_file_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/access/",
+            get_with(read_file_access, read_file_access_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/preflight/",
+            get_with(preflight_file, preflight_file_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/integrity/",
+            get_with(verify_file_integrity, verify_file_integrity_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/versions/",
+            get_with(list_file_versions, list_file_versions_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/restore/",
+            post_with(restore_file_version, restore_file_version_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/comparison/",
+            get_with(compare_file, compare_file_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/password/",
+            put_with(set_file_password, set_file_password_docs)
+                .delete_with(delete_file_password, delete_file_password_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/password/transient/",
+            put_with(supply_file_password, supply_file_password_docs)
+                .delete_with(forget_file_password, forget_file_password_docs),
+        )
         .with_path_items(|item| item.tag("Files"))
 }
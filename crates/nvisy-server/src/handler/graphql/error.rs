@@ -0,0 +1,49 @@
+//! Handler errors as GraphQL errors.
+
+use async_graphql::ErrorExtensions;
+
+use crate::handler::Error;
+use crate::handler::response::ErrorResponse;
+
+/// Renders an error as its REST body would: the client-safe message, with
+/// the error name as the `code` extension. The internal context is dropped.
+impl From<Error<'_>> for async_graphql::Error {
+    fn from(error: Error<'_>) -> Self {
+        let ErrorResponse {
+            name,
+            message,
+            resource,
+            ..
+        } = error.into_error_response();
+
+        Self::new(message).extend_with(|_, extensions| {
+            extensions.set("code", name.into_owned());
+            if let Some(resource) = resource {
+                extensions.set("resource", resource.into_owned());
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Value;
+
+    use super::*;
+    use crate::handler::ErrorKind;
+
+    #[test]
+    fn errors_keep_their_rest_message_and_name() {
+        let error = async_graphql::Error::from(
+            ErrorKind::NotFound
+                .with_message("Workspace not found")
+                .with_resource("workspace")
+                .with_context("slug lookup missed"),
+        );
+        assert_eq!(error.message, "Workspace not found");
+
+        let extensions = error.extensions.unwrap();
+        assert_eq!(extensions.get("code"), Some(&Value::from("not_found")));
+        assert_eq!(extensions.get("resource"), Some(&Value::from("workspace")));
+    }
+}
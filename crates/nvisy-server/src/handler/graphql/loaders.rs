@@ -0,0 +1,293 @@
+//! Batched loading for GraphQL resolvers.
+//!
+//! Keys pair a file or run with the workspace it is expected in, and results
+//! are keyed by the workspace it is actually in, so a key never resolves to
+//! another workspace's rows. Authorization is loaded the same way, so the
+//! files of a page share one permission check.
+
+use std::collections::HashMap;
+
+use async_graphql::dataloader::Loader;
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::{
+    WorkspaceDetectionReview, WorkspaceFile, WorkspacePipeline, WorkspacePipelineRun,
+};
+use nvisy_postgres::query::{
+    TenantScope, WorkspaceDetectionReviewRepository, WorkspaceFileRepository,
+    WorkspacePipelineRunRepository,
+};
+use nvisy_postgres::types::Username;
+use uuid::Uuid;
+
+use crate::extract::{AuthClaims, AuthProvider, Permission};
+use crate::handler::{Error, Result};
+
+/// A file together with the handle of the account that uploaded it.
+pub(super) type FileWithCreator = (WorkspaceFile, Username);
+
+/// A run together with its pipeline and the handle of the account that
+/// triggered it.
+pub(super) type RunWithDetails = (WorkspacePipelineRun, WorkspacePipeline, Option<Username>);
+
+/// A detection review together with the handle of its reviewer.
+pub(super) type ReviewWithReviewer = (WorkspaceDetectionReview, Option<Username>);
+
+/// A permission to check in a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct WorkspacePermission {
+    /// Workspace the permission is checked in.
+    pub workspace_id: Uuid,
+    /// The permission.
+    pub permission: Permission,
+}
+
+/// Checks the caller's permissions, once per workspace and permission for
+/// the whole request.
+///
+/// A denied check is kept as the value of its key rather than failing the
+/// batch, so it does not deny the other keys loaded with it.
+pub(super) struct ScopeLoader {
+    pg_client: PgClient,
+    auth_claims: AuthClaims,
+}
+
+impl ScopeLoader {
+    /// Creates a loader checking the permissions of `auth_claims`.
+    pub fn new(pg_client: PgClient, auth_claims: AuthClaims) -> Self {
+        Self {
+            pg_client,
+            auth_claims,
+        }
+    }
+}
+
+impl Loader<WorkspacePermission> for ScopeLoader {
+    type Error = Error<'static>;
+    type Value = Result<TenantScope>;
+
+    async fn load(
+        &self,
+        keys: &[WorkspacePermission],
+    ) -> Result<HashMap<WorkspacePermission, Result<TenantScope>>> {
+        // Authorized on the primary: an administrator's scope is recorded
+        // with a write.
+        let mut conn = self.pg_client.get_connection().await?;
+
+        let mut scopes = HashMap::with_capacity(keys.len());
+        for key in keys {
+            let scope = self
+                .auth_claims
+                .authorize_tenant(&mut conn, key.workspace_id, key.permission)
+                .await;
+            scopes.insert(*key, scope);
+        }
+
+        Ok(scopes)
+    }
+}
+
+/// Identifies a file within a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct WorkspaceFileKey {
+    /// Workspace the file must belong to.
+    pub workspace_id: Uuid,
+    /// The file.
+    pub file_id: Uuid,
+}
+
+impl WorkspaceFileKey {
+    /// Returns the key a loaded file is found under.
+    fn of(file: &WorkspaceFile) -> Self {
+        Self {
+            workspace_id: file.workspace_id,
+            file_id: file.id,
+        }
+    }
+}
+
+/// Loads files by id.
+pub(super) struct FileLoader {
+    pg_client: PgClient,
+}
+
+impl FileLoader {
+    /// Creates a loader reading from `pg_client`.
+    pub fn new(pg_client: PgClient) -> Self {
+        Self { pg_client }
+    }
+}
+
+impl Loader<WorkspaceFileKey> for FileLoader {
+    type Error = Error<'static>;
+    type Value = FileWithCreator;
+
+    async fn load(
+        &self,
+        keys: &[WorkspaceFileKey],
+    ) -> Result<HashMap<WorkspaceFileKey, FileWithCreator>> {
+        let file_ids: Vec<Uuid> = keys.iter().map(|key| key.file_id).collect();
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let files = conn.find_workspace_files_with_creators(&file_ids).await?;
+
+        Ok(files
+            .into_iter()
+            .map(|file| (WorkspaceFileKey::of(&file.0), file))
+            .collect())
+    }
+}
+
+/// Loads every version of files, newest first: the file itself and the
+/// files that name it as their parent.
+pub(super) struct FileVersionsLoader {
+    pg_client: PgClient,
+}
+
+impl FileVersionsLoader {
+    /// Creates a loader reading from `pg_client`.
+    pub fn new(pg_client: PgClient) -> Self {
+        Self { pg_client }
+    }
+}
+
+impl Loader<WorkspaceFileKey> for FileVersionsLoader {
+    type Error = Error<'static>;
+    type Value = Vec<FileWithCreator>;
+
+    async fn load(
+        &self,
+        keys: &[WorkspaceFileKey],
+    ) -> Result<HashMap<WorkspaceFileKey, Vec<FileWithCreator>>> {
+        let file_ids: Vec<Uuid> = keys.iter().map(|key| key.file_id).collect();
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let files = conn
+            .list_workspace_file_versions_with_creators(&file_ids)
+            .await?;
+
+        Ok(group_versions(keys, files))
+    }
+}
+
+/// Groups the versions loaded for `keys` by the file they are versions of.
+///
+/// A file is a version of itself and of its parent, so a file whose parent
+/// is also requested lands in both groups. Files keep the order they were
+/// loaded in.
+fn group_versions(
+    keys: &[WorkspaceFileKey],
+    files: Vec<FileWithCreator>,
+) -> HashMap<WorkspaceFileKey, Vec<FileWithCreator>> {
+    let mut versions: HashMap<_, Vec<_>> = keys.iter().map(|key| (*key, Vec::new())).collect();
+
+    for file in files {
+        let own = WorkspaceFileKey::of(&file.0);
+        let parent = file.0.parent_id.map(|parent_id| WorkspaceFileKey {
+            workspace_id: file.0.workspace_id,
+            file_id: parent_id,
+        });
+
+        if let Some(group) = parent.and_then(|parent| versions.get_mut(&parent)) {
+            group.push(file.clone());
+        }
+        if let Some(group) = versions.get_mut(&own) {
+            group.push(file);
+        }
+    }
+
+    versions
+}
+
+/// Loads the runs over files, newest first.
+pub(super) struct FileRunsLoader {
+    pg_client: PgClient,
+}
+
+impl FileRunsLoader {
+    /// Creates a loader reading from `pg_client`.
+    pub fn new(pg_client: PgClient) -> Self {
+        Self { pg_client }
+    }
+}
+
+impl Loader<WorkspaceFileKey> for FileRunsLoader {
+    type Error = Error<'static>;
+    type Value = Vec<RunWithDetails>;
+
+    async fn load(
+        &self,
+        keys: &[WorkspaceFileKey],
+    ) -> Result<HashMap<WorkspaceFileKey, Vec<RunWithDetails>>> {
+        let file_ids: Vec<Uuid> = keys.iter().map(|key| key.file_id).collect();
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let runs = conn
+            .list_workspace_file_runs_with_details(&file_ids)
+            .await?;
+
+        let mut by_file: HashMap<_, Vec<_>> = keys.iter().map(|key| (*key, Vec::new())).collect();
+        for run in runs {
+            let key = WorkspaceFileKey {
+                workspace_id: run.1.workspace_id,
+                file_id: run.0.file_id,
+            };
+            if let Some(group) = by_file.get_mut(&key) {
+                group.push(run);
+            }
+        }
+
+        Ok(by_file)
+    }
+}
+
+/// Identifies a run within a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct WorkspaceRunKey {
+    /// Workspace the run must belong to.
+    pub workspace_id: Uuid,
+    /// The run.
+    pub run_id: Uuid,
+}
+
+/// Loads the current decisions on the detections of runs, ordered by
+/// detection.
+pub(super) struct DetectionReviewsLoader {
+    pg_client: PgClient,
+}
+
+impl DetectionReviewsLoader {
+    /// Creates a loader reading from `pg_client`.
+    pub fn new(pg_client: PgClient) -> Self {
+        Self { pg_client }
+    }
+}
+
+impl Loader<WorkspaceRunKey> for DetectionReviewsLoader {
+    type Error = Error<'static>;
+    type Value = Vec<ReviewWithReviewer>;
+
+    async fn load(
+        &self,
+        keys: &[WorkspaceRunKey],
+    ) -> Result<HashMap<WorkspaceRunKey, Vec<ReviewWithReviewer>>> {
+        let run_ids: Vec<Uuid> = keys.iter().map(|key| key.run_id).collect();
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let reviews = conn
+            .list_current_detection_reviews_for_runs(&run_ids)
+            .await?;
+
+        let mut by_run: HashMap<_, Vec<_>> = keys.iter().map(|key| (*key, Vec::new())).collect();
+        for (review, reviewer_username, workspace_id) in reviews {
+            let key = WorkspaceRunKey {
+                workspace_id,
+                run_id: review.run_id,
+            };
+            if let Some(group) = by_run.get_mut(&key) {
+                group.push((review, reviewer_username));
+            }
+        }
+
+        Ok(by_run)
+    }
+}
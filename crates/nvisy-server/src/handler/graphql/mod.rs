@@ -0,0 +1,144 @@
+//! GraphQL endpoint for workspace and document queries.
+//!
+//! Serves `POST /graphql/` among the private routes, behind the same
+//! authentication middleware as REST. The schema is read-only and covers the
+//! caller's workspaces, their files and file versions, the runs over each
+//! file with their annotations and detection reviews, and the workspaces'
+//! activity, with each resolver checking the permission of its REST
+//! endpoint.
+//!
+//! Relations of files (a version's parent, a file's versions and runs, a
+//! run's reviews) and permission checks are loaded through per-request
+//! [`DataLoader`]s, so a page of files costs one query for each relation
+//! rather than one per file. Queries nesting deeper than [`MAX_DEPTH`] or
+//! more complex than [`MAX_COMPLEXITY`] are rejected before they run; a
+//! paginated field counts its selection once for every item the page may
+//! hold.
+
+mod error;
+mod loaders;
+mod schema;
+
+use aide::axum::ApiRouter;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{EmptyMutation, EmptySubscription, Request, Response, Schema};
+use axum::Extension;
+use axum::extract::State;
+use axum::routing::post;
+use nvisy_postgres::PgClient;
+
+use self::loaders::{
+    DetectionReviewsLoader, FileLoader, FileRunsLoader, FileVersionsLoader, ScopeLoader,
+};
+use self::schema::Query;
+use crate::extract::{AuthState, Json};
+use crate::service::{CryptoService, ResidencyService, ServiceState};
+
+/// Tracing target for GraphQL queries.
+const TRACING_TARGET: &str = "nvisy_server::handler::graphql";
+
+/// Deepest nesting of fields a query may use.
+const MAX_DEPTH: usize = 10;
+
+/// Highest complexity a query may reach.
+///
+/// Leaves room for a full page of files with every field and their
+/// versions, but not for a full page of files in each of a full page of
+/// workspaces.
+const MAX_COMPLEXITY: usize = 5_000;
+
+/// The schema served at `/graphql/`.
+type GraphQlSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Runs a GraphQL query for the authenticated account.
+///
+/// Errors are reported in the response body, as GraphQL expects, so the
+/// status is `200` whenever the request itself could be read.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn execute_query(
+    State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
+    State(crypto): State<CryptoService>,
+    Extension(schema): Extension<GraphQlSchema>,
+    AuthState(auth_state): AuthState,
+    Json(request): Json<Request>,
+) -> Json<Response> {
+    let request = request
+        .data(DataLoader::new(
+            ScopeLoader::new(pg_client.clone(), auth_state.clone()),
+            tokio::spawn,
+        ))
+        .data(auth_state)
+        .data(DataLoader::new(
+            FileLoader::new(pg_client.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            FileVersionsLoader::new(pg_client.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            FileRunsLoader::new(pg_client.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            DetectionReviewsLoader::new(pg_client.clone()),
+            tokio::spawn,
+        ))
+        .data(residency)
+        .data(crypto)
+        .data(pg_client);
+
+    let response = schema.execute(request).await;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        error_count = response.errors.len(),
+        "GraphQL query executed"
+    );
+
+    Json(response)
+}
+
+/// Builds the schema with its depth and complexity limits.
+fn schema() -> GraphQlSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Returns a [`Router`] with the GraphQL endpoint.
+///
+/// The endpoint is described by its own schema rather than the OpenAPI
+/// document; clients can introspect it.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    ApiRouter::new()
+        .route("/graphql/", post(execute_query))
+        .layer(Extension(schema()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn deep_and_complex_queries_are_rejected() {
+        let schema = schema();
+
+        let files = "files(limit: 100) { items { id displayName versions { id } } }";
+        let query = format!("{{ workspaces(limit: 100) {{ items {{ {files} }} }} }}");
+        let response = schema.execute(query).await;
+        assert_eq!(response.errors[0].message, "Query is too complex.");
+
+        let nested = "parent { ".repeat(MAX_DEPTH) + "id" + &" }".repeat(MAX_DEPTH);
+        let query = format!(
+            "{{ workspace(slug: \"acme\") {{ file(id: \"{}\") {{ {nested} }} }} }}",
+            uuid::Uuid::nil()
+        );
+        let response = schema.execute(query).await;
+        assert_eq!(response.errors[0].message, "Query is nested too deep.");
+    }
+}
@@ -0,0 +1,706 @@
+//! Types and resolvers of the GraphQL schema.
+//!
+//! Objects wrap the REST response types, so both APIs expose the same
+//! fields, and every resolver checks the permission its REST endpoint
+//! checks. Enums travel as the same snake_case names as in JSON.
+
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Json, Object, OutputType, SimpleObject};
+use jiff::Timestamp;
+use nvisy_engine::AnalyzedDocument;
+use nvisy_postgres::model::{NewWorkspaceFileAccess, WorkspacePipelineRun};
+use nvisy_postgres::query::{
+    TenantScope, WorkspaceActivityRepository, WorkspaceFileRepository, WorkspaceMemberRepository,
+    WorkspaceRepository,
+};
+use nvisy_postgres::types::{CursorPage, DataRegion, Slug, Username};
+use nvisy_postgres::{PgClient, PgConn};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::loaders::{
+    DetectionReviewsLoader, FileLoader, FileRunsLoader, FileVersionsLoader, FileWithCreator,
+    RunWithDetails, ScopeLoader, WorkspaceFileKey, WorkspacePermission, WorkspaceRunKey,
+};
+use crate::extract::{AuthClaims, AuthProvider, Permission};
+use crate::handler::files::record_file_access;
+use crate::handler::request::{CursorPagination, ListActivities, ListFiles};
+use crate::handler::response::{self, Activity, DetectionReview, File, PipelineRun, Workspace};
+use crate::handler::runs::load_analyzed_document;
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{CryptoService, ResidencyService};
+
+/// Complexity of a run's annotations, which are read from object storage
+/// and decrypted rather than loaded in a batch.
+const ANNOTATIONS_COMPLEXITY: usize = 20;
+
+/// Root of every query.
+pub(super) struct Query;
+
+#[Object]
+impl Query {
+    /// Workspaces the caller is a member of.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn workspaces(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u32>,
+        after: Option<String>,
+    ) -> Result<Page<WorkspaceNode>> {
+        let pagination = CursorPagination { limit, after };
+
+        let mut conn = pg_client(ctx).get_connection().await?;
+        let page = conn
+            .cursor_list_account_workspaces_with_details(
+                auth_claims(ctx).account_id,
+                pagination.into(),
+            )
+            .await?;
+
+        Ok(Page::from_cursor_page(
+            page,
+            |(workspace, member, creator_username)| WorkspaceNode {
+                id: workspace.id,
+                workspace: Workspace::from_model_with_membership(
+                    workspace,
+                    member,
+                    creator_username,
+                ),
+            },
+        ))
+    }
+
+    /// A workspace, by slug.
+    async fn workspace(&self, ctx: &Context<'_>, slug: String) -> Result<WorkspaceNode> {
+        let mut conn = pg_client(ctx).get_connection().await?;
+        let (workspace, creator_username) =
+            conn.find_workspace_by_slug(&slug).await?.ok_or_else(|| {
+                ErrorKind::NotFound
+                    .with_message("Workspace not found")
+                    .with_resource("workspace")
+            })?;
+
+        let member = auth_claims(ctx)
+            .authorize_workspace(&mut conn, workspace.id, Permission::ViewWorkspace)
+            .await?;
+
+        let id = workspace.id;
+        let workspace = match member {
+            Some(member) => {
+                Workspace::from_model_with_membership(workspace, member, creator_username)
+            }
+            None => Workspace::from_model(workspace, creator_username),
+        };
+
+        Ok(WorkspaceNode { id, workspace })
+    }
+}
+
+/// A workspace, as `GET /workspaces/{workspaceSlug}/` returns it.
+pub(super) struct WorkspaceNode {
+    id: Uuid,
+    workspace: Workspace,
+}
+
+#[Object(name = "Workspace")]
+impl WorkspaceNode {
+    /// URL-safe workspace identifier.
+    async fn slug(&self) -> &str {
+        self.workspace.slug.as_str()
+    }
+
+    /// Display name of the workspace.
+    async fn display_name(&self) -> &str {
+        &self.workspace.display_name
+    }
+
+    /// Description of the workspace.
+    async fn description(&self) -> Option<&str> {
+        self.workspace.description.as_deref()
+    }
+
+    /// Tags associated with the workspace.
+    async fn tags(&self) -> &[String] {
+        &self.workspace.tags
+    }
+
+    /// Whether processed files need approval before they are visible.
+    async fn require_approval(&self) -> bool {
+        self.workspace.require_approval
+    }
+
+    /// Region the workspace's files are stored and processed in.
+    async fn data_region(&self) -> String {
+        wire_name(&self.workspace.data_region)
+    }
+
+    /// Handle of the account that created the workspace.
+    async fn creator_username(&self) -> &str {
+        self.workspace.creator_username.as_str()
+    }
+
+    /// Role of the caller in the workspace.
+    async fn member_role(&self) -> String {
+        wire_name(&self.workspace.member_role)
+    }
+
+    /// When the workspace was created.
+    async fn created_at(&self) -> Timestamp {
+        self.workspace.created_at
+    }
+
+    /// When the workspace was last updated.
+    async fn updated_at(&self) -> Timestamp {
+        self.workspace.updated_at
+    }
+
+    /// The workspace's files, newest first, optionally searched by name.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn files(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        limit: Option<u32>,
+        after: Option<String>,
+    ) -> Result<Page<FileNode>> {
+        let query = ListFiles {
+            search,
+            formats: None,
+        };
+        let pagination = CursorPagination { limit, after };
+
+        let mut conn = pg_client(ctx).get_connection().await?;
+        let scope = self
+            .authorize_tenant(ctx, &mut conn, Permission::ViewFiles)
+            .await?;
+
+        let page = conn
+            .cursor_list_workspace_files(scope, pagination.into(), query.to_filter())
+            .await?;
+
+        Ok(Page::from_cursor_page(page, |file| {
+            FileNode::new(file, &self.workspace.slug, self.workspace.data_region)
+        }))
+    }
+
+    /// A file of the workspace, by id. Reading it counts as a view.
+    async fn file(&self, ctx: &Context<'_>, id: Uuid) -> Result<FileNode> {
+        let mut conn = pg_client(ctx).get_connection().await?;
+        let scope = self
+            .authorize_tenant(ctx, &mut conn, Permission::ViewFiles)
+            .await?;
+
+        let file = conn
+            .find_file_in_workspace_with_creator(scope, id)
+            .await?
+            .ok_or_else(|| Error::not_found("file"))?;
+
+        record_file_access(&mut conn, NewWorkspaceFileAccess::view(self.id, id)).await;
+
+        Ok(FileNode::new(
+            file,
+            &self.workspace.slug,
+            self.workspace.data_region,
+        ))
+    }
+
+    /// The workspace's audit log, newest first, optionally narrowed to one
+    /// resource or time range.
+    #[graphql(complexity = "page_complexity(limit, child_complexity)")]
+    async fn activity(
+        &self,
+        ctx: &Context<'_>,
+        resource_id: Option<Uuid>,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
+        limit: Option<u32>,
+        after: Option<String>,
+    ) -> Result<Page<ActivityNode>> {
+        let filter = ListActivities {
+            actor: None,
+            activity_type: None,
+            resource_type: None,
+            resource_id,
+            from,
+            to,
+        }
+        .to_filter()?;
+        let pagination = CursorPagination { limit, after };
+
+        // Authorized on the primary: an administrator's scope is recorded
+        // with a write.
+        let scope = self
+            .authorize_tenant(
+                ctx,
+                &mut pg_client(ctx).get_connection().await?,
+                Permission::ViewWorkspace,
+            )
+            .await?;
+
+        // The activity feed tolerates replica lag, so it reads from a replica.
+        let mut conn = pg_client(ctx).read().await?;
+
+        let page = conn
+            .cursor_list_workspace_activity(scope, pagination.into(), filter)
+            .await?;
+
+        Ok(Page::from_cursor_page(
+            page,
+            |(activity, actor_username)| {
+                ActivityNode(Activity::from_model(
+                    activity,
+                    self.workspace.slug.clone(),
+                    actor_username,
+                ))
+            },
+        ))
+    }
+}
+
+impl WorkspaceNode {
+    /// Checks that the caller holds `permission` in the workspace and returns
+    /// the scope for its rows.
+    async fn authorize_tenant(
+        &self,
+        ctx: &Context<'_>,
+        conn: &mut PgConn,
+        permission: Permission,
+    ) -> Result<TenantScope> {
+        let scope = auth_claims(ctx)
+            .authorize_tenant(conn, self.id, permission)
+            .await?;
+        Ok(scope)
+    }
+}
+
+/// A file, as `GET /workspaces/{workspaceSlug}/files/{fileId}/` returns it.
+pub(super) struct FileNode {
+    workspace_id: Uuid,
+    data_region: DataRegion,
+    file: File,
+}
+
+impl FileNode {
+    fn new(
+        (file, uploaded_by): FileWithCreator,
+        workspace_slug: &Slug,
+        data_region: DataRegion,
+    ) -> Self {
+        Self {
+            workspace_id: file.workspace_id,
+            data_region,
+            file: File::from_model(file, workspace_slug.clone(), uploaded_by),
+        }
+    }
+
+    /// Wraps another file of the same workspace.
+    fn sibling(&self, file: FileWithCreator) -> FileNode {
+        FileNode::new(file, &self.file.workspace_slug, self.data_region)
+    }
+
+    /// Returns the key of another file of the same workspace.
+    fn sibling_key(&self, file_id: Uuid) -> WorkspaceFileKey {
+        WorkspaceFileKey {
+            workspace_id: self.workspace_id,
+            file_id,
+        }
+    }
+}
+
+#[Object(name = "File")]
+impl FileNode {
+    /// Unique file identifier.
+    async fn id(&self) -> Uuid {
+        self.file.id
+    }
+
+    /// Display name.
+    async fn display_name(&self) -> &str {
+        &self.file.display_name
+    }
+
+    /// Original filename when uploaded.
+    async fn original_filename(&self) -> &str {
+        &self.file.original_filename
+    }
+
+    /// File extension, without the dot.
+    async fn file_extension(&self) -> &str {
+        &self.file.file_extension
+    }
+
+    /// MIME type.
+    async fn mime_type(&self) -> Option<&str> {
+        self.file.mime_type.as_deref()
+    }
+
+    /// File size in bytes.
+    async fn file_size(&self) -> i64 {
+        self.file.file_size
+    }
+
+    /// Classification tags.
+    async fn tags(&self) -> &[String] {
+        &self.file.tags
+    }
+
+    /// How the file was created.
+    async fn source(&self) -> String {
+        wire_name(&self.file.source)
+    }
+
+    /// Handle of the account that uploaded or created the file.
+    async fn uploaded_by(&self) -> &str {
+        self.file.uploaded_by.as_str()
+    }
+
+    /// Version number: 1 for the original, higher for newer versions.
+    async fn version_number(&self) -> i32 {
+        self.file.version_number
+    }
+
+    /// Version ID of the stored content.
+    async fn storage_version(&self) -> Option<&str> {
+        self.file.storage_version.as_deref()
+    }
+
+    /// Password protection status of the document.
+    async fn protection(&self) -> String {
+        wire_name(&self.file.protection)
+    }
+
+    /// Sensitivity of the content, which decides how it is encrypted.
+    async fn sensitivity(&self) -> String {
+        wire_name(&self.file.sensitivity)
+    }
+
+    /// When the file was created.
+    async fn created_at(&self) -> Timestamp {
+        self.file.created_at
+    }
+
+    /// When the file was last updated.
+    async fn updated_at(&self) -> Timestamp {
+        self.file.updated_at
+    }
+
+    /// The file this one is a newer version of.
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<FileNode>> {
+        let Some(parent_id) = self.file.parent_id else {
+            return Ok(None);
+        };
+
+        let parent = ctx
+            .data_unchecked::<DataLoader<FileLoader>>()
+            .load_one(self.sibling_key(parent_id))
+            .await?;
+
+        Ok(parent.map(|parent| self.sibling(parent)))
+    }
+
+    /// Every version of the file, this one included, newest first.
+    async fn versions(&self, ctx: &Context<'_>) -> Result<Vec<FileNode>> {
+        let versions = ctx
+            .data_unchecked::<DataLoader<FileVersionsLoader>>()
+            .load_one(self.sibling_key(self.file.id))
+            .await?
+            .unwrap_or_default();
+
+        Ok(versions
+            .into_iter()
+            .map(|version| self.sibling(version))
+            .collect())
+    }
+
+    /// Runs of the workspace's pipelines over the file, newest first.
+    async fn runs(&self, ctx: &Context<'_>) -> Result<Vec<RunNode>> {
+        authorize_tenant(ctx, self.workspace_id, Permission::ViewPipelines).await?;
+
+        let runs = ctx
+            .data_unchecked::<DataLoader<FileRunsLoader>>()
+            .load_one(self.sibling_key(self.file.id))
+            .await?
+            .unwrap_or_default();
+
+        Ok(runs
+            .into_iter()
+            .map(|run| RunNode::new(run, &self.file.workspace_slug, self.data_region))
+            .collect())
+    }
+}
+
+/// A pipeline run, as `GET /workspaces/{workspaceSlug}/runs/{runId}/`
+/// returns it.
+pub(super) struct RunNode {
+    workspace_id: Uuid,
+    data_region: DataRegion,
+    model: WorkspacePipelineRun,
+    run: PipelineRun,
+}
+
+impl RunNode {
+    fn new(
+        (model, pipeline, trigger_username): RunWithDetails,
+        workspace_slug: &Slug,
+        data_region: DataRegion,
+    ) -> Self {
+        Self {
+            workspace_id: pipeline.workspace_id,
+            data_region,
+            run: PipelineRun::from_model(
+                model.clone(),
+                pipeline.slug,
+                workspace_slug.clone(),
+                trigger_username,
+            ),
+            model,
+        }
+    }
+}
+
+#[Object(name = "Run")]
+impl RunNode {
+    /// Opaque identifier of the run.
+    async fn id(&self) -> String {
+        self.run.id.to_string()
+    }
+
+    /// Slug of the pipeline this run belongs to.
+    async fn pipeline_slug(&self) -> &str {
+        self.run.pipeline_slug.as_str()
+    }
+
+    /// File this run analyzes.
+    async fn file_id(&self) -> Uuid {
+        self.run.file_id
+    }
+
+    /// Handle of the account that triggered the run, if any.
+    async fn trigger_username(&self) -> Option<&str> {
+        self.run.trigger_username.as_ref().map(Username::as_str)
+    }
+
+    /// How the run was triggered.
+    async fn trigger_type(&self) -> String {
+        wire_name(&self.run.trigger_type)
+    }
+
+    /// Current run status.
+    async fn status(&self) -> String {
+        wire_name(&self.run.status)
+    }
+
+    /// Hex SHA-256 of the inputs the analysis depends on, once analyzed.
+    async fn input_digest(&self) -> Option<&str> {
+        self.run.input_digest.as_deref()
+    }
+
+    /// When the run started.
+    async fn started_at(&self) -> Timestamp {
+        self.run.started_at
+    }
+
+    /// When the run completed.
+    async fn completed_at(&self) -> Option<Timestamp> {
+        self.run.completed_at
+    }
+
+    /// Findings detected in the file, as the run's `detections` endpoint
+    /// returns them. Null until the run is analyzed.
+    #[graphql(complexity = "ANNOTATIONS_COMPLEXITY")]
+    async fn annotations(&self, ctx: &Context<'_>) -> Result<Option<Json<AnalyzedDocument>>> {
+        if self.model.analyzed_document_key.is_none() {
+            return Ok(None);
+        }
+
+        let nats = ctx
+            .data_unchecked::<ResidencyService>()
+            .backends(self.data_region)?
+            .nats();
+        let crypto = ctx.data_unchecked::<CryptoService>();
+        let analyzed = load_analyzed_document(nats, crypto, self.workspace_id, &self.model).await?;
+
+        Ok(Some(Json(analyzed)))
+    }
+
+    /// Current decision on every reviewed detection, ordered by detection.
+    /// Detections not listed are still proposed.
+    async fn reviews(&self, ctx: &Context<'_>) -> Result<Vec<DetectionReviewNode>> {
+        let key = WorkspaceRunKey {
+            workspace_id: self.workspace_id,
+            run_id: self.model.id,
+        };
+        let reviews = ctx
+            .data_unchecked::<DataLoader<DetectionReviewsLoader>>()
+            .load_one(key)
+            .await?
+            .unwrap_or_default();
+
+        Ok(reviews
+            .into_iter()
+            .map(|(review, reviewer_username)| {
+                DetectionReviewNode(DetectionReview::from_model(review, reviewer_username))
+            })
+            .collect())
+    }
+}
+
+/// A reviewer's decision on a detection, as
+/// `GET /workspaces/{workspaceSlug}/runs/{runId}/reviews/` lists it.
+pub(super) struct DetectionReviewNode(DetectionReview);
+
+#[Object(name = "DetectionReview")]
+impl DetectionReviewNode {
+    /// Identifier of the detection within the run's analysis.
+    async fn detection_id(&self) -> &str {
+        &self.0.detection_id
+    }
+
+    /// Version of the detection's review, starting at 1.
+    async fn version(&self) -> i32 {
+        self.0.version
+    }
+
+    /// Decision recorded by this version.
+    async fn decision(&self) -> String {
+        wire_name(&self.0.decision)
+    }
+
+    /// The decision while current, `superseded` once replaced.
+    async fn status(&self) -> String {
+        wire_name(&self.0.status)
+    }
+
+    /// Handle of the reviewer, if the account still exists.
+    async fn reviewer_username(&self) -> Option<&str> {
+        self.0.reviewer_username.as_ref().map(Username::as_str)
+    }
+
+    /// Reviewer comment.
+    async fn comment(&self) -> Option<&str> {
+        self.0.comment.as_deref()
+    }
+
+    /// When the decision was recorded.
+    async fn reviewed_at(&self) -> Timestamp {
+        self.0.reviewed_at
+    }
+}
+
+/// An audit log entry, as `GET /workspaces/{workspaceSlug}/activities/`
+/// lists it.
+pub(super) struct ActivityNode(Activity);
+
+#[Object(name = "Activity")]
+impl ActivityNode {
+    /// Unique activity identifier.
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    /// Handle of the account that performed the activity, if any.
+    async fn actor_username(&self) -> Option<&str> {
+        self.0.actor_username.as_ref().map(Username::as_str)
+    }
+
+    /// Type of activity.
+    async fn activity_type(&self) -> String {
+        wire_name(&self.0.activity_type)
+    }
+
+    /// Human-readable description.
+    async fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    /// Resource the activity acted on, if any.
+    async fn resource_id(&self) -> Option<Uuid> {
+        self.0.resource_id
+    }
+
+    /// Position of the activity in the workspace's audit chain.
+    async fn sequence_number(&self) -> i64 {
+        self.0.sequence_number
+    }
+
+    /// When the activity occurred.
+    async fn created_at(&self) -> Timestamp {
+        self.0.created_at
+    }
+}
+
+/// A page of results, as the REST list endpoints return it.
+#[derive(SimpleObject)]
+#[graphql(
+    concrete(name = "WorkspacePage", params(WorkspaceNode)),
+    concrete(name = "FilePage", params(FileNode)),
+    concrete(name = "ActivityPage", params(ActivityNode))
+)]
+pub(super) struct Page<T: OutputType> {
+    /// Items in this page.
+    items: Vec<T>,
+    /// Cursor to fetch the next page. Present only when more items exist.
+    next_cursor: Option<String>,
+}
+
+impl<T: OutputType> Page<T> {
+    fn from_cursor_page<M>(page: CursorPage<M>, f: impl FnMut(M) -> T) -> Self {
+        let page = response::Page::from_cursor_page(page, f);
+        Self {
+            items: page.items,
+            next_cursor: page.next_cursor,
+        }
+    }
+}
+
+/// Returns the complexity of a paginated field: its selection counts once
+/// for every item the page may hold.
+fn page_complexity(limit: Option<u32>, child_complexity: usize) -> usize {
+    let pagination = CursorPagination { limit, after: None };
+    pagination.limit() as usize * child_complexity
+}
+
+/// Checks that the caller holds `permission` in a workspace and returns the
+/// scope for its rows. Each check runs once per request.
+async fn authorize_tenant(
+    ctx: &Context<'_>,
+    workspace_id: Uuid,
+    permission: Permission,
+) -> Result<TenantScope> {
+    let key = WorkspacePermission {
+        workspace_id,
+        permission,
+    };
+    ctx.data_unchecked::<DataLoader<ScopeLoader>>()
+        .load_one(key)
+        .await?
+        .unwrap_or_else(|| Err(ErrorKind::Forbidden.into_error()))
+}
+
+fn auth_claims<'a>(ctx: &Context<'a>) -> &'a AuthClaims {
+    ctx.data_unchecked::<AuthClaims>()
+}
+
+fn pg_client<'a>(ctx: &Context<'a>) -> &'a PgClient {
+    ctx.data_unchecked::<PgClient>()
+}
+
+/// Returns the name an enum value is serialized as in JSON.
+fn wire_name(value: impl Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_count_their_selection_per_item() {
+        assert_eq!(page_complexity(Some(5), 3), 15);
+        assert_eq!(page_complexity(Some(1_000), 1), 100);
+        assert_eq!(page_complexity(None, 2), 40);
+    }
+}
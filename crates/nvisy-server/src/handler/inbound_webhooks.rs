@@ -0,0 +1,339 @@
+//! Workspace inbound webhook handlers.
+//!
+//! An inbound webhook is an endpoint through which a third-party service
+//! notifies a workspace of its events. Workspace administrators create one
+//! per integration, choosing the provider that decides how requests are
+//! verified, and configure the service with its path and secret. Requests to
+//! that path are public: the signature is the credential. Accepted events are
+//! published to the workspace event stream.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use nvisy_postgres::model::WorkspaceInboundWebhook;
+use nvisy_postgres::query::{TenantScope, WorkspaceInboundWebhookRepository};
+use nvisy_postgres::types::Username;
+use nvisy_postgres::{PgClient, PgConn};
+use uuid::Uuid;
+
+use crate::extract::{
+    AuthProvider, AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceContext,
+};
+use crate::handler::request::{CreateInboundWebhook, CursorPagination, InboundWebhookPathParams};
+use crate::handler::response::{
+    ErrorResponse, InboundDeliveryAccepted, InboundWebhook, InboundWebhookCreated,
+    InboundWebhooksPage,
+};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{CryptoService, InboundReceiver, ServiceState};
+
+/// Tracing target for workspace inbound webhook operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::inbound_webhooks";
+
+/// Creates a new workspace inbound webhook.
+///
+/// Returns the inbound webhook with its secret. Requires `CreateWebhooks`
+/// permission.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn create_inbound_webhook(
+    State(pg_client): State<PgClient>,
+    State(crypto): State<CryptoService>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    ValidateJson(mut request): ValidateJson<CreateInboundWebhook>,
+) -> Result<(StatusCode, Json<InboundWebhookCreated>)> {
+    tracing::debug!(target: TRACING_TARGET, "Creating workspace inbound webhook");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::CreateWebhooks)
+        .await?;
+
+    let secret = match request.secret.take() {
+        Some(secret) => secret,
+        None if request.provider.supplies_secret() => {
+            return Err(ErrorKind::BadRequest
+                .with_message("This provider generates the secret; supply it when creating")
+                .with_resource("inbound_webhook"));
+        }
+        None => crypto.generate_secret()?,
+    };
+    let encrypted_secret = crypto.encrypt(workspace.id, secret.as_bytes())?;
+
+    let new_inbound_webhook =
+        request.into_model(workspace.id, auth_state.account_id, encrypted_secret);
+    let inbound_webhook = conn
+        .create_workspace_inbound_webhook(new_inbound_webhook)
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        inbound_webhook_id = %inbound_webhook.id,
+        provider = %inbound_webhook.provider,
+        "Inbound webhook created",
+    );
+
+    let (inbound_webhook, creator_username) =
+        find_inbound_webhook(&mut conn, scope, inbound_webhook.id).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InboundWebhookCreated::from_model(
+            inbound_webhook,
+            workspace.slug,
+            creator_username,
+            secret,
+        )),
+    ))
+}
+
+fn create_inbound_webhook_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create inbound webhook")
+        .description(
+            "Creates an endpoint through which a third-party service sends events to the \
+             workspace. The response includes the endpoint's path and the secret its requests \
+             are verified with. **Important**: The secret is only shown once upon creation and \
+             cannot be retrieved again.",
+        )
+        .response::<201, Json<InboundWebhookCreated>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Lists the inbound webhooks of a workspace.
+///
+/// Requires `ViewWebhooks` permission.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn list_inbound_webhooks(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Query(pagination): Query<CursorPagination>,
+) -> Result<(StatusCode, Json<InboundWebhooksPage>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing workspace inbound webhooks");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewWebhooks)
+        .await?;
+
+    let page = conn
+        .cursor_list_workspace_inbound_webhooks(scope, pagination.into())
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(InboundWebhooksPage::from_cursor_page(
+            page,
+            |(inbound_webhook, creator_username)| {
+                InboundWebhook::from_model(
+                    inbound_webhook,
+                    workspace.slug.clone(),
+                    creator_username,
+                )
+            },
+        )),
+    ))
+}
+
+fn list_inbound_webhooks_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List inbound webhooks")
+        .description("Returns the workspace's inbound webhooks without their secrets.")
+        .response::<200, Json<InboundWebhooksPage>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Retrieves a specific workspace inbound webhook.
+///
+/// Requires `ViewWebhooks` permission.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        inbound_webhook_id = %path_params.inbound_webhook_id,
+    )
+)]
+async fn read_inbound_webhook(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<InboundWebhookPathParams>,
+) -> Result<(StatusCode, Json<InboundWebhook>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading workspace inbound webhook");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewWebhooks)
+        .await?;
+
+    let (inbound_webhook, creator_username) =
+        find_inbound_webhook(&mut conn, scope, path_params.inbound_webhook_id.as_uuid()).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(InboundWebhook::from_model(
+            inbound_webhook,
+            workspace.slug,
+            creator_username,
+        )),
+    ))
+}
+
+fn read_inbound_webhook_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get inbound webhook")
+        .description("Returns inbound webhook details without the secret.")
+        .response::<200, Json<InboundWebhook>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Deletes a workspace inbound webhook.
+///
+/// Requests to it are refused from then on. Requires `DeleteWebhooks`
+/// permission.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        inbound_webhook_id = %path_params.inbound_webhook_id,
+    )
+)]
+async fn delete_inbound_webhook(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<InboundWebhookPathParams>,
+) -> Result<StatusCode> {
+    tracing::debug!(target: TRACING_TARGET, "Deleting workspace inbound webhook");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::DeleteWebhooks)
+        .await?;
+
+    let deleted = conn
+        .delete_workspace_inbound_webhook(scope, path_params.inbound_webhook_id.as_uuid())
+        .await?;
+    if !deleted {
+        return Err(Error::not_found("inbound_webhook"));
+    }
+
+    tracing::info!(target: TRACING_TARGET, "Inbound webhook deleted");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn delete_inbound_webhook_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Delete inbound webhook")
+        .description("Removes the inbound webhook; requests sent to it are refused from then on.")
+        .response::<204, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Receives a request from a third-party service.
+///
+/// Verifies the request against the inbound webhook's secret and publishes
+/// the event to the workspace event stream, unless the delivery was accepted
+/// before.
+#[tracing::instrument(
+    skip_all,
+    fields(inbound_webhook_id = %path_params.inbound_webhook_id)
+)]
+async fn receive_inbound_request(
+    State(inbound): State<InboundReceiver>,
+    Path(path_params): Path<InboundWebhookPathParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<InboundDeliveryAccepted>)> {
+    let receipt = inbound
+        .receive(path_params.inbound_webhook_id.as_uuid(), &headers, &body)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(receipt.into())))
+}
+
+fn receive_inbound_request_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Receive inbound webhook request")
+        .description(
+            "Endpoint third-party services send their events to. The request is verified \
+             according to the inbound webhook's provider: an `X-Webhook-Signature` over \
+             `{X-Webhook-Timestamp}.{body}` for `hmac`, an HS256-signed JWT body for `jwt`, \
+             `X-DocuSign-Signature-1` for `docusign` and `X-Goog-Channel-Token` for \
+             `google_drive`. A delivery accepted before is acknowledged with `duplicate` set \
+             and not routed again.",
+        )
+        .response::<202, Json<InboundDeliveryAccepted>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Finds an inbound webhook of a workspace with its creator's handle.
+async fn find_inbound_webhook(
+    conn: &mut PgConn,
+    scope: TenantScope,
+    inbound_webhook_id: Uuid,
+) -> Result<(WorkspaceInboundWebhook, Username)> {
+    conn.find_inbound_webhook_in_workspace_with_creator(scope, inbound_webhook_id)
+        .await?
+        .ok_or_else(|| Error::not_found("inbound_webhook"))
+}
+
+/// Returns a [`Router`] with the inbound webhook management routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/workspaces/{workspaceSlug}/inbound-webhooks/",
+            post_with(create_inbound_webhook, create_inbound_webhook_docs)
+                .get_with(list_inbound_webhooks, list_inbound_webhooks_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/inbound-webhooks/{inboundWebhookId}/",
+            get_with(read_inbound_webhook, read_inbound_webhook_docs)
+                .delete_with(delete_inbound_webhook, delete_inbound_webhook_docs),
+        )
+        .with_path_items(|item| item.tag("Inbound Webhooks"))
+}
+
+/// Returns a [`Router`] with the route third-party services send requests to.
+///
+/// [`Router`]: axum::routing::Router
+pub fn public_routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/inbound/{inboundWebhookId}/",
+            post_with(receive_inbound_request, receive_inbound_request_docs),
+        )
+        .with_path_items(|item| item.tag("Inbound Webhooks"))
+}
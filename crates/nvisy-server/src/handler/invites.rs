@@ -11,11 +11,11 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use nvisy_postgres::model::{Account, NewAccountNotification, NewWorkspaceMember, WorkspaceInvite};
 use nvisy_postgres::query::{
-    AccountNotificationRepository, AccountRepository, WorkspaceInviteRepository,
-    WorkspaceMemberRepository, WorkspaceRepository,
+    AccountNotificationRepository, AccountRepository, AdminScope, TenantScope,
+    WorkspaceInviteRepository, WorkspaceMemberRepository, WorkspaceRepository,
 };
 use nvisy_postgres::types::NotificationEvent;
-use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgError};
+use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgConnection, PgError, PgResult};
 use uuid::Uuid;
 
 use crate::extract::{
@@ -29,7 +29,9 @@ use crate::handler::response::{
     ErrorResponse, Invite, InviteCode, InvitePreview, InviteSent, InvitesPage, Member,
 };
 use crate::handler::{ErrorKind, Result};
-use crate::service::ServiceState;
+use crate::service::{
+    InviteAccepted, InviteCreated, MembershipSource, ServiceState, WebhookEmitter,
+};
 
 /// Tracing target for workspace invite operations.
 const TRACING_TARGET: &str = "nvisy_server::handler::invites";
@@ -56,19 +58,20 @@ pub enum InviteOutcome {
 pub struct CreatedInvite {
     /// The persisted invitation.
     pub invite: WorkspaceInvite,
-    /// The account the invitation was addressed to. Unused by this crate;
-    /// exposed for callers that deliver email out-of-band (e.g. the hosted
-    /// edition) and need the recipient's account details.
-    #[allow(dead_code)]
+    /// The account the invitation was addressed to. Also exposed for callers
+    /// that deliver email out-of-band (e.g. the hosted edition) and need the
+    /// recipient's account details.
     pub account: Account,
 }
 
 /// Creates a workspace invitation for an existing platform account.
 ///
-/// Assumes the caller has already authorized `InviteMembers` on the workspace.
+/// Takes the scope the caller was granted `InviteMembers` on the workspace
+/// with.
 /// Rejects an email that already belongs to a member or has a pending invite.
-/// If the email resolves to an account, the invite and an in-app notification
-/// are created together in one transaction and returned as
+/// If the email resolves to an account, the invite, an in-app notification
+/// and the `invite:created` event are created together in one transaction
+/// and returned as
 /// [`InviteOutcome::Created`]; otherwise [`InviteOutcome::UnknownEmail`] is
 /// returned without creating anything.
 ///
@@ -77,12 +80,14 @@ pub struct CreatedInvite {
 /// `UnknownEmail` however it chooses.
 pub async fn create_invite(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    webhook_emitter: &WebhookEmitter,
+    scope: TenantScope,
     actor_id: Uuid,
     request: &CreateInvite,
 ) -> Result<InviteOutcome> {
+    let workspace_id = scope.workspace_id();
     if conn
-        .find_workspace_member_by_email(workspace_id, &request.invitee_email)
+        .find_workspace_member_by_email(scope, &request.invitee_email)
         .await?
         .is_some()
     {
@@ -96,7 +101,7 @@ pub async fn create_invite(
     };
 
     if conn
-        .find_pending_workspace_invite_by_email(workspace_id, &request.invitee_email)
+        .find_pending_workspace_invite_by_email(scope, &request.invitee_email)
         .await?
         .is_some()
     {
@@ -124,6 +129,8 @@ pub async fn create_invite(
             })
             .await?;
 
+            emit_created(conn, webhook_emitter, &invite, Some(account_id), actor_id).await?;
+
             Ok::<_, PgError>(invite)
         })
         .await?;
@@ -151,6 +158,7 @@ pub async fn create_invite(
 )]
 async fn send_invite(
     State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     ValidateJson(request): ValidateJson<CreateInvite>,
@@ -159,11 +167,20 @@ async fn send_invite(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::InviteMembers)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::InviteMembers)
         .await?;
 
-    match create_invite(&mut conn, workspace.id, auth_state.account_id, &request).await? {
+    let outcome = create_invite(
+        &mut conn,
+        &webhook_emitter,
+        scope,
+        auth_state.account_id,
+        &request,
+    )
+    .await?;
+
+    match outcome {
         InviteOutcome::Created(created) => {
             tracing::info!(
                 target: TRACING_TARGET,
@@ -216,17 +233,12 @@ async fn list_invites(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewMembers)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewMembers)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_invites(
-            workspace.id,
-            pagination.into(),
-            query.to_sort(),
-            query.to_filter(),
-        )
+        .cursor_list_workspace_invites(scope, pagination.into(), query.to_sort(), query.to_filter())
         .await?;
 
     tracing::debug!(
@@ -273,12 +285,12 @@ async fn cancel_invite(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::InviteMembers)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::InviteMembers)
         .await?;
 
     // Confirm the invite exists in this workspace before cancelling.
-    find_invite(&mut conn, workspace.id, path_params.invite_id).await?;
+    find_invite(&mut conn, scope, path_params.invite_id).await?;
 
     conn.cancel_workspace_invite(path_params.invite_id, auth_state.account_id)
         .await?;
@@ -313,6 +325,7 @@ fn cancel_invite_docs(op: TransformOperation) -> TransformOperation {
 )]
 async fn reply_to_invite(
     State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<InvitePathParams>,
@@ -322,7 +335,19 @@ async fn reply_to_invite(
 
     let mut conn = pg_client.get_connection().await?;
 
-    let invite = find_invite(&mut conn, workspace.id, path_params.invite_id).await?;
+    // The invitee is not a member yet, so the invite is resolved before any
+    // scope for the workspace exists.
+    let admin = AdminScope::open(&mut conn, "resolve invite for invitee reply").await?;
+    let invite = conn
+        .find_workspace_invite_by_id(&admin, path_params.invite_id)
+        .await?
+        .filter(|invite| invite.workspace_id == workspace.id)
+        .ok_or_else(|| {
+            ErrorKind::NotFound
+                .with_message("Invitation not found")
+                .with_resource("workspace_invite")
+        })?;
+    let scope = admin.tenant(workspace.id);
 
     // Verify invitation is still valid
     if !invite.can_be_used() {
@@ -334,7 +359,7 @@ async fn reply_to_invite(
     let workspace_invite = if request.accept_invite {
         // Check if user is already a member
         if conn
-            .find_workspace_member(invite.workspace_id, auth_state.account_id)
+            .find_workspace_member(scope, auth_state.account_id)
             .await?
             .is_some()
         {
@@ -355,6 +380,15 @@ async fn reply_to_invite(
                 let new_member = NewWorkspaceMember::new(workspace_id, account_id, invited_role);
                 conn.add_workspace_member(new_member).await?;
 
+                emit_accepted(
+                    conn,
+                    &webhook_emitter,
+                    &invite,
+                    account_id,
+                    MembershipSource::Invite,
+                )
+                .await?;
+
                 Ok::<_, PgError>(accepted)
             })
             .await?;
@@ -400,6 +434,7 @@ fn reply_to_invite_docs(op: TransformOperation) -> TransformOperation {
 )]
 async fn generate_invite_code(
     State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     ValidateJson(request): ValidateJson<GenerateInviteCode>,
@@ -412,8 +447,15 @@ async fn generate_invite_code(
         .authorize_workspace(&mut conn, workspace.id, Permission::InviteMembers)
         .await?;
 
+    let new_invite = request.into_model(workspace.id, auth_state.account_id);
+    let actor_id = auth_state.account_id;
+
     let workspace_invite = conn
-        .create_workspace_invite(request.into_model(workspace.id, auth_state.account_id))
+        .transaction(async |conn| {
+            let invite = conn.create_workspace_invite(new_invite).await?;
+            emit_created(conn, &webhook_emitter, &invite, None, actor_id).await?;
+            Ok::<_, PgError>(invite)
+        })
         .await?;
 
     tracing::info!(
@@ -453,7 +495,7 @@ async fn preview_invite_code(
 
     let mut conn = pg_client.get_connection().await?;
 
-    let Some(invite) = conn
+    let Some((invite, scope)) = conn
         .find_workspace_invite_by_token(&path_params.invite_code)
         .await?
     else {
@@ -468,7 +510,7 @@ async fn preview_invite_code(
             .with_resource("invite_code"));
     }
 
-    let Some(workspace) = conn.find_workspace_by_id(invite.workspace_id).await? else {
+    let Some(workspace) = conn.find_workspace_by_id(scope).await? else {
         return Err(ErrorKind::NotFound
             .with_resource("workspace")
             .with_message("Workspace not found"));
@@ -502,6 +544,7 @@ fn preview_invite_code_docs(op: TransformOperation) -> TransformOperation {
 #[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
 async fn reply_to_invite_code(
     State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
     AuthState(auth_state): AuthState,
     Path(path_params): Path<InviteCodePathParams>,
     Json(request): Json<Option<ReplyInvite>>,
@@ -512,7 +555,7 @@ async fn reply_to_invite_code(
 
     let mut conn = pg_client.get_connection().await?;
 
-    let Some(invite) = conn
+    let Some((invite, scope)) = conn
         .find_workspace_invite_by_token(&path_params.invite_code)
         .await?
     else {
@@ -530,7 +573,7 @@ async fn reply_to_invite_code(
     if accept {
         // Check if user is already a member
         if conn
-            .find_workspace_member(invite.workspace_id, auth_state.account_id)
+            .find_workspace_member(scope, auth_state.account_id)
             .await?
             .is_some()
         {
@@ -552,10 +595,19 @@ async fn reply_to_invite_code(
                 conn.add_workspace_member(new_member).await?;
 
                 let result = conn
-                    .find_workspace_member_with_account(workspace_id, account_id)
+                    .find_workspace_member_with_account(scope, account_id)
                     .await?
                     .ok_or_else(|| PgError::Unexpected("Member not found after insert".into()))?;
 
+                emit_accepted(
+                    conn,
+                    &webhook_emitter,
+                    &invite,
+                    account_id,
+                    MembershipSource::InviteCode,
+                )
+                .await?;
+
                 Ok::<_, PgError>(result)
             })
             .await?;
@@ -598,13 +650,74 @@ fn reply_to_invite_code_docs(op: TransformOperation) -> TransformOperation {
         .response::<409, Json<ErrorResponse>>()
 }
 
+/// Records `invite:created` for a new invitation or invite code.
+async fn emit_created(
+    conn: &mut PgConnection,
+    webhook_emitter: &WebhookEmitter,
+    invite: &WorkspaceInvite,
+    invitee_account_id: Option<Uuid>,
+    actor_id: Uuid,
+) -> PgResult<()> {
+    let created = InviteCreated {
+        invited_role: invite.invited_role,
+        invitee_account_id,
+        expires_at: invite.expires_at,
+        source: match invitee_account_id {
+            Some(_) => MembershipSource::Invite,
+            None => MembershipSource::InviteCode,
+        },
+    };
+    webhook_emitter
+        .emit_invite_created(
+            conn,
+            invite.workspace_id,
+            invite.id,
+            Some(actor_id),
+            &created,
+        )
+        .await
+}
+
+/// Records `invite:accepted` and `member:added` for the member an accepted
+/// invitation or invite code created.
+async fn emit_accepted(
+    conn: &mut PgConnection,
+    webhook_emitter: &WebhookEmitter,
+    invite: &WorkspaceInvite,
+    account_id: Uuid,
+    source: MembershipSource,
+) -> PgResult<()> {
+    let accepted = InviteAccepted {
+        account_id,
+        role: invite.invited_role,
+        source,
+    };
+    webhook_emitter
+        .emit_invite_accepted(conn, invite.workspace_id, invite.id, &accepted)
+        .await?;
+
+    let data = serde_json::json!({
+        "role": invite.invited_role.to_string(),
+        "source": source,
+    });
+    webhook_emitter
+        .emit_member_added(
+            conn,
+            invite.workspace_id,
+            account_id,
+            Some(account_id),
+            Some(data),
+        )
+        .await
+}
+
 /// Finds an invite within a workspace or returns NotFound error.
 async fn find_invite(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     invite_id: Uuid,
 ) -> Result<WorkspaceInvite> {
-    conn.find_invite_in_workspace(workspace_id, invite_id)
+    conn.find_invite_in_workspace(scope, invite_id)
         .await?
         .ok_or_else(|| {
             ErrorKind::NotFound
@@ -9,18 +9,20 @@ use aide::axum::ApiRouter;
 use aide::transform::TransformOperation;
 use axum::extract::State;
 use axum::http::StatusCode;
-use nvisy_postgres::query::{AccountRepository, WorkspaceMemberRepository};
-use nvisy_postgres::types::{Username, WorkspaceRole};
-use nvisy_postgres::{PgClient, PgConn};
+use nvisy_postgres::query::{
+    AccountRepository, WorkspaceCustomRoleRepository, WorkspaceMemberRepository,
+};
+use nvisy_postgres::types::{RoleId, Username, WorkspaceRole};
+use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgError};
 use uuid::Uuid;
 
 use crate::extract::{
-    AuthProvider, AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceContext,
+    AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceAccess, WorkspaceContext,
 };
 use crate::handler::request::{CursorPagination, ListMembers, MemberPathParams, UpdateMember};
 use crate::handler::response::{ErrorResponse, Member, MembersPage, Page};
 use crate::handler::{Error, ErrorKind, Result};
-use crate::service::{ServiceState, WebhookEmitter};
+use crate::service::{MemberRoleChanged, MembershipSource, Policy, ServiceState, WebhookEmitter};
 
 /// Tracing target for workspace member operations.
 const TRACING_TARGET: &str = "nvisy_server::handler::members";
@@ -32,31 +34,25 @@ const TRACING_TARGET: &str = "nvisy_server::handler::members";
 #[tracing::instrument(
     skip_all,
     fields(
-        account_id = %auth_state.account_id,
-        workspace_id = %workspace.id,
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
     )
 )]
 async fn list_members(
     State(pg_client): State<PgClient>,
-    AuthState(auth_state): AuthState,
-    WorkspaceContext(workspace): WorkspaceContext,
+    access: WorkspaceAccess,
     Query(query): Query<ListMembers>,
     Query(pagination): Query<CursorPagination>,
 ) -> Result<(StatusCode, Json<MembersPage>)> {
     tracing::debug!(target: TRACING_TARGET, "Listing workspace members");
 
-    let mut conn = pg_client.get_connection().await?;
+    access.require(Permission::ViewMembers)?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewMembers)
-        .await?;
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
 
     let page = conn
-        .cursor_list_workspace_members_with_accounts(
-            workspace.id,
-            pagination.into(),
-            query.to_filter(),
-        )
+        .cursor_list_workspace_members_with_accounts(scope, pagination.into(), query.to_filter())
         .await?;
 
     tracing::info!(
@@ -88,30 +84,28 @@ fn list_members_docs(op: TransformOperation) -> TransformOperation {
 #[tracing::instrument(
     skip_all,
     fields(
-        account_id = %auth_state.account_id,
-        workspace_id = %workspace.id,
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
         member = %path_params.username,
         member_id = tracing::field::Empty,
     )
 )]
 async fn get_member(
     State(pg_client): State<PgClient>,
-    AuthState(auth_state): AuthState,
-    WorkspaceContext(workspace): WorkspaceContext,
+    access: WorkspaceAccess,
     Path(path_params): Path<MemberPathParams>,
 ) -> Result<(StatusCode, Json<Member>)> {
     tracing::debug!(target: TRACING_TARGET, "Retrieving workspace member details");
 
-    let mut conn = pg_client.get_connection().await?;
+    access.require(Permission::ViewMembers)?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewMembers)
-        .await?;
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
 
     let member_account_id = resolve_member_account_id(&mut conn, &path_params.username).await?;
 
     let Some((workspace_member, account)) = conn
-        .find_workspace_member_with_account(workspace.id, member_account_id)
+        .find_workspace_member_with_account(scope, member_account_id)
         .await?
     else {
         return Err(ErrorKind::NotFound
@@ -148,39 +142,37 @@ fn get_member_docs(op: TransformOperation) -> TransformOperation {
 #[tracing::instrument(
     skip_all,
     fields(
-        account_id = %auth_state.account_id,
-        workspace_id = %workspace.id,
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
         member = %path_params.username,
         member_id = tracing::field::Empty,
     )
 )]
 async fn delete_member(
     State(pg_client): State<PgClient>,
+    State(policy): State<Policy>,
     State(webhook_emitter): State<WebhookEmitter>,
-    AuthState(auth_state): AuthState,
-    WorkspaceContext(workspace): WorkspaceContext,
+    access: WorkspaceAccess,
     Path(path_params): Path<MemberPathParams>,
 ) -> Result<StatusCode> {
     tracing::warn!(target: TRACING_TARGET, "Removing workspace member");
 
-    let mut conn = pg_client.get_connection().await?;
+    access.require(Permission::RemoveMembers)?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::RemoveMembers)
-        .await?;
+    let workspace = access.workspace();
+    let actor_id = access.actor().account_id;
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
 
     let member_account_id = resolve_member_account_id(&mut conn, &path_params.username).await?;
 
     // Prevent self-removal (use leave endpoint instead)
-    if auth_state.account_id == member_account_id {
+    if actor_id == member_account_id {
         return Err(ErrorKind::BadRequest
             .with_message("Cannot remove yourself. Use the leave workspace endpoint instead."));
     }
 
-    let Some(member_to_remove) = conn
-        .find_workspace_member(workspace.id, member_account_id)
-        .await?
-    else {
+    let Some(member_to_remove) = conn.find_workspace_member(scope, member_account_id).await? else {
         return Err(ErrorKind::NotFound.with_resource("workspace_member"));
     };
 
@@ -191,28 +183,25 @@ async fn delete_member(
             .with_context("Owners can only leave the workspace themselves"));
     }
 
-    conn.remove_workspace_member(workspace.id, member_account_id)
-        .await?;
-
-    // Emit webhook event (fire-and-forget)
-    let data = serde_json::json!({
-        "removedUsername": path_params.username,
-    });
-    if let Err(err) = webhook_emitter
-        .emit_member_deleted(
-            workspace.id,
-            member_account_id,
-            Some(auth_state.account_id),
-            Some(data),
-        )
-        .await
-    {
-        tracing::warn!(
-            target: TRACING_TARGET,
-            error = %err,
-            "Failed to emit member:deleted webhook event"
-        );
-    }
+    conn.transaction(async |conn| {
+        conn.remove_workspace_member(scope, member_account_id)
+            .await?;
+
+        let data = serde_json::json!({
+            "removedUsername": path_params.username,
+        });
+        webhook_emitter
+            .emit_member_deleted(
+                conn,
+                workspace.id,
+                member_account_id,
+                Some(actor_id),
+                Some(data),
+            )
+            .await
+    })
+    .await?;
+    policy.invalidate(workspace.id, member_account_id).await;
 
     tracing::warn!(target: TRACING_TARGET, "Workspace member removed");
 
@@ -231,16 +220,16 @@ fn delete_member_docs(op: TransformOperation) -> TransformOperation {
         .response::<404, Json<ErrorResponse>>()
 }
 
-/// Updates a workspace member's role.
+/// Updates a workspace member's roles.
 ///
-/// Allows workspace owners to change a member's permission level.
-/// Cannot update your own role. Cannot demote an owner.
-/// Requires `ManageRoles` permission.
+/// Allows workspace owners to change a member's built-in role and assign or
+/// unassign a custom role. Cannot update your own role. Cannot demote an
+/// owner. Requires `ManageRoles` permission.
 #[tracing::instrument(
     skip_all,
     fields(
-        account_id = %auth_state.account_id,
-        workspace_id = %workspace.id,
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
         member = %path_params.username,
         member_id = tracing::field::Empty,
         new_role = ?request.role,
@@ -248,76 +237,101 @@ fn delete_member_docs(op: TransformOperation) -> TransformOperation {
 )]
 async fn update_member(
     State(pg_client): State<PgClient>,
+    State(policy): State<Policy>,
     State(webhook_emitter): State<WebhookEmitter>,
-    AuthState(auth_state): AuthState,
-    WorkspaceContext(workspace): WorkspaceContext,
+    access: WorkspaceAccess,
     Path(path_params): Path<MemberPathParams>,
     ValidateJson(request): ValidateJson<UpdateMember>,
 ) -> Result<(StatusCode, Json<Member>)> {
     tracing::debug!(target: TRACING_TARGET, "Updating workspace member role");
 
-    let mut conn = pg_client.get_connection().await?;
+    access.require(Permission::ManageRoles)?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManageRoles)
-        .await?;
+    let workspace = access.workspace();
+    let actor_id = access.actor().account_id;
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
 
     let member_account_id = resolve_member_account_id(&mut conn, &path_params.username).await?;
 
     // Prevent self-role-update
-    if auth_state.account_id == member_account_id {
+    if actor_id == member_account_id {
         return Err(ErrorKind::BadRequest
             .with_message("Cannot update your own role")
             .with_context("Ask another owner to update your role"));
     }
 
-    let Some(current_member) = conn
-        .find_workspace_member(workspace.id, member_account_id)
-        .await?
-    else {
+    let Some(current_member) = conn.find_workspace_member(scope, member_account_id).await? else {
         return Err(ErrorKind::NotFound.with_resource("workspace_member"));
     };
 
     // Owners cannot be demoted, they can only leave
-    if current_member.member_role == WorkspaceRole::Owner && request.role != WorkspaceRole::Owner {
+    let new_role = request.role.unwrap_or(current_member.member_role);
+    if current_member.member_role == WorkspaceRole::Owner && new_role != WorkspaceRole::Owner {
         return Err(ErrorKind::BadRequest
             .with_message("Cannot demote an owner")
             .with_context("Owners can only leave the workspace themselves"));
     }
 
-    let new_role = request.role;
-    conn.update_workspace_member(workspace.id, member_account_id, request.into_model())
+    // A custom role from another workspace is rejected by the foreign key
+    let updated = conn
+        .transaction(async |conn| {
+            conn.update_workspace_member(scope, member_account_id, request.into_model())
+                .await?;
+
+            let Some((updated_member, account)) = conn
+                .find_workspace_member_with_account(scope, member_account_id)
+                .await?
+            else {
+                return Ok(None);
+            };
+
+            // A role change gets its own event so access mirrors need not
+            // diff every member update.
+            let change = MemberRoleChanged {
+                previous_role: current_member.member_role,
+                new_role: updated_member.member_role,
+                previous_custom_role_id: current_member.custom_role_id.map(RoleId::from_uuid),
+                custom_role_id: updated_member.custom_role_id.map(RoleId::from_uuid),
+                source: MembershipSource::Api,
+            };
+            if change.is_unchanged() {
+                let data = serde_json::json!({
+                    "username": path_params.username,
+                    "previousRole": current_member.member_role.to_string(),
+                    "newRole": new_role.to_string(),
+                    "customRoleId": change.custom_role_id,
+                });
+                webhook_emitter
+                    .emit_member_updated(
+                        conn,
+                        workspace.id,
+                        member_account_id,
+                        Some(actor_id),
+                        Some(data),
+                    )
+                    .await?;
+            } else {
+                webhook_emitter
+                    .emit_member_role_changed(
+                        conn,
+                        workspace.id,
+                        member_account_id,
+                        Some(actor_id),
+                        &change,
+                    )
+                    .await?;
+            }
+
+            Ok::<_, PgError>(Some((updated_member, account)))
+        })
         .await?;
+    policy.invalidate(workspace.id, member_account_id).await;
 
-    let Some((updated_member, account)) = conn
-        .find_workspace_member_with_account(workspace.id, member_account_id)
-        .await?
-    else {
+    let Some((updated_member, account)) = updated else {
         return Err(ErrorKind::NotFound.with_resource("workspace_member"));
     };
 
-    // Emit webhook event (fire-and-forget)
-    let data = serde_json::json!({
-        "username": path_params.username,
-        "previousRole": current_member.member_role.to_string(),
-        "newRole": new_role.to_string(),
-    });
-    if let Err(err) = webhook_emitter
-        .emit_member_updated(
-            workspace.id,
-            member_account_id,
-            Some(auth_state.account_id),
-            Some(data),
-        )
-        .await
-    {
-        tracing::warn!(
-            target: TRACING_TARGET,
-            error = %err,
-            "Failed to emit member:updated webhook event"
-        );
-    }
-
     tracing::info!(
         target: TRACING_TARGET,
         new_role = ?updated_member.member_role,
@@ -333,7 +347,8 @@ async fn update_member(
 fn update_member_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Update member role")
         .description(
-            "Updates a workspace member's role. Cannot update your own role or demote owners.",
+            "Updates a workspace member's built-in role and custom role. Cannot update \
+             your own role or demote owners.",
         )
         .response::<200, Json<Member>>()
         .response::<400, Json<ErrorResponse>>()
@@ -356,6 +371,8 @@ fn update_member_docs(op: TransformOperation) -> TransformOperation {
 )]
 async fn leave_workspace(
     State(pg_client): State<PgClient>,
+    State(policy): State<Policy>,
+    State(webhook_emitter): State<WebhookEmitter>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
 ) -> Result<StatusCode> {
@@ -363,8 +380,8 @@ async fn leave_workspace(
 
     let mut conn = pg_client.get_connection().await?;
 
-    let Some(_member) = conn
-        .find_workspace_member(workspace.id, auth_state.account_id)
+    let Some((member, _, scope)) = conn
+        .find_workspace_member_with_custom_role(workspace.id, auth_state.account_id)
         .await?
     else {
         return Err(ErrorKind::NotFound
@@ -372,8 +389,26 @@ async fn leave_workspace(
             .with_message("You are not a member of this workspace"));
     };
 
-    conn.remove_workspace_member(workspace.id, auth_state.account_id)
-        .await?;
+    conn.transaction(async |conn| {
+        conn.remove_workspace_member(scope, auth_state.account_id)
+            .await?;
+
+        let data = serde_json::json!({
+            "role": member.member_role.to_string(),
+            "left": true,
+        });
+        webhook_emitter
+            .emit_member_deleted(
+                conn,
+                workspace.id,
+                auth_state.account_id,
+                Some(auth_state.account_id),
+                Some(data),
+            )
+            .await
+    })
+    .await?;
+    policy.invalidate(workspace.id, auth_state.account_id).await;
 
     tracing::warn!(target: TRACING_TARGET, "Member left workspace");
 
@@ -4,20 +4,33 @@
 //! [`Handler`]: axum::handler::Handler
 
 mod accounts;
+mod analytics;
+mod api_keys;
 mod authentication;
 mod connections;
 mod contexts;
 mod error;
+mod events;
 mod files;
+#[cfg(feature = "graphql")]
+mod graphql;
+mod inbound_webhooks;
 mod invites;
 mod members;
 mod monitors;
 mod notifications;
+mod operations;
 mod pipelines;
 mod policies;
 pub mod request;
 pub mod response;
+mod retention;
+mod roles;
 mod runs;
+mod scim;
+mod shares;
+mod sso;
+mod storage;
 mod tokens;
 mod utility;
 mod webhooks;
@@ -29,7 +42,13 @@ use aide::axum::ApiRouter;
 use axum::middleware::from_fn_with_state;
 use axum::response::{IntoResponse, Response};
 pub use error::{Error, ErrorKind, Result};
+#[cfg(feature = "grpc")]
+pub(crate) use files::{FileUploadContext, record_file_access, store_uploaded_file};
 pub use invites::{CreatedInvite, InviteOutcome, create_invite};
+#[cfg(feature = "grpc")]
+pub(crate) use operations::{authorize_all_kinds, view_permission};
+#[cfg(feature = "grpc")]
+pub(crate) use runs::{cancel_run, start_run};
 pub use utility::{BuiltinModule, CustomRoutes, RouterMapFn};
 
 use crate::middleware::{require_authentication, validate_token_middleware};
@@ -59,6 +78,9 @@ fn private_routes(
     if is_included(BuiltinModule::Tokens) {
         router = router.merge(tokens::routes());
     }
+    if is_included(BuiltinModule::ApiKeys) {
+        router = router.merge(api_keys::routes());
+    }
     if is_included(BuiltinModule::Workspaces) {
         router = router.merge(workspaces::routes());
     }
@@ -74,12 +96,21 @@ fn private_routes(
     if is_included(BuiltinModule::Members) {
         router = router.merge(members::routes());
     }
+    if is_included(BuiltinModule::Roles) {
+        router = router.merge(roles::routes());
+    }
     if is_included(BuiltinModule::Webhooks) {
         router = router.merge(webhooks::routes());
     }
+    if is_included(BuiltinModule::InboundWebhooks) {
+        router = router.merge(inbound_webhooks::routes());
+    }
     if is_included(BuiltinModule::Files) {
         router = router.merge(files::routes());
     }
+    if is_included(BuiltinModule::Shares) {
+        router = router.merge(shares::routes());
+    }
     if is_included(BuiltinModule::Pipelines) {
         router = router.merge(pipelines::routes());
     }
@@ -89,9 +120,31 @@ fn private_routes(
     if is_included(BuiltinModule::Policies) {
         router = router.merge(policies::routes());
     }
+    if is_included(BuiltinModule::Retention) {
+        router = router.merge(retention::routes());
+    }
     if is_included(BuiltinModule::Notifications) {
         router = router.merge(notifications::routes());
     }
+    if is_included(BuiltinModule::Events) {
+        router = router.merge(events::routes());
+    }
+    if is_included(BuiltinModule::Operations) {
+        router = router.merge(operations::routes());
+    }
+    if is_included(BuiltinModule::Scim) {
+        router = router.merge(scim::routes());
+    }
+    if is_included(BuiltinModule::Analytics) {
+        router = router.merge(analytics::routes());
+    }
+    if is_included(BuiltinModule::Storage) {
+        router = router.merge(storage::routes());
+    }
+    #[cfg(feature = "graphql")]
+    if is_included(BuiltinModule::GraphQl) {
+        router = router.merge(graphql::routes());
+    }
 
     if let Some(additional) = additional_routes {
         router = router.merge(additional);
@@ -112,6 +165,18 @@ fn public_routes(
     if !disable_authentication && !excluded.contains(&BuiltinModule::Authentication) {
         router = router.merge(authentication::routes());
     }
+    if !disable_authentication && !excluded.contains(&BuiltinModule::Sso) {
+        router = router.merge(sso::routes());
+    }
+    if !excluded.contains(&BuiltinModule::Operations) {
+        router = router.merge(operations::public_routes());
+    }
+    if !excluded.contains(&BuiltinModule::Shares) {
+        router = router.merge(shares::public_routes());
+    }
+    if !excluded.contains(&BuiltinModule::InboundWebhooks) {
+        router = router.merge(inbound_webhooks::public_routes());
+    }
 
     router = router.merge(monitors::routes());
 
@@ -155,16 +220,24 @@ pub fn routes(mut routes: CustomRoutes, state: ServiceState) -> ApiRouter<Servic
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use aide::axum::ApiRouter;
     use axum::Router;
     use axum_test::TestServer;
+    use jiff::Timestamp;
+    use nvisy_core::clock::ManualClock;
+    use nvisy_core::id::SequentialIds;
     use nvisy_nats::NatsConfig;
     use nvisy_postgres::PgConfig;
     use nvisy_webhook::reqwest::ReqwestClient;
 
     use crate::handler::{CustomRoutes, routes};
     use crate::service::{
-        CryptoConfig, EngineConfig, HealthConfig, ServiceState, SessionKeysConfig,
+        AuditConfig, CredentialRotationConfig, CryptoConfig, CryptoPolicy, EngineConfig,
+        GarbageCollectionConfig, HealthConfig, KeyMigrationConfig, OidcConfig, OperationConfig,
+        PolicyConfig, PrivacyConfig, ResidencyConfig, RetentionConfig, SecretsConfig, ServiceState,
+        SessionKeysConfig, StorageCostConfig,
     };
 
     /// Builds the service sub-configs from the environment for integration tests.
@@ -187,6 +260,8 @@ mod test {
 
         let crypto = CryptoConfig {
             key_path: var("ENCRYPTION_KEY_FILEPATH")?.into(),
+            policy: CryptoPolicy::Standard,
+            ..Default::default()
         };
 
         Ok((postgres, nats, session, crypto))
@@ -198,14 +273,40 @@ mod test {
     ) -> anyhow::Result<TestServer> {
         let (postgres, nats, session, crypto) = configs_from_env()?;
         let webhook_service = ReqwestClient::default().into_service();
+        // A stopped clock and sequential IDs keep responses within a test
+        // reproducible. The clock starts at the real time because token
+        // expiry and database filters compare against it, and each server
+        // starts its ID counter at a random offset so tests sharing one
+        // database do not collide on primary keys.
+        let clock = Arc::new(ManualClock::new(Timestamp::now()));
+        let ids = Arc::new(SequentialIds::starting_at(
+            clock.clone(),
+            rand::random::<u64>() >> 8,
+        ));
         let state = ServiceState::from_config(
             postgres,
             nats,
+            #[cfg(feature = "redis")]
+            nvisy_redis::RedisConfig::new(std::env::var("REDIS_URL")?),
             session,
+            AuditConfig::default(),
             crypto,
             EngineConfig::default(),
             HealthConfig::default(),
+            OidcConfig::default(),
+            OperationConfig::default(),
+            PolicyConfig::default(),
+            PrivacyConfig::default(),
+            ResidencyConfig::default(),
+            RetentionConfig::default(),
+            KeyMigrationConfig::default(),
+            CredentialRotationConfig::default(),
+            GarbageCollectionConfig::default(),
+            StorageCostConfig::default(),
+            SecretsConfig::default(),
             webhook_service,
+            clock,
+            ids,
         )
         .await?;
         let router = router(state.clone());
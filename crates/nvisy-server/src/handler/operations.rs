@@ -0,0 +1,324 @@
+//! Long-running operation handlers.
+//!
+//! Endpoints that accept work asynchronously return an operation. Clients
+//! poll it here until it reaches a terminal status (`Retry-After` suggests
+//! the interval) or subscribe to the `operation:*` webhooks instead.
+//!
+//! An operation that produced a file links to it with a signed, expiring
+//! URL. The download endpoint is public: the signature is the credential, so
+//! links can be handed to tools that cannot authenticate.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use nvisy_postgres::model::WorkspaceOperation;
+use nvisy_postgres::query::{AdminScope, TenantScope, WorkspaceOperationRepository};
+use nvisy_postgres::types::{OperationId, OperationKind};
+use nvisy_postgres::{PgClient, PgConn};
+use strum::IntoEnumIterator;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::extract::{AuthProvider, AuthState, Json, Path, Permission, Query, WorkspaceContext};
+use crate::handler::request::{
+    ArtifactDownloadQuery, CursorPagination, OperationPathParams, OperationsQuery,
+};
+use crate::handler::response::{ErrorResponse, Operation, OperationsPage};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{OPERATION_POLL_INTERVAL, OperationRunner, ServiceState};
+
+/// Tracing target for operation endpoints.
+const TRACING_TARGET: &str = "nvisy_server::handler::operations";
+
+/// Gets an operation by id.
+///
+/// Operations are addressed by id alone; access requires the permission
+/// needed to view the operation's kind of work in its workspace.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        operation_id = %path_params.operation_id,
+    )
+)]
+async fn get_operation(
+    State(pg_client): State<PgClient>,
+    State(operations): State<OperationRunner>,
+    AuthState(auth_state): AuthState,
+    Path(path_params): Path<OperationPathParams>,
+) -> Result<Response> {
+    tracing::debug!(target: TRACING_TARGET, "Reading operation");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let admin =
+        AdminScope::open(&mut conn, "resolve operation workspace for authorization").await?;
+    let operation = conn
+        .find_workspace_operation_by_id(&admin, path_params.operation_id.as_uuid())
+        .await?
+        .ok_or_else(|| Error::not_found("operation"))?;
+
+    auth_state
+        .authorize_workspace(
+            &mut conn,
+            operation.workspace_id,
+            view_permission(operation.kind),
+        )
+        .await?;
+
+    Ok(operation_response(&operations, operation))
+}
+
+fn get_operation_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get operation")
+        .description(
+            "Returns an operation's status and progress, with its result once it \
+             succeeds or its error once it fails. While the operation is pending or \
+             running the response carries a Retry-After header. A succeeded \
+             operation that produced a file links to it with a signed URL valid for \
+             a short time; fetch the operation again for a fresh link.",
+        )
+        .response::<200, Json<Operation>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Lists a workspace's operations, newest first.
+///
+/// Requires the view permission of every operation kind.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn list_operations(
+    State(pg_client): State<PgClient>,
+    State(operations): State<OperationRunner>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Query(pagination): Query<CursorPagination>,
+    Query(query): Query<OperationsQuery>,
+) -> Result<(StatusCode, Json<OperationsPage>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing operations");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = authorize_all_kinds(&auth_state, &mut conn, workspace.id).await?;
+
+    let page = conn
+        .cursor_list_workspace_operations(scope, pagination.into(), query.status)
+        .await?;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        operation_count = page.items.len(),
+        "Operations listed"
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(OperationsPage::from_cursor_page(page, |operation| {
+            let download = operations.download_link(&operation);
+            Operation::from_model(operation, download)
+        })),
+    ))
+}
+
+fn list_operations_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List operations")
+        .description(
+            "Returns the workspace's long-running operations, most recent first, with \
+             an optional status filter.",
+        )
+        .response::<200, Json<OperationsPage>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Downloads an operation's artifact through a signed link.
+///
+/// Requires no authentication: the link's signature covers the operation and
+/// the link's expiry, and the artifact is only served while the operation is
+/// retained.
+#[tracing::instrument(
+    skip_all,
+    fields(operation_id = %path_params.operation_id)
+)]
+async fn download_artifact(
+    State(pg_client): State<PgClient>,
+    State(operations): State<OperationRunner>,
+    Path(path_params): Path<OperationPathParams>,
+    Query(query): Query<ArtifactDownloadQuery>,
+) -> Result<(StatusCode, HeaderMap, Body)> {
+    tracing::debug!(target: TRACING_TARGET, "Downloading operation artifact");
+
+    let operation_id = path_params.operation_id.as_uuid();
+    if !operations.verify_download_link(operation_id, query.expires, &query.signature) {
+        return Err(ErrorKind::Forbidden.with_message("Download link is invalid or expired"));
+    }
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let admin =
+        AdminScope::open(&mut conn, "serve operation artifact through a signed link").await?;
+    let operation = conn
+        .find_workspace_operation_by_id(&admin, operation_id)
+        .await?
+        .filter(|operation| operation.has_artifact() && !operation.is_expired())
+        .ok_or_else(|| Error::not_found("operation_artifact"))?;
+
+    let scope = admin.tenant(operation.workspace_id);
+    let reader = operations.open_artifact(scope, &operation).await?;
+
+    // The name is chosen by the job, but may echo user input (a pipeline or
+    // file name), so keep it from breaking out of the quoted header value.
+    let safe_name: String = operation
+        .artifact_name
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect();
+    let disposition = format!("attachment; filename=\"{safe_name}\"")
+        .parse()
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+    let content_type = operation
+        .artifact_content_type
+        .as_deref()
+        .and_then(|content_type| content_type.parse().ok())
+        .unwrap_or_else(|| HeaderValue::from_static("application/octet-stream"));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_DISPOSITION, disposition);
+    headers.insert(header::CONTENT_TYPE, content_type);
+    if let Some(size) = operation.artifact_size {
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+    }
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        size = operation.artifact_size,
+        "Streaming operation artifact"
+    );
+
+    let body = Body::from_stream(ReaderStream::new(reader));
+    Ok((StatusCode::OK, headers, body))
+}
+
+fn download_artifact_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Download operation artifact")
+        .description(
+            "Downloads the file produced by an operation. The URL comes from the \
+             operation's `artifact.url` and is signed and short-lived; it needs no \
+             other credentials.",
+        )
+        .response::<200, ()>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Renders an operation, asking the client to come back later while it is
+/// unfinished.
+fn operation_response(operations: &OperationRunner, operation: WorkspaceOperation) -> Response {
+    let in_progress = operation.is_in_progress();
+    let download = operations.download_link(&operation);
+    let body = Json(Operation::from_model(operation, download));
+
+    if in_progress {
+        (StatusCode::OK, [(header::RETRY_AFTER, retry_after())], body).into_response()
+    } else {
+        (StatusCode::OK, body).into_response()
+    }
+}
+
+/// Renders a just-accepted operation as `202 Accepted`, pointing the client
+/// at the endpoint to poll.
+pub(super) fn accepted_operation_response(operation: WorkspaceOperation) -> Response {
+    let location = format!("/operations/{}/", OperationId::from_uuid(operation.id));
+    // Just accepted, so there is no result or artifact to link to yet.
+    let body = Json(Operation::from_model(operation, None));
+
+    (
+        StatusCode::ACCEPTED,
+        [
+            (header::LOCATION, location),
+            (header::RETRY_AFTER, retry_after()),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn retry_after() -> String {
+    OPERATION_POLL_INTERVAL.as_secs().to_string()
+}
+
+/// Returns the permission needed to view an operation of the given kind.
+pub(crate) fn view_permission(kind: OperationKind) -> Permission {
+    match kind {
+        OperationKind::PipelineDetect => Permission::ViewPipelines,
+    }
+}
+
+/// Authorizes viewing operations of every kind in a workspace and returns
+/// the scope for its operations.
+pub(crate) async fn authorize_all_kinds(
+    auth_state: &impl AuthProvider,
+    conn: &mut PgConn,
+    workspace_id: Uuid,
+) -> Result<TenantScope> {
+    let mut scope = None;
+    for kind in OperationKind::iter() {
+        scope = auth_state
+            .check_workspace_permission(conn, workspace_id, view_permission(kind))
+            .await?
+            .into_scope()?;
+    }
+
+    match scope {
+        Some(scope) => Ok(scope),
+        None => {
+            let admin = AdminScope::open(conn, "global administrator workspace access").await?;
+            Ok(admin.tenant(workspace_id))
+        }
+    }
+}
+
+/// Returns a [`Router`] with all operation routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/operations/{operationId}/",
+            get_with(get_operation, get_operation_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/operations/",
+            get_with(list_operations, list_operations_docs),
+        )
+        .with_path_items(|item| item.tag("Operations"))
+}
+
+/// Returns a [`Router`] with the public operation routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn public_routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/operations/{operationId}/artifact/",
+            get_with(download_artifact, download_artifact_docs),
+        )
+        .with_path_items(|item| item.tag("Operations"))
+}
@@ -10,7 +10,8 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use nvisy_postgres::model::WorkspacePipeline;
 use nvisy_postgres::query::{
-    PipelineReferenceRepository, WorkspacePipelineArtifactRepository, WorkspacePipelineRepository,
+    PipelineReferenceRepository, TenantScope, WorkspacePipelineArtifactRepository,
+    WorkspacePipelineRepository,
 };
 use nvisy_postgres::types::{Slug, Username};
 use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgConnection, PgError, PgResult};
@@ -51,28 +52,27 @@ async fn create_pipeline(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::CreatePipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::CreatePipelines)
         .await?;
 
     let (new_pipeline, references) = request
         .into_parts(workspace.id, auth_state.account_id)
         .map_err(serialize_error)?;
 
-    let (policy_ids, context_ids) =
-        resolve_references(&mut conn, workspace.id, &references).await?;
+    let (policy_ids, context_ids) = resolve_references(&mut conn, scope, &references).await?;
 
     let pipeline = conn
         .transaction(async |conn| {
             let pipeline = conn.create_workspace_pipeline(new_pipeline).await?;
-            replace_references(conn, &pipeline, &policy_ids, &context_ids).await?;
+            replace_references(conn, scope, &pipeline, &policy_ids, &context_ids).await?;
             Ok::<WorkspacePipeline, PgError>(pipeline)
         })
         .await?;
 
     // Re-read by slug to pick up the creator's handle via the join.
     let (pipeline, creator_username) =
-        find_pipeline(&mut conn, workspace.id, pipeline.slug.as_str()).await?;
+        find_pipeline(&mut conn, scope, pipeline.slug.as_str()).await?;
 
     // The references were just written from the request, so build the response
     // from its slugs directly instead of reading the join tables back.
@@ -125,13 +125,13 @@ async fn list_pipelines(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
         .await?;
 
     let page = conn
         .cursor_list_workspace_pipelines(
-            workspace.id,
+            scope,
             pagination.into(),
             filter.status,
             filter.search.as_deref(),
@@ -180,14 +180,16 @@ async fn get_pipeline(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
         .await?;
 
     let (pipeline, creator_username) =
-        find_pipeline(&mut conn, workspace.id, &path_params.pipeline_slug).await?;
+        find_pipeline(&mut conn, scope, &path_params.pipeline_slug).await?;
 
-    let artifacts = conn.list_workspace_pipeline_artifacts(pipeline.id).await?;
+    let artifacts = conn
+        .list_workspace_pipeline_artifacts(scope, pipeline.id)
+        .await?;
     let policy_slugs = conn.list_pipeline_policy_slugs(pipeline.id).await?;
     let context_slugs = conn.list_pipeline_context_slugs(pipeline.id).await?;
 
@@ -238,13 +240,13 @@ async fn update_pipeline(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::UpdatePipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::UpdatePipelines)
         .await?;
 
     // Confirm the pipeline exists in this workspace before mutating.
     let (existing, creator_username) =
-        find_pipeline(&mut conn, workspace.id, &path_params.pipeline_slug).await?;
+        find_pipeline(&mut conn, scope, &path_params.pipeline_slug).await?;
 
     let (update_data, references) = request.into_parts().map_err(serialize_error)?;
     let pipeline_id = existing.id;
@@ -252,7 +254,7 @@ async fn update_pipeline(
     // When a definition is supplied, resolve its slugs to ids up front so an
     // unknown reference rejects with 404 before any write.
     let resolved = match &references {
-        Some(references) => Some(resolve_references(&mut conn, workspace.id, references).await?),
+        Some(references) => Some(resolve_references(&mut conn, scope, references).await?),
         None => None,
     };
 
@@ -263,7 +265,7 @@ async fn update_pipeline(
                 .await?;
             // Only touch the join tables when the request supplied a definition.
             if let Some((policy_ids, context_ids)) = &resolved {
-                replace_references(conn, &pipeline, policy_ids, context_ids).await?;
+                replace_references(conn, scope, &pipeline, policy_ids, context_ids).await?;
             }
             Ok::<WorkspacePipeline, PgError>(pipeline)
         })
@@ -320,12 +322,12 @@ async fn delete_pipeline(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::DeletePipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::DeletePipelines)
         .await?;
 
     // Confirm the pipeline exists in this workspace before deleting.
-    let (existing, _) = find_pipeline(&mut conn, workspace.id, &path_params.pipeline_slug).await?;
+    let (existing, _) = find_pipeline(&mut conn, scope, &path_params.pipeline_slug).await?;
 
     conn.delete_workspace_pipeline(existing.id).await?;
 
@@ -347,10 +349,10 @@ fn delete_pipeline_docs(op: TransformOperation) -> TransformOperation {
 /// returns a NotFound error.
 async fn find_pipeline(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     pipeline_slug: &str,
 ) -> Result<(WorkspacePipeline, Username)> {
-    conn.find_pipeline_in_workspace_by_slug(workspace_id, pipeline_slug)
+    conn.find_pipeline_in_workspace_by_slug(scope, pipeline_slug)
         .await?
         .ok_or_else(|| Error::not_found("pipeline"))
 }
@@ -361,13 +363,14 @@ async fn find_pipeline(
 /// its references stay consistent.
 async fn replace_references(
     conn: &mut PgConnection,
+    scope: TenantScope,
     pipeline: &WorkspacePipeline,
     policy_ids: &[Uuid],
     context_ids: &[Uuid],
 ) -> PgResult<()> {
-    conn.replace_workspace_pipeline_policies(pipeline.workspace_id, pipeline.id, policy_ids)
+    conn.replace_workspace_pipeline_policies(scope, pipeline.id, policy_ids)
         .await?;
-    conn.replace_workspace_pipeline_contexts(pipeline.workspace_id, pipeline.id, context_ids)
+    conn.replace_workspace_pipeline_contexts(scope, pipeline.id, context_ids)
         .await?;
     Ok(())
 }
@@ -376,15 +379,15 @@ async fn replace_references(
 /// rejecting the whole request with a 404 if any slug is unknown.
 async fn resolve_references(
     conn: &mut PgConnection,
-    workspace_id: Uuid,
+    scope: TenantScope,
     references: &PipelineReferences,
 ) -> Result<(Vec<Uuid>, Vec<Uuid>)> {
     let policy_ids = conn
-        .resolve_policy_slugs(workspace_id, &references.policy_slugs)
+        .resolve_policy_slugs(scope, &references.policy_slugs)
         .await?
         .ok_or_else(|| Error::not_found("policy"))?;
     let context_ids = conn
-        .resolve_context_slugs(workspace_id, &references.context_slugs)
+        .resolve_context_slugs(scope, &references.context_slugs)
         .await?
         .ok_or_else(|| Error::not_found("context"))?;
     Ok((policy_ids, context_ids))
@@ -11,10 +11,9 @@ use aide::transform::TransformOperation;
 use axum::extract::State;
 use axum::http::StatusCode;
 use nvisy_postgres::model::{NewWorkspacePolicy, UpdateWorkspacePolicy, WorkspacePolicy};
-use nvisy_postgres::query::WorkspacePolicyRepository;
+use nvisy_postgres::query::{TenantScope, WorkspacePolicyRepository};
 use nvisy_postgres::types::Username;
 use nvisy_postgres::{PgClient, PgConn};
-use uuid::Uuid;
 
 use crate::extract::{
     AuthProvider, AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceContext,
@@ -50,8 +49,8 @@ async fn create_policy(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManagePolicies)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManagePolicies)
         .await?;
 
     let definition = &request.definition;
@@ -77,8 +76,7 @@ async fn create_policy(
 
     tracing::info!(target: TRACING_TARGET, policy_slug = %policy.slug, "Policy created");
 
-    let (policy, creator_username) =
-        find_policy(&mut conn, workspace.id, policy.slug.as_str()).await?;
+    let (policy, creator_username) = find_policy(&mut conn, scope, policy.slug.as_str()).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -119,12 +117,12 @@ async fn list_policies(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPolicies)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPolicies)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_policies(workspace.id, pagination.into())
+        .cursor_list_workspace_policies(scope, pagination.into())
         .await?;
 
     tracing::debug!(
@@ -168,12 +166,12 @@ async fn read_policy(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPolicies)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPolicies)
         .await?;
 
     let (policy, creator_username) =
-        find_policy(&mut conn, workspace.id, &path_params.policy_slug).await?;
+        find_policy(&mut conn, scope, &path_params.policy_slug).await?;
 
     tracing::debug!(target: TRACING_TARGET, "Workspace policy read");
 
@@ -221,12 +219,12 @@ async fn update_policy(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManagePolicies)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManagePolicies)
         .await?;
 
     // Confirm the policy exists in this workspace before mutating.
-    let (existing, _) = find_policy(&mut conn, workspace.id, &path_params.policy_slug).await?;
+    let (existing, _) = find_policy(&mut conn, scope, &path_params.policy_slug).await?;
 
     let (version, definition) = match &request.definition {
         Some(definition) => {
@@ -247,7 +245,7 @@ async fn update_policy(
     conn.update_workspace_policy(existing.id, updates).await?;
 
     let (policy, creator_username) =
-        find_policy(&mut conn, workspace.id, &path_params.policy_slug).await?;
+        find_policy(&mut conn, scope, &path_params.policy_slug).await?;
 
     tracing::info!(target: TRACING_TARGET, "Policy updated");
 
@@ -291,12 +289,12 @@ async fn delete_policy(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ManagePolicies)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManagePolicies)
         .await?;
 
     // Confirm the policy exists in this workspace before deleting.
-    let (existing, _) = find_policy(&mut conn, workspace.id, &path_params.policy_slug).await?;
+    let (existing, _) = find_policy(&mut conn, scope, &path_params.policy_slug).await?;
 
     conn.delete_workspace_policy(existing.id).await?;
 
@@ -318,10 +316,10 @@ fn delete_policy_docs(op: TransformOperation) -> TransformOperation {
 /// returns a NotFound error.
 async fn find_policy(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     policy_slug: &str,
 ) -> Result<(WorkspacePolicy, Username)> {
-    conn.find_policy_in_workspace_by_slug(workspace_id, policy_slug)
+    conn.find_policy_in_workspace_by_slug(scope, policy_slug)
         .await?
         .ok_or_else(|| Error::not_found("policy"))
 }
@@ -0,0 +1,90 @@
+//! Administrator analytics request types.
+
+use jiff::Timestamp;
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use nvisy_postgres::model::NewStoragePrice;
+use nvisy_postgres::types::{DataRegion, StorageClass};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Query parameters for a month's storage cost report.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCostQuery {
+    /// Month to report, as `YYYY-MM` (default: the current month, in UTC).
+    #[validate(custom(function = "validate_month"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month: Option<String>,
+    /// Only report this workspace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+}
+
+impl StorageCostQuery {
+    /// Returns the first day of the reported month.
+    pub fn period_start(&self) -> Date {
+        self.month
+            .as_deref()
+            .and_then(parse_month)
+            .unwrap_or_else(|| Timestamp::now().to_zoned(TimeZone::UTC).date())
+            .first_of_month()
+    }
+}
+
+/// Request payload for recording a storage price.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStoragePrice {
+    /// Region whose backends the price applies to.
+    pub data_region: DataRegion,
+    /// Storage class the price applies to.
+    pub storage_class: StorageClass,
+    /// ISO 4217 currency code (e.g. `USD`).
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: String,
+    /// Price of one GiB stored for a month, in millionths of the currency.
+    #[validate(range(min = 0))]
+    pub price_micros_per_gib_month: i64,
+    /// First day the price applies.
+    pub effective_from: Date,
+}
+
+impl CreateStoragePrice {
+    /// Converts the request into a new price record.
+    pub fn into_model(self) -> NewStoragePrice {
+        NewStoragePrice {
+            data_region: self.data_region,
+            storage_class: self.storage_class,
+            currency: self.currency,
+            price_micros_per_gib_month: self.price_micros_per_gib_month,
+            effective_from: self.effective_from.into(),
+        }
+    }
+}
+
+/// Parses a `YYYY-MM` month into its first day.
+fn parse_month(month: &str) -> Option<Date> {
+    let (year, month) = month.split_once('-')?;
+    if year.len() != 4 || month.len() != 2 {
+        return None;
+    }
+    Date::new(year.parse().ok()?, month.parse().ok()?, 1).ok()
+}
+
+fn validate_month(month: &str) -> Result<(), ValidationError> {
+    if parse_month(month).is_none() {
+        return Err(ValidationError::new("month_format"));
+    }
+    Ok(())
+}
+
+fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(ValidationError::new("currency_format"));
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Request structures for account API key operations.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use nvisy_postgres::model::NewAccountApiKey;
+use nvisy_postgres::types::ApiKeyScope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::TokenExpiration;
+use crate::handler::{ErrorKind, Result};
+use crate::service::{DEFAULT_ROTATION_GRACE, IssuedApiKey};
+
+/// Longest grace window a rotation may request (7 days).
+const MAX_ROTATION_GRACE_HOURS: u32 = 7 * 24;
+
+/// Request to create a new account API key.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKey {
+    /// Human-readable name for the key (1-100 characters).
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+
+    /// Scopes granted to the key.
+    #[validate(length(min = 1, max = 3))]
+    pub scopes: Vec<ApiKeyScope>,
+
+    /// When the key expires.
+    #[serde(default)]
+    pub expires_in: TokenExpiration,
+}
+
+impl CreateApiKey {
+    /// Converts this request into a [`NewAccountApiKey`] model.
+    pub fn into_model(self, account_id: Uuid, issued: &IssuedApiKey) -> Result<NewAccountApiKey> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ErrorKind::BadRequest
+                .with_resource("api_key")
+                .with_message("Key name cannot be empty or whitespace only"));
+        }
+
+        let scopes: BTreeSet<_> = self.scopes.into_iter().collect();
+
+        Ok(NewAccountApiKey {
+            account_id,
+            name,
+            scopes: scopes.into_iter().map(Some).collect(),
+            key_prefix: issued.key_prefix.clone(),
+            key_hash: issued.key_hash.to_vec(),
+            expired_at: self.expires_in.to_expiry_timestamp().map(Into::into),
+        })
+    }
+}
+
+/// Request to rotate an account API key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateApiKey {
+    /// Hours the old key keeps working alongside the new one (0-168).
+    ///
+    /// Defaults to 24 hours. Zero retires the old key immediately.
+    #[validate(range(max = MAX_ROTATION_GRACE_HOURS))]
+    pub grace_period_hours: Option<u32>,
+}
+
+impl RotateApiKey {
+    /// Returns the grace window requested for the old key.
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period_hours
+            .map(|hours| Duration::from_secs(u64::from(hours) * 60 * 60))
+            .unwrap_or(DEFAULT_ROTATION_GRACE)
+    }
+}
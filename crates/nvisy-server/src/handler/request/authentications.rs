@@ -3,7 +3,7 @@
 use nvisy_postgres::types::Username;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 /// Request payload for login.
 #[must_use]
@@ -47,6 +47,47 @@ pub struct Signup {
     pub remember_me: bool,
 }
 
+/// Query parameters for starting a single sign-on login.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SsoLogin {
+    /// Path within the application to return to after signing in.
+    #[validate(length(max = 2048))]
+    #[validate(custom(function = "validate_relative_path"))]
+    pub redirect_to: Option<String>,
+    /// Whether to remember this device for extended session. Defaults to false.
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+/// Query parameters the identity provider sends to the callback.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SsoCallback {
+    /// Authorization code, present when the user signed in.
+    #[validate(length(min = 1, max = 2048))]
+    pub code: Option<String>,
+    /// Opaque value issued when the login started.
+    #[validate(length(min = 1, max = 256))]
+    pub state: String,
+    /// Error code, present when the provider refused the login.
+    pub error: Option<String>,
+    /// Human-readable description of `error`.
+    pub error_description: Option<String>,
+}
+
+/// Accepts only same-origin paths, so a login cannot be turned into an open
+/// redirect.
+fn validate_relative_path(path: &str) -> Result<(), ValidationError> {
+    let same_origin = path.starts_with('/') && !path.starts_with("//") && !path.contains('\\');
+    if !same_origin {
+        return Err(ValidationError::new("relative_path"));
+    }
+    Ok(())
+}
+
 // TODO: Implement password reset
 
 /// Request payload for password reset initiation.
@@ -73,3 +114,21 @@ pub struct ConfirmPasswordReset {
     #[validate(length(min = 8, max = 128))]
     pub new_password: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sso_login_rejects_foreign_redirects() {
+        let login = |redirect_to: &str| SsoLogin {
+            redirect_to: Some(redirect_to.to_owned()),
+            remember_me: false,
+        };
+
+        assert!(login("/workspaces/acme").validate().is_ok());
+        assert!(login("https://evil.example").validate().is_err());
+        assert!(login("//evil.example").validate().is_err());
+        assert!(login("/\\evil.example").validate().is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! Connection request types.
 
-use nvisy_postgres::types::ConnectionId;
+use nvisy_postgres::types::{ConnectionCredentials, ConnectionId};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -31,7 +31,7 @@ pub struct CreateConnection {
     pub provider: String,
     /// Connection data to be encrypted (credentials + context).
     /// The structure depends on the provider type.
-    pub data: serde_json::Value,
+    pub data: ConnectionCredentials,
 }
 
 /// Request payload for updating an existing workspace connection.
@@ -43,7 +43,7 @@ pub struct UpdateConnection {
     pub name: Option<String>,
     /// Connection data to be encrypted (credentials + context).
     /// If provided, replaces the existing encrypted data.
-    pub data: Option<serde_json::Value>,
+    pub data: Option<ConnectionCredentials>,
 }
 
 /// Query parameters for listing connections.
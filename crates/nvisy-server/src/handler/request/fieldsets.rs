@@ -0,0 +1,106 @@
+//! Sparse fieldset request types for API endpoints.
+//!
+//! List endpoints accept `?fields=id,displayName` to return only the named
+//! fields of each item. The fields a resource allows are the properties of
+//! its response schema, so the allow-list always matches the documented
+//! shape.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use schemars::{JsonSchema, SchemaGenerator};
+use serde::{Deserialize, Serialize};
+
+use crate::handler::response::FieldSet;
+use crate::handler::{ErrorKind, Result};
+
+/// Sparse fieldset query parameters.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSelection {
+    /// Comma-separated fields to include in each item (e.g.
+    /// `id,displayName`). Omit to return every field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
+}
+
+impl FieldSelection {
+    /// Validates the selection against the fields of `T`.
+    ///
+    /// Returns `None` when every field is to be returned.
+    pub fn resolve<T: JsonSchema>(&self) -> Result<Option<Arc<FieldSet>>> {
+        let Some(fields) = self.fields.as_deref() else {
+            return Ok(None);
+        };
+
+        let requested: BTreeSet<&str> = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .collect();
+        if requested.is_empty() {
+            return Ok(None);
+        }
+
+        let allowed = allowed_fields::<T>();
+        if let Some(unknown) = requested.iter().find(|field| !allowed.contains(**field)) {
+            let allowed: Vec<_> = allowed.into_iter().collect();
+            return Err(ErrorKind::BadRequest
+                .with_message(format!("Unknown field '{unknown}' in `fields`"))
+                .with_suggestion(format!("Select from: {}", allowed.join(", "))));
+        }
+
+        Ok(Some(Arc::new(FieldSet::new(requested))))
+    }
+}
+
+/// Returns the top-level properties of the schema of `T`.
+fn allowed_fields<T: JsonSchema>() -> BTreeSet<String> {
+    let schema = SchemaGenerator::default().into_root_schema_for::<T>();
+    schema
+        .get("properties")
+        .and_then(|properties| properties.as_object())
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct Item {
+        id: u32,
+        display_name: String,
+    }
+
+    fn selection(fields: &str) -> FieldSelection {
+        FieldSelection {
+            fields: Some(fields.to_owned()),
+        }
+    }
+
+    #[test]
+    fn resolves_known_fields() {
+        let fields = selection("id, displayName,").resolve::<Item>().unwrap();
+        let fields = fields.unwrap();
+        assert!(fields.contains("id"));
+        assert!(fields.contains("displayName"));
+
+        assert!(
+            FieldSelection::default()
+                .resolve::<Item>()
+                .unwrap()
+                .is_none()
+        );
+        assert!(selection(" , ").resolve::<Item>().unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let err = selection("id,secret").resolve::<Item>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadRequest);
+    }
+}
@@ -1,9 +1,13 @@
 //! File request types.
 
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use jiff::{Span, Timestamp};
 use nvisy_postgres::model::UpdateWorkspaceFile as UpdateFileModel;
-use nvisy_postgres::types::{FileFilter, FileFormat};
+use nvisy_postgres::types::{DataSensitivity, FileFilter, FileFormat};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::Validate;
 
 /// Request to update file metadata.
@@ -31,6 +35,45 @@ impl UpdateFile {
     }
 }
 
+/// Request to supply the password of a protected document without storing
+/// it.
+///
+/// The password is held in memory only, encrypted under the workspace key,
+/// and used by runs over the file until it expires.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplyFilePassword {
+    /// Password that opens the document.
+    #[validate(length(min = 1, max = 1024))]
+    pub password: String,
+}
+
+/// Request to store the password of a protected document.
+///
+/// The password is encrypted under the workspace key and used by every run
+/// over the file until it is removed.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFilePassword {
+    /// Password that opens the document.
+    #[validate(length(min = 1, max = 1024))]
+    pub password: String,
+}
+
+/// Query parameters for uploading files.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadFiles {
+    /// Sensitivity of the uploaded files; defaults to `medium`.
+    ///
+    /// `high` and `critical` files are encrypted under their own data key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<DataSensitivity>,
+}
+
 /// Query parameters for listing files.
 #[must_use]
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -53,3 +96,56 @@ impl ListFiles {
         }
     }
 }
+
+/// Default number of days access statistics cover.
+const DEFAULT_ACCESS_WINDOW_DAYS: u32 = 30;
+
+/// Largest number of days access statistics may cover.
+const MAX_ACCESS_WINDOW_DAYS: u32 = 365;
+
+/// Returns the first day of a window of `days` days ending today (UTC).
+pub(crate) fn access_window_start(days: Option<u32>) -> Date {
+    let days = days
+        .unwrap_or(DEFAULT_ACCESS_WINDOW_DAYS)
+        .clamp(1, MAX_ACCESS_WINDOW_DAYS);
+    let today = Timestamp::now().to_zoned(TimeZone::UTC).date();
+    today.saturating_sub(Span::new().days(i64::from(days - 1)))
+}
+
+/// Query parameters for opening or downloading a file.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenFile {
+    /// Whether the file was opened from search results, which counts
+    /// towards its search opens.
+    #[serde(default)]
+    pub from_search: bool,
+}
+
+/// Query parameters for a file's access statistics.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessWindow {
+    /// Number of days to cover, ending today (1-365, default: 30).
+    #[validate(range(min = 1, max = 365))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+}
+
+impl FileAccessWindow {
+    /// Returns the first day covered.
+    pub fn since(&self) -> Date {
+        access_window_start(self.days)
+    }
+}
+
+/// Query parameters for comparing two versions of a file.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareFiles {
+    /// The older version to compare the file against.
+    pub base_file_id: Uuid,
+}
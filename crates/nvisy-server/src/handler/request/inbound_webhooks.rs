@@ -0,0 +1,51 @@
+//! Workspace inbound webhook request types.
+
+use nvisy_postgres::model::NewWorkspaceInboundWebhook;
+use nvisy_postgres::types::InboundWebhookProvider;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Request payload for creating a new workspace inbound webhook.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInboundWebhook {
+    /// Human-readable name for the inbound webhook (1-128 characters).
+    #[validate(length(min = 1, max = 128))]
+    pub display_name: String,
+    /// Service sending the requests, deciding how they are verified.
+    pub provider: InboundWebhookProvider,
+    /// Secret to verify requests with (16-256 characters).
+    ///
+    /// Required for `docusign`, whose HMAC keys are generated in DocuSign;
+    /// for other providers, omit it to have one generated.
+    #[validate(length(min = 16, max = 256))]
+    pub secret: Option<String>,
+}
+
+impl CreateInboundWebhook {
+    /// Converts this request into a [`NewWorkspaceInboundWebhook`] model.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - The workspace the events are routed to.
+    /// * `account_id` - The account creating the inbound webhook.
+    /// * `encrypted_secret` - The verification secret, encrypted.
+    #[inline]
+    pub fn into_model(
+        self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+        encrypted_secret: Vec<u8>,
+    ) -> NewWorkspaceInboundWebhook {
+        NewWorkspaceInboundWebhook {
+            workspace_id,
+            provider: self.provider,
+            display_name: self.display_name,
+            encrypted_secret,
+            created_by: account_id,
+        }
+    }
+}
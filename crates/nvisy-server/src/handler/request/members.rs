@@ -2,25 +2,30 @@
 
 use nvisy_postgres::model::UpdateWorkspaceMember;
 use nvisy_postgres::types::{
-    MemberFilter, MemberSortBy, MemberSortField, SortOrder, WorkspaceRole,
+    MemberFilter, MemberSortBy, MemberSortField, RoleId, SortOrder, WorkspaceRole,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-/// Request to update a member's role.
+/// Request to update a member's roles.
 #[must_use]
 #[derive(Debug, Serialize, Deserialize, Validate, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateMember {
-    /// New role for the member.
-    pub role: WorkspaceRole,
+    /// New built-in role for the member.
+    pub role: Option<WorkspaceRole>,
+    /// Custom role granted on top of the built-in role; `null` unassigns it.
+    pub custom_role_id: Option<Option<RoleId>>,
 }
 
 impl UpdateMember {
     pub fn into_model(self) -> UpdateWorkspaceMember {
         UpdateWorkspaceMember {
-            member_role: Some(self.role),
+            member_role: self.role,
+            custom_role_id: self
+                .custom_role_id
+                .map(|role_id| role_id.map(|role_id| role_id.as_uuid())),
             ..Default::default()
         }
     }
@@ -1,36 +1,58 @@
 //! Request types for HTTP handlers.
 
 mod accounts;
+mod analytics;
+mod api_keys;
 mod authentications;
 mod connections;
 mod contexts;
+mod fieldsets;
 mod files;
+mod inbound_webhooks;
 mod invites;
 mod members;
 mod monitors;
+mod operations;
 mod paginations;
 mod paths;
 mod pipeline_runs;
 mod pipelines;
 mod policies;
+mod retention;
+mod reviews;
+mod roles;
+mod scim;
+mod shares;
+mod storage;
 mod tokens;
 mod validations;
 mod webhooks;
 mod workspaces;
 
 pub use accounts::*;
+pub use analytics::*;
+pub use api_keys::*;
 pub use authentications::*;
 pub use connections::*;
 pub use contexts::*;
+pub use fieldsets::*;
 pub use files::*;
+pub use inbound_webhooks::*;
 pub use invites::*;
 pub use members::*;
 pub use monitors::*;
+pub use operations::*;
 pub use paginations::*;
 pub use paths::*;
 pub use pipeline_runs::*;
 pub use pipelines::*;
 pub use policies::*;
+pub use retention::*;
+pub use reviews::*;
+pub use roles::*;
+pub use scim::*;
+pub use shares::*;
+pub use storage::*;
 pub use tokens::*;
 pub use validations::*;
 pub use webhooks::*;
@@ -0,0 +1,23 @@
+//! Long-running operation request types.
+
+use nvisy_postgres::types::OperationStatus;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Query parameters for listing a workspace's operations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationsQuery {
+    /// Filter by operation status.
+    pub status: Option<OperationStatus>,
+}
+
+/// Query parameters of a signed artifact download link.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactDownloadQuery {
+    /// When the link expires, in seconds since the Unix epoch.
+    pub expires: i64,
+    /// Hex-encoded signature over the operation id and expiry.
+    pub signature: String,
+}
@@ -1,6 +1,6 @@
 //! Path parameter types for HTTP handlers.
 
-use nvisy_postgres::types::{RunId, Username, WebhookId};
+use nvisy_postgres::types::{InboundWebhookId, OperationId, RoleId, RunId, Username, WebhookId};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -50,6 +50,27 @@ pub struct WebhookPathParams {
     pub webhook_id: WebhookId,
 }
 
+/// Path parameters for inbound webhook operations, and for the requests
+/// third-party services send to one.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundWebhookPathParams {
+    /// Opaque identifier of the inbound webhook.
+    pub inbound_webhook_id: InboundWebhookId,
+}
+
+/// Path parameters for account API key operations.
+///
+/// Key ownership is verified against the authenticated account.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyPathParams {
+    /// Unique identifier of the API key.
+    pub key_id: Uuid,
+}
+
 /// Path parameters for API token operations.
 ///
 /// Since token IDs are globally unique UUIDs, account context is verified
@@ -91,3 +112,50 @@ pub struct PipelineRunPathParams {
     /// Opaque identifier of the run.
     pub run_id: RunId,
 }
+
+/// Path parameters for a detection's review history.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionReviewPathParams {
+    /// Opaque identifier of the run.
+    pub run_id: RunId,
+    /// Identifier of the detection within the run's analysis.
+    pub detection_id: String,
+}
+
+/// Path parameters for long-running operation lookups.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationPathParams {
+    /// Opaque identifier of the operation.
+    pub operation_id: OperationId,
+}
+
+/// Path parameters for custom role operations.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RolePathParams {
+    /// Opaque identifier of the custom role.
+    pub role_id: RoleId,
+}
+
+/// Path parameters for SCIM user operations.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserPathParams {
+    /// SCIM id of the user: the account id.
+    pub user_id: Uuid,
+}
+
+/// Path parameters for SCIM group operations.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupPathParams {
+    /// SCIM id of the group: the name of a workspace role.
+    pub group_id: String,
+}
@@ -31,4 +31,12 @@ pub struct CreatePipelineRun {
     /// the pipeline default.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scope: Option<ScopeParams>,
+    /// Password for a protected document, used for this run only.
+    ///
+    /// Takes precedence over a password stored on the file. It is held in
+    /// memory only, encrypted, for as long as a transient file password, and
+    /// never stored.
+    #[validate(length(min = 1, max = 1024))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_password: Option<String>,
 }
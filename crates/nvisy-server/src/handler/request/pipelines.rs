@@ -4,7 +4,9 @@
 //! creation, updates, and filtering. All request types support JSON serialization
 //! and validation.
 
-use nvisy_postgres::model::{NewWorkspacePipeline, UpdateWorkspacePipeline as UpdatePipelineModel};
+use nvisy_postgres::model::{
+    NewWorkspacePipeline, UpdateWorkspacePipeline as UpdatePipelineModel, WorkspacePipeline,
+};
 use nvisy_postgres::types::{PipelineStatus, Slug};
 use nvisy_schema::plan::{
     DeduplicationParams, EnricherParams, LabelCatalogParams, RecognizerParams, ScopeParams,
@@ -102,6 +104,50 @@ impl PipelineDefinition {
     }
 }
 
+/// How long a pipeline keeps the artifacts of each stage of its finished runs.
+///
+/// An omitted stage is kept indefinitely. Artifacts of runs under legal hold
+/// or with findings awaiting review are kept regardless.
+#[must_use]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Validate
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactRetention {
+    /// Days input artifacts are kept.
+    #[validate(range(min = 1, max = 36500))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_days: Option<i32>,
+    /// Days intermediate artifacts are kept.
+    #[validate(range(min = 1, max = 36500))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intermediate_days: Option<i32>,
+    /// Days output artifacts are kept.
+    #[validate(range(min = 1, max = 36500))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_days: Option<i32>,
+}
+
+impl ArtifactRetention {
+    /// Reads the retention periods stored on a pipeline.
+    pub fn from_model(pipeline: &WorkspacePipeline) -> Self {
+        Self {
+            input_days: pipeline.input_retention_days,
+            intermediate_days: pipeline.intermediate_retention_days,
+            output_days: pipeline.output_retention_days,
+        }
+    }
+}
+
 /// Request payload for creating a new pipeline.
 ///
 /// Creates a new pipeline with the specified name and optional description.
@@ -122,6 +168,11 @@ pub struct CreatePipeline {
     /// definition that can be filled in via update.
     #[validate(nested)]
     pub definition: Option<PipelineDefinition>,
+    /// How long the artifacts of each stage are kept; defaults to
+    /// indefinitely.
+    #[serde(default)]
+    #[validate(nested)]
+    pub artifact_retention: ArtifactRetention,
 }
 
 /// A pipeline's reference slugs, split out to be resolved to ids and written to
@@ -163,6 +214,9 @@ impl CreatePipeline {
             schedule_cron: None,
             schedule_tz: None,
             next_run_at: None,
+            input_retention_days: self.artifact_retention.input_days,
+            intermediate_retention_days: self.artifact_retention.intermediate_days,
+            output_retention_days: self.artifact_retention.output_days,
         };
         Ok((model, references))
     }
@@ -202,6 +256,9 @@ pub struct UpdatePipeline {
     /// New detection + redaction configuration (replaces the whole definition).
     #[validate(nested)]
     pub definition: Option<PipelineDefinition>,
+    /// New artifact retention (replaces every stage's period).
+    #[validate(nested)]
+    pub artifact_retention: Option<ArtifactRetention>,
 }
 
 impl UpdatePipeline {
@@ -226,11 +283,15 @@ impl UpdatePipeline {
             }
             None => (None, None),
         };
+        let retention = self.artifact_retention;
         let model = UpdatePipelineModel {
             name: self.name,
             description: self.description.map(Some),
             status: self.status,
             definition,
+            input_retention_days: retention.map(|r| r.input_days),
+            intermediate_retention_days: retention.map(|r| r.intermediate_days),
+            output_retention_days: retention.map(|r| r.output_days),
             ..Default::default()
         };
         Ok((model, references))
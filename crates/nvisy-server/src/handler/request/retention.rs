@@ -0,0 +1,122 @@
+//! Retention policy and legal hold request types.
+
+use jiff::civil::Date;
+use nvisy_postgres::types::SortOrder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::files::access_window_start;
+
+/// Default number of files in an access report.
+const DEFAULT_ACCESS_REPORT_LIMIT: u32 = 50;
+
+/// Largest number of files in an access report.
+const MAX_ACCESS_REPORT_LIMIT: u32 = 500;
+
+/// Path parameters for legal hold operations.
+///
+/// The workspace is resolved by the [`WorkspaceContext`] extractor from the
+/// `{workspaceSlug}` path segment.
+///
+/// [`WorkspaceContext`]: crate::extract::WorkspaceContext
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalHoldPathParams {
+    /// Unique identifier of the legal hold.
+    pub hold_id: Uuid,
+}
+
+/// Request payload for setting a workspace's retention policy.
+///
+/// Replaces the whole policy: an omitted period keeps that data
+/// indefinitely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRetentionPolicy {
+    /// Days files are kept after upload.
+    #[validate(range(min = 1, max = 36500))]
+    pub file_retention_days: Option<i32>,
+    /// Days finished pipeline runs and their analyses are kept.
+    #[validate(range(min = 1, max = 36500))]
+    pub run_retention_days: Option<i32>,
+    /// Days audit records are kept.
+    #[validate(range(min = 1, max = 36500))]
+    pub activity_retention_days: Option<i32>,
+}
+
+/// Request payload for placing a legal hold.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLegalHold {
+    /// File to hold; omit to hold the whole workspace.
+    pub file_id: Option<Uuid>,
+    /// Why the data is held (1-1024 characters).
+    #[validate(length(min = 1, max = 1024))]
+    pub reason: String,
+}
+
+/// Which end of the access ranking a report lists.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessRanking {
+    /// The most accessed files first.
+    #[default]
+    MostAccessed,
+    /// The least accessed files first, including files never accessed.
+    LeastAccessed,
+}
+
+impl AccessRanking {
+    /// Returns the order the files are sorted by access count in.
+    pub fn sort_order(self) -> SortOrder {
+        match self {
+            Self::MostAccessed => SortOrder::Desc,
+            Self::LeastAccessed => SortOrder::Asc,
+        }
+    }
+}
+
+/// Query parameters for a workspace's file access report.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessReportQuery {
+    /// Which end of the ranking to list (default: `most_accessed`).
+    #[serde(default)]
+    pub ranking: AccessRanking,
+    /// Number of days to cover, ending today (1-365, default: 30).
+    #[validate(range(min = 1, max = 365))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    /// Maximum number of files to list (1-500, default: 50).
+    #[validate(range(min = 1, max = 500))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl FileAccessReportQuery {
+    /// Returns the first day covered.
+    pub fn since(&self) -> Date {
+        access_window_start(self.days)
+    }
+
+    /// Returns the maximum number of files to list.
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_ACCESS_REPORT_LIMIT)
+            .clamp(1, MAX_ACCESS_REPORT_LIMIT)
+    }
+}
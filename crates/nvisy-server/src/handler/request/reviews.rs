@@ -0,0 +1,49 @@
+//! Detection review request types.
+
+use nvisy_postgres::model::NewWorkspaceDetectionReview;
+use nvisy_postgres::types::ReviewStatus;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// Request payload to record decisions on a run's detections in bulk.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewDetections {
+    /// Decisions to record, at most one per detection.
+    #[validate(length(min = 1, max = 500), nested)]
+    pub decisions: Vec<DetectionDecision>,
+}
+
+/// A reviewer's decision on one detection.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionDecision {
+    /// Identifier of the detection within the run's analysis.
+    #[validate(length(min = 1, max = 256))]
+    pub detection_id: String,
+    /// The decision: `accepted`, `rejected`, or `proposed` to reopen the
+    /// detection.
+    pub status: ReviewStatus,
+    /// Reviewer comment.
+    #[validate(length(max = 2048))]
+    pub comment: Option<String>,
+}
+
+impl DetectionDecision {
+    /// Converts to the database model; the version is assigned on insert.
+    pub fn into_model(self, run_id: Uuid, reviewer_id: Uuid) -> NewWorkspaceDetectionReview {
+        NewWorkspaceDetectionReview {
+            run_id,
+            reviewer_id: Some(reviewer_id),
+            detection_id: self.detection_id,
+            version: 1,
+            decision: self.status,
+            status: self.status,
+            comment: self.comment,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Custom role request types.
+
+use nvisy_postgres::model::{NewWorkspaceCustomRole, UpdateWorkspaceCustomRole};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::service::rbac::Permission;
+
+/// Request payload for creating a custom role.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRole {
+    /// Role name, unique within the workspace.
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    /// Role description.
+    #[validate(length(max = 1024))]
+    pub description: Option<String>,
+    /// Permissions granted on top of the member's built-in role.
+    #[validate(length(max = 64))]
+    pub permissions: Vec<Permission>,
+}
+
+impl CreateRole {
+    /// Converts to the database model.
+    pub fn into_model(self, workspace_id: Uuid, account_id: Uuid) -> NewWorkspaceCustomRole {
+        NewWorkspaceCustomRole {
+            workspace_id,
+            account_id,
+            name: self.name.trim().to_owned(),
+            description: self.description,
+            permissions: permission_keys(&self.permissions),
+        }
+    }
+}
+
+/// Request payload for updating a custom role.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRole {
+    /// Role name, unique within the workspace.
+    #[validate(length(min = 1, max = 64))]
+    pub name: Option<String>,
+    /// Role description.
+    #[validate(length(max = 1024))]
+    pub description: Option<Option<String>>,
+    /// Permissions granted on top of the member's built-in role (replaces
+    /// the current set).
+    #[validate(length(max = 64))]
+    pub permissions: Option<Vec<Permission>>,
+}
+
+impl UpdateRole {
+    /// Converts to the database model.
+    pub fn into_model(self) -> UpdateWorkspaceCustomRole {
+        UpdateWorkspaceCustomRole {
+            name: self.name.map(|name| name.trim().to_owned()),
+            description: self.description,
+            permissions: self.permissions.as_deref().map(permission_keys),
+        }
+    }
+}
+
+/// Returns the deduplicated, sorted keys of `permissions`.
+fn permission_keys(permissions: &[Permission]) -> Vec<String> {
+    let mut keys: Vec<String> = permissions.iter().map(ToString::to_string).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
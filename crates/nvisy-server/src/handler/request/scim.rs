@@ -0,0 +1,35 @@
+//! SCIM request types.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Default page size of SCIM list queries.
+const DEFAULT_COUNT: usize = 100;
+
+/// Query parameters of SCIM list endpoints (RFC 7644, section 3.4.2).
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+    /// Filter expression, e.g. `userName eq "ada@example.com"`.
+    pub filter: Option<String>,
+    /// 1-based index of the first result to return.
+    pub start_index: Option<usize>,
+    /// Maximum number of results to return.
+    pub count: Option<usize>,
+}
+
+impl ScimListQuery {
+    /// Largest page size a list query may request.
+    pub const MAX_COUNT: usize = 1000;
+
+    /// Returns the 1-based start index, defaulting to the first result.
+    pub fn start_index(&self) -> usize {
+        self.start_index.unwrap_or(1).max(1)
+    }
+
+    /// Returns the page size, clamped to what the server is willing to return.
+    pub fn count(&self) -> usize {
+        self.count.unwrap_or(DEFAULT_COUNT).min(Self::MAX_COUNT)
+    }
+}
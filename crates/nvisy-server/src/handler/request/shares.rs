@@ -0,0 +1,139 @@
+//! Share link request types.
+
+use std::collections::BTreeSet;
+
+use nvisy_postgres::model::NewWorkspaceFileShare;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::handler::{ErrorKind, Result};
+use crate::service::IssuedShareToken;
+
+/// Default lifetime of a share link (7 days).
+const DEFAULT_SHARE_LIFETIME_HOURS: u32 = 7 * 24;
+
+/// Longest lifetime a share link may have (90 days).
+const MAX_SHARE_LIFETIME_HOURS: u32 = 90 * 24;
+
+/// Path parameters for operations on one share link of a file.
+///
+/// The workspace is resolved by the [`WorkspaceContext`] extractor from the
+/// `{workspaceSlug}` path segment.
+///
+/// [`WorkspaceContext`]: crate::extract::WorkspaceContext
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSharePathParams {
+    /// Unique identifier of the shared file.
+    pub file_id: Uuid,
+    /// Unique identifier of the share link.
+    pub share_id: Uuid,
+}
+
+/// Path parameters for opening a share link.
+#[must_use]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDocumentPathParams {
+    /// The link's token.
+    pub token: String,
+}
+
+/// Request payload for sharing a file through a link.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShareLink {
+    /// Who the link is issued to (1-255 characters), such as an email
+    /// address. Stamped into watermarks and recorded in the audit log.
+    #[validate(length(min = 1, max = 255))]
+    pub recipient: String,
+
+    /// One-based pages the link grants; omit to grant the whole document.
+    #[validate(length(min = 1, max = 1000))]
+    pub pages: Option<Vec<u32>>,
+
+    /// Hours until the link expires (1-2160). Defaults to 7 days.
+    #[serde(default = "default_lifetime_hours")]
+    #[validate(range(min = 1, max = MAX_SHARE_LIFETIME_HOURS))]
+    pub expires_in_hours: u32,
+
+    /// Number of times the link may be opened; omit for no limit.
+    #[validate(range(min = 1, max = 100000))]
+    pub max_views: Option<i32>,
+
+    /// Whether served copies are watermarked with the recipient.
+    ///
+    /// Defaults to `true`. Word and JSON documents, and documents that need
+    /// a password to open, can only be shared with watermarking turned off.
+    #[serde(default = "default_watermark")]
+    pub watermark: bool,
+}
+
+fn default_lifetime_hours() -> u32 {
+    DEFAULT_SHARE_LIFETIME_HOURS
+}
+
+fn default_watermark() -> bool {
+    true
+}
+
+impl CreateShareLink {
+    /// Converts this request into a [`NewWorkspaceFileShare`] model.
+    pub fn into_model(
+        self,
+        workspace_id: Uuid,
+        file_id: Uuid,
+        account_id: Uuid,
+        issued: &IssuedShareToken,
+    ) -> Result<NewWorkspaceFileShare> {
+        let recipient = self.recipient.trim().to_string();
+        if recipient.is_empty() {
+            return Err(ErrorKind::BadRequest
+                .with_resource("share_link")
+                .with_message("Recipient cannot be empty or whitespace only"));
+        }
+
+        let pages = match self.pages {
+            Some(pages) if pages.contains(&0) => {
+                return Err(ErrorKind::BadRequest
+                    .with_resource("share_link")
+                    .with_message("Pages are numbered from 1"));
+            }
+            Some(pages) => {
+                let pages: BTreeSet<u32> = pages.into_iter().collect();
+                let pages = pages
+                    .into_iter()
+                    .map(|page| i32::try_from(page).ok())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| {
+                        ErrorKind::BadRequest
+                            .with_resource("share_link")
+                            .with_message("Page number is out of range")
+                    })?;
+                Some(pages.into_iter().map(Some).collect())
+            }
+            None => None,
+        };
+
+        let lifetime = jiff::Span::new().hours(i64::from(self.expires_in_hours));
+        let expires_at = jiff::Timestamp::now()
+            .checked_add(lifetime)
+            .map_err(|_| ErrorKind::BadRequest.with_message("Expiry is out of range"))?;
+
+        Ok(NewWorkspaceFileShare {
+            workspace_id,
+            file_id,
+            account_id: Some(account_id),
+            recipient,
+            pages,
+            watermark: self.watermark,
+            token_prefix: issued.token_prefix.clone(),
+            token_hash: issued.token_hash.to_vec(),
+            max_views: self.max_views,
+            expires_at: expires_at.into(),
+        })
+    }
+}
@@ -0,0 +1,30 @@
+//! Administrator object storage request types.
+
+use std::time::Duration;
+
+use nvisy_postgres::types::StorageStage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Request payload for bulk deleting stored objects.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupStorage {
+    /// Pipeline stage whose objects are deleted. Only `intermediate` objects
+    /// can be bulk deleted.
+    pub stage: StorageStage,
+    /// Only objects last written at least this many hours ago are deleted.
+    #[validate(range(min = 1, max = 8760))]
+    pub older_than_hours: u32,
+    /// Count the matching objects without deleting them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl CleanupStorage {
+    /// Returns the minimum age of the deleted objects.
+    pub fn older_than(&self) -> Duration {
+        Duration::from_secs(u64::from(self.older_than_hours) * 60 * 60)
+    }
+}
@@ -101,6 +101,7 @@ impl CreateApiToken {
             session_type: Some(ApiTokenType::Api),
             is_remembered: Some(true),
             expired_at: self.expires_in.to_expiry_timestamp().map(Into::into),
+            ..Default::default()
         })
     }
 }
@@ -5,6 +5,7 @@
 
 use std::collections::HashMap;
 
+use jiff::civil::Date;
 use nvisy_postgres::model::{
     NewWorkspaceWebhook, UpdateWorkspaceWebhook as UpdateWorkspaceWebhookModel,
 };
@@ -12,7 +13,11 @@ use nvisy_postgres::types::{WebhookEvent, WebhookStatus};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use super::files::access_window_start;
+use super::validations::validation_error;
+use crate::service::PayloadFilter;
 
 /// Request payload for creating a new workspace webhook.
 #[must_use]
@@ -30,6 +35,11 @@ pub struct CreateWebhook {
     pub url: String,
     /// List of event types this webhook should receive.
     pub events: Vec<WebhookEvent>,
+    /// JSONPath queries (RFC 9535) evaluated against each payload; an event
+    /// is delivered only if every query selects at least one value (max 16).
+    #[serde(default)]
+    #[validate(custom(function = "validate_payload_filters"))]
+    pub payload_filters: Vec<String>,
     /// Optional custom headers to include in webhook requests.
     pub headers: Option<HashMap<String, String>>,
     /// Initial status of the webhook (active or paused).
@@ -51,6 +61,7 @@ impl CreateWebhook {
         encrypted_secret: Vec<u8>,
     ) -> NewWorkspaceWebhook {
         let events = self.events.into_iter().map(Some).collect();
+        let payload_filters = self.payload_filters.into_iter().map(Some).collect();
         let headers = NewWorkspaceWebhook::serialize_headers_opt(self.headers);
         // Treat Disabled as Paused since users cannot set Disabled status
         let status = self.status.map(|s| match s {
@@ -68,6 +79,7 @@ impl CreateWebhook {
             encrypted_secret,
             status,
             created_by: account_id,
+            payload_filters,
         }
     }
 }
@@ -88,6 +100,9 @@ pub struct UpdateWebhook {
     pub url: Option<String>,
     /// Updated list of event types this webhook should receive.
     pub events: Option<Vec<WebhookEvent>>,
+    /// Updated payload filters; an empty list delivers every subscribed event.
+    #[validate(custom(function = "validate_payload_filters"))]
+    pub payload_filters: Option<Vec<String>>,
     /// Updated custom headers to include in webhook requests.
     pub headers: Option<HashMap<String, String>>,
     /// Updated status (active or paused). Ignored if webhook is currently disabled.
@@ -102,6 +117,9 @@ impl UpdateWebhook {
     #[inline]
     pub fn into_model(self, current_status: WebhookStatus) -> UpdateWorkspaceWebhookModel {
         let events = self.events.map(|e| e.into_iter().map(Some).collect());
+        let payload_filters = self
+            .payload_filters
+            .map(|filters| filters.into_iter().map(Some).collect());
         let headers = NewWorkspaceWebhook::serialize_headers_opt(self.headers);
         // Ignore status changes if webhook is disabled; treat Disabled as Paused
         let status = if current_status.is_disabled() {
@@ -118,6 +136,7 @@ impl UpdateWebhook {
             description: self.description,
             url: self.url,
             events,
+            payload_filters,
             headers,
             status,
             ..Default::default()
@@ -130,7 +149,33 @@ impl UpdateWebhook {
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct TestWebhook {
-    /// Optional custom payload to send in the test request.
-    /// If not provided, a default test payload will be used.
+    /// Optional event metadata to send in the test request, as
+    /// `context.metadata`; the webhook's payload filters are evaluated
+    /// against it. If not provided, a default test payload will be used.
     pub payload: Option<serde_json::Value>,
 }
+
+/// Query parameters for a webhook's delivery statistics.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, Validate, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookStatsWindow {
+    /// Number of days to cover, ending today (1-365, default: 30).
+    #[validate(range(min = 1, max = 365))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+}
+
+impl WebhookStatsWindow {
+    /// Returns the first day covered.
+    pub fn since(&self) -> Date {
+        access_window_start(self.days)
+    }
+}
+
+/// Rejects payload filters that are not valid JSONPath queries.
+fn validate_payload_filters(filters: &[String]) -> Result<(), ValidationError> {
+    PayloadFilter::parse(filters)
+        .map(|_| ())
+        .map_err(|message| validation_error("payload_filters", &message))
+}
@@ -4,10 +4,13 @@
 //! creation, updates, and archival. All request types support JSON serialization
 //! and validation.
 
+use jiff::Timestamp;
 use nvisy_postgres::model::{
     NewWorkspace, UpdateWorkspace as UpdateWorkspaceModel, UpdateWorkspaceMember,
 };
-use nvisy_postgres::types::{NotificationEvent, Slug};
+use nvisy_postgres::types::{
+    ActivityCategory, ActivityFilter, ActivityType, DataRegion, NotificationEvent, Slug, Username,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -33,6 +36,8 @@ pub struct CreateWorkspace {
     pub description: Option<String>,
     /// Whether approval is required for processed files to be visible.
     pub require_approval: Option<bool>,
+    /// Region to pin the workspace's data to. Cannot be changed later.
+    pub data_region: Option<DataRegion>,
 }
 
 impl CreateWorkspace {
@@ -70,6 +75,7 @@ impl CreateWorkspace {
             metadata: None,
             settings: None,
             created_by: account_id,
+            data_region: self.data_region,
         })
     }
 }
@@ -130,3 +136,64 @@ impl UpdateNotificationSettings {
         }
     }
 }
+
+/// Query parameters for the workspace activity breakdown.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityBreakdownQuery {
+    /// Only count activities from the last given number of hours.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hours: Option<u32>,
+}
+
+/// Query parameters for listing workspace activities.
+#[must_use]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListActivities {
+    /// Only include activities performed by this account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<Username>,
+    /// Only include activities of this type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_type: Option<ActivityType>,
+    /// Only include activities on this kind of resource.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<ActivityCategory>,
+    /// Only include activities on this resource.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<Uuid>,
+    /// Only include activities at or after this time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<Timestamp>,
+    /// Only include activities before this time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Timestamp>,
+}
+
+impl ListActivities {
+    /// Converts to filter model.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BadRequest` if the time range ends before it starts.
+    pub fn to_filter(&self) -> Result<ActivityFilter> {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+            && to < from
+        {
+            return Err(ErrorKind::BadRequest
+                .with_message("Invalid time range")
+                .with_context("`to` must not be before `from`"));
+        }
+
+        Ok(ActivityFilter {
+            actor: self.actor.clone(),
+            activity_type: self.activity_type,
+            category: self.resource_type,
+            resource_id: self.resource_id,
+            from: self.from,
+            to: self.to,
+        })
+    }
+}
@@ -1,13 +1,17 @@
 //! Workspace activity response types.
 
+use std::collections::HashMap;
+
 use jiff::Timestamp;
 use nvisy_postgres::model::WorkspaceActivity;
 use nvisy_postgres::types::{ActivityType, Slug, Username};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 use uuid::Uuid;
 
 use super::Page;
+use crate::service::{ChainBreakReason, ChainVerification, PrivacyCharge, PrivacyService};
 
 /// Response type for a workspace activity.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -24,6 +28,17 @@ pub struct Activity {
     pub activity_type: ActivityType,
     /// Human-readable description.
     pub description: String,
+    /// Resource the activity acted on, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<Uuid>,
+    /// Position of the activity in the workspace's audit chain.
+    pub sequence_number: i64,
+    /// Hex-encoded hash of the previous record in the chain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_hash: Option<String>,
+    /// Hex-encoded hash of this record; absent for records predating the chain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_hash: Option<String>,
     /// When the activity occurred.
     pub created_at: Timestamp,
 }
@@ -43,7 +58,178 @@ impl Activity {
             actor_username,
             activity_type: activity.activity_type,
             description: activity.description,
+            resource_id: activity.resource_id,
+            sequence_number: activity.sequence_number,
+            previous_hash: activity.previous_hash.map(hex::encode),
+            record_hash: activity.record_hash.map(hex::encode),
             created_at: activity.created_at.into(),
         }
     }
 }
+
+/// Where a workspace's audit chain failed verification.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityChainBreak {
+    /// Sequence number of the offending record.
+    pub sequence_number: i64,
+    /// ID of the offending record.
+    pub activity_id: Uuid,
+    /// Why verification failed.
+    pub reason: ChainBreakReason,
+}
+
+/// Result of verifying a workspace's audit chain.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityChainVerification {
+    /// Whether every retained record is intact and correctly linked.
+    pub intact: bool,
+    /// Number of records verified.
+    pub records: u64,
+    /// Number of records predating the chain, which carry no hash.
+    pub legacy_records: u64,
+    /// Sequence number of the oldest retained record.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_sequence_number: Option<i64>,
+    /// Sequence number of the last record verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sequence_number: Option<i64>,
+    /// Whether older records were removed by retention.
+    pub truncated: bool,
+    /// Where verification failed, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broken_at: Option<ActivityChainBreak>,
+    /// When the verification ran.
+    pub verified_at: Timestamp,
+}
+
+impl From<ChainVerification> for ActivityChainVerification {
+    fn from(verification: ChainVerification) -> Self {
+        Self {
+            intact: verification.is_intact(),
+            records: verification.records,
+            legacy_records: verification.legacy_records,
+            first_sequence_number: verification.first_sequence_number,
+            last_sequence_number: verification.last_sequence_number,
+            truncated: verification.truncated,
+            broken_at: verification
+                .broken_at
+                .map(|chain_break| ActivityChainBreak {
+                    sequence_number: chain_break.sequence_number,
+                    activity_id: chain_break.activity_id,
+                    reason: chain_break.reason,
+                }),
+            verified_at: Timestamp::now(),
+        }
+    }
+}
+
+/// Number of activities of one type.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityCount {
+    /// Type of activity.
+    pub activity_type: ActivityType,
+    /// Number of activities of this type.
+    pub count: i64,
+}
+
+/// Privacy budget details attached to a noised response.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyNotice {
+    /// Epsilon spent by this query.
+    pub epsilon: f64,
+    /// Epsilon left in the workspace's current budget window.
+    pub remaining_epsilon: f64,
+    /// When the budget window resets.
+    pub resets_at: Timestamp,
+}
+
+impl From<PrivacyCharge> for PrivacyNotice {
+    fn from(charge: PrivacyCharge) -> Self {
+        Self {
+            epsilon: charge.epsilon,
+            remaining_epsilon: charge.remaining,
+            resets_at: charge.resets_at,
+        }
+    }
+}
+
+/// Activity counts for a workspace, grouped by type.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityBreakdown {
+    /// Counts per activity type, largest first.
+    pub items: Vec<ActivityCount>,
+    /// Present when the counts carry differential privacy noise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacyNotice>,
+}
+
+impl ActivityBreakdown {
+    /// Creates a breakdown from exact counts.
+    pub fn from_counts(counts: Vec<(ActivityType, i64)>) -> Self {
+        let items = counts
+            .into_iter()
+            .map(|(activity_type, count)| ActivityCount {
+                activity_type,
+                count,
+            })
+            .collect();
+
+        Self {
+            items,
+            privacy: None,
+        }
+    }
+
+    /// Creates a breakdown with noise added to every count.
+    ///
+    /// `counts` are per account and type. Each account's counts are clamped
+    /// to the contribution bound before they are added up, so one heavy user
+    /// cannot stand out through the noise. Every activity type is reported,
+    /// including those with no activity, so that the set of returned types
+    /// does not itself reveal which occurred.
+    pub fn from_noisy_counts(
+        counts: Vec<(Option<Uuid>, ActivityType, i64)>,
+        privacy: &PrivacyService,
+        charge: PrivacyCharge,
+    ) -> Self {
+        let types: Vec<_> = ActivityType::iter().collect();
+
+        let mut per_account: HashMap<Option<Uuid>, Vec<i64>> = HashMap::new();
+        for (account_id, activity_type, count) in counts {
+            let Some(index) = types.iter().position(|t| *t == activity_type) else {
+                continue;
+            };
+            per_account
+                .entry(account_id)
+                .or_insert_with(|| vec![0; types.len()])[index] += count;
+        }
+
+        let mut totals = vec![0; types.len()];
+        for mut account_counts in per_account.into_values() {
+            privacy.clamp_contributions(&mut account_counts);
+            for (total, count) in totals.iter_mut().zip(account_counts) {
+                *total += count;
+            }
+        }
+
+        let mut items: Vec<_> = types
+            .into_iter()
+            .zip(totals)
+            .map(|(activity_type, exact)| ActivityCount {
+                activity_type,
+                count: privacy.noisy_count(exact),
+            })
+            .collect();
+        items.sort_by(|a, b| b.count.cmp(&a.count));
+
+        Self {
+            items,
+            privacy: Some(charge.into()),
+        }
+    }
+}
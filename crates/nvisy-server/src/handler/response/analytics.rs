@@ -0,0 +1,263 @@
+//! Administrator analytics response types.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use jiff::Timestamp;
+use jiff::civil::Date;
+use nvisy_postgres::model::{StoragePrice as StoragePriceModel, WorkspaceStorageCost};
+use nvisy_postgres::types::{DataRegion, StorageClass, StorageStage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Columns of the storage cost CSV export, in order.
+const STORAGE_COST_CSV_HEADER: &str = "period_start,workspace_id,data_region,stage,\
+    storage_class,object_count,byte_count,byte_days,sampled_days,period_days,currency,\
+    price_per_gib_month,cost";
+
+/// Response type for a storage price.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePrice {
+    /// Unique price identifier.
+    pub id: Uuid,
+    /// Region whose backends the price applies to.
+    pub data_region: DataRegion,
+    /// Storage class the price applies to.
+    pub storage_class: StorageClass,
+    /// ISO 4217 currency code.
+    pub currency: String,
+    /// Price of one GiB stored for a month, in millionths of the currency.
+    pub price_micros_per_gib_month: i64,
+    /// First day the price applies.
+    pub effective_from: Date,
+    /// When the price was recorded.
+    pub created_at: Timestamp,
+}
+
+/// List of storage prices.
+pub type StoragePrices = Vec<StoragePrice>;
+
+impl StoragePrice {
+    /// Creates a response from a database model.
+    pub fn from_model(price: StoragePriceModel) -> Self {
+        Self {
+            id: price.id,
+            data_region: price.data_region,
+            storage_class: price.storage_class,
+            currency: price.currency,
+            price_micros_per_gib_month: price.price_micros_per_gib_month,
+            effective_from: price.effective_from.into(),
+            created_at: price.created_at.into(),
+        }
+    }
+}
+
+/// A workspace's storage in one region, stage and class over a month.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCost {
+    /// Workspace the storage is attributed to.
+    pub workspace_id: Uuid,
+    /// Region whose backends store the objects.
+    pub data_region: DataRegion,
+    /// Pipeline stage the objects are stored for.
+    pub stage: StorageStage,
+    /// Storage class the objects are priced at.
+    pub storage_class: StorageClass,
+    /// Objects stored at the latest sample.
+    pub object_count: i64,
+    /// Bytes stored at the latest sample.
+    pub byte_count: i64,
+    /// Sum of the bytes stored at each daily sample.
+    pub byte_days: i64,
+    /// Days of the month sampled so far.
+    pub sampled_days: i32,
+    /// Days in the month.
+    pub period_days: i32,
+    /// Currency of the cost; absent if no price was in effect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// Price applied at the latest sample, in millionths of the currency per
+    /// GiB-month.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_micros_per_gib_month: Option<i64>,
+    /// Cost accrued so far, in millionths of the currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_micros: Option<i64>,
+    /// Day of the latest sample.
+    pub last_sampled_on: Date,
+}
+
+impl StorageCost {
+    /// Creates a response from a database model.
+    pub fn from_model(cost: WorkspaceStorageCost) -> Self {
+        Self {
+            workspace_id: cost.workspace_id,
+            data_region: cost.data_region,
+            stage: cost.stage,
+            storage_class: cost.storage_class,
+            object_count: cost.object_count,
+            byte_count: cost.byte_count,
+            byte_days: cost.byte_days,
+            sampled_days: cost.sampled_days,
+            period_days: cost.period_days,
+            currency: cost.currency,
+            price_micros_per_gib_month: cost.price_micros_per_gib_month,
+            cost_micros: cost.cost_micros,
+            last_sampled_on: cost.last_sampled_on.into(),
+        }
+    }
+}
+
+/// Total cost accrued in one currency.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCostTotal {
+    /// ISO 4217 currency code.
+    pub currency: String,
+    /// Cost accrued so far, in millionths of the currency.
+    pub cost_micros: i64,
+}
+
+/// Response type for a month's storage cost report.
+///
+/// The current month is reported as accrued so far.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCostReport {
+    /// First day of the reported month.
+    pub period_start: Date,
+    /// Storage per workspace, region, stage and class.
+    pub costs: Vec<StorageCost>,
+    /// Cost accrued per currency; unpriced storage is left out.
+    pub totals: Vec<StorageCostTotal>,
+}
+
+impl StorageCostReport {
+    /// Creates a report from the month's database rows.
+    pub fn from_models(period_start: Date, costs: Vec<WorkspaceStorageCost>) -> Self {
+        let costs: Vec<StorageCost> = costs.into_iter().map(StorageCost::from_model).collect();
+
+        let mut totals = BTreeMap::<&str, i64>::new();
+        for cost in &costs {
+            if let (Some(currency), Some(cost_micros)) = (&cost.currency, cost.cost_micros) {
+                *totals.entry(currency).or_default() += cost_micros;
+            }
+        }
+        let totals = totals
+            .into_iter()
+            .map(|(currency, cost_micros)| StorageCostTotal {
+                currency: currency.to_owned(),
+                cost_micros,
+            })
+            .collect();
+
+        Self {
+            period_start,
+            costs,
+            totals,
+        }
+    }
+
+    /// Renders the report as CSV, one row per cost, with amounts in whole
+    /// currency units.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::with_capacity(128 * (self.costs.len() + 1));
+        csv.push_str(STORAGE_COST_CSV_HEADER);
+        csv.push_str("\r\n");
+
+        for cost in &self.costs {
+            let _ = write!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}\r\n",
+                self.period_start,
+                cost.workspace_id,
+                cost.data_region,
+                cost.stage,
+                cost.storage_class,
+                cost.object_count,
+                cost.byte_count,
+                cost.byte_days,
+                cost.sampled_days,
+                cost.period_days,
+                csv_field(cost.currency.as_deref().unwrap_or_default()),
+                cost.price_micros_per_gib_month
+                    .map(format_micros)
+                    .unwrap_or_default(),
+                cost.cost_micros.map(format_micros).unwrap_or_default(),
+            );
+        }
+
+        csv
+    }
+}
+
+/// Formats an amount in millionths as a decimal in whole units.
+fn format_micros(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    format!("{sign}{}.{:06}", micros / 1_000_000, micros % 1_000_000)
+}
+
+/// Quotes a CSV field if it holds a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn micros_are_formatted_in_whole_units() {
+        assert_eq!(format_micros(0), "0.000000");
+        assert_eq!(format_micros(23_000_000), "23.000000");
+        assert_eq!(format_micros(1_234_567), "1.234567");
+        assert_eq!(format_micros(-5), "-0.000005");
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("USD"), "USD");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn csv_has_a_header_and_a_row_per_cost() {
+        let report = StorageCostReport {
+            period_start: Date::constant(2026, 10, 1),
+            costs: vec![StorageCost {
+                workspace_id: Uuid::nil(),
+                data_region: DataRegion::default(),
+                stage: StorageStage::Upload,
+                storage_class: StorageClass::Hot,
+                object_count: 2,
+                byte_count: 1024,
+                byte_days: 2048,
+                sampled_days: 2,
+                period_days: 31,
+                currency: Some("USD".to_owned()),
+                price_micros_per_gib_month: Some(23_000),
+                cost_micros: Some(1),
+                last_sampled_on: Date::constant(2026, 10, 2),
+            }],
+            totals: Vec::new(),
+        };
+
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], STORAGE_COST_CSV_HEADER);
+        assert!(lines[1].starts_with("2026-10-01,00000000-0000-0000-0000-000000000000,"));
+        assert!(lines[1].ends_with(",upload,hot,2,1024,2048,2,31,USD,0.023000,0.000001"));
+    }
+}
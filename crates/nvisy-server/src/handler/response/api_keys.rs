@@ -0,0 +1,79 @@
+//! Response structures for account API key operations.
+
+use jiff::Timestamp;
+use nvisy_postgres::model::AccountApiKey;
+use nvisy_postgres::types::ApiKeyScope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Page;
+
+/// Account API key response structure.
+///
+/// The key itself is never returned after creation; `keyPrefix` identifies
+/// it in listings.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    /// Unique identifier for the key.
+    pub id: Uuid,
+    /// Human-readable name for the key.
+    pub name: String,
+    /// Scopes granted to the key.
+    pub scopes: Vec<ApiKeyScope>,
+    /// Leading characters of the key.
+    pub key_prefix: String,
+    /// Key that replaced this one, while it is in its rotation grace window.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<Uuid>,
+    /// Timestamp of key creation.
+    pub created_at: Timestamp,
+    /// Timestamp when the key expires (omitted = never expires).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expired_at: Option<Timestamp>,
+    /// Timestamp of most recent key use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<Timestamp>,
+}
+
+impl ApiKey {
+    pub fn from_model(key: AccountApiKey) -> Self {
+        Self {
+            id: key.id,
+            scopes: key.granted_scopes(),
+            name: key.name,
+            key_prefix: key.key_prefix,
+            replaced_by: key.replaced_by,
+            created_at: key.created_at.into(),
+            expired_at: key.expired_at.map(Into::into),
+            last_used_at: key.last_used_at.map(Into::into),
+        }
+    }
+}
+
+/// Paginated response for account API keys.
+pub type ApiKeysPage = Page<ApiKey>;
+
+/// Account API key with its secret (only returned on creation and rotation).
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreated {
+    /// The created key details.
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    /// The full API key, used as a bearer token.
+    ///
+    /// **Important**: This is the only time the key will be shown.
+    pub key: String,
+}
+
+impl ApiKeyCreated {
+    pub fn from_model(key: AccountApiKey, secret: String) -> Self {
+        Self {
+            api_key: ApiKey::from_model(key),
+            key: secret,
+        }
+    }
+}
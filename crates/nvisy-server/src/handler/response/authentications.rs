@@ -19,3 +19,33 @@ pub struct AuthToken {
     /// Timestamp when the token expires.
     pub expires_at: Timestamp,
 }
+
+/// Where to send the user to sign in with the identity provider.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SsoAuthorization {
+    /// Provider URL to navigate the user to.
+    pub authorization_url: String,
+}
+
+/// Response returned after a successful single sign-on login.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SsoToken {
+    /// The issued session.
+    #[serde(flatten)]
+    pub token: AuthToken,
+    /// Path to return to, as requested when the login started.
+    pub redirect_to: Option<String>,
+}
+
+/// Response returned after signing out of a single sign-on session.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SsoLogout {
+    /// Provider URL that ends the provider session too, if supported.
+    pub end_session_url: Option<String>,
+}
@@ -106,6 +106,11 @@ impl<'a> ErrorResponse<'a> {
         "Payload too large.",
         StatusCode::PAYLOAD_TOO_LARGE,
     );
+    pub const PROTECTED_DOCUMENT: Self = Self::new(
+        "protected_document",
+        "Document is password-protected.",
+        StatusCode::UNPROCESSABLE_ENTITY,
+    );
     pub const SERVICE_UNAVAILABLE: Self = Self::new(
         "service_unavailable",
         "Service unavailable.",
@@ -1,13 +1,68 @@
 //! File response types.
 
 use jiff::Timestamp;
-use nvisy_postgres::model::WorkspaceFile as FileModel;
-use nvisy_postgres::types::{FileSource, Slug, Username};
+use jiff::civil::Date;
+use nvisy_postgres::model::{
+    WorkspaceFile as FileModel, WorkspaceFileAccessStats, WorkspaceFileAccessSummary,
+};
+use nvisy_postgres::types::{DataSensitivity, FileFormat, FileSource, RunId, Slug, Username};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::Page;
+use crate::service::{
+    DocumentQuality, PreflightReport, PreflightViolation, ProcessingTier, StructuralDiff, TextDiff,
+    ValueChange,
+};
+
+/// Whether a document needs a password before it can be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentProtection {
+    /// The document is not password-protected.
+    Unprotected,
+    /// The document is password-protected and no password is stored; runs
+    /// must supply one.
+    ProtectedDocument,
+    /// The document is password-protected and its password is stored.
+    PasswordStored,
+}
+
+impl DocumentProtection {
+    fn from_model(file: &FileModel) -> Self {
+        match (file.is_password_protected(), file.has_stored_password()) {
+            (false, _) => Self::Unprotected,
+            (true, false) => Self::ProtectedDocument,
+            (true, true) => Self::PasswordStored,
+        }
+    }
+}
+
+/// A document password held transiently for a file.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransientFilePassword {
+    /// The file the password opens.
+    pub file_id: Uuid,
+    /// When the password stops being held; runs started or redacted after
+    /// it need the password again.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Whether a file's stored content still matches its upload digest.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIntegrity {
+    /// The file that was checked.
+    pub file_id: Uuid,
+    /// Whether the stored content matches the SHA-256 digest recorded when
+    /// it was written; content stored without a digest counts as intact.
+    pub intact: bool,
+}
 
 /// Represents a file in responses.
 #[must_use]
@@ -40,6 +95,13 @@ pub struct File {
     /// Parent file ID if this is a newer version.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_id: Option<Uuid>,
+    /// Version ID of the stored content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_version: Option<String>,
+    /// Password protection status of the document.
+    pub protection: DocumentProtection,
+    /// Sensitivity of the content, which decides how it is encrypted.
+    pub sensitivity: DataSensitivity,
     /// Creation timestamp.
     pub created_at: Timestamp,
     /// Last update timestamp.
@@ -48,6 +110,7 @@ pub struct File {
 
 impl File {
     pub fn from_model(file: FileModel, workspace_slug: Slug, uploaded_by: Username) -> Self {
+        let protection = DocumentProtection::from_model(&file);
         Self {
             id: file.id,
             workspace_slug,
@@ -61,6 +124,9 @@ impl File {
             uploaded_by,
             version_number: file.version_number,
             parent_id: file.parent_id,
+            storage_version: file.storage_version,
+            protection,
+            sensitivity: file.sensitivity,
             created_at: file.created_at.into(),
             updated_at: file.updated_at.into(),
         }
@@ -72,3 +138,308 @@ pub type Files = Vec<File>;
 
 /// Paginated response for file listing.
 pub type FilesPage = Page<File>;
+
+/// A condition that would stop or degrade a run, with its description.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightIssue {
+    /// Kind of violation.
+    pub code: PreflightViolation,
+    /// Human-readable description.
+    pub message: String,
+}
+
+/// Preflight report of a file, computed before any run.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePreflight {
+    /// File the report describes.
+    pub file_id: Uuid,
+    /// Format detected from the content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<FileFormat>,
+    /// Number of pages, when it can be read without processing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<u32>,
+    /// Password protection status of the document.
+    pub protection: DocumentProtection,
+    /// Estimated quality of the document's text.
+    pub quality: DocumentQuality,
+    /// How the document will be processed.
+    pub tier: ProcessingTier,
+    /// Pages a run will be billed for.
+    pub billable_pages: u32,
+    /// Whether nothing would stop a run over the file.
+    pub ready: bool,
+    /// Conditions that would stop or degrade a run.
+    pub violations: Vec<PreflightIssue>,
+    /// When the report was computed.
+    pub inspected_at: Timestamp,
+}
+
+impl FilePreflight {
+    pub fn from_report(file: &FileModel, report: PreflightReport) -> Self {
+        // Detection at upload and the preflight scan agree on stored files;
+        // either one flagging the document is enough.
+        let protection = if report.password_protected && !file.is_password_protected() {
+            DocumentProtection::ProtectedDocument
+        } else {
+            DocumentProtection::from_model(file)
+        };
+
+        Self {
+            file_id: file.id,
+            format: report.format,
+            page_count: report.page_count,
+            protection,
+            quality: report.quality,
+            tier: report.tier,
+            billable_pages: report.billable_pages,
+            ready: report.is_ready(),
+            violations: report
+                .violations
+                .into_iter()
+                .map(|code| PreflightIssue {
+                    code,
+                    message: code.description().to_owned(),
+                })
+                .collect(),
+            inspected_at: Timestamp::now(),
+        }
+    }
+}
+
+/// A version of a file taking part in a comparison.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersion {
+    /// File ID of the version.
+    pub file_id: Uuid,
+    /// Version number.
+    pub version_number: i32,
+    /// Display name of the version.
+    pub display_name: String,
+    /// When the version was created.
+    pub created_at: Timestamp,
+}
+
+impl FileVersion {
+    fn from_model(file: &FileModel) -> Self {
+        Self {
+            file_id: file.id,
+            version_number: file.version_number,
+            display_name: file.display_name.clone(),
+            created_at: file.created_at.into(),
+        }
+    }
+}
+
+/// Why part of a comparison could not be computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonUnavailable {
+    /// The content of a version is not plain text.
+    UnsupportedFormat,
+    /// A version is too large to compare.
+    TooLarge,
+    /// The versions differ too much for a line-level comparison.
+    TooDifferent,
+    /// A version has no analyzed run.
+    NotAnalyzed,
+    /// The caller may not view pipeline runs.
+    NotPermitted,
+}
+
+/// Difference between the detections of two versions' latest analyses.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionsDiff {
+    /// Run whose analysis of the base version was compared.
+    pub base_run_id: RunId,
+    /// Run whose analysis of the compared version was compared.
+    pub run_id: RunId,
+    /// Added, removed and changed findings.
+    pub changes: Vec<ValueChange>,
+    /// Whether changes past the reporting limit were left out.
+    pub truncated: bool,
+}
+
+impl DetectionsDiff {
+    /// Creates a response from the two runs and their structural difference.
+    pub fn new(base_run_id: Uuid, run_id: Uuid, diff: StructuralDiff) -> Self {
+        Self {
+            base_run_id: RunId::from_uuid(base_run_id),
+            run_id: RunId::from_uuid(run_id),
+            changes: diff.changes,
+            truncated: diff.truncated,
+        }
+    }
+}
+
+/// Comparison of two versions of a file.
+///
+/// Each part is present when it could be computed; otherwise the matching
+/// `Unavailable` field says why.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileComparison {
+    /// The older version.
+    pub base: FileVersion,
+    /// The compared version.
+    pub target: FileVersion,
+    /// Line-level difference of the versions' text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextDiff>,
+    /// Why the text was not compared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_unavailable: Option<ComparisonUnavailable>,
+    /// Difference of the versions' detected findings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detections: Option<DetectionsDiff>,
+    /// Why the detections were not compared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detections_unavailable: Option<ComparisonUnavailable>,
+    /// When the comparison was computed.
+    pub compared_at: Timestamp,
+}
+
+impl FileComparison {
+    /// Creates a comparison response from its parts.
+    pub fn new(
+        base: &FileModel,
+        target: &FileModel,
+        text: Result<TextDiff, ComparisonUnavailable>,
+        detections: Result<DetectionsDiff, ComparisonUnavailable>,
+    ) -> Self {
+        Self {
+            base: FileVersion::from_model(base),
+            target: FileVersion::from_model(target),
+            text_unavailable: text.as_ref().err().copied(),
+            text: text.ok(),
+            detections_unavailable: detections.as_ref().err().copied(),
+            detections: detections.ok(),
+            compared_at: Timestamp::now(),
+        }
+    }
+}
+
+/// How often a document was accessed over a period.
+///
+/// Opens from search results are also counted as views or downloads.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema
+)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessCounts {
+    /// Times the file was opened in the workspace.
+    pub views: i64,
+    /// Times the file content was downloaded.
+    pub downloads: i64,
+    /// Opens and downloads that followed a search.
+    pub search_opens: i64,
+    /// Times the file was opened through a share link.
+    pub share_views: i64,
+}
+
+impl FileAccessCounts {
+    fn from_stats(stats: &WorkspaceFileAccessStats) -> Self {
+        Self {
+            views: i64::from(stats.view_count),
+            downloads: i64::from(stats.download_count),
+            search_opens: i64::from(stats.search_open_count),
+            share_views: i64::from(stats.share_view_count),
+        }
+    }
+
+    /// Creates counts from a summed period.
+    pub fn from_summary(summary: &WorkspaceFileAccessSummary) -> Self {
+        Self {
+            views: summary.view_count,
+            downloads: summary.download_count,
+            search_opens: summary.search_open_count,
+            share_views: summary.share_view_count,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            views: self.views + other.views,
+            downloads: self.downloads + other.downloads,
+            search_opens: self.search_opens + other.search_opens,
+            share_views: self.share_views + other.share_views,
+        }
+    }
+}
+
+/// A document's accesses on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessDay {
+    /// The day, in UTC.
+    pub date: Date,
+    /// Accesses on the day.
+    #[serde(flatten)]
+    pub counts: FileAccessCounts,
+}
+
+/// Access statistics of a document.
+///
+/// Accesses are counted per day without recording who made them, so the
+/// statistics show how much a document is read, not by whom.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessStats {
+    /// The file.
+    pub file_id: Uuid,
+    /// First day covered, in UTC; the period ends today.
+    pub since: Date,
+    /// Accesses over the period.
+    pub totals: FileAccessCounts,
+    /// Accesses per day, oldest first; days without accesses are omitted.
+    pub daily: Vec<FileAccessDay>,
+    /// Last day the file was accessed on, even before the period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_accessed_on: Option<Date>,
+}
+
+impl FileAccessStats {
+    /// Creates a response from a file's daily counters.
+    pub fn from_models(
+        file_id: Uuid,
+        since: Date,
+        stats: Vec<WorkspaceFileAccessStats>,
+        last_accessed_on: Option<Date>,
+    ) -> Self {
+        let daily: Vec<FileAccessDay> = stats
+            .iter()
+            .map(|day| FileAccessDay {
+                date: day.access_date.into(),
+                counts: FileAccessCounts::from_stats(day),
+            })
+            .collect();
+        let totals = daily
+            .iter()
+            .fold(FileAccessCounts::default(), |totals, day| {
+                totals.add(day.counts)
+            });
+
+        Self {
+            file_id,
+            since,
+            totals,
+            daily,
+            last_accessed_on,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Workspace inbound webhook response types.
+
+use jiff::Timestamp;
+use nvisy_postgres::model::WorkspaceInboundWebhook;
+use nvisy_postgres::types::{InboundWebhookId, InboundWebhookProvider, Slug, Username};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::Page;
+use crate::service::InboundReceipt;
+
+/// Workspace inbound webhook response.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundWebhook {
+    /// Opaque identifier of the inbound webhook.
+    pub id: InboundWebhookId,
+    /// Slug of the workspace the events are routed to.
+    pub workspace_slug: Slug,
+    /// Service sending the requests.
+    pub provider: InboundWebhookProvider,
+    /// Human-readable name for the inbound webhook.
+    pub display_name: String,
+    /// Path the service sends its requests to, relative to the API.
+    pub path: String,
+    /// Timestamp of the last accepted request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_received_at: Option<Timestamp>,
+    /// Handle of the account that created this inbound webhook.
+    pub creator_username: Username,
+    /// Timestamp when this inbound webhook was created.
+    pub created_at: Timestamp,
+    /// Timestamp when this inbound webhook was last modified.
+    pub updated_at: Timestamp,
+}
+
+impl InboundWebhook {
+    pub fn from_model(
+        inbound_webhook: WorkspaceInboundWebhook,
+        workspace_slug: Slug,
+        creator_username: Username,
+    ) -> Self {
+        let id = InboundWebhookId::from_uuid(inbound_webhook.id);
+
+        Self {
+            path: format!("/inbound/{id}/"),
+            id,
+            workspace_slug,
+            provider: inbound_webhook.provider,
+            display_name: inbound_webhook.display_name,
+            last_received_at: inbound_webhook.last_received_at.map(Into::into),
+            creator_username,
+            created_at: inbound_webhook.created_at.into(),
+            updated_at: inbound_webhook.updated_at.into(),
+        }
+    }
+}
+
+/// Inbound webhook creation response that includes the secret (visible only
+/// once).
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundWebhookCreated {
+    /// The created inbound webhook details.
+    #[serde(flatten)]
+    pub inbound_webhook: InboundWebhook,
+    /// Secret the service authenticates its requests with.
+    ///
+    /// **Important**: This is the only time the secret will be shown.
+    /// Configure it in the service, as it cannot be retrieved again.
+    pub secret: String,
+}
+
+impl InboundWebhookCreated {
+    pub fn from_model(
+        inbound_webhook: WorkspaceInboundWebhook,
+        workspace_slug: Slug,
+        creator_username: Username,
+        secret: String,
+    ) -> Self {
+        Self {
+            inbound_webhook: InboundWebhook::from_model(
+                inbound_webhook,
+                workspace_slug,
+                creator_username,
+            ),
+            secret,
+        }
+    }
+}
+
+/// Paginated response for workspace inbound webhooks.
+pub type InboundWebhooksPage = Page<InboundWebhook>;
+
+/// Acknowledgement of a request accepted by an inbound webhook.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundDeliveryAccepted {
+    /// The service's id of the delivery.
+    pub delivery_id: String,
+    /// The service's name for the event.
+    pub event_type: String,
+    /// Whether the delivery was accepted before and not routed again.
+    pub duplicate: bool,
+}
+
+impl From<InboundReceipt> for InboundDeliveryAccepted {
+    fn from(receipt: InboundReceipt) -> Self {
+        Self {
+            delivery_id: receipt.delivery_id,
+            event_type: receipt.event_type,
+            duplicate: receipt.duplicate,
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use jiff::Timestamp;
 use nvisy_postgres::model::{Account, WorkspaceMember};
-use nvisy_postgres::types::{Username, WorkspaceRole};
+use nvisy_postgres::types::{RoleId, Username, WorkspaceRole};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +22,9 @@ pub struct Member {
     pub display_name: Option<String>,
     /// Role of the member in the workspace.
     pub member_role: WorkspaceRole,
+    /// Custom role granted on top of the built-in role, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_role_id: Option<RoleId>,
     /// Whether the member has two-factor authentication enabled.
     pub has_2fa: bool,
     /// Timestamp when the member joined the workspace.
@@ -37,6 +40,7 @@ impl Member {
             email_address: account.email_address,
             display_name: account.display_name,
             member_role: member.member_role,
+            custom_role_id: member.custom_role_id.map(RoleId::from_uuid),
             has_2fa: false,
             created_at: member.created_at.into(),
         }
@@ -6,38 +6,58 @@ use serde::{Deserialize, Serialize};
 
 mod accounts;
 mod activities;
+mod analytics;
+mod api_keys;
 mod artifacts;
 mod authentications;
 mod connections;
 mod contexts;
 mod errors;
 mod files;
+mod inbound_webhooks;
 mod invites;
 mod members;
 mod monitors;
 mod notifications;
+mod operations;
 mod pipelines;
 mod policies;
+mod retention;
+mod reviews;
+mod roles;
 mod runs;
+mod shares;
+mod sparse;
+mod storage;
 mod tokens;
 mod webhooks;
 mod workspaces;
 
 pub use accounts::*;
 pub use activities::*;
+pub use analytics::*;
+pub use api_keys::*;
 pub use artifacts::*;
 pub use authentications::*;
 pub use connections::*;
 pub use contexts::*;
 pub use errors::*;
 pub use files::*;
+pub use inbound_webhooks::*;
 pub use invites::*;
 pub use members::*;
 pub use monitors::*;
 pub use notifications::*;
+pub use operations::*;
 pub use pipelines::*;
 pub use policies::*;
+pub use retention::*;
+pub use reviews::*;
+pub use roles::*;
 pub use runs::*;
+pub use shares::*;
+pub use sparse::*;
+pub use storage::*;
 pub use tokens::*;
 pub use webhooks::*;
 pub use workspaces::*;
@@ -1,5 +1,7 @@
 //! Monitor response types.
 
+use std::borrow::Cow;
+
 use jiff::Timestamp;
 use nvisy_core::health::{ComponentHealth, HealthStatus};
 use schemars::JsonSchema;
@@ -11,9 +13,38 @@ use serde::Serialize;
 pub struct Health {
     /// Overall service status.
     pub status: HealthStatus,
-    /// Per-component health checks.
-    pub checks: Vec<ComponentHealth>,
+    /// Per-component health checks, with their dependencies resolved.
+    pub checks: Vec<ComponentNode>,
     /// RFC 3339 timestamp of when the check was performed.
     #[schemars(with = "String")]
     pub timestamp: Timestamp,
 }
+
+/// A component in the health dependency graph.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentNode {
+    /// The component's own check result and declared dependencies.
+    #[serde(flatten)]
+    pub component: ComponentHealth,
+    /// Status after accounting for the health of its dependencies.
+    pub effective_status: HealthStatus,
+    /// Unhealthy or degraded components at the root of the effective
+    /// status, when it is worse than the component's own.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<HealthCause>,
+    /// Human-readable explanation of the effective status, e.g.
+    /// `"webhook degraded because nats is unhealthy"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// A root cause of a component's effective status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCause {
+    /// Name of the failing component.
+    pub component: Cow<'static, str>,
+    /// Its own reported status.
+    pub status: HealthStatus,
+}
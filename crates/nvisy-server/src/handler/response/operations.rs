@@ -0,0 +1,109 @@
+//! Long-running operation response types.
+
+use jiff::Timestamp;
+use nvisy_postgres::model::WorkspaceOperation;
+use nvisy_postgres::types::{OperationId, OperationKind, OperationStatus};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Page;
+use crate::service::DownloadLink;
+
+/// Response type for a long-running operation.
+///
+/// Poll until `status` is terminal: a `succeeded` operation carries `result`,
+/// a `failed` one carries `error` in the same shape as an HTTP error body.
+/// Finished operations are kept until `expiresAt`; a succeeded operation that
+/// produced a file exposes it as `artifact`, with a short-lived download link.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    /// Opaque identifier of the operation.
+    pub id: OperationId,
+    /// Workspace the operation runs in.
+    pub workspace_id: Uuid,
+    /// What the operation does.
+    pub kind: OperationKind,
+    /// Current lifecycle status.
+    pub status: OperationStatus,
+    /// Completion percentage (0-100).
+    pub progress: i16,
+    /// Resource the operation acts on or produces, if known yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<Uuid>,
+    /// Outcome of a succeeded operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// Error of a failed operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<serde_json::Value>,
+    /// File produced by the operation, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<ArtifactDownload>,
+    /// When the operation was accepted.
+    pub created_at: Timestamp,
+    /// When the operation was last updated.
+    pub updated_at: Timestamp,
+    /// When the operation started running.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<Timestamp>,
+    /// When the operation finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<Timestamp>,
+    /// When the operation and its artifact will be removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Response type for an operation's artifact.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactDownload {
+    /// File name offered to the client.
+    pub name: String,
+    /// Media type of the file.
+    pub content_type: String,
+    /// Size of the file in bytes.
+    pub size: i64,
+    /// Signed link to download the file; needs no other credentials.
+    pub url: String,
+    /// When the link stops working.
+    pub url_expires_at: Timestamp,
+}
+
+/// Paginated response for operations.
+pub type OperationsPage = Page<Operation>;
+
+impl Operation {
+    /// Renders an operation, with `download` as its artifact's link when it
+    /// has one that can still be downloaded.
+    pub fn from_model(operation: WorkspaceOperation, download: Option<DownloadLink>) -> Self {
+        let artifact = download.and_then(|link| {
+            Some(ArtifactDownload {
+                name: operation.artifact_name.clone()?,
+                content_type: operation.artifact_content_type.clone()?,
+                size: operation.artifact_size?,
+                url: link.url,
+                url_expires_at: link.expires_at,
+            })
+        });
+
+        Self {
+            id: OperationId::from_uuid(operation.id),
+            workspace_id: operation.workspace_id,
+            kind: operation.kind,
+            status: operation.status,
+            progress: operation.progress,
+            target_id: operation.target_id,
+            result: operation.result,
+            error: operation.error,
+            artifact,
+            created_at: operation.created_at.into(),
+            updated_at: operation.updated_at.into(),
+            started_at: operation.started_at.map(Into::into),
+            completed_at: operation.completed_at.map(Into::into),
+            expires_at: operation.expires_at.map(Into::into),
+        }
+    }
+}
@@ -7,7 +7,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::{Artifact, Page};
-use crate::handler::request::PipelineDefinition;
+use crate::handler::request::{ArtifactRetention, PipelineDefinition};
 
 /// Pipeline response.
 #[must_use]
@@ -29,6 +29,8 @@ pub struct Pipeline {
     pub status: PipelineStatus,
     /// Detection + redaction configuration.
     pub definition: PipelineDefinition,
+    /// How long the artifacts of each stage are kept.
+    pub artifact_retention: ArtifactRetention,
     /// Artifacts produced by pipeline runs.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub artifacts: Vec<Artifact>,
@@ -90,6 +92,7 @@ impl Pipeline {
         policy_slugs: Vec<Slug>,
         context_slugs: Vec<Slug>,
     ) -> serde_json::Result<Self> {
+        let artifact_retention = ArtifactRetention::from_model(&pipeline);
         let definition =
             PipelineDefinition::from_parts(pipeline.definition, policy_slugs, context_slugs)?;
         Ok(Self {
@@ -100,6 +103,7 @@ impl Pipeline {
             description: pipeline.description,
             status: pipeline.status,
             definition,
+            artifact_retention,
             artifacts,
             created_at: pipeline.created_at.into(),
             updated_at: pipeline.updated_at.into(),
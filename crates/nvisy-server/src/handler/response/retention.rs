@@ -0,0 +1,156 @@
+//! Retention policy and legal hold response types.
+
+use jiff::Timestamp;
+use jiff::civil::Date;
+use nvisy_postgres::model::{
+    WorkspaceFileAccessSummary, WorkspaceLegalHold, WorkspaceRetentionPolicy,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::FileAccessCounts;
+use crate::handler::request::AccessRanking;
+use crate::service::RetentionReport;
+
+/// Response type for a workspace's retention policy.
+///
+/// A workspace without a policy reports every period as unset.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    /// Days files are kept after upload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_retention_days: Option<i32>,
+    /// Days finished pipeline runs and their analyses are kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_retention_days: Option<i32>,
+    /// Days audit records are kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_retention_days: Option<i32>,
+    /// When the policy was last changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<Timestamp>,
+}
+
+impl RetentionPolicy {
+    /// Creates a response from the workspace's policy, if it has one.
+    pub fn from_model(policy: Option<WorkspaceRetentionPolicy>) -> Self {
+        let Some(policy) = policy else {
+            return Self::default();
+        };
+
+        Self {
+            file_retention_days: policy.file_retention_days,
+            run_retention_days: policy.run_retention_days,
+            activity_retention_days: policy.activity_retention_days,
+            updated_at: Some(policy.updated_at.into()),
+        }
+    }
+}
+
+/// Response type for a dry run of the retention purge.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPreview {
+    /// Files that would be deleted, including soft-deleted ones.
+    pub files: u64,
+    /// Finished pipeline runs that would be deleted.
+    pub pipeline_runs: u64,
+    /// Files storing pipeline run artifacts that would be deleted.
+    pub artifacts: u64,
+    /// Audit records that would be deleted.
+    pub activities: u64,
+}
+
+impl From<RetentionReport> for RetentionPreview {
+    fn from(report: RetentionReport) -> Self {
+        Self {
+            files: report.files,
+            pipeline_runs: report.pipeline_runs,
+            artifacts: report.artifacts,
+            activities: report.activities,
+        }
+    }
+}
+
+/// Response type for a legal hold.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalHold {
+    /// Unique hold identifier.
+    pub id: Uuid,
+    /// Held file; absent when the whole workspace is held.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<Uuid>,
+    /// Why the data is held.
+    pub reason: String,
+    /// When the hold was placed.
+    pub created_at: Timestamp,
+    /// When the hold was released.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub released_at: Option<Timestamp>,
+}
+
+/// List of legal holds.
+pub type LegalHolds = Vec<LegalHold>;
+
+impl LegalHold {
+    /// Creates a response from a database model.
+    pub fn from_model(hold: WorkspaceLegalHold) -> Self {
+        Self {
+            id: hold.id,
+            file_id: hold.file_id,
+            reason: hold.reason,
+            created_at: hold.created_at.into(),
+            released_at: hold.released_at.map(Into::into),
+        }
+    }
+}
+
+/// A file's place in an access report.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessReportEntry {
+    /// The file.
+    pub file_id: Uuid,
+    /// Display name of the file.
+    pub display_name: String,
+    /// When the file was uploaded.
+    pub uploaded_at: Timestamp,
+    /// Accesses over the report's period.
+    pub accesses: FileAccessCounts,
+    /// Last day the file was accessed on; absent if it never was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_accessed_on: Option<Date>,
+}
+
+impl FileAccessReportEntry {
+    /// Creates an entry from a file's summed counters.
+    pub fn from_model(summary: WorkspaceFileAccessSummary) -> Self {
+        Self {
+            file_id: summary.file_id,
+            accesses: FileAccessCounts::from_summary(&summary),
+            display_name: summary.display_name,
+            uploaded_at: summary.created_at.into(),
+            last_accessed_on: summary.last_access_date.map(Into::into),
+        }
+    }
+}
+
+/// Response type for a workspace's most or least accessed files.
+///
+/// Listing the least accessed files shows content that nobody reads, which
+/// is a candidate for a shorter retention period.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessReport {
+    /// First day covered, in UTC; the period ends today.
+    pub since: Date,
+    /// Which end of the ranking is listed.
+    pub ranking: AccessRanking,
+    /// Files in ranking order.
+    pub files: Vec<FileAccessReportEntry>,
+}
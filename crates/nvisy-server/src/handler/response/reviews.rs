@@ -0,0 +1,117 @@
+//! Detection review response types.
+
+use jiff::Timestamp;
+use nvisy_postgres::model::{WorkspaceDetectionReview, WorkspacePipelineRun};
+use nvisy_postgres::types::{ReviewStatus, RunId, Username};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Response type for one version of a detection's review.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionReview {
+    /// Identifier of the detection within the run's analysis.
+    pub detection_id: String,
+    /// Version of the detection's review, starting at 1.
+    pub version: i32,
+    /// Decision recorded by this version.
+    pub decision: ReviewStatus,
+    /// The decision while current, `superseded` once replaced.
+    pub status: ReviewStatus,
+    /// Handle of the reviewer, if the account still exists.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewer_username: Option<Username>,
+    /// Reviewer comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// When the decision was recorded.
+    pub reviewed_at: Timestamp,
+    /// When a later version replaced this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub superseded_at: Option<Timestamp>,
+}
+
+impl DetectionReview {
+    /// Creates a response from a database model and the reviewer's handle.
+    pub fn from_model(
+        review: WorkspaceDetectionReview,
+        reviewer_username: Option<Username>,
+    ) -> Self {
+        Self {
+            detection_id: review.detection_id,
+            version: review.version,
+            decision: review.decision,
+            status: review.status,
+            reviewer_username,
+            comment: review.comment,
+            reviewed_at: review.created_at.into(),
+            superseded_at: review.superseded_at.map(Into::into),
+        }
+    }
+}
+
+/// Response type for the current review decisions of a run.
+///
+/// Detections of the run's analysis that appear here are decided; all others
+/// are still proposed.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionReviews {
+    /// Opaque identifier of the run.
+    pub run_id: RunId,
+    /// Current decision per reviewed detection, ordered by detection.
+    pub reviews: Vec<DetectionReview>,
+}
+
+impl DetectionReviews {
+    /// Creates a response from the run's current reviews.
+    pub fn from_models(
+        run: &WorkspacePipelineRun,
+        reviews: Vec<(WorkspaceDetectionReview, Option<Username>)>,
+    ) -> Self {
+        Self {
+            run_id: RunId::from_uuid(run.id),
+            reviews: reviews
+                .into_iter()
+                .map(|(review, username)| DetectionReview::from_model(review, username))
+                .collect(),
+        }
+    }
+}
+
+/// Response type for the full review history of one detection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionReviewHistory {
+    /// Opaque identifier of the run.
+    pub run_id: RunId,
+    /// Identifier of the detection within the run's analysis.
+    pub detection_id: String,
+    /// Recognizer configuration (models, patterns and prompts) the run
+    /// detected with, as recorded when the run started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposed_by: Option<serde_json::Value>,
+    /// Every version of the review, oldest first.
+    pub versions: Vec<DetectionReview>,
+}
+
+impl DetectionReviewHistory {
+    /// Creates a response from the run, the configuration that proposed the
+    /// detection, and the detection's review versions.
+    pub fn from_models(
+        run: &WorkspacePipelineRun,
+        detection_id: String,
+        proposed_by: Option<serde_json::Value>,
+        versions: Vec<(WorkspaceDetectionReview, Option<Username>)>,
+    ) -> Self {
+        Self {
+            run_id: RunId::from_uuid(run.id),
+            detection_id,
+            proposed_by,
+            versions: versions
+                .into_iter()
+                .map(|(review, username)| DetectionReview::from_model(review, username))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Role and permission response types.
+
+use jiff::Timestamp;
+use nvisy_postgres::model::WorkspaceCustomRole;
+use nvisy_postgres::types::{RoleId, WorkspaceRole};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::service::rbac::{Action, BuiltinRole, Permission, ResourceType};
+
+/// Definition of a permission.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionDefinition {
+    /// Permission key (`resource:action`).
+    pub key: Permission,
+    /// Kind of resource the permission applies to.
+    pub resource_type: ResourceType,
+    /// Action the permission allows.
+    pub action: Action,
+    /// Lowest built-in role holding the permission.
+    pub minimum_role: WorkspaceRole,
+    /// Whether a custom role may grant the permission.
+    pub assignable: bool,
+}
+
+impl From<Permission> for PermissionDefinition {
+    fn from(permission: Permission) -> Self {
+        Self {
+            key: permission,
+            resource_type: permission.resource_type(),
+            action: permission.action(),
+            minimum_role: permission.minimum_required_role(),
+            assignable: permission.is_assignable(),
+        }
+    }
+}
+
+/// Response type for a built-in role.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltinRoleDefinition {
+    /// Stored role value, as used by members and invites.
+    pub role: WorkspaceRole,
+    /// Display name.
+    pub name: String,
+    /// Short description.
+    pub description: String,
+    /// Granted permissions.
+    pub permissions: Vec<Permission>,
+}
+
+impl From<BuiltinRole> for BuiltinRoleDefinition {
+    fn from(role: BuiltinRole) -> Self {
+        Self {
+            role: role.workspace_role(),
+            name: role.name().to_owned(),
+            description: role.description().to_owned(),
+            permissions: role.permissions(),
+        }
+    }
+}
+
+/// Response type for a custom role.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Role {
+    /// Opaque identifier of the role.
+    pub id: RoleId,
+    /// Role name, unique within the workspace.
+    pub name: String,
+    /// Role description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Permissions granted on top of the member's built-in role.
+    pub permissions: Vec<Permission>,
+    /// When the role was created.
+    pub created_at: Timestamp,
+    /// When the role was last updated.
+    pub updated_at: Timestamp,
+}
+
+impl Role {
+    /// Creates a response from a database model.
+    pub fn from_model(role: WorkspaceCustomRole) -> Self {
+        // Keys no longer defined are ignored, as they are when authorizing.
+        let permissions = role
+            .permissions
+            .iter()
+            .filter_map(|key| key.parse().ok())
+            .collect();
+
+        Self {
+            id: RoleId::from_uuid(role.id),
+            name: role.name,
+            description: role.description,
+            permissions,
+            created_at: role.created_at.into(),
+            updated_at: role.updated_at.into(),
+        }
+    }
+}
+
+/// Response type for the roles of a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Roles {
+    /// Built-in roles, from most to least privileged.
+    pub builtin: Vec<BuiltinRoleDefinition>,
+    /// Custom roles defined by the workspace, ordered by name.
+    pub custom: Vec<Role>,
+}
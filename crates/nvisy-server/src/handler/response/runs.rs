@@ -1,13 +1,15 @@
 //! Pipeline run response types.
 
 use jiff::Timestamp;
-use nvisy_postgres::model::WorkspacePipelineRun as PipelineRunModel;
+use nvisy_postgres::model::{
+    WorkspacePipelineArtifact as ArtifactModel, WorkspacePipelineRun as PipelineRunModel,
+};
 use nvisy_postgres::types::{PipelineRunStatus, PipelineTriggerType, RunId, Slug, Username};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::Page;
+use super::{Artifact, Page};
 
 /// Response type for a pipeline run.
 ///
@@ -36,6 +38,11 @@ pub struct PipelineRun {
     pub status: PipelineRunStatus,
     /// Non-encrypted metadata for filtering/display.
     pub metadata: serde_json::Value,
+    /// Hex SHA-256 of the inputs the analysis depends on, once analyzed.
+    ///
+    /// Runs with the same digest reproduce the same findings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_digest: Option<String>,
     /// When the run started.
     pub started_at: Timestamp,
     /// When the run completed.
@@ -63,9 +70,46 @@ impl PipelineRun {
             trigger_username,
             trigger_type: run.trigger_type,
             status: run.status,
+            input_digest: run.input_digest_hex(),
             metadata: run.metadata,
             started_at: run.started_at.into(),
             completed_at: run.completed_at.map(Into::into),
         }
     }
 }
+
+/// Response type for one output set of a file.
+///
+/// An output set is what an analyzed run produced: its findings, addressed by
+/// the digest of their inputs, and the artifacts recorded for the run. Output
+/// sets are immutable; rerunning a file adds a new one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputSet {
+    /// Run that produced the output set.
+    pub run_id: RunId,
+    /// Hex SHA-256 of the inputs the findings depend on.
+    pub input_digest: String,
+    /// Current status of the run.
+    pub status: PipelineRunStatus,
+    /// Artifacts recorded for the run, oldest first.
+    pub artifacts: Vec<Artifact>,
+    /// When the run started.
+    pub started_at: Timestamp,
+}
+
+/// Response for a file's output sets, most recent first.
+pub type OutputSets = Vec<OutputSet>;
+
+impl OutputSet {
+    /// Creates an output set response from an analyzed run and its artifacts.
+    pub fn from_model(run: PipelineRunModel, artifacts: Vec<ArtifactModel>) -> Self {
+        Self {
+            run_id: RunId::from_uuid(run.id),
+            input_digest: run.input_digest_hex().unwrap_or_default(),
+            status: run.status,
+            artifacts: artifacts.into_iter().map(Artifact::from_model).collect(),
+            started_at: run.started_at.into(),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Share link response types.
+
+use jiff::Timestamp;
+use nvisy_postgres::model::WorkspaceFileShare;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Whether a share link can still be opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareLinkStatus {
+    /// The link can be opened.
+    Active,
+    /// The link has been opened as many times as it allows.
+    Exhausted,
+    /// The link has expired.
+    Expired,
+    /// The link was revoked.
+    Revoked,
+}
+
+impl ShareLinkStatus {
+    /// Returns the status of a share link.
+    pub fn of(share: &WorkspaceFileShare) -> Self {
+        if share.is_revoked() {
+            Self::Revoked
+        } else if share.is_expired() {
+            Self::Expired
+        } else if share.is_exhausted() {
+            Self::Exhausted
+        } else {
+            Self::Active
+        }
+    }
+}
+
+/// A link granting read access to a single document.
+///
+/// The token is never returned after creation; `tokenPrefix` identifies the
+/// link in listings.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLink {
+    /// Unique identifier of the link.
+    pub id: Uuid,
+    /// Shared file.
+    pub file_id: Uuid,
+    /// Who the link was issued to.
+    pub recipient: String,
+    /// Pages the link grants; omitted when it grants the whole document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<Vec<u32>>,
+    /// Whether served copies are watermarked with the recipient.
+    pub watermark: bool,
+    /// Leading characters of the link's token.
+    pub token_prefix: String,
+    /// Whether the link can still be opened.
+    pub status: ShareLinkStatus,
+    /// Number of times the link may be opened (omitted = unlimited).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_views: Option<i32>,
+    /// Number of times the link has been opened.
+    pub view_count: i32,
+    /// Account that created the link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<Uuid>,
+    /// When the link was created.
+    pub created_at: Timestamp,
+    /// When the link expires.
+    pub expires_at: Timestamp,
+    /// When the link was last opened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_viewed_at: Option<Timestamp>,
+    /// When the link was revoked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<Timestamp>,
+}
+
+/// Response type for listing a file's share links.
+pub type ShareLinks = Vec<ShareLink>;
+
+impl ShareLink {
+    /// Creates a response from a database model.
+    pub fn from_model(share: WorkspaceFileShare) -> Self {
+        Self {
+            status: ShareLinkStatus::of(&share),
+            pages: share.granted_pages(),
+            id: share.id,
+            file_id: share.file_id,
+            recipient: share.recipient,
+            watermark: share.watermark,
+            token_prefix: share.token_prefix,
+            max_views: share.max_views,
+            view_count: share.view_count,
+            created_by: share.account_id,
+            created_at: share.created_at.into(),
+            expires_at: share.expires_at.into(),
+            last_viewed_at: share.last_viewed_at.map(Into::into),
+            revoked_at: share.revoked_at.map(Into::into),
+        }
+    }
+}
+
+/// Share link with its token (only returned on creation).
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLinkCreated {
+    /// The created link details.
+    #[serde(flatten)]
+    pub share_link: ShareLink,
+    /// The link's token.
+    ///
+    /// **Important**: This is the only time the token will be shown.
+    pub token: String,
+    /// Path at which the recipient opens the document, relative to the API.
+    pub path: String,
+}
+
+impl ShareLinkCreated {
+    pub fn from_model(share: WorkspaceFileShare, token: String) -> Self {
+        Self {
+            share_link: ShareLink::from_model(share),
+            path: format!("/shares/{token}/"),
+            token,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Sparse fieldset response types.
+//!
+//! [`Sparse`] drops the unselected fields of a response item as it is
+//! serialized, so handlers keep building full response types and the
+//! selection is applied in one place.
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+
+use super::Page;
+
+/// Validated set of fields selected with `?fields=`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSet(BTreeSet<String>);
+
+impl FieldSet {
+    /// Creates a set from field names already checked against the resource.
+    pub(crate) fn new<I, S>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(fields.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns whether `field` is selected.
+    pub fn contains(&self, field: &str) -> bool {
+        self.0.contains(field)
+    }
+}
+
+/// A response item serialized with only the selected fields.
+///
+/// Without a selection the item is serialized in full. The documented
+/// schema is that of the full item; every field may be left out.
+#[derive(Debug, Clone)]
+pub struct Sparse<T> {
+    item: T,
+    fields: Option<Arc<FieldSet>>,
+}
+
+impl<T> Sparse<T> {
+    /// Wraps `item`, keeping only `fields` if any are selected.
+    pub fn new(item: T, fields: Option<Arc<FieldSet>>) -> Self {
+        Self { item, fields }
+    }
+}
+
+impl<T: Serialize> Serialize for Sparse<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(fields) = &self.fields else {
+            return self.item.serialize(serializer);
+        };
+
+        let mut value = serde_json::to_value(&self.item).map_err(S::Error::custom)?;
+        if let Some(object) = value.as_object_mut() {
+            object.retain(|field, _| fields.contains(field));
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Sparse<T> {
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        T::json_schema(generator)
+    }
+}
+
+impl<T> Page<T> {
+    /// Keeps only `fields` of every item, if any are selected.
+    pub fn select(self, fields: Option<Arc<FieldSet>>) -> Page<Sparse<T>> {
+        self.map(|item| Sparse::new(item, fields.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Item {
+        id: u32,
+        display_name: &'static str,
+    }
+
+    #[test]
+    fn serializes_selected_fields() {
+        let page = Page::new(
+            vec![Item {
+                id: 1,
+                display_name: "report.pdf",
+            }],
+            None,
+            Some("next".to_owned()),
+        );
+
+        let fields = Arc::new(FieldSet::new(["id"]));
+        let value = serde_json::to_value(page.select(Some(fields))).unwrap();
+        assert_eq!(
+            value,
+            json!({ "items": [{ "id": 1 }], "nextCursor": "next" })
+        );
+    }
+
+    #[test]
+    fn serializes_everything_without_selection() {
+        let item = Item {
+            id: 2,
+            display_name: "scan.png",
+        };
+        let value = serde_json::to_value(Sparse::new(item, None)).unwrap();
+        assert_eq!(value, json!({ "id": 2, "displayName": "scan.png" }));
+    }
+}
@@ -0,0 +1,69 @@
+//! Administrator object storage response types.
+
+use nvisy_nats::object::{BucketLifecycle, DeleteOutput};
+use nvisy_postgres::types::{DataRegion, StorageStage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Response type for a bulk deletion of stored objects.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCleanup {
+    /// Pipeline stage whose objects were deleted.
+    pub stage: StorageStage,
+    /// Objects that matched, across every region.
+    pub matched: u64,
+    /// Objects deleted; zero for a dry run.
+    pub deleted: u64,
+    /// Total size of the matched objects, in bytes.
+    pub bytes: u64,
+    /// Whether the objects were only counted.
+    pub dry_run: bool,
+}
+
+impl StorageCleanup {
+    /// Creates a response from the totals of the deletion.
+    pub fn from_output(stage: StorageStage, output: DeleteOutput) -> Self {
+        Self {
+            stage,
+            matched: output.matched,
+            deleted: output.deleted,
+            bytes: output.bytes,
+            dry_run: output.dry_run,
+        }
+    }
+}
+
+/// Expiration of one object bucket in one data region.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageLifecycle {
+    /// Data region of the bucket.
+    pub region: DataRegion,
+    /// Bucket name.
+    pub bucket: String,
+    /// Age in seconds at which objects should expire; absent if they never do.
+    pub declared_max_age_secs: Option<u64>,
+    /// Age in seconds at which the server expires objects; absent if it never does.
+    pub applied_max_age_secs: Option<u64>,
+    /// Whether the server expires the bucket's objects as declared.
+    pub applied: bool,
+}
+
+impl StorageLifecycle {
+    /// Creates a response from a bucket's lifecycle in a region.
+    pub fn from_lifecycle(region: DataRegion, lifecycle: BucketLifecycle) -> Self {
+        Self {
+            region,
+            bucket: lifecycle.bucket.to_owned(),
+            declared_max_age_secs: lifecycle.declared_max_age.map(|age| age.as_secs()),
+            applied_max_age_secs: lifecycle.applied_max_age.map(|age| age.as_secs()),
+            applied: lifecycle.is_applied(),
+        }
+    }
+}
+
+/// Response type for the object bucket lifecycles of every region.
+pub type StorageLifecycles = Vec<StorageLifecycle>;
@@ -3,10 +3,12 @@
 use std::collections::HashMap;
 
 use jiff::Timestamp;
-use nvisy_postgres::model;
+use jiff::civil::Date;
+use nvisy_postgres::model::{self, WorkspaceWebhookDeliveryStats};
 use nvisy_postgres::types::{Slug, Username, WebhookEvent, WebhookId, WebhookStatus};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use super::Page;
 
@@ -27,6 +29,8 @@ pub struct Webhook {
     pub url: String,
     /// List of event types this webhook receives.
     pub events: Vec<WebhookEvent>,
+    /// JSONPath queries an event's payload must all match to be delivered.
+    pub payload_filters: Vec<String>,
     /// Custom headers included in webhook requests.
     pub headers: HashMap<String, String>,
     /// Current status of the webhook.
@@ -49,6 +53,7 @@ impl Webhook {
         creator_username: Username,
     ) -> Self {
         let events = webhook.subscribed_events();
+        let payload_filters = webhook.payload_filters();
         let headers = webhook.parsed_headers();
 
         Self {
@@ -58,6 +63,7 @@ impl Webhook {
             description: webhook.description,
             url: webhook.url,
             events,
+            payload_filters,
             headers,
             status: webhook.status,
             last_triggered_at: webhook.last_triggered_at.map(Into::into),
@@ -113,11 +119,17 @@ pub struct WebhookResult {
     pub status_code: u16,
     /// Time taken to receive a response in milliseconds.
     pub response_time_ms: i64,
+    /// Whether the webhook's payload filters match the test payload; events
+    /// with such a payload would be delivered.
+    pub filters_matched: bool,
 }
 
 impl WebhookResult {
     /// Creates a WebhookResult from the core webhook response.
-    pub fn from_response(response: nvisy_webhook::provider::WebhookResponse) -> Self {
+    pub fn from_response(
+        response: nvisy_webhook::provider::WebhookResponse,
+        filters_matched: bool,
+    ) -> Self {
         let duration_ms = response
             .duration()
             .total(jiff::Unit::Millisecond)
@@ -126,6 +138,97 @@ impl WebhookResult {
         Self {
             status_code: response.status_code,
             response_time_ms: duration_ms,
+            filters_matched,
+        }
+    }
+}
+
+/// A webhook's delivery counts.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryCounts {
+    /// Requests the endpoint accepted with a 2xx status.
+    pub succeeded: i64,
+    /// Delivery attempts that failed or returned another status; a retried
+    /// request counts once per attempt.
+    pub failed: i64,
+    /// Events not delivered because the payload filters did not match.
+    pub filtered: i64,
+    /// Average response time of the successful deliveries, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_response_time_ms: Option<i64>,
+}
+
+/// A webhook's deliveries on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryDay {
+    /// The day, in UTC.
+    pub date: Date,
+    /// Deliveries on the day.
+    #[serde(flatten)]
+    pub counts: WebhookDeliveryCounts,
+}
+
+/// Delivery statistics of a webhook.
+#[must_use]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryStats {
+    /// The webhook.
+    pub webhook_id: WebhookId,
+    /// First day covered, in UTC; the period ends today.
+    pub since: Date,
+    /// Deliveries over the period.
+    pub totals: WebhookDeliveryCounts,
+    /// Deliveries per day, oldest first; days without deliveries are omitted.
+    pub daily: Vec<WebhookDeliveryDay>,
+}
+
+impl WebhookDeliveryStats {
+    /// Creates a response from a webhook's daily counters.
+    pub fn from_models(
+        webhook_id: Uuid,
+        since: Date,
+        stats: Vec<WorkspaceWebhookDeliveryStats>,
+    ) -> Self {
+        let daily = stats
+            .iter()
+            .map(|day| WebhookDeliveryDay {
+                date: day.delivery_date.into(),
+                counts: WebhookDeliveryCounts {
+                    succeeded: i64::from(day.success_count),
+                    failed: i64::from(day.failure_count),
+                    filtered: i64::from(day.filtered_count),
+                    average_response_time_ms: day.average_response_time_ms(),
+                },
+            })
+            .collect();
+
+        let succeeded: i64 = stats.iter().map(|day| i64::from(day.success_count)).sum();
+        let response_time_ms: i64 = stats.iter().map(|day| day.response_time_ms).sum();
+        let totals = WebhookDeliveryCounts {
+            succeeded,
+            failed: stats.iter().map(|day| i64::from(day.failure_count)).sum(),
+            filtered: stats.iter().map(|day| i64::from(day.filtered_count)).sum(),
+            average_response_time_ms: (succeeded > 0).then(|| response_time_ms / succeeded),
+        };
+
+        Self {
+            webhook_id: WebhookId::from_uuid(webhook_id),
+            since,
+            totals,
+            daily,
         }
     }
 }
@@ -2,7 +2,7 @@
 
 use jiff::Timestamp;
 use nvisy_postgres::model;
-use nvisy_postgres::types::{NotificationEvent, Slug, Username, WorkspaceRole};
+use nvisy_postgres::types::{DataRegion, NotificationEvent, Slug, Username, WorkspaceRole};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +24,8 @@ pub struct Workspace {
     pub tags: Vec<String>,
     /// Whether approval is required to processed files to be visible.
     pub require_approval: bool,
+    /// Region the workspace's files are stored and processed in.
+    pub data_region: DataRegion,
     /// Handle of the account that created this workspace.
     pub creator_username: Username,
     /// Role of the member in the workspace.
@@ -44,6 +46,7 @@ impl Workspace {
             description: workspace.description,
             tags,
             require_approval: workspace.require_approval,
+            data_region: workspace.data_region,
             creator_username,
             member_role: WorkspaceRole::Owner,
             created_at: workspace.created_at.into(),
@@ -64,6 +67,7 @@ impl Workspace {
             description: workspace.description,
             tags,
             require_approval: workspace.require_approval,
+            data_region: workspace.data_region,
             creator_username,
             member_role: member.member_role,
             created_at: workspace.created_at.into(),
@@ -0,0 +1,458 @@
+//! Workspace retention policy and legal hold handlers.
+//!
+//! A workspace's retention policy decides how long its files, finished runs
+//! and audit records are kept before the background purge deletes them.
+//! Legal holds suspend that purge, for the whole workspace or for a single
+//! file, until they are released, and block deleting what they cover.
+//! Released holds are kept as a record, and every placement and release is
+//! appended to the workspace audit log.
+//!
+//! The access report ranks files by how often they were read, so content
+//! nobody reads can be found before deciding how long to keep it.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::StatusCode;
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::{
+    NewWorkspaceActivity, NewWorkspaceLegalHold, NewWorkspaceRetentionPolicy, WorkspaceLegalHold,
+};
+use nvisy_postgres::query::{
+    WorkspaceFileAccessRepository, WorkspaceFileRepository, WorkspaceRetentionRepository,
+};
+use nvisy_postgres::types::ActivityType;
+use uuid::Uuid;
+
+use crate::extract::{
+    AuthProvider, AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceContext,
+};
+use crate::handler::request::{
+    CreateLegalHold, FileAccessReportQuery, LegalHoldPathParams, UpdateRetentionPolicy,
+};
+use crate::handler::response::{
+    ErrorResponse, FileAccessReport, FileAccessReportEntry, LegalHold, LegalHolds, RetentionPolicy,
+    RetentionPreview,
+};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{AuditLog, RetentionService, ServiceState};
+
+/// Tracing target for retention operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::retention";
+
+/// Returns the workspace's retention policy.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn read_retention_policy(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+) -> Result<(StatusCode, Json<RetentionPolicy>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading retention policy");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewRetention)
+        .await?;
+
+    let policy = conn.find_workspace_retention_policy(scope).await?;
+
+    Ok((StatusCode::OK, Json(RetentionPolicy::from_model(policy))))
+}
+
+fn read_retention_policy_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get retention policy")
+        .description(
+            "Returns how long the workspace keeps its files, finished runs and audit records. \
+             Unset periods keep the data indefinitely.",
+        )
+        .response::<200, Json<RetentionPolicy>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Sets the workspace's retention policy.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn update_retention_policy(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    ValidateJson(request): ValidateJson<UpdateRetentionPolicy>,
+) -> Result<(StatusCode, Json<RetentionPolicy>)> {
+    tracing::debug!(target: TRACING_TARGET, "Updating retention policy");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    auth_state
+        .authorize_workspace(&mut conn, workspace.id, Permission::ManageRetention)
+        .await?;
+
+    let policy = conn
+        .set_workspace_retention_policy(NewWorkspaceRetentionPolicy {
+            workspace_id: workspace.id,
+            account_id: Some(auth_state.account_id),
+            file_retention_days: request.file_retention_days,
+            run_retention_days: request.run_retention_days,
+            activity_retention_days: request.activity_retention_days,
+        })
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        file_retention_days = ?policy.file_retention_days,
+        run_retention_days = ?policy.run_retention_days,
+        activity_retention_days = ?policy.activity_retention_days,
+        "Retention policy updated",
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(RetentionPolicy::from_model(Some(policy))),
+    ))
+}
+
+fn update_retention_policy_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Set retention policy")
+        .description(
+            "Replaces the workspace's retention policy. Data older than a set period is \
+             permanently deleted by the background purge unless a legal hold covers it.",
+        )
+        .response::<200, Json<RetentionPolicy>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Reports what the next purge would delete from the workspace.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn preview_retention(
+    State(pg_client): State<PgClient>,
+    State(retention): State<RetentionService>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+) -> Result<(StatusCode, Json<RetentionPreview>)> {
+    tracing::debug!(target: TRACING_TARGET, "Previewing retention purge");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewRetention)
+        .await?;
+
+    let report = retention.preview(scope).await?;
+
+    Ok((StatusCode::OK, Json(report.into())))
+}
+
+fn preview_retention_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Preview retention purge")
+        .description(
+            "Counts the files, finished runs and audit records the purge would delete under the \
+             current policy and legal holds, without deleting anything.",
+        )
+        .response::<200, Json<RetentionPreview>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Ranks the workspace's files by how often they were accessed.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        ranking = ?query.ranking,
+    )
+)]
+async fn file_access_report(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Query(query): Query<FileAccessReportQuery>,
+) -> Result<(StatusCode, Json<FileAccessReport>)> {
+    tracing::debug!(target: TRACING_TARGET, "Building file access report");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewRetention)
+        .await?;
+
+    let since = query.since();
+    let summaries = conn
+        .rank_file_access(
+            scope,
+            since,
+            query.ranking.sort_order(),
+            i64::from(query.limit()),
+        )
+        .await?;
+
+    let report = FileAccessReport {
+        since,
+        ranking: query.ranking,
+        files: summaries
+            .into_iter()
+            .map(FileAccessReportEntry::from_model)
+            .collect(),
+    };
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+fn file_access_report_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Report file access")
+        .description(
+            "Lists the workspace's most or least accessed files over the requested period, with \
+             their views, downloads, search opens and share link views. The least accessed \
+             listing includes files nobody opened, oldest first, as candidates for a shorter \
+             retention period. Deleted files are left out.",
+        )
+        .response::<200, Json<FileAccessReport>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Places a legal hold on the workspace or one of its files.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn create_legal_hold(
+    State(pg_client): State<PgClient>,
+    State(audit): State<AuditLog>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    ValidateJson(request): ValidateJson<CreateLegalHold>,
+) -> Result<(StatusCode, Json<LegalHold>)> {
+    tracing::debug!(target: TRACING_TARGET, "Placing legal hold");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRetention)
+        .await?;
+
+    if let Some(file_id) = request.file_id {
+        conn.find_file_in_workspace(scope, file_id)
+            .await?
+            .ok_or_else(|| Error::not_found("file"))?;
+    }
+
+    let hold = conn
+        .create_workspace_legal_hold(NewWorkspaceLegalHold {
+            workspace_id: workspace.id,
+            file_id: request.file_id,
+            account_id: Some(auth_state.account_id),
+            reason: request.reason,
+        })
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        hold_id = %hold.id,
+        file_id = ?hold.file_id,
+        "Legal hold placed",
+    );
+
+    audit
+        .record(hold_activity(
+            &hold,
+            ActivityType::LegalHoldPlaced,
+            auth_state.account_id,
+        ))
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(LegalHold::from_model(hold))))
+}
+
+fn create_legal_hold_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Place legal hold")
+        .description(
+            "Suspends the retention purge for the whole workspace, or for one file and its runs \
+             when a file is given. Held files cannot be deleted, nor can a workspace with any \
+             active hold. The placement is recorded in the audit log.",
+        )
+        .response::<201, Json<LegalHold>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Lists the workspace's legal holds, including released ones.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn list_legal_holds(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+) -> Result<(StatusCode, Json<LegalHolds>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing legal holds");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewRetention)
+        .await?;
+
+    let holds = conn.list_workspace_legal_holds(scope).await?;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        hold_count = holds.len(),
+        "Legal holds listed",
+    );
+
+    let holds = holds.into_iter().map(LegalHold::from_model).collect();
+    Ok((StatusCode::OK, Json(holds)))
+}
+
+fn list_legal_holds_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List legal holds")
+        .description("Returns the workspace's legal holds, newest first, including released ones.")
+        .response::<200, Json<LegalHolds>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Releases an active legal hold.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        hold_id = %path_params.hold_id,
+    )
+)]
+async fn release_legal_hold(
+    State(pg_client): State<PgClient>,
+    State(audit): State<AuditLog>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<LegalHoldPathParams>,
+) -> Result<(StatusCode, Json<LegalHold>)> {
+    tracing::debug!(target: TRACING_TARGET, "Releasing legal hold");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRetention)
+        .await?;
+
+    let hold = conn
+        .release_workspace_legal_hold(scope, path_params.hold_id)
+        .await?
+        .ok_or_else(|| {
+            ErrorKind::NotFound
+                .with_message("No active legal hold with this id")
+                .with_resource("legal_hold")
+        })?;
+
+    tracing::info!(target: TRACING_TARGET, "Legal hold released");
+
+    audit
+        .record(hold_activity(
+            &hold,
+            ActivityType::LegalHoldReleased,
+            auth_state.account_id,
+        ))
+        .await?;
+
+    Ok((StatusCode::OK, Json(LegalHold::from_model(hold))))
+}
+
+fn release_legal_hold_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Release legal hold")
+        .description(
+            "Releases an active legal hold. Data it covered becomes subject to the retention \
+             policy again on the next purge.",
+        )
+        .response::<200, Json<LegalHold>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Builds the audit record of a hold change made by `account_id`.
+fn hold_activity(
+    hold: &WorkspaceLegalHold,
+    activity_type: ActivityType,
+    account_id: Uuid,
+) -> NewWorkspaceActivity {
+    let description = match (activity_type, hold.file_id) {
+        (ActivityType::LegalHoldReleased, Some(_)) => "Released a legal hold on a file",
+        (ActivityType::LegalHoldReleased, None) => "Released a workspace legal hold",
+        (_, Some(_)) => "Placed a legal hold on a file",
+        (_, None) => "Placed a workspace legal hold",
+    };
+
+    NewWorkspaceActivity {
+        workspace_id: hold.workspace_id,
+        account_id: Some(account_id),
+        activity_type,
+        resource_id: Some(hold.id),
+        description: Some(description.to_owned()),
+        metadata: Some(serde_json::json!({
+            "fileId": hold.file_id,
+            "reason": hold.reason,
+        })),
+        ..Default::default()
+    }
+}
+
+/// Returns routes for retention policy and legal hold management.
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/workspaces/{workspaceSlug}/retention/",
+            get_with(read_retention_policy, read_retention_policy_docs)
+                .put_with(update_retention_policy, update_retention_policy_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/retention/preview/",
+            get_with(preview_retention, preview_retention_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/retention/access/",
+            get_with(file_access_report, file_access_report_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/legal-holds/",
+            post_with(create_legal_hold, create_legal_hold_docs)
+                .get_with(list_legal_holds, list_legal_holds_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/legal-holds/{holdId}/release/",
+            post_with(release_legal_hold, release_legal_hold_docs),
+        )
+        .with_path_items(|item| item.tag("Retention"))
+}
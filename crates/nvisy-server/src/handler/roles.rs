@@ -0,0 +1,323 @@
+//! Role and permission handlers.
+//!
+//! Lists the permission definitions and a workspace's roles, and manages the
+//! workspace's custom roles. Built-in roles are fixed; custom roles grant
+//! extra permissions on top of a member's built-in role and are assigned
+//! through the member endpoints.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::StatusCode;
+use nvisy_postgres::PgClient;
+use nvisy_postgres::query::WorkspaceCustomRoleRepository;
+use strum::IntoEnumIterator;
+
+use crate::extract::{AuthState, Json, Path, ValidateJson, WorkspaceAccess};
+use crate::handler::request::{CreateRole, RolePathParams, UpdateRole};
+use crate::handler::response::{
+    BuiltinRoleDefinition, ErrorResponse, PermissionDefinition, Role, Roles,
+};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::ServiceState;
+use crate::service::rbac::{BuiltinRole, Permission, Policy};
+
+/// Tracing target for role operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::roles";
+
+/// Lists every permission and the lowest built-in role holding it.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn list_permissions(
+    AuthState(auth_state): AuthState,
+) -> Result<(StatusCode, Json<Vec<PermissionDefinition>>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing permissions");
+
+    let permissions = Permission::iter().map(PermissionDefinition::from).collect();
+
+    Ok((StatusCode::OK, Json(permissions)))
+}
+
+fn list_permissions_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List permissions")
+        .description(
+            "Returns every permission as a `resource:action` key, with the lowest \
+             built-in role holding it and whether custom roles may grant it.",
+        )
+        .response::<200, Json<Vec<PermissionDefinition>>>()
+        .response::<401, Json<ErrorResponse>>()
+}
+
+/// Lists a workspace's built-in and custom roles.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+    )
+)]
+async fn list_roles(
+    State(pg_client): State<PgClient>,
+    access: WorkspaceAccess,
+) -> Result<(StatusCode, Json<Roles>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing workspace roles");
+
+    access.require(Permission::ViewMembers)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
+    let custom = conn.list_workspace_custom_roles(scope).await?;
+
+    let roles = Roles {
+        builtin: BuiltinRole::ALL
+            .into_iter()
+            .map(BuiltinRoleDefinition::from)
+            .collect(),
+        custom: custom.into_iter().map(Role::from_model).collect(),
+    };
+
+    Ok((StatusCode::OK, Json(roles)))
+}
+
+fn list_roles_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List roles")
+        .description(
+            "Returns the built-in roles (owner, admin, editor and viewer, stored as \
+             `owner`, `admin`, `member` and `guest`) and the workspace's custom roles.",
+        )
+        .response::<200, Json<Roles>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Creates a custom role.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+    )
+)]
+async fn create_role(
+    State(pg_client): State<PgClient>,
+    access: WorkspaceAccess,
+    ValidateJson(request): ValidateJson<CreateRole>,
+) -> Result<(StatusCode, Json<Role>)> {
+    tracing::debug!(target: TRACING_TARGET, "Creating custom role");
+
+    access.require(Permission::ManageRoles)?;
+    ensure_assignable(&request.permissions)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let role = conn
+        .create_workspace_custom_role(
+            request.into_model(access.workspace().id, access.actor().account_id),
+        )
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        role_id = %role.id,
+        "Custom role created"
+    );
+
+    Ok((StatusCode::CREATED, Json(Role::from_model(role))))
+}
+
+fn create_role_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create custom role")
+        .description(
+            "Creates a custom role granting the given permissions on top of a member's \
+             built-in role. Owner-only permissions cannot be granted.",
+        )
+        .response::<201, Json<Role>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Gets a custom role.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+        role_id = %path_params.role_id,
+    )
+)]
+async fn get_role(
+    State(pg_client): State<PgClient>,
+    access: WorkspaceAccess,
+    Path(path_params): Path<RolePathParams>,
+) -> Result<(StatusCode, Json<Role>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading custom role");
+
+    access.require(Permission::ViewMembers)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
+    let role = conn
+        .find_custom_role_in_workspace(scope, path_params.role_id.as_uuid())
+        .await?
+        .ok_or_else(|| Error::not_found("workspace_custom_role"))?;
+
+    Ok((StatusCode::OK, Json(Role::from_model(role))))
+}
+
+fn get_role_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get custom role")
+        .description("Returns a custom role and the permissions it grants.")
+        .response::<200, Json<Role>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Updates a custom role.
+///
+/// Members holding the role are affected immediately on this instance and
+/// within the role cache TTL on others.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+        role_id = %path_params.role_id,
+    )
+)]
+async fn update_role(
+    State(pg_client): State<PgClient>,
+    State(policy): State<Policy>,
+    access: WorkspaceAccess,
+    Path(path_params): Path<RolePathParams>,
+    ValidateJson(request): ValidateJson<UpdateRole>,
+) -> Result<(StatusCode, Json<Role>)> {
+    tracing::debug!(target: TRACING_TARGET, "Updating custom role");
+
+    access.require(Permission::ManageRoles)?;
+    if let Some(permissions) = &request.permissions {
+        ensure_assignable(permissions)?;
+    }
+
+    let workspace_id = access.workspace().id;
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
+    let existing = conn
+        .find_custom_role_in_workspace(scope, path_params.role_id.as_uuid())
+        .await?
+        .ok_or_else(|| Error::not_found("workspace_custom_role"))?;
+
+    let role = conn
+        .update_workspace_custom_role(scope, existing.id, request.into_model())
+        .await?;
+    policy.invalidate_workspace(workspace_id).await;
+
+    tracing::info!(target: TRACING_TARGET, "Custom role updated");
+
+    Ok((StatusCode::OK, Json(Role::from_model(role))))
+}
+
+fn update_role_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Update custom role")
+        .description(
+            "Updates a custom role's name, description or permissions. A new \
+             permission set replaces the current one.",
+        )
+        .response::<200, Json<Role>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Deletes a custom role, unassigning it from every member holding it.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+        role_id = %path_params.role_id,
+    )
+)]
+async fn delete_role(
+    State(pg_client): State<PgClient>,
+    State(policy): State<Policy>,
+    access: WorkspaceAccess,
+    Path(path_params): Path<RolePathParams>,
+) -> Result<StatusCode> {
+    tracing::debug!(target: TRACING_TARGET, "Deleting custom role");
+
+    access.require(Permission::ManageRoles)?;
+
+    let workspace_id = access.workspace().id;
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
+    let existing = conn
+        .find_custom_role_in_workspace(scope, path_params.role_id.as_uuid())
+        .await?
+        .ok_or_else(|| Error::not_found("workspace_custom_role"))?;
+
+    let unassigned = conn.count_custom_role_members(scope, existing.id).await?;
+    conn.delete_workspace_custom_role(scope, existing.id)
+        .await?;
+    policy.invalidate_workspace(workspace_id).await;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        unassigned_members = unassigned,
+        "Custom role deleted"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn delete_role_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Delete custom role")
+        .description(
+            "Deletes a custom role. Members holding it keep their built-in role and \
+             lose the extra permissions.",
+        )
+        .response::<204, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Rejects permissions a custom role may not grant.
+fn ensure_assignable(permissions: &[Permission]) -> Result<()> {
+    match permissions
+        .iter()
+        .find(|permission| !permission.is_assignable())
+    {
+        Some(permission) => Err(ErrorKind::BadRequest
+            .with_message("Permission cannot be granted by a custom role")
+            .with_context(format!("{permission} is reserved to workspace owners"))),
+        None => Ok(()),
+    }
+}
+
+/// Returns a [`Router`] with all role related routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/permissions/",
+            get_with(list_permissions, list_permissions_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/roles/",
+            get_with(list_roles, list_roles_docs).post_with(create_role, create_role_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/roles/{roleId}/",
+            get_with(get_role, get_role_docs)
+                .patch_with(update_role, update_role_docs)
+                .delete_with(delete_role, delete_role_docs),
+        )
+        .with_path_items(|item| item.tag("Roles"))
+}
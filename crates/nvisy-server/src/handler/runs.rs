@@ -4,47 +4,66 @@
 //! and stores the findings; the run then awaits reviewer verification before
 //! redact consumes the verified findings and produces a redacted file.
 
+use std::collections::HashSet;
 use std::io::Cursor;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use aide::axum::ApiRouter;
 use aide::transform::TransformOperation;
 use axum::extract::State;
 use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use bytes::Bytes;
+use nvisy_core::clock::Clock;
+use nvisy_core::id::IdGenerator;
 use nvisy_engine::AnalyzedDocument;
 use nvisy_nats::NatsClient;
-use nvisy_nats::object::{FileKey, FilesBucket, IntermediateKey, IntermediatesBucket};
+use nvisy_nats::object::{
+    AnalysesBucket, FileKey, FilesBucket, IntermediateKey, IntermediatesBucket,
+};
+use nvisy_nats::stream::{ProgressReporter, RunProgress, RunStage, progress_subject};
 use nvisy_postgres::model::{
-    NewWorkspaceFile, NewWorkspacePipelineArtifact, NewWorkspacePipelineRun,
-    UpdateWorkspacePipelineRun, WorkspaceFile, WorkspacePipeline, WorkspacePipelineArtifact,
-    WorkspacePipelineRun,
+    NewWorkspaceFile, NewWorkspaceOperation, NewWorkspacePipelineArtifact, NewWorkspacePipelineRun,
+    UpdateWorkspacePipelineRun, WorkspaceFile, WorkspaceOperation, WorkspacePipeline,
+    WorkspacePipelineArtifact, WorkspacePipelineRun,
 };
 use nvisy_postgres::query::{
-    AccountRepository, PipelineReferenceRepository, WorkspaceContextRepository,
-    WorkspaceFileRepository, WorkspacePipelineArtifactRepository, WorkspacePipelineRepository,
+    AccountRepository, PipelineReferenceRepository, TenantScope, WorkspaceContextRepository,
+    WorkspaceDetectionReviewRepository, WorkspaceFileRepository, WorkspaceOperationRepository,
+    WorkspacePipelineArtifactRepository, WorkspacePipelineRepository,
     WorkspacePipelineRunRepository, WorkspacePolicyRepository,
 };
-use nvisy_postgres::types::{ArtifactType, PipelineRunStatus, Username};
+use nvisy_postgres::types::{ArtifactType, OperationKind, PipelineRunStatus, RunId, Username};
 use nvisy_postgres::{PgClient, PgConn};
 use nvisy_schema::context::Context as SchemaContext;
 use nvisy_schema::file::Document;
 use nvisy_schema::plan::{AnalyzerParams, AnyAnnotations, ScopeParams};
 use nvisy_schema::policy::Policy as SchemaPolicy;
-use sha2::{Digest, Sha256};
 use tokio::io::AsyncReadExt;
 use uuid::Uuid;
 
 use crate::extract::{
     AuthProvider, AuthState, Json, Path, Permission, Query, ValidateJson, WorkspaceContext,
 };
+use crate::handler::operations::accepted_operation_response;
 use crate::handler::request::{
-    CreatePipelineRun, CursorPagination, PipelineDefinition, PipelinePathParams,
-    PipelineRunPathParams, WorkspaceRunsQuery,
+    CreatePipelineRun, CursorPagination, DetectionReviewPathParams, PipelineDefinition,
+    PipelinePathParams, PipelineRunPathParams, ReviewDetections, WorkspaceFilePathParams,
+    WorkspaceRunsQuery,
+};
+use crate::handler::response::{
+    DetectionReviewHistory, DetectionReviews, ErrorResponse, Operation, OutputSet, OutputSets,
+    PipelineRun, PipelineRunsPage,
 };
-use crate::handler::response::{ErrorResponse, PipelineRun, PipelineRunsPage};
 use crate::handler::{Error, ErrorKind, Result};
-use crate::service::{CryptoService, EngineService, ServiceState};
+use crate::service::crypto::CryptoError;
+use crate::service::{
+    CryptoService, DocumentPasswords, EngineService, OperationHandle, OperationOutput,
+    OperationRunner, RegionBackends, ResidencyService, ServiceState, count_pages,
+};
 
 /// Tracing target for pipeline run operations.
 const TRACING_TARGET: &str = "nvisy_server::handler::runs";
@@ -52,11 +71,30 @@ const TRACING_TARGET: &str = "nvisy_server::handler::runs";
 /// Header carrying the detect idempotency key.
 const IDEMPOTENCY_HEADER: &str = "idempotency-key";
 
+/// Header carrying client preferences (RFC 7240).
+const PREFER_HEADER: &str = "prefer";
+
+/// Preference requesting asynchronous processing.
+const RESPOND_ASYNC: &str = "respond-async";
+
+/// How long to wait for a progress update before polling again.
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Run metadata key recording the recognizer configuration detection ran
+/// with, so reviewed findings can be traced back to what proposed them.
+const RECOGNIZERS_METADATA_KEY: &str = "recognizers";
+
+/// Interval between SSE keep-alive comments, so idle proxies don't drop the
+/// connection.
+const PROGRESS_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
 /// Starts a run: analyzes a file with the pipeline's configuration (detect).
 ///
 /// Returns the run holding the findings for review. A repeated request with the
 /// same `Idempotency-Key` returns the existing run instead of analyzing again.
-/// Requires `RunPipelines` permission.
+/// With `Prefer: respond-async` the run is analyzed in the background and a
+/// `202 Accepted` operation is returned instead. Requires `RunPipelines`
+/// permission.
 #[tracing::instrument(
     skip_all,
     fields(
@@ -69,22 +107,27 @@ async fn create_pipeline_run(
     State(pg_client): State<PgClient>,
     State(nats): State<NatsClient>,
     State(crypto): State<CryptoService>,
-    State(engine): State<EngineService>,
+    State(document_passwords): State<DocumentPasswords>,
+    State(residency): State<ResidencyService>,
+    State(operations): State<OperationRunner>,
+    State(ids): State<Arc<dyn IdGenerator>>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<PipelinePathParams>,
     headers: HeaderMap,
     ValidateJson(request): ValidateJson<CreatePipelineRun>,
-) -> Result<(StatusCode, Json<PipelineRun>)> {
+) -> Result<Response> {
     tracing::debug!(target: TRACING_TARGET, "Starting pipeline run (detect)");
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::RunPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
         .await?;
 
-    let pipeline = find_pipeline(&mut conn, workspace.id, &path_params.pipeline_slug).await?;
+    let pipeline = find_pipeline(&mut conn, scope, &path_params.pipeline_slug).await?;
+    // Storage and inference stay within the workspace's data region.
+    let backends = residency.backends(workspace.data_region)?;
 
     let idempotency_key = idempotency_key(&headers)?;
 
@@ -92,7 +135,7 @@ async fn create_pipeline_run(
     // attributed to whoever originally triggered it (not the current caller).
     if let Some(key) = &idempotency_key
         && let Some(existing) = conn
-            .find_pipeline_run_by_idempotency_key(pipeline.id, key)
+            .find_pipeline_run_by_idempotency_key(scope, pipeline.id, key)
             .await?
     {
         tracing::debug!(target: TRACING_TARGET, "Replaying run for idempotency key");
@@ -105,57 +148,37 @@ async fn create_pipeline_run(
                 workspace.slug.clone(),
                 trigger_username,
             )),
-        ));
+        )
+            .into_response());
     }
 
-    let file = conn
-        .find_file_in_workspace(pipeline.workspace_id, request.file_id)
-        .await?
-        .ok_or_else(|| Error::not_found("file"))?;
-
-    let definition = PipelineDefinition::from_parts(pipeline.definition, Vec::new(), Vec::new())
-        .map_err(serialize_error)?;
-
-    // Create the run first so its id is the engine correlation id.
-    let new_run = NewWorkspacePipelineRun {
-        pipeline_id: pipeline.id,
-        file_id: file.id,
-        account_id: Some(auth_state.account_id),
-        status: Some(PipelineRunStatus::Running),
-        idempotency_key: idempotency_key.clone(),
-        ..Default::default()
-    };
-    let run = conn.create_workspace_pipeline_run(new_run).await?;
-
-    // Assemble the engine inputs and analyze.
-    let document = build_document(&nats, &crypto, &file, run.id).await?;
-    let params = build_analyzer_params(&definition, request.scope);
-    let contexts = resolve_contexts(&mut conn, &crypto, pipeline.workspace_id, pipeline.id).await?;
-
-    let analyzed = match engine.analyze_document(document, &params, &contexts).await {
-        Ok(analyzed) => analyzed,
-        Err(err) => {
-            fail_run(&mut conn, run.id).await;
-            return Err(analysis_error(err));
-        }
+    let ctx = DetectContext {
+        nats,
+        crypto,
+        document_passwords,
+        ids,
+        backends: backends.clone(),
+        tenant: scope,
+        account_id: auth_state.account_id,
     };
+    let (run, job) =
+        DetectJob::prepare(&mut conn, ctx, &pipeline, request, idempotency_key).await?;
+
+    // With `Prefer: respond-async` the analysis continues in the background
+    // and the caller polls the returned operation for the run.
+    if prefers_async(&headers) {
+        let operation = job.spawn(&operations, pg_client).await?;
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            run_id = %run.id,
+            operation_id = %operation.id,
+            "Pipeline run accepted for background analysis"
+        );
+        return Ok(accepted_operation_response(operation));
+    }
 
-    // The analysis is a map of detected PII; encrypt it and hold it in the
-    // intermediates bucket, keeping only its key on the run.
-    let analyzed_key =
-        store_analyzed_document(&nats, &crypto, pipeline.workspace_id, &analyzed).await?;
-    let run = conn
-        .update_workspace_pipeline_run(
-            run.id,
-            UpdateWorkspacePipelineRun {
-                status: Some(PipelineRunStatus::Analyzed),
-                analyzed_document_key: Some(Some(analyzed_key)),
-                ..Default::default()
-            },
-        )
-        .await?;
-
-    tracing::info!(target: TRACING_TARGET, run_id = %run.id, "Pipeline run analyzed");
+    let run = job.run(&mut conn, None).await?;
 
     let trigger_username = resolve_trigger_username(&mut conn, run.account_id).await?;
 
@@ -167,20 +190,28 @@ async fn create_pipeline_run(
             workspace.slug,
             trigger_username,
         )),
-    ))
+    )
+        .into_response())
 }
 
 fn create_pipeline_run_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Start a run (detect)")
         .description(
             "Analyzes a file with the pipeline's configuration and returns the run \
-             holding the findings for review. Accepts an Idempotency-Key header.",
+             holding the findings for review. Accepts an Idempotency-Key header. \
+             Send `Prefer: respond-async` to analyze in the background: the response \
+             is then a 202 with an operation whose result holds the run id. A \
+             password-protected file needs a stored password or a `documentPassword`; \
+             without one the run is rejected with `protected_document`.",
         )
+        .response::<200, Json<PipelineRun>>()
         .response::<201, Json<PipelineRun>>()
+        .response::<202, Json<Operation>>()
         .response::<400, Json<ErrorResponse>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
         .response::<404, Json<ErrorResponse>>()
+        .response::<422, Json<ErrorResponse>>()
 }
 
 /// Lists runs for a specific pipeline.
@@ -203,14 +234,14 @@ async fn list_pipeline_runs(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
         .await?;
 
-    let pipeline = find_pipeline(&mut conn, workspace.id, &path_params.pipeline_slug).await?;
+    let pipeline = find_pipeline(&mut conn, scope, &path_params.pipeline_slug).await?;
 
     let page = conn
-        .cursor_list_workspace_pipeline_runs(pipeline.id, pagination.into(), None)
+        .cursor_list_workspace_pipeline_runs(scope, pipeline.id, pagination.into(), None)
         .await?;
 
     tracing::debug!(
@@ -266,12 +297,12 @@ async fn list_workspace_runs(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_runs(workspace.id, pagination.into(), query.status)
+        .cursor_list_workspace_runs(scope, pagination.into(), query.status)
         .await?;
 
     tracing::debug!(
@@ -327,12 +358,12 @@ async fn get_pipeline_run(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
         .await?;
 
     let (pipeline, run, trigger_username) =
-        find_pipeline_run(&mut conn, workspace.id, path_params.run_id.as_uuid()).await?;
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
 
     tracing::debug!(target: TRACING_TARGET, "Pipeline run retrieved");
 
@@ -356,6 +387,68 @@ fn get_pipeline_run_docs(op: TransformOperation) -> TransformOperation {
         .response::<404, Json<ErrorResponse>>()
 }
 
+/// Cancels a run.
+///
+/// Stops the run's analysis if it is still running in the background, and
+/// keeps the run from being reviewed or redacted. Requires `RunPipelines`.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        run_id = %path_params.run_id,
+    )
+)]
+async fn cancel_pipeline_run(
+    State(pg_client): State<PgClient>,
+    State(nats): State<NatsClient>,
+    State(operations): State<OperationRunner>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<PipelineRunPathParams>,
+) -> Result<(StatusCode, Json<PipelineRun>)> {
+    tracing::debug!(target: TRACING_TARGET, "Cancelling pipeline run");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
+        .await?;
+
+    let (pipeline, run, trigger_username) = cancel_run(
+        &mut conn,
+        &nats,
+        &operations,
+        scope,
+        path_params.run_id.as_uuid(),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(PipelineRun::from_model(
+            run,
+            pipeline.slug,
+            workspace.slug,
+            trigger_username,
+        )),
+    ))
+}
+
+fn cancel_pipeline_run_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Cancel a run")
+        .description(
+            "Cancels a run that is still being analyzed or awaits review. A background \
+             analysis is stopped and its operation is marked `cancelled`. A cancelled run \
+             cannot be reviewed or redacted; start a new run to analyze the file again.",
+        )
+        .response::<200, Json<PipelineRun>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
 /// Returns the run's analyzed document (the detected findings) for review.
 ///
 /// Fetches and decrypts the engine's `AnalyzedDocument` from the intermediates
@@ -370,7 +463,7 @@ fn get_pipeline_run_docs(op: TransformOperation) -> TransformOperation {
 )]
 async fn get_pipeline_run_analysis(
     State(pg_client): State<PgClient>,
-    State(nats): State<NatsClient>,
+    State(residency): State<ResidencyService>,
     State(crypto): State<CryptoService>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
@@ -380,14 +473,15 @@ async fn get_pipeline_run_analysis(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
         .await?;
 
     let (_pipeline, run, _) =
-        find_pipeline_run(&mut conn, workspace.id, path_params.run_id.as_uuid()).await?;
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
 
-    let analyzed = load_analyzed_document(&nats, &crypto, workspace.id, &run).await?;
+    let nats = residency.backends(workspace.data_region)?.nats();
+    let analyzed = load_analyzed_document(nats, &crypto, workspace.id, &run).await?;
 
     tracing::debug!(target: TRACING_TARGET, "Pipeline run analysis retrieved");
 
@@ -404,6 +498,266 @@ fn get_pipeline_run_analysis_docs(op: TransformOperation) -> TransformOperation
         .response::<409, Json<ErrorResponse>>()
 }
 
+/// Lists the output sets produced for a file by its analyzed runs.
+///
+/// Each output set is immutable and addressed by the digest of its inputs, so
+/// runs over unchanged inputs can be told apart from ones whose file,
+/// configuration or models changed. Requires `ViewPipelines`.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn list_file_output_sets(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+) -> Result<(StatusCode, Json<OutputSets>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing file output sets");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
+        .await?;
+
+    let file = conn
+        .find_file_in_workspace(scope, path_params.file_id)
+        .await?
+        .ok_or_else(|| Error::not_found("file"))?;
+
+    let output_sets: OutputSets = conn
+        .list_file_output_sets(scope, file.id)
+        .await?
+        .into_iter()
+        .map(|(run, artifacts)| OutputSet::from_model(run, artifacts))
+        .collect();
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        output_set_count = output_sets.len(),
+        "File output sets listed"
+    );
+
+    Ok((StatusCode::OK, Json(output_sets)))
+}
+
+fn list_file_output_sets_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List file output sets")
+        .description(
+            "Returns the output sets of a file, most recent first: each analyzed run with \
+             the hex SHA-256 of the inputs its findings depend on (file content, pipeline \
+             definition, scope, contexts and the engine's recognizer lineups) and the \
+             artifacts it recorded. Output sets are never rewritten; a rerun adds a new one.",
+        )
+        .response::<200, Json<OutputSets>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Records reviewer decisions on a run's detections in bulk.
+///
+/// Each decision supersedes the detection's current one and becomes the next
+/// version of its review. Only an analyzed run, awaiting redaction, can be
+/// reviewed. Requires `ReviewPipelines` permission.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        run_id = %path_params.run_id,
+    )
+)]
+async fn review_pipeline_run(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<PipelineRunPathParams>,
+    ValidateJson(request): ValidateJson<ReviewDetections>,
+) -> Result<(StatusCode, Json<DetectionReviews>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reviewing pipeline run detections");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ReviewPipelines)
+        .await?;
+
+    let (_pipeline, run, _) =
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
+
+    // Decisions only matter until redact consumes the findings.
+    if !run.is_analyzed() {
+        return Err(ErrorKind::Conflict
+            .with_message("Run is not awaiting review")
+            .with_resource("pipeline_run"));
+    }
+
+    let mut detection_ids = HashSet::with_capacity(request.decisions.len());
+    for decision in &request.decisions {
+        if !decision.status.is_decision() {
+            return Err(ErrorKind::BadRequest
+                .with_message("A detection cannot be marked as superseded")
+                .with_resource("detection_review"));
+        }
+        if !detection_ids.insert(decision.detection_id.as_str()) {
+            return Err(ErrorKind::BadRequest
+                .with_message("Each detection can only be decided once per request")
+                .with_resource("detection_review"));
+        }
+    }
+
+    let reviews = request
+        .decisions
+        .into_iter()
+        .map(|decision| decision.into_model(run.id, auth_state.account_id))
+        .collect();
+    let recorded = conn
+        .record_detection_reviews(scope, run.id, reviews)
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        run_id = %run.id,
+        decisions = recorded.len(),
+        "Pipeline run detections reviewed"
+    );
+
+    let current = conn.list_current_detection_reviews(scope, run.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(DetectionReviews::from_models(&run, current)),
+    ))
+}
+
+fn review_pipeline_run_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Review run detections")
+        .description(
+            "Accepts, rejects or reopens (`proposed`) detections of an analyzed run, \
+             up to 500 at once. Each decision supersedes the detection's previous one \
+             and is kept in its history. Returns the run's current decisions.",
+        )
+        .response::<200, Json<DetectionReviews>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Lists the current review decision on each reviewed detection of a run.
+///
+/// Detections without a decision are still proposed. Requires `ViewPipelines`.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        run_id = %path_params.run_id,
+    )
+)]
+async fn list_pipeline_run_reviews(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<PipelineRunPathParams>,
+) -> Result<(StatusCode, Json<DetectionReviews>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing pipeline run reviews");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
+        .await?;
+
+    let (_pipeline, run, _) =
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
+    let current = conn.list_current_detection_reviews(scope, run.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(DetectionReviews::from_models(&run, current)),
+    ))
+}
+
+fn list_pipeline_run_reviews_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List run reviews")
+        .description(
+            "Returns the current decision on each reviewed detection of the run. \
+             Detections not listed are still proposed.",
+        )
+        .response::<200, Json<DetectionReviews>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Returns every version of a detection's review, with the recognizer
+/// configuration that proposed it.
+///
+/// Requires `ViewPipelines`.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        run_id = %path_params.run_id,
+    )
+)]
+async fn get_detection_review_history(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<DetectionReviewPathParams>,
+) -> Result<(StatusCode, Json<DetectionReviewHistory>)> {
+    tracing::debug!(target: TRACING_TARGET, "Getting detection review history");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
+        .await?;
+
+    let (_pipeline, run, _) =
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
+    let versions = conn
+        .list_detection_review_history(scope, run.id, &path_params.detection_id)
+        .await?;
+    if versions.is_empty() {
+        return Err(Error::not_found("detection_review"));
+    }
+
+    let proposed_by = run.metadata.get(RECOGNIZERS_METADATA_KEY).cloned();
+
+    Ok((
+        StatusCode::OK,
+        Json(DetectionReviewHistory::from_models(
+            &run,
+            path_params.detection_id,
+            proposed_by,
+            versions,
+        )),
+    ))
+}
+
+fn get_detection_review_history_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get detection review history")
+        .description(
+            "Returns every version of a detection's review, oldest first, together \
+             with the recognizer configuration the run detected with.",
+        )
+        .response::<200, Json<DetectionReviewHistory>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
 /// Redacts a run using the reviewer-verified findings, storing the result.
 ///
 /// Consumes the analyzed run (which must be awaiting review), applies the
@@ -421,7 +775,10 @@ async fn redact_pipeline_run(
     State(pg_client): State<PgClient>,
     State(nats): State<NatsClient>,
     State(crypto): State<CryptoService>,
-    State(engine): State<EngineService>,
+    State(document_passwords): State<DocumentPasswords>,
+    State(residency): State<ResidencyService>,
+    State(clock): State<Arc<dyn Clock>>,
+    State(ids): State<Arc<dyn IdGenerator>>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<PipelineRunPathParams>,
@@ -430,12 +787,12 @@ async fn redact_pipeline_run(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::RunPipelines)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::RunPipelines)
         .await?;
 
     let (pipeline, run, trigger_username) =
-        find_pipeline_run(&mut conn, workspace.id, path_params.run_id.as_uuid()).await?;
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
 
     // A run can only be redacted once, after detection.
     if !run.is_analyzed() {
@@ -445,42 +802,67 @@ async fn redact_pipeline_run(
     }
 
     let file = conn
-        .find_file_in_workspace(workspace.id, run.file_id)
+        .find_file_in_workspace(scope, run.file_id)
         .await?
         .ok_or_else(|| Error::not_found("file"))?;
 
-    // The stored analysis is the source of truth for what gets redacted.
-    let analyzed = load_analyzed_document(&nats, &crypto, workspace.id, &run).await?;
-    let policies = resolve_policies(&mut conn, &crypto, workspace.id, pipeline.id).await?;
-    let document = build_document(&nats, &crypto, &file, run.id).await?;
-
-    let anonymized = engine
-        .anonymize_document(document, &policies, &analyzed)
-        .await
-        .map_err(analysis_error)?;
-
-    // Store the redacted bytes as a new workspace file and record the artifact.
-    let artifact_file = store_redacted_file(
-        &mut conn,
-        &nats,
-        &crypto,
-        &file,
-        auth_state.account_id,
-        anonymized.bytes,
-    )
-    .await?;
-    record_artifact(&mut conn, run.id, artifact_file.id).await?;
-
-    let run = conn
-        .update_workspace_pipeline_run(
-            run.id,
-            UpdateWorkspacePipelineRun {
-                status: Some(PipelineRunStatus::Completed),
-                completed_at: Some(Some(jiff::Timestamp::now().into())),
-                ..Default::default()
-            },
+    let backends = residency.backends(workspace.data_region)?;
+    let mut progress = progress_reporter(&nats, workspace.id, run.id).await;
+
+    // Any failing stage is reported, so progress subscribers see it settle.
+    let result: Result<_> = async {
+        // The stored analysis is the source of truth for what gets redacted.
+        report_stage(&mut progress, RunStage::Loading).await;
+        let analyzed = load_analyzed_document(backends.nats(), &crypto, workspace.id, &run).await?;
+        let policies = resolve_policies(&mut conn, &crypto, scope, pipeline.id).await?;
+        let document_password = held_document_password(&document_passwords, &crypto, &file).await?;
+        ensure_document_password(&file, document_password.as_deref())?;
+        let (document, pages) =
+            build_document(backends.nats(), &crypto, &file, document_password, run.id).await?;
+        set_pages_total(&mut progress, pages);
+
+        report_stage(&mut progress, RunStage::Redacting).await;
+        let anonymized = backends
+            .engine()
+            .anonymize_document(document, &policies, &analyzed)
+            .await
+            .map_err(analysis_error)?;
+
+        // Store the redacted bytes as a new workspace file and record the
+        // artifact.
+        report_stage(&mut progress, RunStage::Storing).await;
+        let artifact_file = store_redacted_file(
+            &mut conn,
+            backends.nats(),
+            &crypto,
+            ids.as_ref(),
+            &file,
+            auth_state.account_id,
+            anonymized.bytes,
         )
         .await?;
+        record_artifact(&mut conn, run.id, artifact_file.id).await?;
+
+        let run = conn
+            .update_workspace_pipeline_run(
+                scope,
+                run.id,
+                UpdateWorkspacePipelineRun {
+                    status: Some(PipelineRunStatus::Completed),
+                    completed_at: Some(Some(clock.now().into())),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        Ok((run, artifact_file))
+    }
+    .await;
+
+    if result.is_err() {
+        report_stage(&mut progress, RunStage::Failed).await;
+    }
+    let (run, artifact_file) = result?;
 
     tracing::info!(
         target: TRACING_TARGET,
@@ -488,6 +870,7 @@ async fn redact_pipeline_run(
         artifact_file_id = %artifact_file.id,
         "Pipeline run redacted"
     );
+    report_stage(&mut progress, RunStage::Completed).await;
 
     Ok((
         StatusCode::OK,
@@ -511,6 +894,181 @@ fn redact_pipeline_run_docs(op: TransformOperation) -> TransformOperation {
         .response::<403, Json<ErrorResponse>>()
         .response::<404, Json<ErrorResponse>>()
         .response::<409, Json<ErrorResponse>>()
+        .response::<422, Json<ErrorResponse>>()
+}
+
+/// Streams a run's progress as Server-Sent Events.
+///
+/// Emits the latest known update right away, then each new one as the run
+/// advances, and ends once the run settles (awaits review, completes, or
+/// fails). A finished run gets one update derived from its status, and so
+/// does a run whose status changes without a progress update, as when its
+/// worker died. Requires `ViewPipelines`.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        run_id = %path_params.run_id,
+    )
+)]
+async fn stream_pipeline_run_progress(
+    State(pg_client): State<PgClient>,
+    State(nats): State<NatsClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<PipelineRunPathParams>,
+) -> Result<Response> {
+    tracing::debug!(target: TRACING_TARGET, "Streaming pipeline run progress");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewPipelines)
+        .await?;
+
+    let (_pipeline, run, _) =
+        find_pipeline_run(&mut conn, scope, path_params.run_id.as_uuid()).await?;
+
+    if run.is_finished() {
+        let stage = settled_stage(run.status);
+        let progress = RunProgress::new(workspace.id, run.id, stage);
+        let events = futures::stream::once(async move { progress_event(&progress) });
+        return Ok(Sse::new(events).into_response());
+    }
+
+    let subscriber = nats.run_progress_subscriber().await?;
+    let mut updates = subscriber
+        .watch(&progress_subject(workspace.id, run.id))
+        .await?;
+
+    let workspace_id = workspace.id;
+    let events = async_stream::stream! {
+        loop {
+            match updates.next_with_timeout(PROGRESS_POLL_INTERVAL).await {
+                Ok(Some(message)) => {
+                    let progress = message.into_payload();
+                    let settled = progress.stage.is_settled();
+                    yield progress_event(&progress);
+                    if settled {
+                        break;
+                    }
+                }
+                Ok(None) => {
+                    // Runs only move forward, so any change from the status
+                    // the stream opened with means the run has settled.
+                    let status = current_run_status(&pg_client, scope, run.id).await;
+                    if let Some(status) = status.filter(|status| *status != run.status) {
+                        let stage = settled_stage(status);
+                        yield progress_event(&RunProgress::new(workspace_id, run.id, stage));
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Run progress stream interrupted"
+                    );
+                    break;
+                }
+            }
+        }
+    };
+
+    let keep_alive = KeepAlive::new().interval(PROGRESS_KEEP_ALIVE);
+    Ok(Sse::new(events).keep_alive(keep_alive).into_response())
+}
+
+fn stream_pipeline_run_progress_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Stream run progress")
+        .description(
+            "Streams the run's progress (stage, percent, pages done/total, ETA) as \
+             Server-Sent Events of type `progress`. The stream ends once the run \
+             awaits review, completes, or fails.",
+        )
+        .response::<200, ()>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Reads a run's current status, or `None` if it cannot be read.
+async fn current_run_status(
+    pg_client: &PgClient,
+    scope: TenantScope,
+    run_id: Uuid,
+) -> Option<PipelineRunStatus> {
+    let found = match pg_client.get_connection().await {
+        Ok(mut conn) => conn.find_workspace_run_by_id(scope, run_id).await,
+        Err(err) => Err(err),
+    };
+
+    match found {
+        Ok(found) => found.map(|(run, ..)| run.status),
+        Err(err) => {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Failed to recheck run status"
+            );
+            None
+        }
+    }
+}
+
+/// Serializes a progress update as an SSE `progress` event.
+fn progress_event(progress: &RunProgress) -> Result<Event, axum::Error> {
+    Event::default().event("progress").json_data(progress)
+}
+
+/// Maps a run's status to the stage reported when no live update is available.
+fn settled_stage(status: PipelineRunStatus) -> RunStage {
+    match status {
+        PipelineRunStatus::Running => RunStage::Analyzing,
+        PipelineRunStatus::Analyzed => RunStage::Analyzed,
+        PipelineRunStatus::Completed => RunStage::Completed,
+        PipelineRunStatus::Failed | PipelineRunStatus::Cancelled => RunStage::Failed,
+    }
+}
+
+/// Creates a progress reporter for a run.
+///
+/// Progress is informational: when the stream is unavailable this logs and
+/// returns `None` rather than failing the run.
+async fn progress_reporter(
+    nats: &NatsClient,
+    workspace_id: Uuid,
+    run_id: Uuid,
+) -> Option<ProgressReporter> {
+    match nats.run_progress_publisher().await {
+        Ok(publisher) => Some(ProgressReporter::new(publisher, workspace_id, run_id)),
+        Err(err) => {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Run progress reporting unavailable"
+            );
+            None
+        }
+    }
+}
+
+/// Reports a stage transition (best effort).
+async fn report_stage(reporter: &mut Option<ProgressReporter>, stage: RunStage) {
+    let Some(reporter) = reporter else {
+        return;
+    };
+    if let Err(err) = reporter.stage(stage).await {
+        tracing::warn!(target: TRACING_TARGET, error = %err, "Failed to report run progress");
+    }
+}
+
+/// Attaches the document's page count to a run's later progress updates.
+fn set_pages_total(reporter: &mut Option<ProgressReporter>, pages: Option<u32>) {
+    if let Some(reporter) = reporter {
+        reporter.set_pages_total(pages);
+    }
 }
 
 /// Extracts and validates the optional idempotency key header.
@@ -529,18 +1087,87 @@ fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>> {
     Ok(Some(key.to_owned()))
 }
 
+/// Returns whether the caller asked for asynchronous processing with
+/// `Prefer: respond-async`.
+fn prefers_async(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(PREFER_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|preference| {
+            let token = preference.split(';').next().unwrap_or_default();
+            token.trim().eq_ignore_ascii_case(RESPOND_ASYNC)
+        })
+}
+
 /// Marks a run failed (best effort) after an engine error.
-async fn fail_run(conn: &mut PgConn, run_id: uuid::Uuid) {
+async fn fail_run(conn: &mut PgConn, scope: TenantScope, run_id: Uuid) {
     let update = UpdateWorkspacePipelineRun {
         status: Some(PipelineRunStatus::Failed),
         completed_at: Some(Some(jiff::Timestamp::now().into())),
         ..Default::default()
     };
-    if let Err(err) = conn.update_workspace_pipeline_run(run_id, update).await {
+    if let Err(err) = conn
+        .update_workspace_pipeline_run(scope, run_id, update)
+        .await
+    {
         tracing::warn!(target: TRACING_TARGET, error = %err, "Failed to mark run failed");
     }
 }
 
+/// Decrypts the password stored on a file, if any.
+fn stored_document_password(
+    crypto: &CryptoService,
+    file: &WorkspaceFile,
+) -> Result<Option<String>> {
+    file.encrypted_password
+        .as_deref()
+        .map(|encrypted| decrypt_password(crypto, file.workspace_id, encrypted))
+        .transpose()
+}
+
+/// Resolves the password held for a file's document: a transient one,
+/// else the one stored on the file.
+async fn held_document_password(
+    document_passwords: &DocumentPasswords,
+    crypto: &CryptoService,
+    file: &WorkspaceFile,
+) -> Result<Option<String>> {
+    match document_passwords.get(file).await? {
+        Some(password) => Ok(Some(password)),
+        None => stored_document_password(crypto, file),
+    }
+}
+
+/// Decrypts a document password held under the workspace key.
+fn decrypt_password(
+    crypto: &CryptoService,
+    workspace_id: Uuid,
+    encrypted: &[u8],
+) -> Result<String> {
+    let bytes = crypto.decrypt(workspace_id, encrypted)?;
+    String::from_utf8(bytes).map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Stored document password is invalid")
+            .with_context(err.to_string())
+    })
+}
+
+/// Rejects a password-protected file when no password is available.
+fn ensure_document_password(file: &WorkspaceFile, password: Option<&str>) -> Result<()> {
+    if file.is_password_protected() && password.is_none() {
+        return Err(ErrorKind::ProtectedDocument
+            .with_message("File is password-protected")
+            .with_resource("file")
+            .with_suggestion(
+                "Store the document password on the file, supply it transiently, or pass \
+                 `documentPassword` when starting the run",
+            ));
+    }
+    Ok(())
+}
+
 /// Maps a definition (de)serialization failure to an internal error.
 fn serialize_error(error: serde_json::Error) -> Error<'static> {
     ErrorKind::InternalServerError
@@ -555,13 +1182,99 @@ fn analysis_error(error: nvisy_engine::Error) -> Error<'static> {
         .with_context(error.to_string())
 }
 
+/// Starts a run of a pipeline over a file in the background (detect),
+/// returning the operation that tracks its analysis.
+///
+/// Serves the gRPC `StartRun`; the REST endpoint does the same when sent
+/// `Prefer: respond-async`. The caller authorizes `RunPipelines` first.
+#[cfg(feature = "grpc")]
+pub(crate) async fn start_run(
+    state: &ServiceState,
+    conn: &mut PgConn,
+    scope: TenantScope,
+    backends: &RegionBackends,
+    account_id: Uuid,
+    pipeline_slug: &str,
+    request: CreatePipelineRun,
+) -> Result<WorkspaceOperation> {
+    let pipeline = find_pipeline(conn, scope, pipeline_slug).await?;
+
+    let ctx = DetectContext {
+        nats: state.nats.clone(),
+        crypto: state.crypto.clone(),
+        document_passwords: state.document_passwords.clone(),
+        ids: state.ids.clone(),
+        backends: backends.clone(),
+        tenant: scope,
+        account_id,
+    };
+    let (run, job) = DetectJob::prepare(conn, ctx, &pipeline, request, None).await?;
+    let operation = job.spawn(&state.operations, state.postgres.clone()).await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        run_id = %run.id,
+        operation_id = %operation.id,
+        "Pipeline run accepted for background analysis"
+    );
+
+    Ok(operation)
+}
+
+/// Cancels an active run, stopping its background analysis if it is still
+/// in progress.
+///
+/// Shared by the REST endpoint and the gRPC `CancelRun`. Returns the owning
+/// pipeline, the cancelled run and the handle of the account that triggered
+/// it, or a conflict if the run has already finished.
+pub(crate) async fn cancel_run(
+    conn: &mut PgConn,
+    nats: &NatsClient,
+    operations: &OperationRunner,
+    scope: TenantScope,
+    run_id: Uuid,
+) -> Result<(WorkspacePipeline, WorkspacePipelineRun, Option<Username>)> {
+    let (pipeline, run, trigger_username) = find_pipeline_run(conn, scope, run_id).await?;
+
+    // Stop a background analysis first, so it cannot settle the run.
+    if run.status.is_running()
+        && let Some(operation) = conn
+            .find_in_progress_workspace_operation_by_target(scope, run.id)
+            .await?
+    {
+        match operations.cancel(scope, operation.id).await {
+            Ok(_) => {}
+            // It finished meanwhile; the run is cancelled all the same.
+            Err(err) if err.kind() == ErrorKind::Conflict => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let run = conn
+        .cancel_workspace_pipeline_run(scope, run.id)
+        .await?
+        .ok_or_else(|| {
+            ErrorKind::Conflict
+                .with_message("The run has already finished")
+                .with_resource("pipeline_run")
+        })?;
+
+    // Settle the progress stream for anyone following the run.
+    let mut progress = progress_reporter(nats, scope.workspace_id(), run.id).await;
+    report_stage(&mut progress, settled_stage(run.status)).await;
+
+    tracing::info!(target: TRACING_TARGET, run_id = %run.id, "Pipeline run cancelled");
+
+    Ok((pipeline, run, trigger_username))
+}
+
 /// Finds a pipeline within a workspace by slug or returns NotFound.
 async fn find_pipeline(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     pipeline_slug: &str,
 ) -> Result<WorkspacePipeline> {
-    conn.find_pipeline_in_workspace_by_slug(workspace_id, pipeline_slug)
+    conn.find_pipeline_in_workspace_by_slug(scope, pipeline_slug)
         .await?
         .map(|(pipeline, _)| pipeline)
         .ok_or_else(|| Error::not_found("pipeline"))
@@ -591,11 +1304,11 @@ async fn resolve_trigger_username(
 /// account's handle. The lookup is workspace-scoped through the owning pipeline.
 async fn find_pipeline_run(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     run_id: Uuid,
 ) -> Result<(WorkspacePipeline, WorkspacePipelineRun, Option<Username>)> {
     let (run, pipeline, trigger_username) = conn
-        .find_workspace_run_by_id(workspace_id, run_id)
+        .find_workspace_run_by_id(scope, run_id)
         .await?
         .ok_or_else(|| Error::not_found("pipeline_run"))?;
     Ok((pipeline, run, trigger_username))
@@ -617,29 +1330,283 @@ pub fn routes() -> ApiRouter<ServiceState> {
             post_with(create_pipeline_run, create_pipeline_run_docs)
                 .get_with(list_pipeline_runs, list_pipeline_runs_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/outputs/",
+            get_with(list_file_output_sets, list_file_output_sets_docs),
+        )
         .api_route(
             "/workspaces/{workspaceSlug}/runs/{runId}/",
             get_with(get_pipeline_run, get_pipeline_run_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/runs/{runId}/cancel/",
+            post_with(cancel_pipeline_run, cancel_pipeline_run_docs),
+        )
         .api_route(
             "/workspaces/{workspaceSlug}/runs/{runId}/detections/",
             get_with(get_pipeline_run_analysis, get_pipeline_run_analysis_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/runs/{runId}/reviews/",
+            post_with(review_pipeline_run, review_pipeline_run_docs)
+                .get_with(list_pipeline_run_reviews, list_pipeline_run_reviews_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/runs/{runId}/reviews/{detectionId}/",
+            get_with(
+                get_detection_review_history,
+                get_detection_review_history_docs,
+            ),
+        )
         .api_route(
             "/workspaces/{workspaceSlug}/runs/{runId}/redactions/",
             post_with(redact_pipeline_run, redact_pipeline_run_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/runs/{runId}/progress/",
+            get_with(
+                stream_pipeline_run_progress,
+                stream_pipeline_run_progress_docs,
+            ),
+        )
         .with_path_items(|item| item.tag("Pipeline Runs"))
 }
 
+/// What detect needs besides the request, shared by the REST and gRPC
+/// entry points.
+struct DetectContext {
+    nats: NatsClient,
+    crypto: CryptoService,
+    document_passwords: DocumentPasswords,
+    ids: Arc<dyn IdGenerator>,
+    backends: RegionBackends,
+    tenant: TenantScope,
+    account_id: Uuid,
+}
+
+/// The analysis half of detect, detached from the request so it can run
+/// either inline or in the background as an operation.
+struct DetectJob {
+    nats: NatsClient,
+    crypto: CryptoService,
+    ids: Arc<dyn IdGenerator>,
+    backends: RegionBackends,
+    tenant: TenantScope,
+    account_id: Uuid,
+    pipeline_id: Uuid,
+    run_id: Uuid,
+    file: WorkspaceFile,
+    document_password: Option<String>,
+    definition: PipelineDefinition,
+    scope: Option<ScopeParams>,
+}
+
+impl DetectJob {
+    /// Records a running run of `pipeline` over the requested file and
+    /// prepares its analysis.
+    ///
+    /// Rejects a password-protected file without a usable password before
+    /// the run is recorded.
+    async fn prepare(
+        conn: &mut PgConn,
+        ctx: DetectContext,
+        pipeline: &WorkspacePipeline,
+        request: CreatePipelineRun,
+        idempotency_key: Option<String>,
+    ) -> Result<(WorkspacePipelineRun, Self)> {
+        let file = conn
+            .find_file_in_workspace(ctx.tenant, request.file_id)
+            .await?
+            .ok_or_else(|| Error::not_found("file"))?;
+
+        let definition =
+            PipelineDefinition::from_parts(pipeline.definition.clone(), Vec::new(), Vec::new())
+                .map_err(serialize_error)?;
+        // The pipeline may change after the run; keep the configuration that
+        // proposed the findings with the run itself.
+        let recognizers = serde_json::to_value(&definition.recognizers).map_err(serialize_error)?;
+
+        // A one-time password is held transiently, never persisted, so redact
+        // can open the document again while it lasts.
+        let document_password = match request.document_password {
+            Some(password) => {
+                ctx.document_passwords.supply(&file, &password).await?;
+                Some(password)
+            }
+            None => held_document_password(&ctx.document_passwords, &ctx.crypto, &file).await?,
+        };
+        ensure_document_password(&file, document_password.as_deref())?;
+
+        // Create the run first so its id is the engine correlation id.
+        let new_run = NewWorkspacePipelineRun {
+            pipeline_id: pipeline.id,
+            file_id: file.id,
+            account_id: Some(ctx.account_id),
+            status: Some(PipelineRunStatus::Running),
+            idempotency_key,
+            metadata: Some(serde_json::json!({ RECOGNIZERS_METADATA_KEY: recognizers })),
+            ..Default::default()
+        };
+        let run = conn.create_workspace_pipeline_run(new_run).await?;
+
+        let job = Self {
+            nats: ctx.nats,
+            crypto: ctx.crypto,
+            ids: ctx.ids,
+            backends: ctx.backends,
+            tenant: ctx.tenant,
+            account_id: ctx.account_id,
+            pipeline_id: pipeline.id,
+            run_id: run.id,
+            file,
+            document_password,
+            definition,
+            scope: request.scope,
+        };
+
+        Ok((run, job))
+    }
+
+    /// Analyzes the run in the background, returning the operation that
+    /// tracks the analysis; its result holds the run id.
+    async fn spawn(
+        self,
+        operations: &OperationRunner,
+        pg_client: PgClient,
+    ) -> Result<WorkspaceOperation> {
+        let new_operation = NewWorkspaceOperation {
+            id: None,
+            workspace_id: self.tenant.workspace_id(),
+            account_id: Some(self.account_id),
+            kind: OperationKind::PipelineDetect,
+            target_id: Some(self.run_id),
+        };
+
+        operations
+            .spawn(self.tenant, new_operation, move |handle| async move {
+                let mut conn = pg_client.get_connection().await?;
+                let run = self.run(&mut conn, Some(&handle)).await?;
+                let result = serde_json::json!({ "runId": RunId::from_uuid(run.id) });
+                Ok(OperationOutput::new(result))
+            })
+            .await
+    }
+
+    /// Analyzes the file and stores the findings on the run, marking the run
+    /// failed if any step fails.
+    async fn run(
+        self,
+        conn: &mut PgConn,
+        operation: Option<&OperationHandle>,
+    ) -> Result<WorkspacePipelineRun> {
+        let (tenant, run_id) = (self.tenant, self.run_id);
+        let mut progress = progress_reporter(&self.nats, tenant.workspace_id(), run_id).await;
+
+        let result = self.analyze(conn, operation, &mut progress).await;
+        if result.is_err() {
+            // Nothing resumes a run that stopped halfway; record it as failed
+            // so progress subscribers and later reads see it settle.
+            fail_run(conn, tenant, run_id).await;
+            report_stage(&mut progress, RunStage::Failed).await;
+        }
+        result
+    }
+
+    /// Runs the detect stages, reporting each as it starts.
+    async fn analyze(
+        self,
+        conn: &mut PgConn,
+        operation: Option<&OperationHandle>,
+        progress: &mut Option<ProgressReporter>,
+    ) -> Result<WorkspacePipelineRun> {
+        // Assemble the engine inputs and analyze.
+        advance(progress, operation, RunStage::Loading).await;
+        let (document, pages) = build_document(
+            self.backends.nats(),
+            &self.crypto,
+            &self.file,
+            self.document_password,
+            self.run_id,
+        )
+        .await?;
+        set_pages_total(progress, pages);
+        let contexts = resolve_contexts(conn, &self.crypto, self.tenant, self.pipeline_id).await?;
+        let input_digest = input_digest(
+            &self.crypto,
+            &self.file,
+            &self.definition,
+            self.scope.as_ref(),
+            &contexts,
+            self.backends.engine(),
+        )?;
+        let params = build_analyzer_params(&self.definition, self.scope);
+
+        advance(progress, operation, RunStage::Analyzing).await;
+        let analyzed = self
+            .backends
+            .engine()
+            .analyze_document(document, &params, &contexts)
+            .await
+            .map_err(analysis_error)?;
+
+        // The analysis is a map of detected PII; encrypt it and hold it in the
+        // intermediates bucket, keeping only its key on the run.
+        advance(progress, operation, RunStage::Storing).await;
+        let analyzed_key = store_analyzed_document(
+            self.backends.nats(),
+            &self.crypto,
+            self.ids.as_ref(),
+            self.tenant.workspace_id(),
+            &analyzed,
+        )
+        .await?;
+        let run = conn
+            .update_workspace_pipeline_run(
+                self.tenant,
+                self.run_id,
+                UpdateWorkspacePipelineRun {
+                    status: Some(PipelineRunStatus::Analyzed),
+                    analyzed_document_key: Some(Some(analyzed_key)),
+                    input_digest: Some(Some(input_digest)),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        tracing::info!(target: TRACING_TARGET, run_id = %run.id, "Pipeline run analyzed");
+        report_stage(progress, RunStage::Analyzed).await;
+
+        Ok(run)
+    }
+}
+
+/// Reports a detect stage on the run's progress stream and, when the run is
+/// backed by an operation, as the operation's progress.
+async fn advance(
+    reporter: &mut Option<ProgressReporter>,
+    operation: Option<&OperationHandle>,
+    stage: RunStage,
+) {
+    report_stage(reporter, stage).await;
+
+    if let Some(operation) = operation {
+        operation.progress(stage.percent()).await;
+    }
+}
+
 /// Reads a workspace file's bytes from object storage and builds an engine
 /// [`Document`], stamping the run's id as the correlation id.
+///
+/// A password-protected document carries its password; the runtime decrypts
+/// it before processing. The document's page count is returned alongside,
+/// when it can be read, for progress reporting.
 async fn build_document(
     nats: &NatsClient,
     crypto: &CryptoService,
     file: &WorkspaceFile,
+    password: Option<String>,
     correlation_id: Uuid,
-) -> Result<Document> {
+) -> Result<(Document, Option<u32>)> {
     let store = nats.object_store::<FilesBucket, FileKey>().await?;
     let key = FileKey::from_str(&file.storage_path).map_err(|err| {
         ErrorKind::InternalServerError
@@ -650,6 +1617,14 @@ async fn build_document(
     let data = store.get(&key).await?.ok_or_else(|| {
         ErrorKind::InternalServerError.with_message("File content is missing from storage")
     })?;
+    let content_key = crypto
+        .stored_content_key(file.workspace_id, &data.info().metadata)
+        .await
+        .map_err(|err| {
+            ErrorKind::InternalServerError
+                .with_message("Failed to unwrap file content key")
+                .with_context(err.to_string())
+        })?;
     let mut reader = data.into_reader();
     let mut ciphertext = Vec::new();
     reader.read_to_end(&mut ciphertext).await.map_err(|err| {
@@ -659,14 +1634,22 @@ async fn build_document(
     })?;
 
     let bytes = crypto
-        .decrypt(file.workspace_id, &ciphertext)
+        .decrypt_content(&content_key, &ciphertext)
         .map_err(|err| {
             ErrorKind::InternalServerError
                 .with_message("Failed to decrypt file content")
                 .with_context(err.to_string())
         })?;
 
-    Ok(Document::new(bytes, file.file_extension.clone()).with_correlation_id(correlation_id))
+    let pages = count_pages(&bytes, &file.file_extension);
+    let document =
+        Document::new(bytes, file.file_extension.clone()).with_correlation_id(correlation_id);
+
+    let document = match password {
+        Some(password) => document.with_password(password),
+        None => document,
+    };
+    Ok((document, pages))
 }
 
 /// Assembles the engine's [`AnalyzerParams`] for one detect request.
@@ -692,20 +1675,56 @@ fn build_analyzer_params(
     }
 }
 
+/// Computes the digest addressing a run's analysis.
+///
+/// Covers everything the analysis depends on: the file's content, the pipeline
+/// definition, the requested scope, the resolved contexts and the recognizer
+/// lineups of the engine, which name its models. Every part is length-prefixed,
+/// so distinct inputs never hash the same bytes.
+fn input_digest(
+    crypto: &CryptoService,
+    file: &WorkspaceFile,
+    definition: &PipelineDefinition,
+    scope: Option<&ScopeParams>,
+    contexts: &[SchemaContext],
+    engine: &EngineService,
+) -> Result<Vec<u8>> {
+    let definition = serde_json::to_vec(definition).map_err(serialize_error)?;
+    let scope = serde_json::to_vec(&scope).map_err(serialize_error)?;
+    let contexts = serde_json::to_vec(contexts).map_err(serialize_error)?;
+
+    let mut hasher = crypto.sha256_context();
+    let parts: [&[u8]; 5] = [
+        &file.file_hash_sha256,
+        &definition,
+        &scope,
+        &contexts,
+        engine.lineups(),
+    ];
+    for part in parts {
+        hasher.update(&(part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+
+    Ok(hasher.digest().to_vec())
+}
+
 /// Resolves a pipeline's live context references into decrypted engine contexts.
 ///
 /// Soft-deleted contexts are already filtered out by the repository.
 async fn resolve_contexts(
     conn: &mut PgConn,
     crypto: &CryptoService,
-    workspace_id: Uuid,
+    scope: TenantScope,
     pipeline_id: Uuid,
 ) -> Result<Vec<SchemaContext>> {
     let ids = conn.list_pipeline_context_ids(pipeline_id).await?;
     let mut contexts = Vec::with_capacity(ids.len());
     for id in ids {
-        if let Some(model) = conn.find_context_in_workspace(workspace_id, id).await? {
-            contexts.push(crypto.decrypt_json::<SchemaContext>(workspace_id, &model.definition)?);
+        if let Some(model) = conn.find_context_in_workspace(scope, id).await? {
+            contexts.push(
+                crypto.decrypt_json::<SchemaContext>(scope.workspace_id(), &model.definition)?,
+            );
         }
     }
     Ok(contexts)
@@ -715,14 +1734,16 @@ async fn resolve_contexts(
 async fn resolve_policies(
     conn: &mut PgConn,
     crypto: &CryptoService,
-    workspace_id: Uuid,
+    scope: TenantScope,
     pipeline_id: Uuid,
 ) -> Result<Vec<SchemaPolicy>> {
     let ids = conn.list_pipeline_policy_ids(pipeline_id).await?;
     let mut policies = Vec::with_capacity(ids.len());
     for id in ids {
-        if let Some(model) = conn.find_policy_in_workspace(workspace_id, id).await? {
-            policies.push(crypto.decrypt_json::<SchemaPolicy>(workspace_id, &model.definition)?);
+        if let Some(model) = conn.find_policy_in_workspace(scope, id).await? {
+            policies.push(
+                crypto.decrypt_json::<SchemaPolicy>(scope.workspace_id(), &model.definition)?,
+            );
         }
     }
     Ok(policies)
@@ -736,23 +1757,40 @@ async fn store_redacted_file(
     conn: &mut PgConn,
     nats: &NatsClient,
     crypto: &CryptoService,
+    ids: &dyn IdGenerator,
     source: &WorkspaceFile,
     account_id: Uuid,
     bytes: Bytes,
 ) -> Result<WorkspaceFile> {
     // Record the plaintext size and hash before encrypting; storage holds only
-    // the ciphertext.
+    // the ciphertext. The output is as sensitive as its source.
     let plaintext_size = bytes.len() as i64;
-    let plaintext_hash = Sha256::digest(&bytes).to_vec();
-    let ciphertext = crypto.encrypt(source.workspace_id, &bytes).map_err(|err| {
+    let plaintext_hash = crypto.sha256(&bytes).to_vec();
+    let encrypt_failed = |err: CryptoError| {
         ErrorKind::InternalServerError
             .with_message("Failed to encrypt redacted file")
             .with_context(err.to_string())
-    })?;
+    };
+    let content_key = crypto
+        .new_content_key(
+            source.workspace_id,
+            source.sensitivity.requires_envelope_encryption(),
+        )
+        .await
+        .map_err(encrypt_failed)?;
+    let ciphertext = crypto
+        .encrypt_content(&content_key, &bytes)
+        .map_err(encrypt_failed)?;
 
     let store = nats.object_store::<FilesBucket, FileKey>().await?;
-    let key = FileKey::generate(source.workspace_id);
-    store.put(&key, Cursor::new(ciphertext)).await?;
+    let key = FileKey::generate_with(source.workspace_id, ids);
+    let stored = store
+        .put_with_metadata(
+            &key,
+            Cursor::new(ciphertext),
+            content_key.metadata().clone(),
+        )
+        .await?;
 
     let redacted_name = format!("{}.redacted", source.display_name);
     let new_file = NewWorkspaceFile {
@@ -767,20 +1805,24 @@ async fn store_redacted_file(
         file_hash_sha256: plaintext_hash,
         storage_path: key.to_string(),
         storage_bucket: store.bucket().to_owned(),
+        sensitivity: Some(source.sensitivity),
+        storage_version: Some(stored.version().to_owned()),
         ..Default::default()
     };
 
     Ok(conn.create_workspace_file(new_file).await?)
 }
 
-/// Encrypts an [`AnalyzedDocument`] and stores it in the intermediates bucket,
+/// Encrypts an [`AnalyzedDocument`] and stores it in the analyses bucket,
 /// returning its object-store key.
 ///
 /// The analysis is the map of detected PII, so it is encrypted with the
-/// workspace key before it leaves the process.
+/// workspace key before it leaves the process. It does not expire: reviews
+/// and redaction read it for as long as the run exists.
 async fn store_analyzed_document(
     nats: &NatsClient,
     crypto: &CryptoService,
+    ids: &dyn IdGenerator,
     workspace_id: Uuid,
     analyzed: &AnalyzedDocument,
 ) -> Result<String> {
@@ -792,9 +1834,9 @@ async fn store_analyzed_document(
     })?;
 
     let store = nats
-        .object_store::<IntermediatesBucket, IntermediateKey>()
+        .object_store::<AnalysesBucket, IntermediateKey>()
         .await?;
-    let key = IntermediateKey::generate(workspace_id);
+    let key = IntermediateKey::generate_with(workspace_id, ids);
     store.put(&key, Cursor::new(ciphertext)).await?;
 
     Ok(key.to_string())
@@ -803,7 +1845,9 @@ async fn store_analyzed_document(
 /// Fetches and decrypts a run's stored [`AnalyzedDocument`].
 ///
 /// Errors if the run has not been analyzed yet or the stored object is missing.
-async fn load_analyzed_document(
+/// Analyses stored before they had their own bucket are still read from the
+/// intermediates bucket, until its TTL removes them.
+pub(super) async fn load_analyzed_document(
     nats: &NatsClient,
     crypto: &CryptoService,
     workspace_id: Uuid,
@@ -820,10 +1864,17 @@ async fn load_analyzed_document(
             .with_context(err.to_string())
     })?;
 
-    let store = nats
-        .object_store::<IntermediatesBucket, IntermediateKey>()
+    let analyses = nats
+        .object_store::<AnalysesBucket, IntermediateKey>()
         .await?;
-    let data = store.get(&key).await?.ok_or_else(|| {
+    let mut data = analyses.get(&key).await?;
+    if data.is_none() {
+        let intermediates = nats
+            .object_store::<IntermediatesBucket, IntermediateKey>()
+            .await?;
+        data = intermediates.get(&key).await?;
+    }
+    let data = data.ok_or_else(|| {
         ErrorKind::InternalServerError.with_message("Analysis is missing from storage")
     })?;
     let mut reader = data.into_reader();
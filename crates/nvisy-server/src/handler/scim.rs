@@ -0,0 +1,1172 @@
+//! SCIM 2.0 provisioning handlers (RFC 7644).
+//!
+//! Identity providers manage a workspace's users and their roles through
+//! these endpoints. A SCIM `User` is an account seen through its membership
+//! in the workspace: deactivating it removes the membership, but the account
+//! stays visible to the provider through a SCIM identity so it can be
+//! reactivated later. Groups are fixed, one per role that can be granted;
+//! moving a user between groups changes their role.
+
+use std::collections::HashSet;
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::StatusCode;
+use nvisy_postgres::model::{
+    Account, NewAccount, NewAccountIdentity, NewWorkspaceMember, UpdateAccount,
+    UpdateWorkspaceMember, WorkspaceMember,
+};
+use nvisy_postgres::query::{
+    AccountIdentityRepository, AccountRepository, TenantScope, WorkspaceMemberRepository,
+};
+use nvisy_postgres::types::WorkspaceRole;
+use nvisy_postgres::{AsyncConnection, PgClient, PgConnection, PgResult};
+use serde::Serialize;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use super::request::{ScimGroupPathParams, ScimListQuery, ScimUserPathParams};
+use super::response::ErrorResponse;
+use super::sso::available_username;
+use crate::extract::{AuthState, Json, Path, Permission, Query, WorkspaceContext};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::scim::{
+    Filter, PatchRequest, SCHEMA_GROUP, SCHEMA_SERVICE_PROVIDER_CONFIG, SCHEMA_USER, ScimEmail,
+    ScimError, ScimGroup, ScimListResponse, ScimMember, ScimMeta, ScimUser,
+};
+use crate::service::{
+    CryptoService, MemberRoleChanged, MembershipSource, PasswordService, ServiceState,
+    WebhookEmitter,
+};
+
+/// Tracing target for SCIM provisioning operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::scim";
+
+/// Roles that can be granted through SCIM groups, with their group names.
+/// Ownership is never provisioned.
+const GROUPS: [(WorkspaceRole, &str, &str); 3] = [
+    (WorkspaceRole::Admin, "admin", "Admins"),
+    (WorkspaceRole::Member, "member", "Members"),
+    (WorkspaceRole::Guest, "guest", "Guests"),
+];
+
+/// Role given to users that are provisioned or reactivated.
+const DEFAULT_ROLE: WorkspaceRole = WorkspaceRole::Guest;
+
+/// Lists the workspace's SCIM users.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn list_users(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Query(query): Query<ScimListQuery>,
+) -> Result<(StatusCode, Json<ScimListResponse<ScimUser>>)> {
+    let filter = parse_filter(&query)?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let base = base_path(&workspace.slug);
+    let users = visible_users(&mut conn, scope, &base).await?;
+    let users = filter_resources(users, filter.as_ref())?;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        user_count = users.len(),
+        "SCIM users listed",
+    );
+
+    let response = ScimListResponse::paginate(users, query.start_index(), query.count());
+    Ok((StatusCode::OK, Json(response)))
+}
+
+fn list_users_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List SCIM users")
+        .description(
+            "Returns the workspace's members and deactivated provisioned users as SCIM \
+             resources, optionally narrowed by a filter expression.",
+        )
+        .response::<200, Json<ScimListResponse<ScimUser>>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Provisions a user into the workspace.
+///
+/// Links the account with the user's email address, creating it when none
+/// exists, and adds it to the workspace with the default role when active.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        member_id = tracing::field::Empty,
+    )
+)]
+async fn create_user(
+    State(pg_client): State<PgClient>,
+    State(password): State<PasswordService>,
+    State(crypto): State<CryptoService>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Json(request): Json<ScimUser>,
+) -> Result<(StatusCode, Json<ScimUser>)> {
+    let Some(email_address) = request.email().map(str::to_owned) else {
+        return Err(ScimError::InvalidValue("the user needs an email address".to_owned()).into());
+    };
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    // Generated before the transaction: hashing is deliberately slow.
+    let unusable_password = password.hash(&crypto.generate_secret()?)?;
+
+    let actor = auth_state.account_id;
+    let (account, member) = conn
+        .transaction(async |conn| {
+            let account = match conn.find_account_by_email(&email_address).await? {
+                Some(account) => {
+                    if is_provisioned(conn, scope, account.id).await? {
+                        return Err(ScimError::Uniqueness(format!(
+                            "user '{email_address}' already exists"
+                        ))
+                        .into());
+                    }
+                    account
+                }
+                None => {
+                    create_account(conn, &request, email_address.clone(), unusable_password).await?
+                }
+            };
+
+            let member = if request.active {
+                let member = add_member(conn, scope, account.id, DEFAULT_ROLE, actor).await?;
+                let change = MembershipChange::Added(DEFAULT_ROLE);
+                emit_change(
+                    conn,
+                    &webhook_emitter,
+                    workspace.id,
+                    account.id,
+                    actor,
+                    change,
+                )
+                .await?;
+                Some(member)
+            } else {
+                None
+            };
+
+            conn.create_account_identity(NewAccountIdentity {
+                account_id: account.id,
+                issuer: scim_issuer(workspace.id),
+                subject: account.id.to_string(),
+                email_address: Some(account.email_address.clone()),
+            })
+            .await?;
+
+            Ok::<_, Error>((account, member))
+        })
+        .await?;
+
+    tracing::Span::current().record("member_id", tracing::field::display(account.id));
+
+    tracing::info!(target: TRACING_TARGET, "SCIM user provisioned");
+
+    let base = base_path(&workspace.slug);
+    let mut user = render_user(&base, &account, member.map(|m| m.member_role));
+    user.external_id = request.external_id;
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+fn create_user_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Provision SCIM user")
+        .description(
+            "Adds a user to the workspace, creating an account for the email address when \
+             none exists. Active users join with the guest role.",
+        )
+        .response::<201, Json<ScimUser>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Returns a single SCIM user.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        member_id = %path_params.user_id,
+    )
+)]
+async fn get_user(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<ScimUserPathParams>,
+) -> Result<(StatusCode, Json<ScimUser>)> {
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let (account, member) = find_user(&mut conn, scope, path_params.user_id).await?;
+
+    let base = base_path(&workspace.slug);
+    let user = render_user(&base, &account, member.map(|m| m.member_role));
+    Ok((StatusCode::OK, Json(user)))
+}
+
+fn get_user_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get SCIM user")
+        .description("Returns a workspace member or deactivated provisioned user.")
+        .response::<200, Json<ScimUser>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Replaces a SCIM user.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        member_id = %path_params.user_id,
+    )
+)]
+async fn replace_user(
+    State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<ScimUserPathParams>,
+    Json(request): Json<ScimUser>,
+) -> Result<(StatusCode, Json<ScimUser>)> {
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let actor = auth_state.account_id;
+    let (account, member) = conn
+        .transaction(async |conn| {
+            let (account, member) = find_user(conn, scope, path_params.user_id).await?;
+            let email_address = request.email().map(str::to_owned);
+            let (account, member, change) =
+                apply_user(conn, scope, actor, account, member, email_address, &request).await?;
+
+            if let Some(change) = change {
+                emit_change(
+                    conn,
+                    &webhook_emitter,
+                    workspace.id,
+                    account.id,
+                    actor,
+                    change,
+                )
+                .await?;
+            }
+
+            Ok::<_, Error>((account, member))
+        })
+        .await?;
+
+    tracing::info!(target: TRACING_TARGET, "SCIM user replaced");
+
+    let base = base_path(&workspace.slug);
+    let mut user = render_user(&base, &account, member.map(|m| m.member_role));
+    user.external_id = request.external_id;
+    Ok((StatusCode::OK, Json(user)))
+}
+
+fn replace_user_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Replace SCIM user")
+        .description(
+            "Updates the user's email address and display name. Setting `active` to false \
+             removes the user from the workspace; setting it to true adds them back.",
+        )
+        .response::<200, Json<ScimUser>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Applies a SCIM PATCH to a user.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        member_id = %path_params.user_id,
+    )
+)]
+async fn patch_user(
+    State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<ScimUserPathParams>,
+    Json(request): Json<PatchRequest>,
+) -> Result<(StatusCode, Json<ScimUser>)> {
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let base = base_path(&workspace.slug);
+    let actor = auth_state.account_id;
+    let (account, member) = conn
+        .transaction(async |conn| {
+            let (account, member) = find_user(conn, scope, path_params.user_id).await?;
+            let current = render_user(&base, &account, member.as_ref().map(|m| m.member_role));
+            let patched: ScimUser = patch_resource(&current, &request)?;
+
+            // Providers often rename a user through `userName` alone, leaving
+            // the emails as they were.
+            let email_address =
+                if patched.user_name != current.user_name && patched.user_name.contains('@') {
+                    Some(patched.user_name.clone())
+                } else {
+                    patched.email().map(str::to_owned)
+                };
+
+            let (account, member, change) =
+                apply_user(conn, scope, actor, account, member, email_address, &patched).await?;
+
+            if let Some(change) = change {
+                emit_change(
+                    conn,
+                    &webhook_emitter,
+                    workspace.id,
+                    account.id,
+                    actor,
+                    change,
+                )
+                .await?;
+            }
+
+            Ok::<_, Error>((account, member))
+        })
+        .await?;
+
+    tracing::info!(target: TRACING_TARGET, "SCIM user patched");
+
+    let user = render_user(&base, &account, member.map(|m| m.member_role));
+    Ok((StatusCode::OK, Json(user)))
+}
+
+fn patch_user_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Patch SCIM user")
+        .description(
+            "Applies SCIM PATCH operations to the user. Accepts the same changes as a \
+             replace, including deactivation through `active`.",
+        )
+        .response::<200, Json<ScimUser>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
+}
+
+/// Deprovisions a SCIM user.
+///
+/// Removes the user from the workspace and forgets the SCIM identity; the
+/// account itself is kept, since it may belong to other workspaces.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        member_id = %path_params.user_id,
+    )
+)]
+async fn delete_user(
+    State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<ScimUserPathParams>,
+) -> Result<StatusCode> {
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let account_id = path_params.user_id;
+    let actor = auth_state.account_id;
+    conn.transaction(async |conn| {
+        let (_, member) = find_user(conn, scope, account_id).await?;
+        if let Some(member) = &member {
+            if member.is_owner() {
+                return Err(ScimError::Mutability(
+                    "workspace owners cannot be deprovisioned".to_owned(),
+                )
+                .into());
+            }
+            conn.remove_workspace_member(scope, account_id).await?;
+
+            let change = MembershipChange::Removed(member.member_role);
+            emit_change(
+                conn,
+                &webhook_emitter,
+                workspace.id,
+                account_id,
+                actor,
+                change,
+            )
+            .await?;
+        }
+
+        conn.delete_account_identity(&scim_issuer(workspace.id), &account_id.to_string())
+            .await?;
+
+        Ok::<_, Error>(())
+    })
+    .await?;
+
+    tracing::warn!(target: TRACING_TARGET, "SCIM user deprovisioned");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn delete_user_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Deprovision SCIM user")
+        .description(
+            "Removes the user from the workspace and stops tracking them for provisioning. \
+             The account is kept. Workspace owners cannot be deprovisioned.",
+        )
+        .response::<204, ()>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Lists the workspace's SCIM groups.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+    )
+)]
+async fn list_groups(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Query(query): Query<ScimListQuery>,
+) -> Result<(StatusCode, Json<ScimListResponse<ScimGroup>>)> {
+    let filter = parse_filter(&query)?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let members = conn.list_workspace_members_with_accounts(scope).await?;
+
+    let base = base_path(&workspace.slug);
+    let groups = GROUPS
+        .iter()
+        .map(|&(role, ..)| render_group(&base, role, &members))
+        .collect();
+    let groups = filter_resources(groups, filter.as_ref())?;
+
+    let response = ScimListResponse::paginate(groups, query.start_index(), query.count());
+    Ok((StatusCode::OK, Json(response)))
+}
+
+fn list_groups_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List SCIM groups")
+        .description(
+            "Returns one group per workspace role that can be provisioned, with the members \
+             holding that role.",
+        )
+        .response::<200, Json<ScimListResponse<ScimGroup>>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Returns a single SCIM group.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        group_id = %path_params.group_id,
+    )
+)]
+async fn get_group(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<ScimGroupPathParams>,
+) -> Result<(StatusCode, Json<ScimGroup>)> {
+    let role = group_role(&path_params.group_id)?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let members = conn.list_workspace_members_with_accounts(scope).await?;
+
+    let base = base_path(&workspace.slug);
+    Ok((StatusCode::OK, Json(render_group(&base, role, &members))))
+}
+
+fn get_group_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get SCIM group")
+        .description("Returns the group of a workspace role with its members.")
+        .response::<200, Json<ScimGroup>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Applies a SCIM PATCH to a group's members.
+///
+/// Users added to the group get its role, joining the workspace if needed.
+/// Users removed from a group fall back to the guest role; removing them
+/// from the guests removes them from the workspace.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        group_id = %path_params.group_id,
+    )
+)]
+async fn patch_group(
+    State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<ScimGroupPathParams>,
+    Json(request): Json<PatchRequest>,
+) -> Result<(StatusCode, Json<ScimGroup>)> {
+    let role = group_role(&path_params.group_id)?;
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ManageRoles)
+        .await?;
+
+    let base = base_path(&workspace.slug);
+    let actor = auth_state.account_id;
+    let group = conn
+        .transaction(async |conn| {
+            let members = conn.list_workspace_members_with_accounts(scope).await?;
+            let current = render_group(&base, role, &members);
+            let patched: ScimGroup = patch_resource(&current, &request)?;
+            if patched.display_name != current.display_name {
+                return Err(
+                    ScimError::Mutability("group names cannot be changed".to_owned()).into(),
+                );
+            }
+
+            let before = member_ids(&current)?;
+            let after = member_ids(&patched)?;
+
+            let mut changes = Vec::new();
+            for &account_id in after.difference(&before) {
+                let member = members
+                    .iter()
+                    .find(|(member, _)| member.account_id == account_id)
+                    .map(|(member, _)| member.clone());
+                if let Some(change) =
+                    grant_role(conn, scope, actor, account_id, member, role).await?
+                {
+                    changes.push((account_id, change));
+                }
+            }
+            for &account_id in before.difference(&after) {
+                let change = if role == DEFAULT_ROLE {
+                    conn.remove_workspace_member(scope, account_id).await?;
+                    MembershipChange::Removed(role)
+                } else {
+                    set_role(conn, scope, actor, account_id, DEFAULT_ROLE).await?;
+                    MembershipChange::Updated(role, DEFAULT_ROLE)
+                };
+                changes.push((account_id, change));
+            }
+
+            for (account_id, change) in changes {
+                emit_change(
+                    conn,
+                    &webhook_emitter,
+                    workspace.id,
+                    account_id,
+                    actor,
+                    change,
+                )
+                .await?;
+            }
+
+            let members = conn.list_workspace_members_with_accounts(scope).await?;
+            Ok::<_, Error>(render_group(&base, role, &members))
+        })
+        .await?;
+
+    tracing::info!(target: TRACING_TARGET, "SCIM group patched");
+
+    Ok((StatusCode::OK, Json(group)))
+}
+
+fn patch_group_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Patch SCIM group")
+        .description(
+            "Adds or removes group members, which grants or revokes the group's workspace \
+             role. Group names are read-only.",
+        )
+        .response::<200, Json<ScimGroup>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Returns the SCIM features this server supports.
+#[tracing::instrument(skip_all)]
+async fn service_provider_config(
+    WorkspaceContext(workspace): WorkspaceContext,
+) -> Result<(StatusCode, Json<Value>)> {
+    let config = json!({
+        "schemas": [SCHEMA_SERVICE_PROVIDER_CONFIG],
+        "patch": { "supported": true },
+        "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+        "filter": { "supported": true, "maxResults": ScimListQuery::MAX_COUNT },
+        "changePassword": { "supported": false },
+        "sort": { "supported": false },
+        "etag": { "supported": false },
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "Bearer token",
+            "description": "An API key or access token with permission to manage roles.",
+            "primary": true,
+        }],
+        "meta": {
+            "resourceType": "ServiceProviderConfig",
+            "location": format!("{}/ServiceProviderConfig", base_path(&workspace.slug)),
+        },
+    });
+
+    Ok((StatusCode::OK, Json(config)))
+}
+
+fn service_provider_config_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get SCIM service provider configuration")
+        .description("Describes the SCIM features supported by this server.")
+        .response::<200, Json<Value>>()
+        .response::<401, Json<ErrorResponse>>()
+}
+
+/// A change to a user's workspace membership, reported through webhooks.
+enum MembershipChange {
+    Added(WorkspaceRole),
+    Updated(WorkspaceRole, WorkspaceRole),
+    Removed(WorkspaceRole),
+}
+
+/// Records the member event matching a membership change.
+async fn emit_change(
+    conn: &mut PgConnection,
+    webhook_emitter: &WebhookEmitter,
+    workspace_id: Uuid,
+    account_id: Uuid,
+    actor: Uuid,
+    change: MembershipChange,
+) -> PgResult<()> {
+    match change {
+        MembershipChange::Added(role) => {
+            let data = json!({ "role": role.to_string(), "source": "scim" });
+            webhook_emitter
+                .emit_member_added(conn, workspace_id, account_id, Some(actor), Some(data))
+                .await
+        }
+        MembershipChange::Updated(previous, new) if previous != new => {
+            let change = MemberRoleChanged {
+                previous_role: previous,
+                new_role: new,
+                previous_custom_role_id: None,
+                custom_role_id: None,
+                source: MembershipSource::Scim,
+            };
+            webhook_emitter
+                .emit_member_role_changed(conn, workspace_id, account_id, Some(actor), &change)
+                .await
+        }
+        MembershipChange::Updated(previous, new) => {
+            let data = json!({
+                "previousRole": previous.to_string(),
+                "newRole": new.to_string(),
+                "source": "scim",
+            });
+            webhook_emitter
+                .emit_member_updated(conn, workspace_id, account_id, Some(actor), Some(data))
+                .await
+        }
+        MembershipChange::Removed(role) => {
+            let data = json!({ "role": role.to_string(), "source": "scim" });
+            webhook_emitter
+                .emit_member_deleted(conn, workspace_id, account_id, Some(actor), Some(data))
+                .await
+        }
+    }
+}
+
+/// Issuer of the identities that mark accounts as provisioned into a
+/// workspace; the subject is the account id.
+fn scim_issuer(workspace_id: Uuid) -> String {
+    format!("scim:{workspace_id}")
+}
+
+/// Path of a workspace's SCIM endpoints, used in resource locations.
+fn base_path(workspace_slug: &str) -> String {
+    format!("/workspaces/{workspace_slug}/scim/v2")
+}
+
+/// Returns whether the account is a member of, or provisioned into, the
+/// workspace.
+async fn is_provisioned(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    account_id: Uuid,
+) -> Result<bool> {
+    if conn
+        .find_workspace_member(scope, account_id)
+        .await?
+        .is_some()
+    {
+        return Ok(true);
+    }
+
+    let identity = conn
+        .find_account_identity(&scim_issuer(scope.workspace_id()), &account_id.to_string())
+        .await?;
+    Ok(identity.is_some())
+}
+
+/// Returns every user visible to the provider: the workspace's members,
+/// followed by provisioned users that were deactivated.
+async fn visible_users(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    base: &str,
+) -> Result<Vec<ScimUser>> {
+    let members = conn.list_workspace_members_with_accounts(scope).await?;
+
+    let mut seen: HashSet<Uuid> = members
+        .iter()
+        .map(|(member, _)| member.account_id)
+        .collect();
+    let mut users: Vec<ScimUser> = members
+        .iter()
+        .map(|(member, account)| render_user(base, account, Some(member.member_role)))
+        .collect();
+
+    let identities = conn
+        .list_account_identities_by_issuer(&scim_issuer(scope.workspace_id()))
+        .await?;
+    for identity in identities {
+        if !seen.insert(identity.account_id) {
+            continue;
+        }
+        if let Some(account) = conn.find_account_by_id(identity.account_id).await? {
+            users.push(render_user(base, &account, None));
+        }
+    }
+
+    Ok(users)
+}
+
+/// Finds a user visible to the provider, with their membership if active.
+async fn find_user(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    account_id: Uuid,
+) -> Result<(Account, Option<WorkspaceMember>)> {
+    if let Some((member, account)) = conn
+        .find_workspace_member_with_account(scope, account_id)
+        .await?
+    {
+        return Ok((account, Some(member)));
+    }
+
+    let not_found = || Error::not_found("scim_user");
+    conn.find_account_identity(&scim_issuer(scope.workspace_id()), &account_id.to_string())
+        .await?
+        .ok_or_else(not_found)?;
+    let account = conn
+        .find_account_by_id(account_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    Ok((account, None))
+}
+
+/// Creates an account for a user the provider introduced.
+///
+/// The account gets a random password nobody knows; its owner signs in
+/// through single sign-on or resets the password.
+async fn create_account(
+    conn: &mut PgConnection,
+    user: &ScimUser,
+    email_address: String,
+    password_hash: String,
+) -> Result<Account> {
+    let hint = email_address.split('@').next().unwrap_or("user");
+    let username = available_username(conn, hint).await?;
+
+    let account = conn
+        .create_account(NewAccount {
+            username,
+            display_name: user.preferred_display_name(),
+            email_address,
+            password_hash,
+            avatar_url: None,
+            timezone: None,
+            locale: None,
+        })
+        .await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        member_id = %account.id,
+        "Account provisioned through SCIM",
+    );
+
+    Ok(account)
+}
+
+/// Brings an account and its membership in line with the desired user.
+async fn apply_user(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    actor: Uuid,
+    account: Account,
+    member: Option<WorkspaceMember>,
+    email_address: Option<String>,
+    desired: &ScimUser,
+) -> Result<(Account, Option<WorkspaceMember>, Option<MembershipChange>)> {
+    let mut changes = UpdateAccount::default();
+    let mut changed = false;
+
+    if let Some(email_address) = email_address
+        && !email_address.eq_ignore_ascii_case(&account.email_address)
+    {
+        if conn
+            .email_exists_for_other(&email_address, account.id)
+            .await?
+        {
+            return Err(ScimError::Uniqueness(format!(
+                "email address '{email_address}' is already in use"
+            ))
+            .into());
+        }
+        changes.email_address = Some(email_address);
+        changed = true;
+    }
+
+    let display_name = desired.preferred_display_name();
+    if display_name != account.display_name {
+        changes.display_name = Some(display_name);
+        changed = true;
+    }
+
+    let account = if changed {
+        conn.update_account(account.id, changes).await?
+    } else {
+        account
+    };
+
+    let (member, change) = match member {
+        Some(member) if !desired.active => {
+            if member.is_owner() {
+                return Err(ScimError::Mutability(
+                    "workspace owners cannot be deactivated".to_owned(),
+                )
+                .into());
+            }
+            conn.remove_workspace_member(scope, account.id).await?;
+            (None, Some(MembershipChange::Removed(member.member_role)))
+        }
+        None if desired.active => {
+            let member = add_member(conn, scope, account.id, DEFAULT_ROLE, actor).await?;
+            (Some(member), Some(MembershipChange::Added(DEFAULT_ROLE)))
+        }
+        member => (member, None),
+    };
+
+    Ok((account, member, change))
+}
+
+/// Gives an account a group's role, adding it to the workspace if needed.
+async fn grant_role(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    actor: Uuid,
+    account_id: Uuid,
+    member: Option<WorkspaceMember>,
+    role: WorkspaceRole,
+) -> Result<Option<MembershipChange>> {
+    match member {
+        Some(member) if member.is_owner() => Err(ScimError::Mutability(
+            "workspace owners cannot be assigned to groups".to_owned(),
+        )
+        .into()),
+        Some(member) if member.member_role == role => Ok(None),
+        Some(member) => {
+            set_role(conn, scope, actor, account_id, role).await?;
+            Ok(Some(MembershipChange::Updated(member.member_role, role)))
+        }
+        None => {
+            if conn.find_account_by_id(account_id).await?.is_none() {
+                return Err(
+                    ScimError::InvalidValue(format!("no user with id '{account_id}'")).into(),
+                );
+            }
+            add_member(conn, scope, account_id, role, actor).await?;
+            Ok(Some(MembershipChange::Added(role)))
+        }
+    }
+}
+
+async fn add_member(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    account_id: Uuid,
+    role: WorkspaceRole,
+    actor: Uuid,
+) -> Result<WorkspaceMember> {
+    let member = NewWorkspaceMember {
+        created_by: actor,
+        updated_by: actor,
+        ..NewWorkspaceMember::new(scope.workspace_id(), account_id, role)
+    };
+    Ok(conn.add_workspace_member(member).await?)
+}
+
+async fn set_role(
+    conn: &mut PgConnection,
+    scope: TenantScope,
+    actor: Uuid,
+    account_id: Uuid,
+    role: WorkspaceRole,
+) -> Result<WorkspaceMember> {
+    let changes = UpdateWorkspaceMember {
+        member_role: Some(role),
+        updated_by: Some(actor),
+        ..Default::default()
+    };
+    Ok(conn
+        .update_workspace_member(scope, account_id, changes)
+        .await?)
+}
+
+/// Renders an account as a SCIM user; `role` is its role in the workspace,
+/// if it is a member.
+fn render_user(base: &str, account: &Account, role: Option<WorkspaceRole>) -> ScimUser {
+    let id = account.id.to_string();
+    let groups = role
+        .and_then(group_of)
+        .map(|(group_id, name)| ScimMember {
+            value: group_id.to_owned(),
+            display: Some(name.to_owned()),
+            reference: Some(format!("{base}/Groups/{group_id}")),
+        })
+        .into_iter()
+        .collect();
+
+    ScimUser {
+        schemas: vec![SCHEMA_USER.to_owned()],
+        id: Some(id.clone()),
+        external_id: None,
+        user_name: account.email_address.clone(),
+        name: None,
+        display_name: account.display_name.clone(),
+        emails: vec![ScimEmail {
+            value: account.email_address.clone(),
+            kind: Some("work".to_owned()),
+            primary: true,
+        }],
+        active: role.is_some() && !account.is_suspended(),
+        groups,
+        meta: Some(ScimMeta {
+            resource_type: "User".to_owned(),
+            created: Some(account.created_at.into()),
+            last_modified: Some(account.updated_at.into()),
+            location: Some(format!("{base}/Users/{id}")),
+        }),
+    }
+}
+
+/// Renders the group of a role with the members holding it.
+fn render_group(
+    base: &str,
+    role: WorkspaceRole,
+    members: &[(WorkspaceMember, Account)],
+) -> ScimGroup {
+    let (group_id, display_name) = group_of(role).unwrap_or(("", ""));
+    let members = members
+        .iter()
+        .filter(|(member, _)| member.member_role == role)
+        .map(|(member, account)| ScimMember {
+            value: member.account_id.to_string(),
+            display: Some(account.email_address.clone()),
+            reference: Some(format!("{base}/Users/{}", member.account_id)),
+        })
+        .collect();
+
+    ScimGroup {
+        schemas: vec![SCHEMA_GROUP.to_owned()],
+        id: Some(group_id.to_owned()),
+        display_name: display_name.to_owned(),
+        members,
+        meta: Some(ScimMeta {
+            resource_type: "Group".to_owned(),
+            created: None,
+            last_modified: None,
+            location: Some(format!("{base}/Groups/{group_id}")),
+        }),
+    }
+}
+
+/// Returns the id and name of a role's group; owners have none.
+fn group_of(role: WorkspaceRole) -> Option<(&'static str, &'static str)> {
+    GROUPS
+        .iter()
+        .find(|(group_role, ..)| *group_role == role)
+        .map(|&(_, id, name)| (id, name))
+}
+
+/// Resolves a group id to its role.
+fn group_role(group_id: &str) -> Result<WorkspaceRole> {
+    GROUPS
+        .iter()
+        .find(|(_, id, _)| *id == group_id)
+        .map(|&(role, ..)| role)
+        .ok_or_else(|| Error::not_found("scim_group"))
+}
+
+/// Collects the account ids of a group's members.
+fn member_ids(group: &ScimGroup) -> Result<HashSet<Uuid>> {
+    group
+        .members
+        .iter()
+        .map(|member| {
+            member.value.parse().map_err(|_| {
+                Error::from(ScimError::InvalidValue(format!(
+                    "no user with id '{}'",
+                    member.value
+                )))
+            })
+        })
+        .collect()
+}
+
+fn parse_filter(query: &ScimListQuery) -> Result<Option<Filter>> {
+    Ok(query
+        .filter
+        .as_deref()
+        .map(str::parse::<Filter>)
+        .transpose()?)
+}
+
+/// Keeps the resources matching `filter`.
+fn filter_resources<T: Serialize>(resources: Vec<T>, filter: Option<&Filter>) -> Result<Vec<T>> {
+    let Some(filter) = filter else {
+        return Ok(resources);
+    };
+
+    let mut matching = Vec::with_capacity(resources.len());
+    for resource in resources {
+        if filter.matches(&to_value(&resource)?) {
+            matching.push(resource);
+        }
+    }
+    Ok(matching)
+}
+
+/// Applies a PATCH request to the JSON form of a resource and reads it back.
+fn patch_resource<T>(resource: &T, request: &PatchRequest) -> Result<T>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+{
+    let mut value = to_value(resource)?;
+    request.apply(&mut value)?;
+    serde_json::from_value(value)
+        .map_err(|err| Error::from(ScimError::InvalidValue(err.to_string())))
+}
+
+fn to_value<T: Serialize>(resource: &T) -> Result<Value> {
+    serde_json::to_value(resource).map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Failed to serialize SCIM resource")
+            .with_context(err.to_string())
+    })
+}
+
+/// Returns a [`Router`] with all SCIM related routes.
+///
+/// Paths have no trailing slash: SCIM clients are configured with the base
+/// URL and append resource paths such as `/Users` themselves.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/workspaces/{workspaceSlug}/scim/v2/Users",
+            get_with(list_users, list_users_docs).post_with(create_user, create_user_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/scim/v2/Users/{userId}",
+            get_with(get_user, get_user_docs)
+                .put_with(replace_user, replace_user_docs)
+                .patch_with(patch_user, patch_user_docs)
+                .delete_with(delete_user, delete_user_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/scim/v2/Groups",
+            get_with(list_groups, list_groups_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/scim/v2/Groups/{groupId}",
+            get_with(get_group, get_group_docs).patch_with(patch_group, patch_group_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/scim/v2/ServiceProviderConfig",
+            get_with(service_provider_config, service_provider_config_docs),
+        )
+        .with_path_items(|item| item.tag("SCIM"))
+}
@@ -0,0 +1,543 @@
+//! Document share link handlers.
+//!
+//! A share link grants someone outside the workspace read access to one
+//! document, optionally limited to some of its pages, until it expires, is
+//! revoked or has been opened `maxViews` times. Opening a link is public:
+//! the token is the credential. Every copy it serves is watermarked with the
+//! link's recipient unless the link was created without watermarking, and
+//! every attempt to open it, granted or refused, is recorded in the audit
+//! log.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use ipnet::IpNet;
+use jiff::Timestamp;
+use nvisy_nats::object::{FileKey, FilesBucket};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::{
+    NewWorkspaceActivity, NewWorkspaceFileAccess, WorkspaceFile, WorkspaceFileShare,
+};
+use nvisy_postgres::query::{
+    WorkspaceFileRepository, WorkspaceFileShareRepository, WorkspaceRepository,
+};
+use nvisy_postgres::types::{ActivityType, FileFormat};
+use uuid::Uuid;
+
+use crate::extract::{
+    AppConnectInfo, AuthProvider, AuthState, Json, Path, Permission, ValidateJson, WorkspaceContext,
+};
+use crate::handler::files::{read_file_content, record_file_access};
+use crate::handler::request::{
+    CreateShareLink, FileSharePathParams, SharedDocumentPathParams, WorkspaceFilePathParams,
+};
+use crate::handler::response::{ErrorResponse, ShareLink, ShareLinkCreated, ShareLinks};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{
+    AuditLog, CryptoService, IssuedShareToken, ResidencyService, SHARE_TOKEN_PREFIX, ServiceState,
+    Watermark, WatermarkMode, select_pages, supports_page_selection,
+};
+
+/// Tracing target for share link operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::shares";
+
+/// Response header naming how a served copy was watermarked.
+const WATERMARK_HEADER: &str = "x-nvisy-watermark";
+
+/// Returns the format of a file, as far as sharing is concerned.
+fn file_format(file: &WorkspaceFile) -> Option<FileFormat> {
+    FileFormat::from_extension(&file.file_extension)
+}
+
+/// Creates a share link for a file.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn create_share_link(
+    State(pg_client): State<PgClient>,
+    State(crypto): State<CryptoService>,
+    State(audit): State<AuditLog>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+    ValidateJson(request): ValidateJson<CreateShareLink>,
+) -> Result<(StatusCode, Json<ShareLinkCreated>)> {
+    tracing::debug!(target: TRACING_TARGET, "Creating share link");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ShareFiles)
+        .await?;
+
+    let file = conn
+        .find_file_in_workspace(scope, path_params.file_id)
+        .await?
+        .ok_or_else(|| Error::not_found("file"))?;
+
+    // Refuse up front what could not be honored when the link is opened,
+    // rather than serving an unmarked or whole copy.
+    let format = file_format(&file);
+    if request.watermark && format == Some(FileFormat::Doc) {
+        return Err(ErrorKind::BadRequest
+            .with_message("Word documents cannot be watermarked")
+            .with_resource("share_link")
+            .with_suggestion(
+                "Share a PDF export of the document, or create the link with `watermark` set \
+                 to false",
+            ));
+    }
+    if request.watermark && format.and_then(WatermarkMode::for_format).is_none() {
+        return Err(ErrorKind::BadRequest
+            .with_message("This file's format cannot be watermarked")
+            .with_resource("share_link")
+            .with_suggestion("Create the link with `watermark` set to false"));
+    }
+    if request.watermark && file.is_password_protected() {
+        return Err(ErrorKind::BadRequest
+            .with_message("Password-protected documents cannot be watermarked")
+            .with_resource("share_link")
+            .with_suggestion("Create the link with `watermark` set to false"));
+    }
+    if request.pages.is_some() && !format.is_some_and(supports_page_selection) {
+        return Err(ErrorKind::BadRequest
+            .with_message("This file's format cannot be limited to pages")
+            .with_resource("share_link")
+            .with_suggestion("Omit `pages` to share the whole document"));
+    }
+
+    let issued = IssuedShareToken::issue(&crypto)?;
+    let new_share = request.into_model(workspace.id, file.id, auth_state.account_id, &issued)?;
+    let share = conn.create_workspace_file_share(new_share).await?;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        share_id = %share.id,
+        expires_at = %Timestamp::from(share.expires_at),
+        "Share link created",
+    );
+
+    audit
+        .record(share_activity(
+            &share,
+            ActivityType::ShareCreated,
+            Some(auth_state.account_id),
+            "Created a share link",
+        ))
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ShareLinkCreated::from_model(share, issued.secret)),
+    ))
+}
+
+fn create_share_link_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create share link")
+        .description(
+            "Creates a link granting read access to the file, optionally limited to some \
+             pages and to a number of views, until it expires. The link's token is returned \
+             only in this response. Text documents are paginated by form feeds; text \
+             documents and PDFs are watermarked with a line at the top of every page. \
+             Images are a single page and carry the watermark in their metadata. PDFs and \
+             Word documents can only be shared whole, and Word documents only without a \
+             watermark.",
+        )
+        .response::<201, Json<ShareLinkCreated>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Lists a file's share links.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        file_id = %path_params.file_id,
+    )
+)]
+async fn list_share_links(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WorkspaceFilePathParams>,
+) -> Result<(StatusCode, Json<ShareLinks>)> {
+    tracing::debug!(target: TRACING_TARGET, "Listing share links");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ShareFiles)
+        .await?;
+
+    conn.find_file_in_workspace(scope, path_params.file_id)
+        .await?
+        .ok_or_else(|| Error::not_found("file"))?;
+
+    let shares = conn
+        .list_workspace_file_shares(scope, path_params.file_id)
+        .await?;
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        share_count = shares.len(),
+        "Share links listed",
+    );
+
+    let shares = shares.into_iter().map(ShareLink::from_model).collect();
+    Ok((StatusCode::OK, Json(shares)))
+}
+
+fn list_share_links_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List share links")
+        .description(
+            "Lists the file's share links, newest first, including expired and revoked \
+             ones, with how often each has been opened.",
+        )
+        .response::<200, Json<ShareLinks>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Revokes a share link.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        share_id = %path_params.share_id,
+    )
+)]
+async fn revoke_share_link(
+    State(pg_client): State<PgClient>,
+    State(audit): State<AuditLog>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<FileSharePathParams>,
+) -> Result<(StatusCode, Json<ShareLink>)> {
+    tracing::debug!(target: TRACING_TARGET, "Revoking share link");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ShareFiles)
+        .await?;
+
+    let not_found = || {
+        ErrorKind::NotFound
+            .with_message("No active share link with this id")
+            .with_resource("share_link")
+    };
+
+    let share = conn
+        .find_workspace_file_share(scope, path_params.share_id)
+        .await?
+        .filter(|share| share.file_id == path_params.file_id)
+        .ok_or_else(not_found)?;
+    let share = conn
+        .revoke_workspace_file_share(scope, share.id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    tracing::info!(target: TRACING_TARGET, "Share link revoked");
+
+    audit
+        .record(share_activity(
+            &share,
+            ActivityType::ShareRevoked,
+            Some(auth_state.account_id),
+            "Revoked a share link",
+        ))
+        .await?;
+
+    Ok((StatusCode::OK, Json(ShareLink::from_model(share))))
+}
+
+fn revoke_share_link_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Revoke share link")
+        .description("Revokes a share link. It stops working immediately.")
+        .response::<200, Json<ShareLink>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Opens a share link, serving the shared document.
+///
+/// Requires no authentication: the token is the credential. The view is
+/// counted before the document is read, so a failed read still uses up a
+/// view rather than letting concurrent requests exceed the limit.
+#[tracing::instrument(skip_all)]
+async fn open_share_link(
+    State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
+    State(crypto): State<CryptoService>,
+    State(audit): State<AuditLog>,
+    ConnectInfo(connect_info): ConnectInfo<AppConnectInfo>,
+    request_headers: HeaderMap,
+    Path(path_params): Path<SharedDocumentPathParams>,
+) -> Result<(StatusCode, HeaderMap, Vec<u8>)> {
+    tracing::debug!(target: TRACING_TARGET, "Opening share link");
+
+    let invalid = || ErrorKind::NotFound.with_message("Share link is invalid or has been removed");
+    if !path_params.token.starts_with(SHARE_TOKEN_PREFIX) {
+        return Err(invalid());
+    }
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let token_hash = IssuedShareToken::hash(&crypto, &path_params.token);
+    let (share, scope) = conn
+        .find_workspace_file_share_by_hash(&token_hash)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let client = AccessClient {
+        ip_address: Some(IpNet::from(connect_info.client_ip())),
+        user_agent: request_headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned),
+    };
+
+    let Some(share) = conn
+        .record_workspace_file_share_view(scope, share.id)
+        .await?
+    else {
+        // Re-read to report why: the link may have changed since the lookup.
+        let share = conn
+            .find_workspace_file_share(scope, share.id)
+            .await?
+            .unwrap_or(share);
+        let reason = refusal_reason(&share);
+
+        tracing::warn!(
+            target: TRACING_TARGET,
+            share_id = %share.id,
+            reason,
+            "Share link refused",
+        );
+        audit
+            .record(access_activity(&share, &client, Err(reason)))
+            .await?;
+
+        return Err(ErrorKind::Forbidden
+            .with_message(format!("Share link is {reason}"))
+            .with_resource("share_link"));
+    };
+
+    let file = conn
+        .find_file_in_workspace(scope, share.file_id)
+        .await?
+        .ok_or_else(|| Error::not_found("file"))?;
+    let workspace = conn
+        .find_workspace_by_id(scope)
+        .await?
+        .ok_or_else(|| Error::not_found("workspace"))?;
+
+    let nats_client = residency.backends(workspace.data_region)?.nats();
+    let file_store = nats_client.object_store::<FilesBucket, FileKey>().await?;
+    let mut content = read_file_content(&file_store, &crypto, &file).await?;
+
+    let format = file_format(&file);
+    if let Some(pages) = share.granted_pages() {
+        content = format
+            .and_then(|format| select_pages(format, &content, &pages))
+            .ok_or_else(|| {
+                ErrorKind::InternalServerError.with_message("Shared pages could not be extracted")
+            })?;
+    }
+
+    let mut headers = HeaderMap::new();
+    if share.watermark {
+        let (format, mode) = format
+            .and_then(|format| Some((format, WatermarkMode::for_format(format)?)))
+            .ok_or_else(|| {
+                ErrorKind::InternalServerError.with_message("Document cannot be watermarked")
+            })?;
+        let watermark = Watermark::new(&share.recipient, share.id, Timestamp::now());
+        content = watermark.apply(format, &content).map_err(|err| {
+            ErrorKind::InternalServerError
+                .with_message("Document could not be watermarked")
+                .with_context(err.to_string())
+        })?;
+        headers.insert(WATERMARK_HEADER, HeaderValue::from_static(mode.as_str()));
+    }
+
+    audit
+        .record(access_activity(&share, &client, Ok(content.len())))
+        .await?;
+    record_file_access(
+        &mut conn,
+        NewWorkspaceFileAccess::share_view(share.workspace_id, file.id),
+    )
+    .await;
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        share_id = %share.id,
+        view_count = share.view_count,
+        size = content.len(),
+        "Shared document served",
+    );
+
+    // The display name is user-controlled, so keep it from breaking out of
+    // the quoted header value.
+    let safe_name: String = file
+        .display_name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect();
+    let disposition = format!("inline; filename=\"{safe_name}\"")
+        .parse()
+        .unwrap_or_else(|_| HeaderValue::from_static("inline"));
+    let content_type = file
+        .mime_type
+        .as_deref()
+        .and_then(|mime_type| mime_type.parse().ok())
+        .unwrap_or_else(|| HeaderValue::from_static("application/octet-stream"));
+
+    headers.insert(header::CONTENT_DISPOSITION, disposition);
+    headers.insert(header::CONTENT_TYPE, content_type);
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+    Ok((StatusCode::OK, headers, content))
+}
+
+fn open_share_link_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Open share link")
+        .description(
+            "Serves the document a share link grants, limited to its pages and \
+             watermarked with its recipient. Needs no other credentials. Each request \
+             counts as one view; the `X-Nvisy-Watermark` header tells whether the copy \
+             carries a `visible` or `embedded` watermark.",
+        )
+        .response::<200, ()>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// The client opening a share link, as recorded in the audit log.
+struct AccessClient {
+    ip_address: Option<IpNet>,
+    user_agent: Option<String>,
+}
+
+/// Returns why a share link cannot be opened.
+fn refusal_reason(share: &WorkspaceFileShare) -> &'static str {
+    if share.is_revoked() {
+        "revoked"
+    } else if share.is_expired() {
+        "expired"
+    } else {
+        "exhausted"
+    }
+}
+
+/// Builds the audit record of a change to a share link.
+fn share_activity(
+    share: &WorkspaceFileShare,
+    activity_type: ActivityType,
+    account_id: Option<Uuid>,
+    description: &str,
+) -> NewWorkspaceActivity {
+    NewWorkspaceActivity {
+        workspace_id: share.workspace_id,
+        account_id,
+        activity_type,
+        resource_id: Some(share.id),
+        description: Some(description.to_owned()),
+        metadata: Some(serde_json::json!({
+            "fileId": share.file_id,
+            "recipient": share.recipient,
+            "pages": share.granted_pages(),
+            "maxViews": share.max_views,
+            "expiresAt": Timestamp::from(share.expires_at),
+        })),
+        ..Default::default()
+    }
+}
+
+/// Builds the audit record of an attempt to open a share link: the number
+/// of bytes served, or why it was refused.
+fn access_activity(
+    share: &WorkspaceFileShare,
+    client: &AccessClient,
+    outcome: std::result::Result<usize, &str>,
+) -> NewWorkspaceActivity {
+    let (description, metadata) = match outcome {
+        Ok(size) => (
+            "Opened a share link",
+            serde_json::json!({
+                "fileId": share.file_id,
+                "recipient": share.recipient,
+                "granted": true,
+                "viewCount": share.view_count,
+                "size": size,
+            }),
+        ),
+        Err(reason) => (
+            "Refused to open a share link",
+            serde_json::json!({
+                "fileId": share.file_id,
+                "recipient": share.recipient,
+                "granted": false,
+                "reason": reason,
+            }),
+        ),
+    };
+
+    NewWorkspaceActivity {
+        workspace_id: share.workspace_id,
+        account_id: None,
+        activity_type: ActivityType::ShareAccessed,
+        resource_id: Some(share.id),
+        description: Some(description.to_owned()),
+        metadata: Some(metadata),
+        ip_address: client.ip_address,
+        user_agent: client.user_agent.clone(),
+    }
+}
+
+/// Returns a [`Router`] with the workspace share link routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/shares/",
+            post_with(create_share_link, create_share_link_docs)
+                .get_with(list_share_links, list_share_links_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/files/{fileId}/shares/{shareId}/",
+            delete_with(revoke_share_link, revoke_share_link_docs),
+        )
+        .with_path_items(|item| item.tag("Shares"))
+}
+
+/// Returns a [`Router`] with the public share link routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn public_routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/shares/{token}/",
+            get_with(open_share_link, open_share_link_docs),
+        )
+        .with_path_items(|item| item.tag("Shares"))
+}
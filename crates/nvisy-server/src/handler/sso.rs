@@ -0,0 +1,456 @@
+//! Single sign-on handlers for the OpenID Connect authorization-code flow.
+//!
+//! The client starts a login, navigates the user to the returned provider
+//! URL, and forwards the provider's callback parameters to the callback
+//! endpoint, which answers with a regular session token. Accounts are
+//! provisioned on the first login through an identity, and workspace
+//! membership is brought in line with the user's provider groups on every
+//! login.
+
+use std::sync::Arc;
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum_extra::headers::UserAgent;
+use jiff::{Span, Timestamp};
+use nvisy_core::clock::Clock;
+use nvisy_core::id::IdGenerator;
+use nvisy_postgres::model::{
+    Account, NewAccount, NewAccountApiToken, NewAccountIdentity, NewWorkspaceMember,
+    UpdateWorkspaceMember,
+};
+use nvisy_postgres::query::{
+    AccountApiTokenRepository, AccountIdentityRepository, AccountRepository,
+    WorkspaceCustomRoleRepository, WorkspaceMemberRepository,
+};
+use nvisy_postgres::types::{
+    ApiTokenType, HasDeletedAt, USERNAME_MAX_LENGTH, USERNAME_MIN_LENGTH, Username,
+};
+use nvisy_postgres::{AsyncConnection, PgClient, PgConnection};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::request::{SsoCallback, SsoLogin};
+use super::response::{AuthToken, ErrorResponse, SsoAuthorization, SsoLogout, SsoToken};
+use crate::extract::{AuthClaims, AuthHeader, AuthState, Json, Query, TypedHeader};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{
+    CryptoService, IdTokenClaims, OidcError, OidcService, PasswordService, Provisioning,
+    ServiceState, SessionKeys, UserAgentParser,
+};
+
+/// Tracing target for single sign-on operations.
+const TRACING_TARGET: &str = "nvisy_server::handler::sso";
+
+/// Attempts at finding a free username before giving up.
+const USERNAME_ATTEMPTS: usize = 5;
+
+/// Starts a single sign-on login.
+#[tracing::instrument(skip_all)]
+async fn sso_login(
+    State(oidc): State<OidcService>,
+    Query(request): Query<SsoLogin>,
+) -> Result<Json<SsoAuthorization>> {
+    request.validate()?;
+
+    let authorization_url = oidc
+        .begin_login(request.redirect_to, request.remember_me)
+        .await?;
+
+    tracing::debug!(target: TRACING_TARGET, "Single sign-on login started");
+
+    Ok(Json(SsoAuthorization {
+        authorization_url: authorization_url.into(),
+    }))
+}
+
+fn sso_login_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Start single sign-on")
+        .description(
+            "Starts an OpenID Connect login and returns the identity provider URL to send \
+             the user to. The login must be completed within 10 minutes.",
+        )
+        .response::<200, Json<SsoAuthorization>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Completes a single sign-on login and issues a session token.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all)]
+async fn sso_callback(
+    State(pg_client): State<PgClient>,
+    State(oidc): State<OidcService>,
+    State(password): State<PasswordService>,
+    State(crypto): State<CryptoService>,
+    State(auth_keys): State<SessionKeys>,
+    State(ua_parser): State<UserAgentParser>,
+    State(clock): State<Arc<dyn Clock>>,
+    State(ids): State<Arc<dyn IdGenerator>>,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Query(request): Query<SsoCallback>,
+) -> Result<(StatusCode, Json<SsoToken>)> {
+    request.validate()?;
+
+    if let Some(error) = request.error {
+        tracing::warn!(target: TRACING_TARGET, error = %error, "Identity provider denied login");
+        return Err(OidcError::Denied(request.error_description.unwrap_or(error)).into());
+    }
+    let Some(code) = request.code else {
+        return Err(ErrorKind::BadRequest
+            .with_message("Missing authorization code")
+            .with_resource("code"));
+    };
+
+    let login = oidc.complete_login(&request.state, &code).await?;
+    let provisioning = oidc.provisioning()?;
+    let claims = &login.claims;
+
+    // Generated before the transaction: hashing is deliberately slow.
+    let unusable_password = password.hash(&crypto.generate_secret()?)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let account = conn
+        .transaction(async |conn| {
+            let account = resolve_account(conn, provisioning, claims, unusable_password).await?;
+            sync_membership(conn, provisioning, &account, claims).await?;
+            Ok::<_, Error>(account)
+        })
+        .await?;
+
+    if account.is_suspended() {
+        tracing::warn!(target: TRACING_TARGET, reason = "account_suspended", "SSO login failed");
+        return Err(ErrorKind::Forbidden
+            .with_resource("account")
+            .with_message("Account is suspended"));
+    }
+    if account.is_deleted() {
+        tracing::warn!(target: TRACING_TARGET, reason = "account_deleted", "SSO login failed");
+        return Err(ErrorKind::Forbidden
+            .with_resource("account")
+            .with_message("Account has been deleted"));
+    }
+
+    let now = clock.now();
+    let expired_at = now + Span::new().hours(90 * 24);
+    let new_token = NewAccountApiToken {
+        id: Some(ids.generate()),
+        account_id: account.id,
+        name: ua_parser.parse(user_agent.as_str()),
+        ip_address: None,
+        user_agent: Some(user_agent.to_string()),
+        is_remembered: Some(login.remember_me),
+        session_type: Some(ApiTokenType::Web),
+        issued_at: Some(now.into()),
+        expired_at: Some(expired_at.into()),
+    };
+    let account_api_token = conn.create_account_api_token(new_token).await?;
+
+    let auth_claims = AuthClaims::new(&account, &account_api_token);
+    let auth_header = AuthHeader::new(auth_claims, auth_keys);
+
+    let auth_claims = auth_header.as_auth_claims();
+    let api_token = auth_header.into_string()?;
+    let response = SsoToken {
+        token: AuthToken {
+            api_token,
+            username: account.username.clone(),
+            issued_at: Timestamp::from_second(auth_claims.issued_at).unwrap_or(now),
+            expires_at: Timestamp::from_second(auth_claims.expires_at).unwrap_or(now),
+        },
+        redirect_to: login.redirect_to,
+    };
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        token_id = %auth_claims.token_id,
+        account_id = %auth_claims.account_id,
+        issuer = %claims.issuer,
+        "SSO login successful",
+    );
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+fn sso_callback_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Complete single sign-on")
+        .description(
+            "Redeems the identity provider's callback parameters and returns an access \
+             token. Accounts are created on first login when provisioning allows it.",
+        )
+        .response::<201, Json<SsoToken>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Ends the current session and returns the provider's sign-out URL.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_claims.account_id,
+        token_id = %auth_claims.token_id,
+    )
+)]
+async fn sso_logout(
+    State(pg_client): State<PgClient>,
+    State(oidc): State<OidcService>,
+    AuthState(auth_claims): AuthState,
+) -> Result<Json<SsoLogout>> {
+    let mut conn = pg_client.get_connection().await?;
+    if conn.delete_account_api_token(auth_claims.token_id).await? {
+        tracing::info!(target: TRACING_TARGET, "SSO logout successful");
+    }
+
+    Ok(Json(SsoLogout {
+        end_session_url: oidc.end_session_url().map(Into::into),
+    }))
+}
+
+fn sso_logout_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Single sign-on logout")
+        .description(
+            "Invalidates the current access token. When the identity provider supports it, \
+             the response carries a URL that ends the provider session as well.",
+        )
+        .response::<200, Json<SsoLogout>>()
+        .response::<401, Json<ErrorResponse>>()
+}
+
+/// Finds the account linked to the asserted identity, linking or creating
+/// one on the first login.
+async fn resolve_account(
+    conn: &mut PgConnection,
+    provisioning: &Provisioning,
+    claims: &IdTokenClaims,
+    unusable_password: String,
+) -> Result<Account> {
+    if let Some(identity) = conn
+        .find_account_identity(&claims.issuer, &claims.subject)
+        .await?
+    {
+        conn.touch_account_identity(identity.id, claims.email.clone())
+            .await?;
+        return conn
+            .find_account_by_id(identity.account_id)
+            .await?
+            .ok_or_else(|| {
+                ErrorKind::Forbidden
+                    .with_resource("account")
+                    .with_message("Account has been deleted")
+            });
+    }
+
+    // Only an email the provider vouches for may claim an existing account.
+    let existing = match claims.verified_email() {
+        Some(email) if provisioning.link_by_email => conn.find_account_by_email(email).await?,
+        _ => None,
+    };
+
+    let account = match existing {
+        Some(account) => {
+            tracing::info!(
+                target: TRACING_TARGET,
+                account_id = %account.id,
+                "Linking identity to existing account"
+            );
+            account
+        }
+        None if provisioning.create_accounts => {
+            create_account(conn, claims, unusable_password).await?
+        }
+        None => return Err(OidcError::NotProvisioned("account creation is disabled").into()),
+    };
+
+    conn.create_account_identity(NewAccountIdentity {
+        account_id: account.id,
+        issuer: claims.issuer.clone(),
+        subject: claims.subject.clone(),
+        email_address: claims.email.clone(),
+    })
+    .await?;
+
+    Ok(account)
+}
+
+/// Creates an account for a first-time provider user.
+///
+/// The account gets a random password nobody knows, so it can only sign in
+/// through the provider until its owner sets one.
+async fn create_account(
+    conn: &mut PgConnection,
+    claims: &IdTokenClaims,
+    password_hash: String,
+) -> Result<Account> {
+    let Some(email_address) = claims.email.clone() else {
+        return Err(
+            OidcError::NotProvisioned("the provider did not share an email address").into(),
+        );
+    };
+    if conn.email_exists(&email_address).await? {
+        return Err(
+            OidcError::NotProvisioned("an account with this email address already exists").into(),
+        );
+    }
+
+    let hint = claims
+        .preferred_username
+        .as_deref()
+        .or_else(|| claims.email.as_deref().and_then(|e| e.split('@').next()))
+        .unwrap_or("user");
+    let username = available_username(conn, hint).await?;
+    let account = conn
+        .create_account(NewAccount {
+            username,
+            display_name: claims.name.clone(),
+            email_address,
+            password_hash,
+            avatar_url: None,
+            timezone: None,
+            locale: None,
+        })
+        .await?;
+
+    let account = if claims.verified_email().is_some() {
+        conn.verify_account(account.id).await?
+    } else {
+        account
+    };
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        account_id = %account.id,
+        "Account provisioned from identity provider",
+    );
+
+    Ok(account)
+}
+
+/// Picks a free username derived from a profile name, for accounts created
+/// on behalf of an identity provider.
+pub(super) async fn available_username(conn: &mut PgConnection, hint: &str) -> Result<Username> {
+    let base = username_base(hint);
+
+    for attempt in 0..USERNAME_ATTEMPTS {
+        let candidate = if attempt == 0 {
+            base.clone()
+        } else {
+            let suffix = &Uuid::new_v4().simple().to_string()[..6];
+            format!("{base}-{suffix}")
+        };
+
+        let Ok(username) = Username::parse(candidate) else {
+            continue;
+        };
+        if !conn.username_exists(&username).await? {
+            return Ok(username);
+        }
+    }
+
+    Err(ErrorKind::Conflict
+        .with_resource("username")
+        .with_message("Could not find a free username for this account"))
+}
+
+/// Folds a profile name into username form, leaving room for a suffix.
+fn username_base(hint: &str) -> String {
+    let mut base = String::with_capacity(hint.len());
+    for c in hint.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            base.push(c);
+        } else if !base.is_empty() && !base.ends_with('-') {
+            base.push('-');
+        }
+    }
+
+    // Keep room for a `-xxxxxx` disambiguation suffix.
+    base.truncate(USERNAME_MAX_LENGTH - 7);
+    let mut base = base.trim_end_matches('-').to_owned();
+    while base.len() < USERNAME_MIN_LENGTH {
+        base.push('0');
+    }
+    base
+}
+
+/// Brings the user's membership in the provisioning workspace in line with
+/// their provider groups.
+///
+/// The provider is the source of truth for provisioned roles, so a role
+/// changed in the application is reset on the next login. Owners are never
+/// demoted, and users without a mapped role keep whatever membership they
+/// have.
+async fn sync_membership(
+    conn: &mut PgConnection,
+    provisioning: &Provisioning,
+    account: &Account,
+    claims: &IdTokenClaims,
+) -> Result<()> {
+    let Some(workspace_id) = provisioning.workspace_id else {
+        return Ok(());
+    };
+    let Some(role) = provisioning.role_for(claims.groups(&provisioning.groups_claim)) else {
+        return Ok(());
+    };
+
+    let membership = conn
+        .find_workspace_member_with_custom_role(workspace_id, account.id)
+        .await?;
+    match membership {
+        None => {
+            conn.add_workspace_member(NewWorkspaceMember::new(workspace_id, account.id, role))
+                .await?;
+        }
+        Some((member, _, scope)) if member.member_role != role && !member.is_owner() => {
+            let changes = UpdateWorkspaceMember {
+                member_role: Some(role),
+                updated_by: Some(account.id),
+                ..Default::default()
+            };
+            conn.update_workspace_member(scope, account.id, changes)
+                .await?;
+        }
+        Some(_) => return Ok(()),
+    }
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        account_id = %account.id,
+        workspace_id = %workspace_id,
+        role = %role,
+        "Workspace membership provisioned",
+    );
+
+    Ok(())
+}
+
+/// Returns a [`Router`] with all related routes.
+///
+/// [`Router`]: axum::routing::Router
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route("/auth/sso/login/", get_with(sso_login, sso_login_docs))
+        .api_route(
+            "/auth/sso/callback/",
+            get_with(sso_callback, sso_callback_docs),
+        )
+        .api_route("/auth/sso/logout/", post_with(sso_logout, sso_logout_docs))
+        .with_path_items(|item| item.tag("Authentication"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_username_base() {
+        assert_eq!(username_base("Ada.Lovelace"), "ada-lovelace");
+        assert_eq!(username_base("--x"), "x00");
+        assert_eq!(username_base("émile_zola"), "mile-zola");
+        assert!(username_base(&"a".repeat(64)).len() <= USERNAME_MAX_LENGTH - 7);
+        assert!(Username::parse(username_base("a.b..c")).is_ok());
+    }
+}
@@ -0,0 +1,183 @@
+//! Administrator object storage handlers.
+//!
+//! Maintenance of the objects stored for every workspace, for operators
+//! rather than workspace members: only global administrators may call it.
+//! Intermediate artifacts expire with their bucket's TTL; a cleanup removes
+//! them sooner, in every region, and can count what it would remove first.
+//! Objects of the other stages are removed with the files, runs and
+//! operations that refer to them, so they cannot be bulk deleted.
+//!
+//! Bucket TTLs are applied to every region at startup; the lifecycle
+//! endpoint reports whether each one is in effect.
+
+use aide::axum::ApiRouter;
+use aide::transform::TransformOperation;
+use axum::extract::State;
+use axum::http::StatusCode;
+use nvisy_nats::object::{
+    DeleteOptions, DeleteOutput, IntermediateKey, IntermediatesBucket, ObjectKey,
+};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::query::AdminScope;
+use nvisy_postgres::types::StorageStage;
+
+use crate::extract::{AuthProvider, AuthState, Json, ValidateJson};
+use crate::handler::request::CleanupStorage;
+use crate::handler::response::{
+    ErrorResponse, StorageCleanup, StorageLifecycle, StorageLifecycles,
+};
+use crate::handler::{ErrorKind, Result};
+use crate::service::{ResidencyService, ServiceState};
+
+/// Tracing target for object storage maintenance.
+const TRACING_TARGET: &str = "nvisy_server::handler::storage";
+
+/// Bulk deletes the stored objects of a stage past a given age.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        stage = %request.stage,
+        dry_run = request.dry_run,
+    )
+)]
+async fn cleanup_storage(
+    State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
+    AuthState(auth_state): AuthState,
+    ValidateJson(request): ValidateJson<CleanupStorage>,
+) -> Result<(StatusCode, Json<StorageCleanup>)> {
+    tracing::debug!(target: TRACING_TARGET, "Cleaning up stored objects");
+
+    auth_state.authorize_admin()?;
+
+    if request.stage != StorageStage::Intermediate {
+        return Err(ErrorKind::BadRequest
+            .with_message("Only intermediate objects can be bulk deleted")
+            .with_resource("storage")
+            .with_suggestion(
+                "Objects of other stages are removed with the files, runs and operations that \
+                 refer to them",
+            ));
+    }
+
+    let mut conn = pg_client.get_connection().await?;
+    AdminScope::open(&mut conn, "bulk delete intermediate objects").await?;
+
+    let options = if request.dry_run {
+        DeleteOptions::dry_run()
+    } else {
+        DeleteOptions::default()
+    };
+
+    let mut total = DeleteOutput {
+        dry_run: request.dry_run,
+        ..DeleteOutput::default()
+    };
+    for (region, backends) in residency.regions() {
+        let options = options.clone().with_progress(move |progress| {
+            tracing::info!(
+                target: TRACING_TARGET,
+                region = %region,
+                matched = progress.matched,
+                deleted = progress.deleted,
+                bytes = progress.bytes,
+                "Storage cleanup in progress"
+            );
+        });
+
+        let store = backends
+            .nats()
+            .object_store::<IntermediatesBucket, IntermediateKey>()
+            .await?;
+        let output = store
+            .delete_older_than(IntermediateKey::PREFIX, request.older_than(), &options)
+            .await?;
+        total.add(output);
+    }
+
+    tracing::info!(
+        target: TRACING_TARGET,
+        matched = total.matched,
+        deleted = total.deleted,
+        bytes = total.bytes,
+        "Storage cleanup finished"
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(StorageCleanup::from_output(request.stage, total)),
+    ))
+}
+
+fn cleanup_storage_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Clean up stored objects")
+        .description(
+            "Deletes the objects of a pipeline stage last written at least the given number of \
+             hours ago, in every data region, and reports how many matched and how many bytes \
+             they held. A dry run only counts them. Only intermediate objects can be bulk \
+             deleted. Requires global administrator rights.",
+        )
+        .response::<200, Json<StorageCleanup>>()
+        .response::<400, Json<ErrorResponse>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Reports the expiration rules of the object buckets in every region.
+#[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
+async fn read_storage_lifecycle(
+    State(residency): State<ResidencyService>,
+    AuthState(auth_state): AuthState,
+) -> Result<(StatusCode, Json<StorageLifecycles>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading object bucket lifecycles");
+
+    auth_state.authorize_admin()?;
+
+    let mut lifecycles = StorageLifecycles::new();
+    for (region, backends) in residency.regions() {
+        let buckets = backends.nats().object_bucket_lifecycles().await?;
+        for lifecycle in buckets {
+            if !lifecycle.is_applied() {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    region = %region,
+                    bucket = lifecycle.bucket,
+                    "Object bucket TTL is not in effect"
+                );
+            }
+            lifecycles.push(StorageLifecycle::from_lifecycle(region, lifecycle));
+        }
+    }
+
+    Ok((StatusCode::OK, Json(lifecycles)))
+}
+
+fn read_storage_lifecycle_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get storage lifecycle")
+        .description(
+            "Lists the object buckets of every data region with the age at which their objects \
+             should expire and the age at which the server expires them. The TTLs are applied \
+             at startup; a bucket whose TTL is not in effect is flagged. Requires global \
+             administrator rights.",
+        )
+        .response::<200, Json<StorageLifecycles>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+}
+
+/// Returns routes for administrator object storage maintenance.
+pub fn routes() -> ApiRouter<ServiceState> {
+    use aide::axum::routing::*;
+
+    ApiRouter::new()
+        .api_route(
+            "/admin/storage/cleanup/",
+            post_with(cleanup_storage, cleanup_storage_docs),
+        )
+        .api_route(
+            "/admin/storage/lifecycle/",
+            get_with(read_storage_lifecycle, read_storage_lifecycle_docs),
+        )
+        .with_path_items(|item| item.tag("Storage"))
+}
@@ -11,14 +11,14 @@ use axum::http::StatusCode;
 use axum_extra::headers::UserAgent;
 use nvisy_postgres::model::{AccountApiToken, UpdateAccountApiToken};
 use nvisy_postgres::query::{AccountApiTokenRepository, AccountRepository};
-use nvisy_postgres::types::ApiTokenType;
+use nvisy_postgres::types::{ApiKeyScope, ApiTokenType};
 use nvisy_postgres::{PgClient, PgConn};
 use uuid::Uuid;
 
 use super::request::{CreateApiToken, CursorPagination, TokenPathParams, UpdateApiToken};
 use super::response::{ApiToken, ApiTokenWithJWT, ApiTokensPage, ErrorResponse};
 use crate::extract::{
-    AuthClaims, AuthHeader, AuthState, Json, Path, Query, TypedHeader, ValidateJson,
+    AuthClaims, AuthHeader, AuthProvider, AuthState, Json, Path, Query, TypedHeader, ValidateJson,
 };
 use crate::handler::{ErrorKind, Result};
 use crate::service::{ServiceState, SessionKeys};
@@ -40,6 +40,8 @@ async fn create_api_token(
 ) -> Result<(StatusCode, Json<ApiTokenWithJWT>)> {
     tracing::debug!(target: TRACING_TARGET, "Creating API token");
 
+    auth_claims.authorize_scope(ApiKeyScope::Admin)?;
+
     let mut conn = pg_client.get_connection().await?;
 
     // Fetch the account to generate JWT claims
@@ -150,6 +152,8 @@ async fn update_api_token(
 ) -> Result<(StatusCode, Json<ApiToken>)> {
     tracing::debug!(target: TRACING_TARGET, "Updating API token");
 
+    auth_state.authorize_scope(ApiKeyScope::Admin)?;
+
     let mut conn = pg_client.get_connection().await?;
 
     // Verify the token exists and belongs to the authenticated account
@@ -194,6 +198,8 @@ async fn revoke_api_token(
 ) -> Result<StatusCode> {
     tracing::warn!(target: TRACING_TARGET, "Revoking API token");
 
+    auth_state.authorize_scope(ApiKeyScope::Admin)?;
+
     let mut conn = pg_client.get_connection().await?;
 
     // Verify the token exists and belongs to the authenticated account
@@ -20,6 +20,8 @@ pub enum BuiltinModule {
     Accounts,
     /// API tokens.
     Tokens,
+    /// Scoped account API keys.
+    ApiKeys,
     /// Workspaces.
     Workspaces,
     /// Provider connections.
@@ -30,20 +32,42 @@ pub enum BuiltinModule {
     Invites,
     /// Workspace members.
     Members,
+    /// Permissions and custom roles.
+    Roles,
     /// Webhooks.
     Webhooks,
+    /// Inbound webhooks (`/inbound/{inboundWebhookId}/` is public).
+    InboundWebhooks,
     /// Files.
     Files,
+    /// Document share links (`/shares/{token}/` is public).
+    Shares,
     /// Pipelines.
     Pipelines,
     /// Pipeline runs.
     PipelineRuns,
     /// Redaction policies.
     Policies,
+    /// Retention policy and legal holds.
+    Retention,
     /// Account notifications.
     Notifications,
+    /// Real-time workspace events (SSE).
+    Events,
+    /// Long-running operations (`/operations/{id}`).
+    Operations,
+    /// SCIM provisioning (`/workspaces/{workspaceSlug}/scim/v2/*`).
+    Scim,
+    /// Administrator analytics (`/admin/analytics/*`).
+    Analytics,
+    /// Administrator object storage maintenance (`/admin/storage/*`).
+    Storage,
+    /// GraphQL queries (`/graphql/`), served with the `graphql` feature.
+    GraphQl,
     /// Authentication (`/auth/*`, public).
     Authentication,
+    /// Single sign-on (`/auth/sso/*`, public).
+    Sso,
 }
 
 /// Type alias for a function that maps/transforms an ApiRouter.
@@ -10,7 +10,9 @@ use aide::transform::TransformOperation;
 use axum::extract::State;
 use axum::http::StatusCode;
 use nvisy_postgres::model::WorkspaceWebhook;
-use nvisy_postgres::query::WorkspaceWebhookRepository;
+use nvisy_postgres::query::{
+    TenantScope, WorkspaceWebhookDeliveryRepository, WorkspaceWebhookRepository,
+};
 use nvisy_postgres::types::Username;
 use nvisy_postgres::{PgClient, PgConn};
 use nvisy_webhook::WebhookService;
@@ -23,13 +25,13 @@ use crate::extract::{
 };
 use crate::handler::request::{
     CreateWebhook, CursorPagination, TestWebhook, UpdateWebhook as UpdateWebhookRequest,
-    WebhookPathParams,
+    WebhookPathParams, WebhookStatsWindow,
 };
 use crate::handler::response::{
-    ErrorResponse, Webhook, WebhookCreated, WebhookResult, WebhooksPage,
+    ErrorResponse, Webhook, WebhookCreated, WebhookDeliveryStats, WebhookResult, WebhooksPage,
 };
 use crate::handler::{Error, ErrorKind, Result};
-use crate::service::{CryptoService, ServiceState};
+use crate::service::{CryptoService, PayloadFilter, ServiceState};
 
 /// Tracing target for workspace webhook operations.
 const TRACING_TARGET: &str = "nvisy_server::handler::webhooks";
@@ -55,13 +57,13 @@ async fn create_webhook(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::CreateWebhooks)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::CreateWebhooks)
         .await?;
 
     // Generate the signing secret here so it is returned once and stored only
     // encrypted; the server decrypts it to sign each delivery.
-    let secret = crypto.generate_secret();
+    let secret = crypto.generate_secret()?;
     let encrypted_secret = crypto.encrypt(workspace.id, secret.as_bytes())?;
 
     let new_webhook = request.into_model(workspace.id, auth_state.account_id, encrypted_secret);
@@ -73,7 +75,7 @@ async fn create_webhook(
         "Webhook created",
     );
 
-    let (webhook, creator_username) = find_webhook(&mut conn, workspace.id, webhook.id).await?;
+    let (webhook, creator_username) = find_webhook(&mut conn, scope, webhook.id).await?;
 
     // Return WebhookCreated which includes the secret (visible only once)
     Ok((
@@ -120,12 +122,12 @@ async fn list_webhooks(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewWebhooks)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewWebhooks)
         .await?;
 
     let page = conn
-        .cursor_list_workspace_webhooks(workspace.id, pagination.into())
+        .cursor_list_workspace_webhooks(scope, pagination.into())
         .await?;
 
     tracing::debug!(
@@ -174,12 +176,12 @@ async fn read_webhook(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewWebhooks)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewWebhooks)
         .await?;
 
     let (webhook, creator_username) =
-        find_webhook(&mut conn, workspace.id, path_params.webhook_id.as_uuid()).await?;
+        find_webhook(&mut conn, scope, path_params.webhook_id.as_uuid()).await?;
 
     tracing::debug!(target: TRACING_TARGET, "Workspace webhook read");
 
@@ -224,19 +226,18 @@ async fn update_webhook(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::UpdateWebhooks)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::UpdateWebhooks)
         .await?;
 
-    let (existing, _) =
-        find_webhook(&mut conn, workspace.id, path_params.webhook_id.as_uuid()).await?;
+    let (existing, _) = find_webhook(&mut conn, scope, path_params.webhook_id.as_uuid()).await?;
 
     let update_data = request.into_model(existing.status);
     conn.update_workspace_webhook(existing.id, update_data)
         .await?;
 
     let (webhook, creator_username) =
-        find_webhook(&mut conn, workspace.id, path_params.webhook_id.as_uuid()).await?;
+        find_webhook(&mut conn, scope, path_params.webhook_id.as_uuid()).await?;
 
     tracing::info!(target: TRACING_TARGET, "Webhook updated");
 
@@ -281,12 +282,11 @@ async fn delete_webhook(
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::DeleteWebhooks)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::DeleteWebhooks)
         .await?;
 
-    let (existing, _) =
-        find_webhook(&mut conn, workspace.id, path_params.webhook_id.as_uuid()).await?;
+    let (existing, _) = find_webhook(&mut conn, scope, path_params.webhook_id.as_uuid()).await?;
 
     conn.delete_workspace_webhook(existing.id).await?;
 
@@ -306,7 +306,8 @@ fn delete_webhook_docs(op: TransformOperation) -> TransformOperation {
 
 /// Tests a webhook by sending a test payload.
 ///
-/// Sends a test request to the webhook endpoint and returns the result.
+/// Sends a test request to the webhook endpoint and returns the result,
+/// with whether the webhook's payload filters match the test payload.
 /// Requires `TestWebhooks` permission.
 #[tracing::instrument(
     skip_all,
@@ -322,18 +323,17 @@ async fn test_webhook(
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     Path(path_params): Path<WebhookPathParams>,
-    ValidateJson(_request): ValidateJson<TestWebhook>,
+    ValidateJson(request): ValidateJson<TestWebhook>,
 ) -> Result<(StatusCode, Json<WebhookResult>)> {
     tracing::debug!(target: TRACING_TARGET, "Testing workspace webhook");
 
     let mut conn = pg_client.get_connection().await?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::TestWebhooks)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::TestWebhooks)
         .await?;
 
-    let (webhook, _) =
-        find_webhook(&mut conn, workspace.id, path_params.webhook_id.as_uuid()).await?;
+    let (webhook, _) = find_webhook(&mut conn, scope, path_params.webhook_id.as_uuid()).await?;
 
     // Parse the webhook URL
     let url: Url = webhook.url.parse().map_err(|_| {
@@ -342,8 +342,18 @@ async fn test_webhook(
             .with_resource("webhook")
     })?;
 
-    // Build the test webhook request
-    let webhook_request = WebhookRequest::test(url, webhook.id, webhook.workspace_id);
+    let filter = PayloadFilter::parse(&webhook.payload_filters()).map_err(|message| {
+        ErrorKind::BadRequest
+            .with_message(message)
+            .with_resource("webhook")
+    })?;
+
+    // Build the test webhook request, carrying the sample event metadata
+    let mut webhook_request = WebhookRequest::test(url, webhook.id, webhook.workspace_id);
+    if let Some(payload) = request.payload {
+        webhook_request.context.metadata = payload;
+    }
+    let filters_matched = filter.matches_request(&webhook_request);
     let response = webhook_service.deliver(&webhook_request).await?;
 
     // Update last_triggered_at timestamp
@@ -357,29 +367,91 @@ async fn test_webhook(
         target: TRACING_TARGET,
         success = response.is_success(),
         status_code = ?response.status_code,
+        filters_matched,
         "Webhook test completed"
     );
 
-    Ok((StatusCode::OK, Json(WebhookResult::from_response(response))))
+    Ok((
+        StatusCode::OK,
+        Json(WebhookResult::from_response(response, filters_matched)),
+    ))
 }
 
 fn test_webhook_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Test webhook")
-        .description("Sends a test payload to the webhook endpoint and returns the result.")
+        .description(
+            "Sends a test payload to the webhook endpoint and returns the result. The optional \
+             `payload` is sent as the event metadata (`context.metadata`), and the result tells \
+             whether the webhook's payload filters match it. The test payload is sent even if \
+             they do not.",
+        )
         .response::<200, Json<WebhookResult>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
         .response::<404, Json<ErrorResponse>>()
 }
 
+/// Returns a webhook's delivery statistics.
+///
+/// Requires `ViewWebhooks` permission.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %auth_state.account_id,
+        workspace_id = %workspace.id,
+        webhook_id = %path_params.webhook_id,
+    )
+)]
+async fn read_webhook_stats(
+    State(pg_client): State<PgClient>,
+    AuthState(auth_state): AuthState,
+    WorkspaceContext(workspace): WorkspaceContext,
+    Path(path_params): Path<WebhookPathParams>,
+    Query(window): Query<WebhookStatsWindow>,
+) -> Result<(StatusCode, Json<WebhookDeliveryStats>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading webhook delivery statistics");
+
+    let mut conn = pg_client.get_connection().await?;
+
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::ViewWebhooks)
+        .await?;
+
+    let (webhook, _) = find_webhook(&mut conn, scope, path_params.webhook_id.as_uuid()).await?;
+
+    let since = window.since();
+    let stats = conn
+        .list_webhook_delivery_stats(scope, webhook.id, since)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(WebhookDeliveryStats::from_models(webhook.id, since, stats)),
+    ))
+}
+
+fn read_webhook_stats_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get webhook delivery statistics")
+        .description(
+            "Returns how many events were delivered to the webhook, how many delivery attempts \
+             failed and how many events its payload filters held back, per day over the \
+             requested period, with the average response time of the endpoint. Test deliveries \
+             are not counted.",
+        )
+        .response::<200, Json<WebhookDeliveryStats>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
 /// Finds a webhook within a workspace by id, with its creator's handle, or
 /// returns a NotFound error.
 async fn find_webhook(
     conn: &mut PgConn,
-    workspace_id: Uuid,
+    scope: TenantScope,
     webhook_id: Uuid,
 ) -> Result<(WorkspaceWebhook, Username)> {
-    conn.find_webhook_in_workspace_with_creator(workspace_id, webhook_id)
+    conn.find_webhook_in_workspace_with_creator(scope, webhook_id)
         .await?
         .ok_or_else(|| Error::not_found("webhook"))
 }
@@ -404,5 +476,9 @@ pub fn routes() -> ApiRouter<ServiceState> {
             "/workspaces/{workspaceSlug}/webhooks/{webhookId}/test/",
             post_with(test_webhook, test_webhook_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/webhooks/{webhookId}/stats/",
+            get_with(read_webhook_stats, read_webhook_stats_docs),
+        )
         .with_path_items(|item| item.tag("Webhooks"))
 }
@@ -10,22 +10,29 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use nvisy_postgres::model::{NewWorkspaceMember, Workspace as WorkspaceModel, WorkspaceMember};
 use nvisy_postgres::query::{
-    WorkspaceActivityRepository, WorkspaceMemberRepository, WorkspaceRepository,
+    WorkspaceActivityRepository, WorkspaceCustomRoleRepository, WorkspaceMemberRepository,
+    WorkspaceRepository,
 };
 use nvisy_postgres::types::Username;
-use nvisy_postgres::{AsyncConnection, PgClient, PgConn};
+use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgError};
 
 use crate::extract::{
-    AuthProvider, AuthState, Json, Permission, Query, ValidateJson, WorkspaceContext,
+    AuthProvider, AuthState, Json, Permission, Query, ValidateJson, WorkspaceAccess,
+    WorkspaceContext,
 };
 use crate::handler::request::{
-    CreateWorkspace, CursorPagination, UpdateNotificationSettings, UpdateWorkspace,
+    ActivityBreakdownQuery, CreateWorkspace, CursorPagination, FieldSelection, ListActivities,
+    UpdateNotificationSettings, UpdateWorkspace,
 };
 use crate::handler::response::{
-    ActivitiesPage, Activity, ErrorResponse, NotificationSettings, Page, Workspace, WorkspacesPage,
+    ActivitiesPage, Activity, ActivityBreakdown, ActivityChainVerification, ErrorResponse,
+    NotificationSettings, Page, Sparse, Workspace, WorkspacesPage,
 };
 use crate::handler::{Error, ErrorKind, Result};
-use crate::service::ServiceState;
+use crate::service::{
+    AuditLog, PrivacyService, ResidencyService, ServiceState, WebhookEmitter,
+    WorkspaceSettingsChanged,
+};
 
 /// Tracing target for workspace operations.
 const TRACING_TARGET: &str = "nvisy_server::handler::workspaces";
@@ -37,11 +44,14 @@ const TRACING_TARGET: &str = "nvisy_server::handler::workspaces";
 #[tracing::instrument(skip_all, fields(account_id = %auth_state.account_id))]
 async fn create_workspace(
     State(pg_client): State<PgClient>,
+    State(residency): State<ResidencyService>,
     AuthState(auth_state): AuthState,
     ValidateJson(request): ValidateJson<CreateWorkspace>,
 ) -> Result<(StatusCode, Json<Workspace>)> {
     tracing::debug!(target: TRACING_TARGET, "Creating workspace");
 
+    residency.ensure_supported(request.data_region.unwrap_or_default())?;
+
     let new_workspace = request.into_model(auth_state.account_id)?;
     let mut conn = pg_client.get_connection().await?;
     let creator_id = auth_state.account_id;
@@ -164,6 +174,7 @@ fn read_workspace_docs(op: TransformOperation) -> TransformOperation {
 )]
 async fn update_workspace(
     State(pg_client): State<PgClient>,
+    State(webhook_emitter): State<WebhookEmitter>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
     ValidateJson(request): ValidateJson<UpdateWorkspace>,
@@ -171,14 +182,42 @@ async fn update_workspace(
     tracing::debug!(target: TRACING_TARGET, "Updating workspace");
 
     let mut conn = pg_client.get_connection().await?;
-    let member = auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::UpdateWorkspace)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::UpdateWorkspace)
         .await?;
 
     let update_data = request.into_model();
-    let updated = conn.update_workspace(workspace.id, update_data).await?;
+    let actor_id = auth_state.account_id;
+    let updated = conn
+        .transaction(async |conn| {
+            let updated = conn.update_workspace(scope, update_data).await?;
+
+            // The description is free text, so only the fact that it changed
+            // is reported.
+            let mut changed = WorkspaceSettingsChanged::default();
+            changed.compare(
+                "displayName",
+                &workspace.display_name,
+                &updated.display_name,
+            );
+            changed.compare_redacted("description", &workspace.description, &updated.description);
+            changed.compare(
+                "requireApproval",
+                &workspace.require_approval,
+                &updated.require_approval,
+            );
+            if !changed.is_empty() {
+                webhook_emitter
+                    .emit_workspace_updated(conn, workspace.id, Some(actor_id), &changed)
+                    .await?;
+            }
+
+            Ok::<_, PgError>(updated)
+        })
+        .await?;
 
     let creator_username = find_workspace_creator(&mut conn, updated.slug.as_str()).await?;
+    let member = conn.find_workspace_member(scope, actor_id).await?;
 
     tracing::info!(target: TRACING_TARGET, "Workspace updated");
 
@@ -203,7 +242,8 @@ fn update_workspace_docs(op: TransformOperation) -> TransformOperation {
 /// Soft-deletes a workspace.
 ///
 /// Requires `DeleteWorkspace` permission. The workspace is marked as deleted
-/// but data is retained for potential recovery.
+/// but data is retained for potential recovery. A workspace with an active
+/// legal hold cannot be deleted.
 #[tracing::instrument(
     skip_all,
     fields(
@@ -219,11 +259,11 @@ async fn delete_workspace(
     tracing::debug!(target: TRACING_TARGET, "Deleting workspace");
 
     let mut conn = pg_client.get_connection().await?;
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::DeleteWorkspace)
+    let scope = auth_state
+        .authorize_tenant(&mut conn, workspace.id, Permission::DeleteWorkspace)
         .await?;
 
-    conn.delete_workspace(workspace.id).await?;
+    conn.delete_workspace(scope).await?;
 
     tracing::info!(target: TRACING_TARGET, "Workspace deleted");
 
@@ -232,11 +272,15 @@ async fn delete_workspace(
 
 fn delete_workspace_docs(op: TransformOperation) -> TransformOperation {
     op.summary("Delete workspace")
-        .description("Soft-deletes a workspace. Data is retained for potential recovery.")
+        .description(
+            "Soft-deletes a workspace. Data is retained for potential recovery. A workspace with \
+             an active legal hold cannot be deleted.",
+        )
         .response::<200, ()>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
         .response::<404, Json<ErrorResponse>>()
+        .response::<409, Json<ErrorResponse>>()
 }
 
 /// Retrieves the notification settings for the authenticated user in a workspace.
@@ -253,8 +297,8 @@ async fn get_notification_settings(
     WorkspaceContext(workspace): WorkspaceContext,
 ) -> Result<(StatusCode, Json<NotificationSettings>)> {
     let mut conn = pg_client.get_connection().await?;
-    let Some(member) = conn
-        .find_workspace_member(workspace.id, auth_state.account_id)
+    let Some((member, ..)) = conn
+        .find_workspace_member_with_custom_role(workspace.id, auth_state.account_id)
         .await?
     else {
         return Err(ErrorKind::NotFound
@@ -295,19 +339,18 @@ async fn update_notification_settings(
     let mut conn = pg_client.get_connection().await?;
 
     // Verify membership exists
-    if conn
-        .find_workspace_member(workspace.id, auth_state.account_id)
+    let Some((.., scope)) = conn
+        .find_workspace_member_with_custom_role(workspace.id, auth_state.account_id)
         .await?
-        .is_none()
-    {
+    else {
         return Err(ErrorKind::NotFound
             .with_message("Workspace membership not found")
             .with_resource("workspace_member"));
-    }
+    };
 
     let update_data = request.into_model();
     let member = conn
-        .update_workspace_member(workspace.id, auth_state.account_id, update_data)
+        .update_workspace_member(scope, auth_state.account_id, update_data)
         .await?;
 
     tracing::info!(target: TRACING_TARGET, "Notification settings updated");
@@ -339,18 +382,30 @@ async fn list_activities(
     State(pg_client): State<PgClient>,
     AuthState(auth_state): AuthState,
     WorkspaceContext(workspace): WorkspaceContext,
+    Query(query): Query<ListActivities>,
     Query(pagination): Query<CursorPagination>,
-) -> Result<(StatusCode, Json<ActivitiesPage>)> {
+    Query(selection): Query<FieldSelection>,
+) -> Result<(StatusCode, Json<Page<Sparse<Activity>>>)> {
     tracing::debug!(target: TRACING_TARGET, "Listing workspace activities");
 
-    let mut conn = pg_client.get_connection().await?;
+    let filter = query.to_filter()?;
+    let fields = selection.resolve::<Activity>()?;
 
-    auth_state
-        .authorize_workspace(&mut conn, workspace.id, Permission::ViewWorkspace)
+    // Authorized on the primary: an administrator's scope is recorded with
+    // a write.
+    let scope = auth_state
+        .authorize_tenant(
+            &mut pg_client.get_connection().await?,
+            workspace.id,
+            Permission::ViewWorkspace,
+        )
         .await?;
 
+    // The activity feed tolerates replica lag, so it reads from a replica.
+    let mut conn = pg_client.read().await?;
+
     let page = conn
-        .cursor_list_workspace_activity(workspace.id, pagination.into())
+        .cursor_list_workspace_activity(scope, pagination.into(), filter)
         .await?;
 
     let response = ActivitiesPage::from_cursor_page(page, |(activity, actor_username)| {
@@ -363,17 +418,130 @@ async fn list_activities(
         "Workspace activities listed"
     );
 
-    Ok((StatusCode::OK, Json(response)))
+    Ok((StatusCode::OK, Json(response.select(fields))))
 }
 
 fn list_activities_docs(op: TransformOperation) -> TransformOperation {
     op.summary("List workspace activities")
-        .description("Returns all activity log entries for a workspace.")
+        .description(
+            "Returns the workspace's audit log, newest first. Entries can be filtered by \
+             actor, activity type, resource and time range, and `fields` returns only some \
+             fields of each entry.",
+        )
         .response::<200, Json<ActivitiesPage>>()
+        .response::<400, Json<ErrorResponse>>()
         .response::<401, Json<ErrorResponse>>()
         .response::<403, Json<ErrorResponse>>()
 }
 
+/// Verifies the integrity of a workspace's audit log.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+    )
+)]
+async fn verify_activities(
+    State(pg_client): State<PgClient>,
+    State(audit): State<AuditLog>,
+    access: WorkspaceAccess,
+) -> Result<(StatusCode, Json<ActivityChainVerification>)> {
+    tracing::debug!(target: TRACING_TARGET, "Verifying workspace audit log");
+
+    access.require(Permission::VerifyActivities)?;
+
+    let mut conn = pg_client.get_connection().await?;
+    let scope = access.scope(&mut conn).await?;
+    let verification = audit.verify(scope).await?;
+
+    Ok((StatusCode::OK, Json(verification.into())))
+}
+
+fn verify_activities_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Verify workspace audit log")
+        .description(
+            "Recomputes the audit log's hash chain from the oldest retained entry and \
+             reports the first entry that was modified, removed or relinked. A log \
+             trimmed by retention is reported as truncated, not broken.",
+        )
+        .response::<200, Json<ActivityChainVerification>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<404, Json<ErrorResponse>>()
+}
+
+/// Returns activity counts for a workspace, grouped by type.
+///
+/// Callers holding `ViewActivities` get exact counts. Everyone else with
+/// access to the workspace gets counts with differential privacy noise, and
+/// the query spends from the workspace's privacy budget.
+#[tracing::instrument(
+    skip_all,
+    fields(
+        account_id = %access.actor().account_id,
+        workspace_id = %access.workspace().id,
+    )
+)]
+async fn get_activity_breakdown(
+    State(pg_client): State<PgClient>,
+    State(privacy): State<PrivacyService>,
+    access: WorkspaceAccess,
+    Query(query): Query<ActivityBreakdownQuery>,
+) -> Result<(StatusCode, Json<ActivityBreakdown>)> {
+    tracing::debug!(target: TRACING_TARGET, "Reading workspace activity breakdown");
+
+    access.require(Permission::ViewWorkspace)?;
+    let workspace_id = access.workspace().id;
+    let hours = query.hours.map(i64::from);
+
+    // Scoped on the primary: an administrator's scope is recorded with a
+    // write.
+    let scope = access.scope(&mut pg_client.get_connection().await?).await?;
+
+    let mut conn = pg_client.read().await?;
+
+    let response = if access.check(Permission::ViewActivities).is_allowed() {
+        let counts = conn.get_activity_type_breakdown(scope, hours).await?;
+        ActivityBreakdown::from_counts(counts)
+    } else {
+        let counts = conn
+            .get_activity_type_breakdown_by_account(scope, hours)
+            .await?;
+
+        let Some(charge) = privacy.charge(workspace_id).await? else {
+            return Err(ErrorKind::TooManyRequests
+                .with_message("The workspace's privacy budget is exhausted")
+                .with_resource("privacy_budget")
+                .with_suggestion("Retry once the budget window resets"));
+        };
+
+        ActivityBreakdown::from_noisy_counts(counts, &privacy, charge)
+    };
+
+    tracing::debug!(
+        target: TRACING_TARGET,
+        type_count = response.items.len(),
+        noised = response.privacy.is_some(),
+        "Workspace activity breakdown read"
+    );
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+fn get_activity_breakdown_docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get workspace activity breakdown")
+        .description(
+            "Returns activity counts grouped by type. Counts are exact for callers with \
+             the `activities:view` permission and carry differential privacy noise for \
+             everyone else.",
+        )
+        .response::<200, Json<ActivityBreakdown>>()
+        .response::<401, Json<ErrorResponse>>()
+        .response::<403, Json<ErrorResponse>>()
+        .response::<429, Json<ErrorResponse>>()
+}
+
 /// Returns the handle of the account that created the workspace addressed by
 /// `slug`, or a NotFound error if no such workspace exists.
 async fn find_workspace_creator(conn: &mut PgConn, slug: &str) -> Result<Username> {
@@ -412,5 +580,13 @@ pub fn routes() -> ApiRouter<ServiceState> {
             "/workspaces/{workspaceSlug}/activities/",
             get_with(list_activities, list_activities_docs),
         )
+        .api_route(
+            "/workspaces/{workspaceSlug}/activities/breakdown/",
+            get_with(get_activity_breakdown, get_activity_breakdown_docs),
+        )
+        .api_route(
+            "/workspaces/{workspaceSlug}/activities/verify/",
+            get_with(verify_activities, verify_activities_docs),
+        )
         .with_path_items(|item| item.tag("Workspaces"))
 }
@@ -6,6 +6,9 @@
 mod error;
 
 pub mod extract;
+#[cfg(feature = "grpc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "grpc")))]
+pub mod grpc;
 pub mod handler;
 pub mod middleware;
 pub mod service;
@@ -10,7 +10,7 @@ use axum::response::Response;
 use nvisy_postgres::PgClient;
 use nvisy_postgres::query::AccountApiTokenRepository;
 
-use crate::extract::AuthState;
+use crate::extract::{AuthClaims, AuthState};
 use crate::handler::{ErrorKind, Result};
 use crate::service::ServiceState;
 
@@ -72,6 +72,13 @@ pub async fn validate_token_middleware(
     request: Request,
     next: Next,
 ) -> Result<Response> {
+    validate_token(&auth_claims, &pg_database).await?;
+    Ok(next.run(request).await)
+}
+
+/// Checks that verified claims are not expired and, for session tokens, that
+/// the token still exists, recording its use.
+pub(crate) async fn validate_token(auth_claims: &AuthClaims, pg_database: &PgClient) -> Result<()> {
     if auth_claims.is_expired() {
         tracing::warn!(
             target: TRACING_TARGET,
@@ -84,6 +91,12 @@ pub async fn validate_token_middleware(
             .with_resource("authorization"));
     }
 
+    // API keys were already resolved against `account_api_keys`, which also
+    // records their use; only session tokens have a row to touch here
+    if auth_claims.scopes.is_some() {
+        return Ok(());
+    }
+
     // Verify token exists in database and update last_used_at
     let mut conn = pg_database.get_connection().await?;
     let token = conn.touch_account_api_token(auth_claims.token_id).await;
@@ -100,5 +113,5 @@ pub async fn validate_token_middleware(
             .with_resource("authorization"));
     }
 
-    Ok(next.run(request).await)
+    Ok(())
 }
@@ -0,0 +1,247 @@
+//! Request body size limits by route class.
+//!
+//! Uploads and regular API requests get separate limits. Bodies are counted
+//! as they stream through, so multipart uploads flow into object storage
+//! without being buffered, and a declared `Content-Length` over the limit is
+//! rejected before the handler runs. A request cut off at the limit always
+//! gets a `413 Payload Too Large` naming the limit and the size received,
+//! whichever extractor or handler was reading the body.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use tower::BoxError;
+
+use super::constants::{DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_FILE_BODY_SIZE};
+use crate::handler::ErrorKind;
+
+/// Tracing target for body limit rejections.
+const TRACING_TARGET: &str = "nvisy_server::middleware::body_limit";
+
+/// Route classes with their own body size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BodyLimitClass {
+    /// File uploads: multipart forms, raw binary bodies and gRPC streams.
+    Upload,
+    /// Every other request.
+    Api,
+}
+
+impl BodyLimitClass {
+    /// Classifies a request by its `Content-Type`.
+    pub fn from_request(request: &Request) -> Self {
+        let content_type = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let is_upload = [
+            "multipart/form-data",
+            "application/octet-stream",
+            "application/grpc",
+        ]
+        .iter()
+        .any(|prefix| content_type.starts_with(prefix));
+        if is_upload { Self::Upload } else { Self::Api }
+    }
+
+    /// Returns the string representation for logging and error messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Upload => "upload",
+            Self::Api => "api",
+        }
+    }
+}
+
+/// Maximum request body sizes per [`BodyLimitClass`], in bytes.
+#[derive(Debug, Clone, Copy)]
+#[must_use = "config does nothing unless you use it"]
+pub struct BodyLimitConfig {
+    /// Limit for regular API requests.
+    pub max_body_size: usize,
+    /// Limit for uploads, across all files of a multipart request.
+    pub max_upload_size: usize,
+}
+
+impl Default for BodyLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_upload_size: DEFAULT_MAX_FILE_BODY_SIZE,
+        }
+    }
+}
+
+impl BodyLimitConfig {
+    /// Returns the limit for the given route class.
+    pub fn limit(&self, class: BodyLimitClass) -> u64 {
+        match class {
+            BodyLimitClass::Upload => self.max_upload_size as u64,
+            BodyLimitClass::Api => self.max_body_size as u64,
+        }
+    }
+}
+
+/// Axum middleware function enforcing the body limit of the request's class.
+///
+/// Meant for [`from_fn_with_state`](axum::middleware::from_fn_with_state).
+pub async fn limit_body_size(
+    State(config): State<BodyLimitConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let class = BodyLimitClass::from_request(&request);
+    let limit = config.limit(class);
+
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(declared) = declared.filter(|&declared| declared > limit) {
+        return payload_too_large(class, limit, declared, false);
+    }
+
+    // Count the body as it streams and cut it off past the limit. Whatever
+    // error the reader makes of the cut, the response is replaced below.
+    let received = Arc::new(AtomicU64::new(0));
+    let counter = received.clone();
+    let request = request.map(|body| {
+        let counted = body.into_data_stream().map(move |chunk| {
+            let chunk = chunk?;
+            let total =
+                counter.fetch_add(chunk.len() as u64, Ordering::Relaxed) + chunk.len() as u64;
+            if total > limit {
+                return Err(BoxError::from(format!(
+                    "request body exceeds the {limit}-byte limit"
+                )));
+            }
+            Ok(chunk)
+        });
+        Body::from_stream(counted)
+    });
+
+    let response = next.run(request).await;
+
+    let received = received.load(Ordering::Relaxed);
+    if received > limit {
+        return payload_too_large(class, limit, received, true);
+    }
+    response
+}
+
+/// Builds the `413` response for a body of `size` bytes over `limit`.
+///
+/// A `truncated` body was cut off while streaming, so `size` is only the
+/// part received before the cut.
+fn payload_too_large(class: BodyLimitClass, limit: u64, size: u64, truncated: bool) -> Response {
+    tracing::warn!(
+        target: TRACING_TARGET,
+        class = class.as_str(),
+        limit,
+        size,
+        truncated,
+        "Request body too large"
+    );
+
+    let size = if truncated {
+        format!("at least {size} bytes")
+    } else {
+        format!("{size} bytes")
+    };
+    ErrorKind::PayloadTooLarge
+        .with_message(format!(
+            "Request body of {size} exceeds the {limit}-byte {} limit",
+            class.as_str()
+        ))
+        .with_suggestion("Split the content across several smaller requests")
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Bytes;
+    use axum::extract::DefaultBodyLimit;
+    use axum::http::{Request as HttpRequest, StatusCode};
+    use axum::middleware::from_fn_with_state;
+    use axum::routing::post;
+    use futures::stream;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn router() -> Router {
+        let config = BodyLimitConfig {
+            max_body_size: 8,
+            max_upload_size: 16,
+        };
+        Router::new()
+            .route(
+                "/",
+                post(|body: Bytes| async move { body.len().to_string() }),
+            )
+            .layer(DefaultBodyLimit::disable())
+            .layer(from_fn_with_state(config, limit_body_size))
+    }
+
+    fn request(content_type: &str, body: Body) -> Request {
+        HttpRequest::post("/")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body)
+            .unwrap()
+    }
+
+    #[test]
+    fn classifies_by_content_type() {
+        let upload = request("multipart/form-data; boundary=x", Body::empty());
+        let api = request("application/json", Body::empty());
+        assert_eq!(
+            BodyLimitClass::from_request(&upload),
+            BodyLimitClass::Upload
+        );
+        assert_eq!(BodyLimitClass::from_request(&api), BodyLimitClass::Api);
+    }
+
+    #[tokio::test]
+    async fn limits_apply_per_class() {
+        let body = || Body::from("0123456789");
+
+        let response = router()
+            .oneshot(request("application/json", body()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = router()
+            .oneshot(request("application/octet-stream", body()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn streamed_body_is_cut_off() {
+        let chunks = stream::iter(["0123", "4567", "89"].map(Ok::<_, BoxError>));
+        let response = router()
+            .oneshot(request("application/json", Body::from_stream(chunks)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("at least 10 bytes exceeds the 8-byte api limit"));
+    }
+}
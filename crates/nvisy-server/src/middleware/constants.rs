@@ -2,12 +2,12 @@
 
 /// Default maximum request body size: 4MB.
 ///
-/// Used for security middleware to limit incoming request body sizes
-/// and prevent denial-of-service attacks via large payloads.
+/// The default body limit for regular API requests, preventing
+/// denial-of-service attacks via large payloads.
 pub const DEFAULT_MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
 
-/// Maximum file size for uploads: 12MB.
+/// Default maximum upload body size: 12MB.
 ///
-/// Used in file upload handlers to enforce file size limits
-/// before accepting file data into memory.
+/// The default body limit for uploads, which stream into storage instead of
+/// being buffered in memory.
 pub const DEFAULT_MAX_FILE_BODY_SIZE: usize = 12 * 1024 * 1024;
@@ -16,8 +16,8 @@
 //! 2. **Observability** - Generates request IDs and adds tracing spans early,
 //!    so all subsequent middleware and handlers are properly instrumented.
 //!
-//! 3. **Security** - Applies CORS, security headers, and body limits before
-//!    any request processing occurs.
+//! 3. **Security** - Applies CORS, security headers, and per-route-class body
+//!    limits before any request processing occurs.
 //!
 //! 4. **Metrics** - Tracks request timing and categorization after security
 //!    checks but before authentication.
@@ -47,6 +47,7 @@
 
 mod authentication;
 mod authorization;
+mod body_limit;
 mod constants;
 mod observability;
 mod recovery;
@@ -55,8 +56,11 @@ mod security;
 mod specification;
 mod sunset;
 
+#[cfg(feature = "grpc")]
+pub(crate) use authentication::validate_token;
 pub use authentication::{RouterAuthExt, require_authentication, validate_token_middleware};
 pub use authorization::require_admin;
+pub use body_limit::{BodyLimitClass, BodyLimitConfig, limit_body_size};
 pub use constants::{DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_FILE_BODY_SIZE};
 pub use observability::RouterObservabilityExt;
 pub use recovery::{RecoveryConfig, RouterRecoveryExt};
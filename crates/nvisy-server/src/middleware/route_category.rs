@@ -24,7 +24,7 @@ pub enum RouteCategory {
     Files,
     /// Connection routes (`/connections/*`).
     Connections,
-    /// Webhook routes (`/webhooks/*`).
+    /// Webhook routes (`/webhooks/*`, and `/inbound/*` for inbound webhooks).
     Webhooks,
     /// Health and monitoring routes (`/monitors/*`).
     Monitors,
@@ -51,7 +51,7 @@ impl RouteCategory {
             Self::Files
         } else if path.starts_with("/connections/") {
             Self::Connections
-        } else if path.starts_with("/webhooks/") {
+        } else if path.starts_with("/webhooks/") || path.starts_with("/inbound/") {
             Self::Webhooks
         } else if path.starts_with("/monitors/") {
             Self::Monitors
@@ -1,9 +1,10 @@
 //! Security middleware for HTTP request protection.
 //!
 //! This module provides comprehensive security middleware including CORS
-//! configuration, security headers, request body size limiting, and response
-//! compression. The security stack protects against common web vulnerabilities
-//! such as XSS, clickjacking, protocol downgrade attacks, and request smuggling.
+//! configuration, security headers, request body size limiting per route
+//! class, and negotiated response compression. The security stack protects
+//! against common web vulnerabilities such as XSS, clickjacking, protocol
+//! downgrade attacks, and request smuggling.
 
 use std::time::Duration;
 
@@ -11,12 +12,16 @@ use axum::Router;
 use axum::extract::DefaultBodyLimit;
 use axum::http::Method;
 use axum::http::header::{self, HeaderValue};
+use axum::middleware::from_fn_with_state;
 use tower_http::compression::CompressionLayer;
+use tower_http::compression::predicate::{DefaultPredicate, Predicate, SizeAbove};
 use tower_http::cors::CorsLayer;
-use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::set_header::SetResponseHeaderLayer;
 
-use super::constants::{DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_FILE_BODY_SIZE};
+use super::body_limit::{BodyLimitConfig, limit_body_size};
+
+/// Smallest response body worth compressing, in bytes.
+const COMPRESSION_MIN_SIZE: u64 = 1024;
 
 /// Extension trait for `axum::`[`Router`] to apply security middleware.
 ///
@@ -27,7 +32,12 @@ pub trait RouterSecurityExt<S> {
     ///
     /// This middleware stack applies CORS rules, security headers including
     /// HSTS and CSP, response compression, and request body size limits.
-    fn with_security(self, cors: &CorsConfig, headers: &SecurityHeadersConfig) -> Self;
+    fn with_security(
+        self,
+        cors: &CorsConfig,
+        headers: &SecurityHeadersConfig,
+        body_limits: &BodyLimitConfig,
+    ) -> Self;
 
     /// Layers security middlewares with default configurations.
     ///
@@ -41,7 +51,12 @@ impl<S> RouterSecurityExt<S> for Router<S>
 where
     S: Clone + Send + Sync + 'static,
 {
-    fn with_security(self, cors: &CorsConfig, headers: &SecurityHeadersConfig) -> Self {
+    fn with_security(
+        self,
+        cors: &CorsConfig,
+        headers: &SecurityHeadersConfig,
+        body_limits: &BodyLimitConfig,
+    ) -> Self {
         let cors_layer = CorsLayer::new()
             .allow_origin(cors.to_header_values())
             .allow_methods([
@@ -56,10 +71,12 @@ where
             .allow_credentials(cors.allow_credentials)
             .max_age(cors.max_age);
 
+        // The extractors' own limit is lifted; the middleware enforces the
+        // limit of each route class on the streaming body instead.
         let mut router = self
-            .layer(DefaultBodyLimit::max(DEFAULT_MAX_BODY_SIZE))
-            .layer(RequestBodyLimitLayer::new(DEFAULT_MAX_FILE_BODY_SIZE))
-            .layer(CompressionLayer::new())
+            .layer(DefaultBodyLimit::disable())
+            .layer(from_fn_with_state(*body_limits, limit_body_size))
+            .layer(compression_layer())
             .layer(cors_layer)
             .layer(SetResponseHeaderLayer::overriding(
                 header::STRICT_TRANSPORT_SECURITY,
@@ -89,10 +106,28 @@ where
     }
 
     fn with_default_security(self) -> Self {
-        self.with_security(&CorsConfig::default(), &SecurityHeadersConfig::default())
+        self.with_security(
+            &CorsConfig::default(),
+            &SecurityHeadersConfig::default(),
+            &BodyLimitConfig::default(),
+        )
     }
 }
 
+/// Negotiates zstd, brotli or gzip response compression from
+/// `Accept-Encoding`, compressing bodies as they stream.
+///
+/// Small bodies are sent as is, as are gRPC, image and event-stream
+/// responses, which the default predicate excludes.
+fn compression_layer() -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new()
+        .zstd(true)
+        .br(true)
+        .gzip(true)
+        .deflate(false)
+        .compress_when(DefaultPredicate::new().and(SizeAbove::new(COMPRESSION_MIN_SIZE)))
+}
+
 /// CORS (Cross-Origin Resource Sharing) configuration.
 ///
 /// Controls which origins can access your API and what HTTP methods
@@ -0,0 +1,185 @@
+//! Tamper-evident workspace audit log.
+//!
+//! Workspace activities are appended to a hash chain per workspace: every
+//! record is numbered and hashed together with the previous record's hash
+//! through the configured crypto provider. Postgres rejects updates and
+//! deletes outside of retention, and [`ChainVerifier`] recomputes the chain
+//! to detect records changed, removed or reordered behind the database's
+//! back.
+//!
+//! Records older than the retention period are removed by [`AuditRetention`].
+//! The newest record of each workspace is always kept, so the chain stays
+//! anchored; a verification over a trimmed log reports it as truncated
+//! rather than broken.
+
+mod retention;
+mod verifier;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use jiff::Timestamp;
+use nvisy_core::clock::Clock;
+use nvisy_core::id::IdGenerator;
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::{NewWorkspaceActivity, WorkspaceActivity};
+use nvisy_postgres::query::{
+    AdminScope, AdminScopeEventRepository, TenantScope, WorkspaceActivityRepository,
+};
+use uuid::Uuid;
+
+pub use self::retention::AuditRetention;
+pub use self::verifier::{ChainBreak, ChainBreakReason, ChainVerification, ChainVerifier};
+use crate::handler::Result;
+use crate::service::CryptoService;
+
+/// Tracing target for the audit log.
+const TRACING_TARGET: &str = "nvisy_server::service::audit";
+
+/// Number of records read per batch while verifying a chain.
+const VERIFY_BATCH_SIZE: i64 = 1_000;
+
+/// Default retention of audit records (one year).
+pub const DEFAULT_AUDIT_RETENTION: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Default interval between retention passes.
+pub const DEFAULT_AUDIT_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Audit log configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct AuditConfig {
+    /// How long audit records are kept.
+    pub retention: Duration,
+    /// How often expired records are removed.
+    pub cleanup_interval: Duration,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            retention: DEFAULT_AUDIT_RETENTION,
+            cleanup_interval: DEFAULT_AUDIT_CLEANUP_INTERVAL,
+        }
+    }
+}
+
+/// Writes, verifies and trims the workspace audit log.
+#[derive(Clone)]
+pub struct AuditLog {
+    config: AuditConfig,
+    pg_client: PgClient,
+    crypto: CryptoService,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl AuditLog {
+    /// Creates a new audit log stamping records with `clock` and `ids`.
+    pub fn new(
+        config: AuditConfig,
+        pg_client: PgClient,
+        crypto: CryptoService,
+        clock: Arc<dyn Clock>,
+        ids: Arc<dyn IdGenerator>,
+    ) -> Self {
+        Self {
+            config,
+            pg_client,
+            crypto,
+            clock,
+            ids,
+        }
+    }
+
+    /// Returns the audit log configuration.
+    pub fn config(&self) -> &AuditConfig {
+        &self.config
+    }
+
+    /// Appends an activity to its workspace's chain.
+    pub async fn record(&self, activity: NewWorkspaceActivity) -> Result<WorkspaceActivity> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let activity = conn
+            .log_activity(
+                activity,
+                self.crypto.provider().as_ref(),
+                self.clock.as_ref(),
+                self.ids.as_ref(),
+            )
+            .await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            workspace_id = %activity.workspace_id,
+            sequence_number = activity.sequence_number,
+            "Audit record appended"
+        );
+
+        Ok(activity)
+    }
+
+    /// Verifies the scoped workspace's chain from its oldest retained record.
+    ///
+    /// Reads from the primary, so the result reflects every committed record.
+    /// Stops at the first break.
+    pub async fn verify(&self, scope: TenantScope) -> Result<ChainVerification> {
+        let workspace_id = scope.workspace_id();
+        let mut conn = self.pg_client.get_connection().await?;
+        let mut verifier = ChainVerifier::new(self.crypto.provider().as_ref());
+        let mut after_sequence_number = 0;
+
+        loop {
+            let batch = conn
+                .list_workspace_activity_chain(scope, after_sequence_number, VERIFY_BATCH_SIZE)
+                .await?;
+
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after_sequence_number = last.sequence_number;
+
+            let intact = batch.iter().all(|activity| verifier.push(activity));
+            if !intact || (batch.len() as i64) < VERIFY_BATCH_SIZE {
+                break;
+            }
+        }
+
+        let verification = verifier.finish();
+        match &verification.broken_at {
+            Some(chain_break) => tracing::warn!(
+                target: TRACING_TARGET,
+                workspace_id = %workspace_id,
+                sequence_number = chain_break.sequence_number,
+                reason = ?chain_break.reason,
+                "Audit chain broken"
+            ),
+            None => tracing::debug!(
+                target: TRACING_TARGET,
+                workspace_id = %workspace_id,
+                records = verification.records,
+                "Audit chain verified"
+            ),
+        }
+
+        Ok(verification)
+    }
+
+    /// Removes up to `limit` records older than the retention period, and as
+    /// many recorded admin scope openings.
+    pub async fn purge_expired(&self, limit: i64) -> Result<usize> {
+        let cutoff = self
+            .clock
+            .now()
+            .checked_sub(self.config.retention)
+            .unwrap_or(Timestamp::MIN);
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "remove expired audit records").await?;
+        let activities = conn.cleanup_old_activities(&admin, cutoff, limit).await?;
+        let admin_events = conn
+            .cleanup_old_admin_scope_events(&admin, cutoff, limit)
+            .await?;
+        Ok(activities + admin_events)
+    }
+}
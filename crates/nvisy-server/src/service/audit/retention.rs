@@ -0,0 +1,95 @@
+//! Removal of audit records past their retention period.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::AuditLog;
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for audit retention.
+const TRACING_TARGET: &str = "nvisy_server::worker::audit_retention";
+
+/// Maximum number of records removed per batch.
+const BATCH_SIZE: i64 = 1_000;
+
+/// Periodically removes audit records older than the retention period.
+pub struct AuditRetention {
+    audit: AuditLog,
+    interval: Duration,
+}
+
+impl AuditRetention {
+    /// Create a new retention worker using the audit log's configured interval.
+    pub fn new(audit: AuditLog) -> Self {
+        let interval = audit.config().cleanup_interval;
+        Self { audit, interval }
+    }
+
+    /// Run retention passes until cancelled.
+    ///
+    /// Every server instance may run the worker: each batch is deleted in
+    /// its own transaction, and records already removed by another instance
+    /// are simply not matched. `heartbeat` is beaten after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            retention_secs = self.audit.config().retention.as_secs(),
+            "Starting audit retention"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Audit retention shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.purge().await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Audit retention stopped");
+        Ok(())
+    }
+
+    /// Removes expired records in batches until none are left.
+    async fn purge(&self) {
+        let mut removed = 0;
+        loop {
+            match self.audit.purge_expired(BATCH_SIZE).await {
+                Ok(0) => break,
+                Ok(count) => {
+                    removed += count;
+                    if (count as i64) < BATCH_SIZE {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Failed to remove expired audit records"
+                    );
+                    break;
+                }
+            }
+        }
+
+        if removed > 0 {
+            tracing::info!(
+                target: TRACING_TARGET,
+                removed,
+                "Removed expired audit records"
+            );
+        }
+    }
+}
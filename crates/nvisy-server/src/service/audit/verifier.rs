@@ -0,0 +1,272 @@
+//! Incremental verification of a workspace's audit chain.
+
+use nvisy_core::crypto::CryptoProvider;
+use nvisy_postgres::model::WorkspaceActivity;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Why a chain failed verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainBreakReason {
+    /// A sequence number is missing: a record was removed.
+    MissingRecord,
+    /// The record's contents no longer match its hash.
+    RecordModified,
+    /// The record does not link to the hash of the record before it.
+    LinkMismatch,
+    /// An unchained record follows a chained one.
+    UnchainedRecord,
+}
+
+/// The first point at which a chain failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainBreak {
+    /// Sequence number of the offending record.
+    pub sequence_number: i64,
+    /// ID of the offending record.
+    pub activity_id: Uuid,
+    /// Why verification failed.
+    pub reason: ChainBreakReason,
+}
+
+/// Outcome of verifying a workspace's chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainVerification {
+    /// Number of records examined.
+    pub records: u64,
+    /// Number of records predating the chain, which carry no hash.
+    pub legacy_records: u64,
+    /// Sequence number of the oldest retained record.
+    pub first_sequence_number: Option<i64>,
+    /// Sequence number of the last record examined.
+    pub last_sequence_number: Option<i64>,
+    /// Whether older records were removed by retention.
+    pub truncated: bool,
+    /// Where verification failed, if it did.
+    pub broken_at: Option<ChainBreak>,
+}
+
+impl ChainVerification {
+    /// Returns whether every examined record is intact.
+    pub fn is_intact(&self) -> bool {
+        self.broken_at.is_none()
+    }
+}
+
+/// The last record accepted into the chain.
+struct ChainHead {
+    sequence_number: i64,
+    record_hash: Option<Vec<u8>>,
+}
+
+/// Verifies a chain record by record, in sequence order.
+///
+/// The oldest record fed in is taken as the anchor: its link to a record
+/// removed by retention cannot be checked, so a chain starting after
+/// sequence number 1 is reported as truncated.
+pub struct ChainVerifier<'a> {
+    provider: &'a dyn CryptoProvider,
+    head: Option<ChainHead>,
+    verification: ChainVerification,
+}
+
+impl<'a> ChainVerifier<'a> {
+    /// Creates a verifier hashing with `provider`.
+    pub fn new(provider: &'a dyn CryptoProvider) -> Self {
+        Self {
+            provider,
+            head: None,
+            verification: ChainVerification::default(),
+        }
+    }
+
+    /// Checks the next record.
+    ///
+    /// Returns `false` once the chain is broken; later records are ignored.
+    pub fn push(&mut self, activity: &WorkspaceActivity) -> bool {
+        if self.verification.broken_at.is_some() {
+            return false;
+        }
+
+        if let Some(reason) = self.check(activity) {
+            self.verification.broken_at = Some(ChainBreak {
+                sequence_number: activity.sequence_number,
+                activity_id: activity.id,
+                reason,
+            });
+            return false;
+        }
+
+        let verification = &mut self.verification;
+        verification.records += 1;
+        if !activity.is_chained() {
+            verification.legacy_records += 1;
+        }
+        if verification.first_sequence_number.is_none() {
+            verification.first_sequence_number = Some(activity.sequence_number);
+            verification.truncated = activity.sequence_number > 1;
+        }
+        verification.last_sequence_number = Some(activity.sequence_number);
+
+        self.head = Some(ChainHead {
+            sequence_number: activity.sequence_number,
+            record_hash: activity.record_hash.clone(),
+        });
+        true
+    }
+
+    /// Returns the verification outcome.
+    pub fn finish(self) -> ChainVerification {
+        self.verification
+    }
+
+    fn check(&self, activity: &WorkspaceActivity) -> Option<ChainBreakReason> {
+        if let Some(head) = &self.head
+            && activity.sequence_number != head.sequence_number + 1
+        {
+            return Some(ChainBreakReason::MissingRecord);
+        }
+
+        let Some(record_hash) = &activity.record_hash else {
+            let follows_chained = self
+                .head
+                .as_ref()
+                .is_some_and(|head| head.record_hash.is_some());
+            return follows_chained.then_some(ChainBreakReason::UnchainedRecord);
+        };
+
+        if activity.compute_hash(self.provider).as_slice() != record_hash.as_slice() {
+            return Some(ChainBreakReason::RecordModified);
+        }
+
+        // The anchor's predecessor may have been removed by retention.
+        if let Some(head) = &self.head
+            && activity.previous_hash != head.record_hash
+        {
+            return Some(ChainBreakReason::LinkMismatch);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_core::crypto::RustCryptoProvider;
+    use nvisy_postgres::types::ActivityType;
+
+    use super::*;
+
+    /// Builds a chain of `len` records the way the repository links them.
+    fn chain(len: i64) -> Vec<WorkspaceActivity> {
+        let workspace_id = Uuid::from_u128(7);
+        let mut records: Vec<WorkspaceActivity> = Vec::new();
+        for sequence_number in 1..=len {
+            let mut activity = WorkspaceActivity {
+                id: Uuid::from_u128(sequence_number as u128),
+                workspace_id,
+                account_id: Some(Uuid::from_u128(42)),
+                activity_type: ActivityType::FileCreated,
+                description: format!("Uploaded file {sequence_number}"),
+                metadata: serde_json::json!({ "size": 1024, "name": "report.pdf" }),
+                ip_address: None,
+                user_agent: None,
+                created_at: jiff::Timestamp::from_second(1_700_000_000 + sequence_number)
+                    .unwrap()
+                    .into(),
+                sequence_number,
+                resource_id: Some(Uuid::from_u128(99)),
+                previous_hash: records.last().and_then(|prev| prev.record_hash.clone()),
+                record_hash: None,
+            };
+            activity.record_hash = Some(activity.compute_hash(&RustCryptoProvider).to_vec());
+            records.push(activity);
+        }
+        records
+    }
+
+    fn verify(records: &[WorkspaceActivity]) -> ChainVerification {
+        let mut verifier = ChainVerifier::new(&RustCryptoProvider);
+        for record in records {
+            if !verifier.push(record) {
+                break;
+            }
+        }
+        verifier.finish()
+    }
+
+    #[test]
+    fn test_intact_chain() {
+        let verification = verify(&chain(5));
+        assert!(verification.is_intact());
+        assert_eq!(verification.records, 5);
+        assert!(!verification.truncated);
+        assert_eq!(verification.last_sequence_number, Some(5));
+    }
+
+    #[test]
+    fn test_modified_record() {
+        let mut records = chain(4);
+        records[2].description = "Nothing happened".to_owned();
+
+        let chain_break = verify(&records).broken_at.unwrap();
+        assert_eq!(chain_break.sequence_number, 3);
+        assert_eq!(chain_break.reason, ChainBreakReason::RecordModified);
+    }
+
+    #[test]
+    fn test_removed_record() {
+        let mut records = chain(4);
+        records.remove(1);
+
+        let chain_break = verify(&records).broken_at.unwrap();
+        assert_eq!(chain_break.sequence_number, 3);
+        assert_eq!(chain_break.reason, ChainBreakReason::MissingRecord);
+    }
+
+    #[test]
+    fn test_relinked_record() {
+        let mut records = chain(4);
+        // Rewrite a record consistently with itself, but not with its
+        // predecessor.
+        records[2].previous_hash = Some(vec![0; 32]);
+        records[2].record_hash = Some(records[2].compute_hash(&RustCryptoProvider).to_vec());
+
+        let chain_break = verify(&records).broken_at.unwrap();
+        assert_eq!(chain_break.sequence_number, 3);
+        assert_eq!(chain_break.reason, ChainBreakReason::LinkMismatch);
+    }
+
+    #[test]
+    fn test_truncated_chain() {
+        let records = chain(6);
+        let verification = verify(&records[3..]);
+        assert!(verification.is_intact());
+        assert!(verification.truncated);
+        assert_eq!(verification.first_sequence_number, Some(4));
+    }
+
+    #[test]
+    fn test_legacy_records_precede_chain() {
+        let mut records = chain(4);
+        for record in &mut records[..2] {
+            record.record_hash = None;
+            record.previous_hash = None;
+        }
+        records[2].previous_hash = None;
+        records[2].record_hash = Some(records[2].compute_hash(&RustCryptoProvider).to_vec());
+        records[3].previous_hash = records[2].record_hash.clone();
+        records[3].record_hash = Some(records[3].compute_hash(&RustCryptoProvider).to_vec());
+
+        let verification = verify(&records);
+        assert!(verification.is_intact());
+        assert_eq!(verification.legacy_records, 2);
+
+        records[3].record_hash = None;
+        let chain_break = verify(&records).broken_at.unwrap();
+        assert_eq!(chain_break.reason, ChainBreakReason::UnchainedRecord);
+    }
+}
@@ -0,0 +1,74 @@
+//! Typed caches behind the server's short-lived state.
+//!
+//! API key lookups, pending single sign-on logins and privacy budgets are
+//! kept in NATS KV by default, or in Redis with the `redis` feature. Services
+//! hold them as [`KeyValueStore`] trait objects and never name the backend.
+
+use std::sync::Arc;
+
+use nvisy_core::cache::{KeyValueStore, MemoryStore};
+use nvisy_nats::NatsClient;
+use nvisy_nats::kv::{
+    ApiKey, ApiKeysBucket, DigestKey, KvBucket, OidcLogin, OidcLoginsBucket, PrivacyBudget,
+    PrivacyBudgetsBucket, WorkspaceKey,
+};
+#[cfg(feature = "redis")]
+use nvisy_redis::RedisClient;
+
+/// A shared, backend-agnostic cache.
+pub type Cache<K, V> = Arc<dyn KeyValueStore<K, V>>;
+
+/// The caches the services read and write.
+#[derive(Clone)]
+pub struct Caches {
+    /// Account API keys by digest.
+    pub api_keys: Cache<DigestKey, ApiKey>,
+    /// Pending single sign-on logins by state digest.
+    pub oidc_logins: Cache<DigestKey, OidcLogin>,
+    /// Privacy budgets by workspace.
+    pub privacy_budgets: Cache<WorkspaceKey, PrivacyBudget>,
+}
+
+impl Caches {
+    /// Opens the caches in NATS KV buckets, creating missing buckets.
+    pub async fn nats(client: &NatsClient) -> nvisy_nats::Result<Self> {
+        Ok(Self {
+            api_keys: Arc::new(client.api_key_store().await?),
+            oidc_logins: Arc::new(client.oidc_login_store().await?),
+            privacy_budgets: Arc::new(client.privacy_budget_store().await?),
+        })
+    }
+
+    /// Opens the caches on a Redis server or cluster.
+    #[cfg(feature = "redis")]
+    pub async fn redis(client: &RedisClient) -> nvisy_redis::Result<Self> {
+        Ok(Self {
+            api_keys: Arc::new(client.api_key_store().await?),
+            oidc_logins: Arc::new(client.oidc_login_store().await?),
+            privacy_budgets: Arc::new(client.privacy_budget_store().await?),
+        })
+    }
+
+    /// Creates caches held in this process, expiring entries with the same
+    /// TTLs as the buckets and evicting the least recently used entries past
+    /// a fixed bound.
+    pub fn in_memory() -> Self {
+        Self {
+            api_keys: Arc::new(memory_store::<_, _, ApiKeysBucket>()),
+            oidc_logins: Arc::new(memory_store::<_, _, OidcLoginsBucket>()),
+            privacy_budgets: Arc::new(memory_store::<_, _, PrivacyBudgetsBucket>()),
+        }
+    }
+}
+
+/// Upper bound on the entries of each in-process cache.
+const MEMORY_MAX_ENTRIES: usize = 10_000;
+
+/// Creates a bounded in-process store with the TTL of bucket `B`.
+fn memory_store<K, V, B: KvBucket>() -> MemoryStore<K, V> {
+    let store = match B::TTL {
+        Some(ttl) => MemoryStore::with_ttl(ttl),
+        None => MemoryStore::new(),
+    };
+    store.with_max_entries(MEMORY_MAX_ENTRIES)
+}
@@ -0,0 +1,126 @@
+//! Resealing of connection credentials under the current vault key.
+//!
+//! Adding a key to the [`CredentialVault`](crate::service::CredentialVault)
+//! only changes what new credentials are sealed under. [`CredentialRotation`]
+//! catches the rest up in the background: every connection still sealed
+//! under an older version (including version 0, written before the vault)
+//! is opened and sealed again under the current one. Once nothing remains
+//! on a version, its key file can be retired.
+
+mod reseal;
+
+use std::time::Duration;
+
+use nvisy_postgres::PgClient;
+use nvisy_postgres::query::{AdminScope, WorkspaceConnectionRepository};
+use nvisy_postgres::types::CredentialCipher;
+
+pub use self::reseal::CredentialRotation;
+use crate::handler::Result;
+use crate::service::CryptoService;
+
+/// Tracing target for credential rotation.
+const TRACING_TARGET: &str = "nvisy_server::service::credential_rotation";
+
+/// Default interval between rotation passes.
+pub const DEFAULT_CREDENTIAL_ROTATION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Credential rotation configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct CredentialRotationConfig {
+    /// How often credentials sealed under older keys are resealed.
+    pub interval: Duration,
+}
+
+impl Default for CredentialRotationConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_CREDENTIAL_ROTATION_INTERVAL,
+        }
+    }
+}
+
+/// Outcome of a rotation batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CredentialRotationReport {
+    /// Connections resealed under the current key.
+    pub resealed: u64,
+    /// Connections left on their old key after a failed reseal.
+    pub failed: u64,
+}
+
+/// Reseals connection credentials under the vault's current key version.
+#[derive(Clone)]
+pub struct CredentialRotationService {
+    config: CredentialRotationConfig,
+    pg_client: PgClient,
+    crypto: CryptoService,
+}
+
+impl CredentialRotationService {
+    /// Creates a new credential rotation service.
+    pub fn new(
+        config: CredentialRotationConfig,
+        pg_client: PgClient,
+        crypto: CryptoService,
+    ) -> Self {
+        Self {
+            config,
+            pg_client,
+            crypto,
+        }
+    }
+
+    /// Returns the credential rotation configuration.
+    pub fn config(&self) -> &CredentialRotationConfig {
+        &self.config
+    }
+
+    /// Counts the connections still sealed under an older key version.
+    pub async fn remaining(&self) -> Result<u64> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "count connections to reseal").await?;
+        let key_version = self.crypto.credentials().key_version();
+        Ok(conn
+            .count_connections_to_reseal(&admin, key_version)
+            .await? as u64)
+    }
+
+    /// Reseals up to `limit` connections sealed under an older key version.
+    ///
+    /// A connection that fails to reseal, or that changed since it was read,
+    /// is logged and left for the next pass.
+    pub async fn reseal_batch(&self, limit: i64) -> Result<CredentialRotationReport> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "reseal connection credentials").await?;
+        let vault = self.crypto.credentials();
+        let connections = conn
+            .list_connections_to_reseal(&admin, vault.key_version(), limit)
+            .await?;
+
+        let mut report = CredentialRotationReport::default();
+        for connection in &connections {
+            match conn
+                .reseal_connection_credentials(&admin, connection, vault)
+                .await
+            {
+                Ok(true) => report.resealed += 1,
+                Ok(false) => report.failed += 1,
+                Err(err) => {
+                    report.failed += 1;
+                    tracing::warn!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        workspace_id = %connection.workspace_id,
+                        connection_id = %connection.id,
+                        key_version = connection.credential_key_version,
+                        "Failed to reseal connection credentials"
+                    );
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,104 @@
+//! Background resealing of connection credentials.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::{CredentialRotationReport, CredentialRotationService};
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for the credential rotation worker.
+const TRACING_TARGET: &str = "nvisy_server::worker::credential_rotation";
+
+/// Maximum number of connections resealed per batch.
+const BATCH_SIZE: i64 = 100;
+
+/// Periodically reseals connection credentials still sealed under an older
+/// vault key, reporting how many remain.
+pub struct CredentialRotation {
+    rotation: CredentialRotationService,
+    interval: Duration,
+}
+
+impl CredentialRotation {
+    /// Create a new rotation worker using the service's configured interval.
+    pub fn new(rotation: CredentialRotationService) -> Self {
+        let interval = rotation.config().interval;
+        Self { rotation, interval }
+    }
+
+    /// Run rotation passes until cancelled.
+    ///
+    /// Every server instance may run the worker: a reseal only applies to
+    /// the exact credentials it read, so instances racing on the same row
+    /// write it once. `heartbeat` is beaten after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            "Starting credential rotation"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Credential rotation shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.rotate(&cancel).await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Credential rotation stopped");
+        Ok(())
+    }
+
+    /// Reseals credentials in batches until a batch reseals nothing, then
+    /// reports what is left.
+    async fn rotate(&self, cancel: &CancellationToken) {
+        let mut total = CredentialRotationReport::default();
+        while !cancel.is_cancelled() {
+            match self.rotation.reseal_batch(BATCH_SIZE).await {
+                Ok(batch) => {
+                    total.resealed += batch.resealed;
+                    total.failed += batch.failed;
+                    if batch.resealed == 0 {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Failed to reseal connection credentials"
+                    );
+                    break;
+                }
+            }
+        }
+
+        match self.rotation.remaining().await {
+            Ok(remaining) if remaining > 0 || total.resealed > 0 => tracing::info!(
+                target: TRACING_TARGET,
+                resealed = total.resealed,
+                failed = total.failed,
+                remaining,
+                "Connection credentials resealed"
+            ),
+            Ok(_) => {}
+            Err(err) => tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Failed to count connections to reseal"
+            ),
+        }
+    }
+}
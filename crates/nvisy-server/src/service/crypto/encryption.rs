@@ -1,10 +1,11 @@
-//! XChaCha20-Poly1305 encryption over one authenticated wire format.
+//! Chunked AEAD encryption over one authenticated wire format.
 //!
 //! Data is encrypted in fixed-size authenticated chunks (the STREAM
 //! construction, `aead-stream` `LE31` variant): each chunk is its own AEAD
 //! frame and the final chunk is flagged, so a truncated stream fails to
 //! decrypt. Every entry point produces and consumes this same framing, so they
-//! interoperate freely.
+//! interoperate freely. The cipher itself comes from the [`CryptoProvider`]:
+//! XChaCha20-Poly1305 under the standard policy, AES-256-GCM under FIPS.
 //!
 //! Three shapes over that format: [`encrypt`] / [`decrypt`] for buffers already
 //! in memory, [`encrypt_json`] / [`decrypt_json`] for serializable values, and
@@ -14,23 +15,22 @@
 //! # Wire Format
 //!
 //! ```text
-//! nonce_prefix (20 bytes) || frame*
+//! nonce_prefix (nonce length - 4 bytes) || frame*
 //! frame       := ciphertext_chunk || tag (16 bytes)
 //! ```
 //!
 //! Every frame but the last carries exactly [`CHUNK_SIZE`] plaintext bytes; the
 //! last carries the remainder (possibly zero) and is authenticated as the final
-//! block. The 20-byte prefix is the XChaCha20-Poly1305 nonce (24 bytes) minus
-//! the 4 bytes `LE31` reserves for its per-chunk counter and last-block flag.
+//! block. The prefix is the provider's AEAD nonce minus the 4 bytes `LE31`
+//! reserves for its per-chunk counter and last-block flag: 20 bytes for
+//! XChaCha20-Poly1305, 8 bytes for AES-256-GCM.
 
 use std::io;
+use std::sync::Arc;
 
-use aead_stream::{DecryptorLE31, EncryptorLE31};
 use bytes::Bytes;
-use chacha20poly1305::XChaCha20Poly1305;
-use chacha20poly1305::aead::KeyInit;
 use futures::Stream;
-use rand::Rng;
+use nvisy_core::crypto::{AEAD_TAG_LEN, CryptoProvider};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tokio::io::{AsyncRead, AsyncReadExt};
@@ -42,37 +42,130 @@ use super::key::EncryptionKey;
 /// Plaintext bytes per authenticated chunk (64 KiB).
 const CHUNK_SIZE: usize = 64 * 1024;
 
-/// Size of the XChaCha20-Poly1305 nonce in bytes.
-const NONCE_SIZE: usize = 24;
-
 /// Bytes `LE31` reserves within the nonce for its per-chunk counter and
 /// last-block flag.
 const LE31_OVERHEAD: usize = 4;
 
-/// Size of the Poly1305 tag appended to each chunk.
-const TAG_SIZE: usize = 16;
+/// Highest chunk position `LE31` can encode; the top bit is the last-block flag.
+const LE31_MAX_POSITION: u32 = u32::MAX >> 1;
+
+/// Size of the authentication tag appended to each chunk.
+const TAG_SIZE: usize = AEAD_TAG_LEN;
 
 /// Ciphertext bytes per full (non-final) chunk.
 const ENCRYPTED_CHUNK_SIZE: usize = CHUNK_SIZE + TAG_SIZE;
 
-/// Length of the stream nonce prefix: the nonce less the `LE31` overhead.
-const NONCE_PREFIX_SIZE: usize = NONCE_SIZE - LE31_OVERHEAD;
+/// The `LE31` STREAM state for one message: the nonce prefix and the position
+/// of the next chunk.
+struct StreamCipher<'a> {
+    provider: &'a dyn CryptoProvider,
+    key: &'a EncryptionKey,
+    nonce: Vec<u8>,
+    position: u32,
+}
+
+impl<'a> StreamCipher<'a> {
+    /// Starts a stream with a fresh random nonce prefix.
+    fn generate(provider: &'a dyn CryptoProvider, key: &'a EncryptionKey) -> CryptoResult<Self> {
+        let mut prefix = vec![0u8; nonce_prefix_size(provider)];
+        provider
+            .fill_random(&mut prefix)
+            .map_err(|_| CryptoError::EncryptionFailed)?;
+        Ok(Self::new(provider, key, &prefix))
+    }
+
+    /// Resumes a stream from a nonce prefix read off the wire.
+    fn new(provider: &'a dyn CryptoProvider, key: &'a EncryptionKey, prefix: &[u8]) -> Self {
+        let mut nonce = Vec::with_capacity(provider.aead_nonce_len());
+        nonce.extend_from_slice(prefix);
+        nonce.extend_from_slice(&[0u8; LE31_OVERHEAD]);
+        Self {
+            provider,
+            key,
+            nonce,
+            position: 0,
+        }
+    }
+
+    /// The nonce prefix that leads the wire format.
+    fn prefix(&self) -> &[u8] {
+        &self.nonce[..self.nonce.len() - LE31_OVERHEAD]
+    }
+
+    /// Seals the next intermediate chunk.
+    fn encrypt_next(&mut self, chunk: &[u8]) -> CryptoResult<Vec<u8>> {
+        self.seal(chunk, false)
+    }
+
+    /// Seals the final chunk.
+    fn encrypt_last(&mut self, chunk: &[u8]) -> CryptoResult<Vec<u8>> {
+        self.seal(chunk, true)
+    }
+
+    /// Opens the next intermediate frame.
+    fn decrypt_next(&mut self, frame: &[u8]) -> CryptoResult<Vec<u8>> {
+        self.open(frame, false)
+    }
+
+    /// Opens the final frame.
+    fn decrypt_last(&mut self, frame: &[u8]) -> CryptoResult<Vec<u8>> {
+        self.open(frame, true)
+    }
+
+    fn seal(&mut self, chunk: &[u8], last: bool) -> CryptoResult<Vec<u8>> {
+        if !self.advance(last) {
+            return Err(CryptoError::EncryptionFailed);
+        }
+        self.provider
+            .aead_seal(self.key.as_bytes(), &self.nonce, chunk)
+            .map_err(|_| CryptoError::EncryptionFailed)
+    }
+
+    fn open(&mut self, frame: &[u8], last: bool) -> CryptoResult<Vec<u8>> {
+        if !self.advance(last) {
+            return Err(CryptoError::DecryptionFailed);
+        }
+        self.provider
+            .aead_open(self.key.as_bytes(), &self.nonce, frame)
+            .map_err(|_| CryptoError::DecryptionFailed)
+    }
+
+    /// Writes the current position and last-block flag into the nonce tail and
+    /// moves to the next chunk. Returns `false` once the counter is exhausted.
+    fn advance(&mut self, last: bool) -> bool {
+        if self.position > LE31_MAX_POSITION {
+            return false;
+        }
+
+        let tail = self.position | (u32::from(last) << 31);
+        let split = self.nonce.len() - LE31_OVERHEAD;
+        self.nonce[split..].copy_from_slice(&tail.to_le_bytes());
+        self.position += 1;
+        true
+    }
+}
+
+/// Length of the stream nonce prefix: the provider's nonce less the `LE31`
+/// overhead.
+fn nonce_prefix_size(provider: &dyn CryptoProvider) -> usize {
+    provider.aead_nonce_len() - LE31_OVERHEAD
+}
 
 /// Encrypts `plaintext` as a chunked STREAM, returning the framed ciphertext.
 ///
-/// The returned bytes begin with the [`NONCE_PREFIX_SIZE`]-byte nonce prefix,
-/// followed by one authenticated frame per [`CHUNK_SIZE`] chunk. This is the
-/// in-memory counterpart to the reader-based path; a true streaming pipe reuses
-/// the same framing chunk by chunk.
-pub fn encrypt(key: &EncryptionKey, plaintext: &[u8]) -> CryptoResult<Vec<u8>> {
-    let mut prefix = [0u8; NONCE_PREFIX_SIZE];
-    rand::rng().fill_bytes(&mut prefix);
-
-    let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
-    let mut encryptor = EncryptorLE31::from_aead(cipher, (&prefix).into());
+/// The returned bytes begin with the nonce prefix, followed by one
+/// authenticated frame per [`CHUNK_SIZE`] chunk. This is the in-memory
+/// counterpart to the reader-based path; a true streaming pipe reuses the same
+/// framing chunk by chunk.
+pub fn encrypt(
+    provider: &dyn CryptoProvider,
+    key: &EncryptionKey,
+    plaintext: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    let mut encryptor = StreamCipher::generate(provider, key)?;
 
-    let mut out = Vec::with_capacity(NONCE_PREFIX_SIZE + plaintext.len() + TAG_SIZE);
-    out.extend_from_slice(&prefix);
+    let mut out = Vec::with_capacity(encryptor.prefix().len() + plaintext.len() + TAG_SIZE);
+    out.extend_from_slice(encryptor.prefix());
 
     // All chunks but the last are sealed as intermediate blocks; the final
     // chunk (even if empty) is sealed as the last block so truncation fails.
@@ -80,16 +173,10 @@ pub fn encrypt(key: &EncryptionKey, plaintext: &[u8]) -> CryptoResult<Vec<u8>> {
     loop {
         let chunk = chunks.next().unwrap_or_default();
         if chunks.peek().is_none() {
-            let frame = encryptor
-                .encrypt_last(chunk)
-                .map_err(|_| CryptoError::EncryptionFailed)?;
-            out.extend_from_slice(&frame);
+            out.extend_from_slice(&encryptor.encrypt_last(chunk)?);
             break;
         }
-        let frame = encryptor
-            .encrypt_next(chunk)
-            .map_err(|_| CryptoError::EncryptionFailed)?;
-        out.extend_from_slice(&frame);
+        out.extend_from_slice(&encryptor.encrypt_next(chunk)?);
     }
 
     Ok(out)
@@ -99,48 +186,48 @@ pub fn encrypt(key: &EncryptionKey, plaintext: &[u8]) -> CryptoResult<Vec<u8>> {
 ///
 /// Fails if any frame's tag is invalid or the stream was truncated (the final
 /// frame authenticates as the last block, so a dropped tail is detected).
-pub fn decrypt(key: &EncryptionKey, ciphertext: &[u8]) -> CryptoResult<Vec<u8>> {
-    if ciphertext.len() < NONCE_PREFIX_SIZE + TAG_SIZE {
+pub fn decrypt(
+    provider: &dyn CryptoProvider,
+    key: &EncryptionKey,
+    ciphertext: &[u8],
+) -> CryptoResult<Vec<u8>> {
+    let prefix_size = nonce_prefix_size(provider);
+    if ciphertext.len() < prefix_size + TAG_SIZE {
         return Err(CryptoError::CiphertextTooShort);
     }
 
-    let (prefix, mut frames) = ciphertext.split_at(NONCE_PREFIX_SIZE);
-    let prefix: [u8; NONCE_PREFIX_SIZE] = prefix
-        .try_into()
-        .map_err(|_| CryptoError::CiphertextTooShort)?;
-    let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
-    let mut decryptor = DecryptorLE31::from_aead(cipher, (&prefix).into());
+    let (prefix, mut frames) = ciphertext.split_at(prefix_size);
+    let mut decryptor = StreamCipher::new(provider, key, prefix);
 
     let mut out = Vec::with_capacity(ciphertext.len());
     // Consume full frames as intermediate blocks; whatever remains is the last.
     while frames.len() > ENCRYPTED_CHUNK_SIZE {
         let (frame, rest) = frames.split_at(ENCRYPTED_CHUNK_SIZE);
-        let chunk = decryptor
-            .decrypt_next(frame)
-            .map_err(|_| CryptoError::DecryptionFailed)?;
-        out.extend_from_slice(&chunk);
+        out.extend_from_slice(&decryptor.decrypt_next(frame)?);
         frames = rest;
     }
-    let chunk = decryptor
-        .decrypt_last(frames)
-        .map_err(|_| CryptoError::DecryptionFailed)?;
-    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&decryptor.decrypt_last(frames)?);
 
     Ok(out)
 }
 
 /// Encrypts a serializable value as JSON under the same framing as [`encrypt`].
-pub fn encrypt_json<T: Serialize>(key: &EncryptionKey, value: &T) -> CryptoResult<Vec<u8>> {
+pub fn encrypt_json<T: Serialize>(
+    provider: &dyn CryptoProvider,
+    key: &EncryptionKey,
+    value: &T,
+) -> CryptoResult<Vec<u8>> {
     let json = serde_json::to_vec(value).map_err(|e| CryptoError::Json(e.to_string()))?;
-    encrypt(key, &json)
+    encrypt(provider, key, &json)
 }
 
 /// Decrypts and deserializes a JSON value produced by [`encrypt_json`].
 pub fn decrypt_json<T: DeserializeOwned>(
+    provider: &dyn CryptoProvider,
     key: &EncryptionKey,
     ciphertext: &[u8],
 ) -> CryptoResult<T> {
-    let plaintext = decrypt(key, ciphertext)?;
+    let plaintext = decrypt(provider, key, ciphertext)?;
     serde_json::from_slice(&plaintext).map_err(|e| CryptoError::Json(e.to_string()))
 }
 
@@ -149,11 +236,15 @@ pub fn decrypt_json<T: DeserializeOwned>(
 /// Reads one [`CHUNK_SIZE`] plaintext frame at a time and emits its sealed
 /// frame, so at most one chunk is held in memory regardless of file size. The
 /// nonce prefix leads the output; the final frame is sealed as the last block.
-pub fn encrypt_reader<R>(key: EncryptionKey, reader: R) -> impl AsyncRead
+pub fn encrypt_reader<R>(
+    provider: Arc<dyn CryptoProvider>,
+    key: EncryptionKey,
+    reader: R,
+) -> impl AsyncRead
 where
     R: AsyncRead + Unpin + Send,
 {
-    StreamReader::new(encrypt_frames(key, reader))
+    StreamReader::new(encrypt_frames(provider, key, reader))
 }
 
 /// Wraps a ciphertext reader as an [`AsyncRead`] yielding the decrypted stream.
@@ -161,11 +252,15 @@ where
 /// The inverse of [`encrypt_reader`]: reads the nonce prefix then one encrypted
 /// frame at a time, emitting verified plaintext. A truncated or tampered stream
 /// surfaces as an [`io::Error`] mid-read.
-pub fn decrypt_reader<R>(key: EncryptionKey, reader: R) -> impl AsyncRead
+pub fn decrypt_reader<R>(
+    provider: Arc<dyn CryptoProvider>,
+    key: EncryptionKey,
+    reader: R,
+) -> impl AsyncRead
 where
     R: AsyncRead + Unpin + Send,
 {
-    StreamReader::new(decrypt_frames(key, reader))
+    StreamReader::new(decrypt_frames(provider, key, reader))
 }
 
 /// Streams sealed frames from a plaintext reader.
@@ -173,17 +268,18 @@ where
 /// Reads one full [`CHUNK_SIZE`] plaintext chunk per iteration; a short read
 /// (only reached at EOF, since [`read_chunk`] drains the source) marks the last
 /// frame. An exact-multiple input therefore ends on an empty last frame.
-fn encrypt_frames<R>(key: EncryptionKey, mut reader: R) -> impl Stream<Item = io::Result<Bytes>>
+fn encrypt_frames<R>(
+    provider: Arc<dyn CryptoProvider>,
+    key: EncryptionKey,
+    mut reader: R,
+) -> impl Stream<Item = io::Result<Bytes>>
 where
     R: AsyncRead + Unpin + Send,
 {
     async_stream::try_stream! {
-        let mut prefix = [0u8; NONCE_PREFIX_SIZE];
-        rand::rng().fill_bytes(&mut prefix);
-        yield Bytes::copy_from_slice(&prefix);
-
-        let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
-        let mut encryptor = EncryptorLE31::from_aead(cipher, (&prefix).into());
+        let mut encryptor =
+            StreamCipher::generate(provider.as_ref(), &key).map_err(encrypt_io_error)?;
+        yield Bytes::copy_from_slice(encryptor.prefix());
 
         let mut chunk = vec![0u8; CHUNK_SIZE];
         loop {
@@ -204,16 +300,19 @@ where
 /// LE31 requires knowing which frame is last before opening it, so this holds
 /// one full frame back: it opens the held frame as the last block once the next
 /// read comes up short. Two buffers are swapped to avoid per-frame allocation.
-fn decrypt_frames<R>(key: EncryptionKey, mut reader: R) -> impl Stream<Item = io::Result<Bytes>>
+fn decrypt_frames<R>(
+    provider: Arc<dyn CryptoProvider>,
+    key: EncryptionKey,
+    mut reader: R,
+) -> impl Stream<Item = io::Result<Bytes>>
 where
     R: AsyncRead + Unpin + Send,
 {
     async_stream::try_stream! {
-        let mut prefix = [0u8; NONCE_PREFIX_SIZE];
+        let mut prefix = vec![0u8; nonce_prefix_size(provider.as_ref())];
         reader.read_exact(&mut prefix).await?;
 
-        let cipher = XChaCha20Poly1305::new(key.as_bytes().into());
-        let mut decryptor = DecryptorLE31::from_aead(cipher, (&prefix).into());
+        let mut decryptor = StreamCipher::new(provider.as_ref(), &key, &prefix);
 
         let mut held = vec![0u8; ENCRYPTED_CHUNK_SIZE];
         let mut ahead = vec![0u8; ENCRYPTED_CHUNK_SIZE];
@@ -256,62 +355,68 @@ async fn read_chunk<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> io:
 }
 
 /// Maps a seal failure into an [`io::Error`] for the encrypting adapter.
-fn encrypt_io_error(_: aead_stream::aead::Error) -> io::Error {
-    io::Error::other(CryptoError::EncryptionFailed)
+fn encrypt_io_error(err: CryptoError) -> io::Error {
+    io::Error::other(err)
 }
 
 /// Maps an open failure into an [`io::Error`] for the decrypting adapter.
-fn decrypt_io_error(_: aead_stream::aead::Error) -> io::Error {
-    io::Error::new(io::ErrorKind::InvalidData, CryptoError::DecryptionFailed)
+fn decrypt_io_error(err: CryptoError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
+    use nvisy_core::crypto::RustCryptoProvider;
+
     use super::*;
 
     fn key() -> EncryptionKey {
         EncryptionKey::generate()
     }
 
+    fn provider() -> Arc<dyn CryptoProvider> {
+        Arc::new(RustCryptoProvider)
+    }
+
     #[test]
     fn round_trips_empty() {
         let k = key();
-        let ct = encrypt(&k, b"").unwrap();
-        assert_eq!(decrypt(&k, &ct).unwrap(), b"");
+        let ct = encrypt(&RustCryptoProvider, &k, b"").unwrap();
+        assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), b"");
     }
 
     #[test]
     fn round_trips_small() {
         let k = key();
         let msg = b"the quick brown fox";
-        let ct = encrypt(&k, msg).unwrap();
-        assert_eq!(decrypt(&k, &ct).unwrap(), msg);
+        let ct = encrypt(&RustCryptoProvider, &k, msg).unwrap();
+        assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), msg);
     }
 
     #[test]
     fn round_trips_exactly_one_chunk() {
         let k = key();
         let msg = vec![7u8; CHUNK_SIZE];
-        let ct = encrypt(&k, &msg).unwrap();
-        assert_eq!(decrypt(&k, &ct).unwrap(), msg);
+        let ct = encrypt(&RustCryptoProvider, &k, &msg).unwrap();
+        assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), msg);
     }
 
     #[test]
     fn round_trips_multi_chunk_with_remainder() {
         let k = key();
         let msg = vec![3u8; CHUNK_SIZE * 2 + 123];
-        let ct = encrypt(&k, &msg).unwrap();
-        assert_eq!(decrypt(&k, &ct).unwrap(), msg);
+        let ct = encrypt(&RustCryptoProvider, &k, &msg).unwrap();
+        assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), msg);
     }
 
     #[test]
     fn wrong_key_fails() {
         let msg = b"secret";
-        let ct = encrypt(&key(), msg).unwrap();
+        let ct = encrypt(&RustCryptoProvider, &key(), msg).unwrap();
         assert!(matches!(
-            decrypt(&key(), &ct),
+            decrypt(&RustCryptoProvider, &key(), &ct),
             Err(CryptoError::DecryptionFailed)
         ));
     }
@@ -320,10 +425,10 @@ mod tests {
     fn truncated_tail_fails() {
         let k = key();
         let msg = vec![1u8; CHUNK_SIZE * 2 + 5];
-        let ct = encrypt(&k, &msg).unwrap();
+        let ct = encrypt(&RustCryptoProvider, &k, &msg).unwrap();
         // Drop the final frame: the stream no longer ends on its last block.
-        let truncated = &ct[..NONCE_PREFIX_SIZE + ENCRYPTED_CHUNK_SIZE];
-        assert!(decrypt(&k, truncated).is_err());
+        let truncated = &ct[..nonce_prefix_size(&RustCryptoProvider) + ENCRYPTED_CHUNK_SIZE];
+        assert!(decrypt(&RustCryptoProvider, &k, truncated).is_err());
     }
 
     async fn read_all(reader: impl AsyncRead) -> Vec<u8> {
@@ -336,8 +441,13 @@ mod tests {
     /// Encrypts via the reader adapter and reads it back with the adapter.
     async fn reader_round_trip(msg: &[u8]) {
         let k = key();
-        let ct = read_all(encrypt_reader(k.clone(), Cursor::new(msg.to_vec()))).await;
-        let pt = read_all(decrypt_reader(k.clone(), Cursor::new(ct))).await;
+        let ct = read_all(encrypt_reader(
+            provider(),
+            k.clone(),
+            Cursor::new(msg.to_vec()),
+        ))
+        .await;
+        let pt = read_all(decrypt_reader(provider(), k.clone(), Cursor::new(ct))).await;
         assert_eq!(pt, msg);
     }
 
@@ -360,24 +470,29 @@ mod tests {
         let msg = vec![42u8; CHUNK_SIZE * 2 + 7];
 
         // buffered encrypt -> reader decrypt
-        let ct = encrypt(&k, &msg).unwrap();
-        let pt = read_all(decrypt_reader(k.clone(), Cursor::new(ct))).await;
+        let ct = encrypt(&RustCryptoProvider, &k, &msg).unwrap();
+        let pt = read_all(decrypt_reader(provider(), k.clone(), Cursor::new(ct))).await;
         assert_eq!(pt, msg);
 
         // reader encrypt -> buffered decrypt
-        let ct = read_all(encrypt_reader(k.clone(), Cursor::new(msg.clone()))).await;
-        assert_eq!(decrypt(&k, &ct).unwrap(), msg);
+        let ct = read_all(encrypt_reader(
+            provider(),
+            k.clone(),
+            Cursor::new(msg.clone()),
+        ))
+        .await;
+        assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), msg);
     }
 
     #[tokio::test]
     async fn reader_decrypt_rejects_tamper() {
         let k = key();
         let msg = vec![1u8; CHUNK_SIZE + 10];
-        let mut ct = read_all(encrypt_reader(k.clone(), Cursor::new(msg))).await;
+        let mut ct = read_all(encrypt_reader(provider(), k.clone(), Cursor::new(msg))).await;
         let last = ct.len() - 1;
         ct[last] ^= 0x01;
 
-        let mut reader = std::pin::pin!(decrypt_reader(k.clone(), Cursor::new(ct)));
+        let mut reader = std::pin::pin!(decrypt_reader(provider(), k.clone(), Cursor::new(ct)));
         let mut out = Vec::new();
         assert!(reader.read_to_end(&mut out).await.is_err());
     }
@@ -391,15 +506,20 @@ mod tests {
             let msg = vec![9u8; CHUNK_SIZE * chunks];
 
             // buffered encrypt -> reader decrypt
-            let ct = encrypt(&k, &msg).unwrap();
+            let ct = encrypt(&RustCryptoProvider, &k, &msg).unwrap();
             assert_eq!(
-                read_all(decrypt_reader(k.clone(), Cursor::new(ct))).await,
+                read_all(decrypt_reader(provider(), k.clone(), Cursor::new(ct))).await,
                 msg
             );
 
             // reader encrypt -> buffered decrypt
-            let ct = read_all(encrypt_reader(k.clone(), Cursor::new(msg.clone()))).await;
-            assert_eq!(decrypt(&k, &ct).unwrap(), msg);
+            let ct = read_all(encrypt_reader(
+                provider(),
+                k.clone(),
+                Cursor::new(msg.clone()),
+            ))
+            .await;
+            assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), msg);
         }
     }
 
@@ -415,10 +535,44 @@ mod tests {
             ChunkedReader::new(msg.clone(), 7),
         ))
         .await;
-        let pt = read_all(decrypt_reader(k.clone(), ChunkedReader::new(ct, 13))).await;
+        let pt = read_all(decrypt_reader(
+            provider(),
+            k.clone(),
+            ChunkedReader::new(ct, 13),
+        ))
+        .await;
         assert_eq!(pt, msg);
     }
 
+    /// The provider-based framing is bit-compatible with `aead-stream`'s
+    /// `LE31` encoder, so data sealed before the provider abstraction still
+    /// opens under the standard policy.
+    #[test]
+    fn decrypts_aead_stream_le31_output() {
+        use aead_stream::EncryptorLE31;
+        use chacha20poly1305::XChaCha20Poly1305;
+        use chacha20poly1305::aead::KeyInit;
+
+        let k = key();
+        let msg = vec![6u8; CHUNK_SIZE * 2 + 11];
+        let prefix = [0x5a; 20];
+
+        let cipher = XChaCha20Poly1305::new(k.as_bytes().into());
+        let mut encryptor = EncryptorLE31::from_aead(cipher, (&prefix).into());
+        let mut ct = prefix.to_vec();
+        let mut chunks = msg.chunks(CHUNK_SIZE).peekable();
+        while let Some(chunk) = chunks.next() {
+            let frame = if chunks.peek().is_some() {
+                encryptor.encrypt_next(chunk).unwrap()
+            } else {
+                encryptor.encrypt_last(chunk).unwrap()
+            };
+            ct.extend_from_slice(&frame);
+        }
+
+        assert_eq!(decrypt(&RustCryptoProvider, &k, &ct).unwrap(), msg);
+    }
+
     /// A reader that yields at most `step` bytes per `poll_read`, to exercise the
     /// partial-read coalescing the real multipart and object-store readers hit.
     struct ChunkedReader {
@@ -17,9 +17,18 @@ pub enum CryptoError {
     /// Decryption failed - data may be corrupted or tampered with.
     #[error("decryption failed: data may be corrupted or tampered with")]
     DecryptionFailed,
+    /// The random number generator failed.
+    #[error("random number generator failed")]
+    RandomFailed,
     /// The provided key has an invalid length.
     #[error("invalid key length: expected 32 bytes")]
     InvalidKeyLength,
+    /// The key management service failed to wrap or unwrap a data key.
+    #[error("key management failed: {0}")]
+    KeyManagement(String),
+    /// An object's stored key metadata is incomplete or malformed.
+    #[error("invalid object key metadata")]
+    InvalidKeyMetadata,
     /// JSON serialization/deserialization failed.
     #[error("json error: {0}")]
     Json(String),
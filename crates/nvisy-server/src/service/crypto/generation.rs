@@ -1,13 +1,17 @@
 //! Random secret generation.
 
-use rand::Rng;
+use nvisy_core::crypto::CryptoProvider;
+
+use super::error::{CryptoError, CryptoResult};
 
 /// Bytes of entropy in a generated secret token.
 const SECRET_SIZE: usize = 32;
 
 /// Generates a fresh [`SECRET_SIZE`]-byte random secret, hex-encoded.
-pub fn generate_secret() -> String {
+pub fn generate_secret(provider: &dyn CryptoProvider) -> CryptoResult<String> {
     let mut bytes = [0u8; SECRET_SIZE];
-    rand::rng().fill_bytes(&mut bytes);
-    hex::encode(bytes)
+    provider
+        .fill_random(&mut bytes)
+        .map_err(|_| CryptoError::RandomFailed)?;
+    Ok(hex::encode(bytes))
 }
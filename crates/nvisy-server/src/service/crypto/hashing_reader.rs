@@ -10,14 +10,13 @@ use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
+use nvisy_core::crypto::Sha256Context;
 use pin_project_lite::pin_project;
-use sha2::{Digest, Sha256};
 use tokio::io::{AsyncRead, ReadBuf};
 
 /// Running plaintext size and hash, shared with a [`HashingReader`].
-#[derive(Default)]
 struct Meter {
-    hasher: Sha256,
+    hasher: Box<dyn Sha256Context>,
     bytes: u64,
 }
 
@@ -26,7 +25,7 @@ struct Meter {
 /// Cloneable and readable after the reader has been consumed (e.g. handed to
 /// the encryptor and streamed to storage), which is when the plaintext size and
 /// hash are needed for the file record.
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct Measurements {
     meter: Arc<Mutex<Meter>>,
 }
@@ -39,8 +38,11 @@ impl Measurements {
 
     /// SHA-256 over all plaintext measured.
     pub fn sha256(&self) -> [u8; 32] {
-        let lock = self.meter.lock().expect("measurements lock");
-        lock.hasher.clone().finalize().into()
+        self.meter
+            .lock()
+            .expect("measurements lock")
+            .hasher
+            .digest()
     }
 }
 
@@ -60,8 +62,12 @@ pin_project! {
 
 impl<R> HashingReader<R> {
     /// Wraps a reader, returning it alongside a handle to its measurements.
-    pub fn new(inner: R) -> (Self, Measurements) {
-        let measurements = Measurements::default();
+    ///
+    /// `hasher` is a fresh SHA-256 context from the active crypto provider.
+    pub fn new(inner: R, hasher: Box<dyn Sha256Context>) -> (Self, Measurements) {
+        let measurements = Measurements {
+            meter: Arc::new(Mutex::new(Meter { hasher, bytes: 0 })),
+        };
         let reader = Self {
             inner,
             measurements: measurements.clone(),
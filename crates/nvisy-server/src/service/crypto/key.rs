@@ -2,21 +2,32 @@
 
 use std::fmt;
 
-use hkdf::Hkdf;
+use nvisy_core::crypto::CryptoProvider;
 #[cfg(test)]
 use rand::Rng;
-use sha2::Sha256;
 use uuid::Uuid;
 
 use super::error::{CryptoError, CryptoResult};
 
-/// The size of an XChaCha20-Poly1305 key in bytes.
-pub const KEY_SIZE: usize = 32;
+/// The size of an AEAD key in bytes.
+pub const KEY_SIZE: usize = nvisy_core::crypto::AEAD_KEY_LEN;
 
 /// Domain separation string for workspace key derivation.
 const WORKSPACE_KEY_INFO: &[u8] = b"nvisy-workspace-encryption-key-v1";
 
-/// A 256-bit encryption key for XChaCha20-Poly1305.
+/// Domain separation string for workspace key-encryption key derivation.
+const WORKSPACE_KEK_INFO: &[u8] = b"nvisy-workspace-key-encryption-key-v1";
+
+/// Domain separation string for the master-derived credential vault key.
+const VAULT_KEY_INFO: &[u8] = b"nvisy-credential-vault-key-v1";
+
+/// Domain separation string for workspace credential key derivation.
+const CREDENTIAL_KEY_INFO: &[u8] = b"nvisy-workspace-credential-key-v1";
+
+/// Domain separation string for link signing key derivation.
+const SIGNING_KEY_INFO: &[u8] = b"nvisy-link-signing-key-v1";
+
+/// A 256-bit encryption key for the active provider's AEAD cipher.
 ///
 /// This type wraps the raw key bytes and provides safe construction methods.
 /// The key is stored in memory and should be handled carefully to avoid leaks.
@@ -59,11 +70,77 @@ impl EncryptionKey {
     /// This creates a unique encryption key for each workspace by combining
     /// the master key with the workspace ID.
     #[must_use]
-    pub fn derive_workspace_key(&self, workspace_id: Uuid) -> Self {
-        let hkdf = Hkdf::<Sha256>::new(Some(workspace_id.as_bytes()), &self.bytes);
+    pub fn derive_workspace_key(&self, provider: &dyn CryptoProvider, workspace_id: Uuid) -> Self {
+        let mut derived_key = [0u8; KEY_SIZE];
+        provider
+            .hkdf_sha256(
+                workspace_id.as_bytes(),
+                &self.bytes,
+                WORKSPACE_KEY_INFO,
+                &mut derived_key,
+            )
+            .expect("HKDF expand should not fail for 32-byte output");
 
+        Self { bytes: derived_key }
+    }
+
+    /// Derives a workspace's key-encryption key using HKDF-SHA256.
+    ///
+    /// The key only ever wraps per-object data keys; its derivation info
+    /// keeps it distinct from the workspace encryption key.
+    #[must_use]
+    pub fn derive_workspace_kek(&self, provider: &dyn CryptoProvider, workspace_id: Uuid) -> Self {
         let mut derived_key = [0u8; KEY_SIZE];
-        hkdf.expand(WORKSPACE_KEY_INFO, &mut derived_key)
+        provider
+            .hkdf_sha256(
+                workspace_id.as_bytes(),
+                &self.bytes,
+                WORKSPACE_KEK_INFO,
+                &mut derived_key,
+            )
+            .expect("HKDF expand should not fail for 32-byte output");
+
+        Self { bytes: derived_key }
+    }
+
+    /// Derives the credential vault key the master key stands in for when
+    /// no vault keys are configured, using HKDF-SHA256.
+    #[must_use]
+    pub fn derive_vault_key(&self, provider: &dyn CryptoProvider) -> Self {
+        let mut derived_key = [0u8; KEY_SIZE];
+        provider
+            .hkdf_sha256(&[], &self.bytes, VAULT_KEY_INFO, &mut derived_key)
+            .expect("HKDF expand should not fail for 32-byte output");
+
+        Self { bytes: derived_key }
+    }
+
+    /// Derives a workspace's credential key from a vault key using
+    /// HKDF-SHA256.
+    #[must_use]
+    pub fn derive_credential_key(&self, provider: &dyn CryptoProvider, workspace_id: Uuid) -> Self {
+        let mut derived_key = [0u8; KEY_SIZE];
+        provider
+            .hkdf_sha256(
+                workspace_id.as_bytes(),
+                &self.bytes,
+                CREDENTIAL_KEY_INFO,
+                &mut derived_key,
+            )
+            .expect("HKDF expand should not fail for 32-byte output");
+
+        Self { bytes: derived_key }
+    }
+
+    /// Derives the key for signing links using HKDF-SHA256.
+    ///
+    /// Uses its own derivation info, so it never coincides with a workspace
+    /// encryption key.
+    #[must_use]
+    pub fn derive_signing_key(&self, provider: &dyn CryptoProvider) -> Self {
+        let mut derived_key = [0u8; KEY_SIZE];
+        provider
+            .hkdf_sha256(&[], &self.bytes, SIGNING_KEY_INFO, &mut derived_key)
             .expect("HKDF expand should not fail for 32-byte output");
 
         Self { bytes: derived_key }
@@ -102,6 +179,8 @@ impl AsRef<[u8]> for EncryptionKey {
 
 #[cfg(test)]
 mod tests {
+    use nvisy_core::crypto::RustCryptoProvider;
+
     use super::*;
 
     #[test]
@@ -139,8 +218,8 @@ mod tests {
         let master_key = EncryptionKey::generate();
         let workspace_id = Uuid::new_v4();
 
-        let derived1 = master_key.derive_workspace_key(workspace_id);
-        let derived2 = master_key.derive_workspace_key(workspace_id);
+        let derived1 = master_key.derive_workspace_key(&RustCryptoProvider, workspace_id);
+        let derived2 = master_key.derive_workspace_key(&RustCryptoProvider, workspace_id);
 
         assert_eq!(derived1.as_bytes(), derived2.as_bytes());
     }
@@ -151,8 +230,8 @@ mod tests {
         let workspace1 = Uuid::new_v4();
         let workspace2 = Uuid::new_v4();
 
-        let derived1 = master_key.derive_workspace_key(workspace1);
-        let derived2 = master_key.derive_workspace_key(workspace2);
+        let derived1 = master_key.derive_workspace_key(&RustCryptoProvider, workspace1);
+        let derived2 = master_key.derive_workspace_key(&RustCryptoProvider, workspace2);
 
         assert_ne!(derived1.as_bytes(), derived2.as_bytes());
     }
@@ -163,18 +242,29 @@ mod tests {
         let master2 = EncryptionKey::generate();
         let workspace_id = Uuid::new_v4();
 
-        let derived1 = master1.derive_workspace_key(workspace_id);
-        let derived2 = master2.derive_workspace_key(workspace_id);
+        let derived1 = master1.derive_workspace_key(&RustCryptoProvider, workspace_id);
+        let derived2 = master2.derive_workspace_key(&RustCryptoProvider, workspace_id);
 
         assert_ne!(derived1.as_bytes(), derived2.as_bytes());
     }
 
+    #[test]
+    fn test_derive_workspace_kek_differs_from_workspace_key() {
+        let master_key = EncryptionKey::generate();
+        let workspace_id = Uuid::new_v4();
+
+        let key = master_key.derive_workspace_key(&RustCryptoProvider, workspace_id);
+        let kek = master_key.derive_workspace_kek(&RustCryptoProvider, workspace_id);
+
+        assert_ne!(key.as_bytes(), kek.as_bytes());
+    }
+
     #[test]
     fn test_derived_key_differs_from_master() {
         let master_key = EncryptionKey::generate();
         let workspace_id = Uuid::new_v4();
 
-        let derived = master_key.derive_workspace_key(workspace_id);
+        let derived = master_key.derive_workspace_key(&RustCryptoProvider, workspace_id);
         assert_ne!(derived.as_bytes(), master_key.as_bytes());
     }
 }
@@ -0,0 +1,344 @@
+//! Key management for envelope encryption.
+//!
+//! Envelope-encrypted objects are sealed under their own random data key.
+//! A [`KeyManagement`] implementation wraps that data key with the
+//! workspace's key-encryption key, so only the wrapped form is ever stored,
+//! and unwraps it again when the object is read.
+//!
+//! Two implementations are provided: [`LocalKms`], which derives the
+//! key-encryption key from the master key, and `AwsKms` (behind the
+//! `aws-kms` feature), which delegates wrapping to AWS KMS with the
+//! workspace bound as encryption context.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::prelude::*;
+use nvisy_core::crypto::CryptoProvider;
+use strum::{Display, EnumString};
+use uuid::Uuid;
+
+use super::encryption::{decrypt, encrypt};
+use super::error::{CryptoError, CryptoResult};
+use super::key::EncryptionKey;
+
+/// Object metadata entry naming the key management service.
+const METADATA_PROVIDER: &str = "nvisy-kms-provider";
+
+/// Object metadata entry naming the key-encryption key.
+const METADATA_KEY_ID: &str = "nvisy-kms-key-id";
+
+/// Object metadata entry holding the wrapped data key (base64).
+const METADATA_WRAPPED_KEY: &str = "nvisy-wrapped-key";
+
+/// Identifier of the local key-encryption key derivation.
+const LOCAL_KEY_ID: &str = "workspace-kek-v1";
+
+/// Which key management service wraps data keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum KmsProvider {
+    /// Key-encryption keys derived from the master key.
+    #[default]
+    Local,
+    /// AWS KMS; requires a build with the `aws-kms` feature.
+    Aws,
+}
+
+/// A data key wrapped by a key-encryption key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    /// Key management service that wrapped the key.
+    pub provider: String,
+    /// Key-encryption key the data key is wrapped under.
+    pub key_id: String,
+    /// The wrapped data key.
+    pub ciphertext: Vec<u8>,
+}
+
+impl WrappedKey {
+    /// Encodes the wrapped key as object metadata entries.
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        HashMap::from([
+            (METADATA_PROVIDER.to_owned(), self.provider.clone()),
+            (METADATA_KEY_ID.to_owned(), self.key_id.clone()),
+            (
+                METADATA_WRAPPED_KEY.to_owned(),
+                BASE64_STANDARD.encode(&self.ciphertext),
+            ),
+        ])
+    }
+
+    /// Decodes a wrapped key from object metadata.
+    ///
+    /// Returns `None` when the object is not envelope encrypted.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> CryptoResult<Option<Self>> {
+        let Some(wrapped) = metadata.get(METADATA_WRAPPED_KEY) else {
+            return Ok(None);
+        };
+
+        let (Some(provider), Some(key_id)) = (
+            metadata.get(METADATA_PROVIDER),
+            metadata.get(METADATA_KEY_ID),
+        ) else {
+            return Err(CryptoError::InvalidKeyMetadata);
+        };
+        let ciphertext = BASE64_STANDARD
+            .decode(wrapped)
+            .map_err(|_| CryptoError::InvalidKeyMetadata)?;
+
+        Ok(Some(Self {
+            provider: provider.clone(),
+            key_id: key_id.clone(),
+            ciphertext,
+        }))
+    }
+}
+
+impl fmt::Debug for WrappedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WrappedKey")
+            .field("provider", &self.provider)
+            .field("key_id", &self.key_id)
+            .field("ciphertext_len", &self.ciphertext.len())
+            .finish()
+    }
+}
+
+/// Wraps and unwraps per-object data keys.
+#[async_trait]
+pub trait KeyManagement: Send + Sync + 'static {
+    /// Name recorded with every key this service wraps.
+    fn name(&self) -> &'static str;
+
+    /// Wraps `data_key` under the workspace's key-encryption key.
+    async fn wrap_key(&self, workspace_id: Uuid, data_key: &[u8]) -> CryptoResult<WrappedKey>;
+
+    /// Unwraps a data key previously wrapped for the workspace.
+    async fn unwrap_key(&self, workspace_id: Uuid, wrapped: &WrappedKey) -> CryptoResult<Vec<u8>>;
+}
+
+/// Rejects a key wrapped by another key management service.
+fn ensure_provider(kms: &dyn KeyManagement, wrapped: &WrappedKey) -> CryptoResult<()> {
+    if wrapped.provider != kms.name() {
+        return Err(CryptoError::KeyManagement(format!(
+            "data key was wrapped by '{}', but '{}' is configured",
+            wrapped.provider,
+            kms.name()
+        )));
+    }
+    Ok(())
+}
+
+/// Key management with key-encryption keys derived from the master key.
+///
+/// Each workspace's key-encryption key is derived (HKDF-SHA256) with its own
+/// domain separation, so it never coincides with the workspace data key.
+pub struct LocalKms {
+    master_key: Arc<EncryptionKey>,
+    provider: Arc<dyn CryptoProvider>,
+}
+
+impl LocalKms {
+    /// Creates a local key management service over the master key.
+    pub(crate) fn new(master_key: Arc<EncryptionKey>, provider: Arc<dyn CryptoProvider>) -> Self {
+        Self {
+            master_key,
+            provider,
+        }
+    }
+
+    fn workspace_kek(&self, workspace_id: Uuid) -> EncryptionKey {
+        self.master_key
+            .derive_workspace_kek(self.provider.as_ref(), workspace_id)
+    }
+}
+
+#[async_trait]
+impl KeyManagement for LocalKms {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn wrap_key(&self, workspace_id: Uuid, data_key: &[u8]) -> CryptoResult<WrappedKey> {
+        let kek = self.workspace_kek(workspace_id);
+        Ok(WrappedKey {
+            provider: self.name().to_owned(),
+            key_id: LOCAL_KEY_ID.to_owned(),
+            ciphertext: encrypt(self.provider.as_ref(), &kek, data_key)?,
+        })
+    }
+
+    async fn unwrap_key(&self, workspace_id: Uuid, wrapped: &WrappedKey) -> CryptoResult<Vec<u8>> {
+        ensure_provider(self, wrapped)?;
+        if wrapped.key_id != LOCAL_KEY_ID {
+            return Err(CryptoError::KeyManagement(format!(
+                "unknown local key-encryption key '{}'",
+                wrapped.key_id
+            )));
+        }
+
+        let kek = self.workspace_kek(workspace_id);
+        decrypt(self.provider.as_ref(), &kek, &wrapped.ciphertext)
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+pub use self::aws::AwsKms;
+
+#[cfg(feature = "aws-kms")]
+mod aws {
+    use async_trait::async_trait;
+    use aws_sdk_kms::Client;
+    use aws_sdk_kms::primitives::Blob;
+    use uuid::Uuid;
+
+    use super::{KeyManagement, WrappedKey, ensure_provider};
+    use crate::service::crypto::{CryptoError, CryptoResult};
+
+    /// Encryption context entry binding a wrapped key to its workspace.
+    const CONTEXT_WORKSPACE_ID: &str = "nvisy:workspace_id";
+
+    /// Key management through AWS KMS.
+    ///
+    /// Data keys are wrapped under one KMS key with the workspace ID as
+    /// encryption context, so a wrapped key only unwraps for the workspace
+    /// it was created for. Credentials and region come from the standard AWS
+    /// configuration chain.
+    pub struct AwsKms {
+        client: Client,
+        key_id: String,
+    }
+
+    impl AwsKms {
+        /// Creates a client for the given KMS key ID or ARN.
+        pub async fn new(key_id: impl Into<String>) -> Self {
+            let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+            Self {
+                client: Client::new(&config),
+                key_id: key_id.into(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl KeyManagement for AwsKms {
+        fn name(&self) -> &'static str {
+            "aws"
+        }
+
+        async fn wrap_key(&self, workspace_id: Uuid, data_key: &[u8]) -> CryptoResult<WrappedKey> {
+            let output = self
+                .client
+                .encrypt()
+                .key_id(&self.key_id)
+                .plaintext(Blob::new(data_key))
+                .encryption_context(CONTEXT_WORKSPACE_ID, workspace_id.to_string())
+                .send()
+                .await
+                .map_err(|e| CryptoError::KeyManagement(e.to_string()))?;
+
+            let ciphertext = output.ciphertext_blob().ok_or_else(|| {
+                CryptoError::KeyManagement("KMS returned no ciphertext".to_owned())
+            })?;
+
+            Ok(WrappedKey {
+                provider: self.name().to_owned(),
+                key_id: output.key_id().unwrap_or(&self.key_id).to_owned(),
+                ciphertext: ciphertext.as_ref().to_vec(),
+            })
+        }
+
+        async fn unwrap_key(
+            &self,
+            workspace_id: Uuid,
+            wrapped: &WrappedKey,
+        ) -> CryptoResult<Vec<u8>> {
+            ensure_provider(self, wrapped)?;
+
+            let output = self
+                .client
+                .decrypt()
+                .key_id(&wrapped.key_id)
+                .ciphertext_blob(Blob::new(wrapped.ciphertext.clone()))
+                .encryption_context(CONTEXT_WORKSPACE_ID, workspace_id.to_string())
+                .send()
+                .await
+                .map_err(|e| CryptoError::KeyManagement(e.to_string()))?;
+
+            output
+                .plaintext()
+                .map(|plaintext| plaintext.as_ref().to_vec())
+                .ok_or_else(|| CryptoError::KeyManagement("KMS returned no plaintext".to_owned()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_core::crypto::RustCryptoProvider;
+
+    use super::*;
+
+    fn local_kms() -> LocalKms {
+        LocalKms::new(
+            Arc::new(EncryptionKey::generate()),
+            Arc::new(RustCryptoProvider),
+        )
+    }
+
+    #[test]
+    fn test_wrapped_key_metadata_roundtrip() {
+        let wrapped = WrappedKey {
+            provider: "local".to_owned(),
+            key_id: LOCAL_KEY_ID.to_owned(),
+            ciphertext: vec![1, 2, 3],
+        };
+
+        let metadata = wrapped.to_metadata();
+        assert_eq!(WrappedKey::from_metadata(&metadata).unwrap(), Some(wrapped));
+        assert_eq!(WrappedKey::from_metadata(&HashMap::new()).unwrap(), None);
+
+        let mut partial = metadata.clone();
+        partial.remove(METADATA_KEY_ID);
+        assert!(WrappedKey::from_metadata(&partial).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_wrap_roundtrip() {
+        let kms = local_kms();
+        let workspace_id = Uuid::new_v4();
+
+        let wrapped = kms.wrap_key(workspace_id, &[7; 32]).await.unwrap();
+        assert_ne!(wrapped.ciphertext, vec![7; 32]);
+
+        let unwrapped = kms.unwrap_key(workspace_id, &wrapped).await.unwrap();
+        assert_eq!(unwrapped, vec![7; 32]);
+    }
+
+    #[tokio::test]
+    async fn test_local_unwrap_other_workspace_fails() {
+        let kms = local_kms();
+        let wrapped = kms.wrap_key(Uuid::new_v4(), &[7; 32]).await.unwrap();
+        assert!(kms.unwrap_key(Uuid::new_v4(), &wrapped).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unwrap_other_provider_fails() {
+        let kms = local_kms();
+        let workspace_id = Uuid::new_v4();
+        let mut wrapped = kms.wrap_key(workspace_id, &[7; 32]).await.unwrap();
+        wrapped.provider = "aws".to_owned();
+        assert!(kms.unwrap_key(workspace_id, &wrapped).await.is_err());
+    }
+
+    #[test]
+    fn test_kms_provider_parse() {
+        assert_eq!("local".parse::<KmsProvider>().unwrap(), KmsProvider::Local);
+        assert_eq!("aws".parse::<KmsProvider>().unwrap(), KmsProvider::Aws);
+        assert!("vault".parse::<KmsProvider>().is_err());
+    }
+}
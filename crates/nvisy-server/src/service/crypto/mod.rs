@@ -1,14 +1,20 @@
 //! Cryptographic utilities for secure data handling.
 //!
-//! This module provides encryption and decryption utilities using XChaCha20-Poly1305,
-//! a modern AEAD cipher suitable for encrypting sensitive data at rest.
+//! This module provides encryption and decryption utilities over the AEAD
+//! cipher of the configured [`CryptoProvider`](nvisy_core::crypto::CryptoProvider):
+//! XChaCha20-Poly1305 by default, AES-256-GCM under the FIPS policy.
+//! Data keys for envelope-encrypted objects are wrapped through a pluggable
+//! [`KeyManagement`] service. Connection credentials are sealed with
+//! AES-256-GCM by a versioned [`CredentialVault`].
 
 mod encryption;
 mod error;
 mod generation;
 mod hashing_reader;
 mod key;
+mod kms;
 mod service;
+mod vault;
 
 pub(crate) use encryption::{
     decrypt, decrypt_json, decrypt_reader, encrypt, encrypt_json, encrypt_reader,
@@ -17,4 +23,9 @@ pub use error::{CryptoError, CryptoResult};
 pub(crate) use generation::generate_secret;
 pub(crate) use hashing_reader::HashingReader;
 pub(crate) use key::EncryptionKey;
-pub use service::{CryptoConfig, CryptoService};
+#[cfg(feature = "aws-kms")]
+pub use kms::AwsKms;
+pub use kms::{KeyManagement, KmsProvider, LocalKms, WrappedKey};
+pub use nvisy_core::crypto::CryptoPolicy;
+pub use service::{ContentKey, CryptoConfig, CryptoService};
+pub use vault::{CredentialVault, LEGACY_CREDENTIAL_KEY_VERSION, MASTER_CREDENTIAL_KEY_VERSION};
@@ -4,62 +4,173 @@
 //! per-workspace key (HKDF-SHA256) for each operation, so the master key is
 //! never used directly for workspace data. Master-scoped variants are also
 //! available for data not tied to a workspace.
+//!
+//! Every primitive runs through the [`CryptoProvider`] selected by the
+//! configured [`CryptoPolicy`], which is self-tested before the service is
+//! handed out.
+//!
+//! Sensitive objects are envelope encrypted instead: each is sealed under a
+//! fresh data key that the configured [`KeyManagement`] service wraps, and
+//! the wrapped key travels with the object as metadata (see [`ContentKey`]).
+//!
+//! Connection credentials are sealed by the service's [`CredentialVault`],
+//! whose keyring can be rotated without touching the master key.
 
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use nvisy_core::crypto::{CryptoPolicy, CryptoProvider, Sha256Context, self_test};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tokio::io::AsyncRead;
 use uuid::Uuid;
 
+use super::key::KEY_SIZE;
+use super::kms::{KeyManagement, KmsProvider, LocalKms, WrappedKey};
+use super::vault::CredentialVault;
 use super::{
-    CryptoResult, EncryptionKey, decrypt, decrypt_json, decrypt_reader, encrypt, encrypt_json,
-    encrypt_reader, generate_secret,
+    CryptoError, CryptoResult, EncryptionKey, decrypt, decrypt_json, decrypt_reader, encrypt,
+    encrypt_json, encrypt_reader, generate_secret,
 };
 use crate::{Error, Result};
 
 /// Tracing target for crypto service operations.
 const TRACING_TARGET: &str = "nvisy_server::crypto";
 
-/// Master encryption key file path configuration.
+/// Master encryption key and crypto policy configuration.
 #[derive(Debug, Clone)]
 pub struct CryptoConfig {
     /// File path to the 32-byte master encryption key.
     pub key_path: PathBuf,
+    /// Which crypto provider backs hashing, signing and encryption.
+    pub policy: CryptoPolicy,
+    /// Which key management service wraps envelope data keys.
+    pub kms_provider: KmsProvider,
+    /// Key-encryption key ID or ARN, required by external providers.
+    pub kms_key_id: Option<String>,
+    /// Directory of additional credential vault keys (`v{N}.key`, N >= 2).
+    ///
+    /// Without it credentials are sealed under the master-derived key.
+    pub credential_keys_path: Option<PathBuf>,
 }
 
 impl Default for CryptoConfig {
     fn default() -> Self {
         Self {
             key_path: "./encryption.key".into(),
+            policy: CryptoPolicy::default(),
+            kms_provider: KmsProvider::default(),
+            kms_key_id: None,
+            credential_keys_path: None,
         }
     }
 }
 
+/// The key an object's content is encrypted under.
+///
+/// Obtained from [`CryptoService::new_content_key`] when writing and
+/// [`CryptoService::stored_content_key`] when reading. An envelope key
+/// carries metadata that must be stored with the object; without it the
+/// object cannot be decrypted again.
+pub struct ContentKey {
+    key: EncryptionKey,
+    metadata: HashMap<String, String>,
+}
+
+impl ContentKey {
+    /// Returns the metadata to store alongside the object.
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Returns whether the content uses its own wrapped data key.
+    pub fn is_envelope(&self) -> bool {
+        !self.metadata.is_empty()
+    }
+}
+
+impl fmt::Debug for ContentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContentKey")
+            .field("key", &"[REDACTED]")
+            .field("envelope", &self.is_envelope())
+            .finish()
+    }
+}
+
 /// Workspace-aware encryption service.
 ///
 /// Holds the master key and derives per-workspace keys on demand. Cheap to
-/// clone (the key is shared through an `Arc`).
+/// clone (the key and provider are shared through an `Arc`).
 #[derive(Clone)]
 pub struct CryptoService {
     master_key: Arc<EncryptionKey>,
+    provider: Arc<dyn CryptoProvider>,
+    kms: Arc<dyn KeyManagement>,
+    credentials: CredentialVault,
 }
 
 impl CryptoService {
-    /// Loads the master key from the path specified in `config`.
+    /// Loads the master key from the path specified in `config` and
+    /// instantiates the provider for the configured policy.
     ///
     /// The file must contain exactly 32 raw bytes (256-bit key).
     pub async fn from_config(config: &CryptoConfig) -> Result<Self> {
-        Self::load(&config.key_path).await
+        let provider = Self::provider_for(config.policy)?;
+        let master_key = Arc::new(Self::load(&config.key_path).await?);
+        let kms = Self::kms_for(config, &master_key, &provider).await?;
+        let credential_keys = match &config.credential_keys_path {
+            Some(path) => CredentialVault::load_keys(path).await?,
+            None => BTreeMap::new(),
+        };
+        let credentials =
+            CredentialVault::new(provider.clone(), master_key.clone(), credential_keys);
+        Ok(Self {
+            master_key,
+            provider,
+            kms,
+            credentials,
+        })
     }
 
-    /// Loads the master key from a file path.
+    /// Loads the master key from a file path under the standard policy,
+    /// wrapping envelope keys locally.
     ///
     /// The file must contain exactly 32 raw bytes (256-bit key).
     pub async fn from_key_file(key_path: impl AsRef<Path>) -> Result<Self> {
-        Self::load(key_path.as_ref()).await
+        let provider = Self::provider_for(CryptoPolicy::Standard)?;
+        let master_key = Arc::new(Self::load(key_path.as_ref()).await?);
+        let kms = Arc::new(LocalKms::new(master_key.clone(), provider.clone()));
+        let credentials =
+            CredentialVault::new(provider.clone(), master_key.clone(), BTreeMap::new());
+        Ok(Self {
+            master_key,
+            provider,
+            kms,
+            credentials,
+        })
+    }
+
+    /// Returns the active crypto provider.
+    pub fn provider(&self) -> &Arc<dyn CryptoProvider> {
+        &self.provider
+    }
+
+    /// Returns the vault that seals connection credentials.
+    pub fn credentials(&self) -> &CredentialVault {
+        &self.credentials
+    }
+
+    /// Computes the SHA-256 digest of `data` with the active provider.
+    pub fn sha256(&self, data: &[u8]) -> [u8; 32] {
+        self.provider.sha256(data)
+    }
+
+    /// Starts an incremental SHA-256 computation with the active provider.
+    pub fn sha256_context(&self) -> Box<dyn Sha256Context> {
+        self.provider.sha256_context()
     }
 
     /// Encrypts a serializable value under the given workspace's key.
@@ -68,7 +179,11 @@ impl CryptoService {
         workspace_id: Uuid,
         value: &T,
     ) -> CryptoResult<Vec<u8>> {
-        encrypt_json(&self.workspace_key(workspace_id), value)
+        encrypt_json(
+            self.provider.as_ref(),
+            &self.workspace_key(workspace_id),
+            value,
+        )
     }
 
     /// Decrypts a value previously encrypted under the given workspace's key.
@@ -77,7 +192,11 @@ impl CryptoService {
         workspace_id: Uuid,
         ciphertext: &[u8],
     ) -> CryptoResult<T> {
-        decrypt_json(&self.workspace_key(workspace_id), ciphertext)
+        decrypt_json(
+            self.provider.as_ref(),
+            &self.workspace_key(workspace_id),
+            ciphertext,
+        )
     }
 
     /// Encrypts an in-memory buffer under the given workspace's key.
@@ -88,12 +207,20 @@ impl CryptoService {
     /// Use `encrypt_reader` when the data is file-sized and shouldn't be
     /// buffered whole.
     pub fn encrypt(&self, workspace_id: Uuid, plaintext: &[u8]) -> CryptoResult<Vec<u8>> {
-        encrypt(&self.workspace_key(workspace_id), plaintext)
+        encrypt(
+            self.provider.as_ref(),
+            &self.workspace_key(workspace_id),
+            plaintext,
+        )
     }
 
     /// Decrypts a buffer previously encrypted under the given workspace's key.
     pub fn decrypt(&self, workspace_id: Uuid, ciphertext: &[u8]) -> CryptoResult<Vec<u8>> {
-        decrypt(&self.workspace_key(workspace_id), ciphertext)
+        decrypt(
+            self.provider.as_ref(),
+            &self.workspace_key(workspace_id),
+            ciphertext,
+        )
     }
 
     /// Wraps a plaintext reader so it streams out workspace-encrypted bytes,
@@ -102,7 +229,11 @@ impl CryptoService {
     where
         R: AsyncRead + Unpin + Send,
     {
-        encrypt_reader(self.workspace_key(workspace_id), reader)
+        encrypt_reader(
+            self.provider.clone(),
+            self.workspace_key(workspace_id),
+            reader,
+        )
     }
 
     /// Wraps a ciphertext reader so it yields decrypted plaintext, for streaming
@@ -111,25 +242,202 @@ impl CryptoService {
     where
         R: AsyncRead + Unpin + Send,
     {
-        decrypt_reader(self.workspace_key(workspace_id), reader)
+        decrypt_reader(
+            self.provider.clone(),
+            self.workspace_key(workspace_id),
+            reader,
+        )
+    }
+
+    /// Creates the key for a new object in the workspace.
+    ///
+    /// With `envelope`, a fresh data key is generated and wrapped by the key
+    /// management service; otherwise the workspace key is used.
+    pub async fn new_content_key(
+        &self,
+        workspace_id: Uuid,
+        envelope: bool,
+    ) -> CryptoResult<ContentKey> {
+        if !envelope {
+            return Ok(ContentKey {
+                key: self.workspace_key(workspace_id),
+                metadata: HashMap::new(),
+            });
+        }
+
+        let mut data_key = [0u8; KEY_SIZE];
+        self.provider
+            .fill_random(&mut data_key)
+            .map_err(|_| CryptoError::RandomFailed)?;
+        let wrapped = self.kms.wrap_key(workspace_id, &data_key).await?;
+
+        Ok(ContentKey {
+            key: EncryptionKey::from_bytes(&data_key)?,
+            metadata: wrapped.to_metadata(),
+        })
+    }
+
+    /// Recovers the key of a stored object from its metadata.
+    ///
+    /// Objects without a wrapped data key use the workspace key.
+    pub async fn stored_content_key(
+        &self,
+        workspace_id: Uuid,
+        metadata: &HashMap<String, String>,
+    ) -> CryptoResult<ContentKey> {
+        let Some(wrapped) = WrappedKey::from_metadata(metadata)? else {
+            return Ok(ContentKey {
+                key: self.workspace_key(workspace_id),
+                metadata: HashMap::new(),
+            });
+        };
+
+        let data_key = self.kms.unwrap_key(workspace_id, &wrapped).await?;
+        Ok(ContentKey {
+            key: EncryptionKey::from_bytes(&data_key)?,
+            metadata: wrapped.to_metadata(),
+        })
+    }
+
+    /// Encrypts an object's content in memory under its content key.
+    pub fn encrypt_content(&self, key: &ContentKey, plaintext: &[u8]) -> CryptoResult<Vec<u8>> {
+        encrypt(self.provider.as_ref(), &key.key, plaintext)
+    }
+
+    /// Decrypts an object's content in memory under its content key.
+    pub fn decrypt_content(&self, key: &ContentKey, ciphertext: &[u8]) -> CryptoResult<Vec<u8>> {
+        decrypt(self.provider.as_ref(), &key.key, ciphertext)
+    }
+
+    /// Wraps a plaintext reader so it streams out content encrypted under
+    /// the object's content key.
+    pub fn encrypt_content_reader<R>(&self, key: &ContentKey, reader: R) -> impl AsyncRead + use<R>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        encrypt_reader(self.provider.clone(), key.key.clone(), reader)
+    }
+
+    /// Wraps a ciphertext reader so it yields content decrypted under the
+    /// object's content key.
+    pub fn decrypt_content_reader<R>(&self, key: &ContentKey, reader: R) -> impl AsyncRead + use<R>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        decrypt_reader(self.provider.clone(), key.key.clone(), reader)
     }
 
     /// Generates a fresh random secret token, hex-encoded.
     ///
     /// For shared secrets that must be recoverable in full (e.g. HMAC signing
     /// keys) — hand the plaintext to the caller once, then store it encrypted.
-    pub fn generate_secret(&self) -> String {
-        generate_secret()
+    pub fn generate_secret(&self) -> CryptoResult<String> {
+        generate_secret(self.provider.as_ref())
+    }
+
+    /// Computes an HMAC-SHA256 signature over `parts`.
+    ///
+    /// The key is derived from the master key for signing only. Used for
+    /// self-contained, tamper-evident values such as expiring download links.
+    pub fn sign(&self, parts: &[&[u8]]) -> [u8; 32] {
+        let key = self.master_key.derive_signing_key(self.provider.as_ref());
+        self.provider.hmac_sha256(key.as_bytes(), parts)
+    }
+
+    /// Checks a signature produced by [`sign`](Self::sign), comparing in
+    /// constant time.
+    pub fn verify_signature(&self, parts: &[&[u8]], signature: &[u8]) -> bool {
+        let expected = self.sign(parts);
+        expected.len() == signature.len()
+            && expected
+                .iter()
+                .zip(signature)
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
     }
 
     /// Derives the per-workspace key via HKDF-SHA256.
     #[inline]
     fn workspace_key(&self, workspace_id: Uuid) -> EncryptionKey {
-        self.master_key.derive_workspace_key(workspace_id)
+        self.master_key
+            .derive_workspace_key(self.provider.as_ref(), workspace_id)
+    }
+
+    /// Instantiates and self-tests the provider for `policy`.
+    ///
+    /// Refuses to start on a provider that fails any known-answer check, since
+    /// every hash, signature and ciphertext would be suspect.
+    fn provider_for(policy: CryptoPolicy) -> Result<Arc<dyn CryptoProvider>> {
+        let provider = policy.provider().map_err(|e| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                policy = %policy,
+                error = %e,
+                "Crypto provider unavailable",
+            );
+            Error::config("Crypto provider unavailable").with_source(e)
+        })?;
+
+        let report = self_test(provider.as_ref());
+        if !report.passed() {
+            tracing::error!(
+                target: TRACING_TARGET,
+                provider = report.provider,
+                report = %report,
+                "Crypto provider self-test failed",
+            );
+            return Err(Error::config(format!(
+                "Crypto provider self-test failed: {report}"
+            )));
+        }
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            policy = %policy,
+            provider = report.provider,
+            fips = report.fips,
+            aead = report.aead,
+            checks = report.checks.len(),
+            "Crypto provider self-test passed",
+        );
+
+        Ok(provider)
+    }
+
+    /// Creates the key management service selected by `config`.
+    async fn kms_for(
+        config: &CryptoConfig,
+        master_key: &Arc<EncryptionKey>,
+        provider: &Arc<dyn CryptoProvider>,
+    ) -> Result<Arc<dyn KeyManagement>> {
+        let kms: Arc<dyn KeyManagement> = match config.kms_provider {
+            KmsProvider::Local => Arc::new(LocalKms::new(master_key.clone(), provider.clone())),
+            #[cfg(feature = "aws-kms")]
+            KmsProvider::Aws => {
+                let Some(key_id) = config.kms_key_id.as_deref() else {
+                    return Err(Error::config("AWS KMS requires a key ID"));
+                };
+                Arc::new(super::kms::AwsKms::new(key_id).await)
+            }
+            #[cfg(not(feature = "aws-kms"))]
+            KmsProvider::Aws => {
+                return Err(Error::config(
+                    "AWS KMS is not available: build with the `aws-kms` feature",
+                ));
+            }
+        };
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            kms = kms.name(),
+            "Key management service configured",
+        );
+
+        Ok(kms)
     }
 
     /// Reads and parses the 32-byte master key from disk.
-    async fn load(path: &Path) -> Result<Self> {
+    async fn load(path: &Path) -> Result<EncryptionKey> {
         if !path.exists() {
             return Err(Error::config("Encryption key file does not exist"));
         }
@@ -165,9 +473,7 @@ impl CryptoService {
 
         tracing::info!(target: TRACING_TARGET, "Master encryption key loaded");
 
-        Ok(Self {
-            master_key: Arc::new(key),
-        })
+        Ok(key)
     }
 }
 
@@ -175,6 +481,9 @@ impl fmt::Debug for CryptoService {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("CryptoService")
             .field("master_key", &"[REDACTED]")
+            .field("provider", &self.provider.name())
+            .field("kms", &self.kms.name())
+            .field("credentials", &self.credentials)
             .finish()
     }
 }
@@ -231,6 +540,52 @@ mod tests {
         assert_eq!(decrypted, secret);
     }
 
+    #[tokio::test]
+    async fn signature_roundtrip() {
+        let crypto = service_with_key([0x42; 32]).await;
+        let signature = crypto.sign(&[b"op_1", b".", b"1700000000"]);
+
+        assert!(crypto.verify_signature(&[b"op_1", b".", b"1700000000"], &signature));
+        assert!(!crypto.verify_signature(&[b"op_1", b".", b"1700000001"], &signature));
+        assert!(!crypto.verify_signature(&[b"op_1", b".", b"1700000000"], &signature[..16]));
+
+        let other = service_with_key([0x24; 32]).await;
+        assert!(!other.verify_signature(&[b"op_1", b".", b"1700000000"], &signature));
+    }
+
+    #[tokio::test]
+    async fn envelope_content_roundtrip() {
+        let crypto = service_with_key([0x42; 32]).await;
+        let workspace_id = Uuid::new_v4();
+
+        let key = crypto.new_content_key(workspace_id, true).await.unwrap();
+        assert!(key.is_envelope());
+        let ciphertext = crypto.encrypt_content(&key, b"data").unwrap();
+
+        // The workspace key alone cannot open envelope-encrypted content.
+        assert!(crypto.decrypt(workspace_id, &ciphertext).is_err());
+
+        let stored = crypto
+            .stored_content_key(workspace_id, key.metadata())
+            .await
+            .unwrap();
+        assert_eq!(
+            crypto.decrypt_content(&stored, &ciphertext).unwrap(),
+            b"data"
+        );
+    }
+
+    #[tokio::test]
+    async fn plain_content_key_is_workspace_key() {
+        let crypto = service_with_key([0x42; 32]).await;
+        let workspace_id = Uuid::new_v4();
+
+        let key = crypto.new_content_key(workspace_id, false).await.unwrap();
+        assert!(!key.is_envelope());
+        let ciphertext = crypto.encrypt_content(&key, b"data").unwrap();
+        assert_eq!(crypto.decrypt(workspace_id, &ciphertext).unwrap(), b"data");
+    }
+
     #[tokio::test]
     async fn other_workspace_cannot_decrypt() {
         let crypto = service_with_key([0x42; 32]).await;
@@ -0,0 +1,353 @@
+//! Versioned keyring sealing connection credentials at rest.
+//!
+//! [`CredentialVault`] seals credentials with AES-256-GCM under a per-workspace
+//! key derived from the current vault key, and opens them under whichever
+//! version they were sealed with. Version 1 is derived from the master key,
+//! so a deployment without vault keys still has one; rotating means adding
+//! a key file with a higher version, after which the background rotation
+//! reseals every row still on an older version.
+//!
+//! Version 0 marks credentials written before the vault existed, encrypted
+//! with the workspace key; they are opened the old way until resealed.
+//!
+//! # Wire Format
+//!
+//! ```text
+//! format (1 byte) || key_version (4 bytes, big endian) || nonce (12 bytes) || ciphertext || tag (16 bytes)
+//! ```
+//!
+//! The header and the workspace ID are authenticated as associated data, so
+//! a value cannot be moved to another workspace or relabeled with another
+//! version.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use nvisy_core::crypto::{AEAD_TAG_LEN, AES_GCM_NONCE_LEN, CryptoProvider};
+use nvisy_postgres::types::{
+    CredentialCipher, CredentialError, CredentialResult, SealedCredentials,
+};
+use uuid::Uuid;
+
+use super::{EncryptionKey, decrypt};
+use crate::{Error, Result};
+
+/// Tracing target for the credential vault.
+const TRACING_TARGET: &str = "nvisy_server::crypto::vault";
+
+/// Version marking credentials encrypted with the workspace key, before the
+/// vault.
+pub const LEGACY_CREDENTIAL_KEY_VERSION: i32 = 0;
+
+/// Version of the vault key derived from the master key.
+pub const MASTER_CREDENTIAL_KEY_VERSION: i32 = 1;
+
+/// Leading byte of every sealed value.
+const FORMAT: u8 = 1;
+
+/// Length of the header preceding the ciphertext.
+const HEADER_LEN: usize = 1 + 4 + AES_GCM_NONCE_LEN;
+
+/// Keyring sealing and opening connection credentials.
+///
+/// Cheap to clone (the keys are shared through an `Arc`).
+#[derive(Clone)]
+pub struct CredentialVault {
+    provider: Arc<dyn CryptoProvider>,
+    master_key: Arc<EncryptionKey>,
+    keys: Arc<BTreeMap<i32, EncryptionKey>>,
+    current: i32,
+}
+
+impl CredentialVault {
+    /// Creates a vault from the master key and any additional vault keys.
+    ///
+    /// The highest version present becomes the one new credentials are
+    /// sealed under. `keys` must not hold versions 0 or 1, which are reserved.
+    pub(crate) fn new(
+        provider: Arc<dyn CryptoProvider>,
+        master_key: Arc<EncryptionKey>,
+        mut keys: BTreeMap<i32, EncryptionKey>,
+    ) -> Self {
+        keys.insert(
+            MASTER_CREDENTIAL_KEY_VERSION,
+            master_key.derive_vault_key(provider.as_ref()),
+        );
+        let current = keys
+            .last_key_value()
+            .map_or(MASTER_CREDENTIAL_KEY_VERSION, |(version, _)| *version);
+
+        Self {
+            provider,
+            master_key,
+            keys: Arc::new(keys),
+            current,
+        }
+    }
+
+    /// Loads vault keys from `dir`: one 32-byte file per version, named
+    /// `v{version}.key`, with versions starting at 2.
+    ///
+    /// Files not named like a key are ignored.
+    pub(crate) async fn load_keys(dir: &Path) -> Result<BTreeMap<i32, EncryptionKey>> {
+        let mut entries = tokio::fs::read_dir(dir).await.map_err(|e| {
+            Error::file_system("Failed to read credential key directory").with_source(e)
+        })?;
+
+        let mut keys = BTreeMap::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| {
+            Error::file_system("Failed to read credential key directory").with_source(e)
+        })? {
+            let file_name = entry.file_name();
+            let Some(version) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix('v')?.strip_suffix(".key"))
+                .and_then(|version| version.parse::<i32>().ok())
+            else {
+                continue;
+            };
+
+            if version <= MASTER_CREDENTIAL_KEY_VERSION {
+                return Err(Error::config(format!(
+                    "Credential key version {version} is reserved; number key files from 2"
+                )));
+            }
+
+            let bytes = tokio::fs::read(entry.path()).await.map_err(|e| {
+                Error::file_system("Failed to read credential key file").with_source(e)
+            })?;
+            let key = EncryptionKey::from_bytes(&bytes).map_err(|e| {
+                Error::config(format!(
+                    "Invalid credential key v{version}: expected exactly 32 bytes"
+                ))
+                .with_source(e)
+            })?;
+            keys.insert(version, key);
+        }
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            path = %dir.display(),
+            versions = ?keys.keys().collect::<Vec<_>>(),
+            "Credential vault keys loaded",
+        );
+
+        Ok(keys)
+    }
+
+    /// Returns every key version the vault can open, excluding the legacy
+    /// format.
+    pub fn key_versions(&self) -> impl Iterator<Item = i32> + '_ {
+        self.keys.keys().copied()
+    }
+
+    /// Derives the workspace's key for `version`.
+    fn workspace_key(&self, version: i32, workspace_id: Uuid) -> CredentialResult<EncryptionKey> {
+        let key = self
+            .keys
+            .get(&version)
+            .ok_or(CredentialError::UnknownKeyVersion(version))?;
+        Ok(key.derive_credential_key(self.provider.as_ref(), workspace_id))
+    }
+}
+
+/// Returns the associated data binding a sealed value to its header and
+/// workspace.
+fn associated_data(header: &[u8], workspace_id: Uuid) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + 16);
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(workspace_id.as_bytes());
+    aad
+}
+
+impl CredentialCipher for CredentialVault {
+    fn key_version(&self) -> i32 {
+        self.current
+    }
+
+    fn seal(&self, workspace_id: Uuid, plaintext: &[u8]) -> CredentialResult<SealedCredentials> {
+        let key = self.workspace_key(self.current, workspace_id)?;
+
+        let mut nonce = [0u8; AES_GCM_NONCE_LEN];
+        self.provider
+            .fill_random(&mut nonce)
+            .map_err(|_| CredentialError::SealFailed)?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.push(FORMAT);
+        header.extend_from_slice(&self.current.to_be_bytes());
+        header.extend_from_slice(&nonce);
+
+        let sealed = self
+            .provider
+            .aes256_gcm_seal(
+                key.as_bytes(),
+                &nonce,
+                &associated_data(&header, workspace_id),
+                plaintext,
+            )
+            .map_err(|_| CredentialError::SealFailed)?;
+
+        let mut ciphertext = header;
+        ciphertext.extend_from_slice(&sealed);
+        Ok(SealedCredentials {
+            key_version: self.current,
+            ciphertext,
+        })
+    }
+
+    fn open(
+        &self,
+        workspace_id: Uuid,
+        key_version: i32,
+        ciphertext: &[u8],
+    ) -> CredentialResult<Vec<u8>> {
+        if key_version == LEGACY_CREDENTIAL_KEY_VERSION {
+            let key = self
+                .master_key
+                .derive_workspace_key(self.provider.as_ref(), workspace_id);
+            return decrypt(self.provider.as_ref(), &key, ciphertext)
+                .map_err(|_| CredentialError::OpenFailed);
+        }
+
+        if ciphertext.len() < HEADER_LEN + AEAD_TAG_LEN || ciphertext[0] != FORMAT {
+            return Err(CredentialError::Malformed);
+        }
+        let (header, sealed) = ciphertext.split_at(HEADER_LEN);
+        let version = i32::from_be_bytes(header[1..5].try_into().expect("4-byte slice"));
+        if version != key_version {
+            return Err(CredentialError::Malformed);
+        }
+        let nonce: &[u8; AES_GCM_NONCE_LEN] = header[5..].try_into().expect("12-byte slice");
+
+        let key = self.workspace_key(version, workspace_id)?;
+        self.provider
+            .aes256_gcm_open(
+                key.as_bytes(),
+                nonce,
+                &associated_data(header, workspace_id),
+                sealed,
+            )
+            .map_err(|_| CredentialError::OpenFailed)
+    }
+}
+
+impl fmt::Debug for CredentialVault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialVault")
+            .field("keys", &"[REDACTED]")
+            .field("versions", &self.keys.keys().collect::<Vec<_>>())
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_core::crypto::RustCryptoProvider;
+
+    use super::super::encrypt;
+    use super::*;
+
+    fn vault(versions: &[i32]) -> CredentialVault {
+        let keys = versions
+            .iter()
+            .map(|version| (*version, EncryptionKey::generate()))
+            .collect();
+        CredentialVault::new(
+            Arc::new(RustCryptoProvider),
+            Arc::new(EncryptionKey::from_bytes(&[0x42; 32]).unwrap()),
+            keys,
+        )
+    }
+
+    #[test]
+    fn seal_open_roundtrip() {
+        let vault = vault(&[]);
+        let workspace_id = Uuid::new_v4();
+
+        let sealed = vault.seal(workspace_id, b"secret").unwrap();
+        assert_eq!(sealed.key_version, MASTER_CREDENTIAL_KEY_VERSION);
+        assert_eq!(
+            vault
+                .open(workspace_id, sealed.key_version, &sealed.ciphertext)
+                .unwrap(),
+            b"secret"
+        );
+    }
+
+    #[test]
+    fn sealed_credentials_are_bound_to_workspace_and_version() {
+        let vault = vault(&[2]);
+        let workspace_id = Uuid::new_v4();
+        let sealed = vault.seal(workspace_id, b"secret").unwrap();
+        assert_eq!(sealed.key_version, 2);
+
+        assert_eq!(
+            vault.open(Uuid::new_v4(), 2, &sealed.ciphertext),
+            Err(CredentialError::OpenFailed)
+        );
+        assert_eq!(
+            vault.open(workspace_id, 1, &sealed.ciphertext),
+            Err(CredentialError::Malformed)
+        );
+
+        let mut relabeled = sealed.ciphertext.clone();
+        relabeled[1..5].copy_from_slice(&1i32.to_be_bytes());
+        assert_eq!(
+            vault.open(workspace_id, 1, &relabeled),
+            Err(CredentialError::OpenFailed)
+        );
+    }
+
+    #[test]
+    fn rotated_vault_opens_older_versions() {
+        let workspace_id = Uuid::new_v4();
+        let before = vault(&[]);
+        let sealed = before.seal(workspace_id, b"secret").unwrap();
+
+        // Same master key, so version 1 is the same; version 2 is new.
+        let after = CredentialVault::new(
+            before.provider.clone(),
+            before.master_key.clone(),
+            BTreeMap::from([(2, EncryptionKey::generate())]),
+        );
+        assert_eq!(after.key_version(), 2);
+        assert_eq!(
+            after
+                .open(workspace_id, sealed.key_version, &sealed.ciphertext)
+                .unwrap(),
+            b"secret"
+        );
+        assert_eq!(
+            after.open(workspace_id, 3, &sealed.ciphertext),
+            Err(CredentialError::Malformed)
+        );
+    }
+
+    #[test]
+    fn legacy_credentials_open_with_workspace_key() {
+        let vault = vault(&[]);
+        let workspace_id = Uuid::new_v4();
+        let key = vault
+            .master_key
+            .derive_workspace_key(&RustCryptoProvider, workspace_id);
+        let legacy = encrypt(&RustCryptoProvider, &key, b"secret").unwrap();
+
+        assert_eq!(
+            vault
+                .open(workspace_id, LEGACY_CREDENTIAL_KEY_VERSION, &legacy)
+                .unwrap(),
+            b"secret"
+        );
+    }
+
+    #[test]
+    fn debug_redacts_keys() {
+        let debug = format!("{:?}", vault(&[2]));
+        assert!(debug.contains("REDACTED"));
+        assert!(debug.contains("current: 2"));
+    }
+}
@@ -0,0 +1,492 @@
+//! Comparison of two versions of a document.
+//!
+//! Text is compared line by line with Myers' algorithm, keeping every line so
+//! a client can lay both versions out side by side. Analyses are compared
+//! structurally: findings are matched by their `id` where the runtime assigns
+//! one, and by position otherwise.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Largest number of lines compared per version.
+pub const MAX_DIFF_LINES: usize = 20_000;
+
+/// Largest number of added and removed lines a text diff is computed for.
+///
+/// Memory grows with the square of the edit distance, so versions differing
+/// more than this are reported as not comparable instead.
+pub const MAX_EDIT_DISTANCE: usize = 1_000;
+
+/// Largest number of changes reported by a structural diff.
+pub const MAX_VALUE_CHANGES: usize = 1_000;
+
+/// Why two texts were not compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDiffLimit {
+    /// A version has more than [`MAX_DIFF_LINES`] lines.
+    TooManyLines,
+    /// The versions differ by more than [`MAX_EDIT_DISTANCE`] lines.
+    TooManyEdits,
+}
+
+/// How a line differs between two versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineChange {
+    /// The line is in both versions.
+    Equal,
+    /// The line is only in the newer version.
+    Added,
+    /// The line is only in the older version.
+    Removed,
+}
+
+/// One line of a text diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    /// How the line differs.
+    pub change: LineChange,
+    /// Line number in the older version, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_line: Option<u32>,
+    /// Line number in the newer version, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line: Option<u32>,
+    /// Line content, without its line ending.
+    pub text: String,
+}
+
+/// Line-level difference between two texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDiff {
+    /// Every line of both versions, in reading order.
+    pub lines: Vec<DiffLine>,
+    /// Number of added lines.
+    pub added: u32,
+    /// Number of removed lines.
+    pub removed: u32,
+    /// Number of lines in both versions.
+    pub unchanged: u32,
+}
+
+impl TextDiff {
+    /// Compares two texts line by line.
+    ///
+    /// Fails when either text exceeds [`MAX_DIFF_LINES`] or the texts differ
+    /// by more than [`MAX_EDIT_DISTANCE`] lines.
+    pub fn compute(old: &str, new: &str) -> Result<Self, TextDiffLimit> {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+        if old.len() > MAX_DIFF_LINES || new.len() > MAX_DIFF_LINES {
+            return Err(TextDiffLimit::TooManyLines);
+        }
+
+        // Common ends never need the search.
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let middle = shortest_edit(
+            &old[prefix..old.len() - suffix],
+            &new[prefix..new.len() - suffix],
+        )
+        .ok_or(TextDiffLimit::TooManyEdits)?;
+
+        let mut diff = Self {
+            lines: Vec::with_capacity(prefix + middle.len() + suffix),
+            added: 0,
+            removed: 0,
+            unchanged: 0,
+        };
+        let (mut old_index, mut new_index) = (0, 0);
+        let edits = std::iter::repeat_n(Edit::Equal, prefix)
+            .chain(middle)
+            .chain(std::iter::repeat_n(Edit::Equal, suffix));
+        for edit in edits {
+            let line = match edit {
+                Edit::Equal => {
+                    diff.unchanged += 1;
+                    old_index += 1;
+                    new_index += 1;
+                    DiffLine {
+                        change: LineChange::Equal,
+                        old_line: Some(old_index as u32),
+                        new_line: Some(new_index as u32),
+                        text: new[new_index - 1].to_owned(),
+                    }
+                }
+                Edit::Insert => {
+                    diff.added += 1;
+                    new_index += 1;
+                    DiffLine {
+                        change: LineChange::Added,
+                        old_line: None,
+                        new_line: Some(new_index as u32),
+                        text: new[new_index - 1].to_owned(),
+                    }
+                }
+                Edit::Delete => {
+                    diff.removed += 1;
+                    old_index += 1;
+                    DiffLine {
+                        change: LineChange::Removed,
+                        old_line: Some(old_index as u32),
+                        new_line: None,
+                        text: old[old_index - 1].to_owned(),
+                    }
+                }
+            };
+            diff.lines.push(line);
+        }
+
+        Ok(diff)
+    }
+}
+
+/// A step turning the older sequence into the newer one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Finds a shortest edit script from `a` to `b` (Myers, 1986).
+///
+/// Keeps the furthest-reaching x of each diagonal after every step, trimmed
+/// to the diagonals that step can reach, and walks them back to recover the
+/// script. Returns `None` past [`MAX_EDIT_DISTANCE`] steps.
+fn shortest_edit(a: &[&str], b: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let offset = MAX_EDIT_DISTANCE as isize + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    let at = |k: isize| (k + offset) as usize;
+    let mut finished = false;
+    for d in 0..=(MAX_EDIT_DISTANCE as isize).min(n + m) {
+        for k in (-d..=d).step_by(2) {
+            let down = k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]);
+            let mut x = if down { v[at(k + 1)] } else { v[at(k - 1)] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                finished = true;
+                break;
+            }
+        }
+        trace.push(v[at(-d)..=at(d)].to_vec());
+        if finished {
+            break;
+        }
+    }
+    if !finished {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let k = x - y;
+        if d == 0 {
+            edits.extend(std::iter::repeat_n(Edit::Equal, x as usize));
+            break;
+        }
+
+        let previous = &trace[d as usize - 1];
+        let prev_at = |k: isize| previous[(k + d - 1) as usize];
+        let down = k == -d || (k != d && prev_at(k - 1) < prev_at(k + 1));
+        let prev_k = if down { k + 1 } else { k - 1 };
+        let prev_x = prev_at(prev_k);
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            edits.push(Edit::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        edits.push(if down { Edit::Insert } else { Edit::Delete });
+        (x, y) = (prev_x, prev_y);
+    }
+
+    edits.reverse();
+    Some(edits)
+}
+
+/// How a value differs between two documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueChangeKind {
+    /// The value is only in the newer document.
+    Added,
+    /// The value is only in the older document.
+    Removed,
+    /// The value is in both documents, with different content.
+    Changed,
+}
+
+/// One difference between two JSON documents.
+#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueChange {
+    /// JSON pointer to the value; array items with an `id` are addressed by
+    /// it instead of their position.
+    pub path: String,
+    /// How the value differs.
+    pub kind: ValueChangeKind,
+    /// The value in the older document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    /// The value in the newer document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+/// Structural difference between two JSON documents.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuralDiff {
+    /// Differences, in document order.
+    pub changes: Vec<ValueChange>,
+    /// Whether changes past [`MAX_VALUE_CHANGES`] were left out.
+    pub truncated: bool,
+}
+
+impl StructuralDiff {
+    /// Compares two JSON documents.
+    ///
+    /// Objects are compared key by key. Arrays whose items all carry a
+    /// distinct string `id` are compared item by item on it, so a finding
+    /// that moved is not reported as changed; other arrays are compared by
+    /// position.
+    pub fn compute(before: &Value, after: &Value) -> Self {
+        let mut diff = Self::default();
+        diff.compare(&mut String::new(), before, after);
+        diff
+    }
+
+    fn compare(&mut self, path: &mut String, before: &Value, after: &Value) {
+        if self.truncated || before == after {
+            return;
+        }
+
+        match (before, after) {
+            (Value::Object(old), Value::Object(new)) => {
+                for (key, old_value) in old {
+                    let len = push_segment(path, key);
+                    match new.get(key) {
+                        Some(new_value) => self.compare(path, old_value, new_value),
+                        None => self.record(path, ValueChangeKind::Removed, Some(old_value), None),
+                    }
+                    path.truncate(len);
+                }
+                for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                    let len = push_segment(path, key);
+                    self.record(path, ValueChangeKind::Added, None, Some(new_value));
+                    path.truncate(len);
+                }
+            }
+            (Value::Array(old), Value::Array(new)) => match (keyed(old), keyed(new)) {
+                (Some(old), Some(new)) => {
+                    for (id, old_item) in &old {
+                        let len = push_segment(path, id);
+                        match new.iter().find(|(new_id, _)| new_id == id) {
+                            Some((_, new_item)) => self.compare(path, old_item, new_item),
+                            None => {
+                                self.record(path, ValueChangeKind::Removed, Some(old_item), None)
+                            }
+                        }
+                        path.truncate(len);
+                    }
+                    for (id, new_item) in &new {
+                        if !old.iter().any(|(old_id, _)| old_id == id) {
+                            let len = push_segment(path, id);
+                            self.record(path, ValueChangeKind::Added, None, Some(new_item));
+                            path.truncate(len);
+                        }
+                    }
+                }
+                _ => {
+                    for index in 0..old.len().max(new.len()) {
+                        let len = push_segment(path, &index.to_string());
+                        match (old.get(index), new.get(index)) {
+                            (Some(old_item), Some(new_item)) => {
+                                self.compare(path, old_item, new_item)
+                            }
+                            (Some(old_item), None) => {
+                                self.record(path, ValueChangeKind::Removed, Some(old_item), None)
+                            }
+                            (None, Some(new_item)) => {
+                                self.record(path, ValueChangeKind::Added, None, Some(new_item))
+                            }
+                            (None, None) => {}
+                        }
+                        path.truncate(len);
+                    }
+                }
+            },
+            _ => self.record(path, ValueChangeKind::Changed, Some(before), Some(after)),
+        }
+    }
+
+    fn record(
+        &mut self,
+        path: &str,
+        kind: ValueChangeKind,
+        before: Option<&Value>,
+        after: Option<&Value>,
+    ) {
+        if self.changes.len() == MAX_VALUE_CHANGES {
+            self.truncated = true;
+            return;
+        }
+
+        self.changes.push(ValueChange {
+            path: path.to_owned(),
+            kind,
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+}
+
+/// Returns the items of an array by their `id`, if every item has a distinct
+/// string one.
+fn keyed(items: &[Value]) -> Option<Vec<(&str, &Value)>> {
+    let mut keyed: Vec<(&str, &Value)> = Vec::with_capacity(items.len());
+    for item in items {
+        let id = item.get("id")?.as_str()?;
+        if keyed.iter().any(|(other, _)| *other == id) {
+            return None;
+        }
+        keyed.push((id, item));
+    }
+    Some(keyed)
+}
+
+/// Appends an escaped JSON pointer segment, returning the length to truncate
+/// back to.
+fn push_segment(path: &mut String, segment: &str) -> usize {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn changes(diff: &TextDiff) -> Vec<(LineChange, &str)> {
+        diff.lines
+            .iter()
+            .map(|line| (line.change, line.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_text_diff() {
+        let diff = TextDiff::compute("a\nb\nc\nd\n", "a\nc\nd\ne\n").unwrap();
+        assert_eq!(
+            changes(&diff),
+            [
+                (LineChange::Equal, "a"),
+                (LineChange::Removed, "b"),
+                (LineChange::Equal, "c"),
+                (LineChange::Equal, "d"),
+                (LineChange::Added, "e"),
+            ]
+        );
+        assert_eq!((diff.added, diff.removed, diff.unchanged), (1, 1, 3));
+        assert_eq!(diff.lines[4].old_line, None);
+        assert_eq!(diff.lines[4].new_line, Some(4));
+    }
+
+    #[test]
+    fn test_text_diff_replaced_line() {
+        let diff =
+            TextDiff::compute("name: Jane\nphone: 555\n", "name: John\nphone: 555\n").unwrap();
+        assert_eq!(
+            changes(&diff),
+            [
+                (LineChange::Removed, "name: Jane"),
+                (LineChange::Added, "name: John"),
+                (LineChange::Equal, "phone: 555"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_diff_identical_and_empty() {
+        let same = TextDiff::compute("x\ny", "x\ny").unwrap();
+        assert_eq!((same.added, same.removed, same.unchanged), (0, 0, 2));
+
+        let emptied = TextDiff::compute("x\ny", "").unwrap();
+        assert_eq!((emptied.added, emptied.removed), (0, 2));
+    }
+
+    #[test]
+    fn test_text_diff_too_different() {
+        let old = (0..1_200).map(|i| format!("a{i}\n")).collect::<String>();
+        let new = (0..1_200).map(|i| format!("b{i}\n")).collect::<String>();
+        assert_eq!(
+            TextDiff::compute(&old, &new),
+            Err(TextDiffLimit::TooManyEdits)
+        );
+    }
+
+    #[test]
+    fn test_structural_diff_by_id() {
+        let before = json!({ "entities": [
+            { "id": "e1", "label": "email", "score": 0.9 },
+            { "id": "e2", "label": "phone" },
+        ]});
+        let after = json!({ "entities": [
+            { "id": "e3", "label": "name" },
+            { "id": "e1", "label": "email", "score": 0.7 },
+        ]});
+
+        let diff = StructuralDiff::compute(&before, &after);
+        let summary: Vec<_> = diff
+            .changes
+            .iter()
+            .map(|change| (change.path.as_str(), change.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("/entities/e1/score", ValueChangeKind::Changed),
+                ("/entities/e2", ValueChangeKind::Removed),
+                ("/entities/e3", ValueChangeKind::Added),
+            ]
+        );
+        assert!(!diff.truncated);
+    }
+
+    #[test]
+    fn test_structural_diff_by_position() {
+        let diff = StructuralDiff::compute(&json!({ "a/b": [1, 2] }), &json!({ "a/b": [1] }));
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].path, "/a~1b/1");
+        assert_eq!(diff.changes[0].kind, ValueChangeKind::Removed);
+    }
+}
@@ -0,0 +1,25 @@
+//! Document inspection at ingest and before processing.
+//!
+//! Uploaded bytes are encrypted before they reach storage, so anything the
+//! server needs to know about the document itself has to be learned while the
+//! plaintext streams past, or by decrypting it again for a preflight, a
+//! comparison of two versions or a watermarked copy for a share link.
+
+mod diff;
+mod password;
+mod pdf_security;
+mod preflight;
+mod protection;
+mod watermark;
+
+pub use diff::{
+    DiffLine, LineChange, StructuralDiff, TextDiff, TextDiffLimit, ValueChange, ValueChangeKind,
+};
+pub use password::DocumentPasswords;
+pub use preflight::{
+    DocumentQuality, PreflightReport, PreflightViolation, ProcessingTier, count_pages,
+};
+pub(crate) use protection::{ProtectionProbe, ProtectionReader};
+pub use watermark::{
+    Watermark, WatermarkError, WatermarkMode, select_pages, supports_page_selection,
+};
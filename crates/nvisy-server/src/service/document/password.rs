@@ -0,0 +1,96 @@
+//! Transient passwords of protected documents.
+//!
+//! A document password can be stored on the file, encrypted under the
+//! workspace key, or supplied transiently: when starting a run, or ahead of
+//! runs through the file's transient password endpoint. A transient password
+//! is held, still encrypted, in a NATS KV bucket kept in server memory only,
+//! and expires with the bucket's TTL. It never reaches the database or disk;
+//! once it expires, the document needs its password again.
+//!
+//! The bucket stays on NATS even when the other caches are in Redis, since a
+//! Redis server may snapshot its data to disk.
+
+use std::time::Duration;
+
+use nvisy_nats::kv::{DocumentKey, DocumentPassword};
+use nvisy_postgres::model::WorkspaceFile;
+
+use crate::handler::{ErrorKind, Result};
+use crate::service::{Cache, CryptoService};
+
+/// Tracing target for transient document passwords.
+const TRACING_TARGET: &str = "nvisy_server::document_passwords";
+
+/// Holds the passwords supplied for protected documents without storing
+/// them.
+#[derive(Clone)]
+pub struct DocumentPasswords {
+    cache: Cache<DocumentKey, DocumentPassword>,
+    crypto: CryptoService,
+}
+
+impl DocumentPasswords {
+    /// Creates the service over a memory-only cache.
+    pub fn new(cache: Cache<DocumentKey, DocumentPassword>, crypto: CryptoService) -> Self {
+        Self { cache, crypto }
+    }
+
+    /// Holds `password` for `file`, replacing any password held for it.
+    ///
+    /// Returns how long the password will be held.
+    pub async fn supply(&self, file: &WorkspaceFile, password: &str) -> Result<Option<Duration>> {
+        let key = DocumentKey(file.id);
+        let entry = DocumentPassword {
+            workspace_id: file.workspace_id,
+            encrypted_password: self
+                .crypto
+                .encrypt(file.workspace_id, password.as_bytes())?,
+        };
+        self.cache.put(&key, &entry).await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            file_id = %file.id,
+            "Transient document password held"
+        );
+
+        Ok(self.cache.ttl(&key).await?)
+    }
+
+    /// Returns the password held for `file`, if it has not expired.
+    pub async fn get(&self, file: &WorkspaceFile) -> Result<Option<String>> {
+        let Some(entry) = self.cache.get_value(&DocumentKey(file.id)).await? else {
+            return Ok(None);
+        };
+
+        // Keys are file ids alone; an entry is only ever read back for the
+        // workspace that supplied it.
+        if entry.workspace_id != file.workspace_id {
+            return Ok(None);
+        }
+
+        let bytes = self
+            .crypto
+            .decrypt(file.workspace_id, &entry.encrypted_password)?;
+        let password = String::from_utf8(bytes).map_err(|err| {
+            ErrorKind::InternalServerError
+                .with_message("Transient document password is invalid")
+                .with_context(err.to_string())
+        })?;
+
+        Ok(Some(password))
+    }
+
+    /// Drops the password held for `file`, if any.
+    pub async fn forget(&self, file: &WorkspaceFile) -> Result<()> {
+        self.cache.delete(&DocumentKey(file.id)).await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            file_id = %file.id,
+            "Transient document password dropped"
+        );
+
+        Ok(())
+    }
+}
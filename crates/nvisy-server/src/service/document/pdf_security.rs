@@ -0,0 +1,630 @@
+//! The PDF standard security handler's empty-password check.
+//!
+//! An encrypted PDF is not necessarily password-protected. A document that
+//! only restricts permissions (printing, copying, editing) is encrypted with
+//! an empty user password, and every reader opens it without asking. Telling
+//! the two apart means running the handler's own user-password check
+//! (ISO 32000-2, 7.6.4.4) with the empty password against the `/U` entry of
+//! the encryption dictionary.
+//!
+//! MD5, RC4 and the AES rounds below reproduce the file format's key
+//! derivation; they protect nothing, so they do not go through the crypto
+//! provider.
+
+use aes::Aes128;
+use aes::cipher::{BlockCipherEncrypt, KeyInit};
+use md5::{Digest, Md5};
+use sha2::{Sha256, Sha384, Sha512};
+
+/// Pads a user password to 32 bytes (Algorithm 2, step a).
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xbf, 0x4e, 0x5e, 0x4e, 0x75, 0x8a, 0x41, 0x64, 0x00, 0x4e, 0x56, 0xff, 0xfa, 0x01, 0x08,
+    0x2e, 0x2e, 0x00, 0xb6, 0xd0, 0x68, 0x3e, 0x80, 0x2f, 0x0c, 0xa9, 0xfe, 0x64, 0x53, 0x69, 0x7a,
+];
+
+/// Deepest nesting of arrays and dictionaries the parser follows.
+const MAX_DEPTH: usize = 16;
+
+/// How many candidate dictionary starts are tried per `/Standard` name.
+const MAX_DICTIONARY_CANDIDATES: usize = 32;
+
+/// The fields of a standard security handler's encryption dictionary that
+/// the user-password check needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct StandardSecurity {
+    revision: i64,
+    key_length: usize,
+    owner: Vec<u8>,
+    user: Vec<u8>,
+    permissions: u32,
+    encrypt_metadata: bool,
+}
+
+impl StandardSecurity {
+    /// Reads the handler from a parsed encryption dictionary.
+    ///
+    /// Returns `None` unless the dictionary names the standard handler and
+    /// carries its entries directly (not as indirect references).
+    fn from_dictionary(entries: &[(Vec<u8>, Object)]) -> Option<Self> {
+        let get = |key: &[u8]| {
+            entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+        };
+
+        match get(b"Filter")? {
+            Object::Name(name) if name == b"Standard" => {}
+            _ => return None,
+        }
+
+        let integer = |key: &[u8]| match get(key) {
+            Some(Object::Integer(value)) => Some(*value),
+            _ => None,
+        };
+        let string = |key: &[u8]| match get(key) {
+            Some(Object::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+
+        Some(Self {
+            revision: integer(b"R")?,
+            key_length: integer(b"Length")
+                .and_then(|bits| usize::try_from(bits / 8).ok())
+                .unwrap_or(5),
+            owner: string(b"O")?,
+            user: string(b"U")?,
+            // Writers disagree on the sign; only the low 32 bits are hashed.
+            permissions: integer(b"P")? as u32,
+            encrypt_metadata: !matches!(get(b"EncryptMetadata"), Some(Object::Boolean(false))),
+        })
+    }
+
+    /// Returns whether the empty user password opens the document.
+    ///
+    /// Revisions 2 to 4 mix the first element of the trailer's `/ID` into
+    /// the key, so they cannot be checked without it.
+    pub(super) fn opens_with_empty_password(&self, id: Option<&[u8]>) -> bool {
+        match self.revision {
+            2..=4 => id.is_some_and(|id| self.check_rc4_revision(id)),
+            5 => self.check_sha256_revision(),
+            6 => self.check_hardened_revision(),
+            _ => false,
+        }
+    }
+
+    /// Algorithms 2, 4 and 5: the RC4 revisions.
+    fn check_rc4_revision(&self, id: &[u8]) -> bool {
+        let Some(owner) = self.owner.get(..32) else {
+            return false;
+        };
+
+        let length = if self.revision == 2 {
+            5
+        } else {
+            self.key_length.clamp(5, 16)
+        };
+
+        let mut hasher = Md5::new();
+        hasher.update(PASSWORD_PADDING);
+        hasher.update(owner);
+        hasher.update(self.permissions.to_le_bytes());
+        hasher.update(id);
+        if self.revision >= 4 && !self.encrypt_metadata {
+            hasher.update([0xff; 4]);
+        }
+        let mut key: [u8; 16] = hasher.finalize().into();
+        if self.revision >= 3 {
+            for _ in 0..50 {
+                key = Md5::digest(&key[..length]).into();
+            }
+        }
+        let key = &key[..length];
+
+        if self.revision == 2 {
+            let mut expected = PASSWORD_PADDING;
+            rc4(key, &mut expected);
+            return self.user.get(..32) == Some(&expected[..]);
+        }
+
+        let mut expected: [u8; 16] = Md5::new()
+            .chain_update(PASSWORD_PADDING)
+            .chain_update(id)
+            .finalize()
+            .into();
+        rc4(key, &mut expected);
+        for round in 1..=19 {
+            let round_key: Vec<u8> = key.iter().map(|byte| byte ^ round).collect();
+            rc4(&round_key, &mut expected);
+        }
+        self.user.get(..16) == Some(&expected[..])
+    }
+
+    /// Revision 5: a single SHA-256 over the password and validation salt.
+    fn check_sha256_revision(&self) -> bool {
+        let (Some(hash), Some(salt)) = (self.user.get(..32), self.user.get(32..40)) else {
+            return false;
+        };
+        Sha256::digest(salt).as_slice() == hash
+    }
+
+    /// Algorithm 2.B: the revision 6 hash over the password and validation
+    /// salt.
+    fn check_hardened_revision(&self) -> bool {
+        let (Some(hash), Some(salt)) = (self.user.get(..32), self.user.get(32..40)) else {
+            return false;
+        };
+
+        // With an empty password and no user key, each round's input is the
+        // previous hash repeated 64 times.
+        let mut key = Sha256::digest(salt).to_vec();
+        let mut round = 0;
+        loop {
+            let mut block = key.repeat(64);
+            aes128_cbc_encrypt(&key[..16], &key[16..32], &mut block);
+
+            // The first 16 bytes as a big-endian integer modulo 3; 256 is 1
+            // modulo 3, so summing the bytes gives the same remainder.
+            let selector: u32 = block[..16].iter().map(|&byte| u32::from(byte)).sum();
+            key = match selector % 3 {
+                0 => Sha256::digest(&block).to_vec(),
+                1 => Sha384::digest(&block).to_vec(),
+                _ => Sha512::digest(&block).to_vec(),
+            };
+
+            round += 1;
+            let last = block.last().copied().unwrap_or_default();
+            if round >= 64 && usize::from(last) <= round - 32 {
+                break;
+            }
+        }
+
+        key[..32] == *hash
+    }
+}
+
+/// Encrypts `data` in place with AES-128 in CBC mode, without padding.
+fn aes128_cbc_encrypt(key: &[u8], iv: &[u8], data: &mut [u8]) {
+    let cipher = Aes128::new_from_slice(key).expect("AES-128 key is 16 bytes");
+    let mut previous = [0u8; 16];
+    previous.copy_from_slice(iv);
+
+    for chunk in data.chunks_exact_mut(16) {
+        for (byte, prev) in chunk.iter_mut().zip(previous) {
+            *byte ^= prev;
+        }
+        let block: &mut [u8; 16] = chunk.try_into().expect("16-byte chunk");
+        cipher.encrypt_block(block.into());
+        previous = *block;
+    }
+}
+
+/// Encrypts `data` in place with RC4.
+fn rc4(key: &[u8], data: &mut [u8]) {
+    let mut state: [u8; 256] = std::array::from_fn(|i| i as u8);
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, usize::from(j));
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    for byte in data {
+        i = i.wrapping_add(1);
+        j = j.wrapping_add(state[usize::from(i)]);
+        state.swap(usize::from(i), usize::from(j));
+        let index = state[usize::from(i)].wrapping_add(state[usize::from(j)]);
+        *byte ^= state[usize::from(index)];
+    }
+}
+
+/// Returns the standard security handlers whose encryption dictionaries
+/// contain a `/Standard` name in `bytes`.
+///
+/// The dictionary is found by parsing from each preceding `<<` until one
+/// names the standard handler as its own `/Filter`; nested dictionaries such
+/// as `/CF` do not.
+pub(super) fn find_standard_security(bytes: &[u8]) -> Vec<StandardSecurity> {
+    name_positions(bytes, b"/Standard", false)
+        .filter_map(|at| {
+            (0..at)
+                .rev()
+                .filter(|&start| bytes[start..].starts_with(b"<<"))
+                .take(MAX_DICTIONARY_CANDIDATES)
+                .find_map(|start| match Parser::new(&bytes[start..]).object(0)? {
+                    Object::Dictionary(entries) => StandardSecurity::from_dictionary(&entries),
+                    _ => None,
+                })
+        })
+        .collect()
+}
+
+/// Returns the first element of every `/ID` array in `bytes`.
+pub(super) fn find_document_ids(bytes: &[u8]) -> Vec<Vec<u8>> {
+    name_positions(bytes, b"/ID", false)
+        .filter_map(|at| {
+            let mut parser = Parser::new(&bytes[at + b"/ID".len()..]);
+            match parser.object(0)? {
+                Object::Array(items) => match items.into_iter().next()? {
+                    Object::String(id) => Some(id),
+                    _ => None,
+                },
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Returns where `name` occurs in `bytes` as a complete name token.
+///
+/// The name must be followed by whitespace or a delimiter, so `/Encrypt`
+/// does not match `/EncryptMetadata`. A match at the very end only counts
+/// when `at_eof` is set; in a stream, the next chunk decides it.
+pub(super) fn name_positions<'a>(
+    bytes: &'a [u8],
+    name: &'a [u8],
+    at_eof: bool,
+) -> impl Iterator<Item = usize> + 'a {
+    bytes
+        .windows(name.len())
+        .enumerate()
+        .filter(move |(_, window)| *window == name)
+        .map(|(at, _)| at)
+        .filter(move |&at| match bytes.get(at + name.len()) {
+            Some(&next) => is_whitespace(next) || is_delimiter(next),
+            None => at_eof,
+        })
+}
+
+/// A PDF object, as far as encryption dictionaries need one.
+#[derive(Debug, Clone, PartialEq)]
+enum Object {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Real,
+    Name(Vec<u8>),
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dictionary(Vec<(Vec<u8>, Object)>),
+    Reference,
+}
+
+/// A parser over the object syntax (ISO 32000-2, 7.3).
+///
+/// Only direct objects are read; streams and indirect object headers are
+/// not, and anything malformed or truncated yields `None`.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, at: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.at).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            if is_whitespace(byte) {
+                self.at += 1;
+            } else if byte == b'%' {
+                while self
+                    .peek()
+                    .is_some_and(|byte| byte != b'\n' && byte != b'\r')
+                {
+                    self.at += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Option<Object> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        self.skip_whitespace();
+        let rest = &self.bytes[self.at..];
+        match *rest.first()? {
+            b'<' if rest.get(1) == Some(&b'<') => {
+                self.at += 2;
+                self.dictionary(depth)
+            }
+            b'<' => {
+                self.at += 1;
+                self.hex_string()
+            }
+            b'(' => {
+                self.at += 1;
+                self.literal_string()
+            }
+            b'[' => {
+                self.at += 1;
+                self.array(depth)
+            }
+            b'/' => {
+                self.at += 1;
+                Some(Object::Name(self.name()))
+            }
+            b'+' | b'-' | b'.' | b'0'..=b'9' => self.number(),
+            _ => match self.keyword() {
+                b"true" => Some(Object::Boolean(true)),
+                b"false" => Some(Object::Boolean(false)),
+                b"null" => Some(Object::Null),
+                _ => None,
+            },
+        }
+    }
+
+    fn dictionary(&mut self, depth: usize) -> Option<Object> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.bytes[self.at..].starts_with(b">>") {
+                self.at += 2;
+                return Some(Object::Dictionary(entries));
+            }
+            if self.peek()? != b'/' {
+                return None;
+            }
+            self.at += 1;
+            let key = self.name();
+            let value = self.object(depth + 1)?;
+            entries.push((key, value));
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Option<Object> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek()? == b']' {
+                self.at += 1;
+                return Some(Object::Array(items));
+            }
+            items.push(self.object(depth + 1)?);
+        }
+    }
+
+    /// Reads a name after its `/`, decoding `#xx` escapes.
+    fn name(&mut self) -> Vec<u8> {
+        let mut name = Vec::new();
+        while let Some(byte) = self.peek() {
+            if is_whitespace(byte) || is_delimiter(byte) {
+                break;
+            }
+            self.at += 1;
+            let escaped = (byte == b'#')
+                .then(|| self.bytes.get(self.at..self.at + 2))
+                .flatten()
+                .and_then(|hex| Some(hex_value(hex[0])? << 4 | hex_value(hex[1])?));
+            match escaped {
+                Some(decoded) => {
+                    name.push(decoded);
+                    self.at += 2;
+                }
+                None => name.push(byte),
+            }
+        }
+        name
+    }
+
+    /// Reads a hex string after its `<`.
+    fn hex_string(&mut self) -> Option<Object> {
+        let mut digits = Vec::new();
+        loop {
+            let byte = self.peek()?;
+            self.at += 1;
+            match byte {
+                b'>' => break,
+                _ if is_whitespace(byte) => {}
+                _ => digits.push(hex_value(byte)?),
+            }
+        }
+        // A final odd digit is followed by an implied zero.
+        let string = digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+            .collect();
+        Some(Object::String(string))
+    }
+
+    /// Reads a literal string after its `(`.
+    fn literal_string(&mut self) -> Option<Object> {
+        let mut string = Vec::new();
+        let mut open = 1usize;
+        loop {
+            let byte = self.peek()?;
+            self.at += 1;
+            match byte {
+                b'(' => {
+                    open += 1;
+                    string.push(byte);
+                }
+                b')' => {
+                    open -= 1;
+                    if open == 0 {
+                        return Some(Object::String(string));
+                    }
+                    string.push(byte);
+                }
+                b'\\' => {
+                    let escaped = self.peek()?;
+                    self.at += 1;
+                    match escaped {
+                        b'n' => string.push(b'\n'),
+                        b'r' => string.push(b'\r'),
+                        b't' => string.push(b'\t'),
+                        b'b' => string.push(b'\x08'),
+                        b'f' => string.push(b'\x0c'),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(digit @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(digit - b'0');
+                                        self.at += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            string.push(value as u8);
+                        }
+                        // A backslash at the end of a line continues it.
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.at += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => string.push(other),
+                    }
+                }
+                _ => string.push(byte),
+            }
+        }
+    }
+
+    /// Reads a number, or an `N G R` indirect reference starting with one.
+    fn number(&mut self) -> Option<Object> {
+        let token = self.keyword();
+        if token.contains(&b'.') {
+            return Some(Object::Real);
+        }
+        let value: i64 = std::str::from_utf8(token).ok()?.parse().ok()?;
+
+        let resume = self.at;
+        self.skip_whitespace();
+        let generation = self.keyword();
+        self.skip_whitespace();
+        if !generation.is_empty()
+            && generation.iter().all(u8::is_ascii_digit)
+            && self.keyword() == b"R"
+        {
+            return Some(Object::Reference);
+        }
+
+        self.at = resume;
+        Some(Object::Integer(value))
+    }
+
+    /// Reads a run of regular characters.
+    fn keyword(&mut self) -> &'a [u8] {
+        let start = self.at;
+        while self
+            .peek()
+            .is_some_and(|byte| !is_whitespace(byte) && !is_delimiter(byte))
+        {
+            self.at += 1;
+        }
+        &self.bytes[start..self.at]
+    }
+}
+
+/// Returns whether a byte is PDF whitespace.
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b'\0' | b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+/// Returns whether a byte is a PDF delimiter.
+fn is_delimiter(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+/// Returns the value of a hex digit.
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &[u8] = b"<5C2D0A1B8E7F6A5B4C3D2E1F00112233>";
+
+    fn handler(dictionary: &[u8]) -> StandardSecurity {
+        let found = find_standard_security(dictionary);
+        assert_eq!(found.len(), 1, "one standard handler");
+        found.into_iter().next().unwrap()
+    }
+
+    fn id() -> Vec<u8> {
+        let trailer = [b"trailer <</ID [".as_slice(), ID, ID, b"]>>"].concat();
+        find_document_ids(&trailer).remove(0)
+    }
+
+    #[test]
+    fn test_rc4_empty_user_password() {
+        let security = handler(
+            b"5 0 obj\n<</Filter/Standard/V 2/R 3/Length 128/P -3904\
+              /O<566FA873EE33C797CD3B904FDADF814AFA34DF9A38F6ED41B984E2C6DA2AA6F5>\
+              /U<26F95B18A8D101128C55C60B032EEC0B00000000000000000000000000000000>>>\nendobj",
+        );
+        assert!(security.opens_with_empty_password(Some(&id())));
+        assert!(!security.opens_with_empty_password(None));
+    }
+
+    #[test]
+    fn test_rc4_user_password() {
+        let security = handler(
+            b"5 0 obj\n<</Filter/Standard/V 2/R 3/Length 128/P -3904\
+              /O<0DB5855FC5326569E765906CAF64E4429A4C20D6E996FDEF963E9B5080F9E083>\
+              /U<B90E9C0695FEB8C02FF3729563A407F100000000000000000000000000000000>>>\nendobj",
+        );
+        assert!(!security.opens_with_empty_password(Some(&id())));
+    }
+
+    #[test]
+    fn test_aes256_empty_user_password() {
+        let security = handler(
+            b"<</CF<</StdCF<</AuthEvent/DocOpen/CFM/AESV3/Length 32>>>>\
+              /Filter /Standard /V 5 /R 6 /Length 256 /P 4294963392\
+              /O<00>/U<8D1EFB4F1BDBB651341704C2139DE4F6BE05D6D4609AF56916B21646ED74825C\
+              0102030405060708090A0B0C0D0E0F10>>>",
+        );
+        assert!(security.opens_with_empty_password(None));
+    }
+
+    #[test]
+    fn test_aes256_user_password() {
+        let security = handler(
+            b"<</Filter/Standard/V 5/R 6/Length 256/P -3904/O<00>\
+              /U<F73C954722FB8E39ECD42D6FBBA64C7B7C9E2066D3D250CCC990BC183B4AB5B8\
+              0102030405060708090A0B0C0D0E0F10>>>",
+        );
+        assert!(!security.opens_with_empty_password(None));
+    }
+
+    #[test]
+    fn test_ignores_other_handlers_and_references() {
+        assert!(find_standard_security(b"<</Filter/Adobe.PubSec/R 3>>").is_empty());
+        assert!(
+            find_standard_security(b"<</Filter/Standard/R 3/O 6 0 R/U 7 0 R/P -4>>").is_empty()
+        );
+        assert!(find_standard_security(b"<</Encoding/StandardEncoding>>").is_empty());
+    }
+
+    #[test]
+    fn test_parses_literal_strings() {
+        let mut parser = Parser::new(b"(a\\(b\\)\\101\\\n(c)\\n)");
+        assert_eq!(
+            parser.object(0),
+            Some(Object::String(b"a(b)A(c)\n".to_vec()))
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Preflight inspection of a stored document.
+//!
+//! A preflight reads the document's structure without running the engine:
+//! enough to tell the uploader what the pipeline will see, how it will be
+//! processed, and what would stop a run, before any of the heavy work starts.
+//! Page count and quality are estimates; PDFs that keep their page tree in
+//! compressed object streams report no page count.
+
+use nvisy_postgres::types::FileFormat;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::protection;
+
+/// Magic bytes of a PNG image.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Magic bytes of a JPEG image.
+const JPEG_SIGNATURE: &[u8] = b"\xff\xd8\xff";
+
+/// Magic bytes of a ZIP container (DOCX).
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+
+/// Magic bytes of an OLE compound file (legacy DOC).
+const OLE_SIGNATURE: &[u8] = b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1";
+
+/// How far into a PDF the header may appear.
+const PDF_HEADER_WINDOW: usize = 1024;
+
+/// Estimated quality of a document's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentQuality {
+    /// The document carries its text (born-digital PDF, office or text file).
+    Digital,
+    /// The text is only in images and has to be recognized.
+    Scanned,
+    /// Neither text nor images were found.
+    Unknown,
+}
+
+/// How the pipeline will process a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingTier {
+    /// Text is extracted directly.
+    Text,
+    /// Text is recognized from page images.
+    Ocr,
+}
+
+/// A condition that would stop or degrade a run over the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightViolation {
+    /// The file is empty.
+    EmptyFile,
+    /// The content matches no supported format.
+    UnrecognizedFormat,
+    /// The content does not match the file's extension.
+    FormatMismatch,
+    /// The document is password-protected and no password is stored.
+    PasswordRequired,
+}
+
+impl PreflightViolation {
+    /// Returns a short description of the violation.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::EmptyFile => "The file is empty.",
+            Self::UnrecognizedFormat => "The file content matches no supported format.",
+            Self::FormatMismatch => "The file content does not match its extension.",
+            Self::PasswordRequired => {
+                "The document is password-protected; store its password or supply one \
+                 when starting a run."
+            }
+        }
+    }
+}
+
+/// Outcome of a preflight inspection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightReport {
+    /// Format detected from the content.
+    pub format: Option<FileFormat>,
+    /// Number of pages, when it can be read without decompressing.
+    pub page_count: Option<u32>,
+    /// Whether the document is a password-protected PDF.
+    pub password_protected: bool,
+    /// Estimated quality of the document's text.
+    pub quality: DocumentQuality,
+    /// How the document will be processed.
+    pub tier: ProcessingTier,
+    /// Pages the run will be billed for.
+    pub billable_pages: u32,
+    /// Conditions that would stop or degrade a run.
+    pub violations: Vec<PreflightViolation>,
+}
+
+impl PreflightReport {
+    /// Inspects a document's plaintext.
+    ///
+    /// `extension` is the file's recorded extension and `has_password`
+    /// whether a password is stored for it.
+    pub fn inspect(bytes: &[u8], extension: &str, has_password: bool) -> Self {
+        let declared = FileFormat::from_extension(extension);
+        let format = detect_format(bytes, declared);
+        let password_protected = protection::is_password_protected(bytes);
+
+        let (page_count, quality) = match format {
+            Some(FileFormat::Pdf) => (count_pdf_pages(bytes), pdf_quality(bytes)),
+            Some(FileFormat::Png | FileFormat::Jpeg) => (Some(1), DocumentQuality::Scanned),
+            Some(_) => (None, DocumentQuality::Digital),
+            None => (None, DocumentQuality::Unknown),
+        };
+        let tier = match quality {
+            DocumentQuality::Scanned => ProcessingTier::Ocr,
+            DocumentQuality::Digital | DocumentQuality::Unknown => ProcessingTier::Text,
+        };
+
+        let mut violations = Vec::new();
+        if bytes.is_empty() {
+            violations.push(PreflightViolation::EmptyFile);
+        } else if format.is_none() {
+            violations.push(PreflightViolation::UnrecognizedFormat);
+        } else if format != declared {
+            violations.push(PreflightViolation::FormatMismatch);
+        }
+        if password_protected && !has_password {
+            violations.push(PreflightViolation::PasswordRequired);
+        }
+
+        Self {
+            format,
+            page_count,
+            password_protected,
+            quality,
+            tier,
+            billable_pages: page_count.unwrap_or(1).max(1),
+            violations,
+        }
+    }
+
+    /// Returns whether nothing would stop a run over the document.
+    pub fn is_ready(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Counts a stored document's pages, when its format has them and they can be
+/// read without decompressing.
+pub fn count_pages(bytes: &[u8], extension: &str) -> Option<u32> {
+    match detect_format(bytes, FileFormat::from_extension(extension)) {
+        Some(FileFormat::Pdf) => count_pdf_pages(bytes),
+        Some(FileFormat::Png | FileFormat::Jpeg) => Some(1),
+        _ => None,
+    }
+}
+
+/// Detects a document's format from its content.
+///
+/// Text formats cannot be told apart by content, so valid UTF-8 keeps the
+/// declared text format and falls back to plain text.
+fn detect_format(bytes: &[u8], declared: Option<FileFormat>) -> Option<FileFormat> {
+    let head = &bytes[..bytes.len().min(PDF_HEADER_WINDOW)];
+    if find(head, b"%PDF-").is_some() {
+        Some(FileFormat::Pdf)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        Some(FileFormat::Png)
+    } else if bytes.starts_with(JPEG_SIGNATURE) {
+        Some(FileFormat::Jpeg)
+    } else if bytes.starts_with(ZIP_SIGNATURE) || bytes.starts_with(OLE_SIGNATURE) {
+        Some(FileFormat::Doc)
+    } else if !bytes.is_empty() && std::str::from_utf8(bytes).is_ok() {
+        match declared {
+            Some(
+                format @ (FileFormat::Txt | FileFormat::Md | FileFormat::Csv | FileFormat::Json),
+            ) => Some(format),
+            _ => Some(FileFormat::Txt),
+        }
+    } else {
+        None
+    }
+}
+
+/// Counts the page objects of a PDF (`/Type /Page`, but not `/Pages`).
+fn count_pdf_pages(bytes: &[u8]) -> Option<u32> {
+    let mut pages = 0u32;
+    let mut rest = bytes;
+    while let Some(start) = find(rest, b"/Type") {
+        let after = rest[start + 5..].trim_ascii_start();
+        if let Some(tail) = after.strip_prefix(b"/Page")
+            && tail
+                .first()
+                .is_none_or(|byte| !byte.is_ascii_alphanumeric())
+        {
+            pages += 1;
+        }
+        rest = &rest[start + 5..];
+    }
+
+    (pages > 0).then_some(pages)
+}
+
+/// Estimates whether a PDF carries text or only page images.
+fn pdf_quality(bytes: &[u8]) -> DocumentQuality {
+    if find(bytes, b"/Font").is_some() {
+        DocumentQuality::Digital
+    } else if find(bytes, b"/Image").is_some() {
+        DocumentQuality::Scanned
+    } else {
+        DocumentQuality::Unknown
+    }
+}
+
+/// Returns the offset of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_fixtures::{Corpus, FixtureFormat};
+
+    use super::*;
+
+    const TEXT_PDF: &[u8] = b"%PDF-1.7\n1 0 obj <</Type /Pages /Count 2>> endobj\n\
+        2 0 obj <</Type /Page /Resources <</Font <<>>>>>> endobj\n\
+        3 0 obj <</Type/Page>> endobj\ntrailer <</Root 1 0 R>>";
+
+    #[test]
+    fn test_text_pdf() {
+        let report = PreflightReport::inspect(TEXT_PDF, "pdf", false);
+        assert_eq!(report.format, Some(FileFormat::Pdf));
+        assert_eq!(report.page_count, Some(2));
+        assert_eq!(report.quality, DocumentQuality::Digital);
+        assert_eq!(report.tier, ProcessingTier::Text);
+        assert!(report.is_ready());
+    }
+
+    #[test]
+    fn test_count_pages() {
+        assert_eq!(count_pages(TEXT_PDF, "pdf"), Some(2));
+        assert_eq!(count_pages(b"\x89PNG\r\n\x1a\n", "png"), Some(1));
+        assert_eq!(count_pages(b"plain text", "txt"), None);
+    }
+
+    #[test]
+    fn test_scanned_pdf_needs_ocr() {
+        let pdf = b"%PDF-1.4\n<</Type /Page>>\n<</Subtype /Image /Width 10>>";
+        let report = PreflightReport::inspect(pdf, "pdf", false);
+        assert_eq!(report.quality, DocumentQuality::Scanned);
+        assert_eq!(report.tier, ProcessingTier::Ocr);
+        assert_eq!(report.billable_pages, 1);
+    }
+
+    #[test]
+    fn test_protected_pdf_without_password() {
+        let pdf = b"%PDF-1.7\n<</Type /Page>>\ntrailer <</Encrypt 4 0 R>>";
+        let report = PreflightReport::inspect(pdf, "pdf", false);
+        assert!(report.password_protected);
+        assert_eq!(report.violations, [PreflightViolation::PasswordRequired]);
+        assert!(PreflightReport::inspect(pdf, "pdf", true).is_ready());
+    }
+
+    #[test]
+    fn test_format_mismatch() {
+        let report = PreflightReport::inspect(PNG_SIGNATURE, "pdf", false);
+        assert_eq!(report.format, Some(FileFormat::Png));
+        assert_eq!(report.violations, [PreflightViolation::FormatMismatch]);
+    }
+
+    #[test]
+    fn test_text_keeps_declared_format() {
+        let report = PreflightReport::inspect(b"name,email\n", "csv", false);
+        assert_eq!(report.format, Some(FileFormat::Csv));
+        assert!(report.is_ready());
+    }
+
+    #[test]
+    fn test_empty_and_binary() {
+        let empty = PreflightReport::inspect(b"", "txt", false);
+        assert_eq!(empty.violations, [PreflightViolation::EmptyFile]);
+
+        let binary = PreflightReport::inspect(b"\x00\xff\xfe", "bin", false);
+        assert_eq!(binary.violations, [PreflightViolation::UnrecognizedFormat]);
+    }
+
+    #[test]
+    fn test_standard_corpus() {
+        for fixture in Corpus::standard() {
+            let report =
+                PreflightReport::inspect(fixture.bytes(), fixture.format().extension(), false);
+            assert!(report.is_ready(), "{}", fixture.name());
+
+            let quality = if fixture.kind().is_scan() {
+                DocumentQuality::Scanned
+            } else {
+                DocumentQuality::Digital
+            };
+            assert_eq!(report.quality, quality, "{}", fixture.name());
+
+            if fixture.format() == FixtureFormat::Pdf {
+                let pages = fixture.golden().page_count() as u32;
+                assert_eq!(report.page_count, Some(pages), "{}", fixture.name());
+            }
+        }
+    }
+}
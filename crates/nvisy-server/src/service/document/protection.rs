@@ -0,0 +1,317 @@
+//! Detection of password-protected PDFs.
+//!
+//! A PDF encrypted with a user password keeps its object structure in the
+//! clear and names its security handler from the trailer's `/Encrypt` entry.
+//! [`ProtectionReader`] watches the upload for the `%PDF-` header and that
+//! key, so a protected document is flagged before anyone tries to process it.
+//!
+//! Encryption alone does not mean a password is needed: documents that only
+//! restrict permissions are encrypted with an empty user password. The
+//! reader keeps the bytes around the standard security handler's dictionary
+//! and the trailer's `/ID`, and a document the empty password opens is not
+//! flagged. Any other security handler, or a dictionary that cannot be read,
+//! is treated as protected.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use super::pdf_security;
+
+/// Header every PDF starts with.
+const PDF_HEADER: &[u8] = b"%PDF-";
+
+/// How far into the file the header may appear (readers tolerate leading
+/// garbage up to this offset).
+const PDF_HEADER_WINDOW: usize = 1024;
+
+/// Trailer key referencing the encryption dictionary.
+const ENCRYPT_KEY: &[u8] = b"/Encrypt";
+
+/// Names around which the bytes are kept for the empty-password check.
+const CAPTURE_KEYS: [&[u8]; 2] = [b"/Standard", b"/ID"];
+
+/// Bytes kept on each side of a captured name.
+///
+/// Enough for an encryption dictionary written in any order: the revision 6
+/// entries are the longest, at a few hundred bytes in hex.
+const CAPTURE_RADIUS: usize = 4096;
+
+/// Most captures kept per document.
+const MAX_CAPTURES: usize = 16;
+
+/// Bytes kept around a name that may belong to the encryption dictionary or
+/// the trailer.
+#[derive(Debug)]
+struct Capture {
+    bytes: Vec<u8>,
+    /// Bytes still to be appended from the chunks that follow.
+    remaining: usize,
+}
+
+/// Incremental scanner over a document's plaintext.
+#[derive(Debug, Default)]
+struct Scanner {
+    /// Leading bytes, kept until the header window is full.
+    head: Vec<u8>,
+    /// Trailing bytes of the previous chunks, so a key split across chunks
+    /// is still seen and a capture can start before its name.
+    tail: Vec<u8>,
+    /// Whether an `/Encrypt` key has been seen.
+    encrypted: bool,
+    /// Bytes around `/Standard` and `/ID` names.
+    captures: Vec<Capture>,
+}
+
+impl Scanner {
+    /// Feeds the next chunk of plaintext.
+    fn update(&mut self, chunk: &[u8]) {
+        if self.head.len() < PDF_HEADER_WINDOW {
+            let take = chunk.len().min(PDF_HEADER_WINDOW - self.head.len());
+            self.head.extend_from_slice(&chunk[..take]);
+        }
+
+        for capture in &mut self.captures {
+            let take = chunk.len().min(capture.remaining);
+            capture.bytes.extend_from_slice(&chunk[..take]);
+            capture.remaining -= take;
+        }
+
+        let seen = self.tail.len();
+        let mut window = std::mem::take(&mut self.tail);
+        window.extend_from_slice(chunk);
+
+        if !self.encrypted {
+            self.encrypted = contains_encrypt_key(&window, false);
+        }
+
+        // A name ending exactly where the previous window ended could not be
+        // told apart from a longer one then, so it is considered now.
+        for key in CAPTURE_KEYS {
+            for start in pdf_security::name_positions(&window, key, false) {
+                if start + key.len() < seen || self.captures.len() >= MAX_CAPTURES {
+                    continue;
+                }
+                let from = start.saturating_sub(CAPTURE_RADIUS);
+                let to = window.len().min(start + CAPTURE_RADIUS);
+                self.captures.push(Capture {
+                    bytes: window[from..to].to_vec(),
+                    remaining: start + CAPTURE_RADIUS - to,
+                });
+            }
+        }
+
+        let keep = window.len().min(CAPTURE_RADIUS);
+        self.tail = window.split_off(window.len() - keep);
+    }
+
+    /// Returns whether the scanned bytes are a password-protected PDF.
+    fn is_protected(&self) -> bool {
+        let is_pdf = self
+            .head
+            .windows(PDF_HEADER.len())
+            .any(|window| window == PDF_HEADER);
+
+        // A key ending exactly at EOF has no following byte to check.
+        let encrypted = self.encrypted || contains_encrypt_key(&self.tail, true);
+
+        is_pdf && encrypted && !self.opens_with_empty_password()
+    }
+
+    /// Returns whether a captured standard security handler accepts the
+    /// empty user password.
+    fn opens_with_empty_password(&self) -> bool {
+        let ids: Vec<_> = self
+            .captures
+            .iter()
+            .flat_map(|capture| pdf_security::find_document_ids(&capture.bytes))
+            .collect();
+
+        self.captures
+            .iter()
+            .flat_map(|capture| pdf_security::find_standard_security(&capture.bytes))
+            .any(|security| {
+                security.opens_with_empty_password(None)
+                    || ids
+                        .iter()
+                        .any(|id| security.opens_with_empty_password(Some(id)))
+            })
+    }
+}
+
+/// Returns whether `bytes` contain an `/Encrypt` name token.
+///
+/// The key must be followed by a delimiter, so `/EncryptMetadata` does not
+/// count. A match at the very end only counts when `at_eof` is set; otherwise
+/// the next chunk decides it.
+fn contains_encrypt_key(bytes: &[u8], at_eof: bool) -> bool {
+    pdf_security::name_positions(bytes, ENCRYPT_KEY, at_eof)
+        .next()
+        .is_some()
+}
+
+/// Returns whether a complete document is a password-protected PDF.
+pub fn is_password_protected(bytes: &[u8]) -> bool {
+    let mut scanner = Scanner::default();
+    scanner.update(bytes);
+    scanner.is_protected()
+}
+
+/// A shared handle to the result of a [`ProtectionReader`].
+///
+/// Read it once the reader has been drained.
+#[derive(Clone)]
+pub struct ProtectionProbe {
+    scanner: Arc<Mutex<Scanner>>,
+}
+
+impl ProtectionProbe {
+    /// Returns whether the document is a password-protected PDF.
+    pub fn is_password_protected(&self) -> bool {
+        self.scanner
+            .lock()
+            .expect("protection probe lock")
+            .is_protected()
+    }
+}
+
+pin_project! {
+    /// An [`AsyncRead`] wrapper that detects password-protected PDFs in the
+    /// plaintext flowing through it.
+    ///
+    /// Like [`HashingReader`](crate::service::HashingReader), it must sit
+    /// ahead of the encryptor.
+    pub struct ProtectionReader<R> {
+        #[pin]
+        inner: R,
+        probe: ProtectionProbe,
+    }
+}
+
+impl<R> ProtectionReader<R> {
+    /// Wraps a reader, returning it alongside a handle to its result.
+    pub fn new(inner: R) -> (Self, ProtectionProbe) {
+        let probe = ProtectionProbe {
+            scanner: Arc::default(),
+        };
+        let reader = Self {
+            inner,
+            probe: probe.clone(),
+        };
+        (reader, probe)
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for ProtectionReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        let poll = this.inner.poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = &poll {
+            let new = &buf.filled()[before..];
+            if !new.is_empty() {
+                this.probe
+                    .scanner
+                    .lock()
+                    .expect("protection probe lock")
+                    .update(new);
+            }
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(chunks: &[&[u8]]) -> bool {
+        let mut scanner = Scanner::default();
+        for chunk in chunks {
+            scanner.update(chunk);
+        }
+        scanner.is_protected()
+    }
+
+    #[test]
+    fn test_detects_encrypt_in_trailer() {
+        let pdf: &[u8] =
+            b"%PDF-1.7\n1 0 obj\n<<>>\nendobj\ntrailer\n<</Root 1 0 R/Encrypt 2 0 R>>\n%%EOF";
+        assert!(scan(&[pdf]));
+    }
+
+    #[test]
+    fn test_unprotected_pdf() {
+        let pdf: &[u8] =
+            b"%PDF-1.7\n1 0 obj\n<</EncryptMetadata false>>\nendobj\ntrailer\n<</Root 1 0 R>>";
+        assert!(!scan(&[pdf]));
+    }
+
+    #[test]
+    fn test_key_split_across_chunks() {
+        assert!(scan(&[b"%PDF-1.4\ntrailer <</Enc", b"rypt", b" 5 0 R>>"]));
+        assert!(!scan(&[
+            b"%PDF-1.4\ntrailer <</Encrypt",
+            b"Metadata true>>"
+        ]));
+    }
+
+    #[test]
+    fn test_key_at_end_of_file() {
+        assert!(scan(&[b"%PDF-1.4\n/Encrypt"]));
+    }
+
+    /// A revision 3 document whose user password is `user`.
+    fn rc4_pdf(user: &[u8]) -> Vec<u8> {
+        [
+            b"%PDF-1.6\n1 0 obj\n<</Type/Catalog>>\nendobj\n5 0 obj\n\
+              <</Filter/Standard/V 2/R 3/Length 128/P -3904\n/O<"
+                .as_slice(),
+            if user.is_empty() {
+                b"566FA873EE33C797CD3B904FDADF814AFA34DF9A38F6ED41B984E2C6DA2AA6F5".as_slice()
+            } else {
+                b"0DB5855FC5326569E765906CAF64E4429A4C20D6E996FDEF963E9B5080F9E083"
+            },
+            b">\n/U<",
+            if user.is_empty() {
+                b"26F95B18A8D101128C55C60B032EEC0B00000000000000000000000000000000".as_slice()
+            } else {
+                b"B90E9C0695FEB8C02FF3729563A407F100000000000000000000000000000000"
+            },
+            b">>>\nendobj\ntrailer\n<</Root 1 0 R/Encrypt 5 0 R\
+              /ID[<5C2D0A1B8E7F6A5B4C3D2E1F00112233><5C2D0A1B8E7F6A5B4C3D2E1F00112233>]>>\n%%EOF",
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_permissions_only_pdf_is_not_protected() {
+        let pdf = rc4_pdf(b"");
+        assert!(!scan(&[&pdf]));
+
+        let chunks: Vec<&[u8]> = pdf.chunks(7).collect();
+        assert!(!scan(&chunks));
+    }
+
+    #[test]
+    fn test_user_password_pdf_is_protected() {
+        let pdf = rc4_pdf(b"secret");
+        assert!(scan(&[&pdf]));
+
+        let chunks: Vec<&[u8]> = pdf.chunks(7).collect();
+        assert!(scan(&chunks));
+    }
+
+    #[test]
+    fn test_ignores_non_pdf() {
+        assert!(!scan(&[b"plain text mentioning /Encrypt here"]));
+    }
+}
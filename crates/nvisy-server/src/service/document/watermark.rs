@@ -0,0 +1,583 @@
+//! Recipient watermarks and page selection for shared documents.
+//!
+//! A share link stamps the recipient it was issued to into every copy it
+//! serves, so a leaked copy can be traced back to its link. Text documents
+//! get a visible line at the top of every page. PDFs get the same line drawn
+//! at the top of every page by an incremental update, which leaves the
+//! original bytes, and any encryption and permissions, in place. Images carry
+//! the mark in their metadata (a PNG `iTXt` chunk or a JPEG comment segment),
+//! since the server has no renderer to draw on them. Word documents cannot be
+//! rewritten here, so links over them cannot be watermarked; neither PDFs nor
+//! Word documents can be limited to pages yet.
+//!
+//! Plain text, Markdown and CSV are paginated the way printed text is: pages
+//! are separated by form feeds. An image is a single page.
+
+use jiff::Timestamp;
+use lopdf::{Dictionary, IncrementalDocument, Object, ObjectId, Stream, dictionary};
+use nvisy_postgres::types::FileFormat;
+use uuid::Uuid;
+
+/// Separator between the pages of a text document.
+const PAGE_BREAK: u8 = b'\x0c';
+
+/// Signature every PNG starts with.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Length of a PNG's signature and `IHDR` chunk, after which the mark goes.
+const PNG_HEADER_LEN: usize = PNG_SIGNATURE.len() + 4 + 4 + 13 + 4;
+
+/// Keyword of the PNG text chunk carrying the mark.
+const PNG_KEYWORD: &[u8] = b"Comment";
+
+/// Marker every JPEG starts with (start of image).
+const JPEG_SOI: &[u8] = &[0xff, 0xd8];
+
+/// Marker of a JPEG comment segment.
+const JPEG_COM: &[u8] = &[0xff, 0xfe];
+
+/// Resource name of the mark's font on PDF pages, chosen not to clash with
+/// the page's own fonts.
+const PDF_FONT_NAME: &str = "NvisyWatermark";
+
+/// Size of the mark's text on PDF pages, in points.
+const PDF_FONT_SIZE: f32 = 8.0;
+
+/// Distance of the mark from the top left corner of PDF pages, in points.
+const PDF_MARGIN: f32 = 12.0;
+
+/// Media box of a PDF page that does not declare one (US Letter).
+const PDF_DEFAULT_MEDIA_BOX: [f32; 4] = [0.0, 0.0, 612.0, 792.0];
+
+/// Depth past which a PDF page tree is assumed to loop.
+const PDF_MAX_TREE_DEPTH: usize = 32;
+
+/// How a watermark is applied to a format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkMode {
+    /// A line of text at the top of every page.
+    Visible,
+    /// A metadata entry that viewers do not display.
+    Embedded,
+}
+
+impl WatermarkMode {
+    /// Returns how documents of `format` are watermarked, or `None` if they
+    /// cannot be.
+    pub fn for_format(format: FileFormat) -> Option<Self> {
+        match format {
+            FileFormat::Txt | FileFormat::Md | FileFormat::Csv | FileFormat::Pdf => {
+                Some(Self::Visible)
+            }
+            FileFormat::Png | FileFormat::Jpeg => Some(Self::Embedded),
+            FileFormat::Doc | FileFormat::Json => None,
+        }
+    }
+
+    /// Returns the mode's name, as reported to the recipient.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Visible => "visible",
+            Self::Embedded => "embedded",
+        }
+    }
+}
+
+/// Why a document could not be watermarked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum WatermarkError {
+    /// The format cannot be watermarked.
+    #[error("{} documents cannot be watermarked", .0.extensions()[0])]
+    Unsupported(FileFormat),
+    /// The content is not a valid document of its format.
+    #[error("content is not a valid {} document", .0.extensions()[0])]
+    Malformed(FileFormat),
+    /// The document needs a password to open, so it cannot be rewritten.
+    #[error("password-protected documents cannot be watermarked")]
+    Protected,
+}
+
+/// The mark identifying a copy served through a share link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watermark {
+    text: String,
+}
+
+impl Watermark {
+    /// Creates the mark for a copy served to `recipient` through link
+    /// `share_id` at `served_at`.
+    ///
+    /// The recipient is chosen by whoever created the link, so control
+    /// characters are dropped to keep it on one line.
+    pub fn new(recipient: &str, share_id: Uuid, served_at: Timestamp) -> Self {
+        let recipient: String = recipient.chars().filter(|c| !c.is_control()).collect();
+        let link = share_id.simple().to_string();
+        Self {
+            text: format!(
+                "Shared with {recipient} via link {} at {}",
+                &link[..8],
+                served_at.round(jiff::Unit::Second).unwrap_or(served_at),
+            ),
+        }
+    }
+
+    /// Returns the text of the mark.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns `content`, a document of `format`, with the mark applied.
+    pub fn apply(&self, format: FileFormat, content: &[u8]) -> Result<Vec<u8>, WatermarkError> {
+        match format {
+            FileFormat::Txt | FileFormat::Md | FileFormat::Csv => Ok(self.apply_text(content)),
+            FileFormat::Png => self.apply_png(content),
+            FileFormat::Jpeg => self.apply_jpeg(content),
+            FileFormat::Pdf => self.apply_pdf(content),
+            FileFormat::Doc | FileFormat::Json => Err(WatermarkError::Unsupported(format)),
+        }
+    }
+
+    /// Prefixes every page of a text document with the mark.
+    fn apply_text(&self, content: &[u8]) -> Vec<u8> {
+        let pages: Vec<&[u8]> = content.split(|byte| *byte == PAGE_BREAK).collect();
+        let mut marked = Vec::with_capacity(content.len() + pages.len() * (self.text.len() + 2));
+        for (index, page) in pages.into_iter().enumerate() {
+            if index > 0 {
+                marked.push(PAGE_BREAK);
+            }
+            marked.extend_from_slice(self.text.as_bytes());
+            marked.extend_from_slice(b"\n\n");
+            marked.extend_from_slice(page);
+        }
+        marked
+    }
+
+    /// Inserts an `iTXt` chunk with the mark right after the `IHDR` chunk.
+    fn apply_png(&self, content: &[u8]) -> Result<Vec<u8>, WatermarkError> {
+        let is_png = content.starts_with(PNG_SIGNATURE)
+            && content.len() >= PNG_HEADER_LEN
+            && &content[PNG_SIGNATURE.len() + 4..PNG_SIGNATURE.len() + 8] == b"IHDR";
+        if !is_png {
+            return Err(WatermarkError::Malformed(FileFormat::Png));
+        }
+
+        // Keyword, null separator, no compression, no language tag and no
+        // translated keyword, then the UTF-8 text.
+        let mut data = Vec::with_capacity(PNG_KEYWORD.len() + 5 + self.text.len());
+        data.extend_from_slice(PNG_KEYWORD);
+        data.extend_from_slice(&[0, 0, 0, 0, 0]);
+        data.extend_from_slice(self.text.as_bytes());
+
+        let mut chunk = Vec::with_capacity(data.len() + 12);
+        chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        chunk.extend_from_slice(b"iTXt");
+        chunk.extend_from_slice(&data);
+        chunk.extend_from_slice(&crc32(&chunk[4..]).to_be_bytes());
+
+        let mut marked = Vec::with_capacity(content.len() + chunk.len());
+        marked.extend_from_slice(&content[..PNG_HEADER_LEN]);
+        marked.extend_from_slice(&chunk);
+        marked.extend_from_slice(&content[PNG_HEADER_LEN..]);
+        Ok(marked)
+    }
+
+    /// Inserts a comment segment with the mark right after the start marker.
+    fn apply_jpeg(&self, content: &[u8]) -> Result<Vec<u8>, WatermarkError> {
+        if !content.starts_with(JPEG_SOI) {
+            return Err(WatermarkError::Malformed(FileFormat::Jpeg));
+        }
+
+        // The segment length counts its own two bytes and cannot exceed u16.
+        let text = &self.text.as_bytes()[..self.text.len().min(u16::MAX as usize - 2)];
+        let length = (text.len() + 2) as u16;
+
+        let mut marked = Vec::with_capacity(content.len() + text.len() + 4);
+        marked.extend_from_slice(JPEG_SOI);
+        marked.extend_from_slice(JPEG_COM);
+        marked.extend_from_slice(&length.to_be_bytes());
+        marked.extend_from_slice(text);
+        marked.extend_from_slice(&content[JPEG_SOI.len()..]);
+        Ok(marked)
+    }
+
+    /// Appends an incremental update that draws the mark at the top of every
+    /// page.
+    ///
+    /// The original bytes are kept as they are, so an encrypted document
+    /// stays encrypted, with its permissions, and the update is encrypted
+    /// like the rest of it. Documents that need a password to open cannot be
+    /// rewritten.
+    fn apply_pdf(&self, content: &[u8]) -> Result<Vec<u8>, WatermarkError> {
+        let malformed = || WatermarkError::Malformed(FileFormat::Pdf);
+        let mut document: IncrementalDocument = content.try_into().map_err(|_| malformed())?;
+
+        // The loader tries the empty password; a document it could not
+        // decrypt keeps its encryption dictionary.
+        if document.get_prev_documents().is_encrypted() {
+            return Err(WatermarkError::Protected);
+        }
+
+        let pages = document.get_prev_documents().get_pages();
+        if pages.is_empty() {
+            return Err(malformed());
+        }
+
+        let font_id = document.new_document.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        // Isolates the page's graphics state from the mark.
+        let save_id = document
+            .new_document
+            .add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+
+        for page_id in pages.into_values() {
+            self.mark_pdf_page(&mut document, page_id, font_id, save_id)
+                .map_err(|_| malformed())?;
+        }
+
+        let mut marked = Vec::with_capacity(content.len() + 1024);
+        document.save_to(&mut marked).map_err(|_| malformed())?;
+        Ok(marked)
+    }
+
+    /// Adds the mark's font and content stream to one page.
+    fn mark_pdf_page(
+        &self,
+        document: &mut IncrementalDocument,
+        page_id: ObjectId,
+        font_id: ObjectId,
+        save_id: ObjectId,
+    ) -> lopdf::Result<()> {
+        let prev = document.get_prev_documents();
+        let page = prev.get_dictionary(page_id)?;
+
+        // Resources and the media box may be inherited from the page tree;
+        // the page gets its own copy so shared dictionaries stay untouched.
+        let mut resources = match pdf_page_attribute(prev, page, b"Resources") {
+            Some(resources) => prev.dereference(resources)?.1.as_dict()?.clone(),
+            None => Dictionary::new(),
+        };
+        let mut fonts = match resources.get(b"Font") {
+            Ok(fonts) => prev.dereference(fonts)?.1.as_dict()?.clone(),
+            Err(_) => Dictionary::new(),
+        };
+        fonts.set(PDF_FONT_NAME, font_id);
+        resources.set("Font", fonts);
+
+        let media_box = pdf_page_attribute(prev, page, b"MediaBox")
+            .and_then(|media_box| prev.dereference(media_box).ok())
+            .and_then(|(_, media_box)| media_box.as_array().ok())
+            .and_then(|corners| {
+                let corners: Vec<f32> = corners
+                    .iter()
+                    .filter_map(|corner| corner.as_float().ok())
+                    .collect();
+                <[f32; 4]>::try_from(corners).ok()
+            })
+            .unwrap_or(PDF_DEFAULT_MEDIA_BOX);
+        let left = media_box[0].min(media_box[2]) + PDF_MARGIN;
+        let top = media_box[1].max(media_box[3]) - PDF_MARGIN - PDF_FONT_SIZE;
+
+        let mut contents = vec![Object::Reference(save_id)];
+        match page
+            .get(b"Contents")
+            .map(|streams| prev.dereference(streams))
+        {
+            Ok(Ok((_, Object::Array(streams)))) => contents.extend(streams.iter().cloned()),
+            Ok(Ok((Some(stream_id), _))) => contents.push(Object::Reference(stream_id)),
+            _ => {}
+        }
+
+        // Restores the page's graphics state, then draws the mark in grey.
+        let mark = format!(
+            "Q\nq\nBT\n/{PDF_FONT_NAME} {PDF_FONT_SIZE} Tf\n0.5 g\n\
+             {left} {top} Td\n({}) Tj\nET\nQ\n",
+            pdf_string(&self.text),
+        );
+        let mark_id = document
+            .new_document
+            .add_object(Stream::new(Dictionary::new(), mark.into_bytes()));
+        contents.push(Object::Reference(mark_id));
+
+        document.opt_clone_object_to_new_document(page_id)?;
+        let page = document.new_document.get_dictionary_mut(page_id)?;
+        page.set("Resources", resources);
+        page.set("Contents", contents);
+        Ok(())
+    }
+}
+
+/// Returns whether documents of `format` can be limited to some pages.
+pub fn supports_page_selection(format: FileFormat) -> bool {
+    matches!(
+        format,
+        FileFormat::Txt | FileFormat::Md | FileFormat::Csv | FileFormat::Png | FileFormat::Jpeg
+    )
+}
+
+/// Returns the given one-based pages of `content`, a document of `format`,
+/// in document order.
+///
+/// Pages past the end of the document are skipped, so the result may hold
+/// no pages at all. Returns `None` if the format cannot be paginated.
+pub fn select_pages(format: FileFormat, content: &[u8], pages: &[u32]) -> Option<Vec<u8>> {
+    match format {
+        FileFormat::Txt | FileFormat::Md | FileFormat::Csv => {
+            let selected: Vec<&[u8]> = content
+                .split(|byte| *byte == PAGE_BREAK)
+                .enumerate()
+                .filter(|(index, _)| pages.contains(&(*index as u32 + 1)))
+                .map(|(_, page)| page)
+                .collect();
+            Some(selected.join(&PAGE_BREAK))
+        }
+        FileFormat::Png | FileFormat::Jpeg => Some(if pages.contains(&1) {
+            content.to_vec()
+        } else {
+            Vec::new()
+        }),
+        FileFormat::Pdf | FileFormat::Doc | FileFormat::Json => None,
+    }
+}
+
+/// Returns an attribute of a PDF page, inherited from the page tree if the
+/// page does not set it.
+fn pdf_page_attribute<'a>(
+    document: &'a lopdf::Document,
+    page: &'a Dictionary,
+    key: &[u8],
+) -> Option<&'a Object> {
+    let mut node = page;
+    for _ in 0..PDF_MAX_TREE_DEPTH {
+        if let Ok(value) = node.get(key) {
+            return Some(value);
+        }
+        let parent = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+        node = document.get_dictionary(parent).ok()?;
+    }
+    None
+}
+
+/// Encodes `text` as the body of a PDF literal string in WinAnsiEncoding.
+///
+/// Characters outside Latin-1 are replaced with `?`.
+fn pdf_string(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(c);
+            }
+            ' '..='~' => encoded.push(c),
+            '\u{a0}'..='\u{ff}' => encoded.push_str(&format!("\\{:03o}", c as u32)),
+            _ => encoded.push('?'),
+        }
+    }
+    encoded
+}
+
+/// CRC-32 (ISO 3309) of `bytes`, as used by PNG chunks.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watermark() -> Watermark {
+        Watermark::new(
+            "auditor@example.com\n",
+            Uuid::nil(),
+            "2026-10-16T12:00:00.4Z".parse().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_watermark_text() {
+        assert_eq!(
+            watermark().text(),
+            "Shared with auditor@example.com via link 00000000 at 2026-10-16T12:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_text_watermark_marks_every_page() {
+        let marked = watermark()
+            .apply(FileFormat::Txt, b"first\x0csecond")
+            .unwrap();
+        let text = String::from_utf8(marked).unwrap();
+        let pages: Vec<&str> = text.split('\x0c').collect();
+
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|page| page.starts_with("Shared with")));
+        assert!(pages[1].ends_with("\n\nsecond"));
+    }
+
+    #[test]
+    fn test_png_watermark_adds_valid_chunk() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend_from_slice(&13u32.to_be_bytes());
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&[0; 13]);
+        png.extend_from_slice(&[0; 4]);
+        png.extend_from_slice(b"rest");
+
+        let marked = watermark().apply(FileFormat::Png, &png).unwrap();
+        let chunk = &marked[PNG_HEADER_LEN..marked.len() - 4];
+        let length = u32::from_be_bytes(chunk[..4].try_into().unwrap()) as usize;
+        let crc = u32::from_be_bytes(chunk[8 + length..].try_into().unwrap());
+
+        assert_eq!(&chunk[4..8], b"iTXt");
+        assert_eq!(crc, crc32(&chunk[4..8 + length]));
+        assert!(marked.ends_with(b"rest"));
+        assert_eq!(
+            watermark().apply(FileFormat::Png, b"not a png"),
+            Err(WatermarkError::Malformed(FileFormat::Png))
+        );
+    }
+
+    #[test]
+    fn test_jpeg_watermark_adds_comment() {
+        let marked = watermark()
+            .apply(FileFormat::Jpeg, &[0xff, 0xd8, 0xff, 0xd9])
+            .unwrap();
+        assert_eq!(&marked[..4], &[0xff, 0xd8, 0xff, 0xfe]);
+        assert!(marked.ends_with(&[0xff, 0xd9]));
+    }
+
+    fn pdf(user_password: Option<&str>) -> Vec<u8> {
+        use lopdf::encryption::{EncryptionState, EncryptionVersion, Permissions};
+
+        let mut document = lopdf::Document::with_version("1.7");
+        let pages_id = document.new_object_id();
+        let font_id = document.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let content_id = document.add_object(Stream::new(
+            Dictionary::new(),
+            b"BT /F1 12 Tf 72 700 Td (Hello) Tj ET".to_vec(),
+        ));
+        let kids: Vec<Object> = (0..2)
+            .map(|_| {
+                let page = dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                };
+                document.add_object(page).into()
+            })
+            .collect();
+        // Resources and the media box are inherited from the page tree.
+        let pages = dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => 2,
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+            "MediaBox" => vec![0.into(), 0.into(), 300.into(), 400.into()],
+        };
+        document.objects.insert(pages_id, pages.into());
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        document.trailer.set("Root", catalog_id);
+        let id = Object::string_literal(b"0123456789abcdef".to_vec());
+        document.trailer.set("ID", vec![id.clone(), id]);
+
+        if let Some(user_password) = user_password {
+            let state = EncryptionState::try_from(EncryptionVersion::V2 {
+                document: &document,
+                owner_password: "owner",
+                user_password,
+                key_length: 128,
+                permissions: Permissions::PRINTABLE,
+            })
+            .unwrap();
+            document.encrypt(&state).unwrap();
+        }
+
+        let mut bytes = Vec::new();
+        document.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_pdf_watermark_marks_every_page() {
+        let original = pdf(None);
+        let marked = watermark().apply(FileFormat::Pdf, &original).unwrap();
+        assert!(marked.starts_with(&original));
+
+        let document = lopdf::Document::load_mem(&marked).unwrap();
+        let pages = document.get_pages();
+        assert_eq!(pages.len(), 2);
+        for page_id in pages.into_values() {
+            let content = String::from_utf8(document.get_page_content(page_id)).unwrap();
+            assert!(content.contains("(Hello)"));
+            assert!(content.contains("12 380 Td\n(Shared with auditor@example.com via link"));
+            let fonts = document.get_page_fonts(page_id).unwrap();
+            assert!(fonts.contains_key(b"F1".as_slice()));
+            assert!(fonts.contains_key(PDF_FONT_NAME.as_bytes()));
+        }
+
+        assert_eq!(
+            watermark().apply(FileFormat::Pdf, b"%PDF-1.7 not a document"),
+            Err(WatermarkError::Malformed(FileFormat::Pdf))
+        );
+    }
+
+    #[test]
+    fn test_pdf_watermark_keeps_encryption() {
+        // Restricts permissions only: opens without a password.
+        let original = pdf(Some(""));
+        let marked = watermark().apply(FileFormat::Pdf, &original).unwrap();
+        let update = String::from_utf8_lossy(&marked[original.len()..]);
+        assert!(update.contains("/Encrypt"));
+
+        let document = lopdf::Document::load_mem(&marked).unwrap();
+        let page_id = document.page_iter().next().unwrap();
+        let content = String::from_utf8(document.get_page_content(page_id)).unwrap();
+        assert!(content.contains("(Shared with auditor@example.com"));
+
+        assert_eq!(
+            watermark().apply(FileFormat::Pdf, &pdf(Some("secret"))),
+            Err(WatermarkError::Protected)
+        );
+    }
+
+    #[test]
+    fn test_pdf_string() {
+        assert_eq!(pdf_string("a (b) \\ é 名"), "a \\(b\\) \\\\ \\351 ?");
+    }
+
+    #[test]
+    fn test_select_pages() {
+        let content = b"one\x0ctwo\x0cthree";
+        assert_eq!(
+            select_pages(FileFormat::Txt, content, &[3, 1, 9]).unwrap(),
+            b"one\x0cthree"
+        );
+        assert_eq!(select_pages(FileFormat::Png, b"image", &[2]).unwrap(), b"");
+        assert_eq!(select_pages(FileFormat::Pdf, b"%PDF-", &[1]), None);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+}
@@ -6,6 +6,7 @@
 //! analyze / anonymize against it.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use derive_more::Deref;
 use nvisy_engine::Engine;
@@ -13,6 +14,7 @@ use nvisy_engine::llm::LlmConfig;
 use nvisy_engine::ner::NerConfig;
 use serde::{Deserialize, Serialize};
 
+use crate::service::SecretsService;
 use crate::{Error, Result};
 
 /// Deployment configuration for the redaction engine.
@@ -46,34 +48,78 @@ struct RecognizerLineups {
 #[derive(Clone, Deref)]
 #[must_use = "the engine does nothing unless you analyze or anonymize with it"]
 pub struct EngineService {
+    #[deref]
     engine: Engine,
+    /// The lineups the engine was built with, serialized; they name the
+    /// models in use, so a change to them can change an analysis.
+    lineups: Arc<[u8]>,
 }
 
 impl EngineService {
     /// Builds the engine from the deployment configuration.
     ///
     /// Loads the recognizer lineups from the configured file when present;
-    /// otherwise starts with empty NER/LLM lineups.
-    pub async fn from_config(config: EngineConfig) -> Result<Self> {
-        let lineups = match config.config_path {
-            Some(path) => load_lineups(&path).await?,
-            None => RecognizerLineups::default(),
+    /// otherwise starts with empty NER/LLM lineups. Credentials in the file
+    /// may be `secret://` references, resolved through `secrets`.
+    pub async fn from_config(config: EngineConfig, secrets: &SecretsService) -> Result<Self> {
+        let (lineups, serialized) = match config.config_path {
+            Some(path) => load_lineups(&path, secrets).await?,
+            None => {
+                let lineups = RecognizerLineups::default();
+                let serialized = serialize_lineups(&lineups)?;
+                (lineups, serialized)
+            }
         };
         let engine = Engine::new().with_ner(lineups.ner).with_llm(lineups.llm);
-        Ok(Self { engine })
+        Ok(Self {
+            engine,
+            lineups: serialized.into(),
+        })
     }
 
     /// Borrows the underlying [`Engine`].
     pub fn engine(&self) -> &Engine {
         &self.engine
     }
+
+    /// Returns the serialized recognizer lineups the engine was built with.
+    pub fn lineups(&self) -> &[u8] {
+        &self.lineups
+    }
 }
 
 /// Reads and parses the recognizer lineups from a JSON config file.
-async fn load_lineups(path: &PathBuf) -> Result<RecognizerLineups> {
+///
+/// Also returns the lineups serialized before their secret references are
+/// resolved, so credentials never reach the serialized form and rotating
+/// one does not change it.
+async fn load_lineups(
+    path: &PathBuf,
+    secrets: &SecretsService,
+) -> Result<(RecognizerLineups, Vec<u8>)> {
     let bytes = tokio::fs::read(path).await.map_err(|e| {
         Error::internal("engine", "Failed to read engine config file").with_source(e)
     })?;
-    serde_json::from_slice(&bytes)
+    let mut document: serde_json::Value = serde_json::from_slice(&bytes).map_err(|e| {
+        Error::internal("engine", "Failed to parse engine config file").with_source(e)
+    })?;
+
+    let unresolved = parse_lineups(document.clone())?;
+    let serialized = serialize_lineups(&unresolved)?;
+
+    secrets
+        .resolve_json(&mut document)
+        .await
+        .map_err(|e| Error::config("Failed to resolve engine config secrets").with_source(e))?;
+    Ok((parse_lineups(document)?, serialized))
+}
+
+fn parse_lineups(document: serde_json::Value) -> Result<RecognizerLineups> {
+    serde_json::from_value(document)
         .map_err(|e| Error::internal("engine", "Failed to parse engine config file").with_source(e))
 }
+
+fn serialize_lineups(lineups: &RecognizerLineups) -> Result<Vec<u8>> {
+    serde_json::to_vec(lineups)
+        .map_err(|e| Error::internal("engine", "Failed to serialize engine config").with_source(e))
+}
@@ -0,0 +1,110 @@
+//! Background collection of abandoned stored objects.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::{GarbageCollectionReport, GarbageCollectionService};
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for the garbage collection worker.
+const TRACING_TARGET: &str = "nvisy_server::worker::garbage";
+
+/// Maximum number of expired registrations collected per batch.
+const BATCH_SIZE: i64 = 100;
+
+/// Maximum number of unreferenced objects swept per pass.
+const SWEEP_LIMIT: usize = 1000;
+
+/// Periodically removes abandoned objects and reports the storage reclaimed.
+pub struct GarbageCollector {
+    garbage: GarbageCollectionService,
+    interval: Duration,
+}
+
+impl GarbageCollector {
+    /// Create a new collector using the service's configured interval.
+    pub fn new(garbage: GarbageCollectionService) -> Self {
+        let interval = garbage.config().interval;
+        Self { garbage, interval }
+    }
+
+    /// Run collection passes until cancelled.
+    ///
+    /// Every server instance may run the collector: deleting an object that
+    /// another instance already removed is a no-op. `heartbeat` is beaten
+    /// after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            "Starting garbage collection"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Garbage collection shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.collect(&cancel).await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Garbage collection stopped");
+        Ok(())
+    }
+
+    /// Collects expired registrations in batches until a batch removes
+    /// nothing, then sweeps unreferenced objects.
+    async fn collect(&self, cancel: &CancellationToken) {
+        let mut total = GarbageCollectionReport::default();
+        while !cancel.is_cancelled() {
+            match self.garbage.collect_expired(BATCH_SIZE).await {
+                Ok(batch) => {
+                    total.add(batch);
+                    if batch.expired == 0 {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Failed to collect expired temporary objects"
+                    );
+                    break;
+                }
+            }
+        }
+
+        if !cancel.is_cancelled() {
+            match self.garbage.sweep_orphans(SWEEP_LIMIT).await {
+                Ok(swept) => total.add(swept),
+                Err(err) => tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    "Failed to sweep unreferenced objects"
+                ),
+            }
+        }
+
+        if total.total() > 0 {
+            tracing::info!(
+                target: TRACING_TARGET,
+                expired = total.expired,
+                orphans = total.orphans,
+                reclaimed_bytes = total.reclaimed_bytes,
+                "Abandoned objects collected"
+            );
+        }
+    }
+}
@@ -0,0 +1,450 @@
+//! Garbage collection of abandoned stored objects.
+//!
+//! An object written before any row refers to it, such as the content of an
+//! upload in progress, is registered with an owner and an expiry before it
+//! is written and released once its row exists. An upload that is aborted
+//! or fails half-way leaves its registration behind; once it expires,
+//! [`GarbageCollector`] deletes the object and the registration.
+//!
+//! Objects that were never registered can be abandoned too, for instance a
+//! run's analysis stored just before the run failed. The collector also
+//! sweeps the object stores of every region and removes objects that no row
+//! refers to and no live registration covers, once they are older than a
+//! grace period. Every pass reports the bytes it reclaimed.
+
+mod collect;
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use jiff::Timestamp;
+use nvisy_nats::jetstream::object_store::ObjectInfo;
+use nvisy_nats::object::{
+    AnalysesBucket, FileKey, FilesBucket, IntermediateKey, IntermediatesBucket, ObjectBucket,
+    ObjectKey, ObjectStore,
+};
+use nvisy_postgres::model::{NewWorkspaceTemporaryObject, WorkspaceTemporaryObject};
+use nvisy_postgres::query::{AdminScope, TenantScope, WorkspaceTemporaryObjectRepository};
+use nvisy_postgres::types::DataRegion;
+use nvisy_postgres::{PgClient, PgConn, PgConnection, PgResult};
+use uuid::Uuid;
+
+pub use self::collect::GarbageCollector;
+use crate::handler::{ErrorKind, Result};
+use crate::service::{RegionBackends, ResidencyService};
+
+/// Tracing target for garbage collection.
+const TRACING_TARGET: &str = "nvisy_server::service::garbage";
+
+/// Default interval between collection passes.
+pub const DEFAULT_GC_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Default time an upload may take before its object is abandoned.
+pub const DEFAULT_UPLOAD_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Default age an unreferenced object must reach before it is swept.
+pub const DEFAULT_ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of object keys looked up per reference query.
+const LOOKUP_CHUNK: usize = 500;
+
+/// Garbage collection configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct GarbageCollectionConfig {
+    /// How often abandoned objects are collected.
+    pub interval: Duration,
+    /// How long an upload may take before its object is abandoned.
+    pub upload_ttl: Duration,
+    /// How old an unreferenced object must be before it is swept.
+    ///
+    /// Covers objects written just before the row referring to them, so it
+    /// must be longer than any such write takes.
+    pub orphan_grace_period: Duration,
+}
+
+impl Default for GarbageCollectionConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_GC_INTERVAL,
+            upload_ttl: DEFAULT_UPLOAD_TTL,
+            orphan_grace_period: DEFAULT_ORPHAN_GRACE_PERIOD,
+        }
+    }
+}
+
+/// Outcome of a collection pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GarbageCollectionReport {
+    /// Expired registrations removed, with their objects.
+    pub expired: u64,
+    /// Unreferenced objects swept.
+    pub orphans: u64,
+    /// Bytes of object storage reclaimed.
+    pub reclaimed_bytes: u64,
+}
+
+impl GarbageCollectionReport {
+    /// Returns the total number of objects removed.
+    pub fn total(&self) -> u64 {
+        self.expired + self.orphans
+    }
+
+    fn add(&mut self, other: Self) {
+        self.expired += other.expired;
+        self.orphans += other.orphans;
+        self.reclaimed_bytes += other.reclaimed_bytes;
+    }
+}
+
+/// The rows that may refer to a bucket's objects.
+#[derive(Debug, Clone, Copy)]
+enum Referrer {
+    /// Workspace files, by storage path.
+    Files,
+    /// Pipeline runs, by analysis key.
+    Runs,
+}
+
+impl Referrer {
+    /// Returns the referrer of a bucket's objects, if the bucket is collected.
+    fn of_bucket(bucket: &str) -> Option<Self> {
+        match bucket {
+            FilesBucket::NAME => Some(Self::Files),
+            AnalysesBucket::NAME | IntermediatesBucket::NAME => Some(Self::Runs),
+            _ => None,
+        }
+    }
+
+    /// Returns which of `keys` a row refers to.
+    async fn referenced(
+        self,
+        conn: &mut PgConn,
+        admin: &AdminScope,
+        keys: &[String],
+    ) -> Result<Vec<String>> {
+        let referenced = match self {
+            Self::Files => conn.find_referenced_storage_paths(admin, keys).await?,
+            Self::Runs => conn.find_referenced_analysis_keys(admin, keys).await?,
+        };
+        Ok(referenced)
+    }
+}
+
+/// Tracks temporary objects and collects abandoned ones.
+#[derive(Clone)]
+pub struct GarbageCollectionService {
+    config: GarbageCollectionConfig,
+    pg_client: PgClient,
+    residency: ResidencyService,
+}
+
+impl GarbageCollectionService {
+    /// Creates a new garbage collection service.
+    pub fn new(
+        config: GarbageCollectionConfig,
+        pg_client: PgClient,
+        residency: ResidencyService,
+    ) -> Self {
+        Self {
+            config,
+            pg_client,
+            residency,
+        }
+    }
+
+    /// Returns the garbage collection configuration.
+    pub fn config(&self) -> &GarbageCollectionConfig {
+        &self.config
+    }
+
+    /// Registers an upload's object before it is written.
+    ///
+    /// The object is collected unless the registration is
+    /// [released](Self::release) within the upload TTL.
+    pub async fn register_upload(
+        &self,
+        conn: &mut PgConn,
+        workspace_id: Uuid,
+        account_id: Uuid,
+        bucket: &str,
+        object_key: &str,
+    ) -> Result<WorkspaceTemporaryObject> {
+        let expires_at = Timestamp::now()
+            .checked_add(self.config.upload_ttl)
+            .unwrap_or(Timestamp::MAX);
+
+        let object = conn
+            .create_temporary_object(NewWorkspaceTemporaryObject {
+                workspace_id,
+                account_id: Some(account_id),
+                bucket: bucket.to_owned(),
+                object_key: object_key.to_owned(),
+                expires_at: expires_at.into(),
+            })
+            .await?;
+        Ok(object)
+    }
+
+    /// Releases a registration once a row refers to its object.
+    ///
+    /// Takes a plain connection so it can run in the transaction that
+    /// creates the referring row, and the scope of the workspace it was
+    /// registered for.
+    pub async fn release(
+        &self,
+        conn: &mut PgConnection,
+        scope: TenantScope,
+        object: &WorkspaceTemporaryObject,
+    ) -> PgResult<()> {
+        conn.release_temporary_object(scope, object.id).await?;
+        Ok(())
+    }
+
+    /// Removes up to `limit` expired registrations and their objects.
+    ///
+    /// An object a row has come to refer to is kept; only its registration
+    /// goes. An object that fails to delete is logged and retried on the
+    /// next pass.
+    pub async fn collect_expired(&self, limit: i64) -> Result<GarbageCollectionReport> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "collect expired temporary objects").await?;
+        let expired = conn.list_expired_temporary_objects(&admin, limit).await?;
+
+        let mut report = GarbageCollectionReport::default();
+        let mut removable = Vec::with_capacity(expired.len());
+        for (object, region) in &expired {
+            match self
+                .collect_object(&mut conn, &admin, object, *region)
+                .await
+            {
+                Ok(reclaimed) => {
+                    removable.push(object.id);
+                    if let Some(bytes) = reclaimed {
+                        report.expired += 1;
+                        report.reclaimed_bytes += bytes;
+                    }
+                }
+                Err(err) => tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    workspace_id = %object.workspace_id,
+                    bucket = %object.bucket,
+                    object_key = %object.object_key,
+                    "Failed to collect expired temporary object"
+                ),
+            }
+        }
+
+        if !removable.is_empty() {
+            conn.delete_temporary_objects(&admin, &removable).await?;
+        }
+        Ok(report)
+    }
+
+    /// Sweeps up to `limit` unreferenced objects from every region.
+    ///
+    /// A region that fails is logged and skipped, so one unreachable region
+    /// does not stall the others.
+    pub async fn sweep_orphans(&self, limit: usize) -> Result<GarbageCollectionReport> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "sweep unreferenced objects").await?;
+
+        let mut report = GarbageCollectionReport::default();
+        for (region, backends) in self.residency.regions() {
+            let remaining = limit.saturating_sub(report.orphans as usize);
+            if remaining == 0 {
+                break;
+            }
+            match self
+                .sweep_region(&mut conn, &admin, backends, remaining)
+                .await
+            {
+                Ok(swept) => report.add(swept),
+                Err(err) => tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    region = %region,
+                    "Failed to sweep unreferenced objects"
+                ),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Deletes an expired registration's object unless a row refers to it.
+    ///
+    /// Returns the bytes reclaimed, or `None` if the object was kept.
+    async fn collect_object(
+        &self,
+        conn: &mut PgConn,
+        admin: &AdminScope,
+        object: &WorkspaceTemporaryObject,
+        region: DataRegion,
+    ) -> Result<Option<u64>> {
+        let Some(referrer) = Referrer::of_bucket(&object.bucket) else {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                bucket = %object.bucket,
+                "Temporary object in a bucket that is not collected"
+            );
+            return Ok(None);
+        };
+
+        let keys = [object.object_key.clone()];
+        if !referrer.referenced(conn, admin, &keys).await?.is_empty() {
+            return Ok(None);
+        }
+
+        let nats = self.residency.backends(region)?.nats();
+        let reclaimed = match referrer {
+            Referrer::Files => {
+                let store = nats.object_store::<FilesBucket, FileKey>().await?;
+                delete_object(&store, &object.object_key).await?
+            }
+            Referrer::Runs if object.bucket == AnalysesBucket::NAME => {
+                let store = nats
+                    .object_store::<AnalysesBucket, IntermediateKey>()
+                    .await?;
+                delete_object(&store, &object.object_key).await?
+            }
+            Referrer::Runs => {
+                let store = nats
+                    .object_store::<IntermediatesBucket, IntermediateKey>()
+                    .await?;
+                delete_object(&store, &object.object_key).await?
+            }
+        };
+        Ok(Some(reclaimed))
+    }
+
+    /// Sweeps the collected buckets of one region.
+    async fn sweep_region(
+        &self,
+        conn: &mut PgConn,
+        admin: &AdminScope,
+        backends: &RegionBackends,
+        limit: usize,
+    ) -> Result<GarbageCollectionReport> {
+        let nats = backends.nats();
+        let files = nats.object_store::<FilesBucket, FileKey>().await?;
+        let mut report = self
+            .sweep_bucket(conn, admin, &files, Referrer::Files, limit)
+            .await?;
+
+        let remaining = limit.saturating_sub(report.orphans as usize);
+        if remaining > 0 {
+            let analyses = nats
+                .object_store::<AnalysesBucket, IntermediateKey>()
+                .await?;
+            let swept = self
+                .sweep_bucket(conn, admin, &analyses, Referrer::Runs, remaining)
+                .await?;
+            report.add(swept);
+        }
+
+        let remaining = limit.saturating_sub(report.orphans as usize);
+        if remaining > 0 {
+            let intermediates = nats
+                .object_store::<IntermediatesBucket, IntermediateKey>()
+                .await?;
+            let swept = self
+                .sweep_bucket(conn, admin, &intermediates, Referrer::Runs, remaining)
+                .await?;
+            report.add(swept);
+        }
+
+        Ok(report)
+    }
+
+    /// Deletes up to `limit` objects of a bucket past the grace period that
+    /// no row refers to and no live registration covers.
+    async fn sweep_bucket<B, K>(
+        &self,
+        conn: &mut PgConn,
+        admin: &AdminScope,
+        store: &ObjectStore<B, K>,
+        referrer: Referrer,
+        limit: usize,
+    ) -> Result<GarbageCollectionReport>
+    where
+        B: ObjectBucket,
+        K: ObjectKey,
+    {
+        let cutoff = Timestamp::now()
+            .checked_sub(self.config.orphan_grace_period)
+            .unwrap_or(Timestamp::MIN);
+        let candidates: Vec<ObjectInfo> = store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|info| is_older_than(info, cutoff))
+            .collect();
+
+        let mut report = GarbageCollectionReport::default();
+        for chunk in candidates.chunks(LOOKUP_CHUNK) {
+            let names: Vec<String> = chunk.iter().map(|info| info.name.clone()).collect();
+            let mut kept: HashSet<String> = referrer
+                .referenced(conn, admin, &names)
+                .await?
+                .into_iter()
+                .collect();
+            kept.extend(
+                conn.find_live_temporary_object_keys(admin, B::NAME, &names)
+                    .await?,
+            );
+
+            for info in chunk.iter().filter(|info| !kept.contains(&info.name)) {
+                if report.orphans as usize >= limit {
+                    return Ok(report);
+                }
+                match delete_object(store, &info.name).await {
+                    Ok(bytes) => {
+                        report.orphans += 1;
+                        report.reclaimed_bytes += bytes;
+                    }
+                    Err(err) => tracing::warn!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        bucket = B::NAME,
+                        object_key = %info.name,
+                        "Failed to sweep unreferenced object"
+                    ),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Returns whether an object was last modified before `cutoff`.
+///
+/// An object without a modification time is never considered old.
+fn is_older_than(info: &ObjectInfo, cutoff: Timestamp) -> bool {
+    info.modified
+        .is_some_and(|modified| modified.unix_timestamp() < cutoff.as_second())
+}
+
+/// Deletes an object by its stored key, returning the bytes reclaimed.
+///
+/// An object already gone reclaims nothing.
+async fn delete_object<B, K>(store: &ObjectStore<B, K>, object_key: &str) -> Result<u64>
+where
+    B: ObjectBucket,
+    K: ObjectKey,
+{
+    let key = K::from_str(object_key).map_err(|_| {
+        ErrorKind::InternalServerError
+            .with_message("Invalid object key")
+            .with_context(object_key.to_owned())
+    })?;
+
+    let Some(info) = store.info(&key).await? else {
+        return Ok(0);
+    };
+    store.delete(&key).await?;
+    Ok(info.size as u64)
+}
@@ -11,6 +11,7 @@ use std::time::Instant;
 use jiff::Timestamp;
 use nvisy_core::health::{ComponentHealth, HealthCheck, HealthStatus};
 
+use super::graph::resolve;
 use super::snapshot::{HealthCacheEntry, HealthSnapshot};
 use super::{HealthConfig, TRACING_TARGET};
 use crate::handler::response::Health;
@@ -86,11 +87,15 @@ impl HealthCache {
     }
 
     /// Converts a [`HealthSnapshot`] into a [`Health`] response.
+    ///
+    /// The overall status is derived from the effective statuses, after
+    /// failures have propagated to the components depending on them.
     fn snapshot_to_health(snapshot: HealthSnapshot) -> Health {
-        let all_healthy = snapshot.components.iter().all(|c| c.status.is_healthy());
-        let any_healthy = snapshot.components.iter().any(|c| c.status.is_healthy());
+        let checks = resolve(snapshot.components);
+        let all_healthy = checks.iter().all(|c| c.effective_status.is_healthy());
+        let any_healthy = checks.iter().any(|c| c.effective_status.is_healthy());
 
-        let status = if snapshot.components.is_empty() || !any_healthy {
+        let status = if checks.is_empty() || !any_healthy {
             HealthStatus::Unhealthy
         } else if all_healthy {
             HealthStatus::Healthy
@@ -100,7 +105,7 @@ impl HealthCache {
 
         Health {
             status,
-            checks: snapshot.components,
+            checks,
             timestamp: snapshot.timestamp,
         }
     }
@@ -134,7 +139,10 @@ impl HealthCache {
 mod tests {
     use std::borrow::Cow;
 
+    use nvisy_core::health::HealthDependency;
+
     use super::*;
+    use crate::service::health::DependentCheck;
 
     /// A checker that always reports the given status.
     struct StubChecker {
@@ -149,6 +157,7 @@ mod tests {
                 name: Cow::Borrowed(self.name),
                 status: self.status,
                 latency: None,
+                dependencies: Vec::new(),
             }
         }
     }
@@ -190,6 +199,31 @@ mod tests {
         assert_eq!(cache.check().await.status, HealthStatus::Unhealthy);
     }
 
+    #[tokio::test]
+    async fn dependency_failure_is_attributed() {
+        let webhook = DependentCheck::new(
+            checker("webhook", HealthStatus::Healthy),
+            vec![HealthDependency::required("nats")],
+        );
+        let cache = cache(vec![
+            checker("postgres", HealthStatus::Healthy),
+            checker("nats", HealthStatus::Unhealthy),
+            Arc::new(webhook),
+        ]);
+
+        let health = cache.check().await;
+        assert_eq!(health.status, HealthStatus::Degraded);
+
+        let webhook = health
+            .checks
+            .iter()
+            .find(|c| c.component.name == "webhook")
+            .unwrap();
+        assert_eq!(webhook.component.status, HealthStatus::Healthy);
+        assert_eq!(webhook.effective_status, HealthStatus::Unhealthy);
+        assert_eq!(webhook.causes[0].component, "nats");
+    }
+
     #[tokio::test]
     async fn no_components_is_unhealthy() {
         let cache = cache(vec![]);
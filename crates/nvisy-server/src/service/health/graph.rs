@@ -0,0 +1,275 @@
+//! Dependency-graph resolution of component health.
+//!
+//! Every component reports its own status plus the components it depends on.
+//! Resolution walks those edges to compute each component's effective status
+//! and attribute it to the failing components at the root: a component whose
+//! required dependency is unhealthy is unhealthy itself, while a failing
+//! optional dependency degrades it at most.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use nvisy_core::health::{
+    ComponentHealth, DependencyKind, HealthCheck, HealthDependency, HealthStatus,
+};
+
+use super::TRACING_TARGET;
+use crate::handler::response::{ComponentNode, HealthCause};
+
+/// Attaches dependency edges to the results of another health check.
+///
+/// Clients from other crates report only their own status; this wrapper lets
+/// the server declare how they relate, e.g. that webhook delivery relies on
+/// NATS.
+pub struct DependentCheck {
+    inner: Arc<dyn HealthCheck>,
+    dependencies: Vec<HealthDependency>,
+}
+
+impl DependentCheck {
+    /// Wraps `inner`, adding `dependencies` to every result it reports.
+    pub fn new(inner: Arc<dyn HealthCheck>, dependencies: Vec<HealthDependency>) -> Self {
+        Self {
+            inner,
+            dependencies,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for DependentCheck {
+    async fn check_health(&self) -> ComponentHealth {
+        let mut health = self.inner.check_health().await;
+        health
+            .dependencies
+            .extend(self.dependencies.iter().cloned());
+        health
+    }
+}
+
+/// Resolution progress of a single component.
+#[derive(Clone)]
+enum Visit {
+    InProgress,
+    Done(HealthStatus, Vec<HealthCause>),
+}
+
+/// Resolves the effective status and root causes of every component.
+///
+/// Components are matched to dependency edges by name; when several report
+/// the same name, the first one is used. Edges to components that were not
+/// checked are ignored, as are edges closing a cycle.
+pub(super) fn resolve(components: Vec<ComponentHealth>) -> Vec<ComponentNode> {
+    let mut index = HashMap::with_capacity(components.len());
+    for (i, component) in components.iter().enumerate() {
+        index.entry(component.name.clone()).or_insert(i);
+    }
+
+    let mut visits = vec![None; components.len()];
+    for i in 0..components.len() {
+        visit(i, &components, &index, &mut visits);
+    }
+
+    components
+        .into_iter()
+        .zip(visits)
+        .map(|(component, visit)| {
+            let Some(Visit::Done(effective_status, causes)) = visit else {
+                unreachable!("every component is visited");
+            };
+            let reason = reason(&component, effective_status, &causes);
+            ComponentNode {
+                component,
+                effective_status,
+                causes,
+                reason,
+            }
+        })
+        .collect()
+}
+
+/// Depth-first resolution of one component, memoized in `visits`.
+fn visit(
+    i: usize,
+    components: &[ComponentHealth],
+    index: &HashMap<Cow<'static, str>, usize>,
+    visits: &mut [Option<Visit>],
+) -> Option<(HealthStatus, Vec<HealthCause>)> {
+    match &visits[i] {
+        Some(Visit::Done(status, causes)) => return Some((*status, causes.clone())),
+        Some(Visit::InProgress) => return None,
+        None => visits[i] = Some(Visit::InProgress),
+    }
+
+    let component = &components[i];
+    let mut status = component.status;
+    let mut causes: Vec<HealthCause> = Vec::new();
+
+    for dependency in &component.dependencies {
+        let Some(&j) = index.get(&dependency.name) else {
+            continue;
+        };
+        let Some((dep_status, dep_causes)) = visit(j, components, index, visits) else {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                component = %component.name,
+                dependency = %dependency.name,
+                "Health dependency cycle ignored"
+            );
+            continue;
+        };
+        if dep_status.is_healthy() {
+            continue;
+        }
+
+        let propagated = match dependency.kind {
+            DependencyKind::Required => dep_status,
+            DependencyKind::Optional => HealthStatus::Degraded,
+        };
+        status = status.max(propagated);
+
+        // A failing dependency with no failing dependencies of its own is
+        // itself the root cause.
+        let roots = if dep_causes.is_empty() {
+            vec![HealthCause {
+                component: components[j].name.clone(),
+                status: components[j].status,
+            }]
+        } else {
+            dep_causes
+        };
+        for root in roots {
+            if !causes.contains(&root) {
+                causes.push(root);
+            }
+        }
+    }
+
+    // Causes only explain a status worse than the component's own.
+    if status == component.status {
+        causes.clear();
+    }
+
+    visits[i] = Some(Visit::Done(status, causes.clone()));
+    Some((status, causes))
+}
+
+/// Renders the effective status of a component as a sentence.
+fn reason(
+    component: &ComponentHealth,
+    effective_status: HealthStatus,
+    causes: &[HealthCause],
+) -> Option<String> {
+    if effective_status.is_healthy() {
+        return None;
+    }
+
+    let status = status_word(effective_status);
+    if causes.is_empty() {
+        return Some(format!("{} {status}", component.name));
+    }
+
+    let causes = causes
+        .iter()
+        .map(|cause| format!("{} is {}", cause.component, status_word(cause.status)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!("{} {status} because {causes}", component.name))
+}
+
+fn status_word(status: HealthStatus) -> &'static str {
+    match status {
+        HealthStatus::Healthy => "healthy",
+        HealthStatus::Degraded => "degraded",
+        HealthStatus::Unhealthy => "unhealthy",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(name: &'static str, status: HealthStatus) -> ComponentHealth {
+        ComponentHealth {
+            status,
+            ..ComponentHealth::healthy(name)
+        }
+    }
+
+    fn node<'a>(nodes: &'a [ComponentNode], name: &str) -> &'a ComponentNode {
+        nodes.iter().find(|n| n.component.name == name).unwrap()
+    }
+
+    #[test]
+    fn required_failure_propagates_with_root_cause() {
+        let nodes = resolve(vec![
+            component("webhook", HealthStatus::Healthy)
+                .with_dependency(HealthDependency::required("nats")),
+            component("nats", HealthStatus::Unhealthy),
+        ]);
+
+        let webhook = node(&nodes, "webhook");
+        assert_eq!(webhook.effective_status, HealthStatus::Unhealthy);
+        assert_eq!(
+            webhook.causes,
+            vec![HealthCause {
+                component: "nats".into(),
+                status: HealthStatus::Unhealthy,
+            }]
+        );
+        assert_eq!(
+            webhook.reason.as_deref(),
+            Some("webhook unhealthy because nats is unhealthy")
+        );
+
+        let nats = node(&nodes, "nats");
+        assert!(nats.causes.is_empty());
+        assert_eq!(nats.reason.as_deref(), Some("nats unhealthy"));
+    }
+
+    #[test]
+    fn optional_failure_only_degrades() {
+        let nodes = resolve(vec![
+            component("search", HealthStatus::Healthy)
+                .with_dependency(HealthDependency::optional("index")),
+            component("index", HealthStatus::Unhealthy),
+        ]);
+
+        let search = node(&nodes, "search");
+        assert_eq!(search.effective_status, HealthStatus::Degraded);
+        assert_eq!(
+            search.reason.as_deref(),
+            Some("search degraded because index is unhealthy")
+        );
+    }
+
+    #[test]
+    fn causes_point_at_transitive_roots() {
+        let nodes = resolve(vec![
+            component("api", HealthStatus::Healthy)
+                .with_dependency(HealthDependency::required("queue")),
+            component("queue", HealthStatus::Healthy)
+                .with_dependency(HealthDependency::required("nats")),
+            component("nats", HealthStatus::Degraded),
+        ]);
+
+        let api = node(&nodes, "api");
+        assert_eq!(api.effective_status, HealthStatus::Degraded);
+        assert_eq!(api.causes.len(), 1);
+        assert_eq!(api.causes[0].component, "nats");
+    }
+
+    #[test]
+    fn cycles_and_unknown_dependencies_are_ignored() {
+        let nodes = resolve(vec![
+            component("a", HealthStatus::Healthy)
+                .with_dependency(HealthDependency::required("b"))
+                .with_dependency(HealthDependency::required("missing")),
+            component("b", HealthStatus::Healthy).with_dependency(HealthDependency::required("a")),
+        ]);
+
+        assert!(nodes.iter().all(|n| n.effective_status.is_healthy()));
+        assert!(nodes.iter().all(|n| n.reason.is_none()));
+    }
+}
@@ -2,14 +2,18 @@
 //!
 //! Aggregates the [`HealthCheck`](nvisy_core::health::HealthCheck) results of
 //! all registered components ([`HealthCache`]), caching them with a TTL to
-//! balance responsiveness against the cost of repeated probes.
+//! balance responsiveness against the cost of repeated probes. Results are
+//! resolved along the components' dependency edges, so a failure is reported
+//! on the component that caused it and on everything relying on it.
 
 use std::time::Duration;
 
 mod cache;
+mod graph;
 mod snapshot;
 
 pub use cache::HealthCache;
+pub use graph::DependentCheck;
 
 /// Tracing target for health monitoring operations.
 const TRACING_TARGET: &str = "nvisy_server::health";
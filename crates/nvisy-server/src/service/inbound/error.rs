@@ -0,0 +1,23 @@
+//! Inbound webhook error types.
+
+use thiserror::Error;
+
+/// Result type for verifying inbound webhook requests.
+pub type InboundResult<T> = Result<T, InboundError>;
+
+/// Reasons an inbound webhook request is refused.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InboundError {
+    /// A header the provider always sends is absent or not valid text.
+    #[error("missing or invalid {0} header")]
+    MissingHeader(&'static str),
+    /// The signature or token does not match the inbound webhook's secret.
+    #[error("signature verification failed")]
+    InvalidSignature,
+    /// The request was signed too long ago, or claims to come from the future.
+    #[error("request timestamp is outside the accepted window")]
+    Expired,
+    /// The request is authentic but its content cannot be normalized.
+    #[error("malformed payload: {0}")]
+    MalformedPayload(String),
+}
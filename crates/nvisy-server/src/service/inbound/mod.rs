@@ -0,0 +1,19 @@
+//! Inbound webhooks: events sent to the server by third-party services.
+//!
+//! A workspace registers an inbound webhook per integration and gives its
+//! URL, `/inbound/{inboundWebhookId}/`, to the service. The
+//! [`InboundReceiver`] verifies each request with the adapter of the
+//! webhook's provider ([`verify`]), recognizes deliveries it accepted
+//! before, and publishes the normalized event to the workspace event
+//! stream, where it reaches real-time subscribers like any other event.
+
+mod error;
+mod receiver;
+mod verify;
+
+pub use error::{InboundError, InboundResult};
+pub use receiver::{InboundReceipt, InboundReceiver};
+pub use verify::{InboundDelivery, MAX_DELIVERY_AGE, SIGNATURE_TOLERANCE, verify};
+
+/// Tracing target for inbound webhook operations.
+const TRACING_TARGET: &str = "nvisy_server::service::inbound";
@@ -0,0 +1,194 @@
+//! Receiving inbound webhook requests.
+
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use nvisy_core::clock::Clock;
+use nvisy_nats::NatsClient;
+use nvisy_nats::kv::{InboxKey, ProcessedMessage};
+use nvisy_nats::stream::{WorkspaceEvent, workspace_event_subject};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::WorkspaceInboundWebhook;
+use nvisy_postgres::query::{AdminScope, WorkspaceInboundWebhookRepository};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::TRACING_TARGET;
+use super::verify::{InboundDelivery, verify};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::CryptoService;
+
+/// Outcome of a request accepted by an inbound webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundReceipt {
+    /// The provider's id of the delivery.
+    pub delivery_id: String,
+    /// The provider's name for the event.
+    pub event_type: String,
+    /// Whether the delivery was accepted before and not published again.
+    pub duplicate: bool,
+}
+
+/// Verifies inbound webhook requests and routes them to the workspace event
+/// stream.
+///
+/// A delivery is published once: the ids of accepted deliveries are kept
+/// with the processed message ids, under the consumer `inbound-{id}`, and a
+/// request repeating one is acknowledged without being published again.
+/// Two copies arriving together may both pass that check; they are
+/// published with the same message id, so JetStream keeps one.
+#[derive(Clone)]
+pub struct InboundReceiver {
+    pg_client: PgClient,
+    nats_client: NatsClient,
+    crypto: CryptoService,
+    clock: Arc<dyn Clock>,
+}
+
+impl InboundReceiver {
+    /// Creates a new inbound webhook receiver.
+    pub fn new(
+        pg_client: PgClient,
+        nats_client: NatsClient,
+        crypto: CryptoService,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            pg_client,
+            nats_client,
+            crypto,
+            clock,
+        }
+    }
+
+    /// Handles a request addressed to an inbound webhook.
+    ///
+    /// Unknown and deleted inbound webhooks are reported as not found, and
+    /// requests failing verification as unauthorized, without saying why.
+    pub async fn receive(
+        &self,
+        inbound_webhook_id: Uuid,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<InboundReceipt> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(
+            &mut conn,
+            "resolve the inbound webhook a request is addressed to",
+        )
+        .await?;
+        let inbound_webhook = conn
+            .find_inbound_webhook_by_id(&admin, inbound_webhook_id)
+            .await?
+            .ok_or_else(|| Error::not_found("inbound_webhook"))?;
+
+        let secret = self.secret(&inbound_webhook)?;
+        let delivery = verify(
+            self.crypto.provider().as_ref(),
+            inbound_webhook.provider,
+            &secret,
+            headers,
+            body,
+            self.clock.now(),
+        )?;
+
+        let store = self.nats_client.processed_message_store().await?;
+        let key = InboxKey::new(
+            format!("inbound-{inbound_webhook_id}"),
+            &delivery.delivery_id,
+        );
+        if store.exists(&key).await? {
+            tracing::debug!(
+                target: TRACING_TARGET,
+                inbound_webhook_id = %inbound_webhook_id,
+                delivery_id = %delivery.delivery_id,
+                "Acknowledged repeated inbound delivery"
+            );
+            return Ok(receipt(delivery, true));
+        }
+
+        self.publish(&inbound_webhook, &delivery).await?;
+        store.put(&key, &ProcessedMessage::now()).await?;
+
+        if let Err(error) = conn
+            .record_inbound_webhook_received(inbound_webhook_id)
+            .await
+        {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                inbound_webhook_id = %inbound_webhook_id,
+                error = %error,
+                "Failed to record inbound delivery time"
+            );
+        }
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            inbound_webhook_id = %inbound_webhook_id,
+            provider = %inbound_webhook.provider,
+            event_type = %delivery.event_type,
+            "Accepted inbound delivery"
+        );
+
+        Ok(receipt(delivery, false))
+    }
+
+    /// Publishes a delivery to its workspace's event stream as the event
+    /// `inbound:{provider}`.
+    async fn publish(
+        &self,
+        inbound_webhook: &WorkspaceInboundWebhook,
+        delivery: &InboundDelivery,
+    ) -> Result<()> {
+        let provider = inbound_webhook.provider;
+        let event = WorkspaceEvent {
+            workspace_id: inbound_webhook.workspace_id,
+            event: format!("inbound:{provider}"),
+            resource_type: "inbound_webhook".to_owned(),
+            resource_id: inbound_webhook.id,
+            triggered_by: None,
+            data: Some(json!({
+                "provider": provider,
+                "eventType": delivery.event_type,
+                "deliveryId": delivery.delivery_id,
+                "payload": delivery.payload,
+            })),
+            occurred_at: self.clock.now(),
+        };
+
+        let subject =
+            workspace_event_subject(inbound_webhook.workspace_id, &format!("inbound.{provider}"));
+        let message_id = format!("inbound-{}-{}", inbound_webhook.id, delivery.delivery_id);
+
+        self.nats_client
+            .workspace_event_publisher()
+            .await?
+            .publish_to_with_id(&subject, &message_id, &event)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Decrypts an inbound webhook's verification secret.
+    fn secret(&self, inbound_webhook: &WorkspaceInboundWebhook) -> Result<String> {
+        let plaintext = self.crypto.decrypt(
+            inbound_webhook.workspace_id,
+            &inbound_webhook.encrypted_secret,
+        )?;
+
+        String::from_utf8(plaintext).map_err(|error| {
+            ErrorKind::InternalServerError
+                .with_message("Inbound webhook secret is not valid UTF-8")
+                .with_context(error.to_string())
+        })
+    }
+}
+
+/// Builds the receipt of a delivery.
+fn receipt(delivery: InboundDelivery, duplicate: bool) -> InboundReceipt {
+    InboundReceipt {
+        delivery_id: delivery.delivery_id,
+        event_type: delivery.event_type,
+        duplicate,
+    }
+}
@@ -0,0 +1,435 @@
+//! Signature verification and normalization of inbound webhook requests.
+//!
+//! Each provider authenticates its requests differently; [`verify`] checks a
+//! request against the inbound webhook's secret and turns it into an
+//! [`InboundDelivery`]: the provider's id of the delivery, which repeats when
+//! the provider retries, the provider's name for the event, and the payload
+//! as JSON.
+//!
+//! | Provider       | Authentication                                  | Delivery id                         |
+//! |----------------|-------------------------------------------------|-------------------------------------|
+//! | `hmac`         | `X-Webhook-Signature` over `{timestamp}.{body}` | the signature                       |
+//! | `jwt`          | the body is a JWT signed with HS256             | the `jti` claim                     |
+//! | `docusign`     | `X-DocuSign-Signature-<n>` over the body        | envelope, event and generation time |
+//! | `google_drive` | `X-Goog-Channel-Token` equal to the secret      | channel id and message number       |
+//!
+//! The `hmac` scheme is the one outgoing webhooks are signed with, timestamp
+//! in `X-Webhook-Timestamp`. Delivery ids only come from signed material, so
+//! a captured request cannot be replayed under a new id; an `hmac` retry
+//! signed anew is a new delivery. Requests of the `hmac` and `jwt` providers must
+//! have been signed within [`SIGNATURE_TOLERANCE`]; DocuSign retries a failed
+//! delivery for days with the same signature, so its requests are only
+//! refused once older than [`MAX_DELIVERY_AGE`], past which a replay could
+//! no longer be recognized.
+
+use std::time::Duration;
+
+use axum::http::HeaderMap;
+use base64::prelude::*;
+use jiff::Timestamp;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use nvisy_core::crypto::CryptoProvider;
+use nvisy_postgres::types::InboundWebhookProvider;
+use serde_json::{Map, Value, json};
+
+use super::error::{InboundError, InboundResult};
+
+/// How far the signing time of a request may be from the current time.
+pub const SIGNATURE_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+/// Age past which a request is refused whatever its provider.
+///
+/// Kept below the 7-day TTL of the processed message ids, so any request
+/// still accepted is one whose delivery id would be recognized if replayed.
+pub const MAX_DELIVERY_AGE: Duration = Duration::from_secs(6 * 24 * 60 * 60);
+
+/// Event name used when the provider does not name the event.
+const DEFAULT_EVENT_TYPE: &str = "message";
+
+/// A verified request, normalized across providers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboundDelivery {
+    /// The provider's id of the delivery, repeated on retries.
+    pub delivery_id: String,
+    /// The provider's name for the event (e.g. `envelope-completed`).
+    pub event_type: String,
+    /// The event, as JSON.
+    pub payload: Value,
+}
+
+/// Verifies a request received by an inbound webhook of `provider` whose
+/// secret is `secret`, and normalizes it.
+pub fn verify(
+    crypto: &dyn CryptoProvider,
+    provider: InboundWebhookProvider,
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: Timestamp,
+) -> InboundResult<InboundDelivery> {
+    match provider {
+        InboundWebhookProvider::Hmac => verify_hmac(crypto, secret, headers, body, now),
+        InboundWebhookProvider::Jwt => verify_jwt(secret, body, now),
+        InboundWebhookProvider::DocuSign => verify_docusign(crypto, secret, headers, body, now),
+        InboundWebhookProvider::GoogleDrive => verify_google_drive(secret, headers),
+    }
+}
+
+/// Verifies a request signed like the server's own outgoing webhooks.
+fn verify_hmac(
+    crypto: &dyn CryptoProvider,
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: Timestamp,
+) -> InboundResult<InboundDelivery> {
+    let timestamp = header(headers, "x-webhook-timestamp")?;
+    let signature = header(headers, "x-webhook-signature")?;
+
+    let signature = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex| hex::decode(hex).ok())
+        .ok_or(InboundError::InvalidSignature)?;
+    let expected = crypto.hmac_sha256(secret.as_bytes(), &[timestamp.as_bytes(), b".", body]);
+    if !constant_time_eq(&expected, &signature) {
+        return Err(InboundError::InvalidSignature);
+    }
+
+    let signed_at = timestamp
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| Timestamp::from_second(seconds).ok())
+        .ok_or(InboundError::MissingHeader("x-webhook-timestamp"))?;
+    check_age(signed_at, now, SIGNATURE_TOLERANCE)?;
+
+    let payload = json_body(body)?;
+    let delivery_id = hex::encode(signature);
+    let event_type = match optional_header(headers, "x-webhook-event") {
+        Some(event) => event.to_owned(),
+        None => string_field(&payload, "event")
+            .unwrap_or(DEFAULT_EVENT_TYPE)
+            .to_owned(),
+    };
+
+    Ok(InboundDelivery {
+        delivery_id,
+        event_type,
+        payload,
+    })
+}
+
+/// Verifies a request whose body is a JWT signed with HS256.
+///
+/// The token must carry `jti`, `iat` and `exp`, and may name its event in an
+/// `event` claim; the payload is the whole claim set.
+fn verify_jwt(secret: &str, body: &[u8], now: Timestamp) -> InboundResult<InboundDelivery> {
+    let token = std::str::from_utf8(body)
+        .map(str::trim)
+        .map_err(|_| InboundError::MalformedPayload("the body is not a JWT".to_owned()))?;
+
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_required_spec_claims(&["exp", "iat", "jti"]);
+    validation.validate_aud = false;
+    validation.leeway = SIGNATURE_TOLERANCE.as_secs();
+
+    let key = DecodingKey::from_secret(secret.as_bytes());
+    let claims = jsonwebtoken::decode::<Map<String, Value>>(token, &key, &validation)
+        .map_err(|error| match error.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => InboundError::Expired,
+            _ => InboundError::InvalidSignature,
+        })?
+        .claims;
+
+    let issued_at = claims
+        .get("iat")
+        .and_then(Value::as_i64)
+        .and_then(|seconds| Timestamp::from_second(seconds).ok())
+        .ok_or_else(|| InboundError::MalformedPayload("invalid iat claim".to_owned()))?;
+    check_age(issued_at, now, SIGNATURE_TOLERANCE)?;
+
+    let payload = Value::Object(claims);
+    let delivery_id = string_field(&payload, "jti")
+        .ok_or_else(|| InboundError::MalformedPayload("the jti claim must be a string".to_owned()))?
+        .to_owned();
+    let event_type = string_field(&payload, "event")
+        .unwrap_or(DEFAULT_EVENT_TYPE)
+        .to_owned();
+
+    Ok(InboundDelivery {
+        delivery_id,
+        event_type,
+        payload,
+    })
+}
+
+/// Verifies a DocuSign Connect notification sent with HMAC security.
+///
+/// Connect signs the body with every active key of the configuration, one
+/// header each, so any of them matching the secret is enough.
+fn verify_docusign(
+    crypto: &dyn CryptoProvider,
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: Timestamp,
+) -> InboundResult<InboundDelivery> {
+    let expected = crypto.hmac_sha256(secret.as_bytes(), &[body]);
+    let mut signatures = headers
+        .iter()
+        .filter(|(name, _)| name.as_str().starts_with("x-docusign-signature-"))
+        .peekable();
+    if signatures.peek().is_none() {
+        return Err(InboundError::MissingHeader("x-docusign-signature-1"));
+    }
+    let verified = signatures.any(|(_, value)| {
+        BASE64_STANDARD
+            .decode(value.as_bytes())
+            .is_ok_and(|signature| constant_time_eq(&expected, &signature))
+    });
+    if !verified {
+        return Err(InboundError::InvalidSignature);
+    }
+
+    let payload = json_body(body)?;
+    let event_type = string_field(&payload, "event")
+        .ok_or_else(|| InboundError::MalformedPayload("missing event".to_owned()))?
+        .to_owned();
+    let generated_at = string_field(&payload, "generatedDateTime")
+        .ok_or_else(|| InboundError::MalformedPayload("missing generatedDateTime".to_owned()))?;
+    let generated = generated_at
+        .parse::<Timestamp>()
+        .map_err(|_| InboundError::MalformedPayload("invalid generatedDateTime".to_owned()))?;
+    check_age(generated, now, MAX_DELIVERY_AGE)?;
+
+    let envelope_id = payload
+        .pointer("/data/envelopeId")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let delivery_id = format!("{envelope_id}:{event_type}:{generated_at}");
+
+    Ok(InboundDelivery {
+        delivery_id,
+        event_type,
+        payload,
+    })
+}
+
+/// Verifies a Google Drive push notification.
+///
+/// Notifications have no body and no signature: the channel token, set to
+/// the secret when the channel is opened, is echoed in a header, and the
+/// payload is assembled from the other `X-Goog-*` headers.
+fn verify_google_drive(secret: &str, headers: &HeaderMap) -> InboundResult<InboundDelivery> {
+    let token = header(headers, "x-goog-channel-token")?;
+    if !constant_time_eq(secret.as_bytes(), token.as_bytes()) {
+        return Err(InboundError::InvalidSignature);
+    }
+
+    let channel_id = header(headers, "x-goog-channel-id")?;
+    let message_number = header(headers, "x-goog-message-number")?;
+    let resource_state = header(headers, "x-goog-resource-state")?;
+
+    let payload = json!({
+        "channelId": channel_id,
+        "messageNumber": message_number,
+        "resourceState": resource_state,
+        "resourceId": optional_header(headers, "x-goog-resource-id"),
+        "resourceUri": optional_header(headers, "x-goog-resource-uri"),
+        "changed": optional_header(headers, "x-goog-changed"),
+    });
+
+    Ok(InboundDelivery {
+        delivery_id: format!("{channel_id}:{message_number}"),
+        event_type: resource_state.to_owned(),
+        payload,
+    })
+}
+
+/// Refuses a request signed more than `max_age` before or after `now`.
+fn check_age(signed_at: Timestamp, now: Timestamp, max_age: Duration) -> InboundResult<()> {
+    let skew = now.duration_since(signed_at).unsigned_abs();
+    if skew > max_age {
+        return Err(InboundError::Expired);
+    }
+    Ok(())
+}
+
+/// Returns a required header as text.
+fn header<'a>(headers: &'a HeaderMap, name: &'static str) -> InboundResult<&'a str> {
+    optional_header(headers, name).ok_or(InboundError::MissingHeader(name))
+}
+
+/// Returns an optional header as text, ignoring values that are not.
+fn optional_header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Returns a top-level string field of a JSON object.
+fn string_field<'a>(payload: &'a Value, field: &str) -> Option<&'a str> {
+    payload.get(field).and_then(Value::as_str)
+}
+
+/// Parses a request body as JSON.
+fn json_body(body: &[u8]) -> InboundResult<Value> {
+    serde_json::from_slice(body)
+        .map_err(|error| InboundError::MalformedPayload(format!("invalid JSON: {error}")))
+}
+
+/// Compares two byte strings in constant time.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use jsonwebtoken::{EncodingKey, Header};
+    use nvisy_core::crypto::RustCryptoProvider;
+
+    use super::*;
+
+    const SECRET: &str = "inbound-secret";
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn hmac_headers(body: &[u8], signed_at: Timestamp) -> HeaderMap {
+        let timestamp = signed_at.as_second().to_string();
+        let tag =
+            RustCryptoProvider.hmac_sha256(SECRET.as_bytes(), &[timestamp.as_bytes(), b".", body]);
+        headers(&[
+            ("x-webhook-timestamp", timestamp),
+            (
+                "x-webhook-signature",
+                format!("sha256={}", hex::encode(tag)),
+            ),
+        ])
+    }
+
+    #[test]
+    fn hmac_requests_are_verified_and_dated() {
+        let now = Timestamp::from_second(1_800_000_000).unwrap();
+        let body = br#"{"event":"order:paid","id":7}"#;
+        let provider = InboundWebhookProvider::Hmac;
+
+        let headers = hmac_headers(body, now);
+        let delivery = verify(&RustCryptoProvider, provider, SECRET, &headers, body, now).unwrap();
+        assert_eq!(delivery.event_type, "order:paid");
+        assert_eq!(delivery.payload["id"], 7);
+
+        let tampered = br#"{"event":"order:paid","id":8}"#;
+        let error = verify(
+            &RustCryptoProvider,
+            provider,
+            SECRET,
+            &headers,
+            tampered,
+            now,
+        );
+        assert_eq!(error, Err(InboundError::InvalidSignature));
+
+        // The delivery id is bound to the signature, not to unsigned headers.
+        let mut replayed = headers.clone();
+        replayed.insert("x-webhook-id", HeaderValue::from_static("fresh-id"));
+        let replay = verify(&RustCryptoProvider, provider, SECRET, &replayed, body, now).unwrap();
+        assert_eq!(replay.delivery_id, delivery.delivery_id);
+
+        let stale = hmac_headers(body, now - Duration::from_secs(10 * 60));
+        let error = verify(&RustCryptoProvider, provider, SECRET, &stale, body, now);
+        assert_eq!(error, Err(InboundError::Expired));
+    }
+
+    #[test]
+    fn jwt_requests_need_a_valid_token() {
+        let now = Timestamp::now();
+        let claims = json!({
+            "jti": "evt-1",
+            "iat": now.as_second(),
+            "exp": now.as_second() + 60,
+            "event": "ticket.closed",
+        });
+        let key = EncodingKey::from_secret(SECRET.as_bytes());
+        let token = jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &key).unwrap();
+        let provider = InboundWebhookProvider::Jwt;
+
+        let delivery = verify(
+            &RustCryptoProvider,
+            provider,
+            SECRET,
+            &HeaderMap::new(),
+            token.as_bytes(),
+            now,
+        )
+        .unwrap();
+        assert_eq!(delivery.delivery_id, "evt-1");
+        assert_eq!(delivery.event_type, "ticket.closed");
+
+        let error = verify(
+            &RustCryptoProvider,
+            provider,
+            "another-secret",
+            &HeaderMap::new(),
+            token.as_bytes(),
+            now,
+        );
+        assert_eq!(error, Err(InboundError::InvalidSignature));
+    }
+
+    #[test]
+    fn docusign_requests_accept_any_configured_key() {
+        let now: Timestamp = "2026-10-17T10:00:00Z".parse().unwrap();
+        let body = br#"{"event":"envelope-completed","generatedDateTime":"2026-10-17T09:59:58Z","data":{"envelopeId":"env-1"}}"#;
+        let tag = RustCryptoProvider.hmac_sha256(SECRET.as_bytes(), &[body]);
+        let headers = headers(&[
+            ("x-docusign-signature-1", BASE64_STANDARD.encode([0u8; 32])),
+            ("x-docusign-signature-2", BASE64_STANDARD.encode(tag)),
+        ]);
+
+        let provider = InboundWebhookProvider::DocuSign;
+        let delivery = verify(&RustCryptoProvider, provider, SECRET, &headers, body, now).unwrap();
+        assert_eq!(delivery.event_type, "envelope-completed");
+        assert_eq!(
+            delivery.delivery_id,
+            "env-1:envelope-completed:2026-10-17T09:59:58Z"
+        );
+
+        let error = verify(
+            &RustCryptoProvider,
+            provider,
+            SECRET,
+            &HeaderMap::new(),
+            body,
+            now,
+        );
+        assert_eq!(
+            error,
+            Err(InboundError::MissingHeader("x-docusign-signature-1"))
+        );
+    }
+
+    #[test]
+    fn google_drive_requests_echo_the_channel_token() {
+        let now = Timestamp::now();
+        let mut headers = headers(&[
+            ("x-goog-channel-token", SECRET.to_owned()),
+            ("x-goog-channel-id", "chan-1".to_owned()),
+            ("x-goog-message-number", "12".to_owned()),
+            ("x-goog-resource-state", "update".to_owned()),
+        ]);
+
+        let provider = InboundWebhookProvider::GoogleDrive;
+        let delivery = verify(&RustCryptoProvider, provider, SECRET, &headers, b"", now).unwrap();
+        assert_eq!(delivery.delivery_id, "chan-1:12");
+        assert_eq!(delivery.event_type, "update");
+        assert_eq!(delivery.payload["resourceId"], Value::Null);
+
+        headers.insert("x-goog-channel-token", HeaderValue::from_static("guess"));
+        let error = verify(&RustCryptoProvider, provider, SECRET, &headers, b"", now);
+        assert_eq!(error, Err(InboundError::InvalidSignature));
+    }
+}
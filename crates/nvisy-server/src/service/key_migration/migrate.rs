@@ -0,0 +1,107 @@
+//! Background migration of legacy object keys.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::{KeyMigrationReport, KeyMigrationService};
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for the key migration worker.
+const TRACING_TARGET: &str = "nvisy_server::worker::key_migration";
+
+/// Maximum number of objects moved per batch.
+const BATCH_SIZE: i64 = 100;
+
+/// Periodically moves file content from legacy object keys to the current
+/// layout, reporting how many legacy objects remain.
+pub struct KeyMigration {
+    migration: KeyMigrationService,
+    interval: Duration,
+}
+
+impl KeyMigration {
+    /// Create a new migration worker using the service's configured interval.
+    pub fn new(migration: KeyMigrationService) -> Self {
+        let interval = migration.config().interval;
+        Self {
+            migration,
+            interval,
+        }
+    }
+
+    /// Run migration passes until cancelled.
+    ///
+    /// Every server instance may run the worker: a move is only committed
+    /// once the copy is verified, and repeating one that another instance
+    /// finished is harmless. `heartbeat` is beaten after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            "Starting key migration"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Key migration shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.migrate(&cancel).await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Key migration stopped");
+        Ok(())
+    }
+
+    /// Moves legacy objects in batches until a batch moves nothing, then
+    /// reports what is left.
+    async fn migrate(&self, cancel: &CancellationToken) {
+        let mut total = KeyMigrationReport::default();
+        while !cancel.is_cancelled() {
+            match self.migration.migrate_batch(BATCH_SIZE).await {
+                Ok(batch) => {
+                    total.migrated += batch.migrated;
+                    total.failed += batch.failed;
+                    if batch.migrated == 0 {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Failed to migrate legacy object keys"
+                    );
+                    break;
+                }
+            }
+        }
+
+        match self.migration.remaining().await {
+            Ok(remaining) if remaining > 0 || total.migrated > 0 => tracing::info!(
+                target: TRACING_TARGET,
+                migrated = total.migrated,
+                failed = total.failed,
+                remaining,
+                "Legacy object keys migrated"
+            ),
+            Ok(_) => {}
+            Err(err) => tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Failed to count legacy object keys"
+            ),
+        }
+    }
+}
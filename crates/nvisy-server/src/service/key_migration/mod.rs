@@ -0,0 +1,218 @@
+//! Migration of file content stored under legacy object keys.
+//!
+//! File keys used to be written in the [`KeyVersion::V1`] layout. Such keys
+//! still parse, and a parsed key keeps addressing its object, so files
+//! stored under them stay readable. [`KeyMigration`] moves that content to
+//! the current layout in the background: each object is copied to its
+//! upgraded key, the copy is verified against the source by size and
+//! digest, the files are pointed at the new key, and only then is the
+//! legacy object deleted. An interrupted move is picked up where it stopped
+//! on the next pass.
+
+mod migrate;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use nvisy_nats::object::{FileKey, FilesBucket, ObjectStore};
+use nvisy_postgres::query::{
+    AdminScope, TenantScope, WorkspaceFileRepository, WorkspaceRepository,
+};
+use nvisy_postgres::types::DataRegion;
+use nvisy_postgres::{PgClient, PgConn};
+use uuid::Uuid;
+
+pub use self::migrate::KeyMigration;
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::ResidencyService;
+
+/// Tracing target for legacy key migration.
+const TRACING_TARGET: &str = "nvisy_server::service::key_migration";
+
+/// Default interval between migration passes.
+pub const DEFAULT_KEY_MIGRATION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Legacy key migration configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct KeyMigrationConfig {
+    /// How often legacy keys are migrated.
+    pub interval: Duration,
+}
+
+impl Default for KeyMigrationConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_KEY_MIGRATION_INTERVAL,
+        }
+    }
+}
+
+/// Outcome of a migration batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyMigrationReport {
+    /// Objects moved to the current layout.
+    pub migrated: u64,
+    /// Objects left in place after a failed move.
+    pub failed: u64,
+}
+
+/// Moves file content from legacy object keys to the current layout.
+#[derive(Clone)]
+pub struct KeyMigrationService {
+    config: KeyMigrationConfig,
+    pg_client: PgClient,
+    residency: ResidencyService,
+}
+
+impl KeyMigrationService {
+    /// Creates a new key migration service.
+    pub fn new(
+        config: KeyMigrationConfig,
+        pg_client: PgClient,
+        residency: ResidencyService,
+    ) -> Self {
+        Self {
+            config,
+            pg_client,
+            residency,
+        }
+    }
+
+    /// Returns the key migration configuration.
+    pub fn config(&self) -> &KeyMigrationConfig {
+        &self.config
+    }
+
+    /// Counts the objects still stored under a legacy key.
+    pub async fn remaining(&self) -> Result<u64> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "count legacy object keys").await?;
+        Ok(conn.count_legacy_storage_paths(&admin).await? as u64)
+    }
+
+    /// Moves up to `limit` objects stored under a legacy key.
+    ///
+    /// An object that fails to move is logged and left in place for the next
+    /// pass.
+    pub async fn migrate_batch(&self, limit: i64) -> Result<KeyMigrationReport> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "migrate legacy object keys").await?;
+        let paths = conn.list_legacy_storage_paths(&admin, limit).await?;
+
+        let mut regions = HashMap::new();
+        let mut report = KeyMigrationReport::default();
+        for (workspace_id, path) in &paths {
+            let migrated = self
+                .migrate_path(&mut conn, &mut regions, admin.tenant(*workspace_id), path)
+                .await;
+            match migrated {
+                Ok(()) => report.migrated += 1,
+                Err(err) => {
+                    report.failed += 1;
+                    tracing::warn!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        workspace_id = %workspace_id,
+                        storage_path = %path,
+                        "Failed to migrate legacy object key"
+                    );
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Moves one object to its upgraded key and points its files at it.
+    async fn migrate_path(
+        &self,
+        conn: &mut PgConn,
+        regions: &mut HashMap<Uuid, DataRegion>,
+        scope: TenantScope,
+        path: &str,
+    ) -> Result<()> {
+        let workspace_id = scope.workspace_id();
+        let legacy = FileKey::from_str(path).map_err(|err| {
+            ErrorKind::InternalServerError
+                .with_message("Invalid file storage path")
+                .with_context(err.to_string())
+        })?;
+        let current = legacy.upgraded();
+
+        let region = match regions.get(&workspace_id) {
+            Some(region) => *region,
+            None => {
+                let workspace = conn
+                    .find_workspace_by_id(scope)
+                    .await?
+                    .ok_or_else(|| Error::not_found("workspace"))?;
+                regions.insert(workspace_id, workspace.data_region);
+                workspace.data_region
+            }
+        };
+
+        let store = self
+            .residency
+            .backends(region)?
+            .nats()
+            .object_store::<FilesBucket, FileKey>()
+            .await?;
+
+        // A copy left by an interrupted pass is verified rather than redone.
+        if !store.exists(&current).await? && store.copy(&legacy, &current).await?.is_none() {
+            return Err(ErrorKind::InternalServerError
+                .with_message("Legacy object is missing")
+                .with_context(legacy.to_string()));
+        }
+        let version = verify_copy(&store, &legacy, &current).await?;
+
+        conn.rewrite_storage_path(scope, path, &current.to_string(), &version)
+            .await?;
+
+        if store.exists(&legacy).await? {
+            store.delete(&legacy).await?;
+        }
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            workspace_id = %workspace_id,
+            from = %legacy,
+            to = %current,
+            "Migrated legacy object key"
+        );
+
+        Ok(())
+    }
+}
+
+/// Checks that the copy matches its source by size and digest, returning
+/// the copy's version ID.
+///
+/// A source already gone means a previous pass verified the copy and was
+/// interrupted before the files were updated.
+async fn verify_copy(
+    store: &ObjectStore<FilesBucket, FileKey>,
+    source: &FileKey,
+    copy: &FileKey,
+) -> Result<String> {
+    let copied = store.info(copy).await?.ok_or_else(|| {
+        ErrorKind::InternalServerError
+            .with_message("Copied object is missing")
+            .with_context(copy.to_string())
+    })?;
+    let Some(original) = store.info(source).await? else {
+        return Ok(copied.nuid);
+    };
+
+    if copied.size != original.size || copied.digest != original.digest {
+        // Dropped so the next pass copies again.
+        store.delete(copy).await?;
+        return Err(ErrorKind::InternalServerError
+            .with_message("Copied object does not match its source")
+            .with_context(copy.to_string()));
+    }
+
+    Ok(copied.nuid)
+}
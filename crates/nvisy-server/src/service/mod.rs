@@ -1,26 +1,102 @@
 //! Application state and dependency injection.
 
+mod audit;
+mod cache;
+mod credential_rotation;
 pub mod crypto;
+mod document;
 pub mod engine;
+mod garbage;
 mod health;
+mod inbound;
+mod key_migration;
+mod oidc;
+mod operation;
+mod privacy;
+pub mod rbac;
+mod residency;
+mod retention;
+pub mod scim;
+mod secrets;
 mod security;
+mod storage_cost;
 mod webhook;
+mod worker;
 
 use std::sync::Arc;
 
-use nvisy_core::health::HealthCheck;
+use nvisy_core::clock::Clock;
+use nvisy_core::health::{HealthCheck, HealthDependency};
+use nvisy_core::id::IdGenerator;
 use nvisy_nats::{NatsClient, NatsConfig};
 use nvisy_postgres::{PgClient, PgClientMigrationExt, PgConfig};
+#[cfg(feature = "redis")]
+use nvisy_redis::{RedisClient, RedisConfig};
 use nvisy_webhook::WebhookService;
 
+pub use crate::service::audit::{
+    AuditConfig, AuditLog, AuditRetention, ChainBreak, ChainBreakReason, ChainVerification,
+    ChainVerifier,
+};
+pub use crate::service::cache::{Cache, Caches};
+pub use crate::service::credential_rotation::{
+    CredentialRotation, CredentialRotationConfig, CredentialRotationReport,
+    CredentialRotationService,
+};
 pub(crate) use crate::service::crypto::HashingReader;
-pub use crate::service::crypto::{CryptoConfig, CryptoService};
+pub use crate::service::crypto::{
+    ContentKey, CredentialVault, CryptoConfig, CryptoPolicy, CryptoService, KeyManagement,
+    KmsProvider,
+};
+pub use crate::service::document::{
+    DiffLine, DocumentPasswords, DocumentQuality, LineChange, PreflightReport, PreflightViolation,
+    ProcessingTier, StructuralDiff, TextDiff, TextDiffLimit, ValueChange, ValueChangeKind,
+    Watermark, WatermarkError, WatermarkMode, count_pages, select_pages, supports_page_selection,
+};
+pub(crate) use crate::service::document::{ProtectionProbe, ProtectionReader};
 pub use crate::service::engine::{EngineConfig, EngineService};
-pub use crate::service::health::{HealthCache, HealthConfig};
+pub use crate::service::garbage::{
+    GarbageCollectionConfig, GarbageCollectionReport, GarbageCollectionService, GarbageCollector,
+};
+pub use crate::service::health::{DependentCheck, HealthCache, HealthConfig};
+pub use crate::service::inbound::{
+    InboundDelivery, InboundError, InboundReceipt, InboundReceiver, InboundResult,
+};
+pub use crate::service::key_migration::{
+    KeyMigration, KeyMigrationConfig, KeyMigrationReport, KeyMigrationService,
+};
+pub use crate::service::oidc::{
+    IdTokenClaims, OidcConfig, OidcError, OidcLoginResult, OidcResult, OidcService, Provisioning,
+};
+pub use crate::service::operation::{
+    DownloadLink, OPERATION_POLL_INTERVAL, OperationArtifact, OperationCleanup, OperationConfig,
+    OperationHandle, OperationOutput, OperationRunner,
+};
+pub use crate::service::privacy::{PrivacyCharge, PrivacyConfig, PrivacyService};
+pub use crate::service::rbac::{Policy, PolicyConfig};
+pub use crate::service::residency::{
+    RegionBackends, ResidencyConfig, ResidencyError, ResidencyService,
+};
+pub use crate::service::retention::{
+    RetentionConfig, RetentionPurge, RetentionReport, RetentionService,
+};
+pub use crate::service::scim::ScimError;
+pub use crate::service::secrets::{
+    EnvSecrets, SECRET_REFERENCE_PREFIX, SecretValue, SecretsBackend, SecretsConfig, SecretsError,
+    SecretsProvider, SecretsResult, SecretsService, VaultSecrets,
+};
 pub use crate::service::security::{
-    PasswordService, SessionKeys, SessionKeysConfig, UserAgentParser,
+    API_KEY_PREFIX, ApiKeyService, DEFAULT_ROTATION_GRACE, IssuedApiKey, IssuedShareToken,
+    PasswordService, SHARE_TOKEN_PREFIX, SessionKeys, SessionKeysConfig, UserAgentParser,
 };
-pub use crate::service::webhook::{WebhookEmitter, WebhookWorker};
+pub use crate::service::storage_cost::{
+    StorageCostConfig, StorageCostReporter, StorageCostService, StorageSampleReport,
+};
+pub use crate::service::webhook::{
+    ChangeEventBridge, InviteAccepted, InviteCreated, MemberRoleChanged, MembershipSource,
+    PayloadFilter, SettingChange, WebhookEmitter, WebhookWorker, WorkspaceSettingsChanged,
+};
+pub use crate::service::worker::{Heartbeat, WatchdogConfig, WorkerHandles, WorkerStatus};
 use crate::{Error, Result};
 
 /// Application state.
@@ -36,15 +112,36 @@ pub struct ServiceState {
     pub nats: NatsClient,
     pub webhook: WebhookService,
 
+    // Time and identifiers:
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+
     // Security services:
     pub crypto: CryptoService,
+    pub secrets: SecretsService,
 
     // Redaction engine:
     pub engine: EngineService,
 
+    // Region-pinned storage and inference:
+    pub residency: ResidencyService,
+    pub retention: RetentionService,
+    pub key_migration: KeyMigrationService,
+    pub credential_rotation: CredentialRotationService,
+    pub garbage: GarbageCollectionService,
+    pub storage_costs: StorageCostService,
+
     // Internal services:
+    pub api_keys: ApiKeyService,
+    pub audit: AuditLog,
+    pub document_passwords: DocumentPasswords,
     pub health_cache: HealthCache,
+    pub inbound: InboundReceiver,
+    pub oidc: OidcService,
+    pub operations: OperationRunner,
     pub password: PasswordService,
+    pub policy: Policy,
+    pub privacy: PrivacyService,
     pub session_keys: SessionKeys,
     pub user_agent_parser: UserAgentParser,
     pub webhook_emitter: WebhookEmitter,
@@ -54,40 +151,170 @@ impl ServiceState {
     /// Initializes application state from configuration.
     ///
     /// Connects to all external services and loads required resources.
+    /// Timestamps and server-assigned IDs come from `clock` and `ids`.
     pub async fn from_config(
         postgres_config: PgConfig,
-        nats_config: NatsConfig,
+        mut nats_config: NatsConfig,
+        #[cfg(feature = "redis")] mut redis_config: RedisConfig,
         session_config: SessionKeysConfig,
+        audit_config: AuditConfig,
         crypto_config: CryptoConfig,
         engine_config: EngineConfig,
         health_config: HealthConfig,
+        oidc_config: OidcConfig,
+        operation_config: OperationConfig,
+        policy_config: PolicyConfig,
+        privacy_config: PrivacyConfig,
+        residency_config: ResidencyConfig,
+        retention_config: RetentionConfig,
+        key_migration_config: KeyMigrationConfig,
+        credential_rotation_config: CredentialRotationConfig,
+        garbage_config: GarbageCollectionConfig,
+        storage_cost_config: StorageCostConfig,
+        secrets_config: SecretsConfig,
         webhook_service: WebhookService,
+        clock: Arc<dyn Clock>,
+        ids: Arc<dyn IdGenerator>,
     ) -> Result<Self> {
+        let secrets = SecretsService::from_config(&secrets_config).await?;
+        nats_config.nats_token = secrets
+            .resolve_str(&nats_config.nats_token)
+            .await
+            .map_err(|e| Error::config("Failed to resolve the NATS token").with_source(e))?;
+
+        let crypto = CryptoService::from_config(&crypto_config).await?;
         let postgres_client = connect_postgres(postgres_config).await?;
+        let nats_config = nats_config.with_crypto_provider(crypto.provider().clone());
         let nats_client = connect_nats(nats_config).await?;
+        #[cfg(not(feature = "redis"))]
+        let caches = Caches::nats(&nats_client).await?;
+        #[cfg(feature = "redis")]
+        let (caches, redis_client) = {
+            if let Some(password) = &redis_config.redis_password {
+                let password = secrets.resolve_str(password).await.map_err(|e| {
+                    Error::config("Failed to resolve the Redis password").with_source(e)
+                })?;
+                redis_config.redis_password = Some(password);
+            }
+            let redis_client = connect_redis(redis_config).await?;
+            let caches = Caches::redis(&redis_client).await.map_err(|e| {
+                Error::external("Redis", "Failed to open the Redis caches").with_source(e)
+            })?;
+            (caches, redis_client)
+        };
 
-        let crypto = CryptoService::from_config(&crypto_config).await?;
-        let engine = EngineService::from_config(engine_config).await?;
+        let engine = EngineService::from_config(engine_config, &secrets).await?;
+        let residency = ResidencyService::from_config(
+            &residency_config,
+            RegionBackends::new(nats_client.clone(), engine.clone()),
+            &secrets,
+        )
+        .await?;
+        residency.validate_regions_in_use(&postgres_client).await?;
+        let retention =
+            RetentionService::new(retention_config, postgres_client.clone(), residency.clone());
+        let key_migration = KeyMigrationService::new(
+            key_migration_config,
+            postgres_client.clone(),
+            residency.clone(),
+        );
+        let credential_rotation = CredentialRotationService::new(
+            credential_rotation_config,
+            postgres_client.clone(),
+            crypto.clone(),
+        );
+        let garbage = GarbageCollectionService::new(
+            garbage_config,
+            postgres_client.clone(),
+            residency.clone(),
+        );
+        let storage_costs = StorageCostService::new(
+            storage_cost_config,
+            postgres_client.clone(),
+            residency.clone(),
+        );
         let session_keys = SessionKeys::from_config(&session_config).await?;
-        let webhook_emitter =
-            WebhookEmitter::new(postgres_client.clone(), nats_client.clone(), crypto.clone());
+        let privacy = PrivacyService::from_config(&privacy_config, caches.privacy_budgets)?;
+        let api_keys = ApiKeyService::new(caches.api_keys, crypto.clone());
+        let oidc =
+            OidcService::from_config(&oidc_config, caches.oidc_logins, crypto.clone()).await?;
+        // Always on NATS, in memory only: see `DocumentPasswords`.
+        let document_passwords = DocumentPasswords::new(
+            Arc::new(nats_client.document_password_store().await?),
+            crypto.clone(),
+        );
+        let webhook_emitter = WebhookEmitter::new(
+            postgres_client.clone(),
+            nats_client.clone(),
+            crypto.clone(),
+            secrets.clone(),
+        );
+        let inbound = InboundReceiver::new(
+            postgres_client.clone(),
+            nats_client.clone(),
+            crypto.clone(),
+            clock.clone(),
+        );
+        let operations = OperationRunner::new(
+            operation_config,
+            postgres_client.clone(),
+            residency.clone(),
+            crypto.clone(),
+            webhook_emitter.clone(),
+            clock.clone(),
+            ids.clone(),
+        );
+
+        let audit = AuditLog::new(
+            audit_config,
+            postgres_client.clone(),
+            crypto.clone(),
+            clock.clone(),
+            ids.clone(),
+        );
+        let policy = Policy::new(postgres_client.clone(), policy_config);
 
-        let health_checkers: Vec<Arc<dyn HealthCheck>> = vec![
+        let mut health_checkers: Vec<Arc<dyn HealthCheck>> = vec![
             Arc::new(postgres_client.clone()),
             Arc::new(nats_client.clone()),
-            Arc::new(webhook_service.clone()),
+            // Deliveries are queued on NATS before the webhook client sends them.
+            Arc::new(DependentCheck::new(
+                Arc::new(webhook_service.clone()),
+                vec![HealthDependency::required("nats")],
+            )),
         ];
+        #[cfg(feature = "redis")]
+        health_checkers.push(Arc::new(redis_client));
+        health_checkers.extend(residency.health_checks());
 
         let service_state = Self {
             postgres: postgres_client,
             nats: nats_client,
             webhook: webhook_service,
 
+            clock,
+            ids,
+
             crypto,
+            secrets,
             engine,
+            residency,
+            retention,
+            key_migration,
+            credential_rotation,
+            garbage,
+            storage_costs,
 
+            api_keys,
+            audit,
+            document_passwords,
             health_cache: HealthCache::new(&health_config, health_checkers),
+            inbound,
+            oidc,
+            operations,
             password: PasswordService::new(),
+            policy,
+            privacy,
             session_keys,
             user_agent_parser: UserAgentParser::new(),
             webhook_emitter,
@@ -110,11 +337,25 @@ async fn connect_postgres(config: PgConfig) -> Result<PgClient> {
     Ok(pg_client)
 }
 
-/// Connects to the NATS server.
+/// Connects to the NATS server and applies the object bucket TTLs.
 async fn connect_nats(config: NatsConfig) -> Result<NatsClient> {
-    NatsClient::connect(config)
+    let nats_client = NatsClient::connect(config)
+        .await
+        .map_err(|e| Error::external("NATS", "Failed to connect to NATS").with_source(e))?;
+
+    nats_client.sync_object_bucket_ttls().await.map_err(|e| {
+        Error::external("NATS", "Failed to apply object bucket TTLs").with_source(e)
+    })?;
+
+    Ok(nats_client)
+}
+
+/// Connects to the Redis server or cluster.
+#[cfg(feature = "redis")]
+async fn connect_redis(config: RedisConfig) -> Result<RedisClient> {
+    RedisClient::connect(config)
         .await
-        .map_err(|e| Error::external("NATS", "Failed to connect to NATS").with_source(e))
+        .map_err(|e| Error::external("Redis", "Failed to connect to Redis").with_source(e))
 }
 
 macro_rules! impl_di {
@@ -134,12 +375,33 @@ impl_di!(
     webhook: WebhookService
 );
 
+// Time and identifiers:
+impl_di!(
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>
+);
+
 // Internal services:
 impl_di!(
+    api_keys: ApiKeyService,
+    audit: AuditLog,
+    document_passwords: DocumentPasswords,
     crypto: CryptoService,
+    secrets: SecretsService,
     engine: EngineService,
+    residency: ResidencyService,
+    retention: RetentionService,
+    key_migration: KeyMigrationService,
+    credential_rotation: CredentialRotationService,
+    garbage: GarbageCollectionService,
+    storage_costs: StorageCostService,
     health_cache: HealthCache,
+    inbound: InboundReceiver,
+    oidc: OidcService,
+    operations: OperationRunner,
     password: PasswordService,
+    policy: Policy,
+    privacy: PrivacyService,
     session_keys: SessionKeys,
     user_agent_parser: UserAgentParser,
     webhook_emitter: WebhookEmitter
@@ -0,0 +1,30 @@
+//! Single sign-on error types.
+
+use thiserror::Error;
+
+/// Result type for single sign-on operations.
+pub type OidcResult<T> = Result<T, OidcError>;
+
+/// Errors that can occur during a single sign-on login.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OidcError {
+    /// No identity provider is configured.
+    #[error("single sign-on is not configured")]
+    Disabled,
+    /// The callback's `state` matches no pending login, or it expired.
+    #[error("unknown or expired login state")]
+    UnknownState,
+    /// The provider reported an error instead of an authorization code.
+    #[error("identity provider denied the login: {0}")]
+    Denied(String),
+    /// The provider could not be reached or returned an unexpected response.
+    #[error("identity provider request failed: {0}")]
+    Provider(String),
+    /// The ID token failed validation.
+    #[error("invalid ID token: {0}")]
+    InvalidIdToken(String),
+    /// The user has no account and provisioning is disabled, or the token
+    /// lacks the claims needed to provision one.
+    #[error("no account is available for this identity: {0}")]
+    NotProvisioned(&'static str),
+}
@@ -0,0 +1,231 @@
+//! Single sign-on through an external OpenID Connect provider.
+//!
+//! Users sign in with the authorization-code flow (with PKCE): the login
+//! handler redirects to the provider, and the callback exchanges the code,
+//! validates the ID token against the provider's published keys, and issues a
+//! regular session token. Accounts are provisioned on first login and, when
+//! configured, added to a workspace with a role derived from the user's
+//! provider groups.
+//!
+//! The provider is declared in a JSON file; endpoints are read from its
+//! discovery document at startup:
+//!
+//! ```json
+//! {
+//!   "issuerUrl": "https://login.example.com/realms/acme",
+//!   "clientId": "nvisy",
+//!   "clientSecret": "...",
+//!   "redirectUrl": "https://app.example.com/auth/sso/callback",
+//!   "postLogoutRedirectUrl": "https://app.example.com/",
+//!   "provisioning": {
+//!     "workspaceId": "0190f6a2-...",
+//!     "defaultRole": "guest",
+//!     "groupsClaim": "groups",
+//!     "groupRoles": { "redaction-leads": "admin", "analysts": "member" }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use nvisy_postgres::types::WorkspaceRole;
+use serde::Deserialize;
+use url::Url;
+use uuid::Uuid;
+
+mod error;
+mod provider;
+mod service;
+
+pub use error::{OidcError, OidcResult};
+pub use provider::IdTokenClaims;
+pub use service::{OidcLoginResult, OidcService};
+
+use crate::{Error, Result};
+
+/// Tracing target for single sign-on operations.
+const TRACING_TARGET: &str = "nvisy_server::service::oidc";
+
+/// Single sign-on configuration.
+#[derive(Debug, Clone, Default)]
+#[must_use = "config does nothing unless you use it"]
+pub struct OidcConfig {
+    /// Optional path to a JSON file declaring the identity provider.
+    ///
+    /// Absent means single sign-on is disabled.
+    pub config_path: Option<PathBuf>,
+}
+
+/// The identity provider, as loaded from the OIDC config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProviderConfig {
+    /// Issuer URL; the discovery document is served beneath it.
+    issuer_url: Url,
+    /// Client identifier registered with the provider.
+    client_id: String,
+    /// Client secret; absent for public clients relying on PKCE alone.
+    #[serde(default)]
+    client_secret: Option<String>,
+    /// Callback URL registered with the provider.
+    redirect_url: Url,
+    /// Where the provider sends users after signing out.
+    #[serde(default)]
+    post_logout_redirect_url: Option<Url>,
+    /// Scopes requested in addition to `openid`.
+    #[serde(default = "default_scopes")]
+    scopes: Vec<String>,
+    /// Account and membership provisioning rules.
+    #[serde(default)]
+    provisioning: Provisioning,
+}
+
+/// How accounts and memberships are created for provider users.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provisioning {
+    /// Whether unknown users get an account on first login.
+    #[serde(default = "default_true")]
+    pub create_accounts: bool,
+    /// Whether a first login may link to an existing account with the same
+    /// email address, provided the provider marks the email as verified.
+    #[serde(default = "default_true")]
+    pub link_by_email: bool,
+    /// Workspace provider users become members of.
+    #[serde(default)]
+    pub workspace_id: Option<Uuid>,
+    /// Role granted when none of the user's groups is mapped; `None` skips
+    /// membership for unmapped users.
+    #[serde(default)]
+    pub default_role: Option<WorkspaceRole>,
+    /// ID token claim carrying the user's group names.
+    #[serde(default = "default_groups_claim")]
+    pub groups_claim: String,
+    /// Role granted per provider group; the highest matching role wins.
+    #[serde(default)]
+    pub group_roles: HashMap<String, WorkspaceRole>,
+}
+
+impl Provisioning {
+    /// Returns the workspace role for a user in `groups`, if any.
+    pub fn role_for<'a>(&self, groups: impl IntoIterator<Item = &'a str>) -> Option<WorkspaceRole> {
+        groups
+            .into_iter()
+            .filter_map(|group| self.group_roles.get(group).copied())
+            .max()
+            .or(self.default_role)
+    }
+}
+
+impl Default for Provisioning {
+    fn default() -> Self {
+        Self {
+            create_accounts: true,
+            link_by_email: true,
+            workspace_id: None,
+            default_role: None,
+            groups_claim: default_groups_claim(),
+            group_roles: HashMap::new(),
+        }
+    }
+}
+
+fn default_scopes() -> Vec<String> {
+    vec!["email".to_owned(), "profile".to_owned()]
+}
+
+fn default_groups_claim() -> String {
+    "groups".to_owned()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Reads and parses the identity provider from a JSON config file.
+async fn load_provider(path: &Path) -> Result<ProviderConfig> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| Error::config("Failed to read OIDC config file").with_source(e))?;
+
+    let provider: ProviderConfig = serde_json::from_slice(&bytes)
+        .map_err(|e| Error::config("Failed to parse OIDC config file").with_source(e))?;
+
+    if provider.client_id.trim().is_empty() {
+        return Err(Error::config("OIDC client id must not be empty"));
+    }
+
+    // Ownership is never handed out by group membership.
+    let provisioning = &provider.provisioning;
+    if provisioning.default_role == Some(WorkspaceRole::Owner)
+        || provisioning
+            .group_roles
+            .values()
+            .any(|role| role.is_owner())
+    {
+        return Err(Error::config(
+            "OIDC provisioning cannot grant the owner role",
+        ));
+    }
+
+    if provisioning.workspace_id.is_none()
+        && (provisioning.default_role.is_some() || !provisioning.group_roles.is_empty())
+    {
+        return Err(Error::config(
+            "OIDC provisioning maps roles but names no workspace",
+        ));
+    }
+
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provider_file() {
+        let json = r#"{
+            "issuerUrl": "https://login.example.com",
+            "clientId": "nvisy",
+            "redirectUrl": "https://app.example.com/callback"
+        }"#;
+
+        let provider: ProviderConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(provider.client_id, "nvisy");
+        assert_eq!(provider.client_secret, None);
+        assert_eq!(provider.scopes, vec!["email", "profile"]);
+        assert!(provider.provisioning.create_accounts);
+        assert_eq!(provider.provisioning.groups_claim, "groups");
+    }
+
+    #[test]
+    fn test_role_for_groups() {
+        let provisioning = Provisioning {
+            workspace_id: Some(Uuid::nil()),
+            default_role: Some(WorkspaceRole::Guest),
+            group_roles: HashMap::from([
+                ("leads".to_owned(), WorkspaceRole::Admin),
+                ("analysts".to_owned(), WorkspaceRole::Member),
+            ]),
+            ..Provisioning::default()
+        };
+
+        assert_eq!(
+            provisioning.role_for(["analysts", "leads"]),
+            Some(WorkspaceRole::Admin)
+        );
+        assert_eq!(
+            provisioning.role_for(["analysts"]),
+            Some(WorkspaceRole::Member)
+        );
+        assert_eq!(provisioning.role_for([]), Some(WorkspaceRole::Guest));
+
+        let no_default = Provisioning {
+            default_role: None,
+            ..provisioning
+        };
+        assert_eq!(no_default.role_for(["unmapped"]), None);
+    }
+}
@@ -0,0 +1,364 @@
+//! OpenID Connect provider client: discovery, code exchange and ID token
+//! validation.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use url::Url;
+
+use super::{OidcError, OidcResult, ProviderConfig, TRACING_TARGET};
+use crate::{Error, Result};
+
+/// Timeout for every request to the provider.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Clock skew tolerated when checking ID token timestamps, in seconds.
+const CLOCK_LEEWAY_SECS: u64 = 60;
+
+/// Endpoints published in the provider's discovery document.
+#[derive(Debug, Clone, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: Url,
+    token_endpoint: Url,
+    jwks_uri: Url,
+    #[serde(default)]
+    end_session_endpoint: Option<Url>,
+}
+
+/// Token endpoint response; only the ID token is used.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Claims read from a validated ID token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    /// Issuer that asserted the identity.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Stable identifier of the user at the issuer.
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Nonce echoed from the authorization request.
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// Email address of the user.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Whether the provider verified the email address.
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    /// Full name of the user.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Shorthand name the user prefers.
+    #[serde(default)]
+    pub preferred_username: Option<String>,
+    /// Remaining claims, including the configured groups claim.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl IdTokenClaims {
+    /// Returns the email address if the provider vouches for it.
+    pub fn verified_email(&self) -> Option<&str> {
+        self.email
+            .as_deref()
+            .filter(|_| self.email_verified == Some(true))
+    }
+
+    /// Returns the group names carried in `claim`.
+    ///
+    /// Accepts either an array of strings or a single string.
+    pub fn groups(&self, claim: &str) -> Vec<&str> {
+        match self.extra.get(claim) {
+            Some(serde_json::Value::Array(values)) => {
+                values.iter().filter_map(|value| value.as_str()).collect()
+            }
+            Some(serde_json::Value::String(value)) => vec![value.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Client for one OpenID Connect provider.
+pub(super) struct OidcProvider {
+    http: reqwest::Client,
+    discovery: Discovery,
+    client_id: String,
+    client_secret: Option<String>,
+    redirect_url: Url,
+    post_logout_redirect_url: Option<Url>,
+    scopes: Vec<String>,
+    jwks: RwLock<JwkSet>,
+}
+
+impl OidcProvider {
+    /// Fetches the provider's discovery document and signing keys.
+    pub async fn discover(config: ProviderConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| Error::config("Failed to build OIDC HTTP client").with_source(e))?;
+
+        let discovery: Discovery = fetch_json(&http, discovery_url(&config.issuer_url))
+            .await
+            .map_err(|e| {
+                Error::external("oidc", "Failed to load discovery document").with_source(e)
+            })?;
+
+        if normalize_issuer(&discovery.issuer) != normalize_issuer(config.issuer_url.as_str()) {
+            return Err(Error::config(format!(
+                "OIDC discovery issuer '{}' does not match the configured issuer",
+                discovery.issuer
+            )));
+        }
+
+        let jwks: JwkSet = fetch_json(&http, discovery.jwks_uri.clone())
+            .await
+            .map_err(|e| Error::external("oidc", "Failed to load signing keys").with_source(e))?;
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            issuer = %discovery.issuer,
+            keys = jwks.keys.len(),
+            "Identity provider discovered"
+        );
+
+        Ok(Self {
+            http,
+            discovery,
+            client_id: config.client_id,
+            client_secret: config.client_secret,
+            redirect_url: config.redirect_url,
+            post_logout_redirect_url: config.post_logout_redirect_url,
+            scopes: config.scopes,
+            jwks: RwLock::new(jwks),
+        })
+    }
+
+    /// Returns the issuer identifier.
+    pub fn issuer(&self) -> &str {
+        &self.discovery.issuer
+    }
+
+    /// Builds the URL the user is sent to for authentication.
+    pub fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Url {
+        let scope = std::iter::once("openid")
+            .chain(
+                self.scopes
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|s| *s != "openid"),
+            )
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut url = self.discovery.authorization_endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", self.redirect_url.as_str())
+            .append_pair("scope", &scope)
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", "S256");
+        url
+    }
+
+    /// Builds the provider's sign-out URL, if it supports RP-initiated logout.
+    pub fn end_session_url(&self) -> Option<Url> {
+        let mut url = self.discovery.end_session_endpoint.clone()?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("client_id", &self.client_id);
+            if let Some(redirect) = &self.post_logout_redirect_url {
+                query.append_pair("post_logout_redirect_uri", redirect.as_str());
+            }
+        }
+        Some(url)
+    }
+
+    /// Exchanges an authorization code for the user's validated ID token.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> OidcResult<IdTokenClaims> {
+        let form = [
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.redirect_url.as_str()),
+            ("client_id", &self.client_id),
+            ("code_verifier", code_verifier),
+        ];
+
+        let mut request = self
+            .http
+            .post(self.discovery.token_endpoint.clone())
+            .form(&form);
+        if let Some(secret) = &self.client_secret {
+            request = request.basic_auth(&self.client_id, Some(secret));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(OidcError::Provider(format!(
+                "token endpoint returned {}",
+                response.status()
+            )));
+        }
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+
+        self.validate_id_token(&tokens.id_token, nonce).await
+    }
+
+    /// Verifies an ID token's signature, issuer, audience, lifetime and nonce.
+    async fn validate_id_token(&self, id_token: &str, nonce: &str) -> OidcResult<IdTokenClaims> {
+        let header = jsonwebtoken::decode_header(id_token)
+            .map_err(|e| OidcError::InvalidIdToken(e.to_string()))?;
+
+        // Symmetric algorithms would make the client secret a signing key.
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(OidcError::InvalidIdToken(format!(
+                "unsupported signing algorithm {:?}",
+                header.alg
+            )));
+        }
+
+        let key = self.decoding_key(header.kid.as_deref()).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.discovery.issuer]);
+        validation.set_audience(&[&self.client_id]);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+        validation.leeway = CLOCK_LEEWAY_SECS;
+
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| OidcError::InvalidIdToken(e.to_string()))?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OidcError::InvalidIdToken("nonce mismatch".to_owned()));
+        }
+
+        Ok(claims)
+    }
+
+    /// Finds the signing key for `kid`, refetching the key set once when the
+    /// provider has rotated to a key not seen yet.
+    async fn decoding_key(&self, kid: Option<&str>) -> OidcResult<DecodingKey> {
+        if let Some(key) = Self::find_key(&*self.jwks.read().await, kid)? {
+            return Ok(key);
+        }
+
+        let jwks: JwkSet = fetch_json(&self.http, self.discovery.jwks_uri.clone())
+            .await
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+        tracing::info!(
+            target: TRACING_TARGET,
+            keys = jwks.keys.len(),
+            "Refreshed identity provider signing keys"
+        );
+
+        let key = Self::find_key(&jwks, kid)?;
+        *self.jwks.write().await = jwks;
+        key.ok_or_else(|| OidcError::InvalidIdToken("unknown signing key".to_owned()))
+    }
+
+    fn find_key(jwks: &JwkSet, kid: Option<&str>) -> OidcResult<Option<DecodingKey>> {
+        let jwk = match kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        };
+
+        jwk.map(|jwk| {
+            DecodingKey::from_jwk(jwk).map_err(|e| OidcError::InvalidIdToken(e.to_string()))
+        })
+        .transpose()
+    }
+}
+
+/// Fetches and decodes a JSON document from the provider.
+async fn fetch_json<T: serde::de::DeserializeOwned>(
+    http: &reqwest::Client,
+    url: Url,
+) -> reqwest::Result<T> {
+    http.get(url).send().await?.error_for_status()?.json().await
+}
+
+/// Returns the discovery document URL beneath an issuer.
+///
+/// Issuers may carry a path (e.g. a realm), which the well-known suffix is
+/// appended to rather than replacing.
+fn discovery_url(issuer: &Url) -> Url {
+    let mut url = issuer.clone();
+    let path = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.path().trim_end_matches('/')
+    );
+    url.set_path(&path);
+    url.set_query(None);
+    url
+}
+
+/// Compares issuers without regard to a trailing slash.
+fn normalize_issuer(issuer: &str) -> &str {
+    issuer.trim_end_matches('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_url_keeps_issuer_path() {
+        let issuer = Url::parse("https://login.example.com/realms/acme/").unwrap();
+        assert_eq!(
+            discovery_url(&issuer).as_str(),
+            "https://login.example.com/realms/acme/.well-known/openid-configuration"
+        );
+
+        let issuer = Url::parse("https://login.example.com").unwrap();
+        assert_eq!(
+            discovery_url(&issuer).as_str(),
+            "https://login.example.com/.well-known/openid-configuration"
+        );
+    }
+
+    #[test]
+    fn test_id_token_groups_claim() {
+        let claims: IdTokenClaims = serde_json::from_value(serde_json::json!({
+            "iss": "https://login.example.com",
+            "sub": "user-1",
+            "email": "ada@example.com",
+            "email_verified": false,
+            "groups": ["analysts", 7, "leads"],
+            "role": "admins"
+        }))
+        .unwrap();
+
+        assert_eq!(claims.groups("groups"), vec!["analysts", "leads"]);
+        assert_eq!(claims.groups("role"), vec!["admins"]);
+        assert!(claims.groups("missing").is_empty());
+        assert_eq!(claims.verified_email(), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! Single sign-on login flow.
+
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jiff::Timestamp;
+use nvisy_nats::kv::{DigestKey, OidcLogin};
+use url::Url;
+
+use super::provider::{IdTokenClaims, OidcProvider};
+use super::{OidcConfig, OidcError, OidcResult, Provisioning, TRACING_TARGET, load_provider};
+use crate::Result;
+use crate::handler::Result as HandlerResult;
+use crate::service::{Cache, CryptoService};
+
+/// Outcome of a completed provider callback.
+#[derive(Debug, Clone)]
+pub struct OidcLoginResult {
+    /// Claims of the validated ID token.
+    pub claims: IdTokenClaims,
+    /// Client-supplied location to return to, as given at login.
+    pub redirect_to: Option<String>,
+    /// Whether the session should be long-lived.
+    pub remember_me: bool,
+}
+
+/// Drives the authorization-code flow against the configured provider.
+///
+/// Cheap to clone. When no provider is configured every login operation
+/// fails with [`OidcError::Disabled`].
+#[derive(Clone)]
+pub struct OidcService {
+    inner: Option<Arc<OidcInner>>,
+}
+
+struct OidcInner {
+    provider: OidcProvider,
+    provisioning: Provisioning,
+    logins: Cache<DigestKey, OidcLogin>,
+    crypto: CryptoService,
+}
+
+impl OidcService {
+    /// Discovers the configured provider, or returns a disabled service when
+    /// no config file is given.
+    pub async fn from_config(
+        config: &OidcConfig,
+        logins: Cache<DigestKey, OidcLogin>,
+        crypto: CryptoService,
+    ) -> Result<Self> {
+        let Some(path) = &config.config_path else {
+            tracing::debug!(target: TRACING_TARGET, "Single sign-on disabled");
+            return Ok(Self { inner: None });
+        };
+
+        let mut provider_config = load_provider(path).await?;
+        let provisioning = std::mem::take(&mut provider_config.provisioning);
+        let provider = OidcProvider::discover(provider_config).await?;
+
+        Ok(Self {
+            inner: Some(Arc::new(OidcInner {
+                provider,
+                provisioning,
+                logins,
+                crypto,
+            })),
+        })
+    }
+
+    /// Returns whether a provider is configured.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Returns the provider's issuer identifier.
+    pub fn issuer(&self) -> OidcResult<&str> {
+        Ok(self.inner()?.provider.issuer())
+    }
+
+    /// Returns the account and membership provisioning rules.
+    pub fn provisioning(&self) -> OidcResult<&Provisioning> {
+        Ok(&self.inner()?.provisioning)
+    }
+
+    /// Starts a login and returns the provider URL to send the user to.
+    ///
+    /// The state, nonce and PKCE verifier are kept in the cache store until the
+    /// callback redeems them.
+    pub async fn begin_login(
+        &self,
+        redirect_to: Option<String>,
+        remember_me: bool,
+    ) -> HandlerResult<Url> {
+        let inner = self.inner()?;
+
+        let state = inner.crypto.generate_secret()?;
+        let nonce = inner.crypto.generate_secret()?;
+        let code_verifier = inner.crypto.generate_secret()?;
+        let code_challenge = URL_SAFE_NO_PAD.encode(inner.crypto.sha256(code_verifier.as_bytes()));
+
+        let login = OidcLogin {
+            nonce,
+            code_verifier,
+            redirect_to,
+            remember_me,
+            started_at: Timestamp::now(),
+        };
+
+        let key: DigestKey = state.parse()?;
+        inner.logins.put(&key, &login).await?;
+
+        Ok(inner
+            .provider
+            .authorization_url(&state, &login.nonce, &code_challenge))
+    }
+
+    /// Redeems a provider callback and returns the validated identity.
+    ///
+    /// Each `state` can be redeemed once; a replayed or expired callback
+    /// fails with [`OidcError::UnknownState`].
+    pub async fn complete_login(&self, state: &str, code: &str) -> HandlerResult<OidcLoginResult> {
+        let inner = self.inner()?;
+
+        let key: DigestKey = state.parse().map_err(|_| OidcError::UnknownState)?;
+        let login = inner
+            .logins
+            .get_value(&key)
+            .await?
+            .ok_or(OidcError::UnknownState)?;
+        inner.logins.delete(&key).await?;
+
+        let claims = inner
+            .provider
+            .exchange_code(code, &login.code_verifier, &login.nonce)
+            .await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            issuer = %claims.issuer,
+            subject = %claims.subject,
+            "ID token validated"
+        );
+
+        Ok(OidcLoginResult {
+            claims,
+            redirect_to: login.redirect_to,
+            remember_me: login.remember_me,
+        })
+    }
+
+    /// Returns the provider's sign-out URL, if it supports one.
+    pub fn end_session_url(&self) -> Option<Url> {
+        self.inner.as_ref()?.provider.end_session_url()
+    }
+
+    fn inner(&self) -> OidcResult<&OidcInner> {
+        self.inner.as_deref().ok_or(OidcError::Disabled)
+    }
+}
@@ -0,0 +1,65 @@
+//! Job outputs and artifact download links.
+
+use jiff::Timestamp;
+use serde_json::Value;
+
+/// What a finished job hands back to its operation.
+#[derive(Debug, Clone)]
+pub struct OperationOutput {
+    /// Job-specific result recorded on the operation.
+    pub result: Value,
+    /// File produced by the job, if any.
+    pub artifact: Option<OperationArtifact>,
+}
+
+impl OperationOutput {
+    /// Creates an output carrying only a result.
+    pub fn new(result: Value) -> Self {
+        Self {
+            result,
+            artifact: None,
+        }
+    }
+
+    /// Attaches a file for download.
+    pub fn with_artifact(mut self, artifact: OperationArtifact) -> Self {
+        self.artifact = Some(artifact);
+        self
+    }
+}
+
+impl From<Value> for OperationOutput {
+    fn from(result: Value) -> Self {
+        Self::new(result)
+    }
+}
+
+/// A file produced by a job, stored for download once the job succeeds.
+#[derive(Clone)]
+pub struct OperationArtifact {
+    /// File name the artifact downloads as.
+    pub name: String,
+    /// Media type of the content.
+    pub content_type: String,
+    /// The file content.
+    pub content: Vec<u8>,
+}
+
+impl std::fmt::Debug for OperationArtifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperationArtifact")
+            .field("name", &self.name)
+            .field("content_type", &self.content_type)
+            .field("size", &self.content.len())
+            .finish()
+    }
+}
+
+/// A signed, expiring link to an operation's artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadLink {
+    /// Path of the download, including its signature.
+    pub url: String,
+    /// When the link stops working.
+    pub expires_at: Timestamp,
+}
@@ -0,0 +1,95 @@
+//! Removal of operations past their retention period.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::OperationRunner;
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for operation cleanup.
+const TRACING_TARGET: &str = "nvisy_server::worker::operation_cleanup";
+
+/// Maximum number of operations removed per batch.
+const BATCH_SIZE: i64 = 100;
+
+/// Periodically removes expired operations and their artifacts.
+pub struct OperationCleanup {
+    runner: OperationRunner,
+    interval: Duration,
+}
+
+impl OperationCleanup {
+    /// Create a new cleanup worker using the runner's configured interval.
+    pub fn new(runner: OperationRunner) -> Self {
+        let interval = runner.config().cleanup_interval;
+        Self { runner, interval }
+    }
+
+    /// Run cleanup passes until cancelled.
+    ///
+    /// Every server instance may run the worker: concurrent passes race to
+    /// delete the same rows, and deleting an already removed artifact or row
+    /// is a no-op. `heartbeat` is beaten after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            "Starting operation cleanup"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Operation cleanup shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.purge().await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Operation cleanup stopped");
+        Ok(())
+    }
+
+    /// Removes expired operations in batches until none are left (or a
+    /// batch makes no progress).
+    async fn purge(&self) {
+        let mut removed = 0;
+        loop {
+            match self.runner.purge_expired(BATCH_SIZE).await {
+                Ok(0) => break,
+                Ok(count) => {
+                    removed += count;
+                    if (count as i64) < BATCH_SIZE {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Failed to remove expired operations"
+                    );
+                    break;
+                }
+            }
+        }
+
+        if removed > 0 {
+            tracing::info!(
+                target: TRACING_TARGET,
+                removed,
+                "Removed expired operations"
+            );
+        }
+    }
+}
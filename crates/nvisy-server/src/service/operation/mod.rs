@@ -0,0 +1,62 @@
+//! Long-running operations.
+//!
+//! Work that outlives a request (detect over a large file today; imports,
+//! exports and bulk jobs as they move off the request path) is recorded as a
+//! workspace operation. The handler that accepts the work returns the
+//! operation straight away; [`OperationRunner`] drives it in the background
+//! and records its progress and outcome, which clients poll through the
+//! operations endpoints or receive as `operation:*` webhooks.
+//!
+//! A job may also leave a file behind (an export archive, a bulk report).
+//! The artifact is stored encrypted in the workspace's region and handed out
+//! through signed, expiring download links. Finished operations are kept for
+//! the configured retention period, after which [`OperationCleanup`] removes
+//! them together with their artifacts.
+
+mod artifact;
+mod cleanup;
+mod runner;
+
+use std::time::Duration;
+
+pub use artifact::{DownloadLink, OperationArtifact, OperationOutput};
+pub use cleanup::OperationCleanup;
+pub use runner::{OperationHandle, OperationRunner};
+
+/// Tracing target for operation execution.
+const TRACING_TARGET: &str = "nvisy_server::service::operation";
+
+/// Interval clients are asked to wait between polls of an unfinished
+/// operation (sent as `Retry-After`).
+pub const OPERATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default retention of a finished operation and its artifact.
+pub const DEFAULT_RESULT_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Default lifetime of a signed artifact download link.
+pub const DEFAULT_LINK_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Default interval between cleanup passes.
+pub const DEFAULT_CLEANUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Long-running operation configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct OperationConfig {
+    /// How long a finished operation and its artifact are kept.
+    pub result_ttl: Duration,
+    /// How long a signed artifact download link stays valid.
+    pub link_ttl: Duration,
+    /// How often expired operations are cleaned up.
+    pub cleanup_interval: Duration,
+}
+
+impl Default for OperationConfig {
+    fn default() -> Self {
+        Self {
+            result_ttl: DEFAULT_RESULT_TTL,
+            link_ttl: DEFAULT_LINK_TTL,
+            cleanup_interval: DEFAULT_CLEANUP_INTERVAL,
+        }
+    }
+}
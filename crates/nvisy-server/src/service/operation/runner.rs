@@ -0,0 +1,656 @@
+//! Background execution of workspace operations.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Cursor;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use jiff::Timestamp;
+use nvisy_core::clock::Clock;
+use nvisy_core::id::IdGenerator;
+use nvisy_nats::object::{ObjectStore, OperationResultsBucket, ResultKey};
+use nvisy_postgres::model::{NewWorkspaceOperation, UpdateWorkspaceOperation, WorkspaceOperation};
+use nvisy_postgres::query::{
+    AdminScope, TenantScope, WorkspaceOperationRepository, WorkspaceRepository,
+};
+use nvisy_postgres::types::{OperationId, OperationStatus};
+use nvisy_postgres::{AsyncConnection, PgClient, PgConn, PgError};
+use serde_json::{Value, json};
+use tokio::io::AsyncRead;
+use tokio::task::AbortHandle;
+use uuid::Uuid;
+
+use super::{DownloadLink, OperationArtifact, OperationConfig, OperationOutput, TRACING_TARGET};
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{CryptoService, ResidencyService, WebhookEmitter};
+
+/// Runs operation jobs in the background and records their lifecycle.
+///
+/// An operation moves `pending` → `running` → `succeeded` | `failed`, or to
+/// `cancelled` if [`cancel`](Self::cancel) is called first. The job's output
+/// becomes the operation's result (and artifact); an error becomes its error,
+/// rendered the same way the HTTP API renders errors. Completion is announced
+/// with the `operation:succeeded` or `operation:failed` webhook, and starts
+/// the operation's retention period.
+#[derive(Clone)]
+pub struct OperationRunner {
+    config: OperationConfig,
+    /// Jobs running on this instance, so a cancellation can stop them.
+    running: Arc<Mutex<HashMap<Uuid, AbortHandle>>>,
+    pg_client: PgClient,
+    residency: ResidencyService,
+    crypto: CryptoService,
+    webhook_emitter: WebhookEmitter,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl OperationRunner {
+    /// Creates a new operation runner.
+    ///
+    /// Operation IDs come from `ids` and lifecycle timestamps from `clock`.
+    pub fn new(
+        config: OperationConfig,
+        pg_client: PgClient,
+        residency: ResidencyService,
+        crypto: CryptoService,
+        webhook_emitter: WebhookEmitter,
+        clock: Arc<dyn Clock>,
+        ids: Arc<dyn IdGenerator>,
+    ) -> Self {
+        Self {
+            config,
+            running: Arc::default(),
+            pg_client,
+            residency,
+            crypto,
+            webhook_emitter,
+            clock,
+            ids,
+        }
+    }
+
+    /// Returns the runner's configuration.
+    #[inline]
+    pub fn config(&self) -> &OperationConfig {
+        &self.config
+    }
+
+    /// Records a pending operation and starts `job` for it in the background.
+    ///
+    /// Returns the operation as recorded, before the job has started. The job
+    /// receives an [`OperationHandle`] for reporting progress. An operation
+    /// without an ID is given one by the runner. `scope` is the workspace the
+    /// caller was authorized for; its artifact is stored under it.
+    pub async fn spawn<F, Fut>(
+        &self,
+        scope: TenantScope,
+        new_operation: NewWorkspaceOperation,
+        job: F,
+    ) -> Result<WorkspaceOperation>
+    where
+        F: FnOnce(OperationHandle) -> Fut,
+        Fut: Future<Output = Result<OperationOutput>> + Send + 'static,
+    {
+        let new_operation = NewWorkspaceOperation {
+            id: new_operation.id.or_else(|| Some(self.ids.generate())),
+            ..new_operation
+        };
+        let mut conn = self.pg_client.get_connection().await?;
+        let operation = conn.create_workspace_operation(new_operation).await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            operation_id = %operation.id,
+            kind = ?operation.kind,
+            "Operation accepted"
+        );
+
+        let handle = OperationHandle {
+            id: operation.id,
+            pg_client: self.pg_client.clone(),
+        };
+        tokio::spawn(self.clone().drive(scope, operation.clone(), job(handle)));
+
+        Ok(operation)
+    }
+
+    /// Cancels an operation still in progress.
+    ///
+    /// Records the operation as `cancelled` and stops its job if it runs on
+    /// this instance. A job running elsewhere finishes, but its outcome is no
+    /// longer recorded. No webhook is sent: the caller asked for it.
+    pub async fn cancel(
+        &self,
+        scope: TenantScope,
+        operation_id: Uuid,
+    ) -> Result<WorkspaceOperation> {
+        let mut conn = self.pg_client.get_connection().await?;
+        conn.find_workspace_operation(scope, operation_id)
+            .await?
+            .ok_or_else(|| Error::not_found("operation"))?;
+
+        let updates = UpdateWorkspaceOperation {
+            status: Some(OperationStatus::Cancelled),
+            completed_at: Some(Some(self.clock.now().into())),
+            expires_at: Some(Some(self.expires_at().into())),
+            ..Default::default()
+        };
+        let operation = conn
+            .update_in_progress_workspace_operation(operation_id, updates)
+            .await?
+            .ok_or_else(|| {
+                ErrorKind::Conflict
+                    .with_message("The operation has already finished")
+                    .with_resource("operation")
+            })?;
+
+        let running = self.running_jobs().remove(&operation_id);
+        if let Some(job) = running {
+            job.abort();
+        }
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            operation_id = %operation_id,
+            "Operation cancelled"
+        );
+
+        Ok(operation)
+    }
+
+    /// Returns a signed download link for the operation's artifact, or
+    /// `None` when it has no artifact or is past its retention.
+    ///
+    /// The link expires after the configured link lifetime, or earlier when
+    /// the operation itself expires first.
+    pub fn download_link(&self, operation: &WorkspaceOperation) -> Option<DownloadLink> {
+        if !operation.has_artifact() || operation.is_expired() {
+            return None;
+        }
+
+        let mut expires_at = self
+            .clock
+            .now()
+            .checked_add(self.config.link_ttl)
+            .unwrap_or(Timestamp::MAX);
+        if let Some(operation_expires_at) = operation.expires_at.map(Timestamp::from) {
+            expires_at = expires_at.min(operation_expires_at);
+        }
+
+        let expires = expires_at.as_second();
+        let signature = self.link_signature(operation.id, expires);
+
+        Some(DownloadLink {
+            url: format!(
+                "/operations/{}/artifact/?expires={expires}&signature={}",
+                OperationId::from_uuid(operation.id),
+                hex::encode(signature),
+            ),
+            expires_at,
+        })
+    }
+
+    /// Checks a download link's signature and expiry.
+    pub fn verify_download_link(&self, operation_id: Uuid, expires: i64, signature: &str) -> bool {
+        if expires <= self.clock.now().as_second() {
+            return false;
+        }
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+
+        let expires = expires.to_string();
+        self.crypto.verify_signature(
+            &[operation_id.as_bytes(), b".", expires.as_bytes()],
+            &signature,
+        )
+    }
+
+    /// Opens the operation's artifact as a stream of decrypted bytes.
+    pub async fn open_artifact(
+        &self,
+        scope: TenantScope,
+        operation: &WorkspaceOperation,
+    ) -> Result<Pin<Box<dyn AsyncRead + Send>>> {
+        let key = artifact_key(operation)?.ok_or_else(|| Error::not_found("operation_artifact"))?;
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let store = self.result_store(&mut conn, scope).await?;
+        let data = store
+            .get(&key)
+            .await
+            .map_err(storage_error("Failed to retrieve operation result"))?
+            .ok_or_else(|| Error::not_found("operation_artifact"))?;
+
+        Ok(Box::pin(self.crypto.decrypt_reader(
+            operation.workspace_id,
+            data.into_reader(),
+        )))
+    }
+
+    /// Removes up to `limit` expired operations and their artifacts.
+    ///
+    /// An operation whose artifact cannot be deleted is kept, so the next
+    /// pass retries it. Returns the number of operations removed.
+    pub async fn purge_expired(&self, limit: i64) -> Result<usize> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "remove expired operations").await?;
+        let expired = conn
+            .list_expired_workspace_operations(&admin, limit)
+            .await?;
+
+        let mut removable = Vec::with_capacity(expired.len());
+        for operation in &expired {
+            match self.delete_artifact(&mut conn, &admin, operation).await {
+                Ok(()) => removable.push(operation.id),
+                Err(err) => tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    operation_id = %operation.id,
+                    "Failed to delete operation artifact"
+                ),
+            }
+        }
+
+        if removable.is_empty() {
+            return Ok(0);
+        }
+        Ok(conn.delete_workspace_operations(&admin, &removable).await?)
+    }
+
+    /// Runs the job to completion and records its outcome.
+    async fn drive(
+        self,
+        scope: TenantScope,
+        operation: WorkspaceOperation,
+        job: impl Future<Output = Result<OperationOutput>> + Send + 'static,
+    ) {
+        let started = self
+            .record(
+                operation.id,
+                UpdateWorkspaceOperation {
+                    status: Some(OperationStatus::Running),
+                    started_at: Some(Some(self.clock.now().into())),
+                    ..Default::default()
+                },
+            )
+            .await;
+        if !started {
+            tracing::debug!(
+                target: TRACING_TARGET,
+                operation_id = %operation.id,
+                "Operation cancelled before it started"
+            );
+            return;
+        }
+
+        // The job runs in its own task so a panic fails the operation instead
+        // of leaving it running forever, and so a cancellation can abort it.
+        let task = tokio::spawn(job);
+        self.running_jobs()
+            .insert(operation.id, task.abort_handle());
+        let outcome = task.await;
+        self.running_jobs().remove(&operation.id);
+
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            // Aborted by `cancel`, which recorded the cancellation.
+            Err(err) if err.is_cancelled() => return,
+            Err(err) => Err(ErrorKind::InternalServerError
+                .with_message("Operation stopped unexpectedly")
+                .with_context(err.to_string())),
+        };
+
+        let outcome = match outcome {
+            Ok(output) => self.store_output(scope, &operation, output).await,
+            Err(err) => Err(err),
+        };
+
+        match outcome {
+            Ok((result, update)) => self.succeed(&operation, result, update).await,
+            Err(err) => self.fail(&operation, err).await,
+        }
+    }
+
+    /// Stores the output's artifact, if any, and returns the result together
+    /// with the update recording it.
+    async fn store_output(
+        &self,
+        scope: TenantScope,
+        operation: &WorkspaceOperation,
+        output: OperationOutput,
+    ) -> Result<(Value, UpdateWorkspaceOperation)> {
+        let mut update = UpdateWorkspaceOperation {
+            result: Some(Some(output.result.clone())),
+            ..Default::default()
+        };
+
+        if let Some(artifact) = output.artifact {
+            let key = self.store_artifact(scope, operation, &artifact).await?;
+            update.artifact_key = Some(Some(key.to_string()));
+            update.artifact_name = Some(Some(artifact.name));
+            update.artifact_content_type = Some(Some(artifact.content_type));
+            update.artifact_size = Some(Some(artifact.content.len() as i64));
+        }
+
+        Ok((output.result, update))
+    }
+
+    /// Encrypts and uploads an artifact to the workspace's region.
+    async fn store_artifact(
+        &self,
+        scope: TenantScope,
+        operation: &WorkspaceOperation,
+        artifact: &OperationArtifact,
+    ) -> Result<ResultKey> {
+        let ciphertext = self
+            .crypto
+            .encrypt(operation.workspace_id, &artifact.content)
+            .map_err(|err| {
+                ErrorKind::InternalServerError
+                    .with_message("Failed to encrypt operation result")
+                    .with_context(err.to_string())
+            })?;
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let store = self.result_store(&mut conn, scope).await?;
+        let key = ResultKey::new(operation.workspace_id, operation.id);
+        store
+            .put(&key, Cursor::new(ciphertext))
+            .await
+            .map_err(storage_error("Failed to store operation result"))?;
+
+        Ok(key)
+    }
+
+    /// Deletes an operation's artifact, if it has one.
+    async fn delete_artifact(
+        &self,
+        conn: &mut PgConn,
+        admin: &AdminScope,
+        operation: &WorkspaceOperation,
+    ) -> Result<()> {
+        let Some(key) = artifact_key(operation)? else {
+            return Ok(());
+        };
+
+        match self
+            .result_store(conn, admin.tenant(operation.workspace_id))
+            .await
+        {
+            Ok(store) => store
+                .delete(&key)
+                .await
+                .map_err(storage_error("Failed to delete operation result")),
+            // The workspace is gone (its operations are removed with it).
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Opens the result store in the workspace's data region.
+    async fn result_store(
+        &self,
+        conn: &mut PgConn,
+        scope: TenantScope,
+    ) -> Result<ObjectStore<OperationResultsBucket, ResultKey>> {
+        let workspace = conn
+            .find_workspace_by_id(scope)
+            .await?
+            .ok_or_else(|| Error::not_found("workspace"))?;
+        let backends = self.residency.backends(workspace.data_region)?;
+
+        backends
+            .nats()
+            .operation_result_store()
+            .await
+            .map_err(storage_error("Failed to initialize result storage"))
+    }
+
+    async fn succeed(
+        &self,
+        operation: &WorkspaceOperation,
+        result: Value,
+        update: UpdateWorkspaceOperation,
+    ) {
+        tracing::info!(
+            target: TRACING_TARGET,
+            operation_id = %operation.id,
+            "Operation succeeded"
+        );
+
+        let data = json!({
+            "kind": operation.kind,
+            "targetId": operation.target_id,
+            "result": result,
+        });
+        self.complete(
+            operation,
+            UpdateWorkspaceOperation {
+                status: Some(OperationStatus::Succeeded),
+                progress: Some(100),
+                completed_at: Some(Some(self.clock.now().into())),
+                expires_at: Some(Some(self.expires_at().into())),
+                ..update
+            },
+            data,
+        )
+        .await;
+    }
+
+    async fn fail(&self, operation: &WorkspaceOperation, err: Error<'static>) {
+        tracing::warn!(
+            target: TRACING_TARGET,
+            operation_id = %operation.id,
+            error = %err,
+            "Operation failed"
+        );
+
+        // Internal context is logged above and never stored on the operation.
+        let error = serde_json::to_value(err.into_error_response()).unwrap_or(Value::Null);
+
+        let data = json!({
+            "kind": operation.kind,
+            "targetId": operation.target_id,
+            "error": error,
+        });
+        self.complete(
+            operation,
+            UpdateWorkspaceOperation {
+                status: Some(OperationStatus::Failed),
+                error: Some(Some(error)),
+                completed_at: Some(Some(self.clock.now().into())),
+                expires_at: Some(Some(self.expires_at().into())),
+                ..Default::default()
+            },
+            data,
+        )
+        .await;
+    }
+
+    /// Records a terminal state together with its webhook event, so the event
+    /// goes out if and only if the state is stored (best effort, like
+    /// [`record`](Self::record)).
+    async fn complete(
+        &self,
+        operation: &WorkspaceOperation,
+        updates: UpdateWorkspaceOperation,
+        data: Value,
+    ) {
+        let succeeded = matches!(updates.status, Some(OperationStatus::Succeeded));
+        let emitter = &self.webhook_emitter;
+        let result = match self.pg_client.get_connection().await {
+            Ok(mut conn) => {
+                conn.transaction(async |conn| {
+                    // A cancelled operation keeps its state and sends no event.
+                    let finished = conn
+                        .update_in_progress_workspace_operation(operation.id, updates)
+                        .await?;
+                    if finished.is_none() {
+                        tracing::debug!(
+                            target: TRACING_TARGET,
+                            operation_id = %operation.id,
+                            "Operation finished after it was cancelled"
+                        );
+                        return Ok(());
+                    }
+
+                    let (workspace_id, account_id) = (operation.workspace_id, operation.account_id);
+                    if succeeded {
+                        emitter
+                            .emit_operation_succeeded(
+                                conn,
+                                workspace_id,
+                                operation.id,
+                                account_id,
+                                Some(data),
+                            )
+                            .await?;
+                    } else {
+                        emitter
+                            .emit_operation_failed(
+                                conn,
+                                workspace_id,
+                                operation.id,
+                                account_id,
+                                Some(data),
+                            )
+                            .await?;
+                    }
+
+                    Ok::<_, PgError>(())
+                })
+                .await
+            }
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                operation_id = %operation.id,
+                "Failed to record operation state"
+            );
+        }
+    }
+
+    /// Applies a lifecycle update to an operation still in progress, and
+    /// returns `false` if it was cancelled meanwhile.
+    ///
+    /// Best effort: the job's own effects are already durable, so a failed
+    /// write is logged rather than retried.
+    async fn record(&self, operation_id: Uuid, updates: UpdateWorkspaceOperation) -> bool {
+        let result = match self.pg_client.get_connection().await {
+            Ok(mut conn) => {
+                conn.update_in_progress_workspace_operation(operation_id, updates)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(updated) => updated.is_some(),
+            Err(err) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    operation_id = %operation_id,
+                    "Failed to record operation state"
+                );
+                true
+            }
+        }
+    }
+
+    /// Locks the registry of jobs running on this instance.
+    fn running_jobs(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, AbortHandle>> {
+        // The map stays consistent even if a holder panicked.
+        self.running
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Returns when an operation finishing now expires.
+    fn expires_at(&self) -> Timestamp {
+        self.clock
+            .now()
+            .checked_add(self.config.result_ttl)
+            .unwrap_or(Timestamp::MAX)
+    }
+
+    fn link_signature(&self, operation_id: Uuid, expires: i64) -> [u8; 32] {
+        let expires = expires.to_string();
+        self.crypto
+            .sign(&[operation_id.as_bytes(), b".", expires.as_bytes()])
+    }
+}
+
+/// Parses an operation's stored artifact key, if it has one.
+fn artifact_key(operation: &WorkspaceOperation) -> Result<Option<ResultKey>> {
+    operation
+        .artifact_key
+        .as_deref()
+        .map(ResultKey::from_str)
+        .transpose()
+        .map_err(|err| {
+            ErrorKind::InternalServerError
+                .with_message("Invalid operation artifact key")
+                .with_context(err.to_string())
+        })
+}
+
+/// Maps an object storage error to an internal server error.
+fn storage_error(message: &'static str) -> impl FnOnce(nvisy_nats::Error) -> Error<'static> {
+    move |err| {
+        ErrorKind::InternalServerError
+            .with_message(message)
+            .with_context(err.to_string())
+    }
+}
+
+/// Handle given to a running job for reporting on its operation.
+#[derive(Clone)]
+pub struct OperationHandle {
+    id: Uuid,
+    pg_client: PgClient,
+}
+
+impl OperationHandle {
+    /// Returns the operation's id.
+    #[inline]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Records the job's progress as a percentage (best effort).
+    ///
+    /// Values above 99 are held at 99; an operation reaches 100 only when it
+    /// succeeds.
+    pub async fn progress(&self, percent: u8) {
+        let updates = UpdateWorkspaceOperation {
+            progress: Some(i16::from(percent.min(99))),
+            ..Default::default()
+        };
+
+        let result = match self.pg_client.get_connection().await {
+            Ok(mut conn) => conn
+                .update_workspace_operation(self.id, updates)
+                .await
+                .map(drop),
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                error = %err,
+                operation_id = %self.id,
+                "Failed to record operation progress"
+            );
+        }
+    }
+}
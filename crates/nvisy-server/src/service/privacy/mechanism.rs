@@ -0,0 +1,96 @@
+//! Laplace mechanism for count aggregates.
+
+use rand::Rng;
+
+/// Draws a sample from the Laplace distribution centred at zero.
+///
+/// Uses inverse transform sampling over a uniform draw in `(-0.5, 0.5)`.
+fn laplace(rng: &mut impl Rng, scale: f64) -> f64 {
+    // 53 random bits give a uniform f64 in [0, 1) with full mantissa precision.
+    let unit = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    let u = unit - 0.5;
+
+    // `ln(0)` is only reachable at the open interval's edge; clamp away from it.
+    let tail = (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE);
+    -scale * u.signum() * tail.ln()
+}
+
+/// Scales one user's counts down so that they add up to at most `bound`.
+///
+/// Removing a user whose counts were clamped changes a histogram by at most
+/// `bound` in total, which is the sensitivity to calibrate its noise to.
+/// Counts are scaled proportionally, so the user's mix of groups is kept.
+pub fn clamp_contributions(counts: &mut [i64], bound: i64) {
+    let total: i64 = counts.iter().map(|&count| count.max(0)).sum();
+    if total <= bound {
+        return;
+    }
+
+    for count in counts.iter_mut() {
+        // Floored, so the scaled counts never add up to more than `bound`.
+        *count = (i128::from((*count).max(0)) * i128::from(bound) / i128::from(total)) as i64;
+    }
+}
+
+/// Returns `count` with Laplace noise calibrated to `epsilon`.
+///
+/// `sensitivity` is the most a single user can change the count by, or for
+/// a histogram all of its counts together; callers must clamp contributions
+/// to it with [`clamp_contributions`] before aggregating. The result is
+/// rounded and clamped to zero so that it remains a plausible count; both
+/// are post-processing and cost no extra budget.
+pub fn noisy_count(rng: &mut impl Rng, count: i64, sensitivity: f64, epsilon: f64) -> i64 {
+    let noisy = count as f64 + laplace(rng, sensitivity / epsilon);
+    noisy.round().max(0.0) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn test_laplace_is_centred() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples = 20_000;
+        let mean = (0..samples).map(|_| laplace(&mut rng, 1.0)).sum::<f64>() / samples as f64;
+        assert!(mean.abs() < 0.05, "mean was {mean}");
+    }
+
+    #[test]
+    fn test_laplace_scale() {
+        // The mean absolute deviation of Laplace(0, b) is b.
+        let mut rng = StdRng::seed_from_u64(11);
+        let samples = 20_000;
+        let mad = (0..samples)
+            .map(|_| laplace(&mut rng, 2.0).abs())
+            .sum::<f64>()
+            / samples as f64;
+        assert!((mad - 2.0).abs() < 0.1, "mad was {mad}");
+    }
+
+    #[test]
+    fn test_noisy_count_is_non_negative() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..1_000 {
+            assert!(noisy_count(&mut rng, 0, 1.0, 0.1) >= 0);
+        }
+    }
+
+    #[test]
+    fn test_clamp_contributions() {
+        let mut counts = [30, 10, 0];
+        clamp_contributions(&mut counts, 8);
+        assert_eq!(counts, [6, 2, 0]);
+
+        let mut counts = [3, 2];
+        clamp_contributions(&mut counts, 8);
+        assert_eq!(counts, [3, 2]);
+
+        let mut counts = [1, 1, 1];
+        clamp_contributions(&mut counts, 2);
+        assert!(counts.iter().sum::<i64>() <= 2);
+    }
+}
@@ -0,0 +1,59 @@
+//! Differential privacy for aggregate analytics.
+//!
+//! Analytics endpoints return noised results to callers not permitted to see
+//! exact figures, so that dashboards may be shared beyond the workspace's
+//! administrators without revealing per-user activity. Each user's
+//! contribution is clamped to a fixed bound before aggregating, and the noise
+//! is calibrated to that bound. Each noised query spends a fixed epsilon from
+//! the workspace's budget ([`PrivacyService::charge`]); once the budget for
+//! the current window is exhausted, further noised queries are refused until
+//! the window resets.
+
+use std::time::Duration;
+
+mod mechanism;
+mod service;
+
+pub use service::{PrivacyCharge, PrivacyService};
+
+/// Tracing target for differential privacy operations.
+const TRACING_TARGET: &str = "nvisy_server::service::privacy";
+
+/// Default epsilon spent by a single noised query.
+pub const DEFAULT_QUERY_EPSILON: f64 = 0.1;
+
+/// Default epsilon a workspace may spend within one window.
+pub const DEFAULT_WINDOW_BUDGET: f64 = 2.0;
+
+/// Default number of events a single user may contribute to a noised query.
+pub const DEFAULT_MAX_CONTRIBUTIONS: u32 = 20;
+
+/// Default length of a budget window.
+pub const DEFAULT_BUDGET_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Differential privacy configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct PrivacyConfig {
+    /// Epsilon spent by each noised query. Smaller values add more noise.
+    pub query_epsilon: f64,
+    /// Total epsilon a workspace may spend within one window.
+    pub window_budget: f64,
+    /// How long a budget window lasts before the spent epsilon resets.
+    pub budget_window: Duration,
+    /// Most events a single user may contribute to one noised query.
+    ///
+    /// Larger values distort heavy users' activity less but add more noise.
+    pub max_contributions: u32,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            query_epsilon: DEFAULT_QUERY_EPSILON,
+            window_budget: DEFAULT_WINDOW_BUDGET,
+            budget_window: DEFAULT_BUDGET_WINDOW,
+            max_contributions: DEFAULT_MAX_CONTRIBUTIONS,
+        }
+    }
+}
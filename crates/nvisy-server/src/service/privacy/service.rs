@@ -0,0 +1,162 @@
+//! Per-workspace privacy budget accounting.
+
+use std::time::Duration;
+
+use jiff::Timestamp;
+use nvisy_core::cache::CacheResult as Result;
+use nvisy_nats::kv::{PrivacyBudget, WorkspaceKey};
+use uuid::Uuid;
+
+use super::{PrivacyConfig, TRACING_TARGET, mechanism};
+use crate::service::Cache;
+use crate::{Error, Result as ServiceResult};
+
+/// Maximum number of attempts to record a charge under concurrent updates.
+const MAX_CHARGE_ATTEMPTS: usize = 5;
+
+/// Epsilon spent by one noised query and the budget left afterwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrivacyCharge {
+    /// Epsilon spent by this query.
+    pub epsilon: f64,
+    /// Epsilon left in the current window.
+    pub remaining: f64,
+    /// When the current window ends and the budget resets.
+    pub resets_at: Timestamp,
+}
+
+/// Tracks per-workspace epsilon budgets in the cache store.
+#[derive(Clone)]
+pub struct PrivacyService {
+    budgets: Cache<WorkspaceKey, PrivacyBudget>,
+    query_epsilon: f64,
+    window_budget: f64,
+    budget_window: Duration,
+    max_contributions: i64,
+}
+
+impl PrivacyService {
+    /// Creates the service from its configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns a config error if the epsilon values are not positive and
+    /// finite, if a single query costs more than the window budget, or if
+    /// users may not contribute any events.
+    pub fn from_config(
+        config: &PrivacyConfig,
+        budgets: Cache<WorkspaceKey, PrivacyBudget>,
+    ) -> ServiceResult<Self> {
+        let valid = |epsilon: f64| epsilon.is_finite() && epsilon > 0.0;
+        if !valid(config.query_epsilon) || !valid(config.window_budget) {
+            return Err(Error::config("Privacy epsilon values must be positive"));
+        }
+
+        if config.query_epsilon > config.window_budget {
+            return Err(Error::config(
+                "Privacy query epsilon must not exceed the window budget",
+            ));
+        }
+
+        if config.max_contributions == 0 {
+            return Err(Error::config(
+                "Privacy contribution bound must be at least one",
+            ));
+        }
+
+        Ok(Self {
+            budgets,
+            query_epsilon: config.query_epsilon,
+            window_budget: config.window_budget,
+            budget_window: config.budget_window,
+            max_contributions: i64::from(config.max_contributions),
+        })
+    }
+
+    /// Returns the epsilon spent by each noised query.
+    #[inline]
+    pub fn query_epsilon(&self) -> f64 {
+        self.query_epsilon
+    }
+
+    /// Clamps one user's counts to the contribution bound.
+    ///
+    /// Every user's counts must be clamped before they are added up and
+    /// passed to [`noisy_count`](Self::noisy_count).
+    pub fn clamp_contributions(&self, counts: &mut [i64]) {
+        mechanism::clamp_contributions(counts, self.max_contributions);
+    }
+
+    /// Returns `count` with noise calibrated to one query's epsilon.
+    ///
+    /// The noise is scaled to the contribution bound, so this covers a single
+    /// count or a histogram over disjoint groups, as long as each user's
+    /// counts were clamped together.
+    pub fn noisy_count(&self, count: i64) -> i64 {
+        mechanism::noisy_count(
+            &mut rand::rng(),
+            count,
+            self.max_contributions as f64,
+            self.query_epsilon,
+        )
+    }
+
+    /// Spends one query's epsilon from the workspace's budget.
+    ///
+    /// Returns `None` without spending anything when the current window's
+    /// budget cannot cover the query.
+    pub async fn charge(&self, workspace_id: Uuid) -> Result<Option<PrivacyCharge>> {
+        let key = WorkspaceKey(workspace_id);
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            let now = Timestamp::now();
+            let (mut budget, revision) = match self.budgets.get(&key).await? {
+                Some(entry) => (entry.value.roll(self.budget_window, now), entry.revision),
+                // Revision zero only succeeds if the key does not exist yet.
+                None => (PrivacyBudget::new(now), 0),
+            };
+
+            if !budget.can_spend(self.query_epsilon, self.window_budget) {
+                tracing::info!(
+                    target: TRACING_TARGET,
+                    workspace_id = %workspace_id,
+                    epsilon_spent = budget.epsilon_spent,
+                    "Privacy budget exhausted"
+                );
+                return Ok(None);
+            }
+
+            budget.spend(self.query_epsilon);
+
+            match self.budgets.compare_and_swap(&key, &budget, revision).await {
+                Ok(_) => {
+                    tracing::debug!(
+                        target: TRACING_TARGET,
+                        workspace_id = %workspace_id,
+                        epsilon_spent = budget.epsilon_spent,
+                        "Privacy budget charged"
+                    );
+
+                    return Ok(Some(PrivacyCharge {
+                        epsilon: self.query_epsilon,
+                        remaining: budget.remaining(self.window_budget),
+                        resets_at: budget.resets_at(self.budget_window),
+                    }));
+                }
+                Err(err) if err.is_revision_mismatch() && attempt < MAX_CHARGE_ATTEMPTS => {
+                    tracing::debug!(
+                        target: TRACING_TARGET,
+                        workspace_id = %workspace_id,
+                        attempt = attempt,
+                        error = %err,
+                        "Privacy budget changed concurrently, retrying"
+                    );
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Role-based access control.
+//!
+//! Members hold one of the built-in roles (owner, admin, editor, viewer) and
+//! optionally a custom role defined by the workspace, which grants extra
+//! permissions on top of the built-in one. Permissions are defined per
+//! resource type as `resource:action` pairs.
+//!
+//! [`Policy::check`] decides whether an [`Actor`] may perform an [`Action`]
+//! on a [`Resource`], resolving the actor's roles from Postgres through a
+//! short-lived cache. Handlers enforce it through the
+//! [`WorkspaceAccess`](crate::extract::WorkspaceAccess) extractor.
+
+mod permission;
+mod policy;
+mod role;
+
+use std::time::Duration;
+
+pub use permission::{Action, Permission, ResourceType};
+pub use policy::{Actor, Decision, Policy, Resource};
+pub(crate) use policy::{decide, precheck};
+pub use role::{BuiltinRole, CustomRole, RoleGrant};
+
+/// Default lifetime of a cached role grant.
+pub const DEFAULT_ROLE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Access control configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct PolicyConfig {
+    /// How long a resolved role grant is cached.
+    pub role_cache_ttl: Duration,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            role_cache_ttl: DEFAULT_ROLE_CACHE_TTL,
+        }
+    }
+}
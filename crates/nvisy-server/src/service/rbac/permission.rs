@@ -0,0 +1,424 @@
+//! Permission definitions per resource type.
+//!
+//! Every [`Permission`] is an [`Action`] on a [`ResourceType`], written as a
+//! `resource:action` key (`files:upload`). Keys are what custom roles store
+//! and what the API exposes.
+
+use nvisy_postgres::types::WorkspaceRole;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+/// Kinds of workspace resources that permissions apply to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Display, EnumIter, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ResourceType {
+    /// The workspace itself.
+    Workspace,
+    /// Uploaded files.
+    Files,
+    /// Pipelines and their runs.
+    Pipelines,
+    /// Workspace members.
+    Members,
+    /// Member roles, built-in and custom.
+    Roles,
+    /// Provider connections.
+    Connections,
+    /// Detection contexts.
+    Contexts,
+    /// Redaction policies.
+    Policies,
+    /// Webhooks.
+    Webhooks,
+    /// The workspace audit log.
+    Activities,
+    /// Retention policy and legal holds.
+    Retention,
+}
+
+/// Actions that can be performed on a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Display, EnumIter, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    /// Read the resource.
+    View,
+    /// Create a new resource.
+    Create,
+    /// Modify an existing resource.
+    Update,
+    /// Delete a resource.
+    Delete,
+    /// Upload file content.
+    Upload,
+    /// Download file content.
+    Download,
+    /// Share file content outside the workspace.
+    Share,
+    /// Execute a pipeline.
+    Run,
+    /// Accept or reject detected findings.
+    Review,
+    /// Invite new members.
+    Invite,
+    /// Remove members.
+    Remove,
+    /// Create, modify and delete resources of the type.
+    Manage,
+    /// Send test deliveries.
+    Test,
+    /// Check integrity.
+    Verify,
+}
+
+/// Granular workspace permissions for authorization checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, JsonSchema, Display, EnumIter, EnumString)]
+pub enum Permission {
+    // Workspace-level permissions
+    /// Can view workspace basic information.
+    #[serde(rename = "workspace:view")]
+    #[strum(serialize = "workspace:view")]
+    ViewWorkspace,
+    /// Can update workspace settings and metadata.
+    #[serde(rename = "workspace:update")]
+    #[strum(serialize = "workspace:update")]
+    UpdateWorkspace,
+    /// Can delete the entire workspace.
+    #[serde(rename = "workspace:delete")]
+    #[strum(serialize = "workspace:delete")]
+    DeleteWorkspace,
+
+    // File permissions
+    /// Can view and download files.
+    #[serde(rename = "files:view")]
+    #[strum(serialize = "files:view")]
+    ViewFiles,
+    /// Can upload new files to the workspace.
+    #[serde(rename = "files:upload")]
+    #[strum(serialize = "files:upload")]
+    UploadFiles,
+    /// Can update file metadata and properties.
+    #[serde(rename = "files:update")]
+    #[strum(serialize = "files:update")]
+    UpdateFiles,
+    /// Can download files from the workspace.
+    #[serde(rename = "files:download")]
+    #[strum(serialize = "files:download")]
+    DownloadFiles,
+    /// Can share files through expiring links, and list or revoke them.
+    #[serde(rename = "files:share")]
+    #[strum(serialize = "files:share")]
+    ShareFiles,
+    /// Can delete files from the workspace.
+    #[serde(rename = "files:delete")]
+    #[strum(serialize = "files:delete")]
+    DeleteFiles,
+
+    // Pipeline permissions
+    /// Can view pipelines in the workspace.
+    #[serde(rename = "pipelines:view")]
+    #[strum(serialize = "pipelines:view")]
+    ViewPipelines,
+    /// Can create new pipelines.
+    #[serde(rename = "pipelines:create")]
+    #[strum(serialize = "pipelines:create")]
+    CreatePipelines,
+    /// Can update existing pipelines.
+    #[serde(rename = "pipelines:update")]
+    #[strum(serialize = "pipelines:update")]
+    UpdatePipelines,
+    /// Can delete pipelines.
+    #[serde(rename = "pipelines:delete")]
+    #[strum(serialize = "pipelines:delete")]
+    DeletePipelines,
+    /// Can execute pipeline runs.
+    #[serde(rename = "pipelines:run")]
+    #[strum(serialize = "pipelines:run")]
+    RunPipelines,
+    /// Can accept or reject the findings of pipeline runs.
+    #[serde(rename = "pipelines:review")]
+    #[strum(serialize = "pipelines:review")]
+    ReviewPipelines,
+
+    // Member management permissions
+    /// Can view workspace members and their roles.
+    #[serde(rename = "members:view")]
+    #[strum(serialize = "members:view")]
+    ViewMembers,
+    /// Can invite new members to the workspace.
+    #[serde(rename = "members:invite")]
+    #[strum(serialize = "members:invite")]
+    InviteMembers,
+    /// Can remove members from the workspace.
+    #[serde(rename = "members:remove")]
+    #[strum(serialize = "members:remove")]
+    RemoveMembers,
+    /// Can change member roles and permissions.
+    #[serde(rename = "roles:manage")]
+    #[strum(serialize = "roles:manage")]
+    ManageRoles,
+
+    // Connection permissions
+    /// Can view workspace connections.
+    #[serde(rename = "connections:view")]
+    #[strum(serialize = "connections:view")]
+    ViewConnections,
+    /// Can create, modify, and manage workspace connections.
+    #[serde(rename = "connections:manage")]
+    #[strum(serialize = "connections:manage")]
+    ManageConnections,
+
+    // Context permissions
+    /// Can view workspace contexts.
+    #[serde(rename = "contexts:view")]
+    #[strum(serialize = "contexts:view")]
+    ViewContexts,
+    /// Can create, modify, and manage workspace contexts.
+    #[serde(rename = "contexts:manage")]
+    #[strum(serialize = "contexts:manage")]
+    ManageContexts,
+
+    // Policy permissions
+    /// Can view workspace policies.
+    #[serde(rename = "policies:view")]
+    #[strum(serialize = "policies:view")]
+    ViewPolicies,
+    /// Can create, modify, and manage workspace policies.
+    #[serde(rename = "policies:manage")]
+    #[strum(serialize = "policies:manage")]
+    ManagePolicies,
+
+    // Webhook permissions
+    /// Can view workspace webhooks.
+    #[serde(rename = "webhooks:view")]
+    #[strum(serialize = "webhooks:view")]
+    ViewWebhooks,
+    /// Can create new webhooks in the workspace.
+    #[serde(rename = "webhooks:create")]
+    #[strum(serialize = "webhooks:create")]
+    CreateWebhooks,
+    /// Can update existing webhooks.
+    #[serde(rename = "webhooks:update")]
+    #[strum(serialize = "webhooks:update")]
+    UpdateWebhooks,
+    /// Can delete webhooks from the workspace.
+    #[serde(rename = "webhooks:delete")]
+    #[strum(serialize = "webhooks:delete")]
+    DeleteWebhooks,
+    /// Can test webhooks by sending test payloads.
+    #[serde(rename = "webhooks:test")]
+    #[strum(serialize = "webhooks:test")]
+    TestWebhooks,
+
+    // Audit permissions
+    /// Can view exact activity analytics; others only see noised counts.
+    #[serde(rename = "activities:view")]
+    #[strum(serialize = "activities:view")]
+    ViewActivities,
+    /// Can verify the integrity of the workspace audit log.
+    #[serde(rename = "activities:verify")]
+    #[strum(serialize = "activities:verify")]
+    VerifyActivities,
+
+    // Retention permissions
+    /// Can view the retention policy, legal holds and purge previews.
+    #[serde(rename = "retention:view")]
+    #[strum(serialize = "retention:view")]
+    ViewRetention,
+    /// Can change the retention policy and place or release legal holds.
+    #[serde(rename = "retention:manage")]
+    #[strum(serialize = "retention:manage")]
+    ManageRetention,
+}
+
+impl Permission {
+    /// Returns the permission for an action on a resource type, if defined.
+    pub fn new(resource_type: ResourceType, action: Action) -> Option<Self> {
+        Self::iter().find(|perm| perm.resource_type() == resource_type && perm.action() == action)
+    }
+
+    /// Returns the resource type this permission applies to.
+    pub const fn resource_type(self) -> ResourceType {
+        match self {
+            Self::ViewWorkspace | Self::UpdateWorkspace | Self::DeleteWorkspace => {
+                ResourceType::Workspace
+            }
+            Self::ViewFiles
+            | Self::UploadFiles
+            | Self::UpdateFiles
+            | Self::DownloadFiles
+            | Self::ShareFiles
+            | Self::DeleteFiles => ResourceType::Files,
+            Self::ViewPipelines
+            | Self::CreatePipelines
+            | Self::UpdatePipelines
+            | Self::DeletePipelines
+            | Self::RunPipelines
+            | Self::ReviewPipelines => ResourceType::Pipelines,
+            Self::ViewMembers | Self::InviteMembers | Self::RemoveMembers => ResourceType::Members,
+            Self::ManageRoles => ResourceType::Roles,
+            Self::ViewConnections | Self::ManageConnections => ResourceType::Connections,
+            Self::ViewContexts | Self::ManageContexts => ResourceType::Contexts,
+            Self::ViewPolicies | Self::ManagePolicies => ResourceType::Policies,
+            Self::ViewWebhooks
+            | Self::CreateWebhooks
+            | Self::UpdateWebhooks
+            | Self::DeleteWebhooks
+            | Self::TestWebhooks => ResourceType::Webhooks,
+            Self::ViewActivities | Self::VerifyActivities => ResourceType::Activities,
+            Self::ViewRetention | Self::ManageRetention => ResourceType::Retention,
+        }
+    }
+
+    /// Returns the action this permission allows.
+    pub const fn action(self) -> Action {
+        match self {
+            Self::ViewWorkspace
+            | Self::ViewFiles
+            | Self::ViewPipelines
+            | Self::ViewMembers
+            | Self::ViewConnections
+            | Self::ViewContexts
+            | Self::ViewPolicies
+            | Self::ViewWebhooks
+            | Self::ViewActivities
+            | Self::ViewRetention => Action::View,
+            Self::CreatePipelines | Self::CreateWebhooks => Action::Create,
+            Self::UpdateWorkspace
+            | Self::UpdateFiles
+            | Self::UpdatePipelines
+            | Self::UpdateWebhooks => Action::Update,
+            Self::DeleteWorkspace
+            | Self::DeleteFiles
+            | Self::DeletePipelines
+            | Self::DeleteWebhooks => Action::Delete,
+            Self::UploadFiles => Action::Upload,
+            Self::DownloadFiles => Action::Download,
+            Self::ShareFiles => Action::Share,
+            Self::RunPipelines => Action::Run,
+            Self::ReviewPipelines => Action::Review,
+            Self::InviteMembers => Action::Invite,
+            Self::RemoveMembers => Action::Remove,
+            Self::ManageRoles
+            | Self::ManageConnections
+            | Self::ManageContexts
+            | Self::ManagePolicies
+            | Self::ManageRetention => Action::Manage,
+            Self::TestWebhooks => Action::Test,
+            Self::VerifyActivities => Action::Verify,
+        }
+    }
+
+    /// Checks if the given workspace role satisfies this permission requirement.
+    ///
+    /// This method leverages the role hierarchy to determine if the given role
+    /// has sufficient permissions. A role is permitted if it has equal or higher
+    /// permission level than the minimum required role for this permission.
+    pub const fn is_permitted_by_role(self, role: WorkspaceRole) -> bool {
+        role.has_permission_level_of(self.minimum_required_role())
+    }
+
+    /// Returns the minimum role required for this permission.
+    #[must_use]
+    pub const fn minimum_required_role(self) -> WorkspaceRole {
+        match self {
+            // Guest-level permissions (read-only access)
+            Self::ViewWorkspace
+            | Self::ViewFiles
+            | Self::ViewPipelines
+            | Self::ViewMembers
+            | Self::ViewConnections
+            | Self::ViewContexts
+            | Self::ViewPolicies
+            | Self::ViewWebhooks => WorkspaceRole::Guest,
+
+            // Member-level permissions (create and modify own resources)
+            Self::UploadFiles
+            | Self::UpdateFiles
+            | Self::DownloadFiles
+            | Self::ShareFiles
+            | Self::DeleteFiles
+            | Self::CreatePipelines
+            | Self::UpdatePipelines
+            | Self::DeletePipelines
+            | Self::RunPipelines
+            | Self::ReviewPipelines => WorkspaceRole::Member,
+
+            // Admin-level permissions (manage workspace resources)
+            Self::UpdateWorkspace
+            | Self::InviteMembers
+            | Self::RemoveMembers
+            | Self::ManageConnections
+            | Self::ManageContexts
+            | Self::ManagePolicies
+            | Self::CreateWebhooks
+            | Self::UpdateWebhooks
+            | Self::DeleteWebhooks
+            | Self::TestWebhooks
+            | Self::ViewActivities
+            | Self::VerifyActivities
+            | Self::ViewRetention
+            | Self::ManageRetention => WorkspaceRole::Admin,
+
+            // Owner-only permissions (highest level)
+            Self::DeleteWorkspace | Self::ManageRoles => WorkspaceRole::Owner,
+        }
+    }
+
+    /// Returns whether a custom role may grant this permission.
+    ///
+    /// Owner-only permissions are never grantable, so a custom role cannot
+    /// hand out control over the workspace or over roles themselves.
+    pub const fn is_assignable(self) -> bool {
+        !matches!(self.minimum_required_role(), WorkspaceRole::Owner)
+    }
+
+    /// Returns all permissions available to the given role.
+    pub fn permissions_for_role(role: WorkspaceRole) -> Vec<Self> {
+        Self::iter()
+            .filter(|perm| perm.is_permitted_by_role(role))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_round_trip() {
+        for permission in Permission::iter() {
+            let key = permission.to_string();
+            assert_eq!(
+                key,
+                format!("{}:{}", permission.resource_type(), permission.action())
+            );
+            assert_eq!(key.parse::<Permission>().unwrap(), permission);
+            assert_eq!(
+                serde_json::to_value(permission).unwrap(),
+                serde_json::Value::String(key)
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_from_parts() {
+        assert_eq!(
+            Permission::new(ResourceType::Files, Action::Upload),
+            Some(Permission::UploadFiles)
+        );
+        assert_eq!(Permission::new(ResourceType::Files, Action::Run), None);
+    }
+
+    #[test]
+    fn test_owner_permissions_not_assignable() {
+        assert!(!Permission::ManageRoles.is_assignable());
+        assert!(!Permission::DeleteWorkspace.is_assignable());
+        assert!(Permission::ManageConnections.is_assignable());
+    }
+}
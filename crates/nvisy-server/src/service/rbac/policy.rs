@@ -0,0 +1,352 @@
+//! Policy decisions with cached workspace role resolution.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use nvisy_postgres::query::{TenantScope, WorkspaceCustomRoleRepository};
+use nvisy_postgres::types::ApiKeyScope;
+use nvisy_postgres::{PgClient, PgResult};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::{Action, CustomRole, Permission, PolicyConfig, ResourceType, RoleGrant};
+use crate::extract::AuthProvider;
+use crate::handler::{ErrorKind, Result};
+
+/// Tracing target for policy decisions.
+const TRACING_TARGET: &str = "nvisy_server::service::rbac";
+
+/// Maximum number of cached role grants.
+const MAX_CACHED_GRANTS: usize = 10_000;
+
+/// The caller a policy decision is made for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor {
+    /// Account acting.
+    pub account_id: Uuid,
+    /// Whether the account is a global administrator.
+    pub is_admin: bool,
+    /// Scopes of the API key authenticating the request, if any.
+    pub scopes: Option<Vec<ApiKeyScope>>,
+}
+
+impl AuthProvider for Actor {
+    fn account_id(&self) -> Uuid {
+        self.account_id
+    }
+
+    fn is_admin(&self) -> bool {
+        self.is_admin
+    }
+
+    fn scopes(&self) -> Option<&[ApiKeyScope]> {
+        self.scopes.as_deref()
+    }
+}
+
+/// The resource a policy decision is made about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resource {
+    /// Workspace owning the resource.
+    pub workspace_id: Uuid,
+    /// Kind of resource.
+    pub resource_type: ResourceType,
+}
+
+impl Resource {
+    /// Creates a resource of the given type in a workspace.
+    pub const fn new(workspace_id: Uuid, resource_type: ResourceType) -> Self {
+        Self {
+            workspace_id,
+            resource_type,
+        }
+    }
+}
+
+/// Outcome of a policy check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// The action is allowed.
+    Allow,
+    /// The action is denied, with the reason.
+    Deny(Cow<'static, str>),
+}
+
+impl Decision {
+    /// Returns whether the action is allowed.
+    pub const fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allow)
+    }
+
+    /// Converts the decision into a `Forbidden` error when denied.
+    pub fn into_result(self) -> Result<()> {
+        match self {
+            Self::Allow => Ok(()),
+            Self::Deny(reason) => Err(ErrorKind::Forbidden.with_context(reason)),
+        }
+    }
+}
+
+/// A cached role grant.
+struct CachedGrant {
+    grant: RoleGrant,
+    scope: TenantScope,
+    cached_at: Instant,
+}
+
+/// Authorization policy over workspace roles.
+///
+/// Role grants are read from Postgres and cached per workspace and account.
+/// Local changes invalidate the cache straight away; changes made through
+/// another server instance are picked up once the entry expires, so the TTL
+/// bounds how long a revoked permission may still be honored.
+#[derive(Clone)]
+pub struct Policy {
+    pg_client: PgClient,
+    cache: Arc<RwLock<HashMap<(Uuid, Uuid), CachedGrant>>>,
+    ttl: Duration,
+}
+
+impl Policy {
+    /// Creates a policy resolving roles through `pg_client`.
+    pub fn new(pg_client: PgClient, config: PolicyConfig) -> Self {
+        Self {
+            pg_client,
+            cache: Arc::default(),
+            ttl: config.role_cache_ttl,
+        }
+    }
+
+    /// Decides whether `actor` may perform `action` on `resource`.
+    ///
+    /// # Errors
+    ///
+    /// Returns database errors if the actor's role cannot be resolved.
+    pub async fn check(
+        &self,
+        actor: &Actor,
+        action: Action,
+        resource: Resource,
+    ) -> PgResult<Decision> {
+        let Some(permission) = Permission::new(resource.resource_type, action) else {
+            return Ok(Decision::Deny(
+                format!(
+                    "Action {action} is not defined for {}",
+                    resource.resource_type
+                )
+                .into(),
+            ));
+        };
+
+        self.check_permission(actor, permission, resource.workspace_id)
+            .await
+    }
+
+    /// Decides whether `actor` holds `permission` in a workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns database errors if the actor's role cannot be resolved.
+    pub async fn check_permission(
+        &self,
+        actor: &Actor,
+        permission: Permission,
+        workspace_id: Uuid,
+    ) -> PgResult<Decision> {
+        if let Some(decision) = precheck(actor, permission) {
+            return Ok(decision);
+        }
+
+        let grant = self.resolve(workspace_id, actor.account_id).await?;
+        Ok(decide(grant.as_ref(), permission))
+    }
+
+    /// Resolves the roles an account holds in a workspace.
+    ///
+    /// Returns `None` if the account is not a member.
+    ///
+    /// # Errors
+    ///
+    /// Returns database errors if the membership lookup fails.
+    pub async fn resolve(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> PgResult<Option<RoleGrant>> {
+        let membership = self.resolve_member(workspace_id, account_id).await?;
+        Ok(membership.map(|(grant, _)| grant))
+    }
+
+    /// Resolves the roles an account holds in a workspace, together with the
+    /// tenant scope of its membership.
+    ///
+    /// Returns `None` if the account is not a member.
+    ///
+    /// # Errors
+    ///
+    /// Returns database errors if the membership lookup fails.
+    pub async fn resolve_member(
+        &self,
+        workspace_id: Uuid,
+        account_id: Uuid,
+    ) -> PgResult<Option<(RoleGrant, TenantScope)>> {
+        let key = (workspace_id, account_id);
+
+        if let Some(entry) = self.cache.read().await.get(&key)
+            && entry.cached_at.elapsed() < self.ttl
+        {
+            return Ok(Some((entry.grant.clone(), entry.scope)));
+        }
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let membership = conn
+            .find_workspace_member_with_custom_role(workspace_id, account_id)
+            .await?;
+
+        // Non-members are not cached, so a freshly accepted invite takes
+        // effect on the next request.
+        let Some((member, custom_role, scope)) = membership else {
+            self.cache.write().await.remove(&key);
+            return Ok(None);
+        };
+
+        let grant =
+            RoleGrant::new(member.member_role).with_custom(custom_role.map(CustomRole::from));
+
+        let mut cache = self.cache.write().await;
+        if cache.len() >= MAX_CACHED_GRANTS {
+            let ttl = self.ttl;
+            cache.retain(|_, entry| entry.cached_at.elapsed() < ttl);
+            if cache.len() >= MAX_CACHED_GRANTS {
+                cache.clear();
+            }
+        }
+        cache.insert(
+            key,
+            CachedGrant {
+                grant: grant.clone(),
+                scope,
+                cached_at: Instant::now(),
+            },
+        );
+
+        Ok(Some((grant, scope)))
+    }
+
+    /// Drops the cached roles of an account in a workspace.
+    pub async fn invalidate(&self, workspace_id: Uuid, account_id: Uuid) {
+        self.cache.write().await.remove(&(workspace_id, account_id));
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            workspace_id = %workspace_id,
+            account_id = %account_id,
+            "Role grant invalidated"
+        );
+    }
+
+    /// Drops the cached roles of every member of a workspace.
+    pub async fn invalidate_workspace(&self, workspace_id: Uuid) {
+        self.cache
+            .write()
+            .await
+            .retain(|(cached_workspace_id, _), _| *cached_workspace_id != workspace_id);
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            workspace_id = %workspace_id,
+            "Workspace role grants invalidated"
+        );
+    }
+}
+
+/// Decides what can be decided without the actor's workspace role.
+///
+/// API key scopes cap every role, including global administrators, who are
+/// otherwise allowed everything.
+pub(crate) fn precheck(
+    actor: &(impl AuthProvider + ?Sized),
+    permission: Permission,
+) -> Option<Decision> {
+    if !actor.scopes_cover(permission.minimum_required_role()) {
+        tracing::warn!(
+            target: TRACING_TARGET,
+            account_id = %actor.account_id(),
+            permission = %permission,
+            scopes = ?actor.scopes(),
+            "Access denied: API key scope insufficient"
+        );
+
+        return Some(Decision::Deny(
+            format!("API key scope insufficient for {permission} permission").into(),
+        ));
+    }
+
+    if actor.is_admin() {
+        tracing::debug!(
+            target: TRACING_TARGET,
+            account_id = %actor.account_id(),
+            permission = %permission,
+            "Access granted: global administrator"
+        );
+
+        return Some(Decision::Allow);
+    }
+
+    None
+}
+
+/// Decides a permission from the actor's role grant.
+pub(crate) fn decide(grant: Option<&RoleGrant>, permission: Permission) -> Decision {
+    match grant {
+        None => Decision::Deny("Not a workspace member".into()),
+        Some(grant) if grant.permits(permission) => Decision::Allow,
+        Some(grant) => Decision::Deny(
+            format!(
+                "Role {role:?} insufficient for {permission} permission",
+                role = grant.role
+            )
+            .into(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nvisy_postgres::types::WorkspaceRole;
+
+    use super::*;
+
+    fn actor(is_admin: bool, scopes: Option<Vec<ApiKeyScope>>) -> Actor {
+        Actor {
+            account_id: Uuid::nil(),
+            is_admin,
+            scopes,
+        }
+    }
+
+    #[test]
+    fn test_precheck_admin_allowed() {
+        let decision = precheck(&actor(true, None), Permission::DeleteWorkspace);
+        assert_eq!(decision, Some(Decision::Allow));
+    }
+
+    #[test]
+    fn test_precheck_member_deferred() {
+        assert_eq!(precheck(&actor(false, None), Permission::ViewFiles), None);
+    }
+
+    #[test]
+    fn test_decide_non_member_denied() {
+        assert!(!decide(None, Permission::ViewFiles).is_allowed());
+    }
+
+    #[test]
+    fn test_decide_by_role() {
+        let grant = RoleGrant::new(WorkspaceRole::Member);
+        assert!(decide(Some(&grant), Permission::RunPipelines).is_allowed());
+        assert!(!decide(Some(&grant), Permission::ManageConnections).is_allowed());
+    }
+}
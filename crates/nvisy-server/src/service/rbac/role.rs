@@ -0,0 +1,190 @@
+//! Built-in and custom workspace roles.
+
+use std::collections::HashSet;
+
+use nvisy_postgres::model::WorkspaceCustomRole;
+use nvisy_postgres::types::WorkspaceRole;
+use uuid::Uuid;
+
+use super::Permission;
+
+/// Tracing target for role resolution.
+const TRACING_TARGET: &str = "nvisy_server::service::rbac";
+
+/// Built-in workspace roles.
+///
+/// Editor and viewer are the API names of the stored `member` and `guest`
+/// roles; the stored values are kept so existing memberships, invites and
+/// API key scopes keep their meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinRole {
+    /// Full control over the workspace.
+    Owner,
+    /// Manages members, integrations and settings.
+    Admin,
+    /// Works with files and pipelines.
+    Editor,
+    /// Read-only access.
+    Viewer,
+}
+
+impl BuiltinRole {
+    /// All built-in roles, from most to least privileged.
+    pub const ALL: [Self; 4] = [Self::Owner, Self::Admin, Self::Editor, Self::Viewer];
+
+    /// Returns the stored workspace role.
+    pub const fn workspace_role(self) -> WorkspaceRole {
+        match self {
+            Self::Owner => WorkspaceRole::Owner,
+            Self::Admin => WorkspaceRole::Admin,
+            Self::Editor => WorkspaceRole::Member,
+            Self::Viewer => WorkspaceRole::Guest,
+        }
+    }
+
+    /// Returns the display name of the role.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Owner => "Owner",
+            Self::Admin => "Admin",
+            Self::Editor => "Editor",
+            Self::Viewer => "Viewer",
+        }
+    }
+
+    /// Returns a short description of the role.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Owner => "Full control over the workspace, its members and roles.",
+            Self::Admin => "Manages members, connections, policies and webhooks.",
+            Self::Editor => "Uploads files and creates and runs pipelines.",
+            Self::Viewer => "Read-only access to workspace content.",
+        }
+    }
+
+    /// Returns the permissions the role grants.
+    pub fn permissions(self) -> Vec<Permission> {
+        Permission::permissions_for_role(self.workspace_role())
+    }
+}
+
+impl From<WorkspaceRole> for BuiltinRole {
+    fn from(role: WorkspaceRole) -> Self {
+        match role {
+            WorkspaceRole::Owner => Self::Owner,
+            WorkspaceRole::Admin => Self::Admin,
+            WorkspaceRole::Member => Self::Editor,
+            WorkspaceRole::Guest => Self::Viewer,
+        }
+    }
+}
+
+/// A workspace-defined role granting an explicit set of permissions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomRole {
+    /// Role ID.
+    pub id: Uuid,
+    /// Display name.
+    pub name: String,
+    /// Granted permissions.
+    pub permissions: HashSet<Permission>,
+}
+
+impl From<WorkspaceCustomRole> for CustomRole {
+    fn from(role: WorkspaceCustomRole) -> Self {
+        // Keys are validated on write, but a permission removed in a later
+        // release may still be stored; it simply no longer grants anything.
+        let permissions = role
+            .permissions
+            .iter()
+            .filter_map(|key| match key.parse::<Permission>() {
+                Ok(permission) if permission.is_assignable() => Some(permission),
+                _ => {
+                    tracing::warn!(
+                        target: TRACING_TARGET,
+                        role_id = %role.id,
+                        permission = %key,
+                        "Ignoring unknown or reserved custom role permission"
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            id: role.id,
+            name: role.name,
+            permissions,
+        }
+    }
+}
+
+/// The roles an account holds in a workspace.
+///
+/// Every member has a built-in role; a custom role adds its permissions on
+/// top of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleGrant {
+    /// Built-in role.
+    pub role: WorkspaceRole,
+    /// Custom role, if one is assigned.
+    pub custom: Option<CustomRole>,
+}
+
+impl RoleGrant {
+    /// Creates a grant of a built-in role only.
+    pub const fn new(role: WorkspaceRole) -> Self {
+        Self { role, custom: None }
+    }
+
+    /// Adds a custom role to the grant.
+    pub fn with_custom(mut self, custom: Option<CustomRole>) -> Self {
+        self.custom = custom;
+        self
+    }
+
+    /// Returns whether the grant includes a permission.
+    pub fn permits(&self, permission: Permission) -> bool {
+        permission.is_permitted_by_role(self.role)
+            || self
+                .custom
+                .as_ref()
+                .is_some_and(|custom| custom.permissions.contains(&permission))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(permissions: &[Permission]) -> CustomRole {
+        CustomRole {
+            id: Uuid::nil(),
+            name: "Auditor".to_owned(),
+            permissions: permissions.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_roles_round_trip() {
+        for role in BuiltinRole::ALL {
+            assert_eq!(BuiltinRole::from(role.workspace_role()), role);
+        }
+    }
+
+    #[test]
+    fn test_grant_builtin_only() {
+        let grant = RoleGrant::new(WorkspaceRole::Guest);
+        assert!(grant.permits(Permission::ViewFiles));
+        assert!(!grant.permits(Permission::UploadFiles));
+    }
+
+    #[test]
+    fn test_grant_adds_custom_permissions() {
+        let grant = RoleGrant::new(WorkspaceRole::Guest)
+            .with_custom(Some(custom(&[Permission::ManagePolicies])));
+        assert!(grant.permits(Permission::ViewFiles));
+        assert!(grant.permits(Permission::ManagePolicies));
+        assert!(!grant.permits(Permission::ManageConnections));
+    }
+}
@@ -0,0 +1,18 @@
+//! Data residency error types.
+
+use nvisy_postgres::types::DataRegion;
+use thiserror::Error;
+
+/// Result type for data residency routing.
+pub type ResidencyResult<T> = Result<T, ResidencyError>;
+
+/// Errors that can occur while routing a workspace to its region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ResidencyError {
+    /// The requested region is not offered by this deployment.
+    #[error("data region '{0}' is not available")]
+    UnsupportedRegion(DataRegion),
+    /// A workspace is pinned to a region with no configured backends.
+    #[error("no backends are configured for data region '{0}'")]
+    MissingBackends(DataRegion),
+}
@@ -0,0 +1,120 @@
+//! Data residency routing.
+//!
+//! Each workspace is pinned to a [`DataRegion`] at creation. Object storage
+//! and inference for the workspace are then served by the backends configured
+//! for that region ([`ResidencyService::backends`]); the `global` region uses
+//! the deployment's default backends.
+//!
+//! Regional backends are declared in a JSON file:
+//!
+//! ```json
+//! {
+//!   "regions": {
+//!     "eu": {
+//!       "natsUrl": "nats://nats.eu.internal:4222",
+//!       "natsToken": "secret://nats/eu",
+//!       "engineConfigPath": "/etc/nvisy/engine.eu.json"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Tokens, like any string in the file, may be `secret://` references
+//! resolved through the secrets provider.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use nvisy_postgres::types::DataRegion;
+use serde::Deserialize;
+
+mod error;
+mod service;
+
+pub use error::{ResidencyError, ResidencyResult};
+pub use service::{RegionBackends, ResidencyService};
+
+use crate::service::SecretsService;
+use crate::{Error, Result};
+
+/// Tracing target for data residency operations.
+const TRACING_TARGET: &str = "nvisy_server::service::residency";
+
+/// Data residency configuration.
+#[derive(Debug, Clone, Default)]
+#[must_use = "config does nothing unless you use it"]
+pub struct ResidencyConfig {
+    /// Optional path to a JSON file declaring the regional backends.
+    ///
+    /// Absent means only the `global` region is offered.
+    pub config_path: Option<PathBuf>,
+}
+
+/// The regional backends, as loaded from the residency config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegionFile {
+    /// Backends per pinned region.
+    #[serde(default)]
+    regions: HashMap<DataRegion, RegionConfig>,
+}
+
+/// The backends serving one pinned region.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegionConfig {
+    /// NATS server URL for the region's object storage.
+    nats_url: String,
+    /// NATS authentication token.
+    #[serde(default)]
+    nats_token: String,
+    /// Optional path to the region's engine recognizer lineups.
+    #[serde(default)]
+    engine_config_path: Option<PathBuf>,
+}
+
+/// Reads and parses the regional backends from a JSON config file.
+async fn load_regions(path: &Path, secrets: &SecretsService) -> Result<RegionFile> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| Error::config("Failed to read residency config file").with_source(e))?;
+
+    let mut document: serde_json::Value = serde_json::from_slice(&bytes)
+        .map_err(|e| Error::config("Failed to parse residency config file").with_source(e))?;
+    secrets
+        .resolve_json(&mut document)
+        .await
+        .map_err(|e| Error::config("Failed to resolve residency config secrets").with_source(e))?;
+
+    let regions: RegionFile = serde_json::from_value(document)
+        .map_err(|e| Error::config("Failed to parse residency config file").with_source(e))?;
+
+    if regions.regions.contains_key(&DataRegion::Global) {
+        return Err(Error::config(
+            "The global region uses the default backends and cannot be configured",
+        ));
+    }
+
+    Ok(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_region_file() {
+        let json = r#"{
+            "regions": {
+                "eu": { "natsUrl": "nats://eu:4222", "engineConfigPath": "/etc/eu.json" }
+            }
+        }"#;
+
+        let file: RegionFile = serde_json::from_str(json).unwrap();
+        let eu = &file.regions[&DataRegion::Eu];
+        assert_eq!(eu.nats_url, "nats://eu:4222");
+        assert_eq!(eu.nats_token, "");
+        assert_eq!(eu.engine_config_path, Some(PathBuf::from("/etc/eu.json")));
+        assert!(!file.regions.contains_key(&DataRegion::Us));
+    }
+}
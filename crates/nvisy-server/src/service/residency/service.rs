@@ -0,0 +1,200 @@
+//! Region-pinned backend routing.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use nvisy_core::crypto::CryptoProvider;
+use nvisy_core::health::HealthCheck;
+use nvisy_nats::{NatsClient, NatsConfig};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::query::{AdminScope, WorkspaceRepository};
+use nvisy_postgres::types::DataRegion;
+
+use super::{
+    RegionConfig, ResidencyConfig, ResidencyError, ResidencyResult, TRACING_TARGET, load_regions,
+};
+use crate::service::{EngineConfig, EngineService, SecretsService};
+use crate::{Error, Result};
+
+/// The storage and inference backends serving one region.
+#[derive(Clone)]
+pub struct RegionBackends {
+    nats: NatsClient,
+    engine: EngineService,
+}
+
+impl RegionBackends {
+    /// Creates the backends for a region.
+    pub fn new(nats: NatsClient, engine: EngineService) -> Self {
+        Self { nats, engine }
+    }
+
+    /// Returns the NATS client holding the region's object storage.
+    #[inline]
+    pub fn nats(&self) -> &NatsClient {
+        &self.nats
+    }
+
+    /// Returns the redaction engine running the region's inference.
+    #[inline]
+    pub fn engine(&self) -> &EngineService {
+        &self.engine
+    }
+
+    /// Connects to the backends declared for a pinned region.
+    async fn connect(
+        region: DataRegion,
+        config: RegionConfig,
+        crypto_provider: Arc<dyn CryptoProvider>,
+        secrets: &SecretsService,
+    ) -> Result<Self> {
+        let nats_config = NatsConfig::new(config.nats_url, config.nats_token)
+            .with_name(format!("nvisy-server-{region}"))
+            .with_crypto_provider(crypto_provider);
+        let nats = NatsClient::connect(nats_config).await.map_err(|e| {
+            Error::external(
+                "NATS",
+                format!("Failed to connect to NATS for region '{region}'"),
+            )
+            .with_source(e)
+        })?;
+
+        // The region's object buckets expire like the default backends'.
+        nats.sync_object_bucket_ttls().await.map_err(|e| {
+            Error::external(
+                "NATS",
+                format!("Failed to apply object bucket TTLs for region '{region}'"),
+            )
+            .with_source(e)
+        })?;
+
+        let engine_config = EngineConfig {
+            config_path: config.engine_config_path,
+        };
+        let engine = EngineService::from_config(engine_config, secrets).await?;
+
+        Ok(Self { nats, engine })
+    }
+}
+
+/// Routes each workspace to the backends of the region it is pinned to.
+///
+/// Cheaply cloneable; every clone shares the same regional connections.
+#[derive(Clone)]
+pub struct ResidencyService {
+    regions: Arc<HashMap<DataRegion, RegionBackends>>,
+}
+
+impl ResidencyService {
+    /// Connects to every regional backend declared in the configuration.
+    ///
+    /// `global` is served by `default_backends`. Fails if any declared
+    /// backend cannot be reached, so a misconfigured region is caught at
+    /// startup rather than on a customer's first upload.
+    pub async fn from_config(
+        config: &ResidencyConfig,
+        default_backends: RegionBackends,
+        secrets: &SecretsService,
+    ) -> Result<Self> {
+        let declared = match &config.config_path {
+            Some(path) => load_regions(path, secrets).await?.regions,
+            None => HashMap::new(),
+        };
+
+        // Regional downloads are verified with the default client's provider.
+        let crypto_provider = default_backends.nats().config().crypto_provider.clone();
+
+        let mut regions = HashMap::with_capacity(declared.len() + 1);
+        regions.insert(DataRegion::Global, default_backends);
+
+        for (region, region_config) in declared {
+            let backends =
+                RegionBackends::connect(region, region_config, crypto_provider.clone(), secrets)
+                    .await?;
+            tracing::info!(
+                target: TRACING_TARGET,
+                region = %region,
+                "Regional backends connected"
+            );
+            regions.insert(region, backends);
+        }
+
+        Ok(Self {
+            regions: Arc::new(regions),
+        })
+    }
+
+    /// Verifies that every region existing workspaces are pinned to has
+    /// backends configured.
+    pub async fn validate_regions_in_use(&self, pg_client: &PgClient) -> Result<()> {
+        let mut conn = pg_client.get_connection().await.map_err(|e| {
+            Error::external("postgres", "Failed to get a database connection").with_source(e)
+        })?;
+
+        let admin = AdminScope::open(&mut conn, "validate workspace data regions")
+            .await
+            .map_err(|e| {
+                Error::external("postgres", "Failed to open an admin scope").with_source(e)
+            })?;
+        let in_use = conn
+            .list_workspace_data_regions(&admin)
+            .await
+            .map_err(|e| {
+                Error::external("postgres", "Failed to list workspace data regions").with_source(e)
+            })?;
+
+        let missing: Vec<String> = in_use
+            .into_iter()
+            .filter(|region| !self.regions.contains_key(region))
+            .map(|region| region.to_string())
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(Error::config(format!(
+                "Workspaces are pinned to data regions with no configured backends: {}",
+                missing.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether workspaces may be pinned to `region`.
+    #[inline]
+    pub fn is_supported(&self, region: DataRegion) -> bool {
+        self.regions.contains_key(&region)
+    }
+
+    /// Fails unless workspaces may be pinned to `region`.
+    pub fn ensure_supported(&self, region: DataRegion) -> ResidencyResult<()> {
+        if self.is_supported(region) {
+            Ok(())
+        } else {
+            Err(ResidencyError::UnsupportedRegion(region))
+        }
+    }
+
+    /// Returns the backends serving `region`.
+    pub fn backends(&self, region: DataRegion) -> ResidencyResult<&RegionBackends> {
+        self.regions
+            .get(&region)
+            .ok_or(ResidencyError::MissingBackends(region))
+    }
+
+    /// Returns every region with configured backends, `global` included.
+    pub fn regions(&self) -> impl Iterator<Item = (DataRegion, &RegionBackends)> {
+        self.regions
+            .iter()
+            .map(|(region, backends)| (*region, backends))
+    }
+
+    /// Returns health checks for the regional backends (excluding `global`,
+    /// whose backends are checked on their own).
+    pub fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        self.regions
+            .iter()
+            .filter(|(region, _)| region.is_pinned())
+            .map(|(_, backends)| Arc::new(backends.nats.clone()) as Arc<dyn HealthCheck>)
+            .collect()
+    }
+}
@@ -0,0 +1,452 @@
+//! Per-workspace data retention.
+//!
+//! A workspace may set how long it keeps its files, its finished pipeline
+//! runs (with their stored analyses) and its audit records, and each pipeline
+//! how long it keeps the input, intermediate and output artifacts of its
+//! finished runs. [`RetentionPurge`]
+//! periodically removes whatever has outlived those periods, deleting the
+//! stored objects in the workspace's region before the rows that reference
+//! them. Files are purged whether or not they were soft-deleted; audit
+//! records keep the newest record of the chain, as the deployment-wide audit
+//! retention does.
+//!
+//! Active legal holds suspend all of this: a workspace-wide hold stops every
+//! purge in the workspace, and a file hold keeps the file and its runs. The
+//! artifacts of a run whose findings still await review are kept until the
+//! review is done.
+//! [`RetentionService::preview`] reports what a purge would remove without
+//! removing anything.
+
+mod purge;
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use jiff::Timestamp;
+use nvisy_nats::object::{
+    AnalysesBucket, FileKey, FilesBucket, IntermediateKey, IntermediatesBucket, ObjectBucket,
+    ObjectKey, ObjectStore,
+};
+use nvisy_postgres::model::{
+    Workspace, WorkspaceFile, WorkspacePipeline, WorkspaceRetentionPolicy,
+};
+use nvisy_postgres::query::{
+    AdminScope, TenantScope, WorkspaceActivityRepository, WorkspaceRepository,
+    WorkspaceRetentionRepository,
+};
+use nvisy_postgres::types::ArtifactType;
+use nvisy_postgres::{PgClient, PgConn};
+use strum::IntoEnumIterator;
+
+pub use self::purge::RetentionPurge;
+use crate::handler::{Error, ErrorKind, Result};
+use crate::service::{RegionBackends, ResidencyService};
+
+/// Tracing target for retention.
+const TRACING_TARGET: &str = "nvisy_server::service::retention";
+
+/// Default interval between purge passes.
+pub const DEFAULT_RETENTION_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Retention configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct RetentionConfig {
+    /// How often expired workspace data is purged.
+    pub purge_interval: Duration,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            purge_interval: DEFAULT_RETENTION_PURGE_INTERVAL,
+        }
+    }
+}
+
+/// Counts of workspace data past its retention period.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    /// Files, including soft-deleted ones.
+    pub files: u64,
+    /// Finished pipeline runs.
+    pub pipeline_runs: u64,
+    /// Files storing artifacts of finished pipeline runs.
+    pub artifacts: u64,
+    /// Audit records.
+    pub activities: u64,
+}
+
+impl RetentionReport {
+    /// Returns the total number of records.
+    pub fn total(&self) -> u64 {
+        self.files + self.pipeline_runs + self.artifacts + self.activities
+    }
+
+    fn add(&mut self, other: Self) {
+        self.files += other.files;
+        self.pipeline_runs += other.pipeline_runs;
+        self.artifacts += other.artifacts;
+        self.activities += other.activities;
+    }
+}
+
+/// Applies workspace retention policies.
+#[derive(Clone)]
+pub struct RetentionService {
+    config: RetentionConfig,
+    pg_client: PgClient,
+    residency: ResidencyService,
+}
+
+impl RetentionService {
+    /// Creates a new retention service.
+    pub fn new(config: RetentionConfig, pg_client: PgClient, residency: ResidencyService) -> Self {
+        Self {
+            config,
+            pg_client,
+            residency,
+        }
+    }
+
+    /// Returns the retention configuration.
+    pub fn config(&self) -> &RetentionConfig {
+        &self.config
+    }
+
+    /// Counts what a purge of the scope's workspace would remove right now.
+    pub async fn preview(&self, scope: TenantScope) -> Result<RetentionReport> {
+        let mut conn = self.pg_client.get_connection().await?;
+
+        let mut report = RetentionReport::default();
+        for pipeline in conn
+            .list_workspace_artifact_retention_pipelines(scope)
+            .await?
+        {
+            for artifact_type in ArtifactType::iter() {
+                if let Some(cutoff) = cutoff(pipeline.artifact_retention_days(artifact_type)) {
+                    report.artifacts += conn
+                        .count_expired_pipeline_artifact_files(
+                            scope,
+                            pipeline.id,
+                            artifact_type,
+                            cutoff,
+                        )
+                        .await? as u64;
+                }
+            }
+        }
+
+        let Some(policy) = conn.find_workspace_retention_policy(scope).await? else {
+            return Ok(report);
+        };
+        if let Some(cutoff) = cutoff(policy.file_retention_days) {
+            report.files = conn.count_expired_workspace_files(scope, cutoff).await? as u64;
+        }
+        if let Some(cutoff) = cutoff(policy.run_retention_days) {
+            report.pipeline_runs = conn.count_expired_pipeline_runs(scope, cutoff).await? as u64;
+        }
+        if let Some(cutoff) = cutoff(policy.activity_retention_days) {
+            report.activities = conn
+                .count_expired_workspace_activities(scope, cutoff)
+                .await? as u64;
+        }
+
+        Ok(report)
+    }
+
+    /// Removes up to `limit` expired records of each kind from every
+    /// workspace with a retention policy, and up to `limit` expired artifact
+    /// files of each stage from every pipeline that limits one.
+    ///
+    /// A workspace or pipeline that fails is logged and skipped, so one
+    /// unreachable region does not stall the others.
+    pub async fn purge_expired(&self, limit: i64) -> Result<RetentionReport> {
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "purge data past workspace retention").await?;
+
+        let mut removed = RetentionReport::default();
+        // Artifacts go first: their rows would otherwise be removed with
+        // expired runs, leaving the files they point to behind.
+        let pipelines = conn.list_artifact_retention_pipelines(&admin).await?;
+        for pipeline in &pipelines {
+            match self
+                .purge_pipeline_artifacts(
+                    &mut conn,
+                    admin.tenant(pipeline.workspace_id),
+                    pipeline,
+                    limit,
+                )
+                .await
+            {
+                Ok(artifacts) => removed.artifacts += artifacts,
+                Err(err) => tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    workspace_id = %pipeline.workspace_id,
+                    pipeline_id = %pipeline.id,
+                    "Failed to purge expired pipeline artifacts"
+                ),
+            }
+        }
+
+        let policies = conn.list_active_retention_policies(&admin).await?;
+        for policy in &policies {
+            let scope = admin.tenant(policy.workspace_id);
+            match self.purge_workspace(&mut conn, scope, policy, limit).await {
+                Ok(report) => removed.add(report),
+                Err(err) => tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    workspace_id = %policy.workspace_id,
+                    "Failed to purge expired workspace data"
+                ),
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Removes one batch of each kind of expired data from a workspace.
+    async fn purge_workspace(
+        &self,
+        conn: &mut PgConn,
+        scope: TenantScope,
+        policy: &WorkspaceRetentionPolicy,
+        limit: i64,
+    ) -> Result<RetentionReport> {
+        let workspace = conn
+            .find_workspace_by_id(scope)
+            .await?
+            .ok_or_else(|| Error::not_found("workspace"))?;
+
+        let mut report = RetentionReport::default();
+        if let Some(cutoff) = cutoff(policy.run_retention_days) {
+            report.pipeline_runs = self
+                .purge_runs(conn, &workspace, scope, cutoff, limit)
+                .await?;
+        }
+        if let Some(cutoff) = cutoff(policy.file_retention_days) {
+            report.files = self
+                .purge_files(conn, &workspace, scope, cutoff, limit)
+                .await?;
+        }
+        if let Some(cutoff) = cutoff(policy.activity_retention_days) {
+            report.activities = conn
+                .cleanup_workspace_activities(scope, cutoff, limit)
+                .await? as u64;
+        }
+
+        if report.total() > 0 {
+            tracing::info!(
+                target: TRACING_TARGET,
+                workspace_id = %workspace.id,
+                files = report.files,
+                pipeline_runs = report.pipeline_runs,
+                activities = report.activities,
+                "Purged expired workspace data"
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Removes one batch of expired artifact files of each stage from a
+    /// pipeline's finished runs.
+    async fn purge_pipeline_artifacts(
+        &self,
+        conn: &mut PgConn,
+        scope: TenantScope,
+        pipeline: &WorkspacePipeline,
+        limit: i64,
+    ) -> Result<u64> {
+        let workspace = conn
+            .find_workspace_by_id(scope)
+            .await?
+            .ok_or_else(|| Error::not_found("workspace"))?;
+
+        let mut removed = 0;
+        for artifact_type in ArtifactType::iter() {
+            let Some(cutoff) = cutoff(pipeline.artifact_retention_days(artifact_type)) else {
+                continue;
+            };
+            let files = conn
+                .list_expired_pipeline_artifact_files(
+                    scope,
+                    pipeline.id,
+                    artifact_type,
+                    cutoff,
+                    limit,
+                )
+                .await?;
+            removed += self
+                .delete_file_contents(conn, &workspace, scope, &files)
+                .await?;
+        }
+
+        if removed > 0 {
+            tracing::info!(
+                target: TRACING_TARGET,
+                workspace_id = %workspace.id,
+                pipeline_id = %pipeline.id,
+                artifacts = removed,
+                "Purged expired pipeline artifacts"
+            );
+        }
+
+        Ok(removed)
+    }
+
+    /// Removes expired files, their stored content and their runs' analyses.
+    async fn purge_files(
+        &self,
+        conn: &mut PgConn,
+        workspace: &Workspace,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> Result<u64> {
+        let files = conn
+            .list_expired_workspace_files(scope, cutoff, limit)
+            .await?;
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        let backends = self.residency.backends(workspace.data_region)?;
+        let file_ids: Vec<_> = files.iter().map(|file| file.id).collect();
+        // The runs go with their files; a failure here leaves every file in
+        // place for the next pass.
+        for key in conn.list_file_analysis_keys(scope, &file_ids).await? {
+            delete_analysis(backends, &key).await?;
+        }
+
+        self.delete_file_contents(conn, workspace, scope, &files)
+            .await
+    }
+
+    /// Deletes the stored content of files, then the files whose content is
+    /// gone.
+    async fn delete_file_contents(
+        &self,
+        conn: &mut PgConn,
+        workspace: &Workspace,
+        scope: TenantScope,
+        files: &[WorkspaceFile],
+    ) -> Result<u64> {
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        let store = self
+            .residency
+            .backends(workspace.data_region)?
+            .nats()
+            .object_store::<FilesBucket, FileKey>()
+            .await?;
+        let mut removable = Vec::with_capacity(files.len());
+        for file in files {
+            let deleted = match FileKey::from_str(&file.storage_path) {
+                Ok(key) => delete_object(&store, &key).await,
+                Err(err) => Err(ErrorKind::InternalServerError
+                    .with_message("Invalid file storage path")
+                    .with_context(err.to_string())),
+            };
+            match deleted {
+                Ok(()) => removable.push(file.id),
+                Err(err) => tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    file_id = %file.id,
+                    "Failed to delete expired file content"
+                ),
+            }
+        }
+
+        if removable.is_empty() {
+            return Ok(0);
+        }
+        Ok(conn.purge_workspace_files(scope, &removable).await? as u64)
+    }
+
+    /// Removes expired finished runs and their stored analyses.
+    async fn purge_runs(
+        &self,
+        conn: &mut PgConn,
+        workspace: &Workspace,
+        scope: TenantScope,
+        cutoff: Timestamp,
+        limit: i64,
+    ) -> Result<u64> {
+        let runs = conn
+            .list_expired_pipeline_runs(scope, cutoff, limit)
+            .await?;
+        if runs.is_empty() {
+            return Ok(0);
+        }
+
+        let backends = self.residency.backends(workspace.data_region)?;
+        let mut removable = Vec::with_capacity(runs.len());
+        for run in &runs {
+            let deleted = match run.analyzed_document_key.as_deref() {
+                Some(key) => delete_analysis(backends, key).await,
+                None => Ok(()),
+            };
+            match deleted {
+                Ok(()) => removable.push(run.id),
+                Err(err) => tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    run_id = %run.id,
+                    "Failed to delete expired run analysis"
+                ),
+            }
+        }
+
+        if removable.is_empty() {
+            return Ok(0);
+        }
+        Ok(conn.purge_pipeline_runs(scope, &removable).await? as u64)
+    }
+}
+
+/// Returns the cutoff for a retention period in days, if one is set.
+fn cutoff(days: Option<i32>) -> Option<Timestamp> {
+    let days = u64::try_from(days?).ok()?;
+    let period = Duration::from_secs(days * 24 * 60 * 60);
+    Some(
+        Timestamp::now()
+            .checked_sub(period)
+            .unwrap_or(Timestamp::MIN),
+    )
+}
+
+/// Deletes a run's stored analysis, wherever it was stored.
+async fn delete_analysis(backends: &RegionBackends, key: &str) -> Result<()> {
+    let key = IntermediateKey::from_str(key).map_err(|err| {
+        ErrorKind::InternalServerError
+            .with_message("Invalid analysis storage key")
+            .with_context(err.to_string())
+    })?;
+    let nats = backends.nats();
+    let analyses = nats
+        .object_store::<AnalysesBucket, IntermediateKey>()
+        .await?;
+    delete_object(&analyses, &key).await?;
+    let intermediates = nats
+        .object_store::<IntermediatesBucket, IntermediateKey>()
+        .await?;
+    delete_object(&intermediates, &key).await
+}
+
+/// Deletes an object, treating one already gone as deleted.
+async fn delete_object<B, K>(store: &ObjectStore<B, K>, key: &K) -> Result<()>
+where
+    B: ObjectBucket,
+    K: ObjectKey,
+{
+    if store.exists(key).await? {
+        store.delete(key).await?;
+    }
+    Ok(())
+}
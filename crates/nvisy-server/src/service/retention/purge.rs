@@ -0,0 +1,96 @@
+//! Removal of workspace data past its retention period.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::{RetentionReport, RetentionService};
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for the retention purge.
+const TRACING_TARGET: &str = "nvisy_server::worker::retention_purge";
+
+/// Maximum number of records of each kind removed per workspace and batch.
+const BATCH_SIZE: i64 = 100;
+
+/// Periodically removes data that has outlived its workspace's retention
+/// policy.
+pub struct RetentionPurge {
+    retention: RetentionService,
+    interval: Duration,
+}
+
+impl RetentionPurge {
+    /// Create a new purge worker using the service's configured interval.
+    pub fn new(retention: RetentionService) -> Self {
+        let interval = retention.config().purge_interval;
+        Self {
+            retention,
+            interval,
+        }
+    }
+
+    /// Run purge passes until cancelled.
+    ///
+    /// Every server instance may run the worker: a row removed by another
+    /// instance is no longer listed, and an object already gone counts as
+    /// deleted. `heartbeat` is beaten after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            "Starting retention purge"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Retention purge shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.purge(&cancel).await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Retention purge stopped");
+        Ok(())
+    }
+
+    /// Removes expired data in batches until a batch removes nothing.
+    async fn purge(&self, cancel: &CancellationToken) {
+        let mut removed = RetentionReport::default();
+        while !cancel.is_cancelled() {
+            match self.retention.purge_expired(BATCH_SIZE).await {
+                Ok(batch) if batch.total() == 0 => break,
+                Ok(batch) => removed.add(batch),
+                Err(err) => {
+                    tracing::error!(
+                        target: TRACING_TARGET,
+                        error = %err,
+                        "Failed to purge expired workspace data"
+                    );
+                    break;
+                }
+            }
+        }
+
+        if removed.total() > 0 {
+            tracing::info!(
+                target: TRACING_TARGET,
+                files = removed.files,
+                pipeline_runs = removed.pipeline_runs,
+                artifacts = removed.artifacts,
+                activities = removed.activities,
+                "Purged expired workspace data"
+            );
+        }
+    }
+}
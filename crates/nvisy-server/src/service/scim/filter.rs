@@ -0,0 +1,470 @@
+//! SCIM filter expressions (RFC 7644 §3.4.2.2).
+//!
+//! Filters are parsed into a [`Filter`] tree and evaluated against the JSON
+//! representation of a resource, so one evaluator serves every resource type.
+//! Attribute names are matched case-insensitively and string comparisons
+//! ignore case, which suits the attributes exposed here (`userName`, emails,
+//! display names) but not case-exact ones.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::{ScimError, ScimResult};
+
+/// Comparison operator of an attribute expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// `eq`: equal.
+    Eq,
+    /// `ne`: not equal.
+    Ne,
+    /// `co`: contains.
+    Co,
+    /// `sw`: starts with.
+    Sw,
+    /// `ew`: ends with.
+    Ew,
+    /// `gt`: greater than.
+    Gt,
+    /// `ge`: greater than or equal.
+    Ge,
+    /// `lt`: less than.
+    Lt,
+    /// `le`: less than or equal.
+    Le,
+}
+
+impl CompareOp {
+    fn parse(word: &str) -> Option<Self> {
+        Some(match word.to_ascii_lowercase().as_str() {
+            "eq" => Self::Eq,
+            "ne" => Self::Ne,
+            "co" => Self::Co,
+            "sw" => Self::Sw,
+            "ew" => Self::Ew,
+            "gt" => Self::Gt,
+            "ge" => Self::Ge,
+            "lt" => Self::Lt,
+            "le" => Self::Le,
+            _ => return None,
+        })
+    }
+}
+
+/// A parsed SCIM filter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// `attrPath op value`.
+    Compare {
+        path: String,
+        op: CompareOp,
+        value: Value,
+    },
+    /// `attrPath pr`: the attribute has a non-empty value.
+    Present(String),
+    /// `attrPath[filter]`: some element of a multi-valued attribute matches.
+    ValuePath { path: String, filter: Box<Filter> },
+    /// `filter and filter`.
+    And(Box<Filter>, Box<Filter>),
+    /// `filter or filter`.
+    Or(Box<Filter>, Box<Filter>),
+    /// `not (filter)`.
+    Not(Box<Filter>),
+}
+
+impl FromStr for Filter {
+    type Err = ScimError;
+
+    fn from_str(input: &str) -> ScimResult<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            pos: 0,
+        };
+        let filter = parser.expr()?;
+        match parser.next() {
+            None => Ok(filter),
+            Some(token) => Err(ScimError::InvalidFilter(format!(
+                "unexpected {token:?} after the expression"
+            ))),
+        }
+    }
+}
+
+impl Filter {
+    /// Returns whether `resource` matches the filter.
+    pub fn matches(&self, resource: &Value) -> bool {
+        match self {
+            Self::Compare { path, op, value } => attribute_values(resource, path)
+                .iter()
+                .any(|actual| compare(actual, *op, value)),
+            Self::Present(path) => attribute_values(resource, path)
+                .iter()
+                .any(|value| !is_empty(value)),
+            Self::ValuePath { path, filter } => attribute_values(resource, path)
+                .iter()
+                .any(|element| filter.matches(element)),
+            Self::And(lhs, rhs) => lhs.matches(resource) && rhs.matches(resource),
+            Self::Or(lhs, rhs) => lhs.matches(resource) || rhs.matches(resource),
+            Self::Not(inner) => !inner.matches(resource),
+        }
+    }
+}
+
+/// Strips a schema URN from an attribute path, e.g.
+/// `urn:ietf:params:scim:schemas:core:2.0:User:userName` to `userName`.
+pub(super) fn strip_schema(path: &str) -> &str {
+    if path.starts_with("urn:") {
+        path.rsplit_once(':').map_or(path, |(_, attr)| attr)
+    } else {
+        path
+    }
+}
+
+/// Looks up an object member by name, ignoring case.
+pub(super) fn get_ci<'a>(
+    object: &'a serde_json::Map<String, Value>,
+    name: &str,
+) -> Option<(&'a String, &'a Value)> {
+    object
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+}
+
+/// Collects the values at a dotted attribute path.
+///
+/// Multi-valued attributes fan out, so `emails.value` yields every email
+/// address, and a comparison against a multi-valued complex attribute such as
+/// `emails` compares each element's `value`.
+fn attribute_values<'a>(resource: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut current = vec![resource];
+    for segment in strip_schema(path).split('.') {
+        current = current
+            .into_iter()
+            .flat_map(|value| match value {
+                Value::Array(items) => items.iter().collect(),
+                other => vec![other],
+            })
+            .filter_map(|value| value.as_object())
+            .filter_map(|object| get_ci(object, segment).map(|(_, value)| value))
+            .collect();
+    }
+
+    current
+        .into_iter()
+        .flat_map(|value| match value {
+            Value::Array(items) => items
+                .iter()
+                .map(
+                    |item| match item.as_object().and_then(|o| get_ci(o, "value")) {
+                        Some((_, inner)) => inner,
+                        None => item,
+                    },
+                )
+                .collect(),
+            other => vec![other],
+        })
+        .collect()
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+fn compare(actual: &Value, op: CompareOp, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::String(actual), Value::String(expected)) => {
+            let actual = actual.to_lowercase();
+            let expected = expected.to_lowercase();
+            match op {
+                CompareOp::Eq => actual == expected,
+                CompareOp::Ne => actual != expected,
+                CompareOp::Co => actual.contains(&expected),
+                CompareOp::Sw => actual.starts_with(&expected),
+                CompareOp::Ew => actual.ends_with(&expected),
+                _ => ordered(actual.cmp(&expected), op),
+            }
+        }
+        (Value::Number(actual), Value::Number(expected)) => {
+            match (actual.as_f64(), expected.as_f64()) {
+                (Some(actual), Some(expected)) => match actual.partial_cmp(&expected) {
+                    Some(ordering) => ordered(ordering, op),
+                    None => false,
+                },
+                _ => false,
+            }
+        }
+        (actual, expected) => match op {
+            CompareOp::Eq => actual == expected,
+            CompareOp::Ne => actual != expected,
+            _ => false,
+        },
+    }
+}
+
+fn ordered(ordering: Ordering, op: CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::Ne => ordering.is_ne(),
+        CompareOp::Gt => ordering.is_gt(),
+        CompareOp::Ge => ordering.is_ge(),
+        CompareOp::Lt => ordering.is_lt(),
+        CompareOp::Le => ordering.is_le(),
+        CompareOp::Co | CompareOp::Sw | CompareOp::Ew => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Literal(Value),
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
+}
+
+fn tokenize(input: &str) -> ScimResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | '[' | ']' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::OpenParen,
+                    ')' => Token::CloseParen,
+                    '[' => Token::OpenBracket,
+                    _ => Token::CloseBracket,
+                });
+            }
+            '"' => {
+                // Quoted strings follow JSON escaping rules.
+                chars.next();
+                let mut end = None;
+                let mut escaped = false;
+                for (i, c) in chars.by_ref() {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => {
+                            end = Some(i);
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                let end = end.ok_or_else(|| {
+                    ScimError::InvalidFilter("unterminated string literal".to_owned())
+                })?;
+                let literal = serde_json::from_str(&input[start..=end])
+                    .map_err(|_| ScimError::InvalidFilter("invalid string literal".to_owned()))?;
+                tokens.push(Token::Literal(literal));
+            }
+            _ => {
+                let mut end = input.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '"') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push(Token::Word(input[start..end].to_owned()));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token) -> ScimResult<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(ScimError::InvalidFilter(format!(
+                "expected {expected:?}, found {other:?}"
+            ))),
+        }
+    }
+
+    fn expr(&mut self) -> ScimResult<Filter> {
+        let mut filter = self.and_expr()?;
+        while self.peek_keyword("or") {
+            self.next();
+            filter = Filter::Or(Box::new(filter), Box::new(self.and_expr()?));
+        }
+        Ok(filter)
+    }
+
+    fn and_expr(&mut self) -> ScimResult<Filter> {
+        let mut filter = self.unary()?;
+        while self.peek_keyword("and") {
+            self.next();
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+        Ok(filter)
+    }
+
+    fn unary(&mut self) -> ScimResult<Filter> {
+        if self.peek_keyword("not") {
+            self.next();
+            self.expect(Token::OpenParen)?;
+            let inner = self.expr()?;
+            self.expect(Token::CloseParen)?;
+            return Ok(Filter::Not(Box::new(inner)));
+        }
+
+        match self.next() {
+            Some(Token::OpenParen) => {
+                let inner = self.expr()?;
+                self.expect(Token::CloseParen)?;
+                Ok(inner)
+            }
+            Some(Token::Word(path)) => self.attribute(path),
+            other => Err(ScimError::InvalidFilter(format!(
+                "expected an attribute, found {other:?}"
+            ))),
+        }
+    }
+
+    fn attribute(&mut self, path: String) -> ScimResult<Filter> {
+        if self.peek() == Some(&Token::OpenBracket) {
+            self.next();
+            let filter = self.expr()?;
+            self.expect(Token::CloseBracket)?;
+            return Ok(Filter::ValuePath {
+                path,
+                filter: Box::new(filter),
+            });
+        }
+
+        let op = match self.next() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("pr") => {
+                return Ok(Filter::Present(path));
+            }
+            Some(Token::Word(word)) => CompareOp::parse(&word)
+                .ok_or_else(|| ScimError::InvalidFilter(format!("unknown operator '{word}'")))?,
+            other => {
+                return Err(ScimError::InvalidFilter(format!(
+                    "expected an operator after '{path}', found {other:?}"
+                )));
+            }
+        };
+
+        let value = match self.next() {
+            Some(Token::Literal(value)) => value,
+            Some(Token::Word(word)) => serde_json::from_str(&word.to_ascii_lowercase())
+                .ok()
+                .filter(|value: &Value| !value.is_string())
+                .ok_or_else(|| ScimError::InvalidFilter(format!("invalid value '{word}'")))?,
+            other => {
+                return Err(ScimError::InvalidFilter(format!(
+                    "expected a value, found {other:?}"
+                )));
+            }
+        };
+
+        Ok(Filter::Compare { path, op, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn user() -> Value {
+        json!({
+            "userName": "Ada@Example.com",
+            "displayName": "Ada Lovelace",
+            "active": true,
+            "emails": [
+                { "value": "ada@example.com", "type": "work", "primary": true },
+                { "value": "ada@home.example", "type": "home" }
+            ],
+            "meta": { "created": "2026-01-01T00:00:00Z" }
+        })
+    }
+
+    fn matches(filter: &str) -> bool {
+        filter.parse::<Filter>().unwrap().matches(&user())
+    }
+
+    #[test]
+    fn test_attribute_comparisons() {
+        assert!(matches(r#"userName eq "ada@example.com""#));
+        assert!(matches(r#"USERNAME Eq "ADA@EXAMPLE.COM""#));
+        assert!(matches(r#"displayName sw "Ada" and active eq true"#));
+        assert!(matches(r#"emails co "home.example""#));
+        assert!(matches(r#"emails.type eq "home""#));
+        assert!(matches(r#"meta.created gt "2025-12-31T00:00:00Z""#));
+        assert!(matches("displayName pr"));
+        assert!(!matches("nickName pr"));
+        assert!(matches(
+            r#"urn:ietf:params:scim:schemas:core:2.0:User:userName ew "example.com""#
+        ));
+    }
+
+    #[test]
+    fn test_logical_operators() {
+        assert!(matches(r#"userName eq "nobody" or active eq true"#));
+        assert!(!matches(r#"not (active eq true)"#));
+        assert!(matches(
+            r#"(userName eq "nobody" or displayName co "love") and active eq true"#
+        ));
+    }
+
+    #[test]
+    fn test_value_path() {
+        assert!(matches(
+            r#"emails[type eq "work" and value co "example.com"]"#
+        ));
+        assert!(!matches(r#"emails[type eq "other"]"#));
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        for filter in [
+            "",
+            "userName",
+            r#"userName xx "a""#,
+            r#"userName eq "a"#,
+            r#"userName eq "a" extra"#,
+            "userName eq bare",
+            r#"(userName eq "a""#,
+        ] {
+            assert!(filter.parse::<Filter>().is_err(), "{filter}");
+        }
+    }
+}
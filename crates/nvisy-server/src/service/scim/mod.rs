@@ -0,0 +1,76 @@
+//! SCIM 2.0 provisioning protocol support (RFC 7643, RFC 7644).
+//!
+//! Identity providers push users and group memberships through SCIM. This
+//! module holds the protocol pieces that are independent of storage: the
+//! resource model, filter expressions and PATCH semantics. The mapping onto
+//! accounts and workspace members lives with the SCIM handlers.
+
+mod filter;
+mod patch;
+mod resource;
+
+pub use filter::{CompareOp, Filter};
+pub use patch::{PatchOpKind, PatchOperation, PatchRequest};
+pub use resource::{
+    ScimEmail, ScimGroup, ScimListResponse, ScimMember, ScimMeta, ScimName, ScimUser,
+};
+
+/// Schema URN of the core `User` resource.
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+
+/// Schema URN of the core `Group` resource.
+pub const SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+
+/// Schema URN of list query responses.
+pub const SCHEMA_LIST_RESPONSE: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+
+/// Schema URN of PATCH request bodies.
+pub const SCHEMA_PATCH_OP: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+
+/// Schema URN of the service provider configuration.
+pub const SCHEMA_SERVICE_PROVIDER_CONFIG: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+
+/// Protocol-level SCIM errors, each carrying the RFC 7644 `scimType`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScimError {
+    /// The filter expression is malformed or uses an unsupported operator.
+    #[error("invalid filter: {0}")]
+    InvalidFilter(String),
+    /// The request body does not follow the SCIM message structure.
+    #[error("invalid syntax: {0}")]
+    InvalidSyntax(String),
+    /// An attribute path is malformed or does not apply to the resource.
+    #[error("invalid path: {0}")]
+    InvalidPath(String),
+    /// A value is missing or has the wrong type.
+    #[error("invalid value: {0}")]
+    InvalidValue(String),
+    /// A filtered path matched no values.
+    #[error("no target: {0}")]
+    NoTarget(String),
+    /// The operation would change a read-only or immutable attribute.
+    #[error("mutability: {0}")]
+    Mutability(String),
+    /// The value would collide with another resource.
+    #[error("uniqueness: {0}")]
+    Uniqueness(String),
+}
+
+impl ScimError {
+    /// Returns the RFC 7644 `scimType` keyword.
+    pub fn scim_type(&self) -> &'static str {
+        match self {
+            Self::InvalidFilter(_) => "invalidFilter",
+            Self::InvalidSyntax(_) => "invalidSyntax",
+            Self::InvalidPath(_) => "invalidPath",
+            Self::InvalidValue(_) => "invalidValue",
+            Self::NoTarget(_) => "noTarget",
+            Self::Mutability(_) => "mutability",
+            Self::Uniqueness(_) => "uniqueness",
+        }
+    }
+}
+
+/// Result type for SCIM protocol operations.
+pub type ScimResult<T> = std::result::Result<T, ScimError>;
@@ -0,0 +1,272 @@
+//! SCIM core resources (RFC 7643) and list envelopes.
+//!
+//! Optional attributes serialize as `null` rather than being skipped: the
+//! PATCH engine needs every attribute present to resolve paths to their
+//! canonical names, and SCIM treats `null` as unassigned.
+
+use jiff::Timestamp;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{SCHEMA_GROUP, SCHEMA_LIST_RESPONSE, SCHEMA_USER};
+
+/// A SCIM `User`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    /// Schemas the resource conforms to.
+    #[serde(default = "user_schemas")]
+    pub schemas: Vec<String>,
+    /// Server-assigned identifier (the account id).
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Identifier assigned by the provisioning client; echoed, not stored.
+    #[serde(default)]
+    pub external_id: Option<String>,
+    /// Unique login name: the account's email address.
+    pub user_name: String,
+    /// Components of the user's name.
+    #[serde(default)]
+    pub name: Option<ScimName>,
+    /// Name shown for the user.
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Email addresses; the primary one is the account's email.
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    /// Whether the user is a member of the workspace.
+    #[serde(default = "default_true", deserialize_with = "lenient_bool")]
+    pub active: bool,
+    /// Groups the user belongs to; read-only.
+    #[serde(default)]
+    pub groups: Vec<ScimMember>,
+    /// Resource metadata; read-only.
+    #[serde(default)]
+    pub meta: Option<ScimMeta>,
+}
+
+impl ScimUser {
+    /// Returns the email address the user should have: the primary email,
+    /// else the first one, else the user name when it looks like an email.
+    pub fn email(&self) -> Option<&str> {
+        self.emails
+            .iter()
+            .find(|email| email.primary)
+            .or_else(|| self.emails.first())
+            .map(|email| email.value.as_str())
+            .or_else(|| Some(self.user_name.as_str()).filter(|name| name.contains('@')))
+    }
+
+    /// Returns the name to display: `displayName`, else the formatted name,
+    /// else the given and family names joined.
+    pub fn preferred_display_name(&self) -> Option<String> {
+        let name = self.name.as_ref();
+        self.display_name
+            .clone()
+            .or_else(|| name.and_then(|name| name.formatted.clone()))
+            .or_else(|| {
+                let parts = name.map(|name| [&name.given_name, &name.family_name])?;
+                let joined = parts
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(joined).filter(|joined| !joined.is_empty())
+            })
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+    }
+}
+
+/// Components of a user's name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    /// Full name, formatted for display.
+    #[serde(default)]
+    pub formatted: Option<String>,
+    /// Given name.
+    #[serde(default)]
+    pub given_name: Option<String>,
+    /// Family name.
+    #[serde(default)]
+    pub family_name: Option<String>,
+}
+
+/// An email address of a user.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScimEmail {
+    /// The address.
+    pub value: String,
+    /// Label such as `work`.
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    /// Whether this is the user's primary address.
+    #[serde(default, deserialize_with = "lenient_bool")]
+    pub primary: bool,
+}
+
+/// A SCIM `Group`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    /// Schemas the resource conforms to.
+    #[serde(default = "group_schemas")]
+    pub schemas: Vec<String>,
+    /// Server-assigned identifier.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Name of the group.
+    pub display_name: String,
+    /// Users in the group.
+    #[serde(default)]
+    pub members: Vec<ScimMember>,
+    /// Resource metadata; read-only.
+    #[serde(default)]
+    pub meta: Option<ScimMeta>,
+}
+
+/// A reference from a group to a member, or from a user to a group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ScimMember {
+    /// Identifier of the referenced resource.
+    pub value: String,
+    /// Human-readable name of the referenced resource.
+    #[serde(default)]
+    pub display: Option<String>,
+    /// Location of the referenced resource.
+    #[serde(default, rename = "$ref")]
+    pub reference: Option<String>,
+}
+
+/// Resource metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    /// `User` or `Group`.
+    pub resource_type: String,
+    /// When the resource was created.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub created: Option<Timestamp>,
+    /// When the resource was last modified.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub last_modified: Option<Timestamp>,
+    /// Path of the resource.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+/// A page of resources returned by a SCIM list query.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T> {
+    /// Always the `ListResponse` message schema.
+    pub schemas: Vec<String>,
+    /// Number of resources matching the query.
+    pub total_results: usize,
+    /// 1-based index of the first resource in this page.
+    pub start_index: usize,
+    /// Number of resources in this page.
+    pub items_per_page: usize,
+    /// The resources.
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+impl<T> ScimListResponse<T> {
+    /// Paginates matching resources with SCIM's 1-based `startIndex` and
+    /// `count` parameters.
+    pub fn paginate(resources: Vec<T>, start_index: usize, count: usize) -> Self {
+        let total_results = resources.len();
+        let start_index = start_index.max(1);
+        let resources: Vec<T> = resources
+            .into_iter()
+            .skip(start_index - 1)
+            .take(count)
+            .collect();
+
+        Self {
+            schemas: vec![SCHEMA_LIST_RESPONSE.to_owned()],
+            total_results,
+            start_index,
+            items_per_page: resources.len(),
+            resources,
+        }
+    }
+}
+
+fn user_schemas() -> Vec<String> {
+    vec![SCHEMA_USER.to_owned()]
+}
+
+fn group_schemas() -> Vec<String> {
+    vec![SCHEMA_GROUP.to_owned()]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Accepts booleans sent as strings (`"True"`, `"false"`), which some
+/// identity providers do.
+fn lenient_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lenient {
+        Bool(bool),
+        String(String),
+    }
+
+    match Lenient::deserialize(deserializer)? {
+        Lenient::Bool(value) => Ok(value),
+        Lenient::String(value) if value.eq_ignore_ascii_case("true") => Ok(true),
+        Lenient::String(value) if value.eq_ignore_ascii_case("false") => Ok(false),
+        Lenient::String(value) => Err(serde::de::Error::custom(format!(
+            "expected a boolean, found '{value}'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_user_email_and_name() {
+        let user: ScimUser = serde_json::from_value(json!({
+            "userName": "ada",
+            "active": "False",
+            "name": { "givenName": "Ada", "familyName": "Lovelace" },
+            "emails": [
+                { "value": "ada@home.example" },
+                { "value": "ada@example.com", "primary": "true" }
+            ]
+        }))
+        .unwrap();
+
+        assert!(!user.active);
+        assert_eq!(user.email(), Some("ada@example.com"));
+        assert_eq!(
+            user.preferred_display_name().as_deref(),
+            Some("Ada Lovelace")
+        );
+        assert_eq!(user.schemas, vec![SCHEMA_USER]);
+    }
+
+    #[test]
+    fn test_paginate_is_one_based() {
+        let page = ScimListResponse::paginate(vec![1, 2, 3, 4, 5], 2, 2);
+        assert_eq!(page.resources, vec![2, 3]);
+        assert_eq!(page.total_results, 5);
+        assert_eq!(page.items_per_page, 2);
+
+        let page = ScimListResponse::paginate(vec![1, 2], 0, 10);
+        assert_eq!(page.start_index, 1);
+        assert_eq!(page.resources, vec![1, 2]);
+    }
+}
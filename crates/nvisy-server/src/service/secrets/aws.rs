@@ -0,0 +1,82 @@
+//! AWS Secrets Manager secrets provider.
+
+use async_trait::async_trait;
+use aws_sdk_secretsmanager::Client;
+
+use super::error::SecretsResult;
+use super::{SecretValue, SecretsError, SecretsProvider};
+
+/// Secrets stored in AWS Secrets Manager.
+///
+/// A secret's name is its Secrets Manager name; its value is the secret
+/// string of the `AWSCURRENT` version. Rotating a secret stores a new
+/// version, which becomes `AWSCURRENT`. Credentials and region come from the
+/// standard AWS configuration chain.
+pub struct AwsSecretsManager {
+    client: Client,
+}
+
+impl AwsSecretsManager {
+    /// Creates a client from the ambient AWS configuration.
+    pub async fn new() -> Self {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self {
+            client: Client::new(&config),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManager {
+    fn name(&self) -> &'static str {
+        "aws"
+    }
+
+    async fn get(&self, name: &str) -> SecretsResult<Option<SecretValue>> {
+        let output = match self.client.get_secret_value().secret_id(name).send().await {
+            Ok(output) => output,
+            Err(err)
+                if err
+                    .as_service_error()
+                    .is_some_and(|e| e.is_resource_not_found_exception()) =>
+            {
+                return Ok(None);
+            }
+            Err(err) => return Err(SecretsError::backend(self.name(), err)),
+        };
+
+        output
+            .secret_string()
+            .map(|value| Some(SecretValue::new(value)))
+            .ok_or_else(|| SecretsError::Malformed {
+                name: name.to_owned(),
+                reason: "secret has no string value".to_owned(),
+            })
+    }
+
+    async fn rotate(&self, name: &str, value: &SecretValue) -> SecretsResult<()> {
+        self.client
+            .put_secret_value()
+            .secret_id(name)
+            .secret_string(value.expose())
+            .send()
+            .await
+            .map_err(|e| SecretsError::backend(self.name(), e))?;
+        Ok(())
+    }
+
+    async fn list(&self) -> SecretsResult<Vec<String>> {
+        let mut names = Vec::new();
+        let mut pages = self.client.list_secrets().into_paginator().send();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| SecretsError::backend(self.name(), e))?;
+            names.extend(
+                page.secret_list()
+                    .iter()
+                    .filter_map(|entry| entry.name().map(str::to_owned)),
+            );
+        }
+        names.sort();
+        Ok(names)
+    }
+}
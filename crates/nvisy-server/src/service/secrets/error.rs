@@ -0,0 +1,39 @@
+//! Secrets provider error types.
+
+use thiserror::Error;
+
+/// Result type for secrets provider operations.
+pub type SecretsResult<T> = Result<T, SecretsError>;
+
+/// Errors that can occur while reading or rotating secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SecretsError {
+    /// The provider cannot perform the operation.
+    #[error("secrets provider '{provider}' does not support {operation}")]
+    Unsupported {
+        provider: &'static str,
+        operation: &'static str,
+    },
+    /// A configuration value references a secret the provider does not hold.
+    #[error("secret '{0}' not found")]
+    NotFound(String),
+    /// The secret exists but its stored value is unusable.
+    #[error("secret '{name}' is malformed: {reason}")]
+    Malformed { name: String, reason: String },
+    /// The provider could not be reached or rejected the request.
+    #[error("secrets provider '{provider}' failed: {message}")]
+    Backend {
+        provider: &'static str,
+        message: String,
+    },
+}
+
+impl SecretsError {
+    /// Creates a backend error for the named provider.
+    pub(crate) fn backend(provider: &'static str, message: impl ToString) -> Self {
+        Self::Backend {
+            provider,
+            message: message.to_string(),
+        }
+    }
+}
@@ -0,0 +1,445 @@
+//! Provider credentials from a secrets manager.
+//!
+//! Credentials need not appear in plaintext in the environment or in config
+//! files. Any string in the engine lineups, the residency file or the NATS
+//! token may instead reference a secret as `secret://<name>`, which is
+//! resolved through the configured [`SecretsProvider`] when the config is
+//! loaded:
+//!
+//! ```json
+//! { "llm": { "provider": "openai", "apiKey": "secret://llm/openai" } }
+//! ```
+//!
+//! Three providers are available: [`EnvSecrets`] (the default), which reads
+//! prefixed environment variables; [`VaultSecrets`], which reads a HashiCorp
+//! Vault KV v2 engine; and `AwsSecretsManager` (behind the `aws-secrets`
+//! feature). [`SecretsService`] caches what it reads for a configurable
+//! period and notifies rotation hooks when a secret's value changes.
+
+#[cfg(feature = "aws-secrets")]
+mod aws;
+mod error;
+mod provider;
+mod vault;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use strum::{Display, EnumString};
+use url::Url;
+
+#[cfg(feature = "aws-secrets")]
+pub use self::aws::AwsSecretsManager;
+pub use self::error::{SecretsError, SecretsResult};
+pub use self::provider::{EnvSecrets, SecretValue, SecretsProvider};
+pub use self::vault::VaultSecrets;
+use crate::{Error, Result};
+
+/// Tracing target for secrets resolution.
+const TRACING_TARGET: &str = "nvisy_server::service::secrets";
+
+/// Prefix marking a config string as a secret reference.
+pub const SECRET_REFERENCE_PREFIX: &str = "secret://";
+
+/// Default time a secret is served from the cache.
+pub const DEFAULT_SECRETS_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Default prefix of environment variables holding secrets.
+pub const DEFAULT_SECRETS_ENV_PREFIX: &str = "NVISY_SECRET_";
+
+/// Which secrets manager credentials are read from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum SecretsBackend {
+    /// Prefixed environment variables.
+    #[default]
+    Env,
+    /// HashiCorp Vault KV version 2.
+    Vault,
+    /// AWS Secrets Manager; requires a build with the `aws-secrets` feature.
+    Aws,
+}
+
+/// Secrets manager configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct SecretsConfig {
+    /// Secrets manager credentials are read from.
+    pub backend: SecretsBackend,
+    /// How long a read secret is served from the cache.
+    pub cache_ttl: Duration,
+    /// Prefix of the environment variables read by the `env` backend.
+    pub env_prefix: String,
+    /// Address of the Vault server, e.g. `https://vault.internal:8200`.
+    pub vault_address: Option<String>,
+    /// Token authenticating with Vault.
+    pub vault_token: Option<String>,
+    /// Mount path of the Vault KV v2 engine.
+    pub vault_mount: String,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            backend: SecretsBackend::default(),
+            cache_ttl: DEFAULT_SECRETS_CACHE_TTL,
+            env_prefix: DEFAULT_SECRETS_ENV_PREFIX.to_owned(),
+            vault_address: None,
+            vault_token: None,
+            vault_mount: "secret".to_owned(),
+        }
+    }
+}
+
+/// Called with a secret's name when its value changes.
+type RotationHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// A secret as last read from the provider.
+struct CachedSecret {
+    value: Option<SecretValue>,
+    read_at: Instant,
+}
+
+struct SecretsInner {
+    provider: Arc<dyn SecretsProvider>,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, CachedSecret>>,
+    hooks: RwLock<Vec<RotationHook>>,
+}
+
+/// Reads secrets through the configured provider, with caching.
+///
+/// Cheaply cloneable; every clone shares one cache and one set of rotation
+/// hooks. Absent secrets are cached too, so a lookup that usually misses
+/// does not reach the provider on every call.
+#[derive(Clone)]
+pub struct SecretsService {
+    inner: Arc<SecretsInner>,
+}
+
+impl SecretsService {
+    /// Creates a service over an existing provider.
+    pub fn new(provider: Arc<dyn SecretsProvider>, cache_ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(SecretsInner {
+                provider,
+                cache_ttl,
+                cache: Mutex::new(HashMap::new()),
+                hooks: RwLock::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Creates the provider selected by the configuration.
+    pub async fn from_config(config: &SecretsConfig) -> Result<Self> {
+        let provider: Arc<dyn SecretsProvider> = match config.backend {
+            SecretsBackend::Env => Arc::new(EnvSecrets::new(&config.env_prefix)),
+            SecretsBackend::Vault => {
+                let (Some(address), Some(token)) = (&config.vault_address, &config.vault_token)
+                else {
+                    return Err(Error::config("Vault requires an address and a token"));
+                };
+                let address = Url::parse(address)
+                    .map_err(|e| Error::config("Invalid Vault address").with_source(e))?;
+                Arc::new(VaultSecrets::new(
+                    address,
+                    SecretValue::new(token.clone()),
+                    &config.vault_mount,
+                ))
+            }
+            #[cfg(feature = "aws-secrets")]
+            SecretsBackend::Aws => Arc::new(AwsSecretsManager::new().await),
+            #[cfg(not(feature = "aws-secrets"))]
+            SecretsBackend::Aws => {
+                return Err(Error::config(
+                    "AWS Secrets Manager is not available: build with the `aws-secrets` feature",
+                ));
+            }
+        };
+
+        tracing::info!(
+            target: TRACING_TARGET,
+            provider = provider.name(),
+            cache_ttl = ?config.cache_ttl,
+            "Secrets provider configured",
+        );
+
+        Ok(Self::new(provider, config.cache_ttl))
+    }
+
+    /// Returns the underlying provider.
+    pub fn provider(&self) -> &dyn SecretsProvider {
+        self.inner.provider.as_ref()
+    }
+
+    /// Returns a secret's value, or `None` if it does not exist.
+    ///
+    /// Served from the cache while the last read is fresh. A re-read that
+    /// finds a different value than the cached one notifies the rotation
+    /// hooks.
+    pub async fn get(&self, name: &str) -> SecretsResult<Option<SecretValue>> {
+        if let Some(value) = self.cached(name) {
+            return Ok(value);
+        }
+
+        let value = self.inner.provider.get(name).await?;
+        if self.store(name, value.clone()) {
+            self.notify(name);
+        }
+        Ok(value)
+    }
+
+    /// Returns a secret's value, failing if it does not exist.
+    pub async fn require(&self, name: &str) -> SecretsResult<SecretValue> {
+        self.get(name)
+            .await?
+            .ok_or_else(|| SecretsError::NotFound(name.to_owned()))
+    }
+
+    /// Stores a new version of a secret and notifies the rotation hooks.
+    pub async fn rotate(&self, name: &str, value: SecretValue) -> SecretsResult<()> {
+        self.inner.provider.rotate(name, &value).await?;
+        self.store(name, Some(value));
+        self.notify(name);
+
+        tracing::info!(target: TRACING_TARGET, secret = name, "Secret rotated");
+        Ok(())
+    }
+
+    /// Lists the names of every secret the provider holds.
+    pub async fn list(&self) -> SecretsResult<Vec<String>> {
+        self.inner.provider.list().await
+    }
+
+    /// Drops a secret from the cache, so the next read reaches the provider.
+    pub fn invalidate(&self, name: &str) {
+        self.lock_cache().remove(name);
+    }
+
+    /// Registers a hook called with a secret's name whenever its value
+    /// changes, whether rotated here or observed changed on a re-read.
+    pub fn on_rotation(&self, hook: impl Fn(&str) + Send + Sync + 'static) {
+        self.inner
+            .hooks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(hook));
+    }
+
+    /// Resolves a config string, reading it from the provider if it is a
+    /// `secret://` reference and returning it unchanged otherwise.
+    pub async fn resolve_str(&self, value: &str) -> SecretsResult<String> {
+        match value.strip_prefix(SECRET_REFERENCE_PREFIX) {
+            Some(name) => Ok(self.require(name).await?.into_inner()),
+            None => Ok(value.to_owned()),
+        }
+    }
+
+    /// Replaces every `secret://` reference in a JSON document with the
+    /// secret's value.
+    pub async fn resolve_json(&self, value: &mut serde_json::Value) -> SecretsResult<()> {
+        let mut pending = vec![value];
+        while let Some(value) = pending.pop() {
+            match value {
+                serde_json::Value::String(s) if s.starts_with(SECRET_REFERENCE_PREFIX) => {
+                    let resolved = self.resolve_str(s).await?;
+                    *s = resolved;
+                }
+                serde_json::Value::Array(items) => pending.extend(items.iter_mut()),
+                serde_json::Value::Object(fields) => pending.extend(fields.values_mut()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedSecret>> {
+        self.inner.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the cached value if it is still fresh.
+    fn cached(&self, name: &str) -> Option<Option<SecretValue>> {
+        let cache = self.lock_cache();
+        let entry = cache.get(name)?;
+        (entry.read_at.elapsed() < self.inner.cache_ttl).then(|| entry.value.clone())
+    }
+
+    /// Caches a value, returning whether it replaced a different one.
+    fn store(&self, name: &str, value: Option<SecretValue>) -> bool {
+        let entry = CachedSecret {
+            value: value.clone(),
+            read_at: Instant::now(),
+        };
+        let previous = self.lock_cache().insert(name.to_owned(), entry);
+        previous.is_some_and(|previous| previous.value != value)
+    }
+
+    fn notify(&self, name: &str) {
+        let hooks = self
+            .inner
+            .hooks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for hook in hooks {
+            hook(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// An in-memory provider counting reads.
+    #[derive(Default)]
+    struct MemorySecrets {
+        values: Mutex<HashMap<String, String>>,
+        reads: AtomicUsize,
+    }
+
+    impl MemorySecrets {
+        fn set(&self, name: &str, value: &str) {
+            self.values
+                .lock()
+                .unwrap()
+                .insert(name.to_owned(), value.to_owned());
+        }
+    }
+
+    #[async_trait]
+    impl SecretsProvider for MemorySecrets {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        async fn get(&self, name: &str) -> SecretsResult<Option<SecretValue>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.values.lock().unwrap().get(name).map(SecretValue::new))
+        }
+
+        async fn rotate(&self, name: &str, value: &SecretValue) -> SecretsResult<()> {
+            self.set(name, value.expose());
+            Ok(())
+        }
+
+        async fn list(&self) -> SecretsResult<Vec<String>> {
+            Ok(self.values.lock().unwrap().keys().cloned().collect())
+        }
+    }
+
+    fn service(ttl: Duration) -> (Arc<MemorySecrets>, SecretsService) {
+        let provider = Arc::new(MemorySecrets::default());
+        let service = SecretsService::new(provider.clone(), ttl);
+        (provider, service)
+    }
+
+    #[tokio::test]
+    async fn test_get_is_cached() {
+        let (provider, secrets) = service(Duration::from_secs(60));
+        provider.set("llm/openai", "sk-1");
+
+        assert_eq!(
+            secrets.require("llm/openai").await.unwrap().expose(),
+            "sk-1"
+        );
+        provider.set("llm/openai", "sk-2");
+        assert_eq!(
+            secrets.require("llm/openai").await.unwrap().expose(),
+            "sk-1"
+        );
+        assert_eq!(provider.reads.load(Ordering::SeqCst), 1);
+
+        secrets.invalidate("llm/openai");
+        assert_eq!(
+            secrets.require("llm/openai").await.unwrap().expose(),
+            "sk-2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_secrets_are_cached() {
+        let (provider, secrets) = service(Duration::from_secs(60));
+
+        assert_eq!(secrets.get("absent").await.unwrap(), None);
+        assert_eq!(secrets.get("absent").await.unwrap(), None);
+        assert_eq!(provider.reads.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            secrets.require("absent").await,
+            Err(SecretsError::NotFound("absent".to_owned()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_changed_value_notifies_hooks() {
+        let (provider, secrets) = service(Duration::ZERO);
+        let rotations = Arc::new(AtomicUsize::new(0));
+        let counter = rotations.clone();
+        secrets.on_rotation(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        provider.set("nats/eu", "a");
+        secrets.get("nats/eu").await.unwrap();
+        secrets.get("nats/eu").await.unwrap();
+        assert_eq!(rotations.load(Ordering::SeqCst), 0);
+
+        provider.set("nats/eu", "b");
+        secrets.get("nats/eu").await.unwrap();
+        assert_eq!(rotations.load(Ordering::SeqCst), 1);
+
+        secrets
+            .rotate("nats/eu", SecretValue::new("c"))
+            .await
+            .unwrap();
+        assert_eq!(rotations.load(Ordering::SeqCst), 2);
+        assert_eq!(secrets.require("nats/eu").await.unwrap().expose(), "c");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_json_references() {
+        let (provider, secrets) = service(Duration::from_secs(60));
+        provider.set("llm/openai", "sk-1");
+
+        let mut config = serde_json::json!({
+            "llm": { "apiKey": "secret://llm/openai", "model": "gpt" },
+            "fallbacks": [{ "apiKey": "secret://llm/openai" }],
+        });
+        secrets.resolve_json(&mut config).await.unwrap();
+        assert_eq!(config["llm"]["apiKey"], "sk-1");
+        assert_eq!(config["llm"]["model"], "gpt");
+        assert_eq!(config["fallbacks"][0]["apiKey"], "sk-1");
+
+        let mut missing = serde_json::json!({ "token": "secret://absent" });
+        assert!(secrets.resolve_json(&mut missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_plain_string() {
+        let (_, secrets) = service(Duration::from_secs(60));
+        assert_eq!(secrets.resolve_str("plain").await.unwrap(), "plain");
+    }
+
+    #[test]
+    fn test_secrets_backend_parse() {
+        assert_eq!(
+            "env".parse::<SecretsBackend>().unwrap(),
+            SecretsBackend::Env
+        );
+        assert_eq!(
+            "vault".parse::<SecretsBackend>().unwrap(),
+            SecretsBackend::Vault
+        );
+        assert_eq!(
+            "aws".parse::<SecretsBackend>().unwrap(),
+            SecretsBackend::Aws
+        );
+        assert!("gcp".parse::<SecretsBackend>().is_err());
+    }
+}
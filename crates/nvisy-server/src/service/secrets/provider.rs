@@ -0,0 +1,161 @@
+//! Secrets provider trait and the environment-backed implementation.
+
+use std::fmt;
+
+use async_trait::async_trait;
+
+use super::SecretsError;
+use super::error::SecretsResult;
+
+/// A secret value whose `Debug` output never reveals it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretValue(String);
+
+impl SecretValue {
+    /// Wraps a secret value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Returns the secret in plaintext.
+    #[inline]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the wrapper, returning the secret in plaintext.
+    #[inline]
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretValue(..)")
+    }
+}
+
+/// A store of named secrets.
+///
+/// Names are slash-separated paths such as `llm/openai`; each provider maps
+/// them onto its own namespace.
+#[async_trait]
+pub trait SecretsProvider: Send + Sync + 'static {
+    /// Name of the provider, used in logs and errors.
+    fn name(&self) -> &'static str;
+
+    /// Returns the current value of a secret, or `None` if it does not exist.
+    async fn get(&self, name: &str) -> SecretsResult<Option<SecretValue>>;
+
+    /// Stores `value` as the new current version of a secret.
+    async fn rotate(&self, name: &str, value: &SecretValue) -> SecretsResult<()>;
+
+    /// Lists the names of every secret the provider holds.
+    async fn list(&self) -> SecretsResult<Vec<String>>;
+}
+
+/// Secrets read from environment variables.
+///
+/// A secret named `llm/openai` is read from `{prefix}LLM_OPENAI`: the name is
+/// upper-cased and every character other than a letter or digit becomes `_`.
+/// The environment is read-only, so rotation is not supported.
+#[derive(Debug, Clone)]
+pub struct EnvSecrets {
+    prefix: String,
+}
+
+impl EnvSecrets {
+    /// Creates a provider reading variables that start with `prefix`.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Returns the environment variable holding a secret.
+    pub fn variable(&self, name: &str) -> String {
+        let suffix: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}{suffix}", self.prefix)
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for EnvSecrets {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    async fn get(&self, name: &str) -> SecretsResult<Option<SecretValue>> {
+        let variable = self.variable(name);
+        match std::env::var(&variable) {
+            Ok(value) => Ok(Some(SecretValue::new(value))),
+            Err(std::env::VarError::NotPresent) => Ok(None),
+            Err(std::env::VarError::NotUnicode(_)) => Err(SecretsError::Malformed {
+                name: name.to_owned(),
+                reason: format!("{variable} is not valid UTF-8"),
+            }),
+        }
+    }
+
+    async fn rotate(&self, _name: &str, _value: &SecretValue) -> SecretsResult<()> {
+        Err(SecretsError::Unsupported {
+            provider: self.name(),
+            operation: "rotation",
+        })
+    }
+
+    /// Lists the variables carrying the prefix, lower-cased with the prefix
+    /// removed. Separators cannot be recovered, so `llm/openai` is listed as
+    /// `llm_openai`.
+    async fn list(&self) -> SecretsResult<Vec<String>> {
+        let mut names: Vec<String> = std::env::vars_os()
+            .filter_map(|(key, _)| {
+                let key = key.into_string().ok()?;
+                let name = key.strip_prefix(&self.prefix)?;
+                (!name.is_empty()).then(|| name.to_ascii_lowercase())
+            })
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_variable_name() {
+        let env = EnvSecrets::new("NVISY_SECRET_");
+        assert_eq!(env.variable("llm/openai"), "NVISY_SECRET_LLM_OPENAI");
+        assert_eq!(env.variable("eu.nats-token"), "NVISY_SECRET_EU_NATS_TOKEN");
+    }
+
+    #[tokio::test]
+    async fn test_env_missing_secret() {
+        let env = EnvSecrets::new("NVISY_SECRET_TEST_UNSET_");
+        assert_eq!(env.get("anything").await.unwrap(), None);
+        assert!(
+            env.rotate("anything", &SecretValue::new("x"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_secret_value_debug_is_redacted() {
+        let value = SecretValue::new("hunter2");
+        assert_eq!(format!("{value:?}"), "SecretValue(..)");
+        assert_eq!(value.expose(), "hunter2");
+    }
+}
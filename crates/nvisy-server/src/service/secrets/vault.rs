@@ -0,0 +1,180 @@
+//! HashiCorp Vault secrets provider (KV version 2).
+
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+use super::error::SecretsResult;
+use super::{SecretValue, SecretsError, SecretsProvider};
+
+/// Header carrying the Vault token.
+const TOKEN_HEADER: &str = "X-Vault-Token";
+
+/// Field of the KV entry holding the secret.
+const VALUE_FIELD: &str = "value";
+
+/// Secrets stored in a HashiCorp Vault KV v2 engine.
+///
+/// A secret named `llm/openai` is the `value` field of the entry at
+/// `{mount}/data/llm/openai`; rotating it writes a new version of the entry.
+pub struct VaultSecrets {
+    client: Client,
+    address: Url,
+    token: SecretValue,
+    mount: String,
+}
+
+/// Envelope of every Vault response body.
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+/// Body of a KV v2 read.
+#[derive(Debug, Deserialize)]
+struct KvEntry {
+    data: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Body of a KV v2 metadata listing.
+#[derive(Debug, Deserialize)]
+struct KvKeys {
+    keys: Vec<String>,
+}
+
+impl VaultSecrets {
+    /// Creates a provider for the KV engine mounted at `mount`.
+    pub fn new(address: Url, token: SecretValue, mount: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            address,
+            token,
+            mount: mount.into().trim_matches('/').to_owned(),
+        }
+    }
+
+    /// Builds the URL of a KV v2 API path beneath the mount.
+    fn url(&self, section: &str, name: &str) -> SecretsResult<Url> {
+        let path = format!("v1/{}/{section}/{}", self.mount, name.trim_matches('/'));
+        self.address
+            .join(&path)
+            .map_err(|e| SecretsError::backend(self.name(), e))
+    }
+
+    /// Sends an authenticated request, returning `None` on 404.
+    async fn send(&self, request: RequestBuilder) -> SecretsResult<Option<reqwest::Response>> {
+        let response = request
+            .header(TOKEN_HEADER, self.token.expose())
+            .send()
+            .await
+            .map_err(|e| SecretsError::backend(self.name(), e))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response)),
+            status => Err(SecretsError::backend(
+                self.name(),
+                format!("unexpected status {status}"),
+            )),
+        }
+    }
+
+    /// Lists the keys directly beneath a folder; folders end with `/`.
+    async fn list_folder(&self, folder: &str) -> SecretsResult<Vec<String>> {
+        let mut url = self.url("metadata", folder)?;
+        url.query_pairs_mut().append_pair("list", "true");
+
+        let Some(response) = self.send(self.client.get(url)).await? else {
+            return Ok(Vec::new());
+        };
+        let body: Response<KvKeys> = response
+            .json()
+            .await
+            .map_err(|e| SecretsError::backend(self.name(), e))?;
+        Ok(body.data.keys)
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultSecrets {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn get(&self, name: &str) -> SecretsResult<Option<SecretValue>> {
+        let url = self.url("data", name)?;
+        let Some(response) = self.send(self.client.get(url)).await? else {
+            return Ok(None);
+        };
+
+        let body: Response<KvEntry> = response
+            .json()
+            .await
+            .map_err(|e| SecretsError::backend(self.name(), e))?;
+        match body.data.data.get(VALUE_FIELD) {
+            Some(serde_json::Value::String(value)) => Ok(Some(SecretValue::new(value.clone()))),
+            _ => Err(SecretsError::Malformed {
+                name: name.to_owned(),
+                reason: format!("entry has no string '{VALUE_FIELD}' field"),
+            }),
+        }
+    }
+
+    async fn rotate(&self, name: &str, value: &SecretValue) -> SecretsResult<()> {
+        let url = self.url("data", name)?;
+        let body = json!({ "data": { VALUE_FIELD: value.expose() } });
+        self.send(self.client.post(url).json(&body))
+            .await?
+            .ok_or_else(|| SecretsError::NotFound(name.to_owned()))?;
+        Ok(())
+    }
+
+    async fn list(&self) -> SecretsResult<Vec<String>> {
+        let mut names = Vec::new();
+        let mut folders = vec![String::new()];
+        while let Some(folder) = folders.pop() {
+            for key in self.list_folder(&folder).await? {
+                let path = format!("{folder}{key}");
+                if key.ends_with('/') {
+                    folders.push(path);
+                } else {
+                    names.push(path);
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_urls() {
+        let vault = VaultSecrets::new(
+            Url::parse("https://vault.internal:8200/").unwrap(),
+            SecretValue::new("token"),
+            "/secret/",
+        );
+
+        assert_eq!(
+            vault.url("data", "llm/openai").unwrap().as_str(),
+            "https://vault.internal:8200/v1/secret/data/llm/openai"
+        );
+        assert_eq!(
+            vault.url("metadata", "").unwrap().as_str(),
+            "https://vault.internal:8200/v1/secret/metadata/"
+        );
+    }
+
+    #[test]
+    fn test_parse_kv_entry() {
+        let body = r#"{"data": {"data": {"value": "sk-123"}, "metadata": {"version": 3}}}"#;
+        let entry: Response<KvEntry> = serde_json::from_str(body).unwrap();
+        assert_eq!(entry.data.data[VALUE_FIELD], "sk-123");
+    }
+}
@@ -0,0 +1,148 @@
+//! Account API key issuance and authentication lookup.
+//!
+//! Keys are `nvk_`-prefixed random secrets. Only the SHA-256 digest of a key
+//! is stored; the plaintext is shown to the caller once at creation. Lookups
+//! on the authentication path go through a short-lived cache keyed by
+//! digest, so an API-key request normally costs no database round trip.
+
+use std::time::Duration;
+
+use nvisy_nats::kv::{ApiKey, DigestKey};
+use nvisy_postgres::PgConn;
+use nvisy_postgres::model::AccountApiKey;
+use nvisy_postgres::query::AccountApiKeyRepository;
+
+use crate::handler::Result;
+use crate::service::crypto::CryptoResult;
+use crate::service::{Cache, CryptoService};
+
+/// Tracing target for API key operations.
+const TRACING_TARGET: &str = "nvisy_server::api_keys";
+
+/// Prefix distinguishing API keys from session JWTs in a bearer header.
+pub const API_KEY_PREFIX: &str = "nvk_";
+
+/// How long a rotated key keeps working alongside its replacement.
+pub const DEFAULT_ROTATION_GRACE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of leading key characters kept for display.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// A freshly generated API key.
+pub struct IssuedApiKey {
+    /// Full plaintext key, returned to the caller exactly once.
+    pub secret: String,
+    /// Leading characters of the key, stored for display.
+    pub key_prefix: String,
+    /// SHA-256 digest of the key, stored for lookup.
+    pub key_hash: [u8; 32],
+}
+
+/// Issues account API keys and resolves presented keys.
+#[derive(Clone)]
+pub struct ApiKeyService {
+    cache: Cache<DigestKey, ApiKey>,
+    crypto: CryptoService,
+}
+
+impl ApiKeyService {
+    /// Creates a new API key service.
+    pub fn new(cache: Cache<DigestKey, ApiKey>, crypto: CryptoService) -> Self {
+        Self { cache, crypto }
+    }
+
+    /// Returns whether a bearer token is an API key rather than a JWT.
+    #[inline]
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(API_KEY_PREFIX)
+    }
+
+    /// Generates a new key.
+    pub fn issue(&self) -> CryptoResult<IssuedApiKey> {
+        let secret = format!("{API_KEY_PREFIX}{}", self.crypto.generate_secret()?);
+        Ok(IssuedApiKey {
+            key_prefix: secret[..DISPLAY_PREFIX_LEN].to_owned(),
+            key_hash: self.crypto.sha256(secret.as_bytes()),
+            secret,
+        })
+    }
+
+    /// Resolves a presented key to its cached entry.
+    ///
+    /// Returns `None` for unknown, revoked or expired keys. A cache miss falls
+    /// back to the database and records the key's use, so `last_used_at` is
+    /// accurate to within the cache TTL.
+    pub async fn resolve(&self, conn: &mut PgConn, secret: &str) -> Result<Option<ApiKey>> {
+        let key_hash = self.crypto.sha256(secret.as_bytes());
+        let cache_key = DigestKey::from_digest(&key_hash);
+
+        match self.cache.get_value(&cache_key).await {
+            Ok(Some(cached)) if !cached.is_expired() => return Ok(Some(cached)),
+            Ok(_) => {}
+            Err(err) => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    "API key cache read failed, falling back to database"
+                );
+            }
+        }
+
+        let Some(key) = conn.find_active_account_api_key_by_hash(&key_hash).await? else {
+            return Ok(None);
+        };
+        conn.touch_account_api_key(key.id).await?;
+
+        let entry = Self::cache_entry(&key);
+        if let Err(err) = self.cache.put(&cache_key, &entry).await {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                key_id = %key.id,
+                error = %err,
+                "API key cache write failed"
+            );
+        }
+
+        Ok(Some(entry))
+    }
+
+    /// Drops a key from the lookup cache after it was revoked or rotated.
+    ///
+    /// Failures are logged rather than returned: the entry expires with the
+    /// bucket TTL either way.
+    pub async fn invalidate(&self, key: &AccountApiKey) {
+        let cache_key = DigestKey::from_digest(&key.key_hash);
+        if let Err(err) = self.cache.delete(&cache_key).await {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                key_id = %key.id,
+                error = %err,
+                "API key cache invalidation failed"
+            );
+        }
+    }
+
+    fn cache_entry(key: &AccountApiKey) -> ApiKey {
+        ApiKey {
+            key_id: key.id,
+            account_id: key.account_id,
+            scopes: key
+                .granted_scopes()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            expired_at: key.expired_at.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_api_key() {
+        assert!(ApiKeyService::is_api_key("nvk_0123abcd"));
+        assert!(!ApiKeyService::is_api_key("eyJhbGciOiJFZERTQSJ9.e30.sig"));
+    }
+}
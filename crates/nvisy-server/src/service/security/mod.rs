@@ -1,14 +1,19 @@
 //! Security infrastructure services.
 //!
 //! This module provides authentication-related services including password
-//! handling, JWT secret key management, and user agent parsing.
+//! handling, JWT secret key management, account API keys, share link
+//! tokens, and user agent parsing.
 
+mod api_keys;
 mod password;
 mod password_hasher;
 mod password_strength;
 mod session_keys;
+mod share_tokens;
 mod user_agent;
 
+pub use api_keys::{API_KEY_PREFIX, ApiKeyService, DEFAULT_ROTATION_GRACE, IssuedApiKey};
 pub use password::PasswordService;
 pub use session_keys::{SessionKeys, SessionKeysConfig};
+pub use share_tokens::{IssuedShareToken, SHARE_TOKEN_PREFIX};
 pub use user_agent::UserAgentParser;
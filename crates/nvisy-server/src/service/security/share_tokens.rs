@@ -0,0 +1,41 @@
+//! Share link token issuance.
+//!
+//! Share links are `nvs_`-prefixed random secrets carried in the link's URL.
+//! As with API keys, only the SHA-256 digest of a token is stored, so the
+//! full link is shown once, to whoever creates it.
+
+use crate::service::CryptoService;
+use crate::service::crypto::CryptoResult;
+
+/// Prefix distinguishing share link tokens from other secrets.
+pub const SHARE_TOKEN_PREFIX: &str = "nvs_";
+
+/// Number of leading token characters kept for display.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// A freshly generated share link token.
+pub struct IssuedShareToken {
+    /// Full plaintext token, returned to the caller exactly once.
+    pub secret: String,
+    /// Leading characters of the token, stored for display.
+    pub token_prefix: String,
+    /// SHA-256 digest of the token, stored for lookup.
+    pub token_hash: [u8; 32],
+}
+
+impl IssuedShareToken {
+    /// Generates a new token.
+    pub fn issue(crypto: &CryptoService) -> CryptoResult<Self> {
+        let secret = format!("{SHARE_TOKEN_PREFIX}{}", crypto.generate_secret()?);
+        Ok(Self {
+            token_prefix: secret[..DISPLAY_PREFIX_LEN].to_owned(),
+            token_hash: crypto.sha256(secret.as_bytes()),
+            secret,
+        })
+    }
+
+    /// Returns the digest a presented token is looked up by.
+    pub fn hash(crypto: &CryptoService, token: &str) -> [u8; 32] {
+        crypto.sha256(token.as_bytes())
+    }
+}
@@ -0,0 +1,79 @@
+//! Background sampling of stored objects for cost attribution.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use super::StorageCostService;
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for the storage cost worker.
+const TRACING_TARGET: &str = "nvisy_server::worker::storage_cost";
+
+/// Periodically samples stored objects into the monthly cost reports.
+pub struct StorageCostReporter {
+    storage_costs: StorageCostService,
+    interval: Duration,
+}
+
+impl StorageCostReporter {
+    /// Create a new reporter using the service's configured interval.
+    pub fn new(storage_costs: StorageCostService) -> Self {
+        let interval = storage_costs.config().interval;
+        Self {
+            storage_costs,
+            interval,
+        }
+    }
+
+    /// Run sampling passes until cancelled.
+    ///
+    /// Every server instance may run the reporter: a day already accrued by
+    /// another instance is ignored. `heartbeat` is beaten after every pass.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(
+            target: TRACING_TARGET,
+            interval_secs = self.interval.as_secs(),
+            "Starting storage cost reporting"
+        );
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        "Storage cost reporting shutdown requested"
+                    );
+                    break;
+                }
+                _ = ticker.tick() => {
+                    self.sample().await;
+                    heartbeat.beat();
+                }
+            }
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Storage cost reporting stopped");
+        Ok(())
+    }
+
+    async fn sample(&self) {
+        match self.storage_costs.sample().await {
+            Ok(report) => tracing::info!(
+                target: TRACING_TARGET,
+                objects = report.objects,
+                bytes = report.bytes,
+                unattributed = report.unattributed,
+                accrued = report.accrued,
+                "Stored objects sampled"
+            ),
+            Err(err) => tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Failed to sample stored objects"
+            ),
+        }
+    }
+}
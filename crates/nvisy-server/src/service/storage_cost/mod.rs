@@ -0,0 +1,305 @@
+//! Storage cost attribution.
+//!
+//! [`StorageCostReporter`] samples the object stores of every region once a
+//! day. Each object is attributed to the workspace its key names, to the
+//! pipeline stage its bucket holds and to a storage class by age: objects
+//! left untouched for longer than [`StorageCostConfig::cold_after`] are
+//! cold, the rest hot. The day's totals are priced at the provider price in
+//! effect for their region and class and accrued into the month's report,
+//! which the admin analytics API serves.
+//!
+//! Account avatars belong to no workspace and are not attributed.
+
+mod collect;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use jiff::Timestamp;
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use nvisy_nats::object::{
+    AnalysesBucket, ContextFilesBucket, ContextKey, FileKey, FilesBucket, IntermediateKey,
+    IntermediatesBucket, ObjectBucket, ObjectKey, ObjectStore, OperationResultsBucket, ResultKey,
+    ThumbnailsBucket,
+};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::{NewWorkspaceStorageSample, StoragePrice};
+use nvisy_postgres::query::{AdminScope, StorageCostRepository};
+use nvisy_postgres::types::{DataRegion, StorageClass, StorageStage};
+use uuid::Uuid;
+
+pub use self::collect::StorageCostReporter;
+use crate::handler::Result;
+use crate::service::{RegionBackends, ResidencyService};
+
+/// Tracing target for storage cost attribution.
+const TRACING_TARGET: &str = "nvisy_server::service::storage_cost";
+
+/// Default interval between sampling attempts.
+///
+/// A day is accrued once however often it is sampled, so the interval only
+/// bounds how late in the day the sample may be taken.
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Default age after which an untouched object is priced as cold storage.
+pub const DEFAULT_COLD_AFTER: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Storage cost attribution configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct StorageCostConfig {
+    /// How often the object stores are sampled.
+    pub interval: Duration,
+    /// How long an object must go unmodified to be priced as cold storage.
+    pub cold_after: Duration,
+}
+
+impl Default for StorageCostConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_SAMPLE_INTERVAL,
+            cold_after: DEFAULT_COLD_AFTER,
+        }
+    }
+}
+
+/// Outcome of a sampling pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageSampleReport {
+    /// Objects attributed to a workspace.
+    pub objects: u64,
+    /// Bytes attributed to a workspace.
+    pub bytes: u64,
+    /// Objects whose key names no workspace.
+    pub unattributed: u64,
+    /// Reports the sample was accrued into.
+    pub accrued: usize,
+}
+
+/// Object keys that name the workspace owning the object.
+trait WorkspaceObjectKey: ObjectKey {
+    fn workspace_id(&self) -> Uuid;
+}
+
+impl WorkspaceObjectKey for FileKey {
+    fn workspace_id(&self) -> Uuid {
+        self.workspace_id
+    }
+}
+
+impl WorkspaceObjectKey for IntermediateKey {
+    fn workspace_id(&self) -> Uuid {
+        self.workspace_id
+    }
+}
+
+impl WorkspaceObjectKey for ContextKey {
+    fn workspace_id(&self) -> Uuid {
+        self.workspace_id
+    }
+}
+
+impl WorkspaceObjectKey for ResultKey {
+    fn workspace_id(&self) -> Uuid {
+        self.workspace_id
+    }
+}
+
+/// Identifies one row of a month's report.
+type UsageKey = (Uuid, DataRegion, StorageStage, StorageClass);
+
+/// Samples stored objects and accrues their cost per workspace.
+#[derive(Clone)]
+pub struct StorageCostService {
+    config: StorageCostConfig,
+    pg_client: PgClient,
+    residency: ResidencyService,
+}
+
+impl StorageCostService {
+    /// Creates a new storage cost service.
+    pub fn new(
+        config: StorageCostConfig,
+        pg_client: PgClient,
+        residency: ResidencyService,
+    ) -> Self {
+        Self {
+            config,
+            pg_client,
+            residency,
+        }
+    }
+
+    /// Returns the storage cost configuration.
+    pub fn config(&self) -> &StorageCostConfig {
+        &self.config
+    }
+
+    /// Samples every region's object stores and accrues today's usage.
+    ///
+    /// A region that fails is logged and skipped; its usage for the day is
+    /// picked up by a later pass, since a day is only accrued once.
+    pub async fn sample(&self) -> Result<StorageSampleReport> {
+        let now = Timestamp::now();
+        let today = now.to_zoned(TimeZone::UTC).date();
+        let cold_cutoff = now
+            .checked_sub(self.config.cold_after)
+            .unwrap_or(Timestamp::MIN);
+
+        let mut report = StorageSampleReport::default();
+        let mut usage = HashMap::new();
+        for (region, backends) in self.residency.regions() {
+            let mut sampler = Sampler {
+                today,
+                region,
+                cold_cutoff,
+                usage: HashMap::new(),
+                report: StorageSampleReport::default(),
+            };
+
+            match sampler.sample_region(backends).await {
+                Ok(()) => {
+                    usage.extend(sampler.usage);
+                    report.objects += sampler.report.objects;
+                    report.bytes += sampler.report.bytes;
+                    report.unattributed += sampler.report.unattributed;
+                }
+                Err(err) => tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    region = %region,
+                    "Failed to sample stored objects"
+                ),
+            }
+        }
+
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "accrue storage costs").await?;
+        let prices = conn.find_storage_prices_in_effect(&admin, today).await?;
+
+        let samples = usage
+            .into_values()
+            .map(|sample: NewWorkspaceStorageSample| {
+                let price = find_price(&prices, sample.data_region, sample.storage_class);
+                sample.with_price(price)
+            })
+            .collect();
+        report.accrued = conn.record_storage_samples(&admin, samples).await?;
+
+        Ok(report)
+    }
+}
+
+/// Aggregates one region's objects for a day's sample.
+struct Sampler {
+    today: Date,
+    region: DataRegion,
+    cold_cutoff: Timestamp,
+    usage: HashMap<UsageKey, NewWorkspaceStorageSample>,
+    report: StorageSampleReport,
+}
+
+impl Sampler {
+    /// Aggregates every attributable bucket of the region.
+    ///
+    /// Nothing is kept if any bucket fails, so a region is never accrued
+    /// with part of its storage missing.
+    async fn sample_region(&mut self, backends: &RegionBackends) -> Result<()> {
+        let nats = backends.nats();
+
+        let files = nats.object_store::<FilesBucket, FileKey>().await?;
+        self.sample_bucket(&files, StorageStage::Upload).await?;
+
+        let intermediates = nats
+            .object_store::<IntermediatesBucket, IntermediateKey>()
+            .await?;
+        self.sample_bucket(&intermediates, StorageStage::Intermediate)
+            .await?;
+
+        let analyses = nats
+            .object_store::<AnalysesBucket, IntermediateKey>()
+            .await?;
+        self.sample_bucket(&analyses, StorageStage::Intermediate)
+            .await?;
+
+        let thumbnails = nats.object_store::<ThumbnailsBucket, FileKey>().await?;
+        self.sample_bucket(&thumbnails, StorageStage::Thumbnail)
+            .await?;
+
+        let contexts = nats
+            .object_store::<ContextFilesBucket, ContextKey>()
+            .await?;
+        self.sample_bucket(&contexts, StorageStage::Context).await?;
+
+        let results = nats
+            .object_store::<OperationResultsBucket, ResultKey>()
+            .await?;
+        self.sample_bucket(&results, StorageStage::Result).await?;
+
+        Ok(())
+    }
+
+    /// Adds a bucket's objects to the workspaces their keys name.
+    async fn sample_bucket<B, K>(
+        &mut self,
+        store: &ObjectStore<B, K>,
+        stage: StorageStage,
+    ) -> Result<()>
+    where
+        B: ObjectBucket,
+        K: WorkspaceObjectKey,
+    {
+        for info in store.list().await? {
+            let Ok(key) = K::from_str(&info.name) else {
+                self.report.unattributed += 1;
+                tracing::debug!(
+                    target: TRACING_TARGET,
+                    bucket = B::NAME,
+                    object_key = %info.name,
+                    "Stored object names no workspace"
+                );
+                continue;
+            };
+
+            let storage_class = match info.modified {
+                Some(modified) if modified.unix_timestamp() < self.cold_cutoff.as_second() => {
+                    StorageClass::Cold
+                }
+                _ => StorageClass::Hot,
+            };
+
+            let workspace_id = key.workspace_id();
+            let (today, region) = (self.today, self.region);
+            self.usage
+                .entry((workspace_id, region, stage, storage_class))
+                .or_insert_with(|| {
+                    NewWorkspaceStorageSample::new(
+                        today,
+                        workspace_id,
+                        region,
+                        stage,
+                        storage_class,
+                    )
+                })
+                .add_object(info.size as i64);
+
+            self.report.objects += 1;
+            self.report.bytes += info.size as u64;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the price in effect for a region and storage class, if any.
+fn find_price(
+    prices: &[StoragePrice],
+    region: DataRegion,
+    storage_class: StorageClass,
+) -> Option<&StoragePrice> {
+    prices
+        .iter()
+        .find(|price| price.data_region == region && price.storage_class == storage_class)
+}
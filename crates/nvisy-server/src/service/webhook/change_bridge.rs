@@ -0,0 +1,87 @@
+//! Relay from the workspace event outbox to NATS.
+//!
+//! Events are recorded in the `workspace_change_events` outbox with the
+//! change they describe: by the [`WebhookEmitter`] for changes made through
+//! the application, and by database triggers for rows changed outside it
+//! (admin tools, manual SQL, data migrations). This relay publishes them as
+//! [`WorkspaceEvent`]s on the `WORKSPACE_EVENTS` stream, and application
+//! events as webhook requests, so an event survives the process dying
+//! between the commit and the publish.
+//!
+//! [`WorkspaceEvent`]: nvisy_nats::stream::WorkspaceEvent
+
+use nvisy_postgres::model::WorkspaceChangeEvent;
+use nvisy_postgres::query::AdminScope;
+use nvisy_postgres::{PgChangeListener, PgClient};
+use tokio_util::sync::CancellationToken;
+
+use super::WebhookEmitter;
+use crate::Result;
+use crate::service::Heartbeat;
+
+/// Tracing target for the change event bridge.
+const TRACING_TARGET: &str = "nvisy_server::worker::change_bridge";
+
+/// Publishes the events recorded in the outbox.
+pub struct ChangeEventBridge {
+    pg_client: PgClient,
+    webhook_emitter: WebhookEmitter,
+}
+
+impl ChangeEventBridge {
+    /// Create a new change event bridge.
+    pub fn new(pg_client: PgClient, webhook_emitter: WebhookEmitter) -> Self {
+        Self {
+            pg_client,
+            webhook_emitter,
+        }
+    }
+
+    /// Run the bridge until cancelled.
+    ///
+    /// Every server instance may run a bridge: each message is published with
+    /// an id derived from the outbox id, so JetStream discards the copies.
+    /// The listener beats `heartbeat` on every wake-up and delivered event.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
+        tracing::info!(target: TRACING_TARGET, "Starting change event bridge");
+
+        // The outbox holds every workspace's events; the bridge relays them
+        // all under one scope opened for its lifetime.
+        let mut conn = self.pg_client.get_connection().await?;
+        let admin = AdminScope::open(&mut conn, "relay workspace change events").await?;
+        drop(conn);
+
+        let listener =
+            PgChangeListener::new(self.pg_client.clone()).on_progress(move || heartbeat.beat());
+
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                tracing::info!(
+                    target: TRACING_TARGET,
+                    "Change event bridge shutdown requested"
+                );
+            }
+            _ = listener.run(|change| self.publish_change(&admin, change)) => {}
+        }
+
+        tracing::info!(target: TRACING_TARGET, "Change event bridge stopped");
+        Ok(())
+    }
+
+    /// Publishes one change, deduplicated on its outbox id.
+    async fn publish_change(&self, admin: &AdminScope, change: WorkspaceChangeEvent) -> Result<()> {
+        let request_count = self.webhook_emitter.deliver(admin, &change).await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            change_id = change.id,
+            workspace_id = %change.workspace_id,
+            event = %change.event,
+            source = %change.source,
+            request_count,
+            "Published change event"
+        );
+
+        Ok(())
+    }
+}
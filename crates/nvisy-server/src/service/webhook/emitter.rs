@@ -4,17 +4,25 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use nvisy_nats::NatsClient;
-use nvisy_nats::stream::{EventPublisher, WebhookStream};
-use nvisy_postgres::PgClient;
-use nvisy_postgres::model::WorkspaceWebhook;
-use nvisy_postgres::query::WorkspaceWebhookRepository;
-use nvisy_postgres::types::WebhookEvent;
+use nvisy_nats::stream::{EventPublisher, WebhookStream, WorkspaceEvent, workspace_event_subject};
+use nvisy_postgres::model::{
+    NewWorkspaceChangeEvent, NewWorkspaceWebhookDelivery, WorkspaceChangeEvent, WorkspaceWebhook,
+};
+use nvisy_postgres::query::{
+    AdminScope, WorkspaceChangeEventRepository, WorkspaceWebhookDeliveryRepository,
+    WorkspaceWebhookRepository,
+};
+use nvisy_postgres::types::{ChangeEventSource, WebhookEvent};
+use nvisy_postgres::{PgClient, PgConn, PgConnection, PgResult};
 use nvisy_webhook::provider::{WebhookContext, WebhookRequest};
+use serde::Serialize;
 use url::Url;
 use uuid::Uuid;
 
+use super::filter::PayloadFilter;
+use super::payload::{InviteAccepted, InviteCreated, MemberRoleChanged, WorkspaceSettingsChanged};
 use crate::Result;
-use crate::service::CryptoService;
+use crate::service::{CryptoService, SecretsService};
 
 /// Type alias for webhook publisher.
 type WebhookPublisher = EventPublisher<WebhookRequest, WebhookStream>;
@@ -37,41 +45,53 @@ struct EmitContext {
 
 /// Webhook event emitter for publishing domain events.
 ///
-/// This service queries webhooks subscribed to specific events and publishes
-/// requests to NATS for asynchronous delivery.
+/// Events are recorded in the workspace change event outbox with the change
+/// they describe ([`emit`](Self::emit)), and published from there by the
+/// relay ([`deliver`](Self::deliver)): to the workspace event stream, and as
+/// requests to the webhooks subscribed to them for asynchronous delivery.
 #[derive(Clone)]
 pub struct WebhookEmitter {
     pg_client: PgClient,
     nats_client: NatsClient,
     crypto: CryptoService,
+    secrets: SecretsService,
 }
 
 impl WebhookEmitter {
     /// Create a new webhook emitter.
-    pub fn new(pg_client: PgClient, nats_client: NatsClient, crypto: CryptoService) -> Self {
+    pub fn new(
+        pg_client: PgClient,
+        nats_client: NatsClient,
+        crypto: CryptoService,
+        secrets: SecretsService,
+    ) -> Self {
         Self {
             pg_client,
             nats_client,
             crypto,
+            secrets,
         }
     }
 
-    /// Emit a webhook event for a workspace.
+    /// Records an event in the outbox for delivery.
     ///
-    /// This method:
-    /// 1. Queries all active webhooks subscribed to the event type
-    /// 2. Creates a `WebhookRequest` for each webhook
-    /// 3. Publishes the requests to NATS for asynchronous delivery
+    /// Call it on the connection, or in the transaction, that makes the
+    /// change the event describes: the event is committed if and only if the
+    /// change is, and the relay ([`ChangeEventBridge`]) publishes it once it
+    /// is. An error here should fail the change rather than be ignored.
     ///
     /// # Arguments
     ///
+    /// * `conn` - The connection or transaction making the change
     /// * `workspace_id` - The workspace where the event occurred
     /// * `event` - The type of event that occurred
     /// * `resource_id` - The ID of the affected resource
     /// * `triggered_by` - The account ID that triggered the event (if any)
     /// * `data` - Additional event-specific data
+    ///
+    /// [`ChangeEventBridge`]: super::ChangeEventBridge
     #[tracing::instrument(
-        skip(self, data),
+        skip(self, conn, data),
         fields(
             workspace_id = %workspace_id,
             event = %event,
@@ -80,68 +100,128 @@ impl WebhookEmitter {
     )]
     pub async fn emit(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         event: WebhookEvent,
         resource_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
+    ) -> PgResult<()> {
+        let new_event = NewWorkspaceChangeEvent::application(
+            workspace_id,
+            event,
+            resource_id,
+            triggered_by,
+            data,
+        );
+        let recorded = conn.record_change_event(new_event).await?;
+
+        tracing::debug!(
+            target: TRACING_TARGET,
+            change_id = recorded.id,
+            "Recorded event in the outbox"
+        );
+
+        Ok(())
+    }
+
+    /// Publishes an event drained from the outbox.
+    ///
+    /// Every event goes to the workspace event stream. Application events
+    /// also fan out to the webhooks subscribed to them; database-originated
+    /// changes carry no authenticated actor or payload and are not delivered
+    /// to webhooks. A webhook whose payload filters don't match the request
+    /// built for it is skipped and the event counted as filtered. Each message
+    /// is published with an id derived from the outbox id, so JetStream
+    /// discards the copies a retried delivery sends.
+    ///
+    /// Takes the relay's cross-tenant scope, since the outbox spans every
+    /// workspace. Returns the number of webhook requests published.
+    pub async fn deliver(
+        &self,
+        admin: &AdminScope,
+        change: &WorkspaceChangeEvent,
     ) -> Result<usize> {
+        let event_subject = change.event.as_subject();
+        let subject = workspace_event_subject(change.workspace_id, event_subject);
+        let message_id = format!("change-{}", change.id);
+
+        self.nats_client
+            .workspace_event_publisher()
+            .await?
+            .publish_to_with_id(&subject, &message_id, &workspace_event(change))
+            .await?;
+
+        if !change.source.is_application() {
+            return Ok(0);
+        }
+
         // Find all active webhooks subscribed to this event
         let mut conn = self.pg_client.get_connection().await?;
-        let webhooks = conn.find_webhooks_for_event(workspace_id, event).await?;
+        let webhooks = conn
+            .find_webhooks_for_event(admin.tenant(change.workspace_id), change.event)
+            .await?;
 
         if webhooks.is_empty() {
             tracing::debug!(
                 target: TRACING_TARGET,
+                change_id = change.id,
                 "No webhooks subscribed to event"
             );
             return Ok(0);
         }
 
-        tracing::debug!(
-            target: TRACING_TARGET,
-            webhook_count = webhooks.len(),
-            "Found webhooks subscribed to event"
-        );
-
         // Build a signed request per webhook, skipping any that can't be built.
-        let event_subject = event.as_subject();
         let context = EmitContext {
-            workspace_id,
-            resource_id,
-            resource_type: event.category().to_string(),
-            event: event.to_string(),
-            triggered_by,
-            data,
+            workspace_id: change.workspace_id,
+            resource_id: change.resource_id,
+            resource_type: change.event.category().to_string(),
+            event: change.event.to_string(),
+            triggered_by: change.triggered_by,
+            data: change.data.clone(),
         };
 
-        let requests: Vec<WebhookRequest> = webhooks
-            .into_iter()
-            .filter_map(|webhook| self.build_request(webhook, &context))
-            .collect();
-
-        let request_count = requests.len();
+        let mut requests = Vec::with_capacity(webhooks.len());
+        for webhook in webhooks {
+            let webhook_id = webhook.id;
+            let Some(filter) = payload_filter(&webhook) else {
+                continue;
+            };
+            let Some(request) = self.build_request(webhook, &context).await else {
+                continue;
+            };
+
+            if filter.matches_request(&request) {
+                requests.push((webhook_id, request));
+            } else {
+                let filtered =
+                    NewWorkspaceWebhookDelivery::filtered(change.workspace_id, webhook_id);
+                record_webhook_delivery(&mut conn, filtered).await;
+            }
+        }
 
-        if request_count == 0 {
+        if requests.is_empty() {
             return Ok(0);
         }
 
-        // Publish requests to NATS
+        // Publish requests to NATS, routed by workspace_id.event_subject
         let publisher: WebhookPublisher = self.nats_client.event_publisher().await?;
-
-        for request in &requests {
-            // Use workspace_id.event_subject as the routing subject
-            let subject = format!("{}.{}", request.context.workspace_id, event_subject);
-            publisher.publish_to(&subject, request).await?;
+        let subject = format!("{}.{}", change.workspace_id, event_subject);
+        for (webhook_id, request) in &requests {
+            let message_id = format!("change-{}-{webhook_id}", change.id);
+            publisher
+                .publish_to_with_id(&subject, &message_id, request)
+                .await?;
         }
 
         tracing::info!(
             target: TRACING_TARGET,
-            request_count,
+            change_id = change.id,
+            request_count = requests.len(),
             "Published webhook requests"
         );
 
-        Ok(request_count)
+        Ok(requests.len())
     }
 
     /// Builds a signed delivery request for one webhook.
@@ -149,7 +229,7 @@ impl WebhookEmitter {
     /// Returns `None` — logging the reason — when the webhook can't be turned
     /// into a valid request (bad URL, unrecoverable secret), so a single
     /// misconfigured webhook doesn't abort the whole emission.
-    fn build_request(
+    async fn build_request(
         &self,
         webhook: WorkspaceWebhook,
         ctx: &EmitContext,
@@ -168,7 +248,7 @@ impl WebhookEmitter {
             })
             .ok()?;
 
-        let secret = self.decrypt_secret(&webhook, ctx.workspace_id)?;
+        let secret = self.signing_secret(&webhook, ctx.workspace_id).await?;
 
         let mut context = WebhookContext::new(webhook.id, ctx.workspace_id, ctx.resource_id)
             .with_resource_type(&ctx.resource_type);
@@ -191,6 +271,35 @@ impl WebhookEmitter {
         Some(request)
     }
 
+    /// Returns the secret a webhook's requests are signed with.
+    ///
+    /// An operator may manage a webhook's secret in the secrets provider,
+    /// under `webhooks/{workspace_id}/{webhook_id}`; that value takes
+    /// precedence over the stored one and follows its rotations. The name is
+    /// derived here, never taken from the workspace, so a workspace cannot
+    /// read another secret. If the provider fails the webhook is skipped
+    /// rather than signed with a secret the receiver may no longer accept.
+    async fn signing_secret(
+        &self,
+        webhook: &WorkspaceWebhook,
+        workspace_id: Uuid,
+    ) -> Option<String> {
+        let name = format!("webhooks/{workspace_id}/{}", webhook.id);
+        match self.secrets.get(&name).await {
+            Ok(Some(secret)) => Some(secret.into_inner()),
+            Ok(None) => self.decrypt_secret(webhook, workspace_id),
+            Err(err) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    webhook_id = %webhook.id,
+                    error = %err,
+                    "Skipping webhook whose managed secret could not be read"
+                );
+                None
+            }
+        }
+    }
+
     /// Decrypts a webhook's stored signing secret, returning `None` (and logging)
     /// if it can't be recovered — the request is signed or not sent at all.
     fn decrypt_secret(&self, webhook: &WorkspaceWebhook, workspace_id: Uuid) -> Option<String> {
@@ -223,12 +332,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_document_created(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         document_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::FileCreated,
             document_id,
@@ -242,12 +353,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_document_updated(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         document_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::FileUpdated,
             document_id,
@@ -261,12 +374,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_document_deleted(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         document_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::FileDeleted,
             document_id,
@@ -280,12 +395,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_file_created(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         file_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::FileCreated,
             file_id,
@@ -299,12 +416,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_file_updated(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         file_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::FileUpdated,
             file_id,
@@ -318,12 +437,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_file_deleted(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         file_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::FileDeleted,
             file_id,
@@ -337,12 +458,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_member_added(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         member_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::MemberAdded,
             member_id,
@@ -356,12 +479,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_member_updated(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         member_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::MemberUpdated,
             member_id,
@@ -375,12 +500,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_member_deleted(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         member_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::MemberDeleted,
             member_id,
@@ -390,16 +517,96 @@ impl WebhookEmitter {
         .await
     }
 
+    /// Emit a member role changed event.
+    pub async fn emit_member_role_changed(
+        &self,
+        conn: &mut PgConnection,
+        workspace_id: Uuid,
+        member_id: Uuid,
+        triggered_by: Option<Uuid>,
+        change: &MemberRoleChanged,
+    ) -> PgResult<()> {
+        self.emit(
+            conn,
+            workspace_id,
+            WebhookEvent::MemberRoleChanged,
+            member_id,
+            triggered_by,
+            payload_data(change),
+        )
+        .await
+    }
+
+    /// Emit an invite created event.
+    pub async fn emit_invite_created(
+        &self,
+        conn: &mut PgConnection,
+        workspace_id: Uuid,
+        invite_id: Uuid,
+        triggered_by: Option<Uuid>,
+        invite: &InviteCreated,
+    ) -> PgResult<()> {
+        self.emit(
+            conn,
+            workspace_id,
+            WebhookEvent::InviteCreated,
+            invite_id,
+            triggered_by,
+            payload_data(invite),
+        )
+        .await
+    }
+
+    /// Emit an invite accepted event.
+    pub async fn emit_invite_accepted(
+        &self,
+        conn: &mut PgConnection,
+        workspace_id: Uuid,
+        invite_id: Uuid,
+        accepted: &InviteAccepted,
+    ) -> PgResult<()> {
+        self.emit(
+            conn,
+            workspace_id,
+            WebhookEvent::InviteAccepted,
+            invite_id,
+            Some(accepted.account_id),
+            payload_data(accepted),
+        )
+        .await
+    }
+
+    /// Emit a workspace settings updated event.
+    pub async fn emit_workspace_updated(
+        &self,
+        conn: &mut PgConnection,
+        workspace_id: Uuid,
+        triggered_by: Option<Uuid>,
+        changed: &WorkspaceSettingsChanged,
+    ) -> PgResult<()> {
+        self.emit(
+            conn,
+            workspace_id,
+            WebhookEvent::WorkspaceUpdated,
+            workspace_id,
+            triggered_by,
+            payload_data(changed),
+        )
+        .await
+    }
+
     /// Emit a connection created event.
     #[inline]
     pub async fn emit_connection_created(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         connection_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::ConnectionCreated,
             connection_id,
@@ -413,12 +620,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_connection_updated(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         connection_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::ConnectionUpdated,
             connection_id,
@@ -432,12 +641,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_connection_deleted(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         connection_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::ConnectionDeleted,
             connection_id,
@@ -451,12 +662,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_connection_synced(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         connection_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::ConnectionSynced,
             connection_id,
@@ -470,12 +683,14 @@ impl WebhookEmitter {
     #[inline]
     pub async fn emit_connection_desynced(
         &self,
+        conn: &mut PgConnection,
         workspace_id: Uuid,
         connection_id: Uuid,
         triggered_by: Option<Uuid>,
         data: Option<serde_json::Value>,
-    ) -> Result<usize> {
+    ) -> PgResult<()> {
         self.emit(
+            conn,
             workspace_id,
             WebhookEvent::ConnectionDesynced,
             connection_id,
@@ -484,6 +699,106 @@ impl WebhookEmitter {
         )
         .await
     }
+
+    /// Emit an operation succeeded event.
+    #[inline]
+    pub async fn emit_operation_succeeded(
+        &self,
+        conn: &mut PgConnection,
+        workspace_id: Uuid,
+        operation_id: Uuid,
+        triggered_by: Option<Uuid>,
+        data: Option<serde_json::Value>,
+    ) -> PgResult<()> {
+        self.emit(
+            conn,
+            workspace_id,
+            WebhookEvent::OperationSucceeded,
+            operation_id,
+            triggered_by,
+            data,
+        )
+        .await
+    }
+
+    /// Emit an operation failed event.
+    #[inline]
+    pub async fn emit_operation_failed(
+        &self,
+        conn: &mut PgConnection,
+        workspace_id: Uuid,
+        operation_id: Uuid,
+        triggered_by: Option<Uuid>,
+        data: Option<serde_json::Value>,
+    ) -> PgResult<()> {
+        self.emit(
+            conn,
+            workspace_id,
+            WebhookEvent::OperationFailed,
+            operation_id,
+            triggered_by,
+            data,
+        )
+        .await
+    }
+}
+
+/// Parses a webhook's payload filters.
+///
+/// Filters are validated when they are set, so an invalid one means the
+/// webhook was changed by other means; it is skipped, with a warning, rather
+/// than sent events its filters were meant to hold back.
+fn payload_filter(webhook: &WorkspaceWebhook) -> Option<PayloadFilter> {
+    PayloadFilter::parse(&webhook.payload_filters())
+        .inspect_err(|err| {
+            tracing::warn!(
+                target: TRACING_TARGET,
+                webhook_id = %webhook.id,
+                error = %err,
+                "Skipping webhook with invalid payload filters"
+            );
+        })
+        .ok()
+}
+
+/// Adds a delivery outcome to a webhook's statistics.
+///
+/// Statistics are informational, so a failure to record them is logged and
+/// never fails the delivery.
+pub(super) async fn record_webhook_delivery(
+    conn: &mut PgConn,
+    delivery: NewWorkspaceWebhookDelivery,
+) {
+    let webhook_id = delivery.webhook_id;
+    if let Err(err) = conn.record_webhook_delivery(delivery).await {
+        tracing::warn!(
+            target: TRACING_TARGET,
+            webhook_id = %webhook_id,
+            error = %err,
+            "Failed to record webhook delivery"
+        );
+    }
+}
+
+/// Converts an outbox event into the workspace event subscribers receive.
+fn workspace_event(change: &WorkspaceChangeEvent) -> WorkspaceEvent {
+    let data = match change.source {
+        ChangeEventSource::Application => change.data.clone(),
+        ChangeEventSource::Database => Some(serde_json::json!({
+            "source": "database",
+            "changeId": change.id,
+        })),
+    };
+
+    WorkspaceEvent {
+        workspace_id: change.workspace_id,
+        event: change.event.to_string(),
+        resource_type: change.event.category().to_string(),
+        resource_id: change.resource_id,
+        triggered_by: change.triggered_by,
+        data,
+        occurred_at: change.created_at.into(),
+    }
 }
 
 /// Extracts a webhook's custom headers from its stored JSON, keeping only
@@ -497,3 +812,58 @@ fn parse_headers(headers: &serde_json::Value) -> Option<HashMap<String, String>>
 
     (!map.is_empty()).then_some(map)
 }
+
+/// Serializes a typed event payload as the request's metadata.
+fn payload_data(payload: &impl Serialize) -> Option<serde_json::Value> {
+    serde_json::to_value(payload)
+        .inspect_err(|err| {
+            tracing::error!(
+                target: TRACING_TARGET,
+                error = %err,
+                "Failed to serialize webhook payload"
+            );
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+
+    use super::*;
+
+    fn change(source: ChangeEventSource) -> WorkspaceChangeEvent {
+        WorkspaceChangeEvent {
+            id: 7,
+            workspace_id: Uuid::nil(),
+            resource_id: Uuid::nil(),
+            event: WebhookEvent::MemberAdded,
+            created_at: Timestamp::UNIX_EPOCH.into(),
+            published_at: None,
+            source,
+            triggered_by: None,
+            data: None,
+        }
+    }
+
+    #[test]
+    fn test_workspace_event_from_change() {
+        let event = workspace_event(&change(ChangeEventSource::Database));
+        assert_eq!(event.event, "member:added");
+        assert_eq!(event.resource_type, "member");
+        assert_eq!(event.triggered_by, None);
+        assert_eq!(event.data.unwrap()["changeId"], 7);
+    }
+
+    #[test]
+    fn test_workspace_event_from_application_event() {
+        let actor = Uuid::new_v4();
+        let event = workspace_event(&WorkspaceChangeEvent {
+            triggered_by: Some(actor),
+            data: Some(serde_json::json!({ "role": "member" })),
+            ..change(ChangeEventSource::Application)
+        });
+        assert_eq!(event.triggered_by, Some(actor));
+        assert_eq!(event.data.unwrap(), serde_json::json!({ "role": "member" }));
+    }
+}
@@ -0,0 +1,118 @@
+//! Payload filters of webhooks.
+//!
+//! A webhook may narrow the events it subscribes to with JSONPath queries
+//! (RFC 9535) evaluated against the payload it would receive, as the
+//! endpoint sees it: `$.event`, `$.context.resource_type`,
+//! `$.context.metadata`, and so on. The payload is delivered only if every
+//! query selects at least one value, so `$.context.metadata.tags` requires
+//! the field to be present, and `$.context[?@.size > 1048576]`, which selects
+//! the metadata object when its `size` is large enough, requires a large file.
+
+use nvisy_webhook::provider::WebhookRequest;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// The JSONPath queries a payload must all match to be delivered.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadFilter {
+    queries: Vec<JsonPath>,
+}
+
+impl PayloadFilter {
+    /// Maximum number of queries of a webhook.
+    pub const MAX_QUERIES: usize = 16;
+
+    /// Parses a webhook's queries, describing the first invalid one.
+    pub fn parse<S: AsRef<str>>(filters: &[S]) -> Result<Self, String> {
+        if filters.len() > Self::MAX_QUERIES {
+            return Err(format!(
+                "At most {} payload filters are allowed",
+                Self::MAX_QUERIES
+            ));
+        }
+
+        let queries = filters
+            .iter()
+            .enumerate()
+            .map(|(index, filter)| {
+                JsonPath::parse(filter.as_ref()).map_err(|error| {
+                    format!(
+                        "Payload filter at index {index} is not a valid JSONPath query: {error}"
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { queries })
+    }
+
+    /// Returns whether the filter has no queries and lets every payload through.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Returns whether every query selects at least one value of `payload`.
+    pub fn matches(&self, payload: &Value) -> bool {
+        self.queries
+            .iter()
+            .all(|query| !query.query(payload).is_empty())
+    }
+
+    /// Returns whether the payload `request` delivers matches the filter.
+    pub fn matches_request(&self, request: &WebhookRequest) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        serde_json::to_value(request.to_payload()).is_ok_and(|payload| self.matches(&payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn payload() -> Value {
+        json!({
+            "event": "file:created",
+            "context": {
+                "resource_type": "file",
+                "metadata": { "fileExtension": "pdf", "size": 2048 }
+            }
+        })
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = PayloadFilter::parse::<&str>(&[]).unwrap();
+        assert!(filter.matches(&payload()));
+    }
+
+    #[test]
+    fn every_query_must_select_a_value() {
+        let filter = PayloadFilter::parse(&[
+            "$.context[?@.fileExtension == 'pdf']",
+            "$.context.metadata.size",
+        ])
+        .unwrap();
+        assert!(filter.matches(&payload()));
+
+        let filter = PayloadFilter::parse(&[
+            "$.context[?@.fileExtension == 'pdf']",
+            "$.context[?@.size > 4096]",
+        ])
+        .unwrap();
+        assert!(!filter.matches(&payload()));
+    }
+
+    #[test]
+    fn invalid_queries_are_rejected() {
+        let error = PayloadFilter::parse(&["$.context", "context["]).unwrap_err();
+        assert!(error.starts_with("Payload filter at index 1"));
+
+        let filters = vec!["$.event"; PayloadFilter::MAX_QUERIES + 1];
+        assert!(PayloadFilter::parse(&filters).is_err());
+    }
+}
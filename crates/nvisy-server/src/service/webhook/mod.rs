@@ -1,11 +1,23 @@
 //! Webhook event emission and delivery services.
 //!
 //! Provides helpers for emitting domain events to webhooks via NATS JetStream
-//! ([`WebhookEmitter`]) and the background worker that delivers them
-//! ([`WebhookWorker`]).
+//! ([`WebhookEmitter`]), the background worker that delivers them
+//! ([`WebhookWorker`]), and the bridge that republishes changes made directly
+//! in the database ([`ChangeEventBridge`]). Membership and settings events
+//! carry typed payloads such as [`MemberRoleChanged`]. A webhook's
+//! [`PayloadFilter`] decides which of its events are delivered.
 
+mod change_bridge;
 mod emitter;
+mod filter;
+mod payload;
 mod worker;
 
+pub use change_bridge::ChangeEventBridge;
 pub use emitter::WebhookEmitter;
+pub use filter::PayloadFilter;
+pub use payload::{
+    InviteAccepted, InviteCreated, MemberRoleChanged, MembershipSource, SettingChange,
+    WorkspaceSettingsChanged,
+};
 pub use worker::WebhookWorker;
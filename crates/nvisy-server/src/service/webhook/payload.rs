@@ -0,0 +1,187 @@
+//! Typed payloads of membership and settings webhook events.
+//!
+//! These events let downstream systems mirror who can access a workspace,
+//! so their payloads are fixed shapes rather than ad-hoc JSON. They carry
+//! identifiers and roles, never email addresses or free text.
+
+use jiff::Timestamp;
+use nvisy_postgres::types::{RoleId, WorkspaceRole};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Where a membership change came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MembershipSource {
+    /// A workspace administrator, through the API.
+    Api,
+    /// An identity provider, through SCIM provisioning.
+    Scim,
+    /// An invitation addressed to the member.
+    Invite,
+    /// A shareable invite code.
+    InviteCode,
+}
+
+/// Payload of `member:role_changed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberRoleChanged {
+    /// Role before the change.
+    pub previous_role: WorkspaceRole,
+    /// Role after the change.
+    pub new_role: WorkspaceRole,
+    /// Custom role before the change.
+    pub previous_custom_role_id: Option<RoleId>,
+    /// Custom role after the change.
+    pub custom_role_id: Option<RoleId>,
+    /// Where the change came from.
+    pub source: MembershipSource,
+}
+
+impl MemberRoleChanged {
+    /// Returns whether the change leaves the member's access as it was.
+    pub fn is_unchanged(&self) -> bool {
+        self.previous_role == self.new_role && self.previous_custom_role_id == self.custom_role_id
+    }
+}
+
+/// Payload of `invite:created`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCreated {
+    /// Role granted on acceptance.
+    pub invited_role: WorkspaceRole,
+    /// Account the invitation is addressed to; absent for invite codes.
+    pub invitee_account_id: Option<Uuid>,
+    /// When the invitation stops being usable.
+    pub expires_at: Timestamp,
+    /// Whether this is an addressed invitation or a shareable code.
+    pub source: MembershipSource,
+}
+
+/// Payload of `invite:accepted`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteAccepted {
+    /// Account that accepted and became a member.
+    pub account_id: Uuid,
+    /// Role the new member was given.
+    pub role: WorkspaceRole,
+    /// Whether an addressed invitation or a shareable code was accepted.
+    pub source: MembershipSource,
+}
+
+/// One changed workspace setting.
+///
+/// Values are included only for settings that are safe to share; free-text
+/// settings report that they changed without their contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+    /// Name of the setting, as in the workspace API.
+    pub setting: &'static str,
+    /// Value before the change, when safe to share.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    /// Value after the change, when safe to share.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+impl SettingChange {
+    /// Records a change with its values.
+    pub fn values(setting: &'static str, before: impl Serialize, after: impl Serialize) -> Self {
+        Self {
+            setting,
+            before: serde_json::to_value(before).ok(),
+            after: serde_json::to_value(after).ok(),
+        }
+    }
+
+    /// Records a change without its values.
+    pub fn redacted(setting: &'static str) -> Self {
+        Self {
+            setting,
+            before: None,
+            after: None,
+        }
+    }
+}
+
+/// Payload of `workspace:updated`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSettingsChanged {
+    /// The settings that changed.
+    pub changes: Vec<SettingChange>,
+}
+
+impl WorkspaceSettingsChanged {
+    /// Records `setting` with its values if they differ.
+    pub fn compare<T>(&mut self, setting: &'static str, before: &T, after: &T)
+    where
+        T: PartialEq + Serialize,
+    {
+        if before != after {
+            self.changes
+                .push(SettingChange::values(setting, before, after));
+        }
+    }
+
+    /// Records `setting` without its values if they differ.
+    pub fn compare_redacted<T: PartialEq>(&mut self, setting: &'static str, before: &T, after: &T) {
+        if before != after {
+            self.changes.push(SettingChange::redacted(setting));
+        }
+    }
+
+    /// Returns whether no setting changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_settings_change_redacts_free_text() {
+        let mut changed = WorkspaceSettingsChanged::default();
+        changed.compare("displayName", &"Legal", &"Legal EU");
+        changed.compare("requireApproval", &false, &false);
+        changed.compare_redacted("description", &Some("old"), &Some("new"));
+
+        assert_eq!(
+            serde_json::to_value(&changed).unwrap(),
+            json!({
+                "changes": [
+                    { "setting": "displayName", "before": "Legal", "after": "Legal EU" },
+                    { "setting": "description" },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_role_change_detects_no_op() {
+        let change = MemberRoleChanged {
+            previous_role: WorkspaceRole::Member,
+            new_role: WorkspaceRole::Member,
+            previous_custom_role_id: None,
+            custom_role_id: None,
+            source: MembershipSource::Api,
+        };
+        assert!(change.is_unchanged());
+
+        let promoted = MemberRoleChanged {
+            new_role: WorkspaceRole::Admin,
+            ..change
+        };
+        assert!(!promoted.is_unchanged());
+        assert_eq!(serde_json::to_value(&promoted).unwrap()["source"], "api");
+    }
+}
@@ -1,16 +1,27 @@
 //! Webhook delivery worker.
 //!
 //! Consumes webhook requests from NATS and delivers them to external endpoints.
+//! The worker reads from whichever consumer version the shared
+//! [`ActiveConsumer`] handle points at, and resubscribes when a migration
+//! switches it. Requests pass through the consumer's [`Inbox`], so a
+//! redelivered request is not sent to the endpoint twice. Every attempt is
+//! counted in its webhook's delivery statistics.
 
 use std::time::Duration;
 
 use nvisy_nats::NatsClient;
-use nvisy_nats::stream::{EventSubscriber, WebhookStream};
+use nvisy_nats::stream::{
+    ActiveConsumer, EventStream, EventSubscriber, Inbox, InboxOutcome, TypedMessage, WebhookStream,
+};
+use nvisy_postgres::PgClient;
+use nvisy_postgres::model::NewWorkspaceWebhookDelivery;
 use nvisy_webhook::WebhookService;
-use nvisy_webhook::provider::WebhookRequest;
+use nvisy_webhook::provider::{WebhookRequest, WebhookResponse};
 use tokio_util::sync::CancellationToken;
 
+use super::emitter::record_webhook_delivery;
 use crate::Result;
+use crate::service::Heartbeat;
 
 /// Type alias for webhook subscriber.
 type WebhookSubscriber = EventSubscriber<WebhookRequest, WebhookStream>;
@@ -24,30 +35,39 @@ const TRACING_TARGET: &str = "nvisy_server::worker::webhook";
 /// webhook payloads to external endpoints with HMAC-SHA256 signatures.
 pub struct WebhookWorker {
     nats_client: NatsClient,
+    pg_client: PgClient,
     webhook_service: WebhookService,
+    active: ActiveConsumer,
 }
 
 impl WebhookWorker {
-    /// Create a new webhook worker.
-    pub fn new(nats_client: NatsClient, webhook_service: WebhookService) -> Self {
+    /// Create a new webhook worker reading from the `active` consumer.
+    pub fn new(
+        nats_client: NatsClient,
+        pg_client: PgClient,
+        webhook_service: WebhookService,
+        active: ActiveConsumer,
+    ) -> Self {
         Self {
             nats_client,
+            pg_client,
             webhook_service,
+            active,
         }
     }
 
     /// Run the webhook worker until cancelled.
     ///
     /// This method will continuously consume webhook requests from NATS and
-    /// deliver them to the configured endpoints. Logs lifecycle events
-    /// (start, stop, errors) internally.
-    pub async fn run(&self, cancel: CancellationToken) -> Result<()> {
+    /// deliver them to the configured endpoints, beating `heartbeat` once per
+    /// poll. Logs lifecycle events (start, stop, errors) internally.
+    pub async fn run(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
         tracing::info!(
             target: TRACING_TARGET,
             "Starting webhook worker"
         );
 
-        let result = self.run_inner(cancel).await;
+        let result = self.run_inner(heartbeat, cancel).await;
 
         match &result {
             Ok(()) => {
@@ -69,12 +89,24 @@ impl WebhookWorker {
     }
 
     /// Internal run loop.
-    async fn run_inner(&self, cancel: CancellationToken) -> Result<()> {
+    async fn run_inner(&self, heartbeat: Heartbeat, cancel: CancellationToken) -> Result<()> {
         let subscriber: WebhookSubscriber = self.nats_client.webhook_subscriber().await?;
+        let inbox = self
+            .nats_client
+            .message_inbox(WebhookStream::CONSUMER_NAME)
+            .await?;
 
-        let mut stream = subscriber.subscribe().await?;
+        let mut active_rx = self.active.watch();
+        active_rx.mark_unchanged();
+        let mut stream = tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            result = subscriber.subscribe_active(&self.active) => result?,
+        };
 
         loop {
+            // A delivery wedged on an unresponsive endpoint stops the beats.
+            heartbeat.beat();
+
             tokio::select! {
                 _ = cancel.cancelled() => {
                     tracing::info!(
@@ -83,38 +115,23 @@ impl WebhookWorker {
                     );
                     break;
                 }
+                Ok(()) = active_rx.changed() => {
+                    // A migration moved to another consumer version (or paused
+                    // to drain): drop the old pull and follow the handle.
+                    let consumer = active_rx.borrow_and_update().clone();
+                    tracing::info!(
+                        target: TRACING_TARGET,
+                        consumer = ?consumer,
+                        "Active webhook consumer changed, resubscribing"
+                    );
+                    stream = tokio::select! {
+                        _ = cancel.cancelled() => break,
+                        result = subscriber.subscribe_active(&self.active) => result?,
+                    };
+                }
                 result = stream.next_with_timeout(Duration::from_secs(5)) => {
                     match result {
-                        Ok(Some(mut message)) => {
-                            let request = message.payload();
-
-                            if let Err(err) = self.deliver(request).await {
-                                tracing::error!(
-                                    target: TRACING_TARGET,
-                                    error = %err,
-                                    request_id = %request.request_id,
-                                    webhook_id = %request.context.webhook_id,
-                                    "Failed to deliver webhook"
-                                );
-                                // Nack the message for redelivery
-                                if let Err(nack_err) = message.nack().await {
-                                    tracing::error!(
-                                        target: TRACING_TARGET,
-                                        error = %nack_err,
-                                        "Failed to nack message"
-                                    );
-                                }
-                            } else {
-                                // Ack successful delivery
-                                if let Err(ack_err) = message.ack().await {
-                                    tracing::error!(
-                                        target: TRACING_TARGET,
-                                        error = %ack_err,
-                                        "Failed to ack message"
-                                    );
-                                }
-                            }
-                        }
+                        Ok(Some(message)) => self.process(&inbox, &message).await,
                         Ok(None) => {
                             // Timeout, continue loop
                         }
@@ -124,8 +141,10 @@ impl WebhookWorker {
                                 error = %err,
                                 "Error receiving message from stream"
                             );
-                            // Brief pause before retrying
+                            // Brief pause, then resubscribe in case another
+                            // instance migrated the consumer away.
                             tokio::time::sleep(Duration::from_secs(1)).await;
+                            stream = subscriber.subscribe_active(&self.active).await?;
                         }
                     }
                 }
@@ -135,6 +154,44 @@ impl WebhookWorker {
         Ok(())
     }
 
+    /// Delivers one request unless it was delivered before, then
+    /// acknowledges it; a failed delivery is left for redelivery.
+    async fn process(&self, inbox: &Inbox, message: &TypedMessage<WebhookRequest>) {
+        let request = message.payload();
+        let outcome = inbox
+            .process_once(message, |request| self.deliver(request))
+            .await;
+
+        match outcome {
+            Ok(InboxOutcome::Handled) => {}
+            Ok(InboxOutcome::Duplicate) => {
+                tracing::debug!(
+                    target: TRACING_TARGET,
+                    request_id = %request.request_id,
+                    webhook_id = %request.context.webhook_id,
+                    "Skipped already delivered webhook"
+                );
+            }
+            Ok(InboxOutcome::Failed(err)) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    request_id = %request.request_id,
+                    webhook_id = %request.context.webhook_id,
+                    "Failed to deliver webhook"
+                );
+            }
+            Err(err) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    error = %err,
+                    request_id = %request.request_id,
+                    "Failed to acknowledge webhook request"
+                );
+            }
+        }
+    }
+
     /// Deliver a webhook request.
     ///
     /// The `WebhookService` handles HMAC-SHA256 signing automatically
@@ -148,7 +205,10 @@ impl WebhookWorker {
             "Delivering webhook"
         );
 
-        let response = self.webhook_service.deliver(request).await.map_err(|err| {
+        let result = self.webhook_service.deliver(request).await;
+        self.record_outcome(request, result.as_ref().ok()).await;
+
+        let response = result.map_err(|err| {
             crate::error::Error::external("webhook", format!("Delivery failed: {}", err))
         })?;
 
@@ -176,4 +236,36 @@ impl WebhookWorker {
             ))
         }
     }
+
+    /// Counts a delivery attempt in its webhook's statistics; `response` is
+    /// `None` when the endpoint could not be reached.
+    async fn record_outcome(&self, request: &WebhookRequest, response: Option<&WebhookResponse>) {
+        let context = &request.context;
+        let delivery = match response {
+            Some(response) if response.is_success() => {
+                let response_time_ms = response
+                    .duration()
+                    .total(jiff::Unit::Millisecond)
+                    .unwrap_or(0.0) as i64;
+                NewWorkspaceWebhookDelivery::succeeded(
+                    context.workspace_id,
+                    context.webhook_id,
+                    response_time_ms,
+                )
+            }
+            _ => NewWorkspaceWebhookDelivery::failed(context.workspace_id, context.webhook_id),
+        };
+
+        match self.pg_client.get_connection().await {
+            Ok(mut conn) => record_webhook_delivery(&mut conn, delivery).await,
+            Err(err) => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    webhook_id = %context.webhook_id,
+                    error = %err,
+                    "Failed to record webhook delivery"
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,372 @@
+//! Worker supervision: spawning, stall detection, restart and escalation.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::task::{JoinError, JoinHandle};
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+use super::{Heartbeat, TRACING_TARGET, WatchdogConfig};
+use crate::Result;
+
+/// How long a worker may take to wind down after cancellation before its
+/// task is aborted.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Delay before the first restart; doubles with each recent restart.
+const RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound on the delay between restarts.
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Point-in-time health of one supervised worker.
+#[derive(Debug, Clone)]
+pub struct WorkerStatus {
+    /// Worker name given to [`WorkerHandles::spawn`].
+    pub name: &'static str,
+    /// Restarts since the worker was first spawned.
+    pub restarts: usize,
+    /// Time since the running instance last reported progress.
+    pub since_last_beat: Duration,
+    /// Whether restarts were escalated within the current escalation window.
+    pub escalated: bool,
+}
+
+/// Spawns background workers and keeps them alive.
+///
+/// Each worker runs under a supervisor task that restarts it when it stops
+/// beating its [`Heartbeat`] for longer than the stall timeout, panics, or
+/// exits before shutdown. A restart cancels the stuck instance, aborts it
+/// after a grace period (dropping whatever subscriptions and connections it
+/// held), and builds a fresh one from the factory.
+pub struct WorkerHandles {
+    config: WatchdogConfig,
+    cancel: CancellationToken,
+    workers: Vec<(Arc<WorkerState>, JoinHandle<()>)>,
+}
+
+/// State shared between a supervisor and [`WorkerHandles::statuses`].
+struct WorkerState {
+    name: &'static str,
+    heartbeat: Mutex<Heartbeat>,
+    restarts: Mutex<usize>,
+    last_escalation: Mutex<Option<Instant>>,
+}
+
+/// Why a watched worker instance stopped.
+enum WorkerExit {
+    /// Shutdown was requested.
+    Cancelled,
+    /// The task returned or panicked on its own.
+    Finished(std::result::Result<Result<()>, JoinError>),
+    /// No heartbeat for the given duration.
+    Stalled(Duration),
+}
+
+impl WorkerHandles {
+    /// Creates an empty set of supervised workers.
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            cancel: CancellationToken::new(),
+            workers: Vec::new(),
+        }
+    }
+
+    /// Spawns a supervised worker.
+    ///
+    /// `factory` builds a fresh worker instance for every (re)start; the
+    /// instance must beat the heartbeat as it makes progress and return once
+    /// the cancellation token fires.
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, factory: F)
+    where
+        F: Fn(Heartbeat, CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let state = Arc::new(WorkerState {
+            name,
+            heartbeat: Mutex::new(Heartbeat::new()),
+            restarts: Mutex::new(0),
+            last_escalation: Mutex::new(None),
+        });
+
+        let handle = tokio::spawn(supervise(
+            state.clone(),
+            self.config.clone(),
+            self.cancel.clone(),
+            factory,
+        ));
+        self.workers.push((state, handle));
+    }
+
+    /// Returns the current status of every worker.
+    pub fn statuses(&self) -> Vec<WorkerStatus> {
+        self.workers
+            .iter()
+            .map(|(state, _)| state.status(&self.config))
+            .collect()
+    }
+
+    /// Stops every worker and waits for them to wind down.
+    pub async fn shutdown(self) {
+        self.cancel.cancel();
+
+        for (state, handle) in self.workers {
+            if let Err(err) = handle.await {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    worker = state.name,
+                    error = %err,
+                    "Worker supervisor panicked"
+                );
+            }
+        }
+    }
+}
+
+impl WorkerState {
+    fn status(&self, config: &WatchdogConfig) -> WorkerStatus {
+        let escalated = self
+            .last_escalation
+            .lock()
+            .expect("worker state lock")
+            .is_some_and(|at| at.elapsed() < config.escalation_window);
+
+        WorkerStatus {
+            name: self.name,
+            restarts: *self.restarts.lock().expect("worker state lock"),
+            since_last_beat: self
+                .heartbeat
+                .lock()
+                .expect("worker state lock")
+                .since_last_beat(),
+            escalated,
+        }
+    }
+}
+
+/// Runs and restarts one worker until shutdown.
+async fn supervise<F, Fut>(
+    state: Arc<WorkerState>,
+    config: WatchdogConfig,
+    cancel: CancellationToken,
+    factory: F,
+) where
+    F: Fn(Heartbeat, CancellationToken) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let mut recent_restarts = VecDeque::new();
+
+    loop {
+        let heartbeat = Heartbeat::new();
+        *state.heartbeat.lock().expect("worker state lock") = heartbeat.clone();
+
+        let instance_cancel = cancel.child_token();
+        let mut task = tokio::spawn(factory(heartbeat.clone(), instance_cancel.clone()));
+
+        match watch(&mut task, &heartbeat, &config, &cancel).await {
+            WorkerExit::Cancelled => {
+                stop(task).await;
+                return;
+            }
+            WorkerExit::Finished(Ok(Ok(()))) => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    worker = state.name,
+                    "Worker exited before shutdown, restarting"
+                );
+            }
+            WorkerExit::Finished(Ok(Err(err))) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    worker = state.name,
+                    error = %err,
+                    "Worker failed, restarting"
+                );
+            }
+            WorkerExit::Finished(Err(err)) => {
+                tracing::error!(
+                    target: TRACING_TARGET,
+                    worker = state.name,
+                    error = %err,
+                    "Worker panicked, restarting"
+                );
+            }
+            WorkerExit::Stalled(silent) => {
+                tracing::warn!(
+                    target: TRACING_TARGET,
+                    worker = state.name,
+                    silent_secs = silent.as_secs(),
+                    stall_timeout_secs = config.stall_timeout.as_secs(),
+                    "Worker made no progress, restarting"
+                );
+                instance_cancel.cancel();
+                stop(task).await;
+            }
+        }
+
+        let restarts = record_restart(&state, &config, &mut recent_restarts);
+
+        let delay = RESTART_BACKOFF
+            .saturating_mul(1 << (restarts - 1).min(6))
+            .min(MAX_RESTART_BACKOFF);
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+}
+
+/// Waits until the worker instance finishes, stalls, or shutdown begins.
+async fn watch(
+    task: &mut JoinHandle<Result<()>>,
+    heartbeat: &Heartbeat,
+    config: &WatchdogConfig,
+    cancel: &CancellationToken,
+) -> WorkerExit {
+    let mut interval = tokio::time::interval(config.check_interval());
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return WorkerExit::Cancelled,
+            result = &mut *task => return WorkerExit::Finished(result),
+            _ = interval.tick() => {
+                let silent = heartbeat.since_last_beat();
+                if silent >= config.stall_timeout {
+                    return WorkerExit::Stalled(silent);
+                }
+            }
+        }
+    }
+}
+
+/// Gives a cancelled instance [`SHUTDOWN_GRACE`] to return, then aborts it.
+async fn stop(mut task: JoinHandle<Result<()>>) {
+    if tokio::time::timeout(SHUTDOWN_GRACE, &mut task)
+        .await
+        .is_err()
+    {
+        task.abort();
+        let _ = task.await;
+    }
+}
+
+/// Counts a restart and escalates once restarts repeat within the window.
+///
+/// Returns the number of restarts within the current window.
+fn record_restart(
+    state: &WorkerState,
+    config: &WatchdogConfig,
+    recent_restarts: &mut VecDeque<Instant>,
+) -> usize {
+    let now = Instant::now();
+    recent_restarts.push_back(now);
+    while recent_restarts
+        .front()
+        .is_some_and(|&at| now.duration_since(at) > config.escalation_window)
+    {
+        recent_restarts.pop_front();
+    }
+
+    let total = {
+        let mut restarts = state.restarts.lock().expect("worker state lock");
+        *restarts += 1;
+        *restarts
+    };
+
+    let recent = recent_restarts.len();
+    if recent >= config.escalation_restarts {
+        *state.last_escalation.lock().expect("worker state lock") = Some(now);
+        tracing::error!(
+            target: TRACING_TARGET,
+            worker = state.name,
+            recent_restarts = recent,
+            total_restarts = total,
+            window_secs = config.escalation_window.as_secs(),
+            "Worker keeps restarting, escalating: the stage it serves may be halted"
+        );
+    }
+
+    recent
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn config() -> WatchdogConfig {
+        WatchdogConfig {
+            stall_timeout: Duration::from_secs(60),
+            escalation_restarts: 2,
+            escalation_window: Duration::from_secs(600),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restarts_stalled_worker() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let mut workers = WorkerHandles::new(config());
+
+        let counter = starts.clone();
+        workers.spawn("stuck", move |_heartbeat, _cancel| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            // Never beats and ignores cancellation, like a wedged call.
+            std::future::pending()
+        });
+
+        tokio::time::sleep(Duration::from_secs(200)).await;
+
+        assert!(starts.load(Ordering::SeqCst) >= 2);
+        assert!(workers.statuses()[0].restarts >= 1);
+
+        workers.shutdown().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_beating_worker_is_left_alone() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let mut workers = WorkerHandles::new(config());
+
+        let counter = starts.clone();
+        workers.spawn("healthy", move |heartbeat, cancel| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                loop {
+                    tokio::select! {
+                        _ = cancel.cancelled() => return Ok(()),
+                        _ = tokio::time::sleep(Duration::from_secs(5)) => heartbeat.beat(),
+                    }
+                }
+            }
+        });
+
+        tokio::time::sleep(Duration::from_secs(600)).await;
+
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        assert_eq!(workers.statuses()[0].restarts, 0);
+
+        workers.shutdown().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_escalates_repeated_restarts() {
+        let mut workers = WorkerHandles::new(config());
+        workers.spawn("failing", |_heartbeat, _cancel| async {
+            Err(crate::Error::internal("test", "boom"))
+        });
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+
+        let status = &workers.statuses()[0];
+        assert!(status.restarts >= 2);
+        assert!(status.escalated);
+
+        workers.shutdown().await;
+    }
+}
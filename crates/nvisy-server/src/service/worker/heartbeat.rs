@@ -0,0 +1,71 @@
+//! Progress heartbeat shared between a worker and its watchdog.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Progress signal a supervised worker bumps as it makes progress.
+///
+/// Workers call [`beat`](Self::beat) on every loop iteration, including idle
+/// polls that return nothing, so only a worker blocked inside a call goes
+/// quiet. Clones share the same signal.
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    inner: Arc<HeartbeatInner>,
+}
+
+#[derive(Debug)]
+struct HeartbeatInner {
+    origin: Instant,
+    last_beat_ms: AtomicU64,
+}
+
+impl Heartbeat {
+    /// Creates a heartbeat that counts as having just beaten.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(HeartbeatInner {
+                origin: Instant::now(),
+                last_beat_ms: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Records progress.
+    #[inline]
+    pub fn beat(&self) {
+        let elapsed_ms = self.inner.origin.elapsed().as_millis() as u64;
+        self.inner.last_beat_ms.store(elapsed_ms, Ordering::Relaxed);
+    }
+
+    /// Time since the last recorded progress.
+    pub fn since_last_beat(&self) -> Duration {
+        let last_beat = Duration::from_millis(self.inner.last_beat_ms.load(Ordering::Relaxed));
+        self.inner.origin.elapsed().saturating_sub(last_beat)
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_beat_resets_silence() {
+        let heartbeat = Heartbeat::new();
+        let worker_side = heartbeat.clone();
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(heartbeat.since_last_beat(), Duration::from_secs(30));
+
+        worker_side.beat();
+        assert_eq!(heartbeat.since_last_beat(), Duration::ZERO);
+    }
+}
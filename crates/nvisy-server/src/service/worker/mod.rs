@@ -0,0 +1,58 @@
+//! Supervised background workers.
+//!
+//! Long-running workers (webhook delivery, the change event bridge) run under
+//! [`WorkerHandles`], which watches a [`Heartbeat`] each worker bumps as it
+//! makes progress. A worker that stops beating for longer than the stall
+//! timeout, or exits unexpectedly, is torn down and started afresh; repeated
+//! restarts within a window are escalated so a wedged stage does not halt
+//! silently.
+
+use std::time::Duration;
+
+mod handles;
+mod heartbeat;
+
+pub use handles::{WorkerHandles, WorkerStatus};
+pub use heartbeat::Heartbeat;
+
+/// Tracing target for worker supervision.
+const TRACING_TARGET: &str = "nvisy_server::worker::watchdog";
+
+/// Default time a worker may go without a heartbeat before it is restarted.
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Default number of restarts within the window that triggers escalation.
+pub const DEFAULT_ESCALATION_RESTARTS: usize = 3;
+
+/// Default window over which restarts are counted for escalation.
+pub const DEFAULT_ESCALATION_WINDOW: Duration = Duration::from_secs(30 * 60);
+
+/// Worker watchdog configuration.
+#[derive(Debug, Clone)]
+#[must_use = "config does nothing unless you use it"]
+pub struct WatchdogConfig {
+    /// How long a worker may go without a heartbeat before it is restarted.
+    pub stall_timeout: Duration,
+    /// Restarts within `escalation_window` at which restarts are escalated.
+    pub escalation_restarts: usize,
+    /// Window over which restarts are counted for escalation.
+    pub escalation_window: Duration,
+}
+
+impl WatchdogConfig {
+    /// How often heartbeats are inspected: a quarter of the stall timeout,
+    /// so a stall is noticed within 125% of it.
+    fn check_interval(&self) -> Duration {
+        (self.stall_timeout / 4).max(Duration::from_millis(10))
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            stall_timeout: DEFAULT_STALL_TIMEOUT,
+            escalation_restarts: DEFAULT_ESCALATION_RESTARTS,
+            escalation_window: DEFAULT_ESCALATION_WINDOW,
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Circuit breaking for webhook delivery.
+//!
+//! [`CircuitBreakerProvider`] wraps any [`WebhookProvider`] and keeps one
+//! [`CircuitBreaker`] per endpoint origin, so a receiver that is down stops
+//! being called (and stops holding up delivery workers) without affecting
+//! deliveries to anyone else.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use nvisy_core::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitState, StateChange,
+};
+use nvisy_core::health::{ComponentHealth, HealthStatus};
+
+use crate::provider::{WebhookProvider, WebhookRequest, WebhookResponse};
+use crate::{Error, ErrorKind, Result, TRACING_TARGET};
+
+/// Callback invoked when an endpoint's circuit changes state.
+type StateChangeFn = Arc<dyn Fn(&StateChange) + Send + Sync>;
+
+/// A [`WebhookProvider`] that stops delivering to failing endpoints.
+///
+/// Transport failures, timeouts and `429`/`5xx` responses count against an
+/// endpoint; other client errors mean the endpoint is up and only reject the
+/// payload. While an endpoint's circuit is open, deliveries to it fail with
+/// [`ErrorKind::CircuitOpen`] without being attempted, and are retried like
+/// any other transient failure.
+pub struct CircuitBreakerProvider<P> {
+    inner: P,
+    config: CircuitBreakerConfig,
+    circuits: Mutex<HashMap<String, Arc<CircuitBreaker>>>,
+    listeners: Vec<StateChangeFn>,
+}
+
+impl<P> CircuitBreakerProvider<P> {
+    /// Wraps `inner`, giving each endpoint a circuit with the given
+    /// thresholds.
+    pub fn new(inner: P, config: CircuitBreakerConfig) -> Self {
+        Self {
+            inner,
+            config,
+            circuits: Mutex::new(HashMap::new()),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Registers a callback invoked whenever an endpoint's circuit changes
+    /// state, for alerting.
+    ///
+    /// The change is named after the endpoint's origin.
+    pub fn on_state_change(
+        mut self,
+        listener: impl Fn(&StateChange) + Send + Sync + 'static,
+    ) -> Self {
+        self.listeners.push(Arc::new(listener));
+        self
+    }
+
+    /// Returns the circuit of the endpoint `origin`, creating it if needed.
+    fn circuit(&self, origin: String) -> Arc<CircuitBreaker> {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|err| err.into_inner());
+
+        circuits
+            .entry(origin)
+            .or_insert_with_key(|origin| {
+                let mut breaker = CircuitBreaker::new(origin.clone(), self.config.clone())
+                    .on_state_change(log_state_change);
+                for listener in &self.listeners {
+                    let listener = listener.clone();
+                    breaker = breaker.on_state_change(move |change| listener(change));
+                }
+                Arc::new(breaker)
+            })
+            .clone()
+    }
+
+    /// Returns the origins of the endpoints whose circuit is not closed.
+    fn tripped_origins(&self) -> Vec<String> {
+        let circuits: Vec<_> = self
+            .circuits
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .values()
+            .cloned()
+            .collect();
+
+        circuits
+            .iter()
+            .filter(|circuit| circuit.state() != CircuitState::Closed)
+            .map(|circuit| circuit.name().to_owned())
+            .collect()
+    }
+}
+
+impl<P> fmt::Debug for CircuitBreakerProvider<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreakerProvider")
+            .field("config", &self.config)
+            .field("listeners", &self.listeners.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl<P> WebhookProvider for CircuitBreakerProvider<P>
+where
+    P: WebhookProvider,
+{
+    async fn deliver(&self, request: &WebhookRequest) -> Result<WebhookResponse> {
+        let origin = request.url.origin().ascii_serialization();
+        let circuit = self.circuit(origin);
+
+        let Some(permit) = circuit.try_acquire() else {
+            return Err(Error::new(ErrorKind::CircuitOpen)
+                .with_message(format!("Circuit open for {}", circuit.name())));
+        };
+
+        let result = self.inner.deliver(request).await;
+        let failed = match &result {
+            Ok(response) => is_endpoint_failure(response.status_code),
+            Err(err) => err.is_retryable(),
+        };
+        if failed {
+            permit.failure();
+        } else {
+            permit.success();
+        }
+
+        result
+    }
+
+    /// Reports the inner provider's health, degraded while any endpoint's
+    /// circuit is open.
+    async fn health_check(&self) -> Result<ComponentHealth> {
+        let mut health = self.inner.health_check().await?;
+
+        let tripped = self.tripped_origins();
+        if !tripped.is_empty() {
+            tracing::debug!(
+                target: TRACING_TARGET,
+                endpoints = ?tripped,
+                "Webhook endpoints with open circuits"
+            );
+            health.status = health.status.max(HealthStatus::Degraded);
+        }
+
+        Ok(health)
+    }
+}
+
+/// Whether a response means the endpoint itself is failing.
+fn is_endpoint_failure(status_code: u16) -> bool {
+    status_code == 0 || status_code == 429 || status_code >= 500
+}
+
+fn log_state_change(change: &StateChange) {
+    tracing::warn!(
+        target: TRACING_TARGET,
+        endpoint = %change.name,
+        from = %change.from,
+        to = %change.to,
+        failure_rate = change.failure_rate,
+        slow_call_rate = change.slow_call_rate,
+        "Webhook endpoint circuit changed state"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use jiff::Timestamp;
+    use url::Url;
+    use uuid::Uuid;
+
+    use super::*;
+
+    /// Answers every delivery with a fixed status, counting the calls.
+    struct FixedStatus {
+        status_code: u16,
+        calls: AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl WebhookProvider for FixedStatus {
+        async fn deliver(&self, request: &WebhookRequest) -> Result<WebhookResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(WebhookResponse::new(
+                request.request_id,
+                self.status_code,
+                Timestamp::now(),
+            ))
+        }
+
+        async fn health_check(&self) -> Result<ComponentHealth> {
+            Ok(ComponentHealth::healthy("webhook"))
+        }
+    }
+
+    fn provider(status_code: u16) -> CircuitBreakerProvider<FixedStatus> {
+        let inner = FixedStatus {
+            status_code,
+            calls: AtomicU32::new(0),
+        };
+        let config = CircuitBreakerConfig {
+            window_size: 2,
+            minimum_calls: 2,
+            open_duration: Duration::from_secs(60),
+            ..CircuitBreakerConfig::default()
+        };
+        CircuitBreakerProvider::new(inner, config)
+    }
+
+    fn request(url: &str) -> WebhookRequest {
+        WebhookRequest::test(Url::parse(url).unwrap(), Uuid::new_v4(), Uuid::new_v4())
+    }
+
+    #[tokio::test]
+    async fn failing_endpoint_is_no_longer_called() {
+        let provider = provider(503);
+        let down = request("https://down.example/hook");
+
+        for _ in 0..2 {
+            provider.deliver(&down).await.unwrap();
+        }
+        let err = provider.deliver(&down).await.unwrap_err();
+
+        assert_eq!(err.kind, ErrorKind::CircuitOpen);
+        assert_eq!(provider.inner.calls.load(Ordering::SeqCst), 2);
+
+        let health = provider.health_check().await.unwrap();
+        assert_eq!(health.status, HealthStatus::Degraded);
+    }
+
+    #[tokio::test]
+    async fn circuits_are_kept_per_endpoint() {
+        let provider = provider(503);
+
+        for _ in 0..2 {
+            provider
+                .deliver(&request("https://down.example/hook"))
+                .await
+                .unwrap();
+        }
+
+        assert!(
+            provider
+                .deliver(&request("https://other.example/hook"))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn client_errors_do_not_open_the_circuit() {
+        let provider = provider(404);
+        let rejecting = request("https://rejecting.example/hook");
+
+        for _ in 0..5 {
+            assert!(provider.deliver(&rejecting).await.is_ok());
+        }
+    }
+}
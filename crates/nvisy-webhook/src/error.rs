@@ -29,6 +29,8 @@ pub enum ErrorKind {
     Serialization,
     /// The webhook client is misconfigured.
     Configuration,
+    /// The endpoint's circuit is open; the delivery was not attempted.
+    CircuitOpen,
     /// An unclassified delivery error occurred.
     #[default]
     Unknown,
@@ -38,7 +40,10 @@ impl ErrorKind {
     /// Whether a delivery failure of this kind is worth retrying.
     #[must_use]
     pub const fn is_retryable(&self) -> bool {
-        matches!(self, Self::DeliveryFailed | Self::Timeout)
+        matches!(
+            self,
+            Self::DeliveryFailed | Self::Timeout | Self::CircuitOpen
+        )
     }
 }
 
@@ -129,6 +134,7 @@ mod tests {
     fn test_is_retryable() {
         assert!(Error::new(ErrorKind::DeliveryFailed).is_retryable());
         assert!(Error::new(ErrorKind::Timeout).is_retryable());
+        assert!(Error::new(ErrorKind::CircuitOpen).is_retryable());
 
         assert!(!Error::new(ErrorKind::InvalidEndpoint).is_retryable());
         assert!(!Error::new(ErrorKind::NonRetryableStatus).is_retryable());
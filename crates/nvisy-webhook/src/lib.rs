@@ -2,6 +2,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
+mod circuit_breaker;
 mod client;
 mod error;
 pub mod provider;
@@ -10,6 +11,7 @@ pub mod provider;
 #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
 pub mod reqwest;
 
+pub use circuit_breaker::CircuitBreakerProvider;
 pub use client::WebhookService;
 pub use error::{BoxedError, Error, ErrorKind, Result};
 
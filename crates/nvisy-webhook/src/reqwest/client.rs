@@ -3,23 +3,20 @@
 use std::fmt;
 use std::sync::Arc;
 
-use hmac::{Hmac, KeyInit, Mac};
 use jiff::Timestamp;
+use nvisy_core::crypto::{CryptoProvider, RustCryptoProvider};
 use nvisy_core::health::ComponentHealth;
 use reqwest::Client;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::RetryTransientMiddleware;
 use reqwest_retry::policies::ExponentialBackoff;
 use reqwest_tracing::TracingMiddleware;
-use sha2::Sha256;
 
 use super::error::Error;
 use super::{ReqwestConfig, TRACING_TARGET};
 use crate::WebhookService;
 use crate::provider::{WebhookProvider, WebhookRequest, WebhookResponse};
 
-type HmacSha256 = Hmac<Sha256>;
-
 /// Reqwest-based HTTP client for delivering webhook payloads to external endpoints.
 ///
 /// This client implements the [`WebhookProvider`] trait and provides HTTP-based
@@ -43,11 +40,14 @@ type HmacSha256 = Hmac<Sha256>;
 #[derive(Clone)]
 pub struct ReqwestClient {
     http: Arc<ClientWithMiddleware>,
+    crypto: Arc<dyn CryptoProvider>,
 }
 
 impl fmt::Debug for ReqwestClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ReqwestClient").finish_non_exhaustive()
+        f.debug_struct("ReqwestClient")
+            .field("crypto", &self.crypto.name())
+            .finish_non_exhaustive()
     }
 }
 
@@ -86,9 +86,17 @@ impl ReqwestClient {
 
         Self {
             http: Arc::new(http),
+            crypto: Arc::new(RustCryptoProvider),
         }
     }
 
+    /// Signs payloads with the given crypto provider instead of the default
+    /// pure-Rust one.
+    pub fn with_crypto_provider(mut self, crypto: Arc<dyn CryptoProvider>) -> Self {
+        self.crypto = crypto;
+        self
+    }
+
     /// Converts this client into a [`WebhookService`] for use with dependency injection.
     pub fn into_service(self) -> WebhookService {
         WebhookService::new(self)
@@ -97,17 +105,14 @@ impl ReqwestClient {
     /// Signs a payload using HMAC-SHA256.
     ///
     /// The signature is computed over the raw bytes: `{timestamp}.{payload}`.
-    pub(crate) fn sign_payload(secret: &str, timestamp: i64, payload: &[u8]) -> String {
+    pub(crate) fn sign_payload(&self, secret: &str, timestamp: i64, payload: &[u8]) -> String {
         let timestamp_bytes = timestamp.to_string();
 
-        let mut mac =
-            HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
-        mac.update(timestamp_bytes.as_bytes());
-        mac.update(b".");
-        mac.update(payload);
-
-        let result = mac.finalize();
-        hex::encode(result.into_bytes())
+        let tag = self.crypto.hmac_sha256(
+            secret.as_bytes(),
+            &[timestamp_bytes.as_bytes(), b".", payload],
+        );
+        hex::encode(tag)
     }
 }
 
@@ -143,7 +148,7 @@ impl WebhookProvider for ReqwestClient {
 
         // Add HMAC-SHA256 signature if secret is present
         if let Some(ref secret) = request.secret {
-            let signature = Self::sign_payload(secret, timestamp, &payload_bytes);
+            let signature = self.sign_payload(secret, timestamp, &payload_bytes);
             http_request =
                 http_request.header("X-Webhook-Signature", format!("sha256={signature}"));
         }
@@ -181,7 +186,7 @@ mod tests {
         let timestamp = 1234567890i64;
         let payload = b"{\"event\":\"test\"}";
 
-        let signature = ReqwestClient::sign_payload(secret, timestamp, payload);
+        let signature = ReqwestClient::default().sign_payload(secret, timestamp, payload);
 
         // Signature should be a hex string (64 chars for SHA256)
         assert_eq!(signature.len(), 64);
@@ -194,8 +199,9 @@ mod tests {
         let timestamp = 100i64;
         let payload = b"hello";
 
-        let sig1 = ReqwestClient::sign_payload(secret, timestamp, payload);
-        let sig2 = ReqwestClient::sign_payload(secret, timestamp, payload);
+        let client = ReqwestClient::default();
+        let sig1 = client.sign_payload(secret, timestamp, payload);
+        let sig2 = client.sign_payload(secret, timestamp, payload);
         assert_eq!(sig1, sig2);
     }
 
@@ -0,0 +1,5 @@
+-- Revert workspace data residency
+
+DROP INDEX IF EXISTS workspaces_data_region_idx;
+ALTER TABLE workspaces DROP COLUMN IF EXISTS data_region;
+DROP TYPE IF EXISTS DATA_REGION;
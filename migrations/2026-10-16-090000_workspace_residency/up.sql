@@ -0,0 +1,22 @@
+-- This migration pins each workspace to a data residency region
+
+-- Regions a workspace's data can be pinned to
+CREATE TYPE DATA_REGION AS ENUM (
+    'global',   -- No residency requirement; served by the default backends
+    'eu',       -- European Union
+    'us'        -- United States
+);
+
+COMMENT ON TYPE DATA_REGION IS
+    'Data residency regions that route object storage and inference to region-pinned backends.';
+
+-- The region is fixed at creation: moving a workspace means migrating its data.
+ALTER TABLE workspaces
+    ADD COLUMN IF NOT EXISTS data_region DATA_REGION NOT NULL DEFAULT 'global';
+
+CREATE INDEX IF NOT EXISTS workspaces_data_region_idx
+    ON workspaces (data_region)
+    WHERE data_region <> 'global' AND deleted_at IS NULL;
+
+COMMENT ON COLUMN workspaces.data_region IS
+    'Residency region pinning where the workspace''s files are stored and processed';
@@ -0,0 +1,7 @@
+-- Revert the workspace change event bridge
+
+DROP TRIGGER IF EXISTS workspace_connections_change_event_trigger ON workspace_connections;
+DROP TRIGGER IF EXISTS workspace_members_change_event_trigger ON workspace_members;
+DROP TRIGGER IF EXISTS workspace_files_change_event_trigger ON workspace_files;
+DROP FUNCTION IF EXISTS record_workspace_change_event();
+DROP TABLE IF EXISTS workspace_change_events;
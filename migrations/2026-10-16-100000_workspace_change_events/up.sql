@@ -0,0 +1,104 @@
+-- This migration records row changes made outside the application (admin
+-- tools, manual SQL, data migrations) so they can be bridged to NATS events.
+--
+-- Application connections set `nvisy.change_source = 'application'` on
+-- connect; their changes already emit events and are not recorded here.
+
+-- Outbox of database-originated changes awaiting publication
+CREATE TABLE workspace_change_events (
+    -- Monotonic identifier; also the publication order
+    id              BIGSERIAL       PRIMARY KEY,
+
+    -- Affected workspace and resource
+    workspace_id    UUID            NOT NULL,
+    resource_id     UUID            NOT NULL,
+    event           WEBHOOK_EVENT   NOT NULL,
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    published_at    TIMESTAMPTZ     DEFAULT NULL,
+
+    CONSTRAINT workspace_change_events_published_after_created
+        CHECK (published_at IS NULL OR published_at >= created_at)
+);
+
+-- Pending events, in publication order
+CREATE INDEX workspace_change_events_pending_idx
+    ON workspace_change_events (id)
+    WHERE published_at IS NULL;
+
+-- Pruning of published events
+CREATE INDEX workspace_change_events_published_at_idx
+    ON workspace_change_events (published_at)
+    WHERE published_at IS NOT NULL;
+
+COMMENT ON TABLE workspace_change_events IS
+    'Outbox of row changes made outside the application, bridged to NATS workspace events.';
+
+COMMENT ON COLUMN workspace_change_events.id IS 'Monotonic change identifier (publication order)';
+COMMENT ON COLUMN workspace_change_events.workspace_id IS 'Workspace the changed row belongs to';
+COMMENT ON COLUMN workspace_change_events.resource_id IS 'Identifier of the changed row';
+COMMENT ON COLUMN workspace_change_events.event IS 'Event the change corresponds to';
+COMMENT ON COLUMN workspace_change_events.created_at IS 'Change timestamp';
+COMMENT ON COLUMN workspace_change_events.published_at IS 'Publication timestamp (NULL while pending)';
+
+-- Records a change and wakes the listener.
+--
+-- Arguments: the event category (e.g. 'file') and the verb used for inserts
+-- ('created' or 'added'). Soft deletes are reported as deletions.
+CREATE OR REPLACE FUNCTION record_workspace_change_event()
+RETURNS TRIGGER AS $$
+DECLARE
+    _row      JSONB;
+    _verb     TEXT;
+    _event_id BIGINT;
+BEGIN
+    IF current_setting('nvisy.change_source', TRUE) = 'application' THEN
+        RETURN NULL;
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        _row := to_jsonb(OLD);
+        _verb := 'deleted';
+    ELSIF TG_OP = 'INSERT' THEN
+        _row := to_jsonb(NEW);
+        _verb := TG_ARGV[1];
+    ELSE
+        _row := to_jsonb(NEW);
+        IF _row ->> 'deleted_at' IS NOT NULL AND to_jsonb(OLD) ->> 'deleted_at' IS NULL THEN
+            _verb := 'deleted';
+        ELSE
+            _verb := 'updated';
+        END IF;
+    END IF;
+
+    INSERT INTO workspace_change_events (workspace_id, resource_id, event)
+    VALUES (
+        (_row ->> 'workspace_id')::UUID,
+        COALESCE(_row ->> 'id', _row ->> 'account_id')::UUID,
+        (TG_ARGV[0] || ':' || _verb)::WEBHOOK_EVENT
+    )
+    RETURNING id INTO _event_id;
+
+    PERFORM pg_notify('workspace_change_events', _event_id::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION record_workspace_change_event() IS
+    'Records non-application row changes in workspace_change_events and notifies listeners.';
+
+CREATE TRIGGER workspace_files_change_event_trigger
+    AFTER INSERT OR UPDATE OR DELETE ON workspace_files
+    FOR EACH ROW
+    EXECUTE FUNCTION record_workspace_change_event('file', 'created');
+
+CREATE TRIGGER workspace_members_change_event_trigger
+    AFTER INSERT OR UPDATE OR DELETE ON workspace_members
+    FOR EACH ROW
+    EXECUTE FUNCTION record_workspace_change_event('member', 'added');
+
+CREATE TRIGGER workspace_connections_change_event_trigger
+    AFTER INSERT OR UPDATE OR DELETE ON workspace_connections
+    FOR EACH ROW
+    EXECUTE FUNCTION record_workspace_change_event('connection', 'created');
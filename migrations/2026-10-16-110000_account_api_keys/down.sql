@@ -0,0 +1,4 @@
+-- Revert account API keys
+
+DROP TABLE IF EXISTS account_api_keys;
+DROP TYPE IF EXISTS API_KEY_SCOPE;
@@ -0,0 +1,73 @@
+-- This migration creates long-lived, scoped API keys for programmatic access.
+--
+-- Unlike session tokens (account_api_tokens), API keys are opaque secrets:
+-- only their SHA-256 hash is stored, and the plaintext is shown once on
+-- creation or rotation.
+
+-- Create api_key_scope enum
+CREATE TYPE API_KEY_SCOPE AS ENUM (
+    'read',     -- View and download workspace resources
+    'write',    -- Create, update and run workspace resources
+    'admin'     -- Delete resources and manage members, roles and settings
+);
+
+COMMENT ON TYPE API_KEY_SCOPE IS
+    'Enumeration of permission scopes that can be granted to an API key.';
+
+-- Create account API keys table
+CREATE TABLE account_api_keys (
+    -- Primary identifier
+    id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Account reference
+    account_id            UUID        NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+
+    -- Key metadata
+    name                  TEXT        NOT NULL,
+    scopes                API_KEY_SCOPE[] NOT NULL DEFAULT '{read}',
+
+    CONSTRAINT account_api_keys_name_not_empty CHECK (trim(name) <> ''),
+    CONSTRAINT account_api_keys_name_length CHECK (length(name) <= 100),
+    CONSTRAINT account_api_keys_scopes_not_empty CHECK (cardinality(scopes) > 0),
+
+    -- Key material
+    key_prefix            TEXT        NOT NULL,
+    key_hash              BYTEA       NOT NULL,
+
+    CONSTRAINT account_api_keys_key_hash_length CHECK (length(key_hash) = 32),
+    CONSTRAINT account_api_keys_key_hash_unique UNIQUE (key_hash),
+
+    -- Rotation
+    replaced_by           UUID        DEFAULT NULL REFERENCES account_api_keys (id) ON DELETE SET NULL,
+
+    -- Lifecycle timestamps
+    created_at            TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+    expired_at            TIMESTAMPTZ DEFAULT NULL,
+    last_used_at          TIMESTAMPTZ DEFAULT NULL,
+    deleted_at            TIMESTAMPTZ DEFAULT NULL,
+
+    CONSTRAINT account_api_keys_expired_after_created CHECK (expired_at IS NULL OR expired_at > created_at),
+    CONSTRAINT account_api_keys_deleted_after_created CHECK (deleted_at IS NULL OR deleted_at >= created_at),
+    CONSTRAINT account_api_keys_last_used_after_created CHECK (last_used_at IS NULL OR last_used_at >= created_at)
+);
+
+-- Create indexes for API key management
+CREATE INDEX account_api_keys_account_active_idx
+    ON account_api_keys (account_id, created_at DESC)
+    WHERE deleted_at IS NULL;
+
+-- Add table and column comments
+COMMENT ON TABLE account_api_keys IS
+    'Scoped API keys for programmatic access, stored as SHA-256 hashes.';
+
+COMMENT ON COLUMN account_api_keys.id IS 'Unique key identifier (UUID primary key)';
+COMMENT ON COLUMN account_api_keys.account_id IS 'Reference to the account this key belongs to';
+COMMENT ON COLUMN account_api_keys.name IS 'Human-readable name for the key (max 100 characters)';
+COMMENT ON COLUMN account_api_keys.scopes IS 'Permission scopes granted to the key';
+COMMENT ON COLUMN account_api_keys.key_prefix IS 'Leading characters of the key, for identification in listings';
+COMMENT ON COLUMN account_api_keys.key_hash IS 'SHA-256 hash of the full key';
+COMMENT ON COLUMN account_api_keys.replaced_by IS 'Key issued when this key was rotated (NULL if never rotated)';
+COMMENT ON COLUMN account_api_keys.created_at IS 'Timestamp when the key was created';
+COMMENT ON COLUMN account_api_keys.expired_at IS 'Timestamp when the key expires (NULL if it never expires)';
+COMMENT ON COLUMN account_api_keys.last_used_at IS 'Timestamp of most recent key usage';
+COMMENT ON COLUMN account_api_keys.deleted_at IS 'Timestamp when the key was revoked (NULL if active)';
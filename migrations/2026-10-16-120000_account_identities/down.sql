@@ -0,0 +1,3 @@
+-- Revert account identities
+
+DROP TABLE IF EXISTS account_identities;
@@ -0,0 +1,47 @@
+-- This migration links accounts to identities at external OpenID Connect
+-- providers, so single sign-on logins resolve to the same account every time.
+--
+-- An identity is keyed by the provider's issuer URL and the stable subject
+-- identifier it assigns; the email address is informational only.
+
+-- Create account identities table
+CREATE TABLE account_identities (
+    -- Primary identifier
+    id                    UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Account reference
+    account_id            UUID        NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+
+    -- Provider identity
+    issuer                TEXT        NOT NULL,
+    subject               TEXT        NOT NULL,
+    email_address         TEXT        DEFAULT NULL,
+
+    CONSTRAINT account_identities_issuer_not_empty CHECK (trim(issuer) <> ''),
+    CONSTRAINT account_identities_issuer_length CHECK (length(issuer) <= 2048),
+    CONSTRAINT account_identities_subject_not_empty CHECK (trim(subject) <> ''),
+    CONSTRAINT account_identities_subject_length CHECK (length(subject) <= 255),
+    CONSTRAINT account_identities_issuer_subject_unique UNIQUE (issuer, subject),
+
+    -- Lifecycle timestamps
+    created_at            TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+    last_login_at         TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+
+    CONSTRAINT account_identities_last_login_after_created CHECK (last_login_at >= created_at)
+);
+
+-- Create indexes for identity lookups
+CREATE INDEX account_identities_account_idx
+    ON account_identities (account_id);
+
+-- Add table and column comments
+COMMENT ON TABLE account_identities IS
+    'External OpenID Connect identities linked to accounts for single sign-on.';
+
+COMMENT ON COLUMN account_identities.id IS 'Unique identity identifier (UUID primary key)';
+COMMENT ON COLUMN account_identities.account_id IS 'Reference to the linked account';
+COMMENT ON COLUMN account_identities.issuer IS 'Issuer URL of the identity provider (the ID token iss claim)';
+COMMENT ON COLUMN account_identities.subject IS 'Subject identifier assigned by the provider (the ID token sub claim)';
+COMMENT ON COLUMN account_identities.email_address IS 'Email address reported by the provider at the last login';
+COMMENT ON COLUMN account_identities.created_at IS 'Timestamp when the identity was linked';
+COMMENT ON COLUMN account_identities.last_login_at IS 'Timestamp of the most recent login through this identity';
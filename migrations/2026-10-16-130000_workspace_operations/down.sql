@@ -0,0 +1,8 @@
+-- Revert workspace operations
+--
+-- PostgreSQL cannot drop enum values, so the 'operation:*' webhook events
+-- stay defined; nothing references them once the table is gone.
+
+DROP TABLE IF EXISTS workspace_operations;
+DROP TYPE IF EXISTS OPERATION_STATUS;
+DROP TYPE IF EXISTS OPERATION_KIND;
@@ -0,0 +1,95 @@
+-- This migration adds long-running operations: a single resource tracking
+-- any job that outlives the request starting it. The request answers with
+-- the operation, and clients poll it (or subscribe to its completion
+-- webhooks) instead of learning a job-specific status endpoint.
+
+-- Operation kind enum
+CREATE TYPE OPERATION_KIND AS ENUM (
+    'pipeline_detect'   -- Detect pass of a pipeline run
+);
+
+COMMENT ON TYPE OPERATION_KIND IS
+    'Defines the kinds of jobs tracked as long-running operations.';
+
+-- Operation status enum
+CREATE TYPE OPERATION_STATUS AS ENUM (
+    'pending',      -- Accepted, not started yet
+    'running',      -- In progress
+    'succeeded',    -- Finished with a result
+    'failed',       -- Finished with an error
+    'cancelled'     -- Stopped before finishing
+);
+
+COMMENT ON TYPE OPERATION_STATUS IS
+    'Defines the lifecycle status of long-running operations.';
+
+-- Completion webhook events. The new values are not used in this migration,
+-- so adding them inside its transaction is safe.
+ALTER TYPE WEBHOOK_EVENT ADD VALUE IF NOT EXISTS 'operation:succeeded';
+ALTER TYPE WEBHOOK_EVENT ADD VALUE IF NOT EXISTS 'operation:failed';
+
+-- Create workspace operations table
+CREATE TABLE workspace_operations (
+    -- Primary identifier
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- References
+    workspace_id    UUID                NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    account_id      UUID                DEFAULT NULL REFERENCES accounts (id) ON DELETE SET NULL,
+
+    -- Operation state
+    kind            OPERATION_KIND      NOT NULL,
+    status          OPERATION_STATUS    NOT NULL DEFAULT 'pending',
+    progress        SMALLINT            NOT NULL DEFAULT 0,
+
+    CONSTRAINT workspace_operations_progress_range CHECK (progress BETWEEN 0 AND 100),
+
+    -- Resource the operation acts on or produces, e.g. a pipeline run
+    target_id       UUID                DEFAULT NULL,
+
+    -- Outcome: a result on success, an error on failure
+    result          JSONB               DEFAULT NULL,
+    error           JSONB               DEFAULT NULL,
+
+    CONSTRAINT workspace_operations_result_size CHECK (result IS NULL OR length(result::TEXT) <= 65536),
+    CONSTRAINT workspace_operations_error_size CHECK (error IS NULL OR length(error::TEXT) <= 8192),
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ         NOT NULL DEFAULT current_timestamp,
+    updated_at      TIMESTAMPTZ         NOT NULL DEFAULT current_timestamp,
+    started_at      TIMESTAMPTZ         DEFAULT NULL,
+    completed_at    TIMESTAMPTZ         DEFAULT NULL,
+
+    CONSTRAINT workspace_operations_updated_after_created CHECK (updated_at >= created_at),
+    CONSTRAINT workspace_operations_started_after_created CHECK (started_at IS NULL OR started_at >= created_at),
+    CONSTRAINT workspace_operations_completed_after_created CHECK (completed_at IS NULL OR completed_at >= created_at)
+);
+
+-- Set up automatic updated_at trigger
+SELECT setup_updated_at('workspace_operations');
+
+-- Create indexes for operation lookups
+CREATE INDEX workspace_operations_workspace_idx
+    ON workspace_operations (workspace_id, created_at DESC);
+
+CREATE INDEX workspace_operations_active_idx
+    ON workspace_operations (status, created_at)
+    WHERE status IN ('pending', 'running');
+
+-- Add table and column comments
+COMMENT ON TABLE workspace_operations IS
+    'Long-running operations: status, progress and outcome of asynchronous jobs.';
+
+COMMENT ON COLUMN workspace_operations.id IS 'Unique operation identifier (UUID primary key)';
+COMMENT ON COLUMN workspace_operations.workspace_id IS 'Workspace the operation runs in';
+COMMENT ON COLUMN workspace_operations.account_id IS 'Account that started the operation (optional)';
+COMMENT ON COLUMN workspace_operations.kind IS 'Kind of job the operation tracks';
+COMMENT ON COLUMN workspace_operations.status IS 'Current lifecycle status';
+COMMENT ON COLUMN workspace_operations.progress IS 'Completion estimate in percent';
+COMMENT ON COLUMN workspace_operations.target_id IS 'Resource the operation acts on or produces';
+COMMENT ON COLUMN workspace_operations.result IS 'Job-specific result, set when the operation succeeds';
+COMMENT ON COLUMN workspace_operations.error IS 'Error code and message, set when the operation fails';
+COMMENT ON COLUMN workspace_operations.created_at IS 'Timestamp when the operation was accepted';
+COMMENT ON COLUMN workspace_operations.updated_at IS 'Timestamp of the last status or progress change';
+COMMENT ON COLUMN workspace_operations.started_at IS 'Timestamp when the job started running';
+COMMENT ON COLUMN workspace_operations.completed_at IS 'Timestamp when the operation reached a final status';
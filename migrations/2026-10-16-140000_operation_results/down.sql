@@ -0,0 +1,14 @@
+-- Revert operation result retention
+
+DROP INDEX IF EXISTS workspace_operations_expires_idx;
+
+ALTER TABLE workspace_operations
+    DROP CONSTRAINT IF EXISTS workspace_operations_expires_after_created,
+    DROP CONSTRAINT IF EXISTS workspace_operations_artifact_size_min,
+    DROP CONSTRAINT IF EXISTS workspace_operations_artifact_name_length,
+    DROP CONSTRAINT IF EXISTS workspace_operations_artifact_complete,
+    DROP COLUMN IF EXISTS expires_at,
+    DROP COLUMN IF EXISTS artifact_size,
+    DROP COLUMN IF EXISTS artifact_content_type,
+    DROP COLUMN IF EXISTS artifact_name,
+    DROP COLUMN IF EXISTS artifact_key;
@@ -0,0 +1,42 @@
+-- This migration adds result retention to long-running operations. A job may
+-- leave a downloadable artifact (an export archive, a bulk report) in object
+-- storage, and every finished operation is kept only until its expiry, after
+-- which cleanup removes the row and its artifact.
+
+ALTER TABLE workspace_operations
+    -- Result artifact held in object storage
+    ADD COLUMN artifact_key          TEXT        DEFAULT NULL,
+    ADD COLUMN artifact_name         TEXT        DEFAULT NULL,
+    ADD COLUMN artifact_content_type TEXT        DEFAULT NULL,
+    ADD COLUMN artifact_size         BIGINT      DEFAULT NULL,
+
+    -- Retention
+    ADD COLUMN expires_at            TIMESTAMPTZ DEFAULT NULL;
+
+ALTER TABLE workspace_operations
+    ADD CONSTRAINT workspace_operations_artifact_complete CHECK (
+        (artifact_key IS NULL AND artifact_name IS NULL
+            AND artifact_content_type IS NULL AND artifact_size IS NULL)
+        OR (artifact_key IS NOT NULL AND artifact_name IS NOT NULL
+            AND artifact_content_type IS NOT NULL AND artifact_size IS NOT NULL)
+    ),
+    ADD CONSTRAINT workspace_operations_artifact_name_length CHECK (
+        artifact_name IS NULL OR length(trim(artifact_name)) BETWEEN 1 AND 255
+    ),
+    ADD CONSTRAINT workspace_operations_artifact_size_min CHECK (
+        artifact_size IS NULL OR artifact_size >= 0
+    ),
+    ADD CONSTRAINT workspace_operations_expires_after_created CHECK (
+        expires_at IS NULL OR expires_at > created_at
+    );
+
+-- Cleanup scans finished operations by expiry
+CREATE INDEX workspace_operations_expires_idx
+    ON workspace_operations (expires_at)
+    WHERE expires_at IS NOT NULL;
+
+COMMENT ON COLUMN workspace_operations.artifact_key IS 'Object storage key of the result artifact';
+COMMENT ON COLUMN workspace_operations.artifact_name IS 'File name the artifact downloads as';
+COMMENT ON COLUMN workspace_operations.artifact_content_type IS 'Media type of the artifact';
+COMMENT ON COLUMN workspace_operations.artifact_size IS 'Size of the artifact in bytes';
+COMMENT ON COLUMN workspace_operations.expires_at IS 'Timestamp after which the operation and its artifact are removed';
@@ -0,0 +1,9 @@
+-- Revert workspace custom roles
+
+DROP INDEX IF EXISTS workspace_members_custom_role_idx;
+
+ALTER TABLE workspace_members
+    DROP CONSTRAINT IF EXISTS workspace_members_custom_role_fkey,
+    DROP COLUMN IF EXISTS custom_role_id;
+
+DROP TABLE IF EXISTS workspace_custom_roles;
@@ -0,0 +1,72 @@
+-- This migration adds custom workspace roles. A custom role is a named set
+-- of permissions defined by a workspace; assigned to a member, it grants its
+-- permissions on top of the member's built-in role.
+
+-- Workspace custom roles table
+CREATE TABLE workspace_custom_roles (
+    -- Primary identifier
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- References
+    workspace_id    UUID            NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    account_id      UUID            NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+
+    -- Composite key target for the member assignment below.
+    CONSTRAINT workspace_custom_roles_workspace_id_id_key UNIQUE (workspace_id, id),
+
+    -- Core attributes
+    name            TEXT            NOT NULL,
+    description     TEXT            DEFAULT NULL,
+
+    CONSTRAINT workspace_custom_roles_name_length CHECK (length(trim(name)) BETWEEN 1 AND 64),
+    CONSTRAINT workspace_custom_roles_description_length CHECK (description IS NULL OR length(description) <= 1024),
+
+    -- Granted permissions as `resource:action` keys (e.g. `files:upload`).
+    permissions     TEXT[]          NOT NULL DEFAULT '{}',
+
+    CONSTRAINT workspace_custom_roles_permissions_count CHECK (cardinality(permissions) <= 64),
+    CONSTRAINT workspace_custom_roles_permissions_format CHECK (
+        array_to_string(permissions, ',') ~ '^([a-z_]+:[a-z_]+(,[a-z_]+:[a-z_]+)*)?$'
+    ),
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp,
+    updated_at      TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp,
+
+    CONSTRAINT workspace_custom_roles_updated_after_created CHECK (updated_at >= created_at)
+);
+
+-- Triggers
+SELECT setup_updated_at('workspace_custom_roles');
+
+-- Indexes
+CREATE UNIQUE INDEX workspace_custom_roles_name_unique_idx
+    ON workspace_custom_roles (workspace_id, lower(trim(name)));
+
+-- Comments
+COMMENT ON TABLE workspace_custom_roles IS
+    'Workspace-defined roles granting permissions on top of a member''s built-in role.';
+
+COMMENT ON COLUMN workspace_custom_roles.id IS 'Unique role identifier';
+COMMENT ON COLUMN workspace_custom_roles.workspace_id IS 'Parent workspace reference';
+COMMENT ON COLUMN workspace_custom_roles.account_id IS 'Creator account reference';
+COMMENT ON COLUMN workspace_custom_roles.name IS 'Role name, unique within the workspace (1-64 chars)';
+COMMENT ON COLUMN workspace_custom_roles.description IS 'Role description (up to 1024 chars)';
+COMMENT ON COLUMN workspace_custom_roles.permissions IS 'Granted permission keys (resource:action)';
+COMMENT ON COLUMN workspace_custom_roles.created_at IS 'Creation timestamp';
+COMMENT ON COLUMN workspace_custom_roles.updated_at IS 'Last modification timestamp';
+
+-- Member assignment. The composite key keeps a member from holding another
+-- workspace's role; deleting a role unassigns it.
+ALTER TABLE workspace_members
+    ADD COLUMN custom_role_id UUID DEFAULT NULL,
+    ADD CONSTRAINT workspace_members_custom_role_fkey
+        FOREIGN KEY (workspace_id, custom_role_id)
+        REFERENCES workspace_custom_roles (workspace_id, id)
+        ON DELETE SET NULL (custom_role_id);
+
+CREATE INDEX workspace_members_custom_role_idx
+    ON workspace_members (custom_role_id)
+    WHERE custom_role_id IS NOT NULL;
+
+COMMENT ON COLUMN workspace_members.custom_role_id IS 'Assigned custom role, if any';
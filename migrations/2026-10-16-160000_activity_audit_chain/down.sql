@@ -0,0 +1,21 @@
+-- Revert the activity audit chain
+
+DROP TRIGGER IF EXISTS workspace_activities_protect_trigger ON workspace_activities;
+DROP FUNCTION IF EXISTS protect_workspace_activities();
+
+DROP INDEX IF EXISTS workspace_activities_created_idx;
+DROP INDEX IF EXISTS workspace_activities_resource_idx;
+DROP INDEX IF EXISTS workspace_activities_sequence_unique_idx;
+
+ALTER TABLE workspace_activities
+    DROP CONSTRAINT IF EXISTS workspace_activities_previous_hash_chained,
+    DROP CONSTRAINT IF EXISTS workspace_activities_previous_hash_length,
+    DROP CONSTRAINT IF EXISTS workspace_activities_record_hash_length,
+    DROP CONSTRAINT IF EXISTS workspace_activities_sequence_number_min,
+    DROP COLUMN IF EXISTS record_hash,
+    DROP COLUMN IF EXISTS previous_hash,
+    DROP COLUMN IF EXISTS resource_id,
+    DROP COLUMN IF EXISTS sequence_number;
+
+COMMENT ON TABLE workspace_activities IS
+    'Comprehensive audit log for all workspace activities and changes.';
@@ -0,0 +1,88 @@
+-- This migration turns workspace activities into a tamper-evident audit log.
+-- Each workspace's records are numbered in order and every record carries a
+-- SHA-256 hash over its contents and the previous record's hash, computed by
+-- the server when the record is written. Records cannot be modified, and are
+-- only deleted by retention or when their workspace is removed.
+
+ALTER TABLE workspace_activities
+    -- Position in the workspace's chain
+    ADD COLUMN sequence_number BIGINT      DEFAULT NULL,
+
+    -- Resource the activity acted on
+    ADD COLUMN resource_id     UUID        DEFAULT NULL,
+
+    -- Hash chain
+    ADD COLUMN previous_hash   BYTEA       DEFAULT NULL,
+    ADD COLUMN record_hash     BYTEA       DEFAULT NULL;
+
+-- Existing records predate chaining: they are numbered in creation order but
+-- carry no hash, and the chain starts after them.
+UPDATE workspace_activities AS activity
+SET sequence_number = numbered.sequence_number
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY workspace_id ORDER BY created_at, id) AS sequence_number
+    FROM workspace_activities
+) AS numbered
+WHERE activity.id = numbered.id;
+
+ALTER TABLE workspace_activities
+    ALTER COLUMN sequence_number SET NOT NULL;
+
+ALTER TABLE workspace_activities
+    ADD CONSTRAINT workspace_activities_sequence_number_min CHECK (sequence_number >= 1),
+    ADD CONSTRAINT workspace_activities_record_hash_length CHECK (
+        record_hash IS NULL OR length(record_hash) = 32
+    ),
+    ADD CONSTRAINT workspace_activities_previous_hash_length CHECK (
+        previous_hash IS NULL OR length(previous_hash) = 32
+    ),
+    ADD CONSTRAINT workspace_activities_previous_hash_chained CHECK (
+        previous_hash IS NULL OR record_hash IS NOT NULL
+    );
+
+CREATE UNIQUE INDEX workspace_activities_sequence_unique_idx
+    ON workspace_activities (workspace_id, sequence_number);
+
+CREATE INDEX workspace_activities_resource_idx
+    ON workspace_activities (workspace_id, resource_id, created_at DESC)
+    WHERE resource_id IS NOT NULL;
+
+-- Retention scans records across workspaces by age
+CREATE INDEX workspace_activities_created_idx
+    ON workspace_activities (created_at);
+
+-- Trigger function keeping activity records append-only
+CREATE OR REPLACE FUNCTION protect_workspace_activities()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Foreign key actions (a removed workspace or account) run as nested
+    -- triggers and are allowed through.
+    IF pg_trigger_depth() > 1 THEN
+        RETURN COALESCE(NEW, OLD);
+    END IF;
+
+    IF TG_OP = 'DELETE'
+        AND current_setting('nvisy.activity_retention', true) = 'on' THEN
+        RETURN OLD;
+    END IF;
+
+    RAISE EXCEPTION 'workspace_activities records are append-only'
+        USING ERRCODE = 'restrict_violation';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER workspace_activities_protect_trigger
+    BEFORE UPDATE OR DELETE ON workspace_activities
+    FOR EACH ROW
+    EXECUTE FUNCTION protect_workspace_activities();
+
+COMMENT ON FUNCTION protect_workspace_activities() IS
+    'Rejects updates and deletes of activity records outside retention and cascades.';
+
+COMMENT ON TABLE workspace_activities IS
+    'Tamper-evident audit log of workspace activities, hash-chained per workspace.';
+
+COMMENT ON COLUMN workspace_activities.sequence_number IS 'Position of the record in its workspace chain (from 1)';
+COMMENT ON COLUMN workspace_activities.resource_id IS 'Resource the activity acted on, if any';
+COMMENT ON COLUMN workspace_activities.previous_hash IS 'Hash of the previous record in the chain (NULL for the first)';
+COMMENT ON COLUMN workspace_activities.record_hash IS 'SHA-256 over the record and previous_hash (NULL for records predating the chain)';
@@ -0,0 +1,10 @@
+-- Revert protected document tracking
+
+ALTER TABLE workspace_pipeline_runs
+    DROP CONSTRAINT IF EXISTS workspace_pipeline_runs_document_password_active,
+    DROP COLUMN IF EXISTS encrypted_document_password;
+
+ALTER TABLE workspace_files
+    DROP CONSTRAINT IF EXISTS workspace_files_encrypted_password_protected,
+    DROP COLUMN IF EXISTS encrypted_password,
+    DROP COLUMN IF EXISTS password_protected;
@@ -0,0 +1,25 @@
+-- This migration tracks password-protected documents. Encrypted PDFs are
+-- flagged at upload; their password can be stored on the file (encrypted
+-- under the workspace key) or supplied for a single run, in which case it is
+-- held on the run only until the run settles.
+
+ALTER TABLE workspace_files
+    ADD COLUMN password_protected BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN encrypted_password BYTEA DEFAULT NULL,
+    ADD CONSTRAINT workspace_files_encrypted_password_protected CHECK (
+        encrypted_password IS NULL OR password_protected
+    );
+
+ALTER TABLE workspace_pipeline_runs
+    ADD COLUMN encrypted_document_password BYTEA DEFAULT NULL,
+    ADD CONSTRAINT workspace_pipeline_runs_document_password_active CHECK (
+        encrypted_document_password IS NULL OR status IN ('running', 'analyzed')
+    );
+
+-- Comments
+COMMENT ON COLUMN workspace_files.password_protected IS
+    'Whether the document is encrypted and needs a password to be processed';
+COMMENT ON COLUMN workspace_files.encrypted_password IS
+    'Stored document password, encrypted under the workspace key';
+COMMENT ON COLUMN workspace_pipeline_runs.encrypted_document_password IS
+    'One-time document password, encrypted under the workspace key and cleared when the run settles';
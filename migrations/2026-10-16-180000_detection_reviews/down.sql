@@ -0,0 +1,9 @@
+-- Revert detection reviews
+
+DROP TABLE IF EXISTS workspace_detection_reviews;
+DROP TYPE IF EXISTS REVIEW_STATUS;
+
+-- Fails if a run's metadata has grown past the previous limit.
+ALTER TABLE workspace_pipeline_runs
+    DROP CONSTRAINT workspace_pipeline_runs_metadata_size,
+    ADD CONSTRAINT workspace_pipeline_runs_metadata_size CHECK (length(metadata::TEXT) BETWEEN 2 AND 65536);
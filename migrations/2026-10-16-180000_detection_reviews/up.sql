@@ -0,0 +1,84 @@
+-- This migration adds the review workflow for a run's detections. Every
+-- reviewer decision on a detection is a new version; the version it replaces
+-- is kept as superseded, so the history of a detection can be followed from
+-- the run that proposed it to its current decision.
+
+-- Runs record the recognizer configuration they detected with in their
+-- metadata, which can be as large as the pipeline definition it comes from.
+ALTER TABLE workspace_pipeline_runs
+    DROP CONSTRAINT workspace_pipeline_runs_metadata_size,
+    ADD CONSTRAINT workspace_pipeline_runs_metadata_size CHECK (length(metadata::TEXT) BETWEEN 2 AND 1048576);
+
+-- Review status enum
+CREATE TYPE REVIEW_STATUS AS ENUM (
+    'proposed',     -- Proposed by detection, not decided yet
+    'accepted',     -- Accepted by a reviewer
+    'rejected',     -- Rejected by a reviewer
+    'superseded'    -- Replaced by a later version
+);
+
+COMMENT ON TYPE REVIEW_STATUS IS
+    'Defines the review status of a detected finding.';
+
+-- Detection reviews table
+CREATE TABLE workspace_detection_reviews (
+    -- Primary identifier
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- References
+    run_id          UUID            NOT NULL REFERENCES workspace_pipeline_runs (id) ON DELETE CASCADE,
+    reviewer_id     UUID            DEFAULT NULL REFERENCES accounts (id) ON DELETE SET NULL,
+
+    -- Reviewed detection, identified within the run's analysis
+    detection_id    TEXT            NOT NULL,
+    version         INTEGER         NOT NULL,
+
+    CONSTRAINT workspace_detection_reviews_detection_id_length CHECK (length(detection_id) BETWEEN 1 AND 256),
+    CONSTRAINT workspace_detection_reviews_version_min CHECK (version >= 1),
+    CONSTRAINT workspace_detection_reviews_run_id_detection_id_version_key UNIQUE (run_id, detection_id, version),
+
+    -- Review state: the decision recorded by this version, and its status,
+    -- which becomes 'superseded' once a later version replaces it.
+    decision        REVIEW_STATUS   NOT NULL,
+    status          REVIEW_STATUS   NOT NULL,
+    comment         TEXT            DEFAULT NULL,
+
+    CONSTRAINT workspace_detection_reviews_decision_valid CHECK (decision <> 'superseded'),
+    CONSTRAINT workspace_detection_reviews_status_valid CHECK (status IN (decision, 'superseded')),
+    CONSTRAINT workspace_detection_reviews_comment_length CHECK (comment IS NULL OR length(comment) <= 2048),
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp,
+    superseded_at   TIMESTAMPTZ     DEFAULT NULL,
+
+    CONSTRAINT workspace_detection_reviews_superseded_consistent CHECK (
+        (status = 'superseded') = (superseded_at IS NOT NULL)
+    ),
+    CONSTRAINT workspace_detection_reviews_superseded_after_created CHECK (
+        superseded_at IS NULL OR superseded_at >= created_at
+    )
+);
+
+-- Indexes
+CREATE UNIQUE INDEX workspace_detection_reviews_current_idx
+    ON workspace_detection_reviews (run_id, detection_id)
+    WHERE status <> 'superseded';
+
+CREATE INDEX workspace_detection_reviews_reviewer_idx
+    ON workspace_detection_reviews (reviewer_id)
+    WHERE reviewer_id IS NOT NULL;
+
+-- Comments
+COMMENT ON TABLE workspace_detection_reviews IS
+    'Versioned reviewer decisions on the findings of a pipeline run.';
+
+COMMENT ON COLUMN workspace_detection_reviews.id IS 'Unique review identifier';
+COMMENT ON COLUMN workspace_detection_reviews.run_id IS 'Reference to the reviewed pipeline run';
+COMMENT ON COLUMN workspace_detection_reviews.reviewer_id IS 'Reference to the reviewing account';
+COMMENT ON COLUMN workspace_detection_reviews.detection_id IS 'Detection identifier within the run''s analysis (1-256 chars)';
+COMMENT ON COLUMN workspace_detection_reviews.version IS 'Version of the detection''s review, starting at 1';
+COMMENT ON COLUMN workspace_detection_reviews.decision IS 'Decision recorded by this version';
+COMMENT ON COLUMN workspace_detection_reviews.status IS 'Decision while current, superseded once replaced';
+COMMENT ON COLUMN workspace_detection_reviews.comment IS 'Reviewer comment (up to 2048 chars)';
+COMMENT ON COLUMN workspace_detection_reviews.created_at IS 'Decision timestamp';
+COMMENT ON COLUMN workspace_detection_reviews.superseded_at IS 'When a later version replaced this one';
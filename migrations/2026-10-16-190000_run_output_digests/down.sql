@@ -0,0 +1,12 @@
+-- Revert run output digests
+
+DROP TRIGGER IF EXISTS workspace_pipeline_artifacts_immutable ON workspace_pipeline_artifacts;
+DROP FUNCTION IF EXISTS protect_pipeline_artifacts();
+
+DROP TRIGGER IF EXISTS workspace_pipeline_runs_outputs_immutable ON workspace_pipeline_runs;
+DROP FUNCTION IF EXISTS protect_pipeline_run_outputs();
+
+DROP INDEX IF EXISTS workspace_pipeline_runs_input_digest_idx;
+
+ALTER TABLE workspace_pipeline_runs
+    DROP COLUMN IF EXISTS input_digest;
@@ -0,0 +1,65 @@
+-- This migration makes the outputs of a pipeline run immutable and content
+-- addressed. A run records the digest of everything its analysis depends on:
+-- the file content, the pipeline definition, the scope, the contexts and the
+-- engine's recognizer lineups. Once written, neither the digest nor the
+-- analysis it addresses can change, so rerunning a file produces a new output
+-- set next to the earlier ones instead of replacing them.
+
+ALTER TABLE workspace_pipeline_runs
+    ADD COLUMN input_digest BYTEA DEFAULT NULL,
+    ADD CONSTRAINT workspace_pipeline_runs_input_digest_length CHECK (
+        input_digest IS NULL OR length(input_digest) = 32
+    ),
+    ADD CONSTRAINT workspace_pipeline_runs_input_digest_analyzed CHECK (
+        input_digest IS NULL OR analyzed_document_key IS NOT NULL
+    );
+
+-- Output sets of a file, and reruns with the same inputs.
+CREATE INDEX workspace_pipeline_runs_input_digest_idx
+    ON workspace_pipeline_runs (file_id, input_digest)
+    WHERE input_digest IS NOT NULL;
+
+-- Rejects rewriting a run's analysis once it has been stored.
+CREATE OR REPLACE FUNCTION protect_pipeline_run_outputs()
+RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF (OLD.analyzed_document_key IS NOT NULL
+            AND NEW.analyzed_document_key IS DISTINCT FROM OLD.analyzed_document_key)
+        OR (OLD.input_digest IS NOT NULL
+            AND NEW.input_digest IS DISTINCT FROM OLD.input_digest) THEN
+        RAISE EXCEPTION 'Outputs of pipeline run % are immutable', OLD.id
+            USING ERRCODE = 'check_violation',
+                  CONSTRAINT = 'workspace_pipeline_runs_outputs_immutable';
+    END IF;
+    RETURN NEW;
+END;
+$$;
+
+CREATE OR REPLACE TRIGGER workspace_pipeline_runs_outputs_immutable
+    BEFORE UPDATE OF analyzed_document_key, input_digest ON workspace_pipeline_runs
+    FOR EACH ROW EXECUTE FUNCTION protect_pipeline_run_outputs();
+
+-- Rejects any change to a recorded artifact.
+CREATE OR REPLACE FUNCTION protect_pipeline_artifacts()
+RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    RAISE EXCEPTION 'Pipeline artifact % is immutable', OLD.id
+        USING ERRCODE = 'check_violation',
+              CONSTRAINT = 'workspace_pipeline_artifacts_immutable';
+END;
+$$;
+
+CREATE OR REPLACE TRIGGER workspace_pipeline_artifacts_immutable
+    BEFORE UPDATE ON workspace_pipeline_artifacts
+    FOR EACH ROW EXECUTE FUNCTION protect_pipeline_artifacts();
+
+-- Comments
+COMMENT ON COLUMN workspace_pipeline_runs.input_digest IS
+    'SHA-256 of the inputs the analysis depends on; runs with equal digests reproduce the same output set';
+
+COMMENT ON FUNCTION protect_pipeline_run_outputs() IS
+    'Rejects changes to the analysis key or input digest of a run once they are set.';
+COMMENT ON FUNCTION protect_pipeline_artifacts() IS
+    'Rejects changes to recorded pipeline artifacts.';
@@ -0,0 +1,7 @@
+-- Revert workspace retention
+
+DROP INDEX IF EXISTS workspace_pipeline_runs_started_idx;
+DROP INDEX IF EXISTS workspace_files_created_idx;
+
+DROP TABLE IF EXISTS workspace_legal_holds;
+DROP TABLE IF EXISTS workspace_retention_policies;
@@ -0,0 +1,98 @@
+-- This migration adds per-workspace data retention. A workspace may limit
+-- how long its files, its pipeline runs (with the analyses they keep in
+-- object storage) and its audit records are kept; a purge job removes what
+-- has expired. Legal holds, on the whole workspace or on a single file,
+-- suspend the purge until they are released.
+
+-- Retention policies table
+CREATE TABLE workspace_retention_policies (
+    -- One policy per workspace
+    workspace_id            UUID        PRIMARY KEY REFERENCES workspaces (id) ON DELETE CASCADE,
+    account_id              UUID        DEFAULT NULL REFERENCES accounts (id) ON DELETE SET NULL,
+
+    -- Retention periods in days; NULL keeps the data indefinitely
+    file_retention_days     INTEGER     DEFAULT NULL,
+    run_retention_days      INTEGER     DEFAULT NULL,
+    activity_retention_days INTEGER     DEFAULT NULL,
+
+    CONSTRAINT workspace_retention_policies_file_retention_days_range CHECK (
+        file_retention_days IS NULL OR file_retention_days BETWEEN 1 AND 36500
+    ),
+    CONSTRAINT workspace_retention_policies_run_retention_days_range CHECK (
+        run_retention_days IS NULL OR run_retention_days BETWEEN 1 AND 36500
+    ),
+    CONSTRAINT workspace_retention_policies_activity_retention_days_range CHECK (
+        activity_retention_days IS NULL OR activity_retention_days BETWEEN 1 AND 36500
+    ),
+
+    -- Lifecycle timestamps
+    created_at              TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+    updated_at              TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+
+    CONSTRAINT workspace_retention_policies_updated_after_created CHECK (updated_at >= created_at)
+);
+
+SELECT setup_updated_at('workspace_retention_policies');
+
+-- Legal holds table
+CREATE TABLE workspace_legal_holds (
+    -- Primary identifier
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- References; a hold without a file covers the whole workspace
+    workspace_id    UUID            NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    file_id         UUID            DEFAULT NULL REFERENCES workspace_files (id) ON DELETE CASCADE,
+    account_id      UUID            DEFAULT NULL REFERENCES accounts (id) ON DELETE SET NULL,
+
+    -- Hold attributes
+    reason          TEXT            NOT NULL,
+
+    CONSTRAINT workspace_legal_holds_reason_length CHECK (length(trim(reason)) BETWEEN 1 AND 1024),
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp,
+    released_at     TIMESTAMPTZ     DEFAULT NULL,
+
+    CONSTRAINT workspace_legal_holds_released_after_created CHECK (
+        released_at IS NULL OR released_at >= created_at
+    )
+);
+
+-- Indexes
+CREATE INDEX workspace_legal_holds_active_idx
+    ON workspace_legal_holds (workspace_id, file_id)
+    WHERE released_at IS NULL;
+
+CREATE INDEX workspace_legal_holds_created_idx
+    ON workspace_legal_holds (workspace_id, created_at DESC);
+
+-- Purges scan a workspace's files (soft-deleted ones included) and runs by age
+CREATE INDEX workspace_files_created_idx
+    ON workspace_files (workspace_id, created_at);
+
+CREATE INDEX workspace_pipeline_runs_started_idx
+    ON workspace_pipeline_runs (started_at)
+    WHERE status IN ('completed', 'failed', 'cancelled');
+
+-- Comments
+COMMENT ON TABLE workspace_retention_policies IS
+    'Per-workspace retention periods enforced by the purge job.';
+
+COMMENT ON COLUMN workspace_retention_policies.workspace_id IS 'Workspace the policy applies to';
+COMMENT ON COLUMN workspace_retention_policies.account_id IS 'Account that last changed the policy';
+COMMENT ON COLUMN workspace_retention_policies.file_retention_days IS 'Days files are kept after upload (NULL keeps them)';
+COMMENT ON COLUMN workspace_retention_policies.run_retention_days IS 'Days finished pipeline runs and their analyses are kept (NULL keeps them)';
+COMMENT ON COLUMN workspace_retention_policies.activity_retention_days IS 'Days audit records are kept (NULL defers to the deployment retention)';
+COMMENT ON COLUMN workspace_retention_policies.created_at IS 'Policy creation timestamp';
+COMMENT ON COLUMN workspace_retention_policies.updated_at IS 'Last modification timestamp';
+
+COMMENT ON TABLE workspace_legal_holds IS
+    'Legal holds suspending retention for a workspace or a single file.';
+
+COMMENT ON COLUMN workspace_legal_holds.id IS 'Unique hold identifier';
+COMMENT ON COLUMN workspace_legal_holds.workspace_id IS 'Workspace the hold belongs to';
+COMMENT ON COLUMN workspace_legal_holds.file_id IS 'Held file (NULL holds the whole workspace)';
+COMMENT ON COLUMN workspace_legal_holds.account_id IS 'Account that placed the hold';
+COMMENT ON COLUMN workspace_legal_holds.reason IS 'Why the data is held (1-1024 chars)';
+COMMENT ON COLUMN workspace_legal_holds.created_at IS 'When the hold was placed';
+COMMENT ON COLUMN workspace_legal_holds.released_at IS 'When the hold was released (NULL while active)';
@@ -0,0 +1,10 @@
+-- Revert legal hold enforcement
+--
+-- PostgreSQL cannot drop enum values, so the 'legal_hold:*' activity types
+-- stay defined; existing audit records keep referencing them.
+
+DROP TRIGGER IF EXISTS workspaces_legal_hold ON workspaces;
+DROP FUNCTION IF EXISTS protect_held_workspaces();
+
+DROP TRIGGER IF EXISTS workspace_files_legal_hold ON workspace_files;
+DROP FUNCTION IF EXISTS protect_held_workspace_files();
@@ -0,0 +1,76 @@
+-- This migration enforces legal holds in the database and records hold
+-- changes in the audit log. A held file cannot be deleted, soft or hard, and
+-- a workspace with any active hold cannot be deleted; every delete path,
+-- including ones that bypass the API, is rejected the same way.
+
+-- Audit log events for hold changes. The new values are not used in this
+-- migration, so adding them inside its transaction is safe.
+ALTER TYPE ACTIVITY_TYPE ADD VALUE IF NOT EXISTS 'legal_hold:placed';
+ALTER TYPE ACTIVITY_TYPE ADD VALUE IF NOT EXISTS 'legal_hold:released';
+
+-- Rejects deleting a file covered by an active file or workspace-wide hold.
+CREATE OR REPLACE FUNCTION protect_held_workspace_files()
+RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND (OLD.deleted_at IS NOT NULL OR NEW.deleted_at IS NULL) THEN
+        RETURN NEW;
+    END IF;
+
+    IF EXISTS (
+        SELECT 1 FROM workspace_legal_holds
+        WHERE workspace_id = OLD.workspace_id
+          AND (file_id IS NULL OR file_id = OLD.id)
+          AND released_at IS NULL
+    ) THEN
+        RAISE EXCEPTION 'File % is under a legal hold', OLD.id
+            USING ERRCODE = 'check_violation',
+                  CONSTRAINT = 'workspace_legal_holds_file_held';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$;
+
+CREATE OR REPLACE TRIGGER workspace_files_legal_hold
+    BEFORE UPDATE OF deleted_at OR DELETE ON workspace_files
+    FOR EACH ROW EXECUTE FUNCTION protect_held_workspace_files();
+
+-- Rejects deleting a workspace with any active hold.
+CREATE OR REPLACE FUNCTION protect_held_workspaces()
+RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND (OLD.deleted_at IS NOT NULL OR NEW.deleted_at IS NULL) THEN
+        RETURN NEW;
+    END IF;
+
+    IF EXISTS (
+        SELECT 1 FROM workspace_legal_holds
+        WHERE workspace_id = OLD.id
+          AND released_at IS NULL
+    ) THEN
+        RAISE EXCEPTION 'Workspace % is under a legal hold', OLD.id
+            USING ERRCODE = 'check_violation',
+                  CONSTRAINT = 'workspace_legal_holds_workspace_held';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$;
+
+CREATE OR REPLACE TRIGGER workspaces_legal_hold
+    BEFORE UPDATE OF deleted_at OR DELETE ON workspaces
+    FOR EACH ROW EXECUTE FUNCTION protect_held_workspaces();
+
+-- Comments
+COMMENT ON FUNCTION protect_held_workspace_files() IS
+    'Rejects deleting a file covered by an active legal hold.';
+COMMENT ON FUNCTION protect_held_workspaces() IS
+    'Rejects deleting a workspace with an active legal hold.';
@@ -0,0 +1,9 @@
+-- Revert file sensitivity
+--
+-- Content already stored with a per-object data key stays encrypted that way
+-- and can no longer be read once the column is gone.
+
+ALTER TABLE workspace_files
+    DROP COLUMN IF EXISTS sensitivity;
+
+DROP TYPE IF EXISTS DATA_SENSITIVITY;
@@ -0,0 +1,21 @@
+-- This migration classifies files by sensitivity. Content of high and
+-- critical files is envelope encrypted: each object gets its own data key,
+-- wrapped by the workspace key-encryption key through the configured key
+-- management service and stored in the object's metadata.
+
+CREATE TYPE DATA_SENSITIVITY AS ENUM (
+    'low',          -- Public or non-sensitive content
+    'medium',       -- Internal content (default)
+    'high',         -- Confidential content; envelope encrypted
+    'critical'      -- Restricted content; envelope encrypted
+);
+
+COMMENT ON TYPE DATA_SENSITIVITY IS
+    'Defines how sensitive a file''s content is and how it is protected at rest.';
+
+ALTER TABLE workspace_files
+    ADD COLUMN sensitivity DATA_SENSITIVITY NOT NULL DEFAULT 'medium';
+
+-- Comments
+COMMENT ON COLUMN workspace_files.sensitivity IS
+    'Content sensitivity; high and critical content has a per-object data key';
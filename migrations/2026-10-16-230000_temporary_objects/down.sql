@@ -0,0 +1,3 @@
+-- Revert temporary objects
+
+DROP TABLE IF EXISTS workspace_temporary_objects;
@@ -0,0 +1,45 @@
+-- This migration tracks objects written to storage before anything refers to
+-- them, such as the content of an upload in progress. Each is registered with
+-- an owner and an expiry before it is written and released once a row refers
+-- to it; whatever is left past its expiry was abandoned, and the garbage
+-- collector removes the object along with its registration.
+
+-- Temporary objects table
+CREATE TABLE workspace_temporary_objects (
+    -- Primary identifier
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- References
+    workspace_id    UUID            NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    account_id      UUID            DEFAULT NULL REFERENCES accounts (id) ON DELETE SET NULL,
+
+    -- Storage location
+    bucket          TEXT            NOT NULL,
+    object_key      TEXT            NOT NULL,
+
+    CONSTRAINT workspace_temporary_objects_bucket_length CHECK (length(bucket) BETWEEN 1 AND 255),
+    CONSTRAINT workspace_temporary_objects_object_key_length CHECK (length(object_key) BETWEEN 1 AND 1024),
+    CONSTRAINT workspace_temporary_objects_object_unique UNIQUE (bucket, object_key),
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp,
+    expires_at      TIMESTAMPTZ     NOT NULL,
+
+    CONSTRAINT workspace_temporary_objects_expires_after_created CHECK (expires_at > created_at)
+);
+
+-- Indexes
+CREATE INDEX workspace_temporary_objects_expires_idx
+    ON workspace_temporary_objects (expires_at);
+
+-- Comments
+COMMENT ON TABLE workspace_temporary_objects IS
+    'Stored objects not yet referenced by any row, removed by garbage collection once expired.';
+
+COMMENT ON COLUMN workspace_temporary_objects.id IS 'Unique registration identifier';
+COMMENT ON COLUMN workspace_temporary_objects.workspace_id IS 'Workspace the object belongs to';
+COMMENT ON COLUMN workspace_temporary_objects.account_id IS 'Account that wrote the object';
+COMMENT ON COLUMN workspace_temporary_objects.bucket IS 'Object storage bucket';
+COMMENT ON COLUMN workspace_temporary_objects.object_key IS 'Object key within the bucket';
+COMMENT ON COLUMN workspace_temporary_objects.created_at IS 'When the object was registered';
+COMMENT ON COLUMN workspace_temporary_objects.expires_at IS 'When an unreleased object is considered abandoned';
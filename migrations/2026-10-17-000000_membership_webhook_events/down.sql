@@ -0,0 +1,16 @@
+-- Revert membership webhook events
+--
+-- PostgreSQL cannot drop enum values, so the new webhook events stay
+-- defined; webhooks are unsubscribed from them instead.
+
+UPDATE workspace_webhooks
+SET events = array_remove(
+    array_remove(
+        array_remove(
+            array_remove(events, 'member:role_changed'::WEBHOOK_EVENT),
+            'invite:created'::WEBHOOK_EVENT
+        ),
+        'invite:accepted'::WEBHOOK_EVENT
+    ),
+    'workspace:updated'::WEBHOOK_EVENT
+);
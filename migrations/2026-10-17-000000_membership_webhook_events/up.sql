@@ -0,0 +1,9 @@
+-- This migration adds webhook events for membership and settings changes,
+-- so systems mirroring workspace access can follow role changes, invites
+-- and settings updates without polling. The new values are not used in
+-- this migration, so adding them inside its transaction is safe.
+
+ALTER TYPE WEBHOOK_EVENT ADD VALUE IF NOT EXISTS 'member:role_changed';
+ALTER TYPE WEBHOOK_EVENT ADD VALUE IF NOT EXISTS 'invite:created';
+ALTER TYPE WEBHOOK_EVENT ADD VALUE IF NOT EXISTS 'invite:accepted';
+ALTER TYPE WEBHOOK_EVENT ADD VALUE IF NOT EXISTS 'workspace:updated';
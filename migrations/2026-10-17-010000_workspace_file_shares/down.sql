@@ -0,0 +1,6 @@
+-- Revert share links
+--
+-- PostgreSQL cannot drop enum values, so the 'share:*' activity types stay
+-- defined; existing audit records keep referencing them.
+
+DROP TABLE IF EXISTS workspace_file_shares;
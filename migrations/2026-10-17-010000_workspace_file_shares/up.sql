@@ -0,0 +1,87 @@
+-- This migration adds share links: expiring, revocable tokens that grant
+-- read access to a single document, optionally limited to some of its pages
+-- and to a number of views. Like API keys, a link's token is an opaque
+-- secret of which only the SHA-256 hash is stored.
+--
+-- Every use of a link, granted or denied, is recorded in the audit log.
+
+-- Audit log events for share links. The new values are not used in this
+-- migration, so adding them inside its transaction is safe.
+ALTER TYPE ACTIVITY_TYPE ADD VALUE IF NOT EXISTS 'share:created';
+ALTER TYPE ACTIVITY_TYPE ADD VALUE IF NOT EXISTS 'share:accessed';
+ALTER TYPE ACTIVITY_TYPE ADD VALUE IF NOT EXISTS 'share:revoked';
+
+-- Share links table
+CREATE TABLE workspace_file_shares (
+    -- Primary identifier
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- References
+    workspace_id    UUID            NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    file_id         UUID            NOT NULL REFERENCES workspace_files (id) ON DELETE CASCADE,
+    account_id      UUID            DEFAULT NULL REFERENCES accounts (id) ON DELETE SET NULL,
+
+    -- Grant
+    recipient       TEXT            NOT NULL,
+    pages           INTEGER[]       DEFAULT NULL,
+    watermark       BOOLEAN         NOT NULL DEFAULT TRUE,
+
+    CONSTRAINT workspace_file_shares_recipient_length CHECK (length(trim(recipient)) BETWEEN 1 AND 255),
+    CONSTRAINT workspace_file_shares_pages_range CHECK (
+        pages IS NULL OR (cardinality(pages) BETWEEN 1 AND 1000 AND 1 <= ALL (pages))
+    ),
+
+    -- Token material
+    token_prefix    TEXT            NOT NULL,
+    token_hash      BYTEA           NOT NULL,
+
+    CONSTRAINT workspace_file_shares_token_hash_length CHECK (length(token_hash) = 32),
+    CONSTRAINT workspace_file_shares_token_hash_unique UNIQUE (token_hash),
+
+    -- Usage limits
+    max_views       INTEGER         DEFAULT NULL,
+    view_count      INTEGER         NOT NULL DEFAULT 0,
+
+    CONSTRAINT workspace_file_shares_max_views_min CHECK (max_views IS NULL OR max_views >= 1),
+    CONSTRAINT workspace_file_shares_view_count_range CHECK (
+        view_count >= 0 AND (max_views IS NULL OR view_count <= max_views)
+    ),
+
+    -- Lifecycle timestamps
+    created_at      TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp,
+    expires_at      TIMESTAMPTZ     NOT NULL,
+    last_viewed_at  TIMESTAMPTZ     DEFAULT NULL,
+    revoked_at      TIMESTAMPTZ     DEFAULT NULL,
+
+    CONSTRAINT workspace_file_shares_expires_after_created CHECK (expires_at > created_at),
+    CONSTRAINT workspace_file_shares_last_viewed_after_created CHECK (
+        last_viewed_at IS NULL OR last_viewed_at >= created_at
+    ),
+    CONSTRAINT workspace_file_shares_revoked_after_created CHECK (
+        revoked_at IS NULL OR revoked_at >= created_at
+    )
+);
+
+-- Indexes
+CREATE INDEX workspace_file_shares_file_idx
+    ON workspace_file_shares (workspace_id, file_id, created_at DESC);
+
+-- Comments
+COMMENT ON TABLE workspace_file_shares IS
+    'Expiring, revocable links granting read access to a single document.';
+
+COMMENT ON COLUMN workspace_file_shares.id IS 'Unique share link identifier';
+COMMENT ON COLUMN workspace_file_shares.workspace_id IS 'Workspace the shared file belongs to';
+COMMENT ON COLUMN workspace_file_shares.file_id IS 'Shared file';
+COMMENT ON COLUMN workspace_file_shares.account_id IS 'Account that created the link';
+COMMENT ON COLUMN workspace_file_shares.recipient IS 'Who the link was issued to, stamped into watermarks (1-255 chars)';
+COMMENT ON COLUMN workspace_file_shares.pages IS 'One-based pages the link grants (NULL grants the whole document)';
+COMMENT ON COLUMN workspace_file_shares.watermark IS 'Whether served content is watermarked with the recipient';
+COMMENT ON COLUMN workspace_file_shares.token_prefix IS 'Leading characters of the token, for identification in listings';
+COMMENT ON COLUMN workspace_file_shares.token_hash IS 'SHA-256 hash of the full token';
+COMMENT ON COLUMN workspace_file_shares.max_views IS 'Number of times the link may be opened (NULL is unlimited)';
+COMMENT ON COLUMN workspace_file_shares.view_count IS 'Number of times the link has been opened';
+COMMENT ON COLUMN workspace_file_shares.created_at IS 'When the link was created';
+COMMENT ON COLUMN workspace_file_shares.expires_at IS 'When the link stops working';
+COMMENT ON COLUMN workspace_file_shares.last_viewed_at IS 'When the link was last opened';
+COMMENT ON COLUMN workspace_file_shares.revoked_at IS 'When the link was revoked (NULL while usable)';
@@ -0,0 +1,12 @@
+-- Revert the connection credential vault
+--
+-- Credentials already sealed by the vault stay in that format and can no
+-- longer be read once the version column is gone.
+
+DROP INDEX IF EXISTS workspace_connections_credential_key_version_idx;
+
+ALTER TABLE workspace_connections
+    DROP CONSTRAINT IF EXISTS workspace_connections_credential_key_version_min;
+
+ALTER TABLE workspace_connections
+    DROP COLUMN IF EXISTS credential_key_version;
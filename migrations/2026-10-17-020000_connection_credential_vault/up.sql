@@ -0,0 +1,23 @@
+-- This migration moves connection credentials into the credential vault.
+-- Credentials are sealed with AES-256-GCM under a versioned vault key, and
+-- each row records the version it was sealed under so rows left on a
+-- retired key can be found and resealed after a rotation. Version 0 marks
+-- credentials written before the vault, under the workspace key; they stay
+-- readable and are resealed in the background.
+
+ALTER TABLE workspace_connections
+    ADD COLUMN credential_key_version INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE workspace_connections
+    ADD CONSTRAINT workspace_connections_credential_key_version_min
+        CHECK (credential_key_version >= 0);
+
+-- Rows awaiting reseal after a rotation
+CREATE INDEX workspace_connections_credential_key_version_idx
+    ON workspace_connections (credential_key_version, id);
+
+-- Comments
+COMMENT ON COLUMN workspace_connections.encrypted_data IS
+    'Connection data sealed by the credential vault (legacy rows: workspace key)';
+COMMENT ON COLUMN workspace_connections.credential_key_version IS
+    'Vault key version the data is sealed under (0 = legacy workspace key)';
@@ -0,0 +1,19 @@
+-- Revert the application event outbox
+--
+-- Pending application events are dropped with the columns they need; drain
+-- the outbox before reverting.
+
+DELETE FROM workspace_change_events WHERE source = 'application';
+
+DROP TRIGGER IF EXISTS workspace_change_events_notify_trigger ON workspace_change_events;
+DROP FUNCTION IF EXISTS notify_workspace_change_event();
+
+ALTER TABLE workspace_change_events
+    DROP COLUMN IF EXISTS data,
+    DROP COLUMN IF EXISTS triggered_by,
+    DROP COLUMN IF EXISTS source;
+
+DROP TYPE IF EXISTS CHANGE_EVENT_SOURCE;
+
+COMMENT ON TABLE workspace_change_events IS
+    'Outbox of row changes made outside the application, bridged to NATS workspace events.';
@@ -0,0 +1,44 @@
+-- This migration turns the change event table into the outbox for every
+-- workspace event. The application now records its own events here, in the
+-- transaction that makes the change, instead of publishing to NATS after
+-- commit; the relay publishes them with the row id as the message id.
+
+-- Where a change event was recorded
+CREATE TYPE CHANGE_EVENT_SOURCE AS ENUM (
+    'database',     -- Recorded by a trigger for a change made outside the application
+    'application'   -- Recorded by the application with the change it describes
+);
+
+COMMENT ON TYPE CHANGE_EVENT_SOURCE IS
+    'Indicates who recorded a workspace change event.';
+
+ALTER TABLE workspace_change_events
+    ADD COLUMN source       CHANGE_EVENT_SOURCE NOT NULL DEFAULT 'database',
+    ADD COLUMN triggered_by UUID                DEFAULT NULL,
+    ADD COLUMN data         JSONB               DEFAULT NULL;
+
+-- Wakes the relay when an application event commits. Notifications are
+-- delivered on commit, so a rolled back change never wakes it.
+CREATE OR REPLACE FUNCTION notify_workspace_change_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('workspace_change_events', NEW.id::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION notify_workspace_change_event() IS
+    'Notifies listeners of an application event recorded in workspace_change_events.';
+
+CREATE TRIGGER workspace_change_events_notify_trigger
+    AFTER INSERT ON workspace_change_events
+    FOR EACH ROW
+    WHEN (NEW.source = 'application')
+    EXECUTE FUNCTION notify_workspace_change_event();
+
+-- Comments
+COMMENT ON TABLE workspace_change_events IS
+    'Outbox of workspace events awaiting publication to NATS.';
+COMMENT ON COLUMN workspace_change_events.source IS 'Who recorded the event';
+COMMENT ON COLUMN workspace_change_events.triggered_by IS 'Account that triggered the event, if any';
+COMMENT ON COLUMN workspace_change_events.data IS 'Event-specific payload, if any';
@@ -0,0 +1,3 @@
+-- Revert file access statistics
+
+DROP TABLE IF EXISTS workspace_file_access_stats;
@@ -0,0 +1,41 @@
+-- This migration adds per-document access statistics: daily counters of
+-- how often each file is viewed, downloaded, opened from search results and
+-- opened through a share link.
+--
+-- Only counts are kept. No account, address or time of day is recorded, so
+-- the statistics show which documents are read, not who read them or when
+-- within a day.
+
+-- File access statistics table
+CREATE TABLE workspace_file_access_stats (
+    -- References
+    workspace_id        UUID        NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    file_id             UUID        NOT NULL REFERENCES workspace_files (id) ON DELETE CASCADE,
+
+    -- Day the accesses happened on, in UTC
+    access_date         DATE        NOT NULL,
+
+    -- Counters
+    view_count          INTEGER     NOT NULL DEFAULT 0,
+    download_count      INTEGER     NOT NULL DEFAULT 0,
+    search_open_count   INTEGER     NOT NULL DEFAULT 0,
+    share_view_count    INTEGER     NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (file_id, access_date)
+);
+
+-- Indexes
+CREATE INDEX workspace_file_access_stats_workspace_idx
+    ON workspace_file_access_stats (workspace_id, access_date);
+
+-- Comments
+COMMENT ON TABLE workspace_file_access_stats IS
+    'Daily access counters per document, without the accounts that accessed them.';
+
+COMMENT ON COLUMN workspace_file_access_stats.workspace_id IS 'Workspace the file belongs to';
+COMMENT ON COLUMN workspace_file_access_stats.file_id IS 'Accessed file';
+COMMENT ON COLUMN workspace_file_access_stats.access_date IS 'Day the accesses happened on (UTC)';
+COMMENT ON COLUMN workspace_file_access_stats.view_count IS 'Number of times the file was opened in the workspace';
+COMMENT ON COLUMN workspace_file_access_stats.download_count IS 'Number of times the file content was downloaded';
+COMMENT ON COLUMN workspace_file_access_stats.search_open_count IS 'Number of opens or downloads that followed a search';
+COMMENT ON COLUMN workspace_file_access_stats.share_view_count IS 'Number of times the file was opened through a share link';
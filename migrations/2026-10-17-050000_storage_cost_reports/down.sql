@@ -0,0 +1,6 @@
+-- Revert storage cost reports
+
+DROP TABLE IF EXISTS workspace_storage_costs;
+DROP TABLE IF EXISTS storage_prices;
+DROP TYPE IF EXISTS STORAGE_CLASS;
+DROP TYPE IF EXISTS STORAGE_STAGE;
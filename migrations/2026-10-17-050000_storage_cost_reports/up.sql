@@ -0,0 +1,121 @@
+-- This migration attributes object storage costs to workspaces. A daily
+-- sample of every region's object stores is aggregated by workspace, stage
+-- and storage class and accrued into monthly reports, priced from a table of
+-- per-region storage prices. Prices and costs are kept in millionths of the
+-- currency unit so sums stay exact.
+
+-- Pipeline stages objects are stored for
+CREATE TYPE STORAGE_STAGE AS ENUM (
+    'upload',       -- Uploaded and imported document content
+    'intermediate', -- Analyses held between pipeline steps
+    'thumbnail',    -- Document previews
+    'context',      -- Detection context files
+    'result'        -- Operation result artifacts
+);
+
+COMMENT ON TYPE STORAGE_STAGE IS
+    'Pipeline stages object storage is attributed to.';
+
+-- Storage classes objects are priced at
+CREATE TYPE STORAGE_CLASS AS ENUM (
+    'hot',  -- Recently written objects
+    'cold'  -- Objects left untouched past the cold threshold
+);
+
+COMMENT ON TYPE STORAGE_CLASS IS
+    'Storage classes object storage is priced at, assigned by object age.';
+
+-- Storage prices table
+CREATE TABLE storage_prices (
+    -- Primary identifier
+    id                          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Price
+    data_region                 DATA_REGION     NOT NULL,
+    storage_class               STORAGE_CLASS   NOT NULL,
+    currency                    TEXT            NOT NULL DEFAULT 'USD',
+    price_micros_per_gib_month  BIGINT          NOT NULL,
+
+    CONSTRAINT storage_prices_currency_format CHECK (currency ~ '^[A-Z]{3}$'),
+    CONSTRAINT storage_prices_price_min CHECK (price_micros_per_gib_month >= 0),
+
+    -- Validity
+    effective_from              DATE            NOT NULL,
+
+    CONSTRAINT storage_prices_effective_unique UNIQUE (data_region, storage_class, effective_from),
+
+    -- Lifecycle timestamps
+    created_at                  TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp
+);
+
+COMMENT ON TABLE storage_prices IS
+    'Object storage prices per region and storage class; the latest price in effect applies.';
+
+COMMENT ON COLUMN storage_prices.id IS 'Unique price identifier';
+COMMENT ON COLUMN storage_prices.data_region IS 'Region whose backends the price applies to';
+COMMENT ON COLUMN storage_prices.storage_class IS 'Storage class the price applies to';
+COMMENT ON COLUMN storage_prices.currency IS 'ISO 4217 currency code';
+COMMENT ON COLUMN storage_prices.price_micros_per_gib_month IS 'Price of one GiB stored for a month, in millionths of the currency unit';
+COMMENT ON COLUMN storage_prices.effective_from IS 'First day the price applies';
+COMMENT ON COLUMN storage_prices.created_at IS 'When the price was recorded';
+
+-- Storage cost reports table
+CREATE TABLE workspace_storage_costs (
+    -- Report key
+    period_start                DATE            NOT NULL,
+    workspace_id                UUID            NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    data_region                 DATA_REGION     NOT NULL,
+    stage                       STORAGE_STAGE   NOT NULL,
+    storage_class               STORAGE_CLASS   NOT NULL,
+
+    PRIMARY KEY (period_start, workspace_id, data_region, stage, storage_class),
+
+    CONSTRAINT workspace_storage_costs_period_month CHECK (extract(day FROM period_start) = 1),
+
+    -- Usage at the latest sample
+    object_count                BIGINT          NOT NULL,
+    byte_count                  BIGINT          NOT NULL,
+
+    -- Usage accrued over the period
+    byte_days                   BIGINT          NOT NULL,
+    sampled_days                INTEGER         NOT NULL,
+    period_days                 INTEGER         NOT NULL,
+    last_sampled_on             DATE            NOT NULL,
+
+    CONSTRAINT workspace_storage_costs_usage_min CHECK (object_count >= 0 AND byte_count >= 0 AND byte_days >= 0),
+    CONSTRAINT workspace_storage_costs_days_range CHECK (sampled_days BETWEEN 1 AND period_days AND period_days BETWEEN 28 AND 31),
+
+    -- Pricing at the latest sample; NULL while no price is in effect
+    currency                    TEXT            DEFAULT NULL,
+    price_micros_per_gib_month  BIGINT          DEFAULT NULL,
+    cost_micros                 BIGINT          GENERATED ALWAYS AS (
+        round(byte_days::NUMERIC * price_micros_per_gib_month / 1073741824 / period_days)::BIGINT
+    ) STORED,
+
+    -- Lifecycle timestamps
+    updated_at                  TIMESTAMPTZ     NOT NULL DEFAULT current_timestamp
+);
+
+-- Indexes
+CREATE INDEX workspace_storage_costs_workspace_idx
+    ON workspace_storage_costs (workspace_id, period_start);
+
+-- Comments
+COMMENT ON TABLE workspace_storage_costs IS
+    'Monthly object storage usage and cost per workspace, stage and storage class, accrued from daily samples.';
+
+COMMENT ON COLUMN workspace_storage_costs.period_start IS 'First day of the reported month';
+COMMENT ON COLUMN workspace_storage_costs.workspace_id IS 'Workspace the storage is attributed to';
+COMMENT ON COLUMN workspace_storage_costs.data_region IS 'Region whose backends store the objects';
+COMMENT ON COLUMN workspace_storage_costs.stage IS 'Pipeline stage the objects are stored for';
+COMMENT ON COLUMN workspace_storage_costs.storage_class IS 'Storage class the objects are priced at';
+COMMENT ON COLUMN workspace_storage_costs.object_count IS 'Objects stored at the latest sample';
+COMMENT ON COLUMN workspace_storage_costs.byte_count IS 'Bytes stored at the latest sample';
+COMMENT ON COLUMN workspace_storage_costs.byte_days IS 'Sum of the bytes stored at each daily sample';
+COMMENT ON COLUMN workspace_storage_costs.sampled_days IS 'Days of the month sampled';
+COMMENT ON COLUMN workspace_storage_costs.period_days IS 'Days in the month';
+COMMENT ON COLUMN workspace_storage_costs.last_sampled_on IS 'Day of the latest sample';
+COMMENT ON COLUMN workspace_storage_costs.currency IS 'ISO 4217 currency of the price applied';
+COMMENT ON COLUMN workspace_storage_costs.price_micros_per_gib_month IS 'Price applied at the latest sample, in millionths of the currency unit per GiB-month';
+COMMENT ON COLUMN workspace_storage_costs.cost_micros IS 'Cost accrued over the sampled days, in millionths of the currency unit';
+COMMENT ON COLUMN workspace_storage_costs.updated_at IS 'When the latest sample was recorded';
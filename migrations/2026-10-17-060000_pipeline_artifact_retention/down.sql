@@ -0,0 +1,8 @@
+-- Revert pipeline artifact retention
+
+DROP INDEX IF EXISTS workspace_pipeline_artifacts_created_idx;
+
+ALTER TABLE workspace_pipelines
+    DROP COLUMN IF EXISTS input_retention_days,
+    DROP COLUMN IF EXISTS intermediate_retention_days,
+    DROP COLUMN IF EXISTS output_retention_days;
@@ -0,0 +1,32 @@
+-- This migration adds per-stage artifact retention to pipelines. A pipeline
+-- may limit how long the artifacts of each stage of its runs are kept, for
+-- example dropping intermediates after a week while keeping outputs
+-- indefinitely. The retention job removes expired artifacts of finished
+-- runs, except those under a legal hold or whose run still awaits review.
+
+ALTER TABLE workspace_pipelines
+    ADD COLUMN input_retention_days        INTEGER DEFAULT NULL,
+    ADD COLUMN intermediate_retention_days INTEGER DEFAULT NULL,
+    ADD COLUMN output_retention_days       INTEGER DEFAULT NULL,
+
+    ADD CONSTRAINT workspace_pipelines_input_retention_days_range CHECK (
+        input_retention_days IS NULL OR input_retention_days BETWEEN 1 AND 36500
+    ),
+    ADD CONSTRAINT workspace_pipelines_intermediate_retention_days_range CHECK (
+        intermediate_retention_days IS NULL OR intermediate_retention_days BETWEEN 1 AND 36500
+    ),
+    ADD CONSTRAINT workspace_pipelines_output_retention_days_range CHECK (
+        output_retention_days IS NULL OR output_retention_days BETWEEN 1 AND 36500
+    );
+
+-- Indexes
+CREATE INDEX workspace_pipeline_artifacts_created_idx
+    ON workspace_pipeline_artifacts (artifact_type, created_at);
+
+-- Comments
+COMMENT ON COLUMN workspace_pipelines.input_retention_days IS
+    'Days input artifacts of finished runs are kept (NULL = indefinitely)';
+COMMENT ON COLUMN workspace_pipelines.intermediate_retention_days IS
+    'Days intermediate artifacts of finished runs are kept (NULL = indefinitely)';
+COMMENT ON COLUMN workspace_pipelines.output_retention_days IS
+    'Days output artifacts of finished runs are kept (NULL = indefinitely)';
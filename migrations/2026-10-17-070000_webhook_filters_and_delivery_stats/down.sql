@@ -0,0 +1,7 @@
+-- Revert webhook payload filters and delivery statistics
+
+DROP TABLE IF EXISTS workspace_webhook_delivery_stats;
+
+ALTER TABLE workspace_webhooks
+    DROP CONSTRAINT IF EXISTS workspace_webhooks_payload_filters_count,
+    DROP COLUMN IF EXISTS payload_filters;
@@ -0,0 +1,50 @@
+-- This migration adds payload filters to webhooks and per-webhook delivery
+-- statistics.
+--
+-- A webhook's payload filters are JSONPath queries evaluated against each
+-- payload it would receive; the payload is delivered only if every query
+-- selects at least one value. Delivery statistics are daily counters of the
+-- requests delivered, failed and filtered out for each webhook.
+
+ALTER TABLE workspace_webhooks
+    ADD COLUMN payload_filters TEXT[] NOT NULL DEFAULT '{}',
+    ADD CONSTRAINT workspace_webhooks_payload_filters_count CHECK (
+        cardinality(payload_filters) <= 16
+    );
+
+-- Webhook delivery statistics table
+CREATE TABLE workspace_webhook_delivery_stats (
+    -- References
+    workspace_id        UUID        NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    webhook_id          UUID        NOT NULL REFERENCES workspace_webhooks (id) ON DELETE CASCADE,
+
+    -- Day the deliveries happened on, in UTC
+    delivery_date       DATE        NOT NULL,
+
+    -- Counters
+    success_count       INTEGER     NOT NULL DEFAULT 0,
+    failure_count       INTEGER     NOT NULL DEFAULT 0,
+    filtered_count      INTEGER     NOT NULL DEFAULT 0,
+    response_time_ms    BIGINT      NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (webhook_id, delivery_date)
+);
+
+-- Indexes
+CREATE INDEX workspace_webhook_delivery_stats_workspace_idx
+    ON workspace_webhook_delivery_stats (workspace_id, delivery_date);
+
+-- Comments
+COMMENT ON COLUMN workspace_webhooks.payload_filters IS
+    'JSONPath queries that must all select a value in a payload for it to be delivered';
+
+COMMENT ON TABLE workspace_webhook_delivery_stats IS
+    'Daily delivery counters per webhook.';
+
+COMMENT ON COLUMN workspace_webhook_delivery_stats.workspace_id IS 'Workspace the webhook belongs to';
+COMMENT ON COLUMN workspace_webhook_delivery_stats.webhook_id IS 'Webhook the requests were addressed to';
+COMMENT ON COLUMN workspace_webhook_delivery_stats.delivery_date IS 'Day the deliveries happened on (UTC)';
+COMMENT ON COLUMN workspace_webhook_delivery_stats.success_count IS 'Number of requests the endpoint accepted with a 2xx status';
+COMMENT ON COLUMN workspace_webhook_delivery_stats.failure_count IS 'Number of delivery attempts that failed or returned a non-2xx status';
+COMMENT ON COLUMN workspace_webhook_delivery_stats.filtered_count IS 'Number of events not delivered because the payload filters did not match';
+COMMENT ON COLUMN workspace_webhook_delivery_stats.response_time_ms IS 'Total response time of the successful deliveries, in milliseconds';
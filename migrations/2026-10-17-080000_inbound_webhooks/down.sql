@@ -0,0 +1,4 @@
+-- Revert inbound webhooks
+
+DROP TABLE IF EXISTS workspace_inbound_webhooks;
+DROP TYPE IF EXISTS INBOUND_WEBHOOK_PROVIDER;
@@ -0,0 +1,72 @@
+-- This migration adds inbound webhooks: endpoints through which third-party
+-- services notify a workspace of their events. Each endpoint belongs to one
+-- workspace, names the provider that calls it, which decides how requests
+-- are verified and normalized, and holds the secret they are verified with.
+
+-- Inbound webhook provider enum
+CREATE TYPE INBOUND_WEBHOOK_PROVIDER AS ENUM (
+    'hmac',          -- Any sender signing the body with HMAC-SHA256
+    'jwt',           -- Any sender posting its event as an HS256-signed JWT
+    'docusign',      -- DocuSign Connect, with HMAC security enabled
+    'google_drive'   -- Google Drive push notifications
+);
+
+COMMENT ON TYPE INBOUND_WEBHOOK_PROVIDER IS
+    'Defines the senders inbound webhooks accept requests from.';
+
+-- Workspace inbound webhooks table definition
+CREATE TABLE workspace_inbound_webhooks (
+    -- Primary identifier
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Reference
+    workspace_id     UUID                     NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+
+    -- Endpoint details
+    provider         INBOUND_WEBHOOK_PROVIDER NOT NULL,
+    display_name     TEXT                     NOT NULL,
+
+    CONSTRAINT workspace_inbound_webhooks_display_name_length CHECK (length(trim(display_name)) BETWEEN 1 AND 128),
+
+    -- Verification secret, XChaCha20-Poly1305 encrypted under the workspace key.
+    -- Either generated and returned to the caller once, or supplied by them
+    -- when the provider generates it.
+    encrypted_secret BYTEA                    NOT NULL,
+
+    -- Delivery tracking
+    last_received_at TIMESTAMPTZ              DEFAULT NULL,
+
+    -- Audit tracking
+    created_by       UUID                     NOT NULL REFERENCES accounts (id),
+
+    -- Lifecycle timestamps
+    created_at       TIMESTAMPTZ              NOT NULL DEFAULT current_timestamp,
+    updated_at       TIMESTAMPTZ              NOT NULL DEFAULT current_timestamp,
+    deleted_at       TIMESTAMPTZ              DEFAULT NULL,
+
+    CONSTRAINT workspace_inbound_webhooks_updated_after_created CHECK (updated_at >= created_at),
+    CONSTRAINT workspace_inbound_webhooks_deleted_after_created CHECK (deleted_at IS NULL OR deleted_at >= created_at)
+);
+
+-- Triggers for workspace_inbound_webhooks table
+SELECT setup_updated_at('workspace_inbound_webhooks');
+
+-- Indexes for workspace_inbound_webhooks table
+CREATE INDEX workspace_inbound_webhooks_workspace_idx
+    ON workspace_inbound_webhooks (workspace_id, created_at DESC)
+    WHERE deleted_at IS NULL;
+
+-- Comments for workspace_inbound_webhooks table
+COMMENT ON TABLE workspace_inbound_webhooks IS
+    'Endpoints receiving event notifications from third-party services.';
+
+COMMENT ON COLUMN workspace_inbound_webhooks.id IS 'Unique inbound webhook identifier, part of its URL';
+COMMENT ON COLUMN workspace_inbound_webhooks.workspace_id IS 'Workspace the events are routed to';
+COMMENT ON COLUMN workspace_inbound_webhooks.provider IS 'Sender of the requests, deciding how they are verified and normalized';
+COMMENT ON COLUMN workspace_inbound_webhooks.display_name IS 'Human-readable name (1-128 chars)';
+COMMENT ON COLUMN workspace_inbound_webhooks.encrypted_secret IS 'Verification secret, encrypted under the workspace key';
+COMMENT ON COLUMN workspace_inbound_webhooks.last_received_at IS 'Timestamp of the last accepted request';
+COMMENT ON COLUMN workspace_inbound_webhooks.created_by IS 'Account that created the inbound webhook';
+COMMENT ON COLUMN workspace_inbound_webhooks.created_at IS 'Creation timestamp';
+COMMENT ON COLUMN workspace_inbound_webhooks.updated_at IS 'Last modification timestamp';
+COMMENT ON COLUMN workspace_inbound_webhooks.deleted_at IS 'Soft deletion timestamp';
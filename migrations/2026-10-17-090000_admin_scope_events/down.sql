@@ -0,0 +1,3 @@
+-- Revert admin scope events
+
+DROP TABLE IF EXISTS admin_scope_events;
@@ -0,0 +1,32 @@
+-- This migration records every query scope opened across workspaces. Such
+-- scopes bypass tenant isolation, for maintenance jobs and for resolving a
+-- row to its workspace before authorization, so each one is logged with the
+-- reason given and the code location that opened it.
+
+-- Admin scope events table definition
+CREATE TABLE admin_scope_events (
+    -- Primary identifier
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Scope details
+    reason           TEXT        NOT NULL,
+    caller           TEXT        NOT NULL,
+
+    CONSTRAINT admin_scope_events_reason_length CHECK (length(trim(reason)) BETWEEN 1 AND 256),
+
+    -- Lifecycle timestamps
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
+
+-- Indexes for admin_scope_events table
+CREATE INDEX admin_scope_events_created_idx
+    ON admin_scope_events (created_at);
+
+-- Comments for admin_scope_events table
+COMMENT ON TABLE admin_scope_events IS
+    'Audit trail of query scopes opened across workspaces.';
+
+COMMENT ON COLUMN admin_scope_events.id IS 'Unique event identifier';
+COMMENT ON COLUMN admin_scope_events.reason IS 'Reason given for crossing workspaces';
+COMMENT ON COLUMN admin_scope_events.caller IS 'Source location that opened the scope';
+COMMENT ON COLUMN admin_scope_events.created_at IS 'When the scope was opened';
@@ -0,0 +1,10 @@
+-- Revert transient document passwords
+
+ALTER TABLE workspace_pipeline_runs
+    ADD COLUMN IF NOT EXISTS encrypted_document_password BYTEA DEFAULT NULL,
+    ADD CONSTRAINT workspace_pipeline_runs_document_password_active CHECK (
+        encrypted_document_password IS NULL OR status IN ('running', 'analyzed')
+    );
+
+COMMENT ON COLUMN workspace_pipeline_runs.encrypted_document_password IS
+    'One-time document password, encrypted under the workspace key and cleared when the run settles';
@@ -0,0 +1,8 @@
+-- This migration stops keeping one-time document passwords on pipeline runs.
+-- A password supplied for a single run is now held, encrypted, in a
+-- memory-only store that expires it, and is never written to the database.
+-- Passwords stored on the file itself are unchanged.
+
+ALTER TABLE workspace_pipeline_runs
+    DROP CONSTRAINT IF EXISTS workspace_pipeline_runs_document_password_active,
+    DROP COLUMN IF EXISTS encrypted_document_password;
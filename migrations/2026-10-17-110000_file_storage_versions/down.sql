@@ -0,0 +1,4 @@
+-- Revert file storage versions
+
+ALTER TABLE workspace_files
+    DROP COLUMN IF EXISTS storage_version;
@@ -0,0 +1,10 @@
+-- This migration records the version ID of each file's stored content.
+-- Files are stored in a versioned bucket: every version of a document is its
+-- own object, and the ID NATS assigns to it on upload identifies the version.
+-- Files stored before this migration have no recorded version.
+
+ALTER TABLE workspace_files
+    ADD COLUMN storage_version TEXT DEFAULT NULL;
+
+COMMENT ON COLUMN workspace_files.storage_version IS
+    'Version ID of the stored content, assigned by object storage on upload';